@@ -0,0 +1,208 @@
+//! Adaptive strategy sub-module: how the loop reacts when the model keeps failing.
+//!
+//! - After [`MALFORMED_CALLS_BEFORE_ZERO_TEMPERATURE`] tool calls with unparseable arguments
+//!   in one turn, the response is dropped and the same LLM call is retried at temperature 0;
+//!   later calls in the turn stay at 0.
+//! - After [`FAILED_ITERATIONS_BEFORE_FALLBACK`] iterations in a row whose tool calls all
+//!   failed (or when the consecutive-failure limit is hit first), the rest of the turn runs
+//!   on `AgentConfig::fallback_model`, once, and the event sink is told.
+
+use super::super::types::*;
+use super::execution::ExecutionState;
+
+pub(super) const MALFORMED_CALLS_BEFORE_ZERO_TEMPERATURE: usize = 2;
+pub(super) const FAILED_ITERATIONS_BEFORE_FALLBACK: usize = 3;
+
+/// Model for the next LLM call: the fallback once it took over, else the configured one.
+pub(super) fn active_model<'a>(config: &'a AgentConfig, state: &'a ExecutionState) -> &'a str {
+    state.fallback_model.as_deref().unwrap_or(&config.model)
+}
+
+pub(super) fn active_temperature(config: &AgentConfig, state: &ExecutionState) -> Option<f64> {
+    if state.zero_temperature {
+        Some(0.0)
+    } else {
+        config.temperature
+    }
+}
+
+/// Tool calls whose arguments are not valid JSON. Empty arguments are treated as `{}`.
+pub(super) fn count_malformed_tool_calls(tool_calls: &[ToolCall]) -> usize {
+    tool_calls
+        .iter()
+        .filter(|tc| {
+            let args = tc.function.arguments.trim();
+            !args.is_empty() && serde_json::from_str::<serde_json::Value>(args).is_err()
+        })
+        .count()
+}
+
+/// Count malformed calls in a response. Returns `true` when the response should be dropped
+/// and the call retried at temperature 0 (only the first time the threshold is crossed).
+pub(super) fn should_retry_at_zero_temperature(
+    state: &mut ExecutionState,
+    tool_calls: Option<&[ToolCall]>,
+) -> bool {
+    let malformed = tool_calls.map_or(0, count_malformed_tool_calls);
+    if malformed == 0 {
+        return false;
+    }
+    state.malformed_tool_calls += malformed;
+    if state.zero_temperature
+        || state.malformed_tool_calls < MALFORMED_CALLS_BEFORE_ZERO_TEMPERATURE
+    {
+        return false;
+    }
+    state.zero_temperature = true;
+    state.zero_temperature_retries += 1;
+    tracing::warn!(
+        malformed_tool_calls = state.malformed_tool_calls,
+        "Repeated malformed tool-call arguments; retrying the LLM call at temperature 0"
+    );
+    true
+}
+
+/// Track a finished tool batch and switch to the fallback model after enough failed
+/// iterations in a row. Returns `true` when the switch happened.
+pub(super) fn after_tool_batch(
+    config: &AgentConfig,
+    state: &mut ExecutionState,
+    new_calls: usize,
+    new_failures: usize,
+    event_sink: &mut dyn EventSink,
+) -> bool {
+    if new_calls > 0 && new_calls == new_failures {
+        state.consecutive_failed_iterations += 1;
+    } else if new_calls > 0 {
+        state.consecutive_failed_iterations = 0;
+    }
+    if state.consecutive_failed_iterations < FAILED_ITERATIONS_BEFORE_FALLBACK {
+        return false;
+    }
+    let reason = format!(
+        "{} consecutive iterations with only failed tool calls",
+        state.consecutive_failed_iterations
+    );
+    fall_back(config, state, event_sink, &reason)
+}
+
+/// Switch the rest of the turn to `config.fallback_model`. No-op (returns `false`) when no
+/// fallback is configured, it equals the primary model, or the switch already happened.
+pub(super) fn fall_back(
+    config: &AgentConfig,
+    state: &mut ExecutionState,
+    event_sink: &mut dyn EventSink,
+    reason: &str,
+) -> bool {
+    let Some(fallback) = config
+        .fallback_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty() && *m != config.model)
+    else {
+        return false;
+    };
+    if state.fallback_model.is_some() {
+        return false;
+    }
+    tracing::warn!(
+        from = %config.model,
+        to = fallback,
+        reason,
+        "Switching to fallback model"
+    );
+    event_sink.on_model_fallback(&config.model, fallback, reason);
+    state.fallback_model = Some(fallback.to_string());
+    state.consecutive_failed_iterations = 0;
+    state.consecutive_failures = 0;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(arguments: &str) -> ToolCall {
+        ToolCall {
+            id: "c".to_string(),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: "read_file".to_string(),
+                arguments: arguments.to_string(),
+            },
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        fallbacks: Vec<(String, String)>,
+    }
+
+    impl EventSink for RecordingSink {
+        fn on_text(&mut self, _text: &str) {}
+        fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+        fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+        fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+            false
+        }
+        fn on_model_fallback(&mut self, from: &str, to: &str, _reason: &str) {
+            self.fallbacks.push((from.to_string(), to.to_string()));
+        }
+    }
+
+    #[test]
+    fn malformed_arguments_force_one_zero_temperature_retry() {
+        let config = AgentConfig {
+            temperature: Some(0.7),
+            ..AgentConfig::default()
+        };
+        let mut state = ExecutionState::new();
+        let bad = vec![
+            call("{\"path\": \"a.txt\""),
+            call("{\"path\": \"文件.md\"}"),
+        ];
+        assert_eq!(count_malformed_tool_calls(&bad), 1);
+        assert_eq!(count_malformed_tool_calls(&[call("")]), 0);
+
+        assert!(!should_retry_at_zero_temperature(&mut state, Some(&bad)));
+        assert_eq!(active_temperature(&config, &state), Some(0.7));
+        assert!(should_retry_at_zero_temperature(&mut state, Some(&bad)));
+        assert_eq!(active_temperature(&config, &state), Some(0.0));
+        // Already at 0: later malformed calls go through to normal error handling.
+        assert!(!should_retry_at_zero_temperature(&mut state, Some(&bad)));
+        assert_eq!(
+            (state.malformed_tool_calls, state.zero_temperature_retries),
+            (3, 1)
+        );
+    }
+
+    #[test]
+    fn fallback_switches_once_after_failed_iterations_and_needs_config() {
+        let mut sink = RecordingSink::default();
+        let mut state = ExecutionState::new();
+        let no_fallback = AgentConfig::default();
+        for _ in 0..FAILED_ITERATIONS_BEFORE_FALLBACK {
+            assert!(!after_tool_batch(&no_fallback, &mut state, 2, 2, &mut sink));
+        }
+        assert!(sink.fallbacks.is_empty());
+
+        let config = AgentConfig {
+            model: "primary".to_string(),
+            fallback_model: Some("backup".to_string()),
+            ..AgentConfig::default()
+        };
+        let mut state = ExecutionState::new();
+        assert!(!after_tool_batch(&config, &mut state, 1, 1, &mut sink));
+        assert!(!after_tool_batch(&config, &mut state, 2, 1, &mut sink));
+        for _ in 1..FAILED_ITERATIONS_BEFORE_FALLBACK {
+            assert!(!after_tool_batch(&config, &mut state, 1, 1, &mut sink));
+        }
+        assert!(after_tool_batch(&config, &mut state, 1, 1, &mut sink));
+        assert_eq!(active_model(&config, &state), "backup");
+        assert!(!fall_back(&config, &mut state, &mut sink, "again"));
+        assert_eq!(
+            sink.fallbacks,
+            vec![("primary".to_string(), "backup".to_string())]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! End-to-end checks of the adaptive strategy through the real simple loop and a scripted
+//! endpoint: malformed tool-call JSON forces a temperature-0 retry, and repeated failed
+//! iterations hand the turn to the fallback model.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde_json::Value;
+
+use super::run_agent_loop;
+use super::scripted_llm::{self, Reply};
+use crate::types::*;
+
+fn config(api_base: String, workspace: &std::path::Path) -> AgentConfig {
+    AgentConfig {
+        api_base,
+        api_key: "test".to_string(),
+        model: "primary".to_string(),
+        temperature: Some(0.7),
+        max_iterations: 8,
+        workspace: workspace.to_string_lossy().to_string(),
+        enable_task_planning: false,
+        enable_memory: false,
+        ..AgentConfig::default()
+    }
+}
+
+fn is_streaming(body: &Value) -> bool {
+    body.get("stream").and_then(Value::as_bool) == Some(true)
+}
+
+#[derive(Default)]
+struct FallbackSink {
+    fallbacks: Vec<(String, String, String)>,
+}
+
+impl EventSink for FallbackSink {
+    fn on_text(&mut self, _text: &str) {}
+    fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+    fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+    fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+        false
+    }
+    fn on_model_fallback(&mut self, from: &str, to: &str, reason: &str) {
+        self.fallbacks
+            .push((from.to_string(), to.to_string(), reason.to_string()));
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn malformed_tool_json_is_retried_at_temperature_zero() {
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::write(workspace.path().join("笔记.md"), "hello").unwrap();
+    let streamed = AtomicUsize::new(0);
+    let llm = scripted_llm::spawn(move |_, body| {
+        if !is_streaming(body) {
+            return Reply::text("Done.");
+        }
+        match streamed.fetch_add(1, Ordering::SeqCst) {
+            // Truncated arguments, twice in a row.
+            0 | 1 => Reply::tool_call("read_file", "{\"path\": \"笔记.md\""),
+            2 => Reply::tool_call("read_file", "{\"path\": \"笔记.md\"}"),
+            _ => Reply::text("Read it."),
+        }
+    });
+    let config = config(llm.base, workspace.path());
+
+    let mut sink = SilentEventSink;
+    let result = run_agent_loop(
+        &config,
+        Vec::new(),
+        "read 笔记.md",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let temperatures: Vec<Option<f64>> = llm
+        .requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|b| is_streaming(b))
+        .map(|b| b.get("temperature").and_then(Value::as_f64))
+        .collect();
+    assert_eq!(temperatures[..2], [Some(0.7), Some(0.7)]);
+    assert!(temperatures[2..].iter().all(|t| *t == Some(0.0)));
+    // First malformed call ran (and failed); the second response was dropped, not executed.
+    assert_eq!(result.feedback.total_tools, 2);
+    assert_eq!(result.feedback.failed_tools, 1);
+    assert_eq!(result.feedback.fallback_model, None);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn repeated_failed_iterations_switch_to_fallback_model() {
+    let workspace = tempfile::tempdir().unwrap();
+    let llm = scripted_llm::spawn(|index, body| {
+        let model = body.get("model").and_then(Value::as_str).unwrap_or("");
+        if !is_streaming(body) || model == "backup" {
+            Reply::text("Done.")
+        } else {
+            let args = serde_json::json!({ "path": format!("missing-{}.txt", index) });
+            Reply::tool_call("read_file", &args.to_string())
+        }
+    });
+    let mut config = config(llm.base, workspace.path());
+    config.fallback_model = Some("backup".to_string());
+
+    let mut sink = FallbackSink::default();
+    let result = run_agent_loop(&config, Vec::new(), "read it", None, &[], &mut sink, None)
+        .await
+        .unwrap();
+
+    assert_eq!(sink.fallbacks.len(), 1);
+    assert_eq!(sink.fallbacks[0].0, "primary");
+    assert_eq!(sink.fallbacks[0].1, "backup");
+    assert_eq!(result.feedback.model, "primary");
+    assert_eq!(result.feedback.fallback_model.as_deref(), Some("backup"));
+    assert_eq!(
+        result.feedback.failed_tools,
+        super::adaptive::FAILED_ITERATIONS_BEFORE_FALLBACK
+    );
+}
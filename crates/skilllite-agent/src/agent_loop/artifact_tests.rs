@@ -0,0 +1,96 @@
+//! Artifact citation through the real simple loop and a scripted endpoint: files written
+//! during the turn are listed once after an answer that does not mention them.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde_json::Value;
+
+use super::artifacts::ARTIFACTS_HEADING;
+use super::run_agent_loop;
+use super::scripted_llm::{self, Reply};
+use crate::types::*;
+
+#[derive(Default)]
+struct TextSink {
+    texts: Vec<String>,
+}
+
+impl EventSink for TextSink {
+    fn on_text(&mut self, text: &str) {
+        self.texts.push(text.to_string());
+    }
+    fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+    fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+    fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+        false
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn written_files_are_cited_once_with_relative_paths() {
+    let workspace = tempfile::tempdir().unwrap();
+    let streamed = AtomicUsize::new(0);
+    let llm = scripted_llm::spawn(move |_, body| {
+        if body.get("stream").and_then(Value::as_bool) != Some(true) {
+            return Reply::text("Done.");
+        }
+        match streamed.fetch_add(1, Ordering::SeqCst) {
+            0 => Reply::tool_call(
+                "write_output",
+                r#"{"file_path": "reports/summary.html", "content": "<h1>Q3</h1>"}"#,
+            ),
+            1 => Reply::tool_call(
+                "write_file",
+                r#"{"path": "notes/todo.md", "content": "- ship it\n"}"#,
+            ),
+            _ => Reply::text("All done, the report is ready."),
+        }
+    });
+    let config = AgentConfig {
+        api_base: llm.base,
+        api_key: "test".to_string(),
+        model: "primary".to_string(),
+        workspace: workspace.path().to_string_lossy().to_string(),
+        enable_task_planning: false,
+        enable_memory: false,
+        ..AgentConfig::default()
+    };
+
+    let mut sink = TextSink::default();
+    let result = run_agent_loop(
+        &config,
+        Vec::new(),
+        "write the report",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let cited: Vec<(&str, ArtifactRoot)> = result
+        .artifacts
+        .iter()
+        .map(|a| (a.path.as_str(), a.root))
+        .collect();
+    assert_eq!(
+        cited,
+        [
+            ("reports/summary.html", ArtifactRoot::Output),
+            ("notes/todo.md", ArtifactRoot::Workspace),
+        ]
+    );
+    assert_eq!(result.response.matches(ARTIFACTS_HEADING).count(), 1);
+    assert!(
+        result.response.starts_with("All done"),
+        "{}",
+        result.response
+    );
+    assert!(result
+        .response
+        .contains("- `reports/summary.html` — HTML file"));
+    assert!(result.response.contains("- `notes/todo.md` — MD file"));
+    let emitted = sink.texts.concat();
+    assert_eq!(emitted.matches(ARTIFACTS_HEADING).count(), 1, "{emitted}");
+}
@@ -0,0 +1,257 @@
+//! Artifact citation: files written during the turn (write_output / write_file / skill
+//! `result.json` outputs) are returned as [`AgentResult::artifacts`], and listed under a
+//! generated section when the final answer does not mention them.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::Value;
+use skilllite_core::output_index::format_size;
+use skilllite_sandbox::results::SkillResult;
+
+use crate::extensions::{normalize_path, output_root};
+use crate::skills::OUTPUT_FILES_SEPARATOR;
+use crate::types::{AgentResult, Artifact, ArtifactRoot, EventSink};
+
+/// Heading of the generated section. An answer that already has it (resumed turn, model
+/// echoing an earlier answer) is left alone.
+pub(super) const ARTIFACTS_HEADING: &str = "**Artifacts**";
+
+/// Files produced by one successful tool call. `content` must be the tool output before
+/// summarisation so the skill file listing is intact.
+pub(super) fn from_tool_call(
+    tool_name: &str,
+    arguments: &str,
+    content: &str,
+    workspace: &Path,
+    is_skill: bool,
+) -> Vec<Artifact> {
+    let args = || serde_json::from_str::<Value>(arguments).ok();
+    let arg = |args: &Value, keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| args.get(*k).and_then(Value::as_str))
+            .map(str::to_string)
+    };
+    match tool_name {
+        "write_output" => args()
+            .and_then(|a| arg(&a, &["file_path", "path"]))
+            .and_then(|p| relative_artifact(&output_root(workspace).join(p), workspace, None))
+            .into_iter()
+            .collect(),
+        "write_file" => args()
+            .and_then(|a| arg(&a, &["path", "file_path"]))
+            .and_then(|p| relative_artifact(&workspace.join(p), workspace, None))
+            .into_iter()
+            .collect(),
+        _ if is_skill => {
+            let Some((run_output, _)) = content.split_once(OUTPUT_FILES_SEPARATOR) else {
+                return Vec::new();
+            };
+            SkillResult::outputs_from_run_output(run_output)
+                .into_iter()
+                .filter_map(|o| {
+                    let description = o
+                        .description
+                        .filter(|d| !d.trim().is_empty())
+                        .or(o.kind)
+                        .map(|d| d.lines().next().unwrap_or_default().trim().to_string());
+                    relative_artifact(&o.path, workspace, description)
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Express `path` relative to the output directory, else the workspace. `None` outside both.
+fn relative_artifact(
+    path: &Path,
+    workspace: &Path,
+    description: Option<String>,
+) -> Option<Artifact> {
+    let path = normalize_path(path);
+    let roots = [
+        (output_root(workspace), ArtifactRoot::Output),
+        (workspace.to_path_buf(), ArtifactRoot::Workspace),
+    ];
+    let (relative, root) = roots.iter().find_map(|(dir, root)| {
+        let canonical = || Some((path.canonicalize().ok()?, dir.canonicalize().ok()?));
+        path.strip_prefix(dir)
+            .ok()
+            .map(Path::to_path_buf)
+            .or_else(|| {
+                let (path, dir) = canonical()?;
+                path.strip_prefix(dir).ok().map(Path::to_path_buf)
+            })
+            .map(|rel| (rel, *root))
+    })?;
+    let relative: Vec<String> = relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    if relative.is_empty() {
+        return None;
+    }
+    Some(Artifact {
+        description: description.unwrap_or_else(|| describe_file(&path)),
+        path: relative.join("/"),
+        root,
+    })
+}
+
+/// Absolute location of an artifact recorded relative to its root.
+pub(super) fn absolute_path(artifact: &Artifact, workspace: &Path) -> PathBuf {
+    let root = match artifact.root {
+        ArtifactRoot::Output => output_root(workspace),
+        ArtifactRoot::Workspace => workspace.to_path_buf(),
+    };
+    root.join(&artifact.path)
+}
+
+/// e.g. `HTML file, 1.2 KB`.
+fn describe_file(path: &Path) -> String {
+    let kind = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if !ext.is_empty() => format!("{} file", ext.to_uppercase()),
+        _ => "File".to_string(),
+    };
+    match std::fs::metadata(path) {
+        Ok(meta) => format!("{}, {}", kind, format_size(meta.len())),
+        Err(_) => kind,
+    }
+}
+
+/// Add `found` to the turn's list; a path written again keeps its place and the newer
+/// description (size after an append).
+pub(super) fn record(artifacts: &mut Vec<Artifact>, found: Vec<Artifact>) {
+    for artifact in found {
+        match artifacts
+            .iter_mut()
+            .find(|a| a.root == artifact.root && a.path == artifact.path)
+        {
+            Some(existing) => *existing = artifact,
+            None => artifacts.push(artifact),
+        }
+    }
+}
+
+fn is_mentioned(response: &str, artifact: &Artifact) -> bool {
+    let file_name = artifact.path.rsplit('/').next().unwrap_or(&artifact.path);
+    response.contains(&artifact.path) || response.contains(file_name)
+}
+
+/// The section to append to `response`, or `None` when every artifact is already mentioned.
+fn citation_section(response: &str, artifacts: &[Artifact]) -> Option<String> {
+    if response.contains(ARTIFACTS_HEADING) {
+        return None;
+    }
+    let lines: Vec<String> = artifacts
+        .iter()
+        .filter(|a| !is_mentioned(response, a))
+        .map(|a| format!("- `{}` — {}", a.path, a.description))
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    Some(format!("{}\n{}", ARTIFACTS_HEADING, lines.join("\n")))
+}
+
+/// Attach the turn's artifacts to `result`, appending (and emitting) the citation section
+/// when the answer leaves some of them out. No-op without artifacts.
+pub(super) fn cite(
+    result: &mut AgentResult,
+    artifacts: Vec<Artifact>,
+    event_sink: &mut dyn EventSink,
+) {
+    if artifacts.is_empty() {
+        return;
+    }
+    if let Some(section) = citation_section(&result.response, &artifacts) {
+        if !result.response.trim().is_empty() {
+            result.response.push_str("\n\n");
+        }
+        result.response.push_str(&section);
+        event_sink.emit_assistant_visible(&section);
+    }
+    result.artifacts = artifacts;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact(path: &str) -> Artifact {
+        Artifact {
+            path: path.to_string(),
+            root: ArtifactRoot::Output,
+            description: "MD file, 12 B".to_string(),
+        }
+    }
+
+    #[test]
+    fn section_lists_only_unmentioned_files_once() {
+        let artifacts = vec![artifact("reports/summary.md"), artifact("chart.png")];
+        let section = citation_section("Saved the chart as chart.png.", &artifacts).unwrap();
+        assert!(section.contains("`reports/summary.md`"));
+        assert!(!section.contains("chart.png"));
+
+        let answered = format!("Done.\n\n{}", section);
+        assert_eq!(citation_section(&answered, &artifacts), None);
+        assert_eq!(
+            citation_section("See summary.md and chart.png", &artifacts),
+            None
+        );
+    }
+
+    #[test]
+    fn paths_are_relative_to_output_dir_or_workspace() {
+        let workspace = tempfile::tempdir().unwrap();
+        let ws = workspace.path();
+        let out = from_tool_call(
+            "write_output",
+            r#"{"file_path":"sub/../report.html","content":"x"}"#,
+            "",
+            ws,
+            false,
+        );
+        assert_eq!(out[0].path, "report.html");
+        assert_eq!(out[0].root, ArtifactRoot::Output);
+
+        let out = from_tool_call(
+            "write_file",
+            r#"{"path":"docs/notes.md","content":"x"}"#,
+            "",
+            ws,
+            false,
+        );
+        assert_eq!(out[0].path, "docs/notes.md");
+        assert_eq!(out[0].root, ArtifactRoot::Workspace);
+
+        let outside = from_tool_call("write_file", r#"{"path":"/etc/hosts"}"#, "", ws, false);
+        assert!(outside.is_empty());
+    }
+
+    #[test]
+    fn skill_outputs_come_from_the_result_json_listing() {
+        let workspace = tempfile::tempdir().unwrap();
+        let ws = workspace.path();
+        let chart = ws.join("output").join("chart.png");
+        let run_output = serde_json::json!({
+            "stdout": {},
+            "outputs": [{ "path": chart, "type": "image", "description": "Sales chart\nQ3" }],
+        });
+        let content = format!(
+            "{}{}- {}",
+            run_output,
+            OUTPUT_FILES_SEPARATOR,
+            chart.display()
+        );
+
+        let found = from_tool_call("plot", "{}", &content, ws, true);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, "chart.png");
+        assert_eq!(found[0].description, "Sales chart");
+        assert!(from_tool_call("plot", "{}", &content, ws, false).is_empty());
+    }
+}
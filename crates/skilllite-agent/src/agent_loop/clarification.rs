@@ -190,10 +190,12 @@ mod tests {
             ToolExecDetail {
                 tool: "read_file".to_string(),
                 success: true,
+                ..Default::default()
             },
             ToolExecDetail {
                 tool: "run_command".to_string(),
                 success: false,
+                ..Default::default()
             },
         ];
         let m = too_many_failures_message(2, &details);
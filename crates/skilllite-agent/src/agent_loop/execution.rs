@@ -1,14 +1,15 @@
 //! Execution sub-module: tool-call batch processing for the agent loop.
 //!
 //! Handles progressive disclosure, per-task call depth, update_task_plan
-//! replan, failure tracking, and result processing for both simple and
-//! task-planning loop paths.
+//! replan, failure tracking, identical-call loop detection, and result
+//! processing for both simple and task-planning loop paths.
 
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::path::Path;
 use std::time::Instant;
 
 use serde_json::Value;
+use skilllite_core::redact::SecretRedactor;
 
 use super::super::extensions::{
     self, MemoryVectorContext, PlanningControlExecutor, PlanningControlKind,
@@ -17,9 +18,10 @@ use super::super::llm::LlmClient;
 use super::super::skills::LoadedSkill;
 use super::super::task_planner::TaskPlanner;
 use super::super::types::*;
+use super::artifacts;
 use super::helpers::{
-    execute_tool_call, handle_complete_task, handle_update_task_plan,
-    inject_progressive_disclosure, process_result_content,
+    apply_task_plan_update, execute_tool_call, handle_complete_task, inject_progressive_disclosure,
+    parse_update_task_plan, process_result_content,
 };
 
 /// Helper to get current timestamp string
@@ -68,6 +70,7 @@ fn append_tool_call_to_transcript(
         tool_call_id: tool_call_id.to_string(),
         name: name.to_string(),
         arguments: arguments.to_string(),
+        args_digest: Some(args_digest(arguments)),
         timestamp: now,
     };
     let _ = skilllite_executor::transcript::append_entry(&t_path, &tool_call_entry);
@@ -117,6 +120,85 @@ fn append_tool_result_to_transcript(
     let _ = skilllite_executor::transcript::append_entry(&t_path, &tool_result_entry);
 }
 
+/// First 16 hex chars of the SHA-256 of the raw arguments.
+fn args_digest(arguments: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let mut digest = hex::encode(Sha256::digest(arguments.as_bytes()));
+    digest.truncate(16);
+    digest
+}
+
+/// Append one `artifact` entry per file a tool call produced (after its ToolResult).
+fn append_artifacts_to_transcript(
+    session_key: Option<&str>,
+    tool_call_id: &str,
+    workspace: &Path,
+    produced: &[Artifact],
+) {
+    let Some(session_key) = session_key else {
+        return;
+    };
+    if produced.is_empty() {
+        return;
+    }
+    let transcripts_dir = skilllite_executor::chat_root().join("transcripts");
+    let t_path =
+        skilllite_executor::transcript::transcript_path_today(&transcripts_dir, session_key);
+    if t_path
+        .parent()
+        .is_none_or(|p| skilllite_fs::create_dir_all(p).is_err())
+    {
+        return;
+    }
+    for artifact in produced {
+        let path = artifacts::absolute_path(artifact, workspace);
+        let entry = skilllite_executor::transcript::TranscriptEntry::Artifact {
+            id: uuid::Uuid::new_v4().to_string(),
+            parent_id: None,
+            bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            path: path.to_string_lossy().into_owned(),
+            tool_call_id: Some(tool_call_id.to_string()),
+            timestamp: timestamp_now(),
+        };
+        let _ = skilllite_executor::transcript::append_entry(&t_path, &entry);
+    }
+}
+
+/// Record an accepted replan as a `replan` custom transcript entry.
+fn append_replan_to_transcript(
+    session_key: Option<&str>,
+    old_tasks: &[Task],
+    new_tasks: &[Task],
+    reason: &str,
+    replan: usize,
+) {
+    let Some(session_key) = session_key else {
+        return;
+    };
+    let transcripts_dir = skilllite_executor::chat_root().join("transcripts");
+    let t_path =
+        skilllite_executor::transcript::transcript_path_today(&transcripts_dir, session_key);
+    if t_path
+        .parent()
+        .is_none_or(|p| skilllite_fs::create_dir_all(p).is_err())
+    {
+        return;
+    }
+    let entry = skilllite_executor::transcript::TranscriptEntry::Custom {
+        id: uuid::Uuid::new_v4().to_string(),
+        parent_id: None,
+        kind: "replan".to_string(),
+        data: serde_json::json!({
+            "replan": replan,
+            "reason": reason,
+            "old_tasks": old_tasks,
+            "new_tasks": new_tasks,
+            "timestamp": timestamp_now(),
+        }),
+    };
+    let _ = skilllite_executor::transcript::append_entry(&t_path, &entry);
+}
+
 // ── Shared state ─────────────────────────────────────────────────────────────
 
 /// Mutable counters accumulated across all loop iterations.
@@ -132,9 +214,16 @@ pub(super) struct ExecutionState {
     pub consecutive_failures: usize,
     /// Calls since the last per-task depth reset.
     pub tool_calls_current_task: usize,
+    /// Accepted update_task_plan calls this run.
     pub replan_count: usize,
+    /// Replans allowed before large plan rewrites are rejected (`AgentConfig::max_replans`).
+    pub replan_budget: usize,
+    /// Reason of each accepted replan, recorded in the feedback row.
+    pub replan_reasons: Vec<String>,
     pub tools_detail: Vec<ToolExecDetail>,
     pub context_overflow_retries: usize,
+    /// Consecutive turns re-run because the response stream broke after partial output.
+    pub stream_interrupt_retries: usize,
     pub iterations: usize,
     pub rules_used: Vec<String>,
     pub completion_type: TaskCompletionType,
@@ -146,10 +235,61 @@ pub(super) struct ExecutionState {
     pub max_repeated_failure_seen: usize,
     /// Cumulative LLM token usage (API-reported) for this agent run.
     pub llm_usage_totals: LlmUsageTotals,
+    /// Tool calls with unparseable arguments seen this turn (see `adaptive`).
+    pub malformed_tool_calls: usize,
+    /// LLM calls dropped and re-issued at temperature 0 because of malformed calls.
+    pub zero_temperature_retries: usize,
+    /// Set once malformed-call recovery kicked in; later calls use temperature 0.
+    pub zero_temperature: bool,
+    /// Iterations in a row whose tool calls all failed.
+    pub consecutive_failed_iterations: usize,
+    /// Model the turn switched to, if the fallback was used.
+    pub fallback_model: Option<String>,
+    /// Secrets replaced in tool results this run (see [`Self::redact_tool_output`]).
+    pub secrets_redacted: usize,
+    /// Files written by tool calls this turn (see `artifacts`).
+    pub artifacts: Vec<Artifact>,
+    /// Identical calls in a row before one is skipped with an intervention
+    /// (`AgentConfig::tool_loop_threshold`; 0 = off).
+    pub tool_loop_threshold: usize,
+    /// Identical calls in a row that stop the current task or turn (`AgentConfig::tool_loop_hard_stop`).
+    pub tool_loop_hard_stop: usize,
+    /// Loops that reached `tool_loop_threshold` this run.
+    pub tool_loops_detected: usize,
+    /// Most recent calls, oldest first; cleared when a different call comes in, so its length is
+    /// the current run of identical calls.
+    recent_calls: VecDeque<RecentCall>,
+    secret_redactor: SecretRedactor,
 }
 
 const REPEATED_FAILURE_THRESHOLD: usize = 2;
 
+/// One entry of [`ExecutionState`]'s loop-detection window.
+struct RecentCall {
+    tool_name: String,
+    args_hash: String,
+    /// Start of the result; `None` while the call is running or when it was skipped.
+    result: Option<String>,
+}
+
+/// Characters of each earlier result quoted in a loop intervention.
+const LOOP_RESULT_PREVIEW_CHARS: usize = 200;
+
+/// What to do with a tool call given the identical calls right before it.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum LoopCheck {
+    Execute,
+    /// The streak just reached the threshold: skip the call and send `message` once.
+    Intervene {
+        repeats: usize,
+        message: String,
+    },
+    /// Still repeating after the intervention: skip the call (`repeats` so far).
+    Skip(usize),
+    /// The streak reached the hard stop.
+    Stop(usize),
+}
+
 /// After this many repeated complete_task failures, auto-complete the task
 /// (only if the agent has executed at least one real tool, proving work was done).
 const PLANNING_CONTROL_AUTO_RECOVER_THRESHOLD: usize = 3;
@@ -163,8 +303,11 @@ impl ExecutionState {
             consecutive_failures: 0,
             tool_calls_current_task: 0,
             replan_count: 0,
+            replan_budget: DEFAULT_REPLAN_BUDGET,
+            replan_reasons: Vec::new(),
             tools_detail: Vec::new(),
             context_overflow_retries: 0,
+            stream_interrupt_retries: 0,
             iterations: 0,
             rules_used: Vec::new(),
             completion_type: TaskCompletionType::Success,
@@ -173,7 +316,133 @@ impl ExecutionState {
             max_consecutive_failures_seen: 0,
             max_repeated_failure_seen: 0,
             llm_usage_totals: LlmUsageTotals::default(),
+            malformed_tool_calls: 0,
+            zero_temperature_retries: 0,
+            zero_temperature: false,
+            consecutive_failed_iterations: 0,
+            fallback_model: None,
+            secrets_redacted: 0,
+            artifacts: Vec::new(),
+            tool_loop_threshold: 3,
+            tool_loop_hard_stop: 5,
+            tool_loops_detected: 0,
+            recent_calls: VecDeque::new(),
+            secret_redactor: SecretRedactor::from_env(),
+        }
+    }
+
+    /// Record a call about to run and decide whether it may: the `tool_loop_threshold`-th
+    /// identical call in a row is replaced by an intervention, later ones are skipped, and the
+    /// `tool_loop_hard_stop`-th stops the task.
+    pub fn check_tool_loop(&mut self, tool_name: &str, arguments: &str) -> LoopCheck {
+        let args_hash = args_digest(arguments);
+        let same = self
+            .recent_calls
+            .back()
+            .is_some_and(|c| c.tool_name == tool_name && c.args_hash == args_hash);
+        if !same {
+            self.recent_calls.clear();
+        }
+        let threshold = self.tool_loop_threshold;
+        let hard_stop = self.tool_loop_hard_stop.max(threshold + 1);
+        self.recent_calls.push_back(RecentCall {
+            tool_name: tool_name.to_string(),
+            args_hash,
+            result: None,
+        });
+        let repeats = self.recent_calls.len();
+        if threshold == 0 || repeats < threshold {
+            return LoopCheck::Execute;
+        }
+        if repeats >= hard_stop {
+            self.recent_calls.clear();
+            return LoopCheck::Stop(repeats);
+        }
+        if repeats > threshold {
+            return LoopCheck::Skip(repeats);
+        }
+        self.tool_loops_detected += 1;
+        LoopCheck::Intervene {
+            repeats,
+            message: self.loop_intervention(tool_name, repeats),
+        }
+    }
+
+    /// Keep the start of the latest call's result for a later intervention.
+    pub fn record_call_result(&mut self, content: &str) {
+        if let Some(last) = self.recent_calls.back_mut() {
+            last.result = Some(content.chars().take(LOOP_RESULT_PREVIEW_CHARS).collect());
+        }
+    }
+
+    fn loop_intervention(&self, tool_name: &str, repeats: usize) -> String {
+        let mut results: Vec<&str> = Vec::new();
+        for r in self.recent_calls.iter().filter_map(|c| c.result.as_deref()) {
+            if !results.contains(&r) {
+                results.push(r);
+            }
+        }
+        let summary = match results.as_slice() {
+            [] => "no result".to_string(),
+            [only] => format!("the same result each time:\n{}", only),
+            many => many
+                .iter()
+                .enumerate()
+                .map(|(i, r)| format!("{}. {}", i + 1, r))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        format!(
+            "⚠️ TOOL LOOP: `{}` was called {} times in a row with identical arguments; the latest \
+             call was NOT executed. The earlier calls returned {}\n\n\
+             Repeating the call will not produce anything new. Change approach (different \
+             arguments, another tool, or a revised plan), or report that the task is blocked \
+             and why. {} more identical calls will stop the task.",
+            tool_name,
+            repeats,
+            summary,
+            self.tool_loop_hard_stop.max(self.tool_loop_threshold + 1) - repeats,
+        )
+    }
+
+    /// Record the files a successful tool call produced and return them. Call before the
+    /// result is summarized so a skill's file listing is still intact.
+    pub fn record_artifacts(
+        &mut self,
+        registry: &extensions::ExtensionRegistry<'_>,
+        tool_name: &str,
+        arguments: &str,
+        result: &ToolResult,
+        workspace: &Path,
+    ) -> Vec<Artifact> {
+        if result.is_error {
+            return Vec::new();
         }
+        let found = artifacts::from_tool_call(
+            tool_name,
+            arguments,
+            &result.content,
+            workspace,
+            registry.skill_name_for_tool(tool_name).is_some(),
+        );
+        artifacts::record(&mut self.artifacts, found.clone());
+        found
+    }
+
+    /// Redact secrets in a tool result before it is truncated, summarized or sent to the LLM.
+    pub fn redact_tool_output(&mut self, tool_name: &str, content: &mut String) {
+        let redacted = self.secret_redactor.redact(content);
+        if redacted.count == 0 {
+            return;
+        }
+        tracing::info!(
+            tool = tool_name,
+            count = redacted.count,
+            "Redacted secrets from tool result"
+        );
+        let count = redacted.count;
+        *content = redacted.text.into_owned();
+        self.secrets_redacted += count;
     }
 
     /// Record a tool failure and return the repeat count for this specific signature.
@@ -242,6 +511,8 @@ pub(super) struct ToolBatchOutcome {
     pub failure_limit_reached: bool,
     /// Per-task call depth reached — caller should inject depth-limit message.
     pub depth_limit_reached: bool,
+    /// Identical-call loop hit the hard stop with no task to fail — caller should stop.
+    pub loop_stop_reached: bool,
 }
 
 pub(super) fn should_suppress_planning_assistant_text(
@@ -251,14 +522,29 @@ pub(super) fn should_suppress_planning_assistant_text(
     has_tool_calls && !planner.all_completed() && planner.current_task().is_some()
 }
 
-/// Soft upper limit on how many times a single session may call update_task_plan.
-const MAX_REPLANS_PER_SESSION: usize = 3;
+/// Replan budget when the caller does not set one (`SKILLLITE_MAX_REPLANS`).
+const DEFAULT_REPLAN_BUDGET: usize = 3;
+
+/// Once the replan budget is spent, update_task_plan may still swap this many pending tasks.
+const MAX_TASKS_REPLACED_OVER_BUDGET: usize = 2;
+
+/// How many pending tasks `new_tasks` replaces: the larger of tasks dropped from and tasks
+/// added to the pending part of `old_tasks` (compared by description).
+fn replaced_task_count(old_tasks: &[Task], new_tasks: &[Task]) -> usize {
+    let key = |t: &Task| t.description.trim().to_lowercase();
+    let old: Vec<String> = old_tasks.iter().filter(|t| !t.completed).map(key).collect();
+    let new: Vec<String> = new_tasks.iter().filter(|t| !t.completed).map(key).collect();
+    let dropped = old.iter().filter(|d| !new.contains(d)).count();
+    let added = new.iter().filter(|d| !old.contains(d)).count();
+    dropped.max(added)
+}
 
 /// Executor for planning control tools, passed to registry.execute() in planning mode.
 struct PlanningControlExecutorImpl<'a> {
     planner: &'a mut TaskPlanner,
     skills: &'a [LoadedSkill],
     state: &'a mut ExecutionState,
+    session_key: Option<&'a str>,
 }
 
 impl PlanningControlExecutor for PlanningControlExecutorImpl<'_> {
@@ -270,21 +556,61 @@ impl PlanningControlExecutor for PlanningControlExecutorImpl<'_> {
     ) -> super::super::types::ToolResult {
         match kind {
             PlanningControlKind::UpdateTaskPlan => {
+                let update = match parse_update_task_plan(arguments) {
+                    Ok(u) => u,
+                    Err(r) => return r,
+                };
+                let replaced = replaced_task_count(&self.planner.task_list, &update.tasks);
+                if self.state.replan_count >= self.state.replan_budget
+                    && replaced > MAX_TASKS_REPLACED_OVER_BUDGET
+                {
+                    tracing::info!(
+                        "Replan rejected: budget spent ({}/{}), update replaces {} tasks",
+                        self.state.replan_count,
+                        self.state.replan_budget,
+                        replaced
+                    );
+                    return super::super::types::ToolResult {
+                        tool_call_id: String::new(),
+                        tool_name: "update_task_plan".to_string(),
+                        content: format!(
+                            "Replan rejected: the replan budget for this session is spent \
+                             ({} of {} used) and this update replaces {} tasks \
+                             (at most {} may change now). Do NOT call update_task_plan again. \
+                             EXECUTE the current plan step by step, starting with the current task.",
+                            self.state.replan_count,
+                            self.state.replan_budget,
+                            replaced,
+                            MAX_TASKS_REPLACED_OVER_BUDGET
+                        ),
+                        is_error: true,
+                        counts_as_failure: false,
+                    };
+                }
+
+                let old_tasks = self.planner.task_list.clone();
+                let reason = update.reason.clone();
+                let mut r = apply_task_plan_update(update, self.planner, self.skills, event_sink);
+                if r.is_error {
+                    return r;
+                }
                 self.state.replan_count += 1;
-                let mut r =
-                    handle_update_task_plan(arguments, self.planner, self.skills, event_sink);
-                if !r.is_error && self.state.replan_count >= MAX_REPLANS_PER_SESSION {
+                event_sink.on_replan(&old_tasks, &self.planner.task_list, &reason);
+                append_replan_to_transcript(
+                    self.session_key,
+                    &old_tasks,
+                    &self.planner.task_list,
+                    &reason,
+                    self.state.replan_count,
+                );
+                self.state.replan_reasons.push(reason);
+                if self.state.replan_count >= self.state.replan_budget {
                     r.content.push_str(&format!(
-                        "\n\n⚠️ You have now replanned {} time(s). \
+                        "\n\n⚠️ You have now replanned {} time(s) (budget {}). \
                          Please STOP replanning and EXECUTE the current plan step by step. \
-                         Do NOT call update_task_plan again.",
-                        self.state.replan_count
+                         Further plan rewrites will be rejected.",
+                        self.state.replan_count, self.state.replan_budget
                     ));
-                    tracing::info!(
-                        "Replan soft limit reached ({}/{})",
-                        self.state.replan_count,
-                        MAX_REPLANS_PER_SESSION
-                    );
                 }
                 r
             }
@@ -312,6 +638,24 @@ impl PlanningControlExecutor for PlanningControlExecutorImpl<'_> {
 
 // ── Planning-mode batch ───────────────────────────────────────────────────────
 
+/// Tool result for a call skipped by loop detection (the intervention follows the batch).
+const LOOP_SKIPPED_RESULT: &str =
+    "Skipped: identical to the previous calls, which already returned their result.";
+
+/// Answer a tool call without running it; every call still needs a tool result message.
+fn answer_without_running(
+    tc: &ToolCall,
+    content: &str,
+    event_sink: &mut dyn EventSink,
+    messages: &mut Vec<ChatMessage>,
+    session_key: Option<&str>,
+) {
+    let tool_name = &tc.function.name;
+    append_tool_result_to_transcript(session_key, &tc.id, tool_name, content, false, None);
+    event_sink.on_tool_result_with_id(Some(&tc.id), tool_name, content, false);
+    messages.push(ChatMessage::tool_result(&tc.id, content));
+}
+
 /// Execute a batch of tool calls in **planning mode** (supports `update_task_plan`).
 ///
 /// Updates `state` in place. Returns a `ToolBatchOutcome` the caller uses to
@@ -339,6 +683,7 @@ pub(super) async fn execute_tool_batch_planning(
             disclosure_injected: true,
             failure_limit_reached: false,
             depth_limit_reached: false,
+            loop_stop_reached: false,
         };
     }
 
@@ -347,6 +692,8 @@ pub(super) async fn execute_tool_batch_planning(
     // work for future tasks without seeing the updated progress context that
     // the next iteration would inject (task focus message, nudge, etc.).
     let mut task_transitioned = false;
+    let mut intervention = None;
+    let mut loop_stop_reached = false;
 
     for tc in tool_calls {
         let tool_name = &tc.function.name;
@@ -359,41 +706,92 @@ pub(super) async fn execute_tool_batch_planning(
         let is_complete_task = matches!(planning_kind, Some(PlanningControlKind::CompleteTask));
         let result_profile = registry.result_processing_profile(tool_name);
 
+        if loop_stop_reached {
+            answer_without_running(
+                tc,
+                "Skipped: the turn was stopped by a tool-call loop.",
+                event_sink,
+                messages,
+                session_key,
+            );
+            continue;
+        }
         if task_transitioned {
             tracing::info!(
                 "Skipped post-transition tool call: {} (task already advanced, deferring to next iteration)",
                 tool_name
             );
-            let result = ToolResult {
-                tool_call_id: tc.id.clone(),
-                tool_name: tool_name.clone(),
-                content: "Skipped: a task was just completed and the plan advanced. \
-                          Continue with the next task in your next response."
-                    .to_string(),
-                is_error: false,
-                counts_as_failure: false,
-            };
-            append_tool_result_to_transcript(
+            answer_without_running(
+                tc,
+                "Skipped: a task was just completed and the plan advanced. \
+                 Continue with the next task in your next response.",
+                event_sink,
+                messages,
                 session_key,
-                &tc.id,
-                tool_name,
-                &result.content,
-                false,
-                None,
             );
-            event_sink.on_tool_result_with_id(
-                Some(&result.tool_call_id),
-                tool_name,
-                &result.content,
-                false,
-            );
-            messages.push(ChatMessage::tool_result(
-                &result.tool_call_id,
-                &result.content,
-            ));
             continue;
         }
 
+        if !is_planning_control {
+            match state.check_tool_loop(tool_name, arguments) {
+                LoopCheck::Execute => {}
+                LoopCheck::Intervene { repeats, message } => {
+                    event_sink.on_tool_loop(tool_name, repeats, false);
+                    answer_without_running(
+                        tc,
+                        LOOP_SKIPPED_RESULT,
+                        event_sink,
+                        messages,
+                        session_key,
+                    );
+                    intervention = Some(message);
+                    continue;
+                }
+                LoopCheck::Skip(_) => {
+                    answer_without_running(
+                        tc,
+                        LOOP_SKIPPED_RESULT,
+                        event_sink,
+                        messages,
+                        session_key,
+                    );
+                    continue;
+                }
+                LoopCheck::Stop(repeats) => {
+                    event_sink.on_tool_loop(tool_name, repeats, true);
+                    let content = match planner.current_task().map(|t| t.id) {
+                        Some(task_id) => {
+                            tracing::warn!(
+                                "Failing task {} after {} identical {} calls",
+                                task_id,
+                                repeats,
+                                tool_name
+                            );
+                            planner.mark_failed(task_id);
+                            state.observe_completion_type(TaskCompletionType::Failure);
+                            event_sink.on_task_progress(task_id, false, &planner.task_list);
+                            state.tool_calls_current_task = 0;
+                            task_transitioned = true;
+                            format!(
+                                "Stopped: `{}` was called {} times in a row with identical \
+                                 arguments. Task {} was marked failed; continue with the next task.",
+                                tool_name, repeats, task_id
+                            )
+                        }
+                        None => {
+                            loop_stop_reached = true;
+                            format!(
+                                "Stopped: `{}` was called {} times in a row with identical arguments.",
+                                tool_name, repeats
+                            )
+                        }
+                    };
+                    answer_without_running(tc, &content, event_sink, messages, session_key);
+                    continue;
+                }
+            }
+        }
+
         // Snapshot current task before execution to detect task transitions.
         let task_before = planner.current_task().map(|t| t.id);
         let start_time = Instant::now();
@@ -401,6 +799,7 @@ pub(super) async fn execute_tool_batch_planning(
             planner,
             skills,
             state,
+            session_key,
         };
         let mut result = execute_tool_call(
             registry,
@@ -413,8 +812,17 @@ pub(super) async fn execute_tool_batch_planning(
         )
         .await;
         result.tool_call_id = tc.id.clone();
+        planning_executor
+            .state
+            .redact_tool_output(tool_name, &mut result.content);
+        let produced = planning_executor
+            .state
+            .record_artifacts(registry, tool_name, arguments, &result, workspace);
         result.content =
             process_result_content(client, model, result_profile, &result.content).await;
+        if !is_planning_control {
+            planning_executor.state.record_call_result(&result.content);
+        }
 
         if result.counts_as_failure {
             planning_executor.state.failed_tool_calls += 1;
@@ -484,14 +892,21 @@ pub(super) async fn execute_tool_batch_planning(
                 .state
                 .observe_completion_type(completion_type);
         }
+        let elapsed_ms = start_time.elapsed().as_millis() as u64;
         if !is_planning_control {
-            planning_executor.state.tools_detail.push(ToolExecDetail {
-                tool: tool_name.clone(),
-                success: !result.is_error,
-            });
+            planning_executor
+                .state
+                .tools_detail
+                .push(ToolExecDetail::from_call(
+                    tool_name,
+                    registry.skill_name_for_tool(tool_name),
+                    arguments,
+                    &result.content,
+                    !result.is_error,
+                    elapsed_ms,
+                ));
         }
 
-        let elapsed_ms = start_time.elapsed().as_millis() as u64;
         append_tool_result_to_transcript(
             session_key,
             &tc.id,
@@ -500,6 +915,7 @@ pub(super) async fn execute_tool_batch_planning(
             result.is_error,
             Some(elapsed_ms),
         );
+        append_artifacts_to_transcript(session_key, &tc.id, workspace, &produced);
 
         registry.render_tool_result(tool_name, &result, event_sink);
         messages.push(ChatMessage::tool_result(
@@ -510,6 +926,10 @@ pub(super) async fn execute_tool_batch_planning(
         planning_executor.state.tool_calls_current_task += 1;
     }
 
+    if let Some(message) = intervention {
+        messages.push(ChatMessage::system(&message));
+    }
+
     let failure_limit_reached =
         max_consecutive_failures.is_some_and(|limit| state.consecutive_failures >= limit);
     let depth_limit_reached = state.tool_calls_current_task >= max_tool_calls_per_task;
@@ -518,6 +938,7 @@ pub(super) async fn execute_tool_batch_planning(
         disclosure_injected: false,
         failure_limit_reached,
         depth_limit_reached,
+        loop_stop_reached,
     }
 }
 
@@ -545,15 +966,55 @@ pub(super) async fn execute_tool_batch_simple(
             disclosure_injected: true,
             failure_limit_reached: false,
             depth_limit_reached: false,
+            loop_stop_reached: false,
         };
     }
 
+    let mut intervention = None;
+    let mut loop_stop_reached = false;
+
     for tc in tool_calls {
         let tool_name = &tc.function.name;
         let arguments = &tc.function.arguments;
         event_sink.on_tool_call_with_id(Some(&tc.id), tool_name, arguments);
         append_tool_call_to_transcript(session_key, &tc.id, tool_name, arguments);
 
+        if loop_stop_reached {
+            answer_without_running(
+                tc,
+                "Skipped: the turn was stopped by a tool-call loop.",
+                event_sink,
+                messages,
+                session_key,
+            );
+            continue;
+        }
+        match state.check_tool_loop(tool_name, arguments) {
+            LoopCheck::Execute => {}
+            LoopCheck::Intervene { repeats, message } => {
+                event_sink.on_tool_loop(tool_name, repeats, false);
+                answer_without_running(tc, LOOP_SKIPPED_RESULT, event_sink, messages, session_key);
+                intervention = Some(message);
+                continue;
+            }
+            LoopCheck::Skip(_) => {
+                answer_without_running(tc, LOOP_SKIPPED_RESULT, event_sink, messages, session_key);
+                continue;
+            }
+            LoopCheck::Stop(repeats) => {
+                tracing::warn!("Stopping: {} identical {} calls", repeats, tool_name);
+                state.observe_completion_type(TaskCompletionType::Failure);
+                event_sink.on_tool_loop(tool_name, repeats, true);
+                let content = format!(
+                    "Stopped: `{}` was called {} times in a row with identical arguments.",
+                    tool_name, repeats
+                );
+                answer_without_running(tc, &content, event_sink, messages, session_key);
+                loop_stop_reached = true;
+                continue;
+            }
+        }
+
         let result_profile = registry.result_processing_profile(tool_name);
         let start_time = Instant::now();
         let mut result = execute_tool_call(
@@ -561,8 +1022,11 @@ pub(super) async fn execute_tool_batch_simple(
         )
         .await;
         result.tool_call_id = tc.id.clone();
+        state.redact_tool_output(tool_name, &mut result.content);
+        let produced = state.record_artifacts(registry, tool_name, arguments, &result, workspace);
         result.content =
             process_result_content(client, model, result_profile, &result.content).await;
+        state.record_call_result(&result.content);
 
         if result.counts_as_failure {
             state.failed_tool_calls += 1;
@@ -581,12 +1045,16 @@ pub(super) async fn execute_tool_batch_simple(
             state.consecutive_failures = 0;
             state.reset_failure_sig();
         }
-        state.tools_detail.push(ToolExecDetail {
-            tool: tool_name.clone(),
-            success: !result.is_error,
-        });
-
         let elapsed_ms = start_time.elapsed().as_millis() as u64;
+        state.tools_detail.push(ToolExecDetail::from_call(
+            tool_name,
+            registry.skill_name_for_tool(tool_name),
+            arguments,
+            &result.content,
+            !result.is_error,
+            elapsed_ms,
+        ));
+
         append_tool_result_to_transcript(
             session_key,
             &tc.id,
@@ -595,6 +1063,7 @@ pub(super) async fn execute_tool_batch_simple(
             result.is_error,
             Some(elapsed_ms),
         );
+        append_artifacts_to_transcript(session_key, &tc.id, workspace, &produced);
 
         registry.render_tool_result(tool_name, &result, event_sink);
         messages.push(ChatMessage::tool_result(
@@ -604,12 +1073,17 @@ pub(super) async fn execute_tool_batch_simple(
         state.total_tool_calls += 1;
     }
 
+    if let Some(message) = intervention {
+        messages.push(ChatMessage::system(&message));
+    }
+
     let failure_limit_reached =
         max_consecutive_failures.is_some_and(|limit| state.consecutive_failures >= limit);
     ToolBatchOutcome {
         disclosure_injected: false,
         failure_limit_reached,
         depth_limit_reached: false,
+        loop_stop_reached,
     }
 }
 
@@ -932,6 +1406,139 @@ mod tests {
         );
     }
 
+    #[derive(Default)]
+    struct ReplanSink {
+        replans: Vec<(usize, usize, String)>,
+    }
+
+    impl EventSink for ReplanSink {
+        fn on_text(&mut self, _text: &str) {}
+        fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+        fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+        fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+            false
+        }
+        fn on_replan(&mut self, old_tasks: &[Task], new_tasks: &[Task], reason: &str) {
+            self.replans
+                .push((old_tasks.len(), new_tasks.len(), reason.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replans_beyond_budget_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path();
+        let registry = ExtensionRegistry::new(false, false, &[]);
+        let client = LlmClient::new("", "").expect("test client");
+        let mut planner = planner_with_tasks(vec![Task {
+            id: 1,
+            description: "Original task".to_string(),
+            tool_hint: None,
+            completed: false,
+        }]);
+        let mut sink = ReplanSink::default();
+        let mut messages = Vec::new();
+        let mut documented_skills = HashSet::new();
+        let mut state = ExecutionState::new();
+        assert_eq!(state.replan_budget, DEFAULT_REPLAN_BUDGET);
+
+        // A model that rewrites the whole plan on every turn.
+        for attempt in 1..=5 {
+            let args = serde_json::json!({
+                "tasks": [
+                    {"id": 1, "description": format!("Attempt {} step A", attempt)},
+                    {"id": 2, "description": format!("Attempt {} step B", attempt)},
+                    {"id": 3, "description": format!("Attempt {} step C", attempt)},
+                ],
+                "reason": format!("attempt {} looked wrong", attempt),
+            });
+            let tool_calls = vec![ToolCall {
+                id: format!("call_replan_{}", attempt),
+                call_type: "function".to_string(),
+                function: FunctionCall {
+                    name: "update_task_plan".to_string(),
+                    arguments: args.to_string(),
+                },
+            }];
+            execute_tool_batch_planning(
+                &tool_calls,
+                &registry,
+                workspace,
+                &mut sink,
+                None,
+                &client,
+                "test-model",
+                &mut planner,
+                &[],
+                &mut messages,
+                &mut documented_skills,
+                &mut state,
+                8,
+                Some(3),
+                None,
+            )
+            .await;
+        }
+
+        assert_eq!(state.replan_count, DEFAULT_REPLAN_BUDGET);
+        assert_eq!(
+            state.replan_reasons,
+            vec![
+                "attempt 1 looked wrong".to_string(),
+                "attempt 2 looked wrong".to_string(),
+                "attempt 3 looked wrong".to_string(),
+            ]
+        );
+        assert_eq!(sink.replans.len(), DEFAULT_REPLAN_BUDGET);
+        assert_eq!(
+            sink.replans[0],
+            (1, 3, "attempt 1 looked wrong".to_string())
+        );
+        assert!(planner.task_list[0].description.starts_with("Attempt 3"));
+        // Rejections steer the model back to execution without counting as tool failures.
+        assert_eq!(state.failed_tool_calls, 0);
+        let rejections: Vec<&ChatMessage> = messages
+            .iter()
+            .filter(|m| {
+                m.content
+                    .as_deref()
+                    .is_some_and(|c| c.starts_with("Replan rejected"))
+            })
+            .collect();
+        assert_eq!(rejections.len(), 2);
+        assert!(rejections[0]
+            .content
+            .as_deref()
+            .unwrap()
+            .contains("EXECUTE the current plan"));
+    }
+
+    #[test]
+    fn test_replaced_task_count_ignores_completed_and_unchanged_tasks() {
+        let task = |id, description: &str, completed| Task {
+            id,
+            description: description.to_string(),
+            tool_hint: None,
+            completed,
+        };
+        let old = vec![
+            task(1, "done", true),
+            task(2, "Fetch data", false),
+            task(3, "Render chart", false),
+        ];
+        let tweak = vec![
+            task(1, "fetch data ", false),
+            task(2, "Render table", false),
+        ];
+        assert_eq!(replaced_task_count(&old, &tweak), 1);
+        let rewrite = vec![
+            task(1, "Ask the user", false),
+            task(2, "Search the web", false),
+            task(3, "Summarize", false),
+        ];
+        assert_eq!(replaced_task_count(&old, &rewrite), 3);
+    }
+
     #[test]
     fn test_repeated_failure_detection() {
         let mut state = ExecutionState::new();
@@ -955,6 +1562,31 @@ mod tests {
         assert_eq!(c5, 1, "different tool should restart the count");
     }
 
+    #[test]
+    fn test_tool_loop_streak_resets_on_a_different_call() {
+        let mut state = ExecutionState::new();
+        let args = r#"{"path": "a.txt"}"#;
+        assert_eq!(state.check_tool_loop("read_file", args), LoopCheck::Execute);
+        assert_eq!(state.check_tool_loop("read_file", args), LoopCheck::Execute);
+        assert_eq!(
+            state.check_tool_loop("read_file", r#"{"path": "b.txt"}"#),
+            LoopCheck::Execute
+        );
+        assert_eq!(state.check_tool_loop("read_file", args), LoopCheck::Execute);
+        assert_eq!(state.check_tool_loop("read_file", args), LoopCheck::Execute);
+        assert!(matches!(
+            state.check_tool_loop("read_file", args),
+            LoopCheck::Intervene { repeats: 3, .. }
+        ));
+        assert_eq!(state.tool_loops_detected, 1);
+
+        let mut off = ExecutionState::new();
+        off.tool_loop_threshold = 0;
+        for _ in 0..10 {
+            assert_eq!(off.check_tool_loop("read_file", args), LoopCheck::Execute);
+        }
+    }
+
     #[tokio::test]
     async fn test_complete_task_auto_recovery_after_repeated_failures() {
         let tmp = tempfile::tempdir().unwrap();
@@ -5,6 +5,7 @@ use std::collections::HashSet;
 use std::path::Path;
 
 use serde_json::Value;
+use tracing::Instrument;
 
 use crate::Result;
 
@@ -37,24 +38,42 @@ fn unwrap_double_encoded_json(v: Value) -> Value {
     v
 }
 
+/// Parsed `update_task_plan` arguments; tasks are not sanitized yet.
+pub(super) struct TaskPlanUpdate {
+    pub tasks: Vec<Task>,
+    pub reason: String,
+}
+
 /// Handle update_task_plan: parse new tasks, sanitize & enhance (same as initial planning),
 /// replace planner.task_list, notify event_sink.
+#[cfg(test)]
 pub(super) fn handle_update_task_plan(
     arguments: &str,
     planner: &mut TaskPlanner,
     skills: &[LoadedSkill],
     event_sink: &mut dyn EventSink,
 ) -> super::super::types::ToolResult {
+    match parse_update_task_plan(arguments) {
+        Ok(update) => apply_task_plan_update(update, planner, skills, event_sink),
+        Err(result) => result,
+    }
+}
+
+/// Parse and validate update_task_plan arguments (`tasks` non-empty, `reason` required).
+/// Errors come back as the tool result to return to the model.
+pub(super) fn parse_update_task_plan(
+    arguments: &str,
+) -> std::result::Result<TaskPlanUpdate, super::super::types::ToolResult> {
     let args: Value = match serde_json::from_str(arguments) {
         Ok(v) => unwrap_double_encoded_json(v),
         Err(e) => {
-            return super::super::types::ToolResult {
+            return Err(super::super::types::ToolResult {
                 tool_call_id: String::new(),
                 tool_name: "update_task_plan".to_string(),
                 content: format!("Invalid JSON: {}", e),
                 is_error: true,
                 counts_as_failure: true,
-            };
+            });
         }
     };
     let tasks_arr = match args.get("tasks") {
@@ -65,7 +84,7 @@ pub(super) fn handle_update_task_plan(
                 match serde_json::from_str::<Vec<Value>>(s) {
                     Ok(a) => a,
                     Err(_) => {
-                        return super::super::types::ToolResult {
+                        return Err(super::super::types::ToolResult {
                             tool_call_id: String::new(),
                             tool_name: "update_task_plan".to_string(),
                             content: format!(
@@ -76,11 +95,11 @@ pub(super) fn handle_update_task_plan(
                             ),
                             is_error: true,
                             counts_as_failure: true,
-                        };
+                        });
                     }
                 }
             } else {
-                return super::super::types::ToolResult {
+                return Err(super::super::types::ToolResult {
                     tool_call_id: String::new(),
                     tool_name: "update_task_plan".to_string(),
                     content: format!(
@@ -90,18 +109,18 @@ pub(super) fn handle_update_task_plan(
                     ),
                     is_error: true,
                     counts_as_failure: true,
-                };
+                });
             }
         }
         None => {
-            return super::super::types::ToolResult {
+            return Err(super::super::types::ToolResult {
                 tool_call_id: String::new(),
                 tool_name: "update_task_plan".to_string(),
                 content: "Missing required field: 'tasks'. Pass an array of task objects."
                     .to_string(),
                 is_error: true,
                 counts_as_failure: true,
-            };
+            });
         }
     };
     let mut new_tasks = Vec::new();
@@ -131,22 +150,56 @@ pub(super) fn handle_update_task_plan(
         });
     }
     if new_tasks.is_empty() {
-        return super::super::types::ToolResult {
+        return Err(super::super::types::ToolResult {
             tool_call_id: String::new(),
             tool_name: "update_task_plan".to_string(),
             content: "Task list cannot be empty".to_string(),
             is_error: true,
             counts_as_failure: true,
-        };
+        });
     }
+    let reason = args
+        .get("reason")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .unwrap_or("");
+    if reason.is_empty() {
+        return Err(super::super::types::ToolResult {
+            tool_call_id: String::new(),
+            tool_name: "update_task_plan".to_string(),
+            content: "Missing required field: 'reason'. Say briefly why the current plan \
+                      no longer works."
+                .to_string(),
+            is_error: true,
+            counts_as_failure: true,
+        });
+    }
+    Ok(TaskPlanUpdate {
+        tasks: new_tasks,
+        reason: reason.to_string(),
+    })
+}
+
+/// Replace the pending part of the plan with `update.tasks` (completed tasks are kept),
+/// notify `event_sink.on_task_plan`.
+pub(super) fn apply_task_plan_update(
+    update: TaskPlanUpdate,
+    planner: &mut TaskPlanner,
+    skills: &[LoadedSkill],
+    event_sink: &mut dyn EventSink,
+) -> super::super::types::ToolResult {
+    let TaskPlanUpdate {
+        tasks: mut new_tasks,
+        reason,
+    } = update;
     // Apply same sanitize & enhance as initial planning (strip unavailable tool_hints, add SKILL.md if needed).
     planner.sanitize_and_enhance_tasks(&mut new_tasks, skills);
 
-    // Preserve completed tasks — only replace pending portion of the plan.
+    // Preserve completed and failed tasks — only replace pending portion of the plan.
     let completed_tasks: Vec<Task> = planner
         .task_list
         .iter()
-        .filter(|t| t.completed)
+        .filter(|t| t.completed || planner.is_failed(t.id))
         .cloned()
         .collect();
     let next_id = completed_tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
@@ -159,14 +212,10 @@ pub(super) fn handle_update_task_plan(
     merged.extend(new_tasks);
     planner.task_list = merged;
     event_sink.on_task_plan(&planner.task_list);
-    let reason = args.get("reason").and_then(|v| v.as_str()).unwrap_or("");
-    let mut content = format!(
-        "Task plan updated ({} tasks). Continue with the new plan.",
-        new_count
+    let content = format!(
+        "Task plan updated ({} tasks). Continue with the new plan.\nReason: {}",
+        new_count, reason
     );
-    if !reason.is_empty() {
-        content.push_str(&format!("\nReason: {}", reason));
-    }
     super::super::types::ToolResult {
         tool_call_id: String::new(),
         tool_name: "update_task_plan".to_string(),
@@ -336,7 +385,18 @@ pub(super) async fn execute_tool_call(
     embed_ctx: Option<&extensions::MemoryVectorContext<'_>>,
     planning_ctx: Option<&mut dyn extensions::PlanningControlExecutor>,
 ) -> ToolResult {
-    registry
+    // Names, sizes and timings only — never arguments or output (they may hold file contents).
+    let span = tracing::info_span!(
+        "agent.tool",
+        tool_name,
+        skill = registry.skill_name_for_tool(tool_name).unwrap_or(""),
+        arguments_bytes = arguments.len(),
+        result_bytes = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+        is_error = tracing::field::Empty,
+    );
+    let start = std::time::Instant::now();
+    let result = registry
         .execute(
             tool_name,
             arguments,
@@ -345,7 +405,12 @@ pub(super) async fn execute_tool_call(
             embed_ctx,
             planning_ctx,
         )
-        .await
+        .instrument(span.clone())
+        .await;
+    span.record("result_bytes", result.content.len());
+    span.record("duration_ms", start.elapsed().as_millis() as u64);
+    span.record("is_error", result.is_error);
+    result
 }
 
 /// Process tool result content: sync fast path, then async LLM summarization.
@@ -434,10 +499,9 @@ pub(super) fn inject_progressive_disclosure(
             if let Some(skill) = skills::find_skill_by_tool_name(skills, tool_name)
                 .or_else(|| skills::find_skill_by_name(skills, tool_name))
             {
-                if let Some(docs) = prompt::get_skill_full_docs(skill) {
-                    new_docs.push((tool_name.clone(), docs));
-                    documented_skills.insert(normalized);
-                }
+                // Same content the get_skill_details tool returns (docs cached per skill).
+                new_docs.push((tool_name.clone(), prompt::get_skill_details(skill)));
+                documented_skills.insert(normalized);
             }
         }
     }
@@ -849,6 +913,10 @@ pub(super) fn build_agent_result(
         task_plan,
         feedback,
         wiki_update_suggestion,
+        cancelled: false,
+        budget_exceeded: None,
+        tool_failure_cutoff: false,
+        artifacts: Vec::new(),
     }
 }
 
@@ -885,6 +953,21 @@ mod tests {
         assert!(r.content.contains("empty"));
     }
 
+    #[test]
+    fn handle_update_task_plan_requires_reason() {
+        let mut planner = TaskPlanner::new(None, None, None);
+        let mut sink = SilentEventSink;
+        let r = handle_update_task_plan(
+            r#"{"tasks":[{"description":"next step"}],"reason":"  "}"#,
+            &mut planner,
+            &[],
+            &mut sink,
+        );
+        assert!(r.is_error);
+        assert!(r.content.contains("'reason'"));
+        assert!(planner.task_list.is_empty());
+    }
+
     #[test]
     fn handle_update_task_plan_merges_with_completed_tasks() {
         let mut planner = TaskPlanner::new(None, None, None);
@@ -1,9 +1,14 @@
 //! LLM call sub-module: streaming/non-streaming dispatch with automatic
-//! context-overflow recovery.
+//! context-overflow and interrupted-stream recovery.
 //!
 //! Centralizes the LLM call + overflow retry pattern that was duplicated in
 //! both `run_simple_loop` and `run_with_task_planning`.
 
+use std::time::Instant;
+
+use skilllite_core::tokens::{self, HEURISTIC_CHARS_PER_TOKEN};
+use tracing::Instrument;
+
 use crate::Result;
 
 use super::super::llm::{self, llm_usage_report_from_usage, ChatCompletionResponse, LlmClient};
@@ -14,13 +19,16 @@ use super::super::types::{
 /// Maximum number of context overflow recovery retries before giving up.
 const MAX_CONTEXT_OVERFLOW_RETRIES: usize = 3;
 
+/// Maximum number of consecutive re-runs after a stream broke off mid-response.
+const MAX_STREAM_INTERRUPT_RETRIES: usize = 2;
+
 /// Outcome of an LLM call with overflow recovery.
 pub(super) enum LlmCallOutcome {
     /// Successfully received a response.
     Response(ChatCompletionResponse),
-    /// Context overflow detected; messages were truncated. Caller should retry
-    /// (i.e. `continue` the loop).
-    Truncated,
+    /// Recoverable failure (context overflow: messages were truncated; or the stream broke
+    /// after partial output). Caller should retry (i.e. `continue` the loop).
+    Retry,
 }
 
 /// Call the LLM with automatic context-overflow recovery.
@@ -29,9 +37,14 @@ pub(super) enum LlmCallOutcome {
 /// `chat_completion_stream`. When `false`, uses the non-streaming
 /// `chat_completion` (used when planning mode suppresses streaming).
 ///
-/// On context overflow, truncates tool messages and returns `Truncated` so the
+/// On context overflow, truncates tool messages and returns `Retry` so the
 /// caller can `continue`. After `MAX_CONTEXT_OVERFLOW_RETRIES` consecutive
 /// overflows, propagates the error.
+///
+/// Transient HTTP failures are retried inside [`LlmClient`]; a stream that broke after
+/// partial output is not, because the text already reached `event_sink`. That case tells
+/// the sink the response is being redone and returns `Retry`, up to
+/// `MAX_STREAM_INTERRUPT_RETRIES` consecutive times.
 #[allow(clippy::too_many_arguments)]
 pub(super) async fn call_llm_with_recovery(
     client: &LlmClient,
@@ -42,9 +55,21 @@ pub(super) async fn call_llm_with_recovery(
     stream: bool,
     event_sink: &mut dyn EventSink,
     context_overflow_retries: &mut usize,
+    stream_interrupt_retries: &mut usize,
     usage_totals: Option<&mut LlmUsageTotals>,
 ) -> Result<LlmCallOutcome> {
     event_sink.reset_streamed_text_for_llm_call();
+    let span = tracing::info_span!(
+        "agent.llm_call",
+        model,
+        stream,
+        messages = messages.len(),
+        tokens_in = tracing::field::Empty,
+        tokens_out = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+        overflow_retries = *context_overflow_retries,
+    );
+    let start = Instant::now();
     let result = if stream {
         client
             .chat_completion_stream(
@@ -55,22 +80,43 @@ pub(super) async fn call_llm_with_recovery(
                 event_sink,
                 usage_totals,
             )
+            .instrument(span.clone())
             .await
     } else {
         client
             .chat_completion(model, messages, tools, temperature, usage_totals)
+            .instrument(span.clone())
             .await
     };
+    span.record("latency_ms", start.elapsed().as_millis() as u64);
 
     match result {
         Ok(resp) => {
+            if let Some(usage) = &resp.usage {
+                span.record("tokens_in", usage.prompt_tokens);
+                span.record("tokens_out", usage.completion_tokens);
+            }
             *context_overflow_retries = 0;
+            *stream_interrupt_retries = 0;
             let report = resp.usage.as_ref().map(llm_usage_report_from_usage);
             event_sink.on_llm_usage(report);
             Ok(LlmCallOutcome::Response(resp))
         }
         Err(e) => {
-            if llm::is_context_overflow_error(&e.to_string()) {
+            if llm::is_stream_interrupted_error(&e.to_string()) {
+                *stream_interrupt_retries += 1;
+                if *stream_interrupt_retries > MAX_STREAM_INTERRUPT_RETRIES {
+                    return Err(e);
+                }
+                tracing::warn!(
+                    "{} (re-run {}/{})",
+                    e,
+                    *stream_interrupt_retries,
+                    MAX_STREAM_INTERRUPT_RETRIES
+                );
+                event_sink.on_status("response interrupted, regenerating it from the start");
+                Ok(LlmCallOutcome::Retry)
+            } else if llm::is_context_overflow_error(&e.to_string()) {
                 *context_overflow_retries += 1;
                 if *context_overflow_retries >= MAX_CONTEXT_OVERFLOW_RETRIES {
                     tracing::error!(
@@ -85,14 +131,15 @@ pub(super) async fn call_llm_with_recovery(
                     2 => base.max(400) / 2,
                     _ => base.max(400) / 4,
                 };
+                let max_tokens = rc / HEURISTIC_CHARS_PER_TOKEN;
                 tracing::warn!(
-                    "Context overflow (attempt {}/{}), truncating to {} chars",
+                    "Context overflow (attempt {}/{}), truncating tool results to {} tokens",
                     *context_overflow_retries,
                     MAX_CONTEXT_OVERFLOW_RETRIES,
-                    rc
+                    max_tokens
                 );
-                llm::truncate_tool_messages(messages, rc);
-                Ok(LlmCallOutcome::Truncated)
+                llm::truncate_tool_messages(messages, max_tokens, &tokens::for_model(model));
+                Ok(LlmCallOutcome::Retry)
             } else {
                 Err(e)
             }
@@ -0,0 +1,176 @@
+//! Tool-call loop detection through the real loops and a scripted endpoint that repeats the
+//! same call: the third identical call is replaced by one intervention, and the fifth ends the
+//! turn (simple loop) or fails the current task (planning loop).
+
+use serde_json::Value;
+
+use super::run_agent_loop;
+use super::scripted_llm::{self, Reply};
+use crate::types::*;
+
+const READ_NOTES: &str = r#"{"path": "notes.md"}"#;
+const PLAN: &str = r#"[{"id": 1, "description": "Understand notes.md", "completed": false},
+                       {"id": 2, "description": "Summarize it", "completed": false}]"#;
+
+fn config(api_base: String, workspace: &std::path::Path, planning: bool) -> AgentConfig {
+    AgentConfig {
+        api_base,
+        api_key: "test".to_string(),
+        model: "primary".to_string(),
+        max_iterations: 10,
+        workspace: workspace.to_string_lossy().to_string(),
+        enable_task_planning: planning,
+        enable_memory: false,
+        ..AgentConfig::default()
+    }
+}
+
+fn is_streaming(body: &Value) -> bool {
+    body.get("stream").and_then(Value::as_bool) == Some(true)
+}
+
+/// System messages of a request that carry a loop intervention.
+fn interventions(body: &Value) -> Vec<String> {
+    body["messages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|m| m["role"] == "system")
+        .filter_map(|m| m["content"].as_str())
+        .filter(|c| c.contains("TOOL LOOP"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Default)]
+struct LoopSink {
+    loops: Vec<(String, usize, bool)>,
+    completed_tasks: Vec<u32>,
+    failed_tasks: Vec<u32>,
+}
+
+impl EventSink for LoopSink {
+    fn on_text(&mut self, _text: &str) {}
+    fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+    fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+    fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+        false
+    }
+    fn on_tool_loop(&mut self, tool_name: &str, repeats: usize, stopped: bool) {
+        self.loops.push((tool_name.to_string(), repeats, stopped));
+    }
+    fn on_task_progress(&mut self, task_id: u32, completed: bool, _tasks: &[Task]) {
+        if completed {
+            self.completed_tasks.push(task_id);
+        } else {
+            self.failed_tasks.push(task_id);
+        }
+    }
+}
+
+fn workspace() -> tempfile::TempDir {
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::write(workspace.path().join("notes.md"), "hello from the notes").unwrap();
+    workspace
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn simple_loop_intervenes_once_then_stops_at_hard_stop() {
+    let workspace = workspace();
+    let llm = scripted_llm::spawn(|_, body| {
+        if is_streaming(body) {
+            Reply::tool_call("read_file", READ_NOTES)
+        } else {
+            Reply::text("Done.")
+        }
+    });
+    let config = config(llm.base, workspace.path(), false);
+
+    let mut sink = LoopSink::default();
+    let result = run_agent_loop(
+        &config,
+        Vec::new(),
+        "read notes",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        sink.loops,
+        [
+            ("read_file".to_string(), 3, false),
+            ("read_file".to_string(), 5, true)
+        ]
+    );
+    // Calls 3-5 were answered without running.
+    assert_eq!(result.feedback.total_tools, 2);
+    assert_eq!(result.feedback.tool_loops, 1);
+    assert!(!result.feedback.task_completed);
+
+    let requests = llm.requests.lock().unwrap();
+    let streamed: Vec<&Value> = requests.iter().filter(|b| is_streaming(b)).collect();
+    assert_eq!(streamed.len(), 5, "no LLM call after the hard stop");
+    let seen = interventions(streamed[4]);
+    assert_eq!(seen.len(), 1, "the intervention is injected once");
+    assert!(seen[0].contains("`read_file` was called 3 times"));
+    assert!(seen[0].contains("hello from the notes"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn planning_loop_fails_the_looping_task_and_moves_on() {
+    let workspace = workspace();
+    let llm = scripted_llm::spawn(|_, body| {
+        let system = body["messages"][0]["content"].as_str().unwrap_or("");
+        if system.starts_with("You are a task planning assistant") {
+            return Reply::text(PLAN);
+        }
+        let messages = body["messages"].to_string();
+        if messages.contains("\"complete_task\"") {
+            Reply::text("Task 1 was blocked; the notes say hello.")
+        } else if messages.contains("marked failed") {
+            Reply::tool_call(
+                "complete_task",
+                r#"{"task_id": 2, "completion_type": "success"}"#,
+            )
+        } else {
+            Reply::tool_call("read_file", READ_NOTES)
+        }
+    });
+    let config = config(llm.base, workspace.path(), true);
+
+    let mut sink = LoopSink::default();
+    let result = run_agent_loop(
+        &config,
+        Vec::new(),
+        "read notes",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        sink.loops,
+        [
+            ("read_file".to_string(), 3, false),
+            ("read_file".to_string(), 5, true)
+        ]
+    );
+    assert_eq!(sink.completed_tasks, [2]);
+    assert_eq!(sink.failed_tasks, [1]);
+    let done: Vec<(u32, bool)> = result
+        .task_plan
+        .iter()
+        .map(|t| (t.id, t.completed))
+        .collect();
+    assert_eq!(done, [(1, false), (2, true)]);
+    assert_eq!(result.feedback.tool_loops, 1);
+    assert!(!result.feedback.task_completed);
+    assert_eq!(result.feedback.completion_type, TaskCompletionType::Failure);
+}
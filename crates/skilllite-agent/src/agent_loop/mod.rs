@@ -13,21 +13,40 @@
 //!   - `helpers`        — shared low-level utilities (tool execution, result processing, …)
 //!   - `clarification`  — reusable clarification-request pattern
 //!   - `llm_call`       — LLM call dispatch with context-overflow recovery
+//!   - `adaptive`       — temperature-0 retry on malformed tool calls, fallback model
+//!   - `artifacts`      — files written during the turn, cited in the final answer
 //!
 //! **Assistant text to the user:** after each LLM completion, use [`crate::types::EventSink::emit_assistant_visible`]
 //! from this module and `reflection` (not [`crate::types::EventSink::on_text`]). Streaming still uses `on_text_chunk`;
 //! RPC/terminal sinks dedupe a redundant full body in [`crate::types::EventSink::on_text`].
 
+mod adaptive;
+#[cfg(test)]
+mod adaptive_tests;
+#[cfg(test)]
+mod artifact_tests;
+mod artifacts;
 mod clarification;
 mod execution;
 mod helpers;
 mod llm_call;
+#[cfg(test)]
+mod loop_detection_tests;
+#[cfg(test)]
+mod plan_approval_tests;
 mod planning;
 mod reflection;
+#[cfg(test)]
+mod replay_tests;
+#[cfg(test)]
+pub(crate) mod scripted_llm;
+#[cfg(test)]
+mod span_tests;
 
 use crate::Result;
 use std::collections::HashSet;
 use std::path::Path;
+use tracing::Instrument;
 
 use super::extensions::{self, MemoryVectorContext};
 use super::llm::LlmClient;
@@ -35,7 +54,7 @@ use super::prompt;
 use super::skills::LoadedSkill;
 use super::soul::Soul;
 use super::types::*;
-use skilllite_core::config::EmbeddingConfig;
+use skilllite_core::config::{EmbeddingBackend, EmbeddingConfig};
 
 use crate::mcp_client::bootstrap_mcp;
 use clarification::{
@@ -60,6 +79,7 @@ use reflection::{reflect_planning, reflect_simple, ReflectionOutcome};
 async fn build_registry_with_mcp<'a>(
     config: &'a AgentConfig,
     skills: &'a [LoadedSkill],
+    session_key: Option<&str>,
 ) -> extensions::ExtensionRegistry<'a> {
     let mcp = bootstrap_mcp(config).await;
     let policy = if config.read_only_tools {
@@ -74,8 +94,14 @@ async fn build_registry_with_mcp<'a>(
     )
     .with_task_planning(config.enable_task_planning)
     .with_policy(policy)
+    // Session-bound builtin state: edit pre-images (file change review), output index,
+    // background jobs, preview servers.
+    .with_tool_session(
+        session_key.map(|key| extensions::ToolSession::new(skilllite_executor::chat_root(), key)),
+    )
     .register(extensions::get_builtin_tools())
     .register_memory_if(config.enable_memory)
+    .register_custom_tools(Path::new(&config.workspace))
     .register_mcp(mcp.tools, mcp.runtime)
     .build()
 }
@@ -187,6 +213,23 @@ fn build_final_summary_fallback(
     lines.join("\n")
 }
 
+/// Budget check run before each LLM call, i.e. after the previous call and its tool batch.
+/// Emits [`EventSink::on_budget_exceeded`] when the limit is reached.
+fn check_run_budget(
+    config: &AgentConfig,
+    state: &ExecutionState,
+    event_sink: &mut dyn EventSink,
+) -> Option<BudgetExceeded> {
+    let info = BudgetExceeded::check(config, &state.llm_usage_totals)?;
+    tracing::warn!(
+        total_tokens = info.total_tokens,
+        estimated_cost_usd = info.estimated_cost_usd,
+        "Agent run stopped: budget exceeded"
+    );
+    event_sink.on_budget_exceeded(&info);
+    Some(info)
+}
+
 /// User message injected before the **closing** LLM turn in task-planning mode when the
 /// completing tool batch did not include user-visible assistant text (`has_substantial` false).
 /// The closing turn uses the same `call_llm_with_recovery` path with `tools=None`.
@@ -216,7 +259,15 @@ pub async fn run_agent_loop(
     event_sink: &mut dyn EventSink,
     session_key: Option<&str>,
 ) -> Result<AgentResult> {
-    if config.enable_task_planning {
+    let span = tracing::info_span!(
+        "agent.turn",
+        session_key = session_key.unwrap_or(""),
+        model = %config.model,
+        task_planning = config.enable_task_planning,
+        iterations = tracing::field::Empty,
+        tool_calls = tracing::field::Empty,
+    );
+    let result = if config.enable_task_planning {
         run_with_task_planning(
             config,
             initial_messages,
@@ -226,6 +277,7 @@ pub async fn run_agent_loop(
             event_sink,
             session_key,
         )
+        .instrument(span.clone())
         .await
     } else {
         run_simple_loop(
@@ -237,8 +289,14 @@ pub async fn run_agent_loop(
             event_sink,
             session_key,
         )
+        .instrument(span.clone())
         .await
+    };
+    if let Ok(r) = &result {
+        span.record("iterations", r.iterations);
+        span.record("tool_calls", r.tool_calls_count);
     }
+    result
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -255,17 +313,17 @@ async fn run_simple_loop(
     session_key: Option<&str>,
 ) -> Result<AgentResult> {
     let start_time = std::time::Instant::now();
-    let client = LlmClient::new(&config.api_base, &config.api_key)?;
+    let client = LlmClient::from_config(config)?;
     let workspace = Path::new(&config.workspace);
     let embed_config = EmbeddingConfig::from_env();
-    let embed_ctx = (config.enable_memory_vector && !config.api_key.is_empty()).then_some(
-        MemoryVectorContext {
+    let embed_ctx = (config.enable_memory_vector
+        && (!config.api_key.is_empty() || embed_config.backend == EmbeddingBackend::Local))
+        .then_some(MemoryVectorContext {
             client: &client,
             embed_config: &embed_config,
-        },
-    );
+        });
 
-    let registry = build_registry_with_mcp(config, skills).await;
+    let registry = build_registry_with_mcp(config, skills, session_key).await;
     let all_tools = registry.all_tool_definitions();
 
     let chat_root = skilllite_executor::chat_root();
@@ -291,6 +349,8 @@ async fn run_simple_loop(
 
     let mut documented_skills: HashSet<String> = HashSet::new();
     let mut state = ExecutionState::new();
+    state.tool_loop_threshold = config.tool_loop_threshold;
+    state.tool_loop_hard_stop = config.tool_loop_hard_stop;
     let mut no_tool_retries = 0usize;
     let max_no_tool_retries = 3;
     let mut task_completed = true;
@@ -304,7 +364,22 @@ async fn run_simple_loop(
         Some(all_tools.as_slice())
     };
 
+    let mut cancelled = false;
+    let mut budget_exceeded = None;
+    let mut tool_failure_cutoff = false;
+
     loop {
+        if event_sink.is_cancelled() {
+            tracing::info!("Agent loop cancelled by caller");
+            cancelled = true;
+            task_completed = false;
+            break;
+        }
+        if let Some(info) = check_run_budget(config, &state, event_sink) {
+            budget_exceeded = Some(info);
+            task_completed = false;
+            break;
+        }
         if state.iterations >= config.max_iterations {
             tracing::warn!(
                 "Agent loop reached max iterations ({})",
@@ -329,22 +404,24 @@ async fn run_simple_loop(
         }
         state.iterations += 1;
 
-        // ── LLM call (with context-overflow recovery) ─────────────────────
+        // ── LLM call (with context-overflow / stream-interrupt recovery) ──
+        let model = adaptive::active_model(config, &state).to_string();
         let response = match call_llm_with_recovery(
             &client,
-            &config.model,
+            &model,
             &mut messages,
             tools_ref,
-            config.temperature,
+            adaptive::active_temperature(config, &state),
             true,
             event_sink,
             &mut state.context_overflow_retries,
+            &mut state.stream_interrupt_retries,
             Some(&mut state.llm_usage_totals),
         )
         .await?
         {
             LlmCallOutcome::Response(resp) => resp,
-            LlmCallOutcome::Truncated => continue,
+            LlmCallOutcome::Retry => continue,
         };
 
         let choice = response
@@ -357,6 +434,9 @@ async fn run_simple_loop(
         let assistant_content = msg.content;
         let tool_calls = msg.tool_calls;
         let has_tool_calls = tool_calls.as_ref().is_some_and(|tc| !tc.is_empty());
+        if adaptive::should_retry_at_zero_temperature(&mut state, tool_calls.as_deref()) {
+            continue;
+        }
 
         if let Some(tcs) = tool_calls {
             let mut m = ChatMessage::assistant_with_tool_calls(assistant_content.as_deref(), tcs);
@@ -435,6 +515,7 @@ async fn run_simple_loop(
         };
 
         let tools_before = state.total_tool_calls;
+        let failures_before = state.failed_tool_calls;
         let outcome = execute_tool_batch_simple(
             &tool_calls,
             &registry,
@@ -452,12 +533,31 @@ async fn run_simple_loop(
         )
         .await;
 
+        let new_tools = state.total_tool_calls.saturating_sub(tools_before);
+        let new_failures = state.failed_tool_calls.saturating_sub(failures_before);
+        let switched_model =
+            adaptive::after_tool_batch(config, &mut state, new_tools, new_failures, event_sink);
+
         if outcome.disclosure_injected {
             after_successful_tool_batch = false;
             continue;
         }
+        if outcome.loop_stop_reached {
+            task_completed = false;
+            break;
+        }
         if outcome.failure_limit_reached {
             after_successful_tool_batch = false;
+            if switched_model
+                || adaptive::fall_back(
+                    config,
+                    &mut state,
+                    event_sink,
+                    "consecutive tool failure limit reached",
+                )
+            {
+                continue;
+            }
             tracing::warn!(
                 "Stopping: {} consecutive tool failures",
                 state.consecutive_failures
@@ -479,10 +579,10 @@ async fn run_simple_loop(
                 continue;
             }
             task_completed = false;
+            tool_failure_cutoff = true;
             break;
         }
 
-        let new_tools = state.total_tool_calls.saturating_sub(tools_before);
         after_successful_tool_batch = new_tools > 0 && state.consecutive_failures == 0;
 
         // 全局工具次数上限：`tool_call_budget_extension` 初值为 0 时，条件与原先
@@ -537,8 +637,14 @@ async fn run_simple_loop(
         completion_type: state.completion_type,
         task_description: Some(user_message.to_string()),
         rules_used: state.rules_used,
+        rules_in_scope: Vec::new(),
         tools_detail: state.tools_detail,
         llm_usage: state.llm_usage_totals,
+        model: config.model.clone(),
+        fallback_model: state.fallback_model,
+        replan_reasons: state.replan_reasons,
+        secrets_redacted: state.secrets_redacted,
+        tool_loops: state.tool_loops_detected,
     };
     tracing::info!(
         prompt_tokens = feedback.llm_usage.prompt_tokens,
@@ -548,13 +654,18 @@ async fn run_simple_loop(
         responses_without_usage = feedback.llm_usage.responses_without_usage,
         "Agent run LLM token totals (simple loop)"
     );
-    Ok(build_agent_result(
+    let mut result = build_agent_result(
         messages,
         state.total_tool_calls,
         state.iterations,
         Vec::new(),
         feedback,
-    ))
+    );
+    result.cancelled = cancelled;
+    result.tool_failure_cutoff = tool_failure_cutoff;
+    result.budget_exceeded = budget_exceeded;
+    artifacts::cite(&mut result, state.artifacts, event_sink);
+    Ok(result)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -573,20 +684,23 @@ async fn run_with_task_planning(
     session_key: Option<&str>,
 ) -> Result<AgentResult> {
     let start_time = std::time::Instant::now();
-    let client = LlmClient::new(&config.api_base, &config.api_key)?;
+    let client = LlmClient::from_config(config)?;
     let workspace = Path::new(&config.workspace);
     let embed_config = EmbeddingConfig::from_env();
-    let embed_ctx = (config.enable_memory_vector && !config.api_key.is_empty()).then_some(
-        MemoryVectorContext {
+    let embed_ctx = (config.enable_memory_vector
+        && (!config.api_key.is_empty() || embed_config.backend == EmbeddingBackend::Local))
+        .then_some(MemoryVectorContext {
             client: &client,
             embed_config: &embed_config,
-        },
-    );
+        });
 
-    let registry = build_registry_with_mcp(config, skills).await;
+    let registry = build_registry_with_mcp(config, skills, session_key).await;
     let all_tools = registry.all_tool_definitions();
 
     let mut state = ExecutionState::new();
+    state.replan_budget = config.max_replans;
+    state.tool_loop_threshold = config.tool_loop_threshold;
+    state.tool_loop_hard_stop = config.tool_loop_hard_stop;
 
     // ── Planning phase ─────────────────────────────────────────────────────
     let PlanningResult {
@@ -634,8 +748,34 @@ async fn run_with_task_planning(
     // After the last tool batch marks every task complete, we may need one more LLM turn
     // with no tools so the user sees a grounded summary (same loop as the main agent step).
     let mut pending_closing_user_reply = false;
+    let mut cancelled = false;
+    let mut budget_exceeded = None;
+    let mut tool_failure_cutoff = false;
 
     loop {
+        if event_sink.is_cancelled() {
+            tracing::info!("Agent loop cancelled by caller (planning)");
+            cancelled = true;
+            maybe_save_checkpoint(
+                config,
+                &planner,
+                &messages,
+                &state.llm_usage_totals,
+                &chat_root,
+            );
+            break;
+        }
+        if let Some(info) = check_run_budget(config, &state, event_sink) {
+            budget_exceeded = Some(info);
+            maybe_save_checkpoint(
+                config,
+                &planner,
+                &messages,
+                &state.llm_usage_totals,
+                &chat_root,
+            );
+            break;
+        }
         if !pending_closing_user_reply && state.iterations >= effective_max {
             tracing::warn!(
                 "Agent loop reached effective max iterations ({})",
@@ -665,22 +805,24 @@ async fn run_with_task_planning(
             tools_ref
         };
 
-        // ── LLM call (with context-overflow recovery) ─────────────────────
+        // ── LLM call (with context-overflow / stream-interrupt recovery) ──
+        let model = adaptive::active_model(config, &state).to_string();
         let response = match call_llm_with_recovery(
             &client,
-            &config.model,
+            &model,
             &mut messages,
             tools_for_llm,
-            config.temperature,
+            adaptive::active_temperature(config, &state),
             !suppress_stream,
             event_sink,
             &mut state.context_overflow_retries,
+            &mut state.stream_interrupt_retries,
             Some(&mut state.llm_usage_totals),
         )
         .await?
         {
             LlmCallOutcome::Response(resp) => resp,
-            LlmCallOutcome::Truncated => continue,
+            LlmCallOutcome::Retry => continue,
         };
 
         let choice = response
@@ -706,6 +848,9 @@ async fn run_with_task_planning(
             ));
             continue;
         }
+        if adaptive::should_retry_at_zero_temperature(&mut state, tool_calls.as_deref()) {
+            continue;
+        }
 
         let suppressed_planning_text =
             should_suppress_planning_assistant_text(&planner, has_tool_calls)
@@ -866,6 +1011,11 @@ async fn run_with_task_planning(
         } else {
             total_stuck_iterations = 0;
         }
+        let switched_model =
+            adaptive::after_tool_batch(config, &mut state, new_calls, new_failures, event_sink);
+        if switched_model {
+            total_stuck_iterations = 0;
+        }
 
         if total_stuck_iterations >= MAX_TOTAL_STUCK {
             tracing::warn!(
@@ -879,8 +1029,21 @@ async fn run_with_task_planning(
             after_successful_tool_batch = false;
             continue;
         }
+        if outcome.loop_stop_reached {
+            break;
+        }
         if outcome.failure_limit_reached {
             after_successful_tool_batch = false;
+            if switched_model
+                || adaptive::fall_back(
+                    config,
+                    &mut state,
+                    event_sink,
+                    "consecutive tool failure limit reached",
+                )
+            {
+                continue;
+            }
             tracing::warn!(
                 "Stopping: {} consecutive tool failures",
                 state.consecutive_failures
@@ -901,6 +1064,7 @@ async fn run_with_task_planning(
                 state.consecutive_failures = 0;
                 continue;
             }
+            tool_failure_cutoff = true;
             break;
         }
         after_successful_tool_batch = new_calls > 0 && state.consecutive_failures == 0;
@@ -950,11 +1114,10 @@ async fn run_with_task_planning(
         }
 
         maybe_save_checkpoint(
-            session_key,
-            user_message,
             config,
             &planner,
             &messages,
+            &state.llm_usage_totals,
             &chat_root,
         );
 
@@ -1009,7 +1172,7 @@ async fn run_with_task_planning(
     // When the loop exits without all tasks completed, the user may have received no visible
     // assistant text (all intermediate text was popped/suppressed by reflection). Emit a fallback
     // summary derived from tool results so the UI always shows something.
-    if !planner.all_completed() && state.total_tool_calls > 0 {
+    if !cancelled && !planner.all_completed() && state.total_tool_calls > 0 {
         let fallback = build_final_summary_fallback(&planner.task_list, &messages, user_message);
         event_sink.emit_assistant_visible(&fallback);
         messages.push(ChatMessage::assistant(&fallback));
@@ -1034,12 +1197,18 @@ async fn run_with_task_planning(
         iterations: state.iterations,
         elapsed_ms: start_time.elapsed().as_millis() as u64,
         context_overflow_retries: state.context_overflow_retries,
-        task_completed: planner.all_completed(),
+        task_completed: planner.all_completed() && !planner.has_failed_tasks(),
         completion_type: effective_completion_type,
         task_description: Some(user_message.to_string()),
         rules_used: planner.matched_rule_ids().to_vec(),
+        rules_in_scope: planner.in_scope_rule_ids().to_vec(),
         tools_detail: state.tools_detail,
         llm_usage: state.llm_usage_totals,
+        model: config.model.clone(),
+        fallback_model: state.fallback_model,
+        replan_reasons: state.replan_reasons,
+        secrets_redacted: state.secrets_redacted,
+        tool_loops: state.tool_loops_detected,
     };
 
     tracing::info!(
@@ -1051,13 +1220,18 @@ async fn run_with_task_planning(
         "Agent run LLM token totals (task planning)"
     );
 
-    Ok(build_agent_result(
+    let mut result = build_agent_result(
         messages,
         state.total_tool_calls,
         state.iterations,
         planner.task_list,
         feedback,
-    ))
+    );
+    result.cancelled = cancelled;
+    result.tool_failure_cutoff = tool_failure_cutoff;
+    result.budget_exceeded = budget_exceeded;
+    artifacts::cite(&mut result, state.artifacts, event_sink);
+    Ok(result)
 }
 
 #[cfg(test)]
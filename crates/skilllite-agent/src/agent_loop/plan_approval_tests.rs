@@ -0,0 +1,152 @@
+//! Plan-approval mode through the real planning phase and a scripted endpoint: nothing
+//! executes before approval, one rejection regenerates the plan with the feedback, edits
+//! replace the plan, and a sink without an approval channel fails the turn.
+
+use serde_json::Value;
+
+use super::run_agent_loop;
+use super::scripted_llm::{self, Reply};
+use crate::types::*;
+
+const PLAN: &str = r#"[{"id": 1, "description": "Read the notes", "completed": false},
+                       {"id": 2, "description": "Email the summary", "completed": false}]"#;
+const REVISED_PLAN: &str = r#"[{"id": 1, "description": "Read the notes", "completed": false},
+                               {"id": 2, "description": "Write summary.md", "completed": false},
+                               {"id": 3, "description": "Print the summary", "completed": false}]"#;
+
+fn config(api_base: String, workspace: &std::path::Path) -> AgentConfig {
+    AgentConfig {
+        api_base,
+        api_key: "test".to_string(),
+        model: "primary".to_string(),
+        max_iterations: 2,
+        workspace: workspace.to_string_lossy().to_string(),
+        enable_task_planning: true,
+        approve_plan: true,
+        enable_memory: false,
+        ..AgentConfig::default()
+    }
+}
+
+fn is_streaming(body: &Value) -> bool {
+    body.get("stream").and_then(Value::as_bool) == Some(true)
+}
+
+/// Rejects the first proposal, then removes task 3 of the revised one and approves.
+#[derive(Default)]
+struct ReviewSink {
+    proposals: Vec<(Vec<String>, bool)>,
+    plans: Vec<Vec<String>>,
+}
+
+fn descriptions(tasks: &[Task]) -> Vec<String> {
+    tasks.iter().map(|t| t.description.clone()).collect()
+}
+
+impl EventSink for ReviewSink {
+    fn on_text(&mut self, _text: &str) {}
+    fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+    fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+    fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+        false
+    }
+    fn on_plan_proposed(&mut self, tasks: &[Task], can_regenerate: bool) -> PlanApproval {
+        self.proposals.push((descriptions(tasks), can_regenerate));
+        if self.proposals.len() == 1 {
+            return PlanApproval::Reject("write the summary to a file, no email".to_string());
+        }
+        let mut edited = tasks.to_vec();
+        PlanCommand::Remove(3).apply(&mut edited).unwrap();
+        PlanApproval::Approve(edited)
+    }
+    fn on_task_plan(&mut self, tasks: &[Task]) {
+        self.plans.push(descriptions(tasks));
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejected_plan_is_regenerated_and_edits_are_applied_before_execution() {
+    let workspace = tempfile::tempdir().unwrap();
+    let llm = scripted_llm::spawn(|index, body| {
+        if is_streaming(body) {
+            Reply::text("Done.")
+        } else if index == 0 {
+            Reply::text(PLAN)
+        } else {
+            Reply::text(REVISED_PLAN)
+        }
+    });
+    let config = config(llm.base, workspace.path());
+
+    let mut sink = ReviewSink::default();
+    run_agent_loop(
+        &config,
+        Vec::new(),
+        "summarize my notes",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        sink.proposals,
+        [
+            (
+                vec![
+                    "Read the notes".to_string(),
+                    "Email the summary".to_string()
+                ],
+                true
+            ),
+            (
+                vec![
+                    "Read the notes".to_string(),
+                    "Write summary.md".to_string(),
+                    "Print the summary".to_string()
+                ],
+                false
+            ),
+        ]
+    );
+    assert_eq!(sink.plans, [["Read the notes", "Write summary.md"]]);
+    let requests = llm.requests.lock().unwrap();
+    assert!(requests[1]
+        .to_string()
+        .contains("write the summary to a file, no email"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn sink_without_approval_channel_fails_before_execution() {
+    let workspace = tempfile::tempdir().unwrap();
+    let llm = scripted_llm::spawn(|_, body| {
+        if is_streaming(body) {
+            Reply::text("Done.")
+        } else {
+            Reply::text(PLAN)
+        }
+    });
+    let config = config(llm.base, workspace.path());
+
+    let mut sink = SilentEventSink;
+    let err = run_agent_loop(
+        &config,
+        Vec::new(),
+        "summarize my notes",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap_err();
+
+    assert!(
+        err.to_string().contains("no approval was received"),
+        "{}",
+        err
+    );
+    assert!(!llm.requests.lock().unwrap().iter().any(is_streaming));
+}
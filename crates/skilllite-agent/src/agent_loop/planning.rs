@@ -6,6 +6,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::error::bail;
 use crate::Result;
 
 use super::super::extensions::ToolAvailabilityView;
@@ -17,7 +18,10 @@ use super::super::skills::LoadedSkill;
 use super::super::soul::Soul;
 use super::super::task_planner::TaskPlanner;
 use super::super::types::*;
-use super::helpers::{extract_goal_boundaries_hybrid, extract_goal_contract_hybrid};
+use super::helpers::{
+    apply_task_plan_update, extract_goal_boundaries_hybrid, extract_goal_contract_hybrid,
+    TaskPlanUpdate,
+};
 
 /// Output of the planning phase, consumed by the execution loop.
 pub(super) struct PlanningResult {
@@ -132,7 +136,64 @@ pub(super) async fn run_planning_phase(
         }
     }
 
-    event_sink.on_task_plan(&planner.task_list);
+    // Plan-approval mode: nothing executes until the user approves. One rejection
+    // regenerates the plan with their feedback; a second one ends the turn.
+    let mut regenerated = false;
+    loop {
+        if !config.approve_plan || planner.is_empty() {
+            event_sink.on_task_plan(&planner.task_list);
+            break;
+        }
+        match event_sink.on_plan_proposed(&planner.task_list, !regenerated) {
+            PlanApproval::Approve(tasks) => {
+                if tasks.is_empty() {
+                    bail!("Plan approval left no tasks; reject the plan instead");
+                }
+                if same_plan(&tasks, &planner.task_list) {
+                    event_sink.on_task_plan(&planner.task_list);
+                } else {
+                    // Same sanitize / renumber / on_task_plan path as update_task_plan.
+                    apply_task_plan_update(
+                        TaskPlanUpdate {
+                            tasks,
+                            reason: "edited during plan approval".to_string(),
+                        },
+                        &mut planner,
+                        skills,
+                        event_sink,
+                    );
+                }
+                break;
+            }
+            PlanApproval::Reject(feedback) if !regenerated => {
+                regenerated = true;
+                let revised_request = plan_feedback_request(user_message, &planner, &feedback);
+                planner
+                    .generate_task_list(
+                        client,
+                        &config.model,
+                        &revised_request,
+                        skills,
+                        conversation_context.as_deref(),
+                        effective_boundaries.as_ref(),
+                        effective_contract.as_ref(),
+                        soul.as_ref(),
+                        Some(llm_usage_totals),
+                    )
+                    .await?;
+            }
+            PlanApproval::Reject(feedback) => {
+                bail!("Plan rejected: {}", feedback);
+            }
+            PlanApproval::Unavailable => {
+                bail!(
+                    "Plan approval is enabled (--approve-plan / SKILLLITE_APPROVE_PLAN) but no \
+                     approval was received; use the interactive chat or an agent-rpc client \
+                     that answers plan_proposed"
+                );
+            }
+        }
+    }
 
     // Build system prompt
     let system_prompt = if planner.is_empty() {
@@ -179,14 +240,7 @@ pub(super) async fn run_planning_phase(
     ));
 
     // A13: Save initial checkpoint for --resume
-    maybe_save_checkpoint(
-        session_key,
-        user_message,
-        config,
-        &planner,
-        &messages,
-        &chat_root,
-    );
+    maybe_save_checkpoint(config, &planner, &messages, llm_usage_totals, &chat_root);
 
     let _ = (soul, effective_boundaries, effective_contract); // used locally above; not passed to caller
     Ok(PlanningResult {
@@ -196,6 +250,36 @@ pub(super) async fn run_planning_phase(
     })
 }
 
+/// Approved tasks equal the proposal (no edit / remove / add).
+fn same_plan(approved: &[Task], proposed: &[Task]) -> bool {
+    approved.len() == proposed.len()
+        && approved
+            .iter()
+            .zip(proposed)
+            .all(|(a, p)| a.description == p.description && a.tool_hint == p.tool_hint)
+}
+
+/// Planning request for the one regeneration after a rejected plan.
+fn plan_feedback_request(user_message: &str, planner: &TaskPlanner, feedback: &str) -> String {
+    let rejected: Vec<String> = planner
+        .task_list
+        .iter()
+        .map(|t| format!("{}. {}", t.id, t.description))
+        .collect();
+    let feedback = if feedback.trim().is_empty() {
+        "(no feedback given)"
+    } else {
+        feedback.trim()
+    };
+    format!(
+        "{}\n\n[Plan feedback] The user rejected this plan:\n{}\nFeedback: {}\n\
+         Produce a revised plan that addresses the feedback.",
+        user_message,
+        rejected.join("\n"),
+        feedback
+    )
+}
+
 /// Build the per-iteration task-focus message injected after tool execution.
 /// Returns `None` when there is no pending task.
 ///
@@ -243,24 +327,18 @@ do_not_quote_or_repeat_this_block=true\n\
     ))
 }
 
-/// Save a run-mode checkpoint (A13). No-op for non-run sessions.
+/// Save a run-mode checkpoint (A13). No-op unless `config.run_checkpoint` is set.
 pub(super) fn maybe_save_checkpoint(
-    session_key: Option<&str>,
-    user_message: &str,
     config: &AgentConfig,
     planner: &TaskPlanner,
     messages: &[ChatMessage],
+    usage: &LlmUsageTotals,
     chat_root: &Path,
 ) {
-    if session_key != Some("run") {
+    let Some(target) = &config.run_checkpoint else {
         return;
-    }
-    let cp = crate::run_checkpoint::RunCheckpoint::new(
-        user_message.to_string(),
-        config.workspace.clone(),
-        planner.task_list.clone(),
-        messages.to_vec(),
-    );
+    };
+    let cp = target.checkpoint(&config.workspace, &planner.task_list, messages, usage);
     if let Err(e) = crate::run_checkpoint::save_checkpoint(chat_root, &cp) {
         tracing::debug!("Checkpoint save failed: {}", e);
     }
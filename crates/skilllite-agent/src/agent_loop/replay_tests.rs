@@ -0,0 +1,210 @@
+//! Agent-loop runs replayed from checked-in LLM recordings (`recordings/<name>/NNNN.json`),
+//! so the simple and planning loops are exercised end to end in CI without an API.
+//!
+//! The recordings were trimmed by hand (system / planning prompts embed the recording date
+//! and workspace path), so they replay loosely: each call is matched on message count.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use super::run_agent_loop;
+use crate::llm::LlmTape;
+use crate::types::*;
+
+fn recording(name: &str) -> Arc<LlmTape> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/agent_loop/recordings")
+        .join(name);
+    Arc::new(LlmTape::replay(dir, true))
+}
+
+fn config(workspace: &Path, tape: &Arc<LlmTape>, planning: bool) -> AgentConfig {
+    AgentConfig {
+        // Unroutable: every call must come from the recording.
+        api_base: "http://127.0.0.1:9/v1".to_string(),
+        api_key: "test".to_string(),
+        model: "gpt-4o-mini".to_string(),
+        workspace: workspace.to_string_lossy().to_string(),
+        enable_task_planning: planning,
+        enable_memory: false,
+        llm_tape: Some(tape.clone()),
+        ..AgentConfig::default()
+    }
+}
+
+#[derive(Default)]
+struct ChunkSink {
+    chunks: Vec<String>,
+}
+
+impl EventSink for ChunkSink {
+    fn on_text(&mut self, _text: &str) {}
+    fn on_text_chunk(&mut self, chunk: &str) {
+        self.chunks.push(chunk.to_string());
+    }
+    fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+    fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+    fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+        false
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn simple_loop_replays_read_file_turn() {
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::write(
+        workspace.path().join("notes.md"),
+        "release: 2026-10-20\nowner: docs team\n",
+    )
+    .unwrap();
+    let tape = recording("simple_read_file");
+    let config = config(workspace.path(), &tape, false);
+
+    let mut sink = ChunkSink::default();
+    let result = run_agent_loop(
+        &config,
+        Vec::new(),
+        "What release date is written in notes.md?",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(tape.calls(), 2);
+    assert_eq!(
+        (result.feedback.total_tools, result.feedback.failed_tools),
+        (1, 0)
+    );
+    let tool_output = result
+        .messages
+        .iter()
+        .find(|m| m.role == "tool")
+        .and_then(|m| m.content.as_deref())
+        .unwrap();
+    assert!(tool_output.contains("2026-10-20"), "{tool_output}");
+    assert!(
+        result.response.contains("2026-10-20"),
+        "{}",
+        result.response
+    );
+    assert_eq!(sink.chunks.concat().trim(), result.response.trim());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn planning_loop_replays_write_file_turn() {
+    let workspace = tempfile::tempdir().unwrap();
+    let tape = recording("planning_write_file");
+    let config = config(workspace.path(), &tape, true);
+
+    let mut sink = ChunkSink::default();
+    let result = run_agent_loop(
+        &config,
+        Vec::new(),
+        "Create todo.md listing: buy milk, call Alex",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Plan, tool step, closing reply.
+    assert_eq!(tape.calls(), 3);
+    assert_eq!(
+        std::fs::read_to_string(workspace.path().join("todo.md")).unwrap(),
+        "- buy milk\n- call Alex\n"
+    );
+    assert_eq!(result.task_plan.len(), 1);
+    assert!(result.task_plan[0].completed);
+    assert_eq!(result.feedback.failed_tools, 0);
+    assert!(result.response.contains("todo.md"), "{}", result.response);
+}
+
+#[derive(Default)]
+struct BudgetSink {
+    tool_calls: usize,
+    budget: Vec<BudgetExceeded>,
+}
+
+impl EventSink for BudgetSink {
+    fn on_text(&mut self, _text: &str) {}
+    fn on_tool_call(&mut self, _name: &str, _arguments: &str) {
+        self.tool_calls += 1;
+    }
+    fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+    fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+        false
+    }
+    fn on_budget_exceeded(&mut self, info: &BudgetExceeded) {
+        self.budget.push(info.clone());
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn simple_loop_stops_after_tool_batch_when_token_budget_spent() {
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::write(workspace.path().join("notes.md"), "release: 2026-10-20\n").unwrap();
+    let tape = recording("simple_read_file");
+    let mut config = config(workspace.path(), &tape, false);
+    config.max_run_tokens = Some(100);
+
+    let mut sink = BudgetSink::default();
+    let result = run_agent_loop(
+        &config,
+        Vec::new(),
+        "What release date is written in notes.md?",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // The first completion already spends the budget; its read_file batch still runs.
+    assert_eq!(tape.calls(), 1);
+    assert_eq!(sink.tool_calls, 1);
+    assert_eq!(result.feedback.total_tools, 1);
+    assert!(!result.feedback.task_completed);
+    let info = result.budget_exceeded.expect("budget_exceeded set");
+    assert_eq!(info.limit, BudgetLimit::Tokens);
+    assert_eq!(info.total_tokens, 1882);
+    assert_eq!(sink.budget, vec![info]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn planning_loop_stops_on_cost_budget_with_priced_model() {
+    let workspace = tempfile::tempdir().unwrap();
+    let tape = recording("planning_write_file");
+    let mut config = config(workspace.path(), &tape, true);
+    config.model_prices =
+        parse_model_prices_json(r#"{"gpt-4o-mini": {"input": 0.15, "output": 0.6}}"#);
+    config.max_cost_usd = Some(0.000_001);
+
+    let mut sink = BudgetSink::default();
+    let result = run_agent_loop(
+        &config,
+        Vec::new(),
+        "Create todo.md listing: buy milk, call Alex",
+        None,
+        &[],
+        &mut sink,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Planning call only: the loop stops before the first execution step.
+    assert_eq!(tape.calls(), 1);
+    assert_eq!(sink.tool_calls, 0);
+    assert!(!workspace.path().join("todo.md").exists());
+    assert!(!result.cancelled);
+    let info = result.budget_exceeded.expect("budget_exceeded set");
+    assert_eq!(info.limit, BudgetLimit::Cost);
+    assert!(info.estimated_cost_usd.unwrap() > 0.000_001);
+    assert_eq!(sink.budget.len(), 1);
+}
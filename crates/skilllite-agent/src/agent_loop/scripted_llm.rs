@@ -0,0 +1,111 @@
+//! Test-only OpenAI-compatible endpoint that answers from a script, so loop tests drive the
+//! real `LlmClient` (streaming and non-streaming) without a provider.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+
+/// One scripted completion.
+pub(crate) enum Reply {
+    ToolCall { name: String, arguments: String },
+    Text(String),
+}
+
+impl Reply {
+    pub fn tool_call(name: &str, arguments: &str) -> Self {
+        Self::ToolCall {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        }
+    }
+
+    pub fn text(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+pub(crate) struct ScriptedLlm {
+    /// `api_base` for `AgentConfig`.
+    pub base: String,
+    /// Request bodies in arrival order.
+    pub requests: Arc<Mutex<Vec<Value>>>,
+}
+
+fn sse(chunk: Value) -> String {
+    format!("data: {}\n\ndata: [DONE]\n\n", chunk)
+}
+
+fn render(reply: &Reply, call_index: usize, streaming: bool) -> String {
+    let usage = json!({ "prompt_tokens": 12, "completion_tokens": 5, "total_tokens": 17 });
+    let (message, finish) = match reply {
+        Reply::ToolCall { name, arguments } => (
+            json!({ "role": "assistant", "tool_calls": [{
+                "index": 0, "id": format!("call_{}", call_index), "type": "function",
+                "function": { "name": name, "arguments": arguments }
+            }]}),
+            "tool_calls",
+        ),
+        Reply::Text(text) => (json!({ "role": "assistant", "content": text }), "stop"),
+    };
+    if streaming {
+        sse(json!({
+            "model": "fake",
+            "choices": [{ "index": 0, "finish_reason": finish, "delta": message }],
+            "usage": usage
+        }))
+    } else {
+        json!({
+            "id": "c", "model": "fake",
+            "choices": [{ "index": 0, "finish_reason": finish, "message": message }],
+            "usage": usage
+        })
+        .to_string()
+    }
+}
+
+/// Serve completions until the test process exits. `script` gets the 0-based call index and
+/// the request body.
+pub(crate) fn spawn(script: impl Fn(usize, &Value) -> Reply + Send + 'static) -> ScriptedLlm {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let log = requests.clone();
+    std::thread::spawn(move || {
+        for (index, stream) in listener.incoming().enumerate() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0usize;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = v.trim().parse().unwrap_or(0);
+                }
+            }
+            let mut body = vec![0u8; content_length];
+            let _ = reader.read_exact(&mut body);
+            let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+            let streaming = body.get("stream").and_then(Value::as_bool) == Some(true);
+            let payload = render(&script(index, &body), index, streaming);
+            log.lock().unwrap().push(body);
+            let content_type = if streaming {
+                "text/event-stream"
+            } else {
+                "application/json"
+            };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nConnection: close\r\n\r\n{}",
+                content_type, payload
+            );
+        }
+    });
+    ScriptedLlm {
+        base: format!("http://{}/v1", addr),
+        requests,
+    }
+}
@@ -0,0 +1,134 @@
+//! Span hierarchy of one agent turn (what the OTLP exporter ships): `agent.turn` parents every
+//! `agent.llm_call` and `agent.tool`, and span fields never carry tool arguments.
+//!
+//! Runs the real simple loop against a scripted OpenAI-compatible endpoint: the first
+//! streaming call asks for `list_directory`, every later call answers with plain text.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use super::run_agent_loop;
+use super::scripted_llm::{self, Reply};
+use crate::types::{AgentConfig, SilentEventSink};
+
+const SECRET_DIR: &str = "secret-marker-dir";
+
+#[derive(Debug, Clone)]
+struct SpanRecord {
+    name: String,
+    parent: Option<String>,
+    fields: String,
+}
+
+#[derive(Clone, Default)]
+struct SpanLog(Arc<Mutex<Vec<SpanRecord>>>);
+
+struct FieldText<'a>(&'a mut String);
+
+impl Visit for FieldText<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push_str(&format!("{}={:?} ", field.name(), value));
+    }
+}
+
+impl<S> Layer<S> for SpanLog
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: Context<'_, S>,
+    ) {
+        let parent = ctx
+            .span(id)
+            .and_then(|s| s.parent())
+            .map(|p| p.name().to_string());
+        let mut fields = String::new();
+        attrs.record(&mut FieldText(&mut fields));
+        self.0.lock().unwrap().push(SpanRecord {
+            name: attrs.metadata().name().to_string(),
+            parent,
+            fields,
+        });
+    }
+
+    fn on_record(
+        &self,
+        id: &tracing::span::Id,
+        values: &tracing::span::Record<'_>,
+        ctx: Context<'_, S>,
+    ) {
+        let Some(name) = ctx.span(id).map(|s| s.name().to_string()) else {
+            return;
+        };
+        let mut log = self.0.lock().unwrap();
+        if let Some(rec) = log.iter_mut().rev().find(|r| r.name == name) {
+            values.record(&mut FieldText(&mut rec.fields));
+        }
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn agent_turn_parents_llm_and_tool_spans_without_arguments() {
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::create_dir(workspace.path().join(SECRET_DIR)).unwrap();
+    let first_stream = AtomicBool::new(true);
+    let llm = scripted_llm::spawn(move |_, body| {
+        let streaming = body.get("stream").and_then(|v| v.as_bool()) == Some(true);
+        if streaming && first_stream.swap(false, Ordering::SeqCst) {
+            let args = serde_json::json!({ "path": SECRET_DIR }).to_string();
+            Reply::tool_call("list_directory", &args)
+        } else {
+            Reply::text("Done.")
+        }
+    });
+    let config = AgentConfig {
+        api_base: llm.base,
+        api_key: "test".to_string(),
+        model: "fake".to_string(),
+        max_iterations: 4,
+        workspace: workspace.path().to_string_lossy().to_string(),
+        enable_task_planning: false,
+        enable_memory: false,
+        ..AgentConfig::default()
+    };
+
+    let log = SpanLog::default();
+    let _guard = tracing_subscriber::registry()
+        .with(log.clone())
+        .set_default();
+    let mut sink = SilentEventSink;
+    let result = run_agent_loop(&config, Vec::new(), "list it", None, &[], &mut sink, None)
+        .await
+        .unwrap();
+    assert!(result.tool_calls_count >= 1);
+
+    let spans = log.0.lock().unwrap().clone();
+    let turn = spans.iter().find(|s| s.name == "agent.turn").unwrap();
+    assert_eq!(turn.parent, None);
+    assert!(turn.fields.contains("iterations="));
+
+    let llm_calls: Vec<_> = spans
+        .iter()
+        .filter(|s| s.name == "agent.llm_call")
+        .collect();
+    assert!(!llm_calls.is_empty());
+    assert!(llm_calls
+        .iter()
+        .all(|s| s.parent.as_deref() == Some("agent.turn")));
+    assert!(llm_calls[0].fields.contains("tokens_in=12"));
+
+    let tool = spans.iter().find(|s| s.name == "agent.tool").unwrap();
+    assert_eq!(tool.parent.as_deref(), Some("agent.turn"));
+    assert!(tool.fields.contains("list_directory"));
+    assert!(tool.fields.contains("duration_ms="));
+    assert!(spans.iter().all(|s| !s.fields.contains(SECRET_DIR)));
+}
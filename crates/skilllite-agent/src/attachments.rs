@@ -0,0 +1,304 @@
+//! Chat file attachments: register a local file in the workspace and extract a bounded preview.
+//!
+//! Attached files are copied into `<workspace>/attachments/` so tools (read_file, skills) can
+//! reach them inside the sandbox. Each attachment is recorded on the user transcript row
+//! ([`UserFileAttachment`]); [`attachment_note`] turns those records into the system note the
+//! model sees, both on the live turn and when history is rebuilt for a resumed session.
+//!
+//! Preview extraction: plain text / CSV head (bounded by `SKILLLITE_ATTACHMENT_PREVIEW_CHARS`);
+//! PDFs point the model at an installed PDF skill; anything else is reported by size and type.
+//! Files over `SKILLLITE_ATTACHMENT_MAX_BYTES` are rejected.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::error::bail;
+use crate::Result;
+
+use super::skills::LoadedSkill;
+use super::types::{get_attachment_max_bytes, get_attachment_preview_chars, UserFileAttachment};
+
+/// Workspace subdirectory that receives attachment copies.
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// Bytes sniffed for binary detection.
+const SNIFF_BYTES: usize = 8192;
+
+/// Lines kept from the head of a CSV/TSV file.
+const CSV_PREVIEW_LINES: usize = 20;
+
+/// Copy `source` into `<workspace>/attachments/` and build its transcript record.
+///
+/// A file with identical content already in the attachments dir is reused; a different file
+/// with the same name gets a `-1`, `-2`, … suffix.
+pub fn register_attachment(workspace: &Path, source: &Path) -> Result<UserFileAttachment> {
+    let meta = std::fs::metadata(source)
+        .map_err(|e| crate::Error::validation(format!("{}: {}", source.display(), e)))?;
+    if !meta.is_file() {
+        bail!("Not a regular file: {}", source.display());
+    }
+    let max_bytes = get_attachment_max_bytes();
+    if meta.len() > max_bytes {
+        bail!(
+            "{} is {} bytes; attachments are limited to {} bytes (SKILLLITE_ATTACHMENT_MAX_BYTES)",
+            source.display(),
+            meta.len(),
+            max_bytes
+        );
+    }
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string());
+
+    let dir = workspace.join(ATTACHMENTS_DIR);
+    skilllite_fs::create_dir_all(&dir)?;
+    let dest = unique_destination(&dir, &name, source)?;
+    if !dest.exists() {
+        std::fs::copy(source, &dest)?;
+    }
+    let dest = dest.canonicalize().unwrap_or(dest);
+
+    let head = read_head(&dest, SNIFF_BYTES)?;
+    let binary = looks_binary(&head);
+    let media_type = media_type_for(&name, binary);
+    let textual = media_type.starts_with("text/")
+        || matches!(
+            media_type.as_str(),
+            "application/json" | "application/xml" | "application/yaml"
+        );
+    let preview = if binary || !textual {
+        None
+    } else {
+        text_preview(&dest, &media_type, get_attachment_preview_chars())?
+    };
+
+    Ok(UserFileAttachment {
+        path: dest.to_string_lossy().to_string(),
+        name,
+        media_type,
+        size_bytes: meta.len(),
+        preview,
+    })
+}
+
+/// System note describing the attachments of one user message.
+///
+/// `pdf_skill` is the skill to suggest for PDFs (see [`find_pdf_skill`]).
+pub fn attachment_note(attachments: &[UserFileAttachment], pdf_skill: Option<&str>) -> String {
+    let mut out = String::from(
+        "[Attached files]\nThe user attached these files to their next message. \
+         They are copied into the workspace; read them with tools when the preview is not enough.",
+    );
+    for a in attachments {
+        out.push_str(&format!(
+            "\n\n- {} ({}, {} bytes): {}",
+            a.name, a.media_type, a.size_bytes, a.path
+        ));
+        match a.preview.as_deref() {
+            Some(p) if !p.is_empty() => {
+                out.push_str("\n  Preview:\n```\n");
+                out.push_str(p);
+                out.push_str("\n```");
+            }
+            _ if a.media_type == "application/pdf" => match pdf_skill {
+                Some(skill) => out.push_str(&format!(
+                    "\n  No text preview. Extract the text with the `{}` skill.",
+                    skill
+                )),
+                None => out.push_str("\n  No text preview (no PDF skill installed)."),
+            },
+            _ => out.push_str("\n  Binary file; no text preview."),
+        }
+    }
+    out
+}
+
+/// Name of an installed skill that can read PDFs, if any.
+pub fn find_pdf_skill(skills: &[LoadedSkill]) -> Option<&str> {
+    skills
+        .iter()
+        .find(|s| s.name.to_lowercase().contains("pdf"))
+        .or_else(|| {
+            skills.iter().find(|s| {
+                s.metadata
+                    .description
+                    .as_deref()
+                    .is_some_and(|d| d.to_lowercase().contains("pdf"))
+            })
+        })
+        .map(|s| s.name.as_str())
+}
+
+fn unique_destination(dir: &Path, name: &str, source: &Path) -> Result<PathBuf> {
+    let path = Path::new(name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| name.to_string());
+    let ext = path.extension().map(|e| e.to_string_lossy().to_string());
+    for n in 0.. {
+        let candidate_name = match (n, ext.as_deref()) {
+            (0, _) => name.to_string(),
+            (_, Some(ext)) => format!("{}-{}.{}", stem, n, ext),
+            (_, None) => format!("{}-{}", stem, n),
+        };
+        let candidate = dir.join(candidate_name);
+        if !candidate.exists() || same_content(&candidate, source)? {
+            return Ok(candidate);
+        }
+    }
+    unreachable!("attachment name suffixes exhausted")
+}
+
+fn same_content(a: &Path, b: &Path) -> Result<bool> {
+    if std::fs::metadata(a)?.len() != std::fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+    Ok(std::fs::read(a)? == std::fs::read(b)?)
+}
+
+fn read_head(path: &Path, limit: usize) -> Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(limit);
+    std::fs::File::open(path)?
+        .take(limit as u64)
+        .read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// NUL bytes or invalid UTF-8 (ignoring a multi-byte char cut at the sniff boundary).
+fn looks_binary(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => false,
+        Err(e) => e.error_len().is_some(),
+    }
+}
+
+fn media_type_for(name: &str, binary: bool) -> String {
+    let ext = Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let known = match ext.as_str() {
+        "pdf" => "application/pdf",
+        "csv" => "text/csv",
+        "tsv" => "text/tab-separated-values",
+        "json" => "application/json",
+        "md" | "markdown" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "zip" => "application/zip",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        _ => "",
+    };
+    if !known.is_empty() {
+        known.to_string()
+    } else if binary {
+        "application/octet-stream".to_string()
+    } else {
+        "text/plain".to_string()
+    }
+}
+
+fn text_preview(path: &Path, media_type: &str, max_chars: usize) -> Result<Option<String>> {
+    if max_chars == 0 {
+        return Ok(None);
+    }
+    // 4 bytes per char upper bound keeps the read bounded for multi-byte text.
+    let head = read_head(path, max_chars.saturating_mul(4).saturating_add(4))?;
+    let text = String::from_utf8_lossy(&head);
+    let size = std::fs::metadata(path)?.len();
+
+    let mut preview: String = if matches!(media_type, "text/csv" | "text/tab-separated-values") {
+        text.lines()
+            .take(CSV_PREVIEW_LINES)
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        text.to_string()
+    };
+    let mut truncated = (head.len() as u64) < size || preview.len() < text.trim_end().len();
+    if preview.chars().count() > max_chars {
+        preview = preview.chars().take(max_chars).collect();
+        truncated = true;
+    }
+    let preview = preview.trim_end().to_string();
+    if preview.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(if truncated {
+        format!("{}\n…[truncated]", preview)
+    } else {
+        preview
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_attachment_is_copied_with_head_preview() {
+        let src_dir = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let src = src_dir.path().join("data.csv");
+        let rows: Vec<String> = (0..100).map(|i| format!("{},{}", i, i * 2)).collect();
+        std::fs::write(&src, format!("id,double\n{}\n", rows.join("\n"))).unwrap();
+
+        let a = register_attachment(workspace.path(), &src).unwrap();
+        assert_eq!(a.name, "data.csv");
+        assert_eq!(a.media_type, "text/csv");
+        assert!(Path::new(&a.path).starts_with(workspace.path().canonicalize().unwrap()));
+        let preview = a.preview.unwrap();
+        assert!(preview.starts_with("id,double\n0,0"));
+        assert_eq!(preview.lines().count(), CSV_PREVIEW_LINES + 1);
+        assert!(preview.ends_with("…[truncated]"));
+
+        // Same content again → same copy; different content → suffixed copy.
+        let again = register_attachment(workspace.path(), &src).unwrap();
+        assert_eq!(again.path, a.path);
+        std::fs::write(&src, "id\n1\n").unwrap();
+        let other = register_attachment(workspace.path(), &src).unwrap();
+        assert!(other.path.ends_with("data-1.csv"));
+        assert_eq!(other.preview.as_deref(), Some("id\n1"));
+    }
+
+    #[test]
+    fn binary_attachment_has_no_preview() {
+        let src_dir = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let src = src_dir.path().join("blob.bin");
+        std::fs::write(&src, [0x7fu8, b'E', b'L', b'F', 0, 1, 2, 0xff]).unwrap();
+
+        let a = register_attachment(workspace.path(), &src).unwrap();
+        assert_eq!(a.media_type, "application/octet-stream");
+        assert_eq!(a.size_bytes, 8);
+        assert!(a.preview.is_none());
+        let note = attachment_note(&[a], None);
+        assert!(note.contains("blob.bin (application/octet-stream, 8 bytes)"));
+        assert!(note.contains("Binary file; no text preview."));
+    }
+
+    #[test]
+    fn directories_are_rejected() {
+        let workspace = tempfile::tempdir().unwrap();
+        let err = register_attachment(workspace.path(), workspace.path()).unwrap_err();
+        assert!(err.to_string().contains("Not a regular file"));
+    }
+
+    #[test]
+    fn multibyte_char_cut_at_sniff_boundary_is_text() {
+        let mut head = vec![b'a'; SNIFF_BYTES - 1];
+        head.extend_from_slice(&"é".as_bytes()[..1]);
+        assert!(!looks_binary(&head));
+        assert!(looks_binary(&[b'a', 0xff, b'b']));
+    }
+}
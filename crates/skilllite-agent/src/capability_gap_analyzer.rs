@@ -133,6 +133,11 @@ mod tests {
             requires_elevated_permissions: false,
             capabilities: Vec::new(),
             openclaw_installs: None,
+            max_concurrency: None,
+            entry_points: Vec::new(),
+            result_cache: None,
+            examples: Vec::new(),
+            cost_hint: None,
         };
         LoadedSkill {
             name: name.to_string(),
@@ -147,6 +152,8 @@ mod tests {
                 },
             }],
             multi_script_entries: HashMap::new(),
+            docs: Default::default(),
+            source_hash: 0,
         }
     }
 
@@ -268,6 +268,11 @@ mod tests {
             requires_elevated_permissions: false,
             capabilities: Vec::new(),
             openclaw_installs: None,
+            max_concurrency: None,
+            entry_points: Vec::new(),
+            result_cache: None,
+            examples: Vec::new(),
+            cost_hint: None,
         };
         let tools = (0..tool_count)
             .map(|i| ToolDefinition {
@@ -285,6 +290,8 @@ mod tests {
             metadata,
             tool_definitions: tools,
             multi_script_entries: HashMap::new(),
+            docs: Default::default(),
+            source_hash: 0,
         }
     }
 
@@ -9,6 +9,10 @@ use std::path::Path;
 use std::process::Command;
 
 use super::chat_session::ChatSession;
+use super::extensions::AutoMemoryTrigger;
+use super::repl;
+use super::run_checkpoint;
+use super::single_shot::{FailureKind, ResultEnvelope, SingleShotOptions};
 use super::skills;
 use super::types::*;
 
@@ -50,11 +54,7 @@ pub async fn run_single_task(
 /// Clear session (OpenClaw-style): summarize to memory, archive transcript, reset counts.
 /// Called by `skilllite clear-session` and Assistant. Loads .env from workspace.
 pub fn run_clear_session(session_key: &str, workspace: &str) -> Result<()> {
-    let workspace_path = Path::new(workspace).canonicalize().unwrap_or_else(|_| {
-        std::env::current_dir()
-            .unwrap_or_else(|_| std::path::PathBuf::from("."))
-            .join(workspace)
-    });
+    let workspace_path = canonical_workspace(workspace);
     if std::env::set_current_dir(&workspace_path).is_err() {
         // Non-fatal: .env may not exist or API key may be in env already
     }
@@ -82,6 +82,149 @@ pub fn run_clear_session(session_key: &str, workspace: &str) -> Result<()> {
     Ok(())
 }
 
+/// `skilllite sessions list`: every session under the chat root with title and activity.
+pub fn run_list_sessions(json_output: bool) -> Result<()> {
+    let sessions = skilllite_executor::session::list_sessions(&skilllite_executor::chat_root())?;
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&sessions)?);
+    } else if sessions.is_empty() {
+        eprintln!("(no sessions)");
+    } else {
+        for s in sessions {
+            eprintln!(
+                "{:<24} {:>4} msgs  {}",
+                s.session_key,
+                s.message_count,
+                s.title.as_deref().unwrap_or("-")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `skilllite sessions purge-auto-memory`: delete memories written by end-of-session
+/// auto-memory (`memory/auto/`), for one session or all. Explicit memories are untouched.
+pub fn run_purge_auto_memory(session_key: Option<&str>, dry_run: bool) -> Result<()> {
+    let removed = crate::extensions::purge_auto_memories(
+        &skilllite_executor::chat_root(),
+        "default",
+        session_key,
+        dry_run,
+    )?;
+    if removed.is_empty() {
+        eprintln!("(no auto-memories)");
+        return Ok(());
+    }
+    for rel in &removed {
+        eprintln!("  • memory/{}", rel);
+    }
+    if dry_run {
+        eprintln!(
+            "(Dry run — {} file(s) would be removed. Remove --dry-run to delete.)",
+            removed.len()
+        );
+    } else {
+        eprintln!("✅ Removed {} auto-memory file(s)", removed.len());
+    }
+    Ok(())
+}
+
+/// `skilllite sessions create`: add a titled session to sessions.json.
+pub fn run_create_session(title: &str, json_output: bool) -> Result<()> {
+    let session =
+        skilllite_executor::session::create_session(&skilllite_executor::chat_root(), title)?;
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&session)?);
+    } else {
+        eprintln!("Created session {}", session.session_key);
+    }
+    Ok(())
+}
+
+/// `skilllite sessions rename`: persist a display title in sessions.json.
+pub fn run_rename_session(session_key: &str, title: &str) -> Result<()> {
+    skilllite_executor::session::rename_session(
+        &skilllite_executor::chat_root(),
+        session_key,
+        title,
+    )?;
+    Ok(())
+}
+
+/// `skilllite sessions delete`: clear-session (summarize to memory, archive), then remove
+/// the session entry, transcripts and plans.
+pub fn run_delete_session(session_key: &str, workspace: &str) -> Result<()> {
+    if session_key == "default" {
+        bail!("The default session cannot be deleted; use clear-session instead");
+    }
+    run_clear_session(session_key, workspace)?;
+    let removed =
+        skilllite_executor::session::delete_session(&skilllite_executor::chat_root(), session_key)?;
+    tracing::info!("Deleted session {} ({} files)", session_key, removed);
+    Ok(())
+}
+
+fn canonical_workspace(workspace: &str) -> std::path::PathBuf {
+    Path::new(workspace).canonicalize().unwrap_or_else(|_| {
+        std::env::current_dir()
+            .unwrap_or_else(|_| std::path::PathBuf::from("."))
+            .join(workspace)
+    })
+}
+
+/// `skilllite file-history changes`: files the agent edited in `workspace` since `since_ts`
+/// (unix secs), with unified diffs against their pre-session content.
+pub fn run_file_changes(
+    session_key: &str,
+    workspace: &str,
+    since_ts: u64,
+    json_output: bool,
+) -> Result<()> {
+    let changes = skilllite_executor::file_history::changes_since(
+        &skilllite_executor::chat_root(),
+        session_key,
+        &canonical_workspace(workspace),
+        since_ts,
+    )?;
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&changes)?);
+    } else if changes.is_empty() {
+        eprintln!("(no file changes in session {})", session_key);
+    } else {
+        for c in changes {
+            eprintln!("{:<8} {} ({} edits)", c.status, c.path, c.edits);
+            if let Some(diff) = c.diff {
+                eprintln!("{}", diff);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `skilllite file-history revert`: restore a file to its pre-session version.
+pub fn run_revert_file(
+    session_key: &str,
+    workspace: &str,
+    path: &str,
+    json_output: bool,
+) -> Result<()> {
+    let outcome = crate::extensions::revert_session_file(
+        &skilllite_executor::chat_root(),
+        session_key,
+        &canonical_workspace(workspace),
+        path,
+    )?;
+    if json_output {
+        println!(
+            "{}",
+            serde_json::json!({ "path": path, "outcome": outcome })
+        );
+    } else {
+        eprintln!("{:?}: {}", outcome, path);
+    }
+    Ok(())
+}
+
 /// Top-level entry-point called from `main()` for the `chat` subcommand.
 /// Caller should build `config` from env + CLI overrides (e.g. `AgentConfig::from_env()` then set api_base, skill_dirs, etc.).
 pub fn run_chat(
@@ -89,10 +232,85 @@ pub fn run_chat(
     session_key: String,
     single_message: Option<String>,
 ) -> Result<()> {
+    let loaded_skills = load_chat_skills(&config, single_message.is_some())?;
+    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+
+    let verbose = config.verbose;
+    if let Some(msg) = single_message {
+        rt.block_on(async {
+            let mut session = ChatSession::new(config, &session_key, loaded_skills);
+            let mut sink = TerminalEventSink::new(verbose);
+            let result = session.run_turn(&msg, &mut sink).await?;
+            println!("\n{}", result.response);
+            if verbose {
+                print_redaction_count(session.secrets_redacted());
+            }
+            remember_session(&mut session, AutoMemoryTrigger::SingleShot, &mut sink).await;
+            crate::extensions::stop_background_jobs(Some(session.session_key()));
+            Ok(())
+        })
+    } else {
+        rt.block_on(async {
+            run_interactive_chat(config, &session_key, loaded_skills, verbose).await
+        })
+    }
+}
+
+/// `skilllite chat -m`: one turn under the scripting contract of [`crate::single_shot`]
+/// (`--output json`, `--fail-on-empty`). Returns the process exit code.
+pub fn run_chat_single_shot(
+    config: AgentConfig,
+    session_key: String,
+    message: String,
+    opts: SingleShotOptions,
+) -> i32 {
+    let loaded_skills = match load_chat_skills(&config, true) {
+        Ok(skills) => skills,
+        Err(e) => return opts.finish(&ResultEnvelope::failed(FailureKind::Config, e.to_string())),
+    };
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => return opts.finish(&ResultEnvelope::failed(FailureKind::Config, e.to_string())),
+    };
+
+    let verbose = config.verbose;
+    rt.block_on(async {
+        let mut session = ChatSession::new(config, &session_key, loaded_skills);
+        let mut sink = TerminalEventSink::new(verbose);
+        if opts.is_json() {
+            sink = sink.text_to_stderr();
+        }
+        let result = match session.run_turn(&message, &mut sink).await {
+            Ok(result) => result,
+            Err(e) => return opts.finish(&ResultEnvelope::failed(FailureKind::Llm, e.to_string())),
+        };
+        if !opts.is_json() {
+            println!("\n{}", result.response);
+        }
+        if verbose {
+            print_redaction_count(session.secrets_redacted());
+        }
+        remember_session(&mut session, AutoMemoryTrigger::SingleShot, &mut sink).await;
+        crate::extensions::stop_background_jobs(Some(session.session_key()));
+        opts.finish(&ResultEnvelope::from_result(&result, opts.fail_on_empty))
+    })
+}
+
+/// Check the chat configuration, then load skills (auto-discovered when none are configured)
+/// and print the skills banner.
+fn load_chat_skills(config: &AgentConfig, single_shot: bool) -> Result<Vec<skills::LoadedSkill>> {
     skilllite_core::config::ensure_default_output_dir();
 
+    if config.approve_plan && single_shot {
+        bail!(
+            "--approve-plan needs the interactive chat (no --message) or agent-rpc; \
+             single-shot runs have no way to approve the plan"
+        );
+    }
     if config.api_key.is_empty() {
-        bail!("API key required. Set OPENAI_API_KEY env var or use --api-key flag.");
+        bail!(
+            "API key required. Run `skilllite quickstart`, set OPENAI_API_KEY, or use --api-key."
+        );
     }
 
     // Auto-discover skill directories if none specified
@@ -123,60 +341,161 @@ pub fn run_chat(
         eprintln!("│  📦 {}", list);
         eprintln!("└───────────────────────────────────────────────────────────");
     }
+    Ok(loaded_skills)
+}
 
-    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-
-    let verbose = config.verbose;
-    if let Some(msg) = single_message {
-        rt.block_on(async {
-            let mut session = ChatSession::new(config, &session_key, loaded_skills);
-            let mut sink = TerminalEventSink::new(verbose);
-            let result = session.run_turn(&msg, &mut sink).await?;
-            println!("\n{}", result.response);
-            Ok(())
-        })
-    } else {
-        rt.block_on(async {
-            run_interactive_chat(config, &session_key, loaded_skills, verbose).await
-        })
-    }
+/// A prepared `skilllite run --goal` / `--resume`: everything needed to start the turn.
+struct AgentRunSetup {
+    config: AgentConfig,
+    goal: String,
+    history: Option<Vec<ChatMessage>>,
+    run_id: String,
+    skills: Vec<skills::LoadedSkill>,
 }
 
 /// Run agent in unattended mode: one-time goal, continuous execution until done/timeout.
 /// Replan (update_task_plan) does not wait for user — agent continues immediately.
 /// Confirmations (run_command, L3 skill scan) are auto-approved.
-/// A13: When resume=true, load checkpoint and continue from last state.
+/// A13: Progress is checkpointed after each step; `resume` continues a saved checkpoint.
+///
+/// Follows the scripting contract of [`crate::single_shot`] and returns the process exit code.
 ///
 /// Caller should build `config` with run-mode defaults (e.g. enable_task_planning=true,
 /// max_consecutive_failures set, soul_path, skill_dirs, etc.).
-pub fn run_agent_run(config: AgentConfig, goal: String, resume: bool) -> Result<()> {
+pub fn run_agent_run(
+    config: AgentConfig,
+    goal: String,
+    resume: Option<run_checkpoint::ResumeFrom>,
+    opts: SingleShotOptions,
+) -> i32 {
+    let chat_root = skilllite_executor::chat_root();
+    let setup = match prepare_agent_run(config, goal, resume, &chat_root) {
+        Ok(setup) => setup,
+        Err(e) => return opts.finish(&ResultEnvelope::failed(FailureKind::Config, e.to_string())),
+    };
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => return opts.finish(&ResultEnvelope::failed(FailureKind::Config, e.to_string())),
+    };
+
+    let AgentRunSetup {
+        config,
+        goal,
+        history,
+        run_id,
+        skills,
+    } = setup;
+    let verbose = config.verbose;
+    rt.block_on(async {
+        let mut session = ChatSession::new(config, "run", skills);
+        let mut sink = RunModeEventSink::new(verbose);
+        if opts.is_json() {
+            sink = sink.text_to_stderr();
+        }
+        let result = if let Some(history) = history {
+            session
+                .run_turn_with_history(&goal, &mut sink, history)
+                .await
+        } else {
+            session.run_turn(&goal, &mut sink).await
+        };
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => return opts.finish(&ResultEnvelope::failed(FailureKind::Llm, e.to_string())),
+        };
+        if result.feedback.task_completed && !result.cancelled && result.budget_exceeded.is_none() {
+            // Finished runs are not resumable; drop the checkpoint.
+            if let Err(e) = run_checkpoint::discard_checkpoint(&chat_root, &run_id) {
+                tracing::debug!("Checkpoint discard failed: {}", e);
+            }
+        }
+        // Auto-memory would spend past the limit; the checkpoint keeps the progress.
+        if result.budget_exceeded.is_none() {
+            // Response already streamed via sink during run_turn — no extra println
+            remember_session(&mut session, AutoMemoryTrigger::SingleShot, &mut sink).await;
+        }
+        crate::extensions::stop_background_jobs(Some(session.session_key()));
+        opts.finish(&ResultEnvelope::from_result(&result, opts.fail_on_empty))
+    })
+}
+
+/// Validate run-mode options, resolve the checkpoint to resume and load skills.
+fn prepare_agent_run(
+    config: AgentConfig,
+    goal: String,
+    resume: Option<run_checkpoint::ResumeFrom>,
+    chat_root: &Path,
+) -> Result<AgentRunSetup> {
+    if config.approve_plan {
+        bail!(
+            "Plan approval (--approve-plan / SKILLLITE_APPROVE_PLAN) is not available in \
+             unattended run mode; use `skilllite chat --approve-plan` or agent-rpc"
+        );
+    }
     if config.api_key.is_empty() {
-        bail!("API key required. Set OPENAI_API_KEY env var or use --api-key flag.");
+        bail!(
+            "API key required. Run `skilllite quickstart`, set OPENAI_API_KEY, or use --api-key."
+        );
     }
 
     skilllite_core::config::ensure_default_output_dir();
 
     // A13: Resume from checkpoint
-    let (effective_goal, effective_workspace, history_override) = if resume {
-        let chat_root = skilllite_executor::chat_root();
-        match super::run_checkpoint::load_checkpoint(&chat_root)? {
-            Some(cp) => {
-                let resume_msg = super::run_checkpoint::build_resume_message(&cp);
-                // Use checkpoint messages as history; skip first (system) since agent_loop adds its own
-                let history: Vec<ChatMessage> = cp.messages.into_iter().skip(1).collect();
-                eprintln!("📂 从断点续跑 (run_id: {})", cp.run_id);
-                (resume_msg, cp.workspace, Some(history))
-            }
-            None => {
-                bail!("无可用断点。请先运行 `skilllite run --goal \"...\"` 以创建断点。");
+    let (effective_goal, effective_workspace, history_override, target) = match resume {
+        Some(from) => {
+            let id = match &from {
+                run_checkpoint::ResumeFrom::Id(id) => Some(id.as_str()),
+                run_checkpoint::ResumeFrom::LatestInWorkspace => None,
+            };
+            match run_checkpoint::load_checkpoint(chat_root, id, &config.workspace)? {
+                Some(cp) => {
+                    let resume_msg = run_checkpoint::build_resume_message(&cp);
+                    let target = run_checkpoint::RunCheckpointTarget::resuming(&cp);
+                    let (done, total) = cp.progress();
+                    // Use checkpoint messages as history; skip first (system) since agent_loop adds its own
+                    let history: Vec<ChatMessage> = cp.messages.into_iter().skip(1).collect();
+                    eprintln!(
+                        "📂 从断点续跑 (run_id: {}, 进度 {}/{})",
+                        cp.run_id, done, total
+                    );
+                    (resume_msg, cp.workspace, Some(history), target)
+                }
+                None if id.is_some() => {
+                    bail!(
+                        "未找到断点 {}。运行 `skilllite run --list-resumable` 查看可续跑的断点。",
+                        id.unwrap_or_default()
+                    );
+                }
+                None => {
+                    bail!(
+                        "当前工作区无可用断点。请先运行 `skilllite run --goal \"...\"` 以创建断点，\
+                         或用 `skilllite run --list-resumable` 查看其他工作区的断点。"
+                    );
+                }
             }
         }
-    } else {
-        (goal, config.workspace.clone(), None)
+        None => {
+            let target = run_checkpoint::RunCheckpointTarget::new(&goal);
+            (goal, config.workspace.clone(), None, target)
+        }
     };
 
     let mut config = config;
     config.workspace = effective_workspace;
+    let run_id = target.run_id.clone();
+    config.run_checkpoint = Some(target);
+
+    if config.max_cost_usd.is_some() && model_price(&config.model_prices, &config.model).is_none() {
+        eprintln!(
+            "⚠ No price for model {} in SKILLLITE_MODEL_PRICES_JSON; --max-cost is not enforced{}",
+            config.model,
+            if config.max_run_tokens.is_some() {
+                ", only --max-tokens"
+            } else {
+                ""
+            }
+        );
+    }
 
     // Optional first-run guidance: if no SOUL in chain and stdin is TTY, offer to create minimal template
     let _ = super::soul::Soul::offer_bootstrap_soul_if_missing(
@@ -215,22 +534,12 @@ pub fn run_agent_run(config: AgentConfig, goal: String, resume: bool) -> Result<
         eprintln!("└───────────────────────────────────────────────────────────\n");
     }
 
-    let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-
-    let verbose = config.verbose;
-    rt.block_on(async {
-        let mut session = ChatSession::new(config, "run", loaded_skills);
-        let mut sink = RunModeEventSink::new(verbose);
-        let result = if let Some(history) = history_override {
-            session
-                .run_turn_with_history(&effective_goal, &mut sink, history)
-                .await
-        } else {
-            session.run_turn(&effective_goal, &mut sink).await
-        };
-        let _ = result?;
-        // Response already streamed via sink during run_turn — no extra println
-        Ok(())
+    Ok(AgentRunSetup {
+        config,
+        goal: effective_goal,
+        history: history_override,
+        run_id,
+        skills: loaded_skills,
     })
 }
 
@@ -255,7 +564,8 @@ async fn run_interactive_chat(
 ) -> Result<()> {
     eprintln!("┌────────────────────────────────────────────────────────────");
     eprintln!("│  🤖 SkillBox Chat  ·  model: {}", config.model);
-    eprintln!("│  /exit 退出  ·  /clear 清空  ·  /compact 压缩历史");
+    eprintln!("│  /help 查看命令  ·  /exit 退出  ·  /model 切换模型  ·  /session 切换会话");
+    eprintln!("│  多行输入：行尾加 \\ 续行，或用 \"\"\" 开始 / 结束");
     eprintln!("└────────────────────────────────────────────────────────────\n");
 
     let workspace = config.workspace.clone();
@@ -264,52 +574,22 @@ async fn run_interactive_chat(
 
     let mut rl = rustyline::DefaultEditor::new()
         .map_err(|e| crate::Error::validation(format!("Failed to create line editor: {}", e)))?;
+    let history_path = repl::history_path();
+    let _ = rl.load_history(&history_path);
+    let mut pending = repl::MultiLineInput::default();
 
     loop {
-        let readline = rl.readline("You> ");
-        match readline {
-            Ok(line) => {
-                let input = line.trim();
-                if input.is_empty() {
-                    continue;
-                }
-
-                let _ = rl.add_history_entry(input);
-
-                match input {
-                    "/exit" | "/quit" | "/q" => {
-                        eprintln!("👋 Bye!");
-                        break;
-                    }
-                    "/clear" => {
-                        session.clear().await?;
-                        eprintln!("🗑️  Session cleared.");
-                        continue;
-                    }
-                    "/compact" => {
-                        eprintln!("📦 Compacting history...");
-                        match session.force_compact().await {
-                            Ok(true) => eprintln!("✅ History compacted."),
-                            Ok(false) => eprintln!("ℹ️  Not enough messages to compact."),
-                            Err(e) => eprintln!("❌ Compaction failed: {}", format_chat_error(&e)),
-                        }
-                        continue;
-                    }
-                    _ => {}
-                }
-
-                eprintln!();
-                match session.run_turn(input, &mut sink).await {
-                    Ok(result) => {
-                        maybe_prompt_record_wiki_lesson(&workspace, &result, &mut rl);
-                        eprintln!();
-                    }
-                    Err(e) => {
-                        let msg = format_chat_error(&e);
-                        eprintln!("❌ {}", msg);
-                        eprintln!();
-                    }
-                }
+        let prompt = if pending.is_pending() {
+            "...> "
+        } else {
+            "You> "
+        };
+        let line = match rl.readline(prompt) {
+            Ok(line) => line,
+            Err(rustyline::error::ReadlineError::Interrupted) if pending.is_pending() => {
+                pending.reset();
+                eprintln!("^C (multi-line input discarded)");
+                continue;
             }
             Err(rustyline::error::ReadlineError::Interrupted) => {
                 eprintln!("\n^C");
@@ -324,12 +604,153 @@ async fn run_interactive_chat(
                 eprintln!("Error: {}", e);
                 break;
             }
+        };
+        let Some(raw) = pending.push_line(&line) else {
+            continue;
+        };
+        let input = raw.trim();
+        if input.is_empty() {
+            continue;
+        }
+
+        let _ = rl.add_history_entry(input);
+
+        // Multi-line blocks are always prompts, even when they start with `/`.
+        let command = if input.contains('\n') {
+            None
+        } else {
+            repl::parse_repl_command(input)
+        };
+        if let Some(command) = command {
+            match command {
+                repl::ReplCommand::Exit => {
+                    eprintln!("👋 Bye!");
+                    break;
+                }
+                repl::ReplCommand::Help => {
+                    for (usage, description) in repl::SLASH_COMMANDS {
+                        eprintln!("  {:<20} {}", usage, description);
+                    }
+                }
+                repl::ReplCommand::Clear => {
+                    session.clear().await?;
+                    eprintln!("🗑️  Session cleared.");
+                }
+                repl::ReplCommand::Compact => {
+                    eprintln!("📦 Compacting history...");
+                    match session.force_compact().await {
+                        Ok(true) => eprintln!("✅ History compacted."),
+                        Ok(false) => eprintln!("ℹ️  Not enough messages to compact."),
+                        Err(e) => eprintln!("❌ Compaction failed: {}", format_chat_error(&e)),
+                    }
+                }
+                repl::ReplCommand::ReloadSkills => {
+                    session.reload_skills(&mut sink);
+                }
+                repl::ReplCommand::Skills => {
+                    if session.skills().is_empty() {
+                        eprintln!("ℹ️  No skills loaded.");
+                    }
+                    for skill in session.skills() {
+                        eprintln!("  📦 {} — {}", skill.name, skill.summary());
+                    }
+                }
+                repl::ReplCommand::Model(None) => {
+                    eprintln!("🤖 Model: {}", session.model());
+                }
+                repl::ReplCommand::Model(Some(model)) => match session.set_model(&model) {
+                    Ok(()) => eprintln!("🤖 Model switched to {} for the next turns.", model),
+                    Err(e) => eprintln!("❌ {}", format_chat_error(&e)),
+                },
+                repl::ReplCommand::Session(None) => {
+                    eprintln!("💬 Session: {}", session.session_key());
+                }
+                repl::ReplCommand::Session(Some(key)) => {
+                    if let Err(msg) = repl::validate_session_key(&key) {
+                        eprintln!("❌ {}", msg);
+                        continue;
+                    }
+                    match session.switch_session(&key, &mut sink).await {
+                        Ok(n) => eprintln!(
+                            "💬 Switched to session '{}' ({} message(s) of history).",
+                            key, n
+                        ),
+                        Err(e) => eprintln!("❌ Session switch failed: {}", format_chat_error(&e)),
+                    }
+                }
+                repl::ReplCommand::Attach(None) => {
+                    for a in session.pending_attachments() {
+                        eprintln!("📎 {} ({} bytes)", a.name, a.size_bytes);
+                    }
+                    eprintln!("ℹ️  Usage: /attach <path>");
+                }
+                repl::ReplCommand::Attach(Some(path)) => {
+                    match session.attach_file(std::path::Path::new(&path)) {
+                        Ok(a) => eprintln!(
+                            "📎 Attached {} ({}, {} bytes) → {}",
+                            a.name, a.media_type, a.size_bytes, a.path
+                        ),
+                        Err(e) => eprintln!("❌ Attach failed: {}", format_chat_error(&e)),
+                    }
+                }
+                repl::ReplCommand::Unknown(name) => {
+                    eprintln!("❓ Unknown command {}. Type /help for the list.", name);
+                }
+            }
+            continue;
+        }
+
+        eprintln!();
+        match session.run_turn(input, &mut sink).await {
+            Ok(result) => {
+                if verbose && result.feedback.secrets_redacted > 0 {
+                    print_redaction_count(session.secrets_redacted());
+                }
+                maybe_prompt_record_wiki_lesson(&workspace, &result, &mut rl);
+                eprintln!();
+            }
+            Err(e) => {
+                let msg = format_chat_error(&e);
+                eprintln!("❌ {}", msg);
+                eprintln!();
+            }
         }
     }
 
+    if let Some(dir) = history_path.parent() {
+        let _ = skilllite_fs::create_dir_all(dir);
+    }
+    if let Err(e) = rl.save_history(&history_path) {
+        tracing::debug!("Failed to save REPL history: {}", e);
+    }
+    remember_session(&mut session, AutoMemoryTrigger::Exit, &mut sink).await;
+    crate::extensions::stop_preview_servers(Some(session.session_key()));
+    crate::extensions::stop_background_jobs(Some(session.session_key()));
+    session.shutdown();
     Ok(())
 }
 
+/// Auto-memory at session end; failures are logged, never fatal.
+async fn remember_session(
+    session: &mut ChatSession,
+    trigger: AutoMemoryTrigger,
+    sink: &mut dyn EventSink,
+) {
+    if let Err(e) = session.remember_session(trigger, sink).await {
+        tracing::warn!("Auto-memory ({:?}) failed: {}", trigger, e);
+    }
+}
+
+/// Verbose mode: how many secrets were kept out of the LLM context this session.
+fn print_redaction_count(count: usize) {
+    if count > 0 {
+        eprintln!(
+            "🔒 Redacted {} secret(s) from tool output this session",
+            count
+        );
+    }
+}
+
 fn maybe_prompt_record_wiki_lesson(
     workspace: &str,
     result: &AgentResult,
@@ -6,7 +6,7 @@
 
 use crate::Result;
 use anyhow::Context;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
@@ -14,13 +14,16 @@ use std::path::{Path, PathBuf};
 use skilllite_executor::{memory as executor_memory, session, transcript};
 
 use skilllite_core::config::env_keys::evolution as evo_env_keys;
+use skilllite_core::tokens::{self, Tokenizer};
 
 use super::agent_loop;
+use super::attachments;
 use super::evolution;
 use super::extensions;
+use super::idle_evolution::{IdleEvolution, IdleEvolutionConfig, IdleEvolutionNotice};
 use super::llm::{self, LlmClient};
 use super::long_text;
-use super::skills::LoadedSkill;
+use super::skills::{self, LoadedSkill};
 use super::types::*;
 
 // Compaction threshold/keep are configurable via types::get_compaction_threshold()
@@ -44,10 +47,95 @@ pub struct ChatSession {
     skills: Vec<LoadedSkill>,
     /// A9: periodic evolution timer (every N seconds; not reset per user turn).
     periodic_evolution_handle: Option<tokio::task::JoinHandle<()>>,
+    /// A9: idle trigger, armed when a turn ends and disarmed when the next one starts.
+    idle_evolution: IdleEvolution,
     transcript_cache: TranscriptCache,
     /// Run-scoped artifact store. Defaults to `LocalDirArtifactStore` under `data_root`.
     /// Users may inject a custom implementation (S3, DB, etc.) via `with_artifact_store`.
     artifact_store: std::sync::Arc<dyn skilllite_core::artifact_store::ArtifactStore>,
+    /// Files queued by [`ChatSession::attach_file`] for the next turn.
+    pending_attachments: Vec<UserFileAttachment>,
+    /// Secrets redacted from tool results across all turns of this session.
+    secrets_redacted: usize,
+    /// Prompt/skill inputs seen at the previous turn start (see [`PromptInputs`]).
+    prompt_inputs: Option<PromptInputs>,
+    /// When the previous turn ended; drives the idle auto-memory trigger.
+    last_turn_at: Option<std::time::Instant>,
+    /// History length already summarized by auto-memory (skip when nothing new).
+    auto_memory_seen: usize,
+}
+
+/// Content hashes of what each turn's system prompt is built from: prompt files (global
+/// `prompts/` and project `.skilllite/prompts/`) and the skill dirs. The agent loop rebuilds
+/// the prompt every turn; comparing these lets the session reload skills and say why the
+/// prompt changed when evolution or the user edited them between turns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct PromptInputs {
+    /// `prompts/rules.json` → content hash.
+    files: BTreeMap<String, u64>,
+    /// [`skills::skill_dirs_fingerprint`] of the resolved skill dirs.
+    skills: u64,
+}
+
+impl PromptInputs {
+    fn capture(chat_root: &Path, workspace: &str, skill_dirs: &[String]) -> Self {
+        use std::hash::{Hash, Hasher};
+        let project_prompts =
+            skilllite_core::paths::project_skilllite_dir(Path::new(workspace)).join("prompts");
+        let mut files = BTreeMap::new();
+        for (label, dir) in [
+            ("prompts", chat_root.join("prompts")),
+            (".skilllite/prompts", project_prompts),
+        ] {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_prompt = matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("md" | "json")
+                );
+                if !is_prompt || !path.is_file() {
+                    continue;
+                }
+                let Ok(bytes) = std::fs::read(&path) else {
+                    continue;
+                };
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                bytes.hash(&mut hasher);
+                files.insert(
+                    format!("{}/{}", label, entry.file_name().to_string_lossy()),
+                    hasher.finish(),
+                );
+            }
+        }
+        Self {
+            files,
+            skills: skills::skill_dirs_fingerprint(skill_dirs),
+        }
+    }
+
+    /// Why `self` differs from `previous`, e.g. `prompts/rules.json changed`, `skills changed`.
+    fn changes_since(&self, previous: &Self) -> Vec<String> {
+        let mut reasons = Vec::new();
+        for (name, hash) in &self.files {
+            match previous.files.get(name) {
+                None => reasons.push(format!("{} added", name)),
+                Some(old) if old != hash => reasons.push(format!("{} changed", name)),
+                Some(_) => {}
+            }
+        }
+        for name in previous.files.keys() {
+            if !self.files.contains_key(name) {
+                reasons.push(format!("{} removed", name));
+            }
+        }
+        if self.skills != previous.skills {
+            reasons.push("skills changed".to_string());
+        }
+        reasons
+    }
 }
 
 #[derive(Default)]
@@ -77,6 +165,9 @@ impl ChatSession {
     fn new_inner(config: AgentConfig, session_key: &str, skills: Vec<LoadedSkill>) -> Self {
         let data_root = skilllite_executor::chat_root();
         skilllite_evolution::seed::ensure_seed_data(&data_root);
+        if let Err(e) = skilllite_evolution::journal::recover_at_startup(&data_root) {
+            tracing::warn!("Failed to recover interrupted evolution txn: {}", e);
+        }
         let artifact_store: std::sync::Arc<dyn skilllite_core::artifact_store::ArtifactStore> =
             std::sync::Arc::new(skilllite_artifact::LocalDirArtifactStore::new(&data_root));
         Self {
@@ -86,8 +177,14 @@ impl ChatSession {
             data_root,
             skills,
             periodic_evolution_handle: None,
+            idle_evolution: IdleEvolution::default(),
             transcript_cache: TranscriptCache::default(),
             artifact_store,
+            pending_attachments: Vec::new(),
+            secrets_redacted: 0,
+            prompt_inputs: None,
+            last_turn_at: None,
+            auto_memory_seen: 0,
         }
     }
 
@@ -127,10 +224,9 @@ impl ChatSession {
         }
 
         let sessions_path = self.data_root.join("sessions.json");
-        let mut store = session::SessionStore::load(&sessions_path)?;
-        let entry = store.create_or_get(&self.session_key);
-        let session_id = entry.session_id.clone();
-        store.save(&sessions_path)?;
+        let session_id = session::SessionStore::modify(&sessions_path, |store| {
+            Ok(store.create_or_get(&self.session_key).session_id.clone())
+        })?;
 
         // Ensure transcript
         let transcripts_dir = self.data_root.join("transcripts");
@@ -143,6 +239,8 @@ impl ChatSession {
 
     /// Read transcript entries and convert to ChatMessages.
     fn read_history(&mut self) -> Result<Vec<ChatMessage>> {
+        let pdf_skill = attachments::find_pdf_skill(&self.skills).map(str::to_string);
+        let pdf_skill = pdf_skill.as_deref();
         let entries = self.read_history_entries_incremental()?;
         let mut messages = Vec::new();
         let mut use_from_compaction = false;
@@ -174,17 +272,13 @@ impl ChatSession {
                     continue;
                 }
                 if past_compaction {
-                    if let Some(msg) = transcript_entry_to_message(entry) {
-                        messages.push(msg);
-                    }
+                    push_transcript_entry(entry, pdf_skill, &mut messages);
                 }
             }
         } else {
             // No compaction, use all message entries
             for entry in &entries {
-                if let Some(msg) = transcript_entry_to_message(entry) {
-                    messages.push(msg);
-                }
+                push_transcript_entry(entry, pdf_skill, &mut messages);
             }
         }
 
@@ -256,6 +350,97 @@ impl ChatSession {
             .await
     }
 
+    /// Copy a local file into the workspace `attachments/` dir and queue it for the next turn.
+    ///
+    /// The next turn injects a system note with the file path and a bounded preview, and records
+    /// the attachment on the user transcript row so resumed sessions still see it.
+    pub fn attach_file(&mut self, path: &Path) -> Result<&UserFileAttachment> {
+        let workspace = Path::new(&self.config.workspace);
+        let attachment = attachments::register_attachment(workspace, path)?;
+        self.pending_attachments.push(attachment);
+        Ok(self.pending_attachments.last().expect("just pushed"))
+    }
+
+    /// Attachments queued for the next turn.
+    pub fn pending_attachments(&self) -> &[UserFileAttachment] {
+        &self.pending_attachments
+    }
+
+    /// Secrets redacted from tool results so far in this session.
+    pub fn secrets_redacted(&self) -> usize {
+        self.secrets_redacted
+    }
+
+    /// Model used for the next turn.
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    /// Switch the model for subsequent turns. When `SKILLLITE_MODEL_ALLOWLIST` is set,
+    /// only models listed there are accepted.
+    pub fn set_model(&mut self, model: &str) -> Result<()> {
+        let model = model.trim();
+        if model.is_empty() {
+            return Err(crate::Error::validation("Model name is empty"));
+        }
+        let allowlist: Vec<String> = skilllite_core::config::loader::env_optional(
+            skilllite_core::config::env_keys::llm::MODEL_ALLOWLIST,
+            &[],
+        )
+        .map(|raw| {
+            raw.split(',')
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+        if !allowlist.is_empty() && !allowlist.iter().any(|m| m == model) {
+            return Err(crate::Error::validation(format!(
+                "Model '{}' is not in SKILLLITE_MODEL_ALLOWLIST ({})",
+                model,
+                allowlist.join(", ")
+            )));
+        }
+        self.config.model = model.to_string();
+        Ok(())
+    }
+
+    /// Current session key.
+    pub fn session_key(&self) -> &str {
+        &self.session_key
+    }
+
+    /// Switch to another session: end-of-session auto-memory and preview shutdown for
+    /// the current key, then the target's transcript becomes the history (created if
+    /// new). Returns how many history messages the next turn will see.
+    pub async fn switch_session(
+        &mut self,
+        session_key: &str,
+        event_sink: &mut dyn EventSink,
+    ) -> Result<usize> {
+        if session_key == self.session_key {
+            return Ok(self.read_history()?.len());
+        }
+        if let Err(e) = self
+            .remember_session(extensions::AutoMemoryTrigger::Exit, event_sink)
+            .await
+        {
+            tracing::warn!("Auto-memory before session switch failed: {}", e);
+        }
+        extensions::stop_preview_servers(Some(&self.session_key));
+        extensions::stop_background_jobs(Some(&self.session_key));
+
+        self.session_key = session_key.to_string();
+        self.session_id = None;
+        self.transcript_cache = TranscriptCache::default();
+        self.pending_attachments.clear();
+        self.secrets_redacted = 0;
+        self.last_turn_at = None;
+        self.auto_memory_seen = 0;
+        self.ensure_session()?;
+        Ok(self.read_history()?.len())
+    }
+
     /// A13: Run with overridden history (for --resume from checkpoint).
     pub async fn run_turn_with_history(
         &mut self,
@@ -275,10 +460,28 @@ impl ChatSession {
         history_override: Option<Vec<ChatMessage>>,
     ) -> Result<AgentResult> {
         let _session_id = self.ensure_session()?;
+        self.idle_evolution.disarm();
+        for notice in self.idle_evolution.take_notices() {
+            event_sink.on_evolution_completed(&notice.txn_id, &notice.changes);
+        }
+        self.refresh_prompt_inputs(event_sink);
+
+        // Idle auto-memory: the user came back after a long gap — bank the session so far.
+        let auto_memory = extensions::AutoMemoryConfig::from_env();
+        if let Some(last) = self.last_turn_at {
+            if last.elapsed().as_secs() >= auto_memory.idle_secs {
+                if let Err(e) = self
+                    .remember_session(extensions::AutoMemoryTrigger::Idle, event_sink)
+                    .await
+                {
+                    tracing::warn!("Idle auto-memory failed: {}", e);
+                }
+            }
+        }
 
         // EVO-1: Classify previous turn's user feedback from this message.
         // The feedback is attributed to the PREVIOUS decision, not the current one.
-        self.update_previous_feedback(user_message);
+        self.update_previous_feedback(user_message).await;
 
         // Read history from transcript (or use override for resume)
         let history = if let Some(h) = history_override {
@@ -311,12 +514,17 @@ impl ChatSession {
                         if let Err(e) = self.run_memory_flush_turn(&history).await {
                             tracing::warn!("Early memory flush failed: {}", e);
                         } else {
-                            if let Ok(mut store) = session::SessionStore::load(&sessions_path) {
+                            if let Err(e) = session::SessionStore::modify(&sessions_path, |store| {
                                 if let Some(se) = store.sessions.get_mut(&self.session_key) {
                                     se.memory_flush_compaction_count = Some(next_compaction);
                                     se.memory_flush_at = Some(chrono::Utc::now().to_rfc3339());
-                                    let _ = store.save(&sessions_path);
                                 }
+                                Ok(())
+                            }) {
+                                tracing::warn!(
+                                    "Failed to record memory flush in session store: {}",
+                                    e
+                                );
                             }
                             tracing::debug!(
                                 "Early memory flush completed (threshold={})",
@@ -370,19 +578,38 @@ impl ChatSession {
         // ── Guard #2: compress current user message if oversized ─────────────
         // Processed BEFORE transcript write so the stored version is already
         // compressed — read_history on next turn gets the compressed version directly.
-        let client = LlmClient::new(&self.config.api_base, &self.config.api_key)?;
+        let client = LlmClient::from_config(&self.config)?;
         let effective_user_message =
             long_text::maybe_process_user_input(&client, &self.config.model, user_message).await;
 
+        let turn_attachments = std::mem::take(&mut self.pending_attachments);
+        let attachment_note = if turn_attachments.is_empty() {
+            None
+        } else {
+            let pdf_skill = attachments::find_pdf_skill(&self.skills);
+            let note = attachments::attachment_note(&turn_attachments, pdf_skill);
+            history.push(ChatMessage::system(&note));
+            Some(note)
+        };
+
         let imgs_slice = turn_images.as_deref().filter(|s| !s.is_empty());
-        let user_payload_chars = effective_user_message.len()
+        let tokenizer = tokens::for_model(&self.config.model);
+        let user_payload_tokens = tokenizer.count(&effective_user_message)
+            + attachment_note
+                .as_ref()
+                .map(|n| tokenizer.count(n))
+                .unwrap_or(0)
             + imgs_slice
-                .map(|imgs| imgs.iter().map(|im| im.data_base64.len()).sum::<usize>())
+                .map(|imgs| {
+                    imgs.iter()
+                        .map(|im| im.data_base64.len() / tokens::HEURISTIC_CHARS_PER_TOKEN)
+                        .sum::<usize>()
+                })
                 .unwrap_or(0);
-        self.apply_pre_request_context_budget(&mut history, user_payload_chars)
+        self.apply_pre_request_context_budget(&mut history, user_payload_tokens, &tokenizer)
             .await?;
 
-        self.append_user_message_with_images(&effective_user_message, imgs_slice)?;
+        self.append_user_message(&effective_user_message, imgs_slice, &turn_attachments)?;
 
         event_sink.on_turn_start();
 
@@ -413,23 +640,29 @@ impl ChatSession {
 
         // Append assistant response to transcript
         self.append_assistant_message(&result.response, &result.feedback.llm_usage)?;
+        self.secrets_redacted += result.feedback.secrets_redacted;
 
         // EVO-1: Record execution decision (async-safe, <1ms with WAL).
-        // Only record meaningful turns (at least 1 tool call).
-        if result.feedback.total_tools >= 1 {
+        // Only record meaningful turns (at least 1 tool call); a caller-cancelled turn is not a signal.
+        if result.feedback.total_tools >= 1 && !result.cancelled {
             self.record_decision(&result.feedback);
             // A9: decision-count trigger — unprocessed ≥ threshold spawns evolution (in-process).
             self.maybe_trigger_evolution_by_decision_count();
         }
 
+        self.last_turn_at = Some(std::time::Instant::now());
+        self.arm_idle_evolution();
         Ok(result)
     }
 
-    /// Graceful shutdown: flush evolution metrics, cancel evolution timers.
+    /// Graceful shutdown: flush evolution metrics, cancel evolution timers. An idle-triggered
+    /// cycle that is already running is detached, not awaited; the metrics flush skips while
+    /// it holds the evolution mutex.
     pub fn shutdown(&mut self) {
         if let Some(handle) = self.periodic_evolution_handle.take() {
             handle.abort();
         }
+        self.idle_evolution.disarm();
         shutdown_evolution(&self.data_root);
     }
 
@@ -460,6 +693,39 @@ impl ChatSession {
         }
     }
 
+    /// A9: idle trigger — after `SKILLLITE_EVO_IDLE_SECS` without a new turn, run one cycle in
+    /// the background when `should_evolve` finds work.
+    fn arm_idle_evolution(&mut self) {
+        if skilllite_evolution::EvolutionMode::from_env().is_disabled() {
+            return;
+        }
+        let config = self.config.clone();
+        let data_root = self.data_root.clone();
+        let preflight_root = data_root.clone();
+        self.idle_evolution.arm(
+            &IdleEvolutionConfig::from_env(),
+            move || idle_evolution_has_work(&preflight_root),
+            async move {
+                let llm = match LlmClient::from_config(&config) {
+                    Ok(c) => c,
+                    Err(e) => {
+                        tracing::warn!("LLM client build failed for idle evolution: {}", e);
+                        return None;
+                    }
+                };
+                run_evolution_cycle(
+                    &data_root,
+                    &config.workspace,
+                    &llm,
+                    &config.api_base,
+                    &config.api_key,
+                    &config.model,
+                )
+                .await
+            },
+        );
+    }
+
     /// A9: weighted / raw-count / sweep arms (no periodic) — spawn evolution once when due.
     fn maybe_trigger_evolution_by_decision_count(&self) {
         if skilllite_evolution::EvolutionMode::from_env().is_disabled() {
@@ -491,7 +757,16 @@ impl ChatSession {
     /// Record an execution decision to the evolution DB.
     fn record_decision(&self, feedback: &ExecutionFeedback) {
         if let Ok(conn) = skilllite_evolution::feedback::open_evolution_db(&self.data_root) {
-            let input = evolution::execution_feedback_to_decision_input(feedback);
+            let mut input = evolution::execution_feedback_to_decision_input(feedback);
+            if !self.config.workspace.is_empty() {
+                input.workspace = Some(
+                    skilllite_core::paths::resolve_workspace_filesystem_root(
+                        &self.config.workspace,
+                    )
+                    .to_string_lossy()
+                    .to_string(),
+                );
+            }
             if let Err(e) = skilllite_evolution::feedback::insert_decision(
                 &conn,
                 Some(&self.session_key),
@@ -505,16 +780,35 @@ impl ChatSession {
     }
 
     /// Update the previous decision's feedback signal based on the current user message.
-    fn update_previous_feedback(&self, user_message: &str) {
-        let signal = classify_user_feedback(user_message);
-        if signal == FeedbackSignal::Neutral {
+    /// Word lists come from `prompts/feedback_signals.json`; with `SKILLLITE_EVO_FEEDBACK_LLM`
+    /// set, messages they leave neutral are classified by the LLM.
+    async fn update_previous_feedback(&self, user_message: &str) {
+        let mut signal = skilllite_evolution::classify_user_message(&self.data_root, user_message);
+        if signal == evolution::feedback::FeedbackSignal::Neutral
+            && skilllite_evolution::feedback_signals::llm_classification_enabled()
+        {
+            if let Ok(llm) = LlmClient::from_config(&self.config) {
+                let adapter = evolution::EvolutionLlmAdapter { llm: &llm };
+                match skilllite_evolution::feedback_signals::classify_with_llm(
+                    &adapter,
+                    &self.config.model,
+                    user_message,
+                )
+                .await
+                {
+                    Ok(s) => signal = s,
+                    Err(e) => tracing::debug!("LLM feedback classification failed: {}", e),
+                }
+            }
+        }
+        if signal == evolution::feedback::FeedbackSignal::Neutral {
             return;
         }
         if let Ok(conn) = skilllite_evolution::feedback::open_evolution_db(&self.data_root) {
             if let Err(e) = skilllite_evolution::feedback::update_last_decision_feedback(
                 &conn,
                 &self.session_key,
-                evolution::to_evolution_feedback(signal),
+                signal,
             ) {
                 tracing::debug!("Failed to update previous feedback: {}", e);
             }
@@ -543,16 +837,18 @@ impl ChatSession {
             content: Some(content.to_string()),
             tool_calls: None,
             images: None,
+            attachments: None,
             llm_usage,
         };
         Ok(transcript::append_entry(&t_path, &entry)?)
     }
 
-    /// Append a user message, optionally with vision images.
-    fn append_user_message_with_images(
+    /// Append a user message, optionally with vision images and file attachments.
+    fn append_user_message(
         &self,
         text: &str,
         images: Option<&[crate::types::UserImageAttachment]>,
+        files: &[UserFileAttachment],
     ) -> Result<()> {
         let transcripts_dir = self.data_root.join("transcripts");
         let t_path = transcript::transcript_path_today(&transcripts_dir, &self.session_key);
@@ -567,6 +863,11 @@ impl ChatSession {
             },
             tool_calls: None,
             images: images.map(|s| s.to_vec()),
+            attachments: if files.is_empty() {
+                None
+            } else {
+                Some(files.to_vec())
+            },
             llm_usage: None,
         };
         Ok(transcript::append_entry(&t_path, &entry)?)
@@ -616,54 +917,60 @@ impl ChatSession {
         Ok(())
     }
 
-    /// If estimated history + this turn's user payload exceeds `get_context_soft_limit_chars()`
-    /// (`SKILLLITE_CONTEXT_SOFT_LIMIT_CHARS`), shrink tool outputs and optionally run LLM compaction
-    /// so the next request is less likely to hit provider input limits.
+    /// If estimated history + this turn's user payload exceeds `get_context_soft_limit_tokens()`
+    /// (`SKILLLITE_CONTEXT_SOFT_LIMIT_TOKENS`, counted with the session model's tokenizer),
+    /// shrink tool outputs and optionally run LLM compaction so the next request is less likely
+    /// to hit provider input limits.
     ///
-    /// Disabled when `SKILLLITE_CONTEXT_SOFT_LIMIT_CHARS=0`.
+    /// Disabled when the limit is `0` (e.g. `SKILLLITE_CONTEXT_SOFT_LIMIT_CHARS=0`).
     async fn apply_pre_request_context_budget(
         &mut self,
         history: &mut Vec<ChatMessage>,
-        user_payload_chars: usize,
+        user_payload_tokens: usize,
+        tokenizer: &Tokenizer,
     ) -> Result<()> {
-        let soft = get_context_soft_limit_chars();
+        let soft = get_context_soft_limit_tokens();
         if soft == 0 {
             return Ok(());
         }
-        let mut budget = llm::estimate_messages_chars(history) + user_payload_chars;
+        let estimate = |history: &[ChatMessage]| {
+            llm::estimate_messages_tokens(history, tokenizer) + user_payload_tokens
+        };
+        let mut budget = estimate(history);
         if budget <= soft {
             return Ok(());
         }
         tracing::warn!(
-            estimated_chars = budget,
+            estimated_tokens = budget,
             soft_limit = soft,
+            approximate = tokenizer.is_approximate(),
             history_msgs = history.len(),
             "Pre-request context over soft limit; shrinking before LLM call"
         );
-        let recovery_cap = get_tool_result_recovery_max_chars();
-        llm::truncate_tool_messages(history, recovery_cap);
-        budget = llm::estimate_messages_chars(history) + user_payload_chars;
+        let recovery_cap = get_tool_result_recovery_max_chars() / tokens::HEURISTIC_CHARS_PER_TOKEN;
+        llm::truncate_tool_messages(history, recovery_cap, tokenizer);
+        budget = estimate(history);
         if budget <= soft {
             return Ok(());
         }
         let keep = get_compaction_keep_recent();
         if history.len() > keep {
             tracing::warn!(
-                estimated_chars = budget,
+                estimated_tokens = budget,
                 "Still over soft limit after tool shrink; running compaction"
             );
             let taken = std::mem::take(history);
             *history = self.compact_history_inner(taken, 0).await?;
-            budget = llm::estimate_messages_chars(history) + user_payload_chars;
+            budget = estimate(history);
         }
         if budget > soft {
-            let emergency = recovery_cap.max(500) / 2;
+            let emergency = recovery_cap.max(125) / 2;
             tracing::warn!(
-                estimated_chars = budget,
+                estimated_tokens = budget,
                 emergency_cap = emergency,
                 "Applying emergency tool truncation to stay under soft limit"
             );
-            llm::truncate_tool_messages(history, emergency);
+            llm::truncate_tool_messages(history, emergency, tokenizer);
         }
         Ok(())
     }
@@ -692,13 +999,22 @@ impl ChatSession {
                                 "Memory flush failed (continuing with compaction): {}",
                                 e
                             );
-                        } else if let Ok(mut store) = session::SessionStore::load(&sessions_path) {
-                            if let Some(session_entry) = store.sessions.get_mut(&self.session_key) {
-                                session_entry.memory_flush_compaction_count =
-                                    Some(next_compaction_count);
-                                session_entry.memory_flush_at =
-                                    Some(chrono::Utc::now().to_rfc3339());
-                                let _ = store.save(&sessions_path);
+                        } else {
+                            if let Err(e) = session::SessionStore::modify(&sessions_path, |store| {
+                                if let Some(session_entry) =
+                                    store.sessions.get_mut(&self.session_key)
+                                {
+                                    session_entry.memory_flush_compaction_count =
+                                        Some(next_compaction_count);
+                                    session_entry.memory_flush_at =
+                                        Some(chrono::Utc::now().to_rfc3339());
+                                }
+                                Ok(())
+                            }) {
+                                tracing::warn!(
+                                    "Failed to record memory flush in session store: {}",
+                                    e
+                                );
                             }
                         }
                     }
@@ -758,7 +1074,7 @@ impl ChatSession {
         let recent_messages = &history[split_point..];
 
         // Build summary of old messages via LLM
-        let client = LlmClient::new(&self.config.api_base, &self.config.api_key)?;
+        let client = LlmClient::from_config(&self.config)?;
         let summary_prompt = format!(
             "Please summarize the following conversation concisely, preserving key context, decisions, and results:\n\n{}",
             old_messages
@@ -814,12 +1130,14 @@ impl ChatSession {
 
         // Update session compaction count
         let sessions_path = self.data_root.join("sessions.json");
-        if let Ok(mut store) = session::SessionStore::load(&sessions_path) {
+        if let Err(e) = session::SessionStore::modify(&sessions_path, |store| {
             if let Some(entry) = store.sessions.get_mut(&self.session_key) {
                 entry.compaction_count += 1;
                 entry.updated_at = chrono::Utc::now().to_rfc3339();
-                let _ = store.save(&sessions_path);
             }
+            Ok(())
+        }) {
+            tracing::warn!("Failed to update compaction count in session store: {}", e);
         }
 
         // Return summary + recent messages
@@ -833,6 +1151,49 @@ impl ChatSession {
         Ok(result)
     }
 
+    /// Re-run skill discovery (`/reload-skills`) and swap in the result. The next turn builds
+    /// its registry and system prompt skill index from the new set; unchanged skills keep
+    /// their cached docs.
+    pub fn reload_skills(&mut self, event_sink: &mut dyn EventSink) -> SkillReloadSummary {
+        let dirs = skills::resolve_skill_dirs(&self.config.skill_dirs, &self.config.workspace);
+        let (next, summary) = skills::reload_skills(&self.skills, &dirs);
+        self.skills = next;
+        if let Some(inputs) = self.prompt_inputs.as_mut() {
+            inputs.skills = skills::skill_dirs_fingerprint(&dirs);
+        }
+        event_sink.on_skills_reloaded(&summary);
+        summary
+    }
+
+    /// Turn start: compare prompt files and skill dirs with the previous turn. Changed skills
+    /// are reloaded; any change is reported via [`EventSink::on_system_prompt_refreshed`].
+    /// The prompt itself is rebuilt by the agent loop as the first message of every request,
+    /// so history (including a compaction summary) never carries a stale copy.
+    fn refresh_prompt_inputs(&mut self, event_sink: &mut dyn EventSink) {
+        let dirs = skills::resolve_skill_dirs(&self.config.skill_dirs, &self.config.workspace);
+        let current = PromptInputs::capture(&self.data_root, &self.config.workspace, &dirs);
+        if let Some(previous) = self.prompt_inputs.take() {
+            let reasons = current.changes_since(&previous);
+            if current.skills != previous.skills {
+                self.reload_skills(event_sink);
+            }
+            if !reasons.is_empty() {
+                tracing::info!(
+                    session_key = %self.session_key,
+                    reasons = ?reasons,
+                    "System prompt inputs changed since last turn"
+                );
+                event_sink.on_system_prompt_refreshed(&reasons);
+            }
+        }
+        self.prompt_inputs = Some(current);
+    }
+
+    /// Currently loaded skills.
+    pub fn skills(&self) -> &[LoadedSkill] {
+        &self.skills
+    }
+
     /// Force compaction: summarize history via LLM regardless of threshold.
     /// Returns true if compaction was performed, false if history was too short.
     pub async fn force_compact(&mut self) -> Result<bool> {
@@ -856,6 +1217,8 @@ impl ChatSession {
         }
         self.archive_transcript()?;
         self.reset_session_counts()?;
+        crate::extensions::stop_preview_servers(Some(&self.session_key));
+        crate::extensions::stop_background_jobs(Some(&self.session_key));
         self.session_id = None;
         self.transcript_cache = TranscriptCache::default();
         Ok(())
@@ -878,9 +1241,11 @@ impl ChatSession {
 
     fn reset_session_counts(&self) -> Result<()> {
         let sessions_path = self.data_root.join("sessions.json");
-        if let Ok(mut store) = session::SessionStore::load(&sessions_path) {
+        if let Err(e) = session::SessionStore::modify(&sessions_path, |store| {
             store.reset_compaction_state(&self.session_key);
-            let _ = store.save(&sessions_path);
+            Ok(())
+        }) {
+            tracing::warn!("Failed to reset session counts in session store: {}", e);
         }
         Ok(())
     }
@@ -900,6 +1265,102 @@ impl ChatSession {
         Ok(())
     }
 
+    /// End-of-session auto-memory: ask the LLM for durable facts in this session and store
+    /// new ones under `memory/auto/` (see [`extensions::AutoMemoryConfig`]). Returns how many
+    /// were remembered; reports them via [`EventSink::on_memories_remembered`].
+    /// No-op with `--no-memory`, when `trigger` is disabled, the per-session cap is reached,
+    /// or nothing happened since the last run.
+    pub async fn remember_session(
+        &mut self,
+        trigger: extensions::AutoMemoryTrigger,
+        event_sink: &mut dyn EventSink,
+    ) -> Result<usize> {
+        let cfg = extensions::AutoMemoryConfig::from_env();
+        if !self.config.enable_memory
+            || !cfg.enabled(trigger)
+            || self.config.api_key.trim().is_empty()
+        {
+            return Ok(0);
+        }
+        let remaining = cfg
+            .max_per_session
+            .saturating_sub(extensions::count_auto_memories(
+                &self.data_root,
+                &self.session_key,
+            ));
+        if remaining == 0 {
+            return Ok(0);
+        }
+        let history = self.read_history()?;
+        if history.len() <= self.auto_memory_seen {
+            return Ok(0);
+        }
+        self.auto_memory_seen = history.len();
+
+        // Newest messages matter most; keep the tail within a fixed budget.
+        const MAX_CONVERSATION_CHARS: usize = 24_000;
+        let mut lines: Vec<String> = Vec::new();
+        let mut used = 0;
+        for m in history.iter().rev() {
+            if !matches!(m.role.as_str(), "user" | "assistant") {
+                continue;
+            }
+            let Some(content) = m.content.as_deref().filter(|c| !c.trim().is_empty()) else {
+                continue;
+            };
+            let line = format!("[{}] {}", m.role, safe_truncate(content, 2_000));
+            used += line.len();
+            if used > MAX_CONVERSATION_CHARS {
+                break;
+            }
+            lines.push(line);
+        }
+        if lines.is_empty() {
+            return Ok(0);
+        }
+        lines.reverse();
+
+        let client = LlmClient::from_config(&self.config)?;
+        let prompt = extensions::extraction_prompt(&lines.join("\n"), remaining);
+        let reply = client
+            .chat_completion(
+                &self.config.model,
+                &[ChatMessage::user(&prompt)],
+                None,
+                Some(0.2),
+                None,
+            )
+            .await?
+            .choices
+            .first()
+            .and_then(|c| c.message.content.clone())
+            .unwrap_or_default();
+        let facts = extensions::parse_facts(&reply);
+        if facts.is_empty() {
+            return Ok(0);
+        }
+
+        let report = extensions::write_auto_memories(
+            &self.data_root,
+            "default",
+            &self.session_key,
+            &facts,
+            remaining,
+        )
+        .await?;
+        tracing::info!(
+            session_key = %self.session_key,
+            ?trigger,
+            remembered = report.remembered.len(),
+            duplicates = report.duplicates,
+            "Auto-memory stored session facts"
+        );
+        if !report.remembered.is_empty() {
+            event_sink.on_memories_remembered(&report.remembered);
+        }
+        Ok(report.remembered.len())
+    }
+
     /// Summarize conversation history and write to memory.
     /// Called before clearing a session to preserve key context.
     async fn summarize_for_memory(&self, history: &[ChatMessage]) -> Result<()> {
@@ -909,7 +1370,7 @@ impl ChatSession {
             return Ok(());
         }
 
-        let client = LlmClient::new(&self.config.api_base, &self.config.api_key)?;
+        let client = LlmClient::from_config(&self.config)?;
 
         let conversation: Vec<String> = history
             .iter()
@@ -1034,13 +1495,12 @@ fn read_entries_from_offset(
         if trimmed.is_empty() {
             continue;
         }
-        let entry: transcript::TranscriptEntry =
-            serde_json::from_str(trimmed).with_context(|| {
-                format!(
-                    "Failed to parse transcript line: {}",
-                    transcript_path.display()
-                )
-            })?;
+        let entry = transcript::parse_entry_line(trimmed).with_context(|| {
+            format!(
+                "Failed to parse transcript line: {}",
+                transcript_path.display()
+            )
+        })?;
         entries.push(entry);
     }
     let next_offset = reader.stream_position().with_context(|| {
@@ -1182,7 +1642,6 @@ async fn run_evolution_and_emit_summary(
     api_key: &str,
     model: &str,
 ) {
-    let skills_root = resolve_evolution_skills_root(workspace);
     let llm = match LlmClient::new(api_base, api_key) {
         Ok(c) => c,
         Err(e) => {
@@ -1190,7 +1649,35 @@ async fn run_evolution_and_emit_summary(
             return;
         }
     };
-    let adapter = evolution::EvolutionLlmAdapter { llm: &llm };
+    if let Some(notice) =
+        run_evolution_cycle(data_root, workspace, &llm, api_base, api_key, model).await
+    {
+        for msg in &notice.changes {
+            eprintln!("{}", msg);
+        }
+    }
+}
+
+/// Idle-trigger preflight: `should_evolve` has at least one dimension to work on.
+fn idle_evolution_has_work(data_root: &Path) -> bool {
+    skilllite_evolution::feedback::open_evolution_db(data_root)
+        .ok()
+        .and_then(|conn| skilllite_evolution::should_evolve(&conn).ok())
+        .is_some_and(|scope| scope.skills || scope.memory || scope.prompts)
+}
+
+/// One evolution cycle plus post-run upkeep (auto-rollback check, knowledge reindex).
+/// Returns the formatted changes when the cycle produced any.
+async fn run_evolution_cycle(
+    data_root: &Path,
+    workspace: &str,
+    llm: &LlmClient,
+    api_base: &str,
+    api_key: &str,
+    model: &str,
+) -> Option<IdleEvolutionNotice> {
+    let skills_root = resolve_evolution_skills_root(workspace);
+    let adapter = evolution::EvolutionLlmAdapter { llm };
     let skills_root_ref = skills_root.as_deref();
     match skilllite_evolution::run_evolution(
         data_root,
@@ -1205,25 +1692,28 @@ async fn run_evolution_and_emit_summary(
     {
         Ok(skilllite_evolution::EvolutionRunResult::Completed(Some(txn_id))) => {
             tracing::info!("Evolution completed: {}", txn_id);
-            if let Ok(conn) = skilllite_evolution::feedback::open_evolution_db(data_root) {
-                let changes = skilllite_evolution::query_changes_by_txn(&conn, &txn_id);
-                for msg in &skilllite_evolution::format_evolution_changes(&changes) {
-                    eprintln!("{}", msg);
-                }
-                let _ = skilllite_evolution::check_auto_rollback(&conn, data_root, skills_root_ref);
-                if changes.iter().any(|(t, _)| t == "memory_knowledge_added") {
-                    let _ = extensions::index_evolution_knowledge(data_root, "default");
-                }
+            let conn = skilllite_evolution::feedback::open_evolution_db(data_root).ok()?;
+            let changes = skilllite_evolution::query_changes_by_txn(&conn, &txn_id);
+            let _ = skilllite_evolution::check_auto_rollback(&conn, data_root, skills_root_ref);
+            if changes.iter().any(|(t, _)| t == "memory_knowledge_added") {
+                let _ = extensions::index_evolution_knowledge(data_root, "default");
             }
+            let changes = skilllite_evolution::format_evolution_changes(&changes);
+            (!changes.is_empty()).then_some(IdleEvolutionNotice { txn_id, changes })
         }
         Ok(skilllite_evolution::EvolutionRunResult::SkippedBusy) => {
             tracing::warn!("Evolution skipped: another run in progress");
+            None
         }
         Ok(skilllite_evolution::EvolutionRunResult::NoScope)
         | Ok(skilllite_evolution::EvolutionRunResult::Completed(None)) => {
             tracing::debug!("Evolution: nothing to evolve");
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Evolution failed: {}", e);
+            None
         }
-        Err(e) => tracing::warn!("Evolution failed: {}", e),
     }
 }
 
@@ -1328,6 +1818,29 @@ pub fn shutdown_evolution(data_root: &std::path::Path) {
     skilllite_evolution::on_shutdown(data_root);
 }
 
+/// Push the ChatMessage(s) for one transcript entry; user rows with file attachments are
+/// preceded by the same attachment note the model saw on the live turn.
+fn push_transcript_entry(
+    entry: &transcript::TranscriptEntry,
+    pdf_skill: Option<&str>,
+    messages: &mut Vec<ChatMessage>,
+) {
+    if let transcript::TranscriptEntry::Message {
+        attachments: Some(files),
+        ..
+    } = entry
+    {
+        if !files.is_empty() {
+            messages.push(ChatMessage::system(&attachments::attachment_note(
+                files, pdf_skill,
+            )));
+        }
+    }
+    if let Some(msg) = transcript_entry_to_message(entry) {
+        messages.push(msg);
+    }
+}
+
 /// Convert a transcript entry to a ChatMessage.
 fn transcript_entry_to_message(entry: &transcript::TranscriptEntry) -> Option<ChatMessage> {
     match entry {
@@ -1414,6 +1927,7 @@ mod history_window_tests {
             content: Some(content.to_string()),
             tool_calls: None,
             images: None,
+            attachments: None,
             llm_usage: None,
         }
     }
@@ -1489,4 +2003,161 @@ mod history_window_tests {
             Some(transcript::TranscriptEntry::Compaction { .. })
         ));
     }
+    #[test]
+    fn resumed_history_replays_attachment_note_before_user_message() {
+        let mut entry = msg("summarize this");
+        if let transcript::TranscriptEntry::Message { attachments, .. } = &mut entry {
+            *attachments = Some(vec![UserFileAttachment {
+                path: "/ws/attachments/report.pdf".to_string(),
+                name: "report.pdf".to_string(),
+                media_type: "application/pdf".to_string(),
+                size_bytes: 2048,
+                preview: None,
+            }]);
+        }
+        let mut messages = Vec::new();
+        push_transcript_entry(&entry, Some("pdf"), &mut messages);
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, "system");
+        let note = messages[0].content.as_deref().unwrap();
+        assert!(note.contains("/ws/attachments/report.pdf"));
+        assert!(note.contains("`pdf` skill"));
+        assert_eq!(messages[1].content.as_deref(), Some("summarize this"));
+    }
+}
+
+#[cfg(test)]
+mod prompt_refresh_tests {
+    use super::*;
+    use crate::agent_loop::scripted_llm::{self, Reply};
+
+    const RULE_MARKER: &str = "Always cite the ticket number PRF-4821";
+
+    #[derive(Default)]
+    struct RefreshSink {
+        refreshes: Vec<Vec<String>>,
+    }
+
+    impl EventSink for RefreshSink {
+        fn on_text(&mut self, _text: &str) {}
+        fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+        fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+        fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+            true
+        }
+        fn on_system_prompt_refreshed(&mut self, reasons: &[String]) {
+            self.refreshes.push(reasons.to_vec());
+        }
+    }
+
+    fn system_prompts(requests: &[serde_json::Value]) -> Vec<String> {
+        requests
+            .iter()
+            .filter_map(|body| body["messages"][0]["content"].as_str())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn prompt_inputs_report_changed_added_and_removed_files() {
+        let root = tempfile::tempdir().unwrap();
+        let prompts = root.path().join("prompts");
+        std::fs::create_dir_all(&prompts).unwrap();
+        std::fs::write(prompts.join("rules.json"), "[]").unwrap();
+        std::fs::write(prompts.join("system.md"), "base").unwrap();
+        let ws = root.path().to_string_lossy().to_string();
+        let before = PromptInputs::capture(root.path(), &ws, &[]);
+        assert!(PromptInputs::capture(root.path(), &ws, &[])
+            .changes_since(&before)
+            .is_empty());
+
+        std::fs::write(prompts.join("rules.json"), "[{}]").unwrap();
+        std::fs::remove_file(prompts.join("system.md")).unwrap();
+        std::fs::write(prompts.join("examples.json"), "[]").unwrap();
+        std::fs::write(prompts.join("notes.txt"), "ignored").unwrap();
+        let after = PromptInputs::capture(root.path(), &ws, &[]);
+        assert_eq!(
+            after.changes_since(&before),
+            vec![
+                "prompts/examples.json added".to_string(),
+                "prompts/rules.json changed".to_string(),
+                "prompts/system.md removed".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn edited_rules_reach_the_next_turn_system_prompt() {
+        let data = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let key = skilllite_core::config::env_keys::paths::SKILLLITE_WORKSPACE;
+        let prev = std::env::var(key).ok();
+        std::env::set_var(key, data.path());
+
+        let llm = scripted_llm::spawn(|_, _| Reply::text("Done."));
+        let config = AgentConfig {
+            api_base: llm.base.clone(),
+            api_key: "test".to_string(),
+            model: "fake".to_string(),
+            max_iterations: 2,
+            workspace: workspace.path().to_string_lossy().to_string(),
+            enable_task_planning: false,
+            enable_memory: false,
+            ..AgentConfig::default()
+        };
+        let mut session = ChatSession::new_for_clear(config, "prompt-refresh", Vec::new());
+        let mut sink = RefreshSink::default();
+
+        session.run_turn("first question", &mut sink).await.unwrap();
+        let first_turn_requests = llm.requests.lock().unwrap().len();
+        assert!(sink.refreshes.is_empty());
+
+        let rules = serde_json::json!([{
+            "id": "user_ticket_citation",
+            "instruction": RULE_MARKER,
+            "mutable": true,
+            "origin": "user"
+        }]);
+        let prompts_dir = data.path().join("chat").join("prompts");
+        std::fs::create_dir_all(&prompts_dir).unwrap();
+        std::fs::write(prompts_dir.join("rules.json"), rules.to_string()).unwrap();
+        session
+            .run_turn("second question", &mut sink)
+            .await
+            .unwrap();
+
+        match prev {
+            Some(v) => std::env::set_var(key, v),
+            None => std::env::remove_var(key),
+        }
+
+        assert_eq!(sink.refreshes.len(), 1);
+        assert!(sink.refreshes[0].contains(&"prompts/rules.json changed".to_string()));
+        let requests = llm.requests.lock().unwrap().clone();
+        let (first, second) = requests.split_at(first_turn_requests);
+        assert!(system_prompts(first)
+            .iter()
+            .all(|p| !p.contains(RULE_MARKER)));
+        let turn_request = second
+            .iter()
+            .find(|body| {
+                body["messages"][0]["content"]
+                    .as_str()
+                    .is_some_and(|p| p.contains(RULE_MARKER))
+            })
+            .expect("second turn request carries the new rule");
+        // Replaced, not appended: only the leading system message carries the rules.
+        let carrying = turn_request["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|m| {
+                m["content"]
+                    .as_str()
+                    .is_some_and(|c| c.contains(RULE_MARKER))
+            })
+            .count();
+        assert_eq!(carrying, 1);
+    }
 }
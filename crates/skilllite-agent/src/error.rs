@@ -38,6 +38,14 @@ impl Error {
     pub fn validation(msg: impl Into<String>) -> Self {
         Error::Validation(msg.into())
     }
+
+    /// Structured failure kind when this wraps a classified sandbox run error.
+    pub fn sandbox_error(&self) -> Option<&skilllite_sandbox::SandboxError> {
+        match self {
+            Error::Sandbox(e) => e.sandbox_error(),
+            _ => None,
+        }
+    }
 }
 
 macro_rules! bail {
@@ -103,12 +103,23 @@ pub fn execution_feedback_to_decision_input(feedback: &ExecutionFeedback) -> Dec
         completion_type_reported: feedback.completion_type.as_str().to_string(),
         task_description: feedback.task_description.clone(),
         rules_used: feedback.rules_used.clone(),
+        rules_in_scope: feedback.rules_in_scope.clone(),
+        model: (!feedback.model.is_empty()).then(|| feedback.model.clone()),
+        fallback_model: feedback.fallback_model.clone(),
+        replan_reasons: feedback.replan_reasons.clone(),
+        tool_loops: feedback.tool_loops,
+        workspace: None,
         tools_detail: feedback
             .tools_detail
             .iter()
             .map(|t| skilllite_evolution::feedback::ToolExecDetail {
                 tool: t.tool.clone(),
                 success: t.success,
+                skill: t.skill.clone(),
+                duration_ms: t.duration_ms,
+                result_bytes: t.result_bytes,
+                error_kind: t.error_kind.clone(),
+                args_present: t.args_present.clone(),
             })
             .collect(),
     }
@@ -151,11 +162,18 @@ mod tests {
             completion_type: TaskCompletionType::Success,
             task_description: Some("test task".to_string()),
             rules_used: vec!["rule.alpha".to_string(), "rule.beta".to_string()],
+            rules_in_scope: vec!["rule.beta".to_string()],
             tools_detail: vec![ToolExecDetail {
                 tool: "read_file".to_string(),
                 success: true,
+                ..Default::default()
             }],
             llm_usage: Default::default(),
+            model: "gpt-4o".to_string(),
+            fallback_model: None,
+            replan_reasons: vec![],
+            secrets_redacted: 0,
+            tool_loops: 0,
         };
 
         let input = execution_feedback_to_decision_input(&feedback);
@@ -163,6 +181,7 @@ mod tests {
             input.rules_used,
             vec!["rule.alpha".to_string(), "rule.beta".to_string()]
         );
+        assert_eq!(input.rules_in_scope, vec!["rule.beta".to_string()]);
         assert_eq!(input.completion_type, "success");
         assert_eq!(input.completion_type_reported, "success");
     }
@@ -182,20 +201,34 @@ mod tests {
             completion_type: TaskCompletionType::Failure,
             task_description: Some("another test task".to_string()),
             rules_used: vec!["rule.gamma".to_string()],
+            rules_in_scope: vec![],
             tools_detail: vec![
                 ToolExecDetail {
                     tool: "list_directory".to_string(),
                     success: true,
+                    ..Default::default()
                 },
                 ToolExecDetail {
                     tool: "write_file".to_string(),
                     success: false,
+                    ..Default::default()
                 },
             ],
             llm_usage: Default::default(),
+            model: "gpt-4o".to_string(),
+            fallback_model: Some("qwen-max".to_string()),
+            replan_reasons: vec!["write_file was denied".to_string()],
+            secrets_redacted: 0,
+            tool_loops: 0,
         };
 
         let input = execution_feedback_to_decision_input(&feedback);
+        assert_eq!(input.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(input.fallback_model.as_deref(), Some("qwen-max"));
+        assert_eq!(
+            input.replan_reasons,
+            vec!["write_file was denied".to_string()]
+        );
         assert_eq!(input.tools_detail.len(), 2);
         assert_eq!(input.tools_detail[0].tool, "list_directory".to_string());
         assert!(input.tools_detail[0].success);
@@ -219,8 +252,14 @@ mod tests {
             completion_type: TaskCompletionType::Success,
             task_description: Some("conflict task".to_string()),
             rules_used: vec![],
+            rules_in_scope: vec![],
             tools_detail: vec![],
             llm_usage: Default::default(),
+            model: "gpt-4o".to_string(),
+            fallback_model: None,
+            replan_reasons: vec![],
+            secrets_redacted: 0,
+            tool_loops: 0,
         };
         let input = execution_feedback_to_decision_input(&feedback);
         assert_eq!(input.completion_type_reported, "success");
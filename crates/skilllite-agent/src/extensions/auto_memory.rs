@@ -0,0 +1,443 @@
+//! Automatic end-of-session memory.
+//!
+//! Memory files used to be written only when the model remembered to call `memory_write`.
+//! At configurable points (`/exit`, single-shot completion, first turn after an idle gap)
+//! the chat session asks the LLM for durable facts — user preferences, project facts,
+//! resolved gotchas — and stores them here under `memory/auto/<session>.md`.
+//!
+//! Entries go through the regular `memory_write` path (redaction + BM25 indexing), skip
+//! facts already present in memory, count against a per-session cap and carry an
+//! `<!-- auto-memory ... -->` marker so they can be purged without touching explicit memories.
+
+use std::path::Path;
+
+use anyhow::Context;
+use rusqlite::Connection;
+use serde::Deserialize;
+use serde_json::json;
+
+use skilllite_core::config::env_keys::memory as mem_keys;
+use skilllite_core::config::loader::env_optional;
+
+use crate::Result;
+
+/// Namespace (under `memory/`) reserved for automatic memories.
+pub const AUTO_MEMORY_DIR: &str = "auto";
+
+/// Marker prefix written before every automatic entry.
+const AUTO_MEMORY_MARKER: &str = "<!-- auto-memory ";
+
+const DEFAULT_MAX_PER_SESSION: usize = 5;
+const DEFAULT_IDLE_SECS: u64 = 900;
+/// Longest fact kept; the model is asked for one sentence.
+const MAX_FACT_CHARS: usize = 500;
+/// Share of a fact's words that must already appear in a memory chunk to count as known.
+const DUPLICATE_OVERLAP: f64 = 0.8;
+
+/// When the session summarizes itself into memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoMemoryTrigger {
+    /// Interactive chat ended (`/exit`, Ctrl-C, Ctrl-D).
+    Exit,
+    /// `chat -m` or `run` finished.
+    SingleShot,
+    /// A new turn arrived after `SKILLLITE_AUTO_MEMORY_IDLE_SECS` without activity.
+    Idle,
+}
+
+impl AutoMemoryTrigger {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "exit" => Some(Self::Exit),
+            "single" | "single-shot" | "single_shot" => Some(Self::SingleShot),
+            "idle" => Some(Self::Idle),
+            _ => None,
+        }
+    }
+}
+
+/// Auto-memory settings (`SKILLLITE_AUTO_MEMORY*`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoMemoryConfig {
+    pub triggers: Vec<AutoMemoryTrigger>,
+    pub max_per_session: usize,
+    pub idle_secs: u64,
+}
+
+impl Default for AutoMemoryConfig {
+    fn default() -> Self {
+        Self {
+            triggers: vec![
+                AutoMemoryTrigger::Exit,
+                AutoMemoryTrigger::SingleShot,
+                AutoMemoryTrigger::Idle,
+            ],
+            max_per_session: DEFAULT_MAX_PER_SESSION,
+            idle_secs: DEFAULT_IDLE_SECS,
+        }
+    }
+}
+
+impl AutoMemoryConfig {
+    pub fn from_env() -> Self {
+        let mut cfg = Self::default();
+        if let Some(raw) = env_optional(mem_keys::SKILLLITE_AUTO_MEMORY, &[]) {
+            cfg.triggers = parse_triggers(&raw);
+        }
+        if let Some(n) = env_optional(mem_keys::SKILLLITE_AUTO_MEMORY_MAX, &[])
+            .and_then(|v| v.trim().parse().ok())
+        {
+            cfg.max_per_session = n;
+        }
+        if let Some(n) = env_optional(mem_keys::SKILLLITE_AUTO_MEMORY_IDLE_SECS, &[])
+            .and_then(|v| v.trim().parse().ok())
+        {
+            cfg.idle_secs = n;
+        }
+        cfg
+    }
+
+    pub fn enabled(&self, trigger: AutoMemoryTrigger) -> bool {
+        self.max_per_session > 0 && self.triggers.contains(&trigger)
+    }
+}
+
+/// `exit,idle` → triggers; `off` / `none` / `0` / `false` → none. Unknown names are ignored.
+fn parse_triggers(raw: &str) -> Vec<AutoMemoryTrigger> {
+    if matches!(
+        raw.trim().to_ascii_lowercase().as_str(),
+        "off" | "none" | "0" | "false" | "no"
+    ) {
+        return Vec::new();
+    }
+    let mut out = Vec::new();
+    for t in raw.split(',').filter_map(AutoMemoryTrigger::parse) {
+        if !out.contains(&t) {
+            out.push(t);
+        }
+    }
+    out
+}
+
+/// One durable fact extracted by the model.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AutoMemoryFact {
+    /// `preference` | `project` | `gotcha`.
+    #[serde(default)]
+    pub kind: String,
+    pub fact: String,
+}
+
+/// Extraction prompt for the conversation transcript; asks for at most `limit` facts as JSON.
+pub fn extraction_prompt(conversation: &str, limit: usize) -> String {
+    format!(
+        "Extract durable facts from this conversation that will still matter in future sessions:\n\
+         - preference: how the user likes things done (tools, style, language)\n\
+         - project: stable facts about their projects (paths, stack, conventions, decisions)\n\
+         - gotcha: a problem that was hit and how it was resolved\n\n\
+         Skip small talk, one-off task details, secrets and anything already obvious from the code.\n\
+         Return ONLY a JSON array (at most {} items, possibly empty) of objects \
+         {{\"kind\": \"preference|project|gotcha\", \"fact\": \"one self-contained sentence\"}}.\n\n\
+         Conversation:\n{}",
+        limit, conversation
+    )
+}
+
+/// Parse the model's reply into facts. Tolerates code fences and prose around the array.
+pub fn parse_facts(raw: &str) -> Vec<AutoMemoryFact> {
+    let (Some(start), Some(end)) = (raw.find('['), raw.rfind(']')) else {
+        return Vec::new();
+    };
+    if end < start {
+        return Vec::new();
+    }
+    let Ok(items) = serde_json::from_str::<Vec<AutoMemoryFact>>(&raw[start..=end]) else {
+        return Vec::new();
+    };
+    items
+        .into_iter()
+        .filter_map(|mut f| {
+            let fact = f.fact.split_whitespace().collect::<Vec<_>>().join(" ");
+            if fact.is_empty() {
+                return None;
+            }
+            f.fact = fact.chars().take(MAX_FACT_CHARS).collect();
+            f.kind = match f.kind.trim().to_ascii_lowercase().as_str() {
+                k @ ("preference" | "gotcha") => k.to_string(),
+                _ => "project".to_string(),
+            };
+            Some(f)
+        })
+        .collect()
+}
+
+/// `memory/`-relative file holding a session's automatic memories.
+pub fn auto_memory_rel_path(session_key: &str) -> String {
+    let safe: String = session_key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}/{}.md", AUTO_MEMORY_DIR, safe)
+}
+
+/// How many automatic memories this session already stored.
+pub fn count_auto_memories(chat_root: &Path, session_key: &str) -> usize {
+    let path = chat_root
+        .join("memory")
+        .join(auto_memory_rel_path(session_key));
+    std::fs::read_to_string(path)
+        .map(|c| c.matches(AUTO_MEMORY_MARKER).count())
+        .unwrap_or(0)
+}
+
+fn words(text: &str) -> Vec<String> {
+    let mut out: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 2)
+        .map(|w| w.to_lowercase())
+        .collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// True when (almost) every word of `fact` already appears in `known`.
+fn covered_by(fact_words: &[String], fact: &str, known: &str) -> bool {
+    if fact_words.len() < 3 {
+        let norm = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ");
+        return norm(&known.to_lowercase()).contains(&norm(&fact.to_lowercase()));
+    }
+    let known_words = words(known);
+    let hits = fact_words
+        .iter()
+        .filter(|w| known_words.binary_search(w).is_ok())
+        .count();
+    hits as f64 / fact_words.len() as f64 >= DUPLICATE_OVERLAP
+}
+
+/// Whether `fact` is already in the memory index (BM25 candidates) or earlier in this batch.
+fn is_known(conn: Option<&Connection>, fact: &str, batch: &[String]) -> bool {
+    let fact_words = words(fact);
+    if batch.iter().any(|b| covered_by(&fact_words, fact, b)) {
+        return true;
+    }
+    let Some(conn) = conn else {
+        return false;
+    };
+    if fact_words.is_empty() {
+        return false;
+    }
+    let query = fact_words
+        .iter()
+        .map(|w| format!("\"{}\"", w.replace('"', "")))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    skilllite_executor::memory::search_bm25(conn, &query, 5)
+        .map(|hits| {
+            hits.iter()
+                .any(|h| covered_by(&fact_words, fact, &h.content))
+        })
+        .unwrap_or(false)
+}
+
+/// Result of [`write_auto_memories`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AutoMemoryReport {
+    /// Facts written, in order.
+    pub remembered: Vec<String>,
+    /// Facts skipped because memory already had them.
+    pub duplicates: usize,
+}
+
+/// Store new facts for `session_key` via `memory_write`, skipping known ones, up to `cap`.
+pub async fn write_auto_memories(
+    chat_root: &Path,
+    agent_id: &str,
+    session_key: &str,
+    facts: &[AutoMemoryFact],
+    cap: usize,
+) -> Result<AutoMemoryReport> {
+    let mut report = AutoMemoryReport::default();
+    let idx_path = skilllite_executor::memory::index_path(chat_root, agent_id);
+    let conn = if idx_path.exists() {
+        Connection::open(&idx_path)
+            .ok()
+            .filter(|c| skilllite_executor::memory::ensure_index(c).is_ok())
+    } else {
+        None
+    };
+
+    let rel_path = auto_memory_rel_path(session_key);
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut batch: Vec<String> = Vec::new();
+    for f in facts {
+        if report.remembered.len() >= cap {
+            break;
+        }
+        if is_known(conn.as_ref(), &f.fact, &batch) {
+            report.duplicates += 1;
+            continue;
+        }
+        let entry = format!(
+            "{}session=\"{}\" at=\"{}\" kind=\"{}\" -->\n- {}",
+            AUTO_MEMORY_MARKER, session_key, now, f.kind, f.fact
+        );
+        let args = json!({ "rel_path": rel_path, "content": entry, "append": true });
+        super::memory::execute_memory_write(&args, chat_root, agent_id, false, None).await?;
+        batch.push(f.fact.clone());
+        report.remembered.push(f.fact.clone());
+    }
+    Ok(report)
+}
+
+/// Delete automatic memory files (one session's, or all) and drop them from the index.
+/// Returns the `memory/`-relative paths that were (or, with `dry_run`, would be) removed.
+pub fn purge_auto_memories(
+    chat_root: &Path,
+    agent_id: &str,
+    session_key: Option<&str>,
+    dry_run: bool,
+) -> Result<Vec<String>> {
+    let memory_dir = chat_root.join("memory");
+    let rel_paths: Vec<String> = match session_key {
+        Some(key) => {
+            let rel = auto_memory_rel_path(key);
+            if memory_dir.join(&rel).is_file() {
+                vec![rel]
+            } else {
+                Vec::new()
+            }
+        }
+        None => skilllite_executor::memory::list_memory_markdown_files(chat_root)?
+            .into_iter()
+            .filter(|p| p.starts_with(&format!("{}/", AUTO_MEMORY_DIR)))
+            .collect(),
+    };
+    if dry_run || rel_paths.is_empty() {
+        return Ok(rel_paths);
+    }
+
+    let idx_path = skilllite_executor::memory::index_path(chat_root, agent_id);
+    let conn = if idx_path.exists() {
+        Some(Connection::open(&idx_path).context("Failed to open memory index")?)
+    } else {
+        None
+    };
+    for rel in &rel_paths {
+        skilllite_fs::remove_file(&memory_dir.join(rel))?;
+        if let Some(conn) = conn.as_ref() {
+            skilllite_executor::memory::remove_indexed_path(conn, rel)?;
+        }
+    }
+    Ok(rel_paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fact(kind: &str, text: &str) -> AutoMemoryFact {
+        AutoMemoryFact {
+            kind: kind.to_string(),
+            fact: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn triggers_parse_and_off_disables() {
+        assert_eq!(
+            parse_triggers("exit, idle,bogus,exit"),
+            vec![AutoMemoryTrigger::Exit, AutoMemoryTrigger::Idle]
+        );
+        assert!(parse_triggers("off").is_empty());
+        let cfg = AutoMemoryConfig {
+            triggers: parse_triggers("single"),
+            ..Default::default()
+        };
+        assert!(cfg.enabled(AutoMemoryTrigger::SingleShot));
+        assert!(!cfg.enabled(AutoMemoryTrigger::Exit));
+    }
+
+    #[test]
+    fn parse_facts_tolerates_fences_and_normalizes_kind() {
+        let raw = "Sure:\n```json\n[{\"kind\":\"Preference\",\"fact\":\"User prefers  pnpm\"},\
+                   {\"kind\":\"other\",\"fact\":\"Repo uses Rust 2021\"},{\"fact\":\"  \"}]\n```";
+        assert_eq!(
+            parse_facts(raw),
+            vec![
+                fact("preference", "User prefers pnpm"),
+                fact("project", "Repo uses Rust 2021"),
+            ]
+        );
+        assert!(parse_facts("nothing to remember").is_empty());
+    }
+
+    #[test]
+    fn rel_path_is_sanitized_into_auto_namespace() {
+        assert_eq!(auto_memory_rel_path("s-1a2b"), "auto/s-1a2b.md");
+        assert_eq!(auto_memory_rel_path("../x y"), "auto/___x_y.md");
+    }
+
+    #[test]
+    fn model_writes_into_auto_namespace_are_detected() {
+        use super::super::memory::writes_auto_namespace;
+        assert!(writes_auto_namespace(&json!({ "rel_path": "auto/s1.md" })));
+        assert!(writes_auto_namespace(
+            &json!({ "rel_path": "./notes/../auto/x.md" })
+        ));
+        assert!(!writes_auto_namespace(&json!({ "rel_path": "autos/x.md" })));
+        assert!(!writes_auto_namespace(
+            &json!({ "rel_path": "preferences.md" })
+        ));
+    }
+
+    #[tokio::test]
+    async fn write_dedups_caps_and_purges() {
+        let tmp = tempfile::tempdir().unwrap();
+        let chat_root = tmp.path();
+        let facts = [
+            fact("preference", "User prefers pnpm over npm for installs"),
+            fact("preference", "The user prefers pnpm over npm for installs"),
+            fact(
+                "project",
+                "Backend lives in crates/server and targets Rust 2021",
+            ),
+            fact("gotcha", "Port 8080 is taken by the VPN client"),
+        ];
+
+        let report = write_auto_memories(chat_root, "default", "s1", &facts, 2)
+            .await
+            .unwrap();
+        assert_eq!(report.remembered.len(), 2);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(count_auto_memories(chat_root, "s1"), 2);
+
+        // Second run: facts already indexed are skipped; only the new one lands.
+        let again = write_auto_memories(chat_root, "default", "s1", &facts, 5)
+            .await
+            .unwrap();
+        assert_eq!(again.remembered, vec![facts[3].fact.clone()]);
+        let body = std::fs::read_to_string(chat_root.join("memory/auto/s1.md")).unwrap();
+        assert!(body.contains("kind=\"gotcha\""));
+
+        std::fs::write(chat_root.join("memory/notes.md"), "explicit").unwrap();
+        assert_eq!(
+            purge_auto_memories(chat_root, "default", None, true).unwrap(),
+            vec!["auto/s1.md".to_string()]
+        );
+        purge_auto_memories(chat_root, "default", None, false).unwrap();
+        assert!(!chat_root.join("memory/auto/s1.md").exists());
+        assert!(chat_root.join("memory/notes.md").exists());
+        let conn =
+            Connection::open(skilllite_executor::memory::index_path(chat_root, "default")).unwrap();
+        assert!(skilllite_executor::memory::search_bm25(&conn, "pnpm", 5)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -82,7 +82,7 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
             tool_type: "function".to_string(),
             function: FunctionDef {
                 name: "update_task_plan".to_string(),
-                description: "Revise the task plan when current tasks are unusable (e.g. chat_history returned irrelevant data for a city comparison). Call with the new task list. Use when: (1) a task's result is clearly not useful for the user's goal; (2) the plan was wrong (e.g. used chat_history for place comparison). Pass `tasks` array with id, description, tool_hint, completed.".to_string(),
+                description: "Revise the task plan when current tasks are unusable (e.g. chat_history returned irrelevant data for a city comparison). Call with the new task list. Use when: (1) a task's result is clearly not useful for the user's goal; (2) the plan was wrong (e.g. used chat_history for place comparison). Pass `tasks` array with id, description, tool_hint, completed, plus a `reason`. Replans are budgeted per session: once the budget is spent, only small adjustments are accepted.".to_string(),
                 parameters: json!({
                     "type": "object",
                     "properties": {
@@ -102,10 +102,10 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
                         },
                         "reason": {
                             "type": "string",
-                            "description": "Brief reason for the plan revision (e.g. chat_history had no relevant city data)"
+                            "description": "Required. Brief reason for the plan revision (e.g. chat_history had no relevant city data)"
                         }
                     },
-                    "required": ["tasks"]
+                    "required": ["tasks", "reason"]
                 }),
             },
         },
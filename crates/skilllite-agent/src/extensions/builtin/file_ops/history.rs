@@ -0,0 +1,82 @@
+//! Pre-image capture for write_file / search_replace / insert_lines.
+//!
+//! When the call belongs to a [`ToolSession`], each builtin edit records the file's previous
+//! content via [`skilllite_executor::file_history`] under that session. Output-dir writes are deliverables, not
+//! workspace edits, and are never captured.
+
+use std::path::Path;
+
+use crate::error::bail;
+use crate::types;
+use crate::Result;
+
+use super::super::{is_sensitive_write_path, resolve_within_workspace, ToolSession};
+use super::reject_misplaced_output_style_write_file_path;
+
+fn is_in_output_dir(resolved: &Path) -> bool {
+    types::get_output_dir().is_some_and(|od| resolved.starts_with(Path::new(&od)))
+}
+
+/// Record `resolved`'s content before a builtin tool writes it. Best effort: failures are
+/// logged and never block the edit.
+pub(super) fn capture_before_write(resolved: &Path, session: Option<&ToolSession>) {
+    if is_in_output_dir(resolved) {
+        return;
+    }
+    let captured = session.map(|s| {
+        skilllite_executor::file_history::record_pre_image(&s.chat_root, &s.session_key, resolved)
+    });
+    if let Some(Err(e)) = captured {
+        tracing::warn!(
+            "file history capture failed for {}: {}",
+            resolved.display(),
+            e
+        );
+    }
+}
+
+/// Like [`capture_before_write`], but a failed capture blocks the write: write_file overwrites
+/// whole files, so the pre-image is the only way back.
+pub(super) fn capture_before_overwrite(
+    resolved: &Path,
+    session: Option<&ToolSession>,
+) -> Result<()> {
+    if is_in_output_dir(resolved) {
+        return Ok(());
+    }
+    let captured = session.map(|s| {
+        skilllite_executor::file_history::record_pre_image(&s.chat_root, &s.session_key, resolved)
+    });
+    if let Some(Err(e)) = captured {
+        bail!(
+            "Refusing to overwrite {}: could not save its previous version to file history ({}). \
+             Retry, or edit it in place with search_replace.",
+            resolved.display(),
+            e
+        );
+    }
+    Ok(())
+}
+
+/// Restore `path` (relative to `workspace` or absolute inside it) to its pre-session version.
+/// Applies the same sensitive-path and workspace checks as write_file.
+pub fn revert_session_file(
+    chat_root: &Path,
+    session_key: &str,
+    workspace: &Path,
+    path: &str,
+) -> Result<skilllite_executor::file_history::RevertOutcome> {
+    if is_sensitive_write_path(path) {
+        bail!(
+            "Blocked: writing to sensitive file '{}' is not allowed",
+            path
+        );
+    }
+    reject_misplaced_output_style_write_file_path(path)?;
+    let resolved = resolve_within_workspace(path, workspace)?;
+    Ok(skilllite_executor::file_history::revert_file(
+        chat_root,
+        session_key,
+        &resolved,
+    )?)
+}
@@ -3,10 +3,14 @@
 //! Split into submodules:
 //! - `search_replace`: search_replace, preview_edit, insert_lines + fuzzy matching + backup + validation
 //! - `grep`: grep_files
+//! - `history`: per-session pre-image capture and revert for the edit tools
 
 mod grep;
+mod history;
 mod search_replace;
 
+pub use history::revert_session_file;
+
 use crate::error::bail;
 use crate::Result;
 use anyhow::Context;
@@ -18,8 +22,12 @@ use crate::types::{EventSink, FunctionDef, ToolDefinition};
 use super::{
     filter_sensitive_content_in_text, get_path_arg, is_key_write_path, is_sensitive_read_path,
     is_sensitive_write_path, resolve_within_workspace, resolve_within_workspace_or_output,
+    ToolSession,
 };
 use crate::high_risk;
+use skilllite_core::config::env_keys::agent::{
+    SKILLLITE_LIST_DIR_MAX_ENTRIES, SKILLLITE_WRITE_FILE_MIN_SHRINK_RATIO,
+};
 
 /// Block a common LLM mistake: `write_file` with `users/<x>/output/...` under the workspace.
 /// Those files are not under the real output dir (`SKILLLITE_OUTPUT_DIR` / `<workspace>/output`),
@@ -78,7 +86,8 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
                 name: "write_file".to_string(),
                 description: "Write content to a file under the workspace (source, config, docs you are editing). Creates parent directories if needed. Blocks sensitive paths (.env, .key, .git/config). \
 For **deliverables** the user should open from the app (reports, tutorials, exports, generated markdown/HTML, screenshots paths in configs): you MUST use **write_output**, not write_file — otherwise files are easy to \"lose\" (not listed in the Output panel). \
-With write_file, use normal project-relative paths (e.g. src/, docs/); do not invent nested paths like users/.../output/.... Use append: true to append instead of overwriting.".to_string(),
+With write_file, use normal project-relative paths (e.g. src/, docs/); do not invent nested paths like users/.../output/.... Use append: true to append instead of overwriting. \
+Overwriting an existing file with much shorter content is refused unless allow_shrink: true; prefer search_replace for partial edits. Pass expected_hash (the sha256 shown by read_file) or expected_contains to make sure you overwrite the version you last read.".to_string(),
                 parameters: json!({
                     "type": "object",
                     "properties": {
@@ -93,6 +102,18 @@ With write_file, use normal project-relative paths (e.g. src/, docs/); do not in
                         "append": {
                             "type": "boolean",
                             "description": "If true, append content to end of file. Default: false (overwrite)."
+                        },
+                        "allow_shrink": {
+                            "type": "boolean",
+                            "description": "Confirm that replacing an existing file with much shorter content is intended. Default: false."
+                        },
+                        "expected_hash": {
+                            "type": "string",
+                            "description": "sha256 (or its first 8+ hex chars) of the file as last read; the write fails if the file changed since."
+                        },
+                        "expected_contains": {
+                            "type": "string",
+                            "description": "Text the current file must contain; the write fails otherwise."
                         }
                     },
                     "required": ["path", "content"]
@@ -186,7 +207,7 @@ With write_file, use normal project-relative paths (e.g. src/, docs/); do not in
             tool_type: "function".to_string(),
             function: FunctionDef {
                 name: "list_directory".to_string(),
-                description: "List files and directories as an ASCII tree (├──/└──). Supports recursive listing; skips heavy dirs (node_modules, .git, target, etc.).".to_string(),
+                description: "List files and directories as an ASCII tree (├──/└──), or as a flat list of relative paths when filtering or sorting. Skips heavy dirs (node_modules, .git, target, etc.). Output is capped and ends with a JSON line {\"total\": N, \"truncated\": bool}.".to_string(),
                 parameters: json!({
                     "type": "object",
                     "properties": {
@@ -196,7 +217,32 @@ With write_file, use normal project-relative paths (e.g. src/, docs/); do not in
                         },
                         "recursive": {
                             "type": "boolean",
-                            "description": "If true, list recursively. Default: false."
+                            "description": "If true, list recursively (unbounded unless max_depth is set). Default: false."
+                        },
+                        "max_depth": {
+                            "type": "integer",
+                            "description": "Levels to descend (1 = direct children only). Implies recursive."
+                        },
+                        "pattern": {
+                            "type": "string",
+                            "description": "Glob on paths relative to 'path' (e.g. '*.csv', 'data/**/*.json'). Without '/', matches file names at any depth."
+                        },
+                        "sort_by": {
+                            "type": "string",
+                            "enum": ["name", "mtime", "size"],
+                            "description": "name (tree order, default), mtime (newest first) or size (largest first)."
+                        },
+                        "dirs_only": {
+                            "type": "boolean",
+                            "description": "Only list directories."
+                        },
+                        "files_only": {
+                            "type": "boolean",
+                            "description": "Only list files."
+                        },
+                        "include_hidden": {
+                            "type": "boolean",
+                            "description": "Also descend into dot-directories such as .git or .venv. Default: false."
                         }
                     },
                     "required": []
@@ -293,6 +339,13 @@ pub(super) fn execute_read_file(args: &Value, workspace: &Path) -> Result<String
                 );
             }
 
+            if let Ok(bytes) = skilllite_fs::read_bytes(&resolved) {
+                output.push_str(&format!(
+                    "\n[sha256: {}]",
+                    &content_sha256(&bytes)[..READ_FILE_HASH_CHARS]
+                ));
+            }
+
             Ok(output)
         }
         Err(e) => {
@@ -319,10 +372,113 @@ pub(super) fn execute_read_file(args: &Value, workspace: &Path) -> Result<String
     }
 }
 
+/// Hex chars of the sha256 shown by read_file; enough for write_file's `expected_hash`.
+const READ_FILE_HASH_CHARS: usize = 16;
+/// Shortest `expected_hash` prefix accepted.
+const MIN_EXPECTED_HASH_CHARS: usize = 8;
+/// Default for `SKILLLITE_WRITE_FILE_MIN_SHRINK_RATIO`.
+const DEFAULT_MIN_SHRINK_RATIO: f64 = 0.3;
+/// Files smaller than this may be overwritten with any content.
+const SHRINK_GUARD_MIN_BYTES: u64 = 1024;
+
+fn content_sha256(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn min_shrink_ratio() -> f64 {
+    std::env::var(SKILLLITE_WRITE_FILE_MIN_SHRINK_RATIO)
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|r| (0.0..=1.0).contains(r))
+        .unwrap_or(DEFAULT_MIN_SHRINK_RATIO)
+}
+
+/// Stale-write protection: the file must still match what the model last read.
+fn check_write_preconditions(args: &Value, resolved: &Path, path_str: &str) -> Result<()> {
+    let expected_hash = args
+        .get("expected_hash")
+        .and_then(|v| v.as_str())
+        .map(|h| h.trim().to_lowercase())
+        .filter(|h| !h.is_empty());
+    let expected_contains = args.get("expected_contains").and_then(|v| v.as_str());
+    if expected_hash.is_none() && expected_contains.is_none() {
+        return Ok(());
+    }
+    if !resolved.is_file() {
+        bail!(
+            "Precondition failed: {} does not exist, so expected_hash / expected_contains cannot match. \
+             Drop them to create the file.",
+            path_str
+        );
+    }
+    let current = skilllite_fs::read_bytes(resolved)
+        .with_context(|| format!("Failed to read file: {}", path_str))?;
+    if let Some(expected) = expected_hash {
+        if expected.len() < MIN_EXPECTED_HASH_CHARS {
+            bail!(
+                "expected_hash must be at least {} hex chars of the sha256 shown by read_file",
+                MIN_EXPECTED_HASH_CHARS
+            );
+        }
+        let actual = content_sha256(&current);
+        if !actual.starts_with(&expected) {
+            bail!(
+                "Precondition failed: {} changed since it was read (sha256 is now {}, expected {}). \
+                 Re-read the file with read_file and apply your edit to the current version.",
+                path_str,
+                &actual[..READ_FILE_HASH_CHARS],
+                expected
+            );
+        }
+    }
+    if let Some(needle) = expected_contains {
+        if !String::from_utf8_lossy(&current).contains(needle) {
+            bail!(
+                "Precondition failed: {} no longer contains the expected_contains text. \
+                 Re-read the file with read_file and apply your edit to the current version.",
+                path_str
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Refuse to replace a large file with a small fraction of its size unless `allow_shrink`.
+fn check_shrink_guard(
+    resolved: &Path,
+    path_str: &str,
+    new_len: usize,
+    allow_shrink: bool,
+) -> Result<()> {
+    if allow_shrink {
+        return Ok(());
+    }
+    let old_len = match std::fs::metadata(resolved) {
+        Ok(m) if m.is_file() => m.len(),
+        _ => return Ok(()),
+    };
+    let ratio = min_shrink_ratio();
+    if old_len < SHRINK_GUARD_MIN_BYTES || (new_len as f64) >= old_len as f64 * ratio {
+        return Ok(());
+    }
+    bail!(
+        "Refusing to overwrite {}: new content is {} bytes, {:.0}% of the existing {} bytes (limit {:.0}%). \
+         This usually means part of the file was lost. To change part of it use search_replace or insert_lines; \
+         to add to it use append: true; if replacing it with shorter content is really intended, retry with allow_shrink: true.",
+        path_str,
+        new_len,
+        new_len as f64 / old_len as f64 * 100.0,
+        old_len,
+        ratio * 100.0
+    );
+}
+
 pub(super) fn execute_write_file(
     args: &Value,
     workspace: &Path,
     event_sink: Option<&mut dyn EventSink>,
+    session: Option<&ToolSession>,
 ) -> Result<String> {
     let path_str = get_path_arg(args, false)
         .ok_or_else(|| crate::Error::validation("'path' or 'file_path' is required"))?;
@@ -334,6 +490,10 @@ pub(super) fn execute_write_file(
         .get("append")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
+    let allow_shrink = args
+        .get("allow_shrink")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
 
     if is_sensitive_write_path(&path_str) {
         bail!(
@@ -344,6 +504,12 @@ pub(super) fn execute_write_file(
 
     reject_misplaced_output_style_write_file_path(&path_str)?;
 
+    let resolved = resolve_within_workspace(&path_str, workspace)?;
+    check_write_preconditions(args, &resolved, &path_str)?;
+    if !append {
+        check_shrink_guard(&resolved, &path_str, content.len(), allow_shrink)?;
+    }
+
     // A11: 关键路径确认
     if high_risk::confirm_write_key_path() && is_key_write_path(&path_str) {
         if let Some(sink) = event_sink {
@@ -362,7 +528,14 @@ pub(super) fn execute_write_file(
         }
     }
 
-    let resolved = resolve_within_workspace(&path_str, workspace)?;
+    let overwriting = !append && resolved.is_file();
+    let backup = if overwriting {
+        history::capture_before_overwrite(&resolved, session)?;
+        search_replace::backup_file_before_edit(&resolved)
+    } else {
+        history::capture_before_write(&resolved, session);
+        None
+    };
 
     if append {
         skilllite_fs::append_file(&resolved, content)
@@ -372,20 +545,25 @@ pub(super) fn execute_write_file(
             .with_context(|| format!("Failed to write file: {}", path_str))?;
     }
 
-    Ok(format!(
+    let mut msg = format!(
         "Successfully {} {} bytes to {}",
         if append { "appended" } else { "wrote" },
         content.len(),
         path_str
-    ))
+    );
+    if let Some(b) = backup {
+        msg.push_str(&format!(" (previous version backed up to {})", b));
+    }
+    Ok(msg)
 }
 
 pub(super) fn execute_search_replace(
     args: &Value,
     workspace: &Path,
     event_sink: Option<&mut dyn EventSink>,
+    session: Option<&ToolSession>,
 ) -> Result<String> {
-    search_replace::execute_search_replace(args, workspace, event_sink)
+    search_replace::execute_search_replace(args, workspace, event_sink, session)
 }
 
 pub(super) fn execute_preview_edit(args: &Value, workspace: &Path) -> Result<String> {
@@ -396,23 +574,76 @@ pub(super) fn execute_insert_lines(
     args: &Value,
     workspace: &Path,
     event_sink: Option<&mut dyn EventSink>,
+    session: Option<&ToolSession>,
 ) -> Result<String> {
-    search_replace::execute_insert_lines(args, workspace, event_sink)
+    search_replace::execute_insert_lines(args, workspace, event_sink, session)
 }
 
 pub(super) fn execute_grep_files(args: &Value, workspace: &Path) -> Result<String> {
     grep::execute_grep_files(args, workspace)
 }
 
+/// Default cap on entries returned by list_directory (`SKILLLITE_LIST_DIR_MAX_ENTRIES`).
+const DEFAULT_LIST_DIR_MAX_ENTRIES: usize = 500;
+
+fn list_dir_max_entries() -> usize {
+    std::env::var(SKILLLITE_LIST_DIR_MAX_ENTRIES)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_LIST_DIR_MAX_ENTRIES)
+}
+
 pub(super) fn execute_list_directory(args: &Value, workspace: &Path) -> Result<String> {
     let path_str = get_path_arg(args, true).unwrap_or_else(|| ".".to_string());
-    let recursive = args
-        .get("recursive")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
+    let flag = |key: &str| args.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+    let max_depth = match args.get("max_depth").and_then(|v| v.as_u64()) {
+        Some(d) => Some((d as usize).max(1)),
+        None if flag("recursive") => None,
+        None => Some(1),
+    };
+    let sort_by = match args.get("sort_by").and_then(|v| v.as_str()) {
+        Some(s) => skilllite_fs::ListSortBy::parse(s).ok_or_else(|| {
+            crate::Error::validation(format!(
+                "Invalid sort_by '{}': expected name, mtime or size",
+                s
+            ))
+        })?,
+        None => skilllite_fs::ListSortBy::Name,
+    };
+    let opts = skilllite_fs::ListOptions {
+        max_depth,
+        pattern: args
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string),
+        sort_by,
+        dirs_only: flag("dirs_only"),
+        files_only: flag("files_only"),
+        include_hidden: flag("include_hidden"),
+        max_entries: list_dir_max_entries(),
+    };
+    if opts.dirs_only && opts.files_only {
+        bail!("'dirs_only' and 'files_only' cannot both be true");
+    }
 
     let resolved = resolve_within_workspace_or_output(&path_str, workspace)?;
-    Ok(skilllite_fs::directory_tree(&resolved, recursive)?)
+    let listing = skilllite_fs::walk_directory(&resolved, &opts)?;
+    let root_label = resolved
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| ".".to_string());
+    let mut output = listing.render(&root_label, &opts);
+    if listing.truncated {
+        output.push_str(&format!(
+            "\n[Showing {} of {} entries; narrow with pattern or max_depth]\n",
+            listing.entries.len(),
+            listing.total
+        ));
+    }
+    output.push_str(&json!({ "total": listing.total, "truncated": listing.truncated }).to_string());
+    Ok(output)
 }
 
 pub(super) fn execute_file_exists(args: &Value, workspace: &Path) -> Result<String> {
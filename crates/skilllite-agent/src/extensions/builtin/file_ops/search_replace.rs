@@ -6,6 +6,7 @@ use anyhow::Context;
 use serde_json::{json, Value};
 use std::path::Path;
 
+use super::super::ToolSession;
 use super::super::{
     get_path_arg, is_key_write_path, is_sensitive_write_path, resolve_within_workspace_or_output,
 };
@@ -16,18 +17,20 @@ pub(super) fn execute_search_replace(
     args: &Value,
     workspace: &Path,
     event_sink: Option<&mut dyn EventSink>,
+    session: Option<&ToolSession>,
 ) -> Result<String> {
-    execute_replace_like(args, workspace, true, event_sink)
+    execute_replace_like(args, workspace, true, event_sink, session)
 }
 
 pub(super) fn execute_preview_edit(args: &Value, workspace: &Path) -> Result<String> {
-    execute_replace_like(args, workspace, false, None)
+    execute_replace_like(args, workspace, false, None, None)
 }
 
 pub(super) fn execute_insert_lines(
     args: &Value,
     workspace: &Path,
     event_sink: Option<&mut dyn EventSink>,
+    session: Option<&ToolSession>,
 ) -> Result<String> {
     let path_str =
         get_path_arg(args, false).ok_or_else(|| crate::Error::validation("'path' is required"))?;
@@ -86,6 +89,7 @@ pub(super) fn execute_insert_lines(
     let inserted_lines = insert_content.lines().count().max(1);
 
     let backup = backup_file_before_edit(&resolved);
+    super::history::capture_before_write(&resolved, session);
     skilllite_fs::write_file(&resolved, &new_content)
         .with_context(|| format!("Failed to write file: {}", path_str))?;
 
@@ -127,6 +131,7 @@ fn execute_replace_like(
     workspace: &Path,
     apply_changes: bool,
     event_sink: Option<&mut dyn EventSink>,
+    session: Option<&ToolSession>,
 ) -> Result<String> {
     let dry_run = args
         .get("dry_run")
@@ -263,6 +268,7 @@ fn execute_replace_like(
 
     if should_write {
         backup = backup_file_before_edit(&resolved);
+        super::history::capture_before_write(&resolved, session);
         skilllite_fs::write_file(&resolved, &result.new_content)
             .with_context(|| format!("Failed to write file: {}", path_str))?;
         validation_warning = validate_syntax(&resolved, &result.new_content);
@@ -311,7 +317,7 @@ fn execute_replace_like(
     }
 }
 
-fn backup_file_before_edit(resolved: &Path) -> Option<String> {
+pub(super) fn backup_file_before_edit(resolved: &Path) -> Option<String> {
     let backup_dir = skilllite_executor::skilllite_data_root().join("edit-backups");
     let path = skilllite_fs::backup_file(resolved, &backup_dir).ok()?;
     skilllite_fs::prune_oldest_files(&backup_dir, 50);
@@ -5,6 +5,7 @@
 use crate::error::bail;
 use crate::Result;
 use serde_json::Value;
+use skilllite_core::path_validation::{normalize_lexical, resolve_under_roots, PathPolicy};
 use std::path::{Path, PathBuf};
 
 use crate::types;
@@ -177,58 +178,24 @@ pub(super) fn is_key_write_path(path: &str) -> bool {
     false
 }
 
+/// Resolve a file-tool path inside the workspace. Symlinks are followed, so a link in the
+/// workspace pointing elsewhere (e.g. at `/etc`) is rejected like `../` traversal.
 pub(super) fn resolve_within_workspace(path: &str, workspace: &Path) -> Result<PathBuf> {
-    let input = Path::new(path);
-    let resolved = if input.is_absolute() {
-        input.to_path_buf()
-    } else {
-        workspace.join(input)
-    };
-
-    let normalized = normalize_path(&resolved);
-
-    if !normalized.starts_with(workspace) {
-        let is_output_path =
-            types::get_output_dir().is_some_and(|od| normalized.starts_with(Path::new(&od)));
-        if is_output_path {
-            bail!(
-                "Path escapes workspace: {} (workspace: {}). \
-                 Hint: this path is in the output directory — use **write_output** \
-                 (with file_path relative to the output dir) instead of write_file.",
-                path,
-                workspace.display()
-            );
-        } else {
-            bail!(
-                "Path escapes workspace: {} (workspace: {})",
-                path,
-                workspace.display()
-            );
-        }
-    }
-
-    Ok(normalized)
-}
-
-pub(super) fn resolve_within_workspace_or_output(path: &str, workspace: &Path) -> Result<PathBuf> {
-    if let Ok(resolved) = resolve_within_workspace(path, workspace) {
+    if let Ok(resolved) = resolve_under_roots(path, &[workspace], PathPolicy::writable("Path")) {
         return Ok(resolved);
     }
-
-    if let Some(output_dir) = types::get_output_dir() {
-        let output_root = PathBuf::from(&output_dir);
-        let input = Path::new(path);
-        let resolved = if input.is_absolute() {
-            input.to_path_buf()
-        } else {
-            output_root.join(input)
-        };
-        let normalized = normalize_path(&resolved);
-        if normalized.starts_with(&output_root) {
-            return Ok(normalized);
-        }
+    let requested = normalize_path(&workspace.join(path));
+    let is_output_path =
+        types::get_output_dir().is_some_and(|od| requested.starts_with(Path::new(&od)));
+    if is_output_path {
+        bail!(
+            "Path escapes workspace: {} (workspace: {}). \
+             Hint: this path is in the output directory — use **write_output** \
+             (with file_path relative to the output dir) instead of write_file.",
+            path,
+            workspace.display()
+        );
     }
-
     bail!(
         "Path escapes workspace: {} (workspace: {})",
         path,
@@ -236,6 +203,22 @@ pub(super) fn resolve_within_workspace_or_output(path: &str, workspace: &Path) -
     )
 }
 
+/// Like [`resolve_within_workspace`], but also accepts paths in the output directory
+/// (relative paths are tried against the workspace first).
+pub(super) fn resolve_within_workspace_or_output(path: &str, workspace: &Path) -> Result<PathBuf> {
+    let output_root = types::get_output_dir().map(PathBuf::from);
+    let mut roots = vec![workspace];
+    roots.extend(output_root.as_deref());
+    match resolve_under_roots(path, &roots, PathPolicy::writable("Path")) {
+        Ok(resolved) => Ok(resolved),
+        Err(_) => bail!(
+            "Path escapes workspace: {} (workspace: {})",
+            path,
+            workspace.display()
+        ),
+    }
+}
+
 pub(super) fn get_path_arg(args: &Value, for_directory: bool) -> Option<String> {
     let path = args.get("path").and_then(|v| v.as_str());
     let alt = if for_directory {
@@ -246,18 +229,9 @@ pub(super) fn get_path_arg(args: &Value, for_directory: bool) -> Option<String>
     path.or(alt).map(String::from)
 }
 
+/// Fold `.` / `..` lexically (no symlink resolution; see [`resolve_within_workspace`]).
 pub(crate) fn normalize_path(path: &Path) -> PathBuf {
-    let mut components = Vec::new();
-    for component in path.components() {
-        match component {
-            std::path::Component::ParentDir => {
-                components.pop();
-            }
-            std::path::Component::CurDir => {}
-            other => components.push(other),
-        }
-    }
-    components.iter().collect()
+    normalize_lexical(path)
 }
 
 // ─── Truncated JSON recovery ─────────────────────────────────────────────────
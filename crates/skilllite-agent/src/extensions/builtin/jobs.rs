@@ -0,0 +1,621 @@
+//! Background jobs: `run_command` with `background: true`, plus list_jobs / job_output / stop_job.
+//!
+//! A job is a shell command spawned in its own process group whose stdout/stderr lines go to
+//! a per-job ring buffer. Jobs belong to the [`ToolSession`] that started them and are
+//! killed, whole process group, when that session ends; their final output is then recorded
+//! in the session transcript.
+
+use crate::error::bail;
+use crate::Result;
+use anyhow::Context;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use skilllite_core::config::env_keys::agent::{
+    SKILLLITE_JOB_OUTPUT_MAX_LINES, SKILLLITE_MAX_BACKGROUND_JOBS,
+};
+
+use crate::types::{FunctionDef, ToolDefinition};
+
+use super::helpers::filter_sensitive_content_in_text;
+use super::tool_session::ToolSession;
+
+// ─── Tool definitions ───────────────────────────────────────────────────────
+
+pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
+    let job_param = json!({
+        "type": "string",
+        "description": "Job name returned by run_command with background=true"
+    });
+    vec![
+        ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDef {
+                name: "list_jobs".to_string(),
+                description: "List background jobs of this session (started by run_command with background=true): name, status, pid, runtime, unread output lines and command.".to_string(),
+                parameters: json!({ "type": "object", "properties": {}, "required": [] }),
+            },
+        },
+        ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDef {
+                name: "job_output".to_string(),
+                description: "Read new output of a background job since the last read (last `lines` lines of it), with its current status.".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "job": job_param,
+                        "lines": {
+                            "type": "integer",
+                            "description": "Max lines to return (default: 50)",
+                            "minimum": 1,
+                            "maximum": MAX_TAIL_LINES
+                        }
+                    },
+                    "required": ["job"]
+                }),
+            },
+        },
+        ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDef {
+                name: "stop_job".to_string(),
+                description: "Stop a background job (its whole process group) and return its last output. Jobs are also stopped when the chat session ends.".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": { "job": job_param },
+                    "required": ["job"]
+                }),
+            },
+        },
+    ]
+}
+
+// ─── Limits ─────────────────────────────────────────────────────────────────
+
+const DEFAULT_MAX_BACKGROUND_JOBS: usize = 4;
+const DEFAULT_JOB_OUTPUT_MAX_LINES: usize = 2000;
+/// Longer lines are cut; dev servers sometimes print minified bundles.
+const MAX_LINE_CHARS: usize = 1000;
+const DEFAULT_TAIL_LINES: usize = 50;
+const MAX_TAIL_LINES: usize = 500;
+const MAX_JOB_NAME_LEN: usize = 64;
+/// How long a start waits for the first output before returning the job id.
+const STARTUP_OUTPUT_WAIT: Duration = Duration::from_secs(2);
+/// SIGTERM → SIGKILL grace period when stopping a job.
+const STOP_GRACE: Duration = Duration::from_secs(2);
+
+fn max_background_jobs() -> usize {
+    std::env::var(SKILLLITE_MAX_BACKGROUND_JOBS)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_MAX_BACKGROUND_JOBS)
+}
+
+fn job_output_max_lines() -> usize {
+    std::env::var(SKILLLITE_JOB_OUTPUT_MAX_LINES)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_JOB_OUTPUT_MAX_LINES)
+}
+
+// ─── Job state ──────────────────────────────────────────────────────────────
+
+/// Running and finished jobs of every session; finished ones stay until the session ends so
+/// their final output can still be read.
+static JOBS: Mutex<Vec<Job>> = Mutex::new(Vec::new());
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JobStatus {
+    Running,
+    Exited(i32),
+    Stopped,
+}
+
+impl JobStatus {
+    fn describe(self) -> String {
+        match self {
+            JobStatus::Running => "running".to_string(),
+            JobStatus::Exited(code) => format!("exited ({})", code),
+            JobStatus::Stopped => "stopped".to_string(),
+        }
+    }
+}
+
+/// Last `capacity` output lines plus a read cursor for `job_output`.
+struct OutputBuffer {
+    lines: VecDeque<String>,
+    capacity: usize,
+    /// Lines ever pushed.
+    total: u64,
+    /// `total` at the last read.
+    read_upto: u64,
+}
+
+impl OutputBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            capacity,
+            total: 0,
+            read_upto: 0,
+        }
+    }
+
+    fn push(&mut self, line: String) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+        self.total += 1;
+    }
+
+    fn unread(&self) -> u64 {
+        self.total - self.read_upto
+    }
+
+    /// Last `max` unread lines and how many earlier unread lines were skipped (not returned
+    /// or already dropped from the buffer). Marks everything read.
+    fn take_unread(&mut self, max: usize) -> (Vec<String>, u64) {
+        let unread = self.unread();
+        let available = (unread as usize).min(self.lines.len());
+        let shown = available.min(max);
+        let lines = self
+            .lines
+            .iter()
+            .skip(self.lines.len() - shown)
+            .cloned()
+            .collect();
+        self.read_upto = self.total;
+        (lines, unread - shown as u64)
+    }
+
+    fn tail(&self, max: usize) -> Vec<String> {
+        let start = self.lines.len().saturating_sub(max);
+        self.lines.iter().skip(start).cloned().collect()
+    }
+}
+
+struct Job {
+    name: String,
+    /// What the user confirmed (`[cwd: web] NODE_ENV=dev npm run dev`).
+    command: String,
+    /// Session that started the job; `None` outside an agent session.
+    owner: Option<String>,
+    chat_root: Option<PathBuf>,
+    started: Instant,
+    child: Child,
+    status: JobStatus,
+    output: Arc<Mutex<OutputBuffer>>,
+}
+
+impl Job {
+    fn refresh(&mut self) {
+        if self.status == JobStatus::Running {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                self.status = JobStatus::Exited(status.code().unwrap_or(-1));
+            }
+        }
+    }
+
+    /// Kill the job's process group (SIGTERM, then SIGKILL after [`STOP_GRACE`]).
+    fn stop(&mut self) {
+        self.refresh();
+        if self.status != JobStatus::Running {
+            return;
+        }
+        kill_process_group(&mut self.child);
+        self.status = JobStatus::Stopped;
+    }
+}
+
+fn lock_jobs() -> Result<std::sync::MutexGuard<'static, Vec<Job>>> {
+    JOBS.lock()
+        .map_err(|e| crate::Error::validation(format!("Job table lock poisoned: {}", e)))
+}
+
+fn owner_of(session: Option<&ToolSession>) -> Option<String> {
+    session.map(|s| s.session_key.clone())
+}
+
+// ─── Start ──────────────────────────────────────────────────────────────────
+
+/// Validated `job_name` argument, or a generated `job-N`.
+fn job_name_arg(args: &Value) -> Result<String> {
+    match args.get("job_name").and_then(|v| v.as_str()).map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            let valid = name.len() <= MAX_JOB_NAME_LEN
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid {
+                bail!(
+                    "Invalid job_name '{}': use letters, digits, '-' and '_' (max {} chars)",
+                    name,
+                    MAX_JOB_NAME_LEN
+                );
+            }
+            Ok(name.to_string())
+        }
+        _ => Ok(format!(
+            "job-{}",
+            NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed)
+        )),
+    }
+}
+
+/// Spawn `cmd` as a background job (confirmation already done by the caller) and return the
+/// tool result: job name, pid and the output of the first seconds.
+pub(super) async fn start_background_job(
+    args: &Value,
+    cmd: &str,
+    display_cmd: &str,
+    run_dir: &Path,
+    env: &BTreeMap<String, String>,
+    session: Option<&ToolSession>,
+) -> Result<String> {
+    let name = job_name_arg(args)?;
+    let owner = owner_of(session);
+    let chat_root = session.map(|s| s.chat_root.clone());
+    {
+        let mut jobs = lock_jobs()?;
+        let mut running = 0;
+        for job in jobs.iter_mut().filter(|j| j.owner == owner) {
+            job.refresh();
+            if job.status == JobStatus::Running {
+                running += 1;
+                if job.name == name {
+                    bail!(
+                        "A background job named '{}' is already running; stop it with stop_job or pick another job_name",
+                        name
+                    );
+                }
+            }
+        }
+        let max = max_background_jobs();
+        if running >= max {
+            bail!(
+                "Too many background jobs running ({}, max {} via {}); stop one with stop_job first",
+                running,
+                max,
+                SKILLLITE_MAX_BACKGROUND_JOBS
+            );
+        }
+        // A finished job with the same name is replaced.
+        jobs.retain(|j| !(j.owner == owner && j.name == name));
+    }
+
+    install_exit_cleanup();
+    let mut child = spawn_job_process(cmd, run_dir, env)?;
+    let pid = child.id();
+    let output = Arc::new(Mutex::new(OutputBuffer::new(job_output_max_lines())));
+    if let Some(stdout) = child.stdout.take() {
+        spawn_output_reader(stdout, None, Arc::clone(&output));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        spawn_output_reader(stderr, Some("stderr"), Arc::clone(&output));
+    }
+    lock_jobs()?.push(Job {
+        name: name.clone(),
+        command: display_cmd.to_string(),
+        owner: owner.clone(),
+        chat_root,
+        started: Instant::now(),
+        child,
+        status: JobStatus::Running,
+        output,
+    });
+
+    let deadline = Instant::now() + STARTUP_OUTPUT_WAIT;
+    while Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if job_status(&name, owner.as_deref())? != JobStatus::Running {
+            break;
+        }
+    }
+    let mut result = format!(
+        "Started background job '{}' (pid {}): {}\nUse job_output to read new output, list_jobs to check status and stop_job to stop it.",
+        name, pid, display_cmd
+    );
+    result.push('\n');
+    result.push_str(&read_job_output(
+        &name,
+        owner.as_deref(),
+        DEFAULT_TAIL_LINES,
+    )?);
+    Ok(result)
+}
+
+#[cfg(not(windows))]
+fn spawn_job_process(cmd: &str, run_dir: &Path, env: &BTreeMap<String, String>) -> Result<Child> {
+    use std::os::unix::process::CommandExt;
+    // Own process group so stop_job / session end can kill the shell and everything it spawned.
+    Ok(Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .current_dir(run_dir)
+        .envs(env)
+        .process_group(0)
+        .spawn()
+        .with_context(|| format!("Failed to spawn background command: {}", cmd))?)
+}
+
+#[cfg(windows)]
+fn spawn_job_process(cmd: &str, run_dir: &Path, env: &BTreeMap<String, String>) -> Result<Child> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+    let comspec = std::env::var_os("COMSPEC").unwrap_or_else(|| "cmd.exe".into());
+    Ok(Command::new(comspec)
+        .arg("/C")
+        .arg(cmd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .current_dir(run_dir)
+        .envs(env)
+        .creation_flags(CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP)
+        .spawn()
+        .with_context(|| format!("Failed to spawn background command: {}", cmd))?)
+}
+
+/// Copy lines of `stream` into `output` until EOF; `label` prefixes stderr lines.
+fn spawn_output_reader(
+    stream: impl Read + Send + 'static,
+    label: Option<&'static str>,
+    output: Arc<Mutex<OutputBuffer>>,
+) {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut raw = Vec::new();
+        loop {
+            raw.clear();
+            match reader.read_until(b'\n', &mut raw) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let text = String::from_utf8_lossy(&raw);
+            let text = text.trim_end_matches(['\n', '\r']);
+            let (mut line, _) = filter_sensitive_content_in_text(text);
+            if line.len() > MAX_LINE_CHARS {
+                line = format!("{}…", crate::types::safe_truncate(&line, MAX_LINE_CHARS));
+            }
+            if let Some(label) = label {
+                line = format!("[{}] {}", label, line);
+            }
+            match output.lock() {
+                Ok(mut buf) => buf.push(line),
+                Err(_) => break,
+            }
+        }
+    });
+}
+
+#[cfg(not(windows))]
+fn kill_process_group(child: &mut Child) {
+    let pgid = child.id() as libc::pid_t;
+    // SAFETY: plain syscall; `pgid` is our own, still unreaped child, so the group id cannot
+    // have been reused.
+    unsafe { libc::killpg(pgid, libc::SIGTERM) };
+    let deadline = Instant::now() + STOP_GRACE;
+    while Instant::now() < deadline {
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    // Also after the shell exited: processes it spawned may have ignored SIGTERM.
+    unsafe { libc::killpg(pgid, libc::SIGKILL) };
+    let _ = child.wait();
+}
+
+#[cfg(windows)]
+fn kill_process_group(child: &mut Child) {
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &child.id().to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Jobs live in their own process groups, so neither the terminal's Ctrl+C nor the SIGTERM
+/// of a desktop stop reaches them: stop them before this process exits on either signal.
+fn install_exit_cleanup() {
+    static INSTALLED: std::sync::Once = std::sync::Once::new();
+    INSTALLED.call_once(|| {
+        let installed = ctrlc::set_handler(|| {
+            stop_background_jobs(None);
+            std::process::exit(130);
+        });
+        if let Err(e) = installed {
+            tracing::warn!(
+                "Background jobs will not be stopped on Ctrl+C / SIGTERM: {}",
+                e
+            );
+        }
+    });
+}
+
+// ─── Tools ──────────────────────────────────────────────────────────────────
+
+fn job_status(name: &str, owner: Option<&str>) -> Result<JobStatus> {
+    let mut jobs = lock_jobs()?;
+    let job = find_job(&mut jobs, name, owner)?;
+    job.refresh();
+    Ok(job.status)
+}
+
+fn find_job<'a>(jobs: &'a mut [Job], name: &str, owner: Option<&str>) -> Result<&'a mut Job> {
+    match jobs
+        .iter_mut()
+        .find(|j| j.name == name && j.owner.as_deref() == owner)
+    {
+        Some(job) => Ok(job),
+        None => bail!("No background job named '{}' (see list_jobs)", name),
+    }
+}
+
+fn job_arg(args: &Value) -> Result<&str> {
+    Ok(args
+        .get("job")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .context("'job' is required")?)
+}
+
+/// `[status]` header plus the unread output tail of one job.
+fn read_job_output(name: &str, owner: Option<&str>, max_lines: usize) -> Result<String> {
+    let mut jobs = lock_jobs()?;
+    let job = find_job(&mut jobs, name, owner)?;
+    job.refresh();
+    let (lines, skipped) = match job.output.lock() {
+        Ok(mut buf) => buf.take_unread(max_lines),
+        Err(_) => (Vec::new(), 0),
+    };
+    let mut out = format!("[{}: {}]", job.name, job.status.describe());
+    if lines.is_empty() {
+        out.push_str("\n(no new output)");
+        return Ok(out);
+    }
+    if skipped > 0 {
+        out.push_str(&format!("\n... {} earlier lines skipped", skipped));
+    }
+    out.push('\n');
+    out.push_str(&lines.join("\n"));
+    Ok(out)
+}
+
+pub(super) fn execute_list_jobs(_args: &Value, session: Option<&ToolSession>) -> Result<String> {
+    let owner = owner_of(session);
+    let mut jobs = lock_jobs()?;
+    let mut rows = Vec::new();
+    for job in jobs.iter_mut().filter(|j| j.owner == owner) {
+        job.refresh();
+        let unread = job.output.lock().map(|b| b.unread()).unwrap_or(0);
+        rows.push(format!(
+            "{}  {}  pid {}  {}s  {} unread lines  {}",
+            job.name,
+            job.status.describe(),
+            job.child.id(),
+            job.started.elapsed().as_secs(),
+            unread,
+            job.command
+        ));
+    }
+    if rows.is_empty() {
+        return Ok("No background jobs in this session.".to_string());
+    }
+    Ok(rows.join("\n"))
+}
+
+pub(super) fn execute_job_output(args: &Value, session: Option<&ToolSession>) -> Result<String> {
+    let name = job_arg(args)?;
+    let lines = args
+        .get("lines")
+        .and_then(|v| v.as_u64())
+        .map(|n| (n as usize).clamp(1, MAX_TAIL_LINES))
+        .unwrap_or(DEFAULT_TAIL_LINES);
+    read_job_output(name, owner_of(session).as_deref(), lines)
+}
+
+pub(super) fn execute_stop_job(args: &Value, session: Option<&ToolSession>) -> Result<String> {
+    let name = job_arg(args)?;
+    let owner = owner_of(session);
+    {
+        let mut jobs = lock_jobs()?;
+        find_job(&mut jobs, name, owner.as_deref())?.stop();
+    }
+    // Let the reader threads drain what the process printed while shutting down.
+    std::thread::sleep(Duration::from_millis(100));
+    read_job_output(name, owner.as_deref(), DEFAULT_TAIL_LINES)
+}
+
+// ─── Session end ────────────────────────────────────────────────────────────
+
+/// Stop background jobs started by `session_key` (all jobs when `None`) and record each
+/// job's final output in its session transcript. Called when a chat session ends. Returns
+/// how many jobs were removed.
+pub fn stop_background_jobs(session_key: Option<&str>) -> usize {
+    let ended: Vec<Job> = {
+        let Ok(mut jobs) = JOBS.lock() else {
+            return 0;
+        };
+        let (ended, kept) = std::mem::take(&mut *jobs)
+            .into_iter()
+            .partition(|j| session_key.is_none_or(|key| j.owner.as_deref() == Some(key)));
+        *jobs = kept;
+        ended
+    };
+    let count = ended.len();
+    for mut job in ended {
+        job.stop();
+        std::thread::sleep(Duration::from_millis(50));
+        append_final_output(&job);
+    }
+    count
+}
+
+fn append_final_output(job: &Job) {
+    let (Some(chat_root), Some(owner)) = (&job.chat_root, &job.owner) else {
+        return;
+    };
+    let tail = job
+        .output
+        .lock()
+        .map(|b| b.tail(DEFAULT_TAIL_LINES))
+        .unwrap_or_default();
+    let transcripts_dir = chat_root.join("transcripts");
+    let t_path = skilllite_executor::transcript::transcript_path_today(&transcripts_dir, owner);
+    let entry = skilllite_executor::transcript::TranscriptEntry::Custom {
+        id: uuid::Uuid::new_v4().to_string(),
+        parent_id: None,
+        kind: "background_job".to_string(),
+        data: json!({
+            "job": job.name,
+            "command": job.command,
+            "status": job.status.describe(),
+            "runtime_secs": job.started.elapsed().as_secs(),
+            "output_tail": tail,
+        }),
+    };
+    if let Err(e) = skilllite_executor::transcript::append_entry(&t_path, &entry) {
+        tracing::warn!("Failed to record final output of job {}: {}", job.name, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutputBuffer;
+
+    #[test]
+    fn ring_buffer_drops_oldest_and_tracks_unread() {
+        let mut buf = OutputBuffer::new(3);
+        for i in 0..5 {
+            buf.push(format!("line {}", i));
+        }
+        assert_eq!(buf.unread(), 5);
+        let (lines, skipped) = buf.take_unread(2);
+        assert_eq!(lines, ["line 3", "line 4"]);
+        assert_eq!(skipped, 3);
+        assert_eq!(buf.unread(), 0);
+        assert_eq!(buf.take_unread(10), (Vec::new(), 0));
+
+        buf.push("line 5".to_string());
+        assert_eq!(buf.take_unread(10), (vec!["line 5".to_string()], 0));
+        assert_eq!(buf.tail(2), ["line 4", "line 5"]);
+    }
+}
@@ -3,9 +3,11 @@
 //! Split into submodules by tool category:
 //! - `file_ops`:    read_file, write_file, search_replace, insert_lines, grep_files, list_directory, file_exists
 //! - `run_command`: run_command (shell execution with confirmation)
+//! - `jobs`:        background jobs of run_command; list_jobs, job_output, stop_job
 //! - `output`:      write_output, list_output
 //! - `preview`:     preview_server (local HTTP file server)
 //! - `chat_data`:   chat_history, chat_plan, update_task_plan
+//! - `skill_details`: get_skill_details (registered only when skills are loaded)
 //!
 //! This module provides shared security helpers, the tool definition registry,
 //! and the dispatch layer that routes tool calls to the appropriate submodule.
@@ -14,13 +16,24 @@ mod chat_data;
 mod delegate_swarm;
 mod file_ops;
 mod helpers;
+mod jobs;
 mod output;
 mod preview;
 mod run_command;
+mod skill_details;
+mod tool_session;
 
 #[cfg(test)]
 mod tests;
 
+pub use file_ops::revert_session_file;
+pub(crate) use helpers::normalize_path;
+pub use jobs::stop_background_jobs;
+pub(crate) use output::{output_root, register_skill_outputs};
+pub use preview::stop_preview_servers;
+pub use skill_details::execute_get_skill_details;
+pub use tool_session::ToolSession;
+
 use serde_json::Value;
 use std::path::Path;
 
@@ -38,6 +51,7 @@ pub fn get_builtin_tool_definitions() -> Vec<ToolDefinition> {
     let mut tools = Vec::new();
     tools.extend(file_ops::tool_definitions());
     tools.extend(run_command::tool_definitions());
+    tools.extend(jobs::tool_definitions());
     tools.extend(output::tool_definitions());
     tools.extend(preview::tool_definitions());
     tools.extend(chat_data::tool_definitions());
@@ -72,6 +86,16 @@ pub fn get_builtin_tools() -> Vec<RegisteredTool> {
         .collect()
 }
 
+/// `get_skill_details`, paired with [`ToolHandler::SkillDetails`]. Kept out of
+/// [`get_builtin_tools`] because it is only useful when skills are loaded.
+pub fn get_skill_details_tool() -> RegisteredTool {
+    RegisteredTool::new(
+        skill_details::tool_definition(),
+        Vec::new(),
+        ToolHandler::SkillDetails,
+    )
+}
+
 /// Map built-in tool name → typed [`PlanningControlKind`].
 /// Returns `None` for non-planning-control tools.
 ///
@@ -107,7 +131,7 @@ fn builtin_capabilities(name: &str) -> Vec<ToolCapability> {
         "write_file" | "search_replace" | "insert_lines" | "write_output" => {
             vec![ToolCapability::FilesystemWrite]
         }
-        "run_command" => vec![ToolCapability::ProcessExec],
+        "run_command" | "stop_job" => vec![ToolCapability::ProcessExec],
         "preview_server" => vec![ToolCapability::Preview],
         "delegate_to_swarm" => vec![ToolCapability::Delegation],
         _ => Vec::new(),
@@ -125,6 +149,7 @@ pub fn execute_builtin_tool(
     arguments: &str,
     workspace: &Path,
     event_sink: Option<&mut dyn EventSink>,
+    session: Option<&ToolSession>,
 ) -> ToolResult {
     let (args, was_recovered) = match serde_json::from_str(arguments) {
         Ok(v) => (v, false),
@@ -163,17 +188,22 @@ pub fn execute_builtin_tool(
 
     let result = match tool_name {
         "read_file" => file_ops::execute_read_file(&args, workspace),
-        "write_file" => file_ops::execute_write_file(&args, workspace, event_sink),
-        "search_replace" => file_ops::execute_search_replace(&args, workspace, event_sink),
+        "write_file" => file_ops::execute_write_file(&args, workspace, event_sink, session),
+        "search_replace" => {
+            file_ops::execute_search_replace(&args, workspace, event_sink, session)
+        }
         "preview_edit" => file_ops::execute_preview_edit(&args, workspace),
-        "insert_lines" => file_ops::execute_insert_lines(&args, workspace, event_sink),
+        "insert_lines" => file_ops::execute_insert_lines(&args, workspace, event_sink, session),
         "grep_files" => file_ops::execute_grep_files(&args, workspace),
         "list_directory" => file_ops::execute_list_directory(&args, workspace),
         "file_exists" => file_ops::execute_file_exists(&args, workspace),
-        "write_output" => output::execute_write_output(&args, workspace),
+        "write_output" => output::execute_write_output(&args, workspace, session),
         "chat_history" => chat_data::execute_chat_history(&args),
         "chat_plan" => chat_data::execute_chat_plan(&args),
-        "list_output" => output::execute_list_output(&args),
+        "list_output" => output::execute_list_output(&args, session),
+        "list_jobs" => jobs::execute_list_jobs(&args, session),
+        "job_output" => jobs::execute_job_output(&args, session),
+        "stop_job" => jobs::execute_stop_job(&args, session),
         "update_task_plan" | "complete_task" => Err(crate::Error::validation(format!(
             "{} is a planning control tool; it must be dispatched via registry.execute with planning_ctx",
             tool_name
@@ -220,6 +250,7 @@ pub async fn execute_async_builtin_tool(
     arguments: &str,
     workspace: &Path,
     event_sink: &mut dyn EventSink,
+    session: Option<&ToolSession>,
 ) -> ToolResult {
     let args: Value = match serde_json::from_str(arguments) {
         Ok(v) => v,
@@ -235,26 +266,11 @@ pub async fn execute_async_builtin_tool(
     };
 
     if tool_name == "run_command" {
-        return match run_command::execute_run_command(&args, workspace, event_sink).await {
-            Ok(outcome) => ToolResult {
-                tool_call_id: String::new(),
-                tool_name: tool_name.to_string(),
-                content: outcome.content,
-                is_error: outcome.is_error,
-                counts_as_failure: outcome.counts_as_failure,
-            },
-            Err(e) => ToolResult {
-                tool_call_id: String::new(),
-                tool_name: tool_name.to_string(),
-                content: format!("Error: {}", e),
-                is_error: true,
-                counts_as_failure: true,
-            },
-        };
+        return execute_run_command_as(tool_name, &args, workspace, event_sink, session).await;
     }
 
     let result = match tool_name {
-        "preview_server" => preview::execute_preview_server(&args, workspace, event_sink),
+        "preview_server" => preview::execute_preview_server(&args, workspace, event_sink, session),
         "delegate_to_swarm" => {
             delegate_swarm::execute_delegate_to_swarm(&args, workspace, event_sink).await
         }
@@ -282,6 +298,33 @@ pub async fn execute_async_builtin_tool(
     }
 }
 
+/// Run `run_command` arguments through its blocklist, shell scan, confirmation policy and
+/// timeout, reporting the result under `tool_name` (workspace custom command tools reuse it).
+pub(super) async fn execute_run_command_as(
+    tool_name: &str,
+    args: &Value,
+    workspace: &Path,
+    event_sink: &mut dyn EventSink,
+    session: Option<&ToolSession>,
+) -> ToolResult {
+    match run_command::execute_run_command(args, workspace, event_sink, session).await {
+        Ok(outcome) => ToolResult {
+            tool_call_id: String::new(),
+            tool_name: tool_name.to_string(),
+            content: outcome.content,
+            is_error: outcome.is_error,
+            counts_as_failure: outcome.counts_as_failure,
+        },
+        Err(e) => ToolResult {
+            tool_call_id: String::new(),
+            tool_name: tool_name.to_string(),
+            content: format!("Error: {}", e),
+            is_error: true,
+            counts_as_failure: true,
+        },
+    }
+}
+
 // ─── Long content handling ──────────────────────────────────────────────────
 
 pub fn process_tool_result_content(content: &str) -> Option<String> {
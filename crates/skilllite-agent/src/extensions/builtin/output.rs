@@ -1,14 +1,24 @@
 //! Output tools: write_output (deliverable files), list_output.
+//!
+//! Both keep the output directory's provenance index and quota
+//! (`skilllite_core::output_index`) up to date.
 
 use crate::error::bail;
 use crate::Result;
 use anyhow::Context;
 use serde_json::{json, Value};
+use skilllite_sandbox::results::SkillOutput;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use crate::types::{self, FunctionDef, ToolDefinition};
 
-use super::normalize_path;
+use skilllite_core::output_index::{
+    enforce_quota, format_size, OutputEntry, OutputIndex, OutputQuota,
+};
+use skilllite_core::path_validation::{resolve_under_roots, PathPolicy};
+
+use super::tool_session::ToolSession;
 
 // ─── Tool definitions ───────────────────────────────────────────────────────
 
@@ -43,13 +53,17 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
             tool_type: "function".to_string(),
             function: FunctionDef {
                 name: "list_output".to_string(),
-                description: "List files in the output directory (where write_output saves files). Use when the user asks what files were generated, or to find output files by name. No path needed.".to_string(),
+                description: "List files in the output directory (where write_output saves files) with size, modification time and producing session, plus files reported by skill runs. Use when the user asks what files were generated, or to find output files by name. No path needed.".to_string(),
                 parameters: json!({
                     "type": "object",
                     "properties": {
                         "recursive": {
                             "type": "boolean",
                             "description": "If true, list recursively. Default: false."
+                        },
+                        "current_session": {
+                            "type": "boolean",
+                            "description": "If true, only list files written by the current session. Default: false."
                         }
                     },
                     "required": []
@@ -61,7 +75,11 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
 
 // ─── Execution ──────────────────────────────────────────────────────────────
 
-pub(super) fn execute_write_output(args: &Value, workspace: &Path) -> Result<String> {
+pub(super) fn execute_write_output(
+    args: &Value,
+    workspace: &Path,
+    tool_session: Option<&ToolSession>,
+) -> Result<String> {
     let file_path = args
         .get("file_path")
         .and_then(|v| v.as_str())
@@ -75,26 +93,32 @@ pub(super) fn execute_write_output(args: &Value, workspace: &Path) -> Result<Str
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
-    let output_root = match types::get_output_dir() {
-        Some(dir) => PathBuf::from(dir),
-        None => workspace.join("output"),
-    };
-
-    let input = Path::new(file_path);
-    let resolved = if input.is_absolute() {
-        input.to_path_buf()
-    } else {
-        output_root.join(input)
-    };
+    let output_root = output_root(workspace);
 
-    let normalized = normalize_path(&resolved);
-    if !normalized.starts_with(&output_root) {
+    let Ok(normalized) = resolve_under_roots(
+        file_path,
+        &[&output_root],
+        PathPolicy::writable("Output path"),
+    ) else {
         bail!(
             "Path escapes output directory: {} (output_root: {})",
             file_path,
             output_root.display()
         );
-    }
+    };
+
+    let session = tool_session.map(|s| s.session_key.as_str());
+    let mut index = OutputIndex::load(&output_root);
+    index.reconcile(&output_root);
+    let quota = enforce_quota(
+        &output_root,
+        &mut index,
+        &OutputQuota::from_env(),
+        &normalized,
+        content.len() as u64,
+        append,
+        session,
+    )?;
 
     if append {
         skilllite_fs::append_file(&normalized, content).with_context(|| {
@@ -105,32 +129,217 @@ pub(super) fn execute_write_output(args: &Value, workspace: &Path) -> Result<Str
             .with_context(|| format!("Failed to write output file: {}", normalized.display()))?;
     }
 
-    Ok(format!(
+    index.record(&output_root, &normalized, session);
+    if let Err(e) = index.save(&output_root) {
+        tracing::warn!("Failed to update output index: {}", e);
+    }
+
+    let mut msg = format!(
         "Successfully {} {} bytes to {}",
         if append { "appended" } else { "wrote" },
         content.len(),
         normalized.display()
-    ))
+    );
+    if !quota.expired.is_empty() {
+        msg.push_str(&format!(
+            " (expired {} old output file(s) to stay within quota: {})",
+            quota.expired.len(),
+            quota.expired.join(", ")
+        ));
+    }
+    Ok(msg)
+}
+
+/// Directory `write_output` writes into: `SKILLLITE_OUTPUT_DIR`, else `<workspace>/output`.
+pub(crate) fn output_root(workspace: &Path) -> PathBuf {
+    match types::get_output_dir() {
+        Some(dir) => PathBuf::from(dir),
+        None => workspace.join("output"),
+    }
 }
 
-pub(super) fn execute_list_output(args: &Value) -> Result<String> {
+pub(super) fn execute_list_output(
+    args: &Value,
+    tool_session: Option<&ToolSession>,
+) -> Result<String> {
     let recursive = args
         .get("recursive")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
-    let output_root = types::get_output_dir().map(PathBuf::from).ok_or_else(|| {
-        crate::Error::validation("Output directory not configured (SKILLLITE_OUTPUT_DIR)")
-    })?;
+    let skill_outputs = skill_outputs_section();
+    let output_root = match types::get_output_dir() {
+        Some(dir) => PathBuf::from(dir),
+        None if !skill_outputs.is_empty() => return Ok(skill_outputs),
+        None => bail!("Output directory not configured (SKILLLITE_OUTPUT_DIR)"),
+    };
 
-    let entries = match skilllite_fs::list_directory(&output_root, recursive) {
-        Ok(e) => e,
-        Err(_) => return Ok("Output directory does not exist or is empty.".to_string()),
+    let current_session = args
+        .get("current_session")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let listing = if output_root.is_dir() {
+        let mut index = OutputIndex::load(&output_root);
+        index.reconcile(&output_root);
+        if let Err(e) = index.save(&output_root) {
+            tracing::warn!("Failed to update output index: {}", e);
+        }
+        let session = if current_session {
+            tool_session.map(|s| s.session_key.as_str())
+        } else {
+            None
+        };
+        if current_session && session.is_none() {
+            "No active session; use current_session: false to list all output files.".to_string()
+        } else {
+            format_listing(&index, session, recursive)
+        }
+    } else {
+        "Output directory does not exist or is empty.".to_string()
     };
 
+    if skill_outputs.is_empty() {
+        Ok(listing)
+    } else {
+        Ok(format!("{}\n\n{}", listing, skill_outputs))
+    }
+}
+
+/// Files shown by `list_output` before truncating.
+const MAX_LISTED_OUTPUTS: usize = 200;
+
+/// Newest first; non-recursive listings fold subdirectories into one summary line.
+fn format_listing(index: &OutputIndex, session: Option<&str>, recursive: bool) -> String {
+    let entries = index.listing(session);
     if entries.is_empty() {
-        return Ok("Output directory is empty.".to_string());
+        return if session.is_some() {
+            "No output files from the current session.".to_string()
+        } else {
+            "Output directory is empty.".to_string()
+        };
+    }
+
+    let mut lines = Vec::new();
+    let mut dirs: Vec<(String, usize, u64)> = Vec::new();
+    for entry in &entries {
+        match entry.path.split_once('/') {
+            Some((dir, _)) if !recursive => {
+                match dirs.iter_mut().find(|(name, _, _)| name == dir) {
+                    Some((_, count, bytes)) => {
+                        *count += 1;
+                        *bytes += entry.size;
+                    }
+                    None => dirs.push((dir.to_string(), 1, entry.size)),
+                }
+            }
+            _ => lines.push(format_entry(entry)),
+        }
+    }
+    lines.extend(
+        dirs.into_iter().map(|(dir, count, bytes)| {
+            format!("{}/  ({} files, {})", dir, count, format_size(bytes))
+        }),
+    );
+
+    let total = lines.len();
+    let mut out = format!(
+        "Output files ({} files, {}):\n",
+        entries.len(),
+        format_size(entries.iter().map(|e| e.size).sum())
+    );
+    out.push_str(
+        &lines
+            .into_iter()
+            .take(MAX_LISTED_OUTPUTS)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+    if total > MAX_LISTED_OUTPUTS {
+        out.push_str(&format!(
+            "\n... and {} more (use current_session: true to narrow)",
+            total - MAX_LISTED_OUTPUTS
+        ));
+    }
+    out
+}
+
+fn format_entry(entry: &OutputEntry) -> String {
+    let modified = chrono::DateTime::from_timestamp(entry.modified as i64, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let mut line = format!(
+        "{}  ({}, {})",
+        entry.path,
+        format_size(entry.size),
+        modified
+    );
+    if let Some(session) = &entry.session {
+        line.push_str(&format!(" [session: {}]", session));
+    }
+    line
+}
+
+// ─── Skill outputs (result.json contract) ───────────────────────────────────
+
+/// Most recent skill-reported files kept for `list_output`.
+const MAX_SKILL_OUTPUTS: usize = 200;
+
+struct RegisteredOutput {
+    skill: String,
+    output: SkillOutput,
+}
+
+static SKILL_OUTPUTS: Mutex<Vec<RegisteredOutput>> = Mutex::new(Vec::new());
+
+/// Record files a skill reported in its `result.json` so `list_output` shows them.
+/// Returns the compact list surfaced to the model with the skill result.
+pub(crate) fn register_skill_outputs(skill: &str, outputs: &[SkillOutput]) -> String {
+    if let Ok(mut registered) = SKILL_OUTPUTS.lock() {
+        for output in outputs {
+            registered.retain(|r| r.output.path != output.path);
+            registered.push(RegisteredOutput {
+                skill: skill.to_string(),
+                output: output.clone(),
+            });
+        }
+        let excess = registered.len().saturating_sub(MAX_SKILL_OUTPUTS);
+        registered.drain(..excess);
+    }
+    outputs
+        .iter()
+        .map(format_skill_output)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_skill_output(output: &SkillOutput) -> String {
+    let mut line = format!("- {}", output.path.display());
+    if let Some(kind) = &output.kind {
+        line.push_str(&format!(" ({})", kind));
     }
+    if let Some(description) = output.description.as_deref().filter(|d| !d.is_empty()) {
+        line.push_str(&format!(": {}", description));
+    }
+    line
+}
 
-    Ok(entries.join("\n"))
+fn skill_outputs_section() -> String {
+    let Ok(registered) = SKILL_OUTPUTS.lock() else {
+        return String::new();
+    };
+    let lines: Vec<String> = registered
+        .iter()
+        .filter(|r| r.output.path.exists())
+        .map(|r| format!("{} [{}]", format_skill_output(&r.output), r.skill))
+        .collect();
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!("Skill outputs:\n{}", lines.join("\n"))
+    }
 }
@@ -3,12 +3,17 @@
 use crate::error::bail;
 use crate::Result;
 use serde_json::{json, Value};
-use std::path::Path;
-use std::sync::Mutex;
+use skilllite_core::path_validation::{resolve_under_roots, PathPolicy};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use crate::types::{EventSink, FunctionDef, ToolDefinition};
 
-use super::{get_path_arg, normalize_path, resolve_within_workspace_or_output};
+use super::tool_session::ToolSession;
+use super::{
+    get_path_arg, is_sensitive_read_path, normalize_path, resolve_within_workspace_or_output,
+};
 
 // ─── Tool definition ────────────────────────────────────────────────────────
 
@@ -17,13 +22,13 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
         tool_type: "function".to_string(),
         function: FunctionDef {
             name: "preview_server".to_string(),
-            description: "Start a local HTTP server to preview HTML files in the browser. Specify the directory to serve.".to_string(),
+            description: "Start a local HTTP server to preview HTML files in the browser. Serves one explicit subdirectory of the workspace or output dir (not the workspace root); sensitive files (.env, .key, dotfiles) are never served. Calling again for the same directory reuses the running server and returns the same URL. Use stop=true to shut a server down; servers also stop when the chat session ends.".to_string(),
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "directory_path": {
                         "type": "string",
-                        "description": "Directory to serve (relative to workspace). Also accepts 'path'."
+                        "description": "Subdirectory to serve (relative to workspace or output dir), or an HTML file inside it. Also accepts 'path'."
                     },
                     "path": {
                         "type": "string",
@@ -31,7 +36,7 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
                     },
                     "port": {
                         "type": "integer",
-                        "description": "Port number (default: 8765)",
+                        "description": "Preferred port (default: 8765; next free port is used if taken)",
                         "minimum": 1,
                         "maximum": 65535
                     },
@@ -39,6 +44,16 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
                         "type": "boolean",
                         "description": "Whether to open browser automatically (default: true)",
                         "default": true
+                    },
+                    "allow_lan": {
+                        "type": "boolean",
+                        "description": "Bind on all interfaces and report a LAN URL for other devices (default: false, localhost only)",
+                        "default": false
+                    },
+                    "stop": {
+                        "type": "boolean",
+                        "description": "Stop the server for directory_path (or every preview server of this session when omitted)",
+                        "default": false
                     }
                 },
                 "required": []
@@ -49,11 +64,51 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
 
 // ─── Server state ───────────────────────────────────────────────────────────
 
-static ACTIVE_PREVIEW: Mutex<Option<PreviewServerState>> = Mutex::new(None);
+/// Running preview servers, one per served directory.
+static ACTIVE_PREVIEWS: Mutex<Vec<PreviewServerState>> = Mutex::new(Vec::new());
 
 struct PreviewServerState {
     serve_dir: String,
     port: u16,
+    allow_lan: bool,
+    /// Session that started the server; `None` outside an agent session
+    owner: Option<String>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl PreviewServerState {
+    /// Signal the accept loop and wake it with a dummy connection.
+    fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        let _ = std::net::TcpStream::connect_timeout(
+            &std::net::SocketAddr::from(([127, 0, 0, 1], self.port)),
+            std::time::Duration::from_millis(200),
+        );
+    }
+}
+
+fn lock_previews() -> Result<std::sync::MutexGuard<'static, Vec<PreviewServerState>>> {
+    ACTIVE_PREVIEWS
+        .lock()
+        .map_err(|e| crate::Error::validation(format!("Preview lock poisoned: {}", e)))
+}
+
+/// Stop preview servers started by `session_key` (all servers when `None`). Called when a
+/// chat session ends. Returns how many servers were stopped.
+pub fn stop_preview_servers(session_key: Option<&str>) -> usize {
+    let Ok(mut guard) = ACTIVE_PREVIEWS.lock() else {
+        return 0;
+    };
+    let mut stopped = 0;
+    guard.retain(|state| {
+        let matches = session_key.is_none_or(|key| state.owner.as_deref() == Some(key));
+        if matches {
+            state.shutdown();
+            stopped += 1;
+        }
+        !matches
+    });
+    stopped
 }
 
 // ─── Execution ──────────────────────────────────────────────────────────────
@@ -62,57 +117,67 @@ pub(super) fn execute_preview_server(
     args: &Value,
     workspace: &Path,
     event_sink: &mut dyn EventSink,
+    session: Option<&ToolSession>,
 ) -> Result<String> {
-    let dir_path = get_path_arg(args, true)
+    let dir_path = get_path_arg(args, true);
+    let owner = session.map(|s| s.session_key.clone());
+    if args.get("stop").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return stop_requested(dir_path.as_deref(), workspace, owner.as_deref());
+    }
+
+    let dir_path = dir_path
         .ok_or_else(|| crate::Error::validation("'directory_path' or 'path' is required"))?;
     let requested_port = args.get("port").and_then(|v| v.as_u64()).unwrap_or(8765) as u16;
     let should_open_browser = args
         .get("open_browser")
         .and_then(|v| v.as_bool())
         .unwrap_or(true);
+    let allow_lan = args
+        .get("allow_lan")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
 
-    let resolved = resolve_within_workspace_or_output(&dir_path, workspace)?;
-
-    let (serve_dir, target_file) = if resolved.is_file() {
-        let fname = resolved
-            .file_name()
-            .map(|f| f.to_string_lossy().to_string());
-        (resolved.parent().unwrap_or(&resolved).to_path_buf(), fname)
-    } else {
-        (resolved.clone(), None)
+    let (serve_dir, target_file) = match resolve_serve_dir(&dir_path, workspace) {
+        Ok(v) => v,
+        Err(e) => {
+            event_sink.on_preview_failed(&e.to_string());
+            return Err(e);
+        }
     };
 
-    if !serve_dir.exists() {
-        event_sink.on_preview_failed(&format!("path not found: {}", dir_path));
-        bail!("Path not found: {}", dir_path);
-    }
-
     let serve_dir_str = serve_dir.to_string_lossy().to_string();
     event_sink.on_preview_started(&serve_dir_str, requested_port);
 
     {
-        let guard = ACTIVE_PREVIEW
-            .lock()
-            .map_err(|e| crate::Error::validation(format!("Preview lock poisoned: {}", e)))?;
-        if let Some(ref state) = *guard {
-            if state.serve_dir == serve_dir_str {
+        let mut guard = lock_previews()?;
+        if let Some(pos) = guard.iter().position(|s| s.serve_dir == serve_dir_str) {
+            if guard[pos].allow_lan == allow_lan {
+                let state = &guard[pos];
                 let url = build_preview_url(state.port, target_file.as_deref());
                 event_sink.on_preview_ready(&url, state.port);
                 return Ok(format!(
                     "Preview server already running at {}\n\n\
                      Open in browser: {}\n\
+                     Port: {}\n{}\
                      Serving directory: {}\n\
                      (Server was already started. Browser tab is already open — no action needed.)",
-                    url, url, serve_dir_str
+                    url,
+                    url,
+                    state.port,
+                    lan_line(state.allow_lan, state.port, target_file.as_deref()),
+                    serve_dir_str
                 ));
             }
+            // LAN exposure changed: restart on the new interface
+            guard.remove(pos).shutdown();
         }
     }
 
+    let host = if allow_lan { "0.0.0.0" } else { "127.0.0.1" };
     let listener = {
         let mut bound = None;
         for p in requested_port..requested_port.saturating_add(20) {
-            match std::net::TcpListener::bind(("127.0.0.1", p)) {
+            match std::net::TcpListener::bind((host, p)) {
                 Ok(l) => {
                     bound = Some((l, p));
                     break;
@@ -130,28 +195,20 @@ pub(super) fn execute_preview_server(
                 "could not bind to port {} (tried {}-{})",
                 requested_port,
                 requested_port,
-                requested_port + 19
+                requested_port.saturating_add(19)
             );
             event_sink.on_preview_failed(&message);
             bail!("{}", message)
         }
     };
 
-    {
-        let mut guard = ACTIVE_PREVIEW
-            .lock()
-            .map_err(|e| crate::Error::validation(format!("Preview lock poisoned: {}", e)))?;
-        *guard = Some(PreviewServerState {
-            serve_dir: serve_dir_str.clone(),
-            port: used_port,
-        });
-    }
-
+    let shutdown = Arc::new(AtomicBool::new(false));
     let serve_dir_clone = serve_dir.clone();
+    let shutdown_clone = Arc::clone(&shutdown);
     std::thread::Builder::new()
         .name("preview-server".to_string())
         .spawn(move || {
-            run_file_server(listener, &serve_dir_clone);
+            run_file_server(listener, &serve_dir_clone, &shutdown_clone);
         })
         .map_err(|e| {
             let message = format!("failed to spawn preview server thread: {}", e);
@@ -159,6 +216,14 @@ pub(super) fn execute_preview_server(
             crate::Error::validation(message)
         })?;
 
+    lock_previews()?.push(PreviewServerState {
+        serve_dir: serve_dir_str.clone(),
+        port: used_port,
+        allow_lan,
+        owner,
+        shutdown,
+    });
+
     let url = build_preview_url(used_port, target_file.as_deref());
     event_sink.on_preview_ready(&url, used_port);
     if should_open_browser {
@@ -168,18 +233,110 @@ pub(super) fn execute_preview_server(
     Ok(format!(
         "Preview server started at {}\n\n\
          Open in browser: {}\n\
+         Port: {}\n{}\
          Serving directory: {}\n\
-         (Server runs in background. Stops when you exit.)",
-        url, url, serve_dir_str
+         (Server runs in background until stop=true or the chat session ends.)",
+        url,
+        url,
+        used_port,
+        lan_line(allow_lan, used_port, target_file.as_deref()),
+        serve_dir_str
     ))
 }
 
+/// Validate the requested directory: inside workspace/output, not the workspace root, no
+/// sensitive or hidden path segments. Returns `(serve_dir, file to open)`.
+fn resolve_serve_dir(dir_path: &str, workspace: &Path) -> Result<(PathBuf, Option<String>)> {
+    if is_sensitive_request_path(dir_path) {
+        bail!(
+            "Blocked: '{}' is a sensitive or hidden path and cannot be served",
+            dir_path
+        );
+    }
+    let resolved = resolve_within_workspace_or_output(dir_path, workspace)?;
+
+    let (serve_dir, target_file) = if resolved.is_file() {
+        let fname = resolved
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string());
+        (resolved.parent().unwrap_or(&resolved).to_path_buf(), fname)
+    } else {
+        (resolved.clone(), None)
+    };
+
+    if !serve_dir.exists() {
+        bail!("Path not found: {}", dir_path);
+    }
+    if serve_dir == normalize_path(&std::path::absolute(workspace)?) {
+        bail!(
+            "Refusing to serve the whole workspace; pass the subdirectory that contains the page (e.g. 'site' or 'dist')"
+        );
+    }
+    Ok((serve_dir, target_file))
+}
+
+fn stop_requested(dir_path: Option<&str>, workspace: &Path, owner: Option<&str>) -> Result<String> {
+    let Some(dir_path) = dir_path else {
+        let n = stop_preview_servers(owner);
+        return Ok(format!("Stopped {} preview server(s)", n));
+    };
+    let resolved = resolve_within_workspace_or_output(dir_path, workspace)?;
+    let serve_dir = if resolved.is_file() {
+        resolved.parent().unwrap_or(&resolved).to_path_buf()
+    } else {
+        resolved
+    };
+    let serve_dir_str = serve_dir.to_string_lossy().to_string();
+    let mut guard = lock_previews()?;
+    match guard.iter().position(|s| s.serve_dir == serve_dir_str) {
+        Some(pos) => {
+            let state = guard.remove(pos);
+            state.shutdown();
+            Ok(format!(
+                "Stopped preview server for {} (port {})",
+                serve_dir_str, state.port
+            ))
+        }
+        None => Ok(format!("No preview server running for {}", serve_dir_str)),
+    }
+}
+
+/// `.env`, `.key`, `.git/config` (shared sensitive patterns) and any dot-prefixed segment.
+fn is_sensitive_request_path(path: &str) -> bool {
+    is_sensitive_read_path(path)
+        || path
+            .split(['/', '\\'])
+            .any(|seg| seg.starts_with('.') && seg != "." && seg != "..")
+}
+
+fn lan_line(allow_lan: bool, port: u16, filename: Option<&str>) -> String {
+    if !allow_lan {
+        return String::new();
+    }
+    match lan_ip() {
+        Some(ip) => format!("LAN URL: {}\n", build_url(&ip.to_string(), port, filename)),
+        None => "LAN URL: unavailable (no network interface found)\n".to_string(),
+    }
+}
+
+/// Local address of the default route (no packets are sent by a UDP `connect`).
+fn lan_ip() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("8.8.8.8", 80)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
 // ─── HTTP server internals ──────────────────────────────────────────────────
 
 fn build_preview_url(port: u16, filename: Option<&str>) -> String {
+    build_url("127.0.0.1", port, filename)
+}
+
+fn build_url(host: &str, port: u16, filename: Option<&str>) -> String {
     match filename {
-        Some(f) => format!("http://127.0.0.1:{}/{}", port, f),
-        None => format!("http://127.0.0.1:{}", port),
+        Some(f) => format!("http://{}:{}/{}", host, port, f),
+        None => format!("http://{}:{}", host, port),
     }
 }
 
@@ -204,10 +361,13 @@ fn open_browser(url: &str) {
     let _ = std::process::Command::new("true").spawn();
 }
 
-fn run_file_server(listener: std::net::TcpListener, serve_dir: &Path) {
+fn run_file_server(listener: std::net::TcpListener, serve_dir: &Path, shutdown: &AtomicBool) {
     use std::io::{BufRead, BufReader, Write};
 
     for stream in listener.incoming() {
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
         let mut stream = match stream {
             Ok(s) => s,
             Err(_) => continue,
@@ -236,21 +396,22 @@ fn run_file_server(listener: std::net::TcpListener, serve_dir: &Path) {
             continue;
         }
 
-        let file_path = serve_dir.join(rel);
-        let normalized = normalize_path(&file_path);
-
-        if !normalized.starts_with(serve_dir) {
-            let body = "403 Forbidden";
-            let resp = format!(
-                "HTTP/1.1 403 Forbidden\r\n\
+        let resolved = resolve_under_roots(rel, &[serve_dir], PathPolicy::writable("Preview path"));
+        let normalized = match resolved {
+            Ok(path) if !is_sensitive_request_path(rel) => path,
+            _ => {
+                let body = "403 Forbidden";
+                let resp = format!(
+                    "HTTP/1.1 403 Forbidden\r\n\
                  Content-Length: {}\r\n\
                  Connection: close\r\n\r\n{}",
-                body.len(),
-                body
-            );
-            let _ = stream.write_all(resp.as_bytes());
-            continue;
-        }
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(resp.as_bytes());
+                continue;
+            }
+        };
 
         if normalized.is_file() {
             match skilllite_fs::read_bytes(&normalized) {
@@ -334,7 +495,7 @@ fn serve_directory_fallback(stream: &mut std::net::TcpStream, serve_dir: &Path)
             for (path, is_dir) in entries {
                 if !is_dir {
                     if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        if !name.starts_with('.') {
+                        if !is_sensitive_request_path(name) {
                             all_files.push(name.to_string());
                         }
                     }
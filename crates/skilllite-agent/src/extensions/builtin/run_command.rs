@@ -1,10 +1,12 @@
-//! run_command: shell command execution with confirmation + timeout.
+//! run_command: shell command execution with confirmation + timeout (or as a background
+//! job, see `jobs`).
 
 use crate::error::bail;
 use crate::Result;
 use anyhow::Context;
 use serde_json::{json, Value};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
 
 use crate::high_risk;
@@ -13,7 +15,9 @@ use crate::types::{
     ToolDefinition,
 };
 
-use super::helpers::filter_sensitive_content_in_text;
+use super::helpers::{filter_sensitive_content_in_text, resolve_within_workspace};
+use super::jobs;
+use super::tool_session::ToolSession;
 
 /// Hard block for `run_command` when inline shell static scan reports Critical (no override).
 const SHELL_SCAN_CRITICAL_BLOCKED: &str =
@@ -26,13 +30,31 @@ pub(super) fn tool_definitions() -> Vec<ToolDefinition> {
         tool_type: "function".to_string(),
         function: FunctionDef {
             name: "run_command".to_string(),
-            description: "Execute a shell command in the workspace directory. Uses the platform shell: Unix/macOS runs `sh -c`; Windows runs `%ComSpec% /C` (normally cmd.exe). A static shell scan (same engine family as skill L3 checks) runs before spawn; findings require confirmation. Reading sensitive paths (.env, .key, .pem, .git/config) via shell requires explicit confirmation. Regex-based dangerous patterns (rm -rf, curl|bash, etc.) add warnings. Timeout: 300 seconds.".to_string(),
+            description: "Execute a shell command in the workspace directory. Uses the platform shell: Unix/macOS runs `sh -c`; Windows runs `%ComSpec% /C` (normally cmd.exe). A static shell scan (same engine family as skill L3 checks) runs before spawn; findings require confirmation. Reading sensitive paths (.env, .key, .pem, .git/config) via shell requires explicit confirmation. Regex-based dangerous patterns (rm -rf, curl|bash, etc.) add warnings. Timeout: 300 seconds. For long-running processes (dev servers, watchers) set background=true: the command keeps running as a named job and the call returns its first seconds of output; manage it with list_jobs, job_output and stop_job.".to_string(),
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "command": {
                         "type": "string",
                         "description": "The shell command to execute"
+                    },
+                    "cwd": {
+                        "type": "string",
+                        "description": "Working directory relative to the workspace (must exist). Use this instead of `cd dir && ...`"
+                    },
+                    "env": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": "Extra environment variables, e.g. {\"NODE_ENV\": \"production\"}. Use this instead of inline `VAR=val` prefixes. PATH, HOME, LD_PRELOAD and similar cannot be overridden"
+                    },
+                    "background": {
+                        "type": "boolean",
+                        "description": "Run as a background job instead of waiting for exit (no timeout; stopped when the session ends)",
+                        "default": false
+                    },
+                    "job_name": {
+                        "type": "string",
+                        "description": "Name for the background job (letters, digits, '-', '_'); generated when omitted"
                     }
                 },
                 "required": ["command"]
@@ -144,12 +166,177 @@ fn check_sensitive_file_read(cmd: &str) -> Option<String> {
     None
 }
 
+// ─── Working directory / environment arguments ──────────────────────────────
+
+/// Variables that change what runs or where it looks for code; never settable via `env`.
+const ENV_DENYLIST: &[&str] = &[
+    "PATH",
+    "PATHEXT",
+    "HOME",
+    "USERPROFILE",
+    "SHELL",
+    "COMSPEC",
+    "SYSTEMROOT",
+    "IFS",
+    "ENV",
+    "BASH_ENV",
+    "PS4",
+    "PROMPT_COMMAND",
+    "LD_PRELOAD",
+    "LD_LIBRARY_PATH",
+    "LD_AUDIT",
+    "NODE_OPTIONS",
+    "PYTHONPATH",
+    "PYTHONSTARTUP",
+    "PERL5OPT",
+    "RUBYOPT",
+];
+
+/// Prefixes denied like [`ENV_DENYLIST`] (macOS dyld injection, SkillLite's own config).
+const ENV_DENYLIST_PREFIXES: &[&str] = &["DYLD_", "SKILLLITE_", "SKILLBOX_"];
+
+const MAX_ENV_VARS: usize = 32;
+const MAX_ENV_KEY_LEN: usize = 128;
+const MAX_ENV_VALUE_LEN: usize = 4096;
+
+/// Validated `cwd` / `env` arguments of one `run_command` call.
+#[derive(Debug, Default)]
+struct CommandContext {
+    /// Absolute working directory; `None` = workspace root.
+    cwd: Option<PathBuf>,
+    /// `cwd` relative to the workspace, for display.
+    cwd_display: Option<String>,
+    env: BTreeMap<String, String>,
+}
+
+impl CommandContext {
+    fn from_args(args: &Value, workspace: &Path) -> Result<Self> {
+        let mut ctx = Self::default();
+
+        if let Some(cwd) = args.get("cwd").filter(|v| !v.is_null()) {
+            let cwd = cwd.as_str().context("'cwd' must be a string")?.trim();
+            if !cwd.is_empty() && cwd != "." {
+                let resolved = resolve_within_workspace(cwd, workspace)?;
+                // Symlinks inside the workspace must not lead outside it.
+                let canonical = resolved
+                    .canonicalize()
+                    .with_context(|| format!("cwd does not exist: {}", cwd))?;
+                let canonical_ws = workspace
+                    .canonicalize()
+                    .unwrap_or_else(|_| workspace.to_path_buf());
+                if !canonical.starts_with(&canonical_ws) {
+                    bail!(
+                        "Path escapes workspace: {} (workspace: {})",
+                        cwd,
+                        workspace.display()
+                    );
+                }
+                if !canonical.is_dir() {
+                    bail!("cwd is not a directory: {}", cwd);
+                }
+                let display = resolved
+                    .strip_prefix(workspace)
+                    .unwrap_or(&resolved)
+                    .to_string_lossy()
+                    .into_owned();
+                ctx.cwd_display = Some(display);
+                ctx.cwd = Some(canonical);
+            }
+        }
+
+        if let Some(env) = args.get("env").filter(|v| !v.is_null()) {
+            let env = env
+                .as_object()
+                .context("'env' must be an object of string values")?;
+            if env.len() > MAX_ENV_VARS {
+                bail!(
+                    "Too many env variables: {} (max {})",
+                    env.len(),
+                    MAX_ENV_VARS
+                );
+            }
+            for (key, value) in env {
+                let value = value
+                    .as_str()
+                    .with_context(|| format!("env value for '{}' must be a string", key))?;
+                validate_env_var(key, value)?;
+                ctx.env.insert(key.clone(), value.to_string());
+            }
+        }
+
+        Ok(ctx)
+    }
+
+    /// What the user sees: `[cwd: web] NODE_ENV=production npm run build`.
+    fn describe(&self, cmd: &str) -> String {
+        let mut out = String::new();
+        if let Some(cwd) = &self.cwd_display {
+            out.push_str(&format!("[cwd: {}] ", cwd));
+        }
+        for (key, value) in &self.env {
+            out.push_str(&format!("{}={} ", key, shell_quote_for_display(value)));
+        }
+        out.push_str(cmd);
+        out
+    }
+}
+
+fn validate_env_var(key: &str, value: &str) -> Result<()> {
+    let valid_key = key.len() <= MAX_ENV_KEY_LEN
+        && key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_key {
+        bail!(
+            "Invalid env variable name '{}': use letters, digits and '_' (max {} chars), not starting with a digit",
+            key,
+            MAX_ENV_KEY_LEN
+        );
+    }
+    let upper = key.to_ascii_uppercase();
+    if ENV_DENYLIST.contains(&upper.as_str())
+        || ENV_DENYLIST_PREFIXES.iter().any(|p| upper.starts_with(p))
+    {
+        bail!(
+            "Env variable '{}' cannot be overridden via run_command",
+            key
+        );
+    }
+    if value.len() > MAX_ENV_VALUE_LEN {
+        bail!(
+            "Env value for '{}' is too long: {} bytes (max {})",
+            key,
+            value.len(),
+            MAX_ENV_VALUE_LEN
+        );
+    }
+    if value.contains('\0') {
+        bail!("Env value for '{}' must not contain NUL bytes", key);
+    }
+    Ok(())
+}
+
+fn shell_quote_for_display(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:,=@%+".contains(c));
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
 // ─── Execution ──────────────────────────────────────────────────────────────
 
 pub(super) async fn execute_run_command(
     args: &Value,
     workspace: &Path,
     event_sink: &mut dyn EventSink,
+    session: Option<&ToolSession>,
 ) -> Result<RunCommandOutcome> {
     let cmd = args
         .get("command")
@@ -160,6 +347,10 @@ pub(super) async fn execute_run_command(
         bail!("command must not be empty");
     }
 
+    let context = CommandContext::from_args(args, workspace)?;
+    let display_cmd = context.describe(cmd);
+    let run_dir = context.cwd.as_deref().unwrap_or(workspace);
+
     if let Some(reason) = check_blocked_command(cmd) {
         bail!(
             "Blocked: command is not allowed ({}). \
@@ -212,7 +403,7 @@ pub(super) async fn execute_run_command(
                  It also matches a risky pattern ({}).\n\n\
                  Command:\n  {}\n\n\
                  Confirm execution?",
-                sr, dr, cmd
+                sr, dr, display_cmd
             ),
             (Some(sr), None) => format!(
                 "⚠️ Sensitive file access\n\n\
@@ -220,16 +411,16 @@ pub(super) async fn execute_run_command(
                  Paths like .env, .key, .pem, and .git/config require explicit approval when run via run_command.\n\n\
                  Command:\n  {}\n\n\
                  Confirm execution?",
-                sr, cmd
+                sr, display_cmd
             ),
             (None, Some(dr)) => format!(
                 "⚠️ Dangerous command detected\n\n\
                  Pattern that may cause serious harm: {}\n\n\
                  Command: {}\n\n\
                  Please verify before confirming execution.",
-                dr, cmd
+                dr, display_cmd
             ),
-            (None, None) => format!("About to execute command:\n  {}\n\nConfirm execution?", cmd),
+            (None, None) => format!("About to execute command:\n  {}\n\nConfirm execution?", display_cmd),
         };
 
         if let Some(note) = &shell_scan_note {
@@ -254,6 +445,22 @@ pub(super) async fn execute_run_command(
         }
     }
 
+    if args
+        .get("background")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        let content =
+            jobs::start_background_job(args, cmd, &display_cmd, run_dir, &context.env, session)
+                .await?;
+        event_sink.on_command_started(&display_cmd);
+        return Ok(RunCommandOutcome {
+            content,
+            is_error: false,
+            counts_as_failure: false,
+        });
+    }
+
     use tokio::io::{AsyncBufReadExt, BufReader};
     use tokio::process::Command;
     use tokio::sync::mpsc;
@@ -272,7 +479,8 @@ pub(super) async fn execute_run_command(
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .current_dir(workspace);
+            .current_dir(run_dir)
+            .envs(&context.env);
         const CREATE_NO_WINDOW: u32 = 0x0800_0000;
         c.as_std_mut().creation_flags(CREATE_NO_WINDOW);
         c.spawn()
@@ -285,10 +493,11 @@ pub(super) async fn execute_run_command(
         .stdin(std::process::Stdio::null())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
-        .current_dir(workspace)
+        .current_dir(run_dir)
+        .envs(&context.env)
         .spawn()
         .with_context(|| format!("Failed to spawn command: {}", cmd))?;
-    event_sink.on_command_started(cmd);
+    event_sink.on_command_started(&display_cmd);
 
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
@@ -328,9 +537,15 @@ pub(super) async fn execute_run_command(
         let mut wait_fut = Box::pin(child.wait());
         let mut status: Option<ExitStatus> = None;
         let mut streams_open = true;
+        let mut cancel_poll = tokio::time::interval(CANCEL_POLL_INTERVAL);
 
         while status.is_none() || streams_open {
             tokio::select! {
+                _ = cancel_poll.tick() => {
+                    if event_sink.is_cancelled() {
+                        return Ok(None);
+                    }
+                }
                 maybe = rx.recv(), if streams_open => {
                     match maybe {
                         Some((stream, line)) => {
@@ -354,9 +569,14 @@ pub(super) async fn execute_run_command(
             }
         }
 
-        status.context("command finished without exit status")
+        status.context("command finished without exit status").map(Some)
     }).await {
-        Ok(Ok(status)) => status,
+        Ok(Ok(Some(status))) => status,
+        Ok(Ok(None)) => {
+            let _ = child.kill().await;
+            event_sink.on_command_finished(false, -1, start_time.elapsed().as_millis() as u64);
+            return Ok(build_cancelled_outcome());
+        }
         Ok(Err(e)) => return Err(e.into()),
         Err(_) => {
             let _ = child.kill().await;
@@ -385,6 +605,9 @@ pub(super) async fn execute_run_command(
 
 const MAX_COMMAND_RESULT_CHARS: usize = 2000;
 
+/// How often a running command checks [`EventSink::is_cancelled`].
+const CANCEL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
 #[derive(Debug, Clone)]
 pub(super) struct RunCommandOutcome {
     pub content: String,
@@ -437,6 +660,14 @@ fn build_timeout_outcome() -> RunCommandOutcome {
     }
 }
 
+fn build_cancelled_outcome() -> RunCommandOutcome {
+    RunCommandOutcome {
+        content: "Command cancelled: the turn was aborted by the caller".to_string(),
+        is_error: true,
+        counts_as_failure: false,
+    }
+}
+
 #[cfg(test)]
 pub(super) fn timeout_outcome_for_test() -> RunCommandOutcome {
     build_timeout_outcome()
@@ -558,3 +789,99 @@ mod blocked_command_tests {
         assert!(check_dangerous_command("rm -rf ./dist").is_some());
     }
 }
+
+#[cfg(test)]
+mod command_context_tests {
+    use super::CommandContext;
+    use serde_json::json;
+
+    fn context(
+        args: serde_json::Value,
+        workspace: &std::path::Path,
+    ) -> crate::Result<CommandContext> {
+        CommandContext::from_args(&args, workspace)
+    }
+
+    #[test]
+    fn cwd_must_stay_inside_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path().join("ws");
+        std::fs::create_dir_all(ws.join("web")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("outside")).unwrap();
+
+        let ok = context(json!({"cwd": "web"}), &ws).unwrap();
+        assert_eq!(ok.cwd_display.as_deref(), Some("web"));
+        assert!(ok.cwd.unwrap().ends_with("web"));
+
+        let absolute_outside = tmp.path().join("outside").to_string_lossy().into_owned();
+        for escape in ["../outside", "web/../../outside", absolute_outside.as_str()] {
+            let err = context(json!({ "cwd": escape }), &ws).unwrap_err();
+            assert!(
+                err.to_string().contains("escapes workspace"),
+                "{escape}: {err}"
+            );
+        }
+        assert!(context(json!({"cwd": "missing"}), &ws).is_err());
+        std::fs::write(ws.join("file.txt"), "x").unwrap();
+        let err = context(json!({"cwd": "file.txt"}), &ws).unwrap_err();
+        assert!(err.to_string().contains("not a directory"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cwd_symlink_out_of_workspace_is_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path().join("ws");
+        std::fs::create_dir_all(&ws).unwrap();
+        std::fs::create_dir_all(tmp.path().join("outside")).unwrap();
+        std::os::unix::fs::symlink(tmp.path().join("outside"), ws.join("link")).unwrap();
+
+        let err = context(json!({"cwd": "link"}), &ws).unwrap_err();
+        assert!(err.to_string().contains("escapes workspace"));
+    }
+
+    #[test]
+    fn env_denylist_and_key_validation() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path();
+        for key in [
+            "PATH",
+            "path",
+            "LD_PRELOAD",
+            "HOME",
+            "DYLD_INSERT_LIBRARIES",
+            "NODE_OPTIONS",
+            "SKILLLITE_SANDBOX_LEVEL",
+        ] {
+            let err = context(json!({ "env": { key: "x" } }), ws).unwrap_err();
+            assert!(err.to_string().contains("cannot be overridden"), "{key}");
+        }
+        for key in ["1ABC", "A-B", "A B", ""] {
+            let err = context(json!({ "env": { key: "x" } }), ws).unwrap_err();
+            assert!(
+                err.to_string().contains("Invalid env variable name"),
+                "{key}"
+            );
+        }
+        let long = "v".repeat(super::MAX_ENV_VALUE_LEN + 1);
+        assert!(context(json!({ "env": { "BIG": long } }), ws).is_err());
+        assert!(context(json!({ "env": { "NUM": 3 } }), ws).is_err());
+    }
+
+    #[test]
+    fn describe_shows_cwd_and_env() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("web")).unwrap();
+        let ctx = context(
+            json!({"cwd": "web", "env": {"NODE_ENV": "production", "GREETING": "hi there"}}),
+            tmp.path(),
+        )
+        .unwrap();
+        assert_eq!(
+            ctx.describe("npm run build"),
+            "[cwd: web] GREETING='hi there' NODE_ENV=production npm run build"
+        );
+        let plain = context(json!({}), tmp.path()).unwrap();
+        assert_eq!(plain.describe("ls"), "ls");
+    }
+}
@@ -0,0 +1,63 @@
+//! get_skill_details: on-demand SKILL.md body, references and tool schemas for one skill.
+//!
+//! The system prompt only lists skills by name + one-line summary; this tool is how the
+//! model pulls the rest. Registered by the registry only when skills are loaded.
+
+use serde_json::{json, Value};
+
+use crate::prompt;
+use crate::skills::{self, LoadedSkill};
+use crate::types::{FunctionDef, ToolDefinition, ToolResult};
+
+pub const TOOL_NAME: &str = "get_skill_details";
+
+pub(super) fn tool_definition() -> ToolDefinition {
+    ToolDefinition {
+        tool_type: "function".to_string(),
+        function: FunctionDef {
+            name: TOOL_NAME.to_string(),
+            description: "Get the full documentation of one skill: its SKILL.md body (usage, examples), reference docs, and the parameter schema of each of its tools. Call this before using a skill listed under Available Skills.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Skill name as listed under Available Skills (or one of its tool names)"
+                    }
+                },
+                "required": ["name"]
+            }),
+        },
+    }
+}
+
+pub fn execute_get_skill_details(arguments: &str, skills: &[LoadedSkill]) -> ToolResult {
+    let name = serde_json::from_str::<Value>(arguments)
+        .ok()
+        .and_then(|v| v.get("name").and_then(|n| n.as_str()).map(str::to_string))
+        .unwrap_or_default();
+    let found = skills::find_skill_by_name(skills, &name)
+        .or_else(|| skills::find_skill_by_tool_name(skills, &name));
+    let (content, is_error) = match found {
+        Some(skill) => (prompt::get_skill_details(skill), false),
+        None => {
+            let mut names: Vec<&str> = skills.iter().map(|s| s.name.as_str()).collect();
+            names.sort_unstable();
+            (
+                format!(
+                    "Unknown skill: '{}'. Available skills: {}",
+                    name,
+                    names.join(", ")
+                ),
+                true,
+            )
+        }
+    };
+    ToolResult {
+        tool_call_id: String::new(),
+        tool_name: TOOL_NAME.to_string(),
+        content,
+        is_error,
+        counts_as_failure: is_error,
+    }
+}
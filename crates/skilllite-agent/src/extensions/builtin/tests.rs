@@ -1,6 +1,10 @@
 //! Tests for the builtin tools module.
 
 use super::*;
+use crate::extensions::ExtensionRegistry;
+
+/// Serializes tests that point `SKILLLITE_OUTPUT_DIR` at their own temp dir.
+static OUTPUT_DIR_ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 
 /// 测试启动时禁用审计，避免 edit 操作污染真实 audit 日志。
 #[ctor::ctor]
@@ -43,7 +47,7 @@ fn test_search_replace_first_occurrence() {
         "new_string": "hi world",
         "replace_all": false
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result
         .content
@@ -67,7 +71,7 @@ fn test_search_replace_requires_unique_match_by_default() {
         "old_string": "hello",
         "new_string": "hi"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(result.is_error);
     assert!(result
         .content
@@ -87,7 +91,7 @@ fn test_search_replace_all() {
         "new_string": "qux",
         "replace_all": true
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result
         .content
@@ -109,7 +113,7 @@ fn test_search_replace_old_string_not_found() {
         "old_string": "xyz",
         "new_string": "abc"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(result.is_error);
     assert!(result.content.contains("old_string not found"));
 }
@@ -126,7 +130,7 @@ fn test_search_replace_blocks_sensitive_path() {
         "old_string": "KEY=value",
         "new_string": "KEY=modified"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(result.is_error);
     assert!(result.content.contains("Blocked"));
 }
@@ -144,7 +148,7 @@ fn test_search_replace_normalize_whitespace_trailing() {
         "new_string": "hi",
         "normalize_whitespace": true
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -165,7 +169,7 @@ fn test_search_replace_normalize_whitespace_replace_all() {
         "replace_all": true,
         "normalize_whitespace": true
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -185,7 +189,7 @@ fn test_search_replace_normalize_whitespace_literal_replacement() {
         "new_string": "price: $200",
         "normalize_whitespace": true
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -206,7 +210,7 @@ fn test_search_replace_output_directory() {
         "old_string": "Old Title",
         "new_string": "New Title"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -225,7 +229,7 @@ fn test_preview_edit_does_not_write_file() {
         "old_string": "alpha",
         "new_string": "gamma"
     });
-    let result = execute_builtin_tool("preview_edit", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("preview_edit", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("Preview edit"));
     assert!(result.content.contains("\"changed\": true"));
@@ -245,7 +249,7 @@ fn test_read_file_with_line_numbers() {
     std::fs::write(&file_path, "line1\nline2\nline3\n").unwrap();
 
     let args = serde_json::json!({ "path": "test.txt" });
-    let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("1|line1"));
     assert!(result.content.contains("2|line2"));
@@ -264,7 +268,7 @@ fn test_read_file_with_range() {
         "start_line": 2,
         "end_line": 4
     });
-    let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("2|bbb"));
     assert!(result.content.contains("3|ccc"));
@@ -285,11 +289,37 @@ fn test_read_file_range_beyond_end() {
         "path": "test.txt",
         "start_line": 100
     });
-    let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("File has 1 lines"));
 }
 
+#[cfg(unix)]
+#[test]
+fn test_file_tools_reject_symlink_escaping_workspace() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("ws");
+    let outside = tmp.path().join("outside");
+    std::fs::create_dir_all(&workspace).unwrap();
+    std::fs::create_dir_all(&outside).unwrap();
+    std::fs::write(outside.join("hosts"), "127.0.0.1 localhost\n").unwrap();
+    std::os::unix::fs::symlink(&outside, workspace.join("etc")).unwrap();
+
+    let read = serde_json::json!({ "path": "etc/hosts" });
+    let result = execute_builtin_tool("read_file", &read.to_string(), &workspace, None, None);
+    assert!(result.is_error);
+    assert!(
+        result.content.contains("escapes workspace"),
+        "{}",
+        result.content
+    );
+
+    let write = serde_json::json!({ "path": "etc/new.txt", "content": "x" });
+    let result = execute_builtin_tool("write_file", &write.to_string(), &workspace, None, None);
+    assert!(result.is_error);
+    assert!(!outside.join("new.txt").exists());
+}
+
 // ─── P0: read_file blocks .env/.key/.git/config，其他文件过滤敏感信息 ───
 
 #[test]
@@ -302,7 +332,7 @@ fn test_read_file_blocks_sensitive_paths() {
 
     for path in [".env", "secret.key", "cert.pem"] {
         let args = serde_json::json!({ "path": path });
-        let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None);
+        let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None, None);
         assert!(result.is_error);
         assert!(result.content.contains("Blocked: reading sensitive file"));
         assert!(result.content.contains(path));
@@ -325,7 +355,7 @@ fn test_read_file_redacts_sensitive_in_other_files() {
     .unwrap();
 
     let args = serde_json::json!({ "path": "config.json" });
-    let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("read_file", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains(r#""api_key": "[REDACTED]""#));
     assert!(result.content.contains(r#""password": "[REDACTED]""#));
@@ -333,7 +363,7 @@ fn test_read_file_redacts_sensitive_in_other_files() {
     assert!(result.content.contains("Sensitive values"));
 
     let args2 = serde_json::json!({ "path": "README.md" });
-    let result2 = execute_builtin_tool("read_file", &args2.to_string(), workspace, None);
+    let result2 = execute_builtin_tool("read_file", &args2.to_string(), workspace, None, None);
     assert!(!result2.is_error);
     // API_KEY=xxx 被脱敏为 API_KEY=[REDACTED]，或 sk-xxx 被脱敏为 sk-[REDACTED]
     assert!(
@@ -357,7 +387,7 @@ fn test_insert_lines_at_beginning() {
         "line": 0,
         "content": "inserted"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("Successfully inserted"));
 
@@ -377,7 +407,7 @@ fn test_insert_lines_in_middle() {
         "line": 1,
         "content": "new_line"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -396,7 +426,7 @@ fn test_insert_lines_at_end() {
         "line": 2,
         "content": "last_line"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -415,7 +445,7 @@ fn test_insert_lines_multiline_content() {
         "line": 1,
         "content": "x1\nx2\nx3"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("\"lines_inserted\": 3"));
 
@@ -435,7 +465,7 @@ fn test_insert_lines_beyond_end_fails() {
         "line": 99,
         "content": "nope"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(result.is_error);
     assert!(result.content.contains("beyond end of file"));
 }
@@ -452,7 +482,7 @@ fn test_insert_lines_no_trailing_newline() {
         "line": 2,
         "content": "end"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -474,7 +504,7 @@ fn test_search_replace_dry_run_no_write() {
         "new_string": "gamma",
         "dry_run": true
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("Preview edit"));
     assert!(result.content.contains("no changes written"));
@@ -498,7 +528,7 @@ fn test_search_replace_match_type_exact() {
         "old_string": "hello world",
         "new_string": "hi world"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("\"match_type\": \"exact\""));
 }
@@ -522,7 +552,7 @@ fn test_fuzzy_match_indent_difference() {
         "old_string": "  let x = 1;\n  let y = 2;",
         "new_string": "    let a = 10;\n    let b = 20;"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error, "Error: {}", result.content);
     assert!(result
         .content
@@ -550,7 +580,7 @@ fn test_fuzzy_match_trailing_whitespace_auto() {
         "new_string": "hi\nnext"
     });
     // Exact match succeeds here (substring match)
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("\"match_type\": \"exact\""));
 }
@@ -572,7 +602,7 @@ fn test_fuzzy_match_multiline_indent() {
         "old_string": "x = 1\ny = 2",
         "new_string": "    a = 10\n    b = 20"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result
         .content
@@ -598,7 +628,7 @@ fn test_fuzzy_match_blank_line_difference() {
         "old_string": "aaa\nbbb",
         "new_string": "xxx\nyyy"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result
         .content
@@ -627,7 +657,7 @@ fn test_fuzzy_match_similarity() {
         "old_string": "fn calculate_totl(items: &[Item]) -> f64 {\n    items.iter().map(|i| i.price).sum()\n}",
         "new_string": "fn calculate_total(items: &[Item]) -> u64 {\n    items.iter().map(|i| i.price as u64).sum()\n}"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("similarity("));
 
@@ -647,7 +677,7 @@ fn test_fuzzy_match_low_similarity_fails() {
         "old_string": "nothing even close to matching this at all",
         "new_string": "replacement"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(result.is_error);
     assert!(result.content.contains("old_string not found"));
 }
@@ -666,7 +696,7 @@ fn test_insert_lines_blocks_sensitive_path() {
         "line": 0,
         "content": "INJECTED=bad"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(result.is_error);
     assert!(result.content.contains("Blocked"));
 }
@@ -681,7 +711,7 @@ fn test_grep_files_basic_match() {
     std::fs::write(workspace.join("b.txt"), "hello rust\nbaz\n").unwrap();
 
     let args = serde_json::json!({ "pattern": "hello" });
-    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("a.txt:1:hello world"));
     assert!(result.content.contains("b.txt:1:hello rust"));
@@ -699,7 +729,7 @@ fn test_grep_files_regex_pattern() {
     .unwrap();
 
     let args = serde_json::json!({ "pattern": r"fn\s+\w+" });
-    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("code.rs:1:fn main()"));
 }
@@ -712,7 +742,7 @@ fn test_grep_files_include_filter() {
     std::fs::write(workspace.join("b.py"), "match_me\n").unwrap();
 
     let args = serde_json::json!({ "pattern": "match_me", "include": "*.rs" });
-    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("a.rs:1:match_me"));
     assert!(!result.content.contains("b.py"));
@@ -725,7 +755,7 @@ fn test_grep_files_no_match() {
     std::fs::write(workspace.join("a.txt"), "hello\n").unwrap();
 
     let args = serde_json::json!({ "pattern": "xyz_not_here" });
-    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("No matches found"));
 }
@@ -740,7 +770,7 @@ fn test_grep_files_skips_git_dir() {
     std::fs::write(workspace.join("src.txt"), "find_me\n").unwrap();
 
     let args = serde_json::json!({ "pattern": "find_me" });
-    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("src.txt"));
     assert!(!result.content.contains(".git"));
@@ -755,7 +785,7 @@ fn test_grep_files_recursive() {
     std::fs::write(sub.join("nested.txt"), "deep_match\n").unwrap();
 
     let args = serde_json::json!({ "pattern": "deep_match" });
-    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("sub/deep/nested.txt:1:deep_match"));
 }
@@ -766,11 +796,204 @@ fn test_grep_files_invalid_regex() {
     let workspace = tmp.path();
 
     let args = serde_json::json!({ "pattern": "[invalid" });
-    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("grep_files", &args.to_string(), workspace, None, None);
     assert!(result.is_error);
     assert!(result.content.contains("Invalid regex"));
 }
 
+// ─── list_directory: glob / depth / sort / cap ───────────────────────
+
+fn list_dir_workspace() -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    for (rel, body) in [
+        ("data/2024/q1.csv", "a,b\n1,2\n"),
+        ("data/2024/deep/old.csv", "x\n"),
+        ("data/summary.csv", "a,b,c,d,e,f,g,h\n"),
+        ("data/notes.md", "notes\n"),
+        ("node_modules/pkg/index.csv", "x\n"),
+        (".cache/hidden.csv", "x\n"),
+    ] {
+        let path = tmp.path().join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, body).unwrap();
+    }
+    tmp
+}
+
+fn trailing_summary(content: &str) -> serde_json::Value {
+    serde_json::from_str(content.lines().last().unwrap()).unwrap()
+}
+
+#[test]
+fn test_list_directory_pattern_depth_and_size_sort() {
+    let tmp = list_dir_workspace();
+    let args = serde_json::json!({
+        "path": "data",
+        "pattern": "*.csv",
+        "max_depth": 2,
+        "sort_by": "size"
+    });
+    let result = execute_builtin_tool("list_directory", &args.to_string(), tmp.path(), None, None);
+    assert!(!result.is_error, "{}", result.content);
+    let lines: Vec<&str> = result.content.lines().collect();
+    assert!(lines[0].starts_with("summary.csv"));
+    assert!(lines[1].starts_with("2024/q1.csv"));
+    assert!(!result.content.contains("old.csv"));
+    assert!(!result.content.contains("notes.md"));
+    assert_eq!(
+        trailing_summary(&result.content),
+        serde_json::json!({ "total": 2, "truncated": false })
+    );
+}
+
+#[test]
+fn test_list_directory_skips_heavy_and_hidden_dirs_unless_asked() {
+    let tmp = list_dir_workspace();
+    let args = serde_json::json!({ "pattern": "*.csv", "recursive": true });
+    let result = execute_builtin_tool("list_directory", &args.to_string(), tmp.path(), None, None);
+    assert!(!result.content.contains("index.csv"));
+    assert!(!result.content.contains("hidden.csv"));
+
+    let args = serde_json::json!({ "pattern": "*.csv", "recursive": true, "include_hidden": true });
+    let result = execute_builtin_tool("list_directory", &args.to_string(), tmp.path(), None, None);
+    assert!(result.content.contains(".cache/hidden.csv"));
+    assert!(!result.content.contains("index.csv"));
+
+    let args = serde_json::json!({ "dirs_only": true, "files_only": true });
+    let result = execute_builtin_tool("list_directory", &args.to_string(), tmp.path(), None, None);
+    assert!(result.is_error);
+}
+
+#[test]
+fn test_list_directory_tree_output_is_capped() {
+    let tmp = list_dir_workspace();
+    let key = "SKILLLITE_LIST_DIR_MAX_ENTRIES";
+    let saved = std::env::var(key).ok();
+    std::env::set_var(key, "3");
+    let args = serde_json::json!({ "path": "data", "recursive": true });
+    let result = execute_builtin_tool("list_directory", &args.to_string(), tmp.path(), None, None);
+    match saved {
+        Some(ref v) => std::env::set_var(key, v),
+        None => std::env::remove_var(key),
+    }
+    assert!(!result.is_error);
+    assert!(result.content.starts_with("data/\n├── 2024/"));
+    assert!(result.content.contains("[Showing 3 of 6 entries"));
+    assert_eq!(
+        trailing_summary(&result.content),
+        serde_json::json!({ "total": 6, "truncated": true })
+    );
+}
+
+// ─── write_file: shrink guard + stale-write preconditions ─────────────
+
+/// Serial env mutation: the shrink ratio is read from env on each call.
+#[test]
+fn test_write_file_shrink_guard_ratio_boundary() {
+    let key = "SKILLLITE_WRITE_FILE_MIN_SHRINK_RATIO";
+    let saved = std::env::var(key).ok();
+    std::env::remove_var(key);
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path();
+    let original = "x".repeat(10_000);
+    let write = |content: String, allow_shrink: bool| {
+        std::fs::write(workspace.join("big.txt"), &original).unwrap();
+        let args = serde_json::json!({
+            "path": "big.txt",
+            "content": content,
+            "allow_shrink": allow_shrink
+        });
+        execute_builtin_tool("write_file", &args.to_string(), workspace, None, None)
+    };
+
+    let blocked = write("y".repeat(2_999), false);
+    assert!(blocked.is_error);
+    assert!(blocked.content.contains("allow_shrink: true"));
+    assert!(blocked.content.contains("search_replace"));
+    assert_eq!(
+        std::fs::read_to_string(workspace.join("big.txt")).unwrap(),
+        original
+    );
+
+    let at_limit = write("y".repeat(3_000), false);
+    assert!(!at_limit.is_error, "{}", at_limit.content);
+    assert!(at_limit.content.contains("previous version backed up"));
+
+    let allowed = write("short".to_string(), true);
+    assert!(!allowed.is_error, "{}", allowed.content);
+    assert_eq!(
+        std::fs::read_to_string(workspace.join("big.txt")).unwrap(),
+        "short"
+    );
+
+    std::env::set_var(key, "0.5");
+    assert!(write("y".repeat(4_999), false).is_error);
+    match saved {
+        Some(ref v) => std::env::set_var(key, v),
+        None => std::env::remove_var(key),
+    }
+
+    // Appends and small files are never guarded
+    std::fs::write(workspace.join("small.txt"), "a".repeat(500)).unwrap();
+    let args = serde_json::json!({ "path": "small.txt", "content": "b" });
+    assert!(!execute_builtin_tool("write_file", &args.to_string(), workspace, None, None).is_error);
+}
+
+#[test]
+fn test_write_file_expected_hash_and_contains_preconditions() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path();
+    std::fs::write(workspace.join("cfg.toml"), "name = \"a\"\n").unwrap();
+
+    let read = execute_builtin_tool(
+        "read_file",
+        &serde_json::json!({ "path": "cfg.toml" }).to_string(),
+        workspace,
+        None,
+        None,
+    );
+    let hash = read
+        .content
+        .rsplit("[sha256: ")
+        .next()
+        .unwrap()
+        .trim_end_matches(']')
+        .to_string();
+    assert_eq!(hash.len(), 16);
+
+    // Someone else edits the file after it was read
+    std::fs::write(workspace.join("cfg.toml"), "name = \"b\"\n").unwrap();
+    let args = serde_json::json!({
+        "path": "cfg.toml",
+        "content": "name = \"c\"\n",
+        "expected_hash": hash
+    });
+    let stale = execute_builtin_tool("write_file", &args.to_string(), workspace, None, None);
+    assert!(stale.is_error);
+    assert!(stale.content.contains("changed since it was read"));
+
+    let args = serde_json::json!({
+        "path": "cfg.toml",
+        "content": "name = \"c\"\n",
+        "expected_contains": "name = \"a\""
+    });
+    let missing = execute_builtin_tool("write_file", &args.to_string(), workspace, None, None);
+    assert!(missing.is_error);
+    assert!(missing.content.contains("expected_contains"));
+
+    let args = serde_json::json!({
+        "path": "cfg.toml",
+        "content": "name = \"c\"\n",
+        "expected_contains": "name = \"b\""
+    });
+    let ok = execute_builtin_tool("write_file", &args.to_string(), workspace, None, None);
+    assert!(!ok.is_error, "{}", ok.content);
+    assert_eq!(
+        std::fs::read_to_string(workspace.join("cfg.toml")).unwrap(),
+        "name = \"c\"\n"
+    );
+}
+
 // ─── Phase II: auto-backup ───────────────────────────────────────────
 
 #[test]
@@ -785,7 +1008,7 @@ fn test_search_replace_creates_backup() {
         "old_string": "original",
         "new_string": "modified"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("\"backup\""));
     assert!(result.content.contains("edit-backups"));
@@ -803,7 +1026,7 @@ fn test_insert_lines_creates_backup() {
         "line": 0,
         "content": "prepended"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("\"backup\""));
     assert!(result.content.contains("edit-backups"));
@@ -822,7 +1045,7 @@ fn test_dry_run_no_backup() {
         "new_string": "gamma",
         "dry_run": true
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("\"backup\": null"));
 }
@@ -846,7 +1069,7 @@ fn test_validation_warns_on_invalid_json() {
         "old_string": "{\"key\": \"value\"}",
         "new_string": "{\"key\": \"value\""
     });
-    let result = execute_builtin_tool("search_replace", &args2.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args2.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("JSON syntax warning"));
 }
@@ -863,7 +1086,7 @@ fn test_validation_warns_on_unmatched_bracket() {
         "old_string": "fn main() {\n    println!(\"hi\");\n}",
         "new_string": "fn main() {\n    println!(\"hi\");\n"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("validation_warning"));
     assert!(result.content.contains("Unclosed"));
@@ -881,7 +1104,7 @@ fn test_validation_no_warning_on_valid_code() {
         "old_string": "1 + 2",
         "new_string": "3 + 4"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("\"validation_warning\": null"));
 }
@@ -899,7 +1122,7 @@ fn test_search_replace_multibyte_content_no_panic() {
         "old_string": "**轻量级 AI Agent 安全引擎**，内置原生系统级沙箱，零依赖，本地执行。",
         "new_string": "**A lightweight AI Agent secure engine** with built-in sandbox, zero deps."
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error, "Error: {}", result.content);
     assert!(result.content.contains("\"match_type\": \"exact\""));
 
@@ -919,7 +1142,7 @@ fn test_validation_warns_on_invalid_yaml() {
         "old_string": "nested:\n  a: 1",
         "new_string": "nested:\n  a: 1\n  b: [unclosed"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
     assert!(result.content.contains("YAML syntax warning") || result.content.contains("Unclosed"));
 }
@@ -942,7 +1165,7 @@ fn test_failure_hint_shows_closest_match_context() {
         "old_string": "completely_unrelated_string_that_wont_match_anything_at_all_xyz"
     ,   "new_string": "replacement"
     });
-    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("search_replace", &args.to_string(), workspace, None, None);
     assert!(result.is_error);
     assert!(result.content.contains("Closest match found at line"));
     assert!(result.content.contains("similarity:"));
@@ -967,7 +1190,7 @@ fn test_insert_lines_auto_indent() {
         "line": 2,
         "content": "let z = 3;"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -990,7 +1213,7 @@ fn test_insert_lines_no_indent_when_content_already_indented() {
         "line": 1,
         "content": "    y = 2"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -1013,7 +1236,7 @@ fn test_insert_lines_no_indent_at_top_level() {
         "line": 1,
         "content": "new_line"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -1032,7 +1255,7 @@ fn test_insert_lines_multiline_auto_indent() {
         "line": 1,
         "content": "let y = 2;\nlet z = 3;"
     });
-    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None);
+    let result = execute_builtin_tool("insert_lines", &args.to_string(), workspace, None, None);
     assert!(!result.is_error);
 
     let content = std::fs::read_to_string(&file_path).unwrap();
@@ -1091,7 +1314,7 @@ async fn test_run_command_sensitive_cat_env_cancelled_when_denied() {
         "cat .env"
     };
     let args = serde_json::json!({ "command": read_env });
-    let outcome = run_command::execute_run_command(&args, workspace, &mut sink)
+    let outcome = run_command::execute_run_command(&args, workspace, &mut sink, None)
         .await
         .unwrap();
     assert!(!outcome.is_error);
@@ -1114,7 +1337,7 @@ async fn test_run_command_sensitive_cat_env_runs_when_approved() {
         "cat .env"
     };
     let args = serde_json::json!({ "command": read_env });
-    let outcome = run_command::execute_run_command(&args, workspace, &mut sink)
+    let outcome = run_command::execute_run_command(&args, workspace, &mut sink, None)
         .await
         .unwrap();
     assert!(!outcome.is_error);
@@ -1125,6 +1348,50 @@ async fn test_run_command_sensitive_cat_env_runs_when_approved() {
     );
 }
 
+#[cfg(not(windows))]
+#[tokio::test]
+async fn test_run_command_kills_child_when_sink_cancelled() {
+    use super::run_command;
+    use crate::types::EventSink;
+
+    struct CancelledSink {
+        finished: Vec<(bool, i32)>,
+    }
+
+    impl EventSink for CancelledSink {
+        fn on_text(&mut self, _text: &str) {}
+        fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+        fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+        fn on_command_finished(&mut self, success: bool, exit_code: i32, _duration_ms: u64) {
+            self.finished.push((success, exit_code));
+        }
+        fn on_confirmation_request(
+            &mut self,
+            _request: &crate::types::ConfirmationRequest,
+        ) -> bool {
+            true
+        }
+        fn is_cancelled(&self) -> bool {
+            true
+        }
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let args = serde_json::json!({ "command": "sleep 30" });
+    let mut sink = CancelledSink {
+        finished: Vec::new(),
+    };
+    let started = std::time::Instant::now();
+    let outcome = run_command::execute_run_command(&args, tmp.path(), &mut sink, None)
+        .await
+        .unwrap();
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert!(outcome.is_error);
+    assert!(!outcome.counts_as_failure);
+    assert!(outcome.content.contains("cancelled"));
+    assert_eq!(sink.finished, vec![(false, -1)]);
+}
+
 #[tokio::test]
 async fn test_run_command_streams_output_and_returns_summary() {
     use super::run_command;
@@ -1171,7 +1438,7 @@ async fn test_run_command_streams_output_and_returns_summary() {
         finished: Vec::new(),
     };
 
-    let outcome = run_command::execute_run_command(&args, workspace, &mut sink)
+    let outcome = run_command::execute_run_command(&args, workspace, &mut sink, None)
         .await
         .unwrap();
     let result = outcome.content;
@@ -1196,6 +1463,91 @@ async fn test_run_command_streams_output_and_returns_summary() {
     assert!(!result.contains("[stdout]\nhello"));
 }
 
+#[tokio::test]
+async fn test_run_command_uses_cwd_and_env_and_reports_them() {
+    use super::run_command;
+    use crate::types::EventSink;
+
+    struct StartedSink {
+        started: Vec<String>,
+        confirmations: Vec<String>,
+    }
+
+    impl EventSink for StartedSink {
+        fn on_text(&mut self, _text: &str) {}
+        fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+        fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+        fn on_command_started(&mut self, command: &str) {
+            self.started.push(command.to_string());
+        }
+        fn on_confirmation_request(&mut self, request: &crate::types::ConfirmationRequest) -> bool {
+            self.confirmations.push(request.prompt.clone());
+            true
+        }
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path();
+    std::fs::create_dir_all(workspace.join("web")).unwrap();
+    std::fs::write(workspace.join("web").join("marker.txt"), "x").unwrap();
+    let cmd = if cfg!(windows) {
+        "echo %APP_MODE% & dir /b"
+    } else {
+        "echo \"$APP_MODE\"; ls"
+    };
+    let args = serde_json::json!({
+        "command": cmd,
+        "cwd": "web",
+        "env": {"APP_MODE": "production"}
+    });
+    let mut sink = StartedSink {
+        started: Vec::new(),
+        confirmations: Vec::new(),
+    };
+
+    let outcome = run_command::execute_run_command(&args, workspace, &mut sink, None)
+        .await
+        .unwrap();
+
+    assert!(!outcome.is_error, "{}", outcome.content);
+    assert_eq!(
+        sink.started,
+        vec![format!("[cwd: web] APP_MODE=production {}", cmd)]
+    );
+    assert!(sink
+        .confirmations
+        .iter()
+        .all(|prompt| prompt.contains("[cwd: web] APP_MODE=production")));
+    assert!(
+        outcome.content.contains("marker.txt"),
+        "{}",
+        outcome.content
+    );
+}
+
+#[tokio::test]
+async fn test_run_command_rejects_cwd_escape_and_denied_env() {
+    use super::run_command;
+    use crate::types::SilentEventSink;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("ws");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let mut sink = SilentEventSink;
+
+    let escape = serde_json::json!({ "command": "echo hi", "cwd": ".." });
+    let err = run_command::execute_run_command(&escape, &workspace, &mut sink, None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("escapes workspace"));
+
+    let preload = serde_json::json!({ "command": "echo hi", "env": {"LD_PRELOAD": "/tmp/x.so"} });
+    let err = run_command::execute_run_command(&preload, &workspace, &mut sink, None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("cannot be overridden"));
+}
+
 #[tokio::test]
 async fn test_run_command_success_preview_is_compact() {
     use super::run_command;
@@ -1211,7 +1563,7 @@ async fn test_run_command_success_preview_is_compact() {
     let args = serde_json::json!({ "command": preview_cmd });
     let mut sink = SilentEventSink;
 
-    let outcome = run_command::execute_run_command(&args, workspace, &mut sink)
+    let outcome = run_command::execute_run_command(&args, workspace, &mut sink, None)
         .await
         .unwrap();
     let result = outcome.content;
@@ -1250,7 +1602,8 @@ async fn test_execute_async_builtin_run_command_marks_nonzero_exit_as_error() {
     let arguments = serde_json::json!({ "command": fail_cmd }).to_string();
     let mut sink = SilentEventSink;
 
-    let result = execute_async_builtin_tool("run_command", &arguments, workspace, &mut sink).await;
+    let result =
+        execute_async_builtin_tool("run_command", &arguments, workspace, &mut sink, None).await;
 
     assert!(result.is_error);
     assert!(!result.counts_as_failure);
@@ -1269,7 +1622,8 @@ async fn test_execute_async_builtin_run_command_marks_zero_exit_as_success() {
     .to_string();
     let mut sink = SilentEventSink;
 
-    let result = execute_async_builtin_tool("run_command", &arguments, workspace, &mut sink).await;
+    let result =
+        execute_async_builtin_tool("run_command", &arguments, workspace, &mut sink, None).await;
 
     assert!(!result.is_error);
     assert!(!result.counts_as_failure);
@@ -1329,7 +1683,7 @@ fn test_preview_server_emits_started_and_ready_events() {
         preview_failed: Vec::new(),
     };
 
-    let result = preview::execute_preview_server(&args, workspace, &mut sink).unwrap();
+    let result = preview::execute_preview_server(&args, workspace, &mut sink, None).unwrap();
 
     assert!(result.contains("Preview server started at"));
     assert_eq!(sink.preview_started.len(), 1);
@@ -1337,6 +1691,90 @@ fn test_preview_server_emits_started_and_ready_events() {
     assert!(sink.preview_failed.is_empty());
 }
 
+#[test]
+fn test_preview_server_reuses_running_server_for_same_directory() {
+    use super::preview;
+    use crate::types::SilentEventSink;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path();
+    std::fs::create_dir_all(workspace.join("site")).unwrap();
+    std::fs::write(workspace.join("site/index.html"), "<h1>ok</h1>").unwrap();
+
+    let free_port = {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let args = serde_json::json!({ "path": "site", "port": free_port, "open_browser": false });
+    let first =
+        preview::execute_preview_server(&args, workspace, &mut SilentEventSink, None).unwrap();
+    let second =
+        preview::execute_preview_server(&args, workspace, &mut SilentEventSink, None).unwrap();
+
+    let url = |out: &str| {
+        out.lines()
+            .find_map(|l| l.strip_prefix("Open in browser: "))
+            .map(str::to_string)
+    };
+    assert!(first.contains("Preview server started at"));
+    assert!(second.contains("already running"));
+    assert_eq!(url(&first), url(&second));
+
+    let stop = serde_json::json!({ "path": "site", "stop": true });
+    let stopped =
+        preview::execute_preview_server(&stop, workspace, &mut SilentEventSink, None).unwrap();
+    assert!(stopped.contains("Stopped preview server"));
+}
+
+#[test]
+fn test_preview_server_refuses_sensitive_paths() {
+    use super::preview;
+    use crate::types::SilentEventSink;
+    use std::io::{Read, Write};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path();
+    std::fs::create_dir_all(workspace.join("site")).unwrap();
+    std::fs::create_dir_all(workspace.join(".secrets")).unwrap();
+    std::fs::write(workspace.join("site/index.html"), "<h1>ok</h1>").unwrap();
+    std::fs::write(workspace.join("site/.env"), "API_KEY=secret").unwrap();
+
+    for path in [".secrets", "."] {
+        let args = serde_json::json!({ "path": path, "open_browser": false });
+        assert!(
+            preview::execute_preview_server(&args, workspace, &mut SilentEventSink, None).is_err(),
+            "serving '{}' should be refused",
+            path
+        );
+    }
+
+    let free_port = {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let args = serde_json::json!({ "path": "site", "port": free_port, "open_browser": false });
+    let out =
+        preview::execute_preview_server(&args, workspace, &mut SilentEventSink, None).unwrap();
+    let port: u16 = out
+        .lines()
+        .find_map(|l| l.strip_prefix("Port: "))
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream
+        .write_all(b"GET /.env HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response);
+    assert!(response.starts_with("HTTP/1.1 403"), "got: {}", response);
+    assert!(!response.contains("API_KEY"));
+
+    let stop = serde_json::json!({ "path": "site", "stop": true });
+    preview::execute_preview_server(&stop, workspace, &mut SilentEventSink, None).unwrap();
+}
+
 #[tokio::test]
 async fn test_delegate_to_swarm_emits_failed_only_when_unconfigured() {
     use super::delegate_swarm;
@@ -1390,3 +1828,347 @@ async fn test_delegate_to_swarm_emits_failed_only_when_unconfigured() {
     assert!(sink.swarm_progress.is_empty());
     assert_eq!(sink.swarm_failed.len(), 1);
 }
+
+#[test]
+fn test_file_history_skips_output_dir_writes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let chat_root = tmp.path().join("chat");
+    let workspace = tmp.path().join("ws");
+    let output_dir = workspace.join("output");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    std::fs::write(output_dir.join("report.md"), "draft\n").unwrap();
+    std::fs::write(workspace.join("notes.txt"), "todo\n").unwrap();
+
+    let key = "SKILLLITE_OUTPUT_DIR";
+    let saved = std::env::var(key).ok();
+    std::env::set_var(key, &output_dir);
+    let _serial = OUTPUT_DIR_ENV_LOCK
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let session = ToolSession::new(&chat_root, "s-history");
+
+    let write = |path: &str, content: &str| {
+        let args = serde_json::json!({ "path": path, "content": content });
+        execute_builtin_tool(
+            "write_file",
+            &args.to_string(),
+            &workspace,
+            None,
+            Some(&session),
+        )
+    };
+    assert!(!write("output/report.md", "final\n").is_error);
+    let args = serde_json::json!({
+        "path": "output/report.md",
+        "old_string": "final",
+        "new_string": "final v2"
+    });
+    let result = execute_builtin_tool(
+        "search_replace",
+        &args.to_string(),
+        &workspace,
+        None,
+        Some(&session),
+    );
+    assert!(!result.is_error, "{}", result.content);
+    assert!(!write("notes.txt", "done\n").is_error);
+
+    match saved {
+        Some(ref v) => std::env::set_var(key, v),
+        None => std::env::remove_var(key),
+    }
+
+    let records = skilllite_executor::file_history::read_records(&chat_root, "s-history").unwrap();
+    assert!(records.iter().all(|r| !r.path.contains("report.md")));
+    assert!(records.iter().any(|r| r.path.ends_with("notes.txt")));
+
+    let changes =
+        skilllite_executor::file_history::changes_since(&chat_root, "s-history", &workspace, 0)
+            .unwrap();
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0]
+        .diff
+        .as_deref()
+        .unwrap()
+        .contains("-todo\n+done\n"));
+
+    let outcome = revert_session_file(&chat_root, "s-history", &workspace, "notes.txt").unwrap();
+    assert_eq!(
+        outcome,
+        skilllite_executor::file_history::RevertOutcome::Restored
+    );
+    assert_eq!(
+        std::fs::read_to_string(workspace.join("notes.txt")).unwrap(),
+        "todo\n"
+    );
+    assert!(revert_session_file(&chat_root, "s-history", &workspace, ".env").is_err());
+}
+
+/// Registry as the agent loop builds it for one run of `session_key`.
+fn session_registry(chat_root: &std::path::Path, session_key: &str) -> ExtensionRegistry<'static> {
+    ExtensionRegistry::builder(false, false, &[])
+        .with_tool_session(Some(ToolSession::new(chat_root, session_key)))
+        .register(get_builtin_tools())
+        .build()
+}
+
+fn write_via(
+    registry: &ExtensionRegistry<'_>,
+    workspace: &std::path::Path,
+    path: &str,
+    content: &str,
+) {
+    let args = serde_json::json!({ "path": path, "content": content });
+    let result = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(registry.execute(
+            "write_file",
+            &args.to_string(),
+            workspace,
+            &mut crate::types::SilentEventSink,
+            None,
+            None,
+        ));
+    assert!(!result.is_error, "{}", result.content);
+}
+
+fn recorded_paths(chat_root: &std::path::Path, session_key: &str) -> Vec<String> {
+    skilllite_executor::file_history::read_records(chat_root, session_key)
+        .unwrap()
+        .into_iter()
+        .map(|r| r.path)
+        .collect()
+}
+
+#[test]
+fn test_nested_run_does_not_end_the_outer_session() {
+    let tmp = tempfile::tempdir().unwrap();
+    let chat_root = tmp.path().join("chat");
+    let workspace = tmp.path().join("ws");
+    std::fs::create_dir_all(&workspace).unwrap();
+    std::fs::write(workspace.join("a.txt"), "a0\n").unwrap();
+    std::fs::write(workspace.join("b.txt"), "b0\n").unwrap();
+    std::fs::write(workspace.join("c.txt"), "c0\n").unwrap();
+
+    let outer = session_registry(&chat_root, "s-outer");
+    write_via(&outer, &workspace, "a.txt", "a1\n");
+    {
+        // Memory flush: a nested agent run in the middle of the outer one.
+        let inner = session_registry(&chat_root, "s-inner");
+        write_via(&inner, &workspace, "b.txt", "b1\n");
+    }
+    write_via(&outer, &workspace, "c.txt", "c1\n");
+
+    let outer_paths = recorded_paths(&chat_root, "s-outer");
+    assert_eq!(outer_paths.len(), 2, "{outer_paths:?}");
+    assert!(outer_paths[0].ends_with("a.txt") && outer_paths[1].ends_with("c.txt"));
+    let inner_paths = recorded_paths(&chat_root, "s-inner");
+    assert_eq!(inner_paths.len(), 1, "{inner_paths:?}");
+    assert!(inner_paths[0].ends_with("b.txt"));
+}
+
+#[test]
+fn test_concurrent_sessions_record_under_their_own_key() {
+    let tmp = tempfile::tempdir().unwrap();
+    let chat_root = tmp.path().join("chat");
+    let workspace = tmp.path().join("ws");
+    std::fs::create_dir_all(&workspace).unwrap();
+
+    // Swarm / desktop: several runs of different sessions at once.
+    let sessions = ["s-swarm-1", "s-swarm-2", "s-swarm-3"];
+    std::thread::scope(|scope| {
+        for key in sessions {
+            let (chat_root, workspace) = (&chat_root, &workspace);
+            scope.spawn(move || {
+                let registry = session_registry(chat_root, key);
+                for i in 0..10 {
+                    let path = format!("{}-{}.txt", key, i);
+                    std::fs::write(workspace.join(&path), "before\n").unwrap();
+                    write_via(&registry, workspace, &path, "after\n");
+                }
+            });
+        }
+    });
+
+    for key in sessions {
+        let paths = recorded_paths(&chat_root, key);
+        assert_eq!(paths.len(), 10, "{key}: {paths:?}");
+        assert!(
+            paths.iter().all(|p| p.contains(key)),
+            "{key} recorded another session's edit: {paths:?}"
+        );
+    }
+}
+
+#[test]
+fn test_write_output_records_session_and_enforces_quota() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("ws");
+    let output_dir = workspace.join("output");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    std::fs::write(output_dir.join("manual.txt"), "user file\n").unwrap();
+
+    let keys = ["SKILLLITE_OUTPUT_DIR", "SKILLLITE_OUTPUT_MAX_FILES"];
+    let saved: Vec<Option<String>> = keys.iter().map(|k| std::env::var(k).ok()).collect();
+    std::env::set_var(keys[0], &output_dir);
+    std::env::set_var(keys[1], "2");
+    let _serial = OUTPUT_DIR_ENV_LOCK
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let session = ToolSession::new(tmp.path().join("chat"), "s-output");
+
+    let write = |path: &str| {
+        let args = serde_json::json!({ "file_path": path, "content": "<html></html>" });
+        execute_builtin_tool(
+            "write_output",
+            &args.to_string(),
+            &workspace,
+            None,
+            Some(&session),
+        )
+    };
+    let first = write("reports/a.html");
+    assert!(!first.is_error, "{}", first.content);
+    let refused = write("b.html");
+    assert!(refused.is_error);
+    assert!(
+        refused.content.contains("quota exceeded"),
+        "{}",
+        refused.content
+    );
+    assert!(!output_dir.join("b.html").exists());
+
+    let list = |args: serde_json::Value| {
+        execute_builtin_tool(
+            "list_output",
+            &args.to_string(),
+            &workspace,
+            None,
+            Some(&session),
+        )
+    };
+    let all = list(serde_json::json!({ "recursive": true }));
+    assert!(all.content.contains("reports/a.html"), "{}", all.content);
+    assert!(all.content.contains("[session: s-output]"));
+    assert!(all.content.contains("manual.txt"));
+    let mine = list(serde_json::json!({ "recursive": true, "current_session": true }));
+    assert!(mine.content.contains("reports/a.html"));
+    assert!(!mine.content.contains("manual.txt"), "{}", mine.content);
+    let flat = list(serde_json::json!({}));
+    assert!(
+        flat.content.contains("reports/  (1 files"),
+        "{}",
+        flat.content
+    );
+
+    for (key, value) in keys.iter().zip(saved) {
+        match value {
+            Some(v) => std::env::set_var(key, v),
+            None => std::env::remove_var(key),
+        }
+    }
+    assert!(output_dir
+        .join(skilllite_core::output_index::INDEX_FILE)
+        .exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_background_jobs_tail_stop_and_cleanup_on_session_end() {
+    use crate::types::SilentEventSink;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let chat_root = tmp.path().join("chat");
+    let workspace = tmp.path().join("ws");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let session = ToolSession::new(&chat_root, "s-jobs");
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let start = |name: &str, command: &str| {
+        let args = serde_json::json!({ "command": command, "background": true, "job_name": name });
+        rt.block_on(execute_async_builtin_tool(
+            "run_command",
+            &args.to_string(),
+            &workspace,
+            &mut SilentEventSink,
+            Some(&session),
+        ))
+    };
+    let tool = |name: &str, args: serde_json::Value| {
+        execute_builtin_tool(name, &args.to_string(), &workspace, None, Some(&session))
+    };
+
+    let started = start(
+        "ticker",
+        "echo tick 1; echo tick 2; while true; do echo loop; sleep 0.1; done",
+    );
+    assert!(!started.is_error, "{}", started.content);
+    assert!(started.content.contains("Started background job 'ticker'"));
+    assert!(started.content.contains("tick 1"), "{}", started.content);
+    let duplicate = start("ticker", "sleep 1");
+    assert!(duplicate.is_error);
+    assert!(duplicate.content.contains("already running"));
+
+    // Only output since the last read, capped at `lines`.
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let out = tool(
+        "job_output",
+        serde_json::json!({ "job": "ticker", "lines": 2 }),
+    );
+    assert!(
+        out.content.starts_with("[ticker: running]"),
+        "{}",
+        out.content
+    );
+    assert!(out.content.contains("loop"));
+    assert!(!out.content.contains("tick 1"));
+    assert_eq!(out.content.matches("loop").count(), 2, "{}", out.content);
+    assert!(tool("list_jobs", serde_json::json!({}))
+        .content
+        .contains("ticker  running"));
+
+    let stopped = tool("stop_job", serde_json::json!({ "job": "ticker" }));
+    assert!(
+        stopped.content.starts_with("[ticker: stopped]"),
+        "{}",
+        stopped.content
+    );
+    assert!(tool("list_jobs", serde_json::json!({}))
+        .content
+        .contains("ticker  stopped"));
+    assert!(tool("job_output", serde_json::json!({ "job": "nope" })).is_error);
+
+    // A grandchild in the job's process group dies with the session.
+    let server = start("server", "sleep 30 & echo child $!; wait");
+    assert!(!server.is_error, "{}", server.content);
+    let pid: u32 = server
+        .content
+        .lines()
+        .find_map(|l| l.strip_prefix("child "))
+        .and_then(|p| p.trim().parse().ok())
+        .unwrap_or_else(|| panic!("no child pid in {}", server.content));
+    // Killed orphans may linger as zombies until init reaps them.
+    let alive = |pid: u32| {
+        std::process::Command::new("ps")
+            .args(["-o", "stat=", "-p", &pid.to_string()])
+            .output()
+            .is_ok_and(|o| {
+                let stat = String::from_utf8_lossy(&o.stdout);
+                !stat.trim().is_empty() && !stat.trim_start().starts_with('Z')
+            })
+    };
+    assert!(alive(pid));
+
+    assert_eq!(stop_background_jobs(Some("s-jobs")), 2);
+    assert!(!alive(pid), "grandchild {} survived session end", pid);
+    assert_eq!(
+        tool("list_jobs", serde_json::json!({})).content,
+        "No background jobs in this session."
+    );
+    let transcripts = std::fs::read_dir(chat_root.join("transcripts"))
+        .unwrap()
+        .map(|e| std::fs::read_to_string(e.unwrap().path()).unwrap())
+        .collect::<String>();
+    assert_eq!(transcripts.matches("\"background_job\"").count(), 2);
+    assert!(transcripts.contains("child "), "{}", transcripts);
+}
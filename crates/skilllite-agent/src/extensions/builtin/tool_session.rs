@@ -0,0 +1,25 @@
+//! Session a builtin tool call belongs to, for tools that keep per-session state (file
+//! history pre-images, output index, background jobs, preview servers).
+//!
+//! The agent loop sets a [`ToolSession`] on the registry it builds for each run, and the
+//! registry passes it to every builtin call. Nested loops (memory flush) and concurrent
+//! sessions (swarm, desktop) therefore each record under their own session. Without one
+//! (tests, one-off tool calls) tools skip the session-bound behavior.
+
+use std::path::PathBuf;
+
+/// Chat data root and session key of the run a tool call belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolSession {
+    pub chat_root: PathBuf,
+    pub session_key: String,
+}
+
+impl ToolSession {
+    pub fn new(chat_root: impl Into<PathBuf>, session_key: impl Into<String>) -> Self {
+        Self {
+            chat_root: chat_root.into(),
+            session_key: session_key.into(),
+        }
+    }
+}
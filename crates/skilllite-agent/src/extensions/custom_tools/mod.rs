@@ -0,0 +1,734 @@
+//! Workspace custom tools: `.skilllite/tools.toml` exposes a whitelisted shell command or a
+//! small HTTP GET as a tool without creating a skill directory.
+//!
+//! ```toml
+//! [[tool]]
+//! name = "recent_commits"
+//! description = "Show the latest commits of the repository"
+//! [tool.parameters.properties.count]
+//! type = "integer"
+//! description = "How many commits"
+//! [tool.command]
+//! template = "git log --oneline -n {count}"
+//! allow = ["git log:*"]            # Bash(...) patterns; default: "<first word>:*"
+//!
+//! [[tool]]
+//! name = "crate_info"
+//! description = "Look up a crate on crates.io"
+//! [tool.parameters.properties.name]
+//! type = "string"
+//! [tool.http]
+//! method = "GET"                   # GET or HEAD
+//! url = "https://crates.io/api/v1/crates/{name}"
+//! headers = { Accept = "application/json" }
+//! allowed_hosts = ["crates.io"]    # default: the host of `url`
+//! ```
+//!
+//! `parameters` is the tool's JSON schema; every `{placeholder}` must be one of its
+//! properties and is added to `required`. Command arguments are single-quoted for `sh`,
+//! must not start with `-`, and the rendered command is checked by the bash validator
+//! before going through `run_command`'s scan and confirmation policy. URL arguments are
+//! percent-encoded and may only appear in the path or query. A broken entry is logged and
+//! skipped; the remaining tools still load.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use skilllite_sandbox::bash_validator::{validate_bash_command_in, BashToolPattern};
+
+use super::builtin;
+use super::registry::{RegisteredTool, ToolCapability, ToolHandler};
+use crate::high_risk;
+use crate::types::{
+    safe_truncate, ConfirmationRequest, EventSink, FunctionDef, RiskTier, ToolDefinition,
+    ToolResult,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Custom tool definitions, relative to the workspace.
+pub const CUSTOM_TOOLS_FILE: &str = ".skilllite/tools.toml";
+
+/// Headers a `tools.toml` HTTP tool may set; anything else (cookies, auth) is rejected.
+const HTTP_HEADER_ALLOWLIST: &[&str] = &[
+    "accept",
+    "accept-language",
+    "cache-control",
+    "content-type",
+    "user-agent",
+];
+
+const MAX_ARGUMENT_LEN: usize = 4096;
+const MAX_HTTP_BODY_BYTES: usize = 256 * 1024;
+const MAX_HTTP_RESULT_CHARS: usize = 8000;
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_HTTP_REDIRECTS: usize = 5;
+
+// ─── tools.toml schema ──────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTool {
+    name: String,
+    description: String,
+    #[serde(default)]
+    parameters: Option<toml::Value>,
+    #[serde(default)]
+    command: Option<RawCommand>,
+    #[serde(default)]
+    http: Option<RawHttp>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCommand {
+    template: String,
+    #[serde(default)]
+    allow: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawHttp {
+    #[serde(default = "default_http_method")]
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    allowed_hosts: Vec<String>,
+}
+
+fn default_http_method() -> String {
+    "GET".to_string()
+}
+
+/// One piece of a `{placeholder}` template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Param(String),
+}
+
+/// A validated custom tool from `tools.toml`.
+#[derive(Debug)]
+pub struct CustomTool {
+    pub name: String,
+    description: String,
+    parameters: Value,
+    exec: CustomExec,
+}
+
+#[derive(Debug)]
+enum CustomExec {
+    Command(CommandSpec),
+    Http(HttpSpec),
+}
+
+#[derive(Debug)]
+struct CommandSpec {
+    template: Vec<Segment>,
+    patterns: Vec<BashToolPattern>,
+}
+
+#[derive(Debug)]
+struct HttpSpec {
+    method: reqwest::Method,
+    url: Vec<Segment>,
+    headers: Vec<(String, String)>,
+    allowed_hosts: Vec<String>,
+}
+
+// ─── Loading ────────────────────────────────────────────────────────────────
+
+pub fn custom_tools_path(workspace: &Path) -> PathBuf {
+    workspace.join(CUSTOM_TOOLS_FILE)
+}
+
+/// Load `.skilllite/tools.toml`; a missing file means no custom tools.
+pub fn load_custom_tools(workspace: &Path) -> Vec<CustomTool> {
+    let path = custom_tools_path(workspace);
+    if !path.is_file() {
+        return Vec::new();
+    }
+    let content = match skilllite_fs::read_file(&path) {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::warn!("Cannot read {}: {}", path.display(), e);
+            return Vec::new();
+        }
+    };
+    let (tools, warnings) = parse_custom_tools(&content, workspace);
+    for warning in &warnings {
+        tracing::warn!("{}: {}", path.display(), warning);
+    }
+    tools
+}
+
+/// Parse `tools.toml` content. Returns the valid tools and one warning per skipped entry.
+fn parse_custom_tools(content: &str, workspace: &Path) -> (Vec<CustomTool>, Vec<String>) {
+    let table: toml::Table = match toml::from_str(content) {
+        Ok(t) => t,
+        Err(e) => {
+            return (
+                Vec::new(),
+                vec![format!("invalid TOML, no tools loaded: {}", e)],
+            )
+        }
+    };
+    let entries = match table.get("tool") {
+        None => return (Vec::new(), Vec::new()),
+        Some(toml::Value::Array(entries)) => entries.clone(),
+        Some(_) => {
+            return (
+                Vec::new(),
+                vec!["`tool` must be an array of tables ([[tool]])".to_string()],
+            )
+        }
+    };
+
+    let mut tools = Vec::new();
+    let mut warnings = Vec::new();
+    let mut seen = HashSet::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let label = entry
+            .get("name")
+            .and_then(toml::Value::as_str)
+            .map(|n| format!("tool #{} '{}'", index + 1, n))
+            .unwrap_or_else(|| format!("tool #{}", index + 1));
+        let parsed = entry
+            .try_into::<RawTool>()
+            .map_err(|e| e.message().to_string())
+            .and_then(|raw| CustomTool::from_raw(raw, workspace));
+        match parsed {
+            Ok(tool) if !seen.insert(tool.name.clone()) => {
+                warnings.push(format!("{} skipped: duplicate tool name", label));
+            }
+            Ok(tool) => tools.push(tool),
+            Err(e) => warnings.push(format!("{} skipped: {}", label, e)),
+        }
+    }
+    (tools, warnings)
+}
+
+/// Custom tools as registry entries. Names taken by built-in tools are skipped.
+pub(super) fn custom_registered_tools(workspace: &Path) -> Vec<RegisteredTool> {
+    let builtin_names: HashSet<String> = builtin::get_builtin_tool_definitions()
+        .into_iter()
+        .map(|td| td.function.name)
+        .collect();
+    load_custom_tools(workspace)
+        .into_iter()
+        .filter(|tool| {
+            let clash = builtin_names.contains(&tool.name);
+            if clash {
+                tracing::warn!(
+                    "{}: custom tool '{}' skipped: a built-in tool has the same name",
+                    CUSTOM_TOOLS_FILE,
+                    tool.name
+                );
+            }
+            !clash
+        })
+        .map(CustomTool::into_registered)
+        .collect()
+}
+
+impl CustomTool {
+    fn from_raw(raw: RawTool, workspace: &Path) -> Result<Self, String> {
+        validate_tool_name(&raw.name)?;
+        if raw.description.trim().is_empty() {
+            return Err("description must not be empty".to_string());
+        }
+        let mut parameters = parameters_schema(raw.parameters)?;
+        let exec = match (raw.command, raw.http) {
+            (Some(command), None) => {
+                CustomExec::Command(CommandSpec::from_raw(command, workspace)?)
+            }
+            (None, Some(http)) => CustomExec::Http(HttpSpec::from_raw(http)?),
+            _ => return Err("exactly one of [tool.command] or [tool.http] is required".to_string()),
+        };
+        require_placeholders(&mut parameters, exec.placeholders())?;
+        Ok(Self {
+            name: raw.name,
+            description: raw.description.trim().to_string(),
+            parameters,
+            exec,
+        })
+    }
+
+    fn into_registered(self) -> RegisteredTool {
+        let definition = ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDef {
+                name: self.name.clone(),
+                description: self.description.clone(),
+                parameters: self.parameters.clone(),
+            },
+        };
+        let capabilities = match self.exec {
+            CustomExec::Command(_) => vec![ToolCapability::ProcessExec],
+            CustomExec::Http(_) => Vec::new(),
+        };
+        RegisteredTool::new(
+            definition,
+            capabilities,
+            ToolHandler::Custom(Arc::new(self)),
+        )
+    }
+}
+
+impl CustomExec {
+    fn placeholders(&self) -> impl Iterator<Item = &str> {
+        let segments = match self {
+            CustomExec::Command(spec) => &spec.template,
+            CustomExec::Http(spec) => &spec.url,
+        };
+        segments.iter().filter_map(|s| match s {
+            Segment::Param(name) => Some(name.as_str()),
+            Segment::Literal(_) => None,
+        })
+    }
+}
+
+impl CommandSpec {
+    fn from_raw(raw: RawCommand, workspace: &Path) -> Result<Self, String> {
+        let template = parse_template(&raw.template)?;
+        let allow = if raw.allow.is_empty() {
+            let program = raw
+                .template
+                .split_whitespace()
+                .next()
+                .filter(|w| !w.contains('{'))
+                .ok_or("command template must start with a literal program name")?;
+            vec![format!("{}:*", program)]
+        } else {
+            raw.allow
+        };
+        let patterns = bash_patterns(&allow)?;
+        // Check the template's shape once up front so a blocked program fails at load time.
+        let sample = render(&template, |_| Ok("'x'".to_string()))?;
+        validate_bash_command_in(&sample, &patterns, &[workspace])
+            .map_err(|e| format!("command template rejected: {}", e))?;
+        Ok(Self { template, patterns })
+    }
+}
+
+impl HttpSpec {
+    fn from_raw(raw: RawHttp) -> Result<Self, String> {
+        let method = match raw.method.trim().to_ascii_uppercase().as_str() {
+            "GET" => reqwest::Method::GET,
+            "HEAD" => reqwest::Method::HEAD,
+            other => return Err(format!("unsupported HTTP method '{}' (GET or HEAD)", other)),
+        };
+        let url = parse_template(&raw.url)?;
+        let authority_is_literal = match url.first() {
+            Some(Segment::Literal(prefix)) => {
+                let rest = prefix
+                    .strip_prefix("https://")
+                    .or_else(|| prefix.strip_prefix("http://"))
+                    .ok_or("url must start with http:// or https://")?;
+                url.len() == 1 || rest.contains(['/', '?'])
+            }
+            _ => false,
+        };
+        if !authority_is_literal {
+            return Err("placeholders are only allowed in the URL path and query".to_string());
+        }
+        let sample = render(&url, |_| Ok("x".to_string()))?;
+        let host = reqwest::Url::parse(&sample)
+            .map_err(|e| format!("invalid url: {}", e))?
+            .host_str()
+            .map(str::to_ascii_lowercase)
+            .ok_or("url has no host")?;
+        let allowed_hosts: Vec<String> = if raw.allowed_hosts.is_empty() {
+            vec![host.clone()]
+        } else {
+            raw.allowed_hosts
+                .iter()
+                .map(|h| h.trim().to_ascii_lowercase())
+                .collect()
+        };
+        if !allowed_hosts.contains(&host) {
+            return Err(format!("url host '{}' is not in allowed_hosts", host));
+        }
+        let mut headers = Vec::new();
+        for (name, value) in raw.headers {
+            if !HTTP_HEADER_ALLOWLIST.contains(&name.to_ascii_lowercase().as_str()) {
+                return Err(format!(
+                    "header '{}' is not allowed (allowed: {})",
+                    name,
+                    HTTP_HEADER_ALLOWLIST.join(", ")
+                ));
+            }
+            if reqwest::header::HeaderValue::from_str(&value).is_err() {
+                return Err(format!("invalid value for header '{}'", name));
+            }
+            headers.push((name, value));
+        }
+        Ok(Self {
+            method,
+            url,
+            headers,
+            allowed_hosts,
+        })
+    }
+}
+
+fn validate_tool_name(name: &str) -> Result<(), String> {
+    let valid = name.len() <= 64
+        && name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(format!(
+            "invalid name '{}': use letters, digits, '_' or '-' (max 64 chars), starting with a letter",
+            name
+        ));
+    }
+    if name.starts_with("mcp__") {
+        return Err("names starting with 'mcp__' are reserved for MCP tools".to_string());
+    }
+    Ok(())
+}
+
+/// `parameters` as a JSON schema object; defaults to an object without properties.
+fn parameters_schema(raw: Option<toml::Value>) -> Result<Value, String> {
+    let mut schema = match raw {
+        Some(value) => serde_json::to_value(value).map_err(|e| e.to_string())?,
+        None => json!({}),
+    };
+    let obj = schema
+        .as_object_mut()
+        .ok_or("parameters must be a table (JSON schema object)")?;
+    match obj.get("type") {
+        None => {
+            obj.insert("type".to_string(), json!("object"));
+        }
+        Some(t) if t == "object" => {}
+        Some(_) => return Err("parameters.type must be \"object\"".to_string()),
+    }
+    match obj.get("properties") {
+        None => {
+            obj.insert("properties".to_string(), json!({}));
+        }
+        Some(p) if p.is_object() => {}
+        Some(_) => return Err("parameters.properties must be a table".to_string()),
+    }
+    Ok(schema)
+}
+
+/// Every placeholder must be a declared property; it becomes required.
+fn require_placeholders<'p>(
+    schema: &mut Value,
+    placeholders: impl Iterator<Item = &'p str>,
+) -> Result<(), String> {
+    let mut required: Vec<Value> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for name in placeholders {
+        if schema["properties"].get(name).is_none() {
+            return Err(format!(
+                "placeholder '{{{}}}' is not declared in parameters.properties",
+                name
+            ));
+        }
+        if !required.iter().any(|r| r == name) {
+            required.push(json!(name));
+        }
+    }
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    Ok(())
+}
+
+fn bash_patterns(allow: &[String]) -> Result<Vec<BashToolPattern>, String> {
+    let raw = allow
+        .iter()
+        .map(|p| format!("Bash({})", p))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let patterns: Vec<BashToolPattern> = skilllite_core::skill::metadata::parse_allowed_tools(&raw)
+        .into_iter()
+        .map(|p| BashToolPattern {
+            command_prefix: p.command_prefix,
+            raw_pattern: p.raw_pattern,
+        })
+        .collect();
+    if patterns.len() != allow.len() {
+        return Err(format!("invalid allow pattern in {:?}", allow));
+    }
+    Ok(patterns)
+}
+
+// ─── Templates ──────────────────────────────────────────────────────────────
+
+/// Split `text {name} more` into segments; `{{` / `}}` are literal braces.
+fn parse_template(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') if !name.is_empty() => break,
+                        Some(ch) if ch.is_ascii_alphanumeric() || ch == '_' => name.push(ch),
+                        _ => {
+                            return Err(format!(
+                                "invalid placeholder after '{{{}' (use {{name}}, or {{{{ }}}} for literal braces)",
+                                name
+                            ))
+                        }
+                    }
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Param(name));
+            }
+            '}' => return Err("unmatched '}' (use }} for a literal brace)".to_string()),
+            _ => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+fn render(
+    segments: &[Segment],
+    mut value: impl FnMut(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut out = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Literal(text) => out.push_str(text),
+            Segment::Param(name) => out.push_str(&value(name)?),
+        }
+    }
+    Ok(out)
+}
+
+/// Scalar argument as text; rejects control characters (newlines could split commands).
+fn argument_text(args: &Map<String, Value>, name: &str) -> Result<String, String> {
+    let text = match args.get(name) {
+        Some(Value::String(s)) => s.clone(),
+        Some(v @ (Value::Number(_) | Value::Bool(_))) => v.to_string(),
+        None | Some(Value::Null) => return Err(format!("Missing argument '{}'", name)),
+        Some(_) => {
+            return Err(format!(
+                "Argument '{}' must be a string, number or boolean",
+                name
+            ))
+        }
+    };
+    if text.chars().any(char::is_control) {
+        return Err(format!("Argument '{}' contains control characters", name));
+    }
+    if text.len() > MAX_ARGUMENT_LEN {
+        return Err(format!(
+            "Argument '{}' is too long: {} bytes (max {})",
+            name,
+            text.len(),
+            MAX_ARGUMENT_LEN
+        ));
+    }
+    Ok(text)
+}
+
+fn command_argument(args: &Map<String, Value>, name: &str) -> Result<String, String> {
+    let text = argument_text(args, name)?;
+    if args.get(name).is_some_and(Value::is_string) && text.starts_with('-') {
+        return Err(format!(
+            "Argument '{}' must not start with '-' (it would be read as an option)",
+            name
+        ));
+    }
+    Ok(shell_quote(&text))
+}
+
+fn url_argument(args: &Map<String, Value>, name: &str) -> Result<String, String> {
+    let text = argument_text(args, name)?;
+    if text == "." || text == ".." {
+        return Err(format!("Argument '{}' must not be '.' or '..'", name));
+    }
+    Ok(percent_encode(&text))
+}
+
+/// Always single-quote: the value stays one literal word for `sh` and the bash validator.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+// ─── Execution ──────────────────────────────────────────────────────────────
+
+pub(super) async fn execute_custom_tool(
+    tool: &CustomTool,
+    arguments: &str,
+    workspace: &Path,
+    event_sink: &mut dyn EventSink,
+) -> ToolResult {
+    let args = match serde_json::from_str::<Value>(arguments) {
+        Ok(Value::Object(map)) => map,
+        Ok(_) => return error_result(&tool.name, "Invalid arguments JSON: expected object"),
+        Err(e) => return error_result(&tool.name, &format!("Invalid arguments JSON: {}", e)),
+    };
+    match &tool.exec {
+        CustomExec::Command(spec) => {
+            let command = match render(&spec.template, |name| command_argument(&args, name)) {
+                Ok(c) => c,
+                Err(e) => return error_result(&tool.name, &e),
+            };
+            if let Err(e) = validate_bash_command_in(&command, &spec.patterns, &[workspace]) {
+                return error_result(&tool.name, &format!("Command validation failed: {}", e));
+            }
+            builtin::execute_run_command_as(
+                &tool.name,
+                &json!({ "command": command }),
+                workspace,
+                event_sink,
+                // Foreground only: nothing session-bound to record.
+                None,
+            )
+            .await
+        }
+        CustomExec::Http(spec) => match execute_http(tool, spec, &args, event_sink).await {
+            Ok(result) => result,
+            Err(e) => error_result(&tool.name, &e),
+        },
+    }
+}
+
+async fn execute_http(
+    tool: &CustomTool,
+    spec: &HttpSpec,
+    args: &Map<String, Value>,
+    event_sink: &mut dyn EventSink,
+) -> Result<ToolResult, String> {
+    let url = render(&spec.url, |name| url_argument(args, name))?;
+    let url = reqwest::Url::parse(&url).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    if !host_allowed(&url, &spec.allowed_hosts) {
+        return Err(format!("URL '{}' is outside allowed_hosts", url));
+    }
+
+    if high_risk::confirm_network() {
+        let prompt = format!(
+            "About to call custom tool '{}':\n  {} {}\n\nConfirm?",
+            tool.name, spec.method, url
+        );
+        if !event_sink.on_confirmation_request(&ConfirmationRequest::new(prompt, RiskTier::Low)) {
+            return Ok(ToolResult {
+                tool_call_id: String::new(),
+                tool_name: tool.name.clone(),
+                content: "User cancelled request".to_string(),
+                is_error: false,
+                counts_as_failure: false,
+            });
+        }
+    }
+
+    let allowed_hosts = spec.allowed_hosts.clone();
+    let client = reqwest::Client::builder()
+        .connect_timeout(HTTP_CONNECT_TIMEOUT)
+        .timeout(HTTP_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if !host_allowed(attempt.url(), &allowed_hosts) {
+                let message = format!("redirect to '{}' is outside allowed_hosts", attempt.url());
+                attempt.error(message)
+            } else if attempt.previous().len() >= MAX_HTTP_REDIRECTS {
+                attempt.stop()
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()
+        .map_err(|e| format!("Cannot build HTTP client: {}", e))?;
+    let mut request = client.request(spec.method.clone(), url.clone());
+    for (name, value) in &spec.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {:#}", anyhow::Error::from(e)))?;
+
+    let status = response.status();
+    let mut body = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Reading response failed: {}", e))?
+    {
+        body.extend_from_slice(&chunk);
+        if body.len() > MAX_HTTP_BODY_BYTES {
+            body.truncate(MAX_HTTP_BODY_BYTES);
+            truncated = true;
+            break;
+        }
+    }
+    let text = String::from_utf8_lossy(&body);
+    let shown = safe_truncate(&text, MAX_HTTP_RESULT_CHARS);
+    let mut content = format!("HTTP {}\n\n{}", status, shown);
+    if truncated || shown.len() < text.len() {
+        content.push_str("\n\n[response truncated]");
+    }
+    Ok(ToolResult {
+        tool_call_id: String::new(),
+        tool_name: tool.name.clone(),
+        content,
+        is_error: !status.is_success(),
+        counts_as_failure: !status.is_success(),
+    })
+}
+
+fn host_allowed(url: &reqwest::Url, allowed_hosts: &[String]) -> bool {
+    matches!(url.scheme(), "http" | "https")
+        && url
+            .host_str()
+            .is_some_and(|host| allowed_hosts.iter().any(|a| a.eq_ignore_ascii_case(host)))
+}
+
+fn error_result(tool_name: &str, message: &str) -> ToolResult {
+    ToolResult {
+        tool_call_id: String::new(),
+        tool_name: tool_name.to_string(),
+        content: format!("Error: {}", message),
+        is_error: true,
+        counts_as_failure: true,
+    }
+}
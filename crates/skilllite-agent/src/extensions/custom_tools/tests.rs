@@ -0,0 +1,293 @@
+use super::*;
+use crate::extensions::ExtensionRegistry;
+use crate::types::SilentEventSink;
+
+const FIXTURE: &str = include_str!("fixtures/tools.toml");
+
+fn fixture_workspace(port: u16) -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".skilllite")).unwrap();
+    std::fs::write(
+        custom_tools_path(tmp.path()),
+        FIXTURE.replace("@PORT@", &port.to_string()),
+    )
+    .unwrap();
+    tmp
+}
+
+/// Serve one canned HTTP response on 127.0.0.1; yields the raw request head.
+fn serve_once(response: &'static str) -> (u16, std::sync::mpsc::Receiver<String>) {
+    use std::io::{BufRead, BufReader, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut head = String::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                break;
+            }
+            head.push_str(&line);
+        }
+        let _ = stream.write_all(response.as_bytes());
+        let _ = tx.send(head);
+    });
+    (port, rx)
+}
+
+fn find<'a>(tools: &'a [CustomTool], name: &str) -> &'a CustomTool {
+    tools.iter().find(|t| t.name == name).unwrap()
+}
+
+#[test]
+fn fixture_loads_valid_tools_and_skips_each_broken_entry() {
+    let tmp = fixture_workspace(8080);
+    let (tools, warnings) = parse_custom_tools(&FIXTURE.replace("@PORT@", "8080"), tmp.path());
+
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["echo_text", "fetch_item"]);
+    assert_eq!(warnings.len(), 9, "{:#?}", warnings);
+    for (needle, reason) in [
+        ("'bad name!'", "invalid name"),
+        ("'both_kinds'", "exactly one of"),
+        ("'undeclared_placeholder'", "not declared"),
+        ("'blocked_program'", "blocked prefix"),
+        ("'host_placeholder'", "path and query"),
+        ("'cookie_header'", "header 'Cookie' is not allowed"),
+        ("'post_method'", "unsupported HTTP method"),
+        ("'typo_field'", "unknown field"),
+        ("'echo_text'", "duplicate tool name"),
+    ] {
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.contains(needle) && w.contains(reason)),
+            "no warning for {} ({}): {:#?}",
+            needle,
+            reason,
+            warnings
+        );
+    }
+
+    // Placeholders become required fields of the generated schema.
+    let fetch = find(&tools, "fetch_item");
+    assert_eq!(fetch.parameters["required"], json!(["id", "q"]));
+    assert_eq!(fetch.parameters["type"], "object");
+}
+
+#[test]
+fn invalid_toml_loads_nothing_without_panicking() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (tools, warnings) = parse_custom_tools("[[tool]\nname = ", tmp.path());
+    assert!(tools.is_empty());
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("invalid TOML"));
+}
+
+#[test]
+fn registry_registers_custom_tools_with_capabilities() {
+    let tmp = fixture_workspace(8080);
+    let registry = ExtensionRegistry::builder(false, false, &[])
+        .register(builtin::get_builtin_tools())
+        .register_custom_tools(tmp.path())
+        .build();
+    assert!(registry.owns_tool("echo_text"));
+    assert!(registry.owns_tool("fetch_item"));
+    assert!(!registry.owns_tool("blocked_program"));
+    assert!(!registry.tool_profile("echo_text").unwrap().is_read_only);
+    assert!(registry.tool_profile("fetch_item").unwrap().is_read_only);
+
+    let read_only = ExtensionRegistry::builder(false, false, &[])
+        .with_policy(crate::extensions::CapabilityPolicy::read_only())
+        .register_custom_tools(tmp.path())
+        .build();
+    assert!(!read_only.owns_tool("echo_text"));
+    assert!(read_only.owns_tool("fetch_item"));
+}
+
+#[test]
+fn custom_tool_cannot_shadow_builtin() {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".skilllite")).unwrap();
+    std::fs::write(
+        custom_tools_path(tmp.path()),
+        "[[tool]]\nname = \"run_command\"\ndescription = \"x\"\n[tool.command]\ntemplate = \"echo hi\"\n",
+    )
+    .unwrap();
+    assert!(custom_registered_tools(tmp.path()).is_empty());
+}
+
+#[cfg(not(windows))]
+#[tokio::test]
+async fn command_arguments_are_passed_as_one_literal_word() {
+    let tmp = fixture_workspace(8080);
+    let tools = load_custom_tools(tmp.path());
+    let echo = find(&tools, "echo_text");
+    let mut sink = SilentEventSink;
+
+    for payload in [
+        "; touch pwned",
+        "$(touch pwned)",
+        "`touch pwned`",
+        "a && touch pwned",
+        "'; touch pwned; echo '",
+        "x | touch pwned",
+        "> pwned",
+    ] {
+        let args = json!({ "text": payload }).to_string();
+        let result = execute_custom_tool(echo, &args, tmp.path(), &mut sink).await;
+        assert!(!result.is_error, "{}: {}", payload, result.content);
+        assert!(
+            result.content.contains(payload),
+            "{} not echoed literally: {}",
+            payload,
+            result.content
+        );
+        assert!(
+            !tmp.path().join("pwned").exists(),
+            "payload {:?} escaped quoting",
+            payload
+        );
+    }
+}
+
+#[tokio::test]
+async fn command_arguments_reject_newlines_and_options() {
+    let tmp = fixture_workspace(8080);
+    let tools = load_custom_tools(tmp.path());
+    let echo = find(&tools, "echo_text");
+    let mut sink = SilentEventSink;
+
+    let result = execute_custom_tool(
+        echo,
+        &json!({ "text": "hi\ntouch pwned" }).to_string(),
+        tmp.path(),
+        &mut sink,
+    )
+    .await;
+    assert!(result.is_error);
+    assert!(result.content.contains("control characters"));
+
+    let result = execute_custom_tool(
+        echo,
+        &json!({ "text": "--help" }).to_string(),
+        tmp.path(),
+        &mut sink,
+    )
+    .await;
+    assert!(result.is_error);
+    assert!(result.content.contains("must not start with '-'"));
+
+    let result = execute_custom_tool(echo, "{}", tmp.path(), &mut sink).await;
+    assert!(result.is_error);
+    assert!(result.content.contains("Missing argument 'text'"));
+    assert!(!tmp.path().join("pwned").exists());
+}
+
+#[tokio::test]
+async fn command_tool_follows_run_command_confirmation_policy() {
+    if !crate::high_risk::confirm_run_command() {
+        return;
+    }
+    struct DenyConfirmSink;
+    impl EventSink for DenyConfirmSink {
+        fn on_text(&mut self, _text: &str) {}
+        fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+        fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+        fn on_confirmation_request(&mut self, _request: &ConfirmationRequest) -> bool {
+            false
+        }
+    }
+
+    let tmp = fixture_workspace(8080);
+    let tools = load_custom_tools(tmp.path());
+    let result = execute_custom_tool(
+        find(&tools, "echo_text"),
+        r#"{"text":"hi"}"#,
+        tmp.path(),
+        &mut DenyConfirmSink,
+    )
+    .await;
+    assert!(result.content.contains("cancelled"), "{}", result.content);
+    assert_eq!(result.tool_name, "echo_text");
+}
+
+#[tokio::test]
+async fn http_arguments_are_percent_encoded_into_path_and_query() {
+    let (port, head) = serve_once(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 7\r\nConnection: close\r\n\r\nitem ok",
+    );
+    let tmp = fixture_workspace(port);
+    let tools = load_custom_tools(tmp.path());
+    let mut sink = SilentEventSink;
+
+    let result = execute_custom_tool(
+        find(&tools, "fetch_item"),
+        &json!({ "id": "a/../b", "q": "x&admin=1#frag" }).to_string(),
+        tmp.path(),
+        &mut sink,
+    )
+    .await;
+    assert!(!result.is_error, "{}", result.content);
+    assert!(result.content.contains("HTTP 200"));
+    assert!(result.content.contains("item ok"));
+
+    let head = head.recv().unwrap();
+    assert!(
+        head.starts_with("GET /items/a%2F..%2Fb?q=x%26admin%3D1%23frag HTTP/1.1"),
+        "{}",
+        head
+    );
+    assert!(head.to_ascii_lowercase().contains("accept: text/plain"));
+
+    let result = execute_custom_tool(
+        find(&tools, "fetch_item"),
+        &json!({ "id": "..", "q": "x" }).to_string(),
+        tmp.path(),
+        &mut sink,
+    )
+    .await;
+    assert!(result.is_error);
+    assert!(result.content.contains("must not be '.' or '..'"));
+}
+
+#[tokio::test]
+async fn http_redirect_outside_allowed_hosts_is_not_followed() {
+    let (port, _head) = serve_once(
+        "HTTP/1.1 302 Found\r\nLocation: http://example.invalid/steal\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    );
+    let tmp = fixture_workspace(port);
+    let tools = load_custom_tools(tmp.path());
+    let mut sink = SilentEventSink;
+
+    let result = execute_custom_tool(
+        find(&tools, "fetch_item"),
+        r#"{"id":"1","q":"x"}"#,
+        tmp.path(),
+        &mut sink,
+    )
+    .await;
+    assert!(result.is_error);
+    assert!(
+        result.content.contains("outside allowed_hosts"),
+        "{}",
+        result.content
+    );
+}
+
+#[test]
+fn templates_support_escaped_braces_and_reject_stray_ones() {
+    assert_eq!(
+        parse_template("awk '{{print}}' {file}").unwrap(),
+        vec![
+            Segment::Literal("awk '{print}' ".to_string()),
+            Segment::Param("file".to_string()),
+        ]
+    );
+    assert!(parse_template("echo {").is_err());
+    assert!(parse_template("echo }").is_err());
+    assert!(parse_template("echo {a b}").is_err());
+}
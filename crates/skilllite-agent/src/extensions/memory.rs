@@ -139,6 +139,10 @@ pub async fn execute_memory_tool(
         "memory_search" => {
             execute_memory_search(&args, &mem_root, agent_id, enable_vector, embed_ctx).await
         }
+        "memory_write" if writes_auto_namespace(&args) => Err(crate::Error::validation(format!(
+            "memory/{}/ is reserved for automatic session memories; choose another rel_path",
+            super::auto_memory::AUTO_MEMORY_DIR
+        ))),
         "memory_write" => {
             execute_memory_write(&args, &mem_root, agent_id, enable_vector, embed_ctx).await
         }
@@ -201,18 +205,17 @@ async fn execute_memory_search(
         #[cfg(feature = "memory_vector")]
         {
             let ctx = embed_ctx.context("embed_ctx disappeared despite is_some() check")?;
-            let embeddings = ctx
-                .client
-                .embed(
-                    &ctx.embed_config.model,
-                    &[query],
-                    Some(&ctx.embed_config.api_base),
-                    Some(&ctx.embed_config.api_key),
-                )
-                .await
-                .context("Embedding API failed")?;
+            let embeddings = ctx.embed(&[query]).await.context("Embedding failed")?;
             let query_emb = embeddings.first().context("No embedding returned")?;
-            skilllite_executor::memory::ensure_vec0_table(&conn, ctx.embed_config.dimension)?;
+            let cfg = ctx.embed_config;
+            if skilllite_executor::memory::ensure_vec0_table(
+                &conn,
+                cfg.dimension,
+                &cfg.fingerprint(),
+            )? {
+                // Index was built with another backend/model: rebuild before comparing.
+                reembed_all(&conn, chat_root, ctx).await?;
+            }
             skilllite_executor::memory::search_vec(&conn, query_emb, limit)?
         }
         #[cfg(not(feature = "memory_vector"))]
@@ -242,7 +245,7 @@ async fn execute_memory_search(
 
 /// Write content to memory and index for BM25 + vector (when enabled).
 #[allow(unused_variables)]
-async fn execute_memory_write(
+pub(super) async fn execute_memory_write(
     args: &serde_json::Value,
     chat_root: &Path,
     agent_id: &str,
@@ -257,6 +260,15 @@ async fn execute_memory_write(
         .get("content")
         .and_then(|v| v.as_str())
         .context("'content' is required")?;
+    let redacted = skilllite_core::redact::redact_secrets(content);
+    if redacted.count > 0 {
+        tracing::info!(
+            count = redacted.count,
+            rel_path,
+            "Redacted secrets from memory_write content"
+        );
+    }
+    let content = redacted.text.as_ref();
     let append = args
         .get("append")
         .and_then(|v| v.as_bool())
@@ -301,47 +313,129 @@ async fn execute_memory_write(
     #[cfg(feature = "memory_vector")]
     if enable_vector {
         if let Some(ctx) = embed_ctx {
-            let chunks = skilllite_executor::memory::chunk_content_for_embed(&final_content);
-            if !chunks.is_empty() {
-                let texts: Vec<&str> = chunks.iter().map(|s| s.as_str()).collect();
-                match ctx
-                    .client
-                    .embed(
-                        &ctx.embed_config.model,
-                        &texts,
-                        Some(&ctx.embed_config.api_base),
-                        Some(&ctx.embed_config.api_key),
-                    )
-                    .await
-                {
-                    Ok(embeddings) if embeddings.len() == chunks.len() => {
-                        skilllite_executor::memory::ensure_vec0_table(
-                            &conn,
-                            ctx.embed_config.dimension,
-                        )?;
-                        skilllite_executor::memory::index_file_vec(
-                            &conn,
-                            rel_path,
-                            &chunks,
-                            &embeddings,
-                        )?;
+            let cfg = ctx.embed_config;
+            match skilllite_executor::memory::ensure_vec0_table(
+                &conn,
+                cfg.dimension,
+                &cfg.fingerprint(),
+            ) {
+                // Embedding model changed: re-embed every memory file (including this one).
+                Ok(true) => {
+                    if let Err(e) = reembed_all(&conn, chat_root, ctx).await {
+                        tracing::warn!("Memory re-embedding failed, BM25 index only: {}", e);
                     }
-                    Ok(_) => {
-                        tracing::warn!("Embedding count mismatch, skipping vector index");
-                    }
-                    Err(e) => {
+                }
+                Ok(false) => {
+                    if let Err(e) = embed_file(&conn, ctx, rel_path, &final_content).await {
                         tracing::warn!("Embedding failed, BM25 index only: {}", e);
                     }
                 }
+                Err(e) => tracing::warn!("Vector index unavailable, BM25 index only: {}", e),
             }
         }
     }
 
-    Ok(format!(
+    let mut msg = format!(
         "Successfully wrote {} chars to memory://{}",
         final_content.len(),
         rel_path
-    ))
+    );
+    if redacted.count > 0 {
+        msg.push_str(&format!(" ({} secret(s) redacted)", redacted.count));
+    }
+    Ok(msg)
+}
+
+/// Chunk, embed and (re)index one memory file into the vector table.
+#[cfg(feature = "memory_vector")]
+async fn embed_file(
+    conn: &Connection,
+    ctx: &MemoryVectorContext<'_>,
+    rel_path: &str,
+    content: &str,
+) -> Result<usize> {
+    let chunks = skilllite_executor::memory::chunk_content_for_embed(content);
+    if chunks.is_empty() {
+        return Ok(0);
+    }
+    let texts: Vec<&str> = chunks.iter().map(|s| s.as_str()).collect();
+    let embeddings = ctx.embed(&texts).await?;
+    if embeddings.len() != chunks.len() {
+        bail!(
+            "Embedding count mismatch: {} chunks, {} embeddings",
+            chunks.len(),
+            embeddings.len()
+        );
+    }
+    skilllite_executor::memory::index_file_vec(conn, rel_path, &chunks, &embeddings)?;
+    Ok(chunks.len())
+}
+
+/// Re-embed every memory Markdown file into the (already recreated) vector table.
+/// Returns `(files, chunks)` indexed.
+#[cfg(feature = "memory_vector")]
+async fn reembed_all(
+    conn: &Connection,
+    chat_root: &Path,
+    ctx: &MemoryVectorContext<'_>,
+) -> Result<(usize, usize)> {
+    let memory_dir = chat_root.join("memory");
+    let mut files = 0;
+    let mut chunks = 0;
+    for rel in skilllite_executor::memory::list_memory_markdown_files(chat_root)? {
+        let content = skilllite_fs::read_file(&memory_dir.join(&rel))?;
+        if content.trim().is_empty() {
+            continue;
+        }
+        chunks += embed_file(conn, ctx, &rel, &content).await?;
+        files += 1;
+    }
+    tracing::info!(
+        files,
+        chunks,
+        fingerprint = %ctx.embed_config.fingerprint(),
+        "Memory vector index re-embedded"
+    );
+    Ok((files, chunks))
+}
+
+/// Outcome of [`reembed_memory`].
+#[derive(Debug, Clone)]
+pub struct MemoryReembedReport {
+    pub files: usize,
+    pub chunks: usize,
+    /// `backend:model:dimension` now stored with the index.
+    pub fingerprint: String,
+}
+
+/// Rebuild the memory vector index from scratch with the configured embedding backend
+/// (`skilllite reindex --memory`). The BM25 index is refreshed as well.
+#[cfg(feature = "memory_vector")]
+pub async fn reembed_memory(
+    chat_root: &Path,
+    agent_id: &str,
+    ctx: &MemoryVectorContext<'_>,
+) -> Result<MemoryReembedReport> {
+    skilllite_executor::memory::ensure_vec_extension_loaded();
+    let idx_path = skilllite_executor::memory::index_path(chat_root, agent_id);
+    if let Some(parent) = idx_path.parent() {
+        skilllite_fs::create_dir_all(parent)?;
+    }
+    let conn = Connection::open(&idx_path).context("Failed to open memory index")?;
+    skilllite_executor::memory::ensure_index(&conn)?;
+    let cfg = ctx.embed_config;
+    let fingerprint = cfg.fingerprint();
+    if !skilllite_executor::memory::ensure_vec0_table(&conn, cfg.dimension, &fingerprint)? {
+        conn.execute("DELETE FROM memory_vec", [])?;
+    }
+    let rel_paths = skilllite_executor::memory::list_memory_markdown_files(chat_root)?;
+    skilllite_executor::memory::reindex_memory_markdown_files(chat_root, agent_id, &rel_paths)?;
+    let (files, chunks) = reembed_all(&conn, chat_root, ctx).await?;
+    Ok(MemoryReembedReport {
+        files,
+        chunks,
+        fingerprint,
+    })
 }
 
 /// List all memory files.
@@ -553,6 +647,16 @@ pub fn reindex_memory_markdown_files(
 
 // ─── Path helpers ───────────────────────────────────────────────────────────
 
+/// Model-issued writes may not land in the `auto/` namespace, so purging automatic
+/// memories never removes something the model stored on purpose.
+pub(super) fn writes_auto_namespace(args: &serde_json::Value) -> bool {
+    let Some(rel) = args.get("rel_path").and_then(|v| v.as_str()) else {
+        return false;
+    };
+    let normalized = normalize_memory_path(Path::new(rel.trim_start_matches("./")));
+    normalized.starts_with(super::auto_memory::AUTO_MEMORY_DIR)
+}
+
 /// Normalize a path by resolving `.` and `..` components without filesystem access.
 fn normalize_memory_path(path: &Path) -> std::path::PathBuf {
     let mut components = Vec::new();
@@ -4,18 +4,33 @@
 //! - **builtin**: file ops, run_command, output, preview, chat (read_file, write_file, etc.)
 //! - **memory**: memory_search, memory_write, memory_list (optional, enable_memory)
 //! - **skills**: dynamically loaded from skill directories
+//! - **custom_tools**: workspace command / HTTP tools from `.skilllite/tools.toml`
 //!
 //! `ExtensionRegistry` provides a unified interface for tool discovery and execution.
 
+mod auto_memory;
 mod builtin;
+mod custom_tools;
 mod memory;
 mod registry;
 
+pub use auto_memory::{
+    count_auto_memories, extraction_prompt, parse_facts, purge_auto_memories, write_auto_memories,
+    AutoMemoryConfig, AutoMemoryFact, AutoMemoryReport, AutoMemoryTrigger,
+};
 pub use builtin::{
     get_builtin_tools, process_read_file_tool_result_content, process_tool_result_content,
-    process_tool_result_content_fallback,
+    process_tool_result_content_fallback, revert_session_file, stop_background_jobs,
+    stop_preview_servers, ToolSession,
+};
+pub(crate) use builtin::{normalize_path, output_root, register_skill_outputs};
+pub use custom_tools::{load_custom_tools, CustomTool, CUSTOM_TOOLS_FILE};
+#[cfg(feature = "memory_vector")]
+pub use memory::reembed_memory;
+pub use memory::{
+    build_memory_context, index_evolution_knowledge, reindex_memory_markdown_files,
+    MemoryReembedReport,
 };
-pub use memory::{build_memory_context, index_evolution_knowledge, reindex_memory_markdown_files};
 pub use registry::{
     CapabilityPolicy, ExtensionRegistry, ExtensionRegistryBuilder, MemoryVectorContext,
     PlanningControlExecutor, PlanningControlKind, RegisteredTool, ResultProcessingProfile,
@@ -8,6 +8,7 @@ use std::path::Path;
 use std::sync::Arc;
 
 use super::builtin;
+use super::custom_tools::{self, CustomTool};
 use super::memory;
 use crate::llm::LlmClient;
 use crate::mcp_client::McpRuntime;
@@ -41,15 +42,34 @@ pub trait PlanningControlExecutor {
         event_sink: &mut dyn EventSink,
     ) -> ToolResult;
 }
-use skilllite_core::config::EmbeddingConfig;
+use skilllite_core::config::{EmbeddingBackend, EmbeddingConfig};
 
-/// Context for memory vector search (embedding API).
+/// Context for memory vector search (embedding API or local model).
 #[allow(dead_code)] // used when memory_vector feature is enabled
 pub struct MemoryVectorContext<'a> {
     pub client: &'a LlmClient,
     pub embed_config: &'a EmbeddingConfig,
 }
 
+#[allow(dead_code)] // used when memory_vector feature is enabled
+impl MemoryVectorContext<'_> {
+    /// Embed texts with the configured backend. Vectors whose length differs from
+    /// `embed_config.dimension` are rejected so they never reach the index.
+    pub async fn embed(&self, texts: &[&str]) -> crate::Result<Vec<Vec<f32>>> {
+        let cfg = self.embed_config;
+        let embeddings = match cfg.backend {
+            EmbeddingBackend::Http => {
+                self.client
+                    .embed(&cfg.model, texts, Some(&cfg.api_base), Some(&cfg.api_key))
+                    .await?
+            }
+            EmbeddingBackend::Local => crate::llm::local_embed::embed(cfg, texts).await?,
+        };
+        skilllite_executor::memory::check_embedding_dimensions(&embeddings, cfg.dimension)?;
+        Ok(embeddings)
+    }
+}
+
 /// Coarse-grained capabilities used to gate tools in different execution modes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ToolCapability {
@@ -337,6 +357,113 @@ mod tests {
         assert!(result.content.contains("Field 'port'"));
         assert!(result.content.contains("maximum"));
     }
+
+    #[tokio::test]
+    async fn get_skill_details_returns_body_and_schemas_for_known_skill_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill_dir = tmp.path().join("pdf-tools");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: pdf-tools\ndescription: Merge PDFs. Also splits and rotates pages.\nallowed-tools: Bash(qpdf:*)\n---\n\n## Examples\n\nqpdf --empty --pages a.pdf b.pdf -- out.pdf\n",
+        )
+        .unwrap();
+        let skills = crate::skills::load_skills(&[tmp.path().to_string_lossy().to_string()]);
+        let registry = ExtensionRegistry::new(false, false, &skills);
+        assert!(!ExtensionRegistry::new(false, false, &[]).owns_tool("get_skill_details"));
+
+        let skill_tool = registry
+            .all_tool_definitions()
+            .into_iter()
+            .find(|td| td.function.name == "pdf_tools")
+            .unwrap();
+        assert!(skill_tool.function.description.starts_with("Merge PDFs."));
+        assert!(!skill_tool.function.description.contains("rotates"));
+
+        let mut sink = SilentEventSink;
+        let result = registry
+            .execute(
+                "get_skill_details",
+                r#"{"name":"pdf-tools"}"#,
+                tmp.path(),
+                &mut sink,
+                None,
+                None,
+            )
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.contains("qpdf --empty --pages"));
+        assert!(result.content.contains("### pdf_tools"));
+        assert!(result.content.contains("\"command\""));
+
+        let missing = registry
+            .execute(
+                "get_skill_details",
+                r#"{"name":"nope"}"#,
+                tmp.path(),
+                &mut sink,
+                None,
+                None,
+            )
+            .await;
+        assert!(missing.is_error);
+        assert!(missing.content.contains("Available skills: pdf-tools"));
+    }
+
+    #[tokio::test]
+    async fn with_reloaded_skills_swaps_skill_tools_and_keeps_builtins() {
+        let tmp = tempfile::tempdir().unwrap();
+        for name in ["old-skill", "new-skill"] {
+            let dir = tmp.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("SKILL.md"),
+                format!(
+                    "---\nname: {name}\ndescription: {name}\nallowed-tools: Bash(echo:*)\n---\n"
+                ),
+            )
+            .unwrap();
+        }
+        let all = crate::skills::load_skills(&[tmp.path().to_string_lossy().to_string()]);
+        let old: Vec<_> = all
+            .iter()
+            .filter(|s| s.name == "old-skill")
+            .cloned()
+            .collect();
+        let new: Vec<_> = all
+            .iter()
+            .filter(|s| s.name == "new-skill")
+            .cloned()
+            .collect();
+
+        let registry = ExtensionRegistry::new(false, false, &old);
+        assert!(registry.owns_tool("old_skill"));
+        let registry = registry.with_reloaded_skills(&new);
+        assert!(registry.owns_tool("new_skill"));
+        assert!(!registry.owns_tool("old_skill"));
+        assert!(registry.owns_tool("get_skill_details"));
+        assert!(registry.owns_tool("read_file"));
+        assert!(registry.availability().has_skill_hint("new-skill"));
+        assert!(!registry.availability().has_skill_hint("old-skill"));
+
+        let mut sink = SilentEventSink;
+        let result = registry
+            .execute(
+                "old_skill",
+                r#"{"command":"echo hi"}"#,
+                tmp.path(),
+                &mut sink,
+                None,
+                None,
+            )
+            .await;
+        assert!(result.is_error);
+        assert!(result.content.contains("removed by a skill reload"));
+
+        let registry = registry.with_reloaded_skills(&[]);
+        assert!(!registry.owns_tool("get_skill_details"));
+        assert!(registry.owns_tool("read_file"));
+    }
 }
 
 /// Scope that controls when a tool is available.
@@ -357,6 +484,8 @@ pub enum ToolHandler {
     Skill {
         skill_name: String,
     },
+    /// `get_skill_details`: serves docs + schemas for the registry's loaded skills.
+    SkillDetails,
     /// Control tool (e.g. complete_task, update_task_plan) executed via
     /// [`PlanningControlExecutor`]. The carried [`PlanningControlKind`] is what
     /// the executor dispatches on; the tool's string name is no longer the
@@ -367,6 +496,8 @@ pub enum ToolHandler {
         server_id: String,
         remote_tool: String,
     },
+    /// Workspace custom tool from `.skilllite/tools.toml` (command or HTTP template).
+    Custom(Arc<CustomTool>),
 }
 
 /// How an oversized tool result should be processed before being handed back to the LLM.
@@ -752,6 +883,10 @@ pub struct ExtensionRegistry<'a> {
     pub skills: &'a [LoadedSkill],
     /// Active MCP stdio sessions for [`ToolHandler::Mcp`] (same agent loop invocation).
     mcp_runtime: Option<Arc<McpRuntime>>,
+    /// Skill tools dropped by [`ExtensionRegistry::with_reloaded_skills`].
+    retired_skill_tools: HashSet<String>,
+    /// Session builtin tool calls belong to (file history, output index, jobs, previews).
+    tool_session: Option<builtin::ToolSession>,
 }
 
 /// Builder for ExtensionRegistry with explicit tool registration.
@@ -765,6 +900,7 @@ pub struct ExtensionRegistryBuilder<'a> {
     skills: &'a [LoadedSkill],
     mcp_tools: Vec<RegisteredTool>,
     mcp_runtime: Option<Arc<McpRuntime>>,
+    tool_session: Option<builtin::ToolSession>,
 }
 
 impl<'a> ExtensionRegistryBuilder<'a> {
@@ -779,6 +915,7 @@ impl<'a> ExtensionRegistryBuilder<'a> {
             skills,
             mcp_tools: Vec::new(),
             mcp_runtime: None,
+            tool_session: None,
         }
     }
 
@@ -794,6 +931,14 @@ impl<'a> ExtensionRegistryBuilder<'a> {
         self
     }
 
+    /// Session that builtin tool calls of this registry record under (`None`: no session-bound
+    /// state, e.g. one-off tool calls).
+    #[must_use]
+    pub fn with_tool_session(mut self, session: Option<builtin::ToolSession>) -> Self {
+        self.tool_session = session;
+        self
+    }
+
     /// Exclude PlanningOnly tools when false (simple mode).
     #[must_use]
     pub fn with_task_planning(mut self, enable: bool) -> Self {
@@ -815,6 +960,15 @@ impl<'a> ExtensionRegistryBuilder<'a> {
         self
     }
 
+    /// Register the workspace's custom tools from `.skilllite/tools.toml`, if any.
+    /// Broken entries are logged and skipped.
+    #[must_use]
+    pub fn register_custom_tools(mut self, workspace: &Path) -> Self {
+        self.registered_tools
+            .extend(custom_tools::custom_registered_tools(workspace));
+        self
+    }
+
     /// Register memory tools if enable_memory is true.
     #[must_use]
     pub fn register_memory_if(mut self, enable: bool) -> Self {
@@ -824,22 +978,13 @@ impl<'a> ExtensionRegistryBuilder<'a> {
         self
     }
 
-    /// Build the registry. Skills' tool definitions are added at build time.
+    /// Build the registry. Skills' tool definitions are added at build time, with
+    /// descriptions cut to the one-line summary (full docs via `get_skill_details`).
     /// 按 function.name 去重，避免重复声明导致 Gemini 等 API 报 Duplicate function declaration。
     pub fn build(self) -> ExtensionRegistry<'a> {
         let mut registered_tools = self.registered_tools;
         registered_tools.extend(self.mcp_tools);
-        for skill in self.skills {
-            for td in &skill.tool_definitions {
-                registered_tools.push(RegisteredTool::new(
-                    td.clone(),
-                    vec![ToolCapability::SkillExecution],
-                    ToolHandler::Skill {
-                        skill_name: skill.name.clone(),
-                    },
-                ));
-            }
-        }
+        registered_tools.extend(skill_tools(self.skills));
 
         let mut tool_definitions = Vec::new();
         let mut tools_by_name = HashMap::new();
@@ -875,8 +1020,43 @@ impl<'a> ExtensionRegistryBuilder<'a> {
             enable_memory_vector: self.enable_memory_vector,
             skills: self.skills,
             mcp_runtime: self.mcp_runtime,
+            retired_skill_tools: HashSet::new(),
+            tool_session: self.tool_session,
+        }
+    }
+}
+
+/// Tools contributed by loaded skills: one per tool definition plus `get_skill_details`.
+fn skill_tools(skills: &[LoadedSkill]) -> Vec<RegisteredTool> {
+    let mut tools = Vec::new();
+    if !skills.is_empty() {
+        tools.push(builtin::get_skill_details_tool());
+    }
+    for skill in skills {
+        for td in &skill.tool_definitions {
+            let mut td = td.clone();
+            td.function.description = format!(
+                "{} (Call get_skill_details(\"{}\") for usage before first use.)",
+                skill.summary(),
+                skill.name
+            );
+            tools.push(RegisteredTool::new(
+                td,
+                vec![ToolCapability::SkillExecution],
+                ToolHandler::Skill {
+                    skill_name: skill.name.clone(),
+                },
+            ));
         }
     }
+    tools
+}
+
+fn is_skill_tool(tool: &RegisteredTool) -> bool {
+    matches!(
+        tool.handler,
+        ToolHandler::Skill { .. } | ToolHandler::SkillDetails
+    )
 }
 
 impl<'a> ExtensionRegistry<'a> {
@@ -942,6 +1122,56 @@ impl<'a> ExtensionRegistry<'a> {
         ExtensionRegistryBuilder::new(enable_memory, enable_memory_vector, skills)
     }
 
+    /// Swap in a reloaded skill set, keeping builtin / memory / MCP tools as registered.
+    /// Calls to tools of removed skills get an explicit "skill was removed" error.
+    pub fn with_reloaded_skills<'b>(self, skills: &'b [LoadedSkill]) -> ExtensionRegistry<'b> {
+        let mut retired = self.retired_skill_tools;
+        let mut tools_by_name = HashMap::new();
+        for (name, tool) in self.tools_by_name {
+            if is_skill_tool(&tool) {
+                retired.insert(name);
+            } else {
+                tools_by_name.insert(name, tool);
+            }
+        }
+        let mut tool_definitions: Vec<ToolDefinition> = self
+            .tool_definitions
+            .into_iter()
+            .filter(|td| tools_by_name.contains_key(&td.function.name))
+            .collect();
+        for registered in skill_tools(skills) {
+            let tool_name = registered.name().to_string();
+            if !self.policy.allows(&registered.capabilities)
+                || tools_by_name.contains_key(&tool_name)
+            {
+                continue;
+            }
+            tool_definitions.push(registered.definition.clone());
+            tools_by_name.insert(tool_name, registered);
+        }
+        retired.retain(|name| !tools_by_name.contains_key(name));
+
+        let mut availability = ToolAvailabilityView::default();
+        for td in &tool_definitions {
+            if let Some(tool) = tools_by_name.get(&td.function.name) {
+                availability.register(tool);
+            }
+        }
+
+        ExtensionRegistry {
+            tool_definitions,
+            tools_by_name,
+            availability,
+            policy: self.policy,
+            enable_memory: self.enable_memory,
+            enable_memory_vector: self.enable_memory_vector,
+            skills,
+            mcp_runtime: self.mcp_runtime,
+            retired_skill_tools: retired,
+            tool_session: self.tool_session,
+        }
+    }
+
     /// Collect all tool definitions (from registered extensions + skills).
     pub fn all_tool_definitions(&self) -> Vec<ToolDefinition> {
         self.tool_definitions.clone()
@@ -982,6 +1212,14 @@ impl<'a> ExtensionRegistry<'a> {
         })
     }
 
+    /// Skill that backs a tool, or `None` for builtin / memory / MCP tools.
+    pub fn skill_name_for_tool(&self, name: &str) -> Option<&str> {
+        self.tools_by_name.get(name).and_then(|t| match &t.handler {
+            ToolHandler::Skill { skill_name } => Some(skill_name.as_str()),
+            _ => None,
+        })
+    }
+
     /// Render/use tool result via the tool's unified lifecycle hook.
     pub fn render_tool_result(
         &self,
@@ -1014,6 +1252,18 @@ impl<'a> ExtensionRegistry<'a> {
         planning_ctx: Option<&mut dyn PlanningControlExecutor>,
     ) -> ToolResult {
         let Some(registered) = self.tools_by_name.get(tool_name) else {
+            if self.retired_skill_tools.contains(tool_name) {
+                return ToolResult {
+                    tool_call_id: String::new(),
+                    tool_name: tool_name.to_string(),
+                    content: format!(
+                        "Tool '{}' belonged to a skill that was removed by a skill reload; it is no longer available",
+                        tool_name
+                    ),
+                    is_error: true,
+                    counts_as_failure: true,
+                };
+            }
             return ToolResult {
                 tool_call_id: String::new(),
                 tool_name: tool_name.to_string(),
@@ -1063,12 +1313,22 @@ impl<'a> ExtensionRegistry<'a> {
                     }
                 }
             }
-            ToolHandler::BuiltinSync => {
-                builtin::execute_builtin_tool(tool_name, arguments, workspace, Some(event_sink))
-            }
+            ToolHandler::BuiltinSync => builtin::execute_builtin_tool(
+                tool_name,
+                arguments,
+                workspace,
+                Some(event_sink),
+                self.tool_session.as_ref(),
+            ),
             ToolHandler::BuiltinAsync => {
-                builtin::execute_async_builtin_tool(tool_name, arguments, workspace, event_sink)
-                    .await
+                builtin::execute_async_builtin_tool(
+                    tool_name,
+                    arguments,
+                    workspace,
+                    event_sink,
+                    self.tool_session.as_ref(),
+                )
+                .await
             }
             ToolHandler::Memory => {
                 memory::execute_memory_tool(
@@ -1081,12 +1341,13 @@ impl<'a> ExtensionRegistry<'a> {
                 )
                 .await
             }
+            ToolHandler::SkillDetails => builtin::execute_get_skill_details(arguments, self.skills),
             ToolHandler::Skill { skill_name } => {
-                if let Some(skill) = skills::find_skill_by_name(self.skills, skill_name) {
-                    skills::execute_skill(skill, tool_name, arguments, workspace, event_sink, None)
-                } else if let Some(skill) = skills::find_skill_by_tool_name(self.skills, tool_name)
+                if let Some(skill) = skills::find_skill_by_name(self.skills, skill_name)
+                    .or_else(|| skills::find_skill_by_tool_name(self.skills, tool_name))
                 {
-                    skills::execute_skill(skill, tool_name, arguments, workspace, event_sink, None)
+                    skills::execute_skill_queued(skill, tool_name, arguments, workspace, event_sink)
+                        .await
                 } else if let Some(skill) = skills::find_skill_by_name(self.skills, tool_name) {
                     // Reference-only skill (no entry_point / no scripts, just SKILL.md guidance)
                     let docs = prompt::get_skill_full_docs(skill).unwrap_or_else(|| {
@@ -1115,6 +1376,9 @@ impl<'a> ExtensionRegistry<'a> {
                     }
                 }
             }
+            ToolHandler::Custom(tool) => {
+                custom_tools::execute_custom_tool(tool, arguments, workspace, event_sink).await
+            }
             ToolHandler::Mcp {
                 server_id,
                 remote_tool,
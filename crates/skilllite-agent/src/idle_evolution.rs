@@ -0,0 +1,252 @@
+//! A9 idle trigger: run evolution in the background once the chat user goes quiet.
+//!
+//! Decisions used to pile up until someone ran `skilllite evolution run`. The chat session
+//! now arms a timer when a turn ends; after `SKILLLITE_EVO_IDLE_SECS` without input, and only
+//! when the preflight (`should_evolve`) finds work, one cycle runs on a Tokio task. The next
+//! turn (or dropping the session) disarms the timer: a waiting timer is aborted, a cycle
+//! already running is detached (a half-applied cycle is worse than a late one) and finishes on
+//! its own. Overlap with other triggers or a concurrent CLI run is left to `run_evolution`'s
+//! mutex and run lock.
+//!
+//! Finished cycles are queued as [`IdleEvolutionNotice`]s and reported at the next turn via
+//! [`EventSink::on_evolution_completed`](crate::types::EventSink::on_evolution_completed).
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use skilllite_core::config::env_keys::evolution as evo_keys;
+use skilllite_core::config::loader::{env_bool, env_optional};
+
+const DEFAULT_IDLE_SECS: u64 = 600;
+const DEFAULT_MAX_CYCLES: usize = 3;
+
+/// Timer state shared with the spawned task; decides whether disarming aborts or detaches.
+const WAITING: u8 = 0;
+const RUNNING: u8 = 1;
+const CANCELLED: u8 = 2;
+
+/// Idle trigger settings (`SKILLLITE_EVO_IDLE_*`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdleEvolutionConfig {
+    pub enabled: bool,
+    pub idle_secs: u64,
+    /// Background cycles allowed per chat session.
+    pub max_cycles: usize,
+}
+
+impl Default for IdleEvolutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_secs: DEFAULT_IDLE_SECS,
+            max_cycles: DEFAULT_MAX_CYCLES,
+        }
+    }
+}
+
+impl IdleEvolutionConfig {
+    pub fn from_env() -> Self {
+        let mut cfg = Self {
+            enabled: env_bool(evo_keys::SKILLLITE_EVO_IDLE_TRIGGER, &[], true),
+            ..Self::default()
+        };
+        if let Some(n) =
+            env_optional(evo_keys::SKILLLITE_EVO_IDLE_SECS, &[]).and_then(|v| v.trim().parse().ok())
+        {
+            cfg.idle_secs = n;
+        }
+        if let Some(n) = env_optional(evo_keys::SKILLLITE_EVO_IDLE_MAX_CYCLES, &[])
+            .and_then(|v| v.trim().parse().ok())
+        {
+            cfg.max_cycles = n;
+        }
+        cfg
+    }
+}
+
+/// A background cycle that produced changes, waiting to be shown to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdleEvolutionNotice {
+    pub txn_id: String,
+    /// [`skilllite_evolution::format_evolution_changes`] lines.
+    pub changes: Vec<String>,
+}
+
+/// Per-session idle timer plus the notices of cycles it completed.
+#[derive(Default)]
+pub struct IdleEvolution {
+    timer: Option<(tokio::task::JoinHandle<()>, Arc<AtomicU8>)>,
+    cycles: Arc<AtomicUsize>,
+    notices: Arc<Mutex<Vec<IdleEvolutionNotice>>>,
+}
+
+impl IdleEvolution {
+    /// (Re)arm the timer. After `cfg.idle_secs`, `preflight` decides whether there is work and,
+    /// if so, `cycle` runs. No-op when disabled, out of cycles, or outside a Tokio runtime.
+    pub fn arm<P, F>(&mut self, cfg: &IdleEvolutionConfig, preflight: P, cycle: F)
+    where
+        P: FnOnce() -> bool + Send + 'static,
+        F: Future<Output = Option<IdleEvolutionNotice>> + Send + 'static,
+    {
+        self.disarm();
+        if !cfg.enabled || self.cycles.load(Ordering::SeqCst) >= cfg.max_cycles {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let state = Arc::new(AtomicU8::new(WAITING));
+        let task_state = Arc::clone(&state);
+        let cycles = Arc::clone(&self.cycles);
+        let notices = Arc::clone(&self.notices);
+        let idle = Duration::from_secs(cfg.idle_secs);
+        let handle = runtime.spawn(async move {
+            tokio::time::sleep(idle).await;
+            if !preflight() {
+                tracing::debug!("Idle evolution skipped: nothing to evolve");
+                return;
+            }
+            if task_state
+                .compare_exchange(WAITING, RUNNING, Ordering::SeqCst, Ordering::SeqCst)
+                .is_err()
+            {
+                return;
+            }
+            cycles.fetch_add(1, Ordering::SeqCst);
+            tracing::debug!("Idle evolution trigger fired after {}s", idle.as_secs());
+            if let Some(notice) = cycle.await {
+                notices
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(notice);
+            }
+        });
+        self.timer = Some((handle, state));
+    }
+
+    /// Stop waiting: abort a pending timer, detach a cycle that already started.
+    /// Never blocks, so it is safe on shutdown.
+    pub fn disarm(&mut self) {
+        let Some((handle, state)) = self.timer.take() else {
+            return;
+        };
+        if state
+            .compare_exchange(WAITING, CANCELLED, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            handle.abort();
+        } else if !handle.is_finished() {
+            tracing::debug!("Idle evolution still running; detaching it");
+        }
+    }
+
+    /// Notices of cycles finished since the last call.
+    pub fn take_notices(&self) -> Vec<IdleEvolutionNotice> {
+        std::mem::take(&mut *self.notices.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Background cycles started so far.
+    pub fn cycles(&self) -> usize {
+        self.cycles.load(Ordering::SeqCst)
+    }
+}
+
+/// A dropped session (e.g. one `agent_chat` RPC request) leaves no timer behind.
+impl Drop for IdleEvolution {
+    fn drop(&mut self) {
+        self.disarm();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    fn cfg(idle_secs: u64, max_cycles: usize) -> IdleEvolutionConfig {
+        IdleEvolutionConfig {
+            enabled: true,
+            idle_secs,
+            max_cycles,
+        }
+    }
+
+    fn notice(txn_id: &str) -> Option<IdleEvolutionNotice> {
+        Some(IdleEvolutionNotice {
+            txn_id: txn_id.to_string(),
+            changes: vec![format!("changed by {}", txn_id)],
+        })
+    }
+
+    async fn wait_for(mut done: impl FnMut() -> bool) {
+        for _ in 0..200 {
+            if done() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("condition not reached");
+    }
+
+    #[tokio::test]
+    async fn idle_cycle_runs_once_and_queues_its_notice() {
+        let mut idle = IdleEvolution::default();
+        idle.arm(&cfg(0, 1), || true, async { notice("txn-1") });
+        wait_for(|| idle.cycles() == 1 && idle.notices.lock().unwrap().len() == 1).await;
+
+        let notices = idle.take_notices();
+        assert_eq!(notices[0].txn_id, "txn-1");
+        assert!(idle.take_notices().is_empty());
+
+        // Out of cycles: arming again does nothing.
+        idle.arm(&cfg(0, 1), || true, async { notice("txn-2") });
+        assert!(idle.timer.is_none());
+    }
+
+    #[tokio::test]
+    async fn disarm_before_idle_threshold_never_runs_the_cycle() {
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&ran);
+        let mut idle = IdleEvolution::default();
+        idle.arm(&cfg(60, 3), || true, async move {
+            flag.store(true, Ordering::SeqCst);
+            None
+        });
+        idle.disarm();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!ran.load(Ordering::SeqCst));
+        assert_eq!(idle.cycles(), 0);
+    }
+
+    #[tokio::test]
+    async fn preflight_without_work_does_not_count_a_cycle() {
+        let mut idle = IdleEvolution::default();
+        idle.arm(&cfg(0, 3), || false, async { notice("txn-1") });
+        let handle_done = || {
+            idle.timer
+                .as_ref()
+                .is_some_and(|(handle, _)| handle.is_finished())
+        };
+        wait_for(handle_done).await;
+        assert_eq!(idle.cycles(), 0);
+        assert!(idle.take_notices().is_empty());
+    }
+
+    #[tokio::test]
+    async fn disarm_while_running_detaches_and_still_delivers() {
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+        let mut idle = IdleEvolution::default();
+        idle.arm(&cfg(0, 3), || true, async move {
+            let _ = released.await;
+            notice("txn-1")
+        });
+        wait_for(|| idle.cycles() == 1).await;
+
+        idle.disarm();
+        let _ = release.send(());
+        wait_for(|| idle.notices.lock().unwrap().len() == 1).await;
+        assert_eq!(idle.take_notices()[0].txn_id, "txn-1");
+    }
+}
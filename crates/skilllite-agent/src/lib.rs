@@ -2,6 +2,7 @@ pub mod error;
 pub use error::{Error, Result};
 
 pub mod agent_loop;
+pub mod attachments;
 pub mod capability_gap_analyzer;
 pub mod capability_registry;
 pub mod chat;
@@ -13,6 +14,7 @@ pub mod extensions;
 pub mod goal_boundaries;
 pub mod goal_contract;
 pub mod high_risk;
+pub mod idle_evolution;
 pub mod llm;
 pub mod locale_prompt;
 pub mod long_text;
@@ -20,8 +22,10 @@ pub mod mcp_client;
 pub mod planning_guard;
 pub mod planning_rules;
 pub mod prompt;
+pub mod repl;
 pub mod rpc;
 pub mod run_checkpoint;
+pub mod single_shot;
 pub mod skills;
 pub mod soul;
 pub mod task_planner;
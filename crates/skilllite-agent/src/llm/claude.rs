@@ -2,23 +2,19 @@
 
 use crate::error::bail;
 use crate::Result;
-use anyhow::{anyhow, Context};
+use anyhow::Context;
 use futures_util::StreamExt;
 use serde_json::{json, Value};
 
 use crate::types::{
-    get_max_tokens, parse_claude_tool_calls, ChatMessage, EventSink, FunctionCall, ToolCall,
-    ToolDefinition,
+    get_max_tokens, parse_claude_tool_calls, ChatMessage, EventSink, FunctionCall, SilentEventSink,
+    ToolCall, ToolDefinition,
 };
 
 use super::{
     normalize_vision_media_type, ChatCompletionResponse, Choice, ChoiceMessage, LlmClient, Usage,
 };
 
-fn claude_send_err(url: &str, e: reqwest::Error) -> anyhow::Error {
-    anyhow!("Claude API request failed (POST {}): {}", url, e)
-}
-
 impl LlmClient {
     pub(super) fn convert_messages_for_claude(
         messages: &[ChatMessage],
@@ -174,22 +170,17 @@ impl LlmClient {
             }
         }
 
+        let build = || {
+            self.http
+                .post(&url)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("Content-Type", "application/json")
+                .json(&body)
+        };
         let resp = self
-            .http
-            .post(&url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| claude_send_err(&url, e))?;
-
-        let status = resp.status();
-        if !status.is_success() {
-            let body_text = resp.text().await.unwrap_or_default();
-            bail!("{}", super::format_api_error(status, &body_text, "Claude"));
-        }
+            .send_with_retry(&url, "Claude", build, &mut SilentEventSink)
+            .await?;
 
         let response: Value = resp
             .json()
@@ -230,22 +221,17 @@ impl LlmClient {
             }
         }
 
+        let build = || {
+            self.http
+                .post(&url)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("Content-Type", "application/json")
+                .json(&body)
+        };
         let resp = self
-            .http
-            .post(&url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| claude_send_err(&url, e))?;
-
-        let status = resp.status();
-        if !status.is_success() {
-            let body_text = resp.text().await.unwrap_or_default();
-            bail!("{}", super::format_api_error(status, &body_text, "Claude"));
-        }
+            .send_with_retry(&url, "Claude", build, event_sink)
+            .await?;
 
         self.accumulate_claude_stream(resp, model, event_sink).await
     }
@@ -350,13 +336,16 @@ impl LlmClient {
         let mut in_tool_use = false;
         let mut stop_reason = None;
         let mut usage = None;
+        let mut input_tokens = 0u64;
 
         let mut buffer = String::new();
         let mut stream = resp.bytes_stream();
         let mut current_event_type = String::new();
 
         while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.context("Claude stream chunk error")?;
+            let chunk = chunk_result.map_err(|e| {
+                super::retry::stream_failure(format!("Claude stream chunk error: {}", e))
+            })?;
             buffer.push_str(&String::from_utf8_lossy(&chunk));
 
             while let Some(newline_pos) = buffer.find('\n') {
@@ -384,6 +373,25 @@ impl LlmClient {
                 };
 
                 match current_event_type.as_str() {
+                    "message_start" => {
+                        input_tokens = chunk
+                            .pointer("/message/usage/input_tokens")
+                            .and_then(|t| t.as_u64())
+                            .unwrap_or(0);
+                    }
+                    "error" => {
+                        let message =
+                            format!("Claude stream error: {}", super::extract_error_detail(data));
+                        // Overload / rate-limit events mid-stream are transient like a 529 / 429.
+                        let transient = matches!(
+                            chunk.pointer("/error/type").and_then(|t| t.as_str()),
+                            Some("overloaded_error" | "rate_limit_error" | "api_error")
+                        );
+                        if transient {
+                            return Err(super::retry::stream_failure(message));
+                        }
+                        bail!("{}", message);
+                    }
                     "content_block_start" => {
                         if let Some(block) = chunk.get("content_block") {
                             match block.get("type").and_then(|t| t.as_str()) {
@@ -453,16 +461,13 @@ impl LlmClient {
                             }
                         }
                         if let Some(u) = chunk.get("usage") {
+                            // input_tokens only arrives in message_start
+                            let output_tokens =
+                                u.get("output_tokens").and_then(|o| o.as_u64()).unwrap_or(0);
                             usage = Some(Usage {
-                                prompt_tokens: 0, // only available in message_start
-                                completion_tokens: u
-                                    .get("output_tokens")
-                                    .and_then(|o| o.as_u64())
-                                    .unwrap_or(0),
-                                total_tokens: u
-                                    .get("output_tokens")
-                                    .and_then(|o| o.as_u64())
-                                    .unwrap_or(0),
+                                prompt_tokens: input_tokens,
+                                completion_tokens: output_tokens,
+                                total_tokens: input_tokens + output_tokens,
                             });
                         }
                     }
@@ -0,0 +1,80 @@
+//! Local sentence-embedding backend (`SKILLLITE_EMBEDDING_BACKEND=local`).
+//!
+//! Runs an ONNX model via `fastembed` (feature `local_embedding`), so memory vector search works
+//! without an embedding API key. Model files are downloaded once into
+//! `EmbeddingConfig::local_model_dir` (default `~/.skilllite/models/embedding`).
+
+use crate::Result;
+use skilllite_core::config::EmbeddingConfig;
+
+#[cfg(feature = "local_embedding")]
+mod imp {
+    use crate::error::bail;
+    use crate::Result;
+    use anyhow::Context;
+    use fastembed::{InitOptions, TextEmbedding};
+    use skilllite_core::config::EmbeddingConfig;
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    /// Loaded model, keyed by model code (loading takes seconds; keep it for the process).
+    static MODEL: OnceLock<Mutex<Option<(String, Arc<TextEmbedding>)>>> = OnceLock::new();
+
+    fn load(config: &EmbeddingConfig) -> Result<Arc<TextEmbedding>> {
+        let slot = MODEL.get_or_init(|| Mutex::new(None));
+        let mut guard = slot
+            .lock()
+            .map_err(|_| crate::Error::validation("local embedding model lock poisoned"))?;
+        if let Some((code, model)) = guard.as_ref() {
+            if code == &config.model {
+                return Ok(model.clone());
+            }
+        }
+        let Some(info) = TextEmbedding::list_supported_models()
+            .into_iter()
+            .find(|m| m.model_code.eq_ignore_ascii_case(&config.model))
+        else {
+            bail!(
+                "Unsupported local embedding model '{}' (set SKILLLITE_EMBEDDING_MODEL to a fastembed model code, e.g. intfloat/multilingual-e5-small)",
+                config.model
+            );
+        };
+        skilllite_fs::create_dir_all(&config.local_model_dir)?;
+        let model = TextEmbedding::try_new(
+            InitOptions::new(info.model)
+                .with_cache_dir(config.local_model_dir.clone())
+                .with_show_download_progress(false),
+        )
+        .with_context(|| format!("Failed to load local embedding model {}", config.model))?;
+        let model = Arc::new(model);
+        *guard = Some((config.model.clone(), model.clone()));
+        Ok(model)
+    }
+
+    pub(super) async fn embed(config: &EmbeddingConfig, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let model = load(config)?;
+        let docs: Vec<String> = texts.iter().map(|t| t.to_string()).collect();
+        let out = tokio::task::spawn_blocking(move || model.embed(docs, None))
+            .await
+            .context("local embedding task panicked")?
+            .context("Local embedding failed")?;
+        Ok(out)
+    }
+}
+
+/// Embed `texts` with the local model configured in `config`.
+pub async fn embed(config: &EmbeddingConfig, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+    if texts.is_empty() {
+        return Ok(Vec::new());
+    }
+    #[cfg(feature = "local_embedding")]
+    {
+        imp::embed(config, texts).await
+    }
+    #[cfg(not(feature = "local_embedding"))]
+    {
+        let _ = config;
+        Err(crate::Error::validation(
+            "SKILLLITE_EMBEDDING_BACKEND=local requires a build with the `local_embedding` feature",
+        ))
+    }
+}
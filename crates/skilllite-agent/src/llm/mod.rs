@@ -4,7 +4,8 @@
 //!   - **OpenAI-compatible**: `/chat/completions` (GPT-4, DeepSeek, Qwen, etc.)
 //!   - **Claude Native**: `/v1/messages` (Anthropic Claude)
 //!
-//! Auto-detects which API to use based on model name or API base URL.
+//! Auto-detects which API to use based on model name or API base URL, unless an explicit
+//! provider (`openai` / `anthropic`) is set via [`LlmClient::with_provider`].
 //!
 //! Ported from Python `AgenticLoop._call_openai` / `_call_claude`.
 
@@ -13,15 +14,24 @@ use crate::Result;
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use skilllite_core::tokens::{Tokenizer, HEURISTIC_CHARS_PER_TOKEN};
 use skilllite_evolution::sanitize_visible_llm_text;
+use std::sync::Arc;
 
 use super::types::{
-    safe_truncate, ChatMessage, EventSink, LlmUsageReport, LlmUsageTotals, ToolCall,
+    safe_truncate, AgentConfig, ChatMessage, EventSink, LlmUsageReport, LlmUsageTotals, ToolCall,
     ToolDefinition, ToolFormat,
 };
 
 mod claude;
+pub mod local_embed;
 mod openai;
+mod recording;
+mod retry;
+
+pub use recording::{LlmTape, TapeMode};
+use recording::{RecordingSink, TapeRequest};
+pub use retry::RetryPolicy;
 
 fn sanitize_assistant_choice_content(mut resp: ChatCompletionResponse) -> ChatCompletionResponse {
     for choice in &mut resp.choices {
@@ -69,6 +79,12 @@ pub struct LlmClient {
     http: reqwest::Client,
     api_base: String,
     api_key: String,
+    /// Explicit wire format; `None` = [`detect_tool_format`] per call.
+    provider: Option<ToolFormat>,
+    /// Record / replay calls (`SKILLLITE_LLM_RECORD` / `SKILLLITE_LLM_REPLAY`).
+    tape: Option<Arc<LlmTape>>,
+    /// Request-level retry for transient failures (`SKILLLITE_LLM_MAX_ATTEMPTS`).
+    retry: RetryPolicy,
 }
 
 /// TCP 连接阶段超时。`api.minimax.io` 等域名常解析出多个 A 记录，其中个别 IP
@@ -86,9 +102,53 @@ impl LlmClient {
             http,
             api_base: api_base.trim_end_matches('/').to_string(),
             api_key: api_key.to_string(),
+            provider: None,
+            tape: None,
+            retry: RetryPolicy::default(),
         })
     }
 
+    /// Client for an agent config: api_base / api_key plus the optional `provider` override.
+    pub fn from_config(config: &AgentConfig) -> Result<Self> {
+        Ok(Self::new(&config.api_base, &config.api_key)?
+            .with_provider(config.provider.as_deref())
+            .with_tape(config.llm_tape.clone())
+            .with_retry(config.llm_retry.clone()))
+    }
+
+    /// Replace the default [`RetryPolicy`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Record calls to, or replay them from, a [`LlmTape`].
+    pub fn with_tape(mut self, tape: Option<Arc<LlmTape>>) -> Self {
+        self.tape = tape;
+        self
+    }
+
+    /// Pin the wire format instead of detecting it from model / api_base.
+    /// Unknown or empty provider names keep auto-detection (with a warning for unknown ones).
+    pub fn with_provider(mut self, provider: Option<&str>) -> Self {
+        let provider = provider.map(str::trim).filter(|p| !p.is_empty());
+        self.provider = provider.and_then(ToolFormat::from_provider);
+        if let (Some(name), None) = (provider, &self.provider) {
+            tracing::warn!(
+                provider = name,
+                "Unknown LLM provider (expected openai | anthropic); auto-detecting"
+            );
+        }
+        self
+    }
+
+    /// Wire format used for `model`: the configured provider, else auto-detected.
+    pub fn tool_format(&self, model: &str) -> ToolFormat {
+        self.provider
+            .clone()
+            .unwrap_or_else(|| detect_tool_format(model, &self.api_base))
+    }
+
     /// Non-streaming chat completion call (auto-routes based on model/api_base).
     ///
     /// When `usage_totals` is `Some`, successful responses merge API-reported `usage`
@@ -101,15 +161,35 @@ impl LlmClient {
         temperature: Option<f64>,
         usage_totals: Option<&mut LlmUsageTotals>,
     ) -> Result<ChatCompletionResponse> {
-        let format = detect_tool_format(model, &self.api_base);
-        let resp = match format {
-            ToolFormat::Claude => {
-                self.claude_chat_completion(model, messages, tools, temperature)
-                    .await?
-            }
-            ToolFormat::OpenAI => {
-                self.openai_chat_completion(model, messages, tools, temperature)
-                    .await?
+        let format = self.tool_format(model);
+        let request = TapeRequest {
+            format: &format,
+            model,
+            messages,
+            tools,
+            temperature,
+            stream: false,
+        };
+        let resp = match self.tape.as_deref() {
+            Some(tape) if tape.mode() != TapeMode::Record => tape
+                .load(&request)?
+                .result
+                .map_err(crate::Error::validation)?,
+            tape => {
+                let result = match format {
+                    ToolFormat::Claude => {
+                        self.claude_chat_completion(model, messages, tools, temperature)
+                            .await
+                    }
+                    ToolFormat::OpenAI => {
+                        self.openai_chat_completion(model, messages, tools, temperature)
+                            .await
+                    }
+                };
+                if let Some(tape) = tape {
+                    tape.save(&request, &[], &result, &self.api_key)?;
+                }
+                result?
             }
         };
         record_llm_usage_totals(usage_totals, &resp.usage);
@@ -126,14 +206,36 @@ impl LlmClient {
         event_sink: &mut dyn EventSink,
         usage_totals: Option<&mut LlmUsageTotals>,
     ) -> Result<ChatCompletionResponse> {
-        let format = detect_tool_format(model, &self.api_base);
-        let resp = match format {
-            ToolFormat::Claude => {
-                self.claude_chat_completion_stream(model, messages, tools, temperature, event_sink)
-                    .await?
+        let format = self.tool_format(model);
+        let request = TapeRequest {
+            format: &format,
+            model,
+            messages,
+            tools,
+            temperature,
+            stream: true,
+        };
+        let resp = match self.tape.as_deref() {
+            Some(tape) if tape.mode() != TapeMode::Record => {
+                let replayed = tape.load(&request)?;
+                for chunk in &replayed.chunks {
+                    event_sink.on_text_chunk(chunk);
+                }
+                replayed.result.map_err(crate::Error::validation)?
             }
-            ToolFormat::OpenAI => {
-                self.openai_chat_completion_stream(model, messages, tools, temperature, event_sink)
+            Some(tape) => {
+                let mut sink = RecordingSink {
+                    inner: event_sink,
+                    chunks: Vec::new(),
+                };
+                let result = self
+                    .dispatch_stream(&format, model, messages, tools, temperature, &mut sink)
+                    .await;
+                tape.save(&request, &sink.chunks, &result, &self.api_key)?;
+                result?
+            }
+            None => {
+                self.dispatch_stream(&format, model, messages, tools, temperature, event_sink)
                     .await?
             }
         };
@@ -241,7 +343,7 @@ impl LlmClient {
 // Fields id/model/usage/index/finish_reason/role are required for API deserialization
 // but not read by our code.
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[allow(dead_code)]
 pub struct ChatCompletionResponse {
     pub id: String,
@@ -250,7 +352,7 @@ pub struct ChatCompletionResponse {
     pub usage: Option<Usage>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[allow(dead_code)]
 pub struct Choice {
     pub index: u32,
@@ -258,7 +360,7 @@ pub struct Choice {
     pub finish_reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[allow(dead_code)]
 pub struct ChoiceMessage {
     pub role: String,
@@ -360,16 +462,49 @@ fn extract_error_detail(body: &str) -> String {
     }
 }
 
+/// Context-overflow markers in OpenAI-compatible error bodies.
+const OPENAI_OVERFLOW_MARKERS: &[&str] = &[
+    "context_length_exceeded",
+    "maximum context length",
+    "token limit",
+    "too many tokens",
+    "context window",
+    "max_tokens",
+];
+
+/// Context-overflow markers in Anthropic error bodies
+/// (`prompt is too long: N tokens > M maximum`, `input length and max_tokens exceed context limit`,
+/// HTTP 413 `request_too_large`).
+const ANTHROPIC_OVERFLOW_MARKERS: &[&str] = &[
+    "prompt is too long",
+    "exceed context limit",
+    "request_too_large",
+];
+
 /// Check if an error is a context overflow (token limit exceeded).
 /// Ported from Python `_is_context_overflow_error`.
+///
+/// The caller usually only has the formatted error text, so markers of every provider are
+/// checked; use [`is_context_overflow_error_for`] when the wire format is known.
 pub fn is_context_overflow_error(err_msg: &str) -> bool {
+    is_context_overflow_error_for(err_msg, &ToolFormat::OpenAI)
+        || is_context_overflow_error_for(err_msg, &ToolFormat::Claude)
+}
+
+/// Provider-specific variant of [`is_context_overflow_error`].
+pub fn is_context_overflow_error_for(err_msg: &str, format: &ToolFormat) -> bool {
     let lower = err_msg.to_lowercase();
-    lower.contains("context_length_exceeded")
-        || lower.contains("maximum context length")
-        || lower.contains("token limit")
-        || lower.contains("too many tokens")
-        || lower.contains("context window")
-        || lower.contains("max_tokens")
+    let markers = match format {
+        ToolFormat::OpenAI => OPENAI_OVERFLOW_MARKERS,
+        ToolFormat::Claude => ANTHROPIC_OVERFLOW_MARKERS,
+    };
+    markers.iter().any(|m| lower.contains(m))
+}
+
+/// Check if an error is a stream that broke off after partial output reached the sink.
+/// Such a turn must be re-run as a whole; the client does not retry it itself.
+pub fn is_stream_interrupted_error(err_msg: &str) -> bool {
+    err_msg.contains(retry::STREAM_INTERRUPTED)
 }
 
 /// Rough total payload size for context budgeting (chars, not tokens).
@@ -400,18 +535,61 @@ fn message_estimated_chars(m: &ChatMessage) -> usize {
     n
 }
 
-/// Truncate all tool result messages in place to reduce context size.
+/// Estimated input tokens for `messages` with the model's tokenizer (see
+/// [`skilllite_core::tokens`]). Same coverage as [`estimate_messages_chars`]; images are
+/// counted as base64 length / 4.
+pub fn estimate_messages_tokens(messages: &[ChatMessage], tokenizer: &Tokenizer) -> usize {
+    messages
+        .iter()
+        .map(|m| message_estimated_tokens(m, tokenizer))
+        .sum()
+}
+
+fn message_estimated_tokens(m: &ChatMessage, tokenizer: &Tokenizer) -> usize {
+    let mut n = m
+        .content
+        .as_deref()
+        .map(|c| tokenizer.count(c))
+        .unwrap_or(0);
+    n = n.saturating_add(
+        m.reasoning_content
+            .as_deref()
+            .map(|c| tokenizer.count(c))
+            .unwrap_or(0),
+    );
+    if let Some(calls) = &m.tool_calls {
+        for c in calls {
+            n = n.saturating_add(tokenizer.count(&c.id));
+            n = n.saturating_add(tokenizer.count(&c.call_type));
+            n = n.saturating_add(tokenizer.count(&c.function.name));
+            n = n.saturating_add(tokenizer.count(&c.function.arguments));
+        }
+    }
+    if let Some(imgs) = &m.images {
+        for img in imgs {
+            n = n.saturating_add(img.data_base64.len() / HEURISTIC_CHARS_PER_TOKEN);
+        }
+    }
+    n
+}
+
+/// Truncate all tool result messages in place to at most `max_tokens` (model tokenizer).
 /// Ported from Python `_truncate_tool_messages_in_place`.
-pub fn truncate_tool_messages(messages: &mut [ChatMessage], max_chars: usize) {
+pub fn truncate_tool_messages(
+    messages: &mut [ChatMessage],
+    max_tokens: usize,
+    tokenizer: &Tokenizer,
+) {
     for msg in messages.iter_mut() {
         if msg.role == "tool" {
             if let Some(ref mut content) = msg.content {
-                if content.len() > max_chars {
+                let tokens = tokenizer.count(content);
+                if tokens > max_tokens {
                     let truncated = format!(
-                        "{}...\n[truncated: {} chars → {}]",
-                        safe_truncate(content, max_chars),
-                        content.len(),
-                        max_chars
+                        "{}...\n[truncated: {} tokens → {}]",
+                        tokenizer.truncate(content, max_tokens),
+                        tokens,
+                        max_tokens
                     );
                     *content = truncated;
                 }
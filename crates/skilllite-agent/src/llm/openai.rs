@@ -2,12 +2,12 @@
 
 use crate::error::bail;
 use crate::Result;
-use anyhow::{anyhow, Context};
+use anyhow::Context;
 use futures_util::StreamExt;
 use serde_json::{json, Value};
 
 use crate::types::{
-    get_max_tokens, ChatMessage, EventSink, FunctionCall, ToolCall, ToolDefinition,
+    get_max_tokens, ChatMessage, EventSink, FunctionCall, SilentEventSink, ToolCall, ToolDefinition,
 };
 
 use super::{
@@ -154,10 +154,6 @@ fn is_minimax(api_base: &str) -> bool {
     api_base.to_lowercase().contains("minimax")
 }
 
-fn openai_send_err(url: &str, e: reqwest::Error) -> anyhow::Error {
-    anyhow!("LLM API request failed (POST {}): {}", url, e)
-}
-
 impl LlmClient {
     pub(super) async fn openai_chat_completion(
         &self,
@@ -200,21 +196,16 @@ impl LlmClient {
             }
         }
 
+        let build = || {
+            self.http
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .header("Content-Type", "application/json")
+                .json(&body)
+        };
         let resp = self
-            .http
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| openai_send_err(&url, e))?;
-
-        let status = resp.status();
-        if !status.is_success() {
-            let body_text = resp.text().await.unwrap_or_default();
-            bail!("{}", super::format_api_error(status, &body_text, "LLM"));
-        }
+            .send_with_retry(&url, "LLM", build, &mut SilentEventSink)
+            .await?;
 
         let response: ChatCompletionResponse = resp
             .json()
@@ -272,21 +263,14 @@ impl LlmClient {
             }
         }
 
-        let resp = self
-            .http
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| openai_send_err(&url, e))?;
-
-        let status = resp.status();
-        if !status.is_success() {
-            let body_text = resp.text().await.unwrap_or_default();
-            bail!("{}", super::format_api_error(status, &body_text, "LLM"));
-        }
+        let build = || {
+            self.http
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .header("Content-Type", "application/json")
+                .json(&body)
+        };
+        let resp = self.send_with_retry(&url, "LLM", build, event_sink).await?;
 
         self.accumulate_openai_stream(resp, event_sink).await
     }
@@ -316,7 +300,8 @@ impl LlmClient {
         let mut stream = resp.bytes_stream();
 
         while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.context("Stream chunk error")?;
+            let chunk = chunk_result
+                .map_err(|e| super::retry::stream_failure(format!("Stream chunk error: {}", e)))?;
             buffer.push_str(&String::from_utf8_lossy(&chunk));
 
             // Process complete lines
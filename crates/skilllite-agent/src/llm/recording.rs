@@ -0,0 +1,445 @@
+//! Request/response recording and replay for deterministic agent-loop runs.
+//!
+//! - **Record** (`SKILLLITE_LLM_RECORD=dir`): every call is written to `dir/0000.json`,
+//!   `dir/0001.json`, … with the request, the normalized response (or error) and, for
+//!   streaming calls, the text chunks that reached the event sink.
+//! - **Replay** (`SKILLLITE_LLM_REPLAY=dir`): calls are answered from those files in order,
+//!   without network. Each request is hash-checked against the recording to detect drift;
+//!   loose mode (`SKILLLITE_LLM_REPLAY_LOOSE=1` / `--loose`) only compares message counts.
+//!
+//! The request hash covers format, model, stream flag, temperature, tool names and every
+//! non-system message. System prompts are stored but not hashed: they embed the current
+//! date and the workspace path, which would make every recording drift the next day.
+//!
+//! Stored payloads never contain credentials: the API key and any `Authorization` /
+//! `x-api-key` values are replaced with `[REDACTED]` before writing.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use anyhow::Context;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::error::bail;
+use crate::Result;
+
+use super::super::types::{
+    ChatMessage, ConfirmationRequest, EventSink, ToolDefinition, ToolFormat,
+};
+use super::ChatCompletionResponse;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Header / field names whose values are always scrubbed.
+const SECRET_FIELDS: &[&str] = &["authorization", "x-api-key", "api_key", "api-key"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapeMode {
+    Record,
+    Replay { loose: bool },
+}
+
+/// A recording directory plus the index of the next call. Shared (via `Arc`) by every
+/// `LlmClient` built from the same [`AgentConfig`](crate::types::AgentConfig), so helper
+/// calls (planning, compaction, summaries) land in the same numbered sequence.
+#[derive(Debug)]
+pub struct LlmTape {
+    dir: PathBuf,
+    mode: TapeMode,
+    next: AtomicUsize,
+}
+
+/// What one LLM call asked for, in the shape that is stored and hashed.
+pub(super) struct TapeRequest<'a> {
+    pub format: &'a ToolFormat,
+    pub model: &'a str,
+    pub messages: &'a [ChatMessage],
+    pub tools: Option<&'a [ToolDefinition]>,
+    pub temperature: Option<f64>,
+    pub stream: bool,
+}
+
+/// A replayed call: the streamed text chunks and the stored outcome.
+#[derive(Debug)]
+pub(super) struct ReplayedCall {
+    pub chunks: Vec<String>,
+    pub result: std::result::Result<ChatCompletionResponse, String>,
+}
+
+impl LlmTape {
+    /// Start recording into `dir` (created if missing). Existing numbered files are
+    /// overwritten from `0000.json` on.
+    pub fn record(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("create LLM recording dir {}", dir.display()))?;
+        Ok(Self {
+            dir,
+            mode: TapeMode::Record,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Replay from `dir`. `loose` matches requests on message count only.
+    pub fn replay(dir: impl Into<PathBuf>, loose: bool) -> Self {
+        Self {
+            dir: dir.into(),
+            mode: TapeMode::Replay { loose },
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// The process-wide tape from `SKILLLITE_LLM_REPLAY` / `SKILLLITE_LLM_RECORD`, read once
+    /// so every `AgentConfig::from_env` (one per RPC turn, command, …) continues the same
+    /// numbering. Replay wins when both are set; a recording dir that cannot be created
+    /// disables recording with a warning.
+    pub fn from_env() -> Option<Arc<Self>> {
+        static TAPE: OnceLock<Option<Arc<LlmTape>>> = OnceLock::new();
+        TAPE.get_or_init(|| {
+            use skilllite_core::config::env_keys::llm;
+            use skilllite_core::config::loader::{env_bool, env_optional};
+            if let Some(dir) = env_optional(llm::REPLAY, &[]) {
+                let loose = env_bool(llm::REPLAY_LOOSE, &[], false);
+                return Some(Arc::new(Self::replay(dir, loose)));
+            }
+            let dir = env_optional(llm::RECORD, &[])?;
+            match Self::record(&dir) {
+                Ok(tape) => Some(Arc::new(tape)),
+                Err(e) => {
+                    tracing::warn!("LLM recording disabled: {}", e);
+                    None
+                }
+            }
+        })
+        .clone()
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn mode(&self) -> TapeMode {
+        self.mode
+    }
+
+    /// Number of calls recorded or replayed so far.
+    pub fn calls(&self) -> usize {
+        self.next.load(Ordering::SeqCst)
+    }
+
+    fn file_for(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{:04}.json", index))
+    }
+
+    /// Write the next numbered file for a finished call.
+    pub(super) fn save(
+        &self,
+        request: &TapeRequest<'_>,
+        chunks: &[String],
+        result: &Result<ChatCompletionResponse>,
+        api_key: &str,
+    ) -> Result<()> {
+        let index = self.next.fetch_add(1, Ordering::SeqCst);
+        let mut entry = request.to_json();
+        entry["index"] = json!(index);
+        entry["chunks"] = json!(chunks);
+        match result {
+            Ok(resp) => entry["response"] = serde_json::to_value(resp)?,
+            Err(e) => entry["error"] = json!(e.to_string()),
+        }
+        scrub(&mut entry, api_key);
+        let path = self.file_for(index);
+        let text = serde_json::to_string_pretty(&entry)?;
+        std::fs::write(&path, text)
+            .with_context(|| format!("write LLM recording {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Load the next numbered file, checking it was recorded for an equivalent request.
+    pub(super) fn load(&self, request: &TapeRequest<'_>) -> Result<ReplayedCall> {
+        let loose = matches!(self.mode, TapeMode::Replay { loose: true });
+        let index = self.next.fetch_add(1, Ordering::SeqCst);
+        let path = self.file_for(index);
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let text = std::fs::read_to_string(&path).with_context(|| {
+            format!(
+                "LLM replay exhausted: no recording {} for call #{}",
+                path.display(),
+                index
+            )
+        })?;
+        let entry: Value = serde_json::from_str(&text)
+            .with_context(|| format!("parse LLM recording {}", path.display()))?;
+
+        if loose {
+            let recorded = entry.get("message_count").and_then(Value::as_u64);
+            if recorded != Some(request.messages.len() as u64) {
+                bail!(
+                    "LLM replay drift at {}: request has {} messages, recording has {}",
+                    name,
+                    request.messages.len(),
+                    recorded.map_or("none".to_string(), |n| n.to_string())
+                );
+            }
+        } else {
+            let recorded = entry.get("request_hash").and_then(Value::as_str);
+            let actual = request.hash();
+            if recorded != Some(actual.as_str()) {
+                bail!(
+                    "LLM replay drift at {}: request hash {} does not match recorded {} \
+                     (replay loosely to match on message count only)",
+                    name,
+                    &actual[..12],
+                    recorded.map_or("none", |h| &h[..h.len().min(12)])
+                );
+            }
+        }
+
+        let chunks = entry
+            .get("chunks")
+            .and_then(Value::as_array)
+            .map(|a| {
+                a.iter()
+                    .filter_map(|c| c.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let result = match (entry.get("response"), entry.get("error")) {
+            (Some(resp), _) => Ok(serde_json::from_value(resp.clone())
+                .with_context(|| format!("LLM recording {} has an invalid response", name))?),
+            (None, Some(err)) => Err(err.as_str().unwrap_or("recorded LLM error").to_string()),
+            (None, None) => bail!("LLM recording {} has neither response nor error", name),
+        };
+        Ok(ReplayedCall { chunks, result })
+    }
+}
+
+impl TapeRequest<'_> {
+    fn format_name(&self) -> &'static str {
+        match self.format {
+            ToolFormat::OpenAI => "openai",
+            ToolFormat::Claude => "anthropic",
+        }
+    }
+
+    fn tool_names(&self) -> Vec<&str> {
+        self.tools
+            .unwrap_or_default()
+            .iter()
+            .map(|t| t.function.name.as_str())
+            .collect()
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "format": self.format_name(),
+            "model": self.model,
+            "stream": self.stream,
+            "temperature": self.temperature,
+            "message_count": self.messages.len(),
+            "request_hash": self.hash(),
+            "request": {
+                "messages": self.messages,
+                "tools": self.tool_names(),
+            },
+        })
+    }
+
+    /// SHA-256 over the drift-relevant part of the request (see module docs).
+    pub(super) fn hash(&self) -> String {
+        let conversation: Vec<&ChatMessage> = self
+            .messages
+            .iter()
+            .filter(|m| m.role != "system")
+            .collect();
+        let canonical = json!({
+            "format": self.format_name(),
+            "model": self.model,
+            "stream": self.stream,
+            "temperature": self.temperature,
+            "tools": self.tool_names(),
+            "messages": conversation,
+        });
+        hex::encode(Sha256::digest(canonical.to_string().as_bytes()))
+    }
+}
+
+/// Replace credentials anywhere in a stored payload.
+fn scrub(value: &mut Value, api_key: &str) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if SECRET_FIELDS.iter().any(|f| k.eq_ignore_ascii_case(f)) {
+                    *v = json!(REDACTED);
+                } else {
+                    scrub(v, api_key);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| scrub(v, api_key)),
+        Value::String(s) => {
+            if !api_key.trim().is_empty() && s.contains(api_key) {
+                *s = s.replace(api_key, REDACTED);
+            }
+            if let Some(pos) = s.find("Bearer ") {
+                let end = s[pos + 7..]
+                    .find(char::is_whitespace)
+                    .map_or(s.len(), |e| pos + 7 + e);
+                s.replace_range(pos + 7..end, REDACTED);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Forwards streamed text to the real sink while keeping a copy for the recording.
+pub(super) struct RecordingSink<'a> {
+    pub inner: &'a mut dyn EventSink,
+    pub chunks: Vec<String>,
+}
+
+impl EventSink for RecordingSink<'_> {
+    fn on_text(&mut self, text: &str) {
+        self.inner.on_text(text);
+    }
+    fn on_text_chunk(&mut self, chunk: &str) {
+        self.chunks.push(chunk.to_string());
+        self.inner.on_text_chunk(chunk);
+    }
+    fn on_status(&mut self, message: &str) {
+        self.inner.on_status(message);
+    }
+    fn on_tool_call(&mut self, name: &str, arguments: &str) {
+        self.inner.on_tool_call(name, arguments);
+    }
+    fn on_tool_result(&mut self, name: &str, result: &str, is_error: bool) {
+        self.inner.on_tool_result(name, result, is_error);
+    }
+    fn on_confirmation_request(&mut self, request: &ConfirmationRequest) -> bool {
+        self.inner.on_confirmation_request(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FunctionCall, ToolCall};
+
+    fn response(text: &str) -> ChatCompletionResponse {
+        serde_json::from_value(json!({
+            "id": "r", "model": "m",
+            "choices": [{ "index": 0, "finish_reason": "stop",
+                          "message": { "role": "assistant", "content": text } }],
+            "usage": { "prompt_tokens": 3, "completion_tokens": 2, "total_tokens": 5 }
+        }))
+        .unwrap()
+    }
+
+    fn request<'a>(messages: &'a [ChatMessage], format: &'a ToolFormat) -> TapeRequest<'a> {
+        TapeRequest {
+            format,
+            model: "m",
+            messages,
+            tools: None,
+            temperature: Some(0.2),
+            stream: true,
+        }
+    }
+
+    #[test]
+    fn recorded_calls_replay_in_order_and_detect_drift() {
+        let dir = tempfile::tempdir().unwrap();
+        let format = ToolFormat::OpenAI;
+        let first = vec![
+            ChatMessage::system("today"),
+            ChatMessage::user("读一下 a.txt"),
+        ];
+        let mut second = first.clone();
+        second.push(ChatMessage::assistant_with_tool_calls(
+            None,
+            vec![ToolCall {
+                id: "c1".into(),
+                call_type: "function".into(),
+                function: FunctionCall {
+                    name: "read_file".into(),
+                    arguments: r#"{"path":"a.txt"}"#.into(),
+                },
+            }],
+        ));
+        second.push(ChatMessage::tool_result("c1", "hello"));
+
+        let tape = LlmTape::record(dir.path()).unwrap();
+        tape.save(&request(&first, &format), &[], &Ok(response("x")), "k")
+            .unwrap();
+        let chunks = vec!["Done".to_string(), "\n".to_string()];
+        tape.save(
+            &request(&second, &format),
+            &chunks,
+            &Ok(response("Done")),
+            "k",
+        )
+        .unwrap();
+        assert_eq!(tape.calls(), 2);
+
+        // A different system prompt (new date / tempdir) is not drift.
+        let mut first_tomorrow = first.clone();
+        first_tomorrow[0] = ChatMessage::system("tomorrow");
+        let replay = LlmTape::replay(dir.path(), false);
+        replay.load(&request(&first_tomorrow, &format)).unwrap();
+        let call = replay.load(&request(&second, &format)).unwrap();
+        assert_eq!(call.chunks, chunks);
+        assert_eq!(
+            call.result.unwrap().choices[0].message.content.as_deref(),
+            Some("Done")
+        );
+        let err = replay.load(&request(&first, &format)).unwrap_err();
+        assert!(err.to_string().contains("exhausted"), "{err}");
+
+        // Conversation drift fails strict replay but passes loose replay.
+        let mut edited = second.clone();
+        edited[3] = ChatMessage::tool_result("c1", "changed");
+        let strict = LlmTape::replay(dir.path(), false);
+        strict.load(&request(&first, &format)).unwrap();
+        let err = strict.load(&request(&edited, &format)).unwrap_err();
+        assert!(err.to_string().contains("drift at 0001.json"), "{err}");
+        let loose = LlmTape::replay(dir.path(), true);
+        loose.load(&request(&first, &format)).unwrap();
+        loose.load(&request(&edited, &format)).unwrap();
+        let err = LlmTape::replay(dir.path(), true)
+            .load(&request(&second, &format))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("has 4 messages, recording has 2"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn recordings_never_contain_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let format = ToolFormat::Claude;
+        let messages = vec![ChatMessage::user(
+            "my key is sk-live-123456 and header Authorization: Bearer abc.def",
+        )];
+        let tape = LlmTape::record(dir.path()).unwrap();
+        let err: Result<ChatCompletionResponse> =
+            Err(anyhow::anyhow!("401 for x-api-key sk-live-123456").into());
+        tape.save(&request(&messages, &format), &[], &err, "sk-live-123456")
+            .unwrap();
+
+        let text = std::fs::read_to_string(dir.path().join("0000.json")).unwrap();
+        assert!(!text.contains("sk-live-123456"), "{text}");
+        assert!(!text.contains("abc.def"), "{text}");
+        let mut headers = json!({ "Authorization": "Bearer x", "X-Api-Key": "y" });
+        scrub(&mut headers, "");
+        assert_eq!(headers["Authorization"], REDACTED);
+        assert_eq!(headers["X-Api-Key"], REDACTED);
+
+        let replayed = LlmTape::replay(dir.path(), false)
+            .load(&request(&messages, &format))
+            .unwrap();
+        assert!(replayed.result.unwrap_err().contains("401"));
+    }
+}
@@ -0,0 +1,378 @@
+//! Request-level retry for [`LlmClient`].
+//!
+//! Connection failures, HTTP 429 / 408 / 5xx and provider overload (529) are retried with
+//! capped exponential backoff. Server hints win over the computed delay: `retry-after-ms`
+//! (OpenAI), `Retry-After` (seconds or HTTP date), and the "try again in 1.5s" /
+//! `"retryDelay": "7s"` hints OpenAI- and Gemini-style gateways put in rate-limit bodies.
+//! Each wait is reported through [`EventSink::on_status`] so the UI does not look frozen.
+//!
+//! A stream is only retried while nothing visible has reached the sink. Once text was
+//! streamed, a broken connection fails with [`STREAM_INTERRUPTED`] instead, so the agent loop
+//! re-runs the turn rather than this layer silently printing the same text twice.
+
+use std::fmt;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::Regex;
+use reqwest::header::HeaderMap;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use skilllite_core::config::Settings;
+
+use crate::types::{ChatMessage, ConfirmationRequest, EventSink, ToolDefinition, ToolFormat};
+use crate::Result;
+
+use super::{format_api_error, ChatCompletionResponse, LlmClient};
+
+/// Prefix of the error returned when a stream breaks after text reached the sink
+/// (see [`super::is_stream_interrupted_error`]).
+pub(crate) const STREAM_INTERRUPTED: &str = "LLM stream interrupted after partial output";
+
+/// Retry policy for LLM requests (`SKILLLITE_LLM_MAX_ATTEMPTS`,
+/// `SKILLLITE_LLM_RETRY_MAX_BACKOFF_SECS`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first.
+    pub max_attempts: u32,
+    /// First backoff delay; doubles per attempt up to `max_backoff`.
+    pub base_delay: Duration,
+    /// Ceiling for the computed backoff and for server-provided retry hints.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let base = Self::default();
+        Self {
+            max_attempts: settings
+                .get_i64("llm.max_attempts")
+                .map(|v| v.clamp(1, 10) as u32)
+                .unwrap_or(base.max_attempts),
+            max_backoff: settings
+                .get_i64("llm.retry_max_backoff_secs")
+                .map(|v| Duration::from_secs(v.max(0) as u64))
+                .unwrap_or(base.max_backoff),
+            ..base
+        }
+    }
+
+    /// Delay before retry `attempt` (1-based): the server's hint when given, else
+    /// `base_delay * 2^(attempt - 1)`; capped at `max_backoff` either way.
+    fn delay(&self, attempt: u32, hint: Option<Duration>) -> Duration {
+        hint.unwrap_or_else(|| {
+            self.base_delay
+                .saturating_mul(1u32 << attempt.saturating_sub(1).min(16))
+        })
+        .min(self.max_backoff)
+    }
+}
+
+/// Transient failure while reading a streamed body (broken connection, provider `error` event
+/// such as `overloaded_error`). Retried only while nothing was emitted to the sink.
+#[derive(Debug)]
+struct StreamFailure(String);
+
+impl fmt::Display for StreamFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for StreamFailure {}
+
+pub(super) fn stream_failure(message: impl Into<String>) -> crate::Error {
+    crate::Error::Other(anyhow::Error::new(StreamFailure(message.into())))
+}
+
+fn is_stream_failure(err: &crate::Error) -> bool {
+    matches!(err, crate::Error::Other(e) if e.is::<StreamFailure>())
+}
+
+/// Why a non-success status may be retried; `None` = fail immediately.
+fn retry_reason(status: StatusCode, body: &str) -> Option<String> {
+    match status.as_u16() {
+        // OpenAI answers 429 for an exhausted quota too; waiting will not refill it.
+        429 if body.contains("insufficient_quota") => None,
+        429 => Some("rate limited".to_string()),
+        529 => Some("provider overloaded".to_string()),
+        code @ (408 | 500 | 502 | 503 | 504) => Some(format!("server error (HTTP {})", code)),
+        _ => None,
+    }
+}
+
+/// `retry-after-ms` (OpenAI), then `Retry-After` as seconds or an HTTP date.
+fn header_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+    };
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.parse::<f64>().ok()) {
+        return secs_to_duration(ms / 1000.0);
+    }
+    let value = header("retry-after")?;
+    if let Ok(secs) = value.parse::<f64>() {
+        return secs_to_duration(secs);
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(wait.to_std().unwrap_or_default())
+}
+
+/// Retry hints inside rate-limit bodies: OpenAI-style `Please try again in 1.5s` / `in 250ms`,
+/// Gemini-style `"retryDelay": "7s"`.
+fn body_retry_after(body: &str) -> Option<Duration> {
+    static TRY_AGAIN: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"(?i)(?:try again in|"retryDelay"\s*:\s*")\s*(\d+(?:\.\d+)?)\s*(ms|s)\b"#)
+            .expect("valid regex")
+    });
+    let caps = TRY_AGAIN.captures(body)?;
+    let value: f64 = caps[1].parse().ok()?;
+    match &caps[2] {
+        "ms" => secs_to_duration(value / 1000.0),
+        _ => secs_to_duration(value),
+    }
+}
+
+fn secs_to_duration(secs: f64) -> Option<Duration> {
+    (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+fn format_delay(delay: Duration) -> String {
+    if delay.subsec_millis() == 0 {
+        format!("{}s", delay.as_secs())
+    } else {
+        format!("{:.1}s", delay.as_secs_f64())
+    }
+}
+
+/// Forwards to the caller's sink and notes whether any visible text went out.
+struct StreamProgress<'a> {
+    inner: &'a mut dyn EventSink,
+    emitted: bool,
+}
+
+impl EventSink for StreamProgress<'_> {
+    fn on_text(&mut self, text: &str) {
+        self.inner.on_text(text);
+    }
+    fn on_text_chunk(&mut self, chunk: &str) {
+        self.emitted = true;
+        self.inner.on_text_chunk(chunk);
+    }
+    fn on_status(&mut self, message: &str) {
+        self.inner.on_status(message);
+    }
+    fn on_tool_call(&mut self, name: &str, arguments: &str) {
+        self.inner.on_tool_call(name, arguments);
+    }
+    fn on_tool_result(&mut self, name: &str, result: &str, is_error: bool) {
+        self.inner.on_tool_result(name, result, is_error);
+    }
+    fn on_confirmation_request(&mut self, request: &ConfirmationRequest) -> bool {
+        self.inner.on_confirmation_request(request)
+    }
+}
+
+impl LlmClient {
+    /// Send the request `build` produces (rebuilt for every attempt) and return the first
+    /// successful response. Failures that are not retried, or that exhaust
+    /// [`RetryPolicy::max_attempts`], become errors labelled with `provider`.
+    pub(super) async fn send_with_retry(
+        &self,
+        url: &str,
+        provider: &str,
+        build: impl Fn() -> RequestBuilder,
+        status_sink: &mut dyn EventSink,
+    ) -> Result<Response> {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let (err, reason, hint) = match build().send().await {
+                Ok(resp) if resp.status().is_success() => return Ok(resp),
+                Ok(resp) => {
+                    let status = resp.status();
+                    let header_hint = header_retry_after(resp.headers());
+                    let body = resp.text().await.unwrap_or_default();
+                    let err = crate::Error::validation(format_api_error(status, &body, provider));
+                    let Some(reason) = retry_reason(status, &body) else {
+                        return Err(err);
+                    };
+                    (err, reason, header_hint.or_else(|| body_retry_after(&body)))
+                }
+                Err(e) => {
+                    let retryable = e.is_connect() || e.is_timeout() || e.is_request();
+                    let err = crate::Error::Other(anyhow::anyhow!(
+                        "{} API request failed (POST {}): {}",
+                        provider,
+                        url,
+                        e
+                    ));
+                    if !retryable {
+                        return Err(err);
+                    }
+                    (err, "connection failed".to_string(), None)
+                }
+            };
+            if attempt >= max_attempts {
+                return Err(err);
+            }
+            let delay = self.retry.delay(attempt, hint);
+            tracing::warn!(
+                "LLM request failed (attempt {}/{}), retrying in {}ms: {}",
+                attempt,
+                max_attempts,
+                delay.as_millis(),
+                err
+            );
+            status_sink.on_status(&format!("{}, retrying in {}", reason, format_delay(delay)));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Streaming request with whole-stream retry while nothing was shown yet; see the module
+    /// docs for what happens after partial output.
+    pub(super) async fn dispatch_stream(
+        &self,
+        format: &ToolFormat,
+        model: &str,
+        messages: &[ChatMessage],
+        tools: Option<&[ToolDefinition]>,
+        temperature: Option<f64>,
+        event_sink: &mut dyn EventSink,
+    ) -> Result<ChatCompletionResponse> {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let mut progress = StreamProgress {
+                inner: &mut *event_sink,
+                emitted: false,
+            };
+            let result = match format {
+                ToolFormat::Claude => {
+                    self.claude_chat_completion_stream(
+                        model,
+                        messages,
+                        tools,
+                        temperature,
+                        &mut progress,
+                    )
+                    .await
+                }
+                ToolFormat::OpenAI => {
+                    self.openai_chat_completion_stream(
+                        model,
+                        messages,
+                        tools,
+                        temperature,
+                        &mut progress,
+                    )
+                    .await
+                }
+            };
+            let emitted = progress.emitted;
+            let err = match result {
+                Ok(resp) => return Ok(resp),
+                Err(e) if !is_stream_failure(&e) => return Err(e),
+                Err(e) => e,
+            };
+            if emitted {
+                return Err(crate::Error::validation(format!(
+                    "{}: {}",
+                    STREAM_INTERRUPTED, err
+                )));
+            }
+            if attempt >= max_attempts {
+                return Err(err);
+            }
+            let delay = self.retry.delay(attempt, None);
+            tracing::warn!(
+                "LLM stream failed before any output (attempt {}/{}), retrying in {}ms: {}",
+                attempt,
+                max_attempts,
+                delay.as_millis(),
+                err
+            );
+            event_sink.on_status(&format!(
+                "response stream broke off, retrying in {}",
+                format_delay(delay)
+            ));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn retry_hints_from_headers_and_bodies_are_capped() {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("4"));
+        assert_eq!(header_retry_after(&headers), Some(Duration::from_secs(4)));
+        headers.insert("retry-after-ms", HeaderValue::from_static("1500"));
+        assert_eq!(
+            header_retry_after(&headers),
+            Some(Duration::from_millis(1500))
+        );
+        let mut dated = HeaderMap::new();
+        dated.insert(
+            "retry-after",
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(header_retry_after(&dated), Some(Duration::ZERO));
+
+        let openai = r#"{"error":{"message":"Rate limit reached for gpt-4o. Please try again in 1.5s.","type":"requests"}}"#;
+        assert_eq!(body_retry_after(openai), Some(Duration::from_millis(1500)));
+        assert_eq!(
+            body_retry_after("try again in 250ms"),
+            Some(Duration::from_millis(250))
+        );
+        let gemini = r#"{"error":{"details":[{"retryDelay": "7s"}]}}"#;
+        assert_eq!(body_retry_after(gemini), Some(Duration::from_secs(7)));
+        assert_eq!(body_retry_after("slow down"), None);
+
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+        };
+        let delays: Vec<u64> = (1..=4).map(|a| policy.delay(a, None).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 5]);
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(60))),
+            Duration::from_secs(5)
+        );
+
+        assert_eq!(
+            retry_reason(StatusCode::TOO_MANY_REQUESTS, "{}").as_deref(),
+            Some("rate limited")
+        );
+        assert_eq!(
+            retry_reason(
+                StatusCode::TOO_MANY_REQUESTS,
+                r#"{"error":{"code":"insufficient_quota"}}"#
+            ),
+            None
+        );
+        assert!(retry_reason(StatusCode::from_u16(529).unwrap(), "").is_some());
+        assert_eq!(retry_reason(StatusCode::UNAUTHORIZED, ""), None);
+        assert_eq!(format_delay(Duration::from_secs(4)), "4s");
+        assert_eq!(format_delay(Duration::from_millis(1500)), "1.5s");
+    }
+}
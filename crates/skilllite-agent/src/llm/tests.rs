@@ -289,6 +289,25 @@ fn test_estimate_messages_chars_sums_content_and_tool_calls() {
     assert_eq!(n, 5 + (6 + 8 + 9 + 12) + (1 + 4));
 }
 
+#[test]
+fn test_truncate_tool_messages_uses_token_budget() {
+    use skilllite_core::tokens::TokenizerFamily;
+    let tokenizer = Tokenizer::heuristic(TokenizerFamily::Heuristic);
+    let mut msgs = vec![
+        ChatMessage::user(&"u".repeat(400)),
+        ChatMessage::tool_result("call-1", &"x".repeat(400)),
+        ChatMessage::tool_result("call-2", "short"),
+    ];
+    assert_eq!(estimate_messages_tokens(&msgs, &tokenizer), 100 + 100 + 2);
+
+    truncate_tool_messages(&mut msgs, 10, &tokenizer);
+    assert_eq!(msgs[0].content.as_deref().map(str::len), Some(400));
+    let cut = msgs[1].content.as_deref().unwrap();
+    assert!(cut.starts_with(&format!("{}...", "x".repeat(40))), "{cut}");
+    assert!(cut.ends_with("[truncated: 100 tokens → 10]"), "{cut}");
+    assert_eq!(msgs[2].content.as_deref(), Some("short"));
+}
+
 // ─── format_api_error tests ─────────────────────────────────────────────────
 
 #[test]
@@ -408,3 +427,356 @@ fn test_format_api_error_truncates_non_json_body_on_utf8_boundary() {
         "should truncate the long raw body: {result}"
     );
 }
+
+#[test]
+fn test_is_context_overflow_error_per_provider() {
+    let anthropic =
+        "Claude API 错误 (HTTP 400): prompt is too long: 212345 tokens > 200000 maximum";
+    assert!(is_context_overflow_error(anthropic));
+    assert!(is_context_overflow_error_for(
+        anthropic,
+        &ToolFormat::Claude
+    ));
+    assert!(!is_context_overflow_error_for(
+        anthropic,
+        &ToolFormat::OpenAI
+    ));
+    assert!(is_context_overflow_error_for(
+        "input length and `max_tokens` exceed context limit: 190000 + 16384 > 200000",
+        &ToolFormat::Claude
+    ));
+    assert!(!is_context_overflow_error_for(
+        "context_length_exceeded",
+        &ToolFormat::Claude
+    ));
+    assert!(!is_context_overflow_error_for(
+        "overloaded_error: Overloaded",
+        &ToolFormat::Claude
+    ));
+}
+
+#[test]
+fn test_with_provider_overrides_detection() {
+    let client = LlmClient::new("https://proxy.example.com/v1", "k").unwrap();
+    assert_eq!(client.tool_format("my-model"), ToolFormat::OpenAI);
+    let client = client.with_provider(Some("Anthropic"));
+    assert_eq!(client.tool_format("my-model"), ToolFormat::Claude);
+    let client = LlmClient::new("https://api.anthropic.com", "k")
+        .unwrap()
+        .with_provider(Some("openai"));
+    assert_eq!(client.tool_format("claude-sonnet-4"), ToolFormat::OpenAI);
+    // Unknown names fall back to detection.
+    let client = LlmClient::new("https://api.anthropic.com", "k")
+        .unwrap()
+        .with_provider(Some("gemini"));
+    assert_eq!(client.tool_format("x"), ToolFormat::Claude);
+}
+
+// ─── Recorded two-tool-call exchanges, one per provider ─────────────────────
+
+/// Serve one recorded SSE response; returns the `api_base` and the captured
+/// `(request line, JSON body)`.
+fn serve_fixture(sse: &'static str) -> (String, std::sync::mpsc::Receiver<(String, Value)>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut content_length = 0usize;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                break;
+            }
+            if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                content_length = v.trim().parse().unwrap_or(0);
+            }
+        }
+        let mut body = vec![0u8; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let _ = write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n{}",
+            sse
+        );
+        let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+        let _ = tx.send((request_line.trim().to_string(), body));
+    });
+    (format!("http://{}/v1", addr), rx)
+}
+
+fn two_tool_history() -> (Vec<ChatMessage>, Vec<ToolDefinition>) {
+    let call = |id: &str, name: &str, args: &str| ToolCall {
+        id: id.to_string(),
+        call_type: "function".to_string(),
+        function: FunctionCall {
+            name: name.to_string(),
+            arguments: args.to_string(),
+        },
+    };
+    let messages = vec![
+        ChatMessage::system("You are helpful."),
+        ChatMessage::user("看看 docs 和 notes"),
+        ChatMessage::assistant_with_tool_calls(
+            None,
+            vec![
+                call("call_0", "list_directory", r#"{"path":"docs"}"#),
+                call("call_1", "list_directory", r#"{"path":"notes"}"#),
+            ],
+        ),
+        ChatMessage::tool_result("call_0", "README.md"),
+        ChatMessage::tool_result("call_1", "todo.md"),
+    ];
+    let tool = |name: &str| ToolDefinition {
+        tool_type: "function".to_string(),
+        function: crate::types::FunctionDef {
+            name: name.to_string(),
+            description: format!("{name} tool"),
+            parameters: json!({
+                "type": "object",
+                "properties": { "path": { "type": "string" } },
+                "required": ["path"]
+            }),
+        },
+    };
+    (messages, vec![tool("read_file"), tool("list_directory")])
+}
+
+#[derive(Default)]
+struct ChunkSink {
+    chunks: Vec<String>,
+    statuses: Vec<String>,
+}
+
+impl EventSink for ChunkSink {
+    fn on_text(&mut self, _text: &str) {}
+    fn on_text_chunk(&mut self, chunk: &str) {
+        self.chunks.push(chunk.to_string());
+    }
+    fn on_status(&mut self, message: &str) {
+        self.statuses.push(message.to_string());
+    }
+    fn on_tool_call(&mut self, _name: &str, _arguments: &str) {}
+    fn on_tool_result(&mut self, _name: &str, _result: &str, _is_error: bool) {}
+    fn on_confirmation_request(&mut self, _request: &crate::types::ConfirmationRequest) -> bool {
+        false
+    }
+}
+
+/// Both providers must normalize to the same response and sink calls.
+fn assert_normalized_two_tool_calls(
+    resp: &ChatCompletionResponse,
+    sink: &ChunkSink,
+    ids: [&str; 2],
+) {
+    let choice = &resp.choices[0];
+    assert_eq!(choice.finish_reason.as_deref(), Some("tool_calls"));
+    assert_eq!(
+        choice.message.content.as_deref(),
+        Some("Checking both files.")
+    );
+    let calls = choice.message.tool_calls.as_ref().unwrap();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].id, ids[0]);
+    assert_eq!(calls[0].function.name, "read_file");
+    assert_eq!(calls[0].function.arguments, r#"{"path": "README.md"}"#);
+    assert_eq!(calls[1].id, ids[1]);
+    assert_eq!(calls[1].function.name, "list_directory");
+    assert_eq!(calls[1].function.arguments, r#"{"path": "src"}"#);
+    assert_eq!(sink.chunks, vec!["Checking both files.", "\n"]);
+    let usage = resp.usage.as_ref().unwrap();
+    assert_eq!(
+        (
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage.total_tokens
+        ),
+        (412, 58, 470)
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_openai_fixture_two_tool_call_exchange() {
+    let (base, request) = serve_fixture(include_str!("fixtures/openai_two_tool_calls.sse"));
+    let client = LlmClient::new(&base, "k")
+        .unwrap()
+        .with_provider(Some("openai"));
+    let (messages, tools) = two_tool_history();
+    let mut sink = ChunkSink::default();
+    let resp = client
+        .chat_completion_stream("test-model", &messages, Some(&tools), None, &mut sink, None)
+        .await
+        .unwrap();
+
+    let (line, body) = request.recv().unwrap();
+    assert!(line.starts_with("POST /v1/chat/completions "), "{line}");
+    assert_eq!(body["tools"][0]["function"]["name"], "read_file");
+    let sent = body["messages"].as_array().unwrap();
+    assert_eq!(sent.len(), 5);
+    assert_eq!(sent[2]["tool_calls"].as_array().unwrap().len(), 2);
+    assert_eq!(sent[3]["role"], "tool");
+    assert_eq!(sent[4]["tool_call_id"], "call_1");
+    assert_normalized_two_tool_calls(&resp, &sink, ["call_a1", "call_b2"]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_anthropic_fixture_two_tool_call_exchange() {
+    let (base, request) = serve_fixture(include_str!("fixtures/anthropic_two_tool_calls.sse"));
+    let client = LlmClient::new(&base, "k")
+        .unwrap()
+        .with_provider(Some("anthropic"));
+    let (messages, tools) = two_tool_history();
+    let mut sink = ChunkSink::default();
+    let resp = client
+        .chat_completion_stream("test-model", &messages, Some(&tools), None, &mut sink, None)
+        .await
+        .unwrap();
+
+    let (line, body) = request.recv().unwrap();
+    assert!(line.starts_with("POST /v1/messages "), "{line}");
+    assert_eq!(body["system"], "You are helpful.");
+    assert_eq!(body["tools"][1]["name"], "list_directory");
+    assert!(body["tools"][1]["input_schema"].is_object());
+    let sent = body["messages"].as_array().unwrap();
+    assert_eq!(sent.len(), 3);
+    let tool_use = sent[1]["content"].as_array().unwrap();
+    assert_eq!(tool_use[1]["type"], "tool_use");
+    assert_eq!(tool_use[1]["input"]["path"], "notes");
+    let results = sent[2]["content"].as_array().unwrap();
+    assert_eq!(sent[2]["role"], "user");
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["type"], "tool_result");
+    assert_eq!(results[1]["tool_use_id"], "call_1");
+    assert_normalized_two_tool_calls(&resp, &sink, ["toolu_01A", "toolu_01B"]);
+}
+
+// ─── Request-level retry ────────────────────────────────────────────────────
+
+/// Answer successive connections with the given raw HTTP responses, then close each
+/// connection; returns the `api_base` and a counter of connections served.
+fn serve_raw(responses: Vec<String>) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::sync::atomic::Ordering;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let served = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter = served.clone();
+    std::thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0usize;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = v.trim().parse().unwrap_or(0);
+                }
+            }
+            let mut body = vec![0u8; content_length];
+            let _ = reader.read_exact(&mut body);
+            counter.fetch_add(1, Ordering::SeqCst);
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    (format!("http://{}/v1", addr), served)
+}
+
+fn text_delta(text: &str) -> String {
+    format!(
+        "data: {}\n\n",
+        json!({ "model": "m", "choices": [{ "index": 0, "delta": { "content": text } }] })
+    )
+}
+
+/// Complete SSE response streaming `text`.
+fn sse_ok(text: &str) -> String {
+    let body = format!("{}data: [DONE]\n\n", text_delta(text));
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+
+/// Chunked SSE response that sends `events` and then drops the connection mid-body.
+fn sse_broken(events: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n",
+        events.len(),
+        events
+    )
+}
+
+fn retrying_client(base: &str) -> LlmClient {
+    LlmClient::new(base, "k")
+        .unwrap()
+        .with_provider(Some("openai"))
+        .with_retry(RetryPolicy {
+            max_attempts: 3,
+            base_delay: std::time::Duration::ZERO,
+            max_backoff: std::time::Duration::from_secs(1),
+        })
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rate_limited_request_is_retried_and_reported() {
+    let body = r#"{"error":{"message":"Rate limit reached for requests","type":"requests"}}"#;
+    let limited = format!(
+        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let (base, served) = serve_raw(vec![limited, sse_ok("Hello")]);
+    let mut sink = ChunkSink::default();
+    let resp = retrying_client(&base)
+        .chat_completion_stream("m", &[ChatMessage::user("hi")], None, None, &mut sink, None)
+        .await
+        .unwrap();
+
+    assert_eq!(resp.choices[0].message.content.as_deref(), Some("Hello"));
+    assert_eq!(sink.chunks, ["Hello", "\n"]);
+    assert_eq!(sink.statuses, ["rate limited, retrying in 0s"]);
+    assert_eq!(served.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn stream_broken_before_any_text_is_retried() {
+    let role_only = format!(
+        "data: {}\n\n",
+        json!({ "model": "m", "choices": [{ "index": 0, "delta": { "role": "assistant" } }] })
+    );
+    let (base, served) = serve_raw(vec![sse_broken(&role_only), sse_ok("Hello")]);
+    let mut sink = ChunkSink::default();
+    let resp = retrying_client(&base)
+        .chat_completion_stream("m", &[ChatMessage::user("hi")], None, None, &mut sink, None)
+        .await
+        .unwrap();
+
+    assert_eq!(resp.choices[0].message.content.as_deref(), Some("Hello"));
+    assert_eq!(sink.chunks, ["Hello", "\n"]);
+    assert_eq!(sink.statuses.len(), 1);
+    assert_eq!(served.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn stream_broken_after_partial_text_fails_as_interrupted() {
+    let (base, served) = serve_raw(vec![sse_broken(&text_delta("Hel")), sse_ok("Hello")]);
+    let mut sink = ChunkSink::default();
+    let err = retrying_client(&base)
+        .chat_completion_stream("m", &[ChatMessage::user("hi")], None, None, &mut sink, None)
+        .await
+        .unwrap_err();
+
+    assert!(is_stream_interrupted_error(&err.to_string()), "{err}");
+    assert!(!is_context_overflow_error(&err.to_string()));
+    assert_eq!(sink.chunks, ["Hel"]);
+    assert!(sink.statuses.is_empty());
+    assert_eq!(served.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
@@ -7,10 +7,14 @@
 //! EVO-3 fix: Workspace rules (per-project, from `init --use-llm`) are now **merged**
 //! with global rules (seed + evolved) instead of replacing them. This ensures evolved
 //! rules are never silently discarded when a workspace file exists.
+//!
+//! Scoped rules: an evolved rule may carry a [`RuleScope`]; [`RuleScopeContext`] decides
+//! whether it applies to the current workspace, languages, loaded skills and request.
 
+use std::collections::HashMap;
 use std::path::Path;
 
-use super::types::PlanningRule;
+use super::types::{PlanningRule, RuleScope};
 use skilllite_evolution::seed;
 
 /// Load planning rules.
@@ -71,6 +75,172 @@ fn merge_workspace_rules(base: &mut Vec<PlanningRule>, workspace: Vec<PlanningRu
     }
 }
 
+/// What a rule's scope is evaluated against when the planning prompt is assembled.
+#[derive(Debug, Clone, Default)]
+pub struct RuleScopeContext {
+    /// Workspace path with `/` separators.
+    pub workspace: Option<String>,
+    /// Lowercase languages from [`detect_languages`].
+    pub languages: Vec<String>,
+    pub skills: Vec<String>,
+    pub user_message: String,
+}
+
+impl RuleScopeContext {
+    pub fn new(workspace: Option<&Path>, skills: &[String], user_message: &str) -> Self {
+        Self {
+            workspace: workspace.map(|p| p.to_string_lossy().replace('\\', "/")),
+            languages: workspace.map(detect_languages).unwrap_or_default(),
+            skills: skills.to_vec(),
+            user_message: user_message.to_string(),
+        }
+    }
+
+    /// Seed rules and rules without a scope always apply.
+    pub fn includes(&self, rule: &PlanningRule) -> bool {
+        match &rule.scope {
+            Some(scope) if rule.origin != "seed" => self.matches(scope),
+            _ => true,
+        }
+    }
+
+    fn matches(&self, scope: &RuleScope) -> bool {
+        if let Some(glob) = &scope.workspace_glob {
+            let Some(ws) = &self.workspace else {
+                return false;
+            };
+            if !skilllite_fs::matches_path_glob(ws.trim_end_matches('/'), glob) {
+                return false;
+            }
+        }
+        if let Some(lang) = &scope.language {
+            if !self.languages.iter().any(|l| l.eq_ignore_ascii_case(lang)) {
+                return false;
+            }
+        }
+        if let Some(skill) = &scope.requires_skill {
+            if !self.skills.iter().any(|s| s == skill) {
+                return false;
+            }
+        }
+        if !scope.task_keywords.is_empty() {
+            let msg_lower = self.user_message.to_lowercase();
+            if !scope
+                .task_keywords
+                .iter()
+                .any(|k| msg_lower.contains(&k.to_lowercase()))
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Directories the language census never descends into.
+const CENSUS_SKIP_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "vendor",
+    "venv",
+    "__pycache__",
+];
+const CENSUS_MAX_DEPTH: usize = 3;
+const CENSUS_MAX_ENTRIES: usize = 2000;
+/// Share of recognised source files a language needs to count (manifests always count).
+const CENSUS_MIN_SHARE: f32 = 0.1;
+
+fn language_for_extension(ext: &str) -> Option<&'static str> {
+    Some(match ext {
+        "py" | "ipynb" => "python",
+        "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "vue" => "vue",
+        "svelte" => "svelte",
+        "rs" => "rust",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "rb" => "ruby",
+        "php" => "php",
+        "cs" => "csharp",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" => "cpp",
+        "swift" => "swift",
+        "sh" | "bash" => "shell",
+        _ => return None,
+    })
+}
+
+fn language_for_manifest(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "pyproject.toml" | "requirements.txt" | "setup.py" | "Pipfile" => "python",
+        "package.json" => "javascript",
+        "tsconfig.json" => "typescript",
+        "Cargo.toml" => "rust",
+        "go.mod" => "go",
+        "pom.xml" | "build.gradle" => "java",
+        "Gemfile" => "ruby",
+        "composer.json" => "php",
+        _ => return None,
+    })
+}
+
+/// Cheap file-extension census of `root` (bounded depth and entry count, hidden and vendor
+/// directories skipped). Returns sorted lowercase language names.
+pub fn detect_languages(root: &Path) -> Vec<String> {
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    let mut manifests: Vec<&'static str> = Vec::new();
+    let mut seen = 0usize;
+    let mut stack = vec![(root.to_path_buf(), 0usize)];
+    while let Some((dir, depth)) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            seen += 1;
+            if seen > CENSUS_MAX_ENTRIES {
+                break;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if depth + 1 < CENSUS_MAX_DEPTH && !CENSUS_SKIP_DIRS.contains(&name.as_str()) {
+                    stack.push((entry.path(), depth + 1));
+                }
+                continue;
+            }
+            if let Some(lang) = language_for_manifest(&name) {
+                manifests.push(lang);
+            }
+            if let Some(lang) = Path::new(&name)
+                .extension()
+                .and_then(|e| language_for_extension(&e.to_string_lossy().to_lowercase()))
+            {
+                *counts.entry(lang).or_default() += 1;
+            }
+        }
+    }
+    let total: usize = counts.values().sum();
+    let mut langs: Vec<String> = counts
+        .into_iter()
+        .filter(|(_, n)| *n as f32 >= total as f32 * CENSUS_MIN_SHARE)
+        .map(|(l, _)| l)
+        .chain(manifests)
+        .map(String::from)
+        .collect();
+    langs.sort();
+    langs.dedup();
+    langs
+}
+
 /// Load full examples text from disk or compiled-in seed.
 pub fn load_full_examples(chat_root: Option<&Path>) -> String {
     if let Some(root) = chat_root {
@@ -125,3 +295,79 @@ pub fn compact_examples_section(user_message: &str) -> String {
     }
     lines.join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scoped(scope: RuleScope, origin: &str) -> PlanningRule {
+        PlanningRule {
+            id: "r".to_string(),
+            priority: 60,
+            keywords: vec![],
+            context_keywords: vec![],
+            tool_hint: None,
+            instruction: "x".to_string(),
+            mutable: true,
+            origin: origin.to_string(),
+            reusable: false,
+            effectiveness: None,
+            trigger_count: None,
+            provenance: None,
+            scope: Some(scope),
+        }
+    }
+
+    #[test]
+    fn scope_conditions_are_all_required() {
+        let ctx = RuleScopeContext {
+            workspace: Some("/home/u/projects/data-etl".to_string()),
+            languages: vec!["python".to_string()],
+            skills: vec!["csv-tools".to_string()],
+            user_message: "Clean the CSV export".to_string(),
+        };
+        let rule = scoped(
+            RuleScope {
+                workspace_glob: Some("data-*".to_string()),
+                language: Some("Python".to_string()),
+                requires_skill: Some("csv-tools".to_string()),
+                task_keywords: vec!["csv".to_string()],
+            },
+            "evolved",
+        );
+        assert!(ctx.includes(&rule));
+
+        let other_ws = RuleScopeContext {
+            workspace: Some("/home/u/projects/webapp".to_string()),
+            ..ctx.clone()
+        };
+        assert!(!other_ws.includes(&rule));
+        let no_skill = RuleScopeContext {
+            skills: vec![],
+            ..ctx.clone()
+        };
+        assert!(!no_skill.includes(&rule));
+        let other_task = RuleScopeContext {
+            user_message: "Write a poem".to_string(),
+            ..ctx.clone()
+        };
+        assert!(!other_task.includes(&rule));
+        // Seed rules stay global even if a scope was written into rules.json.
+        assert!(other_task.includes(&scoped(rule.scope.clone().unwrap(), "seed")));
+    }
+
+    #[test]
+    fn census_skips_vendor_dirs_and_rare_languages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("node_modules").join("pkg")).unwrap();
+        std::fs::write(root.join("node_modules").join("pkg").join("a.py"), "").unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        for i in 0..12 {
+            std::fs::write(root.join("src").join(format!("m{}.ts", i)), "").unwrap();
+        }
+        std::fs::write(root.join("build.sh"), "").unwrap();
+        std::fs::write(root.join("package.json"), "{}").unwrap();
+        assert_eq!(detect_languages(root), vec!["javascript", "typescript"]);
+    }
+}
@@ -5,14 +5,18 @@
 //!
 //! ## Progressive Disclosure Modes
 //!
-//! Four prompt modes control how much skill information is included:
+//! Five prompt modes control how much skill information is included:
 //!
 //! | Mode        | Content                                       | Usage           |
 //! |-------------|-----------------------------------------------|-----------------|
+//! | Index       | Skill name + one-line summary                  | Agent system    |
 //! | Summary     | Skill name + 150-char description              | Compact views   |
 //! | Standard    | Schema + 200-char description                 | Default prompts |
-//! | Progressive | Standard + "more details available" hint       | Agent system    |
+//! | Progressive | Standard + "more details available" hint       | RPC default     |
 //! | Full        | Complete SKILL.md + references + assets        | First invocation|
+//!
+//! In Index mode the model fetches a skill's docs with the `get_skill_details` tool
+//! (or gets them injected on its first call to the skill).
 
 use std::path::Path;
 use std::sync::LazyLock;
@@ -23,12 +27,14 @@ use super::extensions::ToolAvailabilityView;
 use super::skills::LoadedSkill;
 use super::soul::{build_beliefs_block, Law, Soul};
 use super::types::{get_output_dir, safe_truncate};
-use skilllite_evolution::seed;
+use skilllite_evolution::seed::{self, template, template::TemplateVars};
 
 /// Progressive disclosure mode.
 /// Summary/Standard/Full are used in tests and for API completeness.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PromptMode {
+    /// Name + one-line summary; details via `get_skill_details`.
+    Index,
     /// 150-char description only.
     Summary,
     /// Schema + 200-char description.
@@ -42,7 +48,9 @@ pub enum PromptMode {
 /// Build the complete system prompt.
 ///
 /// EVO-2: The base system prompt is loaded from `~/.skilllite/chat/prompts/system.md`
-/// (or compiled-in seed fallback). A custom_prompt override still takes precedence.
+/// (or compiled-in seed fallback) and rendered with [`template`] (`{{TODAY}}`, `{{YESTERDAY}}`,
+/// `{{WORKSPACE}}`, `{{OUTPUT_DIR}}` plus `.skilllite/prompt_vars.json`). A custom_prompt
+/// override still takes precedence and is used verbatim.
 #[allow(clippy::too_many_arguments)]
 pub fn build_system_prompt(
     custom_prompt: Option<&str>,
@@ -78,12 +86,28 @@ pub fn build_system_prompt(
         cp.to_string()
     } else {
         let ws_path = Path::new(workspace);
-        seed::load_prompt_file_with_project(
+        let base = seed::load_prompt_file_with_project(
             chat_root.unwrap_or(Path::new("/nonexistent")),
             Some(ws_path),
             "system.md",
             include_str!("seed/system.seed.md"),
-        )
+        );
+        let now = chrono::Local::now();
+        let vars = TemplateVars::new()
+            .set("TODAY", now.format("%Y-%m-%d").to_string())
+            .set(
+                "YESTERDAY",
+                (now - chrono::Duration::days(1))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+            .set("WORKSPACE", workspace)
+            .set(
+                "OUTPUT_DIR",
+                get_output_dir().unwrap_or_else(|| format!("{}/output", workspace)),
+            )
+            .with_workspace(Some(ws_path));
+        template::render(&base, &vars)
     };
     parts.push(base_prompt);
 
@@ -163,32 +187,15 @@ pub fn build_system_prompt(
         .map(|view| view.filter_callable_skills(skills))
         .unwrap_or_else(|| skills.iter().collect());
 
-    // Skills context — Progressive mode: summary + "more details available" hint.
-    // Full docs are injected on first tool call via inject_progressive_disclosure.
+    // Skills context — Index mode: one line per skill. Full docs come from get_skill_details
+    // or are injected on the first tool call via inject_progressive_disclosure.
     if !visible_skills.is_empty() {
         parts.push(build_skills_context_from_refs(
             &visible_skills,
-            PromptMode::Progressive,
+            PromptMode::Index,
         ));
     }
 
-    // Bash-tool skills: inject full SKILL.md content upfront
-    // (same as Python _get_bash_tool_skills_context)
-    let bash_skills: Vec<_> = visible_skills
-        .iter()
-        .copied()
-        .filter(|s| s.metadata.is_bash_tool_skill())
-        .collect();
-    if !bash_skills.is_empty() {
-        parts.push("\n\n## Bash-Tool Skills Documentation\n".to_string());
-        for skill in bash_skills {
-            let skill_md_path = skill.skill_dir.join("SKILL.md");
-            if let Ok(content) = skilllite_fs::read_file(&skill_md_path) {
-                parts.push(format!("### {}\n\n{}\n", skill.name, content));
-            }
-        }
-    }
-
     // Optional caller-provided context (e.g. from RPC params.context.append)
     if let Some(append) = context_append {
         if !append.is_empty() {
@@ -399,6 +406,7 @@ fn extract_signatures(workspace: &std::path::Path) -> String {
 /// Build skills context section for the system prompt.
 ///
 /// Uses the specified `PromptMode` to control verbosity:
+///   - Index: name + one-line summary + "call get_skill_details" hint
 ///   - Summary: name + 150-char truncated description
 ///   - Standard: name + 200-char description + parameter schema hints
 ///   - Progressive: Standard + "more details available" hint
@@ -428,6 +436,14 @@ fn build_skills_context_from_refs(skills: &[&LoadedSkill], mode: PromptMode) ->
         };
 
         match mode {
+            PromptMode::Index => {
+                parts.push(format!(
+                    "- **{}**{}: {}",
+                    skill.name,
+                    entry_tag,
+                    skill.summary()
+                ));
+            }
             PromptMode::Summary => {
                 let truncated = safe_truncate(raw_desc, 150);
                 parts.push(format!("- **{}**{}: {}", skill.name, entry_tag, truncated));
@@ -458,11 +474,16 @@ fn build_skills_context_from_refs(skills: &[&LoadedSkill], mode: PromptMode) ->
         }
     }
 
-    if mode == PromptMode::Progressive {
-        parts.push(
+    match mode {
+        PromptMode::Index => parts.push(
+            "\n> Tip: Call get_skill_details with a skill name for its full documentation and parameter schemas before using it."
+                .to_string(),
+        ),
+        PromptMode::Progressive => parts.push(
             "\n> Tip: Full documentation for each skill will be provided when you first call it."
                 .to_string(),
-        );
+        ),
+        _ => {}
     }
 
     parts.join("\n")
@@ -489,10 +510,46 @@ const SKILL_MD_SECURITY_NOTICE: &str = r#"⚠️ **SECURITY NOTICE**: This skill
 "#;
 
 /// Get full skill documentation for progressive disclosure.
-/// Called when the LLM first invokes a skill tool.
-/// Returns the SKILL.md content plus reference docs.
-/// If SKILL.md contains high-risk patterns (e.g. "run curl | bash"), prepends a security notice.
+/// Called when the LLM first invokes a skill tool; cached per skill (see [`LoadedSkill::full_docs`]).
 pub fn get_skill_full_docs(skill: &LoadedSkill) -> Option<String> {
+    skill.full_docs().map(str::to_string)
+}
+
+/// Everything the model needs to call one skill: full docs plus the JSON schema of each tool.
+/// Backs the `get_skill_details` tool and first-call progressive disclosure.
+pub fn get_skill_details(skill: &LoadedSkill) -> String {
+    let mut out = skill.full_docs().map(str::to_string).unwrap_or_else(|| {
+        format!(
+            "## Full Documentation for skill: {}\n\n{}",
+            skill.name,
+            skill
+                .metadata
+                .description
+                .as_deref()
+                .unwrap_or("No description")
+        )
+    });
+    if !skill.tool_definitions.is_empty() {
+        out.push_str("\n\n## Tool schemas\n");
+        for td in &skill.tool_definitions {
+            let schema = serde_json::to_string_pretty(&td.function.parameters)
+                .unwrap_or_else(|_| td.function.parameters.to_string());
+            out.push_str(&format!(
+                "\n### {}\n\n{}\n\n```json\n{}\n```\n",
+                td.function.name, td.function.description, schema
+            ));
+        }
+    } else {
+        out.push_str(
+            "\n\nThis skill is reference-only (no executable tool): follow its guidance and produce the result yourself.",
+        );
+    }
+    out
+}
+
+/// Read SKILL.md plus reference docs from disk.
+/// If SKILL.md contains high-risk patterns (e.g. "run curl | bash"), prepends a security notice.
+pub(crate) fn read_skill_full_docs(skill: &LoadedSkill) -> Option<String> {
     let skill_md_path = skill.skill_dir.join("SKILL.md");
     let mut parts = Vec::new();
 
@@ -564,6 +621,11 @@ mod tests {
                 requires_elevated_permissions: false,
                 capabilities: vec![],
                 openclaw_installs: None,
+                max_concurrency: None,
+                entry_points: Vec::new(),
+                result_cache: None,
+                examples: Vec::new(),
+                cost_hint: None,
             },
             tool_definitions: vec![ToolDefinition {
                 tool_type: "function".to_string(),
@@ -580,6 +642,8 @@ mod tests {
                 },
             }],
             multi_script_entries: HashMap::new(),
+            docs: Default::default(),
+            source_hash: 0,
         }
     }
 
@@ -643,6 +707,28 @@ mod tests {
         assert!(prompt.contains("Workspace: /home/user/project"));
     }
 
+    #[test]
+    fn test_build_system_prompt_renders_project_template_with_workspace_vars() {
+        let ws = tempfile::tempdir().unwrap();
+        let dot = ws.path().join(".skilllite");
+        std::fs::create_dir_all(dot.join("prompts")).unwrap();
+        std::fs::write(
+            dot.join("prompts").join("system.md"),
+            "Repo {{REPO}}{{#if LANGUAGE}} in {{LANGUAGE}}{{/if}}{{#if TEAM}} for {{TEAM}}{{/if}}.{{UNKNOWN}}",
+        )
+        .unwrap();
+        std::fs::write(
+            dot.join("prompt_vars.json"),
+            r#"{"REPO": "skilllite", "LANGUAGE": "Rust"}"#,
+        )
+        .unwrap();
+        let workspace = ws.path().to_string_lossy();
+        let prompt =
+            build_system_prompt(None, &[], &workspace, None, false, None, None, None, None);
+        assert!(prompt.contains("Repo skilllite in Rust."));
+        assert!(!prompt.contains("{{UNKNOWN}}"));
+    }
+
     #[test]
     fn test_build_system_prompt_uses_progressive_mode() {
         let skills = vec![make_test_skill("test-skill", "Test description")];
@@ -708,6 +794,11 @@ mod tests {
                 requires_elevated_permissions: false,
                 capabilities: vec![],
                 openclaw_installs: None,
+                max_concurrency: None,
+                entry_points: Vec::new(),
+                result_cache: None,
+                examples: Vec::new(),
+                cost_hint: None,
             },
             tool_definitions: vec![ToolDefinition {
                 tool_type: "function".to_string(),
@@ -718,11 +809,116 @@ mod tests {
                 },
             }],
             multi_script_entries: HashMap::new(),
+            docs: Default::default(),
+            source_hash: 0,
         };
         let hint = build_schema_hint(&skill);
         assert_eq!(hint, "");
     }
 
+    #[test]
+    fn test_prompt_mode_index_uses_one_line_summary() {
+        let skills = vec![
+            make_test_skill(
+                "calculator",
+                "Does math. Handles matrices, units and symbolic algebra.\nSecond line.",
+            ),
+            make_test_skill("translator", "把文本翻译成英文。支持多种语言。"),
+        ];
+        let ctx = build_skills_context(&skills, PromptMode::Index);
+
+        assert!(ctx.contains("- **calculator**: Does math."), "{ctx}");
+        assert!(!ctx.contains("matrices"), "{ctx}");
+        assert!(
+            ctx.contains("- **translator**: 把文本翻译成英文。"),
+            "{ctx}"
+        );
+        assert!(!ctx.contains("支持多种语言"), "{ctx}");
+        assert!(!ctx.contains("(params:"), "{ctx}");
+        assert!(ctx.contains("get_skill_details"), "{ctx}");
+    }
+
+    #[test]
+    fn test_index_prompt_cuts_initial_skill_context_for_20_skills() {
+        let tmp = tempfile::tempdir().unwrap();
+        let body = "## Usage\n\nRun the tool with the documented flags. BODY-MARKER\n".repeat(40);
+        for i in 0..20 {
+            let dir = tmp.path().join(format!("fixture-{i}"));
+            std::fs::create_dir_all(&dir).unwrap();
+            let frontmatter = if i % 2 == 0 {
+                "allowed-tools: Bash(fixture-cli:*)\n".to_string()
+            } else {
+                std::fs::create_dir_all(dir.join("scripts")).unwrap();
+                std::fs::write(dir.join("scripts/main.py"), "print('ok')\n").unwrap();
+                String::new()
+            };
+            std::fs::write(
+                dir.join("SKILL.md"),
+                format!(
+                    "---\nname: fixture-{i}\ndescription: Fixture skill {i} processes documents. {}\n{frontmatter}---\n\n{body}",
+                    "It supports many input formats, batch mode and detailed reports. ".repeat(4)
+                ),
+            )
+            .unwrap();
+        }
+        let skills = crate::skills::load_skills(&[tmp.path().to_string_lossy().to_string()]);
+        assert_eq!(skills.len(), 20);
+
+        // Previous eager context: Progressive list + upfront bash-tool SKILL.md + full tool descriptions.
+        let eager_tools: Vec<_> = skills
+            .iter()
+            .flat_map(|s| s.tool_definitions.clone())
+            .collect();
+        let eager = build_skills_context(&skills, PromptMode::Progressive).len()
+            + skills
+                .iter()
+                .filter(|s| s.metadata.is_bash_tool_skill())
+                .map(|s| {
+                    std::fs::read_to_string(s.skill_dir.join("SKILL.md"))
+                        .unwrap()
+                        .len()
+                })
+                .sum::<usize>()
+            + serde_json::to_string(&eager_tools).unwrap().len();
+
+        let registry = ExtensionRegistry::builder(false, false, &skills).build();
+        let base = build_system_prompt(
+            None,
+            &[],
+            "/nonexistent",
+            None,
+            false,
+            None,
+            None,
+            None,
+            None,
+        );
+        let prompt = build_system_prompt(
+            None,
+            &skills,
+            "/nonexistent",
+            None,
+            false,
+            None,
+            None,
+            None,
+            None,
+        );
+        let lazy = (prompt.len() - base.len())
+            + serde_json::to_string(&registry.all_tool_definitions())
+                .unwrap()
+                .len();
+
+        assert!(!prompt.contains("BODY-MARKER"));
+        assert!(
+            lazy * 3 < eager,
+            "lazy context {lazy} bytes should be well under eager {eager} bytes"
+        );
+        let details = get_skill_details(&skills[0]);
+        assert!(details.contains("BODY-MARKER"));
+        assert!(details.contains("## Tool schemas"));
+    }
+
     #[test]
     fn test_get_skill_full_docs_truncates_reference_on_utf8_boundary() {
         let tmp = tempfile::tempdir().unwrap();
@@ -0,0 +1,197 @@
+//! Interactive chat REPL helpers: slash-command parsing, multi-line input and the
+//! persistent line-editor history (`~/.skilllite/chat/.repl_history`).
+//!
+//! The REPL loop itself lives in [`crate::chat`]; state changes (model, session) go
+//! through [`crate::chat_session::ChatSession`].
+
+use std::path::PathBuf;
+
+/// Slash commands shown by `/help`: (usage, description).
+pub const SLASH_COMMANDS: &[(&str, &str)] = &[
+    ("/help", "列出所有命令"),
+    ("/exit, /quit, /q", "退出"),
+    ("/clear", "清空当前会话（先总结写入记忆）"),
+    ("/compact", "压缩历史"),
+    ("/model [name]", "查看或切换后续轮次使用的模型"),
+    ("/skills", "列出已加载的技能"),
+    ("/reload-skills", "重新加载技能"),
+    ("/session [key]", "查看或切换会话（切换前保存自动记忆）"),
+    ("/attach <path>", "附加本地文件到下一条消息"),
+    ("\"\"\"", "开始 / 结束多行输入（行尾 \\ 也可续行）"),
+];
+
+/// A parsed REPL line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplCommand {
+    Exit,
+    Help,
+    Clear,
+    Compact,
+    ReloadSkills,
+    Skills,
+    /// `/model` alone shows the current model.
+    Model(Option<String>),
+    /// `/session` alone shows the current session key.
+    Session(Option<String>),
+    /// `/attach` alone lists pending attachments.
+    Attach(Option<String>),
+    /// A `/...` word that is not a known command.
+    Unknown(String),
+}
+
+/// Parse a trimmed input line. `None` means it is a prompt for the agent.
+pub fn parse_repl_command(input: &str) -> Option<ReplCommand> {
+    let rest = input.strip_prefix('/')?;
+    let (name, arg) = match rest.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (rest, ""),
+    };
+    let arg = (!arg.is_empty()).then(|| arg.to_string());
+    Some(match name {
+        "exit" | "quit" | "q" => ReplCommand::Exit,
+        "help" | "?" => ReplCommand::Help,
+        "clear" => ReplCommand::Clear,
+        "compact" => ReplCommand::Compact,
+        "reload-skills" => ReplCommand::ReloadSkills,
+        "skills" => ReplCommand::Skills,
+        "model" => ReplCommand::Model(arg),
+        "session" => ReplCommand::Session(arg),
+        "attach" => {
+            ReplCommand::Attach(arg.map(|p| p.trim_matches(|c| c == '"' || c == '\'').to_string()))
+        }
+        // Absolute paths pasted as a prompt are not commands.
+        _ if name.contains('/') || name.is_empty() => return None,
+        _ => ReplCommand::Unknown(format!("/{}", name)),
+    })
+}
+
+/// Session keys become transcript file names: letters, digits, `-`, `_`, `.` (not leading).
+pub fn validate_session_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > 64 {
+        return Err("session key must be 1-64 characters".to_string());
+    }
+    if key.starts_with('.')
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(format!(
+            "invalid session key '{}': use letters, digits, '-', '_' or '.'",
+            key
+        ));
+    }
+    Ok(())
+}
+
+/// Marker line that opens / closes a multi-line block.
+const HEREDOC_MARKER: &str = "\"\"\"";
+
+/// Accumulates physical lines into one prompt: a trailing `\` continues onto the next
+/// line, and a `"""` line starts a block that runs until the next `"""` line.
+#[derive(Debug, Default)]
+pub struct MultiLineInput {
+    lines: Vec<String>,
+    in_heredoc: bool,
+}
+
+impl MultiLineInput {
+    /// True while more lines are expected (use the continuation prompt).
+    pub fn is_pending(&self) -> bool {
+        self.in_heredoc || !self.lines.is_empty()
+    }
+
+    /// Feed one line; returns the complete input once it is finished.
+    pub fn push_line(&mut self, line: &str) -> Option<String> {
+        if self.in_heredoc {
+            if line.trim() == HEREDOC_MARKER {
+                self.in_heredoc = false;
+                return Some(self.take());
+            }
+            self.lines.push(line.to_string());
+            return None;
+        }
+        if line.trim() == HEREDOC_MARKER {
+            self.in_heredoc = true;
+            return None;
+        }
+        if let Some(head) = line.strip_suffix('\\') {
+            self.lines.push(head.to_string());
+            return None;
+        }
+        self.lines.push(line.to_string());
+        Some(self.take())
+    }
+
+    /// Drop a partially entered block (e.g. on Ctrl-C).
+    pub fn reset(&mut self) {
+        self.lines.clear();
+        self.in_heredoc = false;
+    }
+
+    fn take(&mut self) -> String {
+        std::mem::take(&mut self.lines).join("\n")
+    }
+}
+
+/// Line-editor history file shared across REPL runs.
+pub fn history_path() -> PathBuf {
+    skilllite_executor::chat_root().join(".repl_history")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_with_and_without_arguments() {
+        assert_eq!(parse_repl_command("hello"), None);
+        assert_eq!(parse_repl_command("/q"), Some(ReplCommand::Exit));
+        assert_eq!(parse_repl_command("/model"), Some(ReplCommand::Model(None)));
+        assert_eq!(
+            parse_repl_command("/model  gpt-4o "),
+            Some(ReplCommand::Model(Some("gpt-4o".to_string())))
+        );
+        assert_eq!(
+            parse_repl_command("/attach \"a b.txt\""),
+            Some(ReplCommand::Attach(Some("a b.txt".to_string())))
+        );
+        assert_eq!(
+            parse_repl_command("/modle x"),
+            Some(ReplCommand::Unknown("/modle".to_string()))
+        );
+        assert_eq!(parse_repl_command("/usr/bin/env is broken"), None);
+    }
+
+    #[test]
+    fn multi_line_input_supports_backslash_and_heredoc() {
+        let mut input = MultiLineInput::default();
+        assert_eq!(input.push_line("first \\"), None);
+        assert!(input.is_pending());
+        assert_eq!(input.push_line("second").as_deref(), Some("first \nsecond"));
+        assert!(!input.is_pending());
+
+        assert_eq!(input.push_line("\"\"\""), None);
+        assert_eq!(input.push_line("line one"), None);
+        assert_eq!(input.push_line(""), None);
+        assert_eq!(input.push_line("line three \\"), None);
+        assert_eq!(
+            input.push_line("\"\"\"").as_deref(),
+            Some("line one\n\nline three \\")
+        );
+
+        input.push_line("\"\"\"");
+        input.push_line("abandoned");
+        input.reset();
+        assert!(!input.is_pending());
+        assert_eq!(input.push_line("next").as_deref(), Some("next"));
+    }
+
+    #[test]
+    fn session_keys_are_file_name_safe() {
+        assert!(validate_session_key("work-2026.q4").is_ok());
+        assert!(validate_session_key("").is_err());
+        assert!(validate_session_key("../etc").is_err());
+        assert!(validate_session_key(".hidden").is_err());
+        assert!(validate_session_key("a b").is_err());
+    }
+}
@@ -15,10 +15,12 @@
 //!
 //! Request (one JSON line on stdin):
 //! ```json
-//! {"method": "agent_chat", "params": {
+//! {"id": "req-1", "method": "agent_chat", "params": {
 //!     "message": "user input",
 //!     "session_key": "default",
+//!     "timeout_ms": 120000,  // optional: cancel the turn once elapsed
 //!     "images": [ { "media_type": "image/png", "data_base64": "..." } ],
+//!     "attachments": ["/path/to/report.csv"],  // optional: local files (relative to workspace)
 //!     "context": { "append": "optional string to append to system prompt" },
 //!     "config": { "model": "gpt-4o", ... }  // optional overrides
 //! }}
@@ -42,12 +44,17 @@
 //! {"event": "swarm_finished", "data": {"summary": "remote node completed task"}}
 //! {"event": "swarm_failed", "data": {"message": "timeout, fallback to local execution"}}
 //! {"event": "task_plan", "data": {"tasks": [...]}}
+//! {"event": "plan_proposed", "data": {"tasks": [...], "can_regenerate": true}}
+//! {"event": "replan", "data": {"reason": "api returned 404", "old_tasks": [...], "new_tasks": [...]}}
 //! {"event": "task_progress", "data": {"task_id": 1, "completed": true}}
 //! {"event": "llm_usage", "data": {"prompt_tokens": 1200, "completion_tokens": 80, "total_tokens": 1280}}
 //! {"event": "llm_usage", "data": {"reported": false}}
 //! {"event": "confirmation_request", "data": {"prompt": "Execute rm -rf?", "risk_tier": "confirm_required"}}
 //! {"event": "clarification_request", "data": {"reason": "no_progress", "message": "...", "suggestions": ["...", "..."]}}
-//! {"event": "done", "data": {"task_id": "...", "response": "...", "task_completed": true, "tool_calls": 3, "new_skill": null, "completion_type": "success", "llm_usage": {"prompt_tokens": 0, "completion_tokens": 0, "total_tokens": 0, "responses_with_usage": 0, "responses_without_usage": 0}, "wiki_update_suggestion": null}}
+//! {"event": "done", "data": {"task_id": "...", "request_id": "req-1", "response": "...", "task_completed": true, "tool_calls": 3, "new_skill": null, "completion_type": "success", "llm_usage": {"prompt_tokens": 0, "completion_tokens": 0, "total_tokens": 0, "responses_with_usage": 0, "responses_without_usage": 0}, "wiki_update_suggestion": null, "artifacts": [{"path": "report.html", "root": "output", "description": "HTML file, 1.2 KB"}], "cancelled": false}}
+//! {"event": "skills_reloaded", "data": {"added": ["pdf"], "removed": [], "updated": [], "skills": ["pdf", "..."]}}
+//! {"event": "system_prompt_refreshed", "data": {"reasons": ["prompts/rules.json changed", "skills changed"]}}
+//! {"event": "status", "data": {"message": "rate limited, retrying in 4s"}}
 //! {"event": "error", "data": {"message": "..."}}
 //! ```
 //!
@@ -61,6 +68,34 @@
 //! {"method": "clarify", "params": {"action": "continue", "hint": "optional user input"}}
 //! ```
 //! or `{"method": "clarify", "params": {"action": "stop"}}`
+//!
+//! With `config.approve_plan: true` (or `SKILLLITE_APPROVE_PLAN`), execution waits after
+//! planning. For plan_proposed, the caller sends back:
+//! ```json
+//! {"method": "approve_plan", "params": {"action": "approve", "tasks": ["optional edited plan", "..."]}}
+//! ```
+//! `tasks` (strings or `{"description", "tool_hint"}` objects) replaces the proposal; omit it to
+//! approve as-is. `{"method": "approve_plan", "params": {"action": "reject", "feedback": "..."}}`
+//! regenerates the plan once with the feedback (`can_regenerate: false` means a further
+//! rejection ends the turn with an error).
+//!
+//! To abort the in-flight turn (also triggered by `timeout_ms`), send at any time:
+//! ```json
+//! {"method": "cancel", "params": {"id": "req-1"}}
+//! ```
+//! The loop stops at the next iteration boundary and running `run_command` children are killed.
+//! The transcript and plan are still persisted, and `done` carries `"cancelled": true`,
+//! `"cancel_reason"` (`"cancelled"` | `"timeout"`), the partial `messages` and `completed_tasks`.
+//!
+//! To pick up skills installed or edited since the last turn, send
+//! `{"method": "reload_skills", "params": {"workspace": "...", "skill_dirs": [...]}}` (both optional).
+//! The reply is one `skills_reloaded` event; the next `agent_chat` uses the reloaded set.
+//!
+//! To negotiate before relying on a method or param, send `{"method": "initialize"}`; the reply
+//! is one `initialized` event carrying the binary version, `protocol_version`, the methods with
+//! their params, and compiled features. Unknown params are ignored unless the request sets
+//! `"strict": true` (or a list of required param names), which answers an `error` event instead
+//! (see [`skilllite_core::protocol::negotiation`]).
 
 use crate::error::bail;
 use crate::Result;
@@ -69,15 +104,74 @@ use serde_json::{json, Value};
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::io::{self, BufRead, BufReader, Write};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+use skilllite_core::protocol::negotiation::{self, MethodSpec, ServerInfo};
 use skilllite_executor::transcript::{self, TranscriptEntry};
 
 use super::types::*;
 use super::{chat_session::ChatSession, skills};
 
+// ─── Cancellation ───────────────────────────────────────────────────────────
+
+/// One stdin line, parsed on the reader thread (`Err` carries the JSON parse error).
+type InboundLine = std::result::Result<Value, String>;
+
+/// How often blocking confirmation/clarification waits re-check cancellation.
+const INBOUND_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Cancellation handle for one `agent_chat` turn: explicit `cancel` or `timeout_ms` deadline.
+#[derive(Clone)]
+struct TurnCancellation {
+    flag: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl TurnCancellation {
+    fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            flag: Arc::new(AtomicBool::new(false)),
+            deadline: timeout.map(|t| Instant::now() + t),
+        }
+    }
+
+    fn cancel(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+    }
+
+    /// `"cancelled"` for an explicit cancel, `"timeout"` once the deadline passed.
+    fn reason(&self) -> Option<&'static str> {
+        if self.flag.load(Ordering::SeqCst) {
+            Some("cancelled")
+        } else if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            Some("timeout")
+        } else {
+            None
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+}
+
+/// The single in-flight `agent_chat` (request id + cancellation), shared with the stdin reader.
+type InflightTurn = Arc<Mutex<Option<(String, TurnCancellation)>>>;
+
+/// Normalize a JSON-RPC style id (string or number) for matching `cancel` requests.
+fn request_id_string(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
 // ─── RPC Event Sink ─────────────────────────────────────────────────────────
 
 /// EventSink that writes JSON-Lines events to stdout.
@@ -85,8 +179,10 @@ use super::{chat_session::ChatSession, skills};
 struct RpcEventSink {
     /// Shared writer for thread safety
     writer: Arc<Mutex<io::Stdout>>,
-    /// Shared reader handle for confirmation prompts
-    confirmation_rx: Arc<Mutex<BufReader<io::Stdin>>>,
+    /// Inbound lines from the stdin reader thread (confirm / clarify responses)
+    inbox: Arc<Mutex<Receiver<InboundLine>>>,
+    /// Cancellation for the current turn (checked by the agent loop and while waiting on the caller).
+    cancellation: TurnCancellation,
     /// Current conversation turn index for dedupe scoping.
     turn_id: u64,
     /// Same-turn emitted tool_result keys to suppress duplicates in UI stream.
@@ -100,12 +196,14 @@ struct RpcEventSink {
 impl RpcEventSink {
     fn new(
         writer: Arc<Mutex<io::Stdout>>,
-        reader: Arc<Mutex<BufReader<io::Stdin>>>,
+        inbox: Arc<Mutex<Receiver<InboundLine>>>,
+        cancellation: TurnCancellation,
         transcript_path: Option<PathBuf>,
     ) -> Self {
         Self {
             writer,
-            confirmation_rx: reader,
+            inbox,
+            cancellation,
             turn_id: 0,
             emitted_tool_result_keys: HashSet::new(),
             streamed_text: false,
@@ -121,20 +219,33 @@ impl RpcEventSink {
         }
     }
 
+    /// Block for the caller's next message; `None` on cancellation, EOF, or unparsable input.
+    fn wait_inbound(&self) -> Option<Value> {
+        let inbox = self.inbox.lock().ok()?;
+        loop {
+            match inbox.recv_timeout(INBOUND_POLL_INTERVAL) {
+                Ok(line) => return line.ok(),
+                Err(RecvTimeoutError::Timeout) => {
+                    if self.cancellation.is_cancelled() {
+                        return None;
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+
     fn append_confirmation_transcript(&self, request: &ConfirmationRequest, approved: bool) {
         let Some(path) = &self.transcript_path else {
             return;
         };
-        let entry = TranscriptEntry::CustomMessage {
+        let entry = TranscriptEntry::Confirmation {
             id: Uuid::new_v4().to_string(),
             parent_id: None,
-            data: json!({
-                "ui_kind": "confirmation",
-                "prompt": request.prompt,
-                "risk_tier": request.risk_tier,
-                "resolved": true,
-                "approved": approved,
-            }),
+            command: request.prompt.clone(),
+            risk_tier: json!(request.risk_tier).as_str().map(str::to_string),
+            approved,
+            timestamp: chrono::Utc::now().timestamp().to_string(),
         };
         if let Err(e) = transcript::append_entry(path, &entry) {
             tracing::warn!(
@@ -343,20 +454,15 @@ impl EventSink for RpcEventSink {
             json!({ "prompt": request.prompt, "risk_tier": request.risk_tier }),
         );
 
-        if let Ok(mut reader) = self.confirmation_rx.lock() {
-            let mut line = String::new();
-            if reader.read_line(&mut line).is_ok() {
-                if let Ok(msg) = serde_json::from_str::<Value>(line.trim()) {
-                    if msg.get("method").and_then(|m| m.as_str()) == Some("confirm") {
-                        let approved = msg
-                            .get("params")
-                            .and_then(|p| p.get("approved"))
-                            .and_then(|a| a.as_bool())
-                            .unwrap_or(false);
-                        self.append_confirmation_transcript(request, approved);
-                        return approved;
-                    }
-                }
+        if let Some(msg) = self.wait_inbound() {
+            if msg.get("method").and_then(|m| m.as_str()) == Some("confirm") {
+                let approved = msg
+                    .get("params")
+                    .and_then(|p| p.get("approved"))
+                    .and_then(|a| a.as_bool())
+                    .unwrap_or(false);
+                self.append_confirmation_transcript(request, approved);
+                return approved;
             }
         }
         false
@@ -375,27 +481,22 @@ impl EventSink for RpcEventSink {
             }),
         );
 
-        if let Ok(mut reader) = self.confirmation_rx.lock() {
-            let mut line = String::new();
-            if reader.read_line(&mut line).is_ok() {
-                if let Ok(msg) = serde_json::from_str::<Value>(line.trim()) {
-                    if msg.get("method").and_then(|m| m.as_str()) == Some("clarify") {
-                        let params = msg.get("params").cloned().unwrap_or(json!({}));
-                        let action = params
-                            .get("action")
-                            .and_then(|a| a.as_str())
-                            .unwrap_or("stop");
-                        if action == "continue" {
-                            let hint = params
-                                .get("hint")
-                                .and_then(|h| h.as_str())
-                                .filter(|s| !s.is_empty())
-                                .map(|s| s.to_string());
-                            let response = ClarificationResponse::Continue(hint);
-                            self.append_clarification_transcript(request, &response);
-                            return response;
-                        }
-                    }
+        if let Some(msg) = self.wait_inbound() {
+            if msg.get("method").and_then(|m| m.as_str()) == Some("clarify") {
+                let params = msg.get("params").cloned().unwrap_or(json!({}));
+                let action = params
+                    .get("action")
+                    .and_then(|a| a.as_str())
+                    .unwrap_or("stop");
+                if action == "continue" {
+                    let hint = params
+                        .get("hint")
+                        .and_then(|h| h.as_str())
+                        .filter(|s| !s.is_empty())
+                        .map(|s| s.to_string());
+                    let response = ClarificationResponse::Continue(hint);
+                    self.append_clarification_transcript(request, &response);
+                    return response;
                 }
             }
         }
@@ -408,6 +509,47 @@ impl EventSink for RpcEventSink {
         self.emit("task_plan", json!({ "tasks": tasks }));
     }
 
+    fn on_plan_proposed(&mut self, tasks: &[Task], can_regenerate: bool) -> PlanApproval {
+        self.emit(
+            "plan_proposed",
+            json!({ "tasks": tasks, "can_regenerate": can_regenerate }),
+        );
+
+        let Some(msg) = self.wait_inbound() else {
+            return PlanApproval::Unavailable;
+        };
+        if msg.get("method").and_then(|m| m.as_str()) != Some("approve_plan") {
+            return PlanApproval::Unavailable;
+        }
+        let params = msg.get("params").cloned().unwrap_or(json!({}));
+        match params.get("action").and_then(|a| a.as_str()) {
+            Some("approve") => match params.get("tasks") {
+                None | Some(Value::Null) => PlanApproval::Approve(tasks.to_vec()),
+                Some(edited) => match tasks_from_json(edited) {
+                    Ok(edited) => PlanApproval::Approve(edited),
+                    Err(e) => {
+                        tracing::warn!("approve_plan: invalid tasks ({}), plan rejected", e);
+                        PlanApproval::Reject(format!("invalid edited plan: {}", e))
+                    }
+                },
+            },
+            _ => PlanApproval::Reject(
+                params
+                    .get("feedback")
+                    .and_then(|f| f.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn on_replan(&mut self, old_tasks: &[Task], new_tasks: &[Task], reason: &str) {
+        self.emit(
+            "replan",
+            json!({ "reason": reason, "old_tasks": old_tasks, "new_tasks": new_tasks }),
+        );
+    }
+
     fn on_task_progress(&mut self, task_id: u32, completed: bool, tasks: &[Task]) {
         self.emit(
             "task_progress",
@@ -428,50 +570,125 @@ impl EventSink for RpcEventSink {
             None => self.emit("llm_usage", json!({ "reported": false })),
         }
     }
+
+    fn on_skills_reloaded(&mut self, summary: &SkillReloadSummary) {
+        self.emit("skills_reloaded", skills_reloaded_event_data(summary));
+    }
+
+    fn on_system_prompt_refreshed(&mut self, reasons: &[String]) {
+        self.emit("system_prompt_refreshed", json!({ "reasons": reasons }));
+    }
+
+    fn on_model_fallback(&mut self, from: &str, to: &str, reason: &str) {
+        self.emit(
+            "model_fallback",
+            json!({ "from": from, "to": to, "reason": reason }),
+        );
+    }
+
+    fn on_tool_loop(&mut self, tool_name: &str, repeats: usize, stopped: bool) {
+        self.emit(
+            "tool_loop",
+            json!({ "tool": tool_name, "repeats": repeats, "stopped": stopped }),
+        );
+    }
+
+    fn on_status(&mut self, message: &str) {
+        self.emit("status", json!({ "message": message }));
+    }
+
+    fn on_memories_remembered(&mut self, facts: &[String]) {
+        self.emit(
+            "memories_remembered",
+            json!({ "count": facts.len(), "facts": facts }),
+        );
+    }
+
+    fn on_evolution_completed(&mut self, txn_id: &str, changes: &[String]) {
+        self.emit(
+            "evolution_completed",
+            json!({ "txn_id": txn_id, "changes": changes }),
+        );
+    }
+
+    fn on_budget_exceeded(&mut self, info: &BudgetExceeded) {
+        self.emit(
+            "budget_exceeded",
+            serde_json::to_value(info).unwrap_or_default(),
+        );
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
 }
 
 // ─── RPC Server ─────────────────────────────────────────────────────────────
 
+/// Methods served by `agent-rpc` with the params each understands. Keep in sync with the
+/// dispatch in [`serve_agent_rpc`] when adding a method or param.
+pub fn methods() -> Vec<MethodSpec> {
+    vec![
+        MethodSpec::new(negotiation::INITIALIZE_METHOD, &[]),
+        MethodSpec::new(
+            "agent_chat",
+            &[
+                "message",
+                "session_key",
+                "timeout_ms",
+                "images",
+                "attachments",
+                "context",
+                "config",
+                "skill_dirs",
+            ],
+        ),
+        MethodSpec::new("ping", &[]),
+        MethodSpec::new("reload_skills", &["workspace", "skill_dirs"]),
+        MethodSpec::new("confirm", &["approved"]),
+        MethodSpec::new("clarify", &["action", "hint"]),
+        MethodSpec::new("approve_plan", &["action", "tasks", "feedback"]),
+        MethodSpec::new("cancel", &["id"]),
+    ]
+}
+
 /// Run the agent_chat RPC server over stdio.
 ///
 /// Reads JSON-Lines from stdin, processes agent_chat requests,
-/// streams events as JSON-Lines to stdout.
-pub fn serve_agent_rpc() -> Result<()> {
+/// streams events as JSON-Lines to stdout. `info` answers `initialize` and drives
+/// `strict` param checks.
+///
+/// Stdin is read on a dedicated thread so `cancel` can reach the in-flight turn
+/// while the agent loop (or a confirmation wait) is blocked.
+pub fn serve_agent_rpc(info: ServerInfo) -> Result<()> {
     skilllite_core::config::ensure_default_output_dir();
 
-    let stdin = io::stdin();
     let stdout = io::stdout();
     let writer = Arc::new(Mutex::new(stdout));
-    let reader_arc = Arc::new(Mutex::new(BufReader::new(stdin)));
+    let inflight: InflightTurn = Arc::new(Mutex::new(None));
+    let inbox = Arc::new(Mutex::new(spawn_stdin_reader(
+        Arc::clone(&writer),
+        Arc::clone(&inflight),
+    )));
 
     let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
+    // Preview servers live as long as their chat session: switching sessions stops them.
+    let mut last_session_key: Option<String> = None;
+    // Skills from the last agent_chat / reload_skills; unchanged skills keep their cached docs.
+    let mut loaded_skills: Vec<skills::LoadedSkill> = Vec::new();
 
     loop {
-        let mut line = String::new();
-        {
-            let mut reader = reader_arc
+        let inbound = {
+            let rx = inbox
                 .lock()
                 .map_err(|e| crate::Error::validation(format!("stdin lock poisoned: {}", e)))?;
-            match reader.read_line(&mut line) {
-                Ok(0) => break,
-                Ok(_) => {}
-                Err(e) => {
-                    emit_event(
-                        &writer,
-                        "error",
-                        json!({ "message": format!("stdin read error: {}", e) }),
-                    );
-                    break;
-                }
+            match rx.recv() {
+                Ok(line) => line,
+                Err(_) => break,
             }
-        }
-
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+        };
 
-        let request: Value = match serde_json::from_str(line) {
+        let request: Value = match inbound {
             Ok(v) => v,
             Err(e) => {
                 emit_event(
@@ -486,20 +703,80 @@ pub fn serve_agent_rpc() -> Result<()> {
         let method = request.get("method").and_then(|m| m.as_str()).unwrap_or("");
         let params = request.get("params").cloned().unwrap_or(json!({}));
 
+        if let Err(message) = negotiation::check_strict(&info, method, &request) {
+            emit_event(&writer, "error", json!({ "message": message }));
+            continue;
+        }
+
         match method {
+            negotiation::INITIALIZE_METHOD => {
+                emit_event(&writer, "initialized", info.to_json());
+            }
             "agent_chat" => {
+                let session_key = params
+                    .get("session_key")
+                    .and_then(|s| s.as_str())
+                    .unwrap_or("default")
+                    .to_string();
+                if let Some(prev) = last_session_key.replace(session_key.clone()) {
+                    if prev != session_key {
+                        crate::extensions::stop_preview_servers(Some(&prev));
+                        crate::extensions::stop_background_jobs(Some(&prev));
+                    }
+                }
+                let request_id = request.get("id").and_then(request_id_string);
+                let timeout = params
+                    .get("timeout_ms")
+                    .and_then(|v| v.as_u64())
+                    .filter(|ms| *ms > 0)
+                    .map(Duration::from_millis);
+                let cancellation = TurnCancellation::new(timeout);
+                if let Ok(mut slot) = inflight.lock() {
+                    *slot = Some((
+                        request_id
+                            .clone()
+                            .unwrap_or_else(|| Uuid::new_v4().to_string()),
+                        cancellation.clone(),
+                    ));
+                }
                 let writer_clone = Arc::clone(&writer);
-                let reader_clone = Arc::clone(&reader_arc);
-                if let Err(e) = rt.block_on(handle_agent_chat(&params, writer_clone, reader_clone))
-                {
+                let inbox_clone = Arc::clone(&inbox);
+                let result = rt.block_on(handle_agent_chat(
+                    &params,
+                    request_id.as_deref(),
+                    writer_clone,
+                    inbox_clone,
+                    cancellation,
+                    &mut loaded_skills,
+                ));
+                if let Ok(mut slot) = inflight.lock() {
+                    *slot = None;
+                }
+                if let Err(e) = result {
                     emit_event(&writer, "error", json!({ "message": e.to_string() }));
                 }
             }
             "ping" => {
                 emit_event(&writer, "pong", json!({}));
             }
-            "confirm" | "clarify" => {
-                // 进程管理端在 confirmation_request / clarification_request 后发送响应；
+            "reload_skills" => {
+                let workspace = params
+                    .get("workspace")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| AgentConfig::from_env().workspace);
+                let dirs = rpc_skill_dirs(&params, &workspace);
+                let (next, summary) = skills::reload_skills(&loaded_skills, &dirs);
+                loaded_skills = next;
+                let mut data = skills_reloaded_event_data(&summary);
+                data["skills"] = json!(loaded_skills
+                    .iter()
+                    .map(|s| s.name.as_str())
+                    .collect::<Vec<_>>());
+                emit_event(&writer, "skills_reloaded", data);
+            }
+            "confirm" | "clarify" | "approve_plan" => {
+                // 进程管理端在 confirmation_request / clarification_request / plan_proposed 后发送响应；
                 // 若 agent_chat 已结束，主循环会读到滞后的消息。静默忽略。
             }
             _ => {
@@ -512,9 +789,120 @@ pub fn serve_agent_rpc() -> Result<()> {
         }
     }
 
+    crate::extensions::stop_preview_servers(None);
+    crate::extensions::stop_background_jobs(None);
     Ok(())
 }
 
+/// Read stdin on a background thread. `cancel` is handled here (it must not wait for the
+/// turn to finish); every other line is forwarded to the main loop / event sink.
+fn spawn_stdin_reader(
+    writer: Arc<Mutex<io::Stdout>>,
+    inflight: InflightTurn,
+) -> Receiver<InboundLine> {
+    let (tx, rx) = mpsc::channel::<InboundLine>();
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(io::stdin());
+        loop {
+            let mut line = String::new();
+            match reader.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    emit_event(
+                        &writer,
+                        "error",
+                        json!({ "message": format!("stdin read error: {}", e) }),
+                    );
+                    break;
+                }
+            }
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let parsed = serde_json::from_str::<Value>(line).map_err(|e| e.to_string());
+            if let Ok(msg) = &parsed {
+                if msg.get("method").and_then(|m| m.as_str()) == Some("cancel") {
+                    let params = msg.get("params").cloned().unwrap_or(json!({}));
+                    if let Err(message) = apply_cancel(&params, &inflight) {
+                        emit_event(&writer, "error", json!({ "message": message }));
+                    }
+                    continue;
+                }
+            }
+            if tx.send(parsed).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Flag the in-flight turn whose request id matches `params.id`.
+fn apply_cancel(params: &Value, inflight: &InflightTurn) -> std::result::Result<(), String> {
+    let target = params
+        .get("id")
+        .and_then(request_id_string)
+        .ok_or_else(|| "cancel requires params.id (the agent_chat request id)".to_string())?;
+    let slot = inflight
+        .lock()
+        .map_err(|e| format!("in-flight lock poisoned: {}", e))?;
+    match slot.as_ref() {
+        Some((id, cancellation)) if *id == target => {
+            cancellation.cancel();
+            Ok(())
+        }
+        _ => Err(format!(
+            "cancel: no in-flight agent_chat with id '{}'",
+            target
+        )),
+    }
+}
+
+/// Attach partial results to a `done` payload for a cancelled / timed-out turn.
+fn append_cancellation_data(data: &mut Value, result: &AgentResult, reason: Option<&str>) {
+    let Some(obj) = data.as_object_mut() else {
+        return;
+    };
+    obj.insert("cancelled".to_string(), json!(result.cancelled));
+    if !result.cancelled {
+        return;
+    }
+    obj.insert(
+        "cancel_reason".to_string(),
+        json!(reason.unwrap_or("cancelled")),
+    );
+    let messages: Vec<&ChatMessage> = result
+        .messages
+        .iter()
+        .filter(|m| m.role != "system")
+        .collect();
+    obj.insert("messages".to_string(), json!(messages));
+    let completed: Vec<&Task> = result.task_plan.iter().filter(|t| t.completed).collect();
+    obj.insert("completed_tasks".to_string(), json!(completed));
+    obj.insert("task_plan".to_string(), json!(result.task_plan));
+}
+
+/// `params.skill_dirs` when given, else auto-discovered under `workspace`.
+fn rpc_skill_dirs(params: &Value, workspace: &str) -> Vec<String> {
+    match params.get("skill_dirs").and_then(|v| v.as_array()) {
+        Some(dirs) => dirs
+            .iter()
+            .filter_map(|d| d.as_str().map(|s| s.to_string()))
+            .collect(),
+        None => skills::resolve_skill_dirs(&[], workspace),
+    }
+}
+
+fn skills_reloaded_event_data(summary: &SkillReloadSummary) -> Value {
+    json!({
+        "added": summary.added,
+        "removed": summary.removed,
+        "updated": summary.updated,
+    })
+}
+
 fn emit_event(writer: &Arc<Mutex<io::Stdout>>, event: &str, data: Value) {
     let msg = json!({ "event": event, "data": data });
     if let Ok(mut w) = writer.lock() {
@@ -565,10 +953,34 @@ fn parse_agent_chat_images(
     Ok(Some(out))
 }
 
+/// `params.attachments`: local file paths to attach to this message.
+fn parse_agent_chat_attachments(params: &Value) -> Result<Vec<String>> {
+    let Some(value) = params.get("attachments") else {
+        return Ok(Vec::new());
+    };
+    let Some(arr) = value.as_array() else {
+        bail!("attachments must be an array of file paths");
+    };
+    const MAX_ATTACHMENTS: usize = 10;
+    if arr.len() > MAX_ATTACHMENTS {
+        bail!("At most {} attachments per message", MAX_ATTACHMENTS);
+    }
+    arr.iter()
+        .enumerate()
+        .map(|(i, v)| match v.as_str().map(str::trim) {
+            Some(p) if !p.is_empty() => Ok(p.to_string()),
+            _ => bail!("attachments[{}] must be a non-empty path string", i),
+        })
+        .collect()
+}
+
 async fn handle_agent_chat(
     params: &Value,
+    request_id: Option<&str>,
     writer: Arc<Mutex<io::Stdout>>,
-    reader: Arc<Mutex<BufReader<io::Stdin>>>,
+    inbox: Arc<Mutex<Receiver<InboundLine>>>,
+    cancellation: TurnCancellation,
+    loaded_skills: &mut Vec<skills::LoadedSkill>,
 ) -> Result<()> {
     let message = params
         .get("message")
@@ -577,8 +989,9 @@ async fn handle_agent_chat(
         .to_string();
     let images = parse_agent_chat_images(params)?;
     let has_images = images.as_ref().is_some_and(|v| !v.is_empty());
-    if message.trim().is_empty() && !has_images {
-        bail!("agent_chat requires non-empty 'message' and/or non-empty 'images'");
+    let attachment_paths = parse_agent_chat_attachments(params)?;
+    if message.trim().is_empty() && !has_images && attachment_paths.is_empty() {
+        bail!("agent_chat requires non-empty 'message', 'images' and/or 'attachments'");
     }
     let session_key = params
         .get("session_key")
@@ -596,6 +1009,9 @@ async fn handle_agent_chat(
         if let Some(key) = overrides.get("api_key").and_then(|v| v.as_str()) {
             config.api_key = key.to_string();
         }
+        if let Some(provider) = overrides.get("provider").and_then(|v| v.as_str()) {
+            config.provider = Some(provider.to_string());
+        }
         if let Some(ws) = overrides.get("workspace").and_then(|v| v.as_str()) {
             config.workspace = ws.to_string();
         }
@@ -617,6 +1033,9 @@ async fn handle_agent_chat(
         if let Some(sp) = overrides.get("soul_path").and_then(|v| v.as_str()) {
             config.soul_path = Some(sp.to_string());
         }
+        if let Some(approve) = overrides.get("approve_plan").and_then(|v| v.as_bool()) {
+            config.approve_plan = approve;
+        }
         if let Some(skip) = overrides
             .get("skip_history_for_planning")
             .and_then(|v| v.as_bool())
@@ -655,23 +1074,26 @@ async fn handle_agent_chat(
         bail!("API key required. Set OPENAI_API_KEY env var.");
     }
 
-    let skill_dirs: Vec<String> =
-        if let Some(dirs) = params.get("skill_dirs").and_then(|v| v.as_array()) {
-            dirs.iter()
-                .filter_map(|d| d.as_str().map(|s| s.to_string()))
-                .collect()
-        } else {
-            skilllite_core::skill::discovery::discover_skill_dirs_for_loading(
-                Path::new(&config.workspace),
-                Some(&[".skills", "skills"]),
-            )
-        };
-
-    let loaded_skills = skills::load_skills(&skill_dirs);
+    let skill_dirs = rpc_skill_dirs(params, &config.workspace);
+    let (current_skills, summary) = skills::reload_skills(loaded_skills, &skill_dirs);
+    if !summary.is_empty() {
+        tracing::debug!("agent_chat skills: {}", summary.describe());
+    }
+    *loaded_skills = current_skills.clone();
 
-    let mut session = ChatSession::new(config, session_key, loaded_skills);
+    let workspace = PathBuf::from(&config.workspace);
+    let mut session = ChatSession::new(config, session_key, current_skills);
+    for p in &attachment_paths {
+        let path = workspace.join(p);
+        session.attach_file(&path)?;
+    }
     let transcript_path = session.transcript_append_path();
-    let mut sink = RpcEventSink::new(writer.clone(), reader, Some(transcript_path));
+    let mut sink = RpcEventSink::new(
+        writer.clone(),
+        inbox,
+        cancellation.clone(),
+        Some(transcript_path),
+    );
 
     match session
         .run_turn_with_media(&message, images, &mut sink)
@@ -705,7 +1127,15 @@ async fn handle_agent_chat(
                     serde_json::to_value(&agent_result.wiki_update_suggestion)
                         .unwrap_or(serde_json::Value::Null),
                 );
+                obj.insert(
+                    "artifacts".to_string(),
+                    serde_json::to_value(&agent_result.artifacts).unwrap_or(json!([])),
+                );
+                if let Some(id) = request_id {
+                    obj.insert("request_id".to_string(), json!(id));
+                }
             }
+            append_cancellation_data(&mut data, &agent_result, cancellation.reason());
             emit_event(&writer, "done", data);
         }
         Err(e) => {
@@ -719,8 +1149,112 @@ async fn handle_agent_chat(
 #[cfg(test)]
 mod tests {
     use super::{
-        build_tool_call_event_data, build_tool_result_dedupe_key, build_tool_result_event_data,
+        append_cancellation_data, apply_cancel, build_tool_call_event_data,
+        build_tool_result_dedupe_key, build_tool_result_event_data, methods, request_id_string,
+        InflightTurn, TurnCancellation,
     };
+    use crate::types::{AgentResult, ChatMessage, ExecutionFeedback, Task};
+    use serde_json::json;
+    use skilllite_core::protocol::negotiation::{check_strict, ServerInfo};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn partial_result(cancelled: bool) -> AgentResult {
+        AgentResult {
+            response: "partial".to_string(),
+            messages: vec![
+                ChatMessage::system("sys"),
+                ChatMessage::user("整理目录"),
+                ChatMessage::assistant("正在处理"),
+            ],
+            tool_calls_count: 1,
+            iterations: 2,
+            task_plan: vec![
+                Task {
+                    id: 1,
+                    description: "list".to_string(),
+                    tool_hint: None,
+                    completed: true,
+                },
+                Task {
+                    id: 2,
+                    description: "move".to_string(),
+                    tool_hint: None,
+                    completed: false,
+                },
+            ],
+            feedback: ExecutionFeedback::default(),
+            wiki_update_suggestion: None,
+            cancelled,
+            budget_exceeded: None,
+            tool_failure_cutoff: false,
+            artifacts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn cancellation_reports_explicit_cancel_before_timeout() {
+        let c = TurnCancellation::new(Some(Duration::from_secs(3600)));
+        assert_eq!(c.reason(), None);
+        c.cancel();
+        assert_eq!(c.reason(), Some("cancelled"));
+    }
+
+    #[test]
+    fn cancellation_reports_timeout_once_deadline_passes() {
+        let c = TurnCancellation::new(Some(Duration::from_millis(0)));
+        assert_eq!(c.reason(), Some("timeout"));
+        assert!(!TurnCancellation::new(None).is_cancelled());
+    }
+
+    #[test]
+    fn request_id_accepts_strings_and_numbers() {
+        assert_eq!(request_id_string(&json!("req-1")).as_deref(), Some("req-1"));
+        assert_eq!(request_id_string(&json!(42)).as_deref(), Some("42"));
+        assert_eq!(request_id_string(&json!("")), None);
+        assert_eq!(request_id_string(&json!(null)), None);
+    }
+
+    #[test]
+    fn apply_cancel_flags_matching_inflight_turn_only() {
+        let cancellation = TurnCancellation::new(None);
+        let inflight: InflightTurn = Arc::new(Mutex::new(Some((
+            "req-1".to_string(),
+            cancellation.clone(),
+        ))));
+
+        let err = apply_cancel(&json!({ "id": "other" }), &inflight).unwrap_err();
+        assert!(err.contains("other"));
+        assert!(!cancellation.is_cancelled());
+
+        assert!(apply_cancel(&json!({}), &inflight).is_err());
+
+        apply_cancel(&json!({ "id": "req-1" }), &inflight).unwrap();
+        assert!(cancellation.is_cancelled());
+    }
+
+    #[test]
+    fn cancelled_done_payload_includes_partial_results() {
+        let mut data = json!({ "response": "partial" });
+        append_cancellation_data(&mut data, &partial_result(true), Some("timeout"));
+        assert_eq!(data["cancelled"], true);
+        assert_eq!(data["cancel_reason"], "timeout");
+        let messages = data["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2, "system prompt must not be echoed");
+        assert_eq!(messages[0]["content"], "整理目录");
+        let completed = data["completed_tasks"].as_array().unwrap();
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0]["id"], 1);
+    }
+
+    #[test]
+    fn completed_done_payload_only_marks_not_cancelled() {
+        let mut data = json!({ "response": "ok" });
+        append_cancellation_data(&mut data, &partial_result(false), None);
+        assert_eq!(data["cancelled"], false);
+        assert!(data.get("messages").is_none());
+        assert!(data.get("completed_tasks").is_none());
+    }
 
     #[test]
     fn dedupe_key_is_stable_for_same_input() {
@@ -755,4 +1289,31 @@ mod tests {
         assert!(call.get("tool_call_id").is_none());
         assert!(result.get("tool_call_id").is_none());
     }
+
+    #[test]
+    fn initialize_payload_lists_agent_methods() {
+        let info = ServerInfo::new("agent-rpc", "1.2.3", methods(), vec!["agent".to_string()]);
+        let payload = info.to_json();
+        assert_eq!(payload["server"], json!("agent-rpc"));
+        assert_eq!(payload["version"], json!("1.2.3"));
+        assert_eq!(payload["features"], json!(["agent"]));
+        let names: Vec<&str> = payload["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|m| m["name"].as_str())
+            .collect();
+        for expected in ["initialize", "agent_chat", "reload_skills", "cancel"] {
+            assert!(names.contains(&expected), "{expected} missing");
+        }
+
+        let strict = json!({"method": "agent_chat", "strict": true,
+                            "params": {"message": "hi", "stream_mode": "fast"}});
+        assert!(check_strict(&info, "agent_chat", &strict)
+            .unwrap_err()
+            .contains("stream_mode"));
+        let lenient =
+            json!({"method": "agent_chat", "params": {"message": "hi", "stream_mode": "fast"}});
+        assert!(check_strict(&info, "agent_chat", &lenient).is_ok());
+    }
 }
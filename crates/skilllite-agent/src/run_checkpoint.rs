@@ -1,14 +1,20 @@
 //! A13: Run mode checkpoint — save/restore state for long-running tasks.
 //!
-//! Enables `skilllite run --resume` to continue from where a previous run left off.
+//! Each `skilllite run` writes one checkpoint row (keyed by its run id) to the SQLite store in
+//! [`skilllite_executor::run_checkpoints`]. `skilllite run --list-resumable` lists them,
+//! `--resume [<id>]` continues one (without an id: the latest of the current workspace) and
+//! `--discard <id>` drops one. The old single-slot `run_checkpoints/latest.json` is imported on
+//! first use and renamed to `latest.json.imported`.
 
 use crate::Result;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
-use crate::types::{ChatMessage, Task};
+use skilllite_executor::run_checkpoints::{self, CheckpointInfo, CheckpointStore};
 
-/// Checkpoint state for run mode. Persisted to ~/.skilllite/chat/run_checkpoints/latest.json
+use crate::types::{ChatMessage, LlmUsageTotals, Task};
+
+/// Checkpoint state for run mode. Stored as the `state` JSON of a `run_checkpoints` row.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunCheckpoint {
     pub run_id: String,
@@ -16,6 +22,11 @@ pub struct RunCheckpoint {
     pub workspace: String,
     pub task_plan: Vec<Task>,
     pub messages: Vec<ChatMessage>,
+    /// Tokens spent by this run so far, across resumes.
+    #[serde(default)]
+    pub llm_usage: LlmUsageTotals,
+    #[serde(default)]
+    pub created_at: String,
     pub updated_at: String,
 }
 
@@ -26,13 +37,16 @@ impl RunCheckpoint {
         task_plan: Vec<Task>,
         messages: Vec<ChatMessage>,
     ) -> Self {
+        let now = run_checkpoints::now_timestamp();
         Self {
             run_id: uuid::Uuid::new_v4().to_string(),
             goal,
             workspace,
             task_plan,
             messages,
-            updated_at: chrono::Utc::now().to_rfc3339(),
+            llm_usage: LlmUsageTotals::default(),
+            created_at: now.clone(),
+            updated_at: now,
         }
     }
 
@@ -40,34 +54,189 @@ impl RunCheckpoint {
     pub fn update(&mut self, task_plan: Vec<Task>, messages: Vec<ChatMessage>) {
         self.task_plan = task_plan;
         self.messages = messages;
-        self.updated_at = chrono::Utc::now().to_rfc3339();
+        self.updated_at = run_checkpoints::now_timestamp();
+    }
+
+    /// Completed and total tasks, e.g. `(3, 7)`.
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self.task_plan.iter().filter(|t| t.completed).count();
+        (done, self.task_plan.len())
+    }
+}
+
+/// The checkpoint a `skilllite run` writes to ([`crate::types::AgentConfig::run_checkpoint`]).
+/// Fixed for the whole run, so every save updates the same row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunCheckpointTarget {
+    pub run_id: String,
+    /// The original goal; a resumed run keeps it instead of the continuation prompt.
+    pub goal: String,
+    pub created_at: String,
+    /// Tokens spent by earlier invocations of this run.
+    pub prior_usage: LlmUsageTotals,
+}
+
+impl RunCheckpointTarget {
+    /// A fresh run.
+    pub fn new(goal: &str) -> Self {
+        Self {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            goal: goal.to_string(),
+            created_at: run_checkpoints::now_timestamp(),
+            prior_usage: LlmUsageTotals::default(),
+        }
+    }
+
+    /// Continue writing to `checkpoint`.
+    pub fn resuming(checkpoint: &RunCheckpoint) -> Self {
+        Self {
+            run_id: checkpoint.run_id.clone(),
+            goal: checkpoint.goal.clone(),
+            created_at: checkpoint.created_at.clone(),
+            prior_usage: checkpoint.llm_usage,
+        }
     }
+
+    /// Snapshot of the current state; `usage` is this invocation's spend only.
+    pub fn checkpoint(
+        &self,
+        workspace: &str,
+        task_plan: &[Task],
+        messages: &[ChatMessage],
+        usage: &LlmUsageTotals,
+    ) -> RunCheckpoint {
+        let mut llm_usage = self.prior_usage;
+        llm_usage.add(usage);
+        RunCheckpoint {
+            run_id: self.run_id.clone(),
+            goal: self.goal.clone(),
+            workspace: normalize_workspace(workspace),
+            task_plan: task_plan.to_vec(),
+            messages: messages.to_vec(),
+            llm_usage,
+            created_at: self.created_at.clone(),
+            updated_at: run_checkpoints::now_timestamp(),
+        }
+    }
+}
+
+/// Which checkpoint `skilllite run --resume` continues.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResumeFrom {
+    /// The most recently updated checkpoint of the current workspace.
+    LatestInWorkspace,
+    /// A checkpoint id or unique id prefix, from any workspace.
+    Id(String),
 }
 
 const CHECKPOINT_DIR: &str = "run_checkpoints";
-const CHECKPOINT_FILE: &str = "latest.json";
+const LEGACY_CHECKPOINT_FILE: &str = "latest.json";
+const LEGACY_IMPORTED_FILE: &str = "latest.json.imported";
+
+/// Workspace key used to match `--resume` against the current directory.
+pub fn normalize_workspace(workspace: &str) -> String {
+    std::fs::canonicalize(workspace)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| workspace.to_string())
+}
 
-/// Save checkpoint to chat_root/run_checkpoints/latest.json.
+/// Save (insert or update) a checkpoint under `chat_root`.
 pub fn save_checkpoint(chat_root: &Path, checkpoint: &RunCheckpoint) -> Result<()> {
-    let dir = chat_root.join(CHECKPOINT_DIR);
-    skilllite_fs::create_dir_all(&dir)?;
-    let path = dir.join(CHECKPOINT_FILE);
-    let content = serde_json::to_string_pretty(checkpoint)?;
-    skilllite_fs::atomic_write(&path, &content)?;
-    tracing::debug!("Run checkpoint saved to {}", path.display());
+    let store = open_store(chat_root)?;
+    save_to(&store, checkpoint)?;
+    tracing::debug!("Run checkpoint {} saved", checkpoint.run_id);
     Ok(())
 }
 
-/// Load checkpoint from chat_root/run_checkpoints/latest.json.
-/// Returns None if no checkpoint exists or file is invalid.
-pub fn load_checkpoint(chat_root: &Path) -> Result<Option<RunCheckpoint>> {
-    let path = chat_root.join(CHECKPOINT_DIR).join(CHECKPOINT_FILE);
-    if !path.exists() {
+/// Load the checkpoint `id` (full id or unique prefix), or without an id the most recent one
+/// of `workspace`. Returns None when nothing matches.
+pub fn load_checkpoint(
+    chat_root: &Path,
+    id: Option<&str>,
+    workspace: &str,
+) -> Result<Option<RunCheckpoint>> {
+    let store = open_store(chat_root)?;
+    let record = match id {
+        Some(id) => store.get(id)?,
+        None => store.latest_for_workspace(&normalize_workspace(workspace))?,
+    };
+    record
+        .map(|r| serde_json::from_str(&r.state).map_err(Into::into))
+        .transpose()
+}
+
+/// All resumable checkpoints, most recently updated first.
+pub fn list_checkpoints(chat_root: &Path) -> Result<Vec<CheckpointInfo>> {
+    Ok(open_store(chat_root)?.list()?)
+}
+
+/// Delete checkpoint `id` (full id or unique prefix). Returns the deleted id, if any.
+pub fn discard_checkpoint(chat_root: &Path, id: &str) -> Result<Option<String>> {
+    let store = open_store(chat_root)?;
+    let Some(record) = store.get(id)? else {
         return Ok(None);
+    };
+    store.discard(&record.info.id)?;
+    Ok(Some(record.info.id))
+}
+
+fn open_store(chat_root: &Path) -> Result<CheckpointStore> {
+    let store = CheckpointStore::open(chat_root)?;
+    import_legacy_checkpoint(chat_root, &store);
+    Ok(store)
+}
+
+fn save_to(store: &CheckpointStore, checkpoint: &RunCheckpoint) -> Result<()> {
+    let (tasks_done, tasks_total) = checkpoint.progress();
+    let info = CheckpointInfo {
+        id: checkpoint.run_id.clone(),
+        goal: checkpoint.goal.clone(),
+        workspace: checkpoint.workspace.clone(),
+        created_at: checkpoint.created_at.clone(),
+        updated_at: checkpoint.updated_at.clone(),
+        tasks_total,
+        tasks_done,
+        prompt_tokens: checkpoint.llm_usage.prompt_tokens,
+        completion_tokens: checkpoint.llm_usage.completion_tokens,
+    };
+    store.save(&info, &serde_json::to_string(checkpoint)?)?;
+    Ok(())
+}
+
+/// Move a pre-SQLite `latest.json` into the store, then rename it so it is imported once.
+fn import_legacy_checkpoint(chat_root: &Path, store: &CheckpointStore) {
+    let dir = chat_root.join(CHECKPOINT_DIR);
+    let path = dir.join(LEGACY_CHECKPOINT_FILE);
+    if !path.is_file() {
+        return;
+    }
+    let imported = skilllite_fs::read_file(&path)
+        .map_err(crate::Error::from)
+        .and_then(|content| Ok(serde_json::from_str::<RunCheckpoint>(&content)?))
+        .and_then(|mut cp| {
+            // Legacy timestamps carry sub-seconds and `+00:00`; normalize so they sort.
+            cp.updated_at = chrono::DateTime::parse_from_rfc3339(&cp.updated_at)
+                .map(|t| {
+                    t.with_timezone(&chrono::Utc)
+                        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+                })
+                .unwrap_or_else(|_| run_checkpoints::now_timestamp());
+            if cp.created_at.is_empty() {
+                cp.created_at = cp.updated_at.clone();
+            }
+            cp.workspace = normalize_workspace(&cp.workspace);
+            save_to(store, &cp)?;
+            Ok(cp.run_id)
+        });
+    match imported {
+        Ok(run_id) => {
+            tracing::info!("Imported legacy run checkpoint {}", run_id);
+            if let Err(e) = std::fs::rename(&path, dir.join(LEGACY_IMPORTED_FILE)) {
+                tracing::warn!("Could not rename {}: {}", path.display(), e);
+            }
+        }
+        Err(e) => tracing::warn!("Skipping legacy run checkpoint {}: {}", path.display(), e),
     }
-    let content = skilllite_fs::read_file(&path)?;
-    let checkpoint: RunCheckpoint = serde_json::from_str(&content)?;
-    Ok(Some(checkpoint))
 }
 
 /// Build continuation message for resume. Injects context so the agent continues from checkpoint.
@@ -101,3 +270,94 @@ pub fn build_resume_message(checkpoint: &RunCheckpoint) -> String {
     );
     msg
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: u32, completed: bool) -> Task {
+        Task {
+            id,
+            description: format!("task {}", id),
+            tool_hint: None,
+            completed,
+        }
+    }
+
+    #[test]
+    fn resumed_run_keeps_id_goal_and_accumulates_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let chat_root = dir.path();
+        let ws = dir.path().to_string_lossy().to_string();
+
+        let target = RunCheckpointTarget::new("write the report");
+        let usage = LlmUsageTotals {
+            prompt_tokens: 100,
+            ..Default::default()
+        };
+        let tasks = [task(1, true), task(2, false), task(3, false)];
+        let cp = target.checkpoint(&ws, &tasks, &[ChatMessage::user("hi")], &usage);
+        save_checkpoint(chat_root, &cp).unwrap();
+
+        let loaded = load_checkpoint(chat_root, None, &ws).unwrap().unwrap();
+        assert_eq!(loaded.run_id, target.run_id);
+        assert_eq!(loaded.progress(), (1, 3));
+
+        let resumed = RunCheckpointTarget::resuming(&loaded);
+        let cp = resumed.checkpoint(&ws, &tasks, &[], &usage);
+        save_checkpoint(chat_root, &cp).unwrap();
+
+        let listed = list_checkpoints(chat_root).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].goal, "write the report");
+        assert_eq!(listed[0].prompt_tokens, 200);
+
+        // Another workspace does not see it without an explicit id.
+        assert!(load_checkpoint(chat_root, None, "/elsewhere")
+            .unwrap()
+            .is_none());
+        let prefix = &target.run_id[..8];
+        assert!(load_checkpoint(chat_root, Some(prefix), "/elsewhere")
+            .unwrap()
+            .is_some());
+
+        assert_eq!(
+            discard_checkpoint(chat_root, prefix).unwrap().as_deref(),
+            Some(target.run_id.as_str())
+        );
+        assert!(list_checkpoints(chat_root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn legacy_latest_json_is_imported_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let chat_root = dir.path();
+        let legacy_dir = chat_root.join(CHECKPOINT_DIR);
+        std::fs::create_dir_all(&legacy_dir).unwrap();
+        let mut legacy = RunCheckpoint::new(
+            "legacy goal".to_string(),
+            "/legacy/ws".to_string(),
+            vec![task(1, true), task(2, false)],
+            Vec::new(),
+        );
+        legacy.updated_at = chrono::Utc::now().to_rfc3339();
+        // Files written before the SQLite store had no created_at / llm_usage.
+        let mut json = serde_json::to_value(&legacy).unwrap();
+        json.as_object_mut().unwrap().remove("created_at");
+        json.as_object_mut().unwrap().remove("llm_usage");
+        std::fs::write(legacy_dir.join(LEGACY_CHECKPOINT_FILE), json.to_string()).unwrap();
+
+        let listed = list_checkpoints(chat_root).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, legacy.run_id);
+        assert_eq!((listed[0].tasks_done, listed[0].tasks_total), (1, 2));
+        assert!(listed[0].updated_at.ends_with('Z'));
+        assert!(!legacy_dir.join(LEGACY_CHECKPOINT_FILE).exists());
+        assert!(legacy_dir.join(LEGACY_IMPORTED_FILE).is_file());
+
+        let loaded = load_checkpoint(chat_root, None, "/legacy/ws")
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.goal, "legacy goal");
+    }
+}
@@ -0,0 +1,315 @@
+//! Scripting contract for `skilllite chat -m` and `skilllite run --goal`: process exit
+//! codes and the `--output json` result envelope.
+//!
+//! In JSON mode stdout carries exactly one envelope; progress, tool output and streamed
+//! assistant text go to stderr. The exit code is the same in both output formats.
+
+use serde::Serialize;
+
+use crate::types::{AgentResult, Artifact, LlmUsageTotals};
+
+/// The turn completed.
+pub const EXIT_OK: i32 = 0;
+/// The turn ended before the task was completed (cancelled, iteration or tool-call limit,
+/// unfinished plan).
+pub const EXIT_INCOMPLETE: i32 = 1;
+/// The turn never started: missing API key, invalid options, no checkpoint to resume.
+pub const EXIT_CONFIG: i32 = 2;
+/// The turn failed with an error, typically from the LLM API.
+pub const EXIT_LLM_ERROR: i32 = 3;
+/// The turn stopped on the consecutive tool failure limit.
+pub const EXIT_TOOL_FAILURES: i32 = 4;
+/// The run stopped on `--max-cost` / `--max-tokens`.
+pub const EXIT_BUDGET: i32 = 5;
+/// `--fail-on-empty` and the final response was empty or whitespace.
+pub const EXIT_EMPTY_RESPONSE: i32 = 6;
+
+/// How the result of a single-shot turn is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Response on stdout, progress on stderr (the interactive look).
+    #[default]
+    Text,
+    /// One [`ResultEnvelope`] on stdout, everything else on stderr.
+    Json,
+}
+
+impl OutputFormat {
+    /// Parse a `--output` value (`text` / `json`).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// `--output` / `--fail-on-empty` for one single-shot run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SingleShotOptions {
+    pub output: OutputFormat,
+    pub fail_on_empty: bool,
+}
+
+impl SingleShotOptions {
+    pub fn is_json(&self) -> bool {
+        self.output == OutputFormat::Json
+    }
+
+    /// Print the envelope (JSON) or the failure line (text) and return the exit code.
+    pub fn finish(&self, envelope: &ResultEnvelope) -> i32 {
+        match self.output {
+            OutputFormat::Json => match serde_json::to_string_pretty(envelope) {
+                Ok(text) => println!("{}", text),
+                Err(e) => eprintln!("Error: failed to serialize result: {}", e),
+            },
+            OutputFormat::Text => {
+                if let Some(err) = &envelope.error {
+                    eprintln!("Error ({}): {}", err.kind.as_str(), err.message);
+                }
+            }
+        }
+        envelope.exit_code()
+    }
+}
+
+/// Why a single-shot run did not succeed; each kind has its own exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    Config,
+    Llm,
+    ToolFailures,
+    Budget,
+    Incomplete,
+    EmptyResponse,
+}
+
+impl FailureKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Llm => "llm",
+            Self::ToolFailures => "tool_failures",
+            Self::Budget => "budget",
+            Self::Incomplete => "incomplete",
+            Self::EmptyResponse => "empty_response",
+        }
+    }
+
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Config => EXIT_CONFIG,
+            Self::Llm => EXIT_LLM_ERROR,
+            Self::ToolFailures => EXIT_TOOL_FAILURES,
+            Self::Budget => EXIT_BUDGET,
+            Self::Incomplete => EXIT_INCOMPLETE,
+            Self::EmptyResponse => EXIT_EMPTY_RESPONSE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnvelopeError {
+    pub kind: FailureKind,
+    pub message: String,
+}
+
+/// API-reported token usage of the turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct EnvelopeUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl From<&LlmUsageTotals> for EnvelopeUsage {
+    fn from(u: &LlmUsageTotals) -> Self {
+        Self {
+            prompt_tokens: u.prompt_tokens,
+            completion_tokens: u.completion_tokens,
+            total_tokens: u.total_tokens,
+        }
+    }
+}
+
+/// `--output json` result: `{"ok", "response", "artifacts", "usage", "error"}`.
+/// `error` is null exactly when `ok` is true.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResultEnvelope {
+    pub ok: bool,
+    pub response: String,
+    pub artifacts: Vec<Artifact>,
+    pub usage: EnvelopeUsage,
+    pub error: Option<EnvelopeError>,
+}
+
+impl ResultEnvelope {
+    /// Envelope for a finished turn; partial results keep their response and artifacts.
+    pub fn from_result(result: &AgentResult, fail_on_empty: bool) -> Self {
+        let error = classify(result, fail_on_empty);
+        Self {
+            ok: error.is_none(),
+            response: result.response.clone(),
+            artifacts: result.artifacts.clone(),
+            usage: EnvelopeUsage::from(&result.feedback.llm_usage),
+            error,
+        }
+    }
+
+    /// Envelope for a run that failed with an error before producing a result.
+    pub fn failed(kind: FailureKind, message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            response: String::new(),
+            artifacts: Vec::new(),
+            usage: EnvelopeUsage::default(),
+            error: Some(EnvelopeError {
+                kind,
+                message: message.into(),
+            }),
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.error.as_ref().map_or(EXIT_OK, |e| e.kind.exit_code())
+    }
+}
+
+/// The failure a finished turn reports, if any. Aborts win over "incomplete", and an empty
+/// response only counts when the turn otherwise succeeded.
+fn classify(result: &AgentResult, fail_on_empty: bool) -> Option<EnvelopeError> {
+    let (kind, message) = if result.tool_failure_cutoff {
+        (
+            FailureKind::ToolFailures,
+            format!(
+                "stopped after {} consecutive tool failures",
+                result.feedback.max_consecutive_tool_failures
+            ),
+        )
+    } else if let Some(info) = &result.budget_exceeded {
+        (
+            FailureKind::Budget,
+            format!("budget exceeded: {}", info.describe()),
+        )
+    } else if result.cancelled {
+        (
+            FailureKind::Incomplete,
+            "the turn was cancelled".to_string(),
+        )
+    } else if !result.feedback.task_completed {
+        (
+            FailureKind::Incomplete,
+            "the turn ended before the task was completed".to_string(),
+        )
+    } else if fail_on_empty && result.response.trim().is_empty() {
+        (
+            FailureKind::EmptyResponse,
+            "the model returned an empty response".to_string(),
+        )
+    } else {
+        return None;
+    };
+    Some(EnvelopeError { kind, message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BudgetExceeded, BudgetLimit, ExecutionFeedback};
+
+    fn result(response: &str) -> AgentResult {
+        AgentResult {
+            response: response.to_string(),
+            messages: Vec::new(),
+            tool_calls_count: 0,
+            iterations: 1,
+            task_plan: Vec::new(),
+            feedback: ExecutionFeedback {
+                task_completed: true,
+                ..Default::default()
+            },
+            wiki_update_suggestion: None,
+            cancelled: false,
+            budget_exceeded: None,
+            tool_failure_cutoff: false,
+            artifacts: Vec::new(),
+        }
+    }
+
+    fn kind(result: &AgentResult, fail_on_empty: bool) -> Option<FailureKind> {
+        classify(result, fail_on_empty).map(|e| e.kind)
+    }
+
+    #[test]
+    fn classification_orders_aborts_before_incomplete_and_empty() {
+        assert_eq!(kind(&result("done"), true), None);
+        assert_eq!(kind(&result("  \n"), false), None);
+        assert_eq!(
+            kind(&result("  \n"), true),
+            Some(FailureKind::EmptyResponse)
+        );
+
+        let mut r = result("");
+        r.feedback.task_completed = false;
+        assert_eq!(kind(&r, true), Some(FailureKind::Incomplete));
+
+        r.budget_exceeded = Some(BudgetExceeded {
+            limit: BudgetLimit::Tokens,
+            prompt_tokens: 90,
+            completion_tokens: 20,
+            total_tokens: 110,
+            estimated_cost_usd: None,
+            max_cost_usd: None,
+            max_tokens: Some(100),
+        });
+        assert_eq!(kind(&r, true), Some(FailureKind::Budget));
+
+        r.tool_failure_cutoff = true;
+        assert_eq!(kind(&r, true), Some(FailureKind::ToolFailures));
+    }
+
+    #[test]
+    fn envelope_shape_and_exit_codes() {
+        let mut r = result("answer");
+        r.feedback
+            .llm_usage
+            .record(Some(crate::types::LlmUsageReport::from_counts(10, 5, 15)));
+        let ok = ResultEnvelope::from_result(&r, true);
+        assert_eq!(ok.exit_code(), EXIT_OK);
+        let json = serde_json::to_value(&ok).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "ok": true,
+                "response": "answer",
+                "artifacts": [],
+                "usage": { "prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15 },
+                "error": null,
+            })
+        );
+
+        let failed = ResultEnvelope::failed(FailureKind::Config, "API key required");
+        assert_eq!(failed.exit_code(), EXIT_CONFIG);
+        let json = serde_json::to_value(&failed).unwrap();
+        assert_eq!(json["ok"], false);
+        assert_eq!(json["error"]["kind"], "config");
+        assert_eq!(json["error"]["message"], "API key required");
+
+        let codes = [
+            FailureKind::Config,
+            FailureKind::Llm,
+            FailureKind::ToolFailures,
+            FailureKind::Budget,
+            FailureKind::Incomplete,
+            FailureKind::EmptyResponse,
+        ]
+        .map(FailureKind::exit_code);
+        let mut unique = codes.to_vec();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), codes.len());
+        assert!(!codes.contains(&EXIT_OK));
+    }
+}
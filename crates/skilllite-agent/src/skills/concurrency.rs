@@ -0,0 +1,169 @@
+//! Execution slots for skills: SKILL.md `max_concurrency` per skill plus a process-wide
+//! cap (`SKILLLITE_MAX_PARALLEL_SKILLS`).
+//!
+//! Heavy skills (headless browsers, office converters) run out of memory when several
+//! calls overlap — within one tool batch or across sessions sharing the process (RPC,
+//! swarm tasks). Calls beyond the limit wait for a slot instead of starting.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Waits shorter than this are not reported back to the model.
+const REPORT_WAIT_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// Held while a skill runs; dropping it frees the slot for the next queued call.
+pub struct SkillSlot {
+    _skill: Option<OwnedSemaphorePermit>,
+    _global: Option<OwnedSemaphorePermit>,
+    /// Time spent queued before the slot was granted.
+    pub waited: Duration,
+}
+
+impl SkillSlot {
+    /// Note appended to the tool result when the call had to queue noticeably.
+    pub fn queue_note(&self, skill_name: &str) -> Option<String> {
+        (self.waited >= REPORT_WAIT_THRESHOLD).then(|| {
+            format!(
+                "[queued {:.1}s waiting for a free '{}' execution slot]",
+                self.waited.as_secs_f64(),
+                skill_name
+            )
+        })
+    }
+}
+
+/// Semaphores keyed by skill name, plus an optional global one.
+pub struct SkillLimiter {
+    global: Option<Arc<Semaphore>>,
+    /// skill name → (limit the semaphore was created with, semaphore)
+    per_skill: Mutex<HashMap<String, (usize, Arc<Semaphore>)>>,
+}
+
+impl SkillLimiter {
+    /// `global_limit` of `None` or `Some(0)` means no process-wide cap.
+    pub fn new(global_limit: Option<usize>) -> Self {
+        Self {
+            global: global_limit
+                .filter(|&n| n > 0)
+                .map(|n| Arc::new(Semaphore::new(n))),
+            per_skill: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Process-wide limiter, configured once from `SKILLLITE_MAX_PARALLEL_SKILLS`.
+    pub fn shared() -> &'static SkillLimiter {
+        static SHARED: OnceLock<SkillLimiter> = OnceLock::new();
+        SHARED.get_or_init(|| {
+            let limit = skilllite_core::config::loader::env_optional(
+                skilllite_core::config::env_keys::agent_loop::SKILLLITE_MAX_PARALLEL_SKILLS,
+                &[],
+            )
+            .and_then(|s| s.trim().parse::<usize>().ok());
+            SkillLimiter::new(limit)
+        })
+    }
+
+    fn skill_semaphore(&self, skill_name: &str, limit: usize) -> Arc<Semaphore> {
+        let mut map = self
+            .per_skill
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match map.get(skill_name) {
+            Some((l, sem)) if *l == limit => sem.clone(),
+            // New skill, or its SKILL.md limit changed on reload: calls already running
+            // keep their permits on the old semaphore.
+            _ => {
+                let sem = Arc::new(Semaphore::new(limit));
+                map.insert(skill_name.to_string(), (limit, sem.clone()));
+                sem
+            }
+        }
+    }
+
+    /// Wait for a slot for `skill_name`. The per-skill slot is taken before the global
+    /// one so a call queued behind its own skill does not hold a global slot meanwhile
+    /// (and the fixed order rules out lock-order deadlocks).
+    pub async fn acquire(&self, skill_name: &str, max_concurrency: Option<usize>) -> SkillSlot {
+        let start = Instant::now();
+        let skill = match max_concurrency.filter(|&n| n > 0) {
+            Some(limit) => self
+                .skill_semaphore(skill_name, limit)
+                .acquire_owned()
+                .await
+                .ok(),
+            None => None,
+        };
+        let global = match &self.global {
+            Some(sem) => sem.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        SkillSlot {
+            _skill: skill,
+            _global: global,
+            waited: start.elapsed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `calls` overlapping executions of a slow fixture skill (sleeps 150 ms while
+    /// holding its slot) and returns the peak number running at once plus each wait.
+    async fn run_slow_skill(
+        limiter: Arc<SkillLimiter>,
+        names: &[&'static str],
+        max_concurrency: Option<usize>,
+    ) -> (usize, Vec<Duration>) {
+        let running = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut handles = Vec::new();
+        for &name in names {
+            let (limiter, running, peak) = (limiter.clone(), running.clone(), peak.clone());
+            handles.push(tokio::spawn(async move {
+                let slot = limiter.acquire(name, max_concurrency).await;
+                let now = running.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+                peak.fetch_max(now, std::sync::atomic::Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(150)).await;
+                running.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                slot.waited
+            }));
+        }
+        let mut waits = Vec::new();
+        for h in handles {
+            waits.push(h.await.unwrap());
+        }
+        waits.sort();
+        (peak.load(std::sync::atomic::Ordering::SeqCst), waits)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrency_one_serializes_calls_and_reports_wait() {
+        let limiter = Arc::new(SkillLimiter::new(None));
+        let (peak, waits) = run_slow_skill(limiter, &["browser"; 3], Some(1)).await;
+        assert_eq!(peak, 1);
+        assert!(waits[0] < REPORT_WAIT_THRESHOLD, "{waits:?}");
+        assert!(waits[2] >= Duration::from_millis(250), "{waits:?}");
+        let slot = SkillSlot {
+            _skill: None,
+            _global: None,
+            waited: waits[2],
+        };
+        assert!(slot.queue_note("browser").unwrap().contains("'browser'"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn unlimited_skills_run_together_until_global_cap() {
+        let limiter = Arc::new(SkillLimiter::new(None));
+        let (peak, _) = run_slow_skill(limiter, &["calc"; 3], None).await;
+        assert_eq!(peak, 3);
+
+        let capped = Arc::new(SkillLimiter::new(Some(2)));
+        let (peak, _) = run_slow_skill(capped, &["a", "b", "c", "d"], None).await;
+        assert_eq!(peak, 2);
+    }
+}
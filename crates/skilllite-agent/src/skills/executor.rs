@@ -11,13 +11,20 @@ use super::usage_stats;
 use crate::high_risk;
 use crate::types::{ConfirmationRequest, EventSink, RiskTier, ToolResult};
 use skilllite_core::skill::metadata::{self, SkillMetadata};
+use skilllite_core::skill::origin::SkillSandboxPolicy;
+use skilllite_sandbox::move_protection::EntryFingerprint;
+use skilllite_sandbox::results::SkillResult;
 use skilllite_sandbox::runner::{ResourceLimits, SandboxConfig, SandboxLevel, SandboxRunOptions};
 use skilllite_sandbox::security::SKILL_PRECHECK_CRITICAL_BLOCKED;
+use skilllite_sandbox::SandboxError;
 
 use super::loader::sanitize_tool_name;
 use super::security::{compute_skill_hash, run_security_scan};
 use super::LoadedSkill;
 
+/// Separates a skill's run output from the `result.json` file listing appended to it.
+pub(crate) const OUTPUT_FILES_SEPARATOR: &str = "\n\nOutput files:\n";
+
 /// Execute a skill tool call. Dispatches to sandbox execution.
 /// When `entry_point_override` is `Some` and skill has no entry_point, use it (e.g. 大模型根据 SKILL.md 推理出的入口).
 pub fn execute_skill(
@@ -49,10 +56,18 @@ pub fn execute_skill(
         }
         Err(e) => {
             usage_stats::track_skill_execution(&skill.name, false);
+            let mut content = format!("Error: {}", e);
+            if let Some(err) = e.sandbox_error() {
+                content.push_str(&format!(
+                    "\n\nerror: {}\nHint: {}",
+                    err.to_json(),
+                    sandbox_error_hint(err)
+                ));
+            }
             ToolResult {
                 tool_call_id: String::new(),
                 tool_name: tool_name.to_string(),
-                content: format!("Error: {}", e),
+                content,
                 is_error: true,
                 counts_as_failure: true,
             }
@@ -60,6 +75,89 @@ pub fn execute_skill(
     }
 }
 
+/// [`execute_skill`] once the skill has an execution slot (see [`super::concurrency`]).
+/// Calls beyond `max_concurrency` wait here; a noticeable wait is noted in the result.
+pub async fn execute_skill_queued(
+    skill: &LoadedSkill,
+    tool_name: &str,
+    arguments: &str,
+    workspace: &Path,
+    event_sink: &mut dyn EventSink,
+) -> ToolResult {
+    let slot = super::concurrency::SkillLimiter::shared()
+        .acquire(&skill.name, skill.metadata.max_concurrency)
+        .await;
+    let mut result = execute_skill(skill, tool_name, arguments, workspace, event_sink, None);
+    if let Some(note) = slot.queue_note(&skill.name) {
+        tracing::info!(skill = %skill.name, waited_ms = slot.waited.as_millis() as u64, "Skill call queued");
+        result.content.push_str("\n\n");
+        result.content.push_str(&note);
+    }
+    result
+}
+
+/// Whether a first call of `skill` in a session asks the user before running, using the same
+/// checks as [`execute_skill`]: sandbox level 3 for the skill's effective policy, or A11
+/// network confirmation for a network skill. Security scan findings can still add a prompt.
+pub fn requires_confirmation(skill: &LoadedSkill) -> bool {
+    let mut network = skill.metadata.network.clone();
+    let policy = SkillSandboxPolicy::for_skill(
+        &skill.skill_dir,
+        SandboxLevel::from_env_or_cli(None).as_u8(),
+        &network,
+    );
+    policy.apply_network(&skill.name, &mut network);
+    SandboxLevel::from_env_or_cli(Some(policy.level)) == SandboxLevel::Level3
+        || (high_risk::confirm_network() && network.enabled)
+}
+
+/// Next step for the model, by failure kind; the raw message above it stays unchanged.
+fn sandbox_error_hint(err: &SandboxError) -> String {
+    match err {
+        SandboxError::ScanBlocked { .. } => "The entry script has Critical security findings and \
+cannot be run. Do not retry; tell the user which findings blocked it."
+            .to_string(),
+        SandboxError::IntegrityViolation { .. } => "The skill's files link outside its directory \
+or changed after the security scan, so it was not started. Do not retry; tell the user the \
+listed paths need to be removed or reinstalled."
+            .to_string(),
+        SandboxError::ConsentDenied => {
+            "The user declined to run this skill. Do not retry unless they ask.".to_string()
+        }
+        SandboxError::NetworkDenied { host: Some(host) } => format!(
+            "Network access to '{}' was blocked by the sandbox. Ask the user to allow domain {} \
+(add it to `network.outbound` in SKILL.md) instead of retrying.",
+            host, host
+        ),
+        SandboxError::NetworkDenied { host: None } => "Network access was blocked by the sandbox. \
+Ask the user to enable network for this skill instead of retrying."
+            .to_string(),
+        SandboxError::Timeout { limit_secs } => format!(
+            "The skill was killed after {}s. Retry with a smaller input, or ask the user to raise {}.",
+            limit_secs,
+            skilllite_core::config::env_keys::sandbox::SKILLLITE_TIMEOUT_SECS
+        ),
+        SandboxError::MemoryExceeded { limit_mb, .. } => format!(
+            "The skill exceeded its {} MB memory limit. Retry with a smaller input, or ask the user to raise {}.",
+            limit_mb,
+            skilllite_core::config::env_keys::sandbox::SKILLLITE_MAX_MEMORY_MB
+        ),
+        SandboxError::MissingRuntime { need, .. } => format!(
+            "No interpreter satisfies {}. Ask the user to install it; retrying will not help.",
+            need
+        ),
+        SandboxError::EntryPointMissing { .. } => "The skill has no runnable entry script. Call \
+get_skill_details for its usage instead of retrying the same call."
+            .to_string(),
+        SandboxError::NonZeroExit { .. } => "The script itself failed. Read stderr above, fix the \
+arguments if they caused it, and retry at most once."
+            .to_string(),
+        SandboxError::InvalidOutput { .. } => "The script ran but did not print JSON. Do not \
+retry with the same input; report the raw output to the user."
+            .to_string(),
+    }
+}
+
 // Session-level cache of confirmed skills (skill_name → code_hash).
 // Avoids re-scanning skills that were already confirmed in this session.
 // Thread-local because EventSink requires &mut (not shareable across threads).
@@ -89,6 +187,14 @@ fn execute_skill_inner(
         }
     }
 
+    // Evolved skills: level floor + restricted network (skilllite_core::skill::origin).
+    let policy = SkillSandboxPolicy::for_skill(
+        skill_dir,
+        SandboxLevel::from_env_or_cli(None).as_u8(),
+        &metadata.network,
+    );
+    policy.apply_network(&skill.name, &mut metadata.network);
+
     // Phase 2.5: Multi-script tool routing
     // If tool_name is in the multi_script_entries map, use that script as entry_point.
     // Try exact match first, then normalized match (hyphens → underscores).
@@ -100,14 +206,26 @@ fn execute_skill_inner(
         });
 
     // Same entry the sandbox runner will use (multi-script tool → overridden entry_point).
+    // Declared `entry_points` go through `select_entry` so their language follows the script.
     let mut metadata_for_run = metadata.clone();
     if let Some(ep) = multi_script_entry {
-        metadata_for_run.entry_point = ep.clone();
+        let declared = metadata_for_run
+            .entry_points
+            .iter()
+            .find(|e| &e.script == ep)
+            .map(|e| e.name.clone());
+        if declared.is_none_or(|name| metadata_for_run.select_entry(&name).is_err()) {
+            metadata_for_run.entry_point = ep.clone();
+        }
     }
 
     // Pre-spawn static precheck (SKILL.md + entry): all sandbox levels. The runner never repeats
     // this in the agent process (no TTY); we confirm here then pass `skip_skill_precheck`.
-    let sandbox_level = SandboxLevel::from_env_or_cli(None);
+    let sandbox_level = SandboxLevel::from_env_or_cli(Some(policy.level));
+    // Fingerprint the entry before it is hashed and scanned; the runner verifies the launch
+    // against this, not against whatever is on disk once the user has confirmed.
+    let entry_fingerprint =
+        EntryFingerprint::capture(&skill_dir.join(&metadata_for_run.entry_point)).ok();
     let code_hash = compute_skill_hash(skill_dir, &metadata_for_run);
 
     let skip_precheck_ui = sandbox_level == SandboxLevel::Level3
@@ -127,7 +245,11 @@ fn execute_skill_inner(
                 .filter(|s| !s.trim().is_empty())
                 .map(|s| format!("\n\n{}", s))
                 .unwrap_or_default();
-            bail!("{}{}", SKILL_PRECHECK_CRITICAL_BLOCKED, tail);
+            return Err(skilllite_sandbox::Error::run(
+                SandboxError::scan_blocked(precheck.review_text.as_deref()),
+                format!("{}{}", SKILL_PRECHECK_CRITICAL_BLOCKED, tail),
+            )
+            .into());
         }
 
         if let Some(report) = precheck.review_text.clone() {
@@ -191,13 +313,8 @@ fn execute_skill_inner(
     // Setup environment
     let cache_dir = skilllite_core::config::CacheConfig::cache_dir();
     let env_spec = skilllite_core::EnvSpec::from_metadata(skill_dir, &metadata);
-    let env_path = skilllite_sandbox::env::builder::ensure_environment(
-        skill_dir,
-        &env_spec,
-        cache_dir.as_deref(),
-        None,
-        None,
-    )?;
+    let env_path =
+        ensure_environment_with_status(skill_dir, &env_spec, cache_dir.as_deref(), event_sink)?;
 
     let limits = ResourceLimits::from_env();
 
@@ -218,10 +335,7 @@ fn execute_skill_inner(
                     raw_pattern: p.raw_pattern,
                 })
                 .collect();
-        skilllite_sandbox::bash_validator::validate_bash_command(command, &validator_patterns)
-            .map_err(|e| crate::Error::validation(format!("Command validation failed: {}", e)))?;
 
-        // Execute bash command (same logic as main.rs bash_command).
         // Resolve the effective cwd: prefer SKILLLITE_OUTPUT_DIR so file outputs
         // (screenshots, PDFs, etc.) land in the output directory automatically.
         let effective_cwd = skilllite_core::config::PathsConfig::from_env()
@@ -231,6 +345,15 @@ fn execute_skill_inner(
             .filter(|p| p.is_dir())
             .unwrap_or_else(|| workspace.to_path_buf());
 
+        skilllite_sandbox::bash_validator::validate_bash_command_in(
+            command,
+            &validator_patterns,
+            &[workspace, effective_cwd.as_path()],
+        )
+        .map_err(|e| crate::Error::validation(format!("Command validation failed: {}", e)))?;
+
+        // Execute bash command (same logic as main.rs bash_command).
+
         execute_bash_in_skill(skill_dir, command, &env_path, &effective_cwd, workspace)
     } else {
         // Regular skill or multi-script tool: pass arguments as input JSON
@@ -245,9 +368,12 @@ fn execute_skill_inner(
             .map_err(|e| crate::Error::validation(format!("Invalid input JSON: {}", e)))?;
 
         let runtime = skilllite_sandbox::env::builder::build_runtime_paths(&env_path);
-        let config = build_sandbox_config(skill_dir, &metadata_for_run);
+        let mut config = build_sandbox_config(skill_dir, &metadata_for_run);
+        config.enforce_isolation = policy.enforced;
         let run_options = SandboxRunOptions {
             skip_skill_precheck: true,
+            entry_fingerprint,
+            ..Default::default()
         };
         let output = skilllite_sandbox::runner::run_in_sandbox_with_limits_and_level_opt(
             skill_dir,
@@ -258,11 +384,54 @@ fn execute_skill_inner(
             sandbox_level,
             run_options,
         )?;
-        Ok(output)
+        let output = if policy.is_evolved() {
+            policy.attach_to_output(&output)
+        } else {
+            output
+        };
+        // result.json contract: list reported files for the model and for list_output.
+        let outputs = SkillResult::outputs_from_run_output(&output);
+        if outputs.is_empty() {
+            return Ok(output);
+        }
+        let listing = crate::extensions::register_skill_outputs(&skill.name, &outputs);
+        Ok(format!("{}{}{}", output, OUTPUT_FILES_SEPARATOR, listing))
     }
 }
 
 /// Build a `SandboxConfig` from `SkillMetadata`, resolving language via `detect_language`.
+/// `ensure_environment` on a worker thread, forwarding its setup steps (venv creation,
+/// package installs) to `event_sink.on_status` so a first run does not look hung.
+fn ensure_environment_with_status(
+    skill_dir: &Path,
+    env_spec: &skilllite_core::EnvSpec,
+    cache_dir: Option<&str>,
+    event_sink: &mut dyn EventSink,
+) -> Result<std::path::PathBuf> {
+    let (tx, rx) = std::sync::mpsc::channel::<String>();
+    let env_path = std::thread::scope(|scope| {
+        let worker = scope.spawn(move || {
+            skilllite_sandbox::env::builder::ensure_environment(
+                skill_dir,
+                env_spec,
+                cache_dir,
+                Some(Box::new(move |msg: &str| {
+                    let _ = tx.send(msg.to_string());
+                })),
+                None,
+            )
+        });
+        // Ends when the worker drops the callback (and with it the sender)
+        for message in rx {
+            event_sink.on_status(&message);
+        }
+        worker
+            .join()
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("environment setup panicked").into()))
+    })?;
+    Ok(env_path)
+}
+
 fn build_sandbox_config(skill_dir: &Path, metadata: &SkillMetadata) -> SandboxConfig {
     SandboxConfig {
         name: metadata.name.clone(),
@@ -270,7 +439,11 @@ fn build_sandbox_config(skill_dir: &Path, metadata: &SkillMetadata) -> SandboxCo
         language: metadata::detect_language(skill_dir, metadata),
         network_enabled: metadata.network.enabled,
         network_outbound: metadata.network.outbound.clone(),
+        network_deny: metadata.network.deny.clone(),
         uses_playwright: metadata.uses_playwright(),
+        script_args: Vec::new(),
+        results_dir: None,
+        enforce_isolation: false,
     }
 }
 
@@ -435,3 +608,29 @@ fn rewrite_output_paths(command: &str, output_dir: &Path) -> String {
 
     result_parts.join(" ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sandbox_error_hints_are_actionable_per_kind() {
+        let hint = sandbox_error_hint(&SandboxError::NetworkDenied {
+            host: Some("api.example.com".to_string()),
+        });
+        assert!(hint.contains("allow domain api.example.com"));
+        let hint = sandbox_error_hint(&SandboxError::Timeout { limit_secs: 30 });
+        assert!(hint.contains("30s") && hint.contains("SKILLLITE_TIMEOUT_SECS"));
+        let hint = sandbox_error_hint(&SandboxError::NetworkDenied { host: None });
+        assert!(!hint.contains("domain"));
+    }
+
+    #[test]
+    fn failed_skill_error_exposes_kind_only_when_classified() {
+        let e: crate::Error =
+            skilllite_sandbox::Error::run(SandboxError::ConsentDenied, "blocked").into();
+        assert_eq!(e.sandbox_error(), Some(&SandboxError::ConsentDenied));
+        assert_eq!(e.to_string(), "blocked");
+        assert!(crate::Error::validation("plain").sandbox_error().is_none());
+    }
+}
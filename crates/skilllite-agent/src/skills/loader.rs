@@ -59,6 +59,22 @@ pub(super) fn load_single_skill(skill_dir: &Path) -> Option<LoadedSkill> {
     let name = metadata.name.clone();
     let tool_name = sanitize_tool_name(&name);
 
+    // SKILL.md `entry_points`: one tool per declared command, replacing both the default
+    // entry tool and scripts/ auto-detection.
+    if !metadata.entry_points.is_empty() {
+        let (tool_definitions, multi_script_entries) =
+            declared_entry_point_tools(skill_dir, &metadata);
+        return Some(LoadedSkill {
+            name,
+            skill_dir: skill_dir.to_path_buf(),
+            metadata,
+            tool_definitions,
+            multi_script_entries,
+            docs: Default::default(),
+            source_hash: skill_md_hash(skill_dir),
+        });
+    }
+
     // Generate tool definition based on skill type
     let mut tool_defs = if metadata.is_bash_tool_skill() {
         // Bash-tool skill: command string parameter
@@ -126,9 +142,21 @@ pub(super) fn load_single_skill(skill_dir: &Path) -> Option<LoadedSkill> {
         metadata,
         tool_definitions: tool_defs,
         multi_script_entries,
+        docs: Default::default(),
+        source_hash: skill_md_hash(skill_dir),
     })
 }
 
+/// Content hash of `SKILL.md`, used to spot edited skills on reload.
+fn skill_md_hash(skill_dir: &Path) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    std::fs::read(skill_dir.join("SKILL.md"))
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
 // ─── Phase 2.5: Multi-script skill support ──────────────────────────────────
 
 /// Detect multiple scripts in a skill's `scripts/` directory and generate
@@ -215,6 +243,46 @@ fn detect_multi_script_tools(
     (tools, entries)
 }
 
+/// Tool definitions for SKILL.md `entry_points`, named `skill_name__entry_name` like
+/// auto-detected scripts. The declared `parameters` schema wins; otherwise it is inferred
+/// from the script as for scripts/ tools.
+/// Returns (tool_definitions, entry_map: tool_name → script_path).
+fn declared_entry_point_tools(
+    skill_dir: &Path,
+    metadata: &SkillMetadata,
+) -> (Vec<ToolDefinition>, HashMap<String, String>) {
+    let mut tools = Vec::new();
+    let mut entries = HashMap::new();
+    for ep in &metadata.entry_points {
+        let tool_name = format!(
+            "{}__{}",
+            sanitize_tool_name(&metadata.name),
+            sanitize_tool_name(&ep.name)
+        );
+        let description = ep
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("Execute {} from skill '{}'", ep.script, metadata.name));
+        let schema = ep.parameters.clone().unwrap_or_else(|| {
+            if ep.script.ends_with(".py") {
+                parse_argparse_schema(&skill_dir.join(&ep.script)).unwrap_or_else(flexible_schema)
+            } else {
+                flexible_schema()
+            }
+        });
+        entries.insert(tool_name.clone(), ep.script.clone());
+        tools.push(ToolDefinition {
+            tool_type: "function".to_string(),
+            function: FunctionDef {
+                name: tool_name,
+                description,
+                parameters: schema,
+            },
+        });
+    }
+    (tools, entries)
+}
+
 /// Return a flexible JSON schema that accepts any properties.
 fn flexible_schema() -> serde_json::Value {
     serde_json::json!({
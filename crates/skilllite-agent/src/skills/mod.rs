@@ -8,26 +8,36 @@
 //!   - Multi-script skill support (skill_name__script_name)
 //!   - Argparse schema inference for Python scripts
 //!   - .skilllite.lock dependency resolution
+//!   - Execution slots from SKILL.md `max_concurrency` / `SKILLLITE_MAX_PARALLEL_SKILLS`
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 
 use skilllite_core::skill::metadata::SkillMetadata;
 
-use super::types::ToolDefinition;
+use super::types::{safe_truncate, SkillReloadSummary, ToolDefinition};
 
 use loader::{load_evolved_skills, load_single_skill, sanitize_tool_name};
 
+pub mod concurrency;
 mod executor;
 pub mod infer_entry;
 mod loader;
 pub(crate) mod security;
 pub(crate) mod usage_stats;
 
-pub use executor::execute_skill;
+pub(crate) use executor::OUTPUT_FILES_SEPARATOR;
+pub use executor::{execute_skill, execute_skill_queued, requires_confirmation};
 pub use security::{read_lock_file, write_lock_file, LockFile};
 
+/// Max bytes of the one-line summary used in the prompt index and LLM-facing tool descriptions.
+const SUMMARY_MAX_BYTES: usize = 120;
+
 /// A loaded skill ready for invocation.
+///
+/// Loading parses only the SKILL.md frontmatter; the body and reference docs are read on
+/// first use through [`LoadedSkill::full_docs`].
 #[derive(Debug, Clone)]
 pub struct LoadedSkill {
     pub name: String,
@@ -36,12 +46,56 @@ pub struct LoadedSkill {
     pub tool_definitions: Vec<ToolDefinition>,
     /// Multi-script tool mapping: tool_name → script_path (e.g. "scripts/init_skill.py")
     pub multi_script_entries: HashMap<String, String>,
+    /// Lazily loaded SKILL.md body + references, shared by clones of this skill.
+    pub docs: SkillDocsCell,
+    /// Hash of SKILL.md at load time; [`reload_skills`] re-parses skills whose hash changed.
+    pub source_hash: u64,
+}
+
+/// Per-skill cache for the full documentation, filled on first access.
+#[derive(Debug, Clone, Default)]
+pub struct SkillDocsCell(Arc<OnceLock<Option<String>>>);
+
+impl LoadedSkill {
+    /// SKILL.md body + reference docs, read from disk once per session.
+    /// `None` when SKILL.md is missing.
+    pub fn full_docs(&self) -> Option<&str> {
+        self.docs
+            .0
+            .get_or_init(|| super::prompt::read_skill_full_docs(self))
+            .as_deref()
+    }
+
+    /// First line (or sentence) of the frontmatter description.
+    pub fn summary(&self) -> &str {
+        let desc = self
+            .metadata
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .unwrap_or("No description");
+        let line = desc.lines().next().unwrap_or(desc).trim();
+        let sentence = match line.find(". ").or_else(|| line.find('。')) {
+            Some(i) => {
+                let end = i + line[i..].chars().next().map_or(1, char::len_utf8);
+                &line[..end]
+            }
+            None => line,
+        };
+        safe_truncate(sentence, SUMMARY_MAX_BYTES)
+    }
 }
 
 /// Load skills from directories, parse SKILL.md, generate tool definitions.
 /// Also loads evolved skills from `_evolved/` subdirectories (EVO-4),
-/// skipping archived ones based on `.meta.json`.
+/// skipping archived ones based on `.meta.json`, and skills replicated from swarm peers
+/// under `_replicated/`.
 /// Skills are project-level only: evolution writes to workspace/.skills/_evolved/.
+///
+/// Skills marked `skip` in their manifest are ignored. When two skills share a name (or a
+/// tool name), the first one in `skill_dirs` order wins and the rest are dropped with a
+/// warning; `skilllite reindex --resolve` fixes such collisions on disk.
 pub fn load_skills(skill_dirs: &[String]) -> Vec<LoadedSkill> {
     let mut skills = Vec::new();
 
@@ -81,12 +135,161 @@ pub fn load_skills(skill_dirs: &[String]) -> Vec<LoadedSkill> {
             );
             skills.extend(evolved);
         }
+
+        // Skills replicated from swarm peers (quarantined, run under the evolved policy)
+        let replicated_dir = path.join(skilllite_core::skill::origin::REPLICATED_DIR);
+        if replicated_dir.is_dir() {
+            let replicated = load_evolved_skills(&replicated_dir);
+            tracing::debug!(
+                "Loaded {} replicated skills from {}",
+                replicated.len(),
+                replicated_dir.display()
+            );
+            skills.extend(replicated);
+        }
     }
 
-    skills
+    skills.retain(|s| {
+        let skipped = skilllite_core::skill::manifest::is_skill_skipped(&s.skill_dir);
+        if skipped {
+            tracing::debug!("Skipping disabled skill: {}", s.skill_dir.display());
+        }
+        !skipped
+    });
+    dedupe_skills(skills)
+}
+
+/// Keep the first skill per name and the first definition per tool name.
+fn dedupe_skills(skills: Vec<LoadedSkill>) -> Vec<LoadedSkill> {
+    let mut names: HashMap<String, PathBuf> = HashMap::new();
+    let mut tool_owners: HashMap<String, String> = HashMap::new();
+    let mut out = Vec::with_capacity(skills.len());
+    for mut skill in skills {
+        if let Some(first) = names.get(&skill.name) {
+            tracing::warn!(
+                "Duplicate skill name '{}': using {}, ignoring {} (run `skilllite reindex --resolve`)",
+                skill.name,
+                first.display(),
+                skill.skill_dir.display()
+            );
+            continue;
+        }
+        skill
+            .tool_definitions
+            .retain(|td| match tool_owners.get(&td.function.name) {
+                Some(owner) => {
+                    tracing::warn!(
+                        "Duplicate tool name '{}': keeping skill '{}', dropping it from '{}'",
+                        td.function.name,
+                        owner,
+                        skill.name
+                    );
+                    false
+                }
+                None => {
+                    tool_owners.insert(td.function.name.clone(), skill.name.clone());
+                    true
+                }
+            });
+        names.insert(skill.name.clone(), skill.skill_dir.clone());
+        out.push(skill);
+    }
+    out
+}
+
+/// Skill directories for a session: the configured ones, or auto-discovered
+/// `.skills` / `skills` under the workspace when none are configured.
+pub fn resolve_skill_dirs(configured: &[String], workspace: &str) -> Vec<String> {
+    if configured.is_empty() {
+        skilllite_core::skill::discovery::discover_skill_dirs_for_loading(
+            Path::new(workspace),
+            Some(&[".skills", "skills"]),
+        )
+    } else {
+        configured.to_vec()
+    }
+}
+
+/// Cheap change detector for [`load_skills`]: hashes the path and content of every SKILL.md
+/// (and evolved-skill `.meta.json`) discovery would read, without parsing them.
+pub fn skill_dirs_fingerprint(skill_dirs: &[String]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    fn hash_skill_md(dir: &Path, hasher: &mut impl Hasher) {
+        for name in ["SKILL.md", ".meta.json"] {
+            let file = dir.join(name);
+            if let Ok(bytes) = std::fs::read(&file) {
+                file.hash(hasher);
+                bytes.hash(hasher);
+            }
+        }
+    }
+    fn hash_subdirs(dir: &Path, hasher: &mut impl Hasher) {
+        if let Ok(mut entries) = skilllite_fs::read_dir(dir) {
+            entries.sort();
+            for (entry_path, is_dir) in entries {
+                if is_dir {
+                    hash_skill_md(&entry_path, hasher);
+                }
+            }
+        }
+    }
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for dir_path in skill_dirs {
+        let path = Path::new(dir_path);
+        if !path.is_dir() {
+            continue;
+        }
+        if path.join("SKILL.md").exists() {
+            hash_skill_md(path, &mut hasher);
+        } else {
+            hash_subdirs(path, &mut hasher);
+        }
+        hash_subdirs(&path.join("_evolved"), &mut hasher);
+        hash_subdirs(
+            &path.join(skilllite_core::skill::origin::REPLICATED_DIR),
+            &mut hasher,
+        );
+    }
+    hasher.finish()
+}
+
+/// Re-run discovery over `skill_dirs` and diff against `current`.
+///
+/// Unchanged skills are carried over as-is (keeping their cached docs); skills whose
+/// SKILL.md changed are re-parsed, new ones are appended and missing ones dropped.
+pub fn reload_skills(
+    current: &[LoadedSkill],
+    skill_dirs: &[String],
+) -> (Vec<LoadedSkill>, SkillReloadSummary) {
+    let fresh = load_skills(skill_dirs);
+    let mut summary = SkillReloadSummary::default();
+    let mut next = Vec::with_capacity(fresh.len());
+    for skill in fresh {
+        match current.iter().find(|s| s.name == skill.name) {
+            Some(old)
+                if old.source_hash == skill.source_hash && old.skill_dir == skill.skill_dir =>
+            {
+                next.push(old.clone());
+            }
+            Some(_) => {
+                summary.updated.push(skill.name.clone());
+                next.push(skill);
+            }
+            None => {
+                summary.added.push(skill.name.clone());
+                next.push(skill);
+            }
+        }
+    }
+    summary.removed = current
+        .iter()
+        .filter(|old| !next.iter().any(|s| s.name == old.name))
+        .map(|old| old.name.clone())
+        .collect();
+    (next, summary)
 }
 
-/// Load evolved skills from `_evolved/` directory, filtering out archived ones.
 /// Find a loaded skill by tool name.
 ///
 /// Supports fuzzy matching: normalizes both the query and registered names
@@ -132,3 +335,165 @@ pub fn find_skill_by_name<'a>(skills: &'a [LoadedSkill], name: &str) -> Option<&
         .iter()
         .find(|s| s.name == with_hyphens || s.name == with_underscores)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_skill(root: &Path, name: &str, description: &str) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!(
+                "---\nname: {name}\ndescription: {description}\nallowed-tools: Bash({name}:*)\n---\n\nBody\n"
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn reload_skills_reports_added_removed_and_updated() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dirs = vec![tmp.path().to_string_lossy().to_string()];
+        write_skill(tmp.path(), "alpha", "Alpha tool");
+        write_skill(tmp.path(), "beta", "Beta tool");
+        write_skill(tmp.path(), "gamma", "Gamma tool");
+        let current = load_skills(&dirs);
+        assert_eq!(current.len(), 3);
+
+        std::fs::remove_dir_all(tmp.path().join("alpha")).unwrap();
+        write_skill(tmp.path(), "beta", "Beta tool, now with 中文 docs");
+        write_skill(tmp.path(), "delta", "Delta tool");
+        let (next, summary) = reload_skills(&current, &dirs);
+
+        assert_eq!(summary.added, vec!["delta".to_string()]);
+        assert_eq!(summary.removed, vec!["alpha".to_string()]);
+        assert_eq!(summary.updated, vec!["beta".to_string()]);
+        let beta = find_skill_by_name(&next, "beta").unwrap();
+        assert_eq!(
+            beta.metadata.description.as_deref(),
+            Some("Beta tool, now with 中文 docs")
+        );
+        let old_gamma = find_skill_by_name(&current, "gamma").unwrap();
+        let new_gamma = find_skill_by_name(&next, "gamma").unwrap();
+        assert!(Arc::ptr_eq(&old_gamma.docs.0, &new_gamma.docs.0));
+
+        let (_, unchanged) = reload_skills(&next, &dirs);
+        assert!(unchanged.is_empty(), "{}", unchanged.describe());
+    }
+
+    #[test]
+    fn skill_dirs_fingerprint_tracks_skill_md_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dirs = vec![tmp.path().to_string_lossy().to_string()];
+        write_skill(tmp.path(), "alpha", "Alpha tool");
+        let before = skill_dirs_fingerprint(&dirs);
+        assert_eq!(skill_dirs_fingerprint(&dirs), before);
+
+        std::fs::write(tmp.path().join("alpha").join("notes.txt"), "unrelated").unwrap();
+        assert_eq!(skill_dirs_fingerprint(&dirs), before);
+
+        write_skill(tmp.path(), "alpha", "Alpha tool v2");
+        let edited = skill_dirs_fingerprint(&dirs);
+        assert_ne!(edited, before);
+
+        write_skill(tmp.path(), "beta", "Beta tool");
+        assert_ne!(skill_dirs_fingerprint(&dirs), edited);
+    }
+
+    #[test]
+    fn duplicate_skill_names_keep_first_configured_dir() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        write_skill(first.path(), "web-search", "From the first dir");
+        write_skill(second.path(), "web-search", "From the second dir");
+        write_skill(second.path(), "other", "Other tool");
+        // Same SKILL.md name under a different directory name in the same dir
+        let renamed = second.path().join("web-search-copy");
+        std::fs::create_dir_all(&renamed).unwrap();
+        std::fs::copy(
+            second.path().join("web-search/SKILL.md"),
+            renamed.join("SKILL.md"),
+        )
+        .unwrap();
+
+        let dirs = vec![
+            first.path().to_string_lossy().to_string(),
+            second.path().to_string_lossy().to_string(),
+        ];
+        let skills = load_skills(&dirs);
+        assert_eq!(skills.len(), 2);
+        let web = find_skill_by_name(&skills, "web-search").unwrap();
+        assert_eq!(web.skill_dir, first.path().join("web-search"));
+
+        let mut tool_names: Vec<_> = skills
+            .iter()
+            .flat_map(|s| s.tool_definitions.iter().map(|t| t.function.name.clone()))
+            .collect();
+        let total = tool_names.len();
+        tool_names.sort();
+        tool_names.dedup();
+        assert_eq!(tool_names.len(), total);
+
+        // Disabling the winner lets the next copy through
+        skilllite_core::skill::manifest::set_skill_skipped(&first.path().join("web-search"), true)
+            .unwrap();
+        let skills = load_skills(&dirs);
+        let web = find_skill_by_name(&skills, "web-search").unwrap();
+        assert_eq!(web.skill_dir, second.path().join("web-search"));
+    }
+
+    #[test]
+    fn declared_entry_points_become_one_tool_each() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("pdf-toolkit");
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::write(dir.join("scripts/convert.py"), "").unwrap();
+        std::fs::write(
+            dir.join("scripts/merge.py"),
+            "parser.add_argument('--output', required=True, help='Merged file')\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("scripts/unlisted.py"), "").unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            r#"---
+name: pdf-toolkit
+description: PDF utilities
+entry_points:
+  convert:
+    script: scripts/convert.py
+    description: Convert a document to PDF
+    parameters:
+      type: object
+      properties:
+        path: { type: string }
+      required: [path]
+  merge-all:
+    script: scripts/merge.py
+---
+"#,
+        )
+        .unwrap();
+
+        let skills = load_skills(&[tmp.path().to_string_lossy().to_string()]);
+        let skill = find_skill_by_name(&skills, "pdf-toolkit").unwrap();
+        let tools: Vec<_> = skill.tool_definitions.iter().map(|t| &t.function).collect();
+        assert_eq!(tools.len(), 2, "unlisted scripts are not exposed");
+
+        assert_eq!(tools[0].name, "pdf_toolkit__convert");
+        assert_eq!(tools[0].description, "Convert a document to PDF");
+        assert_eq!(tools[0].parameters["required"], serde_json::json!(["path"]));
+
+        assert_eq!(tools[1].name, "pdf_toolkit__merge_all");
+        assert_eq!(
+            tools[1].parameters["properties"]["output"]["description"],
+            "Merged file"
+        );
+        assert_eq!(
+            skill.multi_script_entries.get("pdf_toolkit__merge_all"),
+            Some(&"scripts/merge.py".to_string())
+        );
+    }
+}
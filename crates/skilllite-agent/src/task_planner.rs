@@ -26,7 +26,7 @@ use super::skills::LoadedSkill;
 use super::soul::Soul;
 use super::tool_hint_resolver;
 use super::types::*;
-use skilllite_evolution::seed;
+use skilllite_evolution::seed::{self, template, template::TemplateVars};
 
 /// Resolve the output directory path for prompt injection.
 fn resolve_output_dir() -> String {
@@ -94,12 +94,16 @@ pub struct TaskPlanner {
     available_rules: Vec<PlanningRule>,
     /// Rule IDs matched for the current user request.
     matched_rule_ids: Vec<String>,
+    /// Scoped rules whose scope held for the current request.
+    in_scope_rule_ids: Vec<String>,
     /// Chat data root for loading prompt templates.
     chat_root: Option<std::path::PathBuf>,
     /// EVO-5: Workspace path for project-level prompt overrides.
     workspace: Option<std::path::PathBuf>,
     /// Final tool availability for the current execution mode, if known.
     availability: Option<ToolAvailabilityView>,
+    /// Tasks given up on (tool-loop hard stop): not completed, but no longer worked on.
+    failed_task_ids: Vec<u32>,
 }
 
 impl TaskPlanner {
@@ -118,9 +122,11 @@ impl TaskPlanner {
             available_rules: rules.clone(),
             rules,
             matched_rule_ids: Vec::new(),
+            in_scope_rule_ids: Vec::new(),
             chat_root: chat_root.map(|p| p.to_path_buf()),
             workspace: workspace.map(|p| p.to_path_buf()),
             availability,
+            failed_task_ids: Vec::new(),
         }
     }
 
@@ -201,11 +207,8 @@ impl TaskPlanner {
         soul: Option<&Soul>,
         usage_totals: Option<&mut LlmUsageTotals>,
     ) -> Result<Vec<Task>> {
-        self.available_rules = self.filter_rules_by_available_skills(&self.rules, skills);
-        self.matched_rule_ids = filter_rules_for_user_message(&self.available_rules, user_message)
-            .into_iter()
-            .map(|r| r.id.clone())
-            .collect();
+        self.failed_task_ids.clear();
+        self.select_rules(user_message, skills);
 
         let visible_skills: Vec<&LoadedSkill> = match self.availability.as_ref() {
             Some(view) => view.filter_callable_skills(skills),
@@ -345,11 +348,43 @@ impl TaskPlanner {
         }
     }
 
+    /// Narrow the loaded rules to those in scope for this request and backed by available
+    /// skills. Out-of-scope rules never reach the prompt, so they take no rule budget.
+    fn select_rules(&mut self, user_message: &str, skills: &[LoadedSkill]) {
+        let skill_names: Vec<String> = skills.iter().map(|s| s.name.clone()).collect();
+        let ctx = planning_rules::RuleScopeContext::new(
+            self.workspace.as_deref(),
+            &skill_names,
+            user_message,
+        );
+        let in_scope: Vec<PlanningRule> = self
+            .rules
+            .iter()
+            .filter(|r| ctx.includes(r))
+            .cloned()
+            .collect();
+        self.in_scope_rule_ids = in_scope
+            .iter()
+            .filter(|r| r.scope.is_some() && r.origin != "seed")
+            .map(|r| r.id.clone())
+            .collect();
+        self.available_rules = self.filter_rules_by_available_skills(&in_scope, skills);
+        self.matched_rule_ids = filter_rules_for_user_message(&self.available_rules, user_message)
+            .into_iter()
+            .map(|r| r.id.clone())
+            .collect();
+    }
+
     /// Return planning rule IDs matched for the current user request.
     pub fn matched_rule_ids(&self) -> &[String] {
         &self.matched_rule_ids
     }
 
+    /// Return IDs of scoped rules that were in scope for the current user request.
+    pub fn in_scope_rule_ids(&self) -> &[String] {
+        &self.in_scope_rule_ids
+    }
+
     /// Parse the LLM response into a task list.
     ///
     /// Handles common LLM output quirks:
@@ -474,9 +509,10 @@ impl TaskPlanner {
         }
     }
 
-    /// Build the planning prompt from the external template.
+    /// Build the planning prompt from the external template ([`seed::template`] dialect).
     /// Placeholders: {{TODAY}}, {{YESTERDAY}}, {{RULES_SECTION}}, {{SKILLS_INFO}},
-    /// {{OUTPUT_DIR}}, {{EXAMPLES_SECTION}}, {{SOUL_SCOPE_BLOCK}} (A8).
+    /// {{OUTPUT_DIR}}, {{EXAMPLES_SECTION}}, {{SOUL_SCOPE_BLOCK}} (A8), plus the
+    /// workspace's `.skilllite/prompt_vars.json`.
     pub(crate) fn build_planning_prompt(
         &self,
         skills_info: &str,
@@ -516,18 +552,21 @@ impl TaskPlanner {
             .and_then(|s| s.to_planning_scope_block())
             .unwrap_or_default();
 
-        template
-            .replace("{{TODAY}}", &today)
-            .replace("{{YESTERDAY}}", &yesterday)
-            .replace("{{RULES_SECTION}}", &rules_section)
-            .replace("{{SKILLS_INFO}}", skills_info)
-            .replace("{{OUTPUT_DIR}}", &output_dir)
-            .replace("{{EXAMPLES_SECTION}}", &examples_section)
-            .replace("{{SOUL_SCOPE_BLOCK}}", &soul_scope)
+        let vars = TemplateVars::new()
+            .set("TODAY", today)
+            .set("YESTERDAY", yesterday)
+            .set("RULES_SECTION", rules_section)
+            .set("SKILLS_INFO", skills_info)
+            .set("OUTPUT_DIR", output_dir)
+            .set("EXAMPLES_SECTION", examples_section)
+            .set("SOUL_SCOPE_BLOCK", soul_scope)
+            .with_workspace(self.workspace.as_deref());
+        template::render(&template, &vars)
     }
 
     /// Build the main execution system prompt from the external template.
-    /// Placeholders: {{TODAY}}, {{YESTERDAY}}, {{SKILLS_LIST}}, {{OUTPUT_DIR}}.
+    /// Placeholders: {{TODAY}}, {{YESTERDAY}}, {{SKILLS_LIST}}, {{OUTPUT_DIR}}, plus the
+    /// workspace's `.skilllite/prompt_vars.json`.
     pub fn build_execution_prompt(&self, skills: &[LoadedSkill]) -> String {
         let visible_skills: Vec<&LoadedSkill> = match self.availability.as_ref() {
             Some(view) => view.filter_callable_skills(skills),
@@ -567,11 +606,13 @@ impl TaskPlanner {
             include_str!("seed/execution.seed.md"),
         );
 
-        template
-            .replace("{{TODAY}}", &today)
-            .replace("{{YESTERDAY}}", &yesterday)
-            .replace("{{SKILLS_LIST}}", &skills_list_str)
-            .replace("{{OUTPUT_DIR}}", &output_dir)
+        let vars = TemplateVars::new()
+            .set("TODAY", today)
+            .set("YESTERDAY", yesterday)
+            .set("SKILLS_LIST", skills_list_str)
+            .set("OUTPUT_DIR", output_dir)
+            .with_workspace(self.workspace.as_deref());
+        template::render(&template, &vars)
     }
 
     /// Build system prompt with task list and execution guidance.
@@ -586,7 +627,7 @@ impl TaskPlanner {
         let task_list_json =
             serde_json::to_string_pretty(&self.task_list).unwrap_or_else(|_| "[]".to_string());
 
-        let current_task = self.task_list.iter().find(|t| !t.completed);
+        let current_task = self.current_task();
         let mut current_task_info = String::new();
         let mut direct_call_instruction = String::new();
 
@@ -662,7 +703,29 @@ impl TaskPlanner {
         false
     }
 
-    /// Check if all tasks are completed.
+    /// Mark a task failed. It stays not completed, but is no longer the current task.
+    pub fn mark_failed(&mut self, task_id: u32) -> bool {
+        let pending = self
+            .task_list
+            .iter()
+            .any(|t| t.id == task_id && !t.completed);
+        if pending && !self.failed_task_ids.contains(&task_id) {
+            self.failed_task_ids.push(task_id);
+        }
+        pending
+    }
+
+    /// Whether `task_id` was given up on via [`Self::mark_failed`].
+    pub fn is_failed(&self, task_id: u32) -> bool {
+        self.failed_task_ids.contains(&task_id)
+    }
+
+    /// Whether any task of the current plan was marked failed.
+    pub fn has_failed_tasks(&self) -> bool {
+        !self.failed_task_ids.is_empty()
+    }
+
+    /// Check if no task is left to work on: every task is completed or failed.
     ///
     /// Returns `false` when the task list is empty: an empty plan means the LLM
     /// decided no explicit tasks were needed, which is *not* the same as having
@@ -670,7 +733,11 @@ impl TaskPlanner {
     /// `run_with_task_planning` to treat the plan as finished immediately (closing reply / break)
     /// after the first batch of tool calls, ending the loop prematurely.
     pub fn all_completed(&self) -> bool {
-        !self.task_list.is_empty() && self.task_list.iter().all(|t| t.completed)
+        !self.task_list.is_empty()
+            && self
+                .task_list
+                .iter()
+                .all(|t| t.completed || self.is_failed(t.id))
     }
 
     /// Check if the task list is empty (LLM decided no tools needed).
@@ -678,9 +745,11 @@ impl TaskPlanner {
         self.task_list.is_empty()
     }
 
-    /// Get the current (first uncompleted) task.
+    /// Get the current task: the first one neither completed nor failed.
     pub fn current_task(&self) -> Option<&Task> {
-        self.task_list.iter().find(|t| !t.completed)
+        self.task_list
+            .iter()
+            .find(|t| !t.completed && !self.is_failed(t.id))
     }
 
     /// Build a nudge message to push the LLM to continue working on tasks.
@@ -763,6 +832,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scoped_rule_follows_workspace_languages() {
+        let py_ws = tempfile::tempdir().unwrap();
+        std::fs::write(py_ws.path().join("pyproject.toml"), "").unwrap();
+        std::fs::write(py_ws.path().join("train.py"), "print(1)").unwrap();
+        let web_ws = tempfile::tempdir().unwrap();
+        std::fs::write(web_ws.path().join("package.json"), "{}").unwrap();
+        std::fs::create_dir(web_ws.path().join("src")).unwrap();
+        std::fs::write(web_ws.path().join("src").join("App.tsx"), "").unwrap();
+
+        let venv_rule = PlanningRule {
+            id: "evo_venv_first".to_string(),
+            priority: 60,
+            keywords: vec![],
+            context_keywords: vec![],
+            tool_hint: None,
+            instruction: "Always create a venv before installing packages.".to_string(),
+            mutable: true,
+            origin: "evolved".to_string(),
+            reusable: false,
+            effectiveness: None,
+            trigger_count: None,
+            provenance: None,
+            scope: Some(RuleScope {
+                language: Some("python".to_string()),
+                ..Default::default()
+            }),
+        };
+        let msg = "install the dependencies";
+        for (ws, expected) in [(py_ws.path(), true), (web_ws.path(), false)] {
+            let mut planner = TaskPlanner::new(Some(ws), None, None);
+            planner.rules.push(venv_rule.clone());
+            planner.select_rules(msg, &[]);
+            let prompt = planner.build_planning_prompt("None", msg, None, None);
+            assert_eq!(prompt.contains("create a venv"), expected, "{:?}", ws);
+            assert_eq!(
+                planner.in_scope_rule_ids() == ["evo_venv_first".to_string()],
+                expected
+            );
+            assert_eq!(planner.matched_rule_ids().contains(&venv_rule.id), expected);
+        }
+    }
+
     #[test]
     fn test_parse_task_list() {
         let planner = TaskPlanner::new(None, None, None);
@@ -832,6 +944,8 @@ mod tests {
                 reusable: false,
                 effectiveness: None,
                 trigger_count: None,
+                provenance: None,
+                scope: None,
             },
             PlanningRule {
                 id: "weather".to_string(),
@@ -845,6 +959,8 @@ mod tests {
                 reusable: false,
                 effectiveness: None,
                 trigger_count: None,
+                provenance: None,
+                scope: None,
             },
             PlanningRule {
                 id: "other".to_string(),
@@ -858,6 +974,8 @@ mod tests {
                 reusable: false,
                 effectiveness: None,
                 trigger_count: None,
+                provenance: None,
+                scope: None,
             },
         ];
 
@@ -898,6 +1016,8 @@ mod tests {
                 reusable: false,
                 effectiveness: None,
                 trigger_count: None,
+                provenance: None,
+                scope: None,
             },
             PlanningRule {
                 id: "seed_weather_skill".to_string(),
@@ -911,6 +1031,8 @@ mod tests {
                 reusable: false,
                 effectiveness: None,
                 trigger_count: None,
+                provenance: None,
+                scope: None,
             },
             PlanningRule {
                 id: "seed_unrelated".to_string(),
@@ -924,6 +1046,8 @@ mod tests {
                 reusable: false,
                 effectiveness: None,
                 trigger_count: None,
+                provenance: None,
+                scope: None,
             },
         ];
         // Ensure available_rules is also updated for filtering simulation
@@ -0,0 +1,197 @@
+//! Run spend limits (`skilllite run --max-cost / --max-tokens`) checked against
+//! [`LlmUsageTotals`] after each LLM call.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::llm_usage::LlmUsageTotals;
+use super::AgentConfig;
+
+/// USD price per million tokens for one model (`SKILLLITE_MODEL_PRICES_JSON`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// USD per 1M prompt tokens.
+    pub input: f64,
+    /// USD per 1M completion tokens.
+    pub output: f64,
+}
+
+impl ModelPrice {
+    /// Estimated spend for `usage`. Only API-reported tokens are counted.
+    pub fn cost_usd(&self, usage: &LlmUsageTotals) -> f64 {
+        (usage.prompt_tokens as f64 * self.input + usage.completion_tokens as f64 * self.output)
+            / 1_000_000.0
+    }
+}
+
+/// Parse `SKILLLITE_MODEL_PRICES_JSON`, e.g.
+/// `{"gpt-4o": {"input": 2.5, "output": 10}}` (best-effort; invalid input yields no prices).
+pub fn parse_model_prices_json(raw: &str) -> HashMap<String, ModelPrice> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return HashMap::new();
+    }
+    match serde_json::from_str::<HashMap<String, ModelPrice>>(raw) {
+        Ok(prices) => prices
+            .into_iter()
+            .filter(|(_, p)| p.input >= 0.0 && p.output >= 0.0)
+            .collect(),
+        Err(e) => {
+            tracing::warn!("SKILLLITE_MODEL_PRICES_JSON parse error: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Price for `model`: exact name first, then the longest configured prefix, so
+/// `gpt-4o-mini` also covers dated ids like `gpt-4o-mini-2024-07-18`.
+pub fn model_price(prices: &HashMap<String, ModelPrice>, model: &str) -> Option<ModelPrice> {
+    if let Some(p) = prices.get(model) {
+        return Some(*p);
+    }
+    prices
+        .iter()
+        .filter(|(name, _)| !name.is_empty() && model.starts_with(name.as_str()))
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, p)| *p)
+}
+
+/// Which limit stopped the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    Cost,
+    Tokens,
+}
+
+/// Totals at the point a run stopped on its budget.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetExceeded {
+    pub limit: BudgetLimit,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    /// None when the model has no configured price.
+    pub estimated_cost_usd: Option<f64>,
+    pub max_cost_usd: Option<f64>,
+    pub max_tokens: Option<u64>,
+}
+
+impl BudgetExceeded {
+    /// Compare `usage` with `config.max_cost_usd` / `config.max_run_tokens`.
+    /// A cost limit is only enforced when `config.model` has a price; otherwise
+    /// only the token limit applies.
+    pub fn check(config: &AgentConfig, usage: &LlmUsageTotals) -> Option<Self> {
+        if config.max_cost_usd.is_none() && config.max_run_tokens.is_none() {
+            return None;
+        }
+        let total_tokens = if usage.total_tokens > 0 {
+            usage.total_tokens
+        } else {
+            usage.prompt_tokens.saturating_add(usage.completion_tokens)
+        };
+        let estimated_cost_usd =
+            model_price(&config.model_prices, &config.model).map(|p| p.cost_usd(usage));
+        let limit = match (config.max_cost_usd, estimated_cost_usd) {
+            (Some(max), Some(cost)) if cost >= max => BudgetLimit::Cost,
+            _ => match config.max_run_tokens {
+                Some(max) if total_tokens >= max => BudgetLimit::Tokens,
+                _ => return None,
+            },
+        };
+        Some(Self {
+            limit,
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens,
+            estimated_cost_usd,
+            max_cost_usd: config.max_cost_usd,
+            max_tokens: config.max_run_tokens,
+        })
+    }
+
+    /// One-line summary, e.g. `12840 tokens, ~$0.0412 (limit $0.04)`.
+    pub fn describe(&self) -> String {
+        let mut s = format!("{} tokens", self.total_tokens);
+        if let Some(cost) = self.estimated_cost_usd {
+            s.push_str(&format!(", ~${:.4}", cost));
+        }
+        match self.limit {
+            BudgetLimit::Cost => {
+                if let Some(max) = self.max_cost_usd {
+                    s.push_str(&format!(" (limit ${})", max));
+                }
+            }
+            BudgetLimit::Tokens => {
+                if let Some(max) = self.max_tokens {
+                    s.push_str(&format!(" (limit {} tokens)", max));
+                }
+            }
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LlmUsageReport;
+
+    fn usage(prompt: u64, completion: u64) -> LlmUsageTotals {
+        let mut t = LlmUsageTotals::default();
+        t.record(Some(LlmUsageReport::from_counts(
+            prompt,
+            completion,
+            prompt + completion,
+        )));
+        t
+    }
+
+    fn priced_config() -> AgentConfig {
+        AgentConfig {
+            model: "gpt-4o-mini-2024-07-18".to_string(),
+            model_prices: parse_model_prices_json(
+                r#"{"gpt-4o": {"input": 2.5, "output": 10}, "gpt-4o-mini": {"input": 0.15, "output": 0.6}}"#,
+            ),
+            ..AgentConfig::default()
+        }
+    }
+
+    #[test]
+    fn longest_prefix_price_wins() {
+        let config = priced_config();
+        let p = model_price(&config.model_prices, &config.model).unwrap();
+        assert_eq!(p.input, 0.15);
+        assert!(model_price(&config.model_prices, "claude-3-5-sonnet").is_none());
+    }
+
+    #[test]
+    fn cost_limit_uses_model_price() {
+        let mut config = priced_config();
+        config.max_cost_usd = Some(0.001);
+        assert!(BudgetExceeded::check(&config, &usage(1_000, 100)).is_none());
+        let hit = BudgetExceeded::check(&config, &usage(6_000, 500)).unwrap();
+        assert_eq!(hit.limit, BudgetLimit::Cost);
+        assert!((hit.estimated_cost_usd.unwrap() - 0.0012).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unknown_model_falls_back_to_token_limit() {
+        let mut config = priced_config();
+        config.model = "local-llama".to_string();
+        config.max_cost_usd = Some(0.000_001);
+        assert!(BudgetExceeded::check(&config, &usage(50_000, 5_000)).is_none());
+        config.max_run_tokens = Some(10_000);
+        let hit = BudgetExceeded::check(&config, &usage(50_000, 5_000)).unwrap();
+        assert_eq!(hit.limit, BudgetLimit::Tokens);
+        assert_eq!(hit.estimated_cost_usd, None);
+        assert_eq!(hit.total_tokens, 55_000);
+    }
+
+    #[test]
+    fn invalid_prices_json_is_ignored() {
+        assert!(parse_model_prices_json("not json").is_empty());
+        assert!(parse_model_prices_json(r#"{"m": {"input": -1, "output": 1}}"#).is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use super::feedback::ExecutionFeedback;
 use super::task::Task;
 
+pub use skilllite_executor::transcript::TranscriptAttachment as UserFileAttachment;
 pub use skilllite_executor::transcript::TranscriptImage as UserImageAttachment;
 
 /// A chat message in OpenAI format.
@@ -135,6 +136,18 @@ pub enum ToolFormat {
     Claude,
 }
 
+impl ToolFormat {
+    /// Parse an explicit provider name (`config.provider` / `SKILLLITE_LLM_PROVIDER`).
+    /// Accepts `openai` and `anthropic` (alias `claude`); anything else is `None`.
+    pub fn from_provider(provider: &str) -> Option<Self> {
+        match provider.trim().to_ascii_lowercase().as_str() {
+            "openai" => Some(Self::OpenAI),
+            "anthropic" | "claude" => Some(Self::Claude),
+            _ => None,
+        }
+    }
+}
+
 /// OpenAI-compatible tool definition.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolDefinition {
@@ -241,6 +254,25 @@ pub fn parse_claude_tool_calls(content_blocks: &[serde_json::Value]) -> Vec<Tool
     calls
 }
 
+/// Directory an [`Artifact`] path is relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactRoot {
+    /// The output directory (`SKILLLITE_OUTPUT_DIR`, else `<workspace>/output`).
+    Output,
+    Workspace,
+}
+
+/// A file produced during the turn (write_output / write_file / skill `result.json` outputs).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    /// Path relative to `root`, `/`-separated.
+    pub path: String,
+    pub root: ArtifactRoot,
+    /// One-line description shown next to the path.
+    pub description: String,
+}
+
 /// Agent loop result.
 #[derive(Debug)]
 pub struct AgentResult {
@@ -257,6 +289,14 @@ pub struct AgentResult {
     pub feedback: ExecutionFeedback,
     /// Optional prompt payload for asking the user to record a Repo Wiki lesson.
     pub wiki_update_suggestion: Option<super::feedback::WikiUpdateSuggestion>,
+    /// True when the turn stopped early because [`super::EventSink::is_cancelled`] returned true.
+    pub cancelled: bool,
+    /// Set when the turn stopped early on `AgentConfig::max_cost_usd` / `max_run_tokens`.
+    pub budget_exceeded: Option<super::BudgetExceeded>,
+    /// True when the turn stopped on the consecutive tool failure limit.
+    pub tool_failure_cutoff: bool,
+    /// Files produced during the turn, in first-write order.
+    pub artifacts: Vec<Artifact>,
 }
 
 impl AgentResult {
@@ -1,6 +1,8 @@
 //! Agent configuration.
 
-use super::McpServerEntry;
+use std::collections::HashMap;
+
+use super::{McpServerEntry, ModelPrice};
 
 /// Agent configuration.
 #[derive(Debug, Clone)]
@@ -11,21 +13,46 @@ pub struct AgentConfig {
     pub api_key: String,
     /// Model name (e.g. "gpt-4o", "claude-3-5-sonnet-20241022")
     pub model: String,
+    /// Wire format override: "openai" | "anthropic" (`SKILLLITE_LLM_PROVIDER`).
+    /// None = detect from model name / api_base.
+    pub provider: Option<String>,
+    /// Record LLM calls to, or replay them from, a directory
+    /// (`SKILLLITE_LLM_RECORD` / `SKILLLITE_LLM_REPLAY`). Shared by clones of this config.
+    pub llm_tape: Option<std::sync::Arc<crate::llm::LlmTape>>,
+    /// Retry for transient LLM request failures (`SKILLLITE_LLM_MAX_ATTEMPTS`,
+    /// `SKILLLITE_LLM_RETRY_MAX_BACKOFF_SECS`).
+    pub llm_retry: crate::llm::RetryPolicy,
     /// Maximum iterations for the agent loop
     pub max_iterations: usize,
     /// Maximum tool calls per task
     pub max_tool_calls_per_task: usize,
+    /// Replan budget per session (`SKILLLITE_MAX_REPLANS`). Once spent, update_task_plan
+    /// calls that replace more than a couple of pending tasks are rejected.
+    pub max_replans: usize,
+    /// Identical tool calls (same name and arguments) in a row before the loop skips the call
+    /// and tells the model to change approach (`SKILLLITE_TOOL_LOOP_THRESHOLD`). 0 = off.
+    pub tool_loop_threshold: usize,
+    /// Identical calls in a row before the current task is failed (planning) or the turn
+    /// ends (simple) (`SKILLLITE_TOOL_LOOP_HARD_STOP`).
+    pub tool_loop_hard_stop: usize,
     /// Workspace root path
     pub workspace: String,
     /// System prompt override (optional)
     pub system_prompt: Option<String>,
     /// Temperature (0.0 - 2.0)
     pub temperature: Option<f64>,
+    /// Secondary model the loop switches to after repeated failed iterations
+    /// (`SKILLLITE_FALLBACK_MODEL`). None = never switch.
+    pub fallback_model: Option<String>,
     /// Skills directories to load (reserved for multi-dir support)
     #[allow(dead_code)]
     pub skill_dirs: Vec<String>,
     /// Enable task planning
     pub enable_task_planning: bool,
+    /// Pause after planning until the user approves, edits or rejects the task list
+    /// (`--approve-plan`, `SKILLLITE_APPROVE_PLAN`). Needs a sink that answers
+    /// [`super::EventSink::on_plan_proposed`].
+    pub approve_plan: bool,
     /// Enable memory tools
     pub enable_memory: bool,
     /// Enable memory vector search (requires memory_vector feature + embedding API)
@@ -61,6 +88,20 @@ pub struct AgentConfig {
     /// Optional outbound MCP servers (stdio). Disabled entries are skipped.
     /// Also loaded from `SKILLLITE_MCP_SERVERS_JSON` in [`AgentConfig::from_env`].
     pub mcp_servers: Vec<McpServerEntry>,
+
+    /// [Run mode] Stop once the estimated spend reaches this many USD (`--max-cost`).
+    /// Only enforced when `model` has an entry in `model_prices`.
+    pub max_cost_usd: Option<f64>,
+
+    /// [Run mode] Stop once API-reported tokens reach this total (`--max-tokens`).
+    pub max_run_tokens: Option<u64>,
+
+    /// Per-model prices used to estimate spend (`SKILLLITE_MODEL_PRICES_JSON`).
+    pub model_prices: HashMap<String, ModelPrice>,
+
+    /// [Run mode] Checkpoint row this run saves its progress to (A13). Set by
+    /// `run_agent_run`; `None` disables checkpointing.
+    pub run_checkpoint: Option<crate::run_checkpoint::RunCheckpointTarget>,
 }
 
 impl Default for AgentConfig {
@@ -69,16 +110,24 @@ impl Default for AgentConfig {
             api_base: "https://api.openai.com/v1".to_string(),
             api_key: String::new(),
             model: "gpt-4o".to_string(),
+            provider: None,
+            llm_tape: None,
+            llm_retry: crate::llm::RetryPolicy::default(),
             max_iterations: 50,
             max_tool_calls_per_task: 15,
+            max_replans: 3,
+            tool_loop_threshold: 3,
+            tool_loop_hard_stop: 5,
             workspace: std::env::current_dir()
                 .unwrap_or_else(|_| std::path::PathBuf::from("."))
                 .to_string_lossy()
                 .to_string(),
             system_prompt: None,
             temperature: None,
+            fallback_model: None,
             skill_dirs: Vec::new(),
             enable_task_planning: true,
+            approve_plan: false,
             enable_memory: true,
             enable_memory_vector: false,
             verbose: false,
@@ -89,6 +138,10 @@ impl Default for AgentConfig {
             skip_history_for_planning: false,
             read_only_tools: false,
             mcp_servers: Vec::new(),
+            max_cost_usd: None,
+            max_run_tokens: None,
+            model_prices: HashMap::new(),
+            run_checkpoint: None,
         }
     }
 }
@@ -96,24 +149,33 @@ impl Default for AgentConfig {
 impl AgentConfig {
     /// Load from environment variables with sensible defaults.
     /// Also reads `.env` file from current directory if present.
-    /// Uses unified config layer: SKILLLITE_* with fallback to OPENAI_* / BASE_URL / API_KEY / MODEL.
+    /// Uses the layered [`skilllite_core::config::Settings`]: config files, then SKILLLITE_* with
+    /// fallback to OPENAI_* / BASE_URL / API_KEY / MODEL.
     pub fn from_env() -> Self {
-        skilllite_core::config::load_dotenv();
-        let llm = skilllite_core::config::LlmConfig::from_env();
-        let paths = skilllite_core::config::PathsConfig::from_env();
-        let flags = skilllite_core::config::AgentFeatureFlags::from_env();
-        let loop_limits = skilllite_core::config::AgentLoopLimitsConfig::from_env();
+        let settings = skilllite_core::config::Settings::load();
+        let llm = skilllite_core::config::LlmConfig::from_settings(&settings);
+        let paths = skilllite_core::config::PathsConfig::from_settings(&settings);
+        let flags = skilllite_core::config::AgentFeatureFlags::from_settings(&settings);
+        let loop_limits = skilllite_core::config::AgentLoopLimitsConfig::from_settings(&settings);
         let mut config = Self {
             api_base: llm.api_base,
             api_key: llm.api_key,
             model: llm.model,
+            provider: llm.provider,
+            llm_tape: crate::llm::LlmTape::from_env(),
+            llm_retry: crate::llm::RetryPolicy::from_settings(&settings),
             max_iterations: loop_limits.max_iterations,
             max_tool_calls_per_task: loop_limits.max_tool_calls_per_task,
+            max_replans: loop_limits.max_replans,
+            tool_loop_threshold: loop_limits.tool_loop_threshold,
+            tool_loop_hard_stop: loop_limits.tool_loop_hard_stop,
             workspace: paths.workspace,
             enable_memory: flags.enable_memory,
             enable_memory_vector: flags.enable_memory_vector,
             enable_task_planning: flags.enable_task_planning,
+            approve_plan: settings.get_bool("agent.approve_plan").unwrap_or(false),
             max_consecutive_failures: Some(5),
+            fallback_model: settings.get("agent.fallback_model").map(str::to_string),
             context_append: crate::locale_prompt::context_append_from_ui_locale_env(),
             ..Default::default()
         };
@@ -123,6 +185,11 @@ impl AgentConfig {
         {
             config.mcp_servers = super::parse_mcp_servers_json(&raw);
         }
+        if let Ok(raw) =
+            std::env::var(skilllite_core::config::env_keys::agent_loop::SKILLLITE_MODEL_PRICES_JSON)
+        {
+            config.model_prices = super::parse_model_prices_json(&raw);
+        }
 
         config
     }
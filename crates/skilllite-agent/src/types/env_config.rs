@@ -3,6 +3,7 @@
 //! Ported from Python `config/env_config.py`.
 
 use skilllite_core::config::env_keys::summarization as sk;
+use skilllite_core::tokens::HEURISTIC_CHARS_PER_TOKEN;
 
 /// Helper: read an env var as usize with fallback.
 fn env_usize(key: &str, default: usize) -> usize {
@@ -101,6 +102,16 @@ pub fn get_user_input_max_chars() -> usize {
     env_usize(sk::SKILLLITE_USER_INPUT_MAX_CHARS, 30000)
 }
 
+/// Max size of a chat file attachment. `SKILLLITE_ATTACHMENT_MAX_BYTES`. Default 20 MiB.
+pub fn get_attachment_max_bytes() -> u64 {
+    env_usize(sk::SKILLLITE_ATTACHMENT_MAX_BYTES, 20 * 1024 * 1024) as u64
+}
+
+/// Max chars of text preview injected per attachment. `SKILLLITE_ATTACHMENT_PREVIEW_CHARS`.
+pub fn get_attachment_preview_chars() -> usize {
+    env_usize(sk::SKILLLITE_ATTACHMENT_PREVIEW_CHARS, 4000)
+}
+
 /// Max chars per tool result. `SKILLLITE_TOOL_RESULT_MAX_CHARS`.
 /// Default raised from 8000→12000 to better accommodate HTML/code tool results
 /// without triggering unnecessary truncation.
@@ -131,6 +142,17 @@ pub fn get_context_soft_limit_chars() -> usize {
     env_usize(sk::SKILLLITE_CONTEXT_SOFT_LIMIT_CHARS, 250_000)
 }
 
+/// The same soft limit in tokens, counted with the session model's tokenizer.
+///
+/// `SKILLLITE_CONTEXT_SOFT_LIMIT_TOKENS`. Default `get_context_soft_limit_chars() / 4`, so
+/// `SKILLLITE_CONTEXT_SOFT_LIMIT_CHARS=0` still disables pre-request shrinking.
+pub fn get_context_soft_limit_tokens() -> usize {
+    env_usize(
+        sk::SKILLLITE_CONTEXT_SOFT_LIMIT_TOKENS,
+        get_context_soft_limit_chars() / HEURISTIC_CHARS_PER_TOKEN,
+    )
+}
+
 /// Output directory override. `SKILLLITE_OUTPUT_DIR`.
 pub fn get_output_dir() -> Option<String> {
     skilllite_core::config::PathsConfig::from_env().output_dir
@@ -1,6 +1,8 @@
 //! Event sink trait and implementations for different output targets.
 
+use super::budget::BudgetExceeded;
 use super::llm_usage::LlmUsageReport;
+use super::plan_approval::{parse_plan_command, PlanApproval, PlanCommand, PLAN_COMMAND_HELP};
 use super::string_utils::safe_truncate;
 use super::task::Task;
 
@@ -47,6 +49,37 @@ impl ConfirmationRequest {
     }
 }
 
+/// Outcome of re-running skill discovery mid-session (`/reload-skills`, RPC `reload_skills`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct SkillReloadSummary {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub updated: Vec<String>,
+}
+
+impl SkillReloadSummary {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+
+    /// One-line human summary, e.g. `+1 added (pdf), -0 removed, ~2 updated (a, b)`.
+    pub fn describe(&self) -> String {
+        fn part(sign: &str, label: &str, names: &[String]) -> String {
+            if names.is_empty() {
+                format!("{}0 {}", sign, label)
+            } else {
+                format!("{}{} {} ({})", sign, names.len(), label, names.join(", "))
+            }
+        }
+        format!(
+            "{}, {}, {}",
+            part("+", "added", &self.added),
+            part("-", "removed", &self.removed),
+            part("~", "updated", &self.updated)
+        )
+    }
+}
+
 /// Event sink trait for different output targets (CLI, RPC, SDK).
 pub trait EventSink: Send {
     /// Called at the start of each conversation turn (before any other events).
@@ -106,6 +139,28 @@ pub trait EventSink: Send {
     fn on_swarm_finished(&mut self, _summary: &str) {}
     /// Called when swarm delegation fails or falls back.
     fn on_swarm_failed(&mut self, _message: &str) {}
+    /// Called after skills were re-discovered mid-session.
+    fn on_skills_reloaded(&mut self, _summary: &SkillReloadSummary) {}
+    /// Called at turn start when prompt files or skills changed since the previous turn, so
+    /// this turn's system prompt differs. `reasons` are e.g. `prompts/rules.json changed`.
+    fn on_system_prompt_refreshed(&mut self, _reasons: &[String]) {}
+    /// Called when the rest of the turn switches to `AgentConfig::fallback_model`.
+    fn on_model_fallback(&mut self, _from: &str, _to: &str, _reason: &str) {}
+    /// Called when the model repeats the same tool call (name and arguments) `repeats` times in
+    /// a row: once when the call is skipped and the model is told to change approach, then with
+    /// `stopped` when the loop fails the current task or ends the turn.
+    fn on_tool_loop(&mut self, _tool_name: &str, _repeats: usize, _stopped: bool) {}
+    /// Transient progress note while the agent waits, e.g. `rate limited, retrying in 4s` or
+    /// a skill environment setup step (`Installing 14 Python package(s)`).
+    fn on_status(&mut self, _message: &str) {}
+    /// Called after end-of-session auto-memory stored `facts` under `memory/auto/`.
+    fn on_memories_remembered(&mut self, _facts: &[String]) {}
+    /// Called at turn start for each background evolution cycle that finished while the user
+    /// was idle. `changes` are [`skilllite_evolution::format_evolution_changes`] lines.
+    fn on_evolution_completed(&mut self, _txn_id: &str, _changes: &[String]) {}
+    /// Called once when the run stops on `AgentConfig::max_cost_usd` / `max_run_tokens`.
+    /// The current tool batch has already finished; a partial result follows.
+    fn on_budget_exceeded(&mut self, _info: &BudgetExceeded) {}
     /// Called when the agent needs user confirmation (tools, L3 security, etc.).
     /// Returns true if the user approves.
     fn on_confirmation_request(&mut self, request: &ConfirmationRequest) -> bool;
@@ -113,6 +168,14 @@ pub trait EventSink: Send {
     fn on_text_chunk(&mut self, _chunk: &str) {}
     /// Called when a task plan is generated. (Phase 2)
     fn on_task_plan(&mut self, _tasks: &[Task]) {}
+    /// Plan-approval mode (`AgentConfig::approve_plan`): called before `on_task_plan`; execution
+    /// waits for the answer. `can_regenerate` is false once the one allowed rejection is spent.
+    /// Sinks without an approval channel keep the default, which fails the turn.
+    fn on_plan_proposed(&mut self, _tasks: &[Task], _can_regenerate: bool) -> PlanApproval {
+        PlanApproval::Unavailable
+    }
+    /// Called after update_task_plan replaced the plan (after `on_task_plan`).
+    fn on_replan(&mut self, _old_tasks: &[Task], _new_tasks: &[Task], _reason: &str) {}
     /// Called when a task's status changes. (Phase 2)
     /// `tasks` contains the full updated task list for progress rendering.
     fn on_task_progress(&mut self, _task_id: u32, _completed: bool, _tasks: &[Task]) {}
@@ -126,6 +189,11 @@ pub trait EventSink: Send {
     ) -> ClarificationResponse {
         ClarificationResponse::Stop
     }
+    /// Polled between agent loop iterations and by long-running async tools (e.g. `run_command`).
+    /// Returning true stops the turn at the next checkpoint; partial results are still returned.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
 }
 
 /// Silent event sink for background operations (e.g. pre-compaction memory flush).
@@ -163,6 +231,8 @@ pub struct TerminalEventSink {
     execution_section_shown: bool,
     /// Whether we've shown the "结果" section header this turn.
     result_section_shown: bool,
+    /// Stream assistant text to stderr, keeping stdout for a machine-readable result.
+    text_to_stderr: bool,
 }
 
 impl TerminalEventSink {
@@ -172,6 +242,25 @@ impl TerminalEventSink {
             streamed_text: false,
             execution_section_shown: false,
             result_section_shown: false,
+            text_to_stderr: false,
+        }
+    }
+
+    /// Send assistant text to stderr instead of stdout (`--output json`).
+    pub fn text_to_stderr(mut self) -> Self {
+        self.text_to_stderr = true;
+        self
+    }
+
+    /// Write assistant text to stdout (or stderr, see [`Self::text_to_stderr`]).
+    fn write_text(&self, text: &str) {
+        use std::io::Write;
+        if self.text_to_stderr {
+            eprint!("{}", text);
+            let _ = std::io::stderr().flush();
+        } else {
+            print!("{}", text);
+            let _ = std::io::stdout().flush();
         }
     }
 
@@ -233,10 +322,8 @@ impl EventSink for TerminalEventSink {
         if !text.trim().is_empty() {
             self.show_result_section();
         }
-        use std::io::Write;
-        print!("{}", text);
-        let _ = std::io::stdout().flush();
-        println!();
+        self.write_text(text);
+        self.write_text("\n");
     }
 
     fn on_text_chunk(&mut self, chunk: &str) {
@@ -245,9 +332,7 @@ impl EventSink for TerminalEventSink {
         if !chunk.trim().is_empty() {
             self.show_result_section();
         }
-        use std::io::Write;
-        print!("{}", chunk);
-        let _ = std::io::stdout().flush();
+        self.write_text(chunk);
     }
 
     fn on_tool_call(&mut self, name: &str, arguments: &str) {
@@ -370,6 +455,75 @@ impl EventSink for TerminalEventSink {
         self.msg(&format!("  ✗ swarm failed: {}", brief));
     }
 
+    fn on_skills_reloaded(&mut self, summary: &SkillReloadSummary) {
+        if summary.is_empty() {
+            self.msg("🔄 Skills reloaded: no changes.");
+        } else {
+            self.msg(&format!("🔄 Skills reloaded: {}", summary.describe()));
+        }
+    }
+
+    fn on_model_fallback(&mut self, from: &str, to: &str, reason: &str) {
+        self.msg(&format!("⚠ Switching model {} → {} ({})", from, to, reason));
+    }
+
+    fn on_tool_loop(&mut self, tool_name: &str, repeats: usize, stopped: bool) {
+        if stopped {
+            self.msg(&format!(
+                "⛔ {} called {} times with identical arguments; stopping",
+                tool_name, repeats
+            ));
+        } else {
+            self.msg(&format!(
+                "⚠ {} called {} times with identical arguments; asking the model to change approach",
+                tool_name, repeats
+            ));
+        }
+    }
+
+    fn on_status(&mut self, message: &str) {
+        if self.streamed_text {
+            // Partial streamed text has no trailing newline yet.
+            self.write_text("\n");
+        }
+        self.msg(&format!("⏳ {}", message));
+    }
+
+    fn on_budget_exceeded(&mut self, info: &BudgetExceeded) {
+        self.msg(&format!(
+            "💰 Budget exceeded: {} — stopping",
+            info.describe()
+        ));
+    }
+
+    fn on_memories_remembered(&mut self, facts: &[String]) {
+        let noun = if facts.len() == 1 { "thing" } else { "things" };
+        self.msg(&format!("📝 remembered {} {}", facts.len(), noun));
+        if self.verbose {
+            for fact in facts {
+                self.msg(&format!("   • {}", fact));
+            }
+        }
+    }
+
+    fn on_evolution_completed(&mut self, _txn_id: &str, changes: &[String]) {
+        self.msg("🧬 空闲期间完成了一轮后台进化:");
+        for change in changes {
+            self.msg(&format!("   {}", change));
+        }
+    }
+
+    fn on_replan(&mut self, _old_tasks: &[Task], _new_tasks: &[Task], reason: &str) {
+        self.msg(&format!("🔄 Replanned: {}", reason));
+    }
+
+    fn on_system_prompt_refreshed(&mut self, reasons: &[String]) {
+        self.msg(&format!(
+            "🔄 System prompt refreshed: {}",
+            reasons.join(", ")
+        ));
+    }
+
     fn on_confirmation_request(&mut self, request: &ConfirmationRequest) -> bool {
         use std::io::Write;
         self.msg_opt(&request.prompt);
@@ -436,6 +590,43 @@ impl EventSink for TerminalEventSink {
         }
     }
 
+    fn on_plan_proposed(&mut self, tasks: &[Task], can_regenerate: bool) -> PlanApproval {
+        use std::io::Write;
+        let mut tasks = tasks.to_vec();
+        self.msg(&format!("─── 📋 待审批计划 ─── {}", SECTION_SEP));
+        loop {
+            for (i, task) in tasks.iter().enumerate() {
+                self.msg(&format!("   {}. {}", i + 1, task.description));
+            }
+            self.msg(&format!("  {}", PLAN_COMMAND_HELP));
+            eprint!("计划审批> ");
+            let _ = std::io::stderr().flush();
+            let mut input = String::new();
+            match std::io::stdin().read_line(&mut input) {
+                Ok(0) | Err(_) => return PlanApproval::Unavailable,
+                Ok(_) => {}
+            }
+            match parse_plan_command(&input) {
+                Ok(PlanCommand::Approve) if tasks.is_empty() => {
+                    self.msg("计划为空：请先 add 任务，或 reject");
+                }
+                Ok(PlanCommand::Approve) => return PlanApproval::Approve(tasks),
+                Ok(PlanCommand::Reject(feedback)) => {
+                    if !can_regenerate {
+                        self.msg("已重新生成过一次，再次拒绝将停止本轮");
+                    }
+                    return PlanApproval::Reject(feedback);
+                }
+                Ok(cmd) => {
+                    if let Err(e) = cmd.apply(&mut tasks) {
+                        self.msg(&format!("  ✗ {}", e));
+                    }
+                }
+                Err(e) => self.msg(&format!("  ✗ {}", e)),
+            }
+        }
+    }
+
     fn on_task_progress(&mut self, task_id: u32, completed: bool, tasks: &[Task]) {
         if completed {
             self.msg(&format!("  ✅ Task {} completed", task_id));
@@ -496,6 +687,12 @@ impl RunModeEventSink {
             inner: TerminalEventSink::new(verbose),
         }
     }
+
+    /// See [`TerminalEventSink::text_to_stderr`].
+    pub fn text_to_stderr(mut self) -> Self {
+        self.inner = self.inner.text_to_stderr();
+        self
+    }
 }
 
 impl EventSink for RunModeEventSink {
@@ -568,6 +765,9 @@ impl EventSink for RunModeEventSink {
     fn on_swarm_failed(&mut self, message: &str) {
         self.inner.on_swarm_failed(message);
     }
+    fn on_status(&mut self, message: &str) {
+        self.inner.on_status(message);
+    }
     fn on_confirmation_request(&mut self, request: &ConfirmationRequest) -> bool {
         if !request.prompt.is_empty() {
             for line in request.prompt.lines() {
@@ -590,6 +790,25 @@ impl EventSink for RunModeEventSink {
     fn on_task_plan(&mut self, tasks: &[Task]) {
         self.inner.on_task_plan(tasks);
     }
+    fn on_replan(&mut self, old_tasks: &[Task], new_tasks: &[Task], reason: &str) {
+        self.inner.on_replan(old_tasks, new_tasks, reason);
+    }
+    fn on_tool_loop(&mut self, tool_name: &str, repeats: usize, stopped: bool) {
+        self.inner.on_tool_loop(tool_name, repeats, stopped);
+    }
+    fn on_system_prompt_refreshed(&mut self, reasons: &[String]) {
+        self.inner.on_system_prompt_refreshed(reasons);
+    }
+    fn on_memories_remembered(&mut self, facts: &[String]) {
+        self.inner.on_memories_remembered(facts);
+    }
+    fn on_evolution_completed(&mut self, txn_id: &str, changes: &[String]) {
+        self.inner.on_evolution_completed(txn_id, changes);
+    }
+    fn on_budget_exceeded(&mut self, info: &BudgetExceeded) {
+        self.inner.on_budget_exceeded(info);
+        eprintln!("  Checkpoint saved. Continue with: skilllite run --resume");
+    }
     fn on_task_progress(&mut self, task_id: u32, completed: bool, tasks: &[Task]) {
         self.inner.on_task_progress(task_id, completed, tasks);
     }
@@ -42,17 +42,91 @@ pub struct ExecutionFeedback {
     pub task_description: Option<String>,
     /// Names of planning rules that were matched for this task.
     pub rules_used: Vec<String>,
+    /// Scoped planning rules whose scope held for this task (matched or not).
+    pub rules_in_scope: Vec<String>,
     /// Per-tool execution details.
     pub tools_detail: Vec<ToolExecDetail>,
     /// Sum of API-reported token usage across LLM calls in this run (when provided by the provider).
     pub llm_usage: LlmUsageTotals,
+    /// Configured (primary) model for this run.
+    pub model: String,
+    /// Fallback model the run switched to, when it did.
+    pub fallback_model: Option<String>,
+    /// Reason of each accepted replan, in order.
+    pub replan_reasons: Vec<String>,
+    /// Secrets redacted from tool results during this run.
+    pub secrets_redacted: usize,
+    /// Runs of identical tool calls that reached `AgentConfig::tool_loop_threshold`.
+    pub tool_loops: usize,
 }
 
 /// Per-tool execution outcome.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ToolExecDetail {
     pub tool: String,
     pub success: bool,
+    /// Skill that provides the tool, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skill: Option<String>,
+    #[serde(default)]
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub result_bytes: usize,
+    /// `kind` of the structured sandbox error in the result, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<String>,
+    /// Sorted names of the arguments that were set (never their values).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args_present: Vec<String>,
+}
+
+impl ToolExecDetail {
+    /// Build the record for one finished call from its raw JSON `arguments` and result `content`.
+    pub fn from_call(
+        tool: &str,
+        skill: Option<&str>,
+        arguments: &str,
+        content: &str,
+        success: bool,
+        duration_ms: u64,
+    ) -> Self {
+        let mut args_present: Vec<String> = serde_json::from_str::<serde_json::Value>(arguments)
+            .ok()
+            .and_then(|v| match v {
+                serde_json::Value::Object(map) => Some(
+                    map.into_iter()
+                        .filter(|(_, v)| !v.is_null())
+                        .map(|(k, _)| k)
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .unwrap_or_default();
+        args_present.sort();
+        Self {
+            tool: tool.to_string(),
+            success,
+            skill: skill.map(str::to_string),
+            duration_ms,
+            result_bytes: content.len(),
+            error_kind: if success {
+                None
+            } else {
+                sandbox_error_kind(content)
+            },
+            args_present,
+        }
+    }
+}
+
+/// `kind` of the structured sandbox error appended to failed skill results as
+/// `error: {"kind": ..., "details": ...}`.
+fn sandbox_error_kind(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let json = line.trim().strip_prefix("error: ")?;
+        let value: serde_json::Value = serde_json::from_str(json).ok()?;
+        value.get("kind")?.as_str().map(str::to_string)
+    })
 }
 
 /// Structured prompt payload for asking the user whether a difficult run should
@@ -173,48 +247,6 @@ impl FeedbackSignal {
     }
 }
 
-/// Classify user feedback from the next message (simple keyword matching).
-/// ~80% accuracy is sufficient; evolution is gradual and tolerates noise.
-pub fn classify_user_feedback(next_user_message: &str) -> FeedbackSignal {
-    let msg = next_user_message.to_lowercase();
-    let negative = [
-        "不对",
-        "错了",
-        "重来",
-        "重新",
-        "wrong",
-        "redo",
-        "fix",
-        "不是这样",
-        "不行",
-        "有问题",
-        "bug",
-        "失败",
-    ];
-    let positive = [
-        "好的",
-        "谢谢",
-        "完美",
-        "不错",
-        "thanks",
-        "great",
-        "perfect",
-        "可以",
-        "没问题",
-        "很好",
-        "nice",
-        "done",
-        "ok",
-    ];
-    if negative.iter().any(|k| msg.contains(k)) {
-        FeedbackSignal::ExplicitNegative
-    } else if positive.iter().any(|k| msg.contains(k)) {
-        FeedbackSignal::ExplicitPositive
-    } else {
-        FeedbackSignal::Neutral
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -228,6 +260,7 @@ mod tests {
             tools_detail: vec![ToolExecDetail {
                 tool: "run_command".to_string(),
                 success: false,
+                ..Default::default()
             }],
             ..ExecutionFeedback::default()
         };
@@ -255,10 +288,12 @@ mod tests {
                 ToolExecDetail {
                     tool: "read_file".to_string(),
                     success: false,
+                    ..Default::default()
                 },
                 ToolExecDetail {
                     tool: "read_file".to_string(),
                     success: false,
+                    ..Default::default()
                 },
             ],
             ..ExecutionFeedback::default()
@@ -281,12 +316,38 @@ mod tests {
             tools_detail: vec![ToolExecDetail {
                 tool: "read_file".to_string(),
                 success: true,
+                ..Default::default()
             }],
             ..ExecutionFeedback::default()
         };
 
         assert!(build_wiki_update_suggestion(&feedback, Vec::new()).is_none());
     }
+
+    #[test]
+    fn tool_detail_records_arg_shape_and_sandbox_error_kind() {
+        let content =
+            "Skill failed\n\nerror: {\"kind\":\"timeout\",\"details\":{\"secs\":30}}\nHint: retry";
+        let detail = ToolExecDetail::from_call(
+            "grep_files",
+            Some("search"),
+            r#"{"pattern": "secret-value", "path": ".", "include": null}"#,
+            content,
+            false,
+            42,
+        );
+        assert_eq!(detail.args_present, vec!["path", "pattern"]);
+        assert_eq!(detail.error_kind.as_deref(), Some("timeout"));
+        assert_eq!(detail.skill.as_deref(), Some("search"));
+        assert_eq!(detail.result_bytes, content.len());
+        assert!(!serde_json::to_string(&detail)
+            .unwrap()
+            .contains("secret-value"));
+
+        let ok = ToolExecDetail::from_call("read_file", None, "not json", "ok", true, 1);
+        assert!(ok.args_present.is_empty());
+        assert_eq!(ok.error_kind, None);
+    }
 }
 
 /// Action type for skill evolution (generate new or refine existing).
@@ -47,6 +47,21 @@ impl LlmUsageTotals {
             }
         }
     }
+
+    /// Add another run's totals (e.g. earlier invocations of a resumed run).
+    pub fn add(&mut self, other: &Self) {
+        self.prompt_tokens = self.prompt_tokens.saturating_add(other.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(other.completion_tokens);
+        self.total_tokens = self.total_tokens.saturating_add(other.total_tokens);
+        self.responses_with_usage = self
+            .responses_with_usage
+            .saturating_add(other.responses_with_usage);
+        self.responses_without_usage = self
+            .responses_without_usage
+            .saturating_add(other.responses_without_usage);
+    }
 }
 
 #[cfg(test)]
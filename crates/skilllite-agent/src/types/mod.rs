@@ -7,8 +7,11 @@
 //! - `feedback`: Execution feedback (EVO-1)
 //! - `event_sink`: Event sink trait and implementations
 //! - `task`: Task planning types
+//! - `plan_approval`: Plan-approval review commands (`--approve-plan`)
+//! - `budget`: Run spend limits and model prices
 //! - `env_config`: Environment config helpers
 
+mod budget;
 mod chat;
 mod config;
 mod env_config;
@@ -16,18 +19,22 @@ mod event_sink;
 mod feedback;
 mod llm_usage;
 mod mcp_servers;
+mod plan_approval;
 mod string_utils;
 mod task;
 
 // Re-export all public types for backward compatibility.
+pub use budget::{model_price, parse_model_prices_json, BudgetExceeded, BudgetLimit, ModelPrice};
 pub use chat::{
-    parse_claude_tool_calls, AgentResult, ChatMessage, FunctionCall, FunctionDef, ToolCall,
-    ToolDefinition, ToolFormat, ToolResult, UserImageAttachment,
+    parse_claude_tool_calls, AgentResult, Artifact, ArtifactRoot, ChatMessage, FunctionCall,
+    FunctionDef, ToolCall, ToolDefinition, ToolFormat, ToolResult, UserFileAttachment,
+    UserImageAttachment,
 };
 pub use config::AgentConfig;
 pub use env_config::{
-    get_chunk_size, get_compact_planning, get_compaction_keep_recent, get_compaction_threshold,
-    get_context_soft_limit_chars, get_extract_top_k, get_head_chunks, get_long_text_strategy,
+    get_attachment_max_bytes, get_attachment_preview_chars, get_chunk_size, get_compact_planning,
+    get_compaction_keep_recent, get_compaction_threshold, get_context_soft_limit_chars,
+    get_context_soft_limit_tokens, get_extract_top_k, get_head_chunks, get_long_text_strategy,
     get_map_model, get_max_output_chars, get_max_tokens, get_memory_flush_enabled,
     get_memory_flush_threshold, get_output_dir, get_read_file_tool_result_max_chars,
     get_summarize_threshold, get_tail_chunks, get_tool_result_max_chars,
@@ -35,14 +42,17 @@ pub use env_config::{
 };
 pub use event_sink::{
     ClarificationRequest, ClarificationResponse, ConfirmationRequest, EventSink, RiskTier,
-    RunModeEventSink, SilentEventSink, TerminalEventSink,
+    RunModeEventSink, SilentEventSink, SkillReloadSummary, TerminalEventSink,
 };
 pub use feedback::{
-    build_wiki_update_suggestion, classify_user_feedback, ExecutionFeedback, FeedbackSignal,
-    SkillAction, TaskCompletionType, ToolExecDetail, WikiUpdateSuggestion, WikiUpdateTrigger,
+    build_wiki_update_suggestion, ExecutionFeedback, FeedbackSignal, SkillAction,
+    TaskCompletionType, ToolExecDetail, WikiUpdateSuggestion, WikiUpdateTrigger,
     WIKI_CONSECUTIVE_TOOL_FAILURE_THRESHOLD,
 };
 pub use llm_usage::{LlmUsageReport, LlmUsageTotals};
 pub use mcp_servers::{parse_mcp_servers_json, McpServerEntry};
+pub use plan_approval::{
+    parse_plan_command, tasks_from_json, PlanApproval, PlanCommand, PLAN_COMMAND_HELP,
+};
 pub use string_utils::{chunk_str, safe_slice_from, safe_truncate};
-pub use task::{PlanningRule, SourceEntry, SourceRegistry, Task};
+pub use task::{PlanningRule, RuleScope, SourceEntry, SourceRegistry, Task};
@@ -0,0 +1,193 @@
+//! Plan-approval mode (`--approve-plan`): review commands applied to a proposed task list
+//! before execution starts.
+
+use serde_json::Value;
+
+use super::task::Task;
+
+/// Answer to [`super::EventSink::on_plan_proposed`].
+#[derive(Debug, Clone)]
+pub enum PlanApproval {
+    /// Execute these tasks (the proposed plan, possibly edited).
+    Approve(Vec<Task>),
+    /// Regenerate the plan once, with this feedback for the planner.
+    Reject(String),
+    /// No approval channel (non-interactive sink, closed stdin, cancelled RPC wait).
+    Unavailable,
+}
+
+/// One REPL review command; task numbers are 1-based positions in the shown plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanCommand {
+    Approve,
+    Edit(usize, String),
+    Remove(usize),
+    Add(String),
+    Reject(String),
+}
+
+/// Usage line shown by the REPL review prompt and in parse errors.
+pub const PLAN_COMMAND_HELP: &str =
+    "approve | edit N <new text> | remove N | add <text> | reject <feedback>";
+
+/// Parse `approve`, `edit N <text>`, `remove N`, `add <text>` or `reject <feedback>`.
+pub fn parse_plan_command(input: &str) -> Result<PlanCommand, String> {
+    let input = input.trim();
+    let (verb, rest) = match input.split_once(char::is_whitespace) {
+        Some((verb, rest)) => (verb, rest.trim()),
+        None => (input, ""),
+    };
+    let number = |s: &str| {
+        s.parse::<usize>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| format!("expected a task number, got {:?}", s))
+    };
+    match verb.to_lowercase().as_str() {
+        "approve" | "a" | "y" | "yes" if rest.is_empty() => Ok(PlanCommand::Approve),
+        "edit" => {
+            let (n, text) = rest
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| "usage: edit N <new text>".to_string())?;
+            Ok(PlanCommand::Edit(number(n)?, text.trim().to_string()))
+        }
+        "remove" | "rm" => Ok(PlanCommand::Remove(number(rest)?)),
+        "add" if !rest.is_empty() => Ok(PlanCommand::Add(rest.to_string())),
+        "add" => Err("usage: add <text>".to_string()),
+        "reject" => Ok(PlanCommand::Reject(rest.to_string())),
+        _ => Err(format!("unknown command; use {}", PLAN_COMMAND_HELP)),
+    }
+}
+
+impl PlanCommand {
+    /// Apply an edit / remove / add to `tasks` and renumber them 1..n. Approve and reject
+    /// leave the list untouched.
+    pub fn apply(&self, tasks: &mut Vec<Task>) -> Result<(), String> {
+        let check = |n: usize, len: usize| {
+            if n > len {
+                Err(format!("no task {} (the plan has {})", n, len))
+            } else {
+                Ok(n - 1)
+            }
+        };
+        match self {
+            PlanCommand::Edit(n, text) => {
+                let i = check(*n, tasks.len())?;
+                tasks[i].description = text.clone();
+            }
+            PlanCommand::Remove(n) => {
+                let i = check(*n, tasks.len())?;
+                tasks.remove(i);
+            }
+            PlanCommand::Add(text) => tasks.push(Task {
+                id: 0,
+                description: text.clone(),
+                tool_hint: None,
+                completed: false,
+            }),
+            PlanCommand::Approve | PlanCommand::Reject(_) => return Ok(()),
+        }
+        for (i, task) in tasks.iter_mut().enumerate() {
+            task.id = i as u32 + 1;
+        }
+        Ok(())
+    }
+}
+
+/// Edited plan sent by an RPC client: each entry is a description string or an object with
+/// `description` and optional `tool_hint`.
+pub fn tasks_from_json(value: &Value) -> Result<Vec<Task>, String> {
+    let items = value
+        .as_array()
+        .ok_or_else(|| "'tasks' must be an array".to_string())?;
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let (description, tool_hint) = match item {
+                Value::String(s) => (s.as_str(), None),
+                _ => (
+                    item.get("description")
+                        .and_then(|d| d.as_str())
+                        .unwrap_or_default(),
+                    item.get("tool_hint").and_then(|h| h.as_str()),
+                ),
+            };
+            if description.trim().is_empty() {
+                return Err(format!("task {} has no description", i + 1));
+            }
+            Ok(Task {
+                id: i as u32 + 1,
+                description: description.trim().to_string(),
+                tool_hint: tool_hint.map(str::to_string),
+                completed: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(descriptions: &[&str]) -> Vec<Task> {
+        descriptions
+            .iter()
+            .enumerate()
+            .map(|(i, d)| Task {
+                id: i as u32 + 1,
+                description: d.to_string(),
+                tool_hint: Some("file_operation".to_string()),
+                completed: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn review_commands_edit_remove_and_add_tasks() {
+        let mut tasks = plan(&["read config", "write report", "send email"]);
+        for line in [
+            "edit 2 write a short report",
+            "remove 3",
+            "add  archive logs ",
+        ] {
+            parse_plan_command(line).unwrap().apply(&mut tasks).unwrap();
+        }
+        let got: Vec<(u32, &str)> = tasks
+            .iter()
+            .map(|t| (t.id, t.description.as_str()))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                (1, "read config"),
+                (2, "write a short report"),
+                (3, "archive logs")
+            ]
+        );
+        assert_eq!(tasks[1].tool_hint.as_deref(), Some("file_operation"));
+
+        assert_eq!(parse_plan_command("APPROVE"), Ok(PlanCommand::Approve));
+        assert_eq!(
+            parse_plan_command("reject use the CSV instead"),
+            Ok(PlanCommand::Reject("use the CSV instead".to_string()))
+        );
+        assert!(parse_plan_command("edit two x").is_err());
+        assert!(parse_plan_command("frobnicate").is_err());
+        assert!(PlanCommand::Remove(9).apply(&mut tasks).is_err());
+        assert_eq!(tasks.len(), 3);
+    }
+
+    #[test]
+    fn rpc_tasks_accept_strings_and_objects() {
+        let tasks = tasks_from_json(&serde_json::json!([
+            "read config",
+            {"description": "run tests", "tool_hint": "run_command"}
+        ]))
+        .unwrap();
+        assert_eq!(tasks[1].id, 2);
+        assert_eq!(tasks[1].tool_hint.as_deref(), Some("run_command"));
+        assert!(tasks_from_json(&serde_json::json!([{"tool_hint": "x"}])).is_err());
+        assert!(tasks_from_json(&serde_json::json!("read config")).is_err());
+    }
+}
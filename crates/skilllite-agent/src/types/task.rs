@@ -14,4 +14,4 @@ pub struct Task {
 }
 
 // Re-export planning types from skilllite-core for backward compatibility.
-pub use skilllite_core::planning::{PlanningRule, SourceEntry, SourceRegistry};
+pub use skilllite_core::planning::{PlanningRule, RuleScope, SourceEntry, SourceRegistry};
@@ -1,4 +1,4 @@
-//! 聊天流、停止、确认与澄清通道。
+//! 聊天流、停止、确认、澄清与计划审批通道。
 
 use tauri::Manager;
 
@@ -12,12 +12,15 @@ pub async fn skilllite_chat_stream(
     session_key: Option<String>,
     config: Option<crate::skilllite_bridge::ChatConfigOverrides>,
     images: Option<Vec<crate::skilllite_bridge::ChatImageAttachment>>,
+    attachments: Option<Vec<String>>,
     conf_state: tauri::State<'_, crate::skilllite_bridge::ConfirmationState>,
     clar_state: tauri::State<'_, crate::skilllite_bridge::ClarificationState>,
+    plan_state: tauri::State<'_, crate::skilllite_bridge::PlanApprovalState>,
     process_state: tauri::State<'_, crate::skilllite_bridge::ChatProcessState>,
 ) -> Result<(), String> {
     let conf = (*conf_state).clone();
     let clar = (*clar_state).clone();
+    let plan = (*plan_state).clone();
     let proc = (*process_state).clone();
     tauri::async_runtime::spawn_blocking(move || {
         crate::skilllite_bridge::chat_stream(
@@ -27,8 +30,10 @@ pub async fn skilllite_chat_stream(
             config,
             session_key,
             images,
+            attachments,
             conf,
             clar,
+            plan,
             proc,
         )
     })
@@ -36,25 +41,32 @@ pub async fn skilllite_chat_stream(
     .map_err(|e| e.to_string())?
 }
 
+/// Stop the stream of `session_key`; without a key every running stream is stopped.
 #[tauri::command]
 pub fn skilllite_stop(
+    session_key: Option<String>,
     process_state: tauri::State<'_, crate::skilllite_bridge::ChatProcessState>,
     conf_state: tauri::State<'_, crate::skilllite_bridge::ConfirmationState>,
     clar_state: tauri::State<'_, crate::skilllite_bridge::ClarificationState>,
+    plan_state: tauri::State<'_, crate::skilllite_bridge::PlanApprovalState>,
 ) -> Result<(), String> {
-    crate::skilllite_bridge::stop_chat(&process_state, &conf_state, &clar_state)
+    crate::skilllite_bridge::stop_chat(
+        &process_state,
+        &conf_state,
+        &clar_state,
+        &plan_state,
+        session_key.as_deref(),
+    )
 }
 
 #[tauri::command]
-pub fn skilllite_confirm(app: tauri::AppHandle, approved: bool) -> Result<(), String> {
+pub fn skilllite_confirm(
+    app: tauri::AppHandle,
+    approved: bool,
+    session_key: Option<String>,
+) -> Result<(), String> {
     let state = app.state::<crate::skilllite_bridge::ConfirmationState>();
-    let mut guard = state
-        .0
-        .lock()
-        .map_err(|_| "ConfirmationState lock poisoned")?;
-    if let Some(tx) = guard.take() {
-        let _ = tx.send(approved);
-    }
+    state.0.send(session_key.as_deref(), approved)?;
     Ok(())
 }
 
@@ -63,14 +75,33 @@ pub fn skilllite_clarify(
     app: tauri::AppHandle,
     action: String,
     hint: Option<String>,
+    session_key: Option<String>,
 ) -> Result<(), String> {
     let state = app.state::<crate::skilllite_bridge::ClarificationState>();
-    let mut guard = state
-        .0
-        .lock()
-        .map_err(|_| "ClarificationState lock poisoned")?;
-    if let Some(tx) = guard.take() {
-        let _ = tx.send(crate::skilllite_bridge::ClarifyResponse { action, hint });
-    }
+    state.0.send(
+        session_key.as_deref(),
+        crate::skilllite_bridge::ClarifyResponse { action, hint },
+    )?;
+    Ok(())
+}
+
+/// Answer `plan_proposed`: `approve` (optional edited `tasks`) or `reject` with `feedback`.
+#[tauri::command]
+pub fn skilllite_approve_plan(
+    app: tauri::AppHandle,
+    action: String,
+    tasks: Option<Vec<serde_json::Value>>,
+    feedback: Option<String>,
+    session_key: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<crate::skilllite_bridge::PlanApprovalState>();
+    state.0.send(
+        session_key.as_deref(),
+        crate::skilllite_bridge::PlanApprovalResponse {
+            action,
+            tasks,
+            feedback,
+        },
+    )?;
     Ok(())
 }
@@ -117,6 +117,29 @@ pub async fn skilllite_read_output_file_base64(
     }
 }
 
+#[tauri::command]
+pub async fn skilllite_preview_output(
+    app: tauri::AppHandle,
+    relative_path: String,
+    format_hint: Option<String>,
+    workspace: Option<String>,
+) -> Result<crate::skilllite_bridge::OutputPreview, String> {
+    let skilllite_path = crate::skilllite_bridge::resolve_skilllite_path_app(&app);
+    match tauri::async_runtime::spawn_blocking(move || {
+        crate::skilllite_bridge::preview_output(
+            &relative_path,
+            format_hint.as_deref(),
+            workspace.as_deref(),
+            &skilllite_path,
+        )
+    })
+    .await
+    {
+        Ok(inner) => inner,
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 #[tauri::command]
 pub async fn skilllite_open_directory(
     module: String,
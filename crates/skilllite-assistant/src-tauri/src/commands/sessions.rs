@@ -1,36 +1,58 @@
 //! 会话与 memory 摘要。
 
 #[tauri::command]
-pub async fn skilllite_list_sessions() -> Vec<crate::skilllite_bridge::SessionInfo> {
-    tauri::async_runtime::spawn_blocking(crate::skilllite_bridge::list_sessions)
+pub async fn skilllite_list_sessions(
+    app: tauri::AppHandle,
+    workspace: Option<String>,
+) -> Result<Vec<crate::skilllite_bridge::SessionInfo>, String> {
+    let ws = workspace.unwrap_or_else(|| ".".to_string());
+    let path = crate::skilllite_bridge::resolve_skilllite_path_app(&app);
+    tauri::async_runtime::spawn_blocking(move || crate::skilllite_bridge::list_sessions(&path, &ws))
         .await
-        .unwrap_or_default()
+        .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
 pub async fn skilllite_create_session(
+    app: tauri::AppHandle,
     display_name: String,
+    workspace: Option<String>,
 ) -> Result<crate::skilllite_bridge::SessionInfo, String> {
+    let ws = workspace.unwrap_or_else(|| ".".to_string());
+    let path = crate::skilllite_bridge::resolve_skilllite_path_app(&app);
     tauri::async_runtime::spawn_blocking(move || {
-        crate::skilllite_bridge::create_session(&display_name)
+        crate::skilllite_bridge::create_session(&display_name, &path, &ws)
     })
     .await
     .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
-pub async fn skilllite_rename_session(session_key: String, new_name: String) -> Result<(), String> {
+pub async fn skilllite_rename_session(
+    app: tauri::AppHandle,
+    session_key: String,
+    new_name: String,
+    workspace: Option<String>,
+) -> Result<(), String> {
+    let ws = workspace.unwrap_or_else(|| ".".to_string());
+    let path = crate::skilllite_bridge::resolve_skilllite_path_app(&app);
     tauri::async_runtime::spawn_blocking(move || {
-        crate::skilllite_bridge::rename_session(&session_key, &new_name)
+        crate::skilllite_bridge::rename_session(&session_key, &new_name, &path, &ws)
     })
     .await
     .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
-pub async fn skilllite_delete_session(session_key: String) -> Result<(), String> {
+pub async fn skilllite_delete_session(
+    app: tauri::AppHandle,
+    session_key: String,
+    workspace: Option<String>,
+) -> Result<(), String> {
+    let ws = workspace.unwrap_or_else(|| ".".to_string());
+    let path = crate::skilllite_bridge::resolve_skilllite_path_app(&app);
     tauri::async_runtime::spawn_blocking(move || {
-        crate::skilllite_bridge::delete_session(&session_key)
+        crate::skilllite_bridge::delete_session(&session_key, &path, &ws)
     })
     .await
     .map_err(|e| e.to_string())?
@@ -1,4 +1,4 @@
-//! 工作区内文本文件的读写与枚举。
+//! 工作区内文本文件的读写与枚举；会话内 agent 改动的 diff 审阅与回滚。
 
 #[tauri::command]
 pub async fn skilllite_write_workspace_file(
@@ -54,3 +54,37 @@ pub async fn skilllite_list_workspace_entries(
     .await
     .map_err(|e| e.to_string())?
 }
+
+#[tauri::command]
+pub async fn skilllite_workspace_changes(
+    app: tauri::AppHandle,
+    since_ts: u64,
+    session_key: Option<String>,
+    workspace: Option<String>,
+) -> Result<Vec<crate::skilllite_bridge::local::engine_types::FileChange>, String> {
+    let key = session_key.unwrap_or_else(|| "default".to_string());
+    let ws = workspace.unwrap_or_else(|| ".".to_string());
+    let path = crate::skilllite_bridge::resolve_skilllite_path_app(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::skilllite_bridge::workspace_changes(&path, &ws, &key, since_ts)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn skilllite_revert_file(
+    app: tauri::AppHandle,
+    path: String,
+    session_key: Option<String>,
+    workspace: Option<String>,
+) -> Result<crate::skilllite_bridge::local::engine_types::RevertFileResult, String> {
+    let key = session_key.unwrap_or_else(|| "default".to_string());
+    let ws = workspace.unwrap_or_else(|| ".".to_string());
+    let bin = crate::skilllite_bridge::resolve_skilllite_path_app(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::skilllite_bridge::revert_file(&bin, &ws, &key, &path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -28,12 +28,14 @@ pub fn run() {
             commands::files_and_dirs::skilllite_read_log_file,
             commands::files_and_dirs::skilllite_read_output_file,
             commands::files_and_dirs::skilllite_read_output_file_base64,
+            commands::files_and_dirs::skilllite_preview_output,
             commands::files_and_dirs::skilllite_read_local_image_b64,
             commands::files_and_dirs::skilllite_open_directory,
             commands::files_and_dirs::skilllite_reveal_in_file_manager,
             commands::files_and_dirs::skilllite_open_skill_directory,
             commands::chat::skilllite_confirm,
             commands::chat::skilllite_clarify,
+            commands::chat::skilllite_approve_plan,
             commands::skills_workspace::skilllite_list_skills,
             commands::skills_workspace::skilllite_repair_skills,
             commands::skills_workspace::skilllite_add_skill,
@@ -56,6 +58,8 @@ pub fn run() {
             commands::workspace_editor::skilllite_read_workspace_file,
             commands::workspace_editor::skilllite_resolve_workspace_file_path,
             commands::workspace_editor::skilllite_list_workspace_entries,
+            commands::workspace_editor::skilllite_workspace_changes,
+            commands::workspace_editor::skilllite_revert_file,
             commands::evolution::skilllite_load_evolution_status,
             commands::evolution::skilllite_list_evolution_pending,
             commands::evolution::skilllite_read_pending_skill_md,
@@ -86,6 +90,7 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .manage(skilllite_bridge::ConfirmationState::default())
         .manage(skilllite_bridge::ClarificationState::default())
+        .manage(skilllite_bridge::PlanApprovalState::default())
         .manage(skilllite_bridge::ChatProcessState::default())
         .manage(gateway_manager::GatewayProcessState::default())
         .manage(life_pulse::LifePulseState::default())
@@ -2,11 +2,13 @@
 
 use serde::Serialize;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::thread;
 use tauri::{Emitter, Manager, Window};
 
@@ -20,9 +22,77 @@ use super::protocol::{
     MAX_TOTAL_INVALID_PROTOCOL_LINES,
 };
 
-/// Shared state for confirmation flow: frontend calls skilllite_confirm → sends to this channel.
+/// Pending reply channels keyed by session, so a window can only answer its own session's prompt.
+pub struct SessionReplies<T>(Arc<Mutex<HashMap<String, mpsc::Sender<T>>>>);
+
+impl<T> Default for SessionReplies<T> {
+    fn default() -> Self {
+        Self(Arc::default())
+    }
+}
+
+impl<T> Clone for SessionReplies<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> SessionReplies<T> {
+    /// Register the pending prompt of `session_key`, replacing any stale one.
+    pub fn open(&self, session_key: &str) -> Result<mpsc::Receiver<T>, String> {
+        let (tx, rx) = mpsc::channel();
+        self.0
+            .lock()
+            .map_err(|_| "SessionReplies lock poisoned")?
+            .insert(session_key.to_string(), tx);
+        Ok(rx)
+    }
+
+    /// Drop the pending prompt of `session_key`; its receiver sees a disconnect.
+    pub fn close(&self, session_key: &str) -> Result<(), String> {
+        self.0
+            .lock()
+            .map_err(|_| "SessionReplies lock poisoned")?
+            .remove(session_key);
+        Ok(())
+    }
+
+    /// Drop every pending prompt.
+    pub fn close_all(&self) -> Result<(), String> {
+        self.0
+            .lock()
+            .map_err(|_| "SessionReplies lock poisoned")?
+            .clear();
+        Ok(())
+    }
+
+    /// Answer the prompt of `session_key`. Without a key (older frontends) the reply goes to the
+    /// only pending prompt; with several pending that is ambiguous and fails. Returns whether a
+    /// prompt was waiting.
+    pub fn send(&self, session_key: Option<&str>, reply: T) -> Result<bool, String> {
+        let mut pending = self.0.lock().map_err(|_| "SessionReplies lock poisoned")?;
+        let key = match session_key {
+            Some(key) => key.to_string(),
+            None if pending.len() > 1 => {
+                return Err(format!(
+                    "{} sessions are waiting for a reply; session_key is required",
+                    pending.len()
+                ))
+            }
+            None => match pending.keys().next() {
+                Some(key) => key.clone(),
+                None => return Ok(false),
+            },
+        };
+        Ok(pending
+            .remove(&key)
+            .is_some_and(|tx| tx.send(reply).is_ok()))
+    }
+}
+
+/// Confirmation flow: frontend calls skilllite_confirm(session_key) → that session's channel.
 #[derive(Default, Clone)]
-pub struct ConfirmationState(pub Arc<Mutex<Option<mpsc::Sender<bool>>>>);
+pub struct ConfirmationState(pub SessionReplies<bool>);
 
 /// Response payload for clarification flow.
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -31,13 +101,201 @@ pub struct ClarifyResponse {
     pub hint: Option<String>,
 }
 
-/// Shared state for clarification flow: frontend calls skilllite_clarify → sends to this channel.
+/// Clarification flow: frontend calls skilllite_clarify(session_key) → that session's channel.
+#[derive(Default, Clone)]
+pub struct ClarificationState(pub SessionReplies<ClarifyResponse>);
+
+/// Response payload for plan approval (`plan_proposed`): `approve` (optionally with the edited
+/// task list) or `reject` with feedback for one regeneration.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct PlanApprovalResponse {
+    pub action: String,
+    pub tasks: Option<Vec<serde_json::Value>>,
+    pub feedback: Option<String>,
+}
+
+/// Plan-approval flow: frontend calls skilllite_approve_plan(session_key) → that session's channel.
 #[derive(Default, Clone)]
-pub struct ClarificationState(pub Arc<Mutex<Option<mpsc::Sender<ClarifyResponse>>>>);
+pub struct PlanApprovalState(pub SessionReplies<PlanApprovalResponse>);
+
+enum ChatProcess {
+    /// Registered but not spawned yet; a stop arriving now cancels the spawn.
+    Starting {
+        stop_requested: bool,
+    },
+    Running(Child),
+}
+
+/// One registered stream. `id` tells a stopped stream's late cleanup apart from a newer
+/// stream of the same session.
+struct ChatEntry {
+    id: u64,
+    process: ChatProcess,
+}
+
+static NEXT_STREAM_ID: AtomicU64 = AtomicU64::new(0);
 
-/// Shared state for the chat subprocess; skilllite_stop can kill it.
+/// `agent-rpc` subprocesses of the running chat streams, keyed by session; skilllite_stop kills
+/// one session's process without touching the others.
 #[derive(Default, Clone)]
-pub struct ChatProcessState(pub Arc<Mutex<Option<std::process::Child>>>);
+pub struct ChatProcessState(Arc<Mutex<HashMap<String, ChatEntry>>>);
+
+impl ChatProcessState {
+    /// Reserve `session_key` before spawning. Fails while a stream for it is already running.
+    pub fn register(&self, session_key: &str) -> Result<ChatProcessGuard, String> {
+        let mut procs = self.lock()?;
+        if procs.contains_key(session_key) {
+            return Err(format!(
+                "A chat is already running for session {}; stop it first",
+                session_key
+            ));
+        }
+        let id = NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed);
+        procs.insert(
+            session_key.to_string(),
+            ChatEntry {
+                id,
+                process: ChatProcess::Starting {
+                    stop_requested: false,
+                },
+            },
+        );
+        Ok(ChatProcessGuard {
+            state: self.clone(),
+            session_key: session_key.to_string(),
+            id,
+        })
+    }
+
+    /// Kill the process of `session_key` (or cancel its pending spawn). Returns whether the
+    /// session had a stream.
+    pub fn stop(&self, session_key: &str) -> Result<bool, String> {
+        let child = {
+            let mut procs = self.lock()?;
+            match procs.get_mut(session_key).map(|e| &mut e.process) {
+                None => return Ok(false),
+                Some(ChatProcess::Starting { stop_requested }) => {
+                    *stop_requested = true;
+                    return Ok(true);
+                }
+                Some(ChatProcess::Running(_)) => match procs.remove(session_key) {
+                    Some(ChatEntry {
+                        process: ChatProcess::Running(child),
+                        ..
+                    }) => child,
+                    _ => unreachable!("entry checked above"),
+                },
+            }
+        };
+        kill_and_reap(child);
+        Ok(true)
+    }
+
+    /// Stop every session's stream.
+    pub fn stop_all(&self) -> Result<(), String> {
+        let keys: Vec<String> = self.lock()?.keys().cloned().collect();
+        for key in keys {
+            self.stop(&key)?;
+        }
+        Ok(())
+    }
+
+    /// Sessions with a registered stream.
+    pub fn sessions(&self) -> Vec<String> {
+        self.lock()
+            .map(|procs| procs.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<String, ChatEntry>>, String> {
+        self.0
+            .lock()
+            .map_err(|_| "ChatProcessState lock poisoned".to_string())
+    }
+}
+
+/// Registration of one session's stream; dropping it unregisters and reaps the process.
+pub struct ChatProcessGuard {
+    state: ChatProcessState,
+    session_key: String,
+    id: u64,
+}
+
+impl ChatProcessGuard {
+    /// Hand the spawned child to the state. When a stop arrived while starting, the child is
+    /// killed and an error returned.
+    pub fn attach(&self, child: Child) -> Result<(), String> {
+        let mut procs = self.state.lock()?;
+        match procs.get_mut(&self.session_key) {
+            Some(entry)
+                if entry.id == self.id
+                    && matches!(
+                        entry.process,
+                        ChatProcess::Starting {
+                            stop_requested: false
+                        }
+                    ) =>
+            {
+                entry.process = ChatProcess::Running(child);
+                Ok(())
+            }
+            _ => {
+                drop(procs);
+                kill_and_reap(child);
+                Err("Chat was stopped before it started".to_string())
+            }
+        }
+    }
+}
+
+impl Drop for ChatProcessGuard {
+    fn drop(&mut self) {
+        let entry = match self.state.0.lock() {
+            Ok(mut procs)
+                if procs
+                    .get(&self.session_key)
+                    .is_some_and(|e| e.id == self.id) =>
+            {
+                procs.remove(&self.session_key)
+            }
+            _ => None,
+        };
+        // stdin is already closed, so agent-rpc exits on its own after `done`.
+        if let Some(ChatEntry {
+            process: ChatProcess::Running(mut child),
+            ..
+        }) = entry
+        {
+            let _ = child.wait();
+        }
+    }
+}
+
+/// SIGTERM first on Unix so agent-rpc can stop its background jobs (they run in their own
+/// process groups and would outlive a SIGKILL); force-kill if it is still alive after a grace
+/// period.
+fn kill_and_reap(mut child: Child) {
+    #[cfg(unix)]
+    {
+        let terminated = Command::new("kill")
+            .args(["-TERM", &child.id().to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success());
+        if terminated {
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(3);
+            while std::time::Instant::now() < deadline {
+                if matches!(child.try_wait(), Ok(Some(_))) {
+                    return;
+                }
+                thread::sleep(std::time::Duration::from_millis(50));
+            }
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
 
 /// Single image from the desktop UI (vision); sent to `agent_chat` as base64.
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
@@ -71,6 +329,8 @@ pub struct ChatConfigOverrides {
     pub ui_locale: Option<String>,
     /// When set, replaces `SKILLLITE_MCP_SERVERS_JSON` for the child (outbound MCP stdio servers).
     pub mcp_servers: Option<Vec<McpServerEntry>>,
+    /// Wait for plan approval (`plan_proposed` → skilllite_approve_plan) before executing.
+    pub approve_plan: Option<bool>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -173,7 +433,6 @@ fn apply_chat_overrides_env(
     m: &mut std::collections::HashMap<String, EnvValueWithSource>,
     cfg: &ChatConfigOverrides,
 ) {
-
     use super::local::env_keys::agent_loop as al_keys;
     use super::local::env_keys::mcp as mcp_keys;
     use super::local::env_keys::sandbox as sb_keys;
@@ -222,7 +481,12 @@ fn apply_chat_overrides_env(
     }
     if let Some(ref url) = cfg.swarm_url {
         if !url.is_empty() {
-            upsert_env(m, swarm_keys::SKILLLITE_SWARM_URL, url.clone(), "ui_override");
+            upsert_env(
+                m,
+                swarm_keys::SKILLLITE_SWARM_URL,
+                url.clone(),
+                "ui_override",
+            );
         }
     }
     if let Some(n) = cfg.max_iterations.filter(|&n| n > 0) {
@@ -270,7 +534,12 @@ fn apply_chat_overrides_env(
     if let Some(ref p) = cfg.evo_profile {
         let t = p.trim();
         if t == "demo" || t == "conservative" {
-            upsert_env(m, evo_keys::SKILLLITE_EVO_PROFILE, t.to_string(), "ui_override");
+            upsert_env(
+                m,
+                evo_keys::SKILLLITE_EVO_PROFILE,
+                t.to_string(),
+                "ui_override",
+            );
         }
     }
     if let Some(h) = cfg
@@ -289,19 +558,19 @@ fn apply_chat_overrides_env(
     if let Some(ref loc) = cfg.ui_locale {
         let t = loc.trim();
         if t == "en" || t == "zh" {
-            upsert_env(m, agent_keys::SKILLLITE_UI_LOCALE, t.to_string(), "ui_override");
+            upsert_env(
+                m,
+                agent_keys::SKILLLITE_UI_LOCALE,
+                t.to_string(),
+                "ui_override",
+            );
         }
     }
 
     if let Some(ref servers) = cfg.mcp_servers {
         match serde_json::to_string(servers) {
             Ok(json) => {
-                upsert_env(
-                    m,
-                    mcp_keys::SKILLLITE_MCP_SERVERS_JSON,
-                    json,
-                    "ui_override",
-                );
+                upsert_env(m, mcp_keys::SKILLLITE_MCP_SERVERS_JSON, json, "ui_override");
             }
             Err(e) => {
                 eprintln!("[skilllite-assistant] mcp_servers serialize error: {}", e);
@@ -353,8 +622,10 @@ pub fn chat_stream(
     config_overrides: Option<ChatConfigOverrides>,
     session_key: Option<String>,
     images: Option<Vec<ChatImageAttachment>>,
+    attachments: Option<Vec<String>>,
     confirmation_state: ConfirmationState,
     clarification_state: ClarificationState,
+    plan_approval_state: PlanApprovalState,
     process_state: ChatProcessState,
 ) -> Result<(), String> {
     let raw_workspace = workspace
@@ -362,6 +633,10 @@ pub fn chat_stream(
         .unwrap_or_else(|| ".".to_string());
     let workspace_root = find_project_root(&raw_workspace);
     let workspace_str = workspace_root.to_string_lossy().to_string();
+    let session = session_key.unwrap_or_else(|| "default".to_string());
+    // Registered before spawning so a stop issued while starting is not lost; dropping the
+    // guard (after stdin below) unregisters and reaps the process.
+    let process_guard = process_state.register(&session)?;
 
     if let Err(e) =
         bundled_skills_sync::sync_bundled_skills_from_resources(window.app_handle(), &raw_workspace)
@@ -393,10 +668,7 @@ pub fn chat_stream(
         cmd.env(k, v);
     }
     cmd.env("RUST_LOG", "error");
-    cmd.env(
-        super::local::env_keys::observability::SKILLLITE_QUIET,
-        "1",
-    );
+    cmd.env(super::local::env_keys::observability::SKILLLITE_QUIET, "1");
     cmd.env(
         super::local::env_keys::observability::SKILLLITE_LOG_JSON,
         "0",
@@ -425,13 +697,7 @@ pub fn chat_stream(
         .take()
         .ok_or_else(|| "Failed to open stdout".to_string())?;
 
-    {
-        let mut guard = process_state
-            .0
-            .lock()
-            .map_err(|_| "ChatProcessState lock poisoned")?;
-        *guard = Some(child);
-    }
+    process_guard.attach(child)?;
 
     let mut config_json = serde_json::Map::new();
     config_json.insert("workspace".to_string(), json!(workspace_str));
@@ -475,9 +741,11 @@ pub fn chat_stream(
                 config_json.insert("mcp_servers".to_string(), v);
             }
         }
+        if let Some(approve) = cfg.approve_plan {
+            config_json.insert("approve_plan".to_string(), json!(approve));
+        }
     }
 
-    let session = session_key.unwrap_or_else(|| "default".to_string());
     let imgs_json: Option<Vec<Value>> = images.map(|v| {
         v.into_iter()
             .map(|img| {
@@ -497,6 +765,9 @@ pub fn chat_stream(
         if let Some(arr) = imgs_json.filter(|a| !a.is_empty()) {
             obj.insert("images".to_string(), json!(arr));
         }
+        if let Some(paths) = attachments.filter(|a| !a.is_empty()) {
+            obj.insert("attachments".to_string(), json!(paths));
+        }
     }
     let request = json!({
         "method": "agent_chat",
@@ -592,14 +863,7 @@ pub fn chat_stream(
                         .get("risk_tier")
                         .and_then(|v| v.as_str())
                         .unwrap_or("confirm_required");
-                    let (confirm_tx, confirm_rx) = mpsc::channel();
-                    {
-                        let mut guard = confirmation_state
-                            .0
-                            .lock()
-                            .map_err(|_| "ConfirmationState lock poisoned")?;
-                        *guard = Some(confirm_tx);
-                    }
+                    let confirm_rx = confirmation_state.0.open(&session)?;
                     if let Err(e) = window.emit(
                         "skilllite-confirmation-request",
                         json!({ "prompt": prompt, "risk_tier": risk_tier, "session_key": &session }),
@@ -607,13 +871,7 @@ pub fn chat_stream(
                         eprintln!("emit confirmation_request error: {}", e);
                     }
                     let approved = confirm_rx.recv().unwrap_or(false);
-                    {
-                        let mut guard = confirmation_state
-                            .0
-                            .lock()
-                            .map_err(|_| "ConfirmationState lock poisoned")?;
-                        *guard = None;
-                    }
+                    confirmation_state.0.close(&session)?;
                     let confirm_msg =
                         json!({ "method": "confirm", "params": { "approved": approved } });
                     if let Err(e) = writeln!(stdin, "{}", confirm_msg) {
@@ -639,14 +897,7 @@ pub fn chat_stream(
                                 .collect()
                         })
                         .unwrap_or_default();
-                    let (clarify_tx, clarify_rx) = mpsc::channel();
-                    {
-                        let mut guard = clarification_state
-                            .0
-                            .lock()
-                            .map_err(|_| "ClarificationState lock poisoned")?;
-                        *guard = Some(clarify_tx);
-                    }
+                    let clarify_rx = clarification_state.0.open(&session)?;
                     if let Err(e) = window.emit(
                         "skilllite-clarification-request",
                         json!({
@@ -662,13 +913,7 @@ pub fn chat_stream(
                         action: "stop".into(),
                         hint: None,
                     });
-                    {
-                        let mut guard = clarification_state
-                            .0
-                            .lock()
-                            .map_err(|_| "ClarificationState lock poisoned")?;
-                        *guard = None;
-                    }
+                    clarification_state.0.close(&session)?;
                     let clarify_msg = json!({
                         "method": "clarify",
                         "params": {
@@ -682,6 +927,44 @@ pub fn chat_stream(
                     let _ = stdin.flush();
                     continue;
                 }
+                if ev.event == "plan_proposed" {
+                    let tasks = ev.data.get("tasks").cloned().unwrap_or(json!([]));
+                    let can_regenerate = ev
+                        .data
+                        .get("can_regenerate")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false);
+                    let approval_rx = plan_approval_state.0.open(&session)?;
+                    if let Err(e) = window.emit(
+                        "skilllite-plan-proposed",
+                        json!({
+                            "tasks": tasks,
+                            "can_regenerate": can_regenerate,
+                            "session_key": &session,
+                        }),
+                    ) {
+                        eprintln!("emit plan_proposed error: {}", e);
+                    }
+                    let response = approval_rx.recv().unwrap_or(PlanApprovalResponse {
+                        action: "reject".into(),
+                        tasks: None,
+                        feedback: None,
+                    });
+                    plan_approval_state.0.close(&session)?;
+                    let approval_msg = json!({
+                        "method": "approve_plan",
+                        "params": {
+                            "action": response.action,
+                            "tasks": response.tasks,
+                            "feedback": response.feedback.unwrap_or_default(),
+                        }
+                    });
+                    if let Err(e) = writeln!(stdin, "{}", approval_msg) {
+                        eprintln!("write approve_plan error: {}", e);
+                    }
+                    let _ = stdin.flush();
+                    continue;
+                }
                 if let Err(e) = window.emit(
                     "skilllite-event",
                     &TaggedEvent {
@@ -712,44 +995,32 @@ pub fn chat_stream(
     }
 
     drop(stdin);
-    let child_opt = {
-        let mut guard = process_state
-            .0
-            .lock()
-            .map_err(|_| "ChatProcessState lock poisoned")?;
-        guard.take()
-    };
-    if let Some(mut c) = child_opt {
-        let _ = c.wait();
-    }
+    drop(process_guard);
     Ok(())
 }
 
+/// Stop the stream of `session_key`, or of every session when `None`. Pending confirmation /
+/// clarification / plan-approval prompts are dropped so the blocked stream thread unwinds.
 pub fn stop_chat(
     process_state: &ChatProcessState,
     confirmation_state: &ConfirmationState,
     clarification_state: &ClarificationState,
+    plan_approval_state: &PlanApprovalState,
+    session_key: Option<&str>,
 ) -> Result<(), String> {
-    {
-        let mut guard = confirmation_state
-            .0
-            .lock()
-            .map_err(|_| "ConfirmationState lock poisoned")?;
-        drop(guard.take());
-    }
-    {
-        let mut guard = clarification_state
-            .0
-            .lock()
-            .map_err(|_| "ClarificationState lock poisoned")?;
-        drop(guard.take());
-    }
-    let mut guard = process_state
-        .0
-        .lock()
-        .map_err(|_| "ChatProcessState lock poisoned")?;
-    if let Some(mut child) = guard.take() {
-        let _ = child.kill();
+    match session_key {
+        Some(key) => {
+            confirmation_state.0.close(key)?;
+            clarification_state.0.close(key)?;
+            plan_approval_state.0.close(key)?;
+            process_state.stop(key)?;
+        }
+        None => {
+            confirmation_state.0.close_all()?;
+            clarification_state.0.close_all()?;
+            plan_approval_state.0.close_all()?;
+            process_state.stop_all()?;
+        }
     }
     Ok(())
 }
@@ -760,19 +1031,114 @@ mod tests {
 
     #[test]
     fn stop_chat_succeeds_when_no_child() {
+        for session_key in [None, Some("default")] {
+            stop_chat(
+                &ChatProcessState::default(),
+                &ConfirmationState::default(),
+                &ClarificationState::default(),
+                &PlanApprovalState::default(),
+                session_key,
+            )
+            .unwrap();
+        }
+    }
+
+    #[cfg(unix)]
+    fn long_running() -> Child {
+        Command::new("sleep").arg("30").spawn().unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stopping_one_session_leaves_the_other_running() {
+        let procs = ChatProcessState::default();
+        let confirmations = ConfirmationState::default();
+        let clarifications = ClarificationState::default();
+        let guard_a = procs.register("a").unwrap();
+        let guard_b = procs.register("b").unwrap();
+        guard_a.attach(long_running()).unwrap();
+        guard_b.attach(long_running()).unwrap();
+        let pending_a = confirmations.0.open("a").unwrap();
+        let pending_b = confirmations.0.open("b").unwrap();
+
         stop_chat(
-            &ChatProcessState::default(),
-            &ConfirmationState::default(),
-            &ClarificationState::default(),
+            &procs,
+            &confirmations,
+            &clarifications,
+            &PlanApprovalState::default(),
+            Some("a"),
         )
         .unwrap();
+
+        assert_eq!(procs.sessions(), vec!["b".to_string()]);
+        assert!(pending_a.recv().is_err(), "a's prompt is dropped");
+        assert!(confirmations.0.send(Some("b"), true).unwrap());
+        assert_eq!(pending_b.recv(), Ok(true));
+        {
+            let mut map = procs.0.lock().unwrap();
+            match map.get_mut("b").map(|e| &mut e.process) {
+                Some(ChatProcess::Running(child)) => assert!(child.try_wait().unwrap().is_none()),
+                _ => panic!("b should still be running"),
+            }
+        }
+
+        // a's stream thread finishing after the stop must not unregister b.
+        drop(guard_a);
+        assert_eq!(procs.sessions(), vec!["b".to_string()]);
+        procs.stop("b").unwrap();
+        drop(guard_b);
+        assert!(procs.sessions().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stop_while_starting_cancels_the_spawn() {
+        let procs = ChatProcessState::default();
+        let guard = procs.register("a").unwrap();
+        assert!(procs.register("a").is_err(), "one stream per session");
+        assert!(procs.stop("a").unwrap());
+        assert!(guard.attach(long_running()).is_err());
+        drop(guard);
+        assert!(procs.sessions().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn late_cleanup_of_a_stopped_stream_keeps_the_newer_one() {
+        let procs = ChatProcessState::default();
+        let old = procs.register("a").unwrap();
+        old.attach(long_running()).unwrap();
+        procs.stop("a").unwrap();
+        let new = procs.register("a").unwrap();
+        new.attach(long_running()).unwrap();
+        drop(old);
+        assert_eq!(procs.sessions(), vec!["a".to_string()]);
+        procs.stop("a").unwrap();
+        drop(new);
+    }
+
+    #[test]
+    fn replies_route_by_session() {
+        let replies = SessionReplies::<bool>::default();
+        assert!(!replies.send(None, true).unwrap(), "nothing pending");
+        let a = replies.open("a").unwrap();
+        let b = replies.open("b").unwrap();
+        assert!(replies.send(None, true).is_err(), "ambiguous without a key");
+        assert!(replies.send(Some("b"), false).unwrap());
+        assert_eq!(b.recv(), Ok(false));
+        assert!(replies.send(None, true).unwrap(), "only a is left");
+        assert_eq!(a.recv(), Ok(true));
+        assert!(!replies.send(Some("a"), true).unwrap());
     }
 
     #[test]
     fn merge_dotenv_with_chat_overrides_prefers_ui_values() {
         let dotenv = vec![
             ("OPENAI_MODEL".to_string(), "dotenv-model".to_string()),
-            ("OPENAI_BASE_URL".to_string(), "https://dotenv.base".to_string()),
+            (
+                "OPENAI_BASE_URL".to_string(),
+                "https://dotenv.base".to_string(),
+            ),
         ];
         let overrides = ChatConfigOverrides {
             model: Some("ui-model".to_string()),
@@ -781,7 +1147,10 @@ mod tests {
         };
         let merged = merge_dotenv_with_chat_overrides(dotenv, Some(&overrides));
         let map: std::collections::HashMap<_, _> = merged.into_iter().collect();
-        assert_eq!(map.get("OPENAI_MODEL").map(String::as_str), Some("ui-model"));
+        assert_eq!(
+            map.get("OPENAI_MODEL").map(String::as_str),
+            Some("ui-model")
+        );
         assert_eq!(
             map.get("OPENAI_BASE_URL").map(String::as_str),
             Some("https://ui.base")
@@ -803,6 +1172,9 @@ mod tests {
                 .map(String::as_str),
             Some("/tmp/project")
         );
-        assert_eq!(map.get("OPENAI_MODEL").map(String::as_str), Some("dotenv-model"));
+        assert_eq!(
+            map.get("OPENAI_MODEL").map(String::as_str),
+            Some("dotenv-model")
+        );
     }
 }
@@ -0,0 +1,58 @@
+//! 会话内文件改动审阅：`skilllite file-history changes/revert --json`（pre-image 由内置编辑工具记录）。
+
+use std::path::Path;
+
+use super::evolution_cli::spawn_skilllite_json;
+use super::local::engine_types::{FileChange, RevertFileResult};
+
+pub fn workspace_changes(
+    skilllite_path: &Path,
+    workspace: &str,
+    session_key: &str,
+    since_ts: u64,
+) -> Result<Vec<FileChange>, String> {
+    let root = super::find_project_root(workspace);
+    let since = since_ts.to_string();
+    spawn_skilllite_json(
+        skilllite_path,
+        workspace,
+        None,
+        &[
+            "file-history",
+            "changes",
+            "--session-key",
+            session_key,
+            "--since",
+            &since,
+            "--workspace",
+            root.to_string_lossy().as_ref(),
+            "--json",
+        ],
+    )
+}
+
+/// 恢复到会话前版本；敏感路径与越界检查在 CLI 侧（与 write_file 相同）。
+pub fn revert_file(
+    skilllite_path: &Path,
+    workspace: &str,
+    session_key: &str,
+    path: &str,
+) -> Result<RevertFileResult, String> {
+    let root = super::find_project_root(workspace);
+    spawn_skilllite_json(
+        skilllite_path,
+        workspace,
+        None,
+        &[
+            "file-history",
+            "revert",
+            "--session-key",
+            session_key,
+            "--path",
+            path,
+            "--workspace",
+            root.to_string_lossy().as_ref(),
+            "--json",
+        ],
+    )
+}
@@ -6,7 +6,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::skilllite_bridge::chat::ChatConfigOverrides;
 use crate::skilllite_bridge::evolution_cli::spawn_skilllite_json;
-use crate::skilllite_bridge::local::engine_types::{GrowthDueDiagnostics, PassiveScheduleDiagnostics};
+use crate::skilllite_bridge::local::engine_types::{
+    GrowthDueDiagnostics, PassiveScheduleDiagnostics,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvolutionLogEntryDto {
@@ -19,6 +21,18 @@ pub struct EvolutionLogEntryDto {
     pub txn_id: Option<String>,
 }
 
+/// Last external-learning fetch of one source (`external_sources[]`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalSourceStatusDto {
+    pub id: String,
+    pub enabled: bool,
+    pub quality_score: f32,
+    pub accessibility_score: f32,
+    #[serde(default)]
+    pub budget_exceeded_streak: u32,
+    pub last_fetch: serde_json::Value,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvolutionStatusPayload {
     pub mode_key: String,
@@ -31,6 +45,8 @@ pub struct EvolutionStatusPayload {
     pub evo_profile_key: String,
     pub evo_cooldown_hours: f64,
     pub unprocessed_decisions: i64,
+    #[serde(default)]
+    pub expired_decisions: i64,
     pub last_run_ts: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_material_run_ts: Option<String>,
@@ -38,6 +54,9 @@ pub struct EvolutionStatusPayload {
     pub judgement_reason: Option<String>,
     pub recent_events: Vec<EvolutionLogEntryDto>,
     pub pending_skill_count: usize,
+    /// Review data per pending skill (`skilllite_evolution::skill_synth::PendingSkill`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_skills: Vec<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub a9: Option<GrowthDueDiagnostics>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -45,6 +64,10 @@ pub struct EvolutionStatusPayload {
     pub would_have_evolution_proposals: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub empty_proposals_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_sources: Vec<ExternalSourceStatusDto>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_rule_conflicts: Vec<serde_json::Value>,
     pub db_error: Option<String>,
 }
 
@@ -6,15 +6,18 @@
 //! | [`prompt_artifact`] | `chat_root/prompts` / `_versions` 白名单与读写；契约单测与 [`super::protocol`] 同级策略 |
 //! | [`evolution_ui`] | 进化状态、待审核技能、Backlog、进程内/子进程触发（`evolution_ui/` 子模块） |
 //! | [`desktop_services`] | 引导体检、运行时预取、Ollama、`schedule.json` |
+//! | [`output_preview`] | `output/` 下 pdf / xlsx / docx / csv / json 预览（二进制格式经沙箱转换）与缓存 |
 
 mod desktop_services;
 mod evolution_ui;
+mod output_preview;
 mod prompt_artifact;
 mod shared;
 mod skill_rpc;
 
 pub use desktop_services::*;
 pub use evolution_ui::*;
+pub use output_preview::*;
 pub use prompt_artifact::*;
 pub use skill_rpc::*;
 
@@ -0,0 +1,640 @@
+//! 输出文件预览：把 webview 不能直接渲染的 `output/` 文件转换成可显示的内容。
+//!
+//! - **csv / json**：纯文本，在进程内按行数 / 字节上限转换（手写 CSV 切分 + HTML 转义，不调用解析库）；
+//! - **xlsx / docx**：不可信的 zip 容器，只在沙箱里解析——内置的标准库 Python 脚本
+//!   （`preview_convert.py`）经 `skilllite exec --sandbox-level 2` 运行，输入文件复制进临时技能目录；
+//! - **pdf**：交给工作区安装的 [`PREVIEW_SKILL`] 技能（`skilllite run`，输出前几页 PNG）；技能与输入
+//!   文件一起复制进临时技能目录（沙箱只挂载技能目录）；未安装时返回「预览不可用」及原因。
+//!
+//! 路径校验与 `skilllite_read_output_file` 相同。结果缓存在源文件旁的 `.<文件名>.preview.json`，
+//! 源文件 mtime 或大小变化即失效；「不可用」结果不缓存（装上转换技能后立即生效）。
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::skilllite_bridge::evolution_cli::spawn_skilllite_json;
+use crate::skilllite_bridge::workspace::resolve_output_file;
+
+use super::shared::find_skill_dir;
+
+/// Converter skill for formats the built-in script does not handle (pdf).
+pub const PREVIEW_SKILL: &str = "file-preview";
+
+/// Cache file name suffix: `.<source name>.preview.json` next to the source.
+pub const PREVIEW_CACHE_SUFFIX: &str = ".preview.json";
+
+const PREVIEW_MAX_ROWS: usize = 200;
+const PREVIEW_MAX_COLS: usize = 50;
+const PREVIEW_PDF_PAGES: usize = 3;
+/// Cap on text / pretty JSON returned to the UI.
+const PREVIEW_MAX_TEXT_BYTES: usize = 256 * 1024;
+/// CSV bytes read to find the first rows.
+const CSV_MAX_READ_BYTES: u64 = 4 * 1024 * 1024;
+/// Larger JSON files are shown raw (truncated) instead of parsed and pretty-printed.
+const JSON_MAX_PARSE_BYTES: u64 = 8 * 1024 * 1024;
+/// Larger sources are not copied into the sandbox.
+const SANDBOX_MAX_SOURCE_BYTES: u64 = 50 * 1024 * 1024;
+const SANDBOX_TIMEOUT_SECS: &str = "30";
+
+const CONVERT_SCRIPT: &str = include_str!("preview_convert.py");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewKind {
+    Html,
+    Text,
+    Images,
+    Unavailable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputPreview {
+    pub kind: PreviewKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// PNG pages, base64.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+    #[serde(default)]
+    pub truncated: bool,
+    /// Why the preview is unavailable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl OutputPreview {
+    fn html(content: String, truncated: bool) -> Self {
+        Self {
+            kind: PreviewKind::Html,
+            content: Some(content),
+            images: Vec::new(),
+            truncated,
+            reason: None,
+        }
+    }
+
+    fn text(content: String, truncated: bool) -> Self {
+        Self {
+            kind: PreviewKind::Text,
+            content: Some(content),
+            images: Vec::new(),
+            truncated,
+            reason: None,
+        }
+    }
+
+    fn unavailable(reason: impl Into<String>) -> Self {
+        Self {
+            kind: PreviewKind::Unavailable,
+            content: None,
+            images: Vec::new(),
+            truncated: false,
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PreviewFormat {
+    Csv,
+    Tsv,
+    Json,
+    Xlsx,
+    Docx,
+    Pdf,
+}
+
+impl PreviewFormat {
+    fn parse(s: &str) -> Option<Self> {
+        match s
+            .trim()
+            .trim_start_matches('.')
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            "json" => Some(Self::Json),
+            "xlsx" => Some(Self::Xlsx),
+            "docx" => Some(Self::Docx),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+            Self::Json => "json",
+            Self::Xlsx => "xlsx",
+            Self::Docx => "docx",
+            Self::Pdf => "pdf",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PreviewCache {
+    format: String,
+    source_mtime_ms: u64,
+    source_size: u64,
+    preview: OutputPreview,
+}
+
+/// Preview `output/<relative_path>`. `format_hint` overrides the file extension.
+pub fn preview_output(
+    relative_path: &str,
+    format_hint: Option<&str>,
+    workspace: Option<&str>,
+    skilllite_path: &Path,
+) -> Result<OutputPreview, String> {
+    let source = resolve_output_file(relative_path, workspace)?;
+    let meta = std::fs::metadata(&source).map_err(|e| format!("无法读取文件: {}", e))?;
+    if !meta.is_file() {
+        return Err("不是文件".to_string());
+    }
+    let hint = format_hint.map(str::trim).filter(|s| !s.is_empty());
+    let ext = source.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let Some(format) = PreviewFormat::parse(hint.unwrap_or(ext)) else {
+        return Ok(OutputPreview::unavailable(format!(
+            "不支持预览的格式: {}",
+            hint.unwrap_or(ext)
+        )));
+    };
+
+    let mtime_ms = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let cache_path = cache_path_for(&source);
+    if let Some(cached) = load_cache(&cache_path, format, mtime_ms, meta.len()) {
+        return Ok(cached);
+    }
+
+    let preview = match format {
+        PreviewFormat::Csv => csv_preview(&source, b',')?,
+        PreviewFormat::Tsv => csv_preview(&source, b'\t')?,
+        PreviewFormat::Json => json_preview(&source, meta.len())?,
+        PreviewFormat::Xlsx | PreviewFormat::Docx => {
+            sandbox_script_preview(&source, format, meta.len(), workspace, skilllite_path)?
+        }
+        PreviewFormat::Pdf => pdf_preview(&source, meta.len(), workspace, skilllite_path)?,
+    };
+
+    if preview.kind != PreviewKind::Unavailable {
+        let cache = PreviewCache {
+            format: format.as_str().to_string(),
+            source_mtime_ms: mtime_ms,
+            source_size: meta.len(),
+            preview: preview.clone(),
+        };
+        if let Ok(json) = serde_json::to_string(&cache) {
+            // Best effort: a read-only output dir still gets previews, just uncached.
+            let _ = std::fs::write(&cache_path, json);
+        }
+    }
+    Ok(preview)
+}
+
+fn cache_path_for(source: &Path) -> PathBuf {
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    source.with_file_name(format!(".{}{}", name, PREVIEW_CACHE_SUFFIX))
+}
+
+fn load_cache(
+    path: &Path,
+    format: PreviewFormat,
+    mtime_ms: u64,
+    size: u64,
+) -> Option<OutputPreview> {
+    let cache: PreviewCache = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    (cache.format == format.as_str()
+        && cache.source_mtime_ms == mtime_ms
+        && cache.source_size == size)
+        .then_some(cache.preview)
+}
+
+// ─── In-process text formats ─────────────────────────────────────────────────
+
+fn csv_preview(source: &Path, delimiter: u8) -> Result<OutputPreview, String> {
+    use std::io::Read;
+    let mut bytes = Vec::new();
+    std::fs::File::open(source)
+        .and_then(|f| f.take(CSV_MAX_READ_BYTES).read_to_end(&mut bytes))
+        .map_err(|e| format!("无法读取文件: {}", e))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+    let (rows, truncated) = split_csv(text, delimiter as char, PREVIEW_MAX_ROWS);
+    let read_all = std::fs::metadata(source)
+        .map(|m| m.len() <= CSV_MAX_READ_BYTES)
+        .unwrap_or(true);
+    Ok(OutputPreview::html(
+        html_table(&rows),
+        truncated || !read_all,
+    ))
+}
+
+/// RFC 4180-style split (quoted fields, `""` escapes, CRLF). Stops after `max_rows` rows and
+/// [`PREVIEW_MAX_COLS`] columns; the flag reports whether anything was cut.
+fn split_csv(text: &str, delimiter: char, max_rows: usize) -> (Vec<Vec<String>>, bool) {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut truncated = false;
+    let mut chars = text.chars().peekable();
+
+    fn end_field(row: &mut Vec<String>, field: &mut String, truncated: &mut bool) {
+        if row.len() < PREVIEW_MAX_COLS {
+            row.push(std::mem::take(field));
+        } else {
+            field.clear();
+            *truncated = true;
+        }
+    }
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            c if c == delimiter => end_field(&mut row, &mut field, &mut truncated),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                end_field(&mut row, &mut field, &mut truncated);
+                rows.push(std::mem::take(&mut row));
+                if rows.len() >= max_rows {
+                    return (rows, truncated || chars.peek().is_some());
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        end_field(&mut row, &mut field, &mut truncated);
+        rows.push(row);
+    }
+    (rows, truncated)
+}
+
+/// First row as header; ragged rows padded to the widest one.
+fn html_table(rows: &[Vec<String>]) -> String {
+    if rows.is_empty() {
+        return "<p>(empty)</p>".to_string();
+    }
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut out = String::from("<table>");
+    for (i, row) in rows.iter().enumerate() {
+        let tag = if i == 0 { "th" } else { "td" };
+        out.push_str("<tr>");
+        for col in 0..width {
+            let cell = row.get(col).map(String::as_str).unwrap_or("");
+            out.push_str(&format!("<{tag}>{}</{tag}>", escape_html(cell)));
+        }
+        out.push_str("</tr>");
+    }
+    out.push_str("</table>");
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn json_preview(source: &Path, size: u64) -> Result<OutputPreview, String> {
+    if size > JSON_MAX_PARSE_BYTES {
+        let (text, _) = read_prefix(source, PREVIEW_MAX_TEXT_BYTES)?;
+        return Ok(OutputPreview::text(text, true));
+    }
+    let raw = std::fs::read(source).map_err(|e| format!("无法读取文件: {}", e))?;
+    let pretty = match serde_json::from_slice::<serde_json::Value>(&raw) {
+        Ok(value) => serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?,
+        // Not valid JSON: show it as is rather than failing the preview.
+        Err(_) => String::from_utf8_lossy(&raw).into_owned(),
+    };
+    let (text, truncated) = truncate_utf8(pretty, PREVIEW_MAX_TEXT_BYTES);
+    Ok(OutputPreview::text(text, truncated))
+}
+
+fn read_prefix(source: &Path, max: usize) -> Result<(String, bool), String> {
+    use std::io::Read;
+    let mut bytes = Vec::new();
+    std::fs::File::open(source)
+        .and_then(|f| f.take(max as u64 + 1).read_to_end(&mut bytes))
+        .map_err(|e| format!("无法读取文件: {}", e))?;
+    Ok(truncate_utf8(
+        String::from_utf8_lossy(&bytes).into_owned(),
+        max,
+    ))
+}
+
+fn truncate_utf8(mut s: String, max: usize) -> (String, bool) {
+    if s.len() <= max {
+        return (s, false);
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    s.truncate(end);
+    (s, true)
+}
+
+// ─── Sandboxed binary formats ────────────────────────────────────────────────
+
+/// Temp skill dir holding the converter (built-in script or a copy of the preview skill)
+/// and a copy of the source; removed on drop.
+struct Scratch(tempfile::TempDir);
+
+impl Scratch {
+    fn new() -> Result<Self, String> {
+        let dir = tempfile::Builder::new()
+            .prefix("skilllite-preview-")
+            .tempdir()
+            .map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(dir.path().join("scripts")).map_err(|e| e.to_string())?;
+        Ok(Self(dir))
+    }
+
+    fn path(&self) -> &Path {
+        self.0.path()
+    }
+}
+
+/// Copies a skill tree into the scratch dir. Symlinks are skipped, not followed.
+fn copy_skill_tree(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = dst.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_skill_tree(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn source_too_large(size: u64) -> Option<OutputPreview> {
+    (size > SANDBOX_MAX_SOURCE_BYTES).then(|| {
+        OutputPreview::unavailable(format!(
+            "文件过大（{} 字节），超过预览上限 {} 字节",
+            size, SANDBOX_MAX_SOURCE_BYTES
+        ))
+    })
+}
+
+fn sandbox_script_preview(
+    source: &Path,
+    format: PreviewFormat,
+    size: u64,
+    workspace: Option<&str>,
+    skilllite_path: &Path,
+) -> Result<OutputPreview, String> {
+    if let Some(unavailable) = source_too_large(size) {
+        return Ok(unavailable);
+    }
+    let scratch = Scratch::new()?;
+    let input_name = format!("input.{}", format.as_str());
+    std::fs::write(
+        scratch.path().join("scripts").join("preview.py"),
+        CONVERT_SCRIPT,
+    )
+    .map_err(|e| e.to_string())?;
+    std::fs::copy(source, scratch.path().join(&input_name)).map_err(|e| e.to_string())?;
+    let input = serde_json::json!({
+        "file": input_name,
+        "format": format.as_str(),
+        "max_rows": PREVIEW_MAX_ROWS,
+        "max_cols": PREVIEW_MAX_COLS,
+        "max_chars": PREVIEW_MAX_TEXT_BYTES,
+    })
+    .to_string();
+    let dir = scratch.path().to_string_lossy().into_owned();
+    let args = [
+        "exec",
+        dir.as_str(),
+        "scripts/preview.py",
+        input.as_str(),
+        "--sandbox-level",
+        "2",
+        "--timeout",
+        SANDBOX_TIMEOUT_SECS,
+    ];
+    run_converter(skilllite_path, workspace, &args)
+}
+
+/// The sandbox only binds the skill dir, so the installed skill and the PDF are copied into
+/// one scratch skill dir and the PDF is passed by its relative name.
+fn pdf_preview(
+    source: &Path,
+    size: u64,
+    workspace: Option<&str>,
+    skilllite_path: &Path,
+) -> Result<OutputPreview, String> {
+    let ws = workspace.unwrap_or(".");
+    let Some(skill_dir) = find_skill_dir(ws, PREVIEW_SKILL) else {
+        return Ok(OutputPreview::unavailable(format!(
+            "PDF 预览需要在沙箱中渲染，请先安装 `{}` 技能",
+            PREVIEW_SKILL
+        )));
+    };
+    if let Some(unavailable) = source_too_large(size) {
+        return Ok(unavailable);
+    }
+    let scratch = Scratch::new()?;
+    let input_name = "input.pdf";
+    copy_skill_tree(&skill_dir, scratch.path()).map_err(|e| e.to_string())?;
+    std::fs::copy(source, scratch.path().join(input_name)).map_err(|e| e.to_string())?;
+    let input = serde_json::json!({
+        "file": input_name,
+        "format": "pdf",
+        "max_pages": PREVIEW_PDF_PAGES,
+    })
+    .to_string();
+    let dir = scratch.path().to_string_lossy().into_owned();
+    let args = [
+        "run",
+        dir.as_str(),
+        input.as_str(),
+        "--timeout",
+        SANDBOX_TIMEOUT_SECS,
+    ];
+    run_converter(skilllite_path, workspace, &args)
+}
+
+/// A converter that fails or prints something unexpected yields "unavailable", not an error:
+/// the UI falls back to "open in system app".
+fn run_converter(
+    skilllite_path: &Path,
+    workspace: Option<&str>,
+    args: &[&str],
+) -> Result<OutputPreview, String> {
+    match spawn_skilllite_json::<OutputPreview>(
+        skilllite_path,
+        workspace.unwrap_or("."),
+        None,
+        args,
+    ) {
+        Ok(mut preview) => {
+            if let Some(content) = preview.content.take() {
+                let (text, cut) = truncate_utf8(content, PREVIEW_MAX_TEXT_BYTES);
+                preview.truncated |= cut;
+                preview.content = Some(text);
+            }
+            preview.images.truncate(PREVIEW_PDF_PAGES);
+            Ok(preview)
+        }
+        Err(e) => Ok(OutputPreview::unavailable(format!("沙箱转换失败: {}", e))),
+    }
+}
+
+#[cfg(test)]
+mod output_preview_tests {
+    use super::*;
+
+    fn temp_workspace(label: &str) -> PathBuf {
+        let ws = std::env::temp_dir().join(format!(
+            "skilllite_preview_{}_{}",
+            label,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&ws);
+        std::fs::create_dir_all(ws.join("output")).unwrap();
+        ws
+    }
+
+    fn preview(ws: &Path, rel: &str, hint: Option<&str>) -> Result<OutputPreview, String> {
+        let ws = ws.to_string_lossy().into_owned();
+        // csv / json never spawn the CLI, so a bogus binary path proves it.
+        preview_output(rel, hint, Some(&ws), Path::new("/nonexistent/skilllite"))
+    }
+
+    #[test]
+    fn csv_becomes_escaped_html_table() {
+        let ws = temp_workspace("csv");
+        std::fs::write(
+            ws.join("output/data.csv"),
+            "\u{feff}name,note\r\nalice,\"a, \"\"quoted\"\" <b>\"\nbob,\"multi\nline\"\n",
+        )
+        .unwrap();
+
+        let got = preview(&ws, "data.csv", None).unwrap();
+
+        assert_eq!(got.kind, PreviewKind::Html);
+        assert!(!got.truncated);
+        assert_eq!(
+            got.content.as_deref(),
+            Some(
+                "<table><tr><th>name</th><th>note</th></tr>\
+                 <tr><td>alice</td><td>a, &quot;quoted&quot; &lt;b&gt;</td></tr>\
+                 <tr><td>bob</td><td>multi\nline</td></tr></table>"
+            )
+        );
+        let _ = std::fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn csv_preview_stops_at_row_limit() {
+        let ws = temp_workspace("csv_rows");
+        let body: String = (0..PREVIEW_MAX_ROWS + 50)
+            .map(|i| format!("{}\n", i))
+            .collect();
+        std::fs::write(ws.join("output/many.csv"), body).unwrap();
+
+        let got = preview(&ws, "many.csv", None).unwrap();
+
+        assert!(got.truncated);
+        let html = got.content.unwrap();
+        assert_eq!(html.matches("<tr>").count(), PREVIEW_MAX_ROWS);
+        let _ = std::fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn json_is_pretty_printed_and_capped() {
+        let ws = temp_workspace("json");
+        std::fs::write(ws.join("output/r.json"), r#"{"a":[1,2],"b":"x"}"#).unwrap();
+        let got = preview(&ws, "r.json", None).unwrap();
+        assert_eq!(got.kind, PreviewKind::Text);
+        assert_eq!(
+            got.content.as_deref(),
+            Some("{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": \"x\"\n}")
+        );
+        assert!(!got.truncated);
+
+        let big = serde_json::json!({ "s": "é".repeat(PREVIEW_MAX_TEXT_BYTES) });
+        std::fs::write(ws.join("output/big.txt"), big.to_string()).unwrap();
+        let got = preview(&ws, "big.txt", Some("json")).unwrap();
+        assert!(got.truncated);
+        assert!(got.content.unwrap().len() <= PREVIEW_MAX_TEXT_BYTES);
+        let _ = std::fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn cache_is_reused_until_the_source_changes() {
+        let ws = temp_workspace("cache");
+        let source = ws.join("output/r.json");
+        std::fs::write(&source, "[1]").unwrap();
+        preview(&ws, "r.json", None).unwrap();
+        let cache = cache_path_for(&source);
+        assert!(cache.is_file());
+
+        // A doctored cache entry is served while mtime and size match...
+        let mut entry: PreviewCache =
+            serde_json::from_str(&std::fs::read_to_string(&cache).unwrap()).unwrap();
+        entry.preview.content = Some("from cache".to_string());
+        std::fs::write(&cache, serde_json::to_string(&entry).unwrap()).unwrap();
+        let got = preview(&ws, "r.json", None).unwrap();
+        assert_eq!(got.content.as_deref(), Some("from cache"));
+
+        // ...and dropped once the source changes.
+        std::fs::write(&source, "[1, 2]").unwrap();
+        let got = preview(&ws, "r.json", None).unwrap();
+        assert_eq!(got.content.as_deref(), Some("[\n  1,\n  2\n]"));
+        let _ = std::fs::remove_dir_all(&ws);
+    }
+
+    #[test]
+    fn paths_outside_output_dir_are_rejected() {
+        let ws = temp_workspace("escape");
+        assert!(preview(&ws, "../secret.json", None).is_err());
+        assert!(preview(&ws, "/etc/passwd", Some("json")).is_err());
+        let got = preview(&ws, "missing.bin", None);
+        assert!(got.is_err());
+        std::fs::write(ws.join("output/notes.bin"), "x").unwrap();
+        let got = preview(&ws, "notes.bin", None).unwrap();
+        assert_eq!(got.kind, PreviewKind::Unavailable);
+        let _ = std::fs::remove_dir_all(&ws);
+    }
+}
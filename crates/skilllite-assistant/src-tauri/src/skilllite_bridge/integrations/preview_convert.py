@@ -0,0 +1,144 @@
+#!/usr/bin/env python3
+"""Output preview converter run by the desktop bridge through `skilllite exec`.
+
+Standard library only. Reads {"file", "format", "max_rows", "max_chars"} from stdin; the file
+sits next to this script's parent directory. Prints one OutputPreview JSON object.
+"""
+import html
+import json
+import os
+import sys
+import zipfile
+from xml.etree import ElementTree as ET
+
+SHEET_NS = "{http://schemas.openxmlformats.org/spreadsheetml/2006/main}"
+WORD_NS = "{http://schemas.openxmlformats.org/wordprocessingml/2006/main}"
+MAX_MEMBER_BYTES = 64 * 1024 * 1024
+
+
+def open_member(archive, name):
+    info = archive.getinfo(name)
+    if info.file_size > MAX_MEMBER_BYTES:
+        raise ValueError("%s is too large to preview (%d bytes)" % (name, info.file_size))
+    return archive.open(info)
+
+
+def column_index(ref):
+    col = 0
+    for ch in ref:
+        if not ch.isalpha():
+            break
+        col = col * 26 + (ord(ch.upper()) - ord("A") + 1)
+    return max(col - 1, 0)
+
+
+def shared_strings(archive):
+    if "xl/sharedStrings.xml" not in archive.namelist():
+        return []
+    strings = []
+    with open_member(archive, "xl/sharedStrings.xml") as fh:
+        for _, elem in ET.iterparse(fh):
+            if elem.tag == SHEET_NS + "si":
+                strings.append("".join(t.text or "" for t in elem.iter(SHEET_NS + "t")))
+                elem.clear()
+    return strings
+
+
+def first_sheet(archive):
+    sheets = sorted(
+        n for n in archive.namelist() if n.startswith("xl/worksheets/sheet") and n.endswith(".xml")
+    )
+    if not sheets:
+        raise ValueError("workbook has no worksheets")
+    return sheets[0]
+
+
+def xlsx_rows(path, max_rows, max_cols):
+    with zipfile.ZipFile(path) as archive:
+        strings = shared_strings(archive)
+        rows, truncated = [], False
+        with open_member(archive, first_sheet(archive)) as fh:
+            for _, elem in ET.iterparse(fh):
+                if elem.tag != SHEET_NS + "row":
+                    continue
+                if len(rows) >= max_rows:
+                    truncated = True
+                    break
+                cells = {}
+                for c in elem.iter(SHEET_NS + "c"):
+                    idx = column_index(c.get("r", ""))
+                    if idx >= max_cols:
+                        truncated = True
+                        continue
+                    kind = c.get("t")
+                    if kind == "inlineStr":
+                        value = "".join(t.text or "" for t in c.iter(SHEET_NS + "t"))
+                    else:
+                        v = c.find(SHEET_NS + "v")
+                        value = v.text if v is not None and v.text else ""
+                        if kind == "s" and value.isdigit() and int(value) < len(strings):
+                            value = strings[int(value)]
+                    cells[idx] = value
+                width = max(cells) + 1 if cells else 0
+                rows.append([cells.get(i, "") for i in range(width)])
+                elem.clear()
+        return rows, truncated
+
+
+def html_table(rows):
+    if not rows:
+        return "<p>(empty sheet)</p>"
+    width = max(len(r) for r in rows)
+    out = ["<table>"]
+    for i, row in enumerate(rows):
+        tag = "th" if i == 0 else "td"
+        cells = "".join(
+            "<%s>%s</%s>" % (tag, html.escape(v), tag) for v in row + [""] * (width - len(row))
+        )
+        out.append("<tr>%s</tr>" % cells)
+    out.append("</table>")
+    return "".join(out)
+
+
+def docx_text(path, max_chars):
+    with zipfile.ZipFile(path) as archive:
+        paragraphs, size = [], 0
+        with open_member(archive, "word/document.xml") as fh:
+            for _, elem in ET.iterparse(fh):
+                if elem.tag != WORD_NS + "p":
+                    continue
+                parts = []
+                for node in elem.iter():
+                    if node.tag == WORD_NS + "t":
+                        parts.append(node.text or "")
+                    elif node.tag == WORD_NS + "tab":
+                        parts.append("\t")
+                text = "".join(parts)
+                paragraphs.append(text)
+                size += len(text) + 1
+                elem.clear()
+                if size > max_chars:
+                    return "\n".join(paragraphs)[:max_chars], True
+        return "\n".join(paragraphs), False
+
+
+def main():
+    req = json.loads(sys.stdin.read())
+    path = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..", req["file"])
+    fmt = req.get("format")
+    try:
+        if fmt == "xlsx":
+            rows, truncated = xlsx_rows(path, int(req.get("max_rows", 200)), int(req.get("max_cols", 50)))
+            result = {"kind": "html", "content": html_table(rows), "truncated": truncated}
+        elif fmt == "docx":
+            text, truncated = docx_text(path, int(req.get("max_chars", 200000)))
+            result = {"kind": "text", "content": text, "truncated": truncated}
+        else:
+            result = {"kind": "unavailable", "reason": "unsupported format: %s" % fmt}
+    except (zipfile.BadZipFile, KeyError, ValueError, ET.ParseError) as e:
+        result = {"kind": "unavailable", "reason": "cannot read %s: %s" % (fmt, e)}
+    print(json.dumps(result))
+
+
+if __name__ == "__main__":
+    main()
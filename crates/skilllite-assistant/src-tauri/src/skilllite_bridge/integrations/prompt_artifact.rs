@@ -200,15 +200,42 @@ pub fn write_chat_prompt_text_file(filename: &str, content: &str) -> Result<(),
     std::fs::write(&path, content).map_err(|e| e.to_string())
 }
 
+/// `changelog.jsonl` plus its rotated archives (`changelog.jsonl.1`, `.2`, …), oldest first.
+fn read_changelog_with_archives(versions_dir: &Path) -> String {
+    let mut archives: Vec<(usize, std::path::PathBuf)> = std::fs::read_dir(versions_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name();
+            let index = name
+                .to_str()?
+                .strip_prefix("changelog.jsonl.")?
+                .parse::<usize>()
+                .ok()?;
+            Some((index, e.path()))
+        })
+        .collect();
+    archives.sort_by(|a, b| b.0.cmp(&a.0));
+    let mut text = String::new();
+    for path in archives
+        .into_iter()
+        .map(|(_, p)| p)
+        .chain(std::iter::once(versions_dir.join("changelog.jsonl")))
+    {
+        if let Ok(chunk) = std::fs::read_to_string(&path) {
+            text.push_str(&chunk);
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+        }
+    }
+    text
+}
+
 fn evolved_prompt_files_from_changelog(chat_root: &Path) -> HashSet<String> {
-    let changelog = chat_root
-        .join("prompts")
-        .join("_versions")
-        .join("changelog.jsonl");
+    let text = read_changelog_with_archives(&chat_root.join("prompts").join("_versions"));
     let mut evolved = HashSet::new();
-    let Ok(text) = std::fs::read_to_string(&changelog) else {
-        return evolved;
-    };
     for line in text.lines() {
         let Ok(v) = serde_json::from_str::<serde_json::Value>(line) else {
             continue;
@@ -109,3 +109,34 @@ pub struct SuggestFollowupResponse {
 pub struct AuthorizeCapabilityResponse {
     pub proposal_id: String,
 }
+
+/// `skilllite sessions list --json` row (executor `SessionSummary`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionSummary {
+    pub session_key: String,
+    pub title: Option<String>,
+    #[serde(default)]
+    pub renamed: bool,
+    pub last_activity: u64,
+    pub message_count: usize,
+}
+
+/// `skilllite file-history changes --json` row (executor `FileChange`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub status: String,
+    pub first_changed: u64,
+    pub last_changed: u64,
+    pub edits: usize,
+    pub diff: Option<String>,
+    pub revertible: bool,
+}
+
+/// `skilllite file-history revert --json` result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevertFileResult {
+    pub path: String,
+    /// `restored` | `removed`
+    pub outcome: String,
+}
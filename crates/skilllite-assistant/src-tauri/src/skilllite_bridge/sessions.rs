@@ -1,10 +1,9 @@
 //! sessions.json 与会话 CRUD。
 
-use serde_json::json;
-use std::path::PathBuf;
+use std::path::Path;
 
-use super::paths::skilllite_chat_root;
-use super::transcript::list_transcript_paths;
+use super::evolution_cli::{spawn_skilllite, spawn_skilllite_json};
+use super::local::engine_types::SessionSummary;
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct SessionInfo {
@@ -12,264 +11,129 @@ pub struct SessionInfo {
     pub display_name: String,
     pub updated_at: String,
     pub message_preview: Option<String>,
+    pub message_count: usize,
 }
 
-fn sessions_json_path() -> PathBuf {
-    skilllite_chat_root().join("sessions.json")
-}
+const DEFAULT_SESSION_NAME: &str = "默认会话";
 
-fn get_last_user_message_from_transcripts(
-    transcripts_dir: &std::path::Path,
-    session_key: &str,
-) -> Option<String> {
-    let paths = list_transcript_paths(transcripts_dir, session_key);
-    for path in paths.into_iter().rev() {
-        let Ok(content) = std::fs::read_to_string(&path) else {
-            continue;
+impl From<SessionSummary> for SessionInfo {
+    fn from(s: SessionSummary) -> Self {
+        // 未重命名时 title 为首条用户消息，作为预览；名称回退到会话 key
+        let (display_name, message_preview) = match s.title {
+            Some(title) if s.renamed => (title, None),
+            preview if s.session_key == "default" => (DEFAULT_SESSION_NAME.to_string(), preview),
+            preview => (s.session_key.clone(), preview),
         };
-        let mut last_msg = None;
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
-            let Ok(v) = serde_json::from_str::<serde_json::Value>(line) else {
-                continue;
-            };
-            if v.get("type").and_then(|t| t.as_str()) == Some("message")
-                && v.get("role").and_then(|r| r.as_str()) == Some("user")
-            {
-                if let Some(c) = v.get("content").and_then(|c| c.as_str()) {
-                    let preview: String = c.chars().take(40).collect();
-                    last_msg = Some(if c.chars().count() > 40 {
-                        format!("{}…", preview)
-                    } else {
-                        preview
-                    });
-                }
-            }
-        }
-        if last_msg.is_some() {
-            return last_msg;
+        SessionInfo {
+            message_preview,
+            display_name,
+            updated_at: s.last_activity.to_string(),
+            message_count: s.message_count,
+            session_key: s.session_key,
         }
     }
-    None
 }
 
-pub fn list_sessions() -> Vec<SessionInfo> {
-    let path = sessions_json_path();
-    let store: serde_json::Value = if path.exists() {
-        std::fs::read_to_string(&path)
-            .ok()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or(json!({}))
-    } else {
-        json!({})
-    };
-
-    let sessions_map = store
-        .get("sessions")
-        .and_then(|s| s.as_object())
-        .cloned()
-        .unwrap_or_default();
-
-    let chat_root = skilllite_chat_root();
-    let transcripts_dir = chat_root.join("transcripts");
-
-    let mut result: Vec<SessionInfo> = sessions_map
-        .iter()
-        .map(|(key, val)| {
-            let display_name = val
-                .get("display_name")
-                .and_then(|d| d.as_str())
-                .unwrap_or(if key == "default" {
-                    "默认会话"
-                } else {
-                    key
-                })
-                .to_string();
-            let updated_at = val
-                .get("updated_at")
-                .and_then(|u| u.as_str())
-                .unwrap_or("0")
-                .to_string();
-            let message_preview = get_last_user_message_from_transcripts(&transcripts_dir, key);
-            SessionInfo {
-                session_key: key.clone(),
-                display_name,
-                updated_at,
-                message_preview,
-            }
-        })
-        .collect();
-
-    result.sort_by(|a, b| {
-        let a_ts: u64 = a.updated_at.parse().unwrap_or(0);
-        let b_ts: u64 = b.updated_at.parse().unwrap_or(0);
-        b_ts.cmp(&a_ts)
-    });
-
+/// 会话列表来自 `skilllite sessions list --json`（数据读取在 executor 中实现）。
+pub fn list_sessions(skilllite_path: &Path, workspace: &str) -> Result<Vec<SessionInfo>, String> {
+    let rows: Vec<SessionSummary> = spawn_skilllite_json(
+        skilllite_path,
+        workspace,
+        None,
+        &["sessions", "list", "--json"],
+    )?;
+    let mut result: Vec<SessionInfo> = rows.into_iter().map(SessionInfo::from).collect();
     if !result.iter().any(|s| s.session_key == "default") {
-        let preview = get_last_user_message_from_transcripts(&transcripts_dir, "default");
         result.push(SessionInfo {
             session_key: "default".to_string(),
-            display_name: "默认会话".to_string(),
+            display_name: DEFAULT_SESSION_NAME.to_string(),
             updated_at: "0".to_string(),
-            message_preview: preview,
+            message_preview: None,
+            message_count: 0,
         });
     }
-
-    result
+    Ok(result)
 }
 
-pub fn create_session(display_name: &str) -> Result<SessionInfo, String> {
-    let path = sessions_json_path();
-    let mut store: serde_json::Value = if path.exists() {
-        std::fs::read_to_string(&path)
-            .ok()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or(json!({ "sessions": {} }))
-    } else {
-        json!({ "sessions": {} })
-    };
-
-    let sessions = store
-        .get_mut("sessions")
-        .and_then(|s| s.as_object_mut())
-        .ok_or_else(|| "Invalid sessions.json format".to_string())?;
-
-    let ts = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_millis())
-        .unwrap_or(0);
-    let session_key = format!("s-{:x}", ts);
-    let now = format!("{}", ts / 1000);
-
-    sessions.insert(
-        session_key.clone(),
-        json!({
-            "session_id": format!("tx-{:x}", ts),
-            "session_key": session_key,
-            "display_name": display_name,
-            "updated_at": now,
-            "input_tokens": 0,
-            "output_tokens": 0,
-            "total_tokens": 0,
-            "context_tokens": 0,
-            "compaction_count": 0
-        }),
-    );
-
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+fn run_sessions_cli(skilllite_path: &Path, workspace: &str, args: &[&str]) -> Result<(), String> {
+    let output = spawn_skilllite(skilllite_path, workspace, None, args)?;
+    if output.status.success() {
+        return Ok(());
     }
-    let content = serde_json::to_string_pretty(&store).map_err(|e| e.to_string())?;
-    std::fs::write(&path, content).map_err(|e| e.to_string())?;
-
-    Ok(SessionInfo {
-        session_key,
-        display_name: display_name.to_string(),
-        updated_at: now,
-        message_preview: None,
-    })
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(format!(
+        "skilllite {} failed: {}",
+        args.join(" "),
+        if stderr.trim().is_empty() {
+            output.status.to_string()
+        } else {
+            stderr.trim().to_string()
+        }
+    ))
 }
 
-pub fn rename_session(session_key: &str, new_name: &str) -> Result<(), String> {
-    let path = sessions_json_path();
-    let mut store: serde_json::Value = if path.exists() {
-        std::fs::read_to_string(&path)
-            .ok()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or(json!({ "sessions": {} }))
-    } else {
-        json!({ "sessions": {} })
-    };
-
-    if store.get("sessions").and_then(|s| s.as_object()).is_none() {
-        store["sessions"] = json!({});
-    }
-
-    let sessions = store
-        .get_mut("sessions")
-        .and_then(|s| s.as_object_mut())
-        .ok_or_else(|| "Invalid sessions.json format".to_string())?;
-
-    match sessions.get_mut(session_key) {
-        Some(entry) => {
-            if let Some(obj) = entry.as_object_mut() {
-                obj.insert("display_name".to_string(), json!(new_name));
-            }
-        }
-        None if session_key == "default" => {
-            sessions.insert(
-                session_key.to_string(),
-                json!({
-                    "display_name": new_name,
-                    "updated_at": "0",
-                }),
-            );
-        }
-        None => {
-            return Err(format!("Session '{}' not found", session_key));
-        }
-    }
+/// 新建会话走 `skilllite sessions create --json`（加锁读写 sessions.json，与 agent 写入互不覆盖）。
+pub fn create_session(
+    display_name: &str,
+    skilllite_path: &Path,
+    workspace: &str,
+) -> Result<SessionInfo, String> {
+    let created: SessionSummary = spawn_skilllite_json(
+        skilllite_path,
+        workspace,
+        None,
+        &["sessions", "create", "--title", display_name, "--json"],
+    )?;
+    Ok(SessionInfo::from(created))
+}
 
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    let content = serde_json::to_string_pretty(&store).map_err(|e| e.to_string())?;
-    std::fs::write(&path, content).map_err(|e| e.to_string())
+pub fn rename_session(
+    session_key: &str,
+    new_name: &str,
+    skilllite_path: &Path,
+    workspace: &str,
+) -> Result<(), String> {
+    run_sessions_cli(
+        skilllite_path,
+        workspace,
+        &[
+            "sessions",
+            "rename",
+            "--session-key",
+            session_key,
+            "--title",
+            new_name,
+        ],
+    )
 }
 
-pub fn delete_session(session_key: &str) -> Result<(), String> {
+/// 与 clear-session 相同（摘要写入 memory 并归档），随后删除会话记录、transcript 与 plan。
+pub fn delete_session(
+    session_key: &str,
+    skilllite_path: &Path,
+    workspace: &str,
+) -> Result<(), String> {
     if session_key == "default" {
         return Err("不能删除默认会话".to_string());
     }
-
-    let path = sessions_json_path();
-    if path.exists() {
-        let mut store: serde_json::Value = std::fs::read_to_string(&path)
-            .ok()
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or(json!({ "sessions": {} }));
-
-        if let Some(sessions) = store.get_mut("sessions").and_then(|s| s.as_object_mut()) {
-            sessions.remove(session_key);
-        }
-
-        let content = serde_json::to_string_pretty(&store).map_err(|e| e.to_string())?;
-        std::fs::write(&path, content).map_err(|e| e.to_string())?;
-    }
-
-    let chat_root = skilllite_chat_root();
-    let transcripts_dir = chat_root.join("transcripts");
-    if transcripts_dir.is_dir() {
-        for p in list_transcript_paths(&transcripts_dir, session_key) {
-            let _ = std::fs::remove_file(p);
-        }
-    }
-
-    let plans_dir = chat_root.join("plans");
-    if plans_dir.is_dir() {
-        if let Ok(entries) = std::fs::read_dir(&plans_dir) {
-            let prefix = format!("{}-", session_key);
-            let exact = format!("{}.json", session_key);
-            let exact_jsonl = format!("{}.jsonl", session_key);
-            for e in entries.flatten() {
-                let name = e.file_name().to_string_lossy().to_string();
-                if name.starts_with(&prefix) || name == exact || name == exact_jsonl {
-                    let _ = std::fs::remove_file(e.path());
-                }
-            }
-        }
-    }
-
-    Ok(())
+    let root = super::find_project_root(workspace);
+    run_sessions_cli(
+        skilllite_path,
+        workspace,
+        &[
+            "sessions",
+            "delete",
+            "--session-key",
+            session_key,
+            "--workspace",
+            root.to_string_lossy().as_ref(),
+        ],
+    )
 }
 
 #[cfg(test)]
 mod tests {
-    use super::list_transcript_paths;
+    use super::super::transcript::list_transcript_paths;
 
     #[test]
     fn list_transcript_paths_sorts_dated_files() {
@@ -24,6 +24,13 @@ pub struct TranscriptLlmUsagePayload {
     pub responses_without_usage: u32,
 }
 
+/// File produced during a turn (transcript `artifact` entry).
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TranscriptArtifactPayload {
+    pub path: String,
+    pub bytes: u64,
+}
+
 /// Single message entry for frontend display.
 #[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct TranscriptMessage {
@@ -44,6 +51,15 @@ pub struct TranscriptMessage {
     /// Present on restored `assistant` messages when the agent persisted turn usage.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub llm_usage: Option<TranscriptLlmUsagePayload>,
+    /// `tool_call` rows: short SHA-256 of the arguments (schema v2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args_digest: Option<String>,
+    /// `tool_result` rows: execution time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// `artifact` rows (schema v2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<TranscriptArtifactPayload>,
 }
 
 /// List transcript file paths for session, sorted by date (legacy first, then YYYY-MM-DD).
@@ -92,7 +108,7 @@ struct TranscriptImageRaw {
     data_base64: String,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct TranscriptEntryRaw {
     ty: String,
     id: String,
@@ -105,6 +121,9 @@ struct TranscriptEntryRaw {
     ui: Option<serde_json::Value>,
     images: Option<Vec<TranscriptImageRaw>>,
     llm_usage: Option<serde_json::Value>,
+    args_digest: Option<String>,
+    duration_ms: Option<u64>,
+    artifact: Option<TranscriptArtifactPayload>,
 }
 
 fn parse_llm_usage_payload(v: &serde_json::Value) -> Option<TranscriptLlmUsagePayload> {
@@ -164,6 +183,29 @@ fn parse_message_images(v: &serde_json::Value) -> Option<Vec<TranscriptImageRaw>
     }
 }
 
+/// `ui` payload of a confirmation row; the prompt text is in `prompt_key` (`prompt` for v1
+/// `custom_message` rows, `command` for v2 `confirmation` rows).
+fn confirmation_ui(v: &Value, prompt_key: &str) -> Value {
+    let mut ui = serde_json::Map::new();
+    ui.insert("kind".into(), json!("confirmation"));
+    ui.insert(
+        "prompt".into(),
+        v.get(prompt_key).cloned().unwrap_or(json!("")),
+    );
+    if let Some(rt) = v.get("risk_tier") {
+        ui.insert("risk_tier".into(), rt.clone());
+    }
+    ui.insert(
+        "resolved".into(),
+        json!(v.get("resolved").and_then(|b| b.as_bool()).unwrap_or(true)),
+    );
+    ui.insert(
+        "approved".into(),
+        json!(v.get("approved").and_then(|b| b.as_bool()).unwrap_or(false)),
+    );
+    Value::Object(ui)
+}
+
 pub fn load_transcript(session_key: &str) -> Vec<TranscriptMessage> {
     let chat_root = skilllite_chat_root();
     if !chat_root.exists() {
@@ -229,6 +271,7 @@ fn load_transcript_from_paths(paths: &[PathBuf]) -> Vec<TranscriptMessage> {
                     ui: None,
                     images,
                     llm_usage,
+                    ..Default::default()
                 });
             } else if ty == "tool_call" {
                 let name = v
@@ -261,6 +304,11 @@ fn load_transcript_from_paths(paths: &[PathBuf]) -> Vec<TranscriptMessage> {
                     ui: None,
                     images: None,
                     llm_usage: None,
+                    args_digest: v
+                        .get("args_digest")
+                        .and_then(|d| d.as_str())
+                        .map(str::to_string),
+                    ..Default::default()
                 });
             } else if ty == "tool_result" {
                 let name = v
@@ -294,6 +342,8 @@ fn load_transcript_from_paths(paths: &[PathBuf]) -> Vec<TranscriptMessage> {
                     ui: None,
                     images: None,
                     llm_usage: None,
+                    duration_ms: v.get("elapsed_ms").and_then(|d| d.as_u64()),
+                    ..Default::default()
                 });
             } else if ty == "compaction" {
                 entries.push(TranscriptEntryRaw {
@@ -308,6 +358,45 @@ fn load_transcript_from_paths(paths: &[PathBuf]) -> Vec<TranscriptMessage> {
                     ui: None,
                     images: None,
                     llm_usage: None,
+                    ..Default::default()
+                });
+            } else if ty == "confirmation" {
+                // Schema v2 typed row; shown like the v1 `custom_message` confirmation.
+                entries.push(TranscriptEntryRaw {
+                    ty: "custom_message".to_string(),
+                    id: v
+                        .get("id")
+                        .and_then(|i| i.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    role: "skilllite_ui".to_string(),
+                    ui: Some(confirmation_ui(&v, "command")),
+                    ..Default::default()
+                });
+            } else if ty == "artifact" {
+                let path = v
+                    .get("path")
+                    .and_then(|p| p.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                entries.push(TranscriptEntryRaw {
+                    ty,
+                    id: v
+                        .get("id")
+                        .and_then(|i| i.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    role: "artifact".to_string(),
+                    content: path.clone(),
+                    tool_call_id: v
+                        .get("tool_call_id")
+                        .and_then(|i| i.as_str())
+                        .map(str::to_string),
+                    artifact: Some(TranscriptArtifactPayload {
+                        path,
+                        bytes: v.get("bytes").and_then(|b| b.as_u64()).unwrap_or(0),
+                    }),
+                    ..Default::default()
                 });
             } else if ty == "custom_message" {
                 let ui_kind = v.get("ui_kind").and_then(|x| x.as_str()).unwrap_or("");
@@ -317,24 +406,7 @@ fn load_transcript_from_paths(paths: &[PathBuf]) -> Vec<TranscriptMessage> {
                         .and_then(|i| i.as_str())
                         .unwrap_or("")
                         .to_string();
-                    let mut ui = serde_json::Map::new();
-                    ui.insert("kind".into(), json!("confirmation"));
-                    ui.insert(
-                        "prompt".into(),
-                        v.get("prompt").cloned().unwrap_or(json!("")),
-                    );
-                    if let Some(rt) = v.get("risk_tier") {
-                        ui.insert("risk_tier".into(), rt.clone());
-                    }
-                    ui.insert(
-                        "resolved".into(),
-                        json!(v.get("resolved").and_then(|b| b.as_bool()).unwrap_or(true)),
-                    );
-                    ui.insert(
-                        "approved".into(),
-                        json!(v.get("approved").and_then(|b| b.as_bool()).unwrap_or(false)),
-                    );
-                    let ui = Value::Object(ui);
+                    let ui = confirmation_ui(&v, "prompt");
                     entries.push(TranscriptEntryRaw {
                         ty: "custom_message".to_string(),
                         id,
@@ -347,6 +419,7 @@ fn load_transcript_from_paths(paths: &[PathBuf]) -> Vec<TranscriptMessage> {
                         ui: Some(ui),
                         images: None,
                         llm_usage: None,
+                        ..Default::default()
                     });
                 } else if ui_kind == "clarification" {
                     let id = v
@@ -376,6 +449,7 @@ fn load_transcript_from_paths(paths: &[PathBuf]) -> Vec<TranscriptMessage> {
                         ui: Some(ui),
                         images: None,
                         llm_usage: None,
+                        ..Default::default()
                     });
                 }
             }
@@ -403,6 +477,7 @@ fn load_transcript_from_paths(paths: &[PathBuf]) -> Vec<TranscriptMessage> {
         let dominated_by_type = e.ty == "message"
             || e.ty == "tool_call"
             || e.ty == "tool_result"
+            || e.ty == "artifact"
             || e.ty == "custom_message";
         if !dominated_by_type {
             continue;
@@ -460,6 +535,9 @@ fn load_transcript_from_paths(paths: &[PathBuf]) -> Vec<TranscriptMessage> {
             images,
             ui: None,
             llm_usage,
+            args_digest: e.args_digest.clone(),
+            duration_ms: e.duration_ms,
+            artifact: e.artifact.clone(),
         });
     }
     messages
@@ -550,4 +628,32 @@ mod tests {
 
         let _ = fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn load_transcript_restores_v2_confirmation_and_artifact_rows() {
+        let dir = unique_test_dir("transcript-v2");
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("default-2026-04-10.jsonl");
+        fs::write(
+            &path,
+            concat!(
+                "{\"type\":\"tool_result\",\"id\":\"r1\",\"tool_call_id\":\"call-1\",\"name\":\"write_output\",\"result\":\"ok\",\"is_error\":false,\"elapsed_ms\":42,\"schema\":2}\n",
+                "{\"type\":\"confirmation\",\"id\":\"k1\",\"parent_id\":null,\"command\":\"rm -rf build\",\"risk_tier\":\"confirm_required\",\"approved\":true,\"timestamp\":\"1\",\"schema\":2}\n",
+                "{\"type\":\"artifact\",\"id\":\"a1\",\"parent_id\":null,\"path\":\"/ws/output/report.md\",\"bytes\":12,\"tool_call_id\":\"call-1\",\"timestamp\":\"1\",\"schema\":2}\n"
+            ),
+        )
+        .expect("write transcript");
+
+        let messages = load_transcript_from_paths(&[path]);
+        assert_eq!(tool_row(&messages, "tool_result").duration_ms, Some(42));
+        let ui = tool_row(&messages, "skilllite_ui").ui.as_ref().unwrap();
+        assert_eq!(ui["kind"], "confirmation");
+        assert_eq!(ui["prompt"], "rm -rf build");
+        assert_eq!(ui["approved"], true);
+        let artifact = tool_row(&messages, "artifact").artifact.as_ref().unwrap();
+        assert_eq!(artifact.path, "/ws/output/report.md");
+        assert_eq!(artifact.bytes, 12);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
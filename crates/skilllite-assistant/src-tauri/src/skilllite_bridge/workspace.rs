@@ -3,6 +3,7 @@
 use base64::Engine;
 use serde::Serialize;
 
+use super::integrations::PREVIEW_CACHE_SUFFIX;
 use super::paths::{
     find_project_root, skilllite_chat_root, validate_chat_subdir_relative,
     validate_transcript_log_filename,
@@ -188,6 +189,14 @@ fn collect_md_files(dir: &std::path::Path, base: &std::path::Path, out: &mut Vec
     }
 }
 
+const OUTPUT_EXTS: &[&str] = &[
+    "md", "html", "htm", "txt", "json", "csv", "png", "jpg", "jpeg", "gif", "webp", "svg", "pdf",
+    "xlsx", "docx",
+];
+
+/// `write_output` 维护的输出目录索引（与 `skilllite_core::output_index::INDEX_FILE` 一致）。
+const OUTPUT_INDEX_FILE: &str = ".skilllite-output-index.json";
+
 fn collect_output_files_inner(
     dir: &std::path::Path,
     base: &std::path::Path,
@@ -199,16 +208,17 @@ fn collect_output_files_inner(
     let Ok(entries) = std::fs::read_dir(dir) else {
         return;
     };
-    const EXTS: &[&str] = &[
-        "md", "html", "htm", "txt", "json", "csv", "png", "jpg", "jpeg", "gif", "webp", "svg",
-    ];
     for e in entries.flatten() {
         let p = e.path();
         if p.is_dir() {
             collect_output_files_inner(&p, base, out);
+        } else if p.file_name().is_some_and(|n| {
+            n == OUTPUT_INDEX_FILE || n.to_string_lossy().ends_with(PREVIEW_CACHE_SUFFIX)
+        }) {
+            continue;
         } else if let Some(ext) = p.extension() {
             let ext_lower = ext.to_string_lossy().to_lowercase();
-            if EXTS.contains(&ext_lower.as_str()) {
+            if OUTPUT_EXTS.contains(&ext_lower.as_str()) {
                 if let Ok(rel) = p.strip_prefix(base) {
                     let mtime = p
                         .metadata()
@@ -274,7 +284,39 @@ pub fn read_log_file(filename: &str) -> Result<String, String> {
     std::fs::read_to_string(&full_path).map_err(|e| e.to_string())
 }
 
+/// 优先读取索引（每个条目仅 stat 一次，不遍历整个目录）；无索引时回退为目录扫描。
+fn load_output_files_from_index(output_dir: &std::path::Path) -> Option<Vec<String>> {
+    let raw = std::fs::read_to_string(output_dir.join(OUTPUT_INDEX_FILE)).ok()?;
+    let index: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    let entries = index.get("entries")?.as_object()?;
+    let mut out: Vec<FileWithMtime> = Vec::new();
+    for entry in entries.values() {
+        let Some(rel) = entry.get("path").and_then(|p| p.as_str()) else {
+            continue;
+        };
+        if validate_chat_subdir_relative(rel).is_err() {
+            continue;
+        }
+        let ext_ok = std::path::Path::new(rel)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .is_some_and(|e| OUTPUT_EXTS.contains(&e.as_str()));
+        if !ext_ok || !output_dir.join(rel).is_file() {
+            continue;
+        }
+        let modified = entry.get("modified").and_then(|m| m.as_u64()).unwrap_or(0);
+        out.push((
+            rel.to_string(),
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(modified),
+        ));
+    }
+    Some(sort_newest_first(out))
+}
+
 fn load_output_files_from_dir(output_dir: &std::path::Path) -> Vec<String> {
+    if let Some(indexed) = load_output_files_from_index(output_dir) {
+        return indexed;
+    }
     let mut out = Vec::new();
     if output_dir.exists() {
         collect_output_files_inner(output_dir, output_dir, &mut out);
@@ -403,13 +445,22 @@ pub fn load_recent(workspace: Option<String>) -> RecentData {
     }
 }
 
-pub fn read_output_file(relative_path: &str, workspace: Option<String>) -> Result<String, String> {
+/// `relative_path` under the workspace `output/` dir; rejects absolute paths and `..`.
+pub(crate) fn resolve_output_file(
+    relative_path: &str,
+    workspace: Option<&str>,
+) -> Result<std::path::PathBuf, String> {
     validate_chat_subdir_relative(relative_path)?;
-    let base = workspace_output_dir(workspace.as_deref());
+    let base = workspace_output_dir(workspace);
     let full_path = base.join(relative_path);
     if !full_path.starts_with(&base) {
         return Err("Path escape".to_string());
     }
+    Ok(full_path)
+}
+
+pub fn read_output_file(relative_path: &str, workspace: Option<String>) -> Result<String, String> {
+    let full_path = resolve_output_file(relative_path, workspace.as_deref())?;
     std::fs::read_to_string(&full_path).map_err(|e| e.to_string())
 }
 
@@ -417,12 +468,7 @@ pub fn read_output_file_base64(
     relative_path: &str,
     workspace: Option<String>,
 ) -> Result<String, String> {
-    validate_chat_subdir_relative(relative_path)?;
-    let base = workspace_output_dir(workspace.as_deref());
-    let full_path = base.join(relative_path);
-    if !full_path.starts_with(&base) {
-        return Err("Path escape".to_string());
-    }
+    let full_path = resolve_output_file(relative_path, workspace.as_deref())?;
     let bytes = std::fs::read(&full_path).map_err(|e| e.to_string())?;
     Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
 }
@@ -747,6 +793,35 @@ pub fn list_workspace_entries(workspace: &str) -> Result<WorkspaceListEntriesPay
 mod workspace_path_tests {
     use super::*;
 
+    #[test]
+    fn output_files_use_index_when_present() {
+        let tmp = std::env::temp_dir().join(format!("skilllite_out_index_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("reports")).unwrap();
+        std::fs::write(tmp.join("reports/a.html"), "x").unwrap();
+        std::fs::write(tmp.join("b.md"), "x").unwrap();
+        std::fs::write(tmp.join("unindexed.txt"), "x").unwrap();
+
+        let scanned = load_output_files_from_dir(&tmp);
+        assert_eq!(scanned.len(), 3);
+
+        let index = serde_json::json!({
+            "entries": {
+                "reports/a.html": {"path": "reports/a.html", "size": 1, "modified": 100},
+                "b.md": {"path": "b.md", "size": 1, "modified": 200},
+                "gone.md": {"path": "gone.md", "size": 1, "modified": 300},
+                "../escape.md": {"path": "../escape.md", "size": 1, "modified": 400}
+            }
+        });
+        std::fs::write(tmp.join(OUTPUT_INDEX_FILE), index.to_string()).unwrap();
+        let indexed = load_output_files_from_dir(&tmp);
+        assert_eq!(
+            indexed,
+            vec!["b.md".to_string(), "reports/a.html".to_string()]
+        );
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
     #[test]
     fn resolve_rejects_parent_escape() {
         let tmp = std::env::temp_dir().join(format!("skilllite_ws_test_{}", std::process::id()));
@@ -836,10 +911,8 @@ mod workspace_path_tests {
 
     #[test]
     fn explicit_workspace_does_not_float_to_parent_skill_root() {
-        let base = std::env::temp_dir().join(format!(
-            "skilllite_ws_explicit_root_{}",
-            std::process::id()
-        ));
+        let base =
+            std::env::temp_dir().join(format!("skilllite_ws_explicit_root_{}", std::process::id()));
         let _ = std::fs::remove_dir_all(&base);
         let parent = base.join("parent");
         let child = parent.join("test");
@@ -43,6 +43,9 @@ type TranscriptEntryDto = {
   ui?: Record<string, unknown> | null;
   images?: ChatImagePreview[];
   llm_usage?: unknown;
+  args_digest?: string;
+  duration_ms?: number;
+  artifact?: { path: string; bytes: number } | null;
 };
 
 type PendingImage = {
@@ -270,7 +273,7 @@ export default function ChatView() {
       statusActions.clearAll();
 
       try {
-        await invoke("skilllite_stop");
+        await invoke("skilllite_stop", { sessionKey: currentSessionKey });
       } catch (e) {
         if (!cancelled) {
           const msg = formatInvokeError(e);
@@ -372,6 +375,8 @@ export default function ChatView() {
             });
             continue;
           }
+          // Artifacts are already listed in the final answer's "Artifacts" section.
+          if (e.role === "artifact") continue;
           const role = e.role === "user" ? "user" : "assistant";
           const uiImages = e.images;
           if (role === "user" && uiImages && uiImages.length > 0) {
@@ -543,7 +548,10 @@ export default function ChatView() {
 
   const handleConfirm = useCallback(async (id: string, approved: boolean) => {
     try {
-      await invoke("skilllite_confirm", { approved });
+      await invoke("skilllite_confirm", {
+        approved,
+        sessionKey: currentSessionKey,
+      });
       setMessages((prev) =>
         prev.map((m) =>
           m.type === "confirmation" && m.id === id
@@ -557,7 +565,7 @@ export default function ChatView() {
         .getState()
         .show(t("toast.confirmFailed", { err: msg }), "error");
     }
-  }, [t]);
+  }, [currentSessionKey, t]);
 
   const autoApproveInFlightRef = useRef<string | null>(null);
   useEffect(() => {
@@ -582,7 +590,11 @@ export default function ChatView() {
     try {
       const hintParam =
         hint != null && hint.trim().length > 0 ? hint : null;
-      await invoke("skilllite_clarify", { action, hint: hintParam });
+      await invoke("skilllite_clarify", {
+        action,
+        hint: hintParam,
+        sessionKey: currentSessionKey,
+      });
       const selectedLabel =
         action === "stop"
           ? "stop"
@@ -726,7 +738,7 @@ export default function ChatView() {
 
   const handleStop = useCallback(async () => {
     try {
-      await invoke("skilllite_stop");
+      await invoke("skilllite_stop", { sessionKey: currentSessionKey });
       setLoading(false);
       setMessages((prev) => {
         const last = prev[prev.length - 1];
@@ -750,7 +762,7 @@ export default function ChatView() {
         .getState()
         .show(translate("toast.stopFailed", { err: msg }), "error");
     }
-  }, [refreshRecentData, statusActions, t]);
+  }, [currentSessionKey, refreshRecentData, statusActions, t]);
 
   const sendMessage = useCallback(
     async (rawText: string, attachments: PendingImage[]) => {
@@ -85,6 +85,7 @@ function LogList({ entries }: { entries: LogEntry[] }) {
           {e.type === "preview_stopped" && "■"}
           {e.type === "swarm_started" && "▶"}
           {e.type === "swarm_progress" && "…"}
+          {e.type === "progress" && "⏳"}
           {e.type === "swarm_finished" && "■"}
           {e.type === "swarm_failed" && "✗"}
           {e.type === "llm_usage" && "∑"}
@@ -100,6 +101,7 @@ function LogFileContent({ files, entries }: { files: string[]; entries: LogEntry
   const { t } = useI18n();
   const [expandedFile, setExpandedFile] = useState<string | null>(null);
   const [fileContent, setFileContent] = useState<string | null>(null);
+  const [preview, setPreview] = useState<OutputPreview | null>(null);
   const [loading, setLoading] = useState(false);
   const loadGenRef = useRef(0);
 
@@ -354,6 +356,54 @@ const getImageMime = (path: string): string | null => {
 };
 const isImageFile = (path: string) => IMAGE_EXTENSIONS.some((ext) => path.toLowerCase().endsWith(ext));
 
+/** Formats rendered via `skilllite_preview_output` (sandboxed conversion, cached next to the file). */
+const PREVIEW_EXTENSIONS = [".pdf", ".xlsx", ".docx", ".csv", ".json"];
+const isPreviewFile = (path: string) => PREVIEW_EXTENSIONS.some((ext) => path.toLowerCase().endsWith(ext));
+
+interface OutputPreview {
+  kind: "html" | "text" | "images" | "unavailable";
+  content?: string | null;
+  images?: string[];
+  truncated?: boolean;
+  reason?: string | null;
+}
+
+function OutputPreviewBody({ preview, title }: { preview: OutputPreview; title: string }) {
+  const { t } = useI18n();
+  return (
+    <>
+      {preview.kind === "unavailable" ? (
+        <span className="text-ink-mute dark:text-ink-dark-mute text-sm">
+          {t("detail.previewUnavailable", { reason: preview.reason ?? "" })}
+        </span>
+      ) : preview.kind === "images" ? (
+        <div className="space-y-2">
+          {(preview.images ?? []).map((b64, i) => (
+            <img
+              key={i}
+              src={`data:image/png;base64,${b64}`}
+              alt={`${title} #${i + 1}`}
+              className="max-w-full object-contain rounded-md"
+            />
+          ))}
+        </div>
+      ) : preview.kind === "html" ? (
+        <iframe
+          srcDoc={preview.content ?? ""}
+          sandbox=""
+          title={title}
+          className="w-full min-h-[200px] border-0 rounded-md bg-paper dark:bg-surface-dark"
+        />
+      ) : (
+        <pre className="whitespace-pre-wrap text-xs break-words">{preview.content ?? ""}</pre>
+      )}
+      {preview.truncated && (
+        <div className="mt-2 text-xs text-ink-mute dark:text-ink-dark-mute">{t("detail.previewTruncated")}</div>
+      )}
+    </>
+  );
+}
+
 function OutputFileContent({ files, workspace }: { files: string[]; workspace: string }) {
   const { t } = useI18n();
   const [expandedFile, setExpandedFile] = useState<string | null>(null);
@@ -375,6 +425,7 @@ function OutputFileContent({ files, workspace }: { files: string[]; workspace: s
       loadGenRef.current += 1;
       setExpandedFile(null);
       setFileContent(null);
+      setPreview(null);
       setLoading(false);
       return;
     }
@@ -382,8 +433,17 @@ function OutputFileContent({ files, workspace }: { files: string[]; workspace: s
     setLoading(true);
     setExpandedFile(path);
     setFileContent(null);
+    setPreview(null);
     try {
-      if (isImageFile(path)) {
+      if (isPreviewFile(path)) {
+        const result = await invoke<OutputPreview>("skilllite_preview_output", {
+          relativePath: path,
+          formatHint: null,
+          workspace,
+        });
+        if (myGen !== loadGenRef.current) return;
+        setPreview(result);
+      } else if (isImageFile(path)) {
         const base64 = await invoke<string>("skilllite_read_output_file_base64", {
           relativePath: path,
           workspace,
@@ -434,6 +494,8 @@ function OutputFileContent({ files, workspace }: { files: string[]; workspace: s
                     <div className="mt-2 p-3 rounded-lg bg-gray-100 dark:bg-gray-700/50 text-sm overflow-y-auto max-h-80 border border-gray-200 dark:border-gray-600">
                       {loading ? (
                         <span className="text-gray-500">{t("detail.loading")}</span>
+                      ) : preview ? (
+                        <OutputPreviewBody preview={preview} title={f} />
                       ) : fileContent ? (
                         fileContent === DETAIL_READ_FAILED ? (
                           <span className="text-red-500 text-sm">{t("detail.readFailed")}</span>
@@ -93,19 +93,67 @@ export interface EvolutionStatusPayload {
   evo_profile_key: string;
   evo_cooldown_hours: number;
   unprocessed_decisions: number;
+  /** Decisions that aged out unread (`evolved = 2`). */
+  expired_decisions?: number;
   last_run_ts: string | null;
   last_material_run_ts?: string | null;
   judgement_label: string | null;
   judgement_reason: string | null;
   recent_events: EvolutionLogEntryDto[];
   pending_skill_count: number;
+  pending_skills?: PendingSkillReviewDto[];
   a9?: GrowthDueDiagnostics | null;
   passive?: PassiveScheduleDiagnostics | null;
   would_have_evolution_proposals?: boolean;
   empty_proposals_reason?: string | null;
+  external_sources?: ExternalSourceStatusDto[];
+  pending_rule_conflicts?: PendingRuleConflictDto[];
   db_error: string | null;
 }
 
+/** Mirrors `skilllite_evolution::skill_synth::PendingSkill` JSON. */
+export interface PendingSkillReviewDto {
+  name: string;
+  created_at: string | null;
+  generation_txn: string | null;
+  excerpt: string[];
+  entry_point: string;
+  language: string;
+  network: boolean;
+  network_domains: string[];
+  scan_status: string | null;
+  needs_review: boolean;
+  files: { path: string; bytes: number }[];
+  age_days: number | null;
+  stale: boolean;
+  confirm_command: string;
+  reject_command: string;
+}
+
+export interface PendingRuleConflictDto {
+  rule: { id: string; instruction: string; provenance?: Record<string, string> | null };
+  conflicts_with: string;
+  conflicting_instruction: string;
+  similarity: number;
+  detected_at: string;
+}
+
+export interface ExternalSourceStatusDto {
+  id: string;
+  enabled: boolean;
+  quality_score: number;
+  accessibility_score: number;
+  budget_exceeded_streak: number;
+  last_fetch: {
+    ts: string;
+    outcome: string;
+    bytes: number;
+    items: number;
+    duration_ms: number;
+    truncated: boolean;
+  };
+}
+
 export interface PendingSkillDto {
   name: string;
   needs_review: boolean;
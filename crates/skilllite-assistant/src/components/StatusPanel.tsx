@@ -55,6 +55,7 @@ function LogList({ entries, limit }: { entries: LogEntry[]; limit?: number }) {
           {e.type === "preview_stopped" && "■"}
           {e.type === "swarm_started" && "▶"}
           {e.type === "swarm_progress" && "…"}
+          {e.type === "progress" && "⏳"}
           {e.type === "swarm_finished" && "■"}
           {e.type === "swarm_failed" && "✗"}
           {e.type === "llm_usage" && "∑"}
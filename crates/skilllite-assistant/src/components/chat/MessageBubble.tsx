@@ -308,6 +308,15 @@ function MessageBubbleInner({
             </span>
           </div>
           {message.args ? <StructuredPayload raw={message.args} /> : null}
+          {message.progress ? (
+            <p
+              className="mt-1.5 text-[11px] leading-snug text-ink-mute dark:text-ink-dark-mute animate-pulse"
+              role="status"
+              aria-live="polite"
+            >
+              ⏳ {message.progress}
+            </p>
+          ) : null}
         </div>
       </div>
     );
@@ -356,6 +356,20 @@ export function useChatEvents({
             ? `${msg}（本轮跳过 ${recoveredLines} 行异常输出）`
             : msg,
         });
+      } else if (event === "status") {
+        const message = (data?.message as string) ?? "";
+        if (!message) return;
+        addLog({ type: "progress" as const, name: "status", text: message });
+        // 显示在尚未返回结果的最近一次工具调用卡片上（首次运行技能时的环境准备）
+        setMessages((prev) => {
+          const callIdx = prev.map((m) => m.type).lastIndexOf("tool_call");
+          if (callIdx < 0 || prev.slice(callIdx + 1).some((m) => m.type === "tool_result")) {
+            return prev;
+          }
+          return prev.map((m, i) =>
+            i === callIdx && m.type === "tool_call" ? { ...m, progress: message } : m
+          );
+        });
       } else if (event === "task_plan") {
         const tasks = (data?.tasks as Array<{
           id?: number; description?: string; tool_hint?: string; completed?: boolean;
@@ -442,7 +456,9 @@ export function useChatEvents({
               sourcePath = readFilePathByToolCallId.get(toolCallId);
             }
             return [
-              ...prev,
+              ...prev.map((m) =>
+                m.type === "tool_call" && m.progress ? { ...m, progress: undefined } : m
+              ),
               {
                 id: crypto.randomUUID(),
                 type: "tool_result" as const,
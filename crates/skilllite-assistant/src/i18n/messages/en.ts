@@ -383,6 +383,7 @@ export const enMessages: Record<string, string> = {
   "evolution.log.type.evolution_run_scope": "Evolution scope snapshot",
   "evolution.log.type.evolution_shallow_skip": "Shallow preflight skip",
   "evolution.log.type.rule_extraction_parse_failed": "Rule extraction parse failed",
+  "evolution.log.type.rule_candidate_rejected": "Candidate rule rejected by evaluation",
   "evolution.log.type.evolution_run": "Evolution run (material)",
   "evolution.log.type.evolution_run_noop": "Evolution run (no output)",
   "evolution.log.type.evolution_proposal": "Proposal update",
@@ -844,6 +845,8 @@ export const enMessages: Record<string, string> = {
   "detail.noMemory": "No memory",
   "detail.noOutput": "No output files",
   "detail.readFailed": "* Could not read file *",
+  "detail.previewUnavailable": "Preview unavailable: {reason}",
+  "detail.previewTruncated": "(preview truncated)",
   "detail.loading": "Loading…",
   "detail.logRecent": "Transcript logs (last 3 days)",
   "detail.liveLog": "Live log",
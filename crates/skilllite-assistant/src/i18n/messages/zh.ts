@@ -361,6 +361,7 @@ export const zhMessages: Record<string, string> = {
   "evolution.log.type.evolution_run_scope": "进化范围快照",
   "evolution.log.type.evolution_shallow_skip": "浅层预检跳过",
   "evolution.log.type.rule_extraction_parse_failed": "规则抽取解析失败",
+  "evolution.log.type.rule_candidate_rejected": "候选规则未通过评估",
   "evolution.log.type.evolution_run": "进化运行（有产出）",
   "evolution.log.type.evolution_run_noop": "进化运行（无产出）",
   "evolution.log.type.evolution_proposal": "提案调度",
@@ -817,6 +818,8 @@ export const zhMessages: Record<string, string> = {
   "detail.noMemory": "暂无记忆",
   "detail.noOutput": "暂无输出文件",
   "detail.readFailed": "* 无法读取文件内容 *",
+  "detail.previewUnavailable": "暂无预览：{reason}",
+  "detail.previewTruncated": "（预览已截断）",
   "detail.loading": "加载中…",
   "detail.logRecent": "最近 3 天的日志文件",
   "detail.liveLog": "实时日志",
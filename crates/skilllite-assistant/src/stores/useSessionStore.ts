@@ -3,12 +3,20 @@ import { persist } from "zustand/middleware";
 import { invoke } from "@tauri-apps/api/core";
 import { formatInvokeError } from "../utils/formatInvokeError";
 import { useUiToastStore } from "./useUiToastStore";
+import { useSettingsStore } from "./useSettingsStore";
 
 export interface SessionInfo {
   session_key: string;
   display_name: string;
   updated_at: string;
   message_preview: string | null;
+  /** user + assistant 消息数（来自 `skilllite sessions list`） */
+  message_count: number;
+}
+
+/** 会话 CLI 读取该工作区的 .env；未设置时由后端回退到 "." */
+function currentWorkspace(): string | undefined {
+  return useSettingsStore.getState().settings.workspace || undefined;
 }
 
 interface SessionState {
@@ -35,7 +43,9 @@ export const useSessionStore = create<SessionState>()(
 
       loadSessions: async () => {
         try {
-          const remote = await invoke<SessionInfo[]>("skilllite_list_sessions");
+          const remote = await invoke<SessionInfo[]>("skilllite_list_sessions", {
+            workspace: currentWorkspace(),
+          });
           const local = get().sessions;
           const remoteKeys = new Set(remote.map((s) => s.session_key));
           const localOnly = local.filter(
@@ -59,6 +69,7 @@ export const useSessionStore = create<SessionState>()(
                   display_name: "默认会话",
                   updated_at: "0",
                   message_preview: null,
+                  message_count: 0,
                 },
               ],
             });
@@ -68,7 +79,9 @@ export const useSessionStore = create<SessionState>()(
 
       createSession: async (name: string) => {
         try {
-          await invoke("skilllite_stop");
+          await invoke("skilllite_stop", {
+            sessionKey: get().currentSessionKey,
+          });
         } catch (e) {
           useUiToastStore
             .getState()
@@ -78,7 +91,7 @@ export const useSessionStore = create<SessionState>()(
         try {
           const session = await invoke<SessionInfo>(
             "skilllite_create_session",
-            { displayName: name }
+            { displayName: name, workspace: currentWorkspace() }
           );
           set((s) => ({
             sessions: [session, ...s.sessions],
@@ -100,6 +113,7 @@ export const useSessionStore = create<SessionState>()(
             display_name: name,
             updated_at: now,
             message_preview: null,
+            message_count: 0,
           };
           set((s) => ({
             sessions: [session, ...s.sessions],
@@ -112,7 +126,9 @@ export const useSessionStore = create<SessionState>()(
       switchSession: async (key: string) => {
         if (get().currentSessionKey === key) return;
         try {
-          await invoke("skilllite_stop");
+          await invoke("skilllite_stop", {
+            sessionKey: get().currentSessionKey,
+          });
         } catch (e) {
           useUiToastStore
             .getState()
@@ -134,6 +150,7 @@ export const useSessionStore = create<SessionState>()(
           await invoke("skilllite_rename_session", {
             sessionKey: key,
             newName: newName,
+            workspace: currentWorkspace(),
           });
         } catch (e) {
           set({ sessions: prevSessions });
@@ -157,7 +174,10 @@ export const useSessionStore = create<SessionState>()(
           };
         });
         try {
-          await invoke("skilllite_delete_session", { sessionKey: key });
+          await invoke("skilllite_delete_session", {
+            sessionKey: key,
+            workspace: currentWorkspace(),
+          });
         } catch (e) {
           set({
             sessions: prevSessions,
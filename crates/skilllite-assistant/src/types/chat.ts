@@ -46,6 +46,8 @@ export type ChatMessage =
       name: string;
       args: string;
       toolCallId?: string;
+      /** 工具返回前的最新进度（agent `status` 事件，如技能环境准备），返回后清除 */
+      progress?: string;
     }
   | {
       id: string;
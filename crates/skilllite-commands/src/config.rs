@@ -0,0 +1,172 @@
+//! `skilllite config show|set` — inspect and edit the layered settings
+//! (`skilllite_core::config::settings`).
+
+use serde::Serialize;
+use skilllite_core::config::settings::{self, SettingSource, Settings, SETTINGS};
+use skilllite_core::config::UserConfigFile;
+
+use crate::Result;
+
+#[derive(Debug, Serialize)]
+struct ShownSetting {
+    key: &'static str,
+    /// `null` when unset and without a default; secrets are masked.
+    value: Option<String>,
+    /// `default` | `user_file` | `workspace_file` | `env` | `cli` | `unset`
+    source: &'static str,
+    /// Variable name for `env` sources.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigReport {
+    user_file: Option<String>,
+    workspace_file: Option<String>,
+    settings: Vec<ShownSetting>,
+    warnings: Vec<String>,
+}
+
+fn mask(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 10 {
+        "****".to_string()
+    } else {
+        let head: String = chars[..3].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}…{}", head, tail)
+    }
+}
+
+fn report(settings: &Settings) -> ConfigReport {
+    let shown = SETTINGS
+        .iter()
+        .map(|spec| {
+            let value = settings.value(spec.key);
+            let (source, env) = match value.map(|v| &v.source) {
+                None => ("unset", None),
+                Some(SettingSource::Default) => ("default", None),
+                Some(SettingSource::UserFile) => ("user_file", None),
+                Some(SettingSource::WorkspaceFile) => ("workspace_file", None),
+                Some(SettingSource::Env(name)) => ("env", Some(name.clone())),
+                Some(SettingSource::Cli) => ("cli", None),
+            };
+            ShownSetting {
+                key: spec.key,
+                value: value.map(|v| {
+                    if spec.secret {
+                        mask(&v.value)
+                    } else {
+                        v.value.clone()
+                    }
+                }),
+                source,
+                env,
+            }
+        })
+        .collect();
+    ConfigReport {
+        user_file: settings.user_file().map(|p| p.display().to_string()),
+        workspace_file: settings.workspace_file().map(|p| p.display().to_string()),
+        settings: shown,
+        warnings: settings.warnings().to_vec(),
+    }
+}
+
+/// `skilllite config show [--json]`
+pub fn cmd_show(json: bool) -> Result<()> {
+    let report = report(&Settings::load());
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let file_line = |label: &str, path: &Option<String>| match path {
+        Some(p) if std::path::Path::new(p).exists() => println!("{:<16}{}", label, p),
+        Some(p) => println!("{:<16}{} (not found)", label, p),
+        None => {}
+    };
+    file_line("User file:", &report.user_file);
+    file_line("Workspace file:", &report.workspace_file);
+    println!();
+
+    let key_width = SETTINGS.iter().map(|s| s.key.len()).max().unwrap_or(0);
+    for s in &report.settings {
+        let source = match &s.env {
+            Some(name) => format!("env {}", name),
+            None => s.source.replace('_', " "),
+        };
+        println!(
+            "{:<kw$}  {:<30}  {}",
+            s.key,
+            s.value.as_deref().unwrap_or("-"),
+            source,
+            kw = key_width
+        );
+    }
+    if !report.warnings.is_empty() {
+        eprintln!();
+        for w in &report.warnings {
+            eprintln!("⚠️  {}", w);
+        }
+    }
+    Ok(())
+}
+
+/// `skilllite config set <key> <value>` — writes the user-level file only.
+pub fn cmd_set(key: &str, value: &str) -> Result<()> {
+    let path = UserConfigFile::path();
+    let stored = settings::set_user_value(&path, key, value)?;
+    let spec = settings::spec(key);
+    let shown = if spec.is_some_and(|s| s.secret) {
+        mask(&stored)
+    } else {
+        stored
+    };
+    eprintln!("✅ {} = {} ({})", key, shown, path.display());
+
+    // Say so when a higher layer will still win.
+    let effective = Settings::load();
+    match effective.value(key).map(|v| &v.source) {
+        Some(SettingSource::Env(name)) => eprintln!(
+            "   Note: {} is set in the environment and takes precedence.",
+            name
+        ),
+        Some(SettingSource::WorkspaceFile) => eprintln!(
+            "   Note: the workspace config file also sets {} and takes precedence.",
+            key
+        ),
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skilllite_core::config::settings::SettingsSources;
+
+    #[test]
+    fn report_masks_secrets_and_names_env_sources() {
+        let tmp = tempfile::tempdir().unwrap();
+        let user_file = tmp.path().join("config.toml");
+        std::fs::write(&user_file, "api_key = \"sk-abcdefghijklmnop\"\n").unwrap();
+        let settings = Settings::load_from(&SettingsSources {
+            user_file: Some(user_file),
+            workspace_file: None,
+            env: &|k| (k == "SKILLBOX_TIMEOUT_SECS").then(|| "45".to_string()),
+            cli: &[],
+        });
+        let report = report(&settings);
+        let get = |key: &str| report.settings.iter().find(|s| s.key == key).unwrap();
+
+        let key = get("api_key");
+        assert_eq!(key.value.as_deref(), Some("sk-…mnop"));
+        assert_eq!(key.source, "user_file");
+        let timeout = get("sandbox.timeout_secs");
+        assert_eq!(timeout.value.as_deref(), Some("45"));
+        assert_eq!(timeout.env.as_deref(), Some("SKILLBOX_TIMEOUT_SECS"));
+        assert_eq!(get("sandbox.level").source, "default");
+        assert_eq!(get("agent.fallback_model").source, "unset");
+    }
+}
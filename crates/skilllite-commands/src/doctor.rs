@@ -0,0 +1,675 @@
+//! `skilllite doctor` — end-to-end environment diagnostics.
+//!
+//! Runs a fixed battery of checks (runtimes, sandbox backend, network proxy, data root,
+//! LLM config, skills directory, evolution DB, prompt rules schema) and prints pass / warn / fail with a
+//! remediation hint per problem. Exits with [`DOCTOR_EXIT_FAILED`] when any check fails,
+//! so the command can gate CI images.
+
+use serde::Serialize;
+use skilllite_core::skill::metadata;
+use skilllite_sandbox::runner::{ResourceLimits, SandboxConfig, SandboxLevel};
+use skilllite_sandbox::sandbox_backend::{NativeSandboxBackend, SandboxBackend};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::Result;
+
+/// Exit code when at least one check failed.
+pub const DOCTOR_EXIT_FAILED: i32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn icon(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️ ",
+            CheckStatus::Fail => "❌",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorReport<'a> {
+    version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    status: CheckStatus,
+    checks: &'a [DoctorCheck],
+}
+
+/// `skilllite doctor`
+pub fn cmd_doctor(skills_dir: &str, online: bool, json: bool) -> Result<()> {
+    skilllite_core::config::load_dotenv();
+    let checks = run_checks(Path::new(skills_dir), online);
+    let status = overall_status(&checks);
+
+    if json {
+        let report = DoctorReport {
+            version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            status,
+            checks: &checks,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "SkillLite doctor (v{}, {}/{})\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        for check in &checks {
+            println!("{} {}: {}", check.status.icon(), check.name, check.detail);
+            if let Some(hint) = &check.hint {
+                println!("     → {}", hint);
+            }
+        }
+        let failed = checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Fail)
+            .count();
+        let warned = checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Warn)
+            .count();
+        println!("\n{} failed, {} warning(s)", failed, warned);
+    }
+
+    if status == CheckStatus::Fail {
+        std::process::exit(DOCTOR_EXIT_FAILED);
+    }
+    Ok(())
+}
+
+fn overall_status(checks: &[DoctorCheck]) -> CheckStatus {
+    checks
+        .iter()
+        .map(|c| c.status)
+        .max()
+        .unwrap_or(CheckStatus::Pass)
+}
+
+fn run_checks(skills_dir: &Path, online: bool) -> Vec<DoctorCheck> {
+    let snap = skilllite_sandbox::probe_runtime_for_ui(None);
+    let python_available = snap.python.source != "none";
+    vec![
+        runtime_check("python", &snap.python, "--python"),
+        runtime_check("node", &snap.node, "--node"),
+        check_sandbox(python_available),
+        #[cfg(target_os = "linux")]
+        check_seccomp(),
+        check_network_proxy(),
+        check_data_root(&skilllite_core::paths::data_root()),
+        check_llm_config(online),
+        check_skills_dir(skills_dir),
+        check_evolution_db(&skilllite_core::paths::chat_root()),
+        check_prompt_files(&skilllite_core::paths::chat_root()),
+    ]
+}
+
+fn runtime_check(
+    name: &'static str,
+    line: &skilllite_sandbox::RuntimeUiLine,
+    flag: &str,
+) -> DoctorCheck {
+    let detail = match &line.detail {
+        Some(d) => format!("{} ({}) — {}", line.label, line.source, d),
+        None => format!("{} ({})", line.label, line.source),
+    };
+    if line.source == "none" {
+        DoctorCheck::warn(
+            name,
+            detail,
+            format!(
+                "Install it on PATH, or run `skilllite runtime provision {}` to download a bundled runtime",
+                flag
+            ),
+        )
+    } else {
+        DoctorCheck::pass(name, detail)
+    }
+}
+
+/// Run a tiny Python script through the native backend to prove the configured level is achievable.
+fn check_sandbox(python_available: bool) -> DoctorCheck {
+    const NAME: &str = "sandbox";
+    let level = SandboxLevel::from_env_or_cli(None);
+    let backend = NativeSandboxBackend;
+    if !level.use_sandbox() {
+        return DoctorCheck::warn(
+            NAME,
+            "sandbox disabled (level 1): skills run without isolation",
+            "Unset SKILLLITE_SANDBOX_LEVEL / SKILLLITE_NO_SANDBOX to run skills isolated",
+        );
+    }
+    if !python_available {
+        return DoctorCheck::warn(
+            NAME,
+            format!(
+                "{} backend not probed: no Python runtime to run the probe",
+                backend.name()
+            ),
+            "Install Python, then re-run `skilllite doctor`",
+        );
+    }
+
+    let result = (|| -> Result<skilllite_sandbox::runner::ExecutionResult> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("main.py"),
+            "import json\nprint(json.dumps({\"ok\": True}))\n",
+        )?;
+        let runtime = skilllite_sandbox::env::builder::build_runtime_paths(Path::new(""));
+        let config = SandboxConfig {
+            name: "doctor-probe".to_string(),
+            entry_point: "main.py".to_string(),
+            language: "python".to_string(),
+            network_enabled: false,
+            network_outbound: Vec::new(),
+            network_deny: Vec::new(),
+            uses_playwright: false,
+            script_args: Vec::new(),
+            results_dir: None,
+            enforce_isolation: false,
+        };
+        let mut limits = ResourceLimits::from_env();
+        limits.timeout_secs = limits.timeout_secs.min(30);
+        Ok(backend.execute(dir.path(), &runtime, &config, "{}", limits)?)
+    })();
+
+    match result {
+        Ok(run) if run.exit_code == 0 && run.stdout.contains("\"ok\"") => DoctorCheck::pass(
+            NAME,
+            format!(
+                "{} backend ran a probe process (level {:?} achievable)",
+                backend.name(),
+                level
+            ),
+        ),
+        Ok(run) => DoctorCheck::fail(
+            NAME,
+            format!(
+                "{} probe exited {}: {}",
+                backend.name(),
+                run.exit_code,
+                run.stderr.trim().chars().take(300).collect::<String>()
+            ),
+            sandbox_hint(),
+        ),
+        Err(e) => DoctorCheck::fail(
+            NAME,
+            format!("{} backend unavailable: {}", backend.name(), e),
+            sandbox_hint(),
+        ),
+    }
+}
+
+/// Report the seccomp profile each sandbox level applies.
+#[cfg(target_os = "linux")]
+fn check_seccomp() -> DoctorCheck {
+    use skilllite_sandbox::seccomp::{self, SeccompProfiles};
+    const NAME: &str = "seccomp";
+    let path = seccomp::profiles_path();
+    let profiles = match SeccompProfiles::load() {
+        Ok(p) => p,
+        Err(e) => {
+            return DoctorCheck::fail(
+                NAME,
+                format!("invalid profiles: {}", e),
+                format!(
+                    "Fix {} (or SKILLLITE_SECCOMP_MODE); sandboxed runs fail until then",
+                    path.display()
+                ),
+            )
+        }
+    };
+    let levels: Vec<String> = [SandboxLevel::Level2, SandboxLevel::Level3]
+        .into_iter()
+        .map(|level| {
+            let profile = profiles
+                .for_level(level)
+                .map_or_else(|| "none".to_string(), |p| p.summary());
+            format!("level {}: {}", level.as_u8(), profile)
+        })
+        .collect();
+    let source = profiles
+        .source()
+        .map_or_else(|| "built-in".to_string(), |p| p.display().to_string());
+    let detail = format!("{} ({})", levels.join(", "), source);
+    if !seccomp::notify_supported() {
+        return DoctorCheck::warn(
+            NAME,
+            detail,
+            "No seccomp user notification (Linux 5.5+): log mode falls back to the kernel \
+audit log and EPERM errors do not name the denied syscall",
+        );
+    }
+    if levels.iter().any(|l| l.ends_with(": none")) {
+        return DoctorCheck::warn(
+            NAME,
+            detail,
+            format!(
+                "A sandbox level runs without a syscall filter; map it to a profile in {}",
+                path.display()
+            ),
+        );
+    }
+    DoctorCheck::pass(NAME, detail)
+}
+
+fn sandbox_hint() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "Install bubblewrap (`apt install bubblewrap`); in containers allow user namespaces, \
+or set SKILLLITE_SANDBOX_LEVEL=1 to run without isolation"
+    } else if cfg!(target_os = "windows") {
+        "Enable WSL2 (`wsl --install`) for full isolation, or set SKILLLITE_SANDBOX_LEVEL=1"
+    } else {
+        "Check that /usr/bin/sandbox-exec is present and not blocked by MDM policy, \
+or set SKILLLITE_SANDBOX_LEVEL=1"
+    }
+}
+
+/// Start the filtering proxy with an empty allowlist and check it refuses a request.
+fn check_network_proxy() -> DoctorCheck {
+    const NAME: &str = "network proxy";
+    let hint = "Another process may block loopback ports; skills with an outbound allowlist \
+will have no network until the proxy can bind 127.0.0.1";
+    let config = skilllite_sandbox::network_proxy::ProxyConfig::with_allowed_domains(Vec::new());
+    let mut manager = match skilllite_sandbox::network_proxy::ProxyManager::new(config) {
+        Ok(m) => m,
+        Err(e) => return DoctorCheck::fail(NAME, format!("could not bind: {}", e), hint),
+    };
+    if let Err(e) = manager.start() {
+        return DoctorCheck::fail(NAME, format!("could not start: {}", e), hint);
+    }
+    let Some(port) = manager.http_port() else {
+        manager.stop();
+        return DoctorCheck::fail(NAME, "HTTP proxy has no port", hint);
+    };
+
+    let response = (|| -> std::io::Result<String> {
+        let mut stream = std::net::TcpStream::connect(("127.0.0.1", port))?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        stream.write_all(
+            b"GET http://doctor.invalid/ HTTP/1.1\r\nHost: doctor.invalid\r\nConnection: close\r\n\r\n",
+        )?;
+        let mut buf = String::new();
+        let _ = stream.read_to_string(&mut buf);
+        Ok(buf)
+    })();
+    manager.stop();
+
+    match response {
+        Ok(r) if r.starts_with("HTTP/1.1 403") => DoctorCheck::pass(
+            NAME,
+            format!(
+                "filtering proxy on 127.0.0.1:{} blocked a non-allowlisted host",
+                port
+            ),
+        ),
+        Ok(r) => DoctorCheck::fail(
+            NAME,
+            format!(
+                "unexpected proxy response: {}",
+                r.lines().next().unwrap_or("(empty)")
+            ),
+            hint,
+        ),
+        Err(e) => DoctorCheck::fail(NAME, format!("could not reach proxy: {}", e), hint),
+    }
+}
+
+fn check_data_root(root: &Path) -> DoctorCheck {
+    const NAME: &str = "data root";
+    let writable = std::fs::create_dir_all(root)
+        .and_then(|_| tempfile::NamedTempFile::new_in(root).map(|_| ()));
+    match writable {
+        Ok(()) => DoctorCheck::pass(NAME, format!("{} is writable", root.display())),
+        Err(e) => DoctorCheck::fail(
+            NAME,
+            format!("{} is not writable: {}", root.display(), e),
+            "Fix permissions, or point SKILLLITE_WORKSPACE at a writable absolute directory",
+        ),
+    }
+}
+
+fn check_llm_config(online: bool) -> DoctorCheck {
+    const NAME: &str = "llm config";
+    let llm = skilllite_core::config::LlmConfig::from_env();
+    let local = ["localhost", "127.0.0.1", "0.0.0.0", "[::1]"]
+        .iter()
+        .any(|h| llm.api_base.contains(h));
+    if llm.api_key.trim().is_empty() && !local {
+        return DoctorCheck::fail(
+            NAME,
+            format!("no API key for {}", llm.api_base),
+            "Set SKILLLITE_API_KEY (or OPENAI_API_KEY), or run `skilllite quickstart`",
+        );
+    }
+    let summary = format!("model {} at {}", llm.model, llm.api_base);
+    if !online {
+        return DoctorCheck::pass(NAME, format!("{} (not probed; use --online)", summary));
+    }
+    probe_llm(NAME, &llm, summary)
+}
+
+#[cfg(feature = "agent")]
+fn probe_llm(
+    name: &'static str,
+    llm: &skilllite_core::config::LlmConfig,
+    summary: String,
+) -> DoctorCheck {
+    match crate::quickstart::validate_llm_endpoint(&llm.api_base, &llm.api_key, &llm.model) {
+        Ok(()) => DoctorCheck::pass(name, format!("{} (1-token probe ok)", summary)),
+        Err(e) => DoctorCheck::fail(
+            name,
+            format!("{}: probe failed: {}", summary, e),
+            "Check SKILLLITE_API_BASE / SKILLLITE_MODEL / SKILLLITE_API_KEY and network access",
+        ),
+    }
+}
+
+#[cfg(not(feature = "agent"))]
+fn probe_llm(
+    name: &'static str,
+    _llm: &skilllite_core::config::LlmConfig,
+    summary: String,
+) -> DoctorCheck {
+    DoctorCheck::warn(
+        name,
+        format!("{} (not probed)", summary),
+        "--online needs a binary built with the `agent` feature",
+    )
+}
+
+fn check_skills_dir(skills_dir: &Path) -> DoctorCheck {
+    const NAME: &str = "skills";
+    let entries = match std::fs::read_dir(skills_dir) {
+        Ok(e) => e,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return DoctorCheck::warn(
+                NAME,
+                format!("{} does not exist", skills_dir.display()),
+                "Run `skilllite init` or `skilllite add <source>`, or pass --skills-dir",
+            );
+        }
+        Err(e) => {
+            return DoctorCheck::fail(
+                NAME,
+                format!("{} is not readable: {}", skills_dir.display(), e),
+                "Fix the directory permissions",
+            );
+        }
+    };
+
+    let mut ok = 0;
+    let mut broken = Vec::new();
+    let mut skill_dirs: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.join("SKILL.md").is_file())
+        .collect();
+    skill_dirs.sort();
+    for dir in &skill_dirs {
+        match metadata::parse_skill_metadata(dir) {
+            Ok(_) => ok += 1,
+            Err(e) => broken.push(format!(
+                "{}: {}",
+                dir.file_name().unwrap_or_default().to_string_lossy(),
+                e
+            )),
+        }
+    }
+
+    if broken.is_empty() {
+        DoctorCheck::pass(
+            NAME,
+            format!("{} skill(s) parse in {}", ok, skills_dir.display()),
+        )
+    } else {
+        DoctorCheck::fail(
+            NAME,
+            format!(
+                "{} of {} skill(s) fail to parse: {}",
+                broken.len(),
+                skill_dirs.len(),
+                broken.join("; ")
+            ),
+            "Fix the SKILL.md front matter, or remove the skill with `skilllite remove <name>`",
+        )
+    }
+}
+
+fn check_evolution_db(chat_root: &Path) -> DoctorCheck {
+    const NAME: &str = "evolution db";
+    let db_path = chat_root.join("feedback.sqlite");
+    if !db_path.exists() {
+        return DoctorCheck::pass(NAME, "not created yet");
+    }
+    let hint = "Back up and delete feedback.sqlite (it is recreated), \
+or run `skilllite evolution reset`";
+    let integrity = skilllite_evolution::feedback::open_evolution_db(chat_root)
+        .map_err(|e| e.to_string())
+        .and_then(|conn| {
+            conn.query_row("PRAGMA integrity_check", [], |r| r.get::<_, String>(0))
+                .map_err(|e| e.to_string())
+        });
+    match integrity {
+        Ok(result) if result == "ok" => {}
+        Ok(result) => {
+            return DoctorCheck::fail(NAME, format!("{}: {}", db_path.display(), result), hint)
+        }
+        Err(e) => {
+            return DoctorCheck::fail(
+                NAME,
+                format!("{} cannot be opened: {}", db_path.display(), e),
+                hint,
+            )
+        }
+    }
+
+    let chain = skilllite_evolution::verify_evolution_log(chat_root);
+    if chain.first_break.is_some() {
+        return DoctorCheck::warn(
+            NAME,
+            format!(
+                "database ok; evolution.log hash chain is {}",
+                chain.status()
+            ),
+            "Run `skilllite evolution verify-audit` for the first broken entry",
+        );
+    }
+    DoctorCheck::pass(
+        NAME,
+        format!(
+            "{} integrity ok; evolution.log chain {}",
+            db_path.display(),
+            chain.status()
+        ),
+    )
+}
+
+/// Schema check of the evolvable `prompts/rules.json` and `prompts/sources.json`.
+fn check_prompt_files(chat_root: &Path) -> DoctorCheck {
+    const NAME: &str = "prompt rules";
+    let report = skilllite_evolution::seed::doctor(chat_root);
+    if report.is_empty() {
+        return DoctorCheck::pass(NAME, "not created yet (built-in seed data)");
+    }
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for (file, problems) in &report {
+        for problem in problems {
+            let line = format!("{} {}", file, problem);
+            if problem.is_warning() {
+                warnings.push(line);
+            } else {
+                errors.push(line);
+            }
+        }
+    }
+    let files = report
+        .iter()
+        .map(|(file, _)| *file)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if !errors.is_empty() {
+        errors.extend(warnings);
+        return DoctorCheck::fail(
+            NAME,
+            format!(
+                "{} schema error(s); the seed data is used instead: {}",
+                errors.len(),
+                errors.join("; ")
+            ),
+            "Fix the listed fields in ~/.skilllite/chat/prompts, or delete the file to restore the seed data",
+        );
+    }
+    if !warnings.is_empty() {
+        return DoctorCheck::warn(
+            NAME,
+            format!("unknown fields are ignored: {}", warnings.join("; ")),
+            "Remove or rename the listed fields",
+        );
+    }
+    DoctorCheck::pass(NAME, format!("{} match the schema", files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overall_status_is_worst_check() {
+        let checks = vec![DoctorCheck::pass("a", ""), DoctorCheck::warn("b", "", "")];
+        assert_eq!(overall_status(&checks), CheckStatus::Warn);
+        let mut checks = checks;
+        checks.push(DoctorCheck::fail("c", "", ""));
+        assert_eq!(overall_status(&checks), CheckStatus::Fail);
+        assert_eq!(overall_status(&[]), CheckStatus::Pass);
+    }
+
+    #[test]
+    fn skills_dir_reports_unparseable_skills() {
+        let tmp = tempfile::tempdir().unwrap();
+        let good = tmp.path().join("good");
+        std::fs::create_dir_all(&good).unwrap();
+        std::fs::write(
+            good.join("SKILL.md"),
+            "---\nname: good\ndescription: ok\n---\n",
+        )
+        .unwrap();
+        assert_eq!(check_skills_dir(tmp.path()).status, CheckStatus::Pass);
+
+        let bad = tmp.path().join("bad");
+        std::fs::create_dir_all(&bad).unwrap();
+        std::fs::write(bad.join("SKILL.md"), "---\nname: [unclosed\n---\n").unwrap();
+        let check = check_skills_dir(tmp.path());
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.detail.contains("bad"), "{}", check.detail);
+
+        let missing = check_skills_dir(&tmp.path().join("nope"));
+        assert_eq!(missing.status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn data_root_and_evolution_db_checks() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(
+            check_data_root(&tmp.path().join("data")).status,
+            CheckStatus::Pass
+        );
+
+        let chat_root = tmp.path().join("chat");
+        assert_eq!(check_evolution_db(&chat_root).status, CheckStatus::Pass);
+        skilllite_evolution::feedback::open_evolution_db(&chat_root).unwrap();
+        assert_eq!(check_evolution_db(&chat_root).status, CheckStatus::Pass);
+
+        std::fs::write(chat_root.join("feedback.sqlite"), "not a database").unwrap();
+        assert_eq!(check_evolution_db(&chat_root).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn prompt_files_report_schema_problems() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(check_prompt_files(tmp.path()).status, CheckStatus::Pass);
+
+        let prompts = tmp.path().join("prompts");
+        std::fs::create_dir_all(&prompts).unwrap();
+        let rules = prompts.join("rules.json");
+        std::fs::write(&rules, r#"[{"id": "a", "instruction": "x"}]"#).unwrap();
+        assert_eq!(check_prompt_files(tmp.path()).status, CheckStatus::Pass);
+
+        std::fs::write(&rules, r#"[{"id": "a", "instruction": "x", "prority": 1}]"#).unwrap();
+        let check = check_prompt_files(tmp.path());
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(
+            check.detail.contains("did you mean `priority`"),
+            "{}",
+            check.detail
+        );
+
+        std::fs::write(&rules, r#"[{"id": "a", "keywords": "x"}]"#).unwrap();
+        let check = check_prompt_files(tmp.path());
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(
+            check.detail.contains("$[0].instruction"),
+            "{}",
+            check.detail
+        );
+        assert!(check.detail.contains("$[0].keywords"), "{}", check.detail);
+    }
+
+    #[test]
+    fn network_proxy_blocks_unlisted_host() {
+        let check = check_network_proxy();
+        assert_eq!(check.status, CheckStatus::Pass, "{}", check.detail);
+    }
+}
@@ -1,11 +1,15 @@
 //! Environment management commands: clean cached virtual environments.
 //!
 //! Cached environments live in `~/.cache/skilllite/envs/` (or `$SKILLLITE_CACHE_DIR`).
-//! Each subdirectory is a hash-keyed environment created by `ensure_environment()`.
+//! Each subdirectory is a hash-keyed environment created by `ensure_environment()`:
+//! shared envs keyed by dependency hash, or legacy per-skill envs from older versions.
+//! `--results` targets the sibling `results/` directory instead (see [`crate::result_cache`]).
 
 use std::fs;
 use std::path::PathBuf;
 
+use skilllite_sandbox::env::shared_cache::{self, CachedEnv};
+
 use crate::Result;
 
 /// Get the cache directory for skill environments.
@@ -24,7 +28,14 @@ fn get_cache_dir() -> PathBuf {
 }
 
 /// `skilllite env clean`
-pub fn cmd_clean(dry_run: bool, force: bool) -> Result<()> {
+///
+/// With `unused`, only shared environments no longer referenced by any existing skill
+/// are removed (legacy per-skill environments are kept). With `results`, the cached skill
+/// results are removed instead of environments.
+pub fn cmd_clean(dry_run: bool, force: bool, unused: bool, results: bool) -> Result<()> {
+    if results {
+        return clean_results(dry_run, force);
+    }
     let cache_dir = get_cache_dir();
 
     if !cache_dir.exists() {
@@ -32,36 +43,40 @@ pub fn cmd_clean(dry_run: bool, force: bool) -> Result<()> {
         return Ok(());
     }
 
+    let selected: Vec<CachedEnv> = if unused {
+        shared_cache::unused_envs(&cache_dir)
+    } else {
+        shared_cache::list_envs(&cache_dir)
+    };
     let mut entries: Vec<(PathBuf, u64)> = Vec::new();
     let mut total_size: u64 = 0;
-
-    if let Ok(dir_entries) = fs::read_dir(&cache_dir) {
-        for entry in dir_entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                let size = dir_size(&path);
-                total_size += size;
-                entries.push((path, size));
-            }
-        }
+    let mut labels = Vec::new();
+    for env in &selected {
+        let size = dir_size(&env.path);
+        total_size += size;
+        entries.push((env.path.clone(), size));
+        labels.push(env_label(env));
     }
 
-    entries.sort_by_key(|e| e.0.file_name().unwrap_or_default().to_os_string());
-
     if entries.is_empty() {
-        eprintln!("No cached environments found at {}", cache_dir.display());
+        if unused {
+            eprintln!("No unused environments found at {}", cache_dir.display());
+        } else {
+            eprintln!("No cached environments found at {}", cache_dir.display());
+        }
         return Ok(());
     }
 
     eprintln!(
-        "🗂  Cached environments ({}) in {}:",
+        "🗂  {} environments ({}) in {}:",
+        if unused { "Unused" } else { "Cached" },
         entries.len(),
         cache_dir.display()
     );
     eprintln!();
-    for (path, size) in &entries {
+    for ((path, size), label) in entries.iter().zip(&labels) {
         let name = path.file_name().unwrap_or_default().to_string_lossy();
-        eprintln!("  • {} ({})", name, format_size(*size));
+        eprintln!("  • {} ({}) {}", name, format_size(*size), label);
     }
     eprintln!();
     eprintln!(
@@ -78,7 +93,11 @@ pub fn cmd_clean(dry_run: bool, force: bool) -> Result<()> {
 
     // Confirm removal
     if !force {
-        eprint!("\nRemove all cached environments? [y/N] ");
+        if unused {
+            eprint!("\nRemove unused environments? [y/N] ");
+        } else {
+            eprint!("\nRemove all cached environments? [y/N] ");
+        }
         let mut answer = String::new();
         std::io::stdin().read_line(&mut answer)?;
         if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
@@ -103,6 +122,9 @@ pub fn cmd_clean(dry_run: bool, force: bool) -> Result<()> {
         }
     }
 
+    // Drop references to skills that no longer exist
+    shared_cache::prune_references(&cache_dir);
+
     eprintln!();
     if errors == 0 {
         eprintln!(
@@ -122,6 +144,60 @@ pub fn cmd_clean(dry_run: bool, force: bool) -> Result<()> {
     Ok(())
 }
 
+/// `skilllite clean-env --results`
+fn clean_results(dry_run: bool, force: bool) -> Result<()> {
+    let Some(dir) = crate::result_cache::results_dir(None).filter(|d| d.exists()) else {
+        eprintln!("No cached skill results found");
+        return Ok(());
+    };
+    let count = fs::read_dir(&dir)?.flatten().count();
+    if count == 0 {
+        eprintln!("No cached skill results found at {}", dir.display());
+        return Ok(());
+    }
+    let size = dir_size(&dir);
+    eprintln!(
+        "🗂  Cached skill results: {} ({}) in {}",
+        count,
+        format_size(size),
+        dir.display()
+    );
+
+    if dry_run {
+        eprintln!();
+        eprintln!("(Dry run — no files removed. Remove --dry-run to delete.)");
+        return Ok(());
+    }
+    if !force {
+        eprint!("\nRemove all cached skill results? [y/N] ");
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            eprintln!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    fs::remove_dir_all(&dir)?;
+    eprintln!();
+    eprintln!(
+        "✓ Removed {} cached result(s), freed {}",
+        count,
+        format_size(size)
+    );
+    Ok(())
+}
+
+fn env_label(env: &CachedEnv) -> String {
+    if !env.shared {
+        return "[legacy]".to_string();
+    }
+    match env.referenced_by.len() {
+        0 => "[shared, unused]".to_string(),
+        n => format!("[shared, {} skill(s)]", n),
+    }
+}
+
 /// Compute total size of a directory recursively.
 fn dir_size(path: &std::path::Path) -> u64 {
     let mut total: u64 = 0;
@@ -139,7 +215,7 @@ fn dir_size(path: &std::path::Path) -> u64 {
 }
 
 /// Format byte size to human-readable string.
-fn format_size(bytes: u64) -> String {
+pub(crate) fn format_size(bytes: u64) -> String {
     if bytes < 1024 {
         format!("{} B", bytes)
     } else if bytes < 1024 * 1024 {
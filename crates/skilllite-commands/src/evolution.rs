@@ -1,12 +1,13 @@
 //! EVO-5: Evolution management CLI commands.
 //!
-//! Provides `skilllite evolution {status,reset,disable,explain,run}` subcommands
+//! Provides `skilllite evolution {status,reset,gc,export,verify-audit,disable,explain,run}` subcommands
 //! for inspecting, controlling, and debugging the self-evolution engine.
 
 pub use crate::evolution_desktop::{
     authorize_capability_evolution as desktop_authorize_capability_evolution,
     confirm_pending_skill as desktop_confirm_pending_skill,
-    list_pending_skills as desktop_list_pending_skills, query_backlog_desktop,
+    list_pending_skills as desktop_list_pending_skills,
+    pending_skill_dir as desktop_pending_skill_dir, query_backlog_desktop,
     query_proposal_status as desktop_query_proposal_status,
     read_pending_skill_md as desktop_read_pending_skill_md,
     reject_pending_skill as desktop_reject_pending_skill, AuthorizeCapabilitySnapshot,
@@ -254,7 +255,22 @@ pub fn cmd_backlog(
 }
 
 /// `skilllite evolution reset` — delete all evolved data, return to seed state.
-pub fn cmd_reset(force: bool) -> Result<()> {
+/// With `keep_data`, only rebuild tables derived from decisions.
+pub fn cmd_reset(force: bool, keep_data: bool) -> Result<()> {
+    if keep_data {
+        let root = paths::chat_root();
+        // Opening migrates the DB to the current schema (with a backup if tables are altered).
+        let conn = skilllite_evolution::feedback::open_evolution_db(&root)?;
+        let report = skilllite_evolution::feedback::rebuild_derived_tables(&conn)?;
+        println!(
+            "✅ 已重建派生数据: {} 天指标, {} 条工具序列键 (schema v{})",
+            report.metric_days,
+            report.sequence_keys,
+            skilllite_evolution::schema::schema_version(&conn)?
+        );
+        println!("   决策、规则、Skill 与进化日志均已保留。");
+        return Ok(());
+    }
     if !force {
         println!("⚠️  这将删除所有进化产物（规则、示例、Skill），回到种子状态。");
         println!("   已有进化经验将永久丢失。种子规则不受影响。");
@@ -290,8 +306,11 @@ pub fn cmd_reset(force: bool) -> Result<()> {
         println!("✅ 已清空进化日志");
     }
 
-    // Remove evolution.log JSONL
-    let log_path = root.join("evolution.log");
+    // Remove evolution.log JSONL and its rotated archives
+    let log_path = skilllite_evolution::retention::evolution_log_path(&root);
+    for (_, archive) in skilllite_evolution::retention::archive_paths(&log_path) {
+        std::fs::remove_file(&archive)?;
+    }
     if log_path.exists() {
         std::fs::remove_file(&log_path)?;
     }
@@ -308,6 +327,137 @@ pub fn cmd_reset(force: bool) -> Result<()> {
     Ok(())
 }
 
+/// Parse a retention period such as `90d`, `12w` or `48h` into whole days (bare number = days).
+fn parse_retention_days(raw: &str) -> Result<i64> {
+    let raw = raw.trim().to_ascii_lowercase();
+    let (num, unit_days) = if let Some(n) = raw.strip_suffix('d') {
+        (n, 1.0)
+    } else if let Some(n) = raw.strip_suffix('w') {
+        (n, 7.0)
+    } else if let Some(n) = raw.strip_suffix('h') {
+        (n, 1.0 / 24.0)
+    } else {
+        (raw.as_str(), 1.0)
+    };
+    match num.trim().parse::<u32>() {
+        Ok(n) => Ok((f64::from(n) * unit_days).floor() as i64),
+        Err(_) => bail!("无效的保留期: '{}'（示例: 90d、12w、48h）", raw),
+    }
+}
+
+/// `skilllite evolution gc --older-than 90d` — prune rotated logs, surplus snapshots and old
+/// `evolution_log` rows. The latest non-rolled-back snapshot is always kept.
+pub fn cmd_gc(older_than: &str) -> Result<()> {
+    let days = parse_retention_days(older_than)?;
+    let root = paths::chat_root();
+    let conn = skilllite_evolution::feedback::open_evolution_db(&root)?;
+    let keep = skilllite_evolution::snapshots::evolution_snapshot_keep_count();
+    let report = skilllite_evolution::retention::gc(&root, &conn, days, keep)?;
+
+    println!("🧹 进化数据清理 (保留 {} 天内)", days);
+    println!("  轮转日志归档: {} 个", report.archives_removed);
+    if keep == 0 {
+        println!("  快照: 未清理 (SKILLLITE_EVOLUTION_SNAPSHOT_KEEP=0)");
+    } else {
+        println!(
+            "  快照: {} 个 (保留最近 {} 个)",
+            report.snapshots_removed, keep
+        );
+    }
+    println!("  evolution_log 记录: {} 条", report.log_rows_removed);
+    if let Some(txn) = &report.protected_txn {
+        println!("  受保护快照: {}", txn);
+    }
+    println!(
+        "✅ 共释放 {}",
+        crate::env::format_size(report.bytes_reclaimed)
+    );
+    Ok(())
+}
+
+/// `skilllite evolution export` — decisions as a Markdown table or JSON array on stdout.
+pub fn cmd_export(format: &str, since: Option<&str>) -> Result<()> {
+    use skilllite_evolution::decisions_export::{load_decisions, render_table, DecisionFilter};
+
+    if let Some(since) = since {
+        if chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d").is_err() {
+            bail!("--since 需要 YYYY-MM-DD 格式的日期，收到 '{}'", since);
+        }
+    }
+    let conn = skilllite_evolution::feedback::open_evolution_db(&paths::chat_root())?;
+    let records = load_decisions(
+        &conn,
+        &DecisionFilter {
+            since: since.map(str::to_string),
+            ..Default::default()
+        },
+    )?;
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&records)?),
+        "md" => {
+            match since {
+                Some(since) => println!(
+                    "# Decisions since {}
+",
+                    since
+                ),
+                None => println!(
+                    "# Decisions
+"
+                ),
+            }
+            print!("{}", render_table(&records));
+        }
+        other => bail!("不支持的格式 '{}'，可选 md 或 json", other),
+    }
+    Ok(())
+}
+
+/// `skilllite evolution verify-audit` — check the hash chains of `evolution.log` and the
+/// audit log (`SKILLLITE_AUDIT_LOG`). Fails when either chain is broken.
+pub fn cmd_verify_audit(json: bool) -> Result<()> {
+    let root = paths::chat_root();
+    let evolution = skilllite_evolution::verify_evolution_log(&root);
+    let audit = skilllite_core::observability::verify_audit_log();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "evolution_log": { "status": evolution.status(), "report": &evolution },
+                "audit_log": { "status": audit.status(), "report": &audit },
+            }))?
+        );
+    } else {
+        println!("🔗 审计日志哈希链校验");
+        for (label, report) in [("evolution.log", &evolution), ("audit log", &audit)] {
+            println!(
+                "  {}: {} ({} 条已校验, {} 条旧格式, {} 个文件)",
+                label,
+                report.status(),
+                report.verified_entries,
+                report.legacy_entries,
+                report.files.len()
+            );
+            if let Some(b) = &report.first_break {
+                println!("    ❌ {}:{} — {}", b.file.display(), b.line, b.reason);
+            }
+        }
+    }
+
+    for (label, report) in [("evolution.log", &evolution), ("audit log", &audit)] {
+        if let Some(b) = &report.first_break {
+            bail!(
+                "{} hash chain broken at {}:{}",
+                label,
+                b.file.display(),
+                b.line
+            );
+        }
+    }
+    Ok(())
+}
+
 /// `skilllite evolution disable <rule_id>` — disable a specific evolved rule.
 pub fn cmd_disable(rule_id: &str) -> Result<()> {
     let root = paths::chat_root();
@@ -358,123 +508,348 @@ pub fn cmd_disable(rule_id: &str) -> Result<()> {
     Ok(())
 }
 
-/// `skilllite evolution explain <rule_id>` — show rule origin, history, effectiveness.
-pub fn cmd_explain(rule_id: &str) -> Result<()> {
-    let root = paths::chat_root();
+/// `skilllite evolution explain <id>` — decision trail for a rule or evolved skill.
+pub fn cmd_explain(target_id: &str, workspace: &str, json: bool) -> Result<()> {
+    use skilllite_evolution::explain::TargetKind;
 
-    // Load rule details
-    let rules_path = root.join("prompts").join("rules.json");
-    if !rules_path.exists() {
-        bail!("规则文件不存在: {}", rules_path.display());
+    let root = paths::chat_root();
+    let conn = skilllite_evolution::feedback::open_evolution_db(&root)?;
+    let skills_root = resolve_run_skills_root(workspace);
+    let skills_root = skills_root.is_dir().then_some(skills_root);
+    let Some(exp) =
+        skilllite_evolution::explain_target_in(&conn, &root, skills_root.as_deref(), target_id)?
+    else {
+        bail!(
+            "未找到规则或 Skill: '{}'\n提示: 使用 `skilllite evolution status` 查看所有规则",
+            target_id
+        );
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&exp)?);
+        return Ok(());
     }
 
-    let content = std::fs::read_to_string(&rules_path)?;
-    let rules: Vec<serde_json::Value> = serde_json::from_str(&content)?;
-
-    let rule = rules
-        .iter()
-        .find(|r| r.get("id").and_then(|v| v.as_str()) == Some(rule_id));
-
-    match rule {
-        Some(rule) => {
+    match exp.kind {
+        TargetKind::Rule => {
             println!("╭─────────────────────────────────────────────╮");
-            println!("│  规则详情: {:33} │", rule_id);
+            println!("│  规则详情: {:33} │", target_id);
             println!("╰─────────────────────────────────────────────╯");
             println!();
-
-            // rules.json uses "instruction" (seed + evolved), some schemas use description/condition/action
-            if let Some(inst) = rule.get("instruction").and_then(|v| v.as_str()) {
-                println!("规则: {}", inst);
-            }
-            if let Some(desc) = rule.get("description").and_then(|v| v.as_str()) {
-                println!("描述: {}", desc);
+            if let Some(rule) = &exp.rule {
+                print_rule_details(rule);
             }
-            if let Some(cond) = rule.get("condition").and_then(|v| v.as_str()) {
-                println!("条件: {}", cond);
-            }
-            if let Some(action) = rule.get("action").and_then(|v| v.as_str()) {
-                println!("动作: {}", action);
-            }
-            if let Some(th) = rule
-                .get("tool_hint")
-                .and_then(|v| v.as_str())
-                .filter(|s| !s.is_empty() && *s != "null")
-            {
-                println!("建议工具: {}", th);
-            }
-            if let Some(r) = rule.get("rationale").and_then(|v| v.as_str()) {
-                println!("依据: {}", r);
+        }
+        TargetKind::PendingRule => {
+            println!("⚖️  规则 {} 与现有规则冲突，尚未生效", target_id);
+            if let Some(conflict) = &exp.conflict {
+                println!();
+                println!("规则: {}", conflict.rule.instruction);
+                println!(
+                    "冲突对象: {} (相似度 {:.2}, 检测于 {})",
+                    conflict.conflicts_with, conflict.similarity, conflict.detected_at
+                );
+                println!("  现有规则: {}", conflict.conflicting_instruction);
+                let provenance = serde_json::to_value(&conflict.rule.provenance).ok();
+                print_rule_provenance(provenance.as_ref());
             }
+        }
+        TargetKind::Skill => println!("🧩 Skill: {}", target_id),
+        TargetKind::RemovedRule => {
+            println!("🗑️  规则 {} 已不在 rules.json 中 (已退役或移除)", target_id)
+        }
+    }
 
-            let mutable = rule
-                .get("mutable")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(true);
-            let reusable = rule
-                .get("reusable")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
-            let origin = rule
-                .get("origin")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown");
-            let priority = rule.get("priority").and_then(|v| v.as_u64()).unwrap_or(0);
-            let disabled = rule
-                .get("disabled")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
+    print_explanation_trail(&exp);
+    Ok(())
+}
 
-            println!();
-            println!("属性:");
-            println!("  来源: {}", origin);
-            println!("  优先级: {}", priority);
-            println!("  可变: {}", if mutable { "是" } else { "否 (种子规则)" });
-            println!("  通用: {}", if reusable { "是 ⬆️" } else { "否" });
-            if disabled {
-                println!("  状态: ⏸️ 已禁用");
-            }
+fn print_rule_details(rule: &serde_json::Value) {
+    // rules.json uses "instruction" (seed + evolved), some schemas use description/condition/action
+    if let Some(inst) = rule.get("instruction").and_then(|v| v.as_str()) {
+        println!("规则: {}", inst);
+    }
+    if let Some(desc) = rule.get("description").and_then(|v| v.as_str()) {
+        println!("描述: {}", desc);
+    }
+    if let Some(cond) = rule.get("condition").and_then(|v| v.as_str()) {
+        println!("条件: {}", cond);
+    }
+    if let Some(action) = rule.get("action").and_then(|v| v.as_str()) {
+        println!("动作: {}", action);
+    }
+    if let Some(th) = rule
+        .get("tool_hint")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty() && *s != "null")
+    {
+        println!("建议工具: {}", th);
+    }
+    if let Some(r) = rule.get("rationale").and_then(|v| v.as_str()) {
+        println!("依据: {}", r);
+    }
 
-            if let Some(eff) = rule.get("effectiveness").and_then(|v| v.as_f64()) {
-                println!("  效果评分: {:.2}", eff);
-            }
-            if let Some(tc) = rule.get("trigger_count").and_then(|v| v.as_u64()) {
-                println!("  触发次数: {}", tc);
-            }
+    let mutable = rule
+        .get("mutable")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let reusable = rule
+        .get("reusable")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let origin = rule
+        .get("origin")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let priority = rule.get("priority").and_then(|v| v.as_u64()).unwrap_or(0);
+    let disabled = rule
+        .get("disabled")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
 
-            // Evolution history from SQLite
-            let conn = skilllite_evolution::feedback::open_evolution_db(&root)?;
+    println!();
+    println!("属性:");
+    println!("  来源: {}", origin);
+    println!("  优先级: {}", priority);
+    println!("  可变: {}", if mutable { "是" } else { "否 (种子规则)" });
+    println!("  通用: {}", if reusable { "是 ⬆️" } else { "否" });
+    if disabled {
+        println!("  状态: ⏸️ 已禁用");
+    }
 
-            println!();
-            println!("进化历史:");
-            let history = skilllite_evolution::feedback::query_rule_history(&conn, rule_id)?;
-            if history.is_empty() {
-                println!("  (无进化历史 — 可能是种子规则)");
+    if let Some(eff) = rule.get("effectiveness").and_then(|v| v.as_f64()) {
+        println!("  效果评分: {:.2}", eff);
+    }
+    if let Some(tc) = rule.get("trigger_count").and_then(|v| v.as_u64()) {
+        println!("  触发次数: {}", tc);
+    }
+    print_rule_scope(rule.get("scope"));
+    print_rule_provenance(rule.get("provenance"));
+}
+
+fn print_rule_scope(scope: Option<&serde_json::Value>) {
+    let Some(s) = scope.filter(|v| v.is_object()) else {
+        println!("  适用范围: 全局");
+        return;
+    };
+    println!();
+    println!("适用范围:");
+    for (key, label) in [
+        ("workspace_glob", "工作区"),
+        ("language", "语言"),
+        ("requires_skill", "依赖技能"),
+    ] {
+        if let Some(v) = s.get(key).and_then(|v| v.as_str()) {
+            println!("  {}: {}", label, v);
+        }
+    }
+    let keywords: Vec<&str> = s
+        .get("task_keywords")
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|k| k.as_str()).collect())
+        .unwrap_or_default();
+    if !keywords.is_empty() {
+        println!("  任务关键词: {}", keywords.join(", "));
+    }
+}
+
+fn short_ts(ts: &str) -> &str {
+    &ts[..ts.char_indices().nth(16).map_or(ts.len(), |(i, _)| i)]
+}
+
+fn print_trail_event(ev: &skilllite_evolution::explain::TrailEvent) {
+    use skilllite_evolution::explain::EventSource;
+    let source = match ev.source {
+        EventSource::Database => "",
+        EventSource::LogFile => " (evolution.log)",
+        EventSource::Changelog => " (changelog)",
+    };
+    let txn = if ev.txn_id.is_empty() {
+        "-"
+    } else {
+        &ev.txn_id
+    };
+    println!(
+        "  {} {} [{}] {}{}",
+        short_ts(&ev.ts),
+        ev.event_type,
+        txn,
+        ev.reason,
+        source
+    );
+}
+
+/// Trail sections shared by rules and skills; every missing piece is listed under 缺失数据.
+fn print_explanation_trail(exp: &skilllite_evolution::Explanation) {
+    use skilllite_evolution::explain::{DecisionLink, SkillLocation};
+
+    println!();
+    println!("引入:");
+    match &exp.introduced {
+        Some(ev) => print_trail_event(ev),
+        None => println!("  (未找到引入事件)"),
+    }
+
+    if let Some(scope) = &exp.introducing_txn {
+        println!();
+        let link = match scope.link {
+            DecisionLink::Recorded => "已记录",
+            DecisionLink::Inferred => "按时间推断",
+        };
+        println!("引入事务 {} 的决策范围 ({}):", scope.txn_id, link);
+        if let Some(reason) = &scope.changelog_reason {
+            println!("  变更说明: {}", reason);
+        }
+        if !scope.changelog_files.is_empty() {
+            println!("  变更文件: {}", scope.changelog_files.join(", "));
+        }
+        if let Some(eval) = &scope.evaluation {
+            let verdict = if eval.promoted() {
+                "已提升"
             } else {
-                for entry in &history {
-                    let date = &entry.ts[..std::cmp::min(16, entry.ts.len())];
-                    println!(
-                        "  {} {} [{}] {}",
-                        date, entry.event_type, entry.txn_id, entry.reason
-                    );
-                }
+                "已拒绝"
+            };
+            println!(
+                "  留出评估 ({}): {} — 原规则 {:.2} / 候选 {:.2}，重放 {} 条，未受影响 {} 条",
+                eval.mode,
+                verdict,
+                eval.baseline_score,
+                eval.candidate_score,
+                eval.replayed,
+                eval.unaffected
+            );
+            for s in &eval.samples {
+                println!(
+                    "    · {} ({:.2} → {:.2})",
+                    truncate_chars(&s.task, 60),
+                    s.baseline_score,
+                    s.candidate_score
+                );
+                println!(
+                    "      原: {}",
+                    truncate_chars(&s.baseline_plan.replace('\n', " "), 100)
+                );
+                println!(
+                    "      新: {}",
+                    truncate_chars(&s.candidate_plan.replace('\n', " "), 100)
+                );
             }
+        }
+        for d in &scope.decisions {
+            let outcome = if d.task_completed { "✅" } else { "❌" };
+            println!(
+                "  #{} {} {} [{}{}] {}",
+                d.id,
+                short_ts(&d.ts),
+                outcome,
+                d.state,
+                d.feedback
+                    .as_deref()
+                    .map(|f| format!(", {}", f))
+                    .unwrap_or_default(),
+                truncate_chars(d.task_description.as_deref().unwrap_or("(无任务描述)"), 60)
+            );
+        }
+        if scope.omitted > 0 {
+            println!("  … 另有 {} 条决策", scope.omitted);
+        }
+    }
 
-            // Effectiveness from decisions
-            let eff = skilllite_evolution::feedback::compute_effectiveness(&conn, rule_id)?;
-            if eff >= 0.0 {
-                println!();
-                println!("实测效果: {:.0}% (基于关联决策计算)", eff * 100.0);
-            }
+    let later: Vec<_> = exp
+        .history
+        .iter()
+        .filter(|e| Some(*e) != exp.introduced.as_ref())
+        .collect();
+    println!();
+    println!("后续变更:");
+    if later.is_empty() {
+        println!("  (无)");
+    }
+    for ev in later {
+        print_trail_event(ev);
+    }
+
+    if let Some(usage) = &exp.usage {
+        println!();
+        println!("使用情况:");
+        println!(
+            "  近 30 天匹配 {} 次, 成功 {} 次 (累计匹配 {} 次)",
+            usage.matched_30d, usage.succeeded_30d, usage.matched_total
+        );
+        match usage.effectiveness {
+            Some(eff) => println!("  实测效果: {:.0}% (基于关联决策计算)", eff * 100.0),
+            None => println!("  实测效果: 样本不足 (近 30 天少于 3 条关联决策)"),
         }
-        None => {
-            bail!(
-                "未找到规则: '{}'\n提示: 使用 `skilllite evolution status` 查看所有规则",
-                rule_id
+        if let Some(last) = &usage.last_matched {
+            println!("  最近匹配: {}", short_ts(last));
+        }
+        if let (Some(recent), Some(total)) = (usage.in_scope_30d, usage.in_scope_total) {
+            println!("  处于适用范围: 近 30 天 {} 次 (累计 {} 次)", recent, total);
+        }
+    }
+
+    if let Some(skill) = &exp.skill {
+        println!();
+        println!("Skill 状态:");
+        let location = match skill.location {
+            SkillLocation::Pending => "待确认 (_evolved/_pending)",
+            SkillLocation::Confirmed => "已确认 (_evolved)",
+            SkillLocation::Archived => "已归档",
+            SkillLocation::Missing => "不存在 (已拒绝或退役)",
+            SkillLocation::NotChecked => "未检查 (未找到 skills 目录)",
+        };
+        println!("  位置: {}", location);
+        println!(
+            "  生成事务: {}",
+            skill.generation_txn.as_deref().unwrap_or("未知")
+        );
+        println!(
+            "  安全扫描: {}{}",
+            skill.scan_status.as_deref().unwrap_or("未知"),
+            if skill.needs_review {
+                " (需人工审核)"
+            } else {
+                ""
+            }
+        );
+        if skill.location != SkillLocation::NotChecked && skill.location != SkillLocation::Missing {
+            println!(
+                "  调用: {} 次 (成功 {}, 失败 {}), 最近使用: {}",
+                skill.call_count,
+                skill.success_count,
+                skill.failure_count,
+                skill.last_used.as_deref().map(short_ts).unwrap_or("从未")
             );
         }
+        println!("  审核记录:");
+        if skill.reviews.is_empty() {
+            println!("    (无确认/拒绝记录)");
+        }
+        for ev in &skill.reviews {
+            print!("  ");
+            print_trail_event(ev);
+        }
     }
 
-    Ok(())
+    if !exp.gaps.is_empty() {
+        println!();
+        println!("缺失数据:");
+        for gap in &exp.gaps {
+            println!("  ⚠️  {}", gap.describe());
+        }
+    }
+}
+
+fn print_rule_provenance(provenance: Option<&serde_json::Value>) {
+    let Some(p) = provenance.filter(|v| v.is_object()) else {
+        return;
+    };
+    let field = |k: &str| p.get(k).and_then(|v| v.as_str()).unwrap_or("-");
+    println!();
+    println!("外部来源:");
+    println!("  信源: {}", field("source_id"));
+    println!("  URL: {}", field("source_url"));
+    println!("  抓取时间: {}", field("fetched_at"));
+    println!("  内容哈希: {}", field("content_hash"));
 }
 
 /// `skilllite evolution confirm <skill_name>` — move pending skill to confirmed (A10).
@@ -591,7 +966,7 @@ pub fn cmd_run(
         bail!("API key required. Set OPENAI_API_KEY env var.");
     }
 
-    let llm = skilllite_agent::llm::LlmClient::new(&config.api_base, &config.api_key)?;
+    let llm = skilllite_agent::llm::LlmClient::from_config(&config)?;
     let adapter = skilllite_agent::evolution::EvolutionLlmAdapter { llm: &llm };
 
     let rt = tokio::runtime::Runtime::new().context("tokio runtime init failed")?;
@@ -786,7 +1161,7 @@ pub fn cmd_repair_skills(skills_filter: Option<Vec<String>>, from_source: bool)
         bail!("API key required. Set OPENAI_API_KEY or SKILLLITE_API_KEY env var.");
     }
 
-    let llm = skilllite_agent::llm::LlmClient::new(&config.api_base, &config.api_key)?;
+    let llm = skilllite_agent::llm::LlmClient::from_config(&config)?;
     let adapter = skilllite_agent::evolution::EvolutionLlmAdapter { llm: &llm };
 
     let rt = tokio::runtime::Runtime::new().context("tokio runtime init failed")?;
@@ -1019,6 +1394,15 @@ pub fn cmd_repair_skills(skills_filter: Option<Vec<String>>, from_source: bool)
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_retention_days_accepts_units() {
+        assert_eq!(parse_retention_days("90d").expect("days"), 90);
+        assert_eq!(parse_retention_days("2w").expect("weeks"), 14);
+        assert_eq!(parse_retention_days("48h").expect("hours"), 2);
+        assert_eq!(parse_retention_days("30").expect("bare"), 30);
+        assert!(parse_retention_days("soon").is_err());
+    }
+
     #[test]
     fn normalize_filters_validate_allowed_values() {
         assert_eq!(
@@ -154,42 +154,82 @@ pub fn list_pending_skills(workspace: &str) -> Result<Vec<PendingSkillSnapshot>>
     )
 }
 
-pub fn read_pending_skill_md(workspace: &str, skill_name: &str) -> Result<String> {
+/// Directory of a pending evolved skill (`<skills>/_evolved/_pending/<name>`); errors when absent.
+pub fn pending_skill_dir(workspace: &str, skill_name: &str) -> Result<PathBuf> {
     let skills_root = resolve_skills_root(workspace)?;
-    let path = skills_root
+    let dir = skills_root
         .join("_evolved")
         .join("_pending")
-        .join(skill_name)
-        .join("SKILL.md");
-    if !path.is_file() {
+        .join(skill_name);
+    if skill_name.contains(['/', '\\']) || !dir.join("SKILL.md").is_file() {
         return Err(crate::Error::validation(format!(
             "pending skill not found: {}",
             skill_name
         )));
     }
+    Ok(dir)
+}
+
+pub fn read_pending_skill_md(workspace: &str, skill_name: &str) -> Result<String> {
+    let path = pending_skill_dir(workspace, skill_name)?.join("SKILL.md");
     std::fs::read_to_string(&path).map_err(Into::into)
 }
 
-pub fn confirm_pending_skill(workspace: &str, skill_name: &str) -> Result<()> {
-    let skills_root = resolve_skills_root(workspace)?;
-    skilllite_evolution::skill_synth::confirm_pending_skill(&skills_root, skill_name)?;
+/// `generation_txn` of a pending skill, read before confirm/reject moves or deletes it.
+fn pending_generation_txn(skills_root: &std::path::Path, skill_name: &str) -> String {
+    let meta_path = skills_root
+        .join("_evolved")
+        .join("_pending")
+        .join(skill_name)
+        .join(".meta.json");
+    std::fs::read_to_string(meta_path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<skilllite_evolution::skill_synth::SkillMeta>(&s).ok())
+        .map(|m| m.generation_txn)
+        .unwrap_or_default()
+}
+
+/// Audit a confirm/reject so `evolution explain` can show the review history. Logged without a
+/// txn id so rolling back the generating txn does not rewrite the user's decision.
+fn log_skill_review(workspace: &str, event_type: &str, skill_name: &str, reason: &str, txn: &str) {
+    let reason = if txn.is_empty() {
+        reason.to_string()
+    } else {
+        format!("{}; generated in {}", reason, txn)
+    };
     let chat_root = chat_root_for_workspace(workspace);
     if let Ok(conn) = skilllite_evolution::feedback::open_evolution_db(&chat_root) {
         let _ = skilllite_evolution::log_evolution_event(
-            &conn,
-            &chat_root,
-            "skill_confirmed",
-            skill_name,
-            "user confirmed (assistant)",
-            "",
+            &conn, &chat_root, event_type, skill_name, &reason, "",
         );
     }
+}
+
+pub fn confirm_pending_skill(workspace: &str, skill_name: &str) -> Result<()> {
+    let skills_root = resolve_skills_root(workspace)?;
+    let txn = pending_generation_txn(&skills_root, skill_name);
+    skilllite_evolution::skill_synth::confirm_pending_skill(&skills_root, skill_name)?;
+    log_skill_review(
+        workspace,
+        "skill_confirmed",
+        skill_name,
+        "user confirmed (assistant)",
+        &txn,
+    );
     Ok(())
 }
 
 pub fn reject_pending_skill(workspace: &str, skill_name: &str) -> Result<()> {
     let skills_root = resolve_skills_root(workspace)?;
+    let txn = pending_generation_txn(&skills_root, skill_name);
     skilllite_evolution::skill_synth::reject_pending_skill(&skills_root, skill_name)?;
+    log_skill_review(
+        workspace,
+        "skill_rejected",
+        skill_name,
+        "user rejected (assistant)",
+        &txn,
+    );
     Ok(())
 }
 
@@ -8,6 +8,8 @@ use serde::Serialize;
 use skilllite_core::config::env_keys::evolution as evo_env;
 use skilllite_core::skill::discovery::resolve_skills_dir_with_legacy_fallback;
 use skilllite_evolution::growth_schedule::GrowthScheduleConfig;
+use skilllite_evolution::rule_conflict::{load_pending_conflicts, PendingRuleConflict};
+use skilllite_evolution::skill_synth::{list_pending_skills, pending_stale_days, PendingSkill};
 use skilllite_evolution::{GrowthDueDiagnostics, PassiveScheduleDiagnostics};
 
 use crate::Result;
@@ -23,6 +25,34 @@ pub struct EvolutionLogEntrySnapshot {
     pub txn_id: Option<String>,
 }
 
+/// Per-source fetch stats from the last external learning run (EVO-6).
+#[derive(Debug, Clone, Serialize)]
+pub struct ExternalSourceStatus {
+    pub id: String,
+    pub enabled: bool,
+    pub quality_score: f32,
+    pub accessibility_score: f32,
+    pub budget_exceeded_streak: u32,
+    pub last_fetch: skilllite_core::planning::SourceFetchStats,
+}
+
+fn external_source_statuses(chat_root: &Path) -> Vec<ExternalSourceStatus> {
+    skilllite_evolution::seed::load_sources(chat_root)
+        .sources
+        .into_iter()
+        .filter_map(|s| {
+            Some(ExternalSourceStatus {
+                last_fetch: s.last_fetch?,
+                id: s.id,
+                enabled: s.enabled,
+                quality_score: s.quality_score,
+                accessibility_score: s.accessibility_score,
+                budget_exceeded_streak: s.budget_exceeded_streak,
+            })
+        })
+        .collect()
+}
+
 /// Desktop-compatible evolution status payload (see `ASSISTANT-SPLIT-ARCHITECTURE.md` §5.2).
 #[derive(Debug, Clone, Serialize)]
 pub struct EvolutionStatusSnapshot {
@@ -36,6 +66,8 @@ pub struct EvolutionStatusSnapshot {
     pub evo_profile_key: String,
     pub evo_cooldown_hours: f64,
     pub unprocessed_decisions: i64,
+    /// Pending decisions that aged out before any run read them (`evolved = 2`).
+    pub expired_decisions: i64,
     pub last_run_ts: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_material_run_ts: Option<String>,
@@ -43,6 +75,9 @@ pub struct EvolutionStatusSnapshot {
     pub judgement_reason: Option<String>,
     pub recent_events: Vec<EvolutionLogEntrySnapshot>,
     pub pending_skill_count: usize,
+    /// Review data for each pending skill (`_evolved/_pending`), oldest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pending_skills: Vec<PendingSkill>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub a9: Option<GrowthDueDiagnostics>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -50,6 +85,20 @@ pub struct EvolutionStatusSnapshot {
     pub would_have_evolution_proposals: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub empty_proposals_reason: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub external_sources: Vec<ExternalSourceStatus>,
+    /// External rules held back because they contradict an existing rule.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pending_rule_conflicts: Vec<PendingRuleConflict>,
+    /// `feedback.sqlite` schema version (`PRAGMA user_version`) after migration on open.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<i64>,
+    /// Interrupted txns settled by the write-ahead journal (`recovered_txn` events), newest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recovered_txns: Vec<EvolutionLogEntrySnapshot>,
+    /// Txns whose journal never committed; the next evolution cycle recovers them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pending_recovery: Vec<String>,
     pub db_error: Option<String>,
 }
 
@@ -145,6 +194,35 @@ fn existing_workspace_skills_root(workspace_root: &Path) -> Option<PathBuf> {
     skills_root.is_dir().then_some(skills_root)
 }
 
+/// Pending evolved skills of the workspace; the confirm / reject commands carry `-w` unless
+/// the workspace is the current directory.
+fn pending_skills_for_workspace(workspace_root: &Path, workspace: &str) -> Vec<PendingSkill> {
+    let Some(skills_root) = existing_workspace_skills_root(workspace_root) else {
+        return Vec::new();
+    };
+    let mut skills = list_pending_skills(&skills_root);
+    let workspace = workspace.trim();
+    if !workspace.is_empty() && workspace != "." {
+        for skill in &mut skills {
+            for cmd in [&mut skill.confirm_command, &mut skill.reject_command] {
+                cmd.push_str(&format!(" -w {}", shell_quote(workspace)));
+            }
+        }
+    }
+    skills
+}
+
+fn shell_quote(arg: &str) -> String {
+    if arg
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "/._-~".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
 /// Build evolution status snapshot (workspace `.env` + process env; no desktop UI overrides).
 pub fn build_evolution_status_snapshot(params: &EvolutionStatusParams) -> EvolutionStatusSnapshot {
     let workspace_root = resolve_workspace_root(&params.workspace);
@@ -154,15 +232,13 @@ pub fn build_evolution_status_snapshot(params: &EvolutionStatusParams) -> Evolut
     let (mode_key, mode_label) = evolution_mode_labels(&mode);
     let schedule_cfg = growth_schedule_for_workspace(&workspace_root);
 
-    let mut pending_skill_count = 0;
-    if let Some(skills_root) = existing_workspace_skills_root(&workspace_root) {
-        pending_skill_count =
-            skilllite_evolution::skill_synth::list_pending_skills_with_review(&skills_root).len();
-    }
+    let pending_skills = pending_skills_for_workspace(&workspace_root, &params.workspace);
+    let pending_skill_count = pending_skills.len();
 
     let chat_root = workspace_root.join("chat");
     let mut db_error = None;
     let mut unprocessed_decisions = 0i64;
+    let mut expired_decisions = 0i64;
     let mut weighted_signal_sum = 0i64;
     let mut recent_events = Vec::new();
     let mut last_run_ts = None;
@@ -173,12 +249,18 @@ pub fn build_evolution_status_snapshot(params: &EvolutionStatusParams) -> Evolut
     let mut passive = None;
     let mut would_have_evolution_proposals = false;
     let mut empty_proposals_reason = None;
+    let mut schema_version = None;
+    let mut recovered_txns = Vec::new();
 
     match skilllite_evolution::feedback::open_evolution_db(&chat_root) {
         Ok(conn) => {
+            schema_version = skilllite_evolution::schema::schema_version(&conn).ok();
             if let Ok(c) = skilllite_evolution::feedback::count_unprocessed_decisions(&conn) {
                 unprocessed_decisions = c;
             }
+            if let Ok(c) = skilllite_evolution::feedback::count_expired_decisions(&conn) {
+                expired_decisions = c;
+            }
             if let Ok(w) = skilllite_evolution::growth_schedule::weighted_unprocessed_signal_sum(
                 &conn,
                 schedule_cfg.signal_window,
@@ -255,6 +337,18 @@ pub fn build_evolution_status_snapshot(params: &EvolutionStatusParams) -> Evolut
                     recent_events.extend(rows.flatten());
                 }
             }
+            recovered_txns = skilllite_evolution::journal::recent_recoveries(&conn, 5)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|r| EvolutionLogEntrySnapshot {
+                    ts: r.ts,
+                    event_type: skilllite_evolution::journal::EVOLUTION_LOG_TYPE_RECOVERED_TXN
+                        .to_string(),
+                    target_id: Some(r.txn_id.clone()),
+                    reason: Some(r.reason),
+                    txn_id: Some(r.txn_id),
+                })
+                .collect();
         }
         Err(e) => {
             db_error = Some(format!("cannot open evolution database: {}", e));
@@ -272,22 +366,62 @@ pub fn build_evolution_status_snapshot(params: &EvolutionStatusParams) -> Evolut
         evo_profile_key: effective_evo_profile_key(&workspace_root).to_string(),
         evo_cooldown_hours: effective_evo_cooldown_hours(&workspace_root),
         unprocessed_decisions,
+        expired_decisions,
         last_run_ts,
         last_material_run_ts,
         judgement_label,
         judgement_reason,
         recent_events,
         pending_skill_count,
+        pending_skills,
         a9,
         passive,
         would_have_evolution_proposals,
         empty_proposals_reason,
+        external_sources: external_source_statuses(&chat_root),
+        pending_rule_conflicts: load_pending_conflicts(&chat_root),
+        schema_version,
+        recovered_txns,
+        pending_recovery: pending_recovery_txns(&chat_root),
         db_error,
     }
 }
 
-/// `skilllite evolution status` — human table or JSON snapshot.
-pub fn cmd_status(json: bool, workspace: &str, periodic_anchor_unix: Option<i64>) -> Result<()> {
+fn pending_recovery_txns(chat_root: &Path) -> Vec<String> {
+    skilllite_evolution::journal::list_uncommitted(chat_root)
+        .into_iter()
+        .map(|j| format!("{} ({})", j.txn_id, j.phase.as_str()))
+        .collect()
+}
+
+/// `skilllite evolution status` — human table or JSON snapshot; `tools` switches to the
+/// per-tool stats view, `pending_only` to the pending-skill review list, `breakdown` to
+/// per-workspace / per-skill daily rates.
+pub fn cmd_status(
+    json: bool,
+    workspace: &str,
+    periodic_anchor_unix: Option<i64>,
+    tools: bool,
+    pending_only: bool,
+    breakdown: bool,
+) -> Result<()> {
+    if tools {
+        return cmd_status_tools(json, workspace);
+    }
+    if breakdown {
+        return cmd_status_breakdown(json, workspace);
+    }
+    if pending_only {
+        let workspace_root = resolve_workspace_root(workspace);
+        skilllite_core::config::load_dotenv_from_dir(&workspace_root);
+        let skills = pending_skills_for_workspace(&workspace_root, workspace);
+        if json {
+            println!("{}", serde_json::to_string_pretty(&skills)?);
+        } else {
+            print_pending_skills(&skills);
+        }
+        return Ok(());
+    }
     if json {
         let snapshot = build_evolution_status_snapshot(&EvolutionStatusParams {
             workspace: workspace.to_string(),
@@ -299,6 +433,222 @@ pub fn cmd_status(json: bool, workspace: &str, periodic_anchor_unix: Option<i64>
     cmd_status_human(workspace)
 }
 
+fn print_pending_skills(skills: &[PendingSkill]) {
+    println!("🆕 待确认技能 ({})", skills.len());
+    if skills.is_empty() {
+        println!("  (无)");
+        println!();
+        return;
+    }
+    for s in skills {
+        let created = s
+            .created_at
+            .as_deref()
+            .map(|t| &t[..t.len().min(16)])
+            .unwrap_or("未知");
+        println!(
+            "  {}  生成于 {}  事务 {}{}",
+            s.name,
+            created,
+            s.generation_txn.as_deref().unwrap_or("未知"),
+            if s.stale {
+                format!(
+                    "  ⌛ 已等待 {} 天，建议确认或清理",
+                    s.age_days.unwrap_or_default()
+                )
+            } else {
+                String::new()
+            }
+        );
+        for line in &s.excerpt {
+            println!("     │ {}", line);
+        }
+        println!(
+            "     入口: {} ({})",
+            if s.entry_point.is_empty() {
+                "-"
+            } else {
+                &s.entry_point
+            },
+            s.language
+        );
+        let network = match (s.network, s.network_domains.is_empty()) {
+            (false, _) => "无".to_string(),
+            (true, true) => "任意 HTTP/HTTPS".to_string(),
+            (true, false) => s.network_domains.join(", "),
+        };
+        println!("     网络: {}", network);
+        match &s.scan_status {
+            Some(status) => println!(
+                "     安全扫描: {}{}",
+                status,
+                if s.needs_review {
+                    " (需人工审核)"
+                } else {
+                    ""
+                }
+            ),
+            None => {
+                println!("     安全扫描: 无记录，文件:");
+                for f in &s.files {
+                    println!("       {} ({}B)", f.path, f.bytes);
+                }
+            }
+        }
+        println!("     确认: {}", s.confirm_command);
+        println!("     拒绝: {}", s.reject_command);
+    }
+    let stale_days = pending_stale_days();
+    if stale_days > 0 && skills.iter().any(|s| s.stale) {
+        println!(
+            "  (⌛ 超过 {} 天未处理，SKILLLITE_EVO_PENDING_STALE_DAYS 可调整)",
+            stale_days
+        );
+    }
+    println!();
+}
+
+/// Days covered by `evolution status --tools`.
+const TOOL_STATS_DAYS: u32 = 7;
+
+/// JSON shape of `evolution status --tools --json`.
+#[derive(Debug, Serialize)]
+struct ToolStatusReport {
+    days: u32,
+    slowest: Vec<skilllite_evolution::feedback::ToolStat>,
+    failure_prone: Vec<skilllite_evolution::feedback::ToolStat>,
+    failure_patterns: Vec<skilllite_evolution::feedback::ToolFailurePattern>,
+}
+
+fn cmd_status_tools(json: bool, workspace: &str) -> Result<()> {
+    use skilllite_evolution::feedback::{self, ToolStatOrder};
+
+    let workspace_root = resolve_workspace_root(workspace);
+    skilllite_core::config::load_dotenv_from_dir(&workspace_root);
+    let conn = feedback::open_evolution_db(&workspace_root.join("chat"))?;
+    let _ = feedback::update_daily_metrics(&conn);
+    let report = ToolStatusReport {
+        days: TOOL_STATS_DAYS,
+        slowest: feedback::tool_stats(&conn, TOOL_STATS_DAYS, 1, ToolStatOrder::Slowest, 10)?,
+        failure_prone: feedback::tool_stats(
+            &conn,
+            TOOL_STATS_DAYS,
+            3,
+            ToolStatOrder::FailureRate,
+            10,
+        )?
+        .into_iter()
+        .filter(|s| s.failures > 0)
+        .collect(),
+        failure_patterns: feedback::failure_correlated_tool_patterns(&conn, 3, 10)?,
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("🐢 最慢的工具 (最近 {} 天)", report.days);
+    if report.slowest.is_empty() {
+        println!("  (暂无数据 — 需要更多使用后才会出现)");
+    }
+    for s in &report.slowest {
+        println!(
+            "  {:24} 平均 {:>7.0}ms  最长 {:>7}ms  调用 {:>4}  平均结果 {:>7.0}B",
+            s.tool, s.avg_duration_ms, s.max_duration_ms, s.calls, s.avg_result_bytes
+        );
+    }
+    println!();
+
+    println!("❌ 失败率最高的工具 (≥3 次调用)");
+    if report.failure_prone.is_empty() {
+        println!("  (无)");
+    }
+    for s in &report.failure_prone {
+        println!(
+            "  {:24} 失败率 {:>4.0}%  ({}/{})",
+            s.tool,
+            s.failure_rate * 100.0,
+            s.failures,
+            s.calls
+        );
+    }
+    println!();
+
+    println!("🔍 与任务失败相关的调用模式");
+    if report.failure_patterns.is_empty() {
+        println!("  (无)");
+    }
+    for p in &report.failure_patterns {
+        println!(
+            "  {:24} 参数 [{}]  任务失败率 {:>4.0}%  调用 {}  工具报错 {}",
+            p.tool,
+            p.args_present.join(", "),
+            p.failure_rate * 100.0,
+            p.calls,
+            p.tool_failures
+        );
+    }
+    Ok(())
+}
+
+/// JSON shape of `evolution status --breakdown --json`.
+#[derive(Debug, Serialize)]
+struct BreakdownStatusReport {
+    days: u32,
+    rows: Vec<skilllite_evolution::metrics_breakdown::BreakdownRow>,
+}
+
+fn cmd_status_breakdown(json: bool, workspace: &str) -> Result<()> {
+    use skilllite_evolution::feedback;
+    use skilllite_evolution::metrics_breakdown::{self, DIMENSION_SKILL, DIMENSION_WORKSPACE};
+
+    let workspace_root = resolve_workspace_root(workspace);
+    skilllite_core::config::load_dotenv_from_dir(&workspace_root);
+    let conn = feedback::open_evolution_db(&workspace_root.join("chat"))?;
+    let _ = feedback::update_daily_metrics(&conn);
+    let report = BreakdownStatusReport {
+        days: TOOL_STATS_DAYS,
+        rows: metrics_breakdown::query_breakdown(&conn, TOOL_STATS_DAYS)?,
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    for (dimension, title) in [
+        (DIMENSION_WORKSPACE, "🗂️  按工作区"),
+        (DIMENSION_SKILL, "🧩 按技能"),
+    ] {
+        println!("{} (最近 {} 天)", title, report.days);
+        let rows: Vec<_> = report
+            .rows
+            .iter()
+            .filter(|r| r.dimension == dimension)
+            .collect();
+        if rows.is_empty() {
+            println!("  (暂无数据 — 需要更多使用后才会出现)");
+        }
+        for r in rows {
+            println!(
+                "  {}  {:24} 决策 {:>4}  首次成功率 {:>4.0}%  纠正率 {:>4.0}%  工具失败 {}/{}",
+                r.date,
+                if dimension == DIMENSION_WORKSPACE {
+                    format!("{} ({})", r.label, r.key)
+                } else {
+                    r.key.clone()
+                },
+                r.decisions,
+                r.first_success_rate * 100.0,
+                r.correction_rate * 100.0,
+                r.tool_failures,
+                r.tool_calls
+            );
+        }
+        println!();
+    }
+    Ok(())
+}
+
 fn cmd_status_human(workspace: &str) -> Result<()> {
     let workspace_root = resolve_workspace_root(workspace);
     skilllite_core::config::load_dotenv_from_dir(&workspace_root);
@@ -313,6 +663,11 @@ fn cmd_status_human(workspace: &str) -> Result<()> {
 
     let (_, mode_label) = evolution_mode_labels(&mode);
     println!("进化模式: {}", mode_label);
+    println!(
+        "数据库 schema: v{} (当前版本 v{})",
+        skilllite_evolution::schema::schema_version(&conn)?,
+        skilllite_evolution::schema::SCHEMA_VERSION
+    );
     println!();
 
     println!("📈 核心指标趋势 (最近 7 天)");
@@ -368,6 +723,37 @@ fn cmd_status_human(workspace: &str) -> Result<()> {
     }
     println!();
 
+    let unprocessed =
+        skilllite_evolution::feedback::count_unprocessed_decisions(&conn).unwrap_or(0);
+    let expired = skilllite_evolution::feedback::count_expired_decisions(&conn).unwrap_or(0);
+    println!(
+        "🗂️  决策队列: 待处理 {} · 已过期 {} (超过 {} 天未进化)",
+        unprocessed,
+        expired,
+        skilllite_evolution::EvolutionThresholds::from_env().decision_max_age_days
+    );
+    println!();
+
+    print_pending_skills(&pending_skills_for_workspace(&workspace_root, workspace));
+
+    let recovered = skilllite_evolution::journal::recent_recoveries(&conn, 5)?;
+    let pending_recovery = pending_recovery_txns(&root);
+    if !recovered.is_empty() || !pending_recovery.is_empty() {
+        println!("🩹 中断恢复");
+        for r in &recovered {
+            println!(
+                "  {} {} {}",
+                &r.ts[..std::cmp::min(16, r.ts.len())],
+                r.txn_id,
+                r.reason
+            );
+        }
+        for txn in &pending_recovery {
+            println!("  ⏳ {} 未提交，将在下次进化开始时恢复", txn);
+        }
+        println!();
+    }
+
     println!("📜 最近进化事件");
     let mut stmt = conn
         .prepare(
@@ -401,6 +787,10 @@ fn cmd_status_human(workspace: &str) -> Result<()> {
             "skill_refined" => "🔧",
             "evolution_judgement" => "🧭",
             "auto_rollback" => "⚠️ ",
+            "skill_flagged" => "🚩",
+            "decisions_expired" => "⌛",
+            "recovered_txn" => "🩹",
+            "skill_removed" => "🧹",
             t if t.contains("retired") => "🗑️ ",
             t if t.contains("rolled_back") => "🔙",
             _ => "  ",
@@ -421,6 +811,44 @@ fn cmd_status_human(workspace: &str) -> Result<()> {
     }
     println!();
 
+    let conflicts = load_pending_conflicts(&root);
+    if !conflicts.is_empty() {
+        println!("⚖️  待审核的冲突外部规则");
+        for c in &conflicts {
+            println!(
+                "  {} ↔ {} (相似度 {:.2})",
+                c.rule.id, c.conflicts_with, c.similarity
+            );
+            println!("     新规则: {}", c.rule.instruction);
+            println!("     现有规则: {}", c.conflicting_instruction);
+        }
+        println!("  (使用 `skilllite evolution explain <rule_id>` 查看来源)");
+        println!();
+    }
+
+    let sources = external_source_statuses(&root);
+    if !sources.is_empty() {
+        println!("🌐 外部信源 (上次抓取)");
+        for s in &sources {
+            let f = &s.last_fetch;
+            println!(
+                "  {:24} {:20} {:>8}B {:>3} 条 {:>6}ms{}{}",
+                s.id,
+                f.outcome,
+                f.bytes,
+                f.items,
+                f.duration_ms,
+                if f.truncated {
+                    "  ⚠️ 超出字节预算"
+                } else {
+                    ""
+                },
+                if s.enabled { "" } else { "  ⏸️ 已暂停" }
+            );
+        }
+        println!();
+    }
+
     Ok(())
 }
 
@@ -516,16 +944,23 @@ mod tests {
             evo_profile_key: "default".into(),
             evo_cooldown_hours: 24.0,
             unprocessed_decisions: 0,
+            expired_decisions: 0,
             last_run_ts: None,
             last_material_run_ts: None,
             judgement_label: None,
             judgement_reason: None,
             recent_events: vec![],
             pending_skill_count: 0,
+            pending_skills: vec![],
             a9: None,
             passive: None,
             would_have_evolution_proposals: false,
             empty_proposals_reason: None,
+            external_sources: vec![],
+            pending_rule_conflicts: vec![],
+            schema_version: None,
+            recovered_txns: vec![],
+            pending_recovery: vec![],
             db_error: None,
         };
         let v = serde_json::to_value(&snap).expect("serialize");
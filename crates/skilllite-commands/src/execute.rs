@@ -5,25 +5,28 @@
 use anyhow::Context;
 use serde_json::json;
 use skilllite_core::config::supply_chain_block_enabled;
-use skilllite_core::path_validation::validate_skill_path;
+use skilllite_core::error::PathValidationError;
+use skilllite_core::path_validation::{resolve_under_roots, validate_skill_path, PathPolicy};
 use skilllite_core::skill;
 use skilllite_core::skill::manifest::{self, SkillIntegrityStatus};
+use skilllite_core::skill::origin::SkillSandboxPolicy;
 use skilllite_core::skill::trust::TrustDecision;
-use skilllite_sandbox::runner::SandboxConfig;
-use std::path::Path;
-use std::sync::Mutex;
+use skilllite_sandbox::env::interpreters::{self, RuntimeChoice};
+use skilllite_sandbox::results::SkillResult;
+use skilllite_sandbox::runner::{SandboxConfig, SandboxLevel, SandboxRunOptions};
+use std::path::{Path, PathBuf};
 
 use crate::error::bail;
+use crate::result_cache;
 use crate::Result;
 
-/// Mutex for exec_script: it uses process-global SKILLLITE_SCRIPT_ARGS env var,
-/// so concurrent exec calls must be serialized. run and bash do not need this.
-static EXEC_ENV_MUTEX: Mutex<()> = Mutex::new(());
-
-use skilllite_core::config::ScopedEnvGuard;
-
 /// Run a skill with the given input.
 /// When `entry_point_override` is `Some`, use it instead of metadata.entry_point (e.g. 大模型根据 SKILL.md 推理出的入口).
+/// When `entry` is `Some`, run that named command from SKILL.md `entry_points` (takes precedence).
+/// When `audit` is true, the skill runs unconfined and the output carries a `sandbox_audit` report.
+/// Skills declaring SKILL.md `cache: { enabled: true }` reuse a stored result for the same skill
+/// content and input (see [`crate::result_cache`]) unless `no_cache` is set.
+#[allow(clippy::too_many_arguments)]
 pub fn run_skill(
     skill_dir: &str,
     input_json: &str,
@@ -32,23 +35,43 @@ pub fn run_skill(
     limits: skilllite_sandbox::runner::ResourceLimits,
     sandbox_level: skilllite_sandbox::runner::SandboxLevel,
     entry_point_override: Option<&str>,
+    entry: Option<&str>,
+    audit: bool,
+    no_cache: bool,
 ) -> Result<String> {
     let skill_path = validate_skill_path(skill_dir)?;
     let mut metadata = skill::metadata::parse_skill_metadata(&skill_path)?;
     enforce_skill_denylist(&metadata.name)?;
     enforce_skill_integrity_before_execution(&skill_path)?;
-    if let Some(ep) = entry_point_override {
+    if let Some(name) = entry {
+        metadata.select_entry(name)?;
+    } else if let Some(ep) = entry_point_override {
         if !ep.is_empty() && skill_path.join(ep).is_file() {
             metadata.entry_point = ep.to_string();
         }
     }
 
     if metadata.entry_point.is_empty() {
+        if !metadata.entry_points.is_empty() {
+            let names: Vec<&str> = metadata
+                .entry_points
+                .iter()
+                .map(|e| e.name.as_str())
+                .collect();
+            return Err(entry_point_missing(
+                "",
+                format!(
+                    "This skill declares named entry points; pick one with `--entry <name>` ({}).",
+                    names.join(", ")
+                ),
+            ));
+        }
         if skill::metadata::has_executable_scripts(&skill_path) {
-            bail!(
+            return Err(entry_point_missing(
+                "",
                 "This skill has executable scripts but no default entry point. \
-Use `skilllite exec <skill-dir> <scripts/...>` or set `entry_point` in SKILL.md."
-            );
+Use `skilllite exec <skill-dir> <scripts/...>` or set `entry_point` in SKILL.md.",
+            ));
         }
         bail!(
             "This skill has no entry point and no executable scripts. It is a prompt-only skill."
@@ -57,13 +80,25 @@ Use `skilllite exec <skill-dir> <scripts/...>` or set `entry_point` in SKILL.md.
 
     let _input: serde_json::Value = serde_json::from_str(input_json)?;
 
+    let cache_slot = if no_cache || audit || allow_network || metadata.network.enabled {
+        None
+    } else {
+        result_cache_slot(&skill_path, &metadata, input_json, cache_dir)
+    };
+    if let Some(hit) = cache_slot
+        .as_ref()
+        .and_then(|(dir, key, _)| result_cache::lookup(dir, key))
+    {
+        return Ok(hit);
+    }
+
     skilllite_sandbox::info_log!("[INFO] ensure_environment start...");
     let env_spec = skilllite_core::EnvSpec::from_metadata(&skill_path, &metadata);
     let env_path = skilllite_sandbox::env::builder::ensure_environment(
         &skill_path,
         &env_spec,
         cache_dir.map(|s| s.as_str()),
-        None,
+        skilllite_sandbox::cli_progress(),
         skilllite_sandbox::cli_confirm_download(),
     )?;
     skilllite_sandbox::info_log!("[INFO] ensure_environment done");
@@ -72,21 +107,98 @@ Use `skilllite exec <skill-dir> <scripts/...>` or set `entry_point` in SKILL.md.
     if allow_network {
         effective_metadata.network.enabled = true;
     }
+    let (policy, sandbox_level) =
+        apply_origin_policy(&skill_path, &mut effective_metadata, sandbox_level, audit)?;
 
     let runtime = skilllite_sandbox::env::builder::build_runtime_paths(&env_path);
-    let config = build_sandbox_config(&skill_path, &effective_metadata);
-    let output = skilllite_sandbox::runner::run_in_sandbox_with_limits_and_level(
+    let mut config = build_sandbox_config(&skill_path, &effective_metadata);
+    config.enforce_isolation = policy.enforced;
+    let output = skilllite_sandbox::runner::run_in_sandbox_with_limits_and_level_opt(
         &skill_path,
         &runtime,
         &config,
         input_json,
         limits,
         sandbox_level,
+        SandboxRunOptions {
+            audit,
+            ..Default::default()
+        },
     )?;
 
+    let output = with_policy_record(&policy, output);
+    // Output files are not part of the entry, so results listing them are never stored.
+    if let Some((dir, key, ttl_secs)) = cache_slot {
+        if SkillResult::outputs_from_run_output(&output).is_empty() {
+            if let Err(e) =
+                result_cache::store(&dir, &key, &effective_metadata.name, ttl_secs, &output)
+            {
+                tracing::warn!(skill = %effective_metadata.name, "result cache write failed: {}", e);
+            }
+        }
+    }
     Ok(output)
 }
 
+/// Cache directory, key and TTL for a call of a skill declaring `cache: { enabled: true }`.
+/// `None` when the skill is not cacheable or the key cannot be computed.
+fn result_cache_slot(
+    skill_path: &Path,
+    metadata: &skill::metadata::SkillMetadata,
+    input_json: &str,
+    cache_dir: Option<&String>,
+) -> Option<(PathBuf, String, u64)> {
+    let policy = metadata.result_cache.as_ref()?;
+    let dir = result_cache::results_dir(cache_dir.map(|s| s.as_str()))?;
+    let key = result_cache::cache_key(skill_path, &metadata.entry_point, input_json, policy)
+        .inspect_err(|e| tracing::warn!(skill = %metadata.name, "result cache key: {}", e))
+        .ok()?;
+    Some((dir, key, policy.ttl_secs))
+}
+
+/// Evolved skills run at least at `SKILLLITE_EVOLVED_SKILL_LEVEL` with restricted network
+/// (see [`skill::origin`]); `--allow-network` does not override that, and `--audit`
+/// (which runs unconfined) is refused.
+fn apply_origin_policy(
+    skill_path: &Path,
+    metadata: &mut skill::metadata::SkillMetadata,
+    level: SandboxLevel,
+    audit: bool,
+) -> Result<(SkillSandboxPolicy, SandboxLevel)> {
+    let policy = SkillSandboxPolicy::for_skill(skill_path, level.as_u8(), &metadata.network);
+    if audit && policy.enforced {
+        bail!(
+            "--audit runs skills unconfined and is not allowed for evolved skill '{}' ({}). \
+Set {}=1 to treat evolved skills like installed ones.",
+            metadata.name,
+            policy.describe(),
+            skilllite_core::config::env_keys::sandbox::SKILLLITE_EVOLVED_SKILL_LEVEL
+        );
+    }
+    policy.apply_network(&metadata.name, &mut metadata.network);
+    let level = SandboxLevel::from_env_or_cli(Some(policy.level));
+    Ok((policy, level))
+}
+
+/// Evolved skills record the effective policy in their result.
+fn with_policy_record(policy: &SkillSandboxPolicy, output: String) -> String {
+    if policy.is_evolved() {
+        policy.attach_to_output(&output)
+    } else {
+        output
+    }
+}
+
+fn entry_point_missing(entry_point: &str, message: impl Into<String>) -> crate::Error {
+    skilllite_sandbox::Error::run(
+        skilllite_sandbox::SandboxError::EntryPointMissing {
+            entry_point: entry_point.to_string(),
+        },
+        message,
+    )
+    .into()
+}
+
 /// Execute a specific script directly in sandbox.
 #[allow(clippy::too_many_arguments)]
 pub fn exec_script(
@@ -98,19 +210,33 @@ pub fn exec_script(
     cache_dir: Option<&String>,
     limits: skilllite_sandbox::runner::ResourceLimits,
     sandbox_level: skilllite_sandbox::runner::SandboxLevel,
+    audit: bool,
 ) -> Result<String> {
     let skill_path = validate_skill_path(skill_dir)?;
     let full_script_path = skill_path.join(script_path);
 
     if !full_script_path.exists() {
-        bail!("Script not found: {}", full_script_path.display());
+        return Err(entry_point_missing(
+            script_path,
+            format!("Script not found: {}", full_script_path.display()),
+        ));
     }
 
-    let full_canonical = full_script_path.canonicalize().map_err(|_| {
-        crate::Error::validation(format!("Script path does not exist: {}", script_path))
-    })?;
-    if !full_canonical.starts_with(&skill_path) {
-        bail!("Script path escapes skill directory: {}", script_path);
+    match resolve_under_roots(
+        script_path,
+        &[&skill_path],
+        PathPolicy::existing("Script path"),
+    ) {
+        Ok(_) => {}
+        Err(PathValidationError::PathEscape { .. }) => {
+            bail!("Script path escapes skill directory: {}", script_path)
+        }
+        Err(_) => {
+            return Err(crate::Error::validation(format!(
+                "Script path does not exist: {}",
+                script_path
+            )))
+        }
     }
 
     let language = detect_script_language(&full_script_path)?;
@@ -126,7 +252,7 @@ pub fn exec_script(
             &skill_path,
             &env_spec,
             cache_dir.map(|s| s.as_str()),
-            None,
+            skilllite_sandbox::cli_progress(),
             skilllite_sandbox::cli_confirm_download(),
         )?;
         (meta, env)
@@ -148,6 +274,11 @@ pub fn exec_script(
             requires_elevated_permissions: false,
             capabilities: Vec::new(),
             openclaw_installs: None,
+            max_concurrency: None,
+            entry_points: Vec::new(),
+            result_cache: None,
+            examples: Vec::new(),
+            cost_hint: None,
         };
         let env_spec = skilllite_core::EnvSpec {
             language: language.clone(),
@@ -159,7 +290,7 @@ pub fn exec_script(
             &skill_path,
             &env_spec,
             cache_dir.map(|s| s.as_str()),
-            None,
+            skilllite_sandbox::cli_progress(),
             skilllite_sandbox::cli_confirm_download(),
         )?;
         (meta, env)
@@ -171,37 +302,30 @@ pub fn exec_script(
     if allow_network {
         effective_metadata.network.enabled = true;
     }
-
-    let _guard = EXEC_ENV_MUTEX
-        .lock()
-        .map_err(|e| crate::Error::validation(format!("Mutex poisoned: {}", e)))?;
-    let _args_guard = if let Some(args_str) = args {
-        skilllite_core::config::set_env_var(
-            skilllite_core::config::env_keys::sandbox::SKILLLITE_SCRIPT_ARGS,
-            args_str,
-        );
-        Some(ScopedEnvGuard(
-            skilllite_core::config::env_keys::sandbox::SKILLLITE_SCRIPT_ARGS,
-        ))
-    } else {
-        skilllite_core::config::remove_env_var(
-            skilllite_core::config::env_keys::sandbox::SKILLLITE_SCRIPT_ARGS,
-        );
-        None
-    };
+    let (policy, sandbox_level) =
+        apply_origin_policy(&skill_path, &mut effective_metadata, sandbox_level, audit)?;
 
     let runtime = skilllite_sandbox::env::builder::build_runtime_paths(&env_path);
-    let config = build_sandbox_config(&skill_path, &effective_metadata);
-    let output = skilllite_sandbox::runner::run_in_sandbox_with_limits_and_level(
+    let mut config = build_sandbox_config(&skill_path, &effective_metadata);
+    config.enforce_isolation = policy.enforced;
+    // Per-call args travel on the config, so concurrent exec calls never share them.
+    config.script_args = args
+        .map(|a| a.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+    let output = skilllite_sandbox::runner::run_in_sandbox_with_limits_and_level_opt(
         &skill_path,
         &runtime,
         &config,
         input_json,
         limits,
         sandbox_level,
+        SandboxRunOptions {
+            audit,
+            ..Default::default()
+        },
     )?;
 
-    Ok(output)
+    Ok(with_policy_record(&policy, output))
 }
 
 /// Execute a bash command for a bash-tool skill.
@@ -240,7 +364,17 @@ pub fn bash_command(
                 raw_pattern: p.raw_pattern,
             })
             .collect();
-    skilllite_sandbox::bash_validator::validate_bash_command(command, &validator_patterns)?;
+    // Redirections may target the working directory the command runs in.
+    let run_dir = cwd
+        .map(PathBuf::from)
+        .filter(|p| p.is_dir())
+        .or_else(|| std::env::current_dir().ok());
+    let roots: Vec<&Path> = run_dir.as_deref().into_iter().collect();
+    skilllite_sandbox::bash_validator::validate_bash_command_in(
+        command,
+        &validator_patterns,
+        &roots,
+    )?;
 
     skilllite_sandbox::info_log!("[INFO] bash: ensure_environment start...");
     let env_spec = skilllite_core::EnvSpec::from_metadata(&skill_path, &metadata);
@@ -248,7 +382,7 @@ pub fn bash_command(
         &skill_path,
         &env_spec,
         cache_dir.map(|s| s.as_str()),
-        None,
+        skilllite_sandbox::cli_progress(),
         skilllite_sandbox::cli_confirm_download(),
     )?;
     skilllite_sandbox::info_log!("[INFO] bash: ensure_environment done");
@@ -313,11 +447,90 @@ fn execute_bash_with_env(
     Ok(result.to_string())
 }
 
-/// Validate a skill without running it.
+// ─── Input / output redirection ─────────────────────────────────────────────
+
+/// Read skill input JSON from `path` (`-` reads stdin), so large payloads stay off the
+/// command line.
+pub fn read_input_file(path: &str) -> Result<String> {
+    if path == "-" {
+        let mut s = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut s)?;
+        return Ok(s);
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read input file: {}", path))?;
+    Ok(content)
+}
+
+/// Pick one field out of a JSON result. `field` is a dotted path (optional `$.` prefix) whose
+/// numeric segments index arrays, e.g. `files.0.path`. Strings come back unquoted so the value
+/// can be used directly in a shell pipeline; other values are printed as JSON.
+pub fn extract_output_field(output: &str, field: &str) -> Result<String> {
+    let value: serde_json::Value = serde_json::from_str(output.trim()).map_err(|e| {
+        crate::Error::validation(format!("--output-field: result is not JSON ({})", e))
+    })?;
+    let path = field.strip_prefix("$.").unwrap_or(field);
+    let path = if path == "$" { "" } else { path };
+    let mut cur = &value;
+    for seg in path.split('.').filter(|s| !s.is_empty()) {
+        let next = match cur {
+            serde_json::Value::Array(items) => seg.parse::<usize>().ok().and_then(|i| items.get(i)),
+            serde_json::Value::Object(map) => map.get(seg),
+            _ => None,
+        };
+        cur = match next {
+            Some(v) => v,
+            None => bail!(
+                "--output-field: '{}' not found in result (at '{}')",
+                field,
+                seg
+            ),
+        };
+    }
+    Ok(match cur {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    })
+}
+
+/// Write a result to `path` atomically, creating parent directories.
+pub fn write_output_file(path: &str, content: &str) -> Result<()> {
+    let mut content = content.to_string();
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    skilllite_fs::atomic_write(Path::new(path), &content)?;
+    Ok(())
+}
+
+/// Validate a skill without running it. Unknown `capabilities` tags are warnings, not errors.
 pub fn validate_skill(skill_dir: &str) -> Result<()> {
     let skill_path = validate_skill_path(skill_dir)?;
+    validate_skill_dir(&skill_path)?;
+
+    let skill_md = std::fs::read_to_string(skill_path.join("SKILL.md"))?;
+    let declared = skill::metadata::declared_capabilities(&skill_md)?;
+    let registry = skill::capabilities::CapabilityRegistry::load();
+    for warning in skill::capabilities::capability_warnings(&registry, &declared) {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(())
+}
+
+/// [`validate_skill`] on an already-resolved directory (e.g. a staging copy outside the
+/// skills root, as used by `skilllite init --from`).
+pub(crate) fn validate_skill_dir(skill_path: &Path) -> Result<()> {
+    let skill_path = skill_path.to_path_buf();
     let metadata = skill::metadata::parse_skill_metadata(&skill_path)?;
 
+    // Every `network.outbound` / `network.deny` entry must follow the whitelist grammar
+    let skill_md = std::fs::read_to_string(skill_path.join("SKILL.md"))?;
+    let problems = skill::network_rules::check_network_policy(&skill_md, &metadata.network);
+    if !problems.is_empty() {
+        let lines: Vec<String> = problems.iter().map(|p| format!("  {}", p)).collect();
+        bail!("Invalid network policy:\n{}", lines.join("\n"));
+    }
+
     if !metadata.entry_point.is_empty() {
         let entry_path = skill_path.join(&metadata.entry_point);
         if !entry_path.exists() {
@@ -326,6 +539,12 @@ pub fn validate_skill(skill_dir: &str) -> Result<()> {
         skill::deps::validate_dependencies(&skill_path, &metadata)?;
     }
 
+    // Fail fast when `compatibility` pins a runtime version nothing can satisfy
+    let env_spec = skilllite_core::EnvSpec::from_metadata(&skill_path, &metadata);
+    if env_spec.language == "python" || env_spec.language == "node" {
+        interpreters::resolve_runtime(&env_spec, None)?;
+    }
+
     Ok(())
 }
 
@@ -349,17 +568,69 @@ pub fn show_skill_info(skill_dir: &str) -> Result<()> {
         "  Language: {}",
         metadata.language.as_deref().unwrap_or("auto-detect")
     );
+    let env_spec = skilllite_core::EnvSpec::from_metadata(&skill_path, &metadata);
+    if let Some(constraint) = env_spec.runtime_constraint() {
+        let runtime = match interpreters::resolve_runtime(&env_spec, None) {
+            Ok(RuntimeChoice::Selected(i)) => {
+                format!("{} ({})", i.version_string(), i.path.display())
+            }
+            Ok(RuntimeChoice::Provision) => "will be provisioned on first run".to_string(),
+            Ok(RuntimeChoice::Default) => "system default".to_string(),
+            Err(e) => format!("unavailable - {}", e),
+        };
+        println!("  Runtime: {} -> {}", constraint, runtime);
+    }
     println!("  Network Enabled: {}", metadata.network.enabled);
-    if !metadata.network.outbound.is_empty() {
-        println!("  Outbound Whitelist:");
-        for host in &metadata.network.outbound {
-            println!("    - {}", host);
+    for (label, entries) in [
+        ("Outbound Whitelist", &metadata.network.outbound),
+        ("Outbound Deny", &metadata.network.deny),
+    ] {
+        if entries.is_empty() {
+            continue;
+        }
+        println!("  {}:", label);
+        for entry in entries {
+            match skill::network_rules::OutboundRule::parse(entry) {
+                Ok(rule) => println!("    - {}  ({})", rule, rule.describe()),
+                Err(e) => println!("    - {}  (invalid: {})", entry, e),
+            }
         }
     }
+    let policy = SkillSandboxPolicy::for_skill(
+        &skill_path,
+        SandboxLevel::from_env_or_cli(None).as_u8(),
+        &metadata.network,
+    );
+    println!("  Sandbox Policy: {}", policy.describe());
+    #[cfg(target_os = "linux")]
+    println!("  Seccomp Profile: {}", seccomp_profile_for(policy.level));
 
     Ok(())
 }
 
+/// The seccomp profile runs at `level` are filtered with, for `skilllite info`.
+#[cfg(target_os = "linux")]
+fn seccomp_profile_for(level: u8) -> String {
+    use skilllite_sandbox::seccomp::{SeccompProfiles, NO_PROFILE};
+    let level = SandboxLevel::from_env_or_cli(Some(level));
+    if !level.use_sandbox() {
+        return format!("{} (level 1 runs without a sandbox)", NO_PROFILE);
+    }
+    match SeccompProfiles::load() {
+        Ok(profiles) => profiles.for_level(level).map_or_else(
+            || {
+                format!(
+                    "{} (level {} runs without a syscall filter)",
+                    NO_PROFILE,
+                    level.as_u8()
+                )
+            },
+            |p| p.summary(),
+        ),
+        Err(e) => format!("invalid profiles - {}", e),
+    }
+}
+
 /// Build a `SandboxConfig` from `SkillMetadata`, resolving language via `detect_language`.
 fn build_sandbox_config(
     skill_dir: &Path,
@@ -371,7 +642,11 @@ fn build_sandbox_config(
         language: skill::metadata::detect_language(skill_dir, metadata),
         network_enabled: metadata.network.enabled,
         network_outbound: metadata.network.outbound.clone(),
+        network_deny: metadata.network.deny.clone(),
         uses_playwright: metadata.uses_playwright(),
+        script_args: Vec::new(),
+        results_dir: None,
+        enforce_isolation: false,
     }
 }
 
@@ -386,7 +661,7 @@ fn detect_script_language(script_path: &Path) -> Result<String> {
         "py" => Ok("python".to_string()),
         "js" | "mjs" | "cjs" => Ok("node".to_string()),
         "ts" => Ok("node".to_string()),
-        "sh" | "bash" => Ok("shell".to_string()),
+        "sh" | "bash" => Ok("bash".to_string()),
         "" => {
             if let Ok(content) = skilllite_fs::read_file(script_path) {
                 if let Some(first_line) = content.lines().next() {
@@ -396,7 +671,7 @@ fn detect_script_language(script_path: &Path) -> Result<String> {
                         } else if first_line.contains("node") {
                             return Ok("node".to_string());
                         } else if first_line.contains("bash") || first_line.contains("sh") {
-                            return Ok("shell".to_string());
+                            return Ok("bash".to_string());
                         }
                     }
                 }
@@ -478,3 +753,28 @@ Set {}=1 to run, or use --confirm in MCP.",
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_field_follows_dotted_path() {
+        let out = r#"{"files":[{"path":"out/report.md","size":12}],"ok":true}"#;
+        assert_eq!(
+            extract_output_field(out, "files.0.path").unwrap(),
+            "out/report.md"
+        );
+        assert_eq!(extract_output_field(out, "$.files.0.size").unwrap(), "12");
+        assert_eq!(extract_output_field(out, "ok").unwrap(), "true");
+        assert_eq!(extract_output_field(out, "$").unwrap(), out);
+
+        let err = extract_output_field(out, "files.1.path").unwrap_err();
+        assert!(
+            err.to_string().contains("'files.1.path' not found"),
+            "{err}"
+        );
+        let err = extract_output_field("plain text", "a").unwrap_err();
+        assert!(err.to_string().contains("not JSON"), "{err}");
+    }
+}
@@ -1,10 +1,11 @@
-//! IDE integration commands: cursor, opencode.
+//! IDE integration commands: cursor, opencode, and `init-ide` MCP config generation.
 //!
 //! Migrated from Python `python-sdk/skilllite/cli/integrations/`.
 
 use anyhow::Context;
 use serde_json::json;
 use std::fs;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -524,3 +525,379 @@ pub fn cmd_opencode(project_dir: Option<&str>, skills_dir: &str, force: bool) ->
 
     Ok(())
 }
+
+// ─── init-ide: MCP client config generator ──────────────────────────────────
+
+/// MCP clients `skilllite init-ide` can configure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdeTarget {
+    Cursor,
+    Opencode,
+    ClaudeCode,
+    Vscode,
+    Generic,
+}
+
+impl IdeTarget {
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "cursor" => Ok(Self::Cursor),
+            "opencode" => Ok(Self::Opencode),
+            "claude-code" | "claude" => Ok(Self::ClaudeCode),
+            "vscode" | "vs-code" => Ok(Self::Vscode),
+            "generic" => Ok(Self::Generic),
+            other => Err(crate::Error::validation(format!(
+                "unknown --target '{}': expected cursor, opencode, claude-code, vscode or generic",
+                other
+            ))),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Cursor => "Cursor",
+            Self::Opencode => "OpenCode",
+            Self::ClaudeCode => "Claude Code",
+            Self::Vscode => "VS Code",
+            Self::Generic => "generic MCP client",
+        }
+    }
+
+    /// JSON path (object keys) of the server map inside the config file.
+    fn servers_path(self) -> &'static [&'static str] {
+        match self {
+            Self::Opencode => &["mcp"],
+            Self::Vscode => &["mcp", "servers"],
+            Self::Cursor | Self::ClaudeCode | Self::Generic => &["mcpServers"],
+        }
+    }
+
+    /// Config file for this target, or `None` for `generic` (printed only).
+    fn config_path(self, project: &Path, global: bool) -> Option<PathBuf> {
+        let home = || dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        match (self, global) {
+            (Self::Generic, _) => None,
+            (Self::Cursor, false) => Some(project.join(".cursor").join("mcp.json")),
+            (Self::Cursor, true) => Some(home().join(".cursor").join("mcp.json")),
+            (Self::Opencode, false) => Some(project.join("opencode.json")),
+            (Self::Opencode, true) => Some(
+                home()
+                    .join(".config")
+                    .join("opencode")
+                    .join("opencode.json"),
+            ),
+            (Self::ClaudeCode, false) => Some(project.join(".mcp.json")),
+            (Self::ClaudeCode, true) => Some(home().join(".claude.json")),
+            (Self::Vscode, false) => Some(project.join(".vscode").join("settings.json")),
+            (Self::Vscode, true) => Some(vscode_user_settings()),
+        }
+    }
+
+    fn server_entry(self, command: &str, args: &[String]) -> serde_json::Value {
+        let env = json!({
+            skilllite_core::config::env_keys::sandbox::SKILLLITE_SANDBOX_LEVEL: "3"
+        });
+        match self {
+            Self::Opencode => {
+                let mut cmd = vec![command.to_string()];
+                cmd.extend(args.iter().cloned());
+                json!({ "type": "local", "command": cmd, "environment": env, "enabled": true })
+            }
+            Self::ClaudeCode | Self::Vscode => {
+                json!({ "type": "stdio", "command": command, "args": args, "env": env })
+            }
+            Self::Cursor | Self::Generic => {
+                json!({ "command": command, "args": args, "env": env })
+            }
+        }
+    }
+}
+
+fn vscode_user_settings() -> PathBuf {
+    let base = if cfg!(target_os = "macos") {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("Library")
+            .join("Application Support")
+    } else {
+        dirs::config_dir().unwrap_or_else(|| PathBuf::from("."))
+    };
+    base.join("Code").join("User").join("settings.json")
+}
+
+/// Absolute path of the running skilllite binary (falls back to `skilllite` on PATH).
+fn skilllite_executable() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| p.canonicalize().ok())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| "skilllite".to_string())
+}
+
+/// Outcome of merging the skilllite entry into an existing config.
+#[derive(Debug, PartialEq)]
+enum MergeOutcome {
+    Added,
+    Unchanged,
+    Updated,
+    /// A different skilllite entry exists and `--force` was not given.
+    Conflict {
+        existing: serde_json::Value,
+    },
+}
+
+/// Insert `entry` as `skilllite` under `path` in `config`, leaving other servers and keys alone.
+fn merge_server_entry(
+    config: &mut serde_json::Value,
+    path: &[&str],
+    entry: &serde_json::Value,
+    force: bool,
+) -> Result<MergeOutcome> {
+    let mut node = config;
+    for key in path {
+        let obj = node.as_object_mut().ok_or_else(|| {
+            crate::Error::validation(format!("'{}' is not a JSON object in config", key))
+        })?;
+        node = obj.entry(key.to_string()).or_insert_with(|| json!({}));
+    }
+    let servers = node.as_object_mut().ok_or_else(|| {
+        crate::Error::validation(format!(
+            "'{}' is not a JSON object in config",
+            path.join(".")
+        ))
+    })?;
+    let outcome = match servers.get("skilllite") {
+        None => MergeOutcome::Added,
+        Some(existing) if existing == entry => return Ok(MergeOutcome::Unchanged),
+        Some(existing) if !force => {
+            return Ok(MergeOutcome::Conflict {
+                existing: existing.clone(),
+            })
+        }
+        Some(_) => MergeOutcome::Updated,
+    };
+    servers.insert("skilllite".to_string(), entry.clone());
+    Ok(outcome)
+}
+
+/// Line diff (LCS) of two small texts, `-`/`+`/` ` prefixed.
+fn line_diff(before: &str, after: &str) -> String {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut out = String::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            out.push_str(&format!("  {}\n", a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("- {}\n", a[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+ {}\n", b[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+/// Resolve the skills dir to an absolute path; offer `skilllite init` when it is missing.
+fn resolve_existing_skills_dir(project: &Path, skills_dir: &str) -> Result<PathBuf> {
+    let resolution = resolve_skills_dir_with_legacy_fallback(project, skills_dir);
+    if let Some(warning) = resolution.conflict_warning() {
+        eprintln!("{}", warning);
+    }
+    let effective = effective_skills_dir_arg(skills_dir, &resolution);
+    let path = project.join(effective.strip_prefix("./").unwrap_or(&effective));
+    if !path.is_dir() {
+        let offered = std::io::stdin().is_terminal() && {
+            eprint!(
+                "Skills directory {} does not exist. Run `skilllite init` now? [y/N] ",
+                path.display()
+            );
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer)?;
+            matches!(answer.trim(), "y" | "Y" | "yes")
+        };
+        if !offered {
+            return Err(crate::Error::validation(format!(
+                "skills directory not found: {}\nRun `skilllite init -s {}` first or pass --skills-dir",
+                path.display(),
+                skills_dir
+            )));
+        }
+        crate::init::cmd_init(&path.to_string_lossy(), false, false, false, false, false)?;
+    }
+    Ok(path.canonicalize().unwrap_or(path))
+}
+
+/// `skilllite init-ide --target <cursor|opencode|claude-code|vscode|generic>`
+///
+/// Writes (or prints, for `generic`) an MCP client entry that runs
+/// `<abs skilllite> mcp --skills-dir <abs skills dir>`. Existing config files are merged: other
+/// servers are kept and only the `skilllite` entry is added or updated. A differing entry is
+/// replaced only with `force`; otherwise the diff is printed and nothing is written.
+pub fn cmd_init_ide(
+    target: &str,
+    project_dir: Option<&str>,
+    skills_dir: &str,
+    global: bool,
+    force: bool,
+) -> Result<()> {
+    let target = IdeTarget::parse(target)?;
+    let project = project_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+    let skills_path = resolve_existing_skills_dir(&project, skills_dir)?;
+
+    let command = skilllite_executable();
+    let args = vec![
+        "mcp".to_string(),
+        "--skills-dir".to_string(),
+        skills_path.to_string_lossy().to_string(),
+    ];
+    let entry = target.server_entry(&command, &args);
+    eprintln!("🚀 Configuring SkillLite MCP server for {}", target.label());
+    eprintln!("   → {} {}", command, args.join(" "));
+
+    let Some(config_path) = target.config_path(&project, global) else {
+        let mut snippet = json!({});
+        merge_server_entry(&mut snippet, target.servers_path(), &entry, true)?;
+        println!("{}", serde_json::to_string_pretty(&snippet)?);
+        eprintln!();
+        eprintln!("Add the `skilllite` entry above to your MCP client's server list (usually an");
+        eprintln!("`mcpServers` object). It starts a stdio server; no network port is opened.");
+        return Ok(());
+    };
+
+    let mut config = if config_path.exists() {
+        let raw = fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read {}", config_path.display()))?;
+        if raw.trim().is_empty() {
+            json!({})
+        } else {
+            serde_json::from_str::<serde_json::Value>(&raw).map_err(|e| {
+                crate::Error::validation(format!(
+                    "cannot parse {} ({}); it was left untouched. Remove comments/trailing commas or add the entry by hand (see `--target generic`)",
+                    config_path.display(),
+                    e
+                ))
+            })?
+        }
+    } else {
+        json!({})
+    };
+    if target == IdeTarget::Opencode && config.get("$schema").is_none() {
+        config["$schema"] = json!("https://opencode.ai/config.json");
+    }
+
+    let outcome = merge_server_entry(&mut config, target.servers_path(), &entry, force)?;
+    match &outcome {
+        MergeOutcome::Unchanged => {
+            eprintln!("✓ {} already up to date", config_path.display());
+            return Ok(());
+        }
+        MergeOutcome::Conflict { existing } => {
+            eprintln!(
+                "⚠ {} already has a different `skilllite` entry; not modified.",
+                config_path.display()
+            );
+            eprintln!("  Re-run with --force to apply this change:");
+            eprintln!();
+            eprint!(
+                "{}",
+                line_diff(
+                    &serde_json::to_string_pretty(existing)?,
+                    &serde_json::to_string_pretty(&entry)?
+                )
+            );
+            return Ok(());
+        }
+        MergeOutcome::Added | MergeOutcome::Updated => {}
+    }
+
+    skilllite_fs::atomic_write(&config_path, &serde_json::to_string_pretty(&config)?)?;
+    let verb = if outcome == MergeOutcome::Added {
+        "Added"
+    } else {
+        "Updated"
+    };
+    eprintln!(
+        "✓ {} `skilllite` in {} ({})",
+        verb,
+        config_path.display(),
+        if global { "global" } else { "project" }
+    );
+    eprintln!(
+        "  Restart or reload {} to pick up the server.",
+        target.label()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_keeps_other_servers_and_reports_conflicts() {
+        let entry = json!({ "command": "/bin/skilllite", "args": ["mcp"] });
+        let mut config = json!({
+            "theme": "dark",
+            "mcp": { "servers": { "other": { "command": "x" } } }
+        });
+        let path = IdeTarget::Vscode.servers_path();
+
+        assert_eq!(
+            merge_server_entry(&mut config, path, &entry, false).unwrap(),
+            MergeOutcome::Added
+        );
+        assert_eq!(config["theme"], "dark");
+        assert_eq!(config["mcp"]["servers"]["other"]["command"], "x");
+        assert_eq!(
+            merge_server_entry(&mut config, path, &entry, false).unwrap(),
+            MergeOutcome::Unchanged
+        );
+
+        let changed = json!({ "command": "/usr/local/bin/skilllite", "args": ["mcp"] });
+        assert!(matches!(
+            merge_server_entry(&mut config, path, &changed, false).unwrap(),
+            MergeOutcome::Conflict { .. }
+        ));
+        assert_eq!(config["mcp"]["servers"]["skilllite"], entry);
+        assert_eq!(
+            merge_server_entry(&mut config, path, &changed, true).unwrap(),
+            MergeOutcome::Updated
+        );
+        assert_eq!(config["mcp"]["servers"]["skilllite"], changed);
+    }
+
+    #[test]
+    fn merge_rejects_non_object_server_map() {
+        let mut config = json!({ "mcpServers": [] });
+        assert!(merge_server_entry(&mut config, &["mcpServers"], &json!({}), true).is_err());
+    }
+
+    #[test]
+    fn line_diff_marks_changed_lines() {
+        assert_eq!(line_diff("a\nb\nc", "a\nx\nc"), "  a\n- b\n+ x\n  c\n");
+    }
+
+    #[test]
+    fn target_parse_accepts_aliases() {
+        assert_eq!(IdeTarget::parse("Claude").unwrap(), IdeTarget::ClaudeCode);
+        assert_eq!(IdeTarget::parse("vscode").unwrap(), IdeTarget::Vscode);
+        assert!(IdeTarget::parse("emacs").is_err());
+    }
+}
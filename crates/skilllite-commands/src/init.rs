@@ -352,7 +352,7 @@ fn install_all_deps(
             tracing::debug!("--use-llm requested but no API key; falling back to whitelist");
             (None, None)
         } else {
-            match skilllite_agent::llm::LlmClient::new(&config.api_base, &config.api_key) {
+            match skilllite_agent::llm::LlmClient::from_config(&config) {
                 Ok(client) => (Some(client), Some(config.model)),
                 Err(e) => {
                     tracing::warn!("LLM client build failed, falling back to whitelist: {}", e);
@@ -465,7 +465,7 @@ fn install_all_deps(
                     &skill_path,
                     &env_spec,
                     cache_dir,
-                    None,
+                    skilllite_sandbox::cli_progress(),
                     skilllite_sandbox::cli_confirm_download(),
                 ) {
                     Ok(_) => {
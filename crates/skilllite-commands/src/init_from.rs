@@ -0,0 +1,500 @@
+//! `skilllite init --from <dir>` — turn an existing folder of scripts into a skill.
+//!
+//! Flow:
+//!   1. Scan the directory with the `scan` analysis (description, argparse/stdin detection)
+//!   2. Pick the likely entry point and language, collect hard-coded hosts and suggested
+//!      capabilities
+//!   3. Ask for name / description / network access (defaults when stdin is not a tty)
+//!      — or, with `--use-llm`, let the configured model draft description and schema
+//!   4. Render SKILL.md, validate it in a staging copy, then write it (or print with `--dry-run`)
+
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::error::bail;
+use crate::scan::URL_HOST_RE;
+use crate::Result;
+
+/// Max characters of the entry script sent to the model with `--use-llm`.
+#[cfg(feature = "agent")]
+const LLM_SOURCE_MAX_CHARS: usize = 12_000;
+
+const NAME_MAX_CHARS: usize = 64;
+const DESCRIPTION_MAX_CHARS: usize = 1024;
+
+/// Imports / calls that imply outbound network use even without a URL literal.
+const NETWORK_HINTS: &[&str] = &[
+    "import requests",
+    "urllib.request",
+    "httpx",
+    "aiohttp",
+    "fetch(",
+    "axios",
+    "require(\"https\")",
+    "require('https')",
+    "curl ",
+    "wget ",
+];
+
+/// Inferred (then user-confirmed) contents of the generated SKILL.md.
+#[derive(Debug, Clone)]
+struct SkillDraft {
+    name: String,
+    description: String,
+    entry_point: String,
+    /// `python`, `node` or `bash` (SKILL.md vocabulary).
+    language: &'static str,
+    /// How the entry point takes input, as reported by the scanner: `json_stdin`, `cli_args`
+    /// or `none`.
+    input_format: String,
+    network: bool,
+    domains: Vec<String>,
+    input_schema: Option<Value>,
+    /// Suggested by the scan heuristics across all scripts.
+    capabilities: Vec<String>,
+}
+
+/// `skilllite init --from <dir>`
+pub fn cmd_init_from(dir: &str, use_llm: bool, dry_run: bool, force: bool) -> Result<()> {
+    let dir = Path::new(dir);
+    if !dir.is_dir() {
+        bail!("--from: {} is not a directory", dir.display());
+    }
+    let skill_md = dir.join("SKILL.md");
+    if skill_md.exists() && !force && !dry_run {
+        bail!(
+            "{} already exists (use --force to overwrite, or --dry-run to preview)",
+            skill_md.display()
+        );
+    }
+
+    eprintln!("🔍 Scanning {}...", dir.display());
+    let mut draft = infer_draft(dir)?;
+    eprintln!(
+        "   Entry point: {} ({}, input: {})",
+        draft.entry_point, draft.language, draft.input_format
+    );
+
+    if use_llm {
+        llm_refine(dir, &mut draft)?;
+    }
+    if std::io::stdin().is_terminal() {
+        ask_questions(&mut draft)?;
+    }
+
+    let content = render_skill_md(&draft);
+    validate_staged(dir, &draft, &content)?;
+
+    if dry_run {
+        print!("{}", content);
+        return Ok(());
+    }
+    fs::write(&skill_md, &content)?;
+    eprintln!("✅ Wrote {}", skill_md.display());
+    eprintln!(
+        "   Next: skilllite validate {}  ·  move it under your skills directory",
+        dir.display()
+    );
+    Ok(())
+}
+
+fn infer_draft(dir: &Path) -> Result<SkillDraft> {
+    let scripts = crate::scan::scan_scripts_parallel(dir, 20)?;
+    let Some(entry) = scripts
+        .iter()
+        .filter(|s| language_of(s).is_some())
+        .max_by(|a, b| entry_score(a).total_cmp(&entry_score(b)))
+    else {
+        bail!(
+            "No Python, Node or shell scripts found in {}",
+            dir.display()
+        );
+    };
+
+    let entry_point = entry["path"]
+        .as_str()
+        .unwrap_or_default()
+        .replace('\\', "/");
+    let language = language_of(entry).unwrap_or("python");
+    let source = fs::read_to_string(dir.join(&entry_point)).unwrap_or_default();
+    let domains = detect_domains(&source);
+    let network = !domains.is_empty() || NETWORK_HINTS.iter().any(|h| source.contains(h));
+    let input_schema = if language == "python" {
+        skilllite_core::skill::schema::parse_argparse_schema(&dir.join(&entry_point))
+    } else {
+        None
+    };
+    let input_format = entry["input_format"].as_str().unwrap_or("none");
+
+    let dir_name = dir
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "my-skill".to_string());
+    let description = entry["description"]
+        .as_str()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Runs {}.", entry_point));
+
+    Ok(SkillDraft {
+        name: slugify(&dir_name),
+        description: truncate_chars(&description, DESCRIPTION_MAX_CHARS),
+        entry_point,
+        language,
+        input_format: input_format.to_string(),
+        network,
+        domains,
+        input_schema,
+        capabilities: crate::scan::merged_capabilities(&scripts),
+    })
+}
+
+/// Map the scanner's language to the SKILL.md one (`typescript` is not runnable as-is).
+fn language_of(script: &Value) -> Option<&'static str> {
+    match script["language"].as_str()? {
+        "python" => Some("python"),
+        "node" => Some("node"),
+        "shell" => Some("bash"),
+        _ => None,
+    }
+}
+
+/// Rank entry point candidates: the scanner's confidence plus name and layout bonuses.
+fn entry_score(script: &Value) -> f64 {
+    let path = script["path"].as_str().unwrap_or_default();
+    let stem = Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut score = script["confidence"].as_f64().unwrap_or(0.0);
+    if matches!(stem.as_str(), "main" | "index" | "cli" | "run" | "app") {
+        score += 1.0;
+    }
+    if script["has_main_entry"].as_bool() == Some(true) {
+        score += 0.5;
+    }
+    if script["in_scripts_dir"].as_bool() == Some(true) {
+        score += 0.25;
+    }
+    if stem.starts_with("test_") || stem.ends_with("_test") || stem == "setup" {
+        score -= 2.0;
+    }
+    // Prefer shallower paths on ties
+    score - path.matches('/').count() as f64 * 0.01
+}
+
+fn detect_domains(source: &str) -> Vec<String> {
+    let mut domains: Vec<String> = URL_HOST_RE
+        .captures_iter(source)
+        .filter_map(|c| c.get(1).map(|m| m.as_str().to_lowercase()))
+        .filter(|d| d != "localhost" && !d.ends_with(".localhost"))
+        .collect();
+    domains.sort();
+    domains.dedup();
+    domains
+}
+
+/// Lowercase letters, digits and hyphens, as required for skill names.
+fn slugify(raw: &str) -> String {
+    let mut slug = String::new();
+    for c in raw.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = truncate_chars(slug.trim_matches('-'), NAME_MAX_CHARS);
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "my-skill".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+fn truncate_chars(s: &str, max: usize) -> String {
+    s.chars().take(max).collect()
+}
+
+fn prompt_with_default(question: &str, default: &str) -> Result<String> {
+    eprint!("{} [{}]: ", question, default);
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    let answer = line.trim();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+fn ask_questions(draft: &mut SkillDraft) -> Result<()> {
+    eprintln!();
+    draft.name = slugify(&prompt_with_default("Skill name", &draft.name)?);
+    draft.description = truncate_chars(
+        &prompt_with_default("Description", &draft.description)?,
+        DESCRIPTION_MAX_CHARS,
+    );
+    let default_network = if draft.network { "y" } else { "n" };
+    let network = prompt_with_default("Needs network access? (y/n)", default_network)?;
+    draft.network = network.to_lowercase().starts_with('y');
+    if draft.network {
+        let domains = prompt_with_default(
+            "Allowed domains (comma-separated, empty = any)",
+            &draft.domains.join(","),
+        )?;
+        draft.domains = domains
+            .split(',')
+            .map(|d| d.trim().to_lowercase())
+            .filter(|d| !d.is_empty())
+            .collect();
+    } else {
+        draft.domains.clear();
+    }
+    Ok(())
+}
+
+fn render_skill_md(draft: &SkillDraft) -> String {
+    let runtime = match draft.language {
+        "python" => "Requires Python 3.x",
+        "node" => "Requires Node.js",
+        _ => "Requires bash",
+    };
+    let compatibility = match (draft.network, draft.domains.is_empty()) {
+        (false, _) => runtime.to_string(),
+        (true, true) => format!("{}, network access", runtime),
+        (true, false) => format!("{}, network access ({})", runtime, draft.domains.join(", ")),
+    };
+    let yaml_str = |s: &str| Value::String(s.to_string()).to_string();
+
+    let mut out = String::new();
+    out.push_str("---\n");
+    out.push_str(&format!("name: {}\n", draft.name));
+    out.push_str(&format!("description: {}\n", yaml_str(&draft.description)));
+    out.push_str(&format!("compatibility: {}\n", yaml_str(&compatibility)));
+    out.push_str(&format!("entry_point: {}\n", draft.entry_point));
+    if !draft.capabilities.is_empty() {
+        out.push_str(&crate::scan::capabilities_line(&draft.capabilities));
+        out.push('\n');
+    }
+    out.push_str("---\n\n");
+    out.push_str(&format!("# {}\n\n{}\n\n", draft.name, draft.description));
+    out.push_str("## Usage\n\n");
+    let input = match draft.input_format.as_str() {
+        "cli_args" => "command-line arguments built from the parameters below",
+        "json_stdin" => "a JSON object on stdin",
+        _ => "not read",
+    };
+    out.push_str(&format!(
+        "Runs `{}` ({}); input is {}.\n",
+        draft.entry_point, draft.language, input
+    ));
+    if draft.network && !draft.domains.is_empty() {
+        out.push_str(&format!(
+            "\nContacts: {}\n",
+            draft
+                .domains
+                .iter()
+                .map(|d| format!("`{}`", d))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    if let Some(schema) = &draft.input_schema {
+        let pretty = serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string());
+        out.push_str(&format!("\n## Parameters\n\n```json\n{}\n```\n", pretty));
+    }
+    out
+}
+
+/// Write SKILL.md plus the entry point into a temp dir and run `validate_skill` on it, so a
+/// broken draft never lands in the source directory.
+fn validate_staged(dir: &Path, draft: &SkillDraft, content: &str) -> Result<()> {
+    let staging = tempfile::tempdir()?;
+    let entry_dst = staging.path().join(&draft.entry_point);
+    if let Some(parent) = entry_dst.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(dir.join(&draft.entry_point), &entry_dst)?;
+    fs::write(staging.path().join("SKILL.md"), content)?;
+    crate::execute::validate_skill_dir(staging.path()).map_err(|e| {
+        crate::Error::validation(format!("Generated SKILL.md failed validation: {}", e))
+    })?;
+    let parsed = skilllite_core::skill::metadata::parse_skill_metadata(staging.path())?;
+    if parsed.entry_point != draft.entry_point || parsed.network.enabled != draft.network {
+        bail!(
+            "Generated SKILL.md does not round-trip (entry_point={:?}, network={})",
+            parsed.entry_point,
+            parsed.network.enabled
+        );
+    }
+    Ok(())
+}
+
+/// Ask the configured model for a description and parameter schema from the entry source.
+#[cfg(feature = "agent")]
+fn llm_refine(dir: &Path, draft: &mut SkillDraft) -> Result<()> {
+    use anyhow::Context;
+    use skilllite_agent::llm::LlmClient;
+    use skilllite_agent::types::{AgentConfig, ChatMessage};
+
+    let config = AgentConfig::from_env();
+    if config.api_key.is_empty() {
+        bail!("--use-llm needs an API key (set SKILLLITE_API_KEY or run `skilllite quickstart`)");
+    }
+    let source = fs::read_to_string(dir.join(&draft.entry_point))?;
+    let truncated = source.chars().count() > LLM_SOURCE_MAX_CHARS;
+    let source = truncate_chars(&source, LLM_SOURCE_MAX_CHARS);
+
+    let system_prompt = r#"You write SKILL.md metadata for an existing script. Reply with one JSON object, no markdown fences:
+{"description": "<what the script does and when to use it, max 3 sentences>",
+ "input_schema": <JSON Schema object for the script's input parameters, or null if it takes none>}"#;
+    let user_content = format!(
+        "Entry point: {} ({}), input: {}\n\n```\n{}{}\n```",
+        draft.entry_point,
+        draft.language,
+        draft.input_format,
+        source,
+        if truncated { "\n... (truncated)" } else { "" }
+    );
+    eprintln!(
+        "🤖 Drafting description and parameters with {}...",
+        config.model
+    );
+    let client = LlmClient::from_config(&config)?;
+    let messages = vec![
+        ChatMessage::system(system_prompt),
+        ChatMessage::user(&user_content),
+    ];
+    let rt = tokio::runtime::Runtime::new().context("tokio runtime")?;
+    let resp =
+        rt.block_on(client.chat_completion(&config.model, &messages, None, Some(0.2), None))?;
+    let raw = resp
+        .choices
+        .first()
+        .and_then(|c| c.message.content.clone())
+        .unwrap_or_default();
+    apply_llm_draft(draft, &raw)
+}
+
+#[cfg(not(feature = "agent"))]
+fn llm_refine(_dir: &Path, _draft: &mut SkillDraft) -> Result<()> {
+    bail!("--use-llm requires a binary built with the `agent` feature")
+}
+
+#[cfg_attr(not(feature = "agent"), allow(dead_code))]
+fn apply_llm_draft(draft: &mut SkillDraft, raw: &str) -> Result<()> {
+    let cleaned = raw
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    let parsed: Value = serde_json::from_str(cleaned).map_err(|e| {
+        crate::Error::validation(format!(
+            "Model reply is not JSON ({}): {}",
+            e,
+            truncate_chars(raw, 200)
+        ))
+    })?;
+    if let Some(desc) = parsed["description"]
+        .as_str()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        draft.description = truncate_chars(desc, DESCRIPTION_MAX_CHARS);
+    }
+    if parsed["input_schema"]["type"] == "object" {
+        draft.input_schema = Some(parsed["input_schema"].clone());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script_dir() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("Weather Tools");
+        fs::create_dir_all(dir.join("scripts")).unwrap();
+        fs::write(
+            dir.join("scripts/main.py"),
+            r#""""Fetch a weather forecast for a city."""
+import argparse
+import requests
+
+def main():
+    parser = argparse.ArgumentParser()
+    parser.add_argument("city", help="City name")
+    parser.add_argument("--days", type=int, help="Forecast days")
+    args = parser.parse_args()
+    print(requests.get("https://api.open-meteo.com/v1/forecast").text)
+
+if __name__ == "__main__":
+    main()
+"#,
+        )
+        .unwrap();
+        fs::write(dir.join("scripts/helpers.py"), "def helper():\n    pass\n").unwrap();
+        fs::write(dir.join("test_main.py"), "def test_x():\n    pass\n").unwrap();
+        tmp
+    }
+
+    #[test]
+    fn infers_entry_point_network_and_argparse_schema() {
+        let tmp = script_dir();
+        let draft = infer_draft(&tmp.path().join("Weather Tools")).unwrap();
+        assert_eq!(draft.name, "weather-tools");
+        assert_eq!(draft.entry_point, "scripts/main.py");
+        assert_eq!(draft.language, "python");
+        assert_eq!(draft.description, "Fetch a weather forecast for a city.");
+        assert!(draft.network);
+        assert_eq!(draft.domains, vec!["api.open-meteo.com"]);
+        assert_eq!(draft.capabilities, vec!["python", "weather", "web"]);
+        let schema = draft.input_schema.as_ref().unwrap();
+        assert_eq!(schema["properties"]["days"]["type"], "integer");
+        assert_eq!(schema["required"], serde_json::json!(["city"]));
+    }
+
+    #[test]
+    fn rendered_skill_md_passes_validation_and_round_trips() {
+        let tmp = script_dir();
+        let dir = tmp.path().join("Weather Tools");
+        let draft = infer_draft(&dir).unwrap();
+        let content = render_skill_md(&draft);
+        validate_staged(&dir, &draft, &content).unwrap();
+        assert!(content.contains("network access (api.open-meteo.com)"));
+        assert!(content.contains("capabilities: [\"python\", \"weather\", \"web\"]\n"));
+        assert!(content.contains("## Parameters"));
+        assert!(!dir.join("SKILL.md").exists());
+    }
+
+    #[test]
+    fn llm_reply_overrides_description_and_schema() {
+        let tmp = script_dir();
+        let mut draft = infer_draft(&tmp.path().join("Weather Tools")).unwrap();
+        apply_llm_draft(
+            &mut draft,
+            "```json\n{\"description\": \"Weather lookup.\", \"input_schema\": {\"type\": \"object\", \"properties\": {\"city\": {\"type\": \"string\"}}}}\n```",
+        )
+        .unwrap();
+        assert_eq!(draft.description, "Weather lookup.");
+        assert!(draft.input_schema.as_ref().unwrap()["properties"]["days"].is_null());
+        assert!(apply_llm_draft(&mut draft, "not json").is_err());
+    }
+
+    #[test]
+    fn slugify_produces_valid_skill_names() {
+        assert_eq!(slugify("My_Cool  Script!"), "my-cool-script");
+        assert_eq!(slugify("___"), "my-skill");
+        assert_eq!(slugify(&"a".repeat(100)).len(), NAME_MAX_CHARS);
+    }
+}
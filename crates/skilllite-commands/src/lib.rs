@@ -26,6 +26,8 @@ pub mod security;
 
 #[cfg(feature = "channel_serve")]
 pub mod channel_serve;
+pub mod config;
+pub mod doctor;
 pub mod env;
 #[cfg(feature = "agent")]
 pub mod evolution;
@@ -35,7 +37,9 @@ mod evolution_desktop;
 mod evolution_status;
 pub mod ide;
 pub mod init;
+pub mod init_from;
 pub mod migrate;
+pub mod output;
 #[cfg(feature = "agent")]
 pub mod planning_rules_gen;
 #[cfg(feature = "agent")]
@@ -45,6 +49,7 @@ pub mod reindex;
 pub mod replay;
 #[cfg(feature = "agent")]
 pub mod replay_quality;
+pub mod result_cache;
 pub mod runtime;
 #[cfg(feature = "agent")]
 pub mod schedule;
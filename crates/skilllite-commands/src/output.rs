@@ -0,0 +1,112 @@
+//! Output directory management: `skilllite clean-output`.
+//!
+//! Shares the provenance index and expiry logic with the agent's `write_output` /
+//! `list_output` tools (`skilllite_core::output_index`).
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use skilllite_core::config::PathsConfig;
+use skilllite_core::output_index::{self, format_size};
+
+use crate::{Error, Result};
+
+/// `SKILLLITE_OUTPUT_DIR`, else `{workspace}/output`.
+fn resolve_output_dir(output_dir: Option<&str>) -> PathBuf {
+    if let Some(dir) = output_dir.map(str::trim).filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    let paths = PathsConfig::from_env();
+    paths
+        .output_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(paths.workspace).join("output"))
+}
+
+/// Parse `30d`, `2w`, `12h` (bare number = days).
+fn parse_older_than(raw: &str) -> Result<Duration> {
+    let raw = raw.trim().to_ascii_lowercase();
+    let (num, unit_secs) = if let Some(n) = raw.strip_suffix('d') {
+        (n, 86_400)
+    } else if let Some(n) = raw.strip_suffix('w') {
+        (n, 7 * 86_400)
+    } else if let Some(n) = raw.strip_suffix('h') {
+        (n, 3_600)
+    } else {
+        (raw.as_str(), 86_400)
+    };
+    match num.trim().parse::<u64>() {
+        Ok(n) => Ok(Duration::from_secs(n.saturating_mul(unit_secs))),
+        Err(_) => Err(Error::validation(format!(
+            "Invalid --older-than '{}' (examples: 30d, 2w, 12h)",
+            raw
+        ))),
+    }
+}
+
+/// `skilllite clean-output --older-than 30d [--dry-run]`
+pub fn cmd_clean_output(output_dir: Option<&str>, older_than: &str, dry_run: bool) -> Result<()> {
+    let age = parse_older_than(older_than)?;
+    let root = resolve_output_dir(output_dir);
+    if !root.is_dir() {
+        eprintln!("No output directory at {}", root.display());
+        return Ok(());
+    }
+
+    let report = output_index::clean_older_than(&root, age, dry_run)?;
+    if report.removed.is_empty() {
+        eprintln!(
+            "No output files older than {} in {}",
+            older_than,
+            root.display()
+        );
+        return Ok(());
+    }
+
+    eprintln!(
+        "🗂  Output files older than {} in {}:",
+        older_than,
+        root.display()
+    );
+    eprintln!();
+    for entry in &report.removed {
+        let session = entry
+            .session
+            .as_deref()
+            .map(|s| format!(" [session: {}]", s))
+            .unwrap_or_default();
+        eprintln!(
+            "  • {} ({}){}",
+            entry.path,
+            format_size(entry.size),
+            session
+        );
+    }
+    eprintln!();
+    eprintln!(
+        "Total: {} ({} files)",
+        format_size(report.bytes),
+        report.removed.len()
+    );
+    if dry_run {
+        eprintln!();
+        eprintln!("(Dry run — no files removed. Remove --dry-run to delete.)");
+    } else {
+        eprintln!("✅ Removed {} files", report.removed.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_older_than_accepts_units() {
+        assert_eq!(parse_older_than("30d").unwrap().as_secs(), 30 * 86_400);
+        assert_eq!(parse_older_than("2w").unwrap().as_secs(), 14 * 86_400);
+        assert_eq!(parse_older_than("12h").unwrap().as_secs(), 12 * 3_600);
+        assert_eq!(parse_older_than("7").unwrap().as_secs(), 7 * 86_400);
+        assert!(parse_older_than("soon").is_err());
+    }
+}
@@ -127,7 +127,7 @@ Example output: [{"id":"csdn_article","priority":88,"keywords":["csdn","CSDN","c
         docs.join("\n\n")
     );
 
-    let client = LlmClient::new(&config.api_base, &config.api_key)?;
+    let client = LlmClient::from_config(&config)?;
     let messages = vec![
         ChatMessage::system(system_prompt),
         ChatMessage::user(&user_content),
@@ -1,19 +1,27 @@
-//! `skilllite quickstart` — Zero-config start: LLM detection + skills setup + chat launch.
+//! `skilllite quickstart` — Zero-config start: LLM setup + skills setup + chat launch.
 //!
 //! Migrated from Python `skilllite quickstart` command.
 //!
 //! Flow:
 //!   1. LLM setup:
-//!      - Priority 1: Existing .env with valid OPENAI_API_KEY
-//!      - Priority 2: Auto-detect Ollama (GET http://localhost:11434/api/tags)
-//!      - Priority 3: Interactive provider selection
+//!      - Priority 1: Existing `~/.skilllite/config.toml` (shown for review; fields editable)
+//!      - Priority 2: Existing .env / environment with a valid API key (used as-is)
+//!      - Priority 3: Auto-detect Ollama (GET http://localhost:11434/api/tags)
+//!      - Priority 4: Interactive provider selection
+//!
+//!      The result is checked with a 1-token completion and saved to `config.toml`
+//!      (0600 on Unix), which `LlmConfig::from_env` reads beneath env vars and .env.
 //!   2. Ensure skills are available
 //!   3. Start interactive chat
+//!
+//! `--non-interactive` applies flags on top of the existing file, validates, saves and exits.
 
 use anyhow::Context;
-use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use skilllite_core::config::{LlmConfig, UserConfigFile};
 
 use crate::error::bail;
 use crate::Result;
@@ -21,6 +29,7 @@ use crate::Result;
 /// Provider options for LLM selection.
 #[derive(Debug, Clone)]
 struct LlmProvider {
+    id: &'static str,
     name: &'static str,
     api_base: &'static str,
     env_key: &'static str,
@@ -29,24 +38,35 @@ struct LlmProvider {
 
 const PROVIDERS: &[LlmProvider] = &[
     LlmProvider {
+        id: "ollama",
         name: "Ollama (local, free)",
         api_base: "http://localhost:11434/v1",
         env_key: "",
         needs_key: false,
     },
     LlmProvider {
+        id: "openai",
         name: "OpenAI",
         api_base: "https://api.openai.com/v1",
         env_key: "OPENAI_API_KEY",
         needs_key: true,
     },
     LlmProvider {
+        id: "deepseek",
         name: "DeepSeek",
         api_base: "https://api.deepseek.com/v1",
         env_key: "DEEPSEEK_API_KEY",
         needs_key: true,
     },
     LlmProvider {
+        id: "moonshot",
+        name: "Moonshot (Kimi)",
+        api_base: "https://api.moonshot.cn/v1",
+        env_key: "MOONSHOT_API_KEY",
+        needs_key: true,
+    },
+    LlmProvider {
+        id: "qwen",
         name: "Qwen (Alibaba Cloud)",
         api_base: "https://dashscope.aliyuncs.com/compatible-mode/v1",
         env_key: "DASHSCOPE_API_KEY",
@@ -54,81 +74,323 @@ const PROVIDERS: &[LlmProvider] = &[
     },
 ];
 
+/// Placeholder key for providers that do not need one (the field can't be empty).
+const NO_KEY: &str = "ollama";
+
+/// CLI options for `skilllite quickstart`.
+#[derive(Debug, Clone, Default)]
+pub struct QuickstartOptions<'a> {
+    pub skills_dir: &'a str,
+    /// Apply the flags below, validate, save and exit without prompting or chatting
+    pub non_interactive: bool,
+    /// Preset id: openai, deepseek, ollama, moonshot, qwen or custom
+    pub provider: Option<&'a str>,
+    pub api_base: Option<&'a str>,
+    pub api_key: Option<&'a str>,
+    pub model: Option<&'a str>,
+    pub workspace: Option<&'a str>,
+    /// Save without the live 1-token completion check
+    pub skip_validation: bool,
+}
+
+/// LLM settings being set up; mirrors the fields of `config.toml`.
+#[derive(Debug, Clone)]
+struct LlmSettings {
+    provider: String,
+    api_base: String,
+    api_key: String,
+    model: String,
+    workspace: String,
+}
+
+impl LlmSettings {
+    fn from_file(file: &UserConfigFile) -> Option<Self> {
+        let api_base = file.api_base.clone()?;
+        Some(Self {
+            provider: file
+                .provider
+                .clone()
+                .unwrap_or_else(|| provider_id_for_base(&api_base).to_string()),
+            api_key: file.api_key.clone().unwrap_or_default(),
+            model: file
+                .model
+                .clone()
+                .unwrap_or_else(|| LlmConfig::default_model_for_base(&api_base).to_string()),
+            workspace: file.workspace.clone().unwrap_or_else(current_dir_string),
+            api_base,
+        })
+    }
+
+    fn to_file(&self) -> UserConfigFile {
+        let non_empty = |s: &str| Some(s.to_string()).filter(|s| !s.is_empty());
+        UserConfigFile {
+            provider: non_empty(&self.provider),
+            api_base: non_empty(&self.api_base),
+            api_key: non_empty(&self.api_key),
+            model: non_empty(&self.model),
+            workspace: non_empty(&self.workspace),
+        }
+    }
+
+    fn print(&self) {
+        eprintln!("     1. Provider:  {}", provider_name(&self.provider));
+        eprintln!("     2. API base:  {}", self.api_base);
+        eprintln!("     3. API key:   {}", mask_secret(&self.api_key));
+        eprintln!("     4. Model:     {}", self.model);
+        eprintln!("     5. Workspace: {}", self.workspace);
+    }
+}
+
 /// `skilllite quickstart`
-pub fn cmd_quickstart(skills_dir: &str) -> Result<()> {
-    crate::init::reject_relative_skills_dir_when_cwd_root(skills_dir)?;
-    let skills_path = crate::init::resolve_path_with_legacy_fallback(skills_dir);
+pub fn cmd_quickstart(opts: QuickstartOptions<'_>) -> Result<()> {
+    if opts.non_interactive {
+        return non_interactive_setup(&opts);
+    }
+
+    crate::init::reject_relative_skills_dir_when_cwd_root(opts.skills_dir)?;
+    let skills_path = crate::init::resolve_path_with_legacy_fallback(opts.skills_dir);
 
     eprintln!("🚀 SkillLite Quickstart");
     eprintln!("   Zero-config setup — let's get you chatting with AI skills!");
     eprintln!();
 
-    // Step 1: LLM setup
-    let (api_base, api_key, model) = setup_llm()?;
+    // Step 1: LLM setup (validated and saved to config.toml)
+    let settings = setup_llm(opts.skip_validation)?;
     eprintln!();
 
     // Step 2: Ensure skills
     ensure_skills(&skills_path)?;
     eprintln!();
 
-    // Step 3: Write .env if it doesn't exist (so future runs remember)
-    write_env_if_needed(&api_base, &api_key, &model)?;
-
-    // Step 4: Launch chat
+    // Step 3: Launch chat
     eprintln!("🤖 Starting chat...");
     eprintln!();
 
-    launch_chat(&api_base, &api_key, &model, &skills_path)
+    launch_chat(&settings, &skills_path)
+}
+
+/// `--non-interactive`: flags override the saved file field by field.
+fn non_interactive_setup(opts: &QuickstartOptions<'_>) -> Result<()> {
+    let file = UserConfigFile::load();
+    let mut settings = LlmSettings::from_file(&file).unwrap_or_else(|| LlmSettings {
+        provider: String::new(),
+        api_base: String::new(),
+        api_key: String::new(),
+        model: String::new(),
+        workspace: file.workspace.clone().unwrap_or_else(current_dir_string),
+    });
+
+    if let Some(id) = opts.provider {
+        let id = id.trim().to_lowercase();
+        match find_provider(&id) {
+            Some(p) => {
+                settings.api_base = p.api_base.to_string();
+                settings.model = LlmConfig::default_model_for_base(p.api_base).to_string();
+            }
+            None if id == "custom" => {}
+            None => bail!(
+                "Unknown provider '{}'. Use one of: {}, custom",
+                id,
+                PROVIDERS
+                    .iter()
+                    .map(|p| p.id)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+        settings.provider = id;
+    }
+    if let Some(base) = opts.api_base {
+        settings.api_base = base.trim().trim_end_matches('/').to_string();
+        if opts.provider.is_none() {
+            settings.provider = provider_id_for_base(&settings.api_base).to_string();
+        }
+    }
+    if let Some(key) = opts.api_key {
+        settings.api_key = key.trim().to_string();
+    }
+    if let Some(model) = opts.model {
+        settings.model = model.trim().to_string();
+    }
+    if let Some(ws) = opts.workspace {
+        settings.workspace = ws.to_string();
+    }
+
+    if settings.api_base.is_empty() {
+        bail!("--provider or --api-base is required (no saved configuration found)");
+    }
+    if settings.model.is_empty() {
+        settings.model = LlmConfig::default_model_for_base(&settings.api_base).to_string();
+    }
+    if settings.api_key.is_empty() {
+        if needs_key(&settings.provider) {
+            bail!("--api-key is required for provider '{}'", settings.provider);
+        }
+        settings.api_key = NO_KEY.to_string();
+    }
+
+    if !opts.skip_validation {
+        eprintln!(
+            "🔍 Validating {} at {}...",
+            settings.model, settings.api_base
+        );
+        validate_llm(&settings)?;
+        eprintln!("   ✅ Provider responded");
+    }
+    save_settings(&settings)
 }
 
-/// Step 1: Detect or interactively set up LLM configuration.
-/// Returns (api_base, api_key, model).
-fn setup_llm() -> Result<(String, String, String)> {
+/// Step 1: Load, detect or interactively set up LLM configuration.
+fn setup_llm(skip_validation: bool) -> Result<LlmSettings> {
     eprintln!("🔍 Step 1/3: Detecting LLM configuration...");
 
-    // Priority 1: Check existing .env / environment variables
-    if let Some(config) = detect_existing_config() {
-        eprintln!("   ✅ Found existing configuration:");
-        eprintln!("      API Base: {}", config.0);
-        eprintln!("      Model: {}", config.2);
-        return Ok(config);
+    // Priority 1: Saved config.toml — show current values and allow edits
+    if let Some(saved) = LlmSettings::from_file(&UserConfigFile::load()) {
+        eprintln!(
+            "   ✅ Found saved configuration ({}):",
+            UserConfigFile::path().display()
+        );
+        let settings = edit_fields(saved)?;
+        return validate_and_save(settings, skip_validation);
+    }
+
+    // Priority 2: Existing .env / environment variables (already configured; not copied)
+    if let Some(settings) = detect_existing_config() {
+        eprintln!("   ✅ Found existing configuration in environment / .env:");
+        eprintln!("      API Base: {}", settings.api_base);
+        eprintln!("      Model: {}", settings.model);
+        return Ok(settings);
     }
 
-    // Priority 2: Probe Ollama
-    if let Some(config) = probe_ollama() {
+    // Priority 3: Probe Ollama
+    if let Some(settings) = probe_ollama() {
         eprintln!("   ✅ Detected local Ollama instance:");
-        eprintln!("      Model: {}", config.2);
-        return Ok(config);
+        eprintln!("      Model: {}", settings.model);
+        return validate_and_save(settings, skip_validation);
     }
 
-    // Priority 3: Interactive selection
+    // Priority 4: Interactive selection
     eprintln!("   No LLM configuration found. Let's set one up!");
     eprintln!();
-    interactive_llm_setup()
+    let settings = interactive_llm_setup()?;
+    validate_and_save(settings, skip_validation)
+}
+
+/// Live-check the settings, offering to edit and retry on failure, then save.
+fn validate_and_save(mut settings: LlmSettings, skip_validation: bool) -> Result<LlmSettings> {
+    if !skip_validation {
+        loop {
+            eprintln!("   Validating with a 1-token completion...");
+            match validate_llm(&settings) {
+                Ok(()) => {
+                    eprintln!("   ✅ Provider responded");
+                    break;
+                }
+                Err(e) => {
+                    eprintln!("   ❌ Validation failed: {}", e);
+                    let answer =
+                        prompt_line("   Edit and retry (e), save anyway (s), quit (q) [e]: ")?;
+                    match answer.to_lowercase().as_str() {
+                        "s" => break,
+                        "q" => bail!("Quickstart aborted; configuration not saved"),
+                        _ => settings = edit_fields(settings)?,
+                    }
+                }
+            }
+        }
+    }
+    save_settings(&settings)?;
+    Ok(settings)
+}
+
+fn save_settings(settings: &LlmSettings) -> Result<()> {
+    let path = settings
+        .to_file()
+        .save()
+        .context("Failed to write quickstart configuration")?;
+    eprintln!("💾 Saved configuration to {}", path.display());
+    Ok(())
+}
+
+/// Show the current values and let the user change individual fields.
+fn edit_fields(mut settings: LlmSettings) -> Result<LlmSettings> {
+    loop {
+        settings.print();
+        let choice = prompt_line("   Field to edit [1-5], Enter to continue: ")?;
+        match choice.as_str() {
+            "" => return Ok(settings),
+            "1" => {
+                let provider = select_provider()?;
+                settings.provider = provider.map(|p| p.id).unwrap_or("custom").to_string();
+                match provider {
+                    Some(p) => {
+                        settings.api_base = p.api_base.to_string();
+                        settings.api_key = if p.needs_key {
+                            key_from_env_or_prompt(p)?
+                        } else {
+                            NO_KEY.to_string()
+                        };
+                    }
+                    None => {
+                        settings.api_base = prompt_line("   API Base URL: ")?;
+                        settings.api_key = prompt_api_key("API_KEY")?;
+                    }
+                }
+                settings.model = LlmConfig::default_model_for_base(&settings.api_base).to_string();
+            }
+            "2" => {
+                let base = prompt_line(&format!("   API base [{}]: ", settings.api_base))?;
+                if !base.is_empty() {
+                    settings.api_base = base.trim_end_matches('/').to_string();
+                }
+            }
+            "3" => settings.api_key = prompt_api_key("API_KEY")?,
+            "4" => {
+                let model = prompt_line(&format!("   Model [{}]: ", settings.model))?;
+                if !model.is_empty() {
+                    settings.model = model;
+                }
+            }
+            "5" => {
+                let ws = prompt_line(&format!("   Workspace [{}]: ", settings.workspace))?;
+                if !ws.is_empty() {
+                    settings.workspace = ws;
+                }
+            }
+            other => eprintln!("   Unknown field: {}", other),
+        }
+        eprintln!();
+    }
 }
 
 /// Check if .env or environment variables already have valid LLM config.
-fn detect_existing_config() -> Option<(String, String, String)> {
+fn detect_existing_config() -> Option<LlmSettings> {
     skilllite_core::config::load_dotenv();
-    let cfg = skilllite_core::config::LlmConfig::try_from_env()?;
+    let cfg = LlmConfig::try_from_env()?;
     if cfg.api_key == "sk-xxx" {
         return None;
     }
     let model = if cfg.model.is_empty() {
-        skilllite_core::config::LlmConfig::default_model_for_base(&cfg.api_base).to_string()
+        LlmConfig::default_model_for_base(&cfg.api_base).to_string()
     } else {
         cfg.model
     };
-    Some((cfg.api_base, cfg.api_key, model))
+    Some(LlmSettings {
+        provider: provider_id_for_base(&cfg.api_base).to_string(),
+        api_base: cfg.api_base,
+        api_key: cfg.api_key,
+        model,
+        workspace: skilllite_core::config::PathsConfig::from_env().workspace,
+    })
 }
 
 /// Probe Ollama at localhost:11434.
-fn probe_ollama() -> Option<(String, String, String)> {
+fn probe_ollama() -> Option<LlmSettings> {
     eprintln!("   Probing Ollama at localhost:11434...");
 
     // Use a blocking HTTP GET with short timeout
     let url = "http://localhost:11434/api/tags";
-    let output = std::process::Command::new("curl")
+    let output = Command::new("curl")
         .args(["-s", "--connect-timeout", "2", "--max-time", "3", url])
         .output()
         .ok()?;
@@ -181,15 +443,68 @@ fn probe_ollama() -> Option<(String, String, String)> {
         selected
     );
 
-    Some((
-        "http://localhost:11434/v1".to_string(),
-        "ollama".to_string(), // Ollama doesn't need a real key, but the field can't be empty
-        selected,
-    ))
+    Some(LlmSettings {
+        provider: "ollama".to_string(),
+        api_base: "http://localhost:11434/v1".to_string(),
+        api_key: NO_KEY.to_string(),
+        model: selected,
+        workspace: current_dir_string(),
+    })
 }
 
-/// Interactive provider selection.
-fn interactive_llm_setup() -> Result<(String, String, String)> {
+/// Interactive provider, key, model and workspace selection.
+fn interactive_llm_setup() -> Result<LlmSettings> {
+    let provider = select_provider()?;
+    let (provider_id, api_base, api_key) = match provider {
+        Some(p) => {
+            let api_key = if p.needs_key {
+                key_from_env_or_prompt(p)?
+            } else {
+                NO_KEY.to_string()
+            };
+            (p.id.to_string(), p.api_base.to_string(), api_key)
+        }
+        None => {
+            let api_base = prompt_line("   API Base URL: ")?;
+            if api_base.is_empty() {
+                bail!("API base URL is required for a custom provider");
+            }
+            let api_key = prompt_api_key("API_KEY")?;
+            (
+                "custom".to_string(),
+                api_base.trim_end_matches('/').to_string(),
+                api_key,
+            )
+        }
+    };
+
+    let default_model = LlmConfig::default_model_for_base(&api_base);
+    let model = prompt_line(&format!("   Model [{}]: ", default_model))?;
+    let model = if model.is_empty() {
+        default_model.to_string()
+    } else {
+        model
+    };
+
+    let default_ws = current_dir_string();
+    let workspace = prompt_line(&format!("   Workspace [{}]: ", default_ws))?;
+    let workspace = if workspace.is_empty() {
+        default_ws
+    } else {
+        workspace
+    };
+
+    Ok(LlmSettings {
+        provider: provider_id,
+        api_base,
+        api_key,
+        model,
+        workspace,
+    })
+}
+
+/// Returns the chosen preset, or `None` for a custom endpoint.
+fn select_provider() -> Result<Option<&'static LlmProvider>> {
     eprintln!("   Select LLM provider:");
     for (i, provider) in PROVIDERS.iter().enumerate() {
         eprintln!("     {}. {}", i + 1, provider.name);
@@ -197,72 +512,173 @@ fn interactive_llm_setup() -> Result<(String, String, String)> {
     eprintln!("     {}. Custom (enter your own URL)", PROVIDERS.len() + 1);
     eprintln!();
 
-    eprint!("   Choice [1]: ");
-    std::io::stderr().flush()?;
-    let mut choice = String::new();
-    std::io::stdin().read_line(&mut choice)?;
-    let choice = choice.trim();
+    let choice = prompt_line("   Choice [1]: ")?;
     let idx: usize = if choice.is_empty() {
         0
     } else {
         choice.parse::<usize>().unwrap_or(1).saturating_sub(1)
     };
+    Ok(PROVIDERS.get(idx))
+}
 
-    if idx < PROVIDERS.len() {
-        let provider = &PROVIDERS[idx];
-        let api_base = provider.api_base.to_string();
-
-        let api_key = if provider.needs_key {
-            // Check env first
-            if let Ok(key) = std::env::var(provider.env_key) {
-                if !key.is_empty() && key != "sk-xxx" {
-                    eprintln!("   ✅ Using {} from environment", provider.env_key);
-                    key
-                } else {
-                    prompt_api_key(provider.env_key)?
-                }
-            } else {
-                prompt_api_key(provider.env_key)?
-            }
-        } else {
-            "ollama".to_string()
-        };
+fn key_from_env_or_prompt(provider: &LlmProvider) -> Result<String> {
+    if let Ok(key) = std::env::var(provider.env_key) {
+        if !key.is_empty() && key != "sk-xxx" {
+            eprintln!("   ✅ Using {} from environment", provider.env_key);
+            return Ok(key);
+        }
+    }
+    prompt_api_key(provider.env_key)
+}
+
+fn prompt_api_key(env_var_name: &str) -> Result<String> {
+    let key = read_secret(&format!(
+        "   API Key (input hidden; or set {} env var): ",
+        env_var_name
+    ))?;
+    if key.is_empty() {
+        bail!("API key is required for this provider");
+    }
+    Ok(key)
+}
 
-        let model =
-            skilllite_core::config::LlmConfig::default_model_for_base(&api_base).to_string();
-        eprintln!("   Model: {} (change via SKILLLITE_MODEL env var)", model);
+fn prompt_line(prompt: &str) -> Result<String> {
+    eprint!("{}", prompt);
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    if std::io::stdin().read_line(&mut line)? == 0 {
+        bail!("Quickstart aborted (end of input)");
+    }
+    Ok(line.trim().to_string())
+}
 
-        Ok((api_base, api_key, model))
-    } else {
-        // Custom
-        eprint!("   API Base URL: ");
-        std::io::stderr().flush()?;
-        let mut api_base = String::new();
-        std::io::stdin().read_line(&mut api_base)?;
-        let api_base = api_base.trim().to_string();
+/// Read a line with terminal echo disabled (falls back to plain input when stdin is not a tty).
+fn read_secret(prompt: &str) -> Result<String> {
+    eprint!("{}", prompt);
+    std::io::stderr().flush()?;
+    let echo_off = set_terminal_echo(false);
+    let mut line = String::new();
+    let read = std::io::stdin().read_line(&mut line);
+    if echo_off {
+        set_terminal_echo(true);
+        eprintln!();
+    }
+    read?;
+    Ok(line.trim().to_string())
+}
 
-        let api_key = prompt_api_key("API_KEY")?;
+#[cfg(unix)]
+fn set_terminal_echo(on: bool) -> bool {
+    use std::io::IsTerminal;
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stdin(Stdio::inherit())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
 
-        eprint!("   Model name: ");
-        std::io::stderr().flush()?;
-        let mut model = String::new();
-        std::io::stdin().read_line(&mut model)?;
-        let model = model.trim().to_string();
+#[cfg(not(unix))]
+fn set_terminal_echo(_on: bool) -> bool {
+    false
+}
 
-        Ok((api_base, api_key, model))
+fn validate_llm(settings: &LlmSettings) -> Result<()> {
+    validate_llm_endpoint(&settings.api_base, &settings.api_key, &settings.model)
+}
+
+/// Send a 1-token chat completion; any 2xx response counts as valid.
+/// Also used by `skilllite doctor --online`.
+pub(crate) fn validate_llm_endpoint(api_base: &str, api_key: &str, model: &str) -> Result<()> {
+    let url = format!("{}/chat/completions", api_base.trim_end_matches('/'));
+    let body = serde_json::json!({
+        "model": model,
+        "messages": [{"role": "user", "content": "ping"}],
+        "max_tokens": 1,
+    });
+    // The Authorization header goes through stdin so the key never shows up in `ps`
+    let mut child = Command::new("curl")
+        .args(["-sS", "--max-time", "30", "-w", "\n%{http_code}"])
+        .args(["-H", "Content-Type: application/json", "-H", "@-"])
+        .args(["-d", &body.to_string(), &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl for validation (is curl installed?)")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "Authorization: Bearer {}", api_key)?;
+    }
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        bail!(
+            "could not reach {}: {}",
+            url,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
     }
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let (response, status) = stdout.rsplit_once('\n').unwrap_or(("", &*stdout));
+    let status: u16 = status.trim().parse().unwrap_or(0);
+    if (200..300).contains(&status) {
+        return Ok(());
+    }
+    let detail = serde_json::from_str::<serde_json::Value>(response)
+        .ok()
+        .and_then(|v| {
+            v.pointer("/error/message")
+                .and_then(|m| m.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| response.chars().take(200).collect());
+    bail!("HTTP {} from {}: {}", status, url, detail)
 }
 
-fn prompt_api_key(env_var_name: &str) -> Result<String> {
-    eprint!("   API Key (or set {} env var): ", env_var_name);
-    std::io::stderr().flush()?;
-    let mut key = String::new();
-    std::io::stdin().read_line(&mut key)?;
-    let key = key.trim().to_string();
-    if key.is_empty() {
-        bail!("API key is required for this provider");
+fn find_provider(id: &str) -> Option<&'static LlmProvider> {
+    PROVIDERS.iter().find(|p| p.id == id)
+}
+
+fn provider_id_for_base(api_base: &str) -> &'static str {
+    PROVIDERS
+        .iter()
+        .find(|p| api_base.trim_end_matches('/') == p.api_base)
+        .map(|p| p.id)
+        .unwrap_or("custom")
+}
+
+fn provider_name(id: &str) -> &str {
+    find_provider(id).map(|p| p.name).unwrap_or(id)
+}
+
+fn needs_key(provider_id: &str) -> bool {
+    find_provider(provider_id)
+        .map(|p| p.needs_key)
+        .unwrap_or(true)
+}
+
+fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if secret.is_empty() {
+        "(not set)".to_string()
+    } else if secret == NO_KEY {
+        "(not needed)".to_string()
+    } else if chars.len() <= 10 {
+        "****".to_string()
+    } else {
+        let head: String = chars[..3].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}…{}", head, tail)
     }
-    Ok(key)
+}
+
+fn current_dir_string() -> String {
+    std::env::current_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .to_string_lossy()
+        .to_string()
 }
 
 /// Step 2: Ensure skills are available.
@@ -285,57 +701,25 @@ fn ensure_skills(skills_path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Write .env file if it doesn't already exist.
-fn write_env_if_needed(api_base: &str, api_key: &str, model: &str) -> Result<()> {
-    let env_path = std::env::current_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join(".env");
-
-    if env_path.exists() {
-        return Ok(());
-    }
-
-    // Don't write .env if using Ollama (no sensitive key)
-    if api_key == "ollama" {
-        skilllite_core::config::init_llm_env(api_base, api_key, model);
-        return Ok(());
-    }
-
-    let content = format!(
-        "# SkillLite LLM Configuration (auto-generated by quickstart)\n\
-         OPENAI_API_BASE={}\n\
-         OPENAI_API_KEY={}\n\
-         SKILLLITE_MODEL={}\n",
-        api_base, api_key, model
-    );
-
-    fs::write(&env_path, &content)
-        .with_context(|| format!("Failed to write .env file: {}", env_path.display()))?;
-
-    eprintln!("💾 Saved configuration to .env");
-    eprintln!("   ⚠ Add .env to .gitignore to avoid leaking your API key!");
-
-    Ok(())
-}
-
 /// Step 3: Launch the chat session.
 #[cfg(feature = "agent")]
-fn launch_chat(api_base: &str, api_key: &str, model: &str, skills_path: &Path) -> Result<()> {
+fn launch_chat(settings: &LlmSettings, skills_path: &Path) -> Result<()> {
     use skilllite_agent::skills;
     use skilllite_agent::types::*;
 
-    skilllite_core::config::init_llm_env(api_base, api_key, model);
+    skilllite_core::config::init_llm_env(&settings.api_base, &settings.api_key, &settings.model);
 
     let mut config = AgentConfig::from_env();
-    config.api_base = api_base.to_string();
-    config.api_key = api_key.to_string();
-    config.model = model.to_string();
+    config.api_base = settings.api_base.clone();
+    config.api_key = settings.api_key.clone();
+    config.model = settings.model.clone();
+    config.workspace = settings.workspace.clone();
     config.enable_task_planning = true;
 
     // Discover skills
     let mut skill_dirs = Vec::new();
     if skills_path.is_dir() {
-        if let Ok(entries) = fs::read_dir(skills_path) {
+        if let Ok(entries) = std::fs::read_dir(skills_path) {
             for entry in entries.flatten() {
                 let p = entry.path();
                 if p.is_dir() && p.join("SKILL.md").exists() {
@@ -368,7 +752,7 @@ async fn run_interactive_quickstart(
     use skilllite_agent::types::*;
 
     eprintln!("🤖 SkillBox Quickstart Chat (model: {})", config.model);
-    eprintln!("   Type /exit to quit, /clear to reset, /compact to compress history");
+    eprintln!("   Type /exit to quit, /clear to reset, /compact to compress history, /reload-skills to pick up skill changes");
     eprintln!();
 
     let mut session = ChatSession::new(config, "quickstart", skills);
@@ -406,6 +790,10 @@ async fn run_interactive_quickstart(
                         }
                         continue;
                     }
+                    "/reload-skills" => {
+                        session.reload_skills(&mut sink);
+                        continue;
+                    }
                     _ => {}
                 }
 
@@ -435,11 +823,12 @@ async fn run_interactive_quickstart(
         }
     }
 
+    skilllite_agent::extensions::stop_background_jobs(Some(session.session_key()));
     Ok(())
 }
 
 #[cfg(not(feature = "agent"))]
-fn launch_chat(_api_base: &str, _api_key: &str, _model: &str, _skills_path: &Path) -> Result<()> {
+fn launch_chat(_settings: &LlmSettings, _skills_path: &Path) -> Result<()> {
     bail!(
         "The `agent` feature is required for quickstart chat.\n\
          Rebuild with: cargo build --features agent"
@@ -1,17 +1,109 @@
 //! Reindex command: rescan skills directory and rebuild metadata cache.
 //!
-//! Validates all SKILL.md files, checks dependencies, and reports status.
+//! Validates all SKILL.md files, checks dependencies, and reports status. Also detects skill
+//! names provided by more than one skills directory (`skills/`, `.skills/`, `_evolved/`) and,
+//! with `--resolve`, marks the losing copies `skip` in their manifest.
+//!
+//! `--memory` instead rebuilds the memory vector index with the configured embedding backend
+//! (needed after switching `SKILLLITE_EMBEDDING_BACKEND` / model; requires `memory_vector`).
 
 use anyhow::Context;
 use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+use skilllite_core::skill::discovery::{self, SkillNameCollision};
 use skilllite_core::skill::manifest;
 use skilllite_core::skill::metadata;
 
+use crate::error::bail;
 use crate::Result;
 
+/// How `--resolve` picks the copy that stays enabled for a duplicated skill name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateResolution {
+    /// Keep the copy whose files were modified most recently.
+    KeepNewest,
+    /// Keep the copy loaders already prefer (first configured dir).
+    KeepFirst,
+    /// Ask for each name.
+    Interactive,
+}
+
+impl std::str::FromStr for DuplicateResolution {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "keep-newest" => Ok(Self::KeepNewest),
+            "keep-first" => Ok(Self::KeepFirst),
+            "interactive" => Ok(Self::Interactive),
+            other => bail!(
+                "Unknown --resolve value '{}' (expected keep-newest, keep-first or interactive)",
+                other
+            ),
+        }
+    }
+}
+
+/// Re-embed all memory files (`~/.skilllite/chat/memory/*.md`) into the vector index.
+pub fn cmd_reindex_memory(verbose: bool) -> Result<()> {
+    #[cfg(feature = "memory_vector")]
+    {
+        use skilllite_agent::extensions::{reembed_memory, MemoryVectorContext};
+        use skilllite_agent::llm::LlmClient;
+        use skilllite_agent::types::AgentConfig;
+        use skilllite_core::config::EmbeddingConfig;
+
+        let embed_config = EmbeddingConfig::from_env();
+        if !embed_config.is_usable() {
+            bail!(
+                "No embedding API key configured \
+                 (set SKILLLITE_EMBEDDING_API_KEY or use SKILLLITE_EMBEDDING_BACKEND=local)"
+            );
+        }
+        let client = LlmClient::from_config(&AgentConfig::from_env())?;
+        let ctx = MemoryVectorContext {
+            client: &client,
+            embed_config: &embed_config,
+        };
+        let chat_root = skilllite_core::paths::chat_root();
+        eprintln!(
+            "🔍 Re-embedding memory in {} with {} ...",
+            chat_root.join("memory").display(),
+            embed_config.fingerprint()
+        );
+        let started = std::time::Instant::now();
+        let rt = tokio::runtime::Runtime::new().context("tokio runtime init failed")?;
+        let report = rt.block_on(reembed_memory(&chat_root, "default", &ctx))?;
+        eprintln!(
+            "✅ {} file(s), {} chunk(s) embedded",
+            report.files, report.chunks
+        );
+        if verbose {
+            eprintln!(
+                "   index fingerprint: {} ({:.1}s)",
+                report.fingerprint,
+                started.elapsed().as_secs_f64()
+            );
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "memory_vector"))]
+    {
+        let _ = verbose;
+        bail!("reindex --memory requires a build with the `memory_vector` feature")
+    }
+}
+
 /// `skilllite reindex`
-pub fn cmd_reindex(skills_dir: &str, verbose: bool, rebuild_manifest: bool) -> Result<()> {
+pub fn cmd_reindex(
+    skills_dir: &str,
+    verbose: bool,
+    rebuild_manifest: bool,
+    resolve: Option<&str>,
+) -> Result<()> {
+    let resolve = resolve.map(str::parse::<DuplicateResolution>).transpose()?;
     crate::init::reject_relative_skills_dir_when_cwd_root(skills_dir)?;
     let skills_path = crate::init::resolve_path_with_legacy_fallback(skills_dir);
 
@@ -135,5 +227,207 @@ pub fn cmd_reindex(skills_dir: &str, verbose: bool, rebuild_manifest: bool) -> R
         eprintln!("⚠ Fix errors in SKILL.md files above to ensure proper functionality.");
     }
 
+    let workspace = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let mut dirs = vec![skills_path.clone()];
+    dirs.extend(
+        discovery::discover_skill_dirs_for_loading(&workspace, Some(&[".skills", "skills"]))
+            .into_iter()
+            .map(PathBuf::from),
+    );
+    let collisions = discovery::find_skill_name_collisions(&dirs);
+    if collisions.is_empty() {
+        return Ok(());
+    }
+    eprintln!();
+    for collision in &collisions {
+        report_collision(collision);
+    }
+    let Some(strategy) = resolve else {
+        eprintln!(
+            "⚠ {} duplicate skill name(s); the first copy listed is loaded. \
+Run `skilllite reindex --resolve keep-newest|keep-first|interactive` to disable the others.",
+            collisions.len()
+        );
+        return Ok(());
+    };
+    for collision in &collisions {
+        let keep = choose_winner(collision, strategy)?;
+        for (i, path) in collision.paths.iter().enumerate() {
+            if i == keep {
+                continue;
+            }
+            manifest::set_skill_skipped(path, true)?;
+            eprintln!("  ⏸ {}: disabled {}", collision.name, path.display());
+        }
+        eprintln!(
+            "  ✓ {}: keeping {}",
+            collision.name,
+            collision.paths[keep].display()
+        );
+    }
     Ok(())
 }
+
+fn report_collision(collision: &SkillNameCollision) {
+    eprintln!(
+        "⚠ Duplicate skill name '{}' ({} copies):",
+        collision.name,
+        collision.paths.len()
+    );
+    let first = &collision.paths[0];
+    for (i, path) in collision.paths.iter().enumerate() {
+        let modified = newest_mtime(path)
+            .map(|t| {
+                chrono::DateTime::<chrono::Local>::from(t)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "?".to_string());
+        let note = if i == 0 {
+            "loaded".to_string()
+        } else {
+            let diffs = metadata_differences(first, path);
+            if diffs.is_empty() {
+                "identical".to_string()
+            } else {
+                format!("differs: {}", diffs.join(", "))
+            }
+        };
+        eprintln!(
+            "    {}. {}  (modified {}, {})",
+            i + 1,
+            path.display(),
+            modified,
+            note
+        );
+    }
+}
+
+/// Names of the metadata fields (and file contents) that differ between two skill copies.
+fn metadata_differences(a: &Path, b: &Path) -> Vec<&'static str> {
+    let (Ok(ma), Ok(mb)) = (
+        metadata::parse_skill_metadata(a),
+        metadata::parse_skill_metadata(b),
+    ) else {
+        return vec!["SKILL.md"];
+    };
+    let mut diffs = Vec::new();
+    if ma.description != mb.description {
+        diffs.push("description");
+    }
+    if ma.version != mb.version {
+        diffs.push("version");
+    }
+    if ma.entry_point != mb.entry_point {
+        diffs.push("entry_point");
+    }
+    if ma.language != mb.language {
+        diffs.push("language");
+    }
+    if ma.compatibility != mb.compatibility {
+        diffs.push("compatibility");
+    }
+    if manifest::compute_skill_fingerprint(a).ok() != manifest::compute_skill_fingerprint(b).ok() {
+        diffs.push("files");
+    }
+    diffs
+}
+
+/// Latest modification time of any file in the skill (SKILL.md and scripts).
+fn newest_mtime(skill_dir: &Path) -> Option<std::time::SystemTime> {
+    walkdir::WalkDir::new(skill_dir)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+}
+
+fn choose_winner(collision: &SkillNameCollision, strategy: DuplicateResolution) -> Result<usize> {
+    match strategy {
+        DuplicateResolution::KeepFirst => Ok(0),
+        DuplicateResolution::KeepNewest => Ok(collision
+            .paths
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, p)| (newest_mtime(p), std::cmp::Reverse(*i)))
+            .map(|(i, _)| i)
+            .unwrap_or(0)),
+        DuplicateResolution::Interactive => loop {
+            eprint!(
+                "Keep which copy of '{}'? [1-{}, default 1]: ",
+                collision.name,
+                collision.paths.len()
+            );
+            std::io::stderr().flush()?;
+            let mut line = String::new();
+            if std::io::stdin().read_line(&mut line)? == 0 {
+                bail!("Aborted (end of input)");
+            }
+            let line = line.trim();
+            if line.is_empty() {
+                return Ok(0);
+            }
+            match line.parse::<usize>() {
+                Ok(n) if (1..=collision.paths.len()).contains(&n) => return Ok(n - 1),
+                _ => eprintln!("  Enter a number between 1 and {}", collision.paths.len()),
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_skill(dir: &Path, name: &str, description: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {name}\ndescription: {description}\n---\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn keep_newest_picks_latest_modified_copy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let old = tmp.path().join("a/web-search");
+        let new = tmp.path().join("b/web-search");
+        write_skill(&old, "web-search", "old");
+        write_skill(&new, "web-search", "new");
+        let past = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        fs::File::options()
+            .write(true)
+            .open(old.join("SKILL.md"))
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+
+        let collision = SkillNameCollision {
+            name: "web-search".to_string(),
+            paths: vec![old.clone(), new.clone()],
+        };
+        assert_eq!(
+            choose_winner(&collision, DuplicateResolution::KeepNewest).unwrap(),
+            1
+        );
+        assert_eq!(
+            choose_winner(&collision, DuplicateResolution::KeepFirst).unwrap(),
+            0
+        );
+        assert_eq!(
+            metadata_differences(&old, &new),
+            vec!["description", "files"]
+        );
+    }
+
+    #[test]
+    fn resolve_values_parse() {
+        assert_eq!(
+            "keep-newest".parse::<DuplicateResolution>().unwrap(),
+            DuplicateResolution::KeepNewest
+        );
+        assert!("newest".parse::<DuplicateResolution>().is_err());
+    }
+}
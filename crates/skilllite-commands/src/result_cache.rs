@@ -0,0 +1,202 @@
+//! Result cache for pure skills (SKILL.md `cache: { enabled: true, ttl: 3600 }`).
+//!
+//! Entries live in `<cache>/skilllite/results/<key>.json` (next to the `envs/` directory).
+//! The key is a SHA-256 over the skill's content fingerprint, the selected entry point, the
+//! input JSON and the values of the environment variables the skill declares in
+//! `cache.env`, so editing any script or changing the input misses. A hit refreshes the
+//! file's mtime; once the directory exceeds `SKILLLITE_RESULT_CACHE_MAX_MB` the least
+//! recently used entries are removed.
+//!
+//! Writes go to a temp file and are renamed into place, so a concurrent reader never sees a
+//! partial entry; when two runs store the same key the last rename wins.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use skilllite_core::config::env_keys::cache as cache_env;
+use skilllite_core::skill::manifest::compute_skill_fingerprint;
+use skilllite_core::skill::metadata::ResultCachePolicy;
+
+use crate::Result;
+
+/// Marker added to a result served from the cache.
+pub const CACHED_KEY: &str = "cached";
+
+/// Default for `SKILLLITE_RESULT_CACHE_MAX_MB`.
+const DEFAULT_MAX_MB: u64 = 64;
+
+/// Bump when the key derivation or entry layout changes.
+const KEY_VERSION: &str = "1";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    skill: String,
+    created_at: u64,
+    ttl_secs: u64,
+    output: String,
+}
+
+/// `<cache>/skilllite/results`, or under `cache_dir` when given (same base as the env cache).
+pub fn results_dir(cache_dir: Option<&str>) -> Option<PathBuf> {
+    let envs = skilllite_sandbox::env::builder::get_cache_dir(cache_dir)?;
+    Some(envs.parent()?.join("results"))
+}
+
+fn max_bytes() -> u64 {
+    std::env::var(cache_env::SKILLLITE_RESULT_CACHE_MAX_MB)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MAX_MB)
+        .saturating_mul(1024 * 1024)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Cache key for one call of `entry_point` in `skill_path` with `input_json`.
+pub fn cache_key(
+    skill_path: &Path,
+    entry_point: &str,
+    input_json: &str,
+    policy: &ResultCachePolicy,
+) -> Result<String> {
+    let fingerprint = compute_skill_fingerprint(skill_path)?;
+    // Key order is fixed by serde_json, so equivalent inputs share an entry.
+    let input = serde_json::from_str::<Value>(input_json)?.to_string();
+    let mut hasher = Sha256::new();
+    for part in [
+        KEY_VERSION,
+        env!("CARGO_PKG_VERSION"),
+        &fingerprint,
+        entry_point,
+        &input,
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+    let mut env_names = policy.env.clone();
+    env_names.sort();
+    env_names.dedup();
+    for name in env_names {
+        hasher.update(name.as_bytes());
+        hasher.update([b'=']);
+        if let Ok(value) = std::env::var(&name) {
+            hasher.update(value.as_bytes());
+        }
+        hasher.update([0u8]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// The stored output for `key`, marked with `"cached": true`. Expired or unreadable entries
+/// are removed and miss.
+pub fn lookup(dir: &Path, key: &str) -> Option<String> {
+    let path = dir.join(format!("{}.json", key));
+    let entry = fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<CacheEntry>(&s).ok());
+    let Some(entry) = entry else {
+        let _ = fs::remove_file(&path);
+        return None;
+    };
+    if now_secs() >= entry.created_at.saturating_add(entry.ttl_secs) {
+        let _ = fs::remove_file(&path);
+        return None;
+    }
+    // Refresh recency for LRU eviction.
+    if let Ok(file) = fs::File::options().append(true).open(&path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+    Some(mark_cached(&entry.output))
+}
+
+/// Store `output` under `key`, then evict least recently used entries over the size cap.
+pub fn store(dir: &Path, key: &str, skill: &str, ttl_secs: u64, output: &str) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let entry = CacheEntry {
+        skill: skill.to_string(),
+        created_at: now_secs(),
+        ttl_secs,
+        output: output.to_string(),
+    };
+    let tmp = tempfile::NamedTempFile::new_in(dir)?;
+    fs::write(tmp.path(), serde_json::to_vec(&entry)?)?;
+    tmp.persist(dir.join(format!("{}.json", key)))
+        .map_err(|e| e.error)?;
+    evict_lru(dir, max_bytes());
+    Ok(())
+}
+
+/// Remove the oldest-used entries until the directory holds at most `max_bytes`.
+fn evict_lru(dir: &Path, max_bytes: u64) {
+    let mut entries: Vec<(SystemTime, u64, PathBuf)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            Some((meta.modified().ok()?, meta.len(), e.path()))
+        })
+        .collect();
+    let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+    if total <= max_bytes {
+        return;
+    }
+    entries.sort_by_key(|(modified, _, _)| *modified);
+    for (_, len, path) in entries {
+        if total <= max_bytes {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(len);
+        }
+    }
+}
+
+fn mark_cached(output: &str) -> String {
+    let merged = match serde_json::from_str::<Value>(output) {
+        Ok(Value::Object(mut map)) => {
+            map.insert(CACHED_KEY.to_string(), Value::Bool(true));
+            Value::Object(map)
+        }
+        Ok(other) => serde_json::json!({ "stdout": other, CACHED_KEY: true }),
+        Err(_) => serde_json::json!({ "stdout": output, CACHED_KEY: true }),
+    };
+    merged.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_entries_miss_and_lru_keeps_recent_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        store(dir.path(), "fresh", "s", 3600, r#"{"n":1}"#).unwrap();
+        let hit: Value = serde_json::from_str(&lookup(dir.path(), "fresh").unwrap()).unwrap();
+        assert_eq!(hit, serde_json::json!({"n": 1, "cached": true}));
+
+        store(dir.path(), "expired", "s", 0, "plain text").unwrap();
+        assert_eq!(lookup(dir.path(), "expired"), None);
+        assert!(!dir.path().join("expired.json").exists());
+
+        let old = dir.path().join("fresh.json");
+        let file = fs::File::options().append(true).open(&old).unwrap();
+        file.set_modified(SystemTime::now() - std::time::Duration::from_secs(600))
+            .unwrap();
+        store(dir.path(), "newer", "s", 3600, r#"{"n":2}"#).unwrap();
+        let newer_len = fs::metadata(dir.path().join("newer.json")).unwrap().len();
+        evict_lru(dir.path(), newer_len);
+        assert!(!old.exists());
+        assert!(lookup(dir.path(), "newer").is_some());
+    }
+}
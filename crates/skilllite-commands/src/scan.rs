@@ -3,9 +3,15 @@
 use rayon::prelude::*;
 use skilllite_core::path_validation::validate_skill_path;
 use skilllite_core::skill;
+use skilllite_core::skill::capabilities::CapabilityRegistry;
+use skilllite_sandbox::security::{SarifReport, ScriptScanner};
+use std::collections::BTreeSet;
 use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
 use walkdir::WalkDir;
 
+use crate::error::bail;
 use crate::Result;
 
 /// Scan skill directory and return JSON with all executable scripts.
@@ -34,6 +40,7 @@ pub fn scan_skill(skill_dir: &str, preview_lines: usize) -> Result<String> {
                 "entry_point": if metadata.entry_point.is_empty() { None } else { Some(&metadata.entry_point) },
                 "language": metadata.language,
                 "network_enabled": metadata.network.enabled,
+                "network_policy": network_policy_json(&metadata.network),
                 "compatibility": metadata.compatibility
             });
         }
@@ -77,6 +84,177 @@ pub fn scan_skill(skill_dir: &str, preview_lines: usize) -> Result<String> {
     Ok(serde_json::to_string_pretty(&result)?)
 }
 
+/// Parsed `network.outbound` / `network.deny` entries; invalid ones carry an `error`.
+fn network_policy_json(network: &skill::metadata::NetworkPolicy) -> serde_json::Value {
+    let entries = |list: &[String]| -> Vec<serde_json::Value> {
+        list.iter()
+            .map(
+                |entry| match skill::network_rules::OutboundRule::parse(entry) {
+                    Ok(rule) => serde_json::json!({
+                        "entry": entry,
+                        "host": rule.host.to_string(),
+                        "ports": rule.ports.map(|p| p.to_string()),
+                        "description": rule.describe(),
+                    }),
+                    Err(e) => serde_json::json!({ "entry": entry, "error": e.to_string() }),
+                },
+            )
+            .collect()
+    };
+    serde_json::json!({
+        "outbound": entries(&network.outbound),
+        "deny": entries(&network.deny),
+    })
+}
+
+/// Security-scan every script in a skill directory and return one SARIF 2.1.0 document.
+///
+/// Network calls are allowed when SKILL.md enables network access, as at run time.
+pub fn scan_skill_sarif(skill_dir: &str) -> Result<String> {
+    let skill_path = validate_skill_path(skill_dir)?;
+    let network_enabled = skill::metadata::parse_skill_metadata(&skill_path)
+        .map(|m| m.network.enabled)
+        .unwrap_or(false);
+    let scanner = ScriptScanner::new().allow_network(network_enabled);
+
+    let mut scripts: Vec<String> = scan_scripts_parallel(&skill_path, 0)?
+        .iter()
+        .filter_map(|s| s.get("path")?.as_str().map(str::to_string))
+        .collect();
+    scripts.sort();
+
+    let mut report = SarifReport::new().with_rules(&scanner.rule_catalog());
+    for script in &scripts {
+        let result = scanner.scan_file(&skill_path.join(script))?;
+        report.add_scan_result(&Path::new(skill_dir).join(script), &result);
+    }
+    Ok(report.to_json())
+}
+
+/// `skilllite scan --suggest-capabilities`: infer `capabilities` from the skill's scripts and
+/// return a ready-to-paste front matter snippet. SKILL.md is only touched with `write`, which
+/// merges the suggestions into the declared list.
+pub fn suggest_capabilities(skill_dir: &str, write: bool) -> Result<String> {
+    let skill_path = validate_skill_path(skill_dir)?;
+    let scripts = scan_scripts_parallel(&skill_path, 0)?;
+    let suggested = merged_capabilities(&scripts);
+    let mut sources: Vec<&str> = scripts
+        .iter()
+        .filter(|s| {
+            s["suggested_capabilities"]
+                .as_array()
+                .is_some_and(|a| !a.is_empty())
+        })
+        .filter_map(|s| s["path"].as_str())
+        .collect();
+    sources.sort();
+
+    let skill_md_path = skill_path.join("SKILL.md");
+    let skill_md = fs::read_to_string(&skill_md_path).ok();
+    let declared = match &skill_md {
+        Some(content) => skill::metadata::declared_capabilities(content)?,
+        None => Vec::new(),
+    };
+    let merged: Vec<String> = declared
+        .iter()
+        .chain(&suggested)
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut out = String::new();
+    if suggested.is_empty() {
+        return Ok("# No capabilities could be inferred from the scripts".to_string());
+    }
+    out.push_str(&format!("# Inferred from {}\n", sources.join(", ")));
+    if !declared.is_empty() {
+        out.push_str(&format!(
+            "# Merged with the declared capabilities: {}\n",
+            declared.join(", ")
+        ));
+    }
+    out.push_str(&capabilities_line(&merged));
+    out.push('\n');
+
+    if write {
+        let Some(content) = skill_md else {
+            bail!(
+                "No SKILL.md in {} (create one with `skilllite init --from {}`)",
+                skill_dir,
+                skill_dir
+            );
+        };
+        let Some(updated) = set_front_matter_capabilities(&content, &merged) else {
+            bail!("SKILL.md has no YAML front matter to add capabilities to");
+        };
+        skilllite_fs::atomic_write(&skill_md_path, &updated)?;
+        out.push_str(&format!(
+            "# Wrote capabilities to {}\n",
+            skill_md_path.display()
+        ));
+    }
+    Ok(out.trim_end().to_string())
+}
+
+/// Union of the per-script `suggested_capabilities`, sorted.
+pub(crate) fn merged_capabilities(scripts: &[serde_json::Value]) -> Vec<String> {
+    scripts
+        .iter()
+        .filter_map(|s| s["suggested_capabilities"].as_array())
+        .flatten()
+        .filter_map(|t| t.as_str().map(str::to_string))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// `capabilities: ["a", "b"]` (YAML flow sequence of JSON strings).
+pub(crate) fn capabilities_line(tags: &[String]) -> String {
+    format!(
+        "capabilities: {}",
+        serde_json::Value::from(tags.to_vec())
+            .to_string()
+            .replace("\",\"", "\", \"")
+    )
+}
+
+/// Replace the top-level `capabilities:` entry of the front matter (flow or block list), or
+/// append one before the closing `---`. `None` without front matter.
+fn set_front_matter_capabilities(content: &str, tags: &[String]) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.first().map(|l| l.trim_end()) != Some("---") {
+        return None;
+    }
+    let end = lines.iter().skip(1).position(|l| l.trim_end() == "---")? + 1;
+    let mut out: Vec<String> = vec![lines[0].to_string()];
+    let mut i = 1;
+    let mut replaced = false;
+    while i < end {
+        if lines[i].starts_with("capabilities:") {
+            out.push(capabilities_line(tags));
+            replaced = true;
+            i += 1;
+            // Skip block-list items and continuation lines of the old value
+            while i < end && (lines[i].starts_with(' ') || lines[i].starts_with('-')) {
+                i += 1;
+            }
+            continue;
+        }
+        out.push(lines[i].to_string());
+        i += 1;
+    }
+    if !replaced {
+        out.push(capabilities_line(tags));
+    }
+    out.extend(lines[end..].iter().map(|l| l.to_string()));
+    let mut updated = out.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
 fn build_llm_prompt_hint(result: &serde_json::Value) -> String {
     let mut hints = Vec::new();
 
@@ -109,6 +287,17 @@ fn build_llm_prompt_hint(result: &serde_json::Value) -> String {
             .collect();
         hints.push(format!("Available scripts: {}", type_str.join(", ")));
 
+        let suppressed: u64 = scripts
+            .iter()
+            .filter_map(|s| s.get("suppressed_findings")?.as_u64())
+            .sum();
+        if suppressed > 0 {
+            hints.push(format!(
+                "{} findings suppressed with justification",
+                suppressed
+            ));
+        }
+
         let described: Vec<_> = scripts
             .iter()
             .filter_map(|s| {
@@ -150,7 +339,7 @@ const SKIP_DIRS: &[&str] = &[
 ];
 
 /// Collects script file paths via walkdir, then analyzes them in parallel with rayon.
-fn scan_scripts_parallel(
+pub(crate) fn scan_scripts_parallel(
     base_path: &std::path::Path,
     preview_lines: usize,
 ) -> Result<Vec<serde_json::Value>> {
@@ -253,6 +442,21 @@ fn analyze_script_file(
         &preview,
     );
     let suggested_command = generate_suggested_command(&path_str, language, rec.method);
+    let suppressions: Vec<serde_json::Value> =
+        skilllite_sandbox::security::parse_suppressions(&content, language)
+            .into_iter()
+            .map(|s| {
+                serde_json::json!({
+                    "line_number": s.line_number,
+                    "category": s.category,
+                    "reason": s.reason,
+                })
+            })
+            .collect();
+    let suppressed_findings = ScriptScanner::new()
+        .scan_content(&content, file_path)
+        .map(|r| r.suppressed.len())
+        .unwrap_or(0);
 
     Some(serde_json::json!({
         "path": path_str,
@@ -270,10 +474,177 @@ fn analyze_script_file(
         "reasoning": rec.reasoning,
         "suggested_command": suggested_command,
         "input_format": rec.input_format,
-        "output_format": rec.output_format
+        "output_format": rec.output_format,
+        "security_suppressions": suppressions,
+        "suppressed_findings": suppressed_findings,
+        "suggested_capabilities": infer_script_capabilities(&content, language)
     }))
 }
 
+/// `http(s)://host` literals in script source.
+pub(crate) static URL_HOST_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r#"https?://([A-Za-z0-9.-]+\.[A-Za-z]{2,})"#)
+        .unwrap_or_else(|_| regex::Regex::new("$^").unwrap_or_else(|_| unreachable!("$^ is valid")))
+});
+
+/// `import x` / `import x, y` / `from x import ...` (top-level package only).
+static PY_IMPORT_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(
+        r"(?m)^\s*(?:from\s+([A-Za-z_]\w*)|import\s+([A-Za-z_][\w.]*(?:\s*,\s*[A-Za-z_][\w.]*)*))",
+    )
+    .unwrap_or_else(|_| regex::Regex::new("$^").unwrap_or_else(|_| unreachable!("$^ is valid")))
+});
+
+/// `require("x")`, `import ... from "x"`, `import("x")`.
+static JS_IMPORT_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r#"(?:require|import)\s*\(\s*['"]([^'"]+)['"]\s*\)|(?m)^\s*import\s+(?:[^'"]*?\s+from\s+)?['"]([^'"]+)['"]"#)
+        .unwrap_or_else(|_| regex::Regex::new("$^").unwrap_or_else(|_| unreachable!("$^ is valid")))
+});
+
+/// Python top-level packages → capability tag.
+const PY_IMPORT_CAPABILITIES: &[(&str, &str)] = &[
+    ("pandas", "data-processing"),
+    ("numpy", "data-processing"),
+    ("polars", "data-processing"),
+    ("scipy", "data-processing"),
+    ("playwright", "browser"),
+    ("selenium", "browser"),
+    ("pyppeteer", "browser"),
+    ("reportlab", "pdf"),
+    ("pdfkit", "pdf"),
+    ("fpdf", "pdf"),
+    ("pypdf", "pdf"),
+    ("PyPDF2", "pdf"),
+    ("pdfplumber", "pdf"),
+    ("weasyprint", "pdf"),
+    ("PIL", "image"),
+    ("cv2", "image"),
+    ("openpyxl", "spreadsheet"),
+    ("xlsxwriter", "spreadsheet"),
+    ("docx", "document"),
+    ("bs4", "scraping"),
+    ("scrapy", "scraping"),
+    ("sklearn", "ml"),
+    ("torch", "ml"),
+    ("tensorflow", "ml"),
+    ("sqlite3", "database"),
+    ("psycopg2", "database"),
+    ("pymysql", "database"),
+    ("sqlalchemy", "database"),
+    ("smtplib", "email"),
+    ("openai", "openai"),
+    ("anthropic", "anthropic"),
+    ("requests", "web"),
+    ("httpx", "web"),
+    ("aiohttp", "web"),
+    ("urllib", "web"),
+];
+
+/// npm packages → capability tag.
+const JS_IMPORT_CAPABILITIES: &[(&str, &str)] = &[
+    ("puppeteer", "browser"),
+    ("puppeteer-core", "browser"),
+    ("playwright", "browser"),
+    ("playwright-core", "browser"),
+    ("pdfkit", "pdf"),
+    ("pdf-lib", "pdf"),
+    ("jspdf", "pdf"),
+    ("sharp", "image"),
+    ("jimp", "image"),
+    ("xlsx", "spreadsheet"),
+    ("exceljs", "spreadsheet"),
+    ("danfojs", "data-processing"),
+    ("cheerio", "scraping"),
+    ("pg", "database"),
+    ("mysql2", "database"),
+    ("sqlite3", "database"),
+    ("mongodb", "database"),
+    ("nodemailer", "email"),
+    ("openai", "openai"),
+    ("@anthropic-ai/sdk", "anthropic"),
+    ("axios", "web"),
+    ("node-fetch", "web"),
+    ("http", "web"),
+    ("https", "web"),
+];
+
+/// Shell commands → capability tag.
+const SHELL_COMMAND_CAPABILITIES: &[(&str, &str)] = &[
+    ("curl", "web"),
+    ("wget", "web"),
+    ("pdftotext", "pdf"),
+    ("wkhtmltopdf", "pdf"),
+    ("magick", "image"),
+    ("sqlite3", "database"),
+    ("psql", "database"),
+    ("mysql", "database"),
+];
+
+static SERVICE_HOSTS: LazyLock<CapabilityRegistry> = LazyLock::new(CapabilityRegistry::builtin);
+
+/// Likely `capabilities` tags of one script, from its imports / commands and hard-coded API
+/// hosts. Suggestions only: nothing here writes to SKILL.md.
+fn infer_script_capabilities(content: &str, language: &str) -> Vec<String> {
+    let mut tags = BTreeSet::new();
+    let mut add_from = |table: &[(&str, &str)], name: &str| {
+        if let Some((_, tag)) = table.iter().find(|(n, _)| *n == name) {
+            tags.insert(tag.to_string());
+        }
+    };
+    match language {
+        "python" => {
+            for cap in PY_IMPORT_RE.captures_iter(content) {
+                let names = cap.get(1).or_else(|| cap.get(2)).map_or("", |m| m.as_str());
+                for name in names.split(',') {
+                    let top = name.trim().split('.').next().unwrap_or_default();
+                    add_from(PY_IMPORT_CAPABILITIES, top);
+                }
+            }
+        }
+        "node" | "typescript" => {
+            for cap in JS_IMPORT_RE.captures_iter(content) {
+                let spec = cap.get(1).or_else(|| cap.get(2)).map_or("", |m| m.as_str());
+                let spec = spec.strip_prefix("node:").unwrap_or(spec);
+                // `@scope/pkg/sub` → `@scope/pkg`, `pkg/sub` → `pkg`
+                let segments = if spec.starts_with('@') { 2 } else { 1 };
+                let package = spec
+                    .splitn(segments + 1, '/')
+                    .take(segments)
+                    .collect::<Vec<_>>();
+                add_from(JS_IMPORT_CAPABILITIES, &package.join("/"));
+            }
+            if content.contains("fetch(") {
+                tags.insert("web".to_string());
+            }
+        }
+        "shell" => {
+            for line in content.lines().map(str::trim_start) {
+                if line.starts_with('#') {
+                    continue;
+                }
+                for word in line.split(|c: char| c.is_whitespace() || "|;&()`$".contains(c)) {
+                    add_from(SHELL_COMMAND_CAPABILITIES, word);
+                }
+            }
+        }
+        _ => {}
+    }
+    for cap in URL_HOST_RE.captures_iter(content) {
+        if let Some(service) = SERVICE_HOSTS.tag_for_host(&cap[1]) {
+            tags.insert(service.to_string());
+            tags.insert("web".to_string());
+        }
+    }
+    if let Some(tag) = match language {
+        "python" => Some("python"),
+        "node" | "typescript" => Some("node"),
+        _ => None,
+    } {
+        tags.insert(tag.to_string());
+    }
+    tags.into_iter().collect()
+}
+
 fn extract_script_description(content: &str, language: &str) -> Option<String> {
     match language {
         "python" => {
@@ -541,6 +912,8 @@ fn generate_suggested_command(path: &str, language: &str, method: &str) -> Strin
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_scan_skill_md_detects_suspicious_patterns() {
         let content = r#"# Prerequisites
@@ -561,4 +934,104 @@ A simple calculator. Use with JSON input."#;
             skilllite_core::skill::skill_md_security::scan_skill_md_suspicious_patterns(content);
         assert!(alerts.is_empty(), "clean content should have no alerts");
     }
+
+    #[test]
+    fn python_imports_and_api_hosts_suggest_capabilities() {
+        let content = r#"import argparse
+import pandas as pd
+from reportlab.lib.pagesizes import A4
+import os, requests
+from playwright.sync_api import sync_playwright
+
+API = "https://api.github.com/repos"
+"#;
+        assert_eq!(
+            infer_script_capabilities(content, "python"),
+            [
+                "browser",
+                "data-processing",
+                "github",
+                "pdf",
+                "python",
+                "web"
+            ]
+        );
+        // Mentions outside import statements are not imports
+        assert_eq!(
+            infer_script_capabilities("print('use pandas here')\n", "python"),
+            ["python"]
+        );
+    }
+
+    #[test]
+    fn node_requires_and_imports_suggest_capabilities() {
+        let content = r#"const puppeteer = require('puppeteer');
+import PDFDocument from "pdfkit";
+import { WebClient } from '@slack/web-api/dist/index.js';
+const res = await fetch("https://hooks.slack.com/services/x");
+"#;
+        assert_eq!(
+            infer_script_capabilities(content, "node"),
+            ["browser", "node", "pdf", "slack", "web"]
+        );
+        assert_eq!(
+            infer_script_capabilities("import Anthropic from '@anthropic-ai/sdk/index';", "node"),
+            ["anthropic", "node"]
+        );
+    }
+
+    #[test]
+    fn shell_commands_suggest_capabilities() {
+        let content = "#!/bin/bash\n# curl is not used in this comment line\nDATA=$(psql -c 'select 1')\npdftotext in.pdf - | wc -l\n";
+        assert_eq!(
+            infer_script_capabilities(content, "shell"),
+            ["database", "pdf"]
+        );
+        assert_eq!(
+            infer_script_capabilities("curl -s https://api.telegram.org/bot", "shell"),
+            ["telegram", "web"]
+        );
+    }
+
+    #[test]
+    fn front_matter_capabilities_are_replaced_or_appended() {
+        let tags = vec!["browser".to_string(), "web".to_string()];
+        let flow = "---\nname: a\ncapabilities: [\"web\"]\ndescription: x\n---\n\n# A\n";
+        assert_eq!(
+            set_front_matter_capabilities(flow, &tags).unwrap(),
+            "---\nname: a\ncapabilities: [\"browser\", \"web\"]\ndescription: x\n---\n\n# A\n"
+        );
+        let block = "---\nname: a\ncapabilities:\n  - web\n  - brwoser\n---\nbody";
+        assert_eq!(
+            set_front_matter_capabilities(block, &tags).unwrap(),
+            "---\nname: a\ncapabilities: [\"browser\", \"web\"]\n---\nbody"
+        );
+        let missing = "---\nname: a\n---\n";
+        assert_eq!(
+            set_front_matter_capabilities(missing, &tags).unwrap(),
+            "---\nname: a\ncapabilities: [\"browser\", \"web\"]\n---\n"
+        );
+        assert!(set_front_matter_capabilities("# no front matter", &tags).is_none());
+    }
+
+    #[test]
+    fn prompt_hint_counts_suppressed_findings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let script = tmp.path().join("scripts").join("convert.py");
+        fs::create_dir_all(script.parent().unwrap()).unwrap();
+        fs::write(
+            &script,
+            "import subprocess\nsubprocess.run(['ffmpeg', '-version'])  # skilllite-allow: process-exec reason=\"invokes bundled ffmpeg\"\n",
+        )
+        .unwrap();
+
+        let info = analyze_script_file(&script, tmp.path(), 5).unwrap();
+        assert_eq!(info["suppressed_findings"], 1);
+        let hint = build_llm_prompt_hint(&serde_json::json!({ "scripts": [info] }));
+        assert!(
+            hint.contains("1 findings suppressed with justification"),
+            "{}",
+            hint
+        );
+    }
 }
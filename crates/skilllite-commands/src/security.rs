@@ -2,18 +2,42 @@
 
 use skilllite_core::path_validation::validate_path_under_root;
 
+use crate::error::bail;
 use crate::Result;
 use skilllite_sandbox::security::{
-    format_scan_result_compact, format_scan_result_json, ScriptScanner,
+    format_scan_result_compact, format_scan_result_json, SarifReport, ScriptScanner,
 };
 
+/// Output format of `security-scan`, `scan` and `dependency-audit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text,
+    Json,
+    /// SARIF 2.1.0 for CI code-scanning annotations.
+    Sarif,
+}
+
+impl ReportFormat {
+    /// `--format` wins; without it the older `--json` flag picks JSON, otherwise `default`.
+    pub fn from_args(format: Option<&str>, json: bool, default: ReportFormat) -> Result<Self> {
+        match format.map(|f| f.trim().to_ascii_lowercase()).as_deref() {
+            Some("text") => Ok(Self::Text),
+            Some("json") => Ok(Self::Json),
+            Some("sarif") => Ok(Self::Sarif),
+            Some(other) => bail!("Unknown format '{}' (expected text, json or sarif)", other),
+            None if json => Ok(Self::Json),
+            None => Ok(default),
+        }
+    }
+}
+
 /// Perform security scan on a script.
 pub fn security_scan_script(
     script_path: &str,
     allow_network: bool,
     allow_file_ops: bool,
     allow_process_exec: bool,
-    json_output: bool,
+    format: ReportFormat,
 ) -> Result<()> {
     let path = validate_path_under_root(script_path, "Script path")?;
 
@@ -24,11 +48,17 @@ pub fn security_scan_script(
 
     let scan_result = scanner.scan_file(&path)?;
 
-    if json_output {
-        println!("{}", format_scan_result_json(&scan_result));
-    } else {
-        println!("Security Scan Results for: {}\n", path.display());
-        println!("{}", format_scan_result_compact(&scan_result));
+    match format {
+        ReportFormat::Json => println!("{}", format_scan_result_json(&scan_result)),
+        ReportFormat::Sarif => {
+            let mut report = SarifReport::new().with_rules(&scanner.rule_catalog());
+            report.add_scan_result(std::path::Path::new(script_path), &scan_result);
+            println!("{}", report.to_json());
+        }
+        ReportFormat::Text => {
+            println!("Security Scan Results for: {}\n", path.display());
+            println!("{}", format_scan_result_compact(&scan_result));
+        }
     }
 
     Ok(())
@@ -58,12 +88,21 @@ pub(crate) fn metadata_hint_from_skill_metadata(
     }
 }
 
+/// Exit code when known vulnerabilities were found.
+#[cfg(feature = "audit")]
+pub const AUDIT_EXIT_VULNERABLE: i32 = 1;
+/// Exit code when no vulnerabilities were found but some packages could not be verified
+/// (offline and uncached, or the vulnerability database was unreachable).
+#[cfg(feature = "audit")]
+pub const AUDIT_EXIT_INCOMPLETE: i32 = 3;
+
 /// Audit skill dependencies for known vulnerabilities via OSV.dev.
 ///
 /// Parses SKILL.md in the commands layer and passes metadata to sandbox for
 /// dependency inference — sandbox never imports or parses skill metadata.
+/// With `offline`, only the on-disk audit cache is consulted.
 #[cfg(feature = "audit")]
-pub fn dependency_audit_skill(skill_dir: &str, json_output: bool) -> Result<()> {
+pub fn dependency_audit_skill(skill_dir: &str, format: ReportFormat, offline: bool) -> Result<()> {
     let path = validate_path_under_root(skill_dir, "Skill directory")?;
 
     // Parse SKILL.md in commands layer; fill resolved_packages from compatibility when needed
@@ -71,25 +110,38 @@ pub fn dependency_audit_skill(skill_dir: &str, json_output: bool) -> Result<()>
         .ok()
         .map(|m| metadata_hint_from_skill_metadata(&m));
 
-    let result = skilllite_sandbox::security::dependency_audit::audit_skill_dependencies(
-        &path,
-        metadata_hint.as_ref(),
-    )?;
+    let options = skilllite_sandbox::security::dependency_audit::AuditOptions {
+        offline,
+        ..Default::default()
+    };
+    let result =
+        skilllite_sandbox::security::dependency_audit::audit_skill_dependencies_with_options(
+            &path,
+            metadata_hint.as_ref(),
+            &options,
+        )?;
 
-    if json_output {
-        println!(
+    match format {
+        ReportFormat::Json => println!(
             "{}",
             skilllite_sandbox::security::dependency_audit::format_audit_result_json(&result)
-        );
-    } else {
-        println!(
+        ),
+        ReportFormat::Sarif => {
+            let mut report = SarifReport::new();
+            report.add_dependency_audit(std::path::Path::new(skill_dir), &result);
+            println!("{}", report.to_json());
+        }
+        ReportFormat::Text => println!(
             "{}",
             skilllite_sandbox::security::dependency_audit::format_audit_result(&result)
-        );
+        ),
     }
 
     if result.vulnerable_count > 0 {
-        std::process::exit(1);
+        std::process::exit(AUDIT_EXIT_VULNERABLE);
+    }
+    if !result.is_complete() {
+        std::process::exit(AUDIT_EXIT_INCOMPLETE);
     }
 
     Ok(())
@@ -124,6 +124,7 @@ fn sample_scripts_for_llm(script_files: &[PathBuf], max_files: usize, max_chars:
 #[cfg(feature = "agent")]
 fn llm_admission_assess(
     skill_name: &str,
+    skill_path: &Path,
     skill_md: &str,
     script_samples: &str,
 ) -> Result<(AdmissionRisk, String)> {
@@ -132,8 +133,8 @@ fn llm_admission_assess(
         bail!("LLM scan skipped: API key not configured");
     }
 
-    // A3: Check scan cache to avoid redundant LLM calls for same content
-    let content_hash = skilllite_core::scan_cache::content_hash(skill_md, script_samples);
+    // A3: Check scan cache (keyed on a digest of every file) to avoid redundant LLM calls
+    let content_hash = skilllite_core::scan_cache::skill_digest(skill_path)?;
     if let Some((risk_str, reason)) = skilllite_core::scan_cache::get_cached(&content_hash)? {
         return Ok((AdmissionRisk::from_cache_str(&risk_str), reason));
     }
@@ -156,7 +157,7 @@ Rules:
         ChatMessage::system(system_prompt),
         ChatMessage::user(&user_prompt),
     ];
-    let client = LlmClient::new(&config.api_base, &config.api_key)?;
+    let client = LlmClient::from_config(&config)?;
     let rt = tokio::runtime::Runtime::new().context("tokio runtime init failed")?;
     let resp = rt.block_on(async {
         let inner = tokio::time::timeout(
@@ -208,6 +209,7 @@ Rules:
 #[cfg(not(feature = "agent"))]
 fn llm_admission_assess(
     _skill_name: &str,
+    _skill_path: &Path,
     _skill_md: &str,
     _script_samples: &str,
 ) -> Result<(AdmissionRisk, String)> {
@@ -398,7 +400,7 @@ fn scan_candidate_skills_inner(
         let needs_llm = risk > AdmissionRisk::Safe && !scan_offline;
         if needs_llm {
             let script_samples = sample_scripts_for_llm(&script_files, 3, 1200);
-            match llm_admission_assess(name, &skill_md_content, &script_samples) {
+            match llm_admission_assess(name, skill_path, &skill_md_content, &script_samples) {
                 Ok((llm_risk, reason)) => {
                     risk = risk.max(llm_risk);
                     messages.push(format!(
@@ -188,7 +188,7 @@ pub(in crate::skill) fn install_skill_deps(skills_dir: &Path, installed: &[Strin
                     &skill_path,
                     &env_spec,
                     cache_dir,
-                    None,
+                    skilllite_sandbox::cli_progress(),
                     skilllite_sandbox::cli_confirm_download(),
                 ) {
                     Ok(_) => {
@@ -2,7 +2,7 @@
 
 use anyhow::Context;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use skilllite_core::skill::manifest;
 use skilllite_core::skill::metadata;
@@ -14,12 +14,14 @@ use crate::Result;
 
 mod admission;
 mod discovery;
+mod registry;
 mod source;
 
 pub(in crate::skill) use admission::{
     scan_candidate_skills, scan_candidate_skills_fast, AdmissionRisk,
 };
 pub(in crate::skill) use discovery::{copy_skill, install_skill_deps};
+pub use registry::{cmd_registry_search, cmd_registry_show, is_registry_query};
 
 use discovery::discover_skills;
 use source::{clone_repo, extract_local_zip, fetch_from_clawhub, parse_source};
@@ -200,13 +202,6 @@ pub fn cmd_add(
             }
             let dest = skills_path.join(skill_name);
             copy_skill(skill_path, &dest)?;
-            let admission = risk_by_name.get(skill_name).copied();
-            let _entry = manifest::upsert_installed_skill_with_admission(
-                &skills_path,
-                &dest,
-                source,
-                admission,
-            )?;
             installed.push(skill_name.clone());
             eprintln!("   ✓ {}: installed to {}", skill_name, dest.display());
         }
@@ -223,6 +218,17 @@ pub fn cmd_add(
             eprintln!("{}", msg);
         }
 
+        // Fingerprint after dependency install so a freshly written skill.lock is covered
+        for skill_name in &installed {
+            let admission = risk_by_name.get(skill_name).copied();
+            manifest::upsert_installed_skill_with_admission(
+                &skills_path,
+                &skills_path.join(skill_name),
+                source,
+                admission,
+            )?;
+        }
+
         eprintln!();
         eprintln!("{}", "=".repeat(50));
         eprintln!(
@@ -245,6 +251,36 @@ pub fn cmd_add(
     result
 }
 
+/// Admission check for a skill package received from a swarm peer (`skilllite swarm
+/// --allow-skill-replication`): the offline scan of `skilllite add --scan-offline` plus the
+/// signature check done when the manifest entry is written. Malicious packages (critical
+/// code findings, high-severity SKILL.md alerts) and invalid signatures are refused;
+/// otherwise returns the admission risk to record.
+pub fn admit_replicated_skill(name: &str, skill_dir: &Path) -> Result<&'static str> {
+    let candidates = [(name.to_string(), skill_dir.to_path_buf())];
+    let Some(report) = scan_candidate_skills(&candidates, true).pop() else {
+        bail!("Admission scan produced no report for '{}'", name);
+    };
+    for msg in &report.messages {
+        tracing::info!(skill = %name, "{}", msg.trim());
+    }
+    if report.risk == AdmissionRisk::Malicious {
+        bail!(
+            "Replicated skill '{}' refused: admission scan rated it malicious",
+            name
+        );
+    }
+    let parent = skill_dir.parent().unwrap_or(skill_dir);
+    let integrity = manifest::evaluate_skill_status(parent, skill_dir)?;
+    if integrity.signature_status == manifest::SignatureStatus::Invalid {
+        bail!(
+            "Replicated skill '{}' refused: SKILL.sig does not match its content",
+            name
+        );
+    }
+    Ok(report.risk.as_str())
+}
+
 /// 从源头更新单个技能（用于 repair：下载的技能失败时按源头覆盖）
 pub fn update_skill_from_source(
     skills_path: &std::path::Path,
@@ -290,3 +326,34 @@ pub fn update_skill_from_source(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_skill(dir: &Path, script: &str) {
+        fs::create_dir_all(dir.join("scripts")).unwrap();
+        fs::write(
+            dir.join("SKILL.md"),
+            "---\nname: calc\ndescription: Adds numbers.\n---\n",
+        )
+        .unwrap();
+        fs::write(dir.join("scripts").join("main.py"), script).unwrap();
+    }
+
+    #[test]
+    fn replicated_skill_with_critical_findings_is_refused() {
+        let tmp = tempfile::tempdir().unwrap();
+        let clean = tmp.path().join("clean").join("calc");
+        write_skill(&clean, "import json, sys\nprint(json.dumps({'sum': 3}))\n");
+        assert_eq!(admit_replicated_skill("calc", &clean).unwrap(), "safe");
+
+        let bad = tmp.path().join("bad").join("calc");
+        write_skill(&bad, "import sys\nexec(sys.stdin.read())\n");
+        let err = admit_replicated_skill("calc", &bad).unwrap_err();
+        assert!(err.to_string().contains("malicious"), "{err}");
+
+        fs::write(clean.join("SKILL.sig"), "0000").unwrap();
+        assert!(admit_replicated_skill("calc", &clean).is_err());
+    }
+}
@@ -0,0 +1,596 @@
+//! `skilllite add --list [query]` / `skilllite add --show <name>` — search a skill registry
+//! index and review a remote SKILL.md before installing.
+//!
+//! The index is a JSON document (`{"skills": [...]}`) at `SKILLLITE_REGISTRY_URL` (an
+//! `https://` URL, `file://` URL or local path). Remote indexes are cached under
+//! `<cache>/skilllite/registry/` for `SKILLLITE_REGISTRY_CACHE_TTL_SECS`; when the registry
+//! is unreachable a cached copy is used, with a staleness warning.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use skilllite_core::config::env_keys::misc as misc_keys;
+
+use super::source::parse_source;
+use crate::error::bail;
+use crate::Result;
+
+const DEFAULT_REGISTRY_URL: &str = "https://clawhub.ai/api/v1/registry/index.json";
+const DEFAULT_CACHE_TTL_SECS: u64 = 60 * 60;
+/// Remote SKILL.md files larger than this are truncated for display.
+const MAX_SKILL_MD_BYTES: usize = 256 * 1024;
+
+// ─── Index format ───────────────────────────────────────────────────────────
+
+/// One skill published in the registry index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct RegistryEntry {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    /// Free-form tags (`pdf`, `ocr`, `browser`, ...) matched by `--capability`.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// Outbound domains the skill declares.
+    #[serde(default, alias = "network")]
+    pub network_domains: Vec<String>,
+    /// Argument for `skilllite add` (`clawhub:<slug>`, `owner/repo/path`, ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skill_md_url: Option<String>,
+    /// Scan result published by the registry (`passed`, `warning`, `blocked`, ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scan_status: Option<String>,
+    #[serde(default)]
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(super) struct RegistryIndex {
+    #[serde(default)]
+    pub skills: Vec<RegistryEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedIndex {
+    fetched_at: u64,
+    url: String,
+    index: RegistryIndex,
+}
+
+/// An index together with where it came from.
+pub(super) struct LoadedIndex {
+    pub index: RegistryIndex,
+    /// Seconds since the index was fetched (0 for a fresh download or a local file).
+    pub age_secs: u64,
+    /// Set when the registry could not be reached and an expired cache was used.
+    pub stale: bool,
+}
+
+// ─── Configuration ──────────────────────────────────────────────────────────
+
+fn registry_url() -> String {
+    skilllite_core::config::load_dotenv();
+    skilllite_core::config::env_or(misc_keys::SKILLLITE_REGISTRY_URL, &[], || {
+        DEFAULT_REGISTRY_URL.to_string()
+    })
+}
+
+fn cache_ttl_secs() -> u64 {
+    skilllite_core::config::load_dotenv();
+    skilllite_core::config::env_optional(misc_keys::SKILLLITE_REGISTRY_CACHE_TTL_SECS, &[])
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(DEFAULT_CACHE_TTL_SECS)
+}
+
+/// `<cache>/skilllite/registry`.
+fn cache_dir() -> Option<PathBuf> {
+    let base = skilllite_core::config::CacheConfig::cache_dir()
+        .map(PathBuf::from)
+        .or_else(|| dirs::cache_dir().map(|d| d.join("skilllite")))?;
+    Some(base.join("registry"))
+}
+
+// ─── Fetching and caching ───────────────────────────────────────────────────
+
+/// `file://` URLs and plain paths are read from disk; everything else goes over HTTP.
+fn local_path(url: &str) -> Option<PathBuf> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return Some(PathBuf::from(path));
+    }
+    (!url.contains("://")).then(|| PathBuf::from(url))
+}
+
+fn fetch_text(url: &str, max_bytes: usize) -> Result<String> {
+    if let Some(path) = local_path(url) {
+        let bytes =
+            std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        return Ok(String::from_utf8_lossy(&bytes[..bytes.len().min(max_bytes)]).into_owned());
+    }
+    fetch_http(url, max_bytes)
+}
+
+#[cfg(feature = "audit")]
+fn fetch_http(url: &str, max_bytes: usize) -> Result<String> {
+    use std::io::Read;
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(std::time::Duration::from_secs(10))
+        .timeout_read(std::time::Duration::from_secs(30))
+        .build();
+    let resp = agent
+        .get(url)
+        .call()
+        .with_context(|| format!("Failed to fetch {}", url))?;
+    let mut bytes = Vec::new();
+    resp.into_reader()
+        .take(max_bytes as u64)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to read response from {}", url))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(not(feature = "audit"))]
+fn fetch_http(_url: &str, _max_bytes: usize) -> Result<String> {
+    bail!("Registry download requires the 'audit' feature (ureq).")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn cache_file(root: &Path, url: &str) -> PathBuf {
+    let slug: String = url
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(120)
+        .collect();
+    root.join(format!("{}.json", slug))
+}
+
+fn read_cache(path: &Path, url: &str) -> Option<CachedIndex> {
+    let cached: CachedIndex = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    (cached.url == url).then_some(cached)
+}
+
+fn write_cache(path: &Path, cached: &CachedIndex) -> std::io::Result<()> {
+    let parent = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(parent)?;
+    serde_json::to_writer(&mut tmp, cached)?;
+    tmp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Load the index at `url`: a fresh cache entry, else a download (cached on success), else
+/// an expired cache entry marked `stale`.
+pub(super) fn load_index(
+    url: &str,
+    cache_root: Option<&Path>,
+    ttl_secs: u64,
+    fetch: impl Fn(&str) -> Result<String>,
+) -> Result<LoadedIndex> {
+    let parse = |text: &str| -> Result<RegistryIndex> {
+        serde_json::from_str(text)
+            .with_context(|| format!("Invalid registry index at {}", url))
+            .map_err(Into::into)
+    };
+    if local_path(url).is_some() {
+        return Ok(LoadedIndex {
+            index: parse(&fetch(url)?)?,
+            age_secs: 0,
+            stale: false,
+        });
+    }
+
+    let cache_path = cache_root.map(|root| cache_file(root, url));
+    let cached = cache_path.as_deref().and_then(|p| read_cache(p, url));
+    let now = now_secs();
+    if let Some(c) = cached.as_ref() {
+        let age = now.saturating_sub(c.fetched_at);
+        if age < ttl_secs {
+            return Ok(LoadedIndex {
+                index: c.index.clone(),
+                age_secs: age,
+                stale: false,
+            });
+        }
+    }
+
+    match fetch(url).and_then(|text| parse(&text)) {
+        Ok(index) => {
+            if let Some(path) = cache_path.as_deref() {
+                let entry = CachedIndex {
+                    fetched_at: now,
+                    url: url.to_string(),
+                    index: index.clone(),
+                };
+                if let Err(e) = write_cache(path, &entry) {
+                    tracing::debug!("Failed to write registry cache {}: {}", path.display(), e);
+                }
+            }
+            Ok(LoadedIndex {
+                index,
+                age_secs: 0,
+                stale: false,
+            })
+        }
+        Err(e) => match cached {
+            Some(c) => {
+                let age = now.saturating_sub(c.fetched_at);
+                eprintln!(
+                    "⚠️  Registry unreachable ({}); using cached index from {} ago",
+                    e,
+                    format_age(age)
+                );
+                Ok(LoadedIndex {
+                    index: c.index,
+                    age_secs: age,
+                    stale: true,
+                })
+            }
+            None => Err(e),
+        },
+    }
+}
+
+fn load_configured_index() -> Result<(String, LoadedIndex)> {
+    let url = registry_url();
+    let loaded = load_index(&url, cache_dir().as_deref(), cache_ttl_secs(), |u| {
+        fetch_text(u, usize::MAX)
+    })?;
+    Ok((url, loaded))
+}
+
+fn format_age(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86_400),
+    }
+}
+
+// ─── Search ─────────────────────────────────────────────────────────────────
+
+/// Whether `skilllite add --list <source>` names a registry search rather than a repo or path.
+pub fn is_registry_query(source: &str) -> bool {
+    !source.contains(['/', '\\', ':']) && parse_source(source).source_type == "git"
+}
+
+/// Entries whose name, description or capabilities contain every query word and that carry
+/// every `capabilities` tag; best name matches first.
+pub(super) fn search<'a>(
+    index: &'a RegistryIndex,
+    query: Option<&str>,
+    capabilities: &[String],
+) -> Vec<&'a RegistryEntry> {
+    let words: Vec<String> = query
+        .unwrap_or("")
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+    let mut hits: Vec<(u8, &RegistryEntry)> = index
+        .skills
+        .iter()
+        .filter(|e| {
+            capabilities
+                .iter()
+                .all(|tag| e.capabilities.iter().any(|c| c.eq_ignore_ascii_case(tag)))
+        })
+        .filter_map(|e| {
+            let name = e.name.to_lowercase();
+            let haystack = format!(
+                "{} {} {}",
+                name,
+                e.description.to_lowercase(),
+                e.capabilities.join(" ").to_lowercase()
+            );
+            if !words.iter().all(|w| haystack.contains(w.as_str())) {
+                return None;
+            }
+            let rank = if words.is_empty() {
+                2
+            } else if words.len() == 1 && name == words[0] {
+                0
+            } else if words.iter().all(|w| name.contains(w.as_str())) {
+                1
+            } else {
+                2
+            };
+            Some((rank, e))
+        })
+        .collect();
+    hits.sort_by(|(ra, a), (rb, b)| ra.cmp(rb).then_with(|| a.name.cmp(&b.name)));
+    hits.into_iter().map(|(_, e)| e).collect()
+}
+
+#[derive(Serialize)]
+struct SearchReport<'a> {
+    index_url: &'a str,
+    stale: bool,
+    age_secs: u64,
+    total: usize,
+    page: usize,
+    limit: usize,
+    pages: usize,
+    skills: Vec<&'a RegistryEntry>,
+}
+
+/// `skilllite add --list [query] [--capability <tag>]... [--limit N] [--page P] [--json]`
+pub fn cmd_registry_search(
+    query: Option<&str>,
+    capabilities: &[String],
+    limit: usize,
+    page: usize,
+    json: bool,
+) -> Result<()> {
+    if limit == 0 || page == 0 {
+        bail!("--limit and --page must be at least 1");
+    }
+    let (url, loaded) = load_configured_index()?;
+    let hits = search(&loaded.index, query, capabilities);
+    let total = hits.len();
+    let pages = total.div_ceil(limit).max(1);
+    let shown: Vec<&RegistryEntry> = hits
+        .into_iter()
+        .skip((page - 1) * limit)
+        .take(limit)
+        .collect();
+
+    if json {
+        let report = SearchReport {
+            index_url: &url,
+            stale: loaded.stale,
+            age_secs: loaded.age_secs,
+            total,
+            page,
+            limit,
+            pages,
+            skills: shown,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    eprintln!("🔎 Registry: {}", url);
+    let filter = match (query, capabilities.is_empty()) {
+        (Some(q), true) => format!(" for \"{}\"", q),
+        (Some(q), false) => format!(" for \"{}\" [{}]", q, capabilities.join(", ")),
+        (None, false) => format!(" [{}]", capabilities.join(", ")),
+        (None, true) => String::new(),
+    };
+    eprintln!("   {} skill(s){} — page {}/{}", total, filter, page, pages);
+    if shown.is_empty() {
+        return Ok(());
+    }
+    eprintln!();
+    for e in shown {
+        let mut status = Vec::new();
+        if e.verified {
+            status.push("verified".to_string());
+        }
+        if let Some(scan) = &e.scan_status {
+            status.push(format!("scan: {}", scan));
+        }
+        eprintln!(
+            "   • {}{}{}",
+            e.name,
+            e.version
+                .as_deref()
+                .map(|v| format!(" {}", v))
+                .unwrap_or_default(),
+            if status.is_empty() {
+                String::new()
+            } else {
+                format!("  [{}]", status.join(", "))
+            }
+        );
+        if !e.description.is_empty() {
+            let short: String = e.description.chars().take(100).collect();
+            eprintln!("     {}", sanitize_for_terminal(&short));
+        }
+        if !e.capabilities.is_empty() {
+            eprintln!("     capabilities: {}", e.capabilities.join(", "));
+        }
+        if !e.network_domains.is_empty() {
+            eprintln!("     network: {}", e.network_domains.join(", "));
+        }
+        if let Some(source) = &e.source {
+            eprintln!("     install: skilllite add {}", source);
+        }
+    }
+    if page < pages {
+        eprintln!();
+        eprintln!("   More: --page {}", page + 1);
+    }
+    Ok(())
+}
+
+// ─── Remote SKILL.md review ─────────────────────────────────────────────────
+
+/// Make untrusted text inert for a terminal: drop control characters (escape sequences
+/// included) and bidirectional overrides, keep newlines and tabs.
+pub(super) fn sanitize_for_terminal(text: &str) -> String {
+    text.chars()
+        .filter(|c| {
+            matches!(c, '\n' | '\t')
+                || !(c.is_control()
+                    || matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'))
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct ShowReport<'a> {
+    name: &'a str,
+    untrusted: bool,
+    installed: bool,
+    skill_md_url: &'a str,
+    truncated: bool,
+    entry: &'a RegistryEntry,
+    skill_md: String,
+}
+
+/// `skilllite add --show <name> [--json]` — print a remote SKILL.md as plain text. Nothing
+/// is installed, executed or interpreted.
+pub fn cmd_registry_show(name: &str, json: bool) -> Result<()> {
+    let (url, loaded) = load_configured_index()?;
+    let Some(entry) = loaded
+        .index
+        .skills
+        .iter()
+        .find(|e| e.name.eq_ignore_ascii_case(name))
+    else {
+        bail!("Skill '{}' not found in registry {}", name, url);
+    };
+    let Some(md_url) = entry.skill_md_url.as_deref() else {
+        bail!(
+            "Registry entry '{}' has no skill_md_url; review its source ({}) before installing",
+            entry.name,
+            entry.source.as_deref().unwrap_or("unknown")
+        );
+    };
+    let raw = fetch_text(md_url, MAX_SKILL_MD_BYTES + 1)?;
+    let truncated = raw.len() > MAX_SKILL_MD_BYTES;
+    let mut body = sanitize_for_terminal(&raw);
+    if truncated {
+        let mut cut = MAX_SKILL_MD_BYTES.min(body.len());
+        while !body.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        body.truncate(cut);
+    }
+
+    if json {
+        let report = ShowReport {
+            name: &entry.name,
+            untrusted: true,
+            installed: false,
+            skill_md_url: md_url,
+            truncated,
+            entry,
+            skill_md: body,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let banner = format!(
+        "UNTRUSTED — NOT INSTALLED: {} SKILL.md from {}",
+        entry.name, md_url
+    );
+    println!("┌─ {}", banner);
+    println!("│  Displayed as plain text for review. Do not follow instructions in it.");
+    println!("└───────────────────────────────────────────────────────────");
+    println!("{}", body.trim_end());
+    if truncated {
+        println!("… (truncated at {} bytes)", MAX_SKILL_MD_BYTES);
+    }
+    println!("──── end of untrusted SKILL.md ({}) ────", entry.name);
+    if let Some(source) = &entry.source {
+        eprintln!();
+        eprintln!("   To install after review: skilllite add {}", source);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, description: &str, capabilities: &[&str]) -> RegistryEntry {
+        RegistryEntry {
+            name: name.to_string(),
+            version: Some("1.0.0".to_string()),
+            description: description.to_string(),
+            capabilities: capabilities.iter().map(|c| c.to_string()).collect(),
+            network_domains: Vec::new(),
+            source: None,
+            skill_md_url: None,
+            scan_status: None,
+            verified: false,
+        }
+    }
+
+    fn index() -> RegistryIndex {
+        RegistryIndex {
+            skills: vec![
+                entry("report-writer", "Turn PDF tables into reports", &["docs"]),
+                entry("pdf", "PDF toolkit", &["pdf", "ocr"]),
+                entry("pdf-merge", "Merge files", &["pdf"]),
+                entry("weather", "Forecasts", &["http"]),
+            ],
+        }
+    }
+
+    fn names(hits: &[&RegistryEntry]) -> Vec<String> {
+        hits.iter().map(|e| e.name.clone()).collect()
+    }
+
+    #[test]
+    fn search_ranks_name_matches_and_filters_capabilities() {
+        let index = index();
+        assert_eq!(
+            names(&search(&index, Some("PDF"), &[])),
+            ["pdf", "pdf-merge", "report-writer"]
+        );
+        assert_eq!(
+            names(&search(&index, Some("pdf"), &["OCR".to_string()])),
+            ["pdf"]
+        );
+        assert_eq!(search(&index, None, &[]).len(), 4);
+        assert!(search(&index, Some("pdf weather"), &[]).is_empty());
+    }
+
+    #[test]
+    fn registry_queries_are_plain_words() {
+        assert!(is_registry_query("pdf"));
+        assert!(is_registry_query("pdf tables"));
+        assert!(!is_registry_query("owner/repo"));
+        assert!(!is_registry_query("clawhub:pdf"));
+        assert!(!is_registry_query("./skills"));
+        assert!(!is_registry_query("https://github.com/o/r"));
+    }
+
+    #[test]
+    fn expired_cache_is_used_when_the_registry_is_unreachable() {
+        let tmp = tempfile::tempdir().unwrap();
+        let url = "https://registry.invalid/index.json";
+        let body = serde_json::to_string(&index()).unwrap();
+
+        let first = load_index(url, Some(tmp.path()), 3600, |_| Ok(body.clone())).unwrap();
+        assert!(!first.stale);
+        // Fresh cache: no fetch.
+        let cached = load_index(url, Some(tmp.path()), 3600, |_| {
+            panic!("fresh cache must not be refetched")
+        })
+        .unwrap();
+        assert_eq!(cached.index.skills.len(), 4);
+
+        let offline = load_index(url, Some(tmp.path()), 0, |_| bail!("offline")).unwrap();
+        assert!(offline.stale);
+        assert_eq!(offline.index.skills.len(), 4);
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(load_index(url, Some(empty.path()), 0, |_| bail!("offline")).is_err());
+    }
+
+    #[test]
+    fn terminal_sanitizer_strips_escapes_and_bidi_overrides() {
+        let text = "# Title\n\x1b[2J\x1b]0;pwned\x07ok\u{202E}gnp.exe\tend\r";
+        assert_eq!(
+            sanitize_for_terminal(text),
+            "# Title\n[2J]0;pwnedokgnp.exe\tend"
+        );
+    }
+}
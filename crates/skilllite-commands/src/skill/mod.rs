@@ -12,7 +12,10 @@ mod remove;
 mod show;
 mod verify;
 
-pub use add::{cmd_add, update_skill_from_source};
+pub use add::{
+    admit_replicated_skill, cmd_add, cmd_registry_search, cmd_registry_show, is_registry_query,
+    update_skill_from_source,
+};
 pub(crate) use common::resolve_skills_dir;
 pub use desktop_list::{cmd_list_desktop, list_desktop_skills, DesktopSkillSnapshot};
 pub use import_openclaw::cmd_import_openclaw_skills;
@@ -1,50 +1,72 @@
 //! `skilllite remove` — Remove an installed skill.
+//!
+//! Besides the skill directory, removal cleans what the skill left elsewhere: its cached
+//! environments (shared envs only when no other skill uses them; kept with `--keep-env`),
+//! its scan-cache entry, its manifest entry plus any other stale ones, and it records a
+//! `skill_removed` evolution event so rules and examples naming the skill are retired by
+//! the next evolution cycle.
 
 use anyhow::Context;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use skilllite_core::skill::manifest;
 use skilllite_core::skill::metadata;
+use skilllite_core::EnvSpec;
+use skilllite_evolution::skill_removal::{self, ReferenceKind, SkillReference};
+use skilllite_sandbox::env::builder;
+use skilllite_sandbox::env::shared_cache;
 
 use super::common;
 
 use crate::error::bail;
 use crate::Result;
 
+/// Everything `skilllite remove` touches for one skill.
+struct RemovalPlan {
+    skill_path: PathBuf,
+    /// SKILL.md `name` (falls back to the directory name)
+    skill_name: String,
+    /// Envs deleted with the skill
+    envs: Vec<PathBuf>,
+    /// Envs kept because other skills use them (or `--keep-env`)
+    kept_envs: Vec<(PathBuf, String)>,
+    envs_dir: Option<PathBuf>,
+    scan_digest: Option<String>,
+    manifest_entries: Vec<String>,
+    references: Vec<SkillReference>,
+}
+
 /// `skilllite remove <name>`
-pub fn cmd_remove(skill_name: &str, skills_dir: &str, force: bool) -> Result<()> {
+pub fn cmd_remove(
+    skill_name: &str,
+    skills_dir: &str,
+    force: bool,
+    keep_env: bool,
+    dry_run: bool,
+) -> Result<()> {
     let skills_path = common::resolve_skills_dir(skills_dir);
 
     if !skills_path.exists() {
         bail!("No skills directory found. Nothing to remove.");
     }
 
-    let mut skill_path = skills_path.join(skill_name);
+    let skill_path = locate_skill(&skills_path, skill_name)?;
+    let chat_root = skilllite_core::paths::chat_root();
+    let envs_dir = builder::get_cache_dir(None);
+    let plan = plan_removal(
+        &skills_path,
+        &skill_path,
+        envs_dir.as_deref(),
+        &chat_root,
+        keep_env,
+    );
+    print_plan(&plan);
 
-    if !skill_path.exists() {
-        let mut found = false;
-        if let Ok(entries) = fs::read_dir(&skills_path) {
-            for entry in entries.flatten() {
-                let p = entry.path();
-                if !p.is_dir() || !p.join("SKILL.md").exists() {
-                    continue;
-                }
-                if let Ok(meta) = metadata::parse_skill_metadata(&p) {
-                    if meta.name == skill_name {
-                        skill_path = p;
-                        found = true;
-                        break;
-                    }
-                }
-            }
-        }
-        if !found {
-            bail!(
-                "Skill '{}' not found in {}",
-                skill_name,
-                skills_path.display()
-            );
-        }
+    if dry_run {
+        eprintln!();
+        eprintln!("(Dry run — nothing removed. Remove --dry-run to delete.)");
+        return Ok(());
     }
 
     if !force {
@@ -62,14 +84,295 @@ pub fn cmd_remove(skill_name: &str, skills_dir: &str, force: bool) -> Result<()>
         }
     }
 
+    apply_removal(&plan, &skills_path, &chat_root)?;
+    let dir_name = skill_path.file_name().unwrap_or_default().to_string_lossy();
+    eprintln!("✓ Removed skill '{}'", dir_name);
+    Ok(())
+}
+
+fn locate_skill(skills_path: &Path, skill_name: &str) -> Result<PathBuf> {
+    let skill_path = skills_path.join(skill_name);
+    if skill_path.exists() {
+        return Ok(skill_path);
+    }
+    if let Ok(entries) = fs::read_dir(skills_path) {
+        for entry in entries.flatten() {
+            let p = entry.path();
+            if !p.is_dir() || !p.join("SKILL.md").exists() {
+                continue;
+            }
+            if let Ok(meta) = metadata::parse_skill_metadata(&p) {
+                if meta.name == skill_name {
+                    return Ok(p);
+                }
+            }
+        }
+    }
+    bail!(
+        "Skill '{}' not found in {}",
+        skill_name,
+        skills_path.display()
+    );
+}
+
+/// Collect what removal would touch. Must run while the skill directory still exists:
+/// env references and the scan-cache key are derived from its path and contents.
+fn plan_removal(
+    skills_path: &Path,
+    skill_path: &Path,
+    envs_dir: Option<&Path>,
+    chat_root: &Path,
+    keep_env: bool,
+) -> RemovalPlan {
     let dir_name = skill_path
         .file_name()
         .unwrap_or_default()
         .to_string_lossy()
         .to_string();
-    fs::remove_dir_all(&skill_path)
-        .with_context(|| format!("Failed to remove skill: {}", skill_path.display()))?;
-    let _ = manifest::remove_skill_entry(&skills_path, &skill_path);
-    eprintln!("✓ Removed skill '{}'", dir_name);
+    let meta = metadata::parse_skill_metadata(skill_path).ok();
+    let skill_name = meta
+        .as_ref()
+        .map(|m| m.name.clone())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| dir_name.clone());
+
+    let mut envs = Vec::new();
+    let mut kept_envs = Vec::new();
+    if let Some(envs_dir) = envs_dir {
+        for env in shared_cache::skill_envs(envs_dir, skill_path) {
+            if keep_env {
+                kept_envs.push((env.path, "--keep-env".to_string()));
+            } else if env.shared_with.is_empty() {
+                envs.push(env.path);
+            } else {
+                let note = format!("still used by {} other skill(s)", env.shared_with.len());
+                kept_envs.push((env.path, note));
+            }
+        }
+        if let Some(meta) = &meta {
+            let spec = EnvSpec::from_metadata(skill_path, meta);
+            let cache_dir = envs_dir.parent().map(|p| p.to_string_lossy().to_string());
+            if let Some(legacy) = builder::legacy_env_dir(skill_path, &spec, cache_dir.as_deref()) {
+                if keep_env {
+                    kept_envs.push((legacy, "--keep-env".to_string()));
+                } else {
+                    envs.push(legacy);
+                }
+            }
+        }
+    }
+
+    let scan_digest = skilllite_core::scan_cache::skill_digest(skill_path)
+        .ok()
+        .filter(|d| skilllite_core::scan_cache::contains(d));
+
+    let mut manifest_entries = manifest::stale_entries(skills_path).unwrap_or_default();
+    let in_manifest = manifest::load_manifest(skills_path)
+        .map(|m| m.skills.contains_key(&dir_name))
+        .unwrap_or(false);
+    if in_manifest {
+        manifest_entries.insert(0, dir_name);
+    }
+
+    RemovalPlan {
+        skill_path: skill_path.to_path_buf(),
+        references: skill_removal::find_skill_references(chat_root, &skill_name),
+        skill_name,
+        envs,
+        kept_envs,
+        envs_dir: envs_dir.map(Path::to_path_buf),
+        scan_digest,
+        manifest_entries,
+    }
+}
+
+fn print_plan(plan: &RemovalPlan) {
+    eprintln!("Removing '{}' touches:", plan.skill_name);
+    eprintln!("  • skill directory {}", plan.skill_path.display());
+    for env in &plan.envs {
+        eprintln!("  • environment {}", env.display());
+    }
+    for (env, why) in &plan.kept_envs {
+        eprintln!("  ◦ keeping environment {} ({})", env.display(), why);
+    }
+    if plan.scan_digest.is_some() {
+        eprintln!("  • scan-cache entry");
+    }
+    if !plan.manifest_entries.is_empty() {
+        eprintln!("  • manifest entries: {}", plan.manifest_entries.join(", "));
+    }
+
+    let rules = |mutable: bool| -> Vec<&str> {
+        plan.references
+            .iter()
+            .filter(|r| r.kind == ReferenceKind::Rule && r.mutable == mutable)
+            .map(|r| r.id.as_str())
+            .collect()
+    };
+    let (evolved, seed) = (rules(true), rules(false));
+    if !evolved.is_empty() {
+        eprintln!(
+            "⚠ {} evolved rule(s) reference this skill — run `skilllite evolution disable <rule_id>` or they will be auto-retired next cycle: {}",
+            evolved.len(),
+            evolved.join(", ")
+        );
+    }
+    if !seed.is_empty() {
+        eprintln!(
+            "⚠ {} seed rule(s) reference this skill (edit prompts/rules.json manually): {}",
+            seed.len(),
+            seed.join(", ")
+        );
+    }
+    let examples = plan
+        .references
+        .iter()
+        .filter(|r| r.kind == ReferenceKind::Example)
+        .count();
+    if examples > 0 {
+        eprintln!(
+            "⚠ {} planning example(s) reference this skill; evolved ones are archived next cycle",
+            examples
+        );
+    }
+}
+
+fn apply_removal(plan: &RemovalPlan, skills_path: &Path, chat_root: &Path) -> Result<()> {
+    fs::remove_dir_all(&plan.skill_path)
+        .with_context(|| format!("Failed to remove skill: {}", plan.skill_path.display()))?;
+
+    for env in &plan.envs {
+        if let Err(e) = fs::remove_dir_all(env) {
+            eprintln!("  ✗ Failed to remove environment {}: {}", env.display(), e);
+        }
+    }
+    if let Some(envs_dir) = &plan.envs_dir {
+        shared_cache::prune_references(envs_dir);
+    }
+    if let Some(digest) = &plan.scan_digest {
+        if let Err(e) = skilllite_core::scan_cache::remove_cached(digest) {
+            tracing::warn!("Failed to purge scan cache entry: {}", e);
+        }
+    }
+    let _ = manifest::remove_skill_entry(skills_path, &plan.skill_path);
+    if let Err(e) = manifest::prune_stale_entries(skills_path) {
+        tracing::warn!("Failed to prune manifest: {}", e);
+    }
+
+    match skilllite_evolution::feedback::open_evolution_db(chat_root) {
+        Ok(conn) => {
+            skill_removal::record_skill_removed(&conn, chat_root, &plan.skill_name)?;
+        }
+        Err(e) => tracing::warn!("Failed to record skill removal: {}", e),
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install_fixture(skills: &Path) -> PathBuf {
+        let skill = skills.join("demo-skill");
+        fs::create_dir_all(skill.join("scripts")).unwrap();
+        fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: demo-skill\ndescription: demo\nentry_point: scripts/main.py\n---\n",
+        )
+        .unwrap();
+        fs::write(skill.join("scripts/main.py"), "print('{}')\n").unwrap();
+        manifest::upsert_installed_skill(skills, &skill, "local").unwrap();
+        skill
+    }
+
+    #[test]
+    fn remove_leaves_no_orphans() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills = tmp.path().join("skills");
+        let cache = tmp.path().join("cache");
+        let chat_root = tmp.path().join("chat");
+        let skill = install_fixture(&skills);
+
+        // Build its environment the way `skilllite run` does
+        let meta = metadata::parse_skill_metadata(&skill).unwrap();
+        let spec = EnvSpec::from_metadata(&skill, &meta);
+        let cache_str = cache.to_string_lossy().to_string();
+        let env = match builder::ensure_environment(&skill, &spec, Some(&cache_str), None, None) {
+            Ok(env) => env,
+            Err(e) => {
+                eprintln!("skipping: cannot build a python env here: {}", e);
+                return;
+            }
+        };
+        assert!(env.is_dir());
+
+        fs::create_dir_all(chat_root.join("prompts")).unwrap();
+        fs::write(
+            chat_root.join("prompts/rules.json"),
+            r#"[{"id": "evo_demo", "tool_hint": "demo_skill", "instruction": "Use demo_skill", "mutable": true, "origin": "evolved"}]"#,
+        )
+        .unwrap();
+
+        let envs_dir = cache.join("envs");
+        let plan = plan_removal(&skills, &skill, Some(&envs_dir), &chat_root, false);
+        assert_eq!(plan.skill_name, "demo-skill");
+        assert_eq!(plan.envs, vec![env.clone()]);
+        assert_eq!(plan.manifest_entries, vec!["demo-skill".to_string()]);
+        assert_eq!(plan.references.len(), 1);
+
+        apply_removal(&plan, &skills, &chat_root).unwrap();
+        assert!(!skill.exists());
+        assert!(!env.exists());
+        assert!(shared_cache::list_envs(&envs_dir).is_empty());
+        let refs = fs::read_dir(envs_dir.join(".refs"))
+            .map(|d| d.count())
+            .unwrap_or(0);
+        assert_eq!(refs, 0);
+        assert!(manifest::load_manifest(&skills).unwrap().skills.is_empty());
+
+        let conn = skilllite_evolution::feedback::open_evolution_db(&chat_root).unwrap();
+        assert_eq!(
+            skill_removal::pending_skill_removals(&conn).unwrap(),
+            vec!["demo-skill".to_string()]
+        );
+    }
+
+    #[test]
+    fn keep_env_and_shared_envs_are_not_deleted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills = tmp.path().join("skills");
+        let envs_dir = tmp.path().join("cache/envs");
+        let skill = install_fixture(&skills);
+        let other = tmp.path().join("other-skill");
+        fs::create_dir_all(&other).unwrap();
+        for (name, users) in [("solo", vec![&skill]), ("common", vec![&skill, &other])] {
+            let dir = envs_dir.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join(shared_cache::ENV_MARKER),
+                r#"{"language": "python", "runtime": "3.12", "created_at": ""}"#,
+            )
+            .unwrap();
+            for user in users {
+                let canonical = user.canonicalize().unwrap();
+                let key = format!("{}-{}", name, user.file_name().unwrap().to_string_lossy());
+                fs::create_dir_all(envs_dir.join(".refs")).unwrap();
+                fs::write(
+                    envs_dir.join(".refs").join(format!("{key}.json")),
+                    serde_json::json!({"skill_dir": canonical, "language": "python", "env": name})
+                        .to_string(),
+                )
+                .unwrap();
+            }
+        }
+
+        let plan = plan_removal(&skills, &skill, Some(&envs_dir), tmp.path(), false);
+        assert_eq!(plan.envs, vec![envs_dir.join("solo")]);
+        assert_eq!(plan.kept_envs.len(), 1);
+        assert_eq!(plan.kept_envs[0].0, envs_dir.join("common"));
+
+        let plan = plan_removal(&skills, &skill, Some(&envs_dir), tmp.path(), true);
+        assert!(plan.envs.is_empty());
+        assert_eq!(plan.kept_envs.len(), 2);
+    }
+}
@@ -1,26 +1,37 @@
 //! `skilllite show` — Show detailed information about a skill.
+//!
+//! The default view is a compact summary. `--full` adds the SKILL.md body, the tool
+//! definitions the agent generates for the skill, capabilities, entry scripts and
+//! install provenance — enough to debug "why does the agent call my skill wrong".
 
-use skilllite_core::skill::metadata;
+use std::path::Path;
+
+use skilllite_core::skill::metadata::{self, SkillMetadata};
+use skilllite_core::skill::origin::SkillSandboxPolicy;
+use skilllite_sandbox::runner::SandboxLevel;
 
 use super::common;
 
 use crate::Result;
 
-/// `skilllite show <name>`
-pub fn cmd_show(skill_name: &str, skills_dir: &str, json_output: bool) -> Result<()> {
+/// `skilllite show <name> [--full] [--json]`
+pub fn cmd_show(skill_name: &str, skills_dir: &str, json_output: bool, full: bool) -> Result<()> {
     let skills_path = common::resolve_skills_dir(skills_dir);
     let skill_path = common::find_skill(&skills_path, skill_name)?;
     let meta = metadata::parse_skill_metadata(&skill_path)?;
     let lang = metadata::detect_language(&skill_path, &meta);
 
     if json_output {
-        let info = common::skill_to_json(&skill_path);
+        let info = show_json(&skill_path, &meta, full);
         println!("{}", serde_json::to_string_pretty(&info)?);
         return Ok(());
     }
 
     eprintln!("📦 Skill: {}", meta.name);
     eprintln!("   Path: {}", skill_path.display());
+    if meta.is_bash_tool_skill() {
+        print_bash_patterns(&meta);
+    }
     eprintln!(
         "   Integrity: {}",
         common::status_label_for_skill(&skill_path)
@@ -36,9 +47,6 @@ pub fn cmd_show(skill_name: &str, skills_dir: &str, json_output: bool) -> Result
     if meta.entry_point.is_empty() {
         if meta.is_bash_tool_skill() {
             eprintln!("   Type: bash-tool skill");
-            if let Some(ref at) = meta.allowed_tools {
-                eprintln!("   Allowed Tools: {}", at);
-            }
         } else if metadata::has_executable_scripts(&skill_path) {
             eprintln!("   Type: script skill (no default entry point)");
         } else {
@@ -58,6 +66,10 @@ pub fn cmd_show(skill_name: &str, skills_dir: &str, json_output: bool) -> Result
     if !meta.network.outbound.is_empty() {
         eprintln!("   Outbound: {}", meta.network.outbound.join(", "));
     }
+    eprintln!(
+        "   Sandbox Policy: {}",
+        sandbox_policy(&skill_path, &meta).describe()
+    );
     if let Some(ref compat) = meta.compatibility {
         eprintln!("   Compatibility: {}", compat);
     }
@@ -65,6 +77,11 @@ pub fn cmd_show(skill_name: &str, skills_dir: &str, json_output: bool) -> Result
         eprintln!("   Resolved Packages: {}", pkgs.join(", "));
     }
 
+    if full {
+        print_full_sections(&skill_path, &meta);
+        return Ok(());
+    }
+
     let scripts_dir = skill_path.join("scripts");
     if scripts_dir.is_dir() {
         eprintln!("   Scripts:");
@@ -80,11 +97,33 @@ pub fn cmd_show(skill_name: &str, skills_dir: &str, json_output: bool) -> Result
         }
     }
 
+    print_references(&skill_path);
+    Ok(())
+}
+
+/// Bash-tool skills are defined by their command patterns; show them before anything else.
+fn print_bash_patterns(meta: &SkillMetadata) {
+    let patterns = meta.get_bash_patterns();
+    if patterns.is_empty() {
+        if let Some(ref at) = meta.allowed_tools {
+            eprintln!("   Allowed Tools: {}", at);
+        }
+        return;
+    }
+    eprintln!("   Allowed Commands:");
+    for p in &patterns {
+        eprintln!("     $ {}", p.raw_pattern);
+    }
+}
+
+fn print_references(skill_path: &Path) {
     let refs_dir = skill_path.join("references");
     if refs_dir.is_dir() {
         eprintln!("   References:");
         if let Ok(entries) = std::fs::read_dir(&refs_dir) {
-            for entry in entries.flatten() {
+            let mut entries: Vec<_> = entries.flatten().collect();
+            entries.sort_by_key(|e| e.file_name());
+            for entry in entries {
                 let name = entry.file_name().to_string_lossy().to_string();
                 if !name.starts_with('.') {
                     eprintln!("     - {}", name);
@@ -92,6 +131,268 @@ pub fn cmd_show(skill_name: &str, skills_dir: &str, json_output: bool) -> Result
             }
         }
     }
+}
 
-    Ok(())
+fn print_full_sections(skill_path: &Path, meta: &SkillMetadata) {
+    let info = common::skill_to_json(skill_path);
+    let field = |key: &str| info.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    eprintln!();
+    eprintln!("   Provenance:");
+    eprintln!(
+        "     Source: {}",
+        field("source").unwrap_or_else(|| "(not in manifest)".to_string())
+    );
+    eprintln!(
+        "     Installed: {}",
+        field("installed_at").unwrap_or_else(|| "unknown".to_string())
+    );
+    eprintln!(
+        "     Manifest: {} (signature: {})",
+        field("integrity_status").unwrap_or_else(|| "UNSIGNED".to_string()),
+        field("signature_status").unwrap_or_else(|| "UNSIGNED".to_string())
+    );
+
+    eprintln!(
+        "   Capabilities: {}",
+        if meta.capabilities.is_empty() {
+            "(none)".to_string()
+        } else {
+            meta.capabilities.join(", ")
+        }
+    );
+    eprintln!(
+        "   Network Whitelist: {}",
+        if !meta.network.enabled {
+            "(network disabled)".to_string()
+        } else if meta.network.outbound.is_empty() {
+            "(any host)".to_string()
+        } else {
+            meta.network.outbound.join(", ")
+        }
+    );
+
+    let scripts = entry_scripts(skill_path);
+    if !scripts.is_empty() {
+        eprintln!("   Entry Scripts:");
+        for s in &scripts {
+            let path = s.get("path").and_then(|v| v.as_str()).unwrap_or("?");
+            let language = s.get("language").and_then(|v| v.as_str()).unwrap_or("?");
+            match s.get("description").and_then(|v| v.as_str()) {
+                Some(desc) if !desc.trim().is_empty() => {
+                    eprintln!("     - {} ({}): {}", path, language, desc.trim())
+                }
+                _ => eprintln!("     - {} ({})", path, language),
+            }
+        }
+    }
+    print_references(skill_path);
+
+    eprintln!();
+    match tool_definitions(skill_path) {
+        Some(defs) if defs.is_empty() => {
+            eprintln!("   Tool Definitions: (none — prompt-only skill)");
+        }
+        Some(defs) => {
+            eprintln!("   Tool Definitions (as sent to the model):");
+            for def in &defs {
+                let pretty = serde_json::to_string_pretty(def).unwrap_or_default();
+                for line in pretty.lines() {
+                    eprintln!("     {}", line);
+                }
+            }
+        }
+        None => {
+            eprintln!("   Tool Definitions: (unavailable — built without the agent feature)");
+        }
+    }
+
+    if let Some(body) = skill_md_body(skill_path) {
+        eprintln!();
+        eprintln!("   SKILL.md:");
+        for line in body.lines() {
+            eprintln!("     {}", line);
+        }
+    }
+}
+
+/// Effective sandbox level and network for the skill's origin (evolved skills are stricter).
+fn sandbox_policy(skill_path: &Path, meta: &SkillMetadata) -> SkillSandboxPolicy {
+    SkillSandboxPolicy::for_skill(
+        skill_path,
+        SandboxLevel::from_env_or_cli(None).as_u8(),
+        &meta.network,
+    )
+}
+
+/// JSON view: `skill_to_json` plus verbatim tool schemas; `--full` adds body and scripts.
+fn show_json(skill_path: &Path, meta: &SkillMetadata, full: bool) -> serde_json::Value {
+    let mut info = common::skill_to_json(skill_path);
+    let Some(obj) = info.as_object_mut() else {
+        return info;
+    };
+    obj.insert(
+        "bash_patterns".to_string(),
+        serde_json::json!(meta
+            .get_bash_patterns()
+            .iter()
+            .map(|p| p.raw_pattern.clone())
+            .collect::<Vec<_>>()),
+    );
+    obj.insert(
+        "tool_definitions".to_string(),
+        tool_definitions(skill_path)
+            .map(serde_json::Value::Array)
+            .unwrap_or(serde_json::Value::Null),
+    );
+    obj.insert(
+        "sandbox_policy".to_string(),
+        serde_json::to_value(sandbox_policy(skill_path, meta)).unwrap_or_default(),
+    );
+    if full {
+        obj.insert(
+            "capabilities".to_string(),
+            serde_json::json!(meta.capabilities),
+        );
+        obj.insert(
+            "network_outbound".to_string(),
+            serde_json::json!(meta.network.outbound),
+        );
+        obj.insert(
+            "entry_scripts".to_string(),
+            serde_json::Value::Array(entry_scripts(skill_path)),
+        );
+        obj.insert(
+            "body".to_string(),
+            serde_json::json!(skill_md_body(skill_path)),
+        );
+    }
+    info
+}
+
+/// Tool definitions exactly as the agent loader builds them for this skill.
+#[cfg(feature = "agent")]
+fn tool_definitions(skill_path: &Path) -> Option<Vec<serde_json::Value>> {
+    let dirs = [skill_path.to_string_lossy().to_string()];
+    let loaded = skilllite_agent::skills::load_skills(&dirs);
+    let skill = loaded.into_iter().find(|s| s.skill_dir == skill_path)?;
+    Some(
+        skill
+            .tool_definitions
+            .iter()
+            .filter_map(|d| serde_json::to_value(d).ok())
+            .collect(),
+    )
+}
+
+#[cfg(not(feature = "agent"))]
+fn tool_definitions(_skill_path: &Path) -> Option<Vec<serde_json::Value>> {
+    None
+}
+
+/// Scripts under `scripts/`, analyzed the same way as `skilllite scan`.
+fn entry_scripts(skill_path: &Path) -> Vec<serde_json::Value> {
+    let scripts_dir = skill_path.join("scripts");
+    if !scripts_dir.is_dir() {
+        return Vec::new();
+    }
+    let mut scripts = crate::scan::scan_scripts_parallel(skill_path, 10)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| {
+            s.get("in_scripts_dir")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+    scripts.sort_by(|a, b| {
+        let key = |v: &serde_json::Value| v.get("path").and_then(|p| p.as_str()).map(String::from);
+        key(a).cmp(&key(b))
+    });
+    scripts
+}
+
+/// SKILL.md without its YAML front matter.
+fn skill_md_body(skill_path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(skill_path.join("SKILL.md")).ok()?;
+    Some(strip_front_matter(&content).trim().to_string())
+}
+
+fn strip_front_matter(content: &str) -> &str {
+    let Some(rest) = content.strip_prefix("---") else {
+        return content;
+    };
+    match rest.find("\n---") {
+        Some(end) => {
+            let after = &rest[end + 4..];
+            after.split_once('\n').map(|(_, body)| body).unwrap_or("")
+        }
+        None => content,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_skill(dir: &Path, front_matter: &str, body: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\n{}\n---\n{}", front_matter, body),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn strip_front_matter_keeps_body_only() {
+        let md = "---\nname: demo\n---\n# Demo\n\nUse it.\n";
+        assert_eq!(strip_front_matter(md), "# Demo\n\nUse it.\n");
+        assert_eq!(strip_front_matter("# No front matter"), "# No front matter");
+    }
+
+    #[test]
+    fn json_includes_bash_patterns_and_full_body() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill = tmp.path().join("browser");
+        write_skill(
+            &skill,
+            "name: browser\ndescription: Drive a browser\nallowed-tools: Bash(agent-browser:*)",
+            "# Browser\n\nRun `agent-browser open <url>`.\n",
+        );
+        let meta = metadata::parse_skill_metadata(&skill).unwrap();
+
+        let compact = show_json(&skill, &meta, false);
+        assert_eq!(
+            compact["bash_patterns"],
+            serde_json::json!(["agent-browser:*"])
+        );
+        assert!(compact.get("body").is_none());
+
+        let full = show_json(&skill, &meta, true);
+        assert_eq!(
+            full["body"],
+            serde_json::json!("# Browser\n\nRun `agent-browser open <url>`.")
+        );
+        assert!(full["entry_scripts"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn entry_scripts_lists_only_scripts_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill = tmp.path().join("calc");
+        write_skill(&skill, "name: calc\ndescription: Math", "Calc");
+        std::fs::create_dir_all(skill.join("scripts")).unwrap();
+        std::fs::write(
+            skill.join("scripts/add.py"),
+            "\"\"\"Add two numbers.\"\"\"\nimport sys\nprint(sys.argv)\n",
+        )
+        .unwrap();
+        std::fs::write(skill.join("helper.py"), "print('x')\n").unwrap();
+
+        let scripts = entry_scripts(&skill);
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0]["path"], "scripts/add.py");
+        assert_eq!(scripts[0]["language"], "python");
+    }
 }
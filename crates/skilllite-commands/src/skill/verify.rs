@@ -1,18 +1,36 @@
-//! `skilllite verify` — Verify skill integrity (fingerprint/signature).
+//! `skilllite verify` — Verify skill integrity (fingerprint/signature) and, with `--deps`,
+//! the installed environment against `skill.lock`.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use skilllite_core::skill::manifest::{self, SignatureStatus, SkillIntegrityStatus};
+use skilllite_core::skill::metadata;
+use skilllite_core::EnvSpec;
+use skilllite_sandbox::env::{builder, lockfile, shared_cache};
+use skilllite_sandbox::security::parse_suppressions_for_path;
+use walkdir::WalkDir;
 
 use super::common;
 
 use crate::error::bail;
 use crate::Result;
 
-/// `skilllite verify <name-or-path>`
-pub fn cmd_verify(target: &str, skills_dir: &str, json_output: bool, strict: bool) -> Result<()> {
+/// `skilllite verify <name-or-path> [--deps [--relock]]`
+pub fn cmd_verify(
+    target: &str,
+    skills_dir: &str,
+    json_output: bool,
+    strict: bool,
+    deps: bool,
+    relock: bool,
+) -> Result<()> {
     let skills_path = common::resolve_skills_dir(skills_dir);
     let skill_path = resolve_target_path(target, &skills_path)?;
+    let deps_check = if deps {
+        Some(check_dependencies(&skill_path, relock)?)
+    } else {
+        None
+    };
     let report = manifest::evaluate_skill_status(&skills_path, &skill_path)?;
 
     let status = match report.status {
@@ -33,6 +51,7 @@ pub fn cmd_verify(target: &str, skills_dir: &str, json_output: bool, strict: boo
         .unwrap_or_default();
     let source = report.entry.as_ref().map(|e| e.source.clone());
     let installed_at = report.entry.as_ref().map(|e| e.installed_at.to_rfc3339());
+    let unjustified = unjustified_suppressions(&skill_path);
 
     if json_output {
         let rating = common::security_rating_for_skill(&skill_path);
@@ -51,7 +70,15 @@ pub fn cmd_verify(target: &str, skills_dir: &str, json_output: bool, strict: boo
                 "current_hash": report.current_hash,
                 "manifest_hash": manifest_hash,
                 "source": source,
-                "installed_at": installed_at
+                "installed_at": installed_at,
+                "unjustified_suppressions": unjustified,
+                "dependencies": deps_check.as_ref().map(|d| serde_json::json!({
+                    "status": d.status,
+                    "relocked": d.relocked,
+                    "locked_packages": d.locked_packages,
+                    "environment": d.environment.as_ref().map(|p| p.to_string_lossy()),
+                    "problems": d.problems,
+                }))
             }))?
         );
     } else {
@@ -75,6 +102,30 @@ pub fn cmd_verify(target: &str, skills_dir: &str, json_output: bool, strict: boo
         if let Some(ref at) = installed_at {
             eprintln!("   Installed At: {}", at);
         }
+        for location in &unjustified {
+            eprintln!("   ⚠ skilllite-allow without reason: {}", location);
+        }
+        if let Some(ref d) = deps_check {
+            if d.relocked {
+                eprintln!("   Relocked: {}", lockfile::SKILL_LOCK_FILE);
+            }
+            eprintln!(
+                "   Dependencies: {} ({} locked package(s))",
+                d.status, d.locked_packages
+            );
+            if let Some(ref env) = d.environment {
+                eprintln!("   Environment: {}", env.display());
+            }
+            for problem in &d.problems {
+                eprintln!("   ⚠ {}", problem);
+            }
+        }
+    }
+
+    if let Some(ref d) = deps_check {
+        if d.status == "CONFLICT" || d.status == "DRIFT" {
+            bail!("Dependency verify failed: {}", d.status);
+        }
     }
 
     if strict {
@@ -84,11 +135,111 @@ pub fn cmd_verify(target: &str, skills_dir: &str, json_output: bool, strict: boo
                 bail!("Strict verify failed: {}", status);
             }
         }
+        if !unjustified.is_empty() {
+            bail!(
+                "Strict verify failed: {} skilllite-allow comment(s) without reason: {}",
+                unjustified.len(),
+                unjustified.join(", ")
+            );
+        }
     }
 
     Ok(())
 }
 
+/// `--deps` result: `skill.lock` against the declared dependencies and the skill's last
+/// built environment.
+struct DepsCheck {
+    /// OK | DRIFT | CONFLICT | NOT_INSTALLED | UNLOCKED | NO_DEPENDENCIES
+    status: &'static str,
+    relocked: bool,
+    locked_packages: usize,
+    environment: Option<PathBuf>,
+    problems: Vec<String>,
+}
+
+/// A tampered or malformed lock is an error rather than a status.
+fn check_dependencies(skill_path: &Path, relock: bool) -> Result<DepsCheck> {
+    let meta = metadata::parse_skill_metadata(skill_path)?;
+    let spec = EnvSpec::from_metadata(skill_path, &meta);
+    if relock {
+        builder::relock(
+            skill_path,
+            &spec,
+            None,
+            skilllite_sandbox::cli_progress(),
+            skilllite_sandbox::cli_confirm_download(),
+        )?;
+    }
+    let mut check = DepsCheck {
+        status: "OK",
+        relocked: relock,
+        locked_packages: 0,
+        environment: None,
+        problems: Vec::new(),
+    };
+    let declared = lockfile::declared_dependencies(skill_path, &spec)?;
+    let Some(lock) = lockfile::load(skill_path)? else {
+        check.status = if declared.is_empty() {
+            "NO_DEPENDENCIES"
+        } else {
+            "UNLOCKED"
+        };
+        return Ok(check);
+    };
+    check.locked_packages = lock.packages.len();
+    if let Err(e) = lockfile::check_declared(skill_path, &lock, &declared) {
+        check.status = "CONFLICT";
+        check.problems.push(e.to_string());
+        return Ok(check);
+    }
+    check.environment = builder::get_cache_dir(None)
+        .and_then(|envs| shared_cache::referenced_env(&envs, skill_path, &spec.language));
+    match check.environment {
+        Some(ref env) => {
+            check.problems = lockfile::check_environment(env, &lock);
+            if !check.problems.is_empty() {
+                check.status = "DRIFT";
+            }
+        }
+        None => check.status = "NOT_INSTALLED",
+    }
+    Ok(check)
+}
+
+/// `path:line` of every `skilllite-allow:` comment in the skill's scripts that has no `reason`.
+fn unjustified_suppressions(skill_path: &Path) -> Vec<String> {
+    let mut found = Vec::new();
+    let files = WalkDir::new(skill_path)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0
+                || !(name.starts_with('.') || name == "node_modules" || name == "__pycache__")
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file());
+    for entry in files {
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let rel = entry
+            .path()
+            .strip_prefix(skill_path)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .to_string();
+        for s in parse_suppressions_for_path(&content, entry.path()) {
+            if s.reason.is_none() {
+                found.push(format!("{}:{}", rel, s.line_number));
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
 fn resolve_target_path(target: &str, skills_path: &std::path::Path) -> Result<PathBuf> {
     let input = PathBuf::from(target);
     if input.exists() {
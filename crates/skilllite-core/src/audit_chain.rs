@@ -0,0 +1,329 @@
+//! Tamper-evident hash chain for append-only JSONL audit logs.
+//!
+//! Every chained line carries `prev_hash` (SHA-256 of the previous raw line, or of the last
+//! line of the previous file after rotation) and `entry_hash` (SHA-256 of the line's own
+//! JSON without `entry_hash`). Editing, deleting or inserting a line breaks the link to the
+//! next chained line. Lines written before chaining existed verify as legacy, not as broken.
+//!
+//! Appends hold an exclusive lock on a `<log>.lock` sidecar from reading the last line to
+//! writing the new one, so concurrent writers (processes or threads) never chain two entries
+//! to the same predecessor. The sidecar survives rotation, which renames the log itself.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+pub const PREV_HASH_KEY: &str = "prev_hash";
+pub const ENTRY_HASH_KEY: &str = "entry_hash";
+
+/// `prev_hash` of the very first entry of a chain (no previous line anywhere).
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Tail read window; audit lines are far below this.
+const TAIL_CHUNK: u64 = 64 * 1024;
+
+pub fn line_hash(line: &str) -> String {
+    hex::encode(Sha256::digest(
+        line.trim_end_matches(['\r', '\n']).as_bytes(),
+    ))
+}
+
+/// SHA-256 of the record's JSON with `entry_hash` removed (keys in serde_json's sorted order).
+fn entry_hash(record: &serde_json::Map<String, Value>) -> String {
+    let mut body = record.clone();
+    body.remove(ENTRY_HASH_KEY);
+    let text = serde_json::to_string(&Value::Object(body)).unwrap_or_default();
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+/// Last non-empty line of `path`, reading only the file tail.
+pub fn last_line(path: &Path) -> io::Result<Option<String>> {
+    let mut file = match File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let len = file.metadata()?.len();
+    let mut window = TAIL_CHUNK.min(len);
+    loop {
+        file.seek(SeekFrom::Start(len - window))?;
+        let mut buf = Vec::with_capacity(window as usize);
+        (&mut file).take(window).read_to_end(&mut buf)?;
+        let text = String::from_utf8_lossy(&buf);
+        let trimmed = text.trim_end();
+        match trimmed.rfind('\n') {
+            Some(i) => return Ok(Some(trimmed[i + 1..].to_string())),
+            None if window == len => return Ok((!trimmed.is_empty()).then(|| trimmed.to_string())),
+            None => window = (window * 2).min(len),
+        }
+    }
+}
+
+/// Sidecar lock file guarding appends to `log`.
+pub fn lock_path(log: &Path) -> PathBuf {
+    let mut name = log.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    log.with_file_name(name)
+}
+
+/// Run `f` while holding the exclusive lock `lock_file` (blocking until it is free). Callers
+/// that rotate or pick `previous_file` themselves do so inside `f` and append with
+/// [`append_chained_unlocked`].
+pub fn with_lock_file<T>(lock_file: &Path, f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    if let Some(parent) = lock_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_file)?;
+    let mut lock = fd_lock::RwLock::new(file);
+    let _guard = lock.write()?;
+    f()
+}
+
+/// Append `record` to `path` as a chained line. When `path` is empty or missing, the chain
+/// continues from the last line of `previous_file` (the archive it was rotated from).
+pub fn append_chained(path: &Path, record: &Value, previous_file: Option<&Path>) -> io::Result<()> {
+    with_lock_file(&lock_path(path), || {
+        append_chained_unlocked(path, record, previous_file)
+    })
+}
+
+/// [`append_chained`] for callers already holding the append lock of `path`.
+pub fn append_chained_unlocked(
+    path: &Path,
+    record: &Value,
+    previous_file: Option<&Path>,
+) -> io::Result<()> {
+    let mut prev = last_line(path)?;
+    if prev.is_none() {
+        if let Some(previous) = previous_file {
+            prev = last_line(previous)?;
+        }
+    }
+    let mut map = match record {
+        Value::Object(m) => m.clone(),
+        other => {
+            let mut m = serde_json::Map::new();
+            m.insert("value".to_string(), other.clone());
+            m
+        }
+    };
+    map.remove(ENTRY_HASH_KEY);
+    map.insert(
+        PREV_HASH_KEY.to_string(),
+        Value::String(
+            prev.as_deref()
+                .map(line_hash)
+                .unwrap_or_else(|| GENESIS_HASH.to_string()),
+        ),
+    );
+    let hash = entry_hash(&map);
+    map.insert(ENTRY_HASH_KEY.to_string(), Value::String(hash));
+    let mut line = serde_json::to_string(&Value::Object(map))?;
+    line.push('\n');
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())
+}
+
+/// First place the chain does not hold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainBreak {
+    pub file: PathBuf,
+    /// 1-based line number within `file`.
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChainReport {
+    pub files: Vec<PathBuf>,
+    /// Lines whose `entry_hash` and `prev_hash` both check out.
+    pub verified_entries: usize,
+    /// Lines without hashes (written before chaining, or by an external writer).
+    pub legacy_entries: usize,
+    pub first_break: Option<ChainBreak>,
+}
+
+impl ChainReport {
+    /// `broken`, `ok`, `legacy (unverifiable)` (no chained entries at all) or `empty`.
+    pub fn status(&self) -> &'static str {
+        if self.first_break.is_some() {
+            "broken"
+        } else if self.verified_entries > 0 {
+            "ok"
+        } else if self.legacy_entries > 0 {
+            "legacy (unverifiable)"
+        } else {
+            "empty"
+        }
+    }
+
+    pub fn is_intact(&self) -> bool {
+        self.first_break.is_none()
+    }
+}
+
+/// Walk `files` (oldest first) as one chain and report the first broken link. The first
+/// entry of the oldest file is not checked against its predecessor, which retention may
+/// already have deleted.
+pub fn verify_chain(files: &[PathBuf]) -> ChainReport {
+    let mut report = ChainReport::default();
+    let mut prev_line: Option<String> = None;
+    let mut anchored = false;
+    for file in files {
+        let Ok(text) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        report.files.push(file.clone());
+        for (idx, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Err(reason) = check_line(line, prev_line.as_deref(), anchored) {
+                match reason {
+                    LineCheck::Legacy => report.legacy_entries += 1,
+                    LineCheck::Broken(reason) => {
+                        report.first_break = Some(ChainBreak {
+                            file: file.clone(),
+                            line: idx + 1,
+                            reason,
+                        });
+                        return report;
+                    }
+                }
+            } else {
+                report.verified_entries += 1;
+            }
+            prev_line = Some(line.to_string());
+            anchored = true;
+        }
+    }
+    report
+}
+
+enum LineCheck {
+    Legacy,
+    Broken(String),
+}
+
+fn check_line(line: &str, prev: Option<&str>, anchored: bool) -> Result<(), LineCheck> {
+    let Ok(Value::Object(map)) = serde_json::from_str::<Value>(line) else {
+        return Err(LineCheck::Broken("line is not a JSON object".to_string()));
+    };
+    let stored_entry = map.get(ENTRY_HASH_KEY).and_then(Value::as_str);
+    let stored_prev = map.get(PREV_HASH_KEY).and_then(Value::as_str);
+    let (Some(stored_entry), Some(stored_prev)) = (stored_entry, stored_prev) else {
+        if stored_entry.is_some() || stored_prev.is_some() {
+            return Err(LineCheck::Broken(
+                "entry has only one of prev_hash / entry_hash".to_string(),
+            ));
+        }
+        return Err(LineCheck::Legacy);
+    };
+    if entry_hash(&map) != stored_entry {
+        return Err(LineCheck::Broken(
+            "entry_hash does not match the entry (line was edited)".to_string(),
+        ));
+    }
+    if anchored {
+        let expected = prev.map(line_hash);
+        if expected.as_deref() != Some(stored_prev) {
+            return Err(LineCheck::Broken(
+                "prev_hash does not match the previous line (a line was edited, removed or inserted before this one)"
+                    .to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn chain_verifies_across_rotation_and_flags_edits_with_line_numbers() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("evolution.log.1");
+        let live = dir.path().join("evolution.log");
+        std::fs::write(&archive, "{\"type\":\"legacy\",\"id\":\"x\"}\n").unwrap();
+        append_chained(
+            &archive,
+            &json!({"type": "rule_added", "id": "规则-1"}),
+            None,
+        )
+        .unwrap();
+        append_chained(
+            &live,
+            &json!({"type": "rule_added", "id": "r2"}),
+            Some(&archive),
+        )
+        .unwrap();
+        append_chained(&live, &json!({"type": "rule_removed", "id": "r2"}), None).unwrap();
+
+        let files = vec![archive.clone(), live.clone()];
+        let report = verify_chain(&files);
+        assert_eq!(report.status(), "ok");
+        assert_eq!((report.verified_entries, report.legacy_entries), (3, 1));
+
+        // Edit the first live entry: its own hash no longer matches.
+        let text = std::fs::read_to_string(&live).unwrap();
+        std::fs::write(&live, text.replacen("\"r2\"", "\"r3\"", 1)).unwrap();
+        let broken = verify_chain(&files).first_break.unwrap();
+        assert_eq!((broken.file.clone(), broken.line), (live.clone(), 1));
+        assert!(broken.reason.contains("entry_hash"));
+
+        // Drop the archive's last line: the rotated file's first entry loses its link.
+        std::fs::write(&live, text).unwrap();
+        std::fs::write(&archive, "{\"type\":\"legacy\",\"id\":\"x\"}\n").unwrap();
+        let broken = verify_chain(&files).first_break.unwrap();
+        assert_eq!((broken.file, broken.line), (live, 1));
+        assert!(broken.reason.contains("prev_hash"));
+    }
+
+    #[test]
+    fn concurrent_appenders_keep_the_chain_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("audit.jsonl");
+        std::thread::scope(|scope| {
+            for writer in 0..8 {
+                let log = &log;
+                scope.spawn(move || {
+                    for seq in 0..25 {
+                        append_chained(log, &json!({"writer": writer, "seq": seq}), None).unwrap();
+                    }
+                });
+            }
+        });
+
+        let report = verify_chain(std::slice::from_ref(&log));
+        assert!(report.is_intact(), "{:?}", report.first_break);
+        assert_eq!(report.verified_entries, 200);
+    }
+
+    #[test]
+    fn unchained_logs_verify_as_legacy() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("audit.jsonl");
+        std::fs::write(&log, "{\"event\":\"a\"}\n{\"event\":\"b\"}\n").unwrap();
+        let report = verify_chain(std::slice::from_ref(&log));
+        assert_eq!(report.status(), "legacy (unverifiable)");
+        assert!(report.is_intact());
+        assert_eq!(
+            last_line(&log).unwrap().as_deref(),
+            Some("{\"event\":\"b\"}")
+        );
+        assert_eq!(
+            verify_chain(&[dir.path().join("missing")]).status(),
+            "empty"
+        );
+    }
+}
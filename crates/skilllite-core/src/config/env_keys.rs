@@ -27,6 +27,27 @@ pub mod llm {
     /// Model
     pub const MODEL: &str = "SKILLLITE_MODEL";
     pub const MODEL_ALIASES: &[&str] = &["OPENAI_MODEL", "MODEL"];
+
+    /// 接口协议：`openai` | `anthropic`；未设置时按 model / api_base 自动识别
+    pub const PROVIDER: &str = "SKILLLITE_LLM_PROVIDER";
+
+    /// 录制目录：每次 LLM 请求/响应（含流式文本块）写成编号 JSON 文件，已去除 API Key
+    pub const RECORD: &str = "SKILLLITE_LLM_RECORD";
+
+    /// 回放目录：按顺序从录制文件返回响应，不访问网络；请求哈希不一致时报错
+    pub const REPLAY: &str = "SKILLLITE_LLM_REPLAY";
+
+    /// 宽松回放：只比对消息条数，不校验请求哈希
+    pub const REPLAY_LOOSE: &str = "SKILLLITE_LLM_REPLAY_LOOSE";
+
+    /// 单次 LLM 请求的总尝试次数（含首次）；连接失败、429、5xx 按指数退避重试。默认 3
+    pub const MAX_ATTEMPTS: &str = "SKILLLITE_LLM_MAX_ATTEMPTS";
+
+    /// 重试等待上限（秒），同时限制服务端 `Retry-After` 给出的等待时间。默认 30
+    pub const RETRY_MAX_BACKOFF_SECS: &str = "SKILLLITE_LLM_RETRY_MAX_BACKOFF_SECS";
+
+    /// 逗号分隔的模型白名单：设置后 chat REPL 的 `/model <name>` 只接受其中的模型
+    pub const MODEL_ALLOWLIST: &str = "SKILLLITE_MODEL_ALLOWLIST";
 }
 
 /// Skills、输出、工作区
@@ -36,12 +57,24 @@ pub mod paths {
 
     pub const SKILLLITE_OUTPUT_DIR: &str = "SKILLLITE_OUTPUT_DIR";
 
+    /// 输出目录配额：总字节数（支持 `512mb`、`2g`；`0` 不限，默认 1g）
+    pub const SKILLLITE_OUTPUT_MAX_BYTES: &str = "SKILLLITE_OUTPUT_MAX_BYTES";
+
+    /// 输出目录配额：文件数（`0` 不限，默认 10000）
+    pub const SKILLLITE_OUTPUT_MAX_FILES: &str = "SKILLLITE_OUTPUT_MAX_FILES";
+
+    /// 超出配额时：`refuse`（默认，拒绝写入）或 `expire`（删除其他会话最旧的产物）
+    pub const SKILLLITE_OUTPUT_QUOTA_POLICY: &str = "SKILLLITE_OUTPUT_QUOTA_POLICY";
+
     pub const SKILLLITE_WORKSPACE: &str = "SKILLLITE_WORKSPACE";
 
     pub const SKILLLITE_SKILLS_REPO: &str = "SKILLLITE_SKILLS_REPO";
 
     pub const SKILLBOX_SKILLS_ROOT: &str = "SKILLBOX_SKILLS_ROOT";
 
+    /// 用户配置文件路径（默认 `~/.skilllite/config.toml`）
+    pub const SKILLLITE_CONFIG_FILE: &str = "SKILLLITE_CONFIG_FILE";
+
     /// Sandbox child-process marker. Set to `1` by sandbox launcher; consulted
     /// by inner code paths to detect "running under sandbox".
     pub const SKILLLITE_SANDBOX: &str = "SKILLLITE_SANDBOX";
@@ -49,6 +82,10 @@ pub mod paths {
     /// Sandbox child-process flag: when sandbox launcher disables the
     /// network for the child, this is set to `1` so inner code can adapt.
     pub const SKILLLITE_NETWORK_DISABLED: &str = "SKILLLITE_NETWORK_DISABLED";
+
+    /// Sandbox child-process results directory. Set by the runner for each
+    /// skill run; a skill may write `result.json` and output files there.
+    pub const SKILLLITE_RESULTS_DIR: &str = "SKILLLITE_RESULTS_DIR";
 }
 
 /// `skilllite schedule tick`：非 dry-run 时是否允许调用 LLM（默认视为关闭，需显式开启）
@@ -60,6 +97,8 @@ pub mod schedule {
 pub mod cache {
     pub const SKILLLITE_CACHE_DIR: &str = "SKILLLITE_CACHE_DIR";
     pub const CACHE_DIR_ALIASES: &[&str] = &["SKILLBOX_CACHE_DIR", "AGENTSKILL_CACHE_DIR"];
+    /// `cache: { enabled: true }` 技能的结果缓存上限（MB，超出按最近使用淘汰，默认 64）
+    pub const SKILLLITE_RESULT_CACHE_MAX_MB: &str = "SKILLLITE_RESULT_CACHE_MAX_MB";
 }
 
 /// 可观测性与日志
@@ -81,6 +120,9 @@ pub mod observability {
 
     pub const SKILLLITE_SECURITY_EVENTS_LOG: &str = "SKILLLITE_SECURITY_EVENTS_LOG";
 
+    /// OTLP/HTTP collector base URL (e.g. `http://localhost:4318`)；需以 `otel` feature 构建，未设置则不导出 span
+    pub const SKILLLITE_OTLP_ENDPOINT: &str = "SKILLLITE_OTLP_ENDPOINT";
+
     /// P0 可观测 vs P1 可阻断：设为 1/true 时，HashChanged/SignatureInvalid/TrustDeny 会阻断执行；不设或 0 时仅展示状态不阻断（P0 模式）
     pub const SKILLLITE_SUPPLY_CHAIN_BLOCK: &str = "SKILLLITE_SUPPLY_CHAIN_BLOCK";
 
@@ -107,12 +149,26 @@ pub mod observability {
     /// 时间窗内 edit 事件触及的不重复路径数超过此值则预警（默认 80）
     pub const SKILLLITE_AUDIT_ALERT_EDIT_UNIQUE_PATHS: &str =
         "SKILLLITE_AUDIT_ALERT_EDIT_UNIQUE_PATHS";
+
+    /// 设为 0/false 时关闭工具结果、transcript 与 memory_write 中的密钥脱敏（默认开启）
+    pub const SKILLLITE_REDACT_SECRETS: &str = "SKILLLITE_REDACT_SECRETS";
+
+    /// 逗号分隔的字面量 token，命中则不脱敏（如测试用的假 key）
+    pub const SKILLLITE_REDACT_ALLOWLIST: &str = "SKILLLITE_REDACT_ALLOWLIST";
 }
 
 /// Agent 行为提示（桌面 / CLI 可选）
 pub mod agent {
     /// 桌面或包装器传入的界面语言：`zh` | `en`。合并进聊天类 system prompt 的附加段（与 `AgentConfig.context_append` 同源逻辑）。
     pub const SKILLLITE_UI_LOCALE: &str = "SKILLLITE_UI_LOCALE";
+    /// list_directory 单次返回的最大条目数（默认 500），超出时截断并提示总数。
+    pub const SKILLLITE_LIST_DIR_MAX_ENTRIES: &str = "SKILLLITE_LIST_DIR_MAX_ENTRIES";
+    /// write_file 覆盖已有文件（≥1 KiB）时，新内容低于原大小的该比例即拒绝，除非 `allow_shrink: true`（默认 0.3，0 关闭）。
+    pub const SKILLLITE_WRITE_FILE_MIN_SHRINK_RATIO: &str = "SKILLLITE_WRITE_FILE_MIN_SHRINK_RATIO";
+    /// 每个会话同时运行的后台任务（`run_command` 的 `background: true`）上限（默认 4）。
+    pub const SKILLLITE_MAX_BACKGROUND_JOBS: &str = "SKILLLITE_MAX_BACKGROUND_JOBS";
+    /// 每个后台任务保留的输出行数（环形缓冲，默认 2000）。
+    pub const SKILLLITE_JOB_OUTPUT_MAX_LINES: &str = "SKILLLITE_JOB_OUTPUT_MAX_LINES";
 }
 
 /// Memory 向量检索
@@ -123,11 +179,22 @@ pub mod memory {
     pub const SKILLLITE_EMBEDDING_BASE_URL: &str = "SKILLLITE_EMBEDDING_BASE_URL";
     /// Optional separate embedding API key; falls back to LLM `API_KEY` chain.
     pub const SKILLLITE_EMBEDDING_API_KEY: &str = "SKILLLITE_EMBEDDING_API_KEY";
+    /// Embedding backend: `http` (OpenAI-compatible API, default) | `local` (ONNX model, `local_embedding` feature).
+    pub const SKILLLITE_EMBEDDING_BACKEND: &str = "SKILLLITE_EMBEDDING_BACKEND";
+    /// Local embedding backend: model cache/download dir (default `~/.skilllite/models/embedding`).
+    pub const SKILLLITE_EMBEDDING_MODEL_DIR: &str = "SKILLLITE_EMBEDDING_MODEL_DIR";
 
     /// Master switch: enable conversation memory subsystem (default `true`).
     pub const SKILLLITE_ENABLE_MEMORY: &str = "SKILLLITE_ENABLE_MEMORY";
     /// Switch: enable the vector-search backend for memory (default `false`).
     pub const SKILLLITE_ENABLE_MEMORY_VECTOR: &str = "SKILLLITE_ENABLE_MEMORY_VECTOR";
+    /// End-of-session auto-memory triggers: comma list of `exit`, `single`, `idle`, or `off`
+    /// (default `exit,single,idle`).
+    pub const SKILLLITE_AUTO_MEMORY: &str = "SKILLLITE_AUTO_MEMORY";
+    /// Max auto-extracted memories per session (default `5`).
+    pub const SKILLLITE_AUTO_MEMORY_MAX: &str = "SKILLLITE_AUTO_MEMORY_MAX";
+    /// Idle gap (seconds) before the next turn triggers auto-memory (default `900`).
+    pub const SKILLLITE_AUTO_MEMORY_IDLE_SECS: &str = "SKILLLITE_AUTO_MEMORY_IDLE_SECS";
 }
 
 /// Long-text summarization & context-window tuning (used by `agent::types::env_config`)
@@ -152,6 +219,10 @@ pub mod summarization {
     pub const SKILLLITE_MAX_TOKENS: &str = "SKILLLITE_MAX_TOKENS";
     /// Max chars for a single user input message before truncation/summarization.
     pub const SKILLLITE_USER_INPUT_MAX_CHARS: &str = "SKILLLITE_USER_INPUT_MAX_CHARS";
+    /// Max size (bytes) of a local file attached to a chat message.
+    pub const SKILLLITE_ATTACHMENT_MAX_BYTES: &str = "SKILLLITE_ATTACHMENT_MAX_BYTES";
+    /// Max chars of extracted preview injected for each chat attachment.
+    pub const SKILLLITE_ATTACHMENT_PREVIEW_CHARS: &str = "SKILLLITE_ATTACHMENT_PREVIEW_CHARS";
     /// Max chars per generic tool result.
     pub const SKILLLITE_TOOL_RESULT_MAX_CHARS: &str = "SKILLLITE_TOOL_RESULT_MAX_CHARS";
     /// Max chars for a single `read_file` tool result.
@@ -162,6 +233,8 @@ pub mod summarization {
         "SKILLLITE_TOOL_RESULT_RECOVERY_MAX_CHARS";
     /// Soft limit on conversation payload before main agent LLM call.
     pub const SKILLLITE_CONTEXT_SOFT_LIMIT_CHARS: &str = "SKILLLITE_CONTEXT_SOFT_LIMIT_CHARS";
+    /// Same soft limit in tokens (model-aware count); defaults to chars / 4.
+    pub const SKILLLITE_CONTEXT_SOFT_LIMIT_TOKENS: &str = "SKILLLITE_CONTEXT_SOFT_LIMIT_TOKENS";
     /// Compaction threshold (message count).
     pub const SKILLLITE_COMPACTION_THRESHOLD: &str = "SKILLLITE_COMPACTION_THRESHOLD";
     /// Pre-compaction memory flush enabled flag.
@@ -206,6 +279,12 @@ pub mod evolution {
     pub const SKILLLITE_EVO_SWEEP_INTERVAL_SECS: &str = "SKILLLITE_EVO_SWEEP_INTERVAL_SECS";
     /// A9: Minimum seconds since last **material** `evolution_run` before another autorun (0 = disabled; `evolution_run_noop` ignored).
     pub const SKILLLITE_EVO_MIN_RUN_GAP_SEC: &str = "SKILLLITE_EVO_MIN_RUN_GAP_SEC";
+    /// A9: Idle trigger — `ChatSession` runs evolution in the background once the user has been idle for `SKILLLITE_EVO_IDLE_SECS` (default on; `0` disables).
+    pub const SKILLLITE_EVO_IDLE_TRIGGER: &str = "SKILLLITE_EVO_IDLE_TRIGGER";
+    /// A9: Seconds without user input before the idle trigger fires. Default 600 (10 min).
+    pub const SKILLLITE_EVO_IDLE_SECS: &str = "SKILLLITE_EVO_IDLE_SECS";
+    /// A9: Maximum idle-triggered evolution cycles per chat session. Default 3.
+    pub const SKILLLITE_EVO_IDLE_MAX_CYCLES: &str = "SKILLLITE_EVO_IDLE_MAX_CYCLES";
     /// Skip snapshot + learners when there is no decision backlog (default on). Set `0` to disable.
     pub const SKILLLITE_EVO_SHALLOW_PREFLIGHT: &str = "SKILLLITE_EVO_SHALLOW_PREFLIGHT";
     /// Active-scope proposals: minimum stable successful decisions before active evolution (default 10).
@@ -214,6 +293,11 @@ pub mod evolution {
     /// Prompt snapshot dirs under `chat/prompts/_versions/` to keep after each evolution (oldest pruned first).
     /// Default `10`. Set to `0` to never delete snapshots (full local history, no Git required; disk usage grows).
     pub const SKILLLITE_EVOLUTION_SNAPSHOT_KEEP: &str = "SKILLLITE_EVOLUTION_SNAPSHOT_KEEP";
+    /// `evolution.log` / `_versions/changelog.jsonl` size (bytes) that triggers rotation to `.1`, `.2`, ….
+    /// Default 5 MiB; `0` disables rotation.
+    pub const SKILLLITE_EVO_LOG_MAX_BYTES: &str = "SKILLLITE_EVO_LOG_MAX_BYTES";
+    /// Rotated archives kept per log file. Default `5`.
+    pub const SKILLLITE_EVO_LOG_KEEP_ARCHIVES: &str = "SKILLLITE_EVO_LOG_KEEP_ARCHIVES";
     /// Allow coordinator to auto-execute low-risk proposals when policy runtime is enabled.
     /// Default enabled (`1`/`true`).
     pub const SKILLLITE_EVO_AUTO_EXECUTE_LOW_RISK: &str = "SKILLLITE_EVO_AUTO_EXECUTE_LOW_RISK";
@@ -245,6 +329,13 @@ pub mod evolution {
     pub const SKILLLITE_EVO_RECENT_DAYS: &str = "SKILLLITE_EVO_RECENT_DAYS";
     /// 时间窗口内最多取多少条决策参与统计。默认 100。
     pub const SKILLLITE_EVO_RECENT_LIMIT: &str = "SKILLLITE_EVO_RECENT_LIMIT";
+    /// 未进化决策的最大保留天数，超出后不再计入进化统计并标记为过期（evolved = 2）。默认 14，0 关闭。
+    pub const SKILLLITE_EVO_DECISION_MAX_AGE_DAYS: &str = "SKILLLITE_EVO_DECISION_MAX_AGE_DAYS";
+    /// 待确认技能（`_evolved/_pending`）超过此天数在 `evolution status` 中标记为待清理。默认 14，0 关闭。
+    pub const SKILLLITE_EVO_PENDING_STALE_DAYS: &str = "SKILLLITE_EVO_PENDING_STALE_DAYS";
+    /// 设为 1 时，除 chat 根目录的 `DECISIONS.md` 外，再为每个记录过决策的工作区导出只含其决策的
+    /// `<workspace>/.skilllite/DECISIONS.md`。默认 0。
+    pub const SKILLLITE_EVO_WORKSPACE_DECISIONS: &str = "SKILLLITE_EVO_WORKSPACE_DECISIONS";
     /// 单条决策至少多少 tool 调用才计入「有意义」条数。默认 2。
     pub const SKILLLITE_EVO_MEANINGFUL_MIN_TOOLS: &str = "SKILLLITE_EVO_MEANINGFUL_MIN_TOOLS";
     /// 技能进化：有意义决策数 ≥ 此值且（有失败或存在重复模式）才触发。默认 3。
@@ -274,6 +365,15 @@ pub mod evolution {
     /// Prompt learner: max recent decisions per success/fail bucket for **rule extraction** summaries. Default `10`.
     pub const SKILLLITE_EVO_PROMPT_RULE_SUMMARY_LIMIT: &str =
         "SKILLLITE_EVO_PROMPT_RULE_SUMMARY_LIMIT";
+    /// Prompt learner: approximate token budget for all examples in `examples.json`; lowest-quality examples are archived above it. Default `1500`.
+    pub const SKILLLITE_EVO_EXAMPLE_TOKEN_BUDGET: &str = "SKILLLITE_EVO_EXAMPLE_TOKEN_BUDGET";
+    /// Prompt learner: holdout evaluation of candidate rules before promotion — `off` (default, rules go live directly), `cheap` (replayed plans must parse and use known tools), `llm` (adds an LLM judge score).
+    pub const SKILLLITE_EVO_PROMPT_EVALUATION: &str = "SKILLLITE_EVO_PROMPT_EVALUATION";
+    /// Prompt learner: recent completed decisions replayed by the holdout evaluation. Default `5`.
+    pub const SKILLLITE_EVO_PROMPT_EVALUATION_SAMPLES: &str =
+        "SKILLLITE_EVO_PROMPT_EVALUATION_SAMPLES";
+    /// Gatekeeper L2: max examples archived per evolution cycle. Default `3`.
+    pub const SKILLLITE_EVO_MAX_EXAMPLE_RETIREMENTS: &str = "SKILLLITE_EVO_MAX_EXAMPLE_RETIREMENTS";
     /// Memory learner: lookback window in days for decision rows fed to extraction. Default `7`.
     pub const SKILLLITE_EVO_MEMORY_RECENT_DAYS: &str = "SKILLLITE_EVO_MEMORY_RECENT_DAYS";
     /// Memory learner: max decision rows in that window for the extraction prompt. Default `15`.
@@ -307,6 +407,22 @@ pub mod evolution {
 
     /// Set to `1` to allow ingesting external (community) learners.
     pub const SKILLLITE_EXTERNAL_LEARNING: &str = "SKILLLITE_EXTERNAL_LEARNING";
+    /// External learning: max response bytes read per source. Default 524288 (512 KiB).
+    pub const SKILLLITE_EVO_EXTERNAL_MAX_BYTES_PER_SOURCE: &str =
+        "SKILLLITE_EVO_EXTERNAL_MAX_BYTES_PER_SOURCE";
+    /// External learning: max parsed items per source sent to the LLM. Default 10.
+    pub const SKILLLITE_EVO_EXTERNAL_MAX_ITEMS_PER_SOURCE: &str =
+        "SKILLLITE_EVO_EXTERNAL_MAX_ITEMS_PER_SOURCE";
+    /// External learning: wall-clock budget (seconds) for the whole phase. Default 60.
+    pub const SKILLLITE_EVO_EXTERNAL_BUDGET_SECS: &str = "SKILLLITE_EVO_EXTERNAL_BUDGET_SECS";
+    /// Evolution LLM calls: total attempts per call (transient errors back off exponentially). Default 3.
+    pub const SKILLLITE_EVO_LLM_MAX_ATTEMPTS: &str = "SKILLLITE_EVO_LLM_MAX_ATTEMPTS";
+    /// Evolution LLM calls: per-attempt timeout in seconds. Default 120.
+    pub const SKILLLITE_EVO_LLM_TIMEOUT_SECS: &str = "SKILLLITE_EVO_LLM_TIMEOUT_SECS";
+    /// Evolution LLM calls: first retry delay in milliseconds (doubles per attempt, max 30s). Default 1000.
+    pub const SKILLLITE_EVO_LLM_RETRY_BASE_MS: &str = "SKILLLITE_EVO_LLM_RETRY_BASE_MS";
+    /// Feedback capture: ask the LLM to classify user messages the word lists leave neutral. Default off.
+    pub const SKILLLITE_EVO_FEEDBACK_LLM: &str = "SKILLLITE_EVO_FEEDBACK_LLM";
 
     /// Minimum recurrence count for a tool/argument pattern to be considered
     /// a candidate for skill synthesis.
@@ -321,6 +437,8 @@ pub mod swarm {
     pub const SKILLLITE_SWARM_TOKEN: &str = "SKILLLITE_SWARM_TOKEN";
     /// 设为 `0` 时关闭 swarm handler 内的 LLM 路由决策，回退到静态规则。
     pub const SKILLLITE_SWARM_LLM_ROUTING: &str = "SKILLLITE_SWARM_LLM_ROUTING";
+    /// 静态 peer（`--peer` / `peers.toml`）健康检查间隔秒数（默认 30）；不可达时按指数退避，上限 10 分钟
+    pub const SKILLLITE_SWARM_PEER_CHECK_SECS: &str = "SKILLLITE_SWARM_PEER_CHECK_SECS";
 }
 
 /// Channel-serve (HTTP/SMS bridge) configuration
@@ -366,6 +484,16 @@ pub mod executor {
     /// Flush after this many milliseconds (for `interval` and `hybrid` modes).
     pub const SKILLLITE_TRANSCRIPT_FLUSH_INTERVAL_MS: &str =
         "SKILLLITE_TRANSCRIPT_FLUSH_INTERVAL_MS";
+    /// Days an unfinished `skilllite run` checkpoint is kept after its last update (`0` = forever).
+    pub const SKILLLITE_RUN_CHECKPOINT_MAX_AGE_DAYS: &str = "SKILLLITE_RUN_CHECKPOINT_MAX_AGE_DAYS";
+}
+
+/// Skill execution stdio daemon (`skilllite serve --stdio`)
+pub mod ipc {
+    /// Maximum in-flight requests; defaults to the number of available CPUs (at least 4).
+    pub const SKILLLITE_IPC_MAX_CONCURRENCY: &str = "SKILLLITE_IPC_MAX_CONCURRENCY";
+    /// Set to `1` to flush every response in request order (per-request `"ordered": true`).
+    pub const SKILLLITE_IPC_ORDERED: &str = "SKILLLITE_IPC_ORDERED";
 }
 
 /// CLI command-layer overrides (used by `skilllite-commands`)
@@ -408,6 +536,23 @@ pub mod agent_loop {
     pub const SKILLLITE_MAX_ITERATIONS: &str = "SKILLLITE_MAX_ITERATIONS";
     /// 单任务内工具调用深度上限，并参与有计划时的有效迭代上限计算（默认 15）。
     pub const SKILLLITE_MAX_TOOL_CALLS_PER_TASK: &str = "SKILLLITE_MAX_TOOL_CALLS_PER_TASK";
+    /// 连续多轮工具调用全部失败后切换到的备用模型（可选）。
+    pub const SKILLLITE_FALLBACK_MODEL: &str = "SKILLLITE_FALLBACK_MODEL";
+    /// 每个会话允许的 update_task_plan 重规划次数（默认 3）；超出后拒绝大幅替换计划。
+    pub const SKILLLITE_MAX_REPLANS: &str = "SKILLLITE_MAX_REPLANS";
+    /// 同一工具以相同参数连续调用达到该次数时跳过执行并提示模型换思路（默认 3；0 = 关闭检测）。
+    pub const SKILLLITE_TOOL_LOOP_THRESHOLD: &str = "SKILLLITE_TOOL_LOOP_THRESHOLD";
+    /// 重复调用达到该次数时强制中止：有计划时将当前任务标记为失败，否则结束本轮（默认 5）。
+    pub const SKILLLITE_TOOL_LOOP_HARD_STOP: &str = "SKILLLITE_TOOL_LOOP_HARD_STOP";
+    /// 模型单价 JSON（美元 / 百万 token），供 `skilllite run --max-cost` 估算花费，
+    /// 例如 `{"gpt-4o": {"input": 2.5, "output": 10}}`；未列出的模型只按 `--max-tokens` 限制。
+    pub const SKILLLITE_MODEL_PRICES_JSON: &str = "SKILLLITE_MODEL_PRICES_JSON";
+    /// 同一进程内同时执行的技能总数上限（所有会话共享；未设置或 0 = 不限）。
+    /// 单个技能另可在 SKILL.md 中用 `max_concurrency` 限制。
+    pub const SKILLLITE_MAX_PARALLEL_SKILLS: &str = "SKILLLITE_MAX_PARALLEL_SKILLS";
+    /// 规划完成后先等待用户审批计划（approve / edit / remove / add / reject）再执行；
+    /// 仅交互式 chat 与 agent-rpc 可用，单次 / 无人值守运行会直接报错。
+    pub const SKILLLITE_APPROVE_PLAN: &str = "SKILLLITE_APPROVE_PLAN";
 }
 
 /// 规划与 dependency-audit
@@ -416,6 +561,16 @@ pub mod misc {
     pub const SKILLLITE_AUDIT_API: &str = "SKILLLITE_AUDIT_API";
     pub const PYPI_MIRROR_URL: &str = "PYPI_MIRROR_URL";
     pub const OSV_API_URL: &str = "OSV_API_URL";
+    /// dependency-audit 缓存有效期（秒），默认 86400；0 = 在线时总是重新查询
+    pub const SKILLLITE_AUDIT_CACHE_TTL_SECS: &str = "SKILLLITE_AUDIT_CACHE_TTL_SECS";
+    /// `skilllite add --list` 使用的技能索引（https / file:// URL 或本地路径）
+    pub const SKILLLITE_REGISTRY_URL: &str = "SKILLLITE_REGISTRY_URL";
+    /// 技能索引缓存有效期（秒），默认 3600；离线时回退到过期缓存
+    pub const SKILLLITE_REGISTRY_CACHE_TTL_SECS: &str = "SKILLLITE_REGISTRY_CACHE_TTL_SECS";
+    /// tiktoken 格式词表目录（`cl100k_base.tiktoken` 等），默认 `~/.skilllite/tokenizers`
+    pub const SKILLLITE_TOKENIZER_DIR: &str = "SKILLLITE_TOKENIZER_DIR";
+    /// 额外的已知 capability 标签（逗号分隔），与内置标签表及 `capability-tags.txt` 合并
+    pub const SKILLLITE_CAPABILITY_TAGS: &str = "SKILLLITE_CAPABILITY_TAGS";
 }
 
 /// 沙箱执行：级别、资源限制、开关等（SKILLLITE_* 优先，兼容 SKILLBOX_*）
@@ -423,12 +578,29 @@ pub mod sandbox {
     pub const SKILLLITE_SANDBOX_LEVEL: &str = "SKILLLITE_SANDBOX_LEVEL";
     pub const SANDBOX_LEVEL_ALIASES: &[&str] = &["SKILLBOX_SANDBOX_LEVEL"];
 
+    /// Minimum sandbox level for evolution-generated skills (`skills/_evolved/`), default 2;
+    /// `1` treats them like installed skills.
+    pub const SKILLLITE_EVOLVED_SKILL_LEVEL: &str = "SKILLLITE_EVOLVED_SKILL_LEVEL";
+
     pub const SKILLLITE_MAX_MEMORY_MB: &str = "SKILLLITE_MAX_MEMORY_MB";
     pub const MAX_MEMORY_MB_ALIASES: &[&str] = &["SKILLBOX_MAX_MEMORY_MB"];
 
     pub const SKILLLITE_TIMEOUT_SECS: &str = "SKILLLITE_TIMEOUT_SECS";
     pub const TIMEOUT_SECS_ALIASES: &[&str] = &["SKILLBOX_TIMEOUT_SECS"];
 
+    /// CPU quota in percent of one core (e.g. `50`, `200`); unset = unlimited. Linux cgroup v2 only.
+    pub const SKILLLITE_MAX_CPU_PERCENT: &str = "SKILLLITE_MAX_CPU_PERCENT";
+
+    /// Set to `0` to disable the Linux cgroup v2 backend and always use rlimits + RSS polling.
+    pub const SKILLLITE_SANDBOX_CGROUP: &str = "SKILLLITE_SANDBOX_CGROUP";
+
+    /// Delegated cgroup (path relative to `/sys/fs/cgroup`) to create per-run cgroups in;
+    /// default: the process's own cgroup, only if systemd delegated it (`Delegate=yes`).
+    pub const SKILLLITE_SANDBOX_CGROUP_PARENT: &str = "SKILLLITE_SANDBOX_CGROUP_PARENT";
+
+    /// `enforce` / `log`: overrides the action of every Linux seccomp profile.
+    pub const SKILLLITE_SECCOMP_MODE: &str = "SKILLLITE_SECCOMP_MODE";
+
     pub const SKILLLITE_AUTO_APPROVE: &str = "SKILLLITE_AUTO_APPROVE";
     pub const AUTO_APPROVE_ALIASES: &[&str] = &["SKILLBOX_AUTO_APPROVE"];
 
@@ -453,6 +625,10 @@ pub mod sandbox {
     /// Set to `1` to skip the interactive runtime-dependency confirmation.
     pub const SKILLLITE_AUTO_APPROVE_RUNTIME: &str = "SKILLLITE_AUTO_APPROVE_RUNTIME";
 
+    /// Overall limit (seconds, default 900) for building a skill's dependency environment
+    /// (venv, pip/npm install, browser download); separate from `SKILLLITE_TIMEOUT_SECS`.
+    pub const SKILLLITE_ENV_SETUP_TIMEOUT_SECS: &str = "SKILLLITE_ENV_SETUP_TIMEOUT_SECS";
+
     /// Optional mirror base URL for downloading the Python runtime.
     pub const SKILLLITE_RUNTIME_PYTHON_BASE_URL: &str = "SKILLLITE_RUNTIME_PYTHON_BASE_URL";
 
@@ -480,21 +656,29 @@ pub fn all_known_keys() -> &'static [&'static str] {
         "SKILLLITE_ALLOW_PLAYWRIGHT",
         "SKILLLITE_API_BASE",
         "SKILLLITE_API_KEY",
+        "SKILLLITE_APPROVE_PLAN",
         "SKILLLITE_ARTIFACT_HTTP_ADDR",
         "SKILLLITE_ARTIFACT_HTTP_ALLOW_INSECURE_NO_AUTH",
         "SKILLLITE_ARTIFACT_HTTP_REQUIRE_AUTH",
+        "SKILLLITE_ATTACHMENT_MAX_BYTES",
+        "SKILLLITE_ATTACHMENT_PREVIEW_CHARS",
         "SKILLLITE_AUDIT_ALERT_EDIT_UNIQUE_PATHS",
         "SKILLLITE_AUDIT_ALERT_FAILURE_RATIO",
         "SKILLLITE_AUDIT_ALERT_MAX_INVOCATIONS_PER_SKILL",
         "SKILLLITE_AUDIT_ALERT_MIN_INVOCATIONS_FOR_FAILURE",
         "SKILLLITE_AUDIT_ALERT_WEBHOOK",
         "SKILLLITE_AUDIT_API",
+        "SKILLLITE_AUDIT_CACHE_TTL_SECS",
         "SKILLLITE_AUDIT_CONTEXT",
         "SKILLLITE_AUDIT_DISABLED",
         "SKILLLITE_AUDIT_LOG",
         "SKILLLITE_AUTO_APPROVE",
         "SKILLLITE_AUTO_APPROVE_RUNTIME",
+        "SKILLLITE_AUTO_MEMORY",
+        "SKILLLITE_AUTO_MEMORY_IDLE_SECS",
+        "SKILLLITE_AUTO_MEMORY_MAX",
         "SKILLLITE_CACHE_DIR",
+        "SKILLLITE_CAPABILITY_TAGS",
         "SKILLLITE_CHANNEL_DINGTALK_SECRET",
         "SKILLLITE_CHANNEL_DINGTALK_WEBHOOK",
         "SKILLLITE_CHANNEL_FEISHU_SECRET",
@@ -508,18 +692,24 @@ pub fn all_known_keys() -> &'static [&'static str] {
         "SKILLLITE_COMPACTION_KEEP_RECENT",
         "SKILLLITE_COMPACTION_THRESHOLD",
         "SKILLLITE_COMPACT_PLANNING",
+        "SKILLLITE_CONFIG_FILE",
         "SKILLLITE_CONTEXT_SOFT_LIMIT_CHARS",
+        "SKILLLITE_CONTEXT_SOFT_LIMIT_TOKENS",
         "SKILLLITE_EMBEDDING_API_KEY",
+        "SKILLLITE_EMBEDDING_BACKEND",
         "SKILLLITE_EMBEDDING_BASE_URL",
         "SKILLLITE_EMBEDDING_DIMENSION",
         "SKILLLITE_EMBEDDING_MODEL",
+        "SKILLLITE_EMBEDDING_MODEL_DIR",
         "SKILLLITE_ENABLE_MEMORY",
         "SKILLLITE_ENABLE_MEMORY_VECTOR",
         "SKILLLITE_ENABLE_TASK_PLANNING",
+        "SKILLLITE_ENV_SETUP_TIMEOUT_SECS",
         "SKILLLITE_EVOLUTION",
         "SKILLLITE_EVOLUTION_DECISION_THRESHOLD",
         "SKILLLITE_EVOLUTION_INTERVAL_SECS",
         "SKILLLITE_EVOLUTION_SNAPSHOT_KEEP",
+        "SKILLLITE_EVOLVED_SKILL_LEVEL",
         "SKILLLITE_EVO_ACCEPTANCE_MAX_CORRECTION_RATE",
         "SKILLLITE_EVO_ACCEPTANCE_MAX_ROLLBACK_RATE",
         "SKILLLITE_EVO_ACCEPTANCE_MIN_SUCCESS_RATE",
@@ -527,9 +717,24 @@ pub fn all_known_keys() -> &'static [&'static str] {
         "SKILLLITE_EVO_ACTIVE_MIN_STABLE_DECISIONS",
         "SKILLLITE_EVO_AUTO_EXECUTE_LOW_RISK",
         "SKILLLITE_EVO_COOLDOWN_HOURS",
+        "SKILLLITE_EVO_DECISION_MAX_AGE_DAYS",
         "SKILLLITE_EVO_DENY_CRITICAL",
+        "SKILLLITE_EVO_EXAMPLE_TOKEN_BUDGET",
+        "SKILLLITE_EVO_EXTERNAL_BUDGET_SECS",
+        "SKILLLITE_EVO_EXTERNAL_MAX_BYTES_PER_SOURCE",
+        "SKILLLITE_EVO_EXTERNAL_MAX_ITEMS_PER_SOURCE",
         "SKILLLITE_EVO_FAILURES_MIN_PROMPTS",
+        "SKILLLITE_EVO_FEEDBACK_LLM",
         "SKILLLITE_EVO_FORCE_PROPOSAL_ID",
+        "SKILLLITE_EVO_IDLE_MAX_CYCLES",
+        "SKILLLITE_EVO_IDLE_SECS",
+        "SKILLLITE_EVO_IDLE_TRIGGER",
+        "SKILLLITE_EVO_LLM_MAX_ATTEMPTS",
+        "SKILLLITE_EVO_LLM_RETRY_BASE_MS",
+        "SKILLLITE_EVO_LLM_TIMEOUT_SECS",
+        "SKILLLITE_EVO_LOG_KEEP_ARCHIVES",
+        "SKILLLITE_EVO_LOG_MAX_BYTES",
+        "SKILLLITE_EVO_MAX_EXAMPLE_RETIREMENTS",
         "SKILLLITE_EVO_MEANINGFUL_MIN_TOOLS",
         "SKILLLITE_EVO_MEANINGFUL_THRESHOLD_MEMORY",
         "SKILLLITE_EVO_MEANINGFUL_THRESHOLD_PROMPTS",
@@ -537,8 +742,11 @@ pub fn all_known_keys() -> &'static [&'static str] {
         "SKILLLITE_EVO_MEMORY_DECISION_LIMIT",
         "SKILLLITE_EVO_MEMORY_RECENT_DAYS",
         "SKILLLITE_EVO_MIN_RUN_GAP_SEC",
+        "SKILLLITE_EVO_PENDING_STALE_DAYS",
         "SKILLLITE_EVO_POLICY_RUNTIME_ENABLED",
         "SKILLLITE_EVO_PROFILE",
+        "SKILLLITE_EVO_PROMPT_EVALUATION",
+        "SKILLLITE_EVO_PROMPT_EVALUATION_SAMPLES",
         "SKILLLITE_EVO_PROMPT_EXAMPLE_MIN_TOOLS",
         "SKILLLITE_EVO_PROMPT_RULE_SUMMARY_LIMIT",
         "SKILLLITE_EVO_RECENT_DAYS",
@@ -557,8 +765,10 @@ pub fn all_known_keys() -> &'static [&'static str] {
         "SKILLLITE_EVO_SWEEP_INTERVAL_SECS",
         "SKILLLITE_EVO_TRIGGER_SIGNAL_WINDOW",
         "SKILLLITE_EVO_TRIGGER_WEIGHTED_MIN",
+        "SKILLLITE_EVO_WORKSPACE_DECISIONS",
         "SKILLLITE_EXTERNAL_LEARNING",
         "SKILLLITE_EXTRACT_TOP_K_RATIO",
+        "SKILLLITE_FALLBACK_MODEL",
         "SKILLLITE_FUZZY_THRESHOLD",
         "SKILLLITE_GATEWAY_SERVE_ALLOW",
         "SKILLLITE_GOAL_LLM_EXTRACT",
@@ -566,15 +776,29 @@ pub fn all_known_keys() -> &'static [&'static str] {
         "SKILLLITE_HEARTBEAT_INTERVAL_SECS",
         "SKILLLITE_HIGH_RISK_CONFIRM",
         "SKILLLITE_HISTORY_WINDOW_MESSAGES",
+        "SKILLLITE_IPC_MAX_CONCURRENCY",
+        "SKILLLITE_IPC_ORDERED",
+        "SKILLLITE_JOB_OUTPUT_MAX_LINES",
+        "SKILLLITE_LIST_DIR_MAX_ENTRIES",
+        "SKILLLITE_LLM_MAX_ATTEMPTS",
+        "SKILLLITE_LLM_PROVIDER",
+        "SKILLLITE_LLM_RECORD",
+        "SKILLLITE_LLM_REPLAY",
+        "SKILLLITE_LLM_REPLAY_LOOSE",
+        "SKILLLITE_LLM_RETRY_MAX_BACKOFF_SECS",
         "SKILLLITE_LOG_JSON",
         "SKILLLITE_LOG_LEVEL",
         "SKILLLITE_LONG_TEXT_STRATEGY",
         "SKILLLITE_MAP_MODEL",
+        "SKILLLITE_MAX_BACKGROUND_JOBS",
+        "SKILLLITE_MAX_CPU_PERCENT",
         "SKILLLITE_MAX_EVOLUTIONS_PER_DAY",
         "SKILLLITE_MAX_ITERATIONS",
         "SKILLLITE_MAX_MEMORY_MB",
         "SKILLLITE_MAX_OUTPUT_CHARS",
+        "SKILLLITE_MAX_PARALLEL_SKILLS",
         "SKILLLITE_MAX_PROCESSES",
+        "SKILLLITE_MAX_REPLANS",
         "SKILLLITE_MAX_TOKENS",
         "SKILLLITE_MAX_TOOL_CALLS_PER_TASK",
         "SKILLLITE_MCP_SERVERS_JSON",
@@ -582,17 +806,33 @@ pub fn all_known_keys() -> &'static [&'static str] {
         "SKILLLITE_MEMORY_FLUSH_THRESHOLD",
         "SKILLLITE_MIN_PATTERN_COUNT",
         "SKILLLITE_MODEL",
+        "SKILLLITE_MODEL_ALLOWLIST",
+        "SKILLLITE_MODEL_PRICES_JSON",
         "SKILLLITE_NETWORK_DISABLED",
         "SKILLLITE_NO_SANDBOX",
+        "SKILLLITE_OTLP_ENDPOINT",
         "SKILLLITE_OUTPUT_DIR",
+        "SKILLLITE_OUTPUT_MAX_BYTES",
+        "SKILLLITE_OUTPUT_MAX_FILES",
+        "SKILLLITE_OUTPUT_QUOTA_POLICY",
         "SKILLLITE_QUIET",
         "SKILLLITE_READ_FILE_TOOL_RESULT_MAX_CHARS",
+        "SKILLLITE_REDACT_ALLOWLIST",
+        "SKILLLITE_REDACT_SECRETS",
+        "SKILLLITE_REGISTRY_CACHE_TTL_SECS",
+        "SKILLLITE_REGISTRY_URL",
+        "SKILLLITE_RESULTS_DIR",
+        "SKILLLITE_RESULT_CACHE_MAX_MB",
         "SKILLLITE_RUNTIME_NODE_BASE_URL",
         "SKILLLITE_RUNTIME_PYTHON_BASE_URL",
+        "SKILLLITE_RUN_CHECKPOINT_MAX_AGE_DAYS",
         "SKILLLITE_SANDBOX",
+        "SKILLLITE_SANDBOX_CGROUP",
+        "SKILLLITE_SANDBOX_CGROUP_PARENT",
         "SKILLLITE_SANDBOX_LEVEL",
         "SKILLLITE_SCHEDULE_ENABLED",
         "SKILLLITE_SCRIPT_ARGS",
+        "SKILLLITE_SECCOMP_MODE",
         "SKILLLITE_SECURITY_EVENTS_LOG",
         "SKILLLITE_SKILLS_DIR",
         "SKILLLITE_SKILLS_REPO",
@@ -601,10 +841,14 @@ pub fn all_known_keys() -> &'static [&'static str] {
         "SKILLLITE_SUMMARIZE_THRESHOLD",
         "SKILLLITE_SUPPLY_CHAIN_BLOCK",
         "SKILLLITE_SWARM_LLM_ROUTING",
+        "SKILLLITE_SWARM_PEER_CHECK_SECS",
         "SKILLLITE_SWARM_TOKEN",
         "SKILLLITE_SWARM_URL",
         "SKILLLITE_TAIL_CHUNKS",
         "SKILLLITE_TIMEOUT_SECS",
+        "SKILLLITE_TOKENIZER_DIR",
+        "SKILLLITE_TOOL_LOOP_HARD_STOP",
+        "SKILLLITE_TOOL_LOOP_THRESHOLD",
         "SKILLLITE_TOOL_RESULT_MAX_CHARS",
         "SKILLLITE_TOOL_RESULT_RECOVERY_MAX_CHARS",
         "SKILLLITE_TRANSCRIPT_FLUSH_EVERY",
@@ -614,6 +858,7 @@ pub fn all_known_keys() -> &'static [&'static str] {
         "SKILLLITE_UI_LOCALE",
         "SKILLLITE_USER_INPUT_MAX_CHARS",
         "SKILLLITE_WORKSPACE",
+        "SKILLLITE_WRITE_FILE_MIN_SHRINK_RATIO",
     ]
 }
 
@@ -664,23 +909,38 @@ mod tests {
             llm::API_BASE,
             llm::API_KEY,
             llm::MODEL,
+            llm::MODEL_ALLOWLIST,
             paths::SKILLLITE_SKILLS_DIR,
             paths::SKILLLITE_SANDBOX,
             paths::SKILLLITE_NETWORK_DISABLED,
             agent_loop::SKILLLITE_MAX_ITERATIONS,
             agent_loop::SKILLLITE_MAX_TOOL_CALLS_PER_TASK,
+            agent_loop::SKILLLITE_MAX_REPLANS,
+            agent_loop::SKILLLITE_TOOL_LOOP_THRESHOLD,
+            agent_loop::SKILLLITE_TOOL_LOOP_HARD_STOP,
+            agent_loop::SKILLLITE_MODEL_PRICES_JSON,
+            agent_loop::SKILLLITE_MAX_PARALLEL_SKILLS,
+            agent::SKILLLITE_MAX_BACKGROUND_JOBS,
+            agent::SKILLLITE_JOB_OUTPUT_MAX_LINES,
             sandbox::SKILLLITE_SANDBOX_LEVEL,
+            sandbox::SKILLLITE_EVOLVED_SKILL_LEVEL,
             sandbox::SKILLLITE_MAX_PROCESSES,
             sandbox::SKILLLITE_AUTO_APPROVE_RUNTIME,
+            sandbox::SKILLLITE_ENV_SETUP_TIMEOUT_SECS,
             sandbox::SKILLLITE_RUNTIME_PYTHON_BASE_URL,
             sandbox::SKILLLITE_RUNTIME_NODE_BASE_URL,
+            executor::SKILLLITE_RUN_CHECKPOINT_MAX_AGE_DAYS,
             evolution::SKILLLITE_EVOLUTION,
             evolution::SKILLLITE_EVO_FORCE_PROPOSAL_ID,
+            evolution::SKILLLITE_EVO_IDLE_TRIGGER,
+            evolution::SKILLLITE_EVO_IDLE_SECS,
+            evolution::SKILLLITE_EVO_IDLE_MAX_CYCLES,
             evolution::SKILLLITE_SKILL_DEDUP_DESCRIPTION,
             evolution::SKILLLITE_EXTERNAL_LEARNING,
             evolution::SKILLLITE_MIN_PATTERN_COUNT,
             swarm::SKILLLITE_SWARM_URL,
             swarm::SKILLLITE_SWARM_LLM_ROUTING,
+            swarm::SKILLLITE_SWARM_PEER_CHECK_SECS,
             channel::SKILLLITE_CHANNEL_SERVE_ALLOW,
             channel::SKILLLITE_CHANNEL_HTTP_ADDR,
             channel::SKILLLITE_CHANNEL_HTTP_ALLOW_INSECURE_NO_AUTH,
@@ -690,13 +950,23 @@ mod tests {
             channel::SKILLLITE_CHANNEL_FEISHU_SECRET,
             channel::SKILLLITE_CHANNEL_TELEGRAM_BOT_TOKEN,
             channel::SKILLLITE_CHANNEL_TELEGRAM_CHAT_ID,
+            paths::SKILLLITE_OUTPUT_MAX_BYTES,
+            paths::SKILLLITE_OUTPUT_MAX_FILES,
+            paths::SKILLLITE_OUTPUT_QUOTA_POLICY,
             artifact::SKILLLITE_ARTIFACT_HTTP_ADDR,
             artifact::SKILLLITE_ARTIFACT_HTTP_REQUIRE_AUTH,
             artifact::SKILLLITE_ARTIFACT_HTTP_ALLOW_INSECURE_NO_AUTH,
             memory::SKILLLITE_EMBEDDING_BASE_URL,
             memory::SKILLLITE_EMBEDDING_API_KEY,
+            memory::SKILLLITE_EMBEDDING_BACKEND,
+            memory::SKILLLITE_EMBEDDING_MODEL_DIR,
             memory::SKILLLITE_ENABLE_MEMORY,
             memory::SKILLLITE_ENABLE_MEMORY_VECTOR,
+            memory::SKILLLITE_AUTO_MEMORY,
+            memory::SKILLLITE_AUTO_MEMORY_MAX,
+            memory::SKILLLITE_AUTO_MEMORY_IDLE_SECS,
+            observability::SKILLLITE_REDACT_SECRETS,
+            observability::SKILLLITE_REDACT_ALLOWLIST,
             executor::SKILLLITE_TRANSCRIPT_FLUSH_MODE,
             executor::SKILLLITE_TRANSCRIPT_FLUSH_EVERY,
             executor::SKILLLITE_TRANSCRIPT_FLUSH_INTERVAL_MS,
@@ -716,6 +986,8 @@ mod tests {
             summarization::SKILLLITE_SUMMARIZE_THRESHOLD,
             summarization::SKILLLITE_MAX_TOKENS,
             summarization::SKILLLITE_USER_INPUT_MAX_CHARS,
+            summarization::SKILLLITE_ATTACHMENT_MAX_BYTES,
+            summarization::SKILLLITE_ATTACHMENT_PREVIEW_CHARS,
             summarization::SKILLLITE_TOOL_RESULT_MAX_CHARS,
             summarization::SKILLLITE_READ_FILE_TOOL_RESULT_MAX_CHARS,
             summarization::SKILLLITE_TOOL_RESULT_RECOVERY_MAX_CHARS,
@@ -725,7 +997,14 @@ mod tests {
             summarization::SKILLLITE_MEMORY_FLUSH_THRESHOLD,
             summarization::SKILLLITE_COMPACTION_KEEP_RECENT,
             summarization::SKILLLITE_HISTORY_WINDOW_MESSAGES,
+            evolution::SKILLLITE_EVO_PROMPT_EVALUATION,
+            evolution::SKILLLITE_EVO_PROMPT_EVALUATION_SAMPLES,
+            sandbox::SKILLLITE_MAX_CPU_PERCENT,
+            sandbox::SKILLLITE_SANDBOX_CGROUP,
+            sandbox::SKILLLITE_SANDBOX_CGROUP_PARENT,
+            sandbox::SKILLLITE_SECCOMP_MODE,
             fs::SKILLLITE_FUZZY_THRESHOLD,
+            misc::SKILLLITE_CAPABILITY_TAGS,
         ];
         for k in must_contain {
             assert!(
@@ -2,16 +2,21 @@
 //!
 //! 所有环境变量读取集中在此模块，业务代码通过结构化配置访问，避免直接 `std::env::var`。
 //!
-//! **配置来源优先级**（高 → 低）：CLI/显式参数 > 环境变量 > .env 文件 > 默认值。
+//! **配置来源优先级**（高 → 低）：CLI/显式参数 > 环境变量 > .env 文件 >
+//! `<workspace>/.skilllite/config.toml` > `~/.skilllite/config.toml` > 默认值（见 [`settings`]）。
 //! 详见 `docs/zh/ENV_REFERENCE.md` 的「配置来源优先级」章节。
 //!
 //! - `loader`：env_or、env_optional、env_bool、load_dotenv、parse_dotenv_* 等
 //! - `schema`：LlmConfig、PathsConfig、AgentFeatureFlags
 //! - `env_keys`：key 常量（含 legacy 向后兼容）
+//! - `settings`：分层合并的 [`Settings`]（配置文件 + 环境变量 + CLI），`skilllite config show/set`
+//! - `user_file`：`~/.skilllite/config.toml` 中 quickstart 写入的 LLM 项
 
 pub mod env_keys;
 pub mod loader;
 pub mod schema;
+pub mod settings;
+pub mod user_file;
 
 pub use loader::{
     ensure_default_output_dir, init_daemon_env, init_llm_env, remove_env_var, set_env_var,
@@ -23,6 +28,8 @@ pub use loader::{
     parse_dotenv_walking_up,
 };
 pub use schema::{
-    AgentFeatureFlags, AgentLoopLimitsConfig, CacheConfig, EmbeddingConfig, LlmConfig,
-    ObservabilityConfig, PathsConfig, SandboxEnvConfig,
+    AgentFeatureFlags, AgentLoopLimitsConfig, CacheConfig, EmbeddingBackend, EmbeddingConfig,
+    LlmConfig, ObservabilityConfig, PathsConfig, SandboxEnvConfig,
 };
+pub use settings::{set_cli_override, SettingSource, Settings};
+pub use user_file::UserConfigFile;
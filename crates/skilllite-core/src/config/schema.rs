@@ -1,11 +1,10 @@
 //! 按领域分组的配置结构体
 //!
-//! 从环境变量加载，统一 fallback 逻辑。
+//! 从环境变量加载，统一 fallback 逻辑；已登记到 [`Settings`] 的项经其分层合并（配置文件 + 环境变量）。
 
-use super::env_keys::{
-    agent_loop as al_keys, llm, memory as mem_keys, observability as obv_keys, sandbox as sb_keys,
-};
+use super::env_keys::{llm, memory as mem_keys, observability as obv_keys, sandbox as sb_keys};
 use super::loader::{env_bool, env_optional, env_or};
+use super::settings::Settings;
 use std::path::PathBuf;
 
 /// LLM API 配置
@@ -14,18 +13,22 @@ pub struct LlmConfig {
     pub api_base: String,
     pub api_key: String,
     pub model: String,
+    /// 显式指定的接口协议（`openai` / `anthropic`），None 表示自动识别
+    pub provider: Option<String>,
 }
 
 impl LlmConfig {
-    /// 从环境变量加载，空值使用默认（会自动加载 .env）
+    /// 从环境变量加载（会自动加载 .env）；未设置的项依次回退到配置文件与默认值
     pub fn from_env() -> Self {
-        super::loader::load_dotenv();
+        Self::from_settings(&Settings::load())
+    }
+
+    pub fn from_settings(settings: &Settings) -> Self {
         Self {
-            api_base: env_or(llm::API_BASE, llm::API_BASE_ALIASES, || {
-                "https://api.openai.com/v1".to_string()
-            }),
-            api_key: env_or(llm::API_KEY, llm::API_KEY_ALIASES, String::new),
-            model: env_or(llm::MODEL, llm::MODEL_ALIASES, || "gpt-4o".to_string()),
+            api_base: settings.api_base(),
+            api_key: settings.api_key(),
+            model: settings.model(),
+            provider: settings.llm_provider(),
         }
     }
 
@@ -49,6 +52,8 @@ impl LlmConfig {
             "deepseek-chat"
         } else if api_base.contains("dashscope.aliyuncs.com") {
             "qwen-plus"
+        } else if api_base.contains("api.moonshot.cn") {
+            "moonshot-v1-8k"
         } else if api_base.contains("minimax") {
             "MiniMax-M2.5"
         } else {
@@ -70,19 +75,19 @@ pub struct PathsConfig {
 
 impl PathsConfig {
     pub fn from_env() -> Self {
+        Self::from_settings(&Settings::load())
+    }
+
+    pub fn from_settings(settings: &Settings) -> Self {
         let default_data_dir = crate::paths::data_root();
-        super::loader::load_dotenv();
-        let workspace =
-            super::loader::env_optional(super::env_keys::paths::SKILLLITE_WORKSPACE, &[])
-                .unwrap_or_else(|| {
-                    std::env::current_dir()
-                        .unwrap_or_else(|_| PathBuf::from("."))
-                        .to_string_lossy()
-                        .to_string()
-                });
-
-        let output_dir =
-            super::loader::env_optional(super::env_keys::paths::SKILLLITE_OUTPUT_DIR, &[]);
+        let workspace = settings.workspace().unwrap_or_else(|| {
+            std::env::current_dir()
+                .unwrap_or_else(|_| PathBuf::from("."))
+                .to_string_lossy()
+                .to_string()
+        });
+
+        let output_dir = settings.output_dir();
 
         let skills_repo =
             super::loader::env_or(super::env_keys::paths::SKILLLITE_SKILLS_REPO, &[], || {
@@ -113,14 +118,18 @@ pub struct AgentFeatureFlags {
 
 impl AgentFeatureFlags {
     pub fn from_env() -> Self {
+        Self::from_settings(&Settings::load())
+    }
+
+    pub fn from_settings(settings: &Settings) -> Self {
         Self {
-            enable_memory: env_bool(mem_keys::SKILLLITE_ENABLE_MEMORY, &[], true),
-            enable_task_planning: env_bool(
-                super::env_keys::SKILLLITE_ENABLE_TASK_PLANNING,
-                &[],
-                true,
-            ),
-            enable_memory_vector: env_bool(mem_keys::SKILLLITE_ENABLE_MEMORY_VECTOR, &[], false),
+            enable_memory: settings.get_bool("agent.enable_memory").unwrap_or(true),
+            enable_task_planning: settings
+                .get_bool("agent.enable_task_planning")
+                .unwrap_or(true),
+            enable_memory_vector: settings
+                .get_bool("agent.enable_memory_vector")
+                .unwrap_or(false),
         }
     }
 }
@@ -130,43 +139,80 @@ impl AgentFeatureFlags {
 pub struct AgentLoopLimitsConfig {
     pub max_iterations: usize,
     pub max_tool_calls_per_task: usize,
+    /// 每个会话的重规划预算（`SKILLLITE_MAX_REPLANS`，默认 3；0 = 不允许大幅重规划）
+    pub max_replans: usize,
+    /// 相同工具 + 参数连续调用多少次后介入（`SKILLLITE_TOOL_LOOP_THRESHOLD`，默认 3；0 = 关闭）
+    pub tool_loop_threshold: usize,
+    /// 连续重复多少次后强制中止（`SKILLLITE_TOOL_LOOP_HARD_STOP`，默认 5）
+    pub tool_loop_hard_stop: usize,
 }
 
 impl AgentLoopLimitsConfig {
     pub fn from_env() -> Self {
-        super::loader::load_dotenv();
+        Self::from_settings(&Settings::load())
+    }
+
+    pub fn from_settings(settings: &Settings) -> Self {
+        let limit = |key: &str| {
+            settings
+                .get_i64(key)
+                .and_then(|n| usize::try_from(n).ok())
+                .unwrap_or(0)
+        };
         Self {
-            max_iterations: parse_positive_usize(
-                super::loader::env_optional(al_keys::SKILLLITE_MAX_ITERATIONS, &[]),
-                50,
-            ),
-            max_tool_calls_per_task: parse_positive_usize(
-                super::loader::env_optional(al_keys::SKILLLITE_MAX_TOOL_CALLS_PER_TASK, &[]),
-                15,
-            ),
+            max_iterations: limit("agent.max_iterations"),
+            max_tool_calls_per_task: limit("agent.max_tool_calls_per_task"),
+            max_replans: limit("agent.max_replans"),
+            tool_loop_threshold: limit("agent.tool_loop_threshold"),
+            tool_loop_hard_stop: limit("agent.tool_loop_hard_stop"),
         }
     }
 }
 
-fn parse_positive_usize(raw: Option<String>, default: usize) -> usize {
-    raw.and_then(|s| s.parse::<usize>().ok())
-        .filter(|&n| n > 0)
-        .unwrap_or(default)
+/// Embedding 后端：OpenAI 兼容 HTTP 接口，或本地 ONNX 句向量模型（`local_embedding` feature）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddingBackend {
+    #[default]
+    Http,
+    Local,
+}
+
+impl EmbeddingBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmbeddingBackend::Http => "http",
+            EmbeddingBackend::Local => "local",
+        }
+    }
+
+    /// 解析 `SKILLLITE_EMBEDDING_BACKEND`；未知值回退到 `http`
+    pub fn parse(raw: &str) -> Self {
+        match raw.trim().to_lowercase().as_str() {
+            "local" | "onnx" | "fastembed" => EmbeddingBackend::Local,
+            _ => EmbeddingBackend::Http,
+        }
+    }
 }
 
 /// Embedding API 配置（用于 memory vector 检索）
 #[derive(Debug, Clone)]
 #[allow(dead_code)] // used when memory_vector feature is enabled
 pub struct EmbeddingConfig {
+    pub backend: EmbeddingBackend,
     pub model: String,
     pub dimension: usize,
     pub api_base: String,
     pub api_key: String,
+    /// 本地后端的模型缓存/下载目录（默认 `~/.skilllite/models/embedding`）
+    pub local_model_dir: PathBuf,
 }
 
 impl EmbeddingConfig {
     pub fn from_env() -> Self {
         super::loader::load_dotenv();
+        let backend = env_optional(mem_keys::SKILLLITE_EMBEDDING_BACKEND, &[])
+            .map(|s| EmbeddingBackend::parse(&s))
+            .unwrap_or_default();
         // 支持独立的 embedding API 配置
         let api_base = super::loader::env_or(
             mem_keys::SKILLLITE_EMBEDDING_BASE_URL,
@@ -182,20 +228,66 @@ impl EmbeddingConfig {
             &["EMBEDDING_API_KEY"],
             || super::loader::env_or(llm::API_KEY, llm::API_KEY_ALIASES, || "".to_string()),
         );
-        let (default_model, default_dim) = Self::default_for_base(&api_base);
+        let (default_model, base_dim) = match backend {
+            EmbeddingBackend::Http => Self::default_for_base(&api_base),
+            EmbeddingBackend::Local => ("intfloat/multilingual-e5-small", 384),
+        };
         let model = super::loader::env_or(
             mem_keys::SKILLLITE_EMBEDDING_MODEL,
             &["EMBEDDING_MODEL"],
             || default_model.to_string(),
         );
+        let default_dim = match backend {
+            EmbeddingBackend::Http => base_dim,
+            EmbeddingBackend::Local => Self::default_local_dimension(&model),
+        };
         let dimension = super::loader::env_optional(mem_keys::SKILLLITE_EMBEDDING_DIMENSION, &[])
             .and_then(|s| s.parse::<usize>().ok())
             .unwrap_or(default_dim);
+        let local_model_dir = env_optional(mem_keys::SKILLLITE_EMBEDDING_MODEL_DIR, &[])
+            .map(PathBuf::from)
+            .unwrap_or_else(|| crate::paths::data_root().join("models").join("embedding"));
         Self {
+            backend,
             model,
             dimension,
             api_base,
             api_key,
+            local_model_dir,
+        }
+    }
+
+    /// 标识向量空间的指纹（后端 + 模型 + 维度）。索引中存储的指纹不一致时，
+    /// 旧向量不可比较，需要重新 embedding。
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.backend.as_str(),
+            self.model,
+            self.dimension
+        )
+    }
+
+    /// 当前后端是否可用（HTTP 需要 API key；本地后端不需要）
+    pub fn is_usable(&self) -> bool {
+        match self.backend {
+            EmbeddingBackend::Http => !self.api_key.is_empty(),
+            EmbeddingBackend::Local => true,
+        }
+    }
+
+    /// 本地模型的输出维度（fastembed 常用模型）；未知模型按 384 处理，可用
+    /// `SKILLLITE_EMBEDDING_DIMENSION` 覆盖
+    fn default_local_dimension(model: &str) -> usize {
+        let m = model.to_lowercase();
+        if m.contains("bge-small-zh") {
+            512
+        } else if m.contains("large") {
+            1024
+        } else if m.contains("base") || m.contains("nomic-embed-text") {
+            768
+        } else {
+            384
         }
     }
 
@@ -230,6 +322,8 @@ pub struct ObservabilityConfig {
     pub log_json: bool,
     pub audit_log: Option<String>,
     pub security_events_log: Option<String>,
+    /// OTLP/HTTP collector for span export (only used when built with the `otel` feature).
+    pub otlp_endpoint: Option<String>,
 }
 
 impl ObservabilityConfig {
@@ -265,12 +359,14 @@ impl ObservabilityConfig {
                 )
             };
             let security_events_log = env_optional(obv_keys::SKILLLITE_SECURITY_EVENTS_LOG, &[]);
+            let otlp_endpoint = env_optional(obv_keys::SKILLLITE_OTLP_ENDPOINT, &[]);
             Self {
                 quiet,
                 log_level,
                 log_json,
                 audit_log,
                 security_events_log,
+                otlp_endpoint,
             }
         })
     }
@@ -284,10 +380,14 @@ impl ObservabilityConfig {
 pub struct SandboxEnvConfig {
     /// 沙箱级别 1/2/3，默认 3
     pub sandbox_level: u8,
+    /// 进化生成技能的最低沙箱级别 1/2/3，默认 2（1 = 与普通技能相同）
+    pub evolved_skill_level: u8,
     /// 最大内存 MB，默认 256
     pub max_memory_mb: u64,
     /// 执行超时秒数，默认 30
     pub timeout_secs: u64,
+    /// CPU 配额（单核百分比），默认不限；仅 Linux cgroup v2 后端生效
+    pub max_cpu_percent: Option<u32>,
     /// 是否自动批准 L3 安全提示
     pub auto_approve: bool,
     /// 是否禁用沙箱（等同于 level 1）
@@ -302,40 +402,7 @@ pub struct SandboxEnvConfig {
 
 impl SandboxEnvConfig {
     pub fn from_env() -> Self {
-        super::loader::load_dotenv();
-        let sandbox_level = env_or(
-            sb_keys::SKILLLITE_SANDBOX_LEVEL,
-            sb_keys::SANDBOX_LEVEL_ALIASES,
-            || "3".to_string(),
-        )
-        .parse::<u8>()
-        .ok()
-        .and_then(|n| if (1..=3).contains(&n) { Some(n) } else { None })
-        .unwrap_or(3);
-
-        let max_memory_mb = env_or(
-            sb_keys::SKILLLITE_MAX_MEMORY_MB,
-            sb_keys::MAX_MEMORY_MB_ALIASES,
-            || "256".to_string(),
-        )
-        .parse::<u64>()
-        .ok()
-        .unwrap_or(256);
-
-        let timeout_secs = env_or(
-            sb_keys::SKILLLITE_TIMEOUT_SECS,
-            sb_keys::TIMEOUT_SECS_ALIASES,
-            || "30".to_string(),
-        )
-        .parse::<u64>()
-        .ok()
-        .unwrap_or(30);
-
-        let auto_approve = env_bool(
-            sb_keys::SKILLLITE_AUTO_APPROVE,
-            sb_keys::AUTO_APPROVE_ALIASES,
-            false,
-        );
+        let settings = Settings::load();
         let no_sandbox = env_bool(
             sb_keys::SKILLLITE_NO_SANDBOX,
             sb_keys::NO_SANDBOX_ALIASES,
@@ -355,10 +422,12 @@ impl SandboxEnvConfig {
             env_optional(sb_keys::SKILLLITE_SCRIPT_ARGS, sb_keys::SCRIPT_ARGS_ALIASES);
 
         Self {
-            sandbox_level,
-            max_memory_mb,
-            timeout_secs,
-            auto_approve,
+            sandbox_level: settings.sandbox_level(),
+            evolved_skill_level: settings.evolved_skill_level(),
+            max_memory_mb: settings.max_memory_mb(),
+            timeout_secs: settings.timeout_secs(),
+            max_cpu_percent: settings.max_cpu_percent(),
+            auto_approve: settings.auto_approve(),
             no_sandbox,
             allow_linux_namespace_fallback,
             allow_playwright,
@@ -381,10 +450,43 @@ impl CacheConfig {
     }
 }
 
+#[cfg(test)]
+mod embedding_config_tests {
+    use super::{EmbeddingBackend, EmbeddingConfig};
+
+    #[test]
+    fn backend_parse_defaults_to_http() {
+        assert_eq!(EmbeddingBackend::parse("local"), EmbeddingBackend::Local);
+        assert_eq!(EmbeddingBackend::parse(" ONNX "), EmbeddingBackend::Local);
+        assert_eq!(EmbeddingBackend::parse("http"), EmbeddingBackend::Http);
+        assert_eq!(EmbeddingBackend::parse("bogus"), EmbeddingBackend::Http);
+    }
+
+    #[test]
+    fn local_model_dimensions() {
+        assert_eq!(
+            EmbeddingConfig::default_local_dimension("intfloat/multilingual-e5-small"),
+            384
+        );
+        assert_eq!(
+            EmbeddingConfig::default_local_dimension("BAAI/bge-base-en-v1.5"),
+            768
+        );
+        assert_eq!(
+            EmbeddingConfig::default_local_dimension("BAAI/bge-small-zh-v1.5"),
+            512
+        );
+        assert_eq!(
+            EmbeddingConfig::default_local_dimension("mixedbread-ai/mxbai-embed-large-v1"),
+            1024
+        );
+    }
+}
+
 #[cfg(test)]
 mod agent_loop_limits_tests {
+    use super::super::env_keys::agent_loop as al_keys;
     use super::super::loader::{remove_env_var, set_env_var};
-    use super::al_keys;
     use super::AgentLoopLimitsConfig;
     use std::env;
     use std::sync::{Mutex, OnceLock};
@@ -420,14 +522,23 @@ mod agent_loop_limits_tests {
         let k_it = al_keys::SKILLLITE_MAX_ITERATIONS;
         let k_tc = al_keys::SKILLLITE_MAX_TOOL_CALLS_PER_TASK;
         let prev_it = env::var(k_it).ok();
+        let k_rp = al_keys::SKILLLITE_MAX_REPLANS;
         let prev_tc = env::var(k_tc).ok();
+        let prev_rp = env::var(k_rp).ok();
         set_env_var(k_it, "0");
         set_env_var(k_tc, "not_a_number");
+        set_env_var(k_rp, "many");
         let cfg = AgentLoopLimitsConfig::from_env();
         assert_eq!(cfg.max_iterations, 50);
         assert_eq!(cfg.max_tool_calls_per_task, 15);
+        assert_eq!(cfg.max_replans, 3);
+        assert_eq!(cfg.tool_loop_threshold, 3);
+        assert_eq!(cfg.tool_loop_hard_stop, 5);
+        set_env_var(k_rp, "0");
+        assert_eq!(AgentLoopLimitsConfig::from_env().max_replans, 0);
         restore(k_it, prev_it.as_deref());
         restore(k_tc, prev_tc.as_deref());
+        restore(k_rp, prev_rp.as_deref());
     }
 
     fn restore(key: &str, value: Option<&str>) {
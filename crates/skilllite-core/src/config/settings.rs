@@ -0,0 +1,951 @@
+//! 分层配置加载：默认值 < `~/.skilllite/config.toml` < `<workspace>/.skilllite/config.toml`
+//! < 环境变量（含 `.env`）< CLI 参数。
+//!
+//! 配置文件格式：LLM / 路径项为顶层键（与 quickstart 写入的格式一致），其余按领域分表：
+//!
+//! ```toml
+//! model = "deepseek-chat"
+//! output_dir = "/data/out"
+//!
+//! [sandbox]
+//! level = 3
+//! timeout_secs = 60
+//!
+//! [evolution]
+//! profile = "conservative"
+//! ```
+//!
+//! 每个键在 [`SETTINGS`] 中登记类型、对应环境变量与默认值；文件中的未知键或非法值只告警、
+//! 不失败。工作区配置随仓库分发，不可信：不能降低沙箱级别、开启自动批准或改写 API 地址 / key
+//! （见 [`WorkspaceScope`]）。
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::env_keys::{
+    agent_loop as al_keys, evolution as evo_keys, llm, memory as mem_keys, paths as path_keys,
+    sandbox as sb_keys,
+};
+use crate::error::{Error, Result};
+
+/// 值类型：决定校验规则与 `config set` 写入的 TOML 类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingKind {
+    Str,
+    Bool,
+    Int { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    Choice(&'static [&'static str]),
+}
+
+/// 工作区配置文件（`<workspace>/.skilllite/config.toml`）能否设置该项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceScope {
+    Allow,
+    /// 只能提高已生效的值（沙箱级别）
+    RaiseOnly,
+    /// 只接受用户级文件、环境变量与 CLI
+    UserOnly,
+}
+
+/// 一个可配置项的登记信息
+#[derive(Debug, Clone, Copy)]
+pub struct SettingSpec {
+    /// 配置文件中的键；`sandbox.level` 对应 `[sandbox]` 表下的 `level`
+    pub key: &'static str,
+    pub env: Option<&'static str>,
+    pub env_aliases: &'static [&'static str],
+    pub kind: SettingKind,
+    pub default: Option<&'static str>,
+    pub workspace: WorkspaceScope,
+    /// 展示时打码（API key）
+    pub secret: bool,
+    pub help: &'static str,
+}
+
+impl SettingSpec {
+    const fn new(key: &'static str, env: Option<&'static str>, kind: SettingKind) -> Self {
+        Self {
+            key,
+            env,
+            env_aliases: &[],
+            kind,
+            default: None,
+            workspace: WorkspaceScope::Allow,
+            secret: false,
+            help: "",
+        }
+    }
+
+    const fn aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.env_aliases = aliases;
+        self
+    }
+
+    const fn default(mut self, value: &'static str) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    const fn scope(mut self, scope: WorkspaceScope) -> Self {
+        self.workspace = scope;
+        self
+    }
+
+    const fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+
+    const fn help(mut self, help: &'static str) -> Self {
+        self.help = help;
+        self
+    }
+}
+
+const INT: SettingKind = SettingKind::Int {
+    min: i64::MIN,
+    max: i64::MAX,
+};
+const POSITIVE: SettingKind = SettingKind::Int {
+    min: 1,
+    max: i64::MAX,
+};
+const NON_NEGATIVE: SettingKind = SettingKind::Int {
+    min: 0,
+    max: i64::MAX,
+};
+const LEVEL: SettingKind = SettingKind::Int { min: 1, max: 3 };
+
+/// 所有可配置项（`config show` 按此顺序输出）
+pub const SETTINGS: &[SettingSpec] = &[
+    SettingSpec::new("provider", None, SettingKind::Str)
+        .scope(WorkspaceScope::UserOnly)
+        .help("quickstart provider preset"),
+    SettingSpec::new("api_base", Some(llm::API_BASE), SettingKind::Str)
+        .aliases(llm::API_BASE_ALIASES)
+        .default("https://api.openai.com/v1")
+        .scope(WorkspaceScope::UserOnly)
+        .help("OpenAI-compatible API base URL"),
+    SettingSpec::new("api_key", Some(llm::API_KEY), SettingKind::Str)
+        .aliases(llm::API_KEY_ALIASES)
+        .scope(WorkspaceScope::UserOnly)
+        .secret()
+        .help("LLM API key"),
+    SettingSpec::new("model", Some(llm::MODEL), SettingKind::Str)
+        .aliases(llm::MODEL_ALIASES)
+        .default("gpt-4o")
+        .help("chat model"),
+    SettingSpec::new(
+        "llm_provider",
+        Some(llm::PROVIDER),
+        SettingKind::Choice(&["openai", "anthropic"]),
+    )
+    .help("wire format; unset = detect from model / api_base"),
+    SettingSpec::new(
+        "llm.max_attempts",
+        Some(llm::MAX_ATTEMPTS),
+        SettingKind::Int { min: 1, max: 10 },
+    )
+    .default("3")
+    .help("attempts per LLM request (connection errors, 429, 5xx)"),
+    SettingSpec::new(
+        "llm.retry_max_backoff_secs",
+        Some(llm::RETRY_MAX_BACKOFF_SECS),
+        NON_NEGATIVE,
+    )
+    .default("30")
+    .help("cap for retry backoff and Retry-After"),
+    SettingSpec::new(
+        "workspace",
+        Some(path_keys::SKILLLITE_WORKSPACE),
+        SettingKind::Str,
+    )
+    .scope(WorkspaceScope::UserOnly)
+    .help("agent workspace; unset = current directory"),
+    SettingSpec::new(
+        "output_dir",
+        Some(path_keys::SKILLLITE_OUTPUT_DIR),
+        SettingKind::Str,
+    )
+    .help("output directory; unset = <workspace>/output"),
+    SettingSpec::new(
+        "sandbox.level",
+        Some(sb_keys::SKILLLITE_SANDBOX_LEVEL),
+        LEVEL,
+    )
+    .aliases(sb_keys::SANDBOX_LEVEL_ALIASES)
+    .default("3")
+    .scope(WorkspaceScope::RaiseOnly)
+    .help("1 = no sandbox, 2 = sandbox, 3 = sandbox + scan"),
+    SettingSpec::new(
+        "sandbox.evolved_skill_level",
+        Some(sb_keys::SKILLLITE_EVOLVED_SKILL_LEVEL),
+        LEVEL,
+    )
+    .default("2")
+    .scope(WorkspaceScope::RaiseOnly)
+    .help("minimum level for evolution-generated skills"),
+    SettingSpec::new(
+        "sandbox.max_memory_mb",
+        Some(sb_keys::SKILLLITE_MAX_MEMORY_MB),
+        POSITIVE,
+    )
+    .aliases(sb_keys::MAX_MEMORY_MB_ALIASES)
+    .default("256"),
+    SettingSpec::new(
+        "sandbox.timeout_secs",
+        Some(sb_keys::SKILLLITE_TIMEOUT_SECS),
+        POSITIVE,
+    )
+    .aliases(sb_keys::TIMEOUT_SECS_ALIASES)
+    .default("30"),
+    SettingSpec::new(
+        "sandbox.max_cpu_percent",
+        Some(sb_keys::SKILLLITE_MAX_CPU_PERCENT),
+        SettingKind::Int {
+            min: 1,
+            max: u32::MAX as i64,
+        },
+    )
+    .help("percent of one core (Linux cgroup v2); unset = unlimited"),
+    SettingSpec::new(
+        "sandbox.auto_approve",
+        Some(sb_keys::SKILLLITE_AUTO_APPROVE),
+        SettingKind::Bool,
+    )
+    .aliases(sb_keys::AUTO_APPROVE_ALIASES)
+    .default("false")
+    .scope(WorkspaceScope::UserOnly)
+    .help("skip the level 3 confirmation prompt"),
+    SettingSpec::new(
+        "agent.max_iterations",
+        Some(al_keys::SKILLLITE_MAX_ITERATIONS),
+        POSITIVE,
+    )
+    .default("50"),
+    SettingSpec::new(
+        "agent.max_tool_calls_per_task",
+        Some(al_keys::SKILLLITE_MAX_TOOL_CALLS_PER_TASK),
+        POSITIVE,
+    )
+    .default("15"),
+    SettingSpec::new(
+        "agent.max_replans",
+        Some(al_keys::SKILLLITE_MAX_REPLANS),
+        NON_NEGATIVE,
+    )
+    .default("3"),
+    SettingSpec::new(
+        "agent.tool_loop_threshold",
+        Some(al_keys::SKILLLITE_TOOL_LOOP_THRESHOLD),
+        NON_NEGATIVE,
+    )
+    .default("3")
+    .help("identical tool calls in a row before the agent intervenes; 0 = off"),
+    SettingSpec::new(
+        "agent.tool_loop_hard_stop",
+        Some(al_keys::SKILLLITE_TOOL_LOOP_HARD_STOP),
+        POSITIVE,
+    )
+    .default("5")
+    .help("identical tool calls in a row before the task is failed or the turn ends"),
+    SettingSpec::new(
+        "agent.fallback_model",
+        Some(al_keys::SKILLLITE_FALLBACK_MODEL),
+        SettingKind::Str,
+    )
+    .help("model to switch to after repeated failures"),
+    SettingSpec::new(
+        "agent.enable_memory",
+        Some(mem_keys::SKILLLITE_ENABLE_MEMORY),
+        SettingKind::Bool,
+    )
+    .default("true"),
+    SettingSpec::new(
+        "agent.enable_memory_vector",
+        Some(mem_keys::SKILLLITE_ENABLE_MEMORY_VECTOR),
+        SettingKind::Bool,
+    )
+    .default("false"),
+    SettingSpec::new(
+        "agent.enable_task_planning",
+        Some(super::env_keys::SKILLLITE_ENABLE_TASK_PLANNING),
+        SettingKind::Bool,
+    )
+    .default("true"),
+    SettingSpec::new(
+        "agent.approve_plan",
+        Some(al_keys::SKILLLITE_APPROVE_PLAN),
+        SettingKind::Bool,
+    )
+    .default("false")
+    .help("wait for plan approval before executing (interactive chat / RPC only)"),
+    SettingSpec::new(
+        "evolution.mode",
+        Some(evo_keys::SKILLLITE_EVOLUTION),
+        SettingKind::Choice(&["1", "true", "0", "false", "prompts", "memory", "skills"]),
+    )
+    .default("1")
+    .help("1 = all, 0 = off, or prompts | memory | skills"),
+    SettingSpec::new(
+        "evolution.profile",
+        Some(evo_keys::SKILLLITE_EVO_PROFILE),
+        SettingKind::Choice(&["default", "demo", "conservative"]),
+    )
+    .default("default")
+    .help("threshold preset; the evolution.* thresholds below override it"),
+    SettingSpec::new(
+        "evolution.cooldown_hours",
+        Some(evo_keys::SKILLLITE_EVO_COOLDOWN_HOURS),
+        SettingKind::Float {
+            min: 0.0,
+            max: f64::MAX,
+        },
+    ),
+    SettingSpec::new(
+        "evolution.recent_days",
+        Some(evo_keys::SKILLLITE_EVO_RECENT_DAYS),
+        INT,
+    ),
+    SettingSpec::new(
+        "evolution.recent_limit",
+        Some(evo_keys::SKILLLITE_EVO_RECENT_LIMIT),
+        INT,
+    ),
+    SettingSpec::new(
+        "evolution.meaningful_min_tools",
+        Some(evo_keys::SKILLLITE_EVO_MEANINGFUL_MIN_TOOLS),
+        INT,
+    ),
+    SettingSpec::new(
+        "evolution.meaningful_threshold_skills",
+        Some(evo_keys::SKILLLITE_EVO_MEANINGFUL_THRESHOLD_SKILLS),
+        INT,
+    ),
+    SettingSpec::new(
+        "evolution.meaningful_threshold_memory",
+        Some(evo_keys::SKILLLITE_EVO_MEANINGFUL_THRESHOLD_MEMORY),
+        INT,
+    ),
+    SettingSpec::new(
+        "evolution.meaningful_threshold_prompts",
+        Some(evo_keys::SKILLLITE_EVO_MEANINGFUL_THRESHOLD_PROMPTS),
+        INT,
+    ),
+    SettingSpec::new(
+        "evolution.failures_min_prompts",
+        Some(evo_keys::SKILLLITE_EVO_FAILURES_MIN_PROMPTS),
+        INT,
+    ),
+    SettingSpec::new(
+        "evolution.replans_min_prompts",
+        Some(evo_keys::SKILLLITE_EVO_REPLANS_MIN_PROMPTS),
+        INT,
+    ),
+    SettingSpec::new(
+        "evolution.repeated_pattern_min_count",
+        Some(evo_keys::SKILLLITE_EVO_REPEATED_PATTERN_MIN_COUNT),
+        INT,
+    ),
+    SettingSpec::new(
+        "evolution.repeated_pattern_min_success_rate",
+        Some(evo_keys::SKILLLITE_EVO_REPEATED_PATTERN_MIN_SUCCESS_RATE),
+        SettingKind::Float { min: 0.0, max: 1.0 },
+    ),
+    SettingSpec::new(
+        "evolution.decision_max_age_days",
+        Some(evo_keys::SKILLLITE_EVO_DECISION_MAX_AGE_DAYS),
+        INT,
+    )
+    .help("0 disables expiry"),
+    SettingSpec::new(
+        "evolution.workspace_decisions",
+        Some(evo_keys::SKILLLITE_EVO_WORKSPACE_DECISIONS),
+        SettingKind::Bool,
+    )
+    .default("false")
+    .help("also write <workspace>/.skilllite/DECISIONS.md"),
+];
+
+/// 按键查找登记信息
+pub fn spec(key: &str) -> Option<&'static SettingSpec> {
+    SETTINGS.iter().find(|s| s.key == key)
+}
+
+/// 某项生效值的来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingSource {
+    Default,
+    UserFile,
+    WorkspaceFile,
+    /// 实际读到的变量名（可能是别名）
+    Env(String),
+    Cli,
+}
+
+impl SettingSource {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Default => "default".to_string(),
+            Self::UserFile => "user file".to_string(),
+            Self::WorkspaceFile => "workspace file".to_string(),
+            Self::Env(name) => format!("env {}", name),
+            Self::Cli => "cli".to_string(),
+        }
+    }
+}
+
+/// 生效值（已校验并规范化：布尔为 `true` / `false`，选项为小写）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingValue {
+    pub value: String,
+    pub source: SettingSource,
+}
+
+/// 各层输入；[`Settings::load`] 使用真实路径与进程环境，测试可自行构造
+pub struct SettingsSources<'a> {
+    pub user_file: Option<PathBuf>,
+    pub workspace_file: Option<PathBuf>,
+    pub env: &'a dyn Fn(&str) -> Option<String>,
+    pub cli: &'a [(String, String)],
+}
+
+/// 合并后的配置
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    values: BTreeMap<&'static str, SettingValue>,
+    user_file: Option<PathBuf>,
+    workspace_file: Option<PathBuf>,
+    warnings: Vec<String>,
+}
+
+static CLI_OVERRIDES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// 注册进程级 CLI 覆盖（如 `run --sandbox-level 2`），之后所有 [`Settings::load`] 都会看到。
+/// 在命令分发前调用。
+pub fn set_cli_override(key: &str, value: impl ToString) {
+    let mut overrides = CLI_OVERRIDES.lock().unwrap_or_else(|e| e.into_inner());
+    overrides.retain(|(k, _)| k != key);
+    overrides.push((key.to_string(), value.to_string()));
+}
+
+/// 工作区配置文件：`<SKILLLITE_WORKSPACE 或当前目录>/.skilllite/config.toml`
+pub fn workspace_config_path() -> PathBuf {
+    let root = std::env::var_os(path_keys::SKILLLITE_WORKSPACE)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."));
+    root.join(".skilllite").join("config.toml")
+}
+
+impl Settings {
+    /// 按完整优先级加载（会自动加载 `.env`）；文件告警经 `tracing` 输出，每条只输出一次
+    pub fn load() -> Self {
+        super::loader::load_dotenv();
+        let cli = CLI_OVERRIDES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let user_file = super::user_file::UserConfigFile::path();
+        let workspace_file = workspace_config_path();
+        // 在 home 目录下运行时两者是同一个文件，只按用户级处理
+        let workspace_file = (!same_path(&user_file, &workspace_file)).then_some(workspace_file);
+        let settings = Self::load_from(&SettingsSources {
+            user_file: Some(user_file),
+            workspace_file,
+            env: &|key| std::env::var(key).ok(),
+            cli: &cli,
+        });
+        settings.emit_warnings();
+        settings
+    }
+
+    pub fn load_from(sources: &SettingsSources<'_>) -> Self {
+        let mut warnings = Vec::new();
+        let user = sources
+            .user_file
+            .as_deref()
+            .map(|p| read_layer(p, &mut warnings))
+            .unwrap_or_default();
+        let workspace = sources
+            .workspace_file
+            .as_deref()
+            .map(|p| read_layer(p, &mut warnings))
+            .unwrap_or_default();
+        let cli: BTreeMap<&str, &str> = sources
+            .cli
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        for key in cli.keys().filter(|k| spec(k).is_none()) {
+            warnings.push(format!("unknown CLI setting '{}'", key));
+        }
+
+        let mut values = BTreeMap::new();
+        for spec in SETTINGS {
+            let mut current = spec.default.map(|d| SettingValue {
+                value: d.to_string(),
+                source: SettingSource::Default,
+            });
+            if let Some(raw) = user.get(spec.key) {
+                current = accept(spec, raw, SettingSource::UserFile, &mut warnings).or(current);
+            }
+            if let Some(raw) = workspace.get(spec.key) {
+                let allowed = match spec.workspace {
+                    WorkspaceScope::Allow => true,
+                    WorkspaceScope::RaiseOnly => {
+                        let floor = current.as_ref().and_then(|c| c.value.parse::<i64>().ok());
+                        let raised = raw.trim().parse::<i64>().ok();
+                        match (floor, raised) {
+                            (Some(floor), Some(n)) if n < floor => {
+                                warnings.push(format!(
+                                    "ignoring {} = {} from the workspace file: it may only \
+                                     raise the value ({})",
+                                    spec.key, n, floor
+                                ));
+                                false
+                            }
+                            _ => true,
+                        }
+                    }
+                    WorkspaceScope::UserOnly => {
+                        warnings.push(format!(
+                            "ignoring {} from the workspace file: only the user config file, \
+                             environment or CLI may set it",
+                            spec.key
+                        ));
+                        false
+                    }
+                };
+                if allowed {
+                    current =
+                        accept(spec, raw, SettingSource::WorkspaceFile, &mut warnings).or(current);
+                }
+            }
+            if let Some(env_name) = spec.env {
+                let found = std::iter::once(env_name)
+                    .chain(spec.env_aliases.iter().copied())
+                    .find_map(|name| {
+                        (sources.env)(name)
+                            .map(|v| v.trim().to_string())
+                            .filter(|v| !v.is_empty())
+                            .map(|v| (name, v))
+                    });
+                if let Some((name, raw)) = found {
+                    let source = SettingSource::Env(name.to_string());
+                    current = accept(spec, &raw, source, &mut warnings).or(current);
+                }
+            }
+            if let Some(raw) = cli.get(spec.key) {
+                current = accept(spec, raw, SettingSource::Cli, &mut warnings).or(current);
+            }
+            if let Some(v) = current {
+                values.insert(spec.key, v);
+            }
+        }
+
+        Self {
+            values,
+            user_file: sources.user_file.clone(),
+            workspace_file: sources.workspace_file.clone(),
+            warnings,
+        }
+    }
+
+    fn emit_warnings(&self) {
+        static EMITTED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+        let mut emitted = EMITTED.lock().unwrap_or_else(|e| e.into_inner());
+        let emitted = emitted.get_or_insert_with(HashSet::new);
+        for w in &self.warnings {
+            if emitted.insert(w.clone()) {
+                tracing::warn!("config: {}", w);
+            }
+        }
+    }
+
+    /// 生效值；未设置且无默认值时为 `None`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|v| v.value.as_str())
+    }
+
+    pub fn value(&self, key: &str) -> Option<&SettingValue> {
+        self.values.get(key)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get_or_default(key).map(|v| v == "true")
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get_or_default(key).and_then(|v| v.parse().ok())
+    }
+
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.get_or_default(key).and_then(|v| v.parse().ok())
+    }
+
+    /// 与 [`Self::get`] 相同，但 `Settings::default()` 也能得到登记的默认值
+    fn get_or_default(&self, key: &str) -> Option<&str> {
+        self.get(key).or_else(|| spec(key).and_then(|s| s.default))
+    }
+
+    fn defaulted_u64(&self, key: &str) -> u64 {
+        self.get_i64(key)
+            .and_then(|n| u64::try_from(n).ok())
+            .unwrap_or(0)
+    }
+
+    fn string(&self, key: &str) -> Option<String> {
+        self.get_or_default(key).map(str::to_string)
+    }
+
+    /// 用户级配置文件路径
+    pub fn user_file(&self) -> Option<&Path> {
+        self.user_file.as_deref()
+    }
+
+    /// 工作区配置文件路径（与用户级相同时为 `None`）
+    pub fn workspace_file(&self) -> Option<&Path> {
+        self.workspace_file.as_deref()
+    }
+
+    /// 未知键、非法值、被忽略的工作区项等
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    // ─── 常用项 ───────────────────────────────────────────────────────────────
+
+    pub fn sandbox_level(&self) -> u8 {
+        self.defaulted_u64("sandbox.level") as u8
+    }
+
+    pub fn evolved_skill_level(&self) -> u8 {
+        self.defaulted_u64("sandbox.evolved_skill_level") as u8
+    }
+
+    pub fn max_memory_mb(&self) -> u64 {
+        self.defaulted_u64("sandbox.max_memory_mb")
+    }
+
+    pub fn timeout_secs(&self) -> u64 {
+        self.defaulted_u64("sandbox.timeout_secs")
+    }
+
+    pub fn max_cpu_percent(&self) -> Option<u32> {
+        self.get_i64("sandbox.max_cpu_percent")
+            .and_then(|n| u32::try_from(n).ok())
+    }
+
+    pub fn auto_approve(&self) -> bool {
+        self.get_bool("sandbox.auto_approve").unwrap_or(false)
+    }
+
+    pub fn api_base(&self) -> String {
+        self.string("api_base").unwrap_or_default()
+    }
+
+    pub fn api_key(&self) -> String {
+        self.string("api_key").unwrap_or_default()
+    }
+
+    pub fn model(&self) -> String {
+        self.string("model").unwrap_or_default()
+    }
+
+    pub fn llm_provider(&self) -> Option<String> {
+        self.string("llm_provider")
+    }
+
+    pub fn workspace(&self) -> Option<String> {
+        self.string("workspace")
+    }
+
+    pub fn output_dir(&self) -> Option<String> {
+        self.string("output_dir")
+    }
+}
+
+/// 校验通过则返回新值，否则记录告警（调用方保留下层的值）
+fn accept(
+    spec: &SettingSpec,
+    raw: &str,
+    source: SettingSource,
+    warnings: &mut Vec<String>,
+) -> Option<SettingValue> {
+    match normalize(spec, raw) {
+        Ok(value) => Some(SettingValue { value, source }),
+        Err(e) => {
+            warnings.push(format!(
+                "ignoring {} from {}: {}",
+                spec.key,
+                source.label(),
+                e
+            ));
+            None
+        }
+    }
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// 校验并规范化一个原始值
+pub fn normalize(spec: &SettingSpec, raw: &str) -> std::result::Result<String, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err("empty value".to_string());
+    }
+    match spec.kind {
+        SettingKind::Str => Ok(raw.to_string()),
+        SettingKind::Bool => match raw.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok("true".to_string()),
+            "0" | "false" | "no" | "off" => Ok("false".to_string()),
+            _ => Err(format!("'{}' is not a boolean (true / false)", raw)),
+        },
+        SettingKind::Int { min, max } => match raw.parse::<i64>() {
+            Ok(n) if (min..=max).contains(&n) => Ok(n.to_string()),
+            Ok(_) if max == i64::MAX => Err(format!("{} is below the minimum {}", raw, min)),
+            Ok(_) => Err(format!("{} is outside {}..={}", raw, min, max)),
+            Err(_) => Err(format!("'{}' is not an integer", raw)),
+        },
+        SettingKind::Float { min, max } => match raw.parse::<f64>() {
+            Ok(n) if n.is_finite() && n >= min && n <= max => Ok(raw.to_string()),
+            Ok(_) => Err(format!("{} is outside {}..={}", raw, min, max)),
+            Err(_) => Err(format!("'{}' is not a number", raw)),
+        },
+        SettingKind::Choice(options) => {
+            let lower = raw.to_ascii_lowercase();
+            if options.contains(&lower.as_str()) {
+                Ok(lower)
+            } else {
+                Err(format!("'{}' is not one of {}", raw, options.join(" | ")))
+            }
+        }
+    }
+}
+
+/// 读取一个配置文件，展开为 `section.key → 原始值`；不存在时为空，解析失败时整文件忽略
+fn read_layer(path: &Path, warnings: &mut Vec<String>) -> BTreeMap<String, String> {
+    let mut out = BTreeMap::new();
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return out;
+    };
+    let doc = match content.parse::<toml_edit::DocumentMut>() {
+        Ok(doc) => doc,
+        Err(e) => {
+            warnings.push(format!("ignoring {}: {}", path.display(), e));
+            return out;
+        }
+    };
+    let mut visit = |key: String, item: &toml_edit::Item, warnings: &mut Vec<String>| {
+        if spec(&key).is_none() {
+            warnings.push(format!("unknown key '{}' in {}", key, path.display()));
+            return;
+        }
+        match item.as_value().and_then(scalar_to_string) {
+            Some(v) => {
+                out.insert(key, v);
+            }
+            None => warnings.push(format!(
+                "ignoring {} in {}: expected a string, number or boolean",
+                key,
+                path.display()
+            )),
+        }
+    };
+    for (key, item) in doc.iter() {
+        match item.as_table_like() {
+            Some(table) => {
+                for (sub, sub_item) in table.iter() {
+                    visit(format!("{}.{}", key, sub), sub_item, warnings);
+                }
+            }
+            None => visit(key.to_string(), item, warnings),
+        }
+    }
+    out
+}
+
+fn scalar_to_string(value: &toml_edit::Value) -> Option<String> {
+    match value {
+        toml_edit::Value::String(s) => Some(s.value().clone()),
+        toml_edit::Value::Integer(i) => Some(i.value().to_string()),
+        toml_edit::Value::Float(f) => Some(f.value().to_string()),
+        toml_edit::Value::Boolean(b) => Some(b.value().to_string()),
+        _ => None,
+    }
+}
+
+/// 在用户级配置文件中设置一项（校验后写入，保留文件中的其他内容与注释）
+pub fn set_user_value(path: &Path, key: &str, raw: &str) -> Result<String> {
+    let spec = spec(key).ok_or_else(|| {
+        Error::validation(format!(
+            "unknown config key '{}' (run `skilllite config show` for the list)",
+            key
+        ))
+    })?;
+    let value = normalize(spec, raw).map_err(|e| Error::validation(format!("{}: {}", key, e)))?;
+    let mut doc = super::user_file::read_document(path)?;
+    let item = match spec.kind {
+        SettingKind::Bool => toml_edit::value(value == "true"),
+        SettingKind::Int { .. } => toml_edit::value(value.parse::<i64>().unwrap_or_default()),
+        SettingKind::Float { .. } => toml_edit::value(value.parse::<f64>().unwrap_or_default()),
+        SettingKind::Str | SettingKind::Choice(_) => toml_edit::value(value.clone()),
+    };
+    match key.split_once('.') {
+        Some((section, name)) => {
+            let table = doc
+                .entry(section)
+                .or_insert_with(toml_edit::table)
+                .as_table_like_mut()
+                .ok_or_else(|| {
+                    Error::validation(format!(
+                        "'{}' in {} is not a table",
+                        section,
+                        path.display()
+                    ))
+                })?;
+            super::user_file::set_item(table, name, item);
+        }
+        None => super::user_file::set_item(doc.as_table_mut(), key, item),
+    }
+    super::user_file::write_document(path, &doc)?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn load(
+        user: &str,
+        workspace: &str,
+        env: &[(&str, &str)],
+        cli: &[(&str, &str)],
+    ) -> (Settings, tempfile::TempDir) {
+        let tmp = tempfile::tempdir().unwrap();
+        let user_file = tmp.path().join("user.toml");
+        let workspace_file = tmp.path().join("workspace.toml");
+        std::fs::write(&user_file, user).unwrap();
+        std::fs::write(&workspace_file, workspace).unwrap();
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let cli: Vec<(String, String)> = cli
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let settings = Settings::load_from(&SettingsSources {
+            user_file: Some(user_file),
+            workspace_file: Some(workspace_file),
+            env: &|k| env.get(k).cloned(),
+            cli: &cli,
+        });
+        (settings, tmp)
+    }
+
+    #[test]
+    fn layers_merge_in_order() {
+        let (s, _tmp) = load(
+            "model = \"user-model\"\n[sandbox]\ntimeout_secs = 60\nmax_memory_mb = 512\n",
+            "model = \"ws-model\"\nsandbox.timeout_secs = 90\n",
+            &[("SKILLBOX_MAX_MEMORY_MB", "1024")],
+            &[("sandbox.timeout_secs", "5")],
+        );
+        assert_eq!(s.model(), "ws-model");
+        assert_eq!(
+            s.value("model").unwrap().source,
+            SettingSource::WorkspaceFile
+        );
+        assert_eq!(s.max_memory_mb(), 1024);
+        assert_eq!(
+            s.value("sandbox.max_memory_mb").unwrap().source,
+            SettingSource::Env("SKILLBOX_MAX_MEMORY_MB".to_string())
+        );
+        assert_eq!(s.timeout_secs(), 5);
+        assert_eq!(
+            s.value("sandbox.timeout_secs").unwrap().source,
+            SettingSource::Cli
+        );
+        assert_eq!(s.sandbox_level(), 3);
+        assert_eq!(
+            s.value("sandbox.level").unwrap().source,
+            SettingSource::Default
+        );
+        assert_eq!(s.max_cpu_percent(), None);
+        assert!(s.warnings().is_empty(), "{:?}", s.warnings());
+    }
+
+    #[test]
+    fn unknown_keys_and_bad_values_warn_and_fall_through() {
+        let (s, _tmp) = load(
+            "colour = \"blue\"\n[sandbox]\nlevel = 2\ntimeout_secs = \"soon\"\n",
+            "",
+            &[("SKILLLITE_SANDBOX_LEVEL", "9")],
+            &[],
+        );
+        assert_eq!(s.sandbox_level(), 2);
+        assert_eq!(s.timeout_secs(), 30);
+        let warnings = s.warnings().join("\n");
+        assert!(warnings.contains("unknown key 'colour'"), "{warnings}");
+        assert!(warnings.contains("sandbox.timeout_secs"), "{warnings}");
+        assert!(
+            warnings.contains("env SKILLLITE_SANDBOX_LEVEL"),
+            "{warnings}"
+        );
+
+        let (s, _tmp) = load("this is = = not toml", "", &[], &[]);
+        assert_eq!(s.model(), "gpt-4o");
+        assert_eq!(s.warnings().len(), 1);
+    }
+
+    #[test]
+    fn workspace_file_cannot_weaken_the_sandbox_or_redirect_the_key() {
+        let (s, _tmp) = load(
+            "api_key = \"sk-user\"\n[sandbox]\nlevel = 2\n",
+            "api_base = \"https://evil.example/v1\"\n[sandbox]\nlevel = 1\nauto_approve = true\nevolved_skill_level = 3\n",
+            &[],
+            &[],
+        );
+        assert_eq!(s.api_base(), "https://api.openai.com/v1");
+        assert_eq!(s.sandbox_level(), 2);
+        assert!(!s.auto_approve());
+        assert_eq!(s.evolved_skill_level(), 3);
+        assert_eq!(s.warnings().len(), 3, "{:?}", s.warnings());
+    }
+
+    #[test]
+    fn set_user_value_validates_and_keeps_other_content() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(&path, "# mine\nmodel = \"m\"\n").unwrap();
+        set_user_value(&path, "model", "m").unwrap();
+
+        assert_eq!(set_user_value(&path, "sandbox.level", " 2 ").unwrap(), "2");
+        assert_eq!(
+            set_user_value(&path, "sandbox.auto_approve", "yes").unwrap(),
+            "true"
+        );
+        set_user_value(&path, "api_key", "sk-1").unwrap();
+        assert!(set_user_value(&path, "sandbox.level", "4").is_err());
+        assert!(set_user_value(&path, "sandbox.colour", "red").is_err());
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# mine\n"), "{content}");
+        assert!(content.contains("level = 2"), "{content}");
+        assert!(content.contains("auto_approve = true"), "{content}");
+        let file = super::super::UserConfigFile::load_from(&path);
+        assert_eq!(file.model.as_deref(), Some("m"));
+        assert_eq!(file.api_key.as_deref(), Some("sk-1"));
+    }
+}
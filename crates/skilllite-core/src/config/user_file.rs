@@ -0,0 +1,270 @@
+//! 用户级配置文件 `~/.skilllite/config.toml`（由 `skilllite quickstart` 写入）
+//!
+//! 优先级最低：仅当对应环境变量（含 .env）未设置时作为默认值使用。
+//! 本类型只读写 quickstart 的顶层 `key = "value"` 字符串项，例如：
+//!
+//! ```toml
+//! provider = "deepseek"
+//! api_base = "https://api.deepseek.com/v1"
+//! api_key = "sk-..."
+//! model = "deepseek-chat"
+//! workspace = "/home/me/projects"
+//! ```
+//!
+//! 同一文件中的 `[sandbox]` 等分表项由 [`super::settings`] 读取，`save` 时原样保留。
+//! 路径可由 `SKILLLITE_CONFIG_FILE` 覆盖（测试与多配置场景）。
+
+use std::path::{Path, PathBuf};
+
+use super::env_keys::paths as path_keys;
+
+/// `~/.skilllite/config.toml` 中的字段；未出现的项为 `None`。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserConfigFile {
+    /// quickstart 预设名（openai / deepseek / ollama / moonshot / qwen / custom）
+    pub provider: Option<String>,
+    pub api_base: Option<String>,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    pub workspace: Option<String>,
+}
+
+impl UserConfigFile {
+    /// 配置文件路径：`SKILLLITE_CONFIG_FILE` 或 `~/.skilllite/config.toml`
+    pub fn path() -> PathBuf {
+        if let Some(p) = std::env::var_os(path_keys::SKILLLITE_CONFIG_FILE) {
+            if !p.is_empty() {
+                return PathBuf::from(p);
+            }
+        }
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".skilllite")
+            .join("config.toml")
+    }
+
+    /// 读取默认路径；文件不存在或不可读时返回空配置
+    pub fn load() -> Self {
+        Self::load_from(&Self::path())
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .map(|c| Self::parse(&c))
+            .unwrap_or_default()
+    }
+
+    /// 解析顶层字符串项；未知键、表头（`[section]`）与非字符串值被忽略
+    pub fn parse(content: &str) -> Self {
+        let mut cfg = Self::default();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                // 只读取顶层项
+                break;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let Some(value) = parse_string_value(value.trim()) else {
+                continue;
+            };
+            let slot = match key.trim() {
+                "provider" => &mut cfg.provider,
+                "api_base" => &mut cfg.api_base,
+                "api_key" => &mut cfg.api_key,
+                "model" => &mut cfg.model,
+                "workspace" => &mut cfg.workspace,
+                _ => continue,
+            };
+            *slot = Some(value).filter(|v| !v.is_empty());
+        }
+        cfg
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out =
+            String::from("# SkillLite user configuration (written by `skilllite quickstart`)\n");
+        out.push_str("# Environment variables and .env take precedence over these values.\n");
+        for (key, value) in [
+            ("provider", &self.provider),
+            ("api_base", &self.api_base),
+            ("api_key", &self.api_key),
+            ("model", &self.model),
+            ("workspace", &self.workspace),
+        ] {
+            if let Some(v) = value {
+                out.push_str(&format!("{} = {}\n", key, quote(v)));
+            }
+        }
+        out
+    }
+
+    /// 写入默认路径（原子替换；Unix 下权限 0600，因含 API key）
+    pub fn save(&self) -> std::io::Result<PathBuf> {
+        let path = Self::path();
+        self.save_to(&path)?;
+        Ok(path)
+    }
+
+    /// 已有文件时只改写上述顶层项，保留注释与其他配置；否则写入完整模板
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if !path.exists() {
+            return write_private(path, &self.to_toml());
+        }
+        let mut doc = read_document(path)?;
+        for (key, value) in [
+            ("provider", &self.provider),
+            ("api_base", &self.api_base),
+            ("api_key", &self.api_key),
+            ("model", &self.model),
+            ("workspace", &self.workspace),
+        ] {
+            match value {
+                Some(v) => set_item(doc.as_table_mut(), key, toml_edit::value(v.as_str())),
+                None => {
+                    doc.remove(key);
+                }
+            }
+        }
+        write_document(path, &doc)
+    }
+}
+
+/// 读取配置文件为可编辑文档；文件不存在时为空文档
+pub(crate) fn read_document(path: &Path) -> std::io::Result<toml_edit::DocumentMut> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    content.parse::<toml_edit::DocumentMut>().map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), e),
+        )
+    })
+}
+
+/// 覆盖已有项时保留键上的注释（`insert` 会连同键的 decor 一起替换）
+pub(crate) fn set_item(table: &mut dyn toml_edit::TableLike, key: &str, item: toml_edit::Item) {
+    match table.get_mut(key) {
+        Some(slot) => *slot = item,
+        None => {
+            table.insert(key, item);
+        }
+    }
+}
+
+pub(crate) fn write_document(path: &Path, doc: &toml_edit::DocumentMut) -> std::io::Result<()> {
+    write_private(path, &doc.to_string())
+}
+
+/// 原子替换，Unix 下权限 0600（文件含 API key）
+fn write_private(path: &Path, content: &str) -> std::io::Result<()> {
+    skilllite_fs::atomic_write_with_mode(path, content, 0o600).map_err(|e| match e {
+        skilllite_fs::Error::Io(e) => e,
+        e => std::io::Error::other(e),
+    })
+}
+
+fn quote(v: &str) -> String {
+    let mut s = String::with_capacity(v.len() + 2);
+    s.push('"');
+    for c in v.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\t' => s.push_str("\\t"),
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+    s
+}
+
+/// `"basic string"`（支持常见转义）或 `'literal string'`，其后可跟注释
+fn parse_string_value(raw: &str) -> Option<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        return rest.split_once('\'').map(|(v, _)| v.to_string());
+    }
+    let rest = raw.strip_prefix('"')?;
+    let mut out = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(out),
+            '\\' => match chars.next()? {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                other => out.push(other),
+            },
+            c => out.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_ignores_unknown_entries() {
+        let cfg = UserConfigFile {
+            provider: Some("custom".to_string()),
+            api_base: Some("http://localhost:8000/v1".to_string()),
+            api_key: Some("sk-\"quoted\"\\x".to_string()),
+            model: Some("m".to_string()),
+            workspace: None,
+        };
+        assert_eq!(UserConfigFile::parse(&cfg.to_toml()), cfg);
+
+        let parsed = UserConfigFile::parse(
+            "model = 'lit' # note\nretries = 3\nunknown = \"x\"\n[extra]\napi_key = \"no\"\n",
+        );
+        assert_eq!(parsed.model.as_deref(), Some("lit"));
+        assert_eq!(parsed.api_key, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_restricts_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("nested").join("config.toml");
+        let cfg = UserConfigFile {
+            api_key: Some("sk-test".to_string()),
+            ..Default::default()
+        };
+        cfg.save_to(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(UserConfigFile::load_from(&path), cfg);
+    }
+
+    #[test]
+    fn save_keeps_other_settings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "# mine\nmodel = \"old\"\nworkspace = \"/w\"\n\n[sandbox]\nlevel = 2\n",
+        )
+        .unwrap();
+        let cfg = UserConfigFile {
+            model: Some("new".to_string()),
+            api_key: Some("sk-1".to_string()),
+            ..Default::default()
+        };
+        cfg.save_to(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# mine\n"), "{content}");
+        assert!(content.contains("[sandbox]\nlevel = 2"), "{content}");
+        assert_eq!(UserConfigFile::load_from(&path), cfg);
+    }
+}
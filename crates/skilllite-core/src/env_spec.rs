@@ -5,6 +5,7 @@
 
 use std::path::Path;
 
+use crate::runtime_constraint::{parse_runtime_constraint, RuntimeConstraint};
 use crate::skill::metadata;
 
 /// Minimal input for building an isolated runtime environment (venv / node_modules).
@@ -53,4 +54,10 @@ impl EnvSpec {
             resolved_packages: meta.resolved_packages.clone(),
         }
     }
+
+    /// Version constraint on this spec's runtime declared in `compatibility`
+    /// (e.g. `python>=3.11`), if any.
+    pub fn runtime_constraint(&self) -> Option<RuntimeConstraint> {
+        parse_runtime_constraint(self.compatibility.as_deref()?, &self.language)
+    }
 }
@@ -1,16 +1,21 @@
 pub mod artifact_store;
+pub mod audit_chain;
 pub(crate) mod audit_preview_redact;
 pub mod config;
 pub mod env_spec;
 pub mod error;
 pub mod observability;
+pub mod output_index;
 pub mod path_validation;
 pub mod paths;
 pub mod planning;
 pub mod protocol;
+pub mod redact;
+pub mod runtime_constraint;
 pub mod scan_cache;
 pub mod schedule;
 pub mod skill;
+pub mod tokens;
 
 pub use env_spec::EnvSpec;
 pub use error::{Error, Result};
@@ -2,14 +2,14 @@
 //!
 //! Uses config::ObservabilityConfig for SKILLLITE_QUIET, LOG_LEVEL, AUDIT_LOG, etc.
 
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 use chrono::Utc;
 use serde_json::json;
-use tracing_subscriber::{prelude::*, EnvFilter};
+use tracing_subscriber::filter::{filter_fn, FilterExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{prelude::*, EnvFilter, Layer};
 use uuid::Uuid;
 
 static SECURITY_EVENTS_PATH: Mutex<Option<String>> = Mutex::new(None);
@@ -23,8 +23,15 @@ pub enum TracingMode {
     Chat,
 }
 
+/// Target of events that the OTLP exporter forwards as span events. Ordinary log events are
+/// never exported (they may quote file contents or commands); emit on this target at TRACE so
+/// the stderr log filter drops them.
+pub const OTEL_EVENT_TARGET: &str = "skilllite::otel";
+
 /// Initialize tracing. Call at process startup.
 /// When SKILLLITE_QUIET=1 (or SKILLBOX_QUIET for compat), only WARN and above are logged.
+/// With the `otel` feature and `SKILLLITE_OTLP_ENDPOINT` set, `skilllite*` spans are also
+/// exported over OTLP/HTTP; call [`shutdown_tracing`] before exit to flush them.
 pub fn init_tracing(mode: TracingMode) {
     let cfg = crate::config::ObservabilityConfig::from_env();
     let mut level: String = if cfg.quiet {
@@ -40,32 +47,125 @@ pub fn init_tracing(mode: TracingMode) {
 
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&level));
 
-    let json = cfg.log_json;
-
-    let _ = if json {
-        tracing_subscriber::registry()
-            .with(filter)
-            .with(
-                tracing_subscriber::fmt::layer()
-                    .json()
-                    .with_target(true)
-                    .with_thread_ids(false)
-                    // tracing-subscriber defaults fmt output to stdout; keep stderr so
-                    // machine-readable CLI lines (e.g. artifact-serve listen addr) stay clean on stdout.
-                    .with_writer(std::io::stderr),
-            )
-            .try_init()
+    let fmt_layer = if cfg.log_json {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_target(true)
+            .with_thread_ids(false)
+            // tracing-subscriber defaults fmt output to stdout; keep stderr so
+            // machine-readable CLI lines (e.g. artifact-serve listen addr) stay clean on stdout.
+            .with_writer(std::io::stderr)
+            .boxed()
     } else {
-        tracing_subscriber::registry()
-            .with(filter)
-            .with(
-                tracing_subscriber::fmt::layer()
-                    .with_target(true)
-                    .with_thread_ids(false)
-                    .with_writer(std::io::stderr),
-            )
-            .try_init()
+        tracing_subscriber::fmt::layer()
+            .with_target(true)
+            .with_thread_ids(false)
+            .with_writer(std::io::stderr)
+            .boxed()
     };
+
+    // Spans exist for the OTLP exporter; keep them out of the log lines.
+    let fmt_filter = filter.and(filter_fn(|meta| !meta.is_span()));
+    let _ = tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(fmt_filter))
+        .with(otlp_layer(cfg.otlp_endpoint.as_deref()))
+        .try_init();
+}
+
+/// Flush and stop the OTLP exporter (no-op when span export is off).
+pub fn shutdown_tracing() {
+    #[cfg(feature = "otel")]
+    otlp::shutdown();
+}
+
+/// What the OTLP exporter may see: `skilllite*` spans (whose fields are names, sizes and
+/// durations only) and events on [`OTEL_EVENT_TARGET`].
+pub fn otel_exports(meta: &tracing::Metadata<'_>) -> bool {
+    if meta.is_span() {
+        meta.target().starts_with("skilllite")
+    } else {
+        meta.target() == OTEL_EVENT_TARGET
+    }
+}
+
+#[cfg(feature = "otel")]
+fn otlp_layer<S>(endpoint: Option<&str>) -> Option<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a> + Send + Sync + 'static,
+{
+    otlp::layer(endpoint?)
+}
+
+#[cfg(not(feature = "otel"))]
+fn otlp_layer<S>(endpoint: Option<&str>) -> Option<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a> + Send + Sync + 'static,
+{
+    if endpoint.is_some() {
+        eprintln!(
+            "[WARN] SKILLLITE_OTLP_ENDPOINT is set but this build has no `otel` feature; spans are not exported"
+        );
+    }
+    None
+}
+
+#[cfg(feature = "otel")]
+mod otlp {
+    use std::sync::OnceLock;
+
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use opentelemetry_sdk::Resource;
+    use tracing_subscriber::filter::filter_fn;
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+
+    static PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+    pub(super) fn layer<S>(endpoint: &str) -> Option<Box<dyn Layer<S> + Send + Sync>>
+    where
+        S: tracing::Subscriber + for<'a> LookupSpan<'a> + Send + Sync + 'static,
+    {
+        let endpoint = endpoint.trim().trim_end_matches('/');
+        if endpoint.is_empty() {
+            return None;
+        }
+        let url = if endpoint.ends_with("/v1/traces") {
+            endpoint.to_string()
+        } else {
+            format!("{}/v1/traces", endpoint)
+        };
+        let exporter = match opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(url)
+            .build()
+        {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("[WARN] OTLP span export disabled: {}", e);
+                return None;
+            }
+        };
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name("skilllite").build())
+            .build();
+        let tracer = provider.tracer("skilllite");
+        let _ = PROVIDER.set(provider);
+        Some(
+            tracing_opentelemetry::layer()
+                .with_tracer(tracer)
+                .with_filter(filter_fn(super::otel_exports))
+                .boxed(),
+        )
+    }
+
+    pub(super) fn shutdown() {
+        if let Some(provider) = PROVIDER.get() {
+            let _ = provider.shutdown();
+        }
+    }
 }
 
 /// 解析审计日志实际写入路径。目录则按天存储 audit_YYYY-MM-DD.jsonl；.jsonl 文件则直接写入。
@@ -113,15 +213,74 @@ fn get_security_events_path() -> Option<String> {
     Some(path)
 }
 
+/// Hash-chained append (see [`crate::audit_chain`]). A new daily audit file continues the
+/// chain from the previous day's file; daily files share one directory lock so the day
+/// handoff is decided under the same lock as the append.
 fn append_jsonl(path: &str, record: &serde_json::Value) {
-    if let Ok(mut f) = OpenOptions::new().create(true).append(true).open(path) {
-        if let Ok(line) = serde_json::to_string(record) {
-            let _ = writeln!(f, "{}", line);
-            let _ = f.flush(); // 确保每条记录单独落盘，避免流式消费时行粘连
-        }
+    let path = Path::new(path);
+    if !is_daily_audit_file(path) {
+        let _ = crate::audit_chain::append_chained(path, record, None);
+        return;
+    }
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let _ = crate::audit_chain::with_lock_file(&dir.join(DAILY_AUDIT_LOCK), || {
+        let previous = if path.exists() {
+            None
+        } else {
+            daily_audit_files(dir)
+                .into_iter()
+                .rfind(|p| p.as_path() < path)
+        };
+        crate::audit_chain::append_chained_unlocked(path, record, previous.as_deref())
+    });
+}
+
+/// Lock file shared by the daily audit files of one directory.
+const DAILY_AUDIT_LOCK: &str = "audit.lock";
+
+/// `audit_YYYY-MM-DD.jsonl` files in `dir`, oldest first.
+fn daily_audit_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_daily_audit_file(p))
+        .collect();
+    files.sort();
+    files
+}
+
+fn is_daily_audit_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with("audit_") && n.ends_with(".jsonl"))
+}
+
+/// Audit log files in chain order (oldest first): the single `.jsonl` file, or every daily
+/// file when `SKILLLITE_AUDIT_LOG` is a directory. Empty when auditing is off.
+pub fn audit_log_files() -> Vec<PathBuf> {
+    let Some(base) = crate::config::ObservabilityConfig::from_env()
+        .audit_log
+        .clone()
+    else {
+        return Vec::new();
+    };
+    if base.is_empty() {
+        Vec::new()
+    } else if base.ends_with(".jsonl") {
+        vec![PathBuf::from(base)]
+    } else {
+        daily_audit_files(Path::new(&base))
     }
 }
 
+/// Verify the hash chain of the audit log ([`audit_log_files`]).
+pub fn verify_audit_log() -> crate::audit_chain::ChainReport {
+    crate::audit_chain::verify_chain(&audit_log_files())
+}
+
 /// Audit: confirmation_requested (Rust-side L3 scan)
 pub fn audit_confirmation_requested(
     skill_id: &str,
@@ -481,6 +640,13 @@ pub fn audit_edit_failed(path: &str, tool_name: &str, reason: &str, workspace: O
 
 /// Audit: evolution event — logged when evolution produces changes or rolls back.
 pub fn audit_evolution_event(event_type: &str, target_id: &str, reason: &str, txn_id: &str) {
+    tracing::trace!(
+        target: OTEL_EVENT_TARGET,
+        event_type,
+        target_id,
+        txn_id,
+        "evolution"
+    );
     if let Some(path) = get_audit_path() {
         let record = json!({
             "ts": Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
@@ -517,6 +683,59 @@ pub fn security_sandbox_fallback(skill_id: &str, reason: &str) {
     }
 }
 
+/// Security event: launch refused because skill/env files link outside their roots or the
+/// entry script changed between scan and exec. Also written to the audit log.
+pub fn security_integrity_violation(skill_id: &str, findings: &[String]) {
+    tracing::warn!(
+        skill_id = %skill_id,
+        findings = findings.len(),
+        "Security: launch integrity violation"
+    );
+    let ts = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    if let Some(path) = get_security_events_path() {
+        let record = json!({
+            "ts": ts,
+            "type": "integrity_violation",
+            "category": "runtime",
+            "skill_id": skill_id,
+            "details": { "findings": findings }
+        });
+        append_jsonl(&path, &record);
+    }
+    if let Some(path) = get_audit_path() {
+        let record = json!({
+            "ts": ts,
+            "event": "integrity_violation",
+            "skill_id": skill_id,
+            "findings": findings,
+            "source": "rust"
+        });
+        append_jsonl(&path, &record);
+    }
+}
+
+/// Security event: a syscall matched the sandbox's seccomp profile
+/// (`blocked`: failed with EPERM; otherwise allowed in log mode)
+pub fn security_seccomp_violation(skill_id: &str, syscall: &str, profile: &str, blocked: bool) {
+    tracing::warn!(
+        skill_id = %skill_id,
+        syscall = %syscall,
+        profile = %profile,
+        blocked,
+        "Security: seccomp violation"
+    );
+    if let Some(path) = get_security_events_path() {
+        let record = json!({
+            "ts": Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            "type": "seccomp_violation",
+            "category": "runtime",
+            "skill_id": skill_id,
+            "details": { "syscall": syscall, "profile": profile, "blocked": blocked }
+        });
+        append_jsonl(&path, &record);
+    }
+}
+
 #[cfg(test)]
 mod summary_tests {
     use serde_json::Value;
@@ -550,3 +769,59 @@ mod summary_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod otel_filter_tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::filter::filter_fn;
+    use tracing_subscriber::layer::Context;
+    use tracing_subscriber::prelude::*;
+    use tracing_subscriber::Layer;
+
+    #[derive(Clone, Default)]
+    struct Seen(Arc<Mutex<Vec<String>>>);
+
+    impl<S: tracing::Subscriber> Layer<S> for Seen {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: Context<'_, S>,
+        ) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("span:{}", attrs.metadata().name()));
+        }
+
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("event:{}", event.metadata().target()));
+        }
+    }
+
+    #[test]
+    fn only_skilllite_spans_and_otel_events_are_exported() {
+        let seen = Seen::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(seen.clone().with_filter(filter_fn(super::otel_exports)));
+        tracing::subscriber::with_default(subscriber, || {
+            let _turn =
+                tracing::info_span!(target: "skilllite_agent::agent_loop", "agent.turn").entered();
+            let _other = tracing::info_span!(target: "hyper::client", "request").entered();
+            tracing::info!(path = "/secret/notes.txt", "read file");
+            tracing::trace!(target: super::OTEL_EVENT_TARGET, event_type = "rule_added", "evolution");
+        });
+        let seen = seen.0.lock().unwrap().clone();
+        assert_eq!(
+            seen,
+            vec![
+                "span:agent.turn".to_string(),
+                format!("event:{}", super::OTEL_EVENT_TARGET)
+            ]
+        );
+    }
+}
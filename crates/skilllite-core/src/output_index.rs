@@ -0,0 +1,553 @@
+//! Output directory management: sidecar provenance index, quota, and expiry.
+//!
+//! `write_output` records every file it writes in `<output_dir>/.skilllite-output-index.json`
+//! (size, mtime, producing session). The index backs `list_output`, the desktop "recent
+//! outputs" panel, and `skilllite clean-output`, so none of them need to rescan the tree.
+//! Files that appear on disk without an index entry (user copies, skill results) are picked
+//! up by [`OutputIndex::reconcile`] with no session.
+//!
+//! Quota (`SKILLLITE_OUTPUT_MAX_BYTES` / `SKILLLITE_OUTPUT_MAX_FILES`) is enforced at write
+//! time. With `SKILLLITE_OUTPUT_QUOTA_POLICY=expire` the oldest session-scoped artifacts of
+//! other sessions are deleted to make room; the default `refuse` policy fails the write.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::env_keys::paths as keys;
+use crate::config::loader::env_optional;
+use crate::error::{Error, Result};
+
+/// Sidecar index file name, stored in the output directory root.
+pub const INDEX_FILE: &str = ".skilllite-output-index.json";
+
+const DEFAULT_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 10_000;
+
+/// One file in the output directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputEntry {
+    /// Path relative to the output directory, `/`-separated.
+    pub path: String,
+    pub size: u64,
+    /// Last modification, unix seconds.
+    pub modified: u64,
+    /// Session that produced the file; `None` for files not written by `write_output`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+}
+
+/// What to do when a write would exceed the quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaPolicy {
+    /// Fail the write with a message pointing at `skilllite clean-output`.
+    Refuse,
+    /// Delete the oldest session-scoped artifacts of other sessions until the write fits.
+    ExpireOldest,
+}
+
+/// Output directory quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputQuota {
+    /// `0` disables the byte limit.
+    pub max_bytes: u64,
+    /// `0` disables the file-count limit.
+    pub max_files: usize,
+    pub policy: QuotaPolicy,
+}
+
+impl Default for OutputQuota {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+            policy: QuotaPolicy::Refuse,
+        }
+    }
+}
+
+impl OutputQuota {
+    /// `SKILLLITE_OUTPUT_MAX_BYTES`, `SKILLLITE_OUTPUT_MAX_FILES`, `SKILLLITE_OUTPUT_QUOTA_POLICY`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let max_bytes = env_optional(keys::SKILLLITE_OUTPUT_MAX_BYTES, &[])
+            .and_then(|v| parse_size(&v))
+            .unwrap_or(defaults.max_bytes);
+        let max_files = env_optional(keys::SKILLLITE_OUTPUT_MAX_FILES, &[])
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(defaults.max_files);
+        let policy = match env_optional(keys::SKILLLITE_OUTPUT_QUOTA_POLICY, &[])
+            .map(|v| v.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("expire") | Some("expire_oldest") => QuotaPolicy::ExpireOldest,
+            _ => QuotaPolicy::Refuse,
+        };
+        Self {
+            max_bytes,
+            max_files,
+            policy,
+        }
+    }
+}
+
+/// Parse `1073741824`, `512mb`, `2g`, `100k` (binary units).
+fn parse_size(raw: &str) -> Option<u64> {
+    let raw = raw.trim().to_ascii_lowercase();
+    let raw = raw.strip_suffix('b').unwrap_or(&raw);
+    let (num, mult) = match raw.chars().last()? {
+        'k' => (&raw[..raw.len() - 1], 1024),
+        'm' => (&raw[..raw.len() - 1], 1024 * 1024),
+        'g' => (&raw[..raw.len() - 1], 1024 * 1024 * 1024),
+        _ => (raw, 1),
+    };
+    num.trim()
+        .parse::<u64>()
+        .ok()
+        .map(|n| n.saturating_mul(mult))
+}
+
+/// Sidecar provenance index, keyed by relative path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OutputIndex {
+    #[serde(default)]
+    pub entries: BTreeMap<String, OutputEntry>,
+}
+
+impl OutputIndex {
+    /// Load the index; a missing or corrupt file yields an empty index.
+    pub fn load(output_root: &Path) -> Self {
+        std::fs::read_to_string(output_root.join(INDEX_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, output_root: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        skilllite_fs::atomic_write(&output_root.join(INDEX_FILE), &json)?;
+        Ok(())
+    }
+
+    /// Record (or refresh) a file after it was written.
+    pub fn record(&mut self, output_root: &Path, file: &Path, session: Option<&str>) {
+        let Some(rel) = relative_key(output_root, file) else {
+            return;
+        };
+        let Ok(meta) = std::fs::metadata(file) else {
+            return;
+        };
+        let session = session
+            .map(str::to_string)
+            .or_else(|| self.entries.get(&rel).and_then(|e| e.session.clone()));
+        self.entries.insert(
+            rel.clone(),
+            OutputEntry {
+                path: rel,
+                size: meta.len(),
+                modified: mtime_secs(&meta),
+                session,
+            },
+        );
+    }
+
+    /// Sync the index with the directory: drop vanished files, add unindexed ones
+    /// (without a session), refresh size/mtime of the rest.
+    pub fn reconcile(&mut self, output_root: &Path) {
+        let mut on_disk = BTreeMap::new();
+        walk_files(output_root, output_root, &mut on_disk);
+        self.entries.retain(|k, _| on_disk.contains_key(k));
+        for (rel, (size, modified)) in on_disk {
+            let entry = self.entries.entry(rel.clone()).or_insert(OutputEntry {
+                path: rel,
+                size,
+                modified,
+                session: None,
+            });
+            entry.size = size;
+            entry.modified = modified;
+        }
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.entries.values().map(|e| e.size).sum()
+    }
+
+    /// Entries newest first, optionally only those produced by `session`.
+    pub fn listing(&self, session: Option<&str>) -> Vec<&OutputEntry> {
+        let mut out: Vec<&OutputEntry> = self
+            .entries
+            .values()
+            .filter(|e| session.is_none() || e.session.as_deref() == session)
+            .collect();
+        out.sort_by(|a, b| b.modified.cmp(&a.modified).then(a.path.cmp(&b.path)));
+        out
+    }
+}
+
+/// Result of [`enforce_quota`]: files deleted to make room (relative paths).
+#[derive(Debug, Default)]
+pub struct QuotaOutcome {
+    pub expired: Vec<String>,
+}
+
+/// Check that writing `incoming_bytes` to `target` fits the quota, expiring old artifacts
+/// when the policy allows. `append` adds to the current size instead of replacing it.
+/// `session` is the writing session; its own artifacts are never expired.
+pub fn enforce_quota(
+    output_root: &Path,
+    index: &mut OutputIndex,
+    quota: &OutputQuota,
+    target: &Path,
+    incoming_bytes: u64,
+    append: bool,
+    session: Option<&str>,
+) -> Result<QuotaOutcome> {
+    let target_key = relative_key(output_root, target).unwrap_or_default();
+    let existing = index.entries.get(&target_key).map(|e| e.size);
+
+    let projected = |index: &OutputIndex| {
+        let base = index.total_bytes() - existing.unwrap_or(0);
+        let new_size = if append {
+            existing.unwrap_or(0) + incoming_bytes
+        } else {
+            incoming_bytes
+        };
+        let files = index.entries.len() + usize::from(existing.is_none());
+        (base + new_size, files)
+    };
+    let fits = |(bytes, files): (u64, usize)| {
+        (quota.max_bytes == 0 || bytes <= quota.max_bytes)
+            && (quota.max_files == 0 || files <= quota.max_files)
+    };
+
+    let mut outcome = QuotaOutcome::default();
+    if fits(projected(index)) {
+        return Ok(outcome);
+    }
+
+    if quota.policy == QuotaPolicy::ExpireOldest {
+        let mut candidates: Vec<OutputEntry> = index
+            .entries
+            .values()
+            .filter(|e| e.path != target_key && e.session.is_some())
+            .filter(|e| session.is_none() || e.session.as_deref() != session)
+            .cloned()
+            .collect();
+        candidates.sort_by_key(|e| e.modified);
+        for entry in candidates {
+            if fits(projected(index)) {
+                break;
+            }
+            let path = output_root.join(&entry.path);
+            if std::fs::remove_file(&path).is_ok() || !path.exists() {
+                remove_empty_parents(output_root, &path);
+                index.entries.remove(&entry.path);
+                outcome.expired.push(entry.path);
+            }
+        }
+        if fits(projected(index)) {
+            return Ok(outcome);
+        }
+    }
+
+    let (bytes, files) = projected(index);
+    Err(Error::validation(format!(
+        "Output directory quota exceeded: writing {} would bring {} to {} bytes / {} files \
+         (limit: {} / {}). Run `skilllite clean-output --older-than 30d` or raise \
+         SKILLLITE_OUTPUT_MAX_BYTES / SKILLLITE_OUTPUT_MAX_FILES \
+         (SKILLLITE_OUTPUT_QUOTA_POLICY=expire deletes old session artifacts automatically).",
+        target_key,
+        output_root.display(),
+        bytes,
+        files,
+        limit_label(quota.max_bytes),
+        limit_label(quota.max_files as u64),
+    )))
+}
+
+fn limit_label(limit: u64) -> String {
+    if limit == 0 {
+        "unlimited".to_string()
+    } else {
+        limit.to_string()
+    }
+}
+
+/// Files removed (or that would be removed) by [`clean_older_than`].
+#[derive(Debug, Default, Serialize)]
+pub struct CleanReport {
+    pub removed: Vec<OutputEntry>,
+    pub bytes: u64,
+    pub dry_run: bool,
+}
+
+/// Delete output files not modified within `older_than`, then update the index.
+pub fn clean_older_than(
+    output_root: &Path,
+    older_than: Duration,
+    dry_run: bool,
+) -> Result<CleanReport> {
+    let mut index = OutputIndex::load(output_root);
+    index.reconcile(output_root);
+    let cutoff = SystemTime::now()
+        .checked_sub(older_than)
+        .unwrap_or(UNIX_EPOCH)
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let mut report = CleanReport {
+        dry_run,
+        ..Default::default()
+    };
+    let stale: Vec<OutputEntry> = index
+        .entries
+        .values()
+        .filter(|e| e.modified < cutoff)
+        .cloned()
+        .collect();
+    for entry in stale {
+        if !dry_run {
+            let path = output_root.join(&entry.path);
+            std::fs::remove_file(&path)?;
+            remove_empty_parents(output_root, &path);
+            index.entries.remove(&entry.path);
+        }
+        report.bytes += entry.size;
+        report.removed.push(entry);
+    }
+    if !dry_run && output_root.is_dir() {
+        index.save(output_root)?;
+    }
+    Ok(report)
+}
+
+/// Human-readable byte size for listings and reports.
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else if bytes < 1024 * 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.2} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    }
+}
+
+fn relative_key(output_root: &Path, file: &Path) -> Option<String> {
+    let rel = file.strip_prefix(output_root).ok()?;
+    let parts: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("/"))
+    }
+}
+
+fn mtime_secs(meta: &std::fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn walk_files(root: &Path, dir: &Path, out: &mut BTreeMap<String, (u64, u64)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            walk_files(root, &path, out);
+        } else if meta.is_file() && path.file_name().is_some_and(|n| n != INDEX_FILE) {
+            if let Some(rel) = relative_key(root, &path) {
+                out.insert(rel, (meta.len(), mtime_secs(&meta)));
+            }
+        }
+    }
+}
+
+fn remove_empty_parents(output_root: &Path, file: &Path) {
+    let mut dir: Option<PathBuf> = file.parent().map(Path::to_path_buf);
+    while let Some(d) = dir {
+        if d == output_root || !d.starts_with(output_root) || std::fs::remove_dir(&d).is_err() {
+            break;
+        }
+        dir = d.parent().map(Path::to_path_buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, rel: &str, bytes: usize) -> PathBuf {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, vec![b'x'; bytes]).unwrap();
+        path
+    }
+
+    fn backdate(index: &mut OutputIndex, rel: &str, modified: u64) {
+        index.entries.get_mut(rel).unwrap().modified = modified;
+    }
+
+    #[test]
+    fn record_and_reconcile_track_provenance() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut index = OutputIndex::default();
+        let a = write(root, "reports/a.html", 10);
+        index.record(root, &a, Some("s1"));
+        write(root, "manual.txt", 3);
+        index.reconcile(root);
+        index.save(root).unwrap();
+
+        let loaded = OutputIndex::load(root);
+        assert_eq!(
+            loaded.entries["reports/a.html"].session.as_deref(),
+            Some("s1")
+        );
+        assert_eq!(loaded.entries["reports/a.html"].size, 10);
+        assert_eq!(loaded.entries["manual.txt"].session, None);
+        assert!(!loaded.entries.contains_key(INDEX_FILE));
+        assert_eq!(loaded.listing(Some("s1")).len(), 1);
+        assert_eq!(loaded.listing(None).len(), 2);
+
+        std::fs::remove_file(&a).unwrap();
+        let mut index = loaded;
+        index.reconcile(root);
+        assert!(!index.entries.contains_key("reports/a.html"));
+    }
+
+    #[test]
+    fn refuse_policy_blocks_oversized_write() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut index = OutputIndex::default();
+        let a = write(root, "a.txt", 60);
+        index.record(root, &a, Some("old"));
+        let quota = OutputQuota {
+            max_bytes: 100,
+            max_files: 0,
+            policy: QuotaPolicy::Refuse,
+        };
+
+        let err = enforce_quota(
+            root,
+            &mut index,
+            &quota,
+            &root.join("b.txt"),
+            50,
+            false,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("quota exceeded"));
+        assert!(a.exists());
+        // Overwriting the same file only counts the new size.
+        enforce_quota(root, &mut index, &quota, &a, 90, false, None).unwrap();
+        assert!(enforce_quota(root, &mut index, &quota, &a, 50, true, None).is_err());
+    }
+
+    #[test]
+    fn expire_policy_removes_oldest_session_artifacts_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut index = OutputIndex::default();
+        for (rel, session, age) in [
+            ("old/one.txt", Some("s-old"), 100),
+            ("two.txt", Some("s-old"), 200),
+            ("mine.txt", Some("s-now"), 50),
+            ("user.txt", None, 10),
+        ] {
+            let path = write(root, rel, 10);
+            index.record(root, &path, session);
+            backdate(&mut index, rel, age);
+        }
+        let quota = OutputQuota {
+            max_bytes: 0,
+            max_files: 4,
+            policy: QuotaPolicy::ExpireOldest,
+        };
+
+        let outcome = enforce_quota(
+            root,
+            &mut index,
+            &quota,
+            &root.join("new.txt"),
+            10,
+            false,
+            Some("s-now"),
+        )
+        .unwrap();
+        assert_eq!(outcome.expired, vec!["old/one.txt".to_string()]);
+        assert!(!root.join("old").exists(), "empty parent dir is removed");
+        assert!(root.join("mine.txt").exists() && root.join("user.txt").exists());
+
+        let tight = OutputQuota {
+            max_files: 1,
+            ..quota
+        };
+        let err = enforce_quota(
+            root,
+            &mut index,
+            &tight,
+            &root.join("new.txt"),
+            10,
+            false,
+            Some("s-now"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("quota exceeded"));
+        assert!(root.join("mine.txt").exists() && root.join("user.txt").exists());
+    }
+
+    #[test]
+    fn clean_older_than_honors_dry_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let old = write(root, "old.txt", 5);
+        let fresh = write(root, "fresh.txt", 7);
+        let mut index = OutputIndex::default();
+        index.reconcile(root);
+        index.save(root).unwrap();
+        let past = SystemTime::now() - Duration::from_secs(40 * 86_400);
+        std::fs::File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+
+        let dry = clean_older_than(root, Duration::from_secs(30 * 86_400), true).unwrap();
+        assert_eq!(dry.removed.len(), 1);
+        assert_eq!(dry.bytes, 5);
+        assert!(old.exists());
+
+        let done = clean_older_than(root, Duration::from_secs(30 * 86_400), false).unwrap();
+        assert_eq!(done.removed[0].path, "old.txt");
+        assert!(!old.exists() && fresh.exists());
+        assert!(!OutputIndex::load(root).entries.contains_key("old.txt"));
+    }
+
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size("2k"), Some(2048));
+        assert_eq!(parse_size("512MB"), Some(512 * 1024 * 1024));
+        assert_eq!(parse_size("1g"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_size("lots"), None);
+    }
+}
@@ -1,9 +1,60 @@
 //! Path validation utilities.
 //!
-//! Ensures paths stay within allowed root to prevent path traversal attacks.
+//! Ensures paths stay within allowed roots to prevent path traversal attacks.
+//! [`resolve_under_roots`] is the single containment check shared by skill paths, the
+//! agent's file tools, `write_output` and the evolution gatekeeper:
+//!
+//! - `..` / `.` are folded lexically first, so paths that do not exist yet (files about to
+//!   be written) are checked too;
+//! - the existing part of the path is then resolved through symlinks and compared against
+//!   the canonical roots, so `workspace/link -> /etc` is rejected;
+//! - with [`PathPolicy::deny_symlink_escape`], every symlink along the path must point
+//!   inside the roots, even when a chain of links ends up back inside;
+//! - on Windows, comparisons ignore case and `\\?\` verbatim prefixes.
 
 use crate::error::PathValidationError;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+
+/// How [`resolve_under_roots`] treats a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathPolicy {
+    /// Used in error messages, e.g. `"Skill path"`.
+    pub path_type: &'static str,
+    /// Missing paths fail with [`PathValidationError::NotFound`].
+    pub must_exist: bool,
+    /// Resolve symlinks in the existing part of the path. `false` = lexical check only.
+    pub resolve_symlinks: bool,
+    /// Reject a path when any symlink along it points outside the roots.
+    pub deny_symlink_escape: bool,
+}
+
+impl PathPolicy {
+    /// An existing path (skill directory, script), symlinks resolved.
+    pub const fn existing(path_type: &'static str) -> Self {
+        Self {
+            path_type,
+            must_exist: true,
+            resolve_symlinks: true,
+            deny_symlink_escape: false,
+        }
+    }
+
+    /// A path that may not exist yet (a file about to be written). No symlink along the
+    /// existing part may point outside the roots.
+    pub const fn writable(path_type: &'static str) -> Self {
+        Self {
+            path_type,
+            must_exist: false,
+            resolve_symlinks: true,
+            deny_symlink_escape: true,
+        }
+    }
+
+    pub const fn deny_symlink_escape(mut self, deny: bool) -> Self {
+        self.deny_symlink_escape = deny;
+        self
+    }
+}
 
 /// Get the allowed root directory for path validation.
 pub fn get_allowed_root() -> Result<PathBuf, PathValidationError> {
@@ -19,31 +70,366 @@ pub fn get_allowed_root() -> Result<PathBuf, PathValidationError> {
 /// Validate path is within allowed root. Prevents path traversal.
 pub fn validate_path_under_root(
     path: &str,
-    path_type: &str,
+    path_type: &'static str,
 ) -> Result<PathBuf, PathValidationError> {
     let allowed_root = get_allowed_root()?;
-    let input = Path::new(path);
-    let full = if input.is_absolute() {
-        input.to_path_buf()
+    resolve_under_roots(path, &[&allowed_root], PathPolicy::existing(path_type))
+}
+
+/// Validate skill_dir is within allowed root. Prevents path traversal.
+pub fn validate_skill_path(skill_dir: &str) -> Result<PathBuf, PathValidationError> {
+    validate_path_under_root(skill_dir, "Skill path")
+}
+
+/// Resolve `input` inside one of `roots`.
+///
+/// A relative `input` is tried against each root in order; an absolute one may lie under
+/// any root. The result is spelled under the (absolute, lexically normalized) root it matched, with
+/// symlinks below that root resolved when the policy asks for it.
+pub fn resolve_under_roots(
+    input: impl AsRef<Path>,
+    roots: &[&Path],
+    policy: PathPolicy,
+) -> Result<PathBuf, PathValidationError> {
+    let input = input.as_ref();
+    let escape = || PathValidationError::PathEscape {
+        path_type: policy.path_type.to_string(),
+        path: input.display().to_string(),
+    };
+    let roots: Vec<(PathBuf, PathBuf)> = roots
+        .iter()
+        .map(|r| {
+            let lexical =
+                normalize_lexical(&std::path::absolute(r).unwrap_or_else(|_| r.to_path_buf()));
+            let canonical = canonicalize_existing_prefix(&lexical);
+            (lexical, canonical)
+        })
+        .collect();
+
+    let candidates: Vec<PathBuf> = if input.is_absolute() {
+        vec![normalize_lexical(input)]
     } else {
-        allowed_root.join(input)
+        roots
+            .iter()
+            .map(|(lexical, _)| normalize_lexical(&lexical.join(input)))
+            .collect()
     };
-    let canonical = full
-        .canonicalize()
-        .map_err(|_| PathValidationError::NotFound {
-            path_type: path_type.to_string(),
-            path: path.to_string(),
-        })?;
-    if !canonical.starts_with(&allowed_root) {
-        return Err(PathValidationError::PathEscape {
-            path_type: path_type.to_string(),
-            path: path.to_string(),
+
+    let mut missing = false;
+    for candidate in candidates {
+        let Some(resolved) = contain(&candidate, &roots, policy) else {
+            continue;
+        };
+        if policy.must_exist && std::fs::symlink_metadata(&resolved).is_err() {
+            missing = true;
+            continue;
+        }
+        return Ok(resolved);
+    }
+    if missing {
+        return Err(PathValidationError::NotFound {
+            path_type: policy.path_type.to_string(),
+            path: input.display().to_string(),
         });
     }
-    Ok(canonical)
+    Err(escape())
 }
 
-/// Validate skill_dir is within allowed root. Prevents path traversal.
-pub fn validate_skill_path(skill_dir: &str) -> Result<PathBuf, PathValidationError> {
-    validate_path_under_root(skill_dir, "Skill path")
+/// `candidate` (already lexically normalized) re-spelled under the root containing it.
+fn contain(candidate: &Path, roots: &[(PathBuf, PathBuf)], policy: PathPolicy) -> Option<PathBuf> {
+    if !policy.resolve_symlinks {
+        return roots
+            .iter()
+            .any(|(lexical, _)| starts_with_root(candidate, lexical))
+            .then(|| candidate.to_path_buf());
+    }
+    let canonical = canonicalize_existing_prefix(candidate);
+    let (lexical_root, canonical_root) = roots
+        .iter()
+        .find(|(_, canonical_root)| starts_with_root(&canonical, canonical_root))?;
+    if policy.deny_symlink_escape && has_escaping_symlink(candidate, roots) {
+        return None;
+    }
+    let rest = strip_root(&canonical, canonical_root)?;
+    Some(if rest.as_os_str().is_empty() {
+        lexical_root.clone()
+    } else {
+        lexical_root.join(rest)
+    })
+}
+
+/// True when a symlink among the existing components of `path` points outside every root.
+/// Only the link's own hop counts: a chain that leaves the roots and comes back is rejected.
+fn has_escaping_symlink(path: &Path, roots: &[(PathBuf, PathBuf)]) -> bool {
+    let mut prefix = PathBuf::new();
+    for component in path.components() {
+        prefix.push(component);
+        let Ok(meta) = std::fs::symlink_metadata(&prefix) else {
+            break;
+        };
+        if !meta.file_type().is_symlink() {
+            continue;
+        }
+        // Links at or above a root (`/tmp -> /private/tmp`, an aliased workspace) are part
+        // of the root itself.
+        let resolved = canonicalize_existing_prefix(&prefix);
+        if roots
+            .iter()
+            .any(|(_, root)| starts_with_root(root, &resolved))
+        {
+            continue;
+        }
+        let Ok(target) = std::fs::read_link(&prefix) else {
+            return true;
+        };
+        let target = normalize_lexical(&prefix.parent().unwrap_or(Path::new("")).join(target));
+        let hop = match (target.parent(), target.file_name()) {
+            (Some(parent), Some(name)) => canonicalize_existing_prefix(parent).join(name),
+            _ => target,
+        };
+        if !roots.iter().any(|(_, root)| starts_with_root(&hop, root)) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Fold `.` and `..` without touching the filesystem. `..` never climbs above the root
+/// (`/../etc` is `/etc`); leading `..` of a relative path are kept.
+pub fn normalize_lexical(path: &Path) -> PathBuf {
+    let mut out: Vec<Component> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match out.last() {
+                Some(Component::Normal(_)) => {
+                    out.pop();
+                }
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => out.push(component),
+            },
+            other => out.push(other),
+        }
+    }
+    out.iter().collect()
+}
+
+/// Canonicalize the longest existing prefix of `path` and append the rest unchanged.
+fn canonicalize_existing_prefix(path: &Path) -> PathBuf {
+    let mut existing = path.to_path_buf();
+    let mut rest: Vec<std::ffi::OsString> = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            let mut out = strip_verbatim(canonical);
+            for part in rest.iter().rev() {
+                out.push(part);
+            }
+            return out;
+        }
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                rest.push(name.to_os_string());
+                existing = parent.to_path_buf();
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// `\\?\C:\x` → `C:\x`, `\\?\UNC\server\share` → `\\server\share` (Windows only).
+#[cfg(windows)]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    let s = path.to_string_lossy();
+    if let Some(unc) = s.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", unc))
+    } else if let Some(local) = s.strip_prefix(r"\\?\") {
+        PathBuf::from(local)
+    } else {
+        path
+    }
+}
+
+#[cfg(not(windows))]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// Component-wise prefix test (so `/work` does not contain `/workspace2`); case-insensitive
+/// on Windows.
+fn starts_with_root(path: &Path, root: &Path) -> bool {
+    strip_root(path, root).is_some()
+}
+
+fn strip_root(path: &Path, root: &Path) -> Option<PathBuf> {
+    #[cfg(windows)]
+    {
+        let fold = |p: &Path| {
+            p.components()
+                .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+                .collect::<Vec<_>>()
+        };
+        let (p, r) = (fold(path), fold(root));
+        if p.len() < r.len() || p[..r.len()] != r[..] {
+            return None;
+        }
+        Some(path.components().skip(r.len()).collect())
+    }
+    #[cfg(not(windows))]
+    {
+        path.strip_prefix(root).ok().map(Path::to_path_buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WRITE: PathPolicy = PathPolicy::writable("File path");
+    const EXISTING: PathPolicy = PathPolicy::existing("File path");
+
+    fn escapes(result: Result<PathBuf, PathValidationError>) -> bool {
+        matches!(result, Err(PathValidationError::PathEscape { .. }))
+    }
+
+    #[test]
+    fn traversal_and_prefix_siblings_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let work = tmp.path().join("work");
+        std::fs::create_dir_all(work.join("sub")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("workspace2")).unwrap();
+        let roots: &[&Path] = &[&work];
+
+        for bad in [
+            "../secret",
+            "sub/../../secret",
+            "./sub/./../..",
+            "../workspace2/x",
+        ] {
+            assert!(escapes(resolve_under_roots(bad, roots, WRITE)), "{bad}");
+        }
+        let sibling = tmp.path().join("workspace2").join("x");
+        assert!(escapes(resolve_under_roots(&sibling, roots, WRITE)));
+        assert!(escapes(resolve_under_roots("/etc/passwd", roots, WRITE)));
+
+        assert_eq!(
+            resolve_under_roots("sub/../new/file.txt", roots, WRITE).unwrap(),
+            work.join("new/file.txt")
+        );
+        assert_eq!(resolve_under_roots(".", roots, WRITE).unwrap(), work);
+        assert_eq!(normalize_lexical(Path::new("/../etc")), Path::new("/etc"));
+        assert_eq!(
+            normalize_lexical(Path::new("../a/./b/..")),
+            Path::new("../a")
+        );
+    }
+
+    #[test]
+    fn mixed_separators_never_leave_the_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let roots: &[&Path] = &[tmp.path()];
+        // A separator on Windows, an ordinary file-name character elsewhere: inside either way.
+        for input in [r"sub\..\..\..\etc", r"sub/..\..\x", r"a\b/c"] {
+            if let Ok(resolved) = resolve_under_roots(input, roots, WRITE) {
+                assert!(resolved.starts_with(tmp.path()), "{input}");
+            }
+        }
+    }
+
+    #[test]
+    fn missing_paths_are_reported_only_when_they_must_exist() {
+        let tmp = tempfile::tempdir().unwrap();
+        let roots: &[&Path] = &[tmp.path()];
+        assert!(matches!(
+            resolve_under_roots("nope", roots, EXISTING),
+            Err(PathValidationError::NotFound { .. })
+        ));
+        assert!(resolve_under_roots("nope", roots, WRITE).is_ok());
+        assert!(escapes(resolve_under_roots("../nope", roots, EXISTING)));
+    }
+
+    #[test]
+    fn relative_inputs_try_each_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (ws, out) = (tmp.path().join("ws"), tmp.path().join("out"));
+        std::fs::create_dir_all(&ws).unwrap();
+        std::fs::create_dir_all(&out).unwrap();
+        std::fs::write(out.join("report.html"), "").unwrap();
+        let roots: &[&Path] = &[&ws, &out];
+        assert_eq!(
+            resolve_under_roots("report.html", roots, EXISTING).unwrap(),
+            out.join("report.html")
+        );
+        assert_eq!(
+            resolve_under_roots("new.txt", roots, WRITE).unwrap(),
+            ws.join("new.txt")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_out_of_the_root_are_rejected() {
+        use std::os::unix::fs::symlink;
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path().join("ws");
+        let outside = tmp.path().join("outside");
+        std::fs::create_dir_all(ws.join("real")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("secret"), "x").unwrap();
+        symlink(&outside, ws.join("escape")).unwrap();
+        symlink(ws.join("real"), ws.join("inner")).unwrap();
+        // A chain that leaves the workspace and comes back.
+        symlink(ws.join("real"), outside.join("back")).unwrap();
+        symlink(outside.join("back"), ws.join("detour")).unwrap();
+        let roots: &[&Path] = &[&ws];
+
+        assert!(escapes(resolve_under_roots(
+            "escape/secret",
+            roots,
+            EXISTING
+        )));
+        assert!(escapes(resolve_under_roots("escape/new.txt", roots, WRITE)));
+        assert!(escapes(resolve_under_roots(
+            ws.join("escape"),
+            roots,
+            EXISTING
+        )));
+        assert_eq!(
+            resolve_under_roots("inner/a.txt", roots, WRITE).unwrap(),
+            ws.join("real/a.txt")
+        );
+
+        assert!(
+            resolve_under_roots("detour/a.txt", roots, EXISTING.deny_symlink_escape(false))
+                .is_err_and(|e| matches!(e, PathValidationError::NotFound { .. }))
+        );
+        assert!(escapes(resolve_under_roots("detour/a.txt", roots, WRITE)));
+
+        let lexical = PathPolicy {
+            resolve_symlinks: false,
+            ..WRITE
+        };
+        assert_eq!(
+            resolve_under_roots("escape/secret", roots, lexical).unwrap(),
+            ws.join("escape/secret")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_roots_keep_their_spelling() {
+        use std::os::unix::fs::symlink;
+        let tmp = tempfile::tempdir().unwrap();
+        let real = tmp.path().join("real");
+        std::fs::create_dir_all(&real).unwrap();
+        let alias = tmp.path().join("alias");
+        symlink(&real, &alias).unwrap();
+        assert_eq!(
+            resolve_under_roots("a/b.txt", &[&alias], WRITE).unwrap(),
+            alias.join("a/b.txt")
+        );
+        assert_eq!(
+            resolve_under_roots(real.join("c.txt"), &[&alias], WRITE).unwrap(),
+            alias.join("c.txt")
+        );
+    }
 }
@@ -0,0 +1,179 @@
+//! Shared planning types used by agent and evolution.
+//!
+//! PlanningRule, RuleScope, SourceEntry, SourceRegistry are used for task planning
+//! and evolution (prompt learning, external knowledge). [`validate`] checks the on-disk
+//! `rules.json` / `sources.json` shapes before they are loaded or written.
+
+use serde::{Deserialize, Serialize};
+
+mod validate;
+
+pub use validate::{
+    lint_rules, lint_sources, validate_rule_value, validate_rules, validate_sources, SchemaError,
+    SchemaProblem,
+};
+
+fn default_priority() -> u32 {
+    50
+}
+
+fn default_origin() -> String {
+    "seed".to_string()
+}
+
+fn default_source_quality() -> f32 {
+    0.70
+}
+
+fn default_source_accessibility() -> f32 {
+    0.80
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A planning rule for task generation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanningRule {
+    pub id: String,
+    #[serde(default = "default_priority")]
+    pub priority: u32,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub context_keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_hint: Option<String>,
+    pub instruction: String,
+    #[serde(default)]
+    pub mutable: bool,
+    #[serde(default = "default_origin")]
+    pub origin: String,
+    #[serde(default)]
+    pub reusable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effectiveness: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_count: Option<u32>,
+    /// Set for `origin: "external"` rules: where the advice was fetched from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<RuleProvenance>,
+    /// Context the rule applies to; `None` (and every seed rule) applies everywhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<RuleScope>,
+}
+
+/// Conditions under which a rule is injected into the planning prompt. All set fields must
+/// hold; an empty scope matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleScope {
+    /// Glob over the workspace path (`**/webapp`, `data-*`); without `/` it matches the
+    /// directory name only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_glob: Option<String>,
+    /// Project language from the workspace file census (`python`, `typescript`, ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Skill that must be loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires_skill: Option<String>,
+    /// At least one must appear in the user message (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub task_keywords: Vec<String>,
+}
+
+impl RuleScope {
+    pub fn is_empty(&self) -> bool {
+        self.workspace_glob.is_none()
+            && self.language.is_none()
+            && self.requires_skill.is_none()
+            && self.task_keywords.is_empty()
+    }
+}
+
+/// Audit trail for a rule learned from an external source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleProvenance {
+    pub source_id: String,
+    pub source_url: String,
+    /// RFC 3339 time the source content was fetched.
+    pub fetched_at: String,
+    /// SHA-256 (hex) of the article text the rule was extracted from.
+    pub content_hash: String,
+}
+
+impl RuleProvenance {
+    pub fn hash_content(content: &str) -> String {
+        use sha2::{Digest, Sha256};
+        hex::encode(Sha256::digest(content.as_bytes()))
+    }
+}
+
+/// A single external information source entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceEntry {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub source_type: String,
+    pub parser: String,
+    pub region: String,
+    pub language: String,
+    #[serde(default)]
+    pub domains: Vec<String>,
+    #[serde(default = "default_source_quality")]
+    pub quality_score: f32,
+    #[serde(default = "default_source_accessibility")]
+    pub accessibility_score: f32,
+    #[serde(default)]
+    pub rules_contributed: u32,
+    #[serde(default)]
+    pub fetch_success_count: u32,
+    #[serde(default)]
+    pub fetch_fail_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_fetched: Option<String>,
+    #[serde(default)]
+    pub mutable: bool,
+    #[serde(default = "default_origin")]
+    pub origin: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// HTTP `ETag` from the last successful fetch (sent as `If-None-Match`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// HTTP `Last-Modified` from the last successful fetch (sent as `If-Modified-Since`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Consecutive runs in which this source hit its byte budget.
+    #[serde(default)]
+    pub budget_exceeded_streak: u32,
+    /// Fetch statistics from the most recent external learning run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_fetch: Option<SourceFetchStats>,
+}
+
+/// Per-source outcome of one external learning fetch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceFetchStats {
+    pub ts: String,
+    /// `ok` | `not_modified` | `robots_disallowed` | `failed` | `skipped_time_budget`
+    pub outcome: String,
+    #[serde(default)]
+    pub bytes: u64,
+    #[serde(default)]
+    pub items: usize,
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Body was cut off at the per-source byte budget.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// The full source registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRegistry {
+    pub version: u32,
+    pub sources: Vec<SourceEntry>,
+}
@@ -0,0 +1,717 @@
+//! Schema validation for `rules.json` and `sources.json`.
+//!
+//! serde reports only the first problem and silently drops unknown fields, so a bad hand edit
+//! or evolution write used to surface as nothing more than "fell back to seed data". These
+//! checks walk the raw JSON against field tables mirroring [`PlanningRule`] and
+//! [`SourceRegistry`] and report every problem with its JSON path and a suggested fix.
+//! Unknown fields are warnings (serde ignores them); everything else is an error.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{PlanningRule, SourceRegistry};
+
+/// What is wrong at a [`SchemaError::path`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaProblem {
+    /// The document is not parseable JSON.
+    InvalidJson(String),
+    MissingField,
+    WrongType {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The id is already used by the entry at `first` (a JSON path).
+    DuplicateId {
+        id: String,
+        first: String,
+    },
+    /// A priority, score or weight outside its allowed range.
+    OutOfRange {
+        min: f64,
+        max: f64,
+        found: f64,
+    },
+    /// Not part of the schema. serde ignores it, so this is only a warning.
+    UnknownField,
+}
+
+/// One problem found in a planning JSON document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError {
+    /// JSON path, e.g. `$[3].scope.language` or `$.sources[0].quality_score`.
+    pub path: String,
+    pub problem: SchemaProblem,
+    pub suggestion: String,
+}
+
+impl SchemaError {
+    /// Warnings do not make a document invalid.
+    pub fn is_warning(&self) -> bool {
+        matches!(self.problem, SchemaProblem::UnknownField)
+    }
+}
+
+impl fmt::Display for SchemaProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(e) => write!(f, "invalid JSON: {}", e),
+            Self::MissingField => f.write_str("missing required field"),
+            Self::WrongType { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            Self::DuplicateId { id, first } => {
+                write!(f, "duplicate id `{}` (first used at {})", id, first)
+            }
+            Self::OutOfRange { min, max, found } => {
+                write!(f, "{} is outside {}..={}", found, min, max)
+            }
+            Self::UnknownField => f.write_str("unknown field"),
+        }
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_warning() {
+            f.write_str("warning: ")?;
+        }
+        write!(f, "{}: {} → {}", self.path, self.problem, self.suggestion)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    String,
+    Bool,
+    /// Non-negative integer up to the given maximum.
+    UInt(u64),
+    Number,
+    StringArray,
+    Object(&'static [Field]),
+    ObjectArray(&'static [Field]),
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::String => "string",
+            Kind::Bool => "boolean",
+            Kind::UInt(_) => "non-negative integer",
+            Kind::Number => "number",
+            Kind::StringArray => "array of strings",
+            Kind::Object(_) => "object",
+            Kind::ObjectArray(_) => "array of objects",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Field {
+    name: &'static str,
+    kind: Kind,
+    required: bool,
+    /// `Option` fields accept an explicit `null`.
+    nullable: bool,
+    range: Option<(f64, f64)>,
+}
+
+impl Field {
+    const fn new(name: &'static str, kind: Kind) -> Self {
+        Self {
+            name,
+            kind,
+            required: false,
+            nullable: false,
+            range: None,
+        }
+    }
+
+    const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    const fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+
+    const fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+}
+
+const U32: u64 = u32::MAX as u64;
+
+const SCOPE_FIELDS: &[Field] = &[
+    Field::new("workspace_glob", Kind::String).nullable(),
+    Field::new("language", Kind::String).nullable(),
+    Field::new("requires_skill", Kind::String).nullable(),
+    Field::new("task_keywords", Kind::StringArray),
+];
+
+const PROVENANCE_FIELDS: &[Field] = &[
+    Field::new("source_id", Kind::String).required(),
+    Field::new("source_url", Kind::String).required(),
+    Field::new("fetched_at", Kind::String).required(),
+    Field::new("content_hash", Kind::String).required(),
+];
+
+const RULE_FIELDS: &[Field] = &[
+    Field::new("id", Kind::String).required(),
+    Field::new("priority", Kind::UInt(U32)).range(0.0, 100.0),
+    Field::new("keywords", Kind::StringArray),
+    Field::new("context_keywords", Kind::StringArray),
+    Field::new("tool_hint", Kind::String).nullable(),
+    Field::new("instruction", Kind::String).required(),
+    Field::new("mutable", Kind::Bool),
+    Field::new("origin", Kind::String),
+    Field::new("reusable", Kind::Bool),
+    Field::new("effectiveness", Kind::Number)
+        .nullable()
+        .range(0.0, 1.0),
+    Field::new("trigger_count", Kind::UInt(U32)).nullable(),
+    Field::new("provenance", Kind::Object(PROVENANCE_FIELDS)).nullable(),
+    Field::new("scope", Kind::Object(SCOPE_FIELDS)).nullable(),
+];
+
+const FETCH_STATS_FIELDS: &[Field] = &[
+    Field::new("ts", Kind::String).required(),
+    Field::new("outcome", Kind::String).required(),
+    Field::new("bytes", Kind::UInt(u64::MAX)),
+    Field::new("items", Kind::UInt(u64::MAX)),
+    Field::new("duration_ms", Kind::UInt(u64::MAX)),
+    Field::new("truncated", Kind::Bool),
+];
+
+const SOURCE_FIELDS: &[Field] = &[
+    Field::new("id", Kind::String).required(),
+    Field::new("name", Kind::String).required(),
+    Field::new("url", Kind::String).required(),
+    Field::new("source_type", Kind::String).required(),
+    Field::new("parser", Kind::String).required(),
+    Field::new("region", Kind::String).required(),
+    Field::new("language", Kind::String).required(),
+    Field::new("domains", Kind::StringArray),
+    Field::new("quality_score", Kind::Number).range(0.0, 1.0),
+    Field::new("accessibility_score", Kind::Number).range(0.0, 1.0),
+    Field::new("rules_contributed", Kind::UInt(U32)),
+    Field::new("fetch_success_count", Kind::UInt(U32)),
+    Field::new("fetch_fail_count", Kind::UInt(U32)),
+    Field::new("last_fetched", Kind::String).nullable(),
+    Field::new("mutable", Kind::Bool),
+    Field::new("origin", Kind::String),
+    Field::new("enabled", Kind::Bool),
+    Field::new("etag", Kind::String).nullable(),
+    Field::new("last_modified", Kind::String).nullable(),
+    Field::new("budget_exceeded_streak", Kind::UInt(U32)),
+    Field::new("last_fetch", Kind::Object(FETCH_STATS_FIELDS)).nullable(),
+];
+
+const REGISTRY_FIELDS: &[Field] = &[
+    Field::new("version", Kind::UInt(U32)).required(),
+    Field::new("sources", Kind::ObjectArray(SOURCE_FIELDS)).required(),
+];
+
+/// Every problem in a `rules.json` document, warnings included.
+pub fn lint_rules(json: &str) -> Vec<SchemaError> {
+    match parse(json) {
+        Ok(value) => lint_rules_value(&value),
+        Err(e) => vec![e],
+    }
+}
+
+/// Every problem in a `sources.json` document, warnings included.
+pub fn lint_sources(json: &str) -> Vec<SchemaError> {
+    match parse(json) {
+        Ok(value) => lint_sources_value(&value),
+        Err(e) => vec![e],
+    }
+}
+
+/// Parse `rules.json`. On error, returns every problem found (warnings included); warnings
+/// alone do not fail validation.
+pub fn validate_rules(json: &str) -> Result<Vec<PlanningRule>, Vec<SchemaError>> {
+    let value = parse(json).map_err(|e| vec![e])?;
+    let problems = lint_rules_value(&value);
+    finish(problems, value)
+}
+
+/// Parse `sources.json` with the same contract as [`validate_rules`].
+pub fn validate_sources(json: &str) -> Result<SourceRegistry, Vec<SchemaError>> {
+    let value = parse(json).map_err(|e| vec![e])?;
+    let problems = lint_sources_value(&value);
+    finish(problems, value)
+}
+
+/// Problems in a single rule object (paths are relative to the rule, `$.priority`). Used to
+/// screen LLM-produced rules one by one before they are merged.
+pub fn validate_rule_value(rule: &Value) -> Vec<SchemaError> {
+    let mut out = Vec::new();
+    check_value(rule, Kind::Object(RULE_FIELDS), "$", &mut out);
+    out
+}
+
+fn parse(json: &str) -> Result<Value, SchemaError> {
+    serde_json::from_str(json).map_err(|e| SchemaError {
+        path: "$".to_string(),
+        problem: SchemaProblem::InvalidJson(e.to_string()),
+        suggestion: "fix the syntax at the reported line/column, or delete the file to fall back to the seed data".to_string(),
+    })
+}
+
+fn lint_rules_value(value: &Value) -> Vec<SchemaError> {
+    let mut out = Vec::new();
+    match value.as_array() {
+        Some(items) => {
+            for (i, item) in items.iter().enumerate() {
+                check_value(
+                    item,
+                    Kind::Object(RULE_FIELDS),
+                    &format!("$[{}]", i),
+                    &mut out,
+                );
+            }
+            check_unique_ids(items, "$", &mut out);
+        }
+        None => out.push(wrong_type("$", "array of rules", value)),
+    }
+    out
+}
+
+fn lint_sources_value(value: &Value) -> Vec<SchemaError> {
+    let mut out = Vec::new();
+    check_value(value, Kind::Object(REGISTRY_FIELDS), "$", &mut out);
+    if let Some(sources) = value.get("sources").and_then(Value::as_array) {
+        check_unique_ids(sources, "$.sources", &mut out);
+    }
+    out
+}
+
+fn finish<T: DeserializeOwned>(
+    problems: Vec<SchemaError>,
+    value: Value,
+) -> Result<T, Vec<SchemaError>> {
+    if problems.iter().any(|p| !p.is_warning()) {
+        return Err(problems);
+    }
+    // The field tables mirror the serde types, so this only fails if they drift apart.
+    serde_json::from_value(value).map_err(|e| {
+        vec![SchemaError {
+            path: "$".to_string(),
+            problem: SchemaProblem::InvalidJson(e.to_string()),
+            suggestion: "compare the document with the seed file".to_string(),
+        }]
+    })
+}
+
+fn check_value(value: &Value, kind: Kind, path: &str, out: &mut Vec<SchemaError>) {
+    match kind {
+        Kind::String if value.is_string() => {}
+        Kind::Bool if value.is_boolean() => {}
+        Kind::Number if value.is_number() => {}
+        Kind::UInt(max) => match value.as_u64() {
+            Some(n) if n > max => out.push(SchemaError {
+                path: path.to_string(),
+                problem: SchemaProblem::OutOfRange {
+                    min: 0.0,
+                    max: max as f64,
+                    found: n as f64,
+                },
+                suggestion: format!("use a value between 0 and {}", max),
+            }),
+            Some(_) => {}
+            None => out.push(wrong_type(path, kind.name(), value)),
+        },
+        Kind::StringArray => match value.as_array() {
+            Some(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if !item.is_string() {
+                        out.push(wrong_type(&format!("{}[{}]", path, i), "string", item));
+                    }
+                }
+            }
+            None => out.push(wrong_type(path, kind.name(), value)),
+        },
+        Kind::Object(fields) => match value.as_object() {
+            Some(map) => check_object(map, fields, path, out),
+            None => out.push(wrong_type(path, kind.name(), value)),
+        },
+        Kind::ObjectArray(fields) => match value.as_array() {
+            Some(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    check_value(item, Kind::Object(fields), &format!("{}[{}]", path, i), out);
+                }
+            }
+            None => out.push(wrong_type(path, kind.name(), value)),
+        },
+        _ => out.push(wrong_type(path, kind.name(), value)),
+    }
+}
+
+fn check_object(
+    map: &serde_json::Map<String, Value>,
+    fields: &[Field],
+    path: &str,
+    out: &mut Vec<SchemaError>,
+) {
+    for field in fields {
+        let field_path = format!("{}.{}", path, field.name);
+        let Some(value) = map.get(field.name) else {
+            if field.required {
+                out.push(SchemaError {
+                    path: field_path,
+                    problem: SchemaProblem::MissingField,
+                    suggestion: format!("add `{}` ({})", field.name, field.kind.name()),
+                });
+            }
+            continue;
+        };
+        if value.is_null() && field.nullable {
+            continue;
+        }
+        let before = out.len();
+        check_value(value, field.kind, &field_path, out);
+        if out.len() > before {
+            continue;
+        }
+        if let (Some((min, max)), Some(found)) = (field.range, value.as_f64()) {
+            if found < min || found > max {
+                out.push(SchemaError {
+                    path: field_path,
+                    problem: SchemaProblem::OutOfRange { min, max, found },
+                    suggestion: format!("use a value between {} and {}", min, max),
+                });
+            }
+        }
+    }
+    for key in map.keys() {
+        if fields.iter().any(|f| f.name == key) {
+            continue;
+        }
+        let suggestion = match closest_field(key, fields) {
+            Some(name) => format!("did you mean `{}`?", name),
+            None => "remove it; it is ignored when loading".to_string(),
+        };
+        out.push(SchemaError {
+            path: format!("{}.{}", path, key),
+            problem: SchemaProblem::UnknownField,
+            suggestion,
+        });
+    }
+}
+
+fn check_unique_ids(items: &[Value], path: &str, out: &mut Vec<SchemaError>) {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (i, item) in items.iter().enumerate() {
+        let Some(id) = item.get("id").and_then(Value::as_str) else {
+            continue;
+        };
+        if let Some(first) = seen.get(id) {
+            out.push(SchemaError {
+                path: format!("{}[{}].id", path, i),
+                problem: SchemaProblem::DuplicateId {
+                    id: id.to_string(),
+                    first: format!("{}[{}]", path, first),
+                },
+                suggestion: "give this entry a unique id or remove one of the copies".to_string(),
+            });
+        } else {
+            seen.insert(id, i);
+        }
+    }
+}
+
+fn wrong_type(path: &str, expected: &'static str, found: &Value) -> SchemaError {
+    let found = match found {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() => "negative integer",
+        Value::Number(n) if n.is_f64() => "fractional number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+    SchemaError {
+        path: path.to_string(),
+        problem: SchemaProblem::WrongType { expected, found },
+        suggestion: format!("change the value to a {}", expected),
+    }
+}
+
+/// Known field within edit distance 2 of `key`, for "did you mean" hints.
+fn closest_field(key: &str, fields: &[Field]) -> Option<&'static str> {
+    fields
+        .iter()
+        .map(|f| (edit_distance(key, f.name), f.name))
+        .filter(|(d, _)| *d <= 2)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, name)| name)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(problem: &SchemaProblem) -> &'static str {
+        match problem {
+            SchemaProblem::InvalidJson(_) => "invalid_json",
+            SchemaProblem::MissingField => "missing",
+            SchemaProblem::WrongType { .. } => "wrong_type",
+            SchemaProblem::DuplicateId { .. } => "duplicate_id",
+            SchemaProblem::OutOfRange { .. } => "out_of_range",
+            SchemaProblem::UnknownField => "unknown_field",
+        }
+    }
+
+    fn assert_reports(label: &str, problems: &[SchemaError], path: &str, expected: &str) {
+        assert!(
+            problems
+                .iter()
+                .any(|p| p.path == path && kind(&p.problem) == expected),
+            "{}: expected {} at {}, got {:?}",
+            label,
+            expected,
+            path,
+            problems
+        );
+    }
+
+    /// (label, rules.json, path, problem kind)
+    const RULE_CASES: &[(&str, &str, &str, &str)] = &[
+        ("syntax", r#"[{"id": "a""#, "$", "invalid_json"),
+        (
+            "root type",
+            r#"{"id": "a", "instruction": "x"}"#,
+            "$",
+            "wrong_type",
+        ),
+        (
+            "missing id",
+            r#"[{"instruction": "x"}]"#,
+            "$[0].id",
+            "missing",
+        ),
+        (
+            "missing instruction",
+            r#"[{"id": "a"}]"#,
+            "$[0].instruction",
+            "missing",
+        ),
+        (
+            "keywords not array",
+            r#"[{"id": "a", "instruction": "x", "keywords": "a,b"}]"#,
+            "$[0].keywords",
+            "wrong_type",
+        ),
+        (
+            "keyword not string",
+            r#"[{"id": "a", "instruction": "x", "keywords": ["a", 1]}]"#,
+            "$[0].keywords[1]",
+            "wrong_type",
+        ),
+        (
+            "priority as string",
+            r#"[{"id": "a", "instruction": "x", "priority": "high"}]"#,
+            "$[0].priority",
+            "wrong_type",
+        ),
+        (
+            "negative trigger count",
+            r#"[{"id": "a", "instruction": "x", "trigger_count": -1}]"#,
+            "$[0].trigger_count",
+            "wrong_type",
+        ),
+        (
+            "nested scope type",
+            r#"[{"id": "a", "instruction": "x", "scope": {"language": 3}}]"#,
+            "$[0].scope.language",
+            "wrong_type",
+        ),
+        (
+            "provenance missing hash",
+            r#"[{"id": "a", "instruction": "x", "provenance":
+                {"source_id": "s", "source_url": "u", "fetched_at": "t"}}]"#,
+            "$[0].provenance.content_hash",
+            "missing",
+        ),
+        (
+            "duplicate id",
+            r#"[{"id": "a", "instruction": "x"}, {"id": "a", "instruction": "y"}]"#,
+            "$[1].id",
+            "duplicate_id",
+        ),
+        (
+            "priority above 100",
+            r#"[{"id": "a", "instruction": "x", "priority": 150}]"#,
+            "$[0].priority",
+            "out_of_range",
+        ),
+        (
+            "effectiveness above 1",
+            r#"[{"id": "a", "instruction": "x", "effectiveness": 1.5}]"#,
+            "$[0].effectiveness",
+            "out_of_range",
+        ),
+        (
+            "unknown field",
+            r#"[{"id": "a", "instruction": "x", "prority": 10}]"#,
+            "$[0].prority",
+            "unknown_field",
+        ),
+    ];
+
+    /// (label, sources.json, path, problem kind)
+    const SOURCE_CASES: &[(&str, &str, &str, &str)] = &[
+        ("syntax", r#"{"version": 1,"#, "$", "invalid_json"),
+        (
+            "missing version",
+            r#"{"sources": []}"#,
+            "$.version",
+            "missing",
+        ),
+        (
+            "sources not array",
+            r#"{"version": 1, "sources": {}}"#,
+            "$.sources",
+            "wrong_type",
+        ),
+        (
+            "source missing url",
+            r#"{"version": 1, "sources": [{"id": "a", "name": "A", "source_type": "rss",
+                "parser": "rss", "region": "global", "language": "en"}]}"#,
+            "$.sources[0].url",
+            "missing",
+        ),
+        (
+            "quality score above 1",
+            r#"{"version": 1, "sources": [{"id": "a", "name": "A", "url": "https://a",
+                "source_type": "rss", "parser": "rss", "region": "global", "language": "en",
+                "quality_score": 1.2}]}"#,
+            "$.sources[0].quality_score",
+            "out_of_range",
+        ),
+        (
+            "fetch stats missing outcome",
+            r#"{"version": 1, "sources": [{"id": "a", "name": "A", "url": "https://a",
+                "source_type": "rss", "parser": "rss", "region": "global", "language": "en",
+                "last_fetch": {"ts": "2026-01-01T00:00:00Z"}}]}"#,
+            "$.sources[0].last_fetch.outcome",
+            "missing",
+        ),
+        (
+            "duplicate source id",
+            r#"{"version": 1, "sources": [
+                {"id": "a", "name": "A", "url": "https://a", "source_type": "rss",
+                 "parser": "rss", "region": "global", "language": "en"},
+                {"id": "a", "name": "B", "url": "https://b", "source_type": "rss",
+                 "parser": "rss", "region": "global", "language": "en"}]}"#,
+            "$.sources[1].id",
+            "duplicate_id",
+        ),
+        (
+            "unknown top-level field",
+            r#"{"version": 1, "sources": [], "extra": true}"#,
+            "$.extra",
+            "unknown_field",
+        ),
+    ];
+
+    #[test]
+    fn rule_fixtures_report_each_error_class() {
+        for (label, json, path, expected) in RULE_CASES {
+            assert_reports(label, &lint_rules(json), path, expected);
+            let result = validate_rules(json);
+            if *expected == "unknown_field" {
+                assert!(result.is_ok(), "{}: warnings alone must not fail", label);
+            } else {
+                let errors = result.expect_err(label);
+                assert_reports(label, &errors, path, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn source_fixtures_report_each_error_class() {
+        for (label, json, path, expected) in SOURCE_CASES {
+            assert_reports(label, &lint_sources(json), path, expected);
+            let result = validate_sources(json);
+            if *expected == "unknown_field" {
+                assert!(result.is_ok(), "{}: warnings alone must not fail", label);
+            } else {
+                let errors = result.expect_err(label);
+                assert_reports(label, &errors, path, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn well_formed_documents_have_no_problems() {
+        let rules = r#"[{"id": "a", "instruction": "x", "priority": 65, "tool_hint": null,
+            "effectiveness": 0.8, "scope": {"language": "rust", "task_keywords": ["ci"]}}]"#;
+        assert_eq!(lint_rules(rules), []);
+        assert_eq!(validate_rules(rules).unwrap()[0].priority, 65);
+
+        let sources = r#"{"version": 1, "sources": [{"id": "a", "name": "A",
+            "url": "https://a", "source_type": "rss", "parser": "rss", "region": "global",
+            "language": "en", "quality_score": 0.7, "last_fetched": null}]}"#;
+        assert_eq!(lint_sources(sources), []);
+        assert_eq!(validate_sources(sources).unwrap().sources.len(), 1);
+    }
+
+    #[test]
+    fn suggestions_name_the_fix() {
+        let problems = lint_rules(r#"[{"id": "a", "instruction": "x", "prority": 10}]"#);
+        assert_eq!(problems[0].suggestion, "did you mean `priority`?");
+        assert!(problems[0].to_string().starts_with("warning: $[0].prority"));
+
+        let problems = lint_rules(r#"[{"id": "a", "instruction": "x", "priority": 150}]"#);
+        assert_eq!(
+            problems[0].problem,
+            SchemaProblem::OutOfRange {
+                min: 0.0,
+                max: 100.0,
+                found: 150.0
+            }
+        );
+        assert_eq!(problems[0].suggestion, "use a value between 0 and 100");
+
+        let problems =
+            lint_rules(r#"[{"id": "a", "instruction": "x"}, {"id": "a", "instruction": "y"}]"#);
+        assert_eq!(
+            problems[0].problem,
+            SchemaProblem::DuplicateId {
+                id: "a".to_string(),
+                first: "$[0]".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn single_rule_value_paths_are_relative() {
+        let rule = serde_json::json!({"id": "a", "instruction": 3, "confidence": 0.9});
+        let problems = validate_rule_value(&rule);
+        assert_reports("rule value", &problems, "$.instruction", "wrong_type");
+        assert_reports("rule value", &problems, "$.confidence", "unknown_field");
+    }
+}
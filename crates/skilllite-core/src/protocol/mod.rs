@@ -3,9 +3,14 @@
 //! These types are the shared "currency" across stdio_rpc, agent_chat, MCP,
 //! and the future P2P layer. They intentionally carry only what a remote peer
 //! (or routing layer) needs — not full agent internals.
+//!
+//! The `initialize` handshake and `strict` field checks shared by the stdio
+//! transports live in [`negotiation`].
 
 use serde::{Deserialize, Serialize};
 
+pub mod negotiation;
+
 // ─── Input types (NodeTask, NodeContext) ─────────────────────────────────────
 
 /// Execution context attached to every [`NodeTask`].
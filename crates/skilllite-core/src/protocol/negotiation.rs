@@ -0,0 +1,192 @@
+//! Version handshake and capability negotiation for stdio RPC transports.
+//!
+//! Every transport (`serve --stdio`, `agent-rpc`, future ones) answers an `initialize`
+//! request with a [`ServerInfo`]: crate version, [`version::PROTOCOL_VERSION`], the methods it
+//! serves with their known params, and the feature flags compiled into the binary. Clients
+//! compare these before relying on a method or param instead of failing silently against an
+//! older binary.
+//!
+//! Unknown params are ignored by default. A request may set the top-level option
+//! `"strict": true` (every sent param is required) or `"strict": ["mode", ...]` (only the
+//! listed params are required); [`check_strict`] then rejects required params the method
+//! does not know.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Protocol versioning constants.
+///
+/// Bump policy for [`PROTOCOL_VERSION`](version::PROTOCOL_VERSION):
+/// - **Bump** when a method is removed or renamed, a param becomes required, a param or
+///   response field changes meaning/type, or an event is removed from a stream.
+/// - **Do not bump** for additive changes — a new method, a new optional param, a new
+///   response field, or a new feature flag. Those are visible through `methods` / `features`
+///   in the `initialize` result, which clients should check instead.
+pub mod version {
+    /// Wire protocol version shared by `serve --stdio` and `agent-rpc`.
+    pub const PROTOCOL_VERSION: u32 = 1;
+}
+
+/// Method name of the handshake request.
+pub const INITIALIZE_METHOD: &str = "initialize";
+/// Top-level request option enabling [`check_strict`].
+pub const STRICT_OPTION: &str = "strict";
+
+/// One method served by a transport and the params it understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MethodSpec {
+    pub name: &'static str,
+    pub params: &'static [&'static str],
+}
+
+impl MethodSpec {
+    pub const fn new(name: &'static str, params: &'static [&'static str]) -> Self {
+        Self { name, params }
+    }
+}
+
+/// `initialize` result payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ServerInfo {
+    /// Transport name (`"stdio-rpc"`, `"agent-rpc"`).
+    pub server: String,
+    /// Version of the `skilllite` binary.
+    pub version: String,
+    pub protocol_version: u32,
+    pub methods: Vec<MethodSpec>,
+    /// Cargo features compiled in (e.g. `agent`, `executor`, `audit`).
+    pub features: Vec<String>,
+}
+
+impl ServerInfo {
+    pub fn new(
+        server: impl Into<String>,
+        version: impl Into<String>,
+        methods: Vec<MethodSpec>,
+        features: Vec<String>,
+    ) -> Self {
+        Self {
+            server: server.into(),
+            version: version.into(),
+            protocol_version: version::PROTOCOL_VERSION,
+            methods,
+            features,
+        }
+    }
+
+    pub fn method(&self, name: &str) -> Option<&MethodSpec> {
+        self.methods.iter().find(|m| m.name == name)
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+/// Reject required-but-unknown params for `request` (see the module docs).
+///
+/// Returns `Ok` when the request is not strict or names a method not in `info` (the
+/// dispatcher reports unknown methods itself).
+pub fn check_strict(info: &ServerInfo, method: &str, request: &Value) -> Result<(), String> {
+    let Some(spec) = info.method(method) else {
+        return Ok(());
+    };
+    let required: Vec<&str> = match request.get(STRICT_OPTION) {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => return Ok(()),
+        Some(Value::Bool(true)) => request
+            .get("params")
+            .and_then(Value::as_object)
+            .map(|p| p.keys().map(String::as_str).collect())
+            .unwrap_or_default(),
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        Some(other) => {
+            return Err(format!(
+                "'{}' must be a boolean or an array of param names, got {}",
+                STRICT_OPTION, other
+            ))
+        }
+    };
+    let mut unknown: Vec<&str> = required
+        .into_iter()
+        .filter(|name| !spec.params.contains(name))
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    unknown.sort_unstable();
+    Err(format!(
+        "Method {} does not support required param(s): {} (protocol_version {})",
+        method,
+        unknown.join(", "),
+        version::PROTOCOL_VERSION
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn info() -> ServerInfo {
+        ServerInfo::new(
+            "stdio-rpc",
+            "0.1.0",
+            vec![
+                MethodSpec::new("memory_search", &["query", "limit"]),
+                MethodSpec::new(INITIALIZE_METHOD, &[]),
+            ],
+            vec!["agent".to_string()],
+        )
+    }
+
+    #[test]
+    fn initialize_payload_round_trips() {
+        let payload = info().to_json();
+        assert_eq!(
+            payload,
+            json!({
+                "server": "stdio-rpc",
+                "version": "0.1.0",
+                "protocol_version": version::PROTOCOL_VERSION,
+                "methods": [
+                    {"name": "memory_search", "params": ["query", "limit"]},
+                    {"name": "initialize", "params": []}
+                ],
+                "features": ["agent"]
+            })
+        );
+        let text = serde_json::to_string(&payload).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), payload);
+    }
+
+    #[test]
+    fn non_strict_requests_ignore_unknown_params() {
+        let req = json!({"method": "memory_search", "params": {"query": "x", "mode": "hybrid"}});
+        assert!(check_strict(&info(), "memory_search", &req).is_ok());
+    }
+
+    #[test]
+    fn strict_true_rejects_any_unknown_param() {
+        let req = json!({"strict": true, "params": {"query": "x", "mode": "hybrid"}});
+        let err = check_strict(&info(), "memory_search", &req).unwrap_err();
+        assert!(err.contains("mode"), "{err}");
+        let ok = json!({"strict": true, "params": {"query": "x", "limit": 3}});
+        assert!(check_strict(&info(), "memory_search", &ok).is_ok());
+    }
+
+    #[test]
+    fn strict_list_only_requires_named_params() {
+        let optional = json!({"strict": ["query"], "params": {"query": "x", "mode": "hybrid"}});
+        assert!(check_strict(&info(), "memory_search", &optional).is_ok());
+        let required = json!({"strict": ["mode"], "params": {"query": "x", "mode": "hybrid"}});
+        assert!(check_strict(&info(), "memory_search", &required).is_err());
+        let bad = json!({"strict": "yes"});
+        assert!(check_strict(&info(), "memory_search", &bad).is_err());
+    }
+
+    #[test]
+    fn unknown_methods_are_left_to_the_dispatcher() {
+        let req = json!({"strict": true, "params": {"a": 1}});
+        assert!(check_strict(&info(), "nope", &req).is_ok());
+    }
+}
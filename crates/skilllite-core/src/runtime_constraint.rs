@@ -0,0 +1,245 @@
+//! Python / Node version constraints declared in a skill's `compatibility` field.
+//!
+//! Recognised forms (case-insensitive):
+//!
+//! - `python>=3.11`, `python >= 3.9, < 3.13`, `python3.11`, `python ~= 3.10`
+//! - `Requires Python 3.10+`, `Python 3.10 or newer` (a bare version is a minimum;
+//!   a bare major alone, as in `Requires Python 3.x`, is not a constraint)
+//! - `node>=18`, `Node.js 20+`, `nodejs ^18.17`
+
+use std::fmt;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+static CONSTRAINT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)
+        \b(?P<lang>python(?:3\b)?|node(?:\.?js)?)\s*(?:version\s*)?
+        (?P<spec>
+            (?:(?:>=|<=|==|!=|~=|>|<|\^|~)\s*)?v?\d+(?:\.\d+){0,2}\+?
+            (?:\s*,\s*(?:>=|<=|==|!=|>|<)\s*v?\d+(?:\.\d+){0,2})*
+        )
+        (?P<newer>\s+or\s+(?:newer|later|higher|above))?",
+    )
+    .expect("CONSTRAINT_RE is valid")
+});
+
+static COMPARATOR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?P<op>>=|<=|==|!=|~=|>|<|\^|~)?\s*v?(?P<ver>\d+(?:\.\d+){0,2})(?P<plus>\+)?")
+        .expect("COMPARATOR_RE is valid")
+});
+
+/// Comparison operator of a single [`Comparator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Ge,
+    Gt,
+    Le,
+    Lt,
+    /// Matches every version starting with the given components (`==3.11` matches 3.11.4)
+    Eq,
+    Ne,
+}
+
+/// One `op version` term; `version` holds only the components that were written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparator {
+    pub op: Op,
+    pub version: Vec<u32>,
+}
+
+impl Comparator {
+    fn matches(&self, actual: (u32, u32, u32)) -> bool {
+        let actual = [actual.0, actual.1, actual.2];
+        let n = self.version.len().min(3);
+        let mut wanted = [0u32; 3];
+        wanted[..n].copy_from_slice(&self.version[..n]);
+        let prefix_eq = actual[..n] == wanted[..n];
+        match self.op {
+            Op::Ge => actual >= wanted,
+            Op::Gt => actual > wanted && !prefix_eq,
+            Op::Le => actual <= wanted || prefix_eq,
+            Op::Lt => actual < wanted,
+            Op::Eq => prefix_eq,
+            Op::Ne => !prefix_eq,
+        }
+    }
+}
+
+/// A version requirement for one runtime ("python" or "node").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeConstraint {
+    pub language: String,
+    pub comparators: Vec<Comparator>,
+}
+
+impl RuntimeConstraint {
+    /// `true` when `version` (major, minor, patch) satisfies every comparator.
+    pub fn matches(&self, version: (u32, u32, u32)) -> bool {
+        self.comparators.iter().all(|c| c.matches(version))
+    }
+}
+
+impl fmt::Display for RuntimeConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms: Vec<String> = self
+            .comparators
+            .iter()
+            .map(|c| {
+                let op = match c.op {
+                    Op::Ge => ">=",
+                    Op::Gt => ">",
+                    Op::Le => "<=",
+                    Op::Lt => "<",
+                    Op::Eq => "==",
+                    Op::Ne => "!=",
+                };
+                let v: Vec<String> = c.version.iter().map(u32::to_string).collect();
+                format!("{}{}", op, v.join("."))
+            })
+            .collect();
+        write!(f, "{}{}", self.language, terms.join(","))
+    }
+}
+
+/// Constraint for `language` ("python" / "node") in a compatibility string, if any.
+/// When several mention the same runtime, their comparators are combined.
+pub fn parse_runtime_constraint(compatibility: &str, language: &str) -> Option<RuntimeConstraint> {
+    let mut comparators = Vec::new();
+    for cap in CONSTRAINT_RE.captures_iter(compatibility) {
+        let lang = if cap["lang"].to_lowercase().starts_with("python") {
+            "python"
+        } else {
+            "node"
+        };
+        if lang != language {
+            continue;
+        }
+        comparators.extend(parse_spec(&cap["spec"], cap.name("newer").is_some()));
+    }
+    (!comparators.is_empty()).then(|| RuntimeConstraint {
+        language: language.to_string(),
+        comparators,
+    })
+}
+
+fn parse_spec(spec: &str, or_newer: bool) -> Vec<Comparator> {
+    let mut out = Vec::new();
+    for cap in COMPARATOR_RE.captures_iter(spec) {
+        let version: Vec<u32> = cap["ver"]
+            .split('.')
+            .filter_map(|p| p.parse().ok())
+            .collect();
+        let op = cap.name("op").map(|m| m.as_str()).unwrap_or("");
+        if op.is_empty() && cap.name("plus").is_none() && !or_newer && version.len() < 2 {
+            continue;
+        }
+        match op {
+            "" | ">=" => out.push(Comparator {
+                op: Op::Ge,
+                version,
+            }),
+            ">" => out.push(Comparator {
+                op: Op::Gt,
+                version,
+            }),
+            "<=" => out.push(Comparator {
+                op: Op::Le,
+                version,
+            }),
+            "<" => out.push(Comparator {
+                op: Op::Lt,
+                version,
+            }),
+            "==" => out.push(Comparator {
+                op: Op::Eq,
+                version,
+            }),
+            "!=" => out.push(Comparator {
+                op: Op::Ne,
+                version,
+            }),
+            // ^X.Y: same major; ~X.Y: same minor; ~=X.Y: same major, ~=X.Y.Z: same minor
+            "^" | "~" | "~=" => {
+                let bump_at = match op {
+                    "^" => 0,
+                    "~" => 1.min(version.len() - 1),
+                    _ => version.len().saturating_sub(2),
+                };
+                let mut upper: Vec<u32> = version[..=bump_at].to_vec();
+                upper[bump_at] += 1;
+                out.push(Comparator {
+                    op: Op::Ge,
+                    version,
+                });
+                out.push(Comparator {
+                    op: Op::Lt,
+                    version: upper,
+                });
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(compat: &str, lang: &str) -> RuntimeConstraint {
+        parse_runtime_constraint(compat, lang).expect("constraint")
+    }
+
+    #[test]
+    fn parses_common_forms() {
+        assert_eq!(c("python>=3.11", "python").to_string(), "python>=3.11");
+        assert_eq!(
+            c("Requires Python 3.10+ and network access", "python").to_string(),
+            "python>=3.10"
+        );
+        assert_eq!(
+            c("Python 3.9 or newer", "python").to_string(),
+            "python>=3.9"
+        );
+        assert_eq!(
+            c("python >= 3.9, < 3.13", "python").to_string(),
+            "python>=3.9,<3.13"
+        );
+        assert_eq!(c("python3.11", "python").to_string(), "python>=3.11");
+        assert_eq!(c("Node.js 20+", "node").to_string(), "node>=20");
+        assert_eq!(c("nodejs ^18.17", "node").to_string(), "node>=18.17,<19");
+        assert_eq!(c("python ~= 3.10", "python").to_string(), "python>=3.10,<4");
+    }
+
+    #[test]
+    fn language_filter_and_absence() {
+        let compat = "Requires python>=3.11 and node>=18";
+        assert_eq!(c(compat, "python").to_string(), "python>=3.11");
+        assert_eq!(c(compat, "node").to_string(), "node>=18");
+        assert!(parse_runtime_constraint("Requires Python, network access", "python").is_none());
+        assert!(parse_runtime_constraint("Requires Python 3.x", "python").is_none());
+        assert!(parse_runtime_constraint("python>=3.11", "node").is_none());
+    }
+
+    #[test]
+    fn matching_semantics() {
+        let ge = c("python>=3.11", "python");
+        assert!(ge.matches((3, 11, 0)));
+        assert!(ge.matches((3, 12, 1)));
+        assert!(!ge.matches((3, 9, 18)));
+
+        let range = c("python>=3.9,<3.13", "python");
+        assert!(range.matches((3, 12, 7)));
+        assert!(!range.matches((3, 13, 0)));
+
+        let eq = c("python==3.11", "python");
+        assert!(eq.matches((3, 11, 9)));
+        assert!(!eq.matches((3, 12, 0)));
+
+        let le = c("node<=20", "node");
+        assert!(le.matches((20, 11, 0)));
+        assert!(!le.matches((21, 0, 0)));
+    }
+}
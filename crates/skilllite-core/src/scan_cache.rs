@@ -1,7 +1,13 @@
 //! A3: LLM admission scan result cache.
 //!
-//! Persists scan results to ~/.skilllite/scan-cache.json. Key = content_hash (SHA256 of
-//! skill_md + script_samples). Same hash within TTL avoids redundant LLM calls.
+//! Persists scan results to ~/.skilllite/scan-cache.json. Key = [`skill_digest`]: SHA-256 over
+//! the skill's sorted relative file list and the SHA-256 of every file's contents, so any edit
+//! (even one that keeps mtime and size) produces a new key. Each entry also records the
+//! scanner version; entries written by another SkillLite version are ignored. Same key within
+//! TTL avoids redundant LLM calls.
+//!
+//! Files written by older releases (a bare `{hash: entry}` map keyed on sampled script
+//! prefixes) are dropped on first access; see [`migrate_legacy_cache`].
 //!
 //! ## Concurrency safety
 //!
@@ -13,94 +19,218 @@
 //! the losing entry will simply be recomputed on the next miss.
 
 use crate::{Error, Result};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const CACHE_TTL_SECS: u64 = 300;
 const CACHE_FILENAME: &str = "scan-cache.json";
 
+/// On-disk layout version; files without it (or with another value) are discarded.
+const CACHE_FORMAT_VERSION: u32 = 2;
+
+/// Verdicts cached by another scanner version are never trusted.
+pub const SCANNER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Directories that never contribute to a skill's digest.
+const SKIPPED_DIRS: &[&str] = &[".git"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CachedEntry {
     risk: String,
     reason: String,
     timestamp: u64,
+    scanner_version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    format: u32,
+    entries: HashMap<String, CachedEntry>,
+}
+
+impl Default for CacheFile {
+    fn default() -> Self {
+        Self {
+            format: CACHE_FORMAT_VERSION,
+            entries: HashMap::new(),
+        }
+    }
 }
 
 fn cache_path() -> PathBuf {
     crate::paths::data_root().join(CACHE_FILENAME)
 }
 
-/// Compute SHA256 hash of content for cache key.
-pub fn content_hash(skill_md: &str, script_samples: &str) -> String {
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Content digest of a skill directory, used as the cache key.
+///
+/// Files are listed recursively (symlinks are hashed by their target path, not followed),
+/// sorted by `/`-separated relative path, and hashed in parallel.
+pub fn skill_digest(skill_dir: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    collect_files(skill_dir, skill_dir, &mut files)?;
+    files.sort();
+
+    let hashed: Vec<(String, String)> = files
+        .par_iter()
+        .map(|rel| -> Result<(String, String)> {
+            let path = skill_dir.join(rel);
+            let meta = fs::symlink_metadata(&path)?;
+            let bytes = if meta.file_type().is_symlink() {
+                fs::read_link(&path)?
+                    .to_string_lossy()
+                    .into_owned()
+                    .into_bytes()
+            } else {
+                fs::read(&path)?
+            };
+            Ok((rel.clone(), hex::encode(Sha256::digest(&bytes))))
+        })
+        .collect::<Result<_>>()?;
+
     let mut hasher = Sha256::new();
-    hasher.update(skill_md.as_bytes());
-    hasher.update(script_samples.as_bytes());
-    hex::encode(hasher.finalize())
+    for (rel, file_hash) in &hashed {
+        hasher.update(rel.as_bytes());
+        hasher.update([0]);
+        hasher.update(file_hash.as_bytes());
+        hasher.update([b'\n']);
+    }
+    Ok(hex::encode(hasher.finalize()))
 }
 
-/// Look up cached LLM admission result. Returns (risk, reason) if found and not expired.
-pub fn get_cached(content_hash: &str) -> Result<Option<(String, String)>> {
-    let path = cache_path();
-    if !path.exists() {
-        return Ok(None);
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()) {
+                continue;
+            }
+            collect_files(root, &path, out)?;
+        } else if let Ok(rel) = path.strip_prefix(root) {
+            let rel: Vec<String> = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            out.push(rel.join("/"));
+        }
     }
-    let content =
-        fs::read_to_string(&path).map_err(|e| Error::validation(format!("read cache: {}", e)))?;
-    let map: HashMap<String, CachedEntry> = serde_json::from_str(&content).unwrap_or_default();
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
+    Ok(())
+}
+
+/// Drop a cache file written in the pre-digest format. Returns `true` if one was removed.
+pub fn migrate_legacy_cache() -> bool {
+    migrate_legacy_cache_at(&cache_path())
+}
+
+fn migrate_legacy_cache_at(path: &Path) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+    let current = serde_json::from_str::<CacheFile>(&content)
+        .map(|f| f.format == CACHE_FORMAT_VERSION)
+        .unwrap_or(false);
+    if current {
+        return false;
+    }
+    tracing::info!("Dropping legacy scan cache {}", path.display());
+    fs::remove_file(path).is_ok()
+}
+
+fn load(path: &Path) -> CacheFile {
+    migrate_legacy_cache_at(path);
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
         .unwrap_or_default()
-        .as_secs();
-    if let Some(entry) = map.get(content_hash) {
-        if now.saturating_sub(entry.timestamp) < CACHE_TTL_SECS {
-            return Ok(Some((entry.risk.clone(), entry.reason.clone())));
-        }
+}
+
+/// Look up cached LLM admission result. Returns (risk, reason) if found, not expired and
+/// written by this scanner version.
+pub fn get_cached(digest: &str) -> Result<Option<(String, String)>> {
+    Ok(get_cached_at(&cache_path(), digest))
+}
+
+fn get_cached_at(path: &Path, digest: &str) -> Option<(String, String)> {
+    let file = load(path);
+    let entry = file.entries.get(digest)?;
+    let fresh = now_secs().saturating_sub(entry.timestamp) < CACHE_TTL_SECS;
+    (fresh && entry.scanner_version == SCANNER_VERSION)
+        .then(|| (entry.risk.clone(), entry.reason.clone()))
+}
+
+/// Whether any entry (fresh or not) is stored under `digest`.
+pub fn contains(digest: &str) -> bool {
+    load(&cache_path()).entries.contains_key(digest)
+}
+
+/// Drop the entry for `digest` (e.g. when the skill is uninstalled). Returns `true` if one
+/// was removed; the file is left untouched otherwise.
+pub fn remove_cached(digest: &str) -> Result<bool> {
+    remove_cached_at(&cache_path(), digest)
+}
+
+fn remove_cached_at(path: &Path, digest: &str) -> Result<bool> {
+    let mut file = load(path);
+    if file.entries.remove(digest).is_none() {
+        return Ok(false);
     }
-    Ok(None)
+    write_atomic(path, &file)?;
+    Ok(true)
 }
 
 /// Store LLM admission result in cache.
 ///
 /// Uses an atomic write (temp file + rename) to prevent concurrent processes
 /// from producing a partially-written / corrupt cache file.
-pub fn put_cached(content_hash: &str, risk: &str, reason: &str) -> Result<()> {
-    let path = cache_path();
-    let parent = path.parent().unwrap_or(path.as_path());
+pub fn put_cached(digest: &str, risk: &str, reason: &str) -> Result<()> {
+    put_cached_at(&cache_path(), digest, risk, reason)
+}
+
+fn put_cached_at(path: &Path, digest: &str, risk: &str, reason: &str) -> Result<()> {
+    let parent = path.parent().unwrap_or(path);
     if !parent.exists() {
         fs::create_dir_all(parent)?;
     }
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-    let mut map: HashMap<String, CachedEntry> = if path.exists() {
-        let content = fs::read_to_string(&path).unwrap_or_default();
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        HashMap::new()
-    };
-    // Evict expired entries before adding
-    map.retain(|_, e| now.saturating_sub(e.timestamp) < CACHE_TTL_SECS);
-    map.insert(
-        content_hash.to_string(),
+    let now = now_secs();
+    let mut file = load(path);
+    // Evict expired and other-version entries before adding
+    file.entries.retain(|_, e| {
+        now.saturating_sub(e.timestamp) < CACHE_TTL_SECS && e.scanner_version == SCANNER_VERSION
+    });
+    file.entries.insert(
+        digest.to_string(),
         CachedEntry {
             risk: risk.to_string(),
             reason: reason.to_string(),
             timestamp: now,
+            scanner_version: SCANNER_VERSION.to_string(),
         },
     );
-    let content = serde_json::to_string_pretty(&map)?;
+    write_atomic(path, &file)
+}
+
+fn write_atomic(path: &Path, file: &CacheFile) -> Result<()> {
+    let content = serde_json::to_string_pretty(file)?;
 
     // Atomic write: write to a per-process temp file, then rename.
     // rename(2) is atomic on POSIX — readers never see a partial write.
     let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
     fs::write(&tmp_path, content.as_bytes())
         .map_err(|e| Error::validation(format!("write scan-cache tmp: {}", e)))?;
-    if let Err(e) = fs::rename(&tmp_path, &path) {
+    if let Err(e) = fs::rename(&tmp_path, path) {
         let _ = fs::remove_file(&tmp_path);
         return Err(Error::validation(format!(
             "atomic rename scan-cache: {}",
@@ -114,29 +244,108 @@ pub fn put_cached(content_hash: &str, risk: &str, reason: &str) -> Result<()> {
 mod tests {
     use super::*;
 
+    fn write_skill(dir: &Path) {
+        fs::create_dir_all(dir.join("scripts")).unwrap();
+        fs::write(dir.join("SKILL.md"), "---\nname: demo\n---\n").unwrap();
+        fs::write(dir.join("scripts/main.py"), "print('hello')\n").unwrap();
+    }
+
     #[test]
-    fn test_content_hash_deterministic() {
-        let h1 = content_hash("skill a", "script b");
-        let h2 = content_hash("skill a", "script b");
+    fn test_skill_digest_deterministic() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(tmp.path());
+        let h1 = skill_digest(tmp.path()).unwrap();
+        let h2 = skill_digest(tmp.path()).unwrap();
         assert_eq!(h1, h2);
         assert_eq!(h1.len(), 64); // SHA256 hex
     }
 
     #[test]
-    fn test_content_hash_different_inputs() {
-        let h1 = content_hash("a", "b");
-        let h2 = content_hash("a", "c");
-        assert_ne!(h1, h2);
+    fn test_skill_digest_covers_file_list() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(tmp.path());
+        let h1 = skill_digest(tmp.path()).unwrap();
+        fs::write(tmp.path().join("scripts/extra.sh"), "").unwrap();
+        assert_ne!(h1, skill_digest(tmp.path()).unwrap());
+        fs::remove_file(tmp.path().join("scripts/extra.sh")).unwrap();
+        fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        fs::write(tmp.path().join(".git/HEAD"), "ref").unwrap();
+        assert_eq!(h1, skill_digest(tmp.path()).unwrap());
     }
 
     #[test]
     fn test_cache_roundtrip() {
-        let hash = "test_hash_123";
-        put_cached(hash, "suspicious", "test reason").unwrap();
-        let cached = get_cached(hash).unwrap();
-        assert!(cached.is_some());
-        let (risk, reason) = cached.unwrap();
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = tmp.path().join(CACHE_FILENAME);
+        put_cached_at(&cache, "test_hash_123", "suspicious", "test reason").unwrap();
+        let (risk, reason) = get_cached_at(&cache, "test_hash_123").unwrap();
         assert_eq!(risk, "suspicious");
         assert_eq!(reason, "test reason");
     }
+
+    #[test]
+    fn removed_entry_is_gone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = tmp.path().join(CACHE_FILENAME);
+        put_cached_at(&cache, "a", "safe", "ok").unwrap();
+        put_cached_at(&cache, "b", "safe", "ok").unwrap();
+        assert!(remove_cached_at(&cache, "a").unwrap());
+        assert!(!remove_cached_at(&cache, "a").unwrap());
+        assert!(get_cached_at(&cache, "a").is_none());
+        assert!(get_cached_at(&cache, "b").is_some());
+    }
+
+    #[test]
+    fn content_change_with_same_mtime_and_size_misses() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill = tmp.path().join("skill");
+        write_skill(&skill);
+        let cache = tmp.path().join(CACHE_FILENAME);
+        let script = skill.join("scripts/main.py");
+        let mtime = fs::metadata(&script).unwrap().modified().unwrap();
+
+        let before = skill_digest(&skill).unwrap();
+        put_cached_at(&cache, &before, "safe", "ok").unwrap();
+        assert!(get_cached_at(&cache, &before).is_some());
+
+        // Same length, same mtime: only the bytes differ.
+        fs::write(&script, "print('hacks')\n").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&script)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+        assert_eq!(fs::metadata(&script).unwrap().modified().unwrap(), mtime);
+
+        let after = skill_digest(&skill).unwrap();
+        assert_ne!(before, after);
+        assert!(get_cached_at(&cache, &after).is_none());
+    }
+
+    #[test]
+    fn other_scanner_version_is_not_trusted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = tmp.path().join(CACHE_FILENAME);
+        put_cached_at(&cache, "h", "safe", "ok").unwrap();
+        let mut file = load(&cache);
+        file.entries.get_mut("h").unwrap().scanner_version = "0.0.1".to_string();
+        fs::write(&cache, serde_json::to_string(&file).unwrap()).unwrap();
+        assert!(get_cached_at(&cache, "h").is_none());
+    }
+
+    #[test]
+    fn legacy_cache_file_is_dropped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = tmp.path().join(CACHE_FILENAME);
+        let now = now_secs();
+        fs::write(
+            &cache,
+            format!(r#"{{"abc": {{"risk": "safe", "reason": "old", "timestamp": {now}}}}}"#),
+        )
+        .unwrap();
+        assert!(get_cached_at(&cache, "abc").is_none());
+        assert!(!cache.exists());
+        assert!(!migrate_legacy_cache_at(&cache));
+    }
 }
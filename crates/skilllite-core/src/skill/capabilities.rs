@@ -0,0 +1,268 @@
+//! Registry of known SKILL.md `capabilities` tags.
+//!
+//! Swarm routing and `aggregate_capability_tags` match tags as exact strings, so a typo
+//! (`brwoser`) silently drops a skill from routing. The registry lets `skilllite validate`
+//! flag unknown tags with a nearest-match suggestion, and gives `scan --suggest-capabilities`
+//! the service hosts it maps to tags.
+//!
+//! Sources (merged): built-in `capability_tags.json`, `SKILLLITE_CAPABILITY_TAGS`
+//! (comma-separated), `~/.skilllite/capability-tags.txt` and `./.skilllite/capability-tags.txt`
+//! (one tag per line, `#` comments).
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::config::env_keys::misc;
+use crate::config::loader::env_optional;
+use crate::paths::data_root;
+
+const BUILTIN_TAGS: &str = include_str!("capability_tags.json");
+
+/// One known tag. `aliases` are accepted spellings; `hosts` map API hosts to service tags.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CapabilityTag {
+    pub tag: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub hosts: Vec<String>,
+}
+
+/// Outcome of checking one declared tag against the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagCheck {
+    Known,
+    /// Accepted alias of a canonical tag (routing still matches the literal alias).
+    Alias {
+        canonical: String,
+    },
+    Unknown {
+        suggestion: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct CapabilityRegistry {
+    tags: Vec<CapabilityTag>,
+}
+
+impl CapabilityRegistry {
+    /// Built-in tags only.
+    pub fn builtin() -> Self {
+        let tags: Vec<CapabilityTag> = serde_json::from_str(BUILTIN_TAGS).unwrap_or_default();
+        Self { tags }
+    }
+
+    /// Built-in tags plus the env var and `capability-tags.txt` files.
+    pub fn load() -> Self {
+        let mut registry = Self::builtin();
+        if let Some(raw) = env_optional(misc::SKILLLITE_CAPABILITY_TAGS, &[]) {
+            registry.extend(raw.split([',', ';']));
+        }
+        for path in extension_paths() {
+            if let Ok(text) = fs::read_to_string(&path) {
+                registry.extend(parse_tag_lines(&text));
+            }
+        }
+        registry
+    }
+
+    /// Add custom tags (no description, aliases or hosts); duplicates are ignored.
+    pub fn extend<'a>(&mut self, tags: impl IntoIterator<Item = &'a str>) {
+        for tag in tags.into_iter().map(str::trim).filter(|t| !t.is_empty()) {
+            if !self.tags.iter().any(|t| t.tag == tag) {
+                self.tags.push(CapabilityTag {
+                    tag: tag.to_string(),
+                    description: String::new(),
+                    aliases: Vec::new(),
+                    hosts: Vec::new(),
+                });
+            }
+        }
+    }
+
+    pub fn tags(&self) -> &[CapabilityTag] {
+        &self.tags
+    }
+
+    pub fn check(&self, tag: &str) -> TagCheck {
+        if self.tags.iter().any(|t| t.tag == tag) {
+            return TagCheck::Known;
+        }
+        if let Some(t) = self
+            .tags
+            .iter()
+            .find(|t| t.aliases.iter().any(|a| a == tag))
+        {
+            return TagCheck::Alias {
+                canonical: t.tag.clone(),
+            };
+        }
+        TagCheck::Unknown {
+            suggestion: self.suggest(tag),
+        }
+    }
+
+    /// Nearest canonical tag (case-insensitive edit distance, aliases count toward their
+    /// canonical tag); `None` when nothing is close.
+    pub fn suggest(&self, tag: &str) -> Option<String> {
+        let needle = tag.trim().to_lowercase();
+        if needle.is_empty() {
+            return None;
+        }
+        let max_distance = (needle.chars().count() / 3).clamp(1, 3);
+        let needle = needle.as_str();
+        self.tags
+            .iter()
+            .flat_map(|t| {
+                std::iter::once(&t.tag)
+                    .chain(&t.aliases)
+                    .map(move |name| (edit_distance(needle, &name.to_lowercase()), &t.tag))
+            })
+            .filter(|(d, _)| *d <= max_distance)
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, canonical)| canonical.clone())
+    }
+
+    /// Service tag whose API host is `host` or a parent domain of it.
+    pub fn tag_for_host(&self, host: &str) -> Option<&str> {
+        let host = host.to_lowercase();
+        self.tags
+            .iter()
+            .find(|t| {
+                t.hosts
+                    .iter()
+                    .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
+            })
+            .map(|t| t.tag.as_str())
+    }
+}
+
+/// Human-readable warnings for unknown tags. Aliases are accepted silently: compatibility
+/// inference emits some of them (`data`) and peers already route on them.
+pub fn capability_warnings(registry: &CapabilityRegistry, capabilities: &[String]) -> Vec<String> {
+    capabilities
+        .iter()
+        .filter_map(|tag| match registry.check(tag) {
+            TagCheck::Known | TagCheck::Alias { .. } => None,
+            TagCheck::Unknown {
+                suggestion: Some(s),
+            } => Some(format!(
+                "unknown capability '{}' (did you mean '{}'?)",
+                tag, s
+            )),
+            TagCheck::Unknown { suggestion: None } => Some(format!(
+                "unknown capability '{}'; add it to {} or {} if it is intentional",
+                tag,
+                user_tags_path().display(),
+                misc::SKILLLITE_CAPABILITY_TAGS
+            )),
+        })
+        .collect()
+}
+
+fn parse_tag_lines(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty())
+}
+
+/// User-level file under data root: `~/.skilllite/capability-tags.txt`
+fn user_tags_path() -> PathBuf {
+    data_root().join("capability-tags.txt")
+}
+
+fn extension_paths() -> Vec<PathBuf> {
+    let mut paths = vec![user_tags_path()];
+    if let Ok(cwd) = std::env::current_dir() {
+        paths.push(cwd.join(".skilllite").join("capability-tags.txt"));
+    }
+    paths
+}
+
+/// Levenshtein distance over chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_registry_parses_and_covers_inferred_tags() {
+        let registry = CapabilityRegistry::builtin();
+        assert!(registry.tags().len() > 20);
+        // Tags produced by compatibility inference in `metadata.rs` must never warn.
+        for tag in ["python", "web", "node", "browser", "data", "calc"] {
+            assert!(
+                !matches!(registry.check(tag), TagCheck::Unknown { .. }),
+                "{}",
+                tag
+            );
+        }
+    }
+
+    #[test]
+    fn misspellings_get_nearest_match() {
+        let registry = CapabilityRegistry::builtin();
+        assert_eq!(registry.suggest("brwoser").as_deref(), Some("browser"));
+        assert_eq!(registry.suggest("Python").as_deref(), Some("python"));
+        assert_eq!(
+            registry.suggest("data-procesing").as_deref(),
+            Some("data-processing")
+        );
+        assert_eq!(registry.suggest("quantum-teleport"), None);
+        assert_eq!(
+            registry.check("excel"),
+            TagCheck::Alias {
+                canonical: "spreadsheet".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn custom_tags_extend_the_registry() {
+        let mut registry = CapabilityRegistry::builtin();
+        assert!(matches!(
+            registry.check("acme-crm"),
+            TagCheck::Unknown { .. }
+        ));
+        registry.extend(parse_tag_lines("# team tags\nacme-crm  # internal CRM\n\n"));
+        assert_eq!(registry.check("acme-crm"), TagCheck::Known);
+        let warnings =
+            capability_warnings(&registry, &["acme-crm".to_string(), "gihtub".to_string()]);
+        assert_eq!(
+            warnings,
+            vec!["unknown capability 'gihtub' (did you mean 'github'?)"]
+        );
+    }
+
+    #[test]
+    fn hosts_map_to_service_tags() {
+        let registry = CapabilityRegistry::builtin();
+        assert_eq!(registry.tag_for_host("api.github.com"), Some("github"));
+        assert_eq!(registry.tag_for_host("hooks.slack.com"), Some("slack"));
+        assert_eq!(
+            registry.tag_for_host("sheets.googleapis.com"),
+            Some("google")
+        );
+        assert_eq!(registry.tag_for_host("example.com"), None);
+        assert_eq!(registry.tag_for_host("notgithub.com"), None);
+    }
+}
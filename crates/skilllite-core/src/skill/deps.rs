@@ -410,6 +410,11 @@ mod tests {
             requires_elevated_permissions: false,
             capabilities: vec![],
             openclaw_installs: Some(installs),
+            max_concurrency: None,
+            entry_points: Vec::new(),
+            result_cache: None,
+            examples: Vec::new(),
+            cost_hint: None,
         }
     }
 
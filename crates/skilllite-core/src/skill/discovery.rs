@@ -277,6 +277,94 @@ pub fn discover_skill_dirs_for_loading(
     result
 }
 
+/// A skill name provided by more than one skill directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillNameCollision {
+    pub name: String,
+    /// In load order: the first path is the one loaders keep.
+    pub paths: Vec<PathBuf>,
+}
+
+/// Skill directories under one skills dir, in the order `load_skills` visits them: the dir
+/// itself when it is a skill, otherwise its sorted subdirectories; then `_evolved/*` minus
+/// archived ones. Skills marked `skip` in the manifest are left out.
+pub fn skills_in_load_order(dir: &Path) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    if dir.join("SKILL.md").is_file() {
+        out.push(dir.to_path_buf());
+    } else {
+        out.extend(sorted_skill_children(dir));
+    }
+    out.extend(
+        sorted_skill_children(&dir.join("_evolved"))
+            .into_iter()
+            .filter(|p| !is_archived_evolved(p)),
+    );
+    out.retain(|p| !super::manifest::is_skill_skipped(p));
+    out
+}
+
+fn sorted_skill_children(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut children: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && p.join("SKILL.md").is_file())
+        .collect();
+    children.sort_by_key(|p| p.file_name().unwrap_or_default().to_owned());
+    children
+}
+
+fn is_archived_evolved(skill_dir: &Path) -> bool {
+    fs::read_to_string(skill_dir.join(".meta.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .and_then(|v| v.get("archived").and_then(|a| a.as_bool()))
+        .unwrap_or(false)
+}
+
+/// Find skill names (SKILL.md `name`, else directory name) that more than one skill provides
+/// across `skill_dirs`, which are taken in priority order. Unparseable skills are ignored,
+/// since loaders skip them too.
+pub fn find_skill_name_collisions(skill_dirs: &[PathBuf]) -> Vec<SkillNameCollision> {
+    let mut seen_dirs = HashSet::new();
+    let mut by_name: Vec<SkillNameCollision> = Vec::new();
+    for dir in skill_dirs {
+        if !dir
+            .canonicalize()
+            .map(|real| seen_dirs.insert(real))
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        for skill in skills_in_load_order(dir) {
+            let Ok(meta) = super::metadata::parse_skill_metadata(&skill) else {
+                continue;
+            };
+            let name = if meta.name.is_empty() {
+                skill
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned()
+            } else {
+                meta.name
+            };
+            match by_name.iter_mut().find(|c| c.name == name) {
+                Some(c) => c.paths.push(skill),
+                None => by_name.push(SkillNameCollision {
+                    name,
+                    paths: vec![skill],
+                }),
+            }
+        }
+    }
+    by_name.retain(|c| c.paths.len() > 1);
+    by_name
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -412,4 +500,34 @@ mod tests {
         let warning = resolved.conflict_warning().unwrap_or_default();
         assert!(warning.contains("dup"));
     }
+
+    #[test]
+    fn test_find_skill_name_collisions_across_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let write = |dir: &Path, name: &str| {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(
+                dir.join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: d\n---\n"),
+            )
+            .unwrap();
+        };
+        let skills = tmp.path().join("skills");
+        let legacy = tmp.path().join(".skills");
+        write(&skills.join("web-search"), "web-search");
+        write(&legacy.join("search"), "web-search");
+        write(&legacy.join("_evolved").join("web-search"), "web-search");
+        write(&legacy.join("unique"), "unique");
+
+        let collisions = find_skill_name_collisions(&[skills.clone(), legacy.clone(), skills]);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].name, "web-search");
+        assert_eq!(collisions[0].paths.len(), 3);
+        assert!(collisions[0].paths[0].starts_with(tmp.path().join("skills")));
+        assert!(collisions[0].paths[2].ends_with("_evolved/web-search"));
+
+        crate::skill::manifest::set_skill_skipped(&legacy.join("search"), true).unwrap();
+        let collisions = find_skill_name_collisions(&[tmp.path().join("skills"), legacy]);
+        assert_eq!(collisions[0].paths.len(), 2);
+    }
 }
@@ -49,6 +49,9 @@ pub struct SkillManifestEntry {
     /// 准入扫描结果：safe/suspicious/malicious（仅 skill add 时写入，存量无此项）
     #[serde(default)]
     pub admission_risk: Option<String>,
+    /// Disabled by `skilllite reindex --resolve` (duplicate skill name); loaders ignore it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -126,11 +129,42 @@ pub fn upsert_installed_skill_with_admission(
         entry.admission_risk = Some(r.to_string());
     }
     let key = skill_key(skill_dir)?;
+    entry.skip = manifest.skills.get(&key).is_some_and(|e| e.skip);
     manifest.skills.insert(key, entry.clone());
     save_manifest(skills_dir, &manifest)?;
     Ok(entry)
 }
 
+/// Set or clear the `skip` flag of the skill at `skill_dir`; the manifest is the one in its
+/// parent directory. Creates a `reindex-local` entry when the skill has none.
+pub fn set_skill_skipped(skill_dir: &Path, skip: bool) -> Result<()> {
+    let skills_dir = skill_dir.parent().unwrap_or(skill_dir);
+    let mut manifest = load_manifest(skills_dir)?;
+    let key = skill_key(skill_dir)?;
+    match manifest.skills.get_mut(&key) {
+        Some(entry) => entry.skip = skip,
+        None => {
+            let mut entry = build_entry(skill_dir, "reindex-local")?;
+            entry.skip = skip;
+            manifest.skills.insert(key, entry);
+        }
+    }
+    save_manifest(skills_dir, &manifest)
+}
+
+/// Whether the skill at `skill_dir` is marked `skip` in its parent directory's manifest.
+pub fn is_skill_skipped(skill_dir: &Path) -> bool {
+    let (Some(skills_dir), Ok(key)) = (skill_dir.parent(), skill_key(skill_dir)) else {
+        return false;
+    };
+    if !manifest_path(skills_dir).exists() {
+        return false;
+    }
+    load_manifest(skills_dir)
+        .map(|m| m.skills.get(&key).is_some_and(|e| e.skip))
+        .unwrap_or(false)
+}
+
 /// 仅更新已有 entry 的 admission_risk 字段，不重建整个 entry
 pub fn update_admission_risk(skills_dir: &Path, skill_dir: &Path, risk: &str) -> Result<()> {
     let mut manifest = load_manifest(skills_dir)?;
@@ -152,6 +186,33 @@ pub fn remove_skill_entry(skills_dir: &Path, skill_dir: &Path) -> Result<bool> {
     Ok(removed)
 }
 
+/// Manifest keys whose skill directory no longer exists under `skills_dir`.
+pub fn stale_entries(skills_dir: &Path) -> Result<Vec<String>> {
+    let manifest = load_manifest(skills_dir)?;
+    Ok(manifest
+        .skills
+        .keys()
+        .filter(|key| !skills_dir.join(key).is_dir())
+        .cloned()
+        .collect())
+}
+
+/// Drop [`stale_entries`] from the manifest; returns the removed keys.
+pub fn prune_stale_entries(skills_dir: &Path) -> Result<Vec<String>> {
+    let mut manifest = load_manifest(skills_dir)?;
+    let stale: Vec<String> = manifest
+        .skills
+        .keys()
+        .filter(|key| !skills_dir.join(key).is_dir())
+        .cloned()
+        .collect();
+    if !stale.is_empty() {
+        manifest.skills.retain(|key, _| !stale.contains(key));
+        save_manifest(skills_dir, &manifest)?;
+    }
+    Ok(stale)
+}
+
 pub fn evaluate_skill_status(skills_dir: &Path, skill_dir: &Path) -> Result<SkillIntegrityReport> {
     let manifest = load_manifest(skills_dir)?;
     let key = skill_key(skill_dir)?;
@@ -230,6 +291,7 @@ fn build_entry(skill_dir: &Path, source: &str) -> Result<SkillManifestEntry> {
         tier_updated_at: Some(Utc::now()),
         installed_at: Utc::now(),
         admission_risk: None,
+        skip: false,
     })
 }
 
@@ -71,6 +71,145 @@ struct FrontMatter {
     /// Can also be nested under `metadata.capabilities` for backward compat.
     #[serde(default)]
     pub capabilities: Vec<String>,
+
+    /// Optional: Max simultaneous executions of this skill in one process
+    /// (e.g. 1 for headless browsers / LibreOffice). Omitted or 0 = unlimited.
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+
+    /// Optional: Declared outbound hosts, e.g. `network: { outbound: ["api.github.com"] }`,
+    /// plus an optional `deny:` list checked first (grammar in [`super::network_rules`]).
+    /// Non-empty `outbound` enables network restricted to these hosts (overrides compatibility).
+    #[serde(default)]
+    pub network: Option<FrontMatterNetwork>,
+
+    /// Optional: Named commands of a toolkit skill, `tool-name: { script, description, parameters }`.
+    /// Kept as a raw mapping so each entry is validated (and reported) on its own.
+    #[serde(default)]
+    pub entry_points: Option<serde_yaml::Mapping>,
+
+    /// Optional: Result caching for pure skills, e.g. `cache: { enabled: true, ttl: 3600 }`.
+    #[serde(default)]
+    pub cache: Option<FrontMatterCache>,
+
+    /// Optional: Sample calls, `examples: [{ input: {...}, output: ..., description, tool }]`.
+    /// Kept raw so a malformed entry is skipped instead of failing the whole skill.
+    #[serde(default)]
+    pub examples: Option<serde_yaml::Value>,
+
+    /// Optional: Expected run time, `cost: fast | medium | slow`.
+    #[serde(default)]
+    pub cost: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+struct FrontMatterCache {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds a cached result stays valid (default [`DEFAULT_RESULT_CACHE_TTL_SECS`]).
+    #[serde(default)]
+    pub ttl: Option<u64>,
+    /// Environment variables whose values the result depends on; part of the cache key.
+    #[serde(default)]
+    pub env: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+struct FrontMatterNetwork {
+    #[serde(default)]
+    pub outbound: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct FrontMatterEntryPoint {
+    script: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    parameters: Option<serde_json::Value>,
+}
+
+/// One named command from SKILL.md `entry_points:`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryPoint {
+    /// Command name as declared; the agent tool is `<skill>__<name>`.
+    pub name: String,
+    /// Script path relative to the skill directory.
+    pub script: String,
+    pub description: Option<String>,
+    /// Declared JSON schema of the input; `None` = infer from the script.
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// TTL of a cached skill result when SKILL.md `cache:` gives none.
+pub const DEFAULT_RESULT_CACHE_TTL_SECS: u64 = 3600;
+
+/// SKILL.md `cache: { enabled: true, ttl: 3600, env: [...] }`: the skill is pure, so
+/// `run` may return a stored result for the same skill content and input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultCachePolicy {
+    pub ttl_secs: u64,
+    /// Environment variables read by the skill; their values are part of the cache key.
+    pub env: Vec<String>,
+}
+
+/// One SKILL.md `examples:` entry: a sample input and, optionally, what it returns.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ToolExample {
+    /// `entry_points` command the example belongs to; `None` = every tool of the skill.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub input: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+}
+
+/// Rough run time of a tool, from SKILL.md `cost:` or observed timings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostHint {
+    Fast,
+    Medium,
+    Slow,
+}
+
+impl CostHint {
+    /// Average run time below this is `fast`.
+    pub const FAST_BELOW_MS: f64 = 2_000.0;
+    /// Average run time below this (and not fast) is `medium`.
+    pub const MEDIUM_BELOW_MS: f64 = 15_000.0;
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "fast" => Some(Self::Fast),
+            "medium" => Some(Self::Medium),
+            "slow" => Some(Self::Slow),
+            _ => None,
+        }
+    }
+
+    pub fn from_avg_duration_ms(avg_ms: f64) -> Self {
+        if avg_ms < Self::FAST_BELOW_MS {
+            Self::Fast
+        } else if avg_ms < Self::MEDIUM_BELOW_MS {
+            Self::Medium
+        } else {
+            Self::Slow
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Medium => "medium",
+            Self::Slow => "slow",
+        }
+    }
 }
 
 /// Parsed pattern from `allowed-tools: Bash(agent-browser:*)`
@@ -89,26 +228,21 @@ pub struct BashToolPattern {
 ///   - `"Bash(agent-browser:*)"` -> `[BashToolPattern { command_prefix: "agent-browser", .. }]`
 ///   - `"Bash(agent-browser:*), Bash(npm:*)"` -> two patterns
 ///   - `"Read, Edit, Bash(mycli:*)"` -> one BashToolPattern (non-Bash tools ignored)
+///   - `"Bash(npm run *:*)"` -> `command_prefix: "npm"`; the full `raw_pattern` is matched
+///     word by word by the sandbox bash validator (`*` before the colon = one argument,
+///     `:*` = any further arguments).
 pub fn parse_allowed_tools(raw: &str) -> Vec<BashToolPattern> {
     let mut patterns = Vec::new();
 
     for cap in ALLOWED_TOOLS_RE.captures_iter(raw) {
         if let Some(inner) = cap.get(1) {
             let pattern_str = inner.as_str().trim();
-            // Extract command prefix: everything before the first ':' or whitespace.
+            // Extract command prefix: the first word before any ':'.
             // e.g. "agent-browser:*" -> "agent-browser"
             // e.g. "infsh *" -> "infsh"
-            // e.g. "agent-browser" -> "agent-browser"
-            let command_prefix = if let Some(idx) = pattern_str.find(':') {
-                pattern_str[..idx].trim().to_string()
-            } else {
-                pattern_str
-                    .split_whitespace()
-                    .next()
-                    .unwrap_or("")
-                    .trim()
-                    .to_string()
-            };
+            // e.g. "npm run *:*" -> "npm"
+            let head = pattern_str.split(':').next().unwrap_or("");
+            let command_prefix = head.split_whitespace().next().unwrap_or("").to_string();
 
             if !command_prefix.is_empty() {
                 patterns.push(BashToolPattern {
@@ -169,6 +303,22 @@ pub struct SkillMetadata {
     /// `node` → npm packages, `uv` → pip packages; `brew` / `go` are recorded but
     /// not auto-installed (would require host package managers).
     pub openclaw_installs: Option<super::openclaw_metadata::OpenClawInstalls>,
+
+    /// Front matter `max_concurrency`: executions beyond this many queue until a
+    /// slot frees up. `None` = unlimited.
+    pub max_concurrency: Option<usize>,
+
+    /// Front matter `entry_points`, in declaration order. Empty for single-entry skills.
+    pub entry_points: Vec<EntryPoint>,
+
+    /// Front matter `cache:` with `enabled: true`. `None` = results are never cached.
+    pub result_cache: Option<ResultCachePolicy>,
+
+    /// Front matter `examples:` that have an `input`, in declaration order.
+    pub examples: Vec<ToolExample>,
+
+    /// Front matter `cost:`; `None` when absent or not one of fast / medium / slow.
+    pub cost_hint: Option<CostHint>,
 }
 
 impl SkillMetadata {
@@ -180,6 +330,49 @@ impl SkillMetadata {
         self.allowed_tools.is_some() && self.entry_point.is_empty()
     }
 
+    /// Examples that apply to the tool for `entry` (an `entry_points` name, or `None` for a
+    /// single-entry skill): those naming that entry plus those naming none.
+    pub fn examples_for(&self, entry: Option<&str>) -> Vec<&ToolExample> {
+        self.examples
+            .iter()
+            .filter(|e| e.tool.is_none() || e.tool.as_deref() == entry)
+            .collect()
+    }
+
+    /// The `entry_points` command called `name`.
+    pub fn entry_point_named(&self, name: &str) -> Result<&EntryPoint> {
+        self.entry_points
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or_else(|| {
+                let available: Vec<&str> =
+                    self.entry_points.iter().map(|e| e.name.as_str()).collect();
+                crate::Error::validation(if available.is_empty() {
+                    format!(
+                        "Skill '{}' declares no entry_points; cannot run entry '{}'",
+                        self.name, name
+                    )
+                } else {
+                    format!(
+                        "Skill '{}' has no entry point '{}' (available: {})",
+                        self.name,
+                        name,
+                        available.join(", ")
+                    )
+                })
+            })
+    }
+
+    /// Make the `entry_points` command `name` the entry point; the language is re-derived
+    /// from its script unless compatibility pins one.
+    pub fn select_entry(&mut self, name: &str) -> Result<()> {
+        let script = self.entry_point_named(name)?.script.clone();
+        self.language = parse_compatibility_for_language(self.compatibility.as_deref())
+            .or_else(|| detect_language_from_entry_point(&script));
+        self.entry_point = script;
+        Ok(())
+    }
+
     /// Parse the `allowed-tools` field into structured `BashToolPattern` items.
     /// Returns an empty vec if `allowed_tools` is None or contains no Bash patterns.
     pub fn get_bash_patterns(&self) -> Vec<BashToolPattern> {
@@ -217,6 +410,27 @@ pub struct NetworkPolicy {
     /// List of allowed outbound hosts (e.g., ["*:80", "*:443"])
     /// When network is enabled via compatibility, defaults to allow all HTTP/HTTPS
     pub outbound: Vec<String>,
+
+    /// Outbound entries that are refused even when `outbound` allows them
+    /// (e.g. `gist.github.com` next to `*.github.com`).
+    pub deny: Vec<String>,
+}
+
+impl NetworkPolicy {
+    /// Outbound hosts that name a domain, i.e. excluding catch-all entries such as `*`
+    /// or `*:443` (which compatibility-inferred network access uses).
+    pub fn declared_hosts(&self) -> Vec<String> {
+        self.outbound
+            .iter()
+            .filter(|h| {
+                !matches!(
+                    super::network_rules::OutboundRule::parse(h),
+                    Ok(rule) if rule.host == super::network_rules::HostPattern::Any
+                )
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 /// Parse compatibility string to extract network policy
@@ -250,6 +464,7 @@ fn parse_compatibility_for_network(compatibility: Option<&str>) -> NetworkPolicy
             // Allow all domains by default when network is enabled via compatibility
             // The "*" wildcard matches all domains in ProxyConfig::domain_matches
             outbound: vec!["*".to_string()],
+            deny: Vec::new(),
         }
     } else {
         NetworkPolicy::default()
@@ -428,6 +643,38 @@ fn infer_capabilities_from_compatibility(
     v
 }
 
+/// Top-level `capabilities:`, else `metadata.capabilities`; empty when neither is declared.
+fn declared_capabilities_of(front_matter: &FrontMatter) -> Vec<String> {
+    if !front_matter.capabilities.is_empty() {
+        return front_matter.capabilities.clone();
+    }
+    front_matter
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("capabilities"))
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Capabilities written in the SKILL.md front matter, without the inference from
+/// `compatibility` that [`SkillMetadata::capabilities`] falls back to.
+pub fn declared_capabilities(content: &str) -> Result<Vec<String>> {
+    let yaml_content = YAML_FRONT_MATTER_RE
+        .captures(content)
+        .and_then(|c| c.get(1))
+        .ok_or_else(|| crate::Error::validation("No YAML front matter found in SKILL.md"))?
+        .as_str();
+    let yaml_content = normalize_yaml_continuation_lines(yaml_content);
+    let front_matter: FrontMatter =
+        serde_yaml::from_str(&yaml_content).with_context(|| "Failed to parse YAML front matter")?;
+    Ok(declared_capabilities_of(&front_matter))
+}
+
 /// Extract YAML front matter from markdown content (for tests without skill_dir)
 #[cfg(test)]
 fn extract_yaml_front_matter(content: &str) -> Result<SkillMetadata> {
@@ -497,8 +744,34 @@ fn extract_yaml_front_matter_impl(
     let language = parse_compatibility_for_language(compatibility.as_deref())
         .or_else(|| detect_language_from_entry_point(&entry_point));
 
-    // Parse network policy from compatibility field
-    let network = parse_compatibility_for_network(compatibility.as_deref());
+    // Network policy: declared `network.outbound` hosts, else inferred from compatibility;
+    // `network.deny` applies either way.
+    let trimmed = |entries: &[String]| -> Vec<String> {
+        entries
+            .iter()
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+            .collect()
+    };
+    let declared_hosts = front_matter
+        .network
+        .as_ref()
+        .map(|n| trimmed(&n.outbound))
+        .unwrap_or_default();
+    let mut network = if declared_hosts.is_empty() {
+        parse_compatibility_for_network(compatibility.as_deref())
+    } else {
+        NetworkPolicy {
+            enabled: true,
+            outbound: declared_hosts,
+            deny: Vec::new(),
+        }
+    };
+    network.deny = front_matter
+        .network
+        .as_ref()
+        .map(|n| trimmed(&n.deny))
+        .unwrap_or_default();
 
     // Read resolved_packages from .skilllite.lock (written by `skilllite init`)
     let resolved_packages =
@@ -508,32 +781,25 @@ fn extract_yaml_front_matter_impl(
 
     // Resolve capabilities: top-level `capabilities:` > metadata.capabilities > infer from compatibility.
     // compatibility is official Agent Skills field; inferring from it enables routing without custom fields.
-    let capabilities = if !front_matter.capabilities.is_empty() {
-        front_matter.capabilities.clone()
+    let capabilities = declared_capabilities_of(&front_matter);
+    let capabilities = if capabilities.is_empty() {
+        infer_capabilities_from_compatibility(
+            compatibility.as_deref().unwrap_or(""),
+            &front_matter.name,
+            front_matter.description.as_deref().unwrap_or(""),
+        )
     } else {
-        front_matter
-            .metadata
-            .as_ref()
-            .and_then(|m| m.get("capabilities"))
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str().map(String::from))
-                    .collect()
-            })
-            .filter(|v: &Vec<String>| !v.is_empty())
-            .unwrap_or_else(|| {
-                infer_capabilities_from_compatibility(
-                    compatibility.as_deref().unwrap_or(""),
-                    &front_matter.name,
-                    front_matter.description.as_deref().unwrap_or(""),
-                )
-            })
+        capabilities
     };
 
     let openclaw_installs =
         super::openclaw_metadata::extract_installs(front_matter.metadata.as_ref());
 
+    let entry_points = match front_matter.entry_points {
+        Some(ref table) => parse_entry_points(table, skill_dir)?,
+        None => Vec::new(),
+    };
+
     let metadata = SkillMetadata {
         name: front_matter.name.clone(),
         entry_point,
@@ -552,6 +818,16 @@ fn extract_yaml_front_matter_impl(
         requires_elevated_permissions: requires_elevated,
         capabilities,
         openclaw_installs,
+        max_concurrency: front_matter.max_concurrency.filter(|&n| n > 0),
+        entry_points,
+        result_cache: front_matter.cache.as_ref().filter(|c| c.enabled).map(|c| {
+            ResultCachePolicy {
+                ttl_secs: c.ttl.unwrap_or(DEFAULT_RESULT_CACHE_TTL_SECS),
+                env: c.env.clone(),
+            }
+        }),
+        examples: parse_examples(front_matter.examples.as_ref()),
+        cost_hint: front_matter.cost.as_deref().and_then(CostHint::parse),
     };
 
     // Validate required fields
@@ -564,6 +840,117 @@ fn extract_yaml_front_matter_impl(
     Ok(metadata)
 }
 
+/// Read `examples:` leniently: entries that are not mappings or lack an `input` are skipped.
+fn parse_examples(raw: Option<&serde_yaml::Value>) -> Vec<ToolExample> {
+    let Some(serde_yaml::Value::Sequence(items)) = raw else {
+        return Vec::new();
+    };
+    let text = |v: &serde_json::Value, key: &str| {
+        v.get(key)
+            .and_then(|s| s.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    };
+    items
+        .iter()
+        .filter_map(|item| serde_json::to_value(item).ok())
+        .filter_map(|item| {
+            let input = item.get("input").filter(|v| !v.is_null())?.clone();
+            Some(ToolExample {
+                tool: text(&item, "tool"),
+                description: text(&item, "description"),
+                input,
+                output: item.get("output").filter(|v| !v.is_null()).cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Validate the `entry_points:` table. Names must be unique as tool names (case and
+/// punctuation folded), scripts must stay inside the skill and, when `skill_dir` is known,
+/// exist; a declared `parameters` schema must be an object schema.
+fn parse_entry_points(
+    table: &serde_yaml::Mapping,
+    skill_dir: Option<&Path>,
+) -> Result<Vec<EntryPoint>> {
+    let mut entries: Vec<EntryPoint> = Vec::with_capacity(table.len());
+    for (key, value) in table {
+        let name = key
+            .as_str()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| {
+                crate::Error::validation(format!(
+                    "entry_points: entry names must be non-empty strings (got {:?})",
+                    key
+                ))
+            })?;
+        let invalid =
+            |msg: String| crate::Error::validation(format!("entry_points.{}: {}", name, msg));
+
+        if let Some(other) = entries
+            .iter()
+            .find(|e| entry_tool_key(&e.name) == entry_tool_key(name))
+        {
+            return Err(invalid(format!(
+                "name collides with '{}' (tool names ignore case and punctuation)",
+                other.name
+            )));
+        }
+        let entry: FrontMatterEntryPoint =
+            serde_yaml::from_value(value.clone()).map_err(|e| invalid(e.to_string()))?;
+
+        let script = entry.script.trim();
+        let script_path = Path::new(script);
+        if script.is_empty()
+            || script_path.is_absolute()
+            || script_path
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            return Err(invalid(format!(
+                "script '{}' must be a path inside the skill directory",
+                script
+            )));
+        }
+        if let Some(dir) = skill_dir {
+            if !dir.join(script).is_file() {
+                return Err(invalid(format!("script '{}' not found", script)));
+            }
+        }
+        if let Some(ref schema) = entry.parameters {
+            let is_object_schema = schema
+                .as_object()
+                .is_some_and(|o| o.get("type").is_none_or(|t| t == "object"));
+            if !is_object_schema {
+                return Err(invalid(
+                    "parameters must be a JSON schema with type: object".to_string(),
+                ));
+            }
+        }
+
+        entries.push(EntryPoint {
+            name: name.to_string(),
+            script: script.to_string(),
+            description: entry
+                .description
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            parameters: entry.parameters,
+        });
+    }
+    Ok(entries)
+}
+
+/// Entry names that produce the same tool name once sanitized collide.
+fn entry_tool_key(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .to_lowercase()
+}
+
 /// Read resolved packages from ``.skilllite.lock`` in *skill_dir*.
 ///
 /// Returns ``None`` if the lock file is missing, invalid, or stale
@@ -716,6 +1103,189 @@ This is a test skill.
         assert!(metadata.capabilities.contains(&"web".to_string()));
     }
 
+    #[test]
+    fn test_max_concurrency_from_front_matter() {
+        let limited = extract_yaml_front_matter(
+            "---\nname: pdf-render\ndescription: Render PDFs\nmax_concurrency: 1\n---\n",
+        )
+        .expect("test YAML parsing should succeed");
+        assert_eq!(limited.max_concurrency, Some(1));
+
+        let unlimited = extract_yaml_front_matter(
+            "---\nname: calc\ndescription: Add numbers\nmax_concurrency: 0\n---\n",
+        )
+        .expect("test YAML parsing should succeed");
+        assert_eq!(unlimited.max_concurrency, None);
+    }
+
+    #[test]
+    fn test_result_cache_from_front_matter() {
+        let cached = extract_yaml_front_matter(
+            "---\nname: slugify\ndescription: Slugify text\ncache:\n  enabled: true\n  env: [LANG]\n---\n",
+        )
+        .expect("test YAML parsing should succeed");
+        assert_eq!(
+            cached.result_cache,
+            Some(ResultCachePolicy {
+                ttl_secs: DEFAULT_RESULT_CACHE_TTL_SECS,
+                env: vec!["LANG".to_string()],
+            })
+        );
+
+        let disabled = extract_yaml_front_matter(
+            "---\nname: slugify\ndescription: Slugify text\ncache: { enabled: false, ttl: 60 }\n---\n",
+        )
+        .expect("test YAML parsing should succeed");
+        assert_eq!(disabled.result_cache, None);
+    }
+
+    #[test]
+    fn test_examples_and_cost_from_front_matter() {
+        let metadata = extract_yaml_front_matter(
+            r#"---
+name: slugify
+description: Slugify text
+cost: Fast
+examples:
+  - description: Basic title
+    input: { text: "Hello World" }
+    output: { slug: hello-world }
+  - tool: strict
+    input: { text: "A  B" }
+  - description: missing input is skipped
+  - just a string
+---
+"#,
+        )
+        .expect("test YAML parsing should succeed");
+        assert_eq!(metadata.cost_hint, Some(CostHint::Fast));
+        assert_eq!(metadata.examples.len(), 2);
+        assert_eq!(
+            metadata.examples[0].input,
+            serde_json::json!({ "text": "Hello World" })
+        );
+        assert_eq!(
+            metadata.examples[0].output,
+            Some(serde_json::json!({ "slug": "hello-world" }))
+        );
+        assert_eq!(metadata.examples_for(None).len(), 1);
+        assert_eq!(metadata.examples_for(Some("strict")).len(), 2);
+
+        let unknown = extract_yaml_front_matter(
+            "---\nname: slugify\ndescription: Slugify text\ncost: cheap\nexamples: nope\n---\n",
+        )
+        .expect("test YAML parsing should succeed");
+        assert_eq!(unknown.cost_hint, None);
+        assert!(unknown.examples.is_empty());
+    }
+
+    #[test]
+    fn test_cost_hint_from_avg_duration() {
+        assert_eq!(CostHint::from_avg_duration_ms(350.0), CostHint::Fast);
+        assert_eq!(CostHint::from_avg_duration_ms(4_000.0), CostHint::Medium);
+        assert_eq!(CostHint::from_avg_duration_ms(60_000.0), CostHint::Slow);
+    }
+
+    fn toolkit_dir(front_matter: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("scripts")).expect("scripts dir");
+        for script in ["convert.py", "merge.py", "split.sh"] {
+            std::fs::write(dir.path().join("scripts").join(script), "").expect("script");
+        }
+        std::fs::write(
+            dir.path().join("SKILL.md"),
+            format!("---\n{}---\n\n# Toolkit\n", front_matter),
+        )
+        .expect("SKILL.md");
+        dir
+    }
+
+    #[test]
+    fn test_entry_points_from_front_matter() {
+        let dir = toolkit_dir(
+            r#"name: pdf-toolkit
+description: PDF utilities
+entry_points:
+  convert:
+    script: scripts/convert.py
+    description: Convert a document to PDF
+    parameters:
+      type: object
+      properties:
+        path: { type: string }
+      required: [path]
+  split:
+    script: scripts/split.sh
+"#,
+        );
+        let mut meta = parse_skill_metadata(dir.path()).expect("valid toolkit");
+        let names: Vec<&str> = meta.entry_points.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["convert", "split"]);
+        let convert = meta.entry_point_named("convert").expect("convert");
+        assert_eq!(convert.script, "scripts/convert.py");
+        assert_eq!(
+            convert.description.as_deref(),
+            Some("Convert a document to PDF")
+        );
+        assert_eq!(
+            convert.parameters.as_ref().expect("schema")["required"],
+            serde_json::json!(["path"])
+        );
+        assert!(meta
+            .entry_point_named("split")
+            .expect("split")
+            .parameters
+            .is_none());
+
+        meta.select_entry("split").expect("select split");
+        assert_eq!(meta.entry_point, "scripts/split.sh");
+        assert_eq!(meta.language.as_deref(), Some("bash"));
+        let err = meta.select_entry("merge").unwrap_err().to_string();
+        assert!(
+            err.contains("'merge'") && err.contains("convert, split"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_entry_points_validation_names_the_entry() {
+        let cases: &[(&str, &str)] = &[
+            (
+                "  merge:\n    script: scripts/missing.py\n",
+                "entry_points.merge: script 'scripts/missing.py' not found",
+            ),
+            (
+                "  merge:\n    script: ../outside.py\n",
+                "entry_points.merge: script '../outside.py' must be a path inside",
+            ),
+            (
+                "  merge:\n    script: scripts/merge.py\n  Merge:\n    script: scripts/convert.py\n",
+                "entry_points.Merge: name collides with 'merge'",
+            ),
+            (
+                "  merge:\n    script: scripts/merge.py\n    parameters:\n      type: string\n",
+                "entry_points.merge: parameters must be a JSON schema",
+            ),
+            (
+                "  merge:\n    script: scripts/merge.py\n    scirpt: typo\n",
+                "entry_points.merge: unknown field `scirpt`",
+            ),
+        ];
+        for (entries, expected) in cases {
+            let dir = toolkit_dir(&format!("name: toolkit\nentry_points:\n{}", entries));
+            let err = parse_skill_metadata(dir.path())
+                .expect_err("invalid entry_points")
+                .to_string();
+            assert!(
+                err.contains(expected),
+                "expected {:?} in {:?}",
+                expected,
+                err
+            );
+        }
+    }
+
     #[test]
     fn test_parse_compatibility_for_network() {
         // Network enabled cases (English)
@@ -763,6 +1333,30 @@ This is a test skill.
         assert_eq!(parse_compatibility_for_language(None), None);
     }
 
+    #[test]
+    fn test_declared_network_hosts() {
+        let content = "---\nname: gh-stats\ndescription: Repo stats\ncompatibility: Requires network access\nnetwork:\n  outbound: [\"api.github.com\", \"*:443\"]\n---\n";
+        let metadata = extract_yaml_front_matter(content).expect("front matter should parse");
+        assert!(metadata.network.enabled);
+        assert_eq!(metadata.network.outbound, vec!["api.github.com", "*:443"]);
+        assert_eq!(metadata.network.declared_hosts(), vec!["api.github.com"]);
+
+        let inferred = extract_yaml_front_matter(
+            "---\nname: web\ndescription: Web\ncompatibility: Requires network access\n---\n",
+        )
+        .expect("front matter should parse");
+        assert!(inferred.network.declared_hosts().is_empty());
+    }
+
+    #[test]
+    fn test_network_deny_list_applies_to_inferred_access() {
+        let content = "---\nname: web\ndescription: Web\ncompatibility: Requires network access\nnetwork:\n  deny: [\" gist.github.com \"]\n---\n";
+        let metadata = extract_yaml_front_matter(content).expect("front matter should parse");
+        assert!(metadata.network.enabled);
+        assert_eq!(metadata.network.outbound, vec!["*"]);
+        assert_eq!(metadata.network.deny, vec!["gist.github.com"]);
+    }
+
     #[test]
     fn test_default_network_policy() {
         let content = r#"---
@@ -816,6 +1410,16 @@ description: A simple skill
         assert_eq!(patterns[0].raw_pattern, "infsh *");
     }
 
+    #[test]
+    fn test_parse_allowed_tools_argument_wildcard() {
+        let patterns = parse_allowed_tools("Bash(npm run *:*), Bash(git status:)");
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].command_prefix, "npm");
+        assert_eq!(patterns[0].raw_pattern, "npm run *:*");
+        assert_eq!(patterns[1].command_prefix, "git");
+        assert_eq!(patterns[1].raw_pattern, "git status:");
+    }
+
     #[test]
     fn test_parse_allowed_tools_empty() {
         let patterns = parse_allowed_tools("Read, Edit");
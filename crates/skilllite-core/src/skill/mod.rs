@@ -1,10 +1,13 @@
+pub mod capabilities;
 pub mod denylist;
 pub mod dependency_resolver;
 pub mod deps;
 pub mod discovery;
 pub mod manifest;
 pub mod metadata;
+pub mod network_rules;
 pub mod openclaw_metadata;
+pub mod origin;
 pub mod schema;
 pub mod skill_md_security;
 pub mod trust;
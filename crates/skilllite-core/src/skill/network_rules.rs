@@ -0,0 +1,449 @@
+//! Outbound whitelist grammar for SKILL.md `network.outbound` and `network.deny`.
+//!
+//! Each entry is `HOST[:PORTS]`:
+//!
+//! | Entry | Matches |
+//! |-------|---------|
+//! | `*` | any host |
+//! | `api.example.com` | exactly that host name |
+//! | `*.example.com` | one or more labels in front of `example.com` (`a.example.com`, `a.b.example.com`), not `example.com` itself |
+//! | `203.0.113.7`, `::1` | that IP address |
+//! | `10.0.0.0/8`, `2001:db8::/32` | any address in the CIDR range |
+//! | `…:443`, `…:443-450` | only that port / inclusive port range (omitted = any port) |
+//!
+//! IPv6 addresses and ranges need brackets when a port follows: `[2001:db8::/32]:443`.
+//! Address rules are checked against the address a host name resolves to, so
+//! `10.0.0.0/8` also covers `db.internal` when it resolves into that range.
+//! `network.deny` uses the same grammar and is evaluated before the allow list.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use crate::Result;
+
+use super::metadata::NetworkPolicy;
+
+/// Host part of an outbound rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostPattern {
+    /// `*`
+    Any,
+    /// A lowercase host name.
+    Exact(String),
+    /// `*.base`: strict subdomains of `base` (stored without the `*.` prefix).
+    Subdomains(String),
+    /// IP literal (full-length prefix) or CIDR range; host bits are cleared.
+    Cidr(IpAddr, u8),
+}
+
+/// Inclusive port range; a single port has `start == end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl PortRange {
+    pub fn contains(&self, port: u16) -> bool {
+        (self.start..=self.end).contains(&port)
+    }
+}
+
+/// One parsed `network.outbound` / `network.deny` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboundRule {
+    pub host: HostPattern,
+    /// `None` = any port.
+    pub ports: Option<PortRange>,
+}
+
+impl OutboundRule {
+    /// Parse one entry; the error message explains what is wrong with it.
+    pub fn parse(entry: &str) -> Result<Self> {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return Err(invalid("entry is empty"));
+        }
+        let (host, ports) = split_host_ports(entry)?;
+        Ok(Self {
+            host: parse_host(host)?,
+            ports: ports.map(parse_ports).transpose()?,
+        })
+    }
+
+    /// `*` without a port: every host on every port.
+    pub fn is_catch_all(&self) -> bool {
+        self.host == HostPattern::Any && self.ports.is_none()
+    }
+
+    /// Whether the rule names `host` (a host name or IP literal as requested by the client).
+    pub fn matches_name(&self, host: &str) -> bool {
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        match &self.host {
+            HostPattern::Any => true,
+            HostPattern::Exact(name) => host == *name,
+            HostPattern::Subdomains(base) => host
+                .strip_suffix(base.as_str())
+                .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+            HostPattern::Cidr(..) => host.parse().is_ok_and(|ip| self.matches_ip(ip)),
+        }
+    }
+
+    /// Whether the rule covers address `ip` (name rules never do, except `*`).
+    pub fn matches_ip(&self, ip: IpAddr) -> bool {
+        match &self.host {
+            HostPattern::Any => true,
+            HostPattern::Cidr(network, prefix) => mask(canonical_ip(ip), *prefix) == Some(*network),
+            HostPattern::Exact(_) | HostPattern::Subdomains(_) => false,
+        }
+    }
+
+    /// Whether the rule covers `port`.
+    pub fn matches_port(&self, port: u16) -> bool {
+        self.ports.is_none_or(|range| range.contains(port))
+    }
+
+    /// Human-readable meaning, e.g. `subdomains of github.com, port 443`.
+    pub fn describe(&self) -> String {
+        let host = match &self.host {
+            HostPattern::Any => "any host".to_string(),
+            HostPattern::Exact(name) => format!("host {}", name),
+            HostPattern::Subdomains(base) => format!("subdomains of {}", base),
+            HostPattern::Cidr(ip, prefix) if *prefix == max_prefix(ip) => format!("address {}", ip),
+            HostPattern::Cidr(ip, prefix) => format!("addresses in {}/{}", ip, prefix),
+        };
+        let ports = match self.ports {
+            None => "any port".to_string(),
+            Some(r) if r.start == r.end => format!("port {}", r.start),
+            Some(r) => format!("ports {}-{}", r.start, r.end),
+        };
+        format!("{}, {}", host, ports)
+    }
+}
+
+impl fmt::Display for HostPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostPattern::Any => write!(f, "*"),
+            HostPattern::Exact(name) => write!(f, "{}", name),
+            HostPattern::Subdomains(base) => write!(f, "*.{}", base),
+            HostPattern::Cidr(ip, prefix) if *prefix == max_prefix(ip) => write!(f, "{}", ip),
+            HostPattern::Cidr(ip, prefix) => write!(f, "{}/{}", ip, prefix),
+        }
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+/// Canonical entry form (lowercase, masked CIDR, IPv6 bracketed before a port).
+impl fmt::Display for OutboundRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.host, self.ports) {
+            (HostPattern::Cidr(IpAddr::V6(_), _), Some(ports)) => {
+                write!(f, "[{}]:{}", self.host, ports)
+            }
+            (host, Some(ports)) => write!(f, "{}:{}", host, ports),
+            (host, None) => write!(f, "{}", host),
+        }
+    }
+}
+
+/// An invalid `network.outbound` / `network.deny` entry and where it is in SKILL.md.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleProblem {
+    /// 1-based SKILL.md line, when the entry could be located.
+    pub line: Option<usize>,
+    /// `outbound` or `deny`.
+    pub field: &'static str,
+    pub entry: String,
+    pub message: String,
+}
+
+impl fmt::Display for RuleProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "SKILL.md:{}: ", line)?,
+            None => write!(f, "SKILL.md: ")?,
+        }
+        write!(
+            f,
+            "network.{} entry '{}': {}",
+            self.field, self.entry, self.message
+        )
+    }
+}
+
+/// Parse every entry of `network.outbound` and `network.deny`; returns the invalid ones,
+/// located in `skill_md` (the SKILL.md text the policy was read from).
+pub fn check_network_policy(skill_md: &str, policy: &NetworkPolicy) -> Vec<RuleProblem> {
+    let lines: Vec<&str> = skill_md.lines().collect();
+    let network_line = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with("network:"))
+        .unwrap_or(0);
+    let mut problems = Vec::new();
+    for (field, entries) in [("outbound", &policy.outbound), ("deny", &policy.deny)] {
+        for entry in entries {
+            if let Err(e) = OutboundRule::parse(entry) {
+                let line = lines[network_line.min(lines.len())..]
+                    .iter()
+                    .position(|l| l.contains(entry.as_str()))
+                    .map(|i| network_line + i + 1);
+                problems.push(RuleProblem {
+                    line,
+                    field,
+                    entry: entry.clone(),
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+    problems
+}
+
+fn invalid(msg: impl Into<String>) -> crate::Error {
+    crate::Error::validation(msg)
+}
+
+/// Split `host[:ports]`, honouring `[ipv6]:port` and bare IPv6 literals (no port).
+fn split_host_ports(entry: &str) -> Result<(&str, Option<&str>)> {
+    if let Some(rest) = entry.strip_prefix('[') {
+        let (host, after) = rest
+            .split_once(']')
+            .ok_or_else(|| invalid("missing ']' after IPv6 address"))?;
+        return match after {
+            "" => Ok((host, None)),
+            _ => match after.strip_prefix(':') {
+                Some(ports) => Ok((host, Some(ports))),
+                None => Err(invalid(format!("unexpected '{}' after ']'", after))),
+            },
+        };
+    }
+    if entry.matches(':').count() > 1 {
+        // Bare IPv6 literal / range; a port would be ambiguous without brackets.
+        return Ok((entry, None));
+    }
+    Ok(match entry.split_once(':') {
+        Some((host, ports)) => (host, Some(ports)),
+        None => (entry, None),
+    })
+}
+
+fn parse_host(host: &str) -> Result<HostPattern> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() {
+        return Err(invalid("host is empty"));
+    }
+    if host == "*" {
+        return Ok(HostPattern::Any);
+    }
+    if let Some((addr, prefix)) = host.split_once('/') {
+        let ip: IpAddr = addr
+            .parse()
+            .map_err(|_| invalid(format!("'{}' is not an IP address", addr)))?;
+        let prefix: u8 = prefix
+            .parse()
+            .ok()
+            .filter(|p| *p <= max_prefix(&ip))
+            .ok_or_else(|| {
+                invalid(format!(
+                    "CIDR prefix must be 0-{} for {}",
+                    max_prefix(&ip),
+                    addr
+                ))
+            })?;
+        let network = mask(ip, prefix).unwrap_or(ip);
+        return Ok(HostPattern::Cidr(network, prefix));
+    }
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        let ip = canonical_ip(ip);
+        return Ok(HostPattern::Cidr(ip, max_prefix(&ip)));
+    }
+    if host.contains(':') {
+        return Err(invalid(format!("'{}' is not an IPv6 address", host)));
+    }
+    let (pattern, name) = match host.strip_prefix("*.") {
+        Some(base) => (HostPattern::Subdomains(base.to_string()), base),
+        None => (HostPattern::Exact(host.clone()), host.as_str()),
+    };
+    if name.contains('*') {
+        return Err(invalid(
+            "'*' is only allowed as the whole host or as a leading '*.' label",
+        ));
+    }
+    for label in name.split('.') {
+        let valid = !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(invalid(format!("'{}' is not a valid host name", name)));
+        }
+    }
+    Ok(pattern)
+}
+
+fn parse_ports(spec: &str) -> Result<PortRange> {
+    let port = |s: &str| {
+        s.parse::<u16>()
+            .ok()
+            .filter(|p| *p > 0)
+            .ok_or_else(|| invalid(format!("'{}' is not a port (1-65535)", s)))
+    };
+    let range = match spec.split_once('-') {
+        Some((start, end)) => PortRange {
+            start: port(start)?,
+            end: port(end)?,
+        },
+        None => {
+            let p = port(spec)?;
+            PortRange { start: p, end: p }
+        }
+    };
+    if range.start > range.end {
+        return Err(invalid(format!("port range {} is reversed", spec)));
+    }
+    Ok(range)
+}
+
+fn max_prefix(ip: &IpAddr) -> u8 {
+    match ip {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// IPv4-mapped IPv6 (`::ffff:a.b.c.d`) is compared as IPv4.
+fn canonical_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        v4 => v4,
+    }
+}
+
+/// `ip` with the bits after `prefix` cleared; `None` if `prefix` is too long for the family.
+fn mask(ip: IpAddr, prefix: u8) -> Option<IpAddr> {
+    match ip {
+        IpAddr::V4(v4) if prefix <= 32 => {
+            let bits = u32::from(v4) & u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            Some(IpAddr::V4(bits.into()))
+        }
+        IpAddr::V6(v6) if prefix <= 128 => {
+            let bits = u128::from(v6) & u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            Some(IpAddr::V6(bits.into()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_each_grammar_element() {
+        let cases: &[(&str, &str, &str)] = &[
+            ("*", "*", "any host, any port"),
+            ("*:443", "*:443", "any host, port 443"),
+            (
+                "API.GitHub.com",
+                "api.github.com",
+                "host api.github.com, any port",
+            ),
+            (
+                "*.github.com",
+                "*.github.com",
+                "subdomains of github.com, any port",
+            ),
+            (
+                "*.github.com:443-450",
+                "*.github.com:443-450",
+                "subdomains of github.com, ports 443-450",
+            ),
+            (
+                "10.1.2.3/8",
+                "10.0.0.0/8",
+                "addresses in 10.0.0.0/8, any port",
+            ),
+            (
+                "203.0.113.7:22",
+                "203.0.113.7:22",
+                "address 203.0.113.7, port 22",
+            ),
+            ("::1", "::1", "address ::1, any port"),
+            (
+                "[2001:db8::/32]:443",
+                "[2001:db8::/32]:443",
+                "addresses in 2001:db8::/32, port 443",
+            ),
+        ];
+        for (entry, canonical, described) in cases {
+            let rule = OutboundRule::parse(entry).unwrap();
+            assert_eq!(rule.to_string(), *canonical, "{}", entry);
+            assert_eq!(rule.describe(), *described, "{}", entry);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        let cases: &[(&str, &str)] = &[
+            ("", "empty"),
+            ("api.*.com", "leading '*.' label"),
+            ("*github.com", "leading '*.' label"),
+            ("bad host.com", "not a valid host name"),
+            ("example.com:0", "not a port"),
+            ("example.com:http", "not a port"),
+            ("example.com:450-443", "reversed"),
+            ("10.0.0.0/33", "CIDR prefix must be 0-32"),
+            ("example.com/8", "not an IP address"),
+            ("[::1", "missing ']'"),
+            ("[::1]443", "unexpected"),
+        ];
+        for (entry, needle) in cases {
+            let err = OutboundRule::parse(entry).unwrap_err().to_string();
+            assert!(err.contains(needle), "{}: {}", entry, err);
+        }
+    }
+
+    #[test]
+    fn wildcard_requires_at_least_one_label() {
+        let rule = OutboundRule::parse("*.github.com").unwrap();
+        assert!(rule.matches_name("api.github.com"));
+        assert!(rule.matches_name("a.b.github.com"));
+        assert!(!rule.matches_name("github.com"));
+        assert!(!rule.matches_name("evilgithub.com"));
+    }
+
+    #[test]
+    fn problems_are_located_by_line() {
+        let md = "---\nname: s\nnetwork:\n  outbound:\n    - api.github.com\n    - \"*.bad.*\"\n  deny:\n    - \"gist.github.com:99999\"\n---\n";
+        let policy = NetworkPolicy {
+            enabled: true,
+            outbound: vec!["api.github.com".into(), "*.bad.*".into()],
+            deny: vec!["gist.github.com:99999".into()],
+        };
+        let problems = check_network_policy(md, &policy);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].line, Some(6));
+        assert!(problems[0]
+            .to_string()
+            .starts_with("SKILL.md:6: network.outbound entry '*.bad.*'"));
+        assert_eq!(problems[1].line, Some(8));
+        assert_eq!(problems[1].field, "deny");
+    }
+}
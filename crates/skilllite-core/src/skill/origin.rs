@@ -0,0 +1,341 @@
+//! Origin-aware sandbox policy: skills written by the evolution engine are the least
+//! trusted code in the system, so they run at a stricter sandbox level than installed
+//! skills and without network unless the user confirmed them.
+//!
+//! A skill is *evolved* when it lives under `<skills>/_evolved/` or its `.meta.json`
+//! records a generation transaction (copied out of `_evolved/`). It is *confirmed* once
+//! `skilllite evolution confirm` moved it out of `_evolved/_pending/`. Skills fetched from
+//! a swarm peer live under `<skills>/_replicated/` and are treated like unconfirmed
+//! evolved skills.
+//!
+//! For evolved and replicated skills (unless `SKILLLITE_EVOLVED_SKILL_LEVEL=1`):
+//! - the sandbox level is at least `SKILLLITE_EVOLVED_SKILL_LEVEL` (default 2);
+//! - network is disabled, except for confirmed skills whose SKILL.md declares
+//!   `network.outbound` domains — then only those domains are allowed.
+
+use std::path::{Component, Path};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::metadata::NetworkPolicy;
+
+/// Key under which [`SkillSandboxPolicy::attach_to_output`] records the policy.
+pub const SANDBOX_POLICY_KEY: &str = "sandbox_policy";
+
+/// Quarantine directory (under a skills root) for skills replicated from swarm peers.
+pub const REPLICATED_DIR: &str = "_replicated";
+
+/// Where a skill came from, as far as sandboxing is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "origin", rename_all = "snake_case")]
+pub enum SkillOrigin {
+    /// Hand-written or installed with `skilllite add`.
+    Installed,
+    /// Generated by the evolution engine.
+    Evolved { confirmed: bool },
+    /// Copied from a swarm peer (`skilllite swarm --allow-skill-replication`).
+    Replicated,
+}
+
+impl SkillOrigin {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Installed => "installed",
+            Self::Evolved { confirmed: true } => "evolved (confirmed)",
+            Self::Evolved { confirmed: false } => "evolved (unconfirmed)",
+            Self::Replicated => "replicated",
+        }
+    }
+}
+
+/// Classify `skill_dir` by location and `.meta.json`.
+pub fn detect_skill_origin(skill_dir: &Path) -> SkillOrigin {
+    let parts: Vec<String> = skill_dir
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => Some(s.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    if parts.iter().any(|p| p == REPLICATED_DIR) {
+        return SkillOrigin::Replicated;
+    }
+    let evolved_at = parts.iter().position(|p| p == "_evolved");
+    if let Some(i) = evolved_at {
+        let pending = parts[i + 1..].iter().any(|p| p == "_pending");
+        return SkillOrigin::Evolved {
+            confirmed: !pending,
+        };
+    }
+    if is_auto_generated(skill_dir) {
+        return SkillOrigin::Evolved { confirmed: false };
+    }
+    SkillOrigin::Installed
+}
+
+/// `.meta.json` written at generation time carries a non-empty `generation_txn`.
+fn is_auto_generated(skill_dir: &Path) -> bool {
+    std::fs::read_to_string(skill_dir.join(".meta.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+        .and_then(|v| {
+            v.get("generation_txn")
+                .and_then(|t| t.as_str())
+                .map(|t| !t.trim().is_empty())
+        })
+        .unwrap_or(false)
+}
+
+/// Effective sandbox settings for one skill execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkillSandboxPolicy {
+    #[serde(flatten)]
+    pub origin: SkillOrigin,
+    /// Sandbox level 1/2/3 the skill runs at.
+    pub level: u8,
+    pub network_enabled: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub network_outbound: Vec<String>,
+    /// True when the evolved-skill rules apply: the skill must run isolated even with
+    /// `SKILLLITE_NO_SANDBOX`, and not in unconfined audit mode.
+    pub enforced: bool,
+    /// True when the origin policy tightened the configured level or network.
+    pub restricted: bool,
+}
+
+impl SkillSandboxPolicy {
+    /// Resolve the policy for `origin`. `configured_level` is the level the caller would
+    /// otherwise use; `evolved_level` is `SKILLLITE_EVOLVED_SKILL_LEVEL`.
+    pub fn resolve(
+        origin: SkillOrigin,
+        configured_level: u8,
+        evolved_level: u8,
+        network: &NetworkPolicy,
+    ) -> Self {
+        let confirmed = match origin {
+            SkillOrigin::Evolved { confirmed } if evolved_level > 1 => confirmed,
+            SkillOrigin::Replicated if evolved_level > 1 => false,
+            _ => {
+                return Self {
+                    origin,
+                    level: configured_level,
+                    network_enabled: network.enabled,
+                    network_outbound: network.outbound.clone(),
+                    enforced: false,
+                    restricted: false,
+                }
+            }
+        };
+        let level = configured_level.max(evolved_level.min(3));
+        let declared = if confirmed && network.enabled {
+            network.declared_hosts()
+        } else {
+            Vec::new()
+        };
+        let network_enabled = !declared.is_empty();
+        let restricted = level != configured_level
+            || network.enabled != network_enabled
+            || (network_enabled && declared != network.outbound);
+        Self {
+            origin,
+            level,
+            network_enabled,
+            network_outbound: declared,
+            enforced: true,
+            restricted,
+        }
+    }
+
+    /// [`Self::resolve`] with the origin detected from `skill_dir` and the evolved level
+    /// from the environment.
+    pub fn for_skill(skill_dir: &Path, configured_level: u8, network: &NetworkPolicy) -> Self {
+        let evolved_level = crate::config::SandboxEnvConfig::from_env().evolved_skill_level;
+        Self::resolve(
+            detect_skill_origin(skill_dir),
+            configured_level,
+            evolved_level,
+            network,
+        )
+    }
+
+    /// True for evolved and replicated skills, whose output carries the policy record.
+    pub fn is_evolved(&self) -> bool {
+        matches!(
+            self.origin,
+            SkillOrigin::Evolved { .. } | SkillOrigin::Replicated
+        )
+    }
+
+    /// Overwrite the skill's network policy with the effective one; logs when the
+    /// origin policy tightened anything.
+    pub fn apply_network(&self, skill_name: &str, network: &mut NetworkPolicy) {
+        if self.restricted {
+            tracing::info!(skill = %skill_name, policy = %self.describe(), "Origin sandbox policy applied");
+        }
+        network.enabled = self.network_enabled;
+        network.outbound = self.network_outbound.clone();
+    }
+
+    /// One line for `skilllite show` and logs, e.g.
+    /// `evolved (unconfirmed): sandbox level 2, network disabled`.
+    pub fn describe(&self) -> String {
+        let network = if !self.network_enabled {
+            "network disabled".to_string()
+        } else if self.network_outbound.is_empty() {
+            "network enabled".to_string()
+        } else {
+            format!("network limited to {}", self.network_outbound.join(", "))
+        };
+        format!(
+            "{}: sandbox level {}, {}",
+            self.origin.label(),
+            self.level,
+            network
+        )
+    }
+
+    /// Record the policy in a skill's JSON output (non-JSON output is wrapped as `stdout`).
+    pub fn attach_to_output(&self, output: &str) -> String {
+        let policy = serde_json::to_value(self).unwrap_or(Value::Null);
+        let merged = match serde_json::from_str::<Value>(output) {
+            Ok(Value::Object(mut map)) => {
+                map.insert(SANDBOX_POLICY_KEY.to_string(), policy);
+                Value::Object(map)
+            }
+            Ok(other) => serde_json::json!({ "stdout": other, SANDBOX_POLICY_KEY: policy }),
+            Err(_) => serde_json::json!({ "stdout": output, SANDBOX_POLICY_KEY: policy }),
+        };
+        merged.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(hosts: &[&str]) -> NetworkPolicy {
+        NetworkPolicy {
+            enabled: !hosts.is_empty(),
+            outbound: hosts.iter().map(|h| h.to_string()).collect(),
+            deny: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn detects_origin_from_location_and_meta() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills = tmp.path().join("skills");
+        assert_eq!(
+            detect_skill_origin(&skills.join("calc")),
+            SkillOrigin::Installed
+        );
+        assert_eq!(
+            detect_skill_origin(&skills.join("_evolved").join("fetch")),
+            SkillOrigin::Evolved { confirmed: true }
+        );
+        assert_eq!(
+            detect_skill_origin(&skills.join("_evolved").join("_pending").join("fetch")),
+            SkillOrigin::Evolved { confirmed: false }
+        );
+        assert_eq!(
+            detect_skill_origin(&skills.join(REPLICATED_DIR).join("fetch")),
+            SkillOrigin::Replicated
+        );
+
+        let copied = skills.join("fetch-copy");
+        std::fs::create_dir_all(&copied).unwrap();
+        std::fs::write(
+            copied.join(".meta.json"),
+            r#"{"name":"fetch","generation_txn":"evo_20260101"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            detect_skill_origin(&copied),
+            SkillOrigin::Evolved { confirmed: false }
+        );
+    }
+
+    #[test]
+    fn evolved_skills_get_level_floor_and_no_network() {
+        let p = SkillSandboxPolicy::resolve(
+            SkillOrigin::Evolved { confirmed: false },
+            1,
+            2,
+            &network(&["api.github.com"]),
+        );
+        assert_eq!(p.level, 2);
+        assert!(!p.network_enabled);
+        assert!(p.enforced && p.restricted);
+        assert_eq!(
+            p.describe(),
+            "evolved (unconfirmed): sandbox level 2, network disabled"
+        );
+
+        // Configured level 3 is never lowered.
+        let p = SkillSandboxPolicy::resolve(
+            SkillOrigin::Evolved { confirmed: false },
+            3,
+            2,
+            &network(&[]),
+        );
+        assert_eq!(p.level, 3);
+        assert!(!p.restricted);
+    }
+
+    #[test]
+    fn confirmed_skills_keep_only_declared_domains() {
+        let confirmed = SkillOrigin::Evolved { confirmed: true };
+        let p = SkillSandboxPolicy::resolve(confirmed, 3, 2, &network(&["api.github.com"]));
+        assert!(p.network_enabled);
+        assert_eq!(p.network_outbound, vec!["api.github.com"]);
+        assert!(!p.restricted);
+
+        // A catch-all (`*`, inferred from compatibility) is not a declared domain.
+        let p = SkillSandboxPolicy::resolve(confirmed, 3, 2, &network(&["*"]));
+        assert!(!p.network_enabled);
+        assert!(p.restricted);
+
+        // Replicated skills are never confirmed: declared domains are dropped too.
+        let p = SkillSandboxPolicy::resolve(
+            SkillOrigin::Replicated,
+            1,
+            2,
+            &network(&["api.github.com"]),
+        );
+        assert_eq!((p.level, p.network_enabled), (2, false));
+        assert!(p.enforced && p.is_evolved());
+    }
+
+    #[test]
+    fn level_one_escape_hatch_and_installed_skills_are_unchanged() {
+        let net = network(&["*"]);
+        for (origin, evolved_level) in [
+            (SkillOrigin::Installed, 2),
+            (SkillOrigin::Evolved { confirmed: false }, 1),
+        ] {
+            let p = SkillSandboxPolicy::resolve(origin, 1, evolved_level, &net);
+            assert_eq!(p.level, 1);
+            assert!(p.network_enabled);
+            assert_eq!(p.network_outbound, vec!["*"]);
+            assert!(!p.enforced && !p.restricted);
+        }
+    }
+
+    #[test]
+    fn policy_is_attached_to_json_output() {
+        let p = SkillSandboxPolicy::resolve(
+            SkillOrigin::Evolved { confirmed: false },
+            2,
+            2,
+            &network(&[]),
+        );
+        let out: Value = serde_json::from_str(&p.attach_to_output(r#"{"sum": 3}"#)).unwrap();
+        assert_eq!(out["sum"], 3);
+        assert_eq!(out[SANDBOX_POLICY_KEY]["origin"], "evolved");
+        assert_eq!(out[SANDBOX_POLICY_KEY]["confirmed"], false);
+        assert_eq!(out[SANDBOX_POLICY_KEY]["level"], 2);
+        let wrapped: Value = serde_json::from_str(&p.attach_to_output("plain")).unwrap();
+        assert_eq!(wrapped["stdout"], "plain");
+    }
+}
@@ -206,7 +206,9 @@ fn flexible_schema() -> Value {
     })
 }
 
-fn sanitize_tool_name(name: &str) -> String {
+/// Tool-name form of a skill or script name: non-alphanumerics become `_`, lowercased.
+/// A skill's tools are named `<sanitized skill>` or `<sanitized skill>__<script>`.
+pub fn sanitize_tool_name(name: &str) -> String {
     name.chars()
         .map(|c| {
             if c.is_alphanumeric() || c == '_' {
@@ -0,0 +1,486 @@
+//! Model-aware token counting for context budgeting and the `token_count` RPC.
+//!
+//! A model name maps to a [`TokenizerFamily`]. Families with a public BPE vocabulary load
+//! `<tokenizer_dir>/<vocab file>` in tiktoken rank format (`base64(token) rank` per line)
+//! and count the way tiktoken does: regex pre-tokenization, then byte-pair merges by rank.
+//! `tokenizer_dir` is `SKILLLITE_TOKENIZER_DIR`, default `~/.skilllite/tokenizers`.
+//!
+//! Without the vocab file, and for models whose tokenizer is not public, counts fall back to
+//! the ~4 chars/token heuristic and [`Tokenizer::is_approximate`] reports it. Tokenizers are
+//! loaded once per family and shared through [`for_model`].
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+use base64::Engine;
+use regex::Regex;
+
+use crate::config::env_keys::misc as keys;
+use crate::config::loader::env_optional;
+use crate::error::{Error, Result};
+
+/// Bytes per token assumed by the heuristic fallback.
+pub const HEURISTIC_CHARS_PER_TOKEN: usize = 4;
+
+/// Pieces longer than this are merged in chunks so a pathological input (one long run of
+/// punctuation) cannot make the quadratic merge loop stall a request.
+const MAX_PIECE_BYTES: usize = 1024;
+
+const CL100K_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+";
+
+const O200K_PATTERN: &str = r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]*[\p{Ll}\p{Lm}\p{Lo}\p{M}]+(?i:'s|'t|'re|'ve|'m|'ll|'d)?|[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]+[\p{Ll}\p{Lm}\p{Lo}\p{M}]*(?i:'s|'t|'re|'ve|'m|'ll|'d)?|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n/]*|\s*[\r\n]+|\s+";
+
+/// Same as cl100k except digits are split one by one.
+const QWEN_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+";
+
+/// DeepSeek-V3 isolates digit groups and CJK / kana runs before the main split.
+const DEEPSEEK_PATTERNS: &[&str] = &[
+    r"\p{N}{1,3}",
+    r"[\x{4E00}-\x{9FA5}\x{3040}-\x{309F}\x{30A0}-\x{30FF}]+",
+    r##"[!"#$%&'()*+,\-./:;<=>?@\[\\\]^_`{|}~][A-Za-z]+|[^\r\n\p{L}\p{P}\p{S}]?[\p{L}\p{M}]+| ?[\p{P}\p{S}]+[\r\n]*|\s*[\r\n]+|\s+"##,
+];
+
+/// Tokenizer used by a model family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenizerFamily {
+    /// GPT-4 / GPT-3.5 / text-embedding-3.
+    Cl100k,
+    /// GPT-4o, GPT-4.1, GPT-5, o-series.
+    O200k,
+    Qwen,
+    DeepSeek,
+    /// No public tokenizer (Claude, Gemini, unknown models): chars/4 only.
+    Heuristic,
+}
+
+impl TokenizerFamily {
+    /// Family for a model name. Provider prefixes (`openai/gpt-4o`, `Qwen/Qwen2.5-7B`) are
+    /// ignored; unknown models get [`TokenizerFamily::Heuristic`].
+    pub fn for_model(model: &str) -> Self {
+        let model = model.trim().to_ascii_lowercase();
+        let name = model.rsplit('/').next().unwrap_or(&model);
+        let starts = |prefixes: &[&str]| prefixes.iter().any(|p| name.starts_with(p));
+        if starts(&["qwen"]) {
+            Self::Qwen
+        } else if starts(&["deepseek"]) {
+            Self::DeepSeek
+        } else if starts(&[
+            "gpt-4o",
+            "chatgpt-4o",
+            "gpt-4.1",
+            "gpt-4.5",
+            "gpt-5",
+            "gpt-oss",
+            "o1",
+            "o3",
+            "o4",
+        ]) {
+            Self::O200k
+        } else if starts(&["gpt-4", "gpt-3.5", "gpt-35", "text-embedding-"]) {
+            Self::Cl100k
+        } else {
+            Self::Heuristic
+        }
+    }
+
+    /// Name reported in the `token_count` RPC response.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cl100k => "cl100k",
+            Self::O200k => "o200k",
+            Self::Qwen => "qwen",
+            Self::DeepSeek => "deepseek",
+            Self::Heuristic => "heuristic",
+        }
+    }
+
+    /// Rank file looked up in the tokenizer directory.
+    pub fn vocab_file(self) -> Option<&'static str> {
+        match self {
+            Self::Cl100k => Some("cl100k_base.tiktoken"),
+            Self::O200k => Some("o200k_base.tiktoken"),
+            Self::Qwen => Some("qwen.tiktoken"),
+            Self::DeepSeek => Some("deepseek_v3.tiktoken"),
+            Self::Heuristic => None,
+        }
+    }
+
+    fn patterns(self) -> &'static [&'static str] {
+        match self {
+            Self::Cl100k => &[CL100K_PATTERN],
+            Self::O200k => &[O200K_PATTERN],
+            Self::Qwen => &[QWEN_PATTERN],
+            Self::DeepSeek => DEEPSEEK_PATTERNS,
+            Self::Heuristic => &[],
+        }
+    }
+}
+
+/// Token counter for one family; exact when its vocabulary is loaded.
+pub struct Tokenizer {
+    family: TokenizerFamily,
+    bpe: Option<Bpe>,
+}
+
+struct Bpe {
+    ranks: HashMap<Vec<u8>, u32>,
+    /// Pre-tokenizer stages; each splits the previous stage's pieces further.
+    stages: Vec<Regex>,
+}
+
+impl Tokenizer {
+    /// chars/4 counter reporting itself as `family`.
+    pub fn heuristic(family: TokenizerFamily) -> Self {
+        Self { family, bpe: None }
+    }
+
+    /// Exact counter from tiktoken-format rank data.
+    pub fn from_ranks(family: TokenizerFamily, ranks: &str) -> Result<Self> {
+        let stages = family
+            .patterns()
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| Error::validation(e.to_string())))
+            .collect::<Result<Vec<_>>>()?;
+        if stages.is_empty() {
+            return Err(Error::validation(format!(
+                "tokenizer family '{}' has no BPE vocabulary",
+                family.as_str()
+            )));
+        }
+        Ok(Self {
+            family,
+            bpe: Some(Bpe {
+                ranks: parse_ranks(ranks)?,
+                stages,
+            }),
+        })
+    }
+
+    /// Load `family`'s vocab from `dir`, falling back to the heuristic when it is missing or
+    /// unreadable.
+    pub fn load(family: TokenizerFamily, dir: &Path) -> Self {
+        let Some(file) = family.vocab_file() else {
+            return Self::heuristic(family);
+        };
+        let path = dir.join(file);
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            tracing::debug!(path = %path.display(), "No tokenizer vocab; using chars/4 estimate");
+            return Self::heuristic(family);
+        };
+        Self::from_ranks(family, &text).unwrap_or_else(|e| {
+            tracing::warn!(path = %path.display(), "Invalid tokenizer vocab ({}); using chars/4 estimate", e);
+            Self::heuristic(family)
+        })
+    }
+
+    pub fn family(&self) -> TokenizerFamily {
+        self.family
+    }
+
+    /// `true` when counts come from the chars/4 heuristic rather than the real vocabulary.
+    pub fn is_approximate(&self) -> bool {
+        self.bpe.is_none()
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        match &self.bpe {
+            Some(bpe) => bpe.count(text),
+            None => text.len().div_ceil(HEURISTIC_CHARS_PER_TOKEN),
+        }
+    }
+
+    /// Prefix of `text` (on a char boundary) that fits in `max_tokens`: whole pre-tokenizer
+    /// pieces while they fit, then the longest fitting part of the next one.
+    pub fn truncate<'a>(&self, text: &'a str, max_tokens: usize) -> &'a str {
+        let end = match &self.bpe {
+            Some(bpe) => bpe.truncate_len(text, max_tokens),
+            None => {
+                let mut end = text
+                    .len()
+                    .min(max_tokens.saturating_mul(HEURISTIC_CHARS_PER_TOKEN));
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                end
+            }
+        };
+        &text[..end]
+    }
+}
+
+impl Bpe {
+    fn count(&self, text: &str) -> usize {
+        split_pieces(&self.stages, text)
+            .into_iter()
+            .map(|(_, piece)| self.count_bytes(piece.as_bytes()))
+            .sum()
+    }
+
+    fn truncate_len(&self, text: &str, max_tokens: usize) -> usize {
+        let mut used = 0;
+        for (start, piece) in split_pieces(&self.stages, text) {
+            let n = self.count_bytes(piece.as_bytes());
+            if used + n > max_tokens {
+                let budget = max_tokens - used;
+                let cut = piece
+                    .char_indices()
+                    .rev()
+                    .map(|(i, _)| i)
+                    .find(|&i| i > 0 && self.count_bytes(&piece.as_bytes()[..i]) <= budget)
+                    .unwrap_or(0);
+                return start + cut;
+            }
+            used += n;
+        }
+        text.len()
+    }
+
+    fn count_bytes(&self, bytes: &[u8]) -> usize {
+        bytes
+            .chunks(MAX_PIECE_BYTES)
+            .map(|chunk| self.count_piece(chunk))
+            .sum()
+    }
+
+    /// Number of tokens after merging the lowest-ranked adjacent pair until none is left.
+    fn count_piece(&self, piece: &[u8]) -> usize {
+        if piece.len() <= 1 || self.ranks.contains_key(piece) {
+            return piece.len().min(1);
+        }
+        // `ranks[i]` is the rank of `piece[parts[i]..parts[i + 2]]`; the last slot is a sentinel.
+        let rank = |parts: &[usize], i: usize| {
+            parts
+                .get(i + 2)
+                .and_then(|&end| self.ranks.get(&piece[parts[i]..end]).copied())
+                .unwrap_or(u32::MAX)
+        };
+        let mut parts: Vec<usize> = (0..=piece.len()).collect();
+        let mut ranks: Vec<u32> = (0..parts.len() - 1).map(|i| rank(&parts, i)).collect();
+        while let Some((i, _)) = ranks
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| **r != u32::MAX)
+            .min_by_key(|(_, r)| **r)
+        {
+            parts.remove(i + 1);
+            ranks.remove(i + 1);
+            ranks[i] = rank(&parts, i);
+            if i > 0 {
+                ranks[i - 1] = rank(&parts, i - 1);
+            }
+        }
+        parts.len() - 1
+    }
+}
+
+fn parse_ranks(text: &str) -> Result<HashMap<Vec<u8>, u32>> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(n, line)| {
+            let invalid = || Error::validation(format!("invalid rank line {}", n + 1));
+            let (token, rank) = line.trim().split_once(' ').ok_or_else(invalid)?;
+            let token = engine.decode(token).map_err(|_| invalid())?;
+            let rank = rank.trim().parse().map_err(|_| invalid())?;
+            Ok((token, rank))
+        })
+        .collect()
+}
+
+/// Apply each stage in turn; matches and the gaps between them both become pieces, returned
+/// with their byte offset in `text`.
+fn split_pieces<'a>(stages: &[Regex], text: &'a str) -> Vec<(usize, &'a str)> {
+    let mut pieces = vec![(0, text)];
+    for (n, re) in stages.iter().enumerate() {
+        let main = n + 1 == stages.len();
+        let mut next = Vec::with_capacity(pieces.len());
+        for (offset, piece) in pieces {
+            let mut pos = 0;
+            while let Some(m) = re.find_at(piece, pos) {
+                if m.start() > pos {
+                    next.push((offset + pos, &piece[pos..m.start()]));
+                }
+                let end = if main {
+                    give_back_trailing_space(piece, m.start(), m.end())
+                } else {
+                    m.end()
+                };
+                next.push((offset + m.start(), &piece[m.start()..end]));
+                pos = end;
+            }
+            if pos < piece.len() {
+                next.push((offset + pos, &piece[pos..]));
+            }
+        }
+        pieces = next;
+    }
+    pieces
+}
+
+/// Emulate the `\s+(?!\S)` branch the `regex` crate cannot express: a run of spaces followed
+/// by a non-space char leaves its last space to start the next piece (`"  x"` → `" "`, `" x"`).
+fn give_back_trailing_space(text: &str, start: usize, end: usize) -> usize {
+    let piece = &text[start..end];
+    let before_word = text[end..]
+        .chars()
+        .next()
+        .is_some_and(|c| !c.is_whitespace());
+    if !before_word || piece.ends_with(['\r', '\n']) || !piece.chars().all(char::is_whitespace) {
+        return end;
+    }
+    match piece.char_indices().last() {
+        Some((last, _)) if last > 0 => start + last,
+        _ => end,
+    }
+}
+
+/// `SKILLLITE_TOKENIZER_DIR`, else `<data root>/tokenizers` (`~/.skilllite/tokenizers`).
+pub fn tokenizer_dir() -> PathBuf {
+    env_optional(keys::SKILLLITE_TOKENIZER_DIR, &[])
+        .map(PathBuf::from)
+        .unwrap_or_else(|| crate::paths::data_root().join("tokenizers"))
+}
+
+/// Shared tokenizer for `model`, loaded from [`tokenizer_dir`] on first use.
+pub fn for_model(model: &str) -> Arc<Tokenizer> {
+    for_family(TokenizerFamily::for_model(model))
+}
+
+/// Shared tokenizer for `family`, loaded from [`tokenizer_dir`] on first use.
+pub fn for_family(family: TokenizerFamily) -> Arc<Tokenizer> {
+    static CACHE: OnceLock<Mutex<HashMap<TokenizerFamily, Arc<Tokenizer>>>> = OnceLock::new();
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    cache
+        .entry(family)
+        .or_insert_with(|| Arc::new(Tokenizer::load(family, &tokenizer_dir())))
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All single bytes plus a handful of merges, in tiktoken rank format.
+    fn tiny_ranks() -> String {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let mut tokens: Vec<Vec<u8>> = (0u8..=255).map(|b| vec![b]).collect();
+        for merged in [
+            "he", "ll", "hell", "hello", " w", "or", " wor", " world", "  ",
+        ] {
+            tokens.push(merged.as_bytes().to_vec());
+        }
+        tokens
+            .iter()
+            .enumerate()
+            .map(|(rank, t)| format!("{} {}\n", engine.encode(t), rank))
+            .collect()
+    }
+
+    fn pieces(family: TokenizerFamily, text: &str) -> Vec<String> {
+        let stages: Vec<Regex> = family
+            .patterns()
+            .iter()
+            .map(|p| Regex::new(p).unwrap())
+            .collect();
+        split_pieces(&stages, text)
+            .into_iter()
+            .map(|(_, piece)| piece.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn models_map_to_families() {
+        let cases = [
+            ("gpt-4o-mini", TokenizerFamily::O200k),
+            ("openai/gpt-5", TokenizerFamily::O200k),
+            ("o3-mini", TokenizerFamily::O200k),
+            ("gpt-4-turbo", TokenizerFamily::Cl100k),
+            ("gpt-3.5-turbo", TokenizerFamily::Cl100k),
+            ("Qwen/Qwen2.5-7B-Instruct", TokenizerFamily::Qwen),
+            ("qwen-max", TokenizerFamily::Qwen),
+            ("deepseek-chat", TokenizerFamily::DeepSeek),
+            ("claude-sonnet-4", TokenizerFamily::Heuristic),
+            ("llama3", TokenizerFamily::Heuristic),
+        ];
+        for (model, family) in cases {
+            assert_eq!(TokenizerFamily::for_model(model), family, "{model}");
+        }
+    }
+
+    #[test]
+    fn pre_tokenizer_matches_tiktoken_splits() {
+        assert_eq!(
+            pieces(TokenizerFamily::Cl100k, "Hello,  world 12345\n\nI'm"),
+            ["Hello", ",", " ", " world", " ", "123", "45", "\n\n", "I", "'m"]
+        );
+        assert_eq!(
+            pieces(TokenizerFamily::Qwen, "v2.10"),
+            ["v", "2", ".", "1", "0"]
+        );
+        assert_eq!(
+            pieces(TokenizerFamily::O200k, "HelloWorld foo/\nbar"),
+            ["Hello", "World", " foo", "/\n", "bar"]
+        );
+        assert_eq!(
+            pieces(TokenizerFamily::DeepSeek, "价格是1234元 ok"),
+            ["价格是", "123", "4", "元", " ok"]
+        );
+    }
+
+    #[test]
+    fn bpe_merges_by_rank() {
+        let tok = Tokenizer::from_ranks(TokenizerFamily::Cl100k, &tiny_ranks()).unwrap();
+        assert!(!tok.is_approximate());
+        assert_eq!(tok.count(""), 0);
+        assert_eq!(tok.count("hello world"), 2);
+        assert_eq!(tok.count("hello   world"), 3);
+        // "help" → "hel" has no merge, so "he" + "l" + "p".
+        assert_eq!(tok.count("help"), 3);
+        assert_eq!(tok.truncate("hello world help", 2), "hello world");
+        assert_eq!(tok.truncate("hello world", 1), "hello");
+        // The last piece is cut inside: " help" is 4 tokens, " he" is 2.
+        assert_eq!(tok.truncate("hello help", 3), "hello he");
+    }
+
+    #[test]
+    fn missing_vocab_falls_back_to_heuristic() {
+        let dir = tempfile::tempdir().unwrap();
+        let tok = Tokenizer::load(TokenizerFamily::O200k, dir.path());
+        assert!(tok.is_approximate());
+        assert_eq!(tok.family(), TokenizerFamily::O200k);
+        assert_eq!(tok.count("abcdefghi"), 3);
+        assert_eq!(tok.truncate("abcdefghi", 1), "abcd");
+        assert!(Tokenizer::from_ranks(TokenizerFamily::Heuristic, "").is_err());
+    }
+
+    /// Reference counts from each family's published tokenizer. Needs the real vocab files
+    /// in `SKILLLITE_TOKENIZER_DIR` (or `~/.skilllite/tokenizers`).
+    #[test]
+    #[ignore = "requires the tiktoken vocab files in the tokenizer dir"]
+    fn counts_match_reference_tokenizers() {
+        let fixtures: &[(TokenizerFamily, &str, usize)] = &[
+            (TokenizerFamily::Cl100k, "hello world", 2),
+            (TokenizerFamily::Cl100k, "Hello, world!", 4),
+            (TokenizerFamily::Cl100k, "tiktoken is great!", 6),
+            (TokenizerFamily::O200k, "hello world", 2),
+            (TokenizerFamily::O200k, "Hello, world!", 4),
+            (TokenizerFamily::O200k, "tiktoken is great!", 6),
+            (TokenizerFamily::Qwen, "hello world", 2),
+            (TokenizerFamily::Qwen, "Hello, world!", 4),
+            (TokenizerFamily::Qwen, "12345", 5),
+            (TokenizerFamily::DeepSeek, "hello world", 2),
+            (TokenizerFamily::DeepSeek, "Hello, world!", 4),
+            (TokenizerFamily::DeepSeek, "12345", 2),
+        ];
+        for &(family, text, expected) in fixtures {
+            let tok = for_family(family);
+            assert!(!tok.is_approximate(), "{} vocab not found", family.as_str());
+            assert_eq!(tok.count(text), expected, "{} {:?}", family.as_str(), text);
+        }
+    }
+}
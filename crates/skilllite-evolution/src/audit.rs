@@ -4,7 +4,9 @@ use std::collections::HashSet;
 use std::path::Path;
 
 use rusqlite::{params, Connection};
+use skilllite_core::audit_chain::{self, ChainReport};
 
+use crate::feedback::{DECISION_STATE_EXPIRED, DECISION_STATE_PENDING, DECISION_STATE_PROCESSED};
 use crate::scope::EvolutionScope;
 use crate::Result;
 
@@ -25,7 +27,7 @@ pub fn log_evolution_event(
         params![ts, event_type, target_id, reason, txn_id],
     )?;
 
-    let log_path = chat_root.join("evolution.log");
+    let log_path = crate::retention::evolution_log_path(chat_root);
     let entry = serde_json::json!({
         "ts": ts,
         "type": event_type,
@@ -33,29 +35,47 @@ pub fn log_evolution_event(
         "reason": reason,
         "txn_id": txn_id,
     });
-    let mut line = serde_json::to_string(&entry)?;
-    line.push('\n');
-    use std::io::Write;
-    let mut file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&log_path)?;
-    file.write_all(line.as_bytes())?;
+    // Rotation and append share the append lock: no writer chains to a file another one is
+    // rotating away.
+    audit_chain::with_lock_file(&audit_chain::lock_path(&log_path), || {
+        if let Err(e) = crate::retention::rotate_if_needed(
+            &log_path,
+            &crate::retention::LogRotationConfig::from_env(),
+        ) {
+            tracing::warn!("evolution.log rotation failed: {}", e);
+        }
+        // After a rotation the live file is new; its first entry chains to the `.1` archive.
+        let previous = crate::retention::archive_paths(&log_path)
+            .into_iter()
+            .next()
+            .map(|(_, p)| p);
+        audit_chain::append_chained_unlocked(&log_path, &entry, previous.as_deref())
+    })?;
 
     skilllite_core::observability::audit_evolution_event(event_type, target_id, reason, txn_id);
 
     Ok(())
 }
 
+/// Verify the hash chain of `evolution.log` and its rotated archives.
+pub fn verify_evolution_log(chat_root: &Path) -> ChainReport {
+    let log_path = crate::retention::evolution_log_path(chat_root);
+    audit_chain::verify_chain(&crate::retention::chronological_files(&log_path))
+}
+
 // ─── Mark decisions evolved ───────────────────────────────────────────────────
 
+/// Mark decisions consumed by a run as processed. Expired rows keep their state so they stay
+/// distinguishable from decisions a learner actually read.
 pub fn mark_decisions_evolved(conn: &Connection, ids: &[i64]) -> Result<()> {
     if ids.is_empty() {
         return Ok(());
     }
     let placeholders: Vec<String> = ids.iter().map(|_| "?".to_string()).collect();
     let sql = format!(
-        "UPDATE decisions SET evolved = 1 WHERE id IN ({})",
+        "UPDATE decisions SET evolved = {} WHERE evolved = {} AND id IN ({})",
+        DECISION_STATE_PROCESSED,
+        DECISION_STATE_PENDING,
         placeholders.join(",")
     );
     let mut stmt = conn.prepare(&sql)?;
@@ -68,6 +88,43 @@ pub fn mark_decisions_evolved(conn: &Connection, ids: &[i64]) -> Result<()> {
     Ok(())
 }
 
+/// Record which decisions txn `txn_id` consumed (read back by [`crate::explain`]).
+pub fn record_decision_txn(conn: &Connection, txn_id: &str, ids: &[i64]) -> Result<()> {
+    let mut stmt =
+        conn.prepare("INSERT INTO decision_txns (decision_id, txn_id) VALUES (?1, ?2)")?;
+    for id in ids {
+        stmt.execute(params![id, txn_id])?;
+    }
+    Ok(())
+}
+
+/// Decision ids recorded for `txn_id` by [`record_decision_txn`].
+pub fn decision_ids_for_txn(conn: &Connection, txn_id: &str) -> Result<Vec<i64>> {
+    let mut stmt = conn
+        .prepare("SELECT decision_id FROM decision_txns WHERE txn_id = ?1 ORDER BY decision_id")?;
+    let ids = stmt
+        .query_map(params![txn_id], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<i64>, _>>()?;
+    Ok(ids)
+}
+
+/// Bulk-mark pending decisions older than `max_age_days` as expired (`evolved = 2`) so scope
+/// queries stop scanning them. Returns the number of rows expired; `max_age_days <= 0` is a no-op.
+pub fn expire_stale_decisions(conn: &Connection, max_age_days: i64) -> Result<usize> {
+    if max_age_days <= 0 {
+        return Ok(0);
+    }
+    let n = conn.execute(
+        &format!(
+            "UPDATE decisions SET evolved = ?1
+             WHERE evolved = ?2 AND ts < datetime('now', '-{} days')",
+            max_age_days
+        ),
+        params![DECISION_STATE_EXPIRED, DECISION_STATE_PENDING],
+    )?;
+    Ok(n)
+}
+
 /// Decision ids to mark `evolved=1` after a run: only rows actually read by enabled learners,
 /// intersected with `scope.decision_ids` (avoids marking the entire recent window).
 pub fn decision_ids_to_mark_after_run(
@@ -169,4 +226,104 @@ mod decision_mark_tests {
             .unwrap();
         assert_eq!(evolved_cnt, to_mark.len() as i64);
     }
+
+    #[test]
+    fn expires_old_pending_decisions_and_keeps_them_apart_from_processed() {
+        let conn = open_mem();
+        for (evolved, age) in [(0, "-20 days"), (0, "-1 days"), (1, "-30 days")] {
+            conn.execute(
+                "INSERT INTO decisions (evolved, total_tools, task_completed, task_description, ts)
+                 VALUES (?1, 2, 1, 'task', datetime('now', ?2))",
+                rusqlite::params![evolved, age],
+            )
+            .unwrap();
+        }
+        assert_eq!(expire_stale_decisions(&conn, 0).unwrap(), 0);
+        assert_eq!(expire_stale_decisions(&conn, 14).unwrap(), 1);
+        assert_eq!(feedback::count_expired_decisions(&conn).unwrap(), 1);
+        assert_eq!(feedback::count_unprocessed_decisions(&conn).unwrap(), 1);
+
+        // A later run that touches the expired row must not relabel it as processed.
+        mark_decisions_evolved(&conn, &[1, 2]).unwrap();
+        let states: Vec<i64> = conn
+            .prepare("SELECT evolved FROM decisions ORDER BY id")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .filter_map(|r| r.ok())
+            .collect();
+        assert_eq!(states, vec![2, 1, 1]);
+    }
+}
+
+#[cfg(test)]
+mod chain_tests {
+    use super::*;
+    use crate::feedback;
+    use crate::retention::{self, LogRotationConfig};
+
+    #[test]
+    fn evolution_log_chain_survives_rotation_and_reports_tampered_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+        feedback::ensure_evolution_tables(&conn).unwrap();
+        let log_path = retention::evolution_log_path(dir.path());
+
+        log_evolution_event(&conn, dir.path(), "rule_added", "r1", "新规则", "t1").unwrap();
+        log_evolution_event(&conn, dir.path(), "rule_added", "r2", "second", "t1").unwrap();
+        let cfg = LogRotationConfig {
+            max_bytes: 1,
+            keep_archives: 3,
+        };
+        assert!(retention::rotate_if_needed(&log_path, &cfg).unwrap());
+        log_evolution_event(&conn, dir.path(), "rule_removed", "r1", "stale", "t2").unwrap();
+        log_evolution_event(&conn, dir.path(), "rule_removed", "r2", "stale", "t2").unwrap();
+
+        let report = verify_evolution_log(dir.path());
+        assert_eq!(report.status(), "ok");
+        assert_eq!(report.verified_entries, 4);
+        assert_eq!(report.files.len(), 2);
+
+        let text = std::fs::read_to_string(&log_path).unwrap();
+        let tampered = text.replacen("\"stale\"", "\"fine\"", 1);
+        std::fs::write(&log_path, tampered).unwrap();
+        let brk = verify_evolution_log(dir.path()).first_break.unwrap();
+        assert_eq!((brk.file, brk.line), (log_path.clone(), 1));
+
+        // Deleting a line breaks the link of the one after it.
+        let lines: Vec<&str> = text.lines().collect();
+        std::fs::write(&log_path, format!("{}\n", lines[1])).unwrap();
+        let brk = verify_evolution_log(dir.path()).first_break.unwrap();
+        assert_eq!(brk.line, 1);
+        assert!(brk.reason.contains("prev_hash"));
+    }
+    #[test]
+    fn concurrent_writers_keep_the_chain_intact_across_rotations() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = skilllite_core::config::env_keys::evolution::SKILLLITE_EVO_LOG_MAX_BYTES;
+        let saved = std::env::var(key).ok();
+        std::env::set_var(key, "2048");
+        std::thread::scope(|scope| {
+            for writer in 0..4 {
+                let dir = dir.path();
+                scope.spawn(move || {
+                    let conn = Connection::open_in_memory().unwrap();
+                    feedback::ensure_evolution_tables(&conn).unwrap();
+                    for seq in 0..30 {
+                        let id = format!("w{}-{}", writer, seq);
+                        log_evolution_event(&conn, dir, "rule_added", &id, "concurrent", "t")
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        match saved {
+            Some(v) => std::env::set_var(key, v),
+            None => std::env::remove_var(key),
+        }
+
+        let report = verify_evolution_log(dir.path());
+        assert!(report.is_intact(), "{:?}", report.first_break);
+        assert!(report.files.len() > 1, "no rotation happened");
+    }
 }
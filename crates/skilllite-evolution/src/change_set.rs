@@ -0,0 +1,203 @@
+//! Gatekeeper funnel for learner output (L2 limits + L3 content).
+//!
+//! Learners propose each new rule, example or skill through a [`ChangeSet`] with a score
+//! (their confidence in it). The set runs the L3 content check per item and, when finished,
+//! keeps the highest-scoring proposals up to the L2 limit. Everything left out comes back as a
+//! [`DroppedChange`] with the reason, so the run summary and changelog can show it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::gatekeeper::{
+    gatekeeper_l3_content, L2_MAX_NEW_EXAMPLES, L2_MAX_NEW_RULES, L2_MAX_NEW_SKILLS,
+};
+
+/// What a learner proposes; each kind has its own L2 limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Rule,
+    Example,
+    Skill,
+}
+
+impl ChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Rule => "rule",
+            Self::Example => "example",
+            Self::Skill => "skill",
+        }
+    }
+
+    /// New items of this kind allowed per run (L2).
+    pub fn l2_limit(self) -> usize {
+        match self {
+            Self::Rule => L2_MAX_NEW_RULES,
+            Self::Example => L2_MAX_NEW_EXAMPLES,
+            Self::Skill => L2_MAX_NEW_SKILLS,
+        }
+    }
+}
+
+/// Which gate rejected a proposal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropGate {
+    /// Over the per-run limit; the proposal may come back in a later run.
+    L2,
+    /// Sensitive or unsafe content.
+    L3,
+}
+
+/// A proposal that was not applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DroppedChange {
+    pub kind: ChangeKind,
+    pub id: String,
+    pub score: f32,
+    pub gate: DropGate,
+    pub reason: String,
+}
+
+impl DroppedChange {
+    /// Dropped for size rather than content.
+    pub fn deferred(&self) -> bool {
+        self.gate == DropGate::L2
+    }
+}
+
+/// Outcome of [`ChangeSet::finish`]: accepted items in proposal order, plus the dropped ones.
+#[derive(Debug)]
+pub struct Applied<T> {
+    pub accepted: Vec<T>,
+    pub dropped: Vec<DroppedChange>,
+}
+
+/// Collects one learner's proposals of a single [`ChangeKind`].
+#[derive(Debug)]
+pub struct ChangeSet<T> {
+    kind: ChangeKind,
+    limit: usize,
+    proposed: Vec<(String, f32, T)>,
+    dropped: Vec<DroppedChange>,
+}
+
+impl<T> ChangeSet<T> {
+    pub fn new(kind: ChangeKind) -> Self {
+        Self {
+            kind,
+            limit: kind.l2_limit(),
+            proposed: Vec::new(),
+            dropped: Vec::new(),
+        }
+    }
+
+    /// Tighten the limit below L2, e.g. to the free slots left in `rules.json`.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit.min(self.kind.l2_limit());
+        self
+    }
+
+    /// Propose `item`. `content` is what L3 checks; `score` ranks it against the other
+    /// proposals when the limit is hit. A repeated id is ignored (the first proposal wins).
+    pub fn propose(&mut self, id: impl Into<String>, score: f32, content: &str, item: T) {
+        let id = id.into();
+        if self.proposed.iter().any(|(existing, _, _)| *existing == id) {
+            return;
+        }
+        if let Err(e) = gatekeeper_l3_content(content) {
+            tracing::warn!("L3 rejected {} {}: {}", self.kind.as_str(), id, e);
+            self.dropped.push(DroppedChange {
+                kind: self.kind,
+                id,
+                score,
+                gate: DropGate::L3,
+                reason: e.to_string(),
+            });
+            return;
+        }
+        self.proposed.push((id, score, item));
+    }
+
+    pub fn len(&self) -> usize {
+        self.proposed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proposed.is_empty()
+    }
+
+    /// Apply the limit: keep the `limit` highest scores (ties go to the earlier proposal).
+    pub fn finish(self) -> Applied<T> {
+        let mut ranked: Vec<usize> = (0..self.proposed.len()).collect();
+        ranked.sort_by(|&a, &b| self.proposed[b].1.total_cmp(&self.proposed[a].1));
+        let mut keep = vec![false; self.proposed.len()];
+        for &i in ranked.iter().take(self.limit) {
+            keep[i] = true;
+        }
+
+        let mut dropped = self.dropped;
+        let mut accepted = Vec::new();
+        for ((id, score, item), kept) in self.proposed.into_iter().zip(keep) {
+            if kept {
+                accepted.push(item);
+                continue;
+            }
+            tracing::info!(
+                "L2 deferred {} {} (score {:.2}): limit {} per run",
+                self.kind.as_str(),
+                id,
+                score,
+                self.limit
+            );
+            dropped.push(DroppedChange {
+                kind: self.kind,
+                id,
+                score,
+                gate: DropGate::L2,
+                reason: format!(
+                    "Gatekeeper L2: at most {} new {}(s) per run; lower score than the ones kept",
+                    self.limit,
+                    self.kind.as_str()
+                ),
+            });
+        }
+        Applied { accepted, dropped }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_highest_scores_in_proposal_order() {
+        let mut set = ChangeSet::new(ChangeKind::Example);
+        for (id, score) in [("a", 0.2), ("b", 0.9), ("c", 0.5), ("d", 0.7), ("e", 0.5)] {
+            set.propose(id, score, "plain text", id.to_string());
+        }
+        set.propose("b", 1.0, "duplicate id", "b2".to_string());
+
+        let applied = set.finish();
+        assert_eq!(applied.accepted, vec!["b", "c", "d"]);
+        let dropped: Vec<&str> = applied.dropped.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(dropped, vec!["a", "e"]);
+        assert!(applied.dropped.iter().all(DroppedChange::deferred));
+    }
+
+    #[test]
+    fn l3_rejects_before_ranking_and_limit_can_be_tightened() {
+        let mut set = ChangeSet::new(ChangeKind::Rule).with_limit(1);
+        set.propose("leaky", 1.0, "store the api_key in notes", ());
+        set.propose("ok_1", 0.6, "read before write", ());
+        set.propose("ok_2", 0.4, "list before read", ());
+
+        let applied = set.finish();
+        assert_eq!(applied.accepted.len(), 1);
+        assert_eq!(applied.dropped.len(), 2);
+        assert_eq!(applied.dropped[0].id, "leaky");
+        assert_eq!(applied.dropped[0].gate, DropGate::L3);
+        assert_eq!(applied.dropped[1].id, "ok_2");
+        assert!(applied.dropped[1].deferred());
+    }
+}
@@ -1,19 +1,28 @@
-//! Append-only changelog under prompts/_versions/.
+//! Append-only changelog under prompts/_versions/ (rotated by [`crate::retention`]).
 
 use std::path::Path;
 
+use crate::change_set::DroppedChange;
+use crate::prompt_eval::PromptEvaluation;
+use crate::retention::{changelog_path, rotate_if_needed, LogRotationConfig};
 use crate::snapshots::versions_dir;
 use crate::Result;
 
 // ─── Changelog ───────────────────────────────────────────────────────────────
 
 #[derive(serde::Serialize)]
-struct ChangelogEntry {
+struct ChangelogEntry<'a> {
     txn_id: String,
     ts: String,
     files: Vec<String>,
     changes: Vec<ChangeDetail>,
     reason: String,
+    /// Holdout verdict, scores and samples when new rules were evaluated before promotion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    evaluation: Option<&'a PromptEvaluation>,
+    /// Proposals the gatekeepers kept out of this run (L2 over-limit or L3 content).
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    dropped: &'a [DroppedChange],
 }
 
 #[derive(serde::Serialize)]
@@ -29,10 +38,14 @@ pub fn append_changelog(
     files: &[String],
     changes: &[(String, String)],
     reason: &str,
+    evaluation: Option<&PromptEvaluation>,
+    dropped: &[DroppedChange],
 ) -> Result<()> {
-    let vdir = versions_dir(chat_root);
-    std::fs::create_dir_all(&vdir)?;
-    let path = vdir.join("changelog.jsonl");
+    std::fs::create_dir_all(versions_dir(chat_root))?;
+    let path = changelog_path(chat_root);
+    if let Err(e) = rotate_if_needed(&path, &LogRotationConfig::from_env()) {
+        tracing::warn!("changelog.jsonl rotation failed: {}", e);
+    }
 
     let entry = ChangelogEntry {
         txn_id: txn_id.to_string(),
@@ -46,6 +59,8 @@ pub fn append_changelog(
             })
             .collect(),
         reason: reason.to_string(),
+        evaluation,
+        dropped,
     };
 
     let mut line = serde_json::to_string(&entry)?;
@@ -1,6 +1,7 @@
 //! Evolution mode, skill action, and threshold env configuration.
 
 use skilllite_core::config::env_keys::evolution as evo_keys;
+use skilllite_core::config::Settings;
 
 // ─── EVO-5: Evolution mode ───────────────────────────────────────────────────
 
@@ -15,8 +16,13 @@ pub enum EvolutionMode {
 }
 
 impl EvolutionMode {
+    /// `evolution.mode` from the layered settings (`SKILLLITE_EVOLUTION` or config files).
     pub fn from_env() -> Self {
-        match std::env::var(evo_keys::SKILLLITE_EVOLUTION).ok().as_deref() {
+        Self::parse(Settings::load().get("evolution.mode"))
+    }
+
+    fn parse(raw: Option<&str>) -> Self {
+        match raw {
             None | Some("1") | Some("true") | Some("") => Self::All,
             Some("0") | Some("false") => Self::Disabled,
             Some("prompts") => Self::PromptsOnly,
@@ -50,6 +56,50 @@ impl EvolutionMode {
     }
 }
 
+// ─── Prompt holdout evaluation ───────────────────────────────────────────────
+
+/// How candidate rules are evaluated before they replace `prompts/rules.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PromptEvaluationMode {
+    /// Rules go live directly (no candidate dir, no replay).
+    #[default]
+    Off,
+    /// Replayed plans must parse and reference known tools.
+    Cheap,
+    /// `Cheap` plus an LLM judge score per plan.
+    Llm,
+}
+
+impl PromptEvaluationMode {
+    pub fn from_env() -> Self {
+        match std::env::var(evo_keys::SKILLLITE_EVO_PROMPT_EVALUATION)
+            .ok()
+            .as_deref()
+            .map(str::trim)
+        {
+            None | Some("") | Some("off") | Some("0") | Some("false") => Self::Off,
+            Some("cheap") => Self::Cheap,
+            Some("llm") => Self::Llm,
+            Some(other) => {
+                tracing::warn!(
+                    "Unknown {} value '{}', defaulting to off",
+                    evo_keys::SKILLLITE_EVO_PROMPT_EVALUATION,
+                    other
+                );
+                Self::Off
+            }
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Cheap => "cheap",
+            Self::Llm => "llm",
+        }
+    }
+}
+
 // ─── SkillAction (used by should_evolve) ──────────────────────────────────────
 
 /// Action type for skill evolution.
@@ -70,7 +120,7 @@ impl SkillAction {
     }
 }
 
-/// 进化触发阈值，均由环境变量配置，未设置时使用下列默认值。
+/// 进化触发阈值，由环境变量或配置文件 `[evolution]` 表设置，未设置时使用下列默认值。
 #[derive(Debug, Clone)]
 pub struct EvolutionThresholds {
     pub cooldown_hours: f64,
@@ -84,6 +134,8 @@ pub struct EvolutionThresholds {
     pub replans_min_prompts: i64,
     pub repeated_pattern_min_count: i64,
     pub repeated_pattern_min_success_rate: f64,
+    /// Unprocessed decisions older than this are ignored and expired (`evolved = 2`); 0 disables.
+    pub decision_max_age_days: i64,
 }
 
 impl Default for EvolutionThresholds {
@@ -100,6 +152,7 @@ impl Default for EvolutionThresholds {
             replans_min_prompts: 2,
             repeated_pattern_min_count: 3,
             repeated_pattern_min_success_rate: 0.8,
+            decision_max_age_days: 14,
         }
     }
 }
@@ -130,6 +183,7 @@ impl EvolutionThresholds {
             replans_min_prompts: 1,
             repeated_pattern_min_count: 2,
             repeated_pattern_min_success_rate: 0.7,
+            decision_max_age_days: 14,
         }
     }
 
@@ -147,28 +201,18 @@ impl EvolutionThresholds {
             replans_min_prompts: 3,
             repeated_pattern_min_count: 4,
             repeated_pattern_min_success_rate: 0.85,
+            decision_max_age_days: 14,
         }
     }
 
+    /// Thresholds from the layered settings (`SKILLLITE_EVO_*` env or the `[evolution]` table of
+    /// the config files) on top of the `evolution.profile` preset.
     pub fn from_env() -> Self {
-        let parse_i64 = |key: &str, default: i64| {
-            std::env::var(key)
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(default)
-        };
-        let parse_f64 = |key: &str, default: f64| {
-            std::env::var(key)
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(default)
-        };
-        let profile = match std::env::var(evo_keys::SKILLLITE_EVO_PROFILE)
-            .ok()
-            .as_deref()
-            .map(str::trim)
-            .filter(|s| !s.is_empty())
-        {
+        Self::from_settings(&Settings::load())
+    }
+
+    pub fn from_settings(settings: &Settings) -> Self {
+        let profile = match settings.get("evolution.profile") {
             Some("demo") => EvolutionProfile::Demo,
             Some("conservative") => EvolutionProfile::Conservative,
             _ => EvolutionProfile::Default,
@@ -178,42 +222,154 @@ impl EvolutionThresholds {
             EvolutionProfile::Demo => Self::demo_preset(),
             EvolutionProfile::Conservative => Self::conservative_preset(),
         };
+        let int = |key: &str, default: i64| settings.get_i64(key).unwrap_or(default);
+        let float = |key: &str, default: f64| settings.get_f64(key).unwrap_or(default);
         Self {
-            cooldown_hours: parse_f64(evo_keys::SKILLLITE_EVO_COOLDOWN_HOURS, base.cooldown_hours),
-            recent_days: parse_i64(evo_keys::SKILLLITE_EVO_RECENT_DAYS, base.recent_days),
-            recent_limit: parse_i64(evo_keys::SKILLLITE_EVO_RECENT_LIMIT, base.recent_limit),
-            meaningful_min_tools: parse_i64(
-                evo_keys::SKILLLITE_EVO_MEANINGFUL_MIN_TOOLS,
-                base.meaningful_min_tools,
-            ),
-            meaningful_threshold_skills: parse_i64(
-                evo_keys::SKILLLITE_EVO_MEANINGFUL_THRESHOLD_SKILLS,
+            cooldown_hours: float("evolution.cooldown_hours", base.cooldown_hours),
+            recent_days: int("evolution.recent_days", base.recent_days),
+            recent_limit: int("evolution.recent_limit", base.recent_limit),
+            meaningful_min_tools: int("evolution.meaningful_min_tools", base.meaningful_min_tools),
+            meaningful_threshold_skills: int(
+                "evolution.meaningful_threshold_skills",
                 base.meaningful_threshold_skills,
             ),
-            meaningful_threshold_memory: parse_i64(
-                evo_keys::SKILLLITE_EVO_MEANINGFUL_THRESHOLD_MEMORY,
+            meaningful_threshold_memory: int(
+                "evolution.meaningful_threshold_memory",
                 base.meaningful_threshold_memory,
             ),
-            meaningful_threshold_prompts: parse_i64(
-                evo_keys::SKILLLITE_EVO_MEANINGFUL_THRESHOLD_PROMPTS,
+            meaningful_threshold_prompts: int(
+                "evolution.meaningful_threshold_prompts",
                 base.meaningful_threshold_prompts,
             ),
-            failures_min_prompts: parse_i64(
-                evo_keys::SKILLLITE_EVO_FAILURES_MIN_PROMPTS,
-                base.failures_min_prompts,
-            ),
-            replans_min_prompts: parse_i64(
-                evo_keys::SKILLLITE_EVO_REPLANS_MIN_PROMPTS,
-                base.replans_min_prompts,
-            ),
-            repeated_pattern_min_count: parse_i64(
-                evo_keys::SKILLLITE_EVO_REPEATED_PATTERN_MIN_COUNT,
+            failures_min_prompts: int("evolution.failures_min_prompts", base.failures_min_prompts),
+            replans_min_prompts: int("evolution.replans_min_prompts", base.replans_min_prompts),
+            repeated_pattern_min_count: int(
+                "evolution.repeated_pattern_min_count",
                 base.repeated_pattern_min_count,
             ),
-            repeated_pattern_min_success_rate: parse_f64(
-                evo_keys::SKILLLITE_EVO_REPEATED_PATTERN_MIN_SUCCESS_RATE,
+            repeated_pattern_min_success_rate: float(
+                "evolution.repeated_pattern_min_success_rate",
                 base.repeated_pattern_min_success_rate,
             ),
+            decision_max_age_days: int(
+                "evolution.decision_max_age_days",
+                base.decision_max_age_days,
+            )
+            .max(0),
+        }
+    }
+
+    /// SQL predicate for decisions that may feed scope counts and pattern detection: inside
+    /// the recent window, not older than `decision_max_age_days`, and not expired.
+    pub fn decision_window_condition(&self) -> String {
+        let days = if self.decision_max_age_days > 0 {
+            self.recent_days.min(self.decision_max_age_days)
+        } else {
+            self.recent_days
+        };
+        format!(
+            "ts >= datetime('now', '-{} days') AND evolved <> {}",
+            days,
+            crate::feedback::DECISION_STATE_EXPIRED
+        )
+    }
+}
+
+// ─── EVO-6: External learning budgets ────────────────────────────────────────
+
+/// Per-source and per-cycle limits for the external learning phase.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalLearningBudget {
+    /// Response bytes read per source; the rest of the body is dropped.
+    pub max_bytes_per_source: usize,
+    /// Parsed items per source passed to LLM rule extraction.
+    pub max_items_per_source: usize,
+    /// Wall-clock budget for the whole phase (fetch + extraction).
+    pub max_phase_secs: u64,
+}
+
+impl Default for ExternalLearningBudget {
+    fn default() -> Self {
+        Self {
+            max_bytes_per_source: 512 * 1024,
+            max_items_per_source: 10,
+            max_phase_secs: 60,
+        }
+    }
+}
+
+impl ExternalLearningBudget {
+    pub fn from_env() -> Self {
+        let base = Self::default();
+        let parse = |key: &str, default: u64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+        Self {
+            max_bytes_per_source: parse(
+                evo_keys::SKILLLITE_EVO_EXTERNAL_MAX_BYTES_PER_SOURCE,
+                base.max_bytes_per_source as u64,
+            ) as usize,
+            max_items_per_source: parse(
+                evo_keys::SKILLLITE_EVO_EXTERNAL_MAX_ITEMS_PER_SOURCE,
+                base.max_items_per_source as u64,
+            ) as usize,
+            max_phase_secs: parse(
+                evo_keys::SKILLLITE_EVO_EXTERNAL_BUDGET_SECS,
+                base.max_phase_secs,
+            ),
+        }
+    }
+}
+
+// ─── Evolution LLM call policy ───────────────────────────────────────────────
+
+/// Retry/timeout policy for evolution LLM calls (see [`crate::llm::complete_with_retry`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmRetryPolicy {
+    /// Total attempts per call, including the first.
+    pub max_attempts: u32,
+    /// Per-attempt timeout.
+    pub timeout: std::time::Duration,
+    /// First backoff delay; doubles per attempt (plus jitter) up to `max_delay`.
+    pub base_delay: std::time::Duration,
+    pub max_delay: std::time::Duration,
+}
+
+impl Default for LlmRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            timeout: std::time::Duration::from_secs(120),
+            base_delay: std::time::Duration::from_millis(1000),
+            max_delay: std::time::Duration::from_secs(30),
+        }
+    }
+}
+
+impl LlmRetryPolicy {
+    pub fn from_env() -> Self {
+        let base = Self::default();
+        let parse = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        Self {
+            max_attempts: parse(evo_keys::SKILLLITE_EVO_LLM_MAX_ATTEMPTS)
+                .map(|v| v.clamp(1, 10) as u32)
+                .unwrap_or(base.max_attempts),
+            timeout: parse(evo_keys::SKILLLITE_EVO_LLM_TIMEOUT_SECS)
+                .filter(|v| *v > 0)
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(base.timeout),
+            base_delay: parse(evo_keys::SKILLLITE_EVO_LLM_RETRY_BASE_MS)
+                .map(std::time::Duration::from_millis)
+                .unwrap_or(base.base_delay),
+            max_delay: base.max_delay,
         }
     }
 }
@@ -0,0 +1,486 @@
+//! Human-readable decision history (`DECISIONS.md`) and `skilllite evolution export`.
+//!
+//! Decisions are written to one file per month under `decisions/YYYY-MM.md`; `DECISIONS.md`
+//! at the chat root is a compact index linking to them. A month file is regenerated only when
+//! its decisions changed since the last export (new rows, evolved / expired, feedback, txn
+//! links), tracked in `decisions/.export_state.json`, so a shutdown or evolution cycle
+//! normally rewrites just the current month. With `evolution.workspace_decisions` enabled,
+//! each workspace that recorded decisions also gets `<workspace>/.skilllite/DECISIONS.md`
+//! with only its own decisions.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::feedback::{DECISION_STATE_EXPIRED, DECISION_STATE_PENDING, DECISION_STATE_PROCESSED};
+use crate::Result;
+
+/// Index file at the chat root.
+pub const DECISIONS_INDEX_FILE: &str = "DECISIONS.md";
+/// Directory of the monthly files, next to the index.
+pub const DECISIONS_DIR: &str = "decisions";
+const EXPORT_STATE_FILE: &str = ".export_state.json";
+/// Task text is cut to this many characters in Markdown tables.
+const TASK_CHARS: usize = 80;
+/// Tools cell is cut to this many characters in Markdown tables.
+const TOOLS_CHARS: usize = 60;
+
+/// One decision as exported (Markdown tables, `evolution export --format json`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecisionRecord {
+    pub id: i64,
+    pub ts: String,
+    /// `pending`, `evolved` or `expired`.
+    pub status: &'static str,
+    pub task: Option<String>,
+    pub task_completed: bool,
+    /// Effective completion type (`success`, `partial_success`, `failure`).
+    pub outcome: String,
+    /// Last user feedback signal (`pos`, `neg`, `neutral`).
+    pub feedback: String,
+    pub elapsed_ms: i64,
+    pub total_tools: i64,
+    pub failed_tools: i64,
+    pub replans: i64,
+    /// Distinct tool names in call order.
+    pub tools: Vec<String>,
+    pub model: Option<String>,
+    pub workspace: Option<String>,
+    /// Evolution txns that consumed this decision.
+    pub evolution_txns: Vec<String>,
+}
+
+/// Which decisions [`load_decisions`] returns; unset fields do not filter.
+#[derive(Debug, Clone, Default)]
+pub struct DecisionFilter {
+    /// Inclusive lower bound on `ts` (`YYYY-MM-DD` or a full timestamp).
+    pub since: Option<String>,
+    /// `YYYY-MM`.
+    pub month: Option<String>,
+    pub workspace: Option<String>,
+}
+
+fn status_label(evolved: i64) -> &'static str {
+    match evolved {
+        DECISION_STATE_PROCESSED => "evolved",
+        DECISION_STATE_EXPIRED => "expired",
+        _ => "pending",
+    }
+}
+
+/// Decisions matching `filter`, oldest first.
+pub fn load_decisions(conn: &Connection, filter: &DecisionFilter) -> Result<Vec<DecisionRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT d.id, d.ts, d.evolved, d.task_description, d.task_completed, d.completion_type,
+                d.feedback, d.elapsed_ms, d.total_tools, d.failed_tools, d.replans,
+                d.tools_detail, d.model, d.workspace,
+                (SELECT group_concat(t.txn_id, ',') FROM decision_txns t
+                 WHERE t.decision_id = d.id)
+         FROM decisions d
+         WHERE (?1 IS NULL OR d.ts >= ?1)
+           AND (?2 IS NULL OR substr(d.ts, 1, 7) = ?2)
+           AND (?3 IS NULL OR d.workspace = ?3)
+         ORDER BY d.ts, d.id",
+    )?;
+    let rows = stmt.query_map(
+        params![filter.since, filter.month, filter.workspace],
+        |row| {
+            let tools_detail: Option<String> = row.get(11)?;
+            let txns: Option<String> = row.get(14)?;
+            Ok(DecisionRecord {
+                id: row.get(0)?,
+                ts: row.get(1)?,
+                status: status_label(
+                    row.get::<_, Option<i64>>(2)?
+                        .unwrap_or(DECISION_STATE_PENDING),
+                ),
+                task: row.get(3)?,
+                task_completed: row.get::<_, Option<bool>>(4)?.unwrap_or(false),
+                outcome: row
+                    .get::<_, Option<String>>(5)?
+                    .unwrap_or_else(|| "success".to_string()),
+                feedback: row
+                    .get::<_, Option<String>>(6)?
+                    .unwrap_or_else(|| "neutral".to_string()),
+                elapsed_ms: row.get::<_, Option<i64>>(7)?.unwrap_or(0),
+                total_tools: row.get::<_, Option<i64>>(8)?.unwrap_or(0),
+                failed_tools: row.get::<_, Option<i64>>(9)?.unwrap_or(0),
+                replans: row.get::<_, Option<i64>>(10)?.unwrap_or(0),
+                tools: tool_names(tools_detail.as_deref()),
+                model: row.get(12)?,
+                workspace: row.get(13)?,
+                evolution_txns: txns
+                    .map(|t| t.split(',').map(str::to_string).collect())
+                    .unwrap_or_default(),
+            })
+        },
+    )?;
+    Ok(rows.collect::<std::result::Result<_, _>>()?)
+}
+
+fn tool_names(tools_detail: Option<&str>) -> Vec<String> {
+    #[derive(Deserialize)]
+    struct Detail {
+        tool: String,
+    }
+    let details: Vec<Detail> = tools_detail
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default();
+    let mut names: Vec<String> = Vec::new();
+    for d in details {
+        if !names.contains(&d.tool) {
+            names.push(d.tool);
+        }
+    }
+    names
+}
+
+// ─── Markdown ────────────────────────────────────────────────────────────────
+
+fn cell(text: &str, max_chars: usize) -> String {
+    let text = text.replace('|', "\\|").replace('\n', " ");
+    if text.chars().count() > max_chars {
+        let cut: String = text.chars().take(max_chars).collect();
+        format!("{}…", cut)
+    } else {
+        text
+    }
+}
+
+fn format_duration(ms: i64) -> String {
+    match ms {
+        ms if ms < 1000 => format!("{}ms", ms),
+        ms if ms < 60_000 => format!("{:.1}s", ms as f64 / 1000.0),
+        ms => format!("{}m {}s", ms / 60_000, ms % 60_000 / 1000),
+    }
+}
+
+/// Markdown table of `records` (one row per decision, in the given order).
+pub fn render_table(records: &[DecisionRecord]) -> String {
+    let mut out = String::from(
+        "| id | ts | status | outcome | feedback | duration | replans | tools | evolution | task |\n\
+         |---|---|---|---|---|---|---|---|---|---|\n",
+    );
+    for d in records {
+        let mut tools = cell(&d.tools.join(", "), TOOLS_CHARS);
+        if d.failed_tools > 0 {
+            tools.push_str(&format!(" ({} failed)", d.failed_tools));
+        }
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            d.id,
+            d.ts,
+            d.status,
+            d.outcome,
+            d.feedback,
+            format_duration(d.elapsed_ms),
+            d.replans,
+            tools,
+            d.evolution_txns.join(", "),
+            cell(d.task.as_deref().unwrap_or_default(), TASK_CHARS),
+        ));
+    }
+    out
+}
+
+// ─── Incremental export ──────────────────────────────────────────────────────
+
+/// Per-group counts; `fingerprint` changes whenever a row of the group would render differently.
+#[derive(Debug, Clone)]
+struct GroupSummary {
+    key: String,
+    decisions: i64,
+    completed: i64,
+    evolved: i64,
+    expired: i64,
+    fingerprint: String,
+}
+
+fn summarize(conn: &Connection, key_expr: &str) -> Result<Vec<GroupSummary>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {key} AS k, COUNT(*), TOTAL(COALESCE(d.task_completed, 0)),
+                TOTAL(d.evolved = ?1), TOTAL(d.evolved = ?2), MAX(d.id),
+                TOTAL(COALESCE(d.corrections, 0) + COALESCE(d.confirmations, 0)),
+                TOTAL((SELECT COUNT(*) FROM decision_txns t WHERE t.decision_id = d.id))
+         FROM decisions d WHERE {key} IS NOT NULL GROUP BY k ORDER BY k",
+        key = key_expr
+    ))?;
+    let rows = stmt.query_map(
+        params![DECISION_STATE_PROCESSED, DECISION_STATE_EXPIRED],
+        |row| {
+            // TOTAL() is a float and never NULL
+            let total = |i: usize| row.get::<_, f64>(i).map(|v| v as i64);
+            let decisions: i64 = row.get(1)?;
+            let (evolved, expired) = (total(3)?, total(4)?);
+            let max_id: i64 = row.get(5)?;
+            let (feedback, links) = (total(6)?, total(7)?);
+            Ok(GroupSummary {
+                key: row.get(0)?,
+                decisions,
+                completed: total(2)?,
+                evolved,
+                expired,
+                fingerprint: format!(
+                    "{}:{}:{}:{}:{}:{}",
+                    decisions, max_id, evolved, expired, feedback, links
+                ),
+            })
+        },
+    )?;
+    Ok(rows.collect::<std::result::Result<_, _>>()?)
+}
+
+/// What was exported last time (`decisions/.export_state.json`).
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExportState {
+    #[serde(default)]
+    last_decision_id: i64,
+    /// Month → fingerprint of the exported file.
+    #[serde(default)]
+    months: BTreeMap<String, String>,
+    /// Workspace root → fingerprint of its exported view.
+    #[serde(default)]
+    workspaces: BTreeMap<String, String>,
+}
+
+/// What [`export_decisions`] rewrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportReport {
+    pub months_written: Vec<String>,
+    pub index_written: bool,
+    pub workspace_files: Vec<PathBuf>,
+}
+
+/// `evolution.workspace_decisions` (`SKILLLITE_EVO_WORKSPACE_DECISIONS`), off by default.
+pub fn workspace_export_enabled() -> bool {
+    skilllite_core::config::Settings::load()
+        .get_bool("evolution.workspace_decisions")
+        .unwrap_or(false)
+}
+
+/// Bring `DECISIONS.md`, `decisions/YYYY-MM.md` and (with `per_workspace`) the workspace views
+/// up to date, rewriting only what changed since the last export.
+pub fn export_decisions(
+    conn: &Connection,
+    chat_root: &Path,
+    per_workspace: bool,
+) -> Result<ExportReport> {
+    let dir = chat_root.join(DECISIONS_DIR);
+    let state_path = dir.join(EXPORT_STATE_FILE);
+    let mut state: ExportState = std::fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    let mut report = ExportReport::default();
+
+    let months = summarize(conn, "substr(d.ts, 1, 7)")?;
+    for month in &months {
+        let path = dir.join(format!("{}.md", month.key));
+        if state.months.get(&month.key) == Some(&month.fingerprint) && path.is_file() {
+            continue;
+        }
+        let records = load_decisions(
+            conn,
+            &DecisionFilter {
+                month: Some(month.key.clone()),
+                ..Default::default()
+            },
+        )?;
+        let md = format!(
+            "# Decisions {}\n\n[← index](../{})\n\n{}",
+            month.key,
+            DECISIONS_INDEX_FILE,
+            render_table(&records)
+        );
+        skilllite_fs::atomic_write(&path, &md)?;
+        state
+            .months
+            .insert(month.key.clone(), month.fingerprint.clone());
+        report.months_written.push(month.key.clone());
+    }
+    // Months whose decisions were all pruned
+    let gone: Vec<String> = state
+        .months
+        .keys()
+        .filter(|k| !months.iter().any(|m| &m.key == *k))
+        .cloned()
+        .collect();
+    for key in &gone {
+        let _ = std::fs::remove_file(dir.join(format!("{}.md", key)));
+        state.months.remove(key);
+    }
+
+    let index_path = chat_root.join(DECISIONS_INDEX_FILE);
+    if !report.months_written.is_empty() || !gone.is_empty() || !index_path.is_file() {
+        skilllite_fs::atomic_write(&index_path, &render_index(conn, &months)?)?;
+        report.index_written = true;
+    }
+
+    if per_workspace {
+        for ws in summarize(conn, "d.workspace")? {
+            let root = Path::new(&ws.key);
+            if state.workspaces.get(&ws.key) == Some(&ws.fingerprint) || !root.is_dir() {
+                continue;
+            }
+            let mut records = load_decisions(
+                conn,
+                &DecisionFilter {
+                    workspace: Some(ws.key.clone()),
+                    ..Default::default()
+                },
+            )?;
+            records.reverse();
+            let md = format!(
+                "# Decisions in this workspace\n\n\
+                 - Decisions: {} ({} completed)\n- Evolved: {}\n- Expired (aged out unread): {}\n\n{}",
+                ws.decisions,
+                ws.completed,
+                ws.evolved,
+                ws.expired,
+                render_table(&records)
+            );
+            let path =
+                skilllite_core::paths::project_skilllite_dir(root).join(DECISIONS_INDEX_FILE);
+            skilllite_fs::atomic_write(&path, &md)?;
+            state.workspaces.insert(ws.key.clone(), ws.fingerprint);
+            report.workspace_files.push(path);
+        }
+    }
+
+    state.last_decision_id =
+        conn.query_row("SELECT COALESCE(MAX(id), 0) FROM decisions", [], |r| {
+            r.get(0)
+        })?;
+    skilllite_fs::atomic_write(&state_path, &serde_json::to_string_pretty(&state)?)?;
+    Ok(report)
+}
+
+fn render_index(conn: &Connection, months: &[GroupSummary]) -> Result<String> {
+    let (pending, processed, expired): (i64, i64, i64) = conn.query_row(
+        "SELECT
+            COUNT(CASE WHEN evolved = ?1 THEN 1 END),
+            COUNT(CASE WHEN evolved = ?2 THEN 1 END),
+            COUNT(CASE WHEN evolved = ?3 THEN 1 END)
+         FROM decisions",
+        params![
+            DECISION_STATE_PENDING,
+            DECISION_STATE_PROCESSED,
+            DECISION_STATE_EXPIRED
+        ],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    )?;
+    let mut out = String::from("# Decisions\n\n");
+    out.push_str(&format!(
+        "- Pending: {}\n- Evolved: {}\n- Expired (aged out unread): {}\n\n",
+        pending, processed, expired
+    ));
+    out.push_str("| month | decisions | completed | evolved | expired |\n");
+    out.push_str("|---|---|---|---|---|\n");
+    for m in months.iter().rev() {
+        out.push_str(&format!(
+            "| [{month}]({dir}/{month}.md) | {} | {} | {} | {} |\n",
+            m.decisions,
+            m.completed,
+            m.evolved,
+            m.expired,
+            month = m.key,
+            dir = DECISIONS_DIR,
+        ));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::ensure_evolution_tables;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        ensure_evolution_tables(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn exports_monthly_files_with_index_and_labels_expired_separately() {
+        let conn = setup_conn();
+        conn.execute_batch(
+            r#"INSERT INTO decisions (ts, evolved, total_tools, failed_tools, elapsed_ms,
+                 task_completed, completion_type, task_description, tools_detail) VALUES
+             ('2026-02-27 10:00:00', 2, 2, 0, 800, 1, 'success', 'old task', NULL),
+             ('2026-03-14 10:00:00', 1, 3, 1, 65000, 1, 'partial_success', 'learned task',
+              '[{"tool":"read_file","success":true},{"tool":"weather","success":false},{"tool":"read_file","success":true}]'),
+             ('2026-03-15 10:00:00', 0, 1, 0, 1500, 0, 'failure', 'new | task', NULL);
+             INSERT INTO decision_txns (decision_id, txn_id) VALUES (2, 'evo_20260314');"#,
+        )
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let report = export_decisions(&conn, dir.path(), false).unwrap();
+        assert_eq!(report.months_written, ["2026-02", "2026-03"]);
+        assert!(report.index_written);
+
+        let index = std::fs::read_to_string(dir.path().join(DECISIONS_INDEX_FILE)).unwrap();
+        assert!(index.contains("- Pending: 1\n- Evolved: 1\n- Expired (aged out unread): 1"));
+        assert!(index.contains("| [2026-03](decisions/2026-03.md) | 2 | 1 | 1 | 0 |"));
+        let feb = std::fs::read_to_string(dir.path().join("decisions/2026-02.md")).unwrap();
+        assert!(feb.contains("| 1 | 2026-02-27 10:00:00 | expired | success | neutral | 800ms |"));
+        let mar = std::fs::read_to_string(dir.path().join("decisions/2026-03.md")).unwrap();
+        assert!(mar.contains(
+            "| evolved | partial_success | neutral | 1m 5s | 0 | read_file, weather (1 failed) | evo_20260314 | learned task |"
+        ));
+        assert!(mar.contains("| pending | failure |"));
+        assert!(mar.contains("new \\| task"));
+
+        // Nothing changed: nothing rewritten
+        let report = export_decisions(&conn, dir.path(), false).unwrap();
+        assert_eq!(report, ExportReport::default());
+
+        // A new decision and an expiry in March only touch that month and the index
+        conn.execute_batch(
+            "INSERT INTO decisions (ts, task_description) VALUES ('2026-03-20 09:00:00', 'later');
+             UPDATE decisions SET evolved = 2 WHERE id = 3;",
+        )
+        .unwrap();
+        let report = export_decisions(&conn, dir.path(), false).unwrap();
+        assert_eq!(report.months_written, ["2026-03"]);
+        assert!(report.index_written);
+        let mar = std::fs::read_to_string(dir.path().join("decisions/2026-03.md")).unwrap();
+        assert!(mar.contains("| later |"));
+        assert!(mar.contains("| 3 | 2026-03-15 10:00:00 | expired |"));
+    }
+
+    #[test]
+    fn workspace_view_and_filters_only_include_that_workspace() {
+        let conn = setup_conn();
+        let ws = tempfile::tempdir().unwrap();
+        let ws_path = ws.path().to_string_lossy().to_string();
+        conn.execute(
+            "INSERT INTO decisions (ts, task_description, workspace) VALUES
+             ('2026-03-01 10:00:00', 'here', ?1),
+             ('2026-03-02 10:00:00', 'elsewhere', '/nonexistent/ws'),
+             ('2026-03-03 10:00:00', 'unknown', NULL)",
+            params![ws_path],
+        )
+        .unwrap();
+        let chat = tempfile::tempdir().unwrap();
+        let report = export_decisions(&conn, chat.path(), true).unwrap();
+        let view = ws.path().join(".skilllite").join(DECISIONS_INDEX_FILE);
+        assert_eq!(report.workspace_files, std::slice::from_ref(&view));
+        let md = std::fs::read_to_string(&view).unwrap();
+        assert!(md.contains("| here |"));
+        assert!(!md.contains("elsewhere") && !md.contains("unknown"));
+
+        let since = load_decisions(
+            &conn,
+            &DecisionFilter {
+                since: Some("2026-03-02".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let tasks: Vec<_> = since.iter().filter_map(|d| d.task.as_deref()).collect();
+        assert_eq!(tasks, ["elsewhere", "unknown"]);
+    }
+}
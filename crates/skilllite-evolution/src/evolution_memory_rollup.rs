@@ -1,11 +1,11 @@
 //! 按月分卷的去重汇总（`YYYY-MM.rollup.md`）：从原始分卷解析条目、合并重复键，供阅读与抽取去重摘要。
 
 use std::collections::BTreeMap;
-use std::path::Path;
 
 use crate::error::bail;
 use crate::gatekeeper_l1_path;
 use crate::gatekeeper_l3_content;
+use crate::journal::TxnWrites;
 use crate::Result;
 
 /// 五维子目录名，与 `memory_learner` 一致。
@@ -257,7 +257,8 @@ fn build_rollup_body(dir_name: &str, content: &str) -> String {
 
 /// 根据 `memory/evolution/<dir>/<month>.md` 重算同目录下的 `<month>.rollup.md`。
 /// 若分卷不存在则删除已陈旧的 rollup 文件。
-pub fn rebuild_rollups_for_month(chat_root: &Path, month: &str) -> Result<()> {
+pub fn rebuild_rollups_for_month(txn: TxnWrites, month: &str) -> Result<()> {
+    let chat_root = txn.chat_root;
     if !month_key_valid(month) {
         bail!("Invalid evolution month key (expected YYYY-MM): {}", month);
     }
@@ -277,9 +278,7 @@ pub fn rebuild_rollups_for_month(chat_root: &Path, month: &str) -> Result<()> {
         }
 
         if !shard.exists() {
-            if rollup_path.exists() {
-                skilllite_fs::remove_file(&rollup_path)?;
-            }
+            txn.remove(&rollup_path)?;
             continue;
         }
 
@@ -299,8 +298,7 @@ pub fn rebuild_rollups_for_month(chat_root: &Path, month: &str) -> Result<()> {
             body = body.trim_end(),
         );
         gatekeeper_l3_content(&doc)?;
-        skilllite_fs::create_dir_all(&shard_dir)?;
-        skilllite_fs::write_file(&rollup_path, &doc)?;
+        txn.write(&rollup_path, &doc)?;
     }
     Ok(())
 }
@@ -372,7 +370,7 @@ mod tests {
         let entities = root.join("memory").join("evolution").join("entities");
         std::fs::create_dir_all(&entities).expect("mkdir");
         std::fs::write(entities.join("2026-04.md"), SAMPLE_ENTITIES).expect("write shard");
-        rebuild_rollups_for_month(root, "2026-04").expect("rollup");
+        rebuild_rollups_for_month(TxnWrites::new(root, "evo_test"), "2026-04").expect("rollup");
         let rollup =
             std::fs::read_to_string(entities.join("2026-04.rollup.md")).expect("read rollup");
         assert!(
@@ -389,7 +387,8 @@ mod tests {
     #[test]
     fn rebuild_rollups_for_month_invalid_month_errors() {
         let tmp = tempfile::tempdir().expect("tempdir");
-        let err = rebuild_rollups_for_month(tmp.path(), "bad").expect_err("expected invalid month");
+        let err = rebuild_rollups_for_month(TxnWrites::new(tmp.path(), "evo_test"), "bad")
+            .expect_err("expected invalid month");
         let msg = format!("{}", err);
         assert!(
             msg.contains("YYYY-MM") || msg.contains("Invalid"),
@@ -0,0 +1,889 @@
+//! Decision trail for a single rule or evolved skill (`skilllite evolution explain`).
+//!
+//! Joins `evolution_log` (plus the hash-chained `evolution.log` and its archives), the
+//! decisions recorded for the introducing txn, `changelog.jsonl` and skill metadata into one
+//! [`Explanation`]. Renderers (CLI, MCP) only format this struct; anything that could not be
+//! reconstructed is listed in [`Explanation::gaps`] instead of being left blank.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::feedback::{DECISION_STATE_EXPIRED, DECISION_STATE_PROCESSED};
+use crate::rule_conflict::PendingRuleConflict;
+use crate::skill_synth::SkillMeta;
+use crate::Result;
+
+/// Event types that bring a target into existence (also matched with a `_rolled_back` suffix).
+const INTRODUCING_EVENTS: &[&str] = &[
+    "rule_added",
+    "external_rule_added",
+    "skill_pending",
+    "skill_generated",
+];
+
+/// Max decisions listed for the introducing txn.
+const MAX_SCOPE_DECISIONS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetKind {
+    /// Present in `prompts/rules.json`.
+    Rule,
+    /// Held in `pending_conflicts.json`, not applied yet.
+    PendingRule,
+    /// Evolved skill (pending, confirmed, archived, or only known from the logs).
+    Skill,
+    /// Only rule events remain; the rule is no longer in rules.json.
+    RemovedRule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSource {
+    Database,
+    /// `evolution.log` or a rotated archive (row pruned by `evolution gc`).
+    LogFile,
+    /// `changelog.jsonl` only.
+    Changelog,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrailEvent {
+    pub ts: String,
+    pub event_type: String,
+    pub txn_id: String,
+    pub reason: String,
+    pub source: EventSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DecisionSummary {
+    pub id: i64,
+    pub ts: String,
+    pub task_description: Option<String>,
+    pub task_completed: bool,
+    pub feedback: Option<String>,
+    /// `pending` / `evolved` / `expired`.
+    pub state: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionLink {
+    /// Ids recorded in `decision_txns` when the txn ran.
+    Recorded,
+    /// Txn predates `decision_txns`; decisions evolved between the previous txn and this one.
+    Inferred,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TxnScope {
+    pub txn_id: String,
+    pub link: DecisionLink,
+    pub decisions: Vec<DecisionSummary>,
+    /// Decisions in scope beyond [`MAX_SCOPE_DECISIONS`].
+    pub omitted: usize,
+    /// Changelog reason for the txn, when the entry is still on disk.
+    pub changelog_reason: Option<String>,
+    pub changelog_files: Vec<String>,
+    /// Holdout evaluation recorded with the txn's new rules, if any.
+    pub evaluation: Option<crate::prompt_eval::PromptEvaluation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleUsage {
+    pub matched_30d: i64,
+    pub succeeded_30d: i64,
+    /// `None` with fewer than 3 matched decisions in 30 days.
+    pub effectiveness: Option<f32>,
+    pub matched_total: i64,
+    pub last_matched: Option<String>,
+    /// Decisions whose context satisfied the rule's `scope` (30 days / total); `None` for
+    /// unscoped rules, which are always in scope.
+    pub in_scope_30d: Option<i64>,
+    pub in_scope_total: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillLocation {
+    Pending,
+    Confirmed,
+    Archived,
+    /// Not under `_evolved/` (rejected or retired).
+    Missing,
+    /// No skills root was given.
+    NotChecked,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkillTrail {
+    pub location: SkillLocation,
+    pub generation_txn: Option<String>,
+    /// `passed` / `fixed` / `failed` (see [`crate::skill_synth::SCAN_PASSED`] and friends).
+    pub scan_status: Option<String>,
+    pub needs_review: bool,
+    pub call_count: u32,
+    pub success_count: u32,
+    pub failure_count: u32,
+    pub last_used: Option<String>,
+    /// `skill_confirmed` / `skill_rejected` events.
+    pub reviews: Vec<TrailEvent>,
+}
+
+/// Data that could not be reconstructed, so renderers can say why instead of printing blanks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "gap", rename_all = "snake_case")]
+pub enum Gap {
+    /// No events anywhere (typical for seed rules).
+    NoHistory,
+    /// Later events exist but the introducing one was rotated out of the logs.
+    IntroductionMissing,
+    /// Introducing txn has no decisions (recorded or inferable).
+    NoDecisionsInScope {
+        txn_id: String,
+    },
+    /// Recorded decision ids no longer present in the decisions table.
+    DecisionsPruned {
+        txn_id: String,
+        count: usize,
+    },
+    /// Decisions in the inferred window aged out unread before the run.
+    DecisionsExpired {
+        txn_id: String,
+        count: usize,
+    },
+    /// No changelog entry for the txn (rotated archive removed).
+    ChangelogMissing {
+        txn_id: String,
+    },
+    SkillMetaMissing,
+    /// Skill was generated before scan results were recorded.
+    ScanStatusUnknown,
+}
+
+impl Gap {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::NoHistory => "no evolution history (seed rule or logs pruned)".to_string(),
+            Self::IntroductionMissing => {
+                "introducing event not found; evolution.log was rotated or pruned".to_string()
+            }
+            Self::NoDecisionsInScope { txn_id } => {
+                format!("no decisions found in scope for txn {}", txn_id)
+            }
+            Self::DecisionsPruned { txn_id, count } => format!(
+                "{} decision(s) in scope for txn {} were deleted",
+                count, txn_id
+            ),
+            Self::DecisionsExpired { txn_id, count } => {
+                format!("{} decision(s) before txn {} expired unread", count, txn_id)
+            }
+            Self::ChangelogMissing { txn_id } => {
+                format!("changelog entry for txn {} was rotated out", txn_id)
+            }
+            Self::SkillMetaMissing => "skill .meta.json not found".to_string(),
+            Self::ScanStatusUnknown => "scan result not recorded for this skill".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    pub target_id: String,
+    pub kind: TargetKind,
+    /// Raw `rules.json` entry for [`TargetKind::Rule`].
+    pub rule: Option<serde_json::Value>,
+    pub conflict: Option<PendingRuleConflict>,
+    pub introduced: Option<TrailEvent>,
+    /// All events, oldest first (including `introduced`).
+    pub history: Vec<TrailEvent>,
+    pub introducing_txn: Option<TxnScope>,
+    pub usage: Option<RuleUsage>,
+    pub skill: Option<SkillTrail>,
+    pub gaps: Vec<Gap>,
+}
+
+/// Reconstruct the trail for `id` (rule id or evolved skill name) without checking skill
+/// directories. Returns `None` when nothing is known about `id`.
+pub fn explain_target(
+    conn: &Connection,
+    chat_root: &Path,
+    id: &str,
+) -> Result<Option<Explanation>> {
+    explain_target_in(conn, chat_root, None, id)
+}
+
+/// Like [`explain_target`], also resolving skill location and `.meta.json` under `skills_root`.
+pub fn explain_target_in(
+    conn: &Connection,
+    chat_root: &Path,
+    skills_root: Option<&Path>,
+    id: &str,
+) -> Result<Option<Explanation>> {
+    let rule = load_rule(chat_root, id);
+    let conflict = if rule.is_none() {
+        crate::rule_conflict::load_pending_conflicts(chat_root)
+            .into_iter()
+            .find(|c| c.rule.id == id)
+    } else {
+        None
+    };
+    let history = collect_history(conn, chat_root, id)?;
+    let skill_dir = skills_root.and_then(|root| locate_skill(root, id));
+
+    let is_skill =
+        skill_dir.is_some() || history.iter().any(|e| e.event_type.starts_with("skill_"));
+    let kind = if rule.is_some() {
+        TargetKind::Rule
+    } else if conflict.is_some() {
+        TargetKind::PendingRule
+    } else if is_skill {
+        TargetKind::Skill
+    } else if !history.is_empty() {
+        TargetKind::RemovedRule
+    } else {
+        return Ok(None);
+    };
+
+    let mut gaps = Vec::new();
+    let introduced = history
+        .iter()
+        .find(|e| is_introducing(&e.event_type))
+        .cloned();
+    if history.is_empty() {
+        gaps.push(Gap::NoHistory);
+    } else if introduced.is_none() {
+        gaps.push(Gap::IntroductionMissing);
+    }
+
+    let introducing_txn = match &introduced {
+        Some(ev) if !ev.txn_id.is_empty() => {
+            Some(txn_scope(conn, chat_root, &ev.txn_id, &ev.ts, &mut gaps)?)
+        }
+        _ => None,
+    };
+
+    let usage = match kind {
+        TargetKind::Rule | TargetKind::RemovedRule => {
+            let scoped = rule
+                .as_ref()
+                .and_then(|r| r.get("scope"))
+                .is_some_and(|s| s.is_object());
+            Some(rule_usage(conn, id, scoped)?)
+        }
+        _ => None,
+    };
+
+    let skill = if kind == TargetKind::Skill {
+        Some(skill_trail(
+            skills_root.is_some(),
+            skill_dir.as_ref(),
+            introduced.as_ref(),
+            &history,
+            &mut gaps,
+        ))
+    } else {
+        None
+    };
+
+    Ok(Some(Explanation {
+        target_id: id.to_string(),
+        kind,
+        rule,
+        conflict,
+        introduced,
+        history,
+        introducing_txn,
+        usage,
+        skill,
+        gaps,
+    }))
+}
+
+fn is_introducing(event_type: &str) -> bool {
+    let base = event_type
+        .strip_suffix("_rolled_back")
+        .unwrap_or(event_type);
+    INTRODUCING_EVENTS.contains(&base)
+}
+
+fn load_rule(chat_root: &Path, id: &str) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(chat_root.join("prompts").join("rules.json")).ok()?;
+    let rules: Vec<serde_json::Value> = serde_json::from_str(&content).ok()?;
+    rules
+        .into_iter()
+        .find(|r| r.get("id").and_then(|v| v.as_str()) == Some(id))
+}
+
+// ─── History ────────────────────────────────────────────────────────────────
+
+/// SQLite rows, then `evolution.log` lines pruned from SQLite, then changelog-only changes.
+fn collect_history(conn: &Connection, chat_root: &Path, id: &str) -> Result<Vec<TrailEvent>> {
+    let mut events: Vec<TrailEvent> = crate::feedback::query_rule_history(conn, id)?
+        .into_iter()
+        .map(|e| TrailEvent {
+            ts: e.ts,
+            event_type: e.event_type,
+            txn_id: e.txn_id,
+            reason: e.reason,
+            source: EventSource::Database,
+        })
+        .collect();
+
+    let known_ts: HashSet<String> = events.iter().map(|e| e.ts.clone()).collect();
+    let log_path = crate::retention::evolution_log_path(chat_root);
+    for v in json_lines(&log_path) {
+        if v.get("id").and_then(|x| x.as_str()) != Some(id) {
+            continue;
+        }
+        let ts = str_field(&v, "ts");
+        if ts.is_empty() || known_ts.contains(&ts) {
+            continue;
+        }
+        events.push(TrailEvent {
+            ts,
+            event_type: str_field(&v, "type"),
+            txn_id: str_field(&v, "txn_id"),
+            reason: str_field(&v, "reason"),
+            source: EventSource::LogFile,
+        });
+    }
+
+    let known: HashSet<(String, String)> = events
+        .iter()
+        .map(|e| (e.txn_id.clone(), e.event_type.clone()))
+        .collect();
+    for entry in json_lines(&crate::retention::changelog_path(chat_root)) {
+        let txn_id = str_field(&entry, "txn_id");
+        let changes = entry.get("changes").and_then(|c| c.as_array());
+        for change in changes.into_iter().flatten() {
+            if change.get("id").and_then(|x| x.as_str()) != Some(id) {
+                continue;
+            }
+            let event_type = str_field(change, "type");
+            if known.contains(&(txn_id.clone(), event_type.clone())) {
+                continue;
+            }
+            events.push(TrailEvent {
+                ts: str_field(&entry, "ts"),
+                event_type,
+                txn_id: txn_id.clone(),
+                reason: str_field(&entry, "reason"),
+                source: EventSource::Changelog,
+            });
+        }
+    }
+
+    events.sort_by(|a, b| a.ts.cmp(&b.ts));
+    Ok(events)
+}
+
+fn json_lines(path: &Path) -> Vec<serde_json::Value> {
+    crate::retention::read_jsonl_lines(path)
+        .iter()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn str_field(v: &serde_json::Value, key: &str) -> String {
+    v.get(key)
+        .and_then(|x| x.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+// ─── Decisions in scope ─────────────────────────────────────────────────────
+
+fn txn_scope(
+    conn: &Connection,
+    chat_root: &Path,
+    txn_id: &str,
+    txn_ts: &str,
+    gaps: &mut Vec<Gap>,
+) -> Result<TxnScope> {
+    let recorded = crate::audit::decision_ids_for_txn(conn, txn_id)?;
+    let (link, mut decisions) = if recorded.is_empty() {
+        (
+            DecisionLink::Inferred,
+            inferred_decisions(conn, txn_id, txn_ts, gaps)?,
+        )
+    } else {
+        let found = decisions_by_ids(conn, &recorded)?;
+        if found.len() < recorded.len() {
+            gaps.push(Gap::DecisionsPruned {
+                txn_id: txn_id.to_string(),
+                count: recorded.len() - found.len(),
+            });
+        }
+        (DecisionLink::Recorded, found)
+    };
+    if decisions.is_empty() {
+        gaps.push(Gap::NoDecisionsInScope {
+            txn_id: txn_id.to_string(),
+        });
+    }
+    decisions.sort_by(|a, b| b.ts.cmp(&a.ts).then(b.id.cmp(&a.id)));
+    let omitted = decisions.len().saturating_sub(MAX_SCOPE_DECISIONS);
+    decisions.truncate(MAX_SCOPE_DECISIONS);
+
+    let entry = json_lines(&crate::retention::changelog_path(chat_root))
+        .into_iter()
+        .find(|e| e.get("txn_id").and_then(|x| x.as_str()) == Some(txn_id));
+    if entry.is_none() {
+        gaps.push(Gap::ChangelogMissing {
+            txn_id: txn_id.to_string(),
+        });
+    }
+    let changelog_files = entry
+        .as_ref()
+        .and_then(|e| e.get("files"))
+        .and_then(|f| f.as_array())
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|f| f.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let evaluation = entry
+        .as_ref()
+        .and_then(|e| e.get("evaluation"))
+        .and_then(|v| serde_json::from_value(v.clone()).ok());
+
+    Ok(TxnScope {
+        txn_id: txn_id.to_string(),
+        link,
+        decisions,
+        omitted,
+        changelog_reason: entry.as_ref().map(|e| str_field(e, "reason")),
+        changelog_files,
+        evaluation,
+    })
+}
+
+const DECISION_COLUMNS: &str =
+    "id, ts, task_description, task_completed, feedback, COALESCE(evolved, 0)";
+
+fn decision_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DecisionSummary> {
+    let state: i64 = row.get(5)?;
+    Ok(DecisionSummary {
+        id: row.get(0)?,
+        ts: row.get(1)?,
+        task_description: row.get(2)?,
+        task_completed: row.get::<_, Option<bool>>(3)?.unwrap_or(false),
+        feedback: row.get(4)?,
+        state: match state {
+            DECISION_STATE_PROCESSED => "evolved",
+            DECISION_STATE_EXPIRED => "expired",
+            _ => "pending",
+        }
+        .to_string(),
+    })
+}
+
+fn decisions_by_ids(conn: &Connection, ids: &[i64]) -> Result<Vec<DecisionSummary>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM decisions WHERE id = ?1",
+        DECISION_COLUMNS
+    ))?;
+    let mut out = Vec::new();
+    for id in ids {
+        if let Some(d) = stmt
+            .query_map(params![id], decision_from_row)?
+            .next()
+            .transpose()?
+        {
+            out.push(d);
+        }
+    }
+    Ok(out)
+}
+
+/// Decisions marked evolved between the previous txn and `txn_ts` (used for txns that predate
+/// `decision_txns`). Expired decisions in the same window are reported as a gap.
+fn inferred_decisions(
+    conn: &Connection,
+    txn_id: &str,
+    txn_ts: &str,
+    gaps: &mut Vec<Gap>,
+) -> Result<Vec<DecisionSummary>> {
+    let previous: Option<String> = conn
+        .query_row(
+            "SELECT MAX(ts) FROM evolution_log
+             WHERE version IS NOT NULL AND version <> '' AND version <> ?1
+               AND julianday(ts) < julianday(?2)",
+            params![txn_id, txn_ts],
+            |row| row.get(0),
+        )
+        .unwrap_or(None);
+    let lower = previous.unwrap_or_else(|| "0000-01-01 00:00:00".to_string());
+    let window = "julianday(ts) > julianday(?2) AND julianday(ts) <= julianday(?3)";
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM decisions WHERE evolved = ?1 AND {}",
+        DECISION_COLUMNS, window
+    ))?;
+    let decisions = stmt
+        .query_map(
+            params![DECISION_STATE_PROCESSED, lower, txn_ts],
+            decision_from_row,
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let expired: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM decisions WHERE evolved = ?1 AND {}",
+            window
+        ),
+        params![DECISION_STATE_EXPIRED, lower, txn_ts],
+        |row| row.get(0),
+    )?;
+    if expired > 0 {
+        gaps.push(Gap::DecisionsExpired {
+            txn_id: txn_id.to_string(),
+            count: expired as usize,
+        });
+    }
+    Ok(decisions)
+}
+
+// ─── Usage ──────────────────────────────────────────────────────────────────
+
+fn rule_usage(conn: &Connection, rule_id: &str, scoped: bool) -> Result<RuleUsage> {
+    let (matched_30d, succeeded_30d, matched_total, last_matched): (i64, i64, i64, Option<String>) =
+        conn.query_row(
+            "SELECT
+            COUNT(CASE WHEN d.ts > datetime('now', '-30 days') THEN 1 END),
+            COUNT(CASE WHEN d.ts > datetime('now', '-30 days')
+                        AND d.task_completed = 1 AND d.feedback != 'neg' THEN 1 END),
+            COUNT(*),
+            MAX(d.ts)
+         FROM decisions d
+         JOIN decision_rules dr ON d.id = dr.decision_id
+         WHERE dr.rule_id = ?1",
+            params![rule_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+    let effectiveness = crate::feedback::compute_effectiveness(conn, rule_id)?;
+    let (in_scope_30d, in_scope_total) = if scoped {
+        let (recent, total): (i64, i64) = conn.query_row(
+            "SELECT COUNT(CASE WHEN d.ts > datetime('now', '-30 days') THEN 1 END), COUNT(*)
+             FROM decisions d
+             JOIN decision_rule_scope s ON d.id = s.decision_id
+             WHERE s.rule_id = ?1",
+            params![rule_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        (Some(recent), Some(total))
+    } else {
+        (None, None)
+    };
+    Ok(RuleUsage {
+        matched_30d,
+        succeeded_30d,
+        effectiveness: (effectiveness >= 0.0).then_some(effectiveness),
+        matched_total,
+        last_matched,
+        in_scope_30d,
+        in_scope_total,
+    })
+}
+
+// ─── Skills ─────────────────────────────────────────────────────────────────
+
+struct SkillDir {
+    location: SkillLocation,
+    meta: Option<SkillMeta>,
+}
+
+fn locate_skill(skills_root: &Path, name: &str) -> Option<SkillDir> {
+    let evolved = skills_root.join("_evolved");
+    let (dir, pending) = if evolved.join("_pending").join(name).is_dir() {
+        (evolved.join("_pending").join(name), true)
+    } else if name != "_pending" && evolved.join(name).is_dir() {
+        (evolved.join(name), false)
+    } else {
+        return None;
+    };
+    let meta: Option<SkillMeta> = std::fs::read_to_string(dir.join(".meta.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok());
+    let location = if pending {
+        SkillLocation::Pending
+    } else if meta.as_ref().is_some_and(|m| m.archived) {
+        SkillLocation::Archived
+    } else {
+        SkillLocation::Confirmed
+    };
+    Some(SkillDir { location, meta })
+}
+
+fn skill_trail(
+    checked: bool,
+    dir: Option<&SkillDir>,
+    introduced: Option<&TrailEvent>,
+    history: &[TrailEvent],
+    gaps: &mut Vec<Gap>,
+) -> SkillTrail {
+    let location = match dir {
+        Some(d) => d.location,
+        None if checked => SkillLocation::Missing,
+        None => SkillLocation::NotChecked,
+    };
+    let meta = dir.and_then(|d| d.meta.as_ref());
+    if dir.is_some() && meta.is_none() {
+        gaps.push(Gap::SkillMetaMissing);
+    }
+    let scan_status = meta.and_then(|m| {
+        if !m.scan_status.is_empty() {
+            Some(m.scan_status.clone())
+        } else if m.needs_review {
+            Some(crate::skill_synth::SCAN_FAILED.to_string())
+        } else {
+            None
+        }
+    });
+    if meta.is_some() && scan_status.is_none() {
+        gaps.push(Gap::ScanStatusUnknown);
+    }
+    let generation_txn = meta
+        .map(|m| m.generation_txn.clone())
+        .filter(|t| !t.is_empty())
+        .or_else(|| {
+            introduced
+                .map(|e| e.txn_id.clone())
+                .filter(|t| !t.is_empty())
+        });
+    SkillTrail {
+        location,
+        generation_txn,
+        scan_status,
+        needs_review: meta.is_some_and(|m| m.needs_review),
+        call_count: meta.map_or(0, |m| m.call_count),
+        success_count: meta.map_or(0, |m| m.success_count),
+        failure_count: meta.map_or(0, |m| m.failure_count),
+        last_used: meta.and_then(|m| m.last_used.clone()),
+        reviews: history
+            .iter()
+            .filter(|e| e.event_type == "skill_confirmed" || e.event_type == "skill_rejected")
+            .cloned()
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::open_evolution_db;
+
+    fn seed(conn: &Connection) {
+        conn.execute_batch(
+            "INSERT INTO decisions (id, ts, task_description, task_completed, feedback, evolved) VALUES
+             (1, datetime('now', '-3 days'), 'deploy docs', 1, 'pos', 1),
+             (2, datetime('now', '-3 days'), 'fix build', 0, 'neg', 1),
+             (3, datetime('now', '-3 days'), 'old task', 0, 'neutral', 2),
+             (4, datetime('now', '-1 days'), 'later task', 1, 'neutral', 0),
+             (5, datetime('now', '-1 days'), 'later task 2', 1, 'pos', 0);
+             INSERT INTO decision_rules (decision_id, rule_id) VALUES (4, 'r1'), (5, 'r1');",
+        )
+        .unwrap();
+    }
+
+    fn write_rules(chat_root: &Path) {
+        std::fs::create_dir_all(chat_root.join("prompts")).unwrap();
+        std::fs::write(
+            chat_root.join("prompts").join("rules.json"),
+            r#"[{"id":"r1","instruction":"check docs first"}]"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn rule_trail_uses_recorded_decisions_and_changelog() {
+        let dir = tempfile::tempdir().unwrap();
+        let chat_root = dir.path();
+        let conn = open_evolution_db(chat_root).unwrap();
+        seed(&conn);
+        write_rules(chat_root);
+        crate::audit::log_evolution_event(&conn, chat_root, "rule_added", "r1", "new", "evo_1")
+            .unwrap();
+        crate::audit::record_decision_txn(&conn, "evo_1", &[1, 2, 99]).unwrap();
+        crate::append_changelog(
+            chat_root,
+            "evo_1",
+            &["rules.json".to_string()],
+            &[("rule_added".to_string(), "r1".to_string())],
+            "1 new rule",
+            None,
+            &[],
+        )
+        .unwrap();
+        crate::audit::log_evolution_event(
+            &conn,
+            chat_root,
+            "rule_promoted",
+            "r1",
+            "reused",
+            "evo_2",
+        )
+        .unwrap();
+
+        let exp = explain_target(&conn, chat_root, "r1").unwrap().unwrap();
+        assert_eq!(exp.kind, TargetKind::Rule);
+        assert_eq!(exp.introduced.as_ref().unwrap().txn_id, "evo_1");
+        assert_eq!(exp.history.len(), 2);
+        assert_eq!(exp.history[1].event_type, "rule_promoted");
+
+        let scope = exp.introducing_txn.unwrap();
+        assert_eq!(scope.link, DecisionLink::Recorded);
+        let ids: Vec<i64> = scope.decisions.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(scope.changelog_reason.as_deref(), Some("1 new rule"));
+        assert_eq!(scope.changelog_files, vec!["rules.json"]);
+        assert_eq!(scope.evaluation, None);
+        assert!(exp.gaps.contains(&Gap::DecisionsPruned {
+            txn_id: "evo_1".to_string(),
+            count: 1
+        }));
+
+        let usage = exp.usage.unwrap();
+        assert_eq!((usage.matched_30d, usage.succeeded_30d), (2, 2));
+        assert_eq!(usage.effectiveness, None);
+    }
+
+    #[test]
+    fn pruned_rows_fall_back_to_log_file_and_inferred_decisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let chat_root = dir.path();
+        let conn = open_evolution_db(chat_root).unwrap();
+        seed(&conn);
+        write_rules(chat_root);
+        crate::audit::log_evolution_event(&conn, chat_root, "rule_added", "r1", "new", "evo_1")
+            .unwrap();
+        conn.execute("DELETE FROM evolution_log", []).unwrap();
+
+        let exp = explain_target(&conn, chat_root, "r1").unwrap().unwrap();
+        let intro = exp.introduced.unwrap();
+        assert_eq!(intro.source, EventSource::LogFile);
+
+        let scope = exp.introducing_txn.unwrap();
+        assert_eq!(scope.link, DecisionLink::Inferred);
+        assert_eq!(scope.decisions.len(), 2);
+        assert!(exp.gaps.contains(&Gap::DecisionsExpired {
+            txn_id: "evo_1".to_string(),
+            count: 1
+        }));
+        assert!(exp.gaps.contains(&Gap::ChangelogMissing {
+            txn_id: "evo_1".to_string()
+        }));
+    }
+
+    #[test]
+    fn scoped_rule_reports_in_scope_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let chat_root = dir.path();
+        let conn = open_evolution_db(chat_root).unwrap();
+        seed(&conn);
+        std::fs::create_dir_all(chat_root.join("prompts")).unwrap();
+        std::fs::write(
+            chat_root.join("prompts").join("rules.json"),
+            r#"[{"id":"r1","instruction":"check docs first"},
+                {"id":"r2","instruction":"create a venv first","scope":{"language":"python"}}]"#,
+        )
+        .unwrap();
+        conn.execute_batch(
+            "INSERT INTO decision_rule_scope (decision_id, rule_id) VALUES (1, 'r2'), (4, 'r2'), (5, 'r2');",
+        )
+        .unwrap();
+
+        let usage = explain_target(&conn, chat_root, "r2")
+            .unwrap()
+            .unwrap()
+            .usage
+            .unwrap();
+        assert_eq!(
+            (usage.in_scope_30d, usage.in_scope_total),
+            (Some(3), Some(3))
+        );
+        assert_eq!(usage.matched_total, 0);
+
+        let unscoped = explain_target(&conn, chat_root, "r1")
+            .unwrap()
+            .unwrap()
+            .usage
+            .unwrap();
+        assert_eq!(unscoped.in_scope_total, None);
+    }
+
+    #[test]
+    fn seed_rule_and_unknown_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let chat_root = dir.path();
+        let conn = open_evolution_db(chat_root).unwrap();
+        write_rules(chat_root);
+
+        let exp = explain_target(&conn, chat_root, "r1").unwrap().unwrap();
+        assert_eq!(exp.gaps, vec![Gap::NoHistory]);
+        assert!(exp.introducing_txn.is_none());
+        assert!(explain_target(&conn, chat_root, "nope").unwrap().is_none());
+    }
+
+    #[test]
+    fn skill_trail_reports_scan_and_reviews() {
+        let dir = tempfile::tempdir().unwrap();
+        let chat_root = dir.path().join("chat");
+        let skills_root = dir.path().join("skills");
+        let conn = open_evolution_db(&chat_root).unwrap();
+        let skill_dir = skills_root.join("_evolved").join("fetch-docs");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join(".meta.json"),
+            r#"{"name":"fetch-docs","source_session":"","created_at":"","success_count":3,
+               "failure_count":1,"call_count":4,"last_used":null,"generation_txn":"evo_7",
+               "scan_status":"fixed"}"#,
+        )
+        .unwrap();
+        crate::audit::log_evolution_event(
+            &conn,
+            &chat_root,
+            "skill_pending",
+            "fetch-docs",
+            "",
+            "evo_7",
+        )
+        .unwrap();
+        crate::audit::log_evolution_event(
+            &conn,
+            &chat_root,
+            "skill_confirmed",
+            "fetch-docs",
+            "user confirmed",
+            "",
+        )
+        .unwrap();
+
+        let exp = explain_target_in(&conn, &chat_root, Some(&skills_root), "fetch-docs")
+            .unwrap()
+            .unwrap();
+        assert_eq!(exp.kind, TargetKind::Skill);
+        assert!(exp.usage.is_none());
+        let skill = exp.skill.unwrap();
+        assert_eq!(skill.location, SkillLocation::Confirmed);
+        assert_eq!(skill.generation_txn.as_deref(), Some("evo_7"));
+        assert_eq!(skill.scan_status.as_deref(), Some("fixed"));
+        assert_eq!(skill.call_count, 4);
+        assert_eq!(skill.reviews.len(), 1);
+
+        // Without a skills root the trail still comes from the logs.
+        let exp = explain_target(&conn, &chat_root, "fetch-docs")
+            .unwrap()
+            .unwrap();
+        let skill = exp.skill.unwrap();
+        assert_eq!(skill.location, SkillLocation::NotChecked);
+        assert_eq!(skill.generation_txn.as_deref(), Some("evo_7"));
+    }
+}
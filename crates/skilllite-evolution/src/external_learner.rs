@@ -7,23 +7,33 @@
 //! Gated by env var: `SKILLLITE_EXTERNAL_LEARNING=1` (default OFF).
 //! Daily cap: max 3 external fetch runs per day.
 //! Network: CN sources use 5s timeout, global sources use 15s timeout.
+//! Budgets ([`ExternalLearningBudget`]): bytes and items per source, wall-clock per run.
+//! robots.txt is honored for http(s) sources; ETag / Last-Modified validators are stored
+//! on the registry entry so unchanged sources are skipped with a 304.
 
+use std::collections::HashMap;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 use crate::error::bail;
 use crate::Result;
 use rusqlite::Connection;
 
 use crate::feedback::open_evolution_db;
-use skilllite_core::planning::{PlanningRule, SourceEntry, SourceRegistry};
+use skilllite_core::planning::{
+    PlanningRule, RuleProvenance, SourceEntry, SourceFetchStats, SourceRegistry,
+};
 
-use skilllite_fs::atomic_write;
 // use crate::feedback; // unused import, commented out
 use crate::gatekeeper_l3_content;
+use crate::journal::TxnWrites;
 use crate::log_evolution_event;
+use crate::rule_conflict;
 use crate::seed;
 use crate::EvolutionLlm;
 use crate::EvolutionMessage;
+use crate::ExternalLearningBudget;
+use crate::{complete_parsed, LlmRetryPolicy};
 
 // ─── Configuration constants ─────────────────────────────────────────────────
 
@@ -42,6 +52,16 @@ const PAUSE_MIN_FAIL_COUNT: u32 = 7;
 const RETIRE_QUALITY_THRESHOLD: f32 = 0.20;
 /// Minimum fetch attempts before retirement eligibility.
 const RETIRE_MIN_FETCHES: u32 = 30;
+/// Consecutive over-budget runs before each further one lowers quality_score.
+const BUDGET_PENALTY_STREAK: u32 = 2;
+/// quality_score penalty per over-budget run once the streak is reached.
+const BUDGET_QUALITY_PENALTY: f32 = 0.1;
+/// Consecutive over-budget runs after which the source is paused.
+const BUDGET_PAUSE_STREAK: u32 = 5;
+const USER_AGENT: &str = "SkillLite/1.0 (external-learning)";
+/// Product token matched against robots.txt `User-agent` lines.
+const ROBOTS_AGENT_TOKEN: &str = "skilllite";
+const ROBOTS_MAX_BYTES: usize = 64 * 1024;
 
 const EXTERNAL_KNOWLEDGE_PROMPT: &str =
     include_str!("seed/evolution_prompts/external_knowledge.seed.md");
@@ -126,40 +146,256 @@ fn update_accessibility(source: &mut SourceEntry, success: bool) {
     source.last_fetched = Some(chrono::Utc::now().to_rfc3339());
 }
 
+// ─── Fetch budget ─────────────────────────────────────────────────────────────
+
+/// Track consecutive over-budget runs; once the streak reaches [`BUDGET_PENALTY_STREAK`]
+/// each further one lowers quality_score so `evolve_sources` can retire the source.
+fn apply_budget_outcome(source: &mut SourceEntry, over_budget: bool) {
+    if !over_budget {
+        source.budget_exceeded_streak = 0;
+        return;
+    }
+    source.budget_exceeded_streak += 1;
+    if source.budget_exceeded_streak >= BUDGET_PENALTY_STREAK {
+        source.quality_score = (source.quality_score - BUDGET_QUALITY_PENALTY).max(0.0);
+        tracing::info!(
+            "Source {} over fetch budget {} runs in a row (quality={:.2})",
+            source.id,
+            source.budget_exceeded_streak,
+            source.quality_score
+        );
+    }
+}
+
 // ─── HTTP fetch ───────────────────────────────────────────────────────────────
 
-/// Fetch raw content from a source. Returns Ok(raw_bytes) or Err.
-async fn fetch_source(source: &SourceEntry) -> Result<String> {
-    let timeout_secs = if source.region == "cn" {
+#[derive(Debug)]
+enum FetchOutcome {
+    /// 304: validators matched, nothing to parse.
+    NotModified,
+    Fetched {
+        body: String,
+        bytes: u64,
+        /// Body was cut off at `max_bytes`.
+        truncated: bool,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+fn source_timeout(source: &SourceEntry) -> Duration {
+    Duration::from_secs(if source.region == "cn" {
         CN_TIMEOUT_SECS
     } else {
         GLOBAL_TIMEOUT_SECS
-    };
-    let timeout = std::time::Duration::from_secs(timeout_secs);
-
-    let client = reqwest::Client::builder()
-        .timeout(timeout)
-        .user_agent("SkillLite/1.0 (external-learning)")
-        .build()?;
+    })
+}
 
+/// Fetch a source, reading at most `max_bytes` of the body. Conditional headers are sent
+/// when the registry entry carries validators from a previous fetch.
+async fn fetch_source(
+    client: &reqwest::Client,
+    source: &SourceEntry,
+    timeout: Duration,
+    max_bytes: usize,
+) -> Result<FetchOutcome> {
     // Special handling for sources that require POST
-    let response = if source.parser == "juejin" {
+    let mut request = if source.parser == "juejin" {
         let body = serde_json::json!({
             "id_type": 2,
             "client_type": 2608,
             "cursor": "0",
             "limit": 20
         });
-        client.post(&source.url).json(&body).send().await?
+        client.post(&source.url).json(&body)
     } else {
-        client.get(&source.url).send().await?
+        let mut req = client.get(&source.url);
+        if let Some(etag) = &source.etag {
+            req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(lm) = &source.last_modified {
+            req = req.header(reqwest::header::IF_MODIFIED_SINCE, lm);
+        }
+        req
     };
+    request = request.timeout(timeout);
+    let mut response = request.send().await?;
 
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(FetchOutcome::NotModified);
+    }
     if !response.status().is_success() {
         bail!("HTTP {} from {}", response.status(), source.url);
     }
 
-    Ok(response.text().await?)
+    let header = |name: reqwest::header::HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(reqwest::header::ETAG);
+    let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+    let mut buf: Vec<u8> = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await? {
+        let room = max_bytes.saturating_sub(buf.len());
+        if chunk.len() > room {
+            buf.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        buf.extend_from_slice(&chunk);
+    }
+
+    Ok(FetchOutcome::Fetched {
+        bytes: buf.len() as u64,
+        body: String::from_utf8_lossy(&buf).into_owned(),
+        truncated,
+        etag,
+        last_modified,
+    })
+}
+
+// ─── robots.txt ──────────────────────────────────────────────────────────────
+
+/// Allow/Disallow rules from the robots.txt group that applies to SkillLite.
+#[derive(Debug, Default)]
+struct RobotsRules {
+    /// (allow, path pattern)
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Parse robots.txt: rules from a `skilllite` group win over `*`; other agents are ignored.
+    fn parse(text: &str) -> Self {
+        let mut specific: Vec<(bool, String)> = Vec::new();
+        let mut wildcard: Vec<(bool, String)> = Vec::new();
+        let mut has_specific = false;
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let field = field.trim().to_ascii_lowercase();
+            let value = value.trim();
+            match field.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    group_agents.push(value.to_ascii_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (field == "allow", value.to_string());
+                    if group_agents.iter().any(|a| a.contains(ROBOTS_AGENT_TOKEN)) {
+                        has_specific = true;
+                        specific.push(rule.clone());
+                    }
+                    if group_agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self {
+            rules: if has_specific { specific } else { wildcard },
+        }
+    }
+
+    /// Longest matching pattern wins; ties favor Allow.
+    fn allows(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if !robots_pattern_matches(pattern, path) {
+                continue;
+            }
+            let len = pattern.len();
+            best = match best {
+                Some((l, a)) if l > len || (l == len && a) => Some((l, a)),
+                _ => Some((len, *allow)),
+            };
+        }
+        best.map(|(_, allow)| allow).unwrap_or(true)
+    }
+}
+
+/// robots.txt path matching with `*` wildcards and a trailing `$` anchor.
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let segments: Vec<&str> = pattern.split('*').collect();
+    let Some(mut rest) = path.strip_prefix(segments[0]) else {
+        return false;
+    };
+    let last = segments.len() - 1;
+    if last == 0 {
+        return !anchored || rest.is_empty();
+    }
+    for seg in &segments[1..last] {
+        match rest.find(seg) {
+            Some(pos) => rest = &rest[pos + seg.len()..],
+            None => return false,
+        }
+    }
+    if anchored {
+        rest.ends_with(segments[last])
+    } else {
+        rest.contains(segments[last])
+    }
+}
+
+/// Whether robots.txt of the source's origin permits fetching it. Non-http(s) URLs,
+/// missing robots.txt and unreachable hosts count as allowed.
+async fn robots_allows(
+    client: &reqwest::Client,
+    url: &str,
+    timeout: Duration,
+    cache: &mut HashMap<String, RobotsRules>,
+) -> bool {
+    let Ok(parsed) = reqwest::Url::parse(url) else {
+        return true;
+    };
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return true;
+    }
+    let origin = parsed.origin().ascii_serialization();
+    if !cache.contains_key(&origin) {
+        let rules = match client
+            .get(format!("{}/robots.txt", origin))
+            .timeout(timeout)
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => match resp.text().await {
+                Ok(text) => {
+                    let capped: String = text.chars().take(ROBOTS_MAX_BYTES).collect();
+                    RobotsRules::parse(&capped)
+                }
+                Err(_) => RobotsRules::default(),
+            },
+            Ok(_) | Err(_) => RobotsRules::default(),
+        };
+        cache.insert(origin.clone(), rules);
+    }
+    let path = match parsed.query() {
+        Some(q) => format!("{}?{}", parsed.path(), q),
+        None => parsed.path().to_string(),
+    };
+    cache.get(&origin).map(|r| r.allows(&path)).unwrap_or(true)
 }
 
 // ─── Content parsers ──────────────────────────────────────────────────────────
@@ -319,6 +555,23 @@ fn strip_html_basic(html: &str) -> String {
 
 // ─── LLM rule extraction ──────────────────────────────────────────────────────
 
+/// Article content block (titles + snippets) sent to the LLM; also the input of
+/// `RuleProvenance::content_hash`.
+fn format_articles(articles: &[(String, String)]) -> String {
+    articles
+        .iter()
+        .enumerate()
+        .map(|(i, (title, snippet))| {
+            if snippet.is_empty() {
+                format!("{}. {}", i + 1, title)
+            } else {
+                format!("{}. {}\n   {}", i + 1, title, snippet)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Extract planning rules from article content using LLM.
 async fn extract_rules_from_content<L: EvolutionLlm>(
     articles: &[(String, String)],
@@ -331,20 +584,7 @@ async fn extract_rules_from_content<L: EvolutionLlm>(
         return Ok(Vec::new());
     }
 
-    // Build article content block (titles + snippets)
-    let article_content = articles
-        .iter()
-        .enumerate()
-        .map(|(i, (title, snippet))| {
-            if snippet.is_empty() {
-                format!("{}. {}", i + 1, title)
-            } else {
-                format!("{}. {}\n   {}", i + 1, title, snippet)
-            }
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
-
+    let article_content = format_articles(articles);
     let domains_str = domains.join(", ");
 
     let prompt = EXTERNAL_KNOWLEDGE_PROMPT
@@ -353,18 +593,21 @@ async fn extract_rules_from_content<L: EvolutionLlm>(
         .replace("{{existing_rules_summary}}", existing_summary);
 
     let messages = vec![EvolutionMessage::user(&prompt)];
-    let content = llm
-        .complete(&messages, model, 0.3)
-        .await?
-        .visible
-        .trim()
-        .to_string();
-
-    if content.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    parse_external_rule_response(&content)
+    complete_parsed(
+        llm,
+        &messages,
+        model,
+        0.3,
+        &LlmRetryPolicy::from_env(),
+        |content| {
+            if content.is_empty() {
+                Ok(Vec::new())
+            } else {
+                parse_external_rule_response(content)
+            }
+        },
+    )
+    .await
 }
 
 fn parse_external_rule_response(content: &str) -> Result<Vec<PlanningRule>> {
@@ -380,6 +623,10 @@ fn parse_external_rule_response(content: &str) -> Result<Vec<PlanningRule>> {
 
     let mut rules = Vec::new();
     for val in arr {
+        if let Some(reason) = seed::rule_rejection(&val) {
+            tracing::warn!("External rule {} rejected:\n{}", val["id"], reason);
+            continue;
+        }
         let id = val["id"].as_str().unwrap_or("").to_string();
         if id.is_empty() || !id.starts_with("ext_") {
             tracing::warn!("External rule rejected: id '{}' must start with 'ext_'", id);
@@ -428,6 +675,8 @@ fn parse_external_rule_response(content: &str) -> Result<Vec<PlanningRule>> {
             reusable: false,
             effectiveness: None,
             trigger_count: None,
+            provenance: None,
+            scope: None,
         });
     }
 
@@ -459,17 +708,20 @@ fn evolve_sources(sources: &mut [SourceEntry]) -> Vec<(String, String)> {
         // Only pause/retire mutable sources (seed sources can't be retired, only paused)
         let total_fetches = source.fetch_success_count + source.fetch_fail_count;
 
-        // Pause: accessibility too low and fail count high enough
+        // Pause: accessibility too low and fail count high enough, or the source keeps
+        // blowing its fetch budget
         if source.enabled
-            && source.accessibility_score < PAUSE_ACCESSIBILITY_THRESHOLD
-            && source.fetch_fail_count >= PAUSE_MIN_FAIL_COUNT
+            && ((source.accessibility_score < PAUSE_ACCESSIBILITY_THRESHOLD
+                && source.fetch_fail_count >= PAUSE_MIN_FAIL_COUNT)
+                || source.budget_exceeded_streak >= BUDGET_PAUSE_STREAK)
         {
             source.enabled = false;
             tracing::info!(
-                "Pausing source {} (accessibility={:.2}, fails={})",
+                "Pausing source {} (accessibility={:.2}, fails={}, over-budget streak={})",
                 source.id,
                 source.accessibility_score,
-                source.fetch_fail_count
+                source.fetch_fail_count,
+                source.budget_exceeded_streak
             );
             changes.push(("source_paused".to_string(), source.id.clone()));
         }
@@ -494,25 +746,22 @@ fn evolve_sources(sources: &mut [SourceEntry]) -> Vec<(String, String)> {
 
 // ─── Persistence helpers ──────────────────────────────────────────────────────
 
-/// Save source registry atomically to prompts/sources.json.
-fn save_sources(chat_root: &Path, registry: &SourceRegistry) -> Result<()> {
-    let path = chat_root.join("prompts").join("sources.json");
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    let json = serde_json::to_string_pretty(registry)?;
-    atomic_write(&path, &json)?;
-    Ok(())
+/// Save source registry to prompts/sources.json through the txn journal.
+fn save_sources(txn: TxnWrites, registry: &SourceRegistry) -> Result<()> {
+    let path = txn.chat_root.join("prompts").join("sources.json");
+    seed::write_sources_checked(&path, registry, Some(txn))
 }
 
-/// Merge new external rules into existing rules.json, skipping duplicates.
+/// Merge new external rules into existing rules.json, skipping duplicates. Rules that
+/// contradict an existing rule are parked in `pending_conflicts.json` instead.
 fn merge_external_rules(
-    chat_root: &Path,
+    txn: TxnWrites,
     new_rules: Vec<PlanningRule>,
 ) -> Result<Vec<(String, String)>> {
     if new_rules.is_empty() {
         return Ok(Vec::new());
     }
+    let chat_root = txn.chat_root;
 
     let rules_path = chat_root.join("prompts").join("rules.json");
     let mut existing: Vec<PlanningRule> = if rules_path.exists() {
@@ -525,19 +774,41 @@ fn merge_external_rules(
     };
 
     let mut changes = Vec::new();
+    let mut pending = rule_conflict::load_pending_conflicts(chat_root);
+    let pending_before = pending.len();
     // External rules share the 50-rule cap with internal evolved rules
     let available_slots = 50_usize.saturating_sub(existing.len());
     for rule in new_rules.into_iter().take(available_slots) {
-        if existing.iter().any(|r| r.id == rule.id) {
+        if existing.iter().any(|r| r.id == rule.id) || pending.iter().any(|p| p.rule.id == rule.id)
+        {
+            continue;
+        }
+        if let Some((other, similarity)) = rule_conflict::find_conflict(&rule, &existing) {
+            tracing::warn!(
+                "EVO-6: External rule {} contradicts {} (similarity {:.2}); held for review",
+                rule.id,
+                other.id,
+                similarity
+            );
+            changes.push(("external_rule_conflict".to_string(), rule.id.clone()));
+            pending.push(rule_conflict::PendingRuleConflict {
+                conflicts_with: other.id.clone(),
+                conflicting_instruction: other.instruction.clone(),
+                similarity,
+                detected_at: chrono::Utc::now().to_rfc3339(),
+                rule,
+            });
             continue;
         }
         changes.push(("external_rule_added".to_string(), rule.id.clone()));
         existing.push(rule);
     }
 
-    if !changes.is_empty() {
-        let json = serde_json::to_string_pretty(&existing)?;
-        atomic_write(&rules_path, &json)?;
+    if changes.iter().any(|(t, _)| t == "external_rule_added") {
+        seed::write_rules_checked(&rules_path, &existing, Some(txn))?;
+    }
+    if pending.len() != pending_before {
+        rule_conflict::save_pending_conflicts(txn, &pending)?;
     }
 
     Ok(changes)
@@ -568,14 +839,49 @@ pub fn apply_external_rule_promotions(
         }
     }
     if !changes.is_empty() {
-        let json = serde_json::to_string_pretty(&rules)?;
-        atomic_write(&rules_path, &json)?;
+        seed::write_rules_checked(&rules_path, &rules, None)?;
     }
     Ok(changes)
 }
 
 // ─── Main entry point ─────────────────────────────────────────────────────────
 
+/// Result of one source within a run, applied to the registry after all fetches.
+struct SourceRunUpdate {
+    id: String,
+    /// Reachability result for the accessibility EMA; `None` when the source was not
+    /// attempted (time budget, robots.txt).
+    success: Option<bool>,
+    rules_added: u32,
+    /// New (ETag, Last-Modified) validators from a 2xx response.
+    validators: Option<(Option<String>, Option<String>)>,
+    stats: SourceFetchStats,
+}
+
+impl SourceRunUpdate {
+    fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            success: None,
+            rules_added: 0,
+            validators: None,
+            stats: SourceFetchStats {
+                ts: chrono::Utc::now().to_rfc3339(),
+                outcome: String::new(),
+                bytes: 0,
+                items: 0,
+                duration_ms: 0,
+                truncated: false,
+            },
+        }
+    }
+
+    fn finish(mut self, started: Instant) -> Self {
+        self.stats.duration_ms = started.elapsed().as_millis() as u64;
+        self
+    }
+}
+
 /// Run external learning cycle. Returns (change_type, id) pairs for the changelog.
 ///
 /// Gated by `SKILLLITE_EXTERNAL_LEARNING=1`. If not enabled, returns Ok(empty).
@@ -607,6 +913,10 @@ pub async fn run_external_learning<L: EvolutionLlm>(
         .collect::<Vec<_>>()
         .join("\n");
 
+    let budget = ExternalLearningBudget::from_env();
+    let phase_budget = Duration::from_secs(budget.max_phase_secs);
+    let started = Instant::now();
+
     let prioritized = prioritize_sources(&registry.sources);
     let to_fetch: Vec<SourceEntry> = prioritized
         .into_iter()
@@ -614,56 +924,125 @@ pub async fn run_external_learning<L: EvolutionLlm>(
         .cloned()
         .collect();
 
+    let client = reqwest::Client::builder().user_agent(USER_AGENT).build()?;
+    let mut robots_cache: HashMap<String, RobotsRules> = HashMap::new();
     let mut all_changes: Vec<(String, String)> = Vec::new();
-    let mut source_update_map: Vec<(String, bool, u32)> = Vec::new(); // (id, success, rules_added)
+    let mut updates: Vec<SourceRunUpdate> = Vec::new();
 
     // Phase 2: async fetch + LLM calls (no Connection held)
     for source in &to_fetch {
-        tracing::debug!("EVO-6: Fetching source {} ({})", source.id, source.url);
+        let fetch_started = Instant::now();
+        let mut update = SourceRunUpdate::new(&source.id);
+        let remaining = phase_budget.saturating_sub(started.elapsed());
+        if remaining.is_zero() {
+            tracing::info!("EVO-6: Time budget exhausted, skipping {}", source.id);
+            update.stats.outcome = "skipped_time_budget".to_string();
+            updates.push(update);
+            continue;
+        }
+        if !robots_allows(
+            &client,
+            &source.url,
+            source_timeout(source).min(remaining),
+            &mut robots_cache,
+        )
+        .await
+        {
+            tracing::info!("EVO-6: robots.txt disallows {}", source.url);
+            update.stats.outcome = "robots_disallowed".to_string();
+            updates.push(update.finish(fetch_started));
+            continue;
+        }
 
-        let fetch_result = fetch_source(source).await;
-        let (success, raw) = match fetch_result {
-            Ok(content) if !content.is_empty() => (true, content),
-            Ok(_) => {
-                tracing::warn!("EVO-6: Empty response from {}", source.id);
-                (false, String::new())
+        tracing::debug!("EVO-6: Fetching source {} ({})", source.id, source.url);
+        let timeout = source_timeout(source).min(phase_budget.saturating_sub(started.elapsed()));
+        let raw = match fetch_source(&client, source, timeout, budget.max_bytes_per_source).await {
+            Ok(FetchOutcome::NotModified) => {
+                tracing::debug!("EVO-6: {} not modified since last fetch", source.id);
+                update.success = Some(true);
+                update.stats.outcome = "not_modified".to_string();
+                updates.push(update.finish(fetch_started));
+                continue;
+            }
+            Ok(FetchOutcome::Fetched {
+                body,
+                bytes,
+                truncated,
+                etag,
+                last_modified,
+            }) => {
+                update.stats.bytes = bytes;
+                update.stats.truncated = truncated;
+                update.validators = Some((etag, last_modified));
+                if truncated {
+                    tracing::warn!(
+                        "EVO-6: {} exceeded byte budget ({} bytes), truncated",
+                        source.id,
+                        budget.max_bytes_per_source
+                    );
+                }
+                body
             }
             Err(e) => {
                 tracing::warn!("EVO-6: Fetch failed for {}: {}", source.id, e);
-                (false, String::new())
+                String::new()
             }
         };
 
-        if !success || raw.is_empty() {
-            source_update_map.push((source.id.clone(), false, 0));
+        if raw.is_empty() {
+            if update.validators.is_some() {
+                tracing::warn!("EVO-6: Empty response from {}", source.id);
+            }
+            update.success = Some(false);
+            update.stats.outcome = "failed".to_string();
+            updates.push(update.finish(fetch_started));
             continue;
         }
+        update.success = Some(true);
+        update.stats.outcome = "ok".to_string();
 
         // Parse content
-        let articles = parse_content(source, &raw);
+        let mut articles = parse_content(source, &raw);
+        articles.truncate(budget.max_items_per_source);
+        update.stats.items = articles.len();
         if articles.is_empty() {
             tracing::debug!("EVO-6: No articles parsed from {}", source.id);
-            source_update_map.push((source.id.clone(), true, 0));
+            updates.push(update.finish(fetch_started));
             continue;
         }
 
-        // LLM rule extraction
-        let new_rules = match extract_rules_from_content(
-            &articles,
-            &source.domains,
-            &existing_summary,
-            llm,
-            model,
+        // LLM rule extraction, bounded by what is left of the phase budget
+        let remaining = phase_budget.saturating_sub(started.elapsed());
+        let mut new_rules = match tokio::time::timeout(
+            remaining,
+            extract_rules_from_content(&articles, &source.domains, &existing_summary, llm, model),
         )
         .await
         {
-            Ok(rules) => rules,
-            Err(e) => {
+            Ok(Ok(rules)) => rules,
+            Ok(Err(e)) => {
                 tracing::warn!("EVO-6: Rule extraction failed for {}: {}", source.id, e);
                 Vec::new()
             }
+            Err(_) => {
+                tracing::warn!(
+                    "EVO-6: Time budget exhausted during rule extraction for {}",
+                    source.id
+                );
+                Vec::new()
+            }
         };
 
+        let content_hash = RuleProvenance::hash_content(&format_articles(&articles));
+        for rule in &mut new_rules {
+            rule.provenance = Some(RuleProvenance {
+                source_id: source.id.clone(),
+                source_url: source.url.clone(),
+                fetched_at: update.stats.ts.clone(),
+                content_hash: content_hash.clone(),
+            });
+        }
+
         tracing::info!(
             "EVO-6: Source {} → {} articles → {} candidate rules",
             source.id,
@@ -672,17 +1051,30 @@ pub async fn run_external_learning<L: EvolutionLlm>(
         );
 
         // Merge rules into rules.json
-        let rule_changes = merge_external_rules(chat_root, new_rules)?;
-        let rules_added = rule_changes.len() as u32;
+        let rule_changes = merge_external_rules(TxnWrites::new(chat_root, txn_id), new_rules)?;
+        update.rules_added = rule_changes
+            .iter()
+            .filter(|(t, _)| t == "external_rule_added")
+            .count() as u32;
         all_changes.extend(rule_changes);
-        source_update_map.push((source.id.clone(), true, rules_added));
+        updates.push(update.finish(fetch_started));
     }
 
     // Phase 3: update registry and apply source evolution (sync)
-    for (id, success, rules_added) in &source_update_map {
-        if let Some(src) = registry.sources.iter_mut().find(|s| s.id == *id) {
-            update_accessibility(src, *success);
-            src.rules_contributed += rules_added;
+    for update in updates {
+        if let Some(src) = registry.sources.iter_mut().find(|s| s.id == update.id) {
+            if let Some(success) = update.success {
+                update_accessibility(src, success);
+            }
+            if let Some((etag, last_modified)) = update.validators {
+                src.etag = etag;
+                src.last_modified = last_modified;
+            }
+            if update.success.is_some() {
+                apply_budget_outcome(src, update.stats.truncated);
+            }
+            src.rules_contributed += update.rules_added;
+            src.last_fetch = Some(update.stats);
         }
     }
 
@@ -695,7 +1087,7 @@ pub async fn run_external_learning<L: EvolutionLlm>(
     let source_changes = evolve_sources(&mut registry.sources);
     all_changes.extend(source_changes);
 
-    save_sources(chat_root, &registry)?;
+    save_sources(TxnWrites::new(chat_root, txn_id), &registry)?;
 
     // Log the run and each change with the same conn
     log_evolution_event(
@@ -704,9 +1096,10 @@ pub async fn run_external_learning<L: EvolutionLlm>(
         "external_fetch_run",
         "",
         &format!(
-            "{} sources fetched, {} changes",
+            "{} sources fetched, {} changes, {:.1}s",
             to_fetch.len(),
-            all_changes.len()
+            all_changes.len(),
+            started.elapsed().as_secs_f32()
         ),
         txn_id,
     )?;
@@ -746,9 +1139,33 @@ mod tests {
             mutable: true,
             origin: "seed".to_string(),
             enabled: true,
+            etag: None,
+            last_modified: None,
+            budget_exceeded_streak: 0,
+            last_fetch: None,
         }
     }
 
+    /// Serve `responses` in order on a local port, one connection each.
+    fn serve_http(responses: Vec<Vec<u8>>) -> (String, std::sync::mpsc::Receiver<String>) {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind");
+        let url = format!("http://{}/feed.xml", listener.local_addr().expect("addr"));
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for response in responses {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let _ = tx.send(String::from_utf8_lossy(&buf[..n]).to_string());
+                let _ = stream.write_all(&response);
+            }
+        });
+        (url, rx)
+    }
+
     #[test]
     fn test_prioritize_sources_cn_first() {
         let sources = vec![
@@ -824,6 +1241,90 @@ mod tests {
         assert!(!changes.iter().any(|(t, _)| t == "source_retired"));
     }
 
+    #[test]
+    fn test_budget_streak_lowers_quality_then_pauses() {
+        let mut src = make_source("huge_feed", "global", 0.9, 0.5);
+        apply_budget_outcome(&mut src, true);
+        assert_eq!(src.budget_exceeded_streak, 1);
+        assert!(
+            (src.quality_score - 0.5).abs() < 1e-5,
+            "first overrun is free"
+        );
+
+        apply_budget_outcome(&mut src, true);
+        assert!((src.quality_score - 0.4).abs() < 1e-5);
+
+        apply_budget_outcome(&mut src, false);
+        assert_eq!(src.budget_exceeded_streak, 0);
+
+        src.budget_exceeded_streak = BUDGET_PAUSE_STREAK;
+        let mut sources = vec![src];
+        let changes = evolve_sources(&mut sources);
+        assert!(!sources[0].enabled);
+        assert!(changes.iter().any(|(t, _)| t == "source_paused"));
+    }
+
+    #[test]
+    fn test_robots_rules_prefer_specific_group_and_longest_match() {
+        let robots = "User-agent: *\nDisallow: /\n\nUser-agent: SkillLite\nDisallow: /private\nAllow: /private/feed*.xml$\n";
+        let rules = RobotsRules::parse(robots);
+        assert!(rules.allows("/rss"));
+        assert!(!rules.allows("/private/notes"));
+        assert!(rules.allows("/private/feed-1.xml"));
+        assert!(!rules.allows("/private/feed-1.xml.bak"));
+
+        let generic = RobotsRules::parse("User-agent: *\nDisallow: /api/\n");
+        assert!(!generic.allows("/api/articles"));
+        assert!(generic.allows("/blog"));
+        assert!(RobotsRules::parse("").allows("/anything"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_source_caps_bytes_and_keeps_validators() {
+        let body = "x".repeat(4096);
+        let ok = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nETag: \"v1\"\r\nLast-Modified: Wed, 01 Jan 2025 00:00:00 GMT\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let not_modified = b"HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_vec();
+        let (url, requests) = serve_http(vec![ok.into_bytes(), not_modified]);
+        let client = reqwest::Client::new();
+        let mut src = make_source("local", "cn", 0.9, 0.9);
+        src.url = url;
+
+        let outcome = fetch_source(&client, &src, Duration::from_secs(5), 1000)
+            .await
+            .expect("first fetch");
+        let FetchOutcome::Fetched {
+            bytes,
+            truncated,
+            etag,
+            last_modified,
+            ..
+        } = outcome
+        else {
+            panic!("expected body, got {:?}", outcome);
+        };
+        assert_eq!(bytes, 1000);
+        assert!(truncated);
+        assert_eq!(etag.as_deref(), Some("\"v1\""));
+        assert!(last_modified.is_some());
+
+        src.etag = etag;
+        src.last_modified = last_modified;
+        let outcome = fetch_source(&client, &src, Duration::from_secs(5), 1000)
+            .await
+            .expect("second fetch");
+        assert!(matches!(outcome, FetchOutcome::NotModified));
+        let _first = requests.recv().expect("first request");
+        let second = requests
+            .recv()
+            .expect("second request")
+            .to_ascii_lowercase();
+        assert!(second.contains("if-none-match: \"v1\""));
+    }
+
     #[test]
     fn test_extract_json_array_with_fences() {
         let input = "```json\n[{\"id\": \"ext_test\"}]\n```";
@@ -902,20 +1403,88 @@ mod tests {
             reusable: false,
             effectiveness: None,
             trigger_count: None,
+            provenance: None,
+            scope: None,
         };
 
         // First merge: should add the rule
-        let changes1 = merge_external_rules(chat_root, vec![new_rule.clone()])
-            .expect("first merge should succeed");
+        let changes1 = merge_external_rules(
+            TxnWrites::new(chat_root, "evo_test"),
+            vec![new_rule.clone()],
+        )
+        .expect("first merge should succeed");
         assert_eq!(changes1.len(), 1);
         assert_eq!(changes1[0].0, "external_rule_added");
 
         // Second merge: duplicate — should not add again
-        let changes2 = merge_external_rules(chat_root, vec![new_rule])
+        let changes2 = merge_external_rules(TxnWrites::new(chat_root, "evo_test"), vec![new_rule])
             .expect("second merge should succeed (no new rules)");
         assert!(
             changes2.is_empty(),
             "duplicate rule should not be added again"
         );
     }
+
+    #[test]
+    fn test_merge_external_rules_holds_conflicting_rule() {
+        let tmp = tempfile::TempDir::new().expect("temp dir should be created");
+        let chat_root = tmp.path();
+        std::fs::create_dir_all(chat_root.join("prompts")).expect("prompts dir");
+        let local = PlanningRule {
+            id: "local_run_tests".to_string(),
+            priority: 60,
+            keywords: vec!["test".to_string()],
+            context_keywords: vec![],
+            tool_hint: None,
+            instruction: "Run the test suite before committing changes.".to_string(),
+            mutable: true,
+            origin: "evolved".to_string(),
+            reusable: false,
+            effectiveness: None,
+            trigger_count: None,
+            provenance: None,
+            scope: None,
+        };
+        skilllite_fs::atomic_write(
+            &chat_root.join("prompts").join("rules.json"),
+            &serde_json::to_string(&vec![local.clone()]).expect("serialize"),
+        )
+        .expect("write rules");
+
+        let external = PlanningRule {
+            id: "ext_skip_tests".to_string(),
+            instruction: "Do not run the test suite before committing changes.".to_string(),
+            origin: "external".to_string(),
+            priority: 50,
+            provenance: Some(RuleProvenance {
+                source_id: "feed".to_string(),
+                source_url: "https://example.com/feed".to_string(),
+                fetched_at: "2026-01-01T00:00:00Z".to_string(),
+                content_hash: RuleProvenance::hash_content("1. Skip tests"),
+            }),
+            ..local.clone()
+        };
+        let changes = merge_external_rules(TxnWrites::new(chat_root, "evo_test"), vec![external])
+            .expect("merge");
+        assert_eq!(
+            changes,
+            vec![(
+                "external_rule_conflict".to_string(),
+                "ext_skip_tests".to_string()
+            )]
+        );
+        let rules = seed::load_rules(chat_root);
+        assert!(!rules.iter().any(|r| r.id == "ext_skip_tests"));
+        let pending = rule_conflict::load_pending_conflicts(chat_root);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].conflicts_with, "local_run_tests");
+        assert_eq!(
+            pending[0]
+                .rule
+                .provenance
+                .as_ref()
+                .map(|p| p.source_id.as_str()),
+            Some("feed")
+        );
+    }
 }
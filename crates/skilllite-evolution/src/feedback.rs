@@ -12,6 +12,13 @@ pub const EVOLUTION_LOG_TYPE_RUN_MATERIAL: &str = "evolution_run";
 /// Does **not** advance passive cooldown or material-only “last run” timers.
 pub const EVOLUTION_LOG_TYPE_RUN_NOOP: &str = "evolution_run_noop";
 
+/// `decisions.evolved`: not yet read by an evolution run.
+pub const DECISION_STATE_PENDING: i64 = 0;
+/// `decisions.evolved`: consumed by a learner during an evolution run.
+pub const DECISION_STATE_PROCESSED: i64 = 1;
+/// `decisions.evolved`: aged out of `SKILLLITE_EVO_DECISION_MAX_AGE_DAYS` without being read.
+pub const DECISION_STATE_EXPIRED: i64 = 2;
+
 // ─── Decision input (agent converts ExecutionFeedback to this) ─────────────────
 
 /// Input for recording a decision. The agent converts its ExecutionFeedback to this.
@@ -26,15 +33,46 @@ pub struct DecisionInput {
     pub completion_type_reported: String,
     pub task_description: Option<String>,
     pub rules_used: Vec<String>,
+    /// Scoped rules that were in scope for the task (`decision_rule_scope`).
+    pub rules_in_scope: Vec<String>,
     pub tools_detail: Vec<ToolExecDetail>,
+    /// Configured model of the run (None for callers that do not track it).
+    pub model: Option<String>,
+    /// Set when the run switched to a fallback model after repeated failures.
+    pub fallback_model: Option<String>,
+    /// Reasons given for each accepted replan (update_task_plan), in order.
+    pub replan_reasons: Vec<String>,
+    /// Runs of identical tool calls the agent loop had to break up.
+    pub tool_loops: usize,
+    /// Workspace root the task ran in (drives the per-workspace `DECISIONS.md` view).
+    pub workspace: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// One tool call of a decision. Only `tool` and `success` are kept in `decisions.tools_detail`;
+/// the rest goes to `decision_tools` (pruned with the decision expiry window).
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct ToolExecDetail {
     pub tool: String,
     pub success: bool,
+    /// Skill that provides the tool, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skill: Option<String>,
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Size of the tool result content in bytes.
+    #[serde(default)]
+    pub result_bytes: usize,
+    /// `kind` of the structured sandbox error when the call failed with one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<String>,
+    /// Sorted names of the top-level arguments that were set; values are never recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args_present: Vec<String>,
 }
 
+/// Rows kept in `decision_tools` regardless of age.
+pub const DECISION_TOOLS_MAX_ROWS: i64 = 20_000;
+
 /// User feedback signal for the last decision.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum FeedbackSignal {
@@ -113,97 +151,10 @@ pub fn open_evolution_db(chat_root: &Path) -> Result<Connection> {
 }
 // ─── Schema ─────────────────────────────────────────────────────────────────
 
+/// Create or migrate the evolution tables to [`crate::schema::SCHEMA_VERSION`]. File-backed
+/// databases are backed up before a migration alters existing tables.
 pub fn ensure_evolution_tables(conn: &Connection) -> Result<()> {
-    conn.execute_batch(
-        r#"
-        CREATE TABLE IF NOT EXISTS decisions (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            ts TEXT NOT NULL DEFAULT (datetime('now')),
-            session_id TEXT,
-            total_tools INTEGER DEFAULT 0,
-            failed_tools INTEGER DEFAULT 0,
-            replans INTEGER DEFAULT 0,
-            elapsed_ms INTEGER DEFAULT 0,
-            task_completed BOOLEAN DEFAULT 0,
-            completion_type TEXT DEFAULT 'success',
-            completion_type_reported TEXT DEFAULT 'success',
-            feedback TEXT DEFAULT 'neutral',
-            evolved BOOLEAN DEFAULT 0,
-            task_description TEXT,
-            tools_detail TEXT,
-            tool_sequence_key TEXT
-        );
-
-        CREATE TABLE IF NOT EXISTS decision_rules (
-            decision_id INTEGER REFERENCES decisions(id) ON DELETE CASCADE,
-            rule_id TEXT NOT NULL
-        );
-
-        CREATE TABLE IF NOT EXISTS evolution_log (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            ts TEXT NOT NULL DEFAULT (datetime('now')),
-            type TEXT NOT NULL,
-            target_id TEXT,
-            reason TEXT,
-            version TEXT
-        );
-
-        CREATE TABLE IF NOT EXISTS evolution_metrics (
-            date TEXT PRIMARY KEY,
-            first_success_rate REAL,
-            avg_replans REAL,
-            avg_tool_calls REAL,
-            user_correction_rate REAL,
-            evolved_rules INTEGER DEFAULT 0,
-            effective_rules INTEGER DEFAULT 0,
-            egl REAL DEFAULT 0.0
-        );
-
-        CREATE TABLE IF NOT EXISTS evolution_backlog (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            proposal_id TEXT NOT NULL UNIQUE,
-            source TEXT NOT NULL,
-            dedupe_key TEXT NOT NULL UNIQUE,
-            scope_json TEXT NOT NULL,
-            risk_level TEXT NOT NULL,
-            roi_score REAL NOT NULL DEFAULT 0.0,
-            expected_gain REAL NOT NULL DEFAULT 0.0,
-            effort REAL NOT NULL DEFAULT 1.0,
-            acceptance_criteria TEXT NOT NULL DEFAULT '[]',
-            status TEXT NOT NULL,
-            acceptance_status TEXT NOT NULL DEFAULT 'pending',
-            note TEXT,
-            created_at TEXT NOT NULL DEFAULT (datetime('now')),
-            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_decisions_evolved ON decisions(evolved);
-        CREATE INDEX IF NOT EXISTS idx_decisions_ts ON decisions(ts);
-        CREATE INDEX IF NOT EXISTS idx_dr_rule ON decision_rules(rule_id);
-        CREATE INDEX IF NOT EXISTS idx_dr_decision ON decision_rules(decision_id);
-        CREATE INDEX IF NOT EXISTS idx_evo_log_ts ON evolution_log(ts);
-        CREATE INDEX IF NOT EXISTS idx_evo_backlog_status_roi ON evolution_backlog(status, roi_score DESC);
-        CREATE INDEX IF NOT EXISTS idx_evo_backlog_created_at ON evolution_backlog(created_at);
-        "#,
-    )?;
-    // Backward-compatible migration: add column for existing DBs (ignored if column exists).
-    let _ = conn.execute(
-        "ALTER TABLE decisions ADD COLUMN tool_sequence_key TEXT",
-        [],
-    );
-    let _ = conn.execute(
-        "ALTER TABLE decisions ADD COLUMN completion_type TEXT DEFAULT 'success'",
-        [],
-    );
-    let _ = conn.execute(
-        "ALTER TABLE decisions ADD COLUMN completion_type_reported TEXT DEFAULT 'success'",
-        [],
-    );
-    // Index must be created after ALTER TABLE so existing DBs have the column first.
-    let _ = conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_decisions_seq ON decisions(tool_sequence_key)",
-        [],
-    );
+    crate::schema::migrate(conn)?;
     Ok(())
 }
 
@@ -231,13 +182,29 @@ pub fn insert_decision(
     feedback: &DecisionInput,
     user_feedback: FeedbackSignal,
 ) -> Result<i64> {
-    let tools_detail_json = serde_json::to_string(&feedback.tools_detail).unwrap_or_default();
+    #[derive(serde::Serialize)]
+    struct CompactDetail<'a> {
+        tool: &'a str,
+        success: bool,
+    }
+    let compact: Vec<_> = feedback
+        .tools_detail
+        .iter()
+        .map(|t| CompactDetail {
+            tool: &t.tool,
+            success: t.success,
+        })
+        .collect();
+    let tools_detail_json = serde_json::to_string(&compact).unwrap_or_default();
     let tool_sequence_key = compute_tool_sequence_key(&feedback.tools_detail);
+    let replan_reasons_json = (!feedback.replan_reasons.is_empty())
+        .then(|| serde_json::to_string(&feedback.replan_reasons).unwrap_or_default());
 
     conn.execute(
         "INSERT INTO decisions (session_id, total_tools, failed_tools, replans,
-         elapsed_ms, task_completed, completion_type, completion_type_reported, feedback, task_description, tools_detail, tool_sequence_key)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+         elapsed_ms, task_completed, completion_type, completion_type_reported, feedback, task_description, tools_detail, tool_sequence_key,
+         model, fallback_model, replan_reasons, workspace, tool_loops)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
         params![
             session_id,
             feedback.total_tools as i64,
@@ -251,6 +218,11 @@ pub fn insert_decision(
             feedback.task_description,
             tools_detail_json,
             tool_sequence_key,
+            feedback.model,
+            feedback.fallback_model,
+            replan_reasons_json,
+            feedback.workspace,
+            feedback.tool_loops as i64,
         ],
     )?;
     let decision_id = conn.last_insert_rowid();
@@ -263,9 +235,60 @@ pub fn insert_decision(
         }
     }
 
+    if !feedback.rules_in_scope.is_empty() {
+        let mut stmt =
+            conn.prepare("INSERT INTO decision_rule_scope (decision_id, rule_id) VALUES (?1, ?2)")?;
+        for rule_id in &feedback.rules_in_scope {
+            stmt.execute(params![decision_id, rule_id])?;
+        }
+    }
+
+    if !feedback.tools_detail.is_empty() {
+        let mut stmt = conn.prepare(
+            "INSERT INTO decision_tools (decision_id, seq, tool, skill, duration_ms,
+             result_bytes, is_error, error_kind, args_present)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+        for (seq, t) in feedback.tools_detail.iter().enumerate() {
+            stmt.execute(params![
+                decision_id,
+                seq as i64,
+                t.tool,
+                t.skill,
+                t.duration_ms as i64,
+                t.result_bytes as i64,
+                !t.success,
+                t.error_kind,
+                serde_json::to_string(&t.args_present).unwrap_or_default(),
+            ])?;
+        }
+    }
+
     Ok(decision_id)
 }
 
+/// Drop `decision_tools` rows older than `max_age_days` (the decision expiry window; `<= 0`
+/// keeps all ages), then trim to the newest [`DECISION_TOOLS_MAX_ROWS`]. Daily aggregates in
+/// `tool_metrics` are kept. Returns the number of rows deleted.
+pub fn prune_decision_tools(conn: &Connection, max_age_days: i64) -> Result<usize> {
+    let mut n = 0;
+    if max_age_days > 0 {
+        n += conn.execute(
+            &format!(
+                "DELETE FROM decision_tools WHERE ts < datetime('now', '-{} days')",
+                max_age_days
+            ),
+            [],
+        )?;
+    }
+    n += conn.execute(
+        "DELETE FROM decision_tools WHERE rowid NOT IN
+         (SELECT rowid FROM decision_tools ORDER BY rowid DESC LIMIT ?1)",
+        params![DECISION_TOOLS_MAX_ROWS],
+    )?;
+    Ok(n)
+}
+
 pub fn count_unprocessed_decisions(conn: &Connection) -> Result<i64> {
     conn.query_row(
         "SELECT COUNT(*) FROM decisions WHERE evolved = 0",
@@ -275,6 +298,16 @@ pub fn count_unprocessed_decisions(conn: &Connection) -> Result<i64> {
     .map_err(Into::into)
 }
 
+/// Decisions that aged out before any evolution run read them.
+pub fn count_expired_decisions(conn: &Connection) -> Result<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM decisions WHERE evolved = ?1",
+        params![DECISION_STATE_EXPIRED],
+        |r| r.get(0),
+    )
+    .map_err(Into::into)
+}
+
 /// Diagnostic: count unprocessed decisions with/without task_description.
 /// Evolution requires task_description to learn from decisions.
 pub fn count_decisions_with_task_desc(conn: &Connection) -> Result<(i64, i64)> {
@@ -291,14 +324,18 @@ pub fn count_decisions_with_task_desc(conn: &Connection) -> Result<(i64, i64)> {
     Ok((total, with_desc))
 }
 
+/// Set the session's latest decision feedback and count the signal: a user may correct or
+/// confirm the same answer several times before the next decision is recorded.
 pub fn update_last_decision_feedback(
     conn: &Connection,
     session_id: &str,
     feedback: FeedbackSignal,
 ) -> Result<()> {
     conn.execute(
-        "UPDATE decisions SET feedback = ?1
-         WHERE id = (SELECT id FROM decisions WHERE session_id = ?2 ORDER BY ts DESC LIMIT 1)",
+        "UPDATE decisions SET feedback = ?1,
+             corrections = COALESCE(corrections, 0) + (?1 = 'neg'),
+             confirmations = COALESCE(confirmations, 0) + (?1 = 'pos')
+         WHERE id = (SELECT id FROM decisions WHERE session_id = ?2 ORDER BY ts DESC, id DESC LIMIT 1)",
         params![feedback.as_str(), session_id],
     )?;
     Ok(())
@@ -352,20 +389,54 @@ pub fn query_rule_history(conn: &Connection, rule_id: &str) -> Result<Vec<RuleHi
 
 // ─── System-level metrics ───────────────────────────────────────────────────
 
+/// Rows rebuilt by [`rebuild_derived_tables`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RebuildReport {
+    pub metric_days: usize,
+    pub sequence_keys: usize,
+}
+
+/// Recompute data derived from `decisions` (daily `evolution_metrics`, `tool_sequence_key`)
+/// without touching decisions, rule links or the evolution log.
+pub fn rebuild_derived_tables(conn: &Connection) -> Result<RebuildReport> {
+    let tx = conn.unchecked_transaction()?;
+    let sequence_keys = crate::schema::recompute_sequence_keys(&tx, false)?;
+    tx.execute("DELETE FROM evolution_metrics", [])?;
+    let dates: Vec<String> = tx
+        .prepare("SELECT DISTINCT date(ts) FROM decisions WHERE ts IS NOT NULL ORDER BY 1")?
+        .query_map([], |row| row.get(0))?
+        .collect::<std::result::Result<_, _>>()?;
+    for date in &dates {
+        upsert_metrics_for_date(&tx, date)?;
+    }
+    tx.commit()?;
+    Ok(RebuildReport {
+        metric_days: dates.len(),
+        sequence_keys,
+    })
+}
+
 pub fn update_daily_metrics(conn: &Connection) -> Result<()> {
     let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
-    let core = compute_core_metrics_for_date(conn, &today)?;
+    upsert_metrics_for_date(conn, &today)
+}
+
+fn upsert_metrics_for_date(conn: &Connection, date: &str) -> Result<()> {
+    let core = compute_core_metrics_for_date(conn, date)?;
 
     let avg_tool_calls: f64 = conn
         .query_row(
             "SELECT COALESCE(AVG(CAST(total_tools AS REAL)), 0.0)
              FROM decisions
              WHERE date(ts) = ?1 AND total_tools >= 1",
-            params![today],
+            params![date],
             |row| row.get(0),
         )
         .unwrap_or(0.0);
-    let egl = compute_egl(conn, &today).unwrap_or(0.0);
+    let egl = compute_egl(conn, date).unwrap_or(0.0);
+
+    upsert_tool_metrics_for_date(conn, date)?;
+    crate::metrics_breakdown::upsert_breakdown_for_date(conn, date)?;
 
     conn.execute(
         "INSERT INTO evolution_metrics (date, first_success_rate, avg_replans,
@@ -375,7 +446,7 @@ pub fn update_daily_metrics(conn: &Connection) -> Result<()> {
             first_success_rate = ?2, avg_replans = ?3,
             avg_tool_calls = ?4, user_correction_rate = ?5, egl = ?6",
         params![
-            today,
+            date,
             core.first_success_rate,
             core.avg_replans,
             avg_tool_calls,
@@ -387,6 +458,147 @@ pub fn update_daily_metrics(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Roll `decision_tools` up into `tool_metrics` for `date`. Dates whose raw rows were already
+/// pruned keep their existing aggregates.
+fn upsert_tool_metrics_for_date(conn: &Connection, date: &str) -> Result<()> {
+    let has_rows: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM decision_tools WHERE date(ts) = ?1)",
+        params![date],
+        |row| row.get(0),
+    )?;
+    if !has_rows {
+        return Ok(());
+    }
+    conn.execute("DELETE FROM tool_metrics WHERE date = ?1", params![date])?;
+    conn.execute(
+        "INSERT INTO tool_metrics (date, tool, calls, failures, total_duration_ms,
+         max_duration_ms, total_result_bytes)
+         SELECT ?1, tool, COUNT(*), SUM(is_error), SUM(duration_ms), MAX(duration_ms),
+                SUM(result_bytes)
+         FROM decision_tools WHERE date(ts) = ?1
+         GROUP BY tool",
+        params![date],
+    )?;
+    Ok(())
+}
+
+/// Call statistics for one tool over a window of days.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ToolStat {
+    pub tool: String,
+    pub calls: i64,
+    pub failures: i64,
+    pub failure_rate: f64,
+    pub avg_duration_ms: f64,
+    pub max_duration_ms: i64,
+    pub avg_result_bytes: f64,
+}
+
+/// Ordering for [`tool_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolStatOrder {
+    Slowest,
+    FailureRate,
+}
+
+/// Per-tool aggregates from `tool_metrics` over the last `days` days. Tools with fewer than
+/// `min_calls` calls are skipped so one-off failures do not top the failure ranking.
+pub fn tool_stats(
+    conn: &Connection,
+    days: u32,
+    min_calls: i64,
+    order: ToolStatOrder,
+    limit: usize,
+) -> Result<Vec<ToolStat>> {
+    let order_by = match order {
+        ToolStatOrder::Slowest => "4 DESC",
+        ToolStatOrder::FailureRate => "CAST(SUM(failures) AS REAL) / SUM(calls) DESC, 2 DESC",
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT tool, SUM(calls), SUM(failures),
+                CAST(SUM(total_duration_ms) AS REAL) / SUM(calls), MAX(max_duration_ms),
+                CAST(SUM(total_result_bytes) AS REAL) / SUM(calls)
+         FROM tool_metrics
+         WHERE date > date('now', ?1)
+         GROUP BY tool
+         HAVING SUM(calls) >= ?2
+         ORDER BY {}, 1
+         LIMIT ?3",
+        order_by
+    ))?;
+    let rows = stmt.query_map(
+        params![format!("-{} days", days), min_calls, limit as i64],
+        |row| {
+            let calls: i64 = row.get(1)?;
+            let failures: i64 = row.get(2)?;
+            Ok(ToolStat {
+                tool: row.get(0)?,
+                calls,
+                failures,
+                failure_rate: failures as f64 / calls.max(1) as f64,
+                avg_duration_ms: row.get(3)?,
+                max_duration_ms: row.get(4)?,
+                avg_result_bytes: row.get(5)?,
+            })
+        },
+    )?;
+    Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+/// A tool + argument-shape combination and how often the decisions using it went wrong.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ToolFailurePattern {
+    pub tool: String,
+    pub args_present: Vec<String>,
+    pub calls: i64,
+    /// Calls that themselves returned an error.
+    pub tool_failures: i64,
+    /// Calls inside decisions that did not complete, were corrected or had a failed tool.
+    pub failed_decisions: i64,
+    pub failure_rate: f64,
+}
+
+/// Tool/argument-shape patterns from the raw `decision_tools` window most correlated with
+/// failed decisions, highest failure rate first. Patterns seen fewer than `min_calls` times
+/// are skipped.
+pub fn failure_correlated_tool_patterns(
+    conn: &Connection,
+    min_calls: i64,
+    limit: usize,
+) -> Result<Vec<ToolFailurePattern>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.tool, t.args_present, COUNT(*), SUM(t.is_error),
+                SUM(d.task_completed = 0 OR d.feedback = 'neg' OR d.failed_tools > 0)
+         FROM decision_tools t JOIN decisions d ON d.id = t.decision_id
+         GROUP BY 1, 2
+         HAVING COUNT(*) >= ?1 AND SUM(d.task_completed = 0 OR d.feedback = 'neg'
+                                         OR d.failed_tools > 0) > 0",
+    )?;
+    let mut patterns = stmt
+        .query_map(params![min_calls], |row| {
+            let args: String = row.get(1)?;
+            let calls: i64 = row.get(2)?;
+            let failed_decisions: i64 = row.get(4)?;
+            Ok(ToolFailurePattern {
+                tool: row.get(0)?,
+                args_present: serde_json::from_str(&args).unwrap_or_default(),
+                calls,
+                tool_failures: row.get(3)?,
+                failed_decisions,
+                failure_rate: failed_decisions as f64 / calls.max(1) as f64,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    patterns.sort_by(|a, b| {
+        b.failure_rate
+            .total_cmp(&a.failure_rate)
+            .then(b.calls.cmp(&a.calls))
+            .then(a.tool.cmp(&b.tool))
+    });
+    patterns.truncate(limit);
+    Ok(patterns)
+}
+
 pub fn compute_core_metrics_for_date(conn: &Connection, date: &str) -> Result<CoreMetrics> {
     // first_success_rate: (total decisions where task_completed = 1 and feedback != 'neg') / total decisions
     let (success_count, total_count): (i64, i64) = conn.query_row(
@@ -413,11 +625,12 @@ pub fn compute_core_metrics_for_date(conn: &Connection, date: &str) -> Result<Co
         )
         .unwrap_or(0.0);
 
-    // user_correction_rate: count of 'neg' feedbacks / (count of 'pos' feedbacks + count of 'neg' feedbacks)
+    // user_correction_rate: corrections / (confirmations + corrections). Each decision counts
+    // its recorded signals, or at least one for its final feedback label.
     let (pos_feedback_count, neg_feedback_count): (i64, i64) = conn.query_row(
         "SELECT
-            COUNT(CASE WHEN feedback = 'pos' THEN 1 END),
-            COUNT(CASE WHEN feedback = 'neg' THEN 1 END)
+            COALESCE(SUM(MAX(COALESCE(confirmations, 0), feedback = 'pos')), 0),
+            COALESCE(SUM(MAX(COALESCE(corrections, 0), feedback = 'neg')), 0)
          FROM decisions
          WHERE date(ts) = ?1",
         params![date],
@@ -436,6 +649,42 @@ pub fn compute_core_metrics_for_date(conn: &Connection, date: &str) -> Result<Co
     })
 }
 
+/// Outcomes of recent decisions grouped by the model that finished them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelOutcome {
+    /// Fallback model when the run switched, else the configured model.
+    pub model: String,
+    /// Whether these runs reached `model` through a fallback.
+    pub via_fallback: bool,
+    pub decisions: i64,
+    pub success_rate: f64,
+}
+
+/// Per-model success rates over the last `days` days (decisions without a recorded model
+/// are skipped), most-used model first.
+pub fn model_outcomes(conn: &Connection, days: u32) -> Result<Vec<ModelOutcome>> {
+    let mut stmt = conn.prepare(
+        "SELECT COALESCE(fallback_model, model), fallback_model IS NOT NULL,
+                COUNT(*),
+                COUNT(CASE WHEN task_completed = 1 AND feedback != 'neg' THEN 1 END)
+         FROM decisions
+         WHERE model IS NOT NULL AND ts > datetime('now', ?1)
+         GROUP BY 1, 2
+         ORDER BY 3 DESC, 1",
+    )?;
+    let rows = stmt.query_map(params![format!("-{} days", days)], |row| {
+        let decisions: i64 = row.get(2)?;
+        let successes: i64 = row.get(3)?;
+        Ok(ModelOutcome {
+            model: row.get(0)?,
+            via_fallback: row.get(1)?,
+            decisions,
+            success_rate: successes as f64 / decisions.max(1) as f64,
+        })
+    })?;
+    Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
 // ─── Evolution decision making ────────────────────────────────────────────────
 
 /// EGL (Evolutionary Grade Level) captures a single, combined score of "goodness"
@@ -457,8 +706,8 @@ pub fn compute_egl_for_rule(conn: &Connection, rule_id: &str) -> Result<f64> {
             COUNT(CASE WHEN d.task_completed = 1 AND d.feedback != 'neg' THEN 1 END),
             COUNT(*),
             SUM(d.replans),
-            COUNT(CASE WHEN d.feedback = 'pos' THEN 1 END),
-            COUNT(CASE WHEN d.feedback = 'neg' THEN 1 END)
+            COALESCE(SUM(MAX(COALESCE(d.confirmations, 0), d.feedback = 'pos')), 0),
+            COALESCE(SUM(MAX(COALESCE(d.corrections, 0), d.feedback = 'neg')), 0)
          FROM decisions d
          JOIN decision_rules dr ON d.id = dr.decision_id
          WHERE dr.rule_id = ?1 AND d.ts > datetime('now', '-30 days')", // Last 30 days
@@ -647,6 +896,41 @@ mod tests {
         conn
     }
 
+    #[test]
+    fn rebuild_derived_tables_keeps_decisions() {
+        let conn = setup_conn();
+        let input = DecisionInput {
+            total_tools: 2,
+            task_completed: true,
+            tools_detail: vec![ToolExecDetail {
+                tool: "read_file".into(),
+                success: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        insert_decision(&conn, Some("s1"), &input, FeedbackSignal::Neutral).unwrap();
+        conn.execute_batch(
+            "UPDATE decisions SET tool_sequence_key = 'stale';
+             INSERT INTO evolution_metrics (date, first_success_rate) VALUES ('2000-01-01', 0.5);",
+        )
+        .unwrap();
+
+        let report = rebuild_derived_tables(&conn).unwrap();
+        assert_eq!(report.metric_days, 1);
+        assert_eq!(report.sequence_keys, 1);
+        let (decisions, key, stale): (i64, String, i64) = conn
+            .query_row(
+                "SELECT COUNT(*), MAX(tool_sequence_key),
+                        (SELECT COUNT(*) FROM evolution_metrics WHERE date = '2000-01-01')
+                 FROM decisions",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((decisions, key.as_str(), stale), (1, "read_file", 0));
+    }
+
     #[test]
     fn test_ensure_evolution_tables() {
         let conn = setup_conn();
@@ -673,6 +957,12 @@ mod tests {
             completion_type_reported: "success".to_string(),
             task_description: Some("test task".to_string()),
             rules_used: vec![],
+            rules_in_scope: vec![],
+            model: None,
+            fallback_model: None,
+            replan_reasons: vec![],
+            tool_loops: 0,
+            workspace: None,
             tools_detail: vec![],
         };
         let decision_id =
@@ -685,6 +975,165 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn replan_reasons_are_stored_as_json() {
+        let conn = setup_conn();
+        let input = DecisionInput {
+            replans: 2,
+            replan_reasons: vec![
+                "api returned 404".to_string(),
+                "split the export".to_string(),
+            ],
+            ..Default::default()
+        };
+        insert_decision(&conn, Some("s1"), &input, FeedbackSignal::Neutral).unwrap();
+        insert_decision(
+            &conn,
+            Some("s1"),
+            &DecisionInput::default(),
+            FeedbackSignal::Neutral,
+        )
+        .unwrap();
+
+        let stored: Vec<Option<String>> = conn
+            .prepare("SELECT replan_reasons FROM decisions ORDER BY id")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(
+            stored,
+            vec![
+                Some(r#"["api returned 404","split the export"]"#.to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn tool_calls_roll_up_into_stats_and_failure_patterns() {
+        let conn = setup_conn();
+        let call = |tool: &str, success: bool, ms: u64, args: &[&str]| ToolExecDetail {
+            tool: tool.to_string(),
+            success,
+            duration_ms: ms,
+            result_bytes: 100,
+            error_kind: (!success).then(|| "timeout".to_string()),
+            args_present: args.iter().map(|a| a.to_string()).collect(),
+            ..Default::default()
+        };
+        let failed = DecisionInput {
+            total_tools: 2,
+            failed_tools: 1,
+            task_completed: false,
+            tools_detail: vec![
+                call("grep_files", true, 40, &["pattern"]),
+                call("run_command", false, 900, &["command"]),
+            ],
+            ..Default::default()
+        };
+        let ok = DecisionInput {
+            total_tools: 2,
+            task_completed: true,
+            tools_detail: vec![
+                call("grep_files", true, 20, &["include", "pattern"]),
+                call("run_command", true, 300, &["command"]),
+            ],
+            ..Default::default()
+        };
+        for input in [&failed, &failed, &failed, &ok] {
+            insert_decision(&conn, Some("s1"), input, FeedbackSignal::Neutral).unwrap();
+        }
+
+        // The decisions row keeps the compact shape; details live in decision_tools.
+        let detail: String = conn
+            .query_row("SELECT tools_detail FROM decisions LIMIT 1", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(
+            detail,
+            r#"[{"tool":"grep_files","success":true},{"tool":"run_command","success":false}]"#
+        );
+
+        update_daily_metrics(&conn).unwrap();
+        let slowest = tool_stats(&conn, 7, 1, ToolStatOrder::Slowest, 10).unwrap();
+        assert_eq!(slowest[0].tool, "run_command");
+        assert_eq!(slowest[0].calls, 4);
+        assert_eq!(slowest[0].avg_duration_ms, 750.0);
+        assert_eq!(slowest[0].max_duration_ms, 900);
+        let failing = tool_stats(&conn, 7, 3, ToolStatOrder::FailureRate, 1).unwrap();
+        assert_eq!(failing[0].tool, "run_command");
+        assert_eq!(failing[0].failures, 3);
+
+        let patterns = failure_correlated_tool_patterns(&conn, 3, 10).unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].tool, "grep_files");
+        assert_eq!(patterns[0].args_present, vec!["pattern"]);
+        assert_eq!(patterns[0].failure_rate, 1.0);
+        assert_eq!(patterns[1].tool, "run_command");
+        assert_eq!(patterns[1].failed_decisions, 3);
+
+        // Pruning drops raw rows but keeps the daily aggregates.
+        conn.execute(
+            "UPDATE decision_tools SET ts = datetime('now', '-20 days')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(prune_decision_tools(&conn, 0).unwrap(), 0);
+        assert_eq!(prune_decision_tools(&conn, 14).unwrap(), 8);
+        update_daily_metrics(&conn).unwrap();
+        assert_eq!(
+            tool_stats(&conn, 7, 1, ToolStatOrder::Slowest, 10)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn model_outcomes_separate_fallback_runs() {
+        let conn = setup_conn();
+        let primary = DecisionInput {
+            total_tools: 1,
+            task_completed: true,
+            model: Some("gpt-4o".to_string()),
+            ..Default::default()
+        };
+        let fell_back = DecisionInput {
+            task_completed: false,
+            fallback_model: Some("通义-max".to_string()),
+            ..primary.clone()
+        };
+        let untracked = DecisionInput {
+            model: None,
+            ..primary.clone()
+        };
+        for input in [&primary, &primary, &fell_back, &untracked] {
+            insert_decision(&conn, Some("s1"), input, FeedbackSignal::Neutral).unwrap();
+        }
+
+        let outcomes = model_outcomes(&conn, 30).unwrap();
+        assert_eq!(
+            outcomes,
+            vec![
+                ModelOutcome {
+                    model: "gpt-4o".to_string(),
+                    via_fallback: false,
+                    decisions: 2,
+                    success_rate: 1.0,
+                },
+                ModelOutcome {
+                    model: "通义-max".to_string(),
+                    via_fallback: true,
+                    decisions: 1,
+                    success_rate: 0.0,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_update_last_decision_feedback() {
         let conn = setup_conn();
@@ -698,6 +1147,12 @@ mod tests {
             completion_type_reported: "success".to_string(),
             task_description: Some("test task".to_string()),
             rules_used: vec![],
+            rules_in_scope: vec![],
+            model: None,
+            fallback_model: None,
+            replan_reasons: vec![],
+            tool_loops: 0,
+            workspace: None,
             tools_detail: vec![],
         };
         insert_decision(&conn, Some("s1"), &input, FeedbackSignal::Neutral).unwrap();
@@ -774,18 +1229,22 @@ mod tests {
             ToolExecDetail {
                 tool: "read_file".to_string(),
                 success: true,
+                ..Default::default()
             },
             ToolExecDetail {
                 tool: "write_file".to_string(),
                 success: true,
+                ..Default::default()
             },
             ToolExecDetail {
                 tool: "run_command".to_string(),
                 success: false,
+                ..Default::default()
             },
             ToolExecDetail {
                 tool: "http_request".to_string(),
                 success: true,
+                ..Default::default()
             }, // Should be ignored
         ];
         let key = compute_tool_sequence_key(&tools_detail);
@@ -798,6 +1257,7 @@ mod tests {
         let single_detail = vec![ToolExecDetail {
             tool: "list_directory".to_string(),
             success: true,
+            ..Default::default()
         }];
         let key = compute_tool_sequence_key(&single_detail);
         assert_eq!(key, Some("list_directory".to_string()));
@@ -816,9 +1276,16 @@ mod tests {
             completion_type_reported: "success".to_string(),
             task_description: Some("test".to_string()),
             rules_used: vec!["rule-a".to_string(), "rule-b".to_string()],
+            rules_in_scope: vec![],
+            model: None,
+            fallback_model: None,
+            replan_reasons: vec![],
+            tool_loops: 0,
+            workspace: None,
             tools_detail: vec![ToolExecDetail {
                 tool: "read_file".to_string(),
                 success: true,
+                ..Default::default()
             }],
         };
 
@@ -877,6 +1344,38 @@ mod tests {
         assert!((metrics.user_correction_rate - 0.5).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_repeated_corrections_are_counted_and_weigh_in_correction_rate() {
+        let conn = setup_conn();
+        let input = DecisionInput {
+            total_tools: 1,
+            task_completed: true,
+            completion_type: "success".to_string(),
+            completion_type_reported: "success".to_string(),
+            task_description: Some("draft release notes".to_string()),
+            ..Default::default()
+        };
+        insert_decision(&conn, Some("s1"), &input, FeedbackSignal::Neutral).unwrap();
+        update_last_decision_feedback(&conn, "s1", FeedbackSignal::ExplicitNegative).unwrap();
+        update_last_decision_feedback(&conn, "s1", FeedbackSignal::ExplicitNegative).unwrap();
+        insert_decision(&conn, Some("s1"), &input, FeedbackSignal::Neutral).unwrap();
+        update_last_decision_feedback(&conn, "s1", FeedbackSignal::ExplicitPositive).unwrap();
+
+        let counts: Vec<(i64, i64)> = conn
+            .prepare("SELECT corrections, confirmations FROM decisions ORDER BY id")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(counts, vec![(2, 0), (0, 1)]);
+
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let metrics = compute_core_metrics_for_date(&conn, &today).unwrap();
+        // 2 corrections / (1 confirmation + 2 corrections)
+        assert!((metrics.user_correction_rate - 2.0 / 3.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_build_latest_judgement_promotes_improving_metrics() {
         let conn = setup_conn();
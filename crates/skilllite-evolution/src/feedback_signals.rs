@@ -0,0 +1,236 @@
+//! EVO-1: classify the user's next message as a correction or confirmation of the last turn.
+//!
+//! The zh/en word lists live in `prompts/feedback_signals.json` (seeded from
+//! `seed/feedback_signals.seed.json`) so evolution can refine them; every write goes through
+//! [`crate::gatekeeper::gatekeeper_feedback_signals`]. Messages the lists leave neutral can
+//! optionally be classified by the LLM (`SKILLLITE_EVO_FEEDBACK_LLM=1`).
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use skilllite_core::config::env_keys::evolution as evo_keys;
+use skilllite_fs::atomic_write;
+
+use crate::feedback::FeedbackSignal;
+use crate::gatekeeper::gatekeeper_feedback_signals;
+use crate::seed::SEED_FEEDBACK_SIGNALS;
+use crate::{complete_with_retry, EvolutionLlm, EvolutionMessage, LlmRetryPolicy, Result};
+
+pub const FEEDBACK_SIGNALS_FILE: &str = "feedback_signals.json";
+
+/// Messages longer than this are treated as new requests, not reactions, by the LLM path.
+const LLM_CLASSIFY_MAX_CHARS: usize = 400;
+
+/// Word lists matched against the user's next message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedbackSignalLists {
+    #[serde(default)]
+    pub version: u32,
+    pub correction: Vec<String>,
+    pub confirmation: Vec<String>,
+}
+
+impl FeedbackSignalLists {
+    pub fn seed() -> Self {
+        serde_json::from_str(SEED_FEEDBACK_SIGNALS).unwrap_or_else(|_| Self {
+            version: 1,
+            correction: Vec::new(),
+            confirmation: Vec::new(),
+        })
+    }
+
+    /// Corrections win over confirmations ("好的，但是不对" is a correction).
+    pub fn classify(&self, message: &str) -> FeedbackSignal {
+        let msg = message.to_lowercase();
+        if self.correction.iter().any(|t| contains_term(&msg, t)) {
+            FeedbackSignal::ExplicitNegative
+        } else if self.confirmation.iter().any(|t| contains_term(&msg, t)) {
+            FeedbackSignal::ExplicitPositive
+        } else {
+            FeedbackSignal::Neutral
+        }
+    }
+}
+
+pub fn feedback_signals_path(chat_root: &Path) -> PathBuf {
+    chat_root.join("prompts").join(FEEDBACK_SIGNALS_FILE)
+}
+
+/// Lists from `prompts/`, falling back to the seed when the file is missing or fails the
+/// gatekeeper (e.g. after a hand edit).
+pub fn load_feedback_signals(chat_root: &Path) -> FeedbackSignalLists {
+    let path = feedback_signals_path(chat_root);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return FeedbackSignalLists::seed();
+    };
+    match serde_json::from_str::<FeedbackSignalLists>(&content) {
+        Ok(lists) => match gatekeeper_feedback_signals(&lists) {
+            Ok(()) => lists,
+            Err(e) => {
+                tracing::warn!("Ignoring {}: {}", path.display(), e);
+                FeedbackSignalLists::seed()
+            }
+        },
+        Err(e) => {
+            tracing::warn!("Failed to parse {}: {}", path.display(), e);
+            FeedbackSignalLists::seed()
+        }
+    }
+}
+
+/// Gatekeeper-checked write of refined word lists.
+pub fn save_feedback_signals(chat_root: &Path, lists: &FeedbackSignalLists) -> Result<()> {
+    gatekeeper_feedback_signals(lists)?;
+    let path = feedback_signals_path(chat_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    atomic_write(&path, &serde_json::to_string_pretty(lists)?)?;
+    Ok(())
+}
+
+/// Heuristic classification with the word lists in `prompts/`.
+pub fn classify_user_message(chat_root: &Path, message: &str) -> FeedbackSignal {
+    load_feedback_signals(chat_root).classify(message)
+}
+
+pub fn llm_classification_enabled() -> bool {
+    std::env::var(evo_keys::SKILLLITE_EVO_FEEDBACK_LLM)
+        .ok()
+        .as_deref()
+        .map(|v| v == "1" || v == "true")
+        .unwrap_or(false)
+}
+
+/// Ask the LLM whether `message` corrects or confirms the assistant's previous answer.
+/// Long messages are returned as neutral without a call.
+pub async fn classify_with_llm<L: EvolutionLlm + ?Sized>(
+    llm: &L,
+    model: &str,
+    message: &str,
+) -> Result<FeedbackSignal> {
+    let message = message.trim();
+    if message.is_empty() || message.chars().count() > LLM_CLASSIFY_MAX_CHARS {
+        return Ok(FeedbackSignal::Neutral);
+    }
+    let prompt = format!(
+        "The user just sent this message right after the assistant finished a task:\n\n\
+         {}\n\n\
+         Does it correct the assistant (says the result was wrong or asks to redo it), confirm \
+         it (approves or thanks), or neither (a new request or unrelated)? \
+         Answer with exactly one word: correction, confirmation, or neutral.",
+        message
+    );
+    let out = complete_with_retry(
+        llm,
+        &[EvolutionMessage::user(&prompt)],
+        model,
+        0.0,
+        &LlmRetryPolicy::from_env(),
+    )
+    .await?;
+    Ok(parse_llm_label(&out.visible))
+}
+
+fn parse_llm_label(text: &str) -> FeedbackSignal {
+    let word = text
+        .trim()
+        .trim_matches(|c: char| !c.is_ascii_alphabetic())
+        .to_lowercase();
+    match word.as_str() {
+        "correction" => FeedbackSignal::ExplicitNegative,
+        "confirmation" => FeedbackSignal::ExplicitPositive,
+        _ => FeedbackSignal::Neutral,
+    }
+}
+
+/// ASCII terms match whole words only ("ok" must not hit "book"); CJK terms match anywhere.
+fn contains_term(text: &str, term: &str) -> bool {
+    let term = term.trim().to_lowercase();
+    if term.is_empty() {
+        return false;
+    }
+    if !term.is_ascii() {
+        return text.contains(&term);
+    }
+    text.match_indices(&term).any(|(i, m)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + m.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_alphanumeric())
+            && !after.is_some_and(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_lists_classify_zh_and_en() {
+        let lists = FeedbackSignalLists::seed();
+        assert!(gatekeeper_feedback_signals(&lists).is_ok());
+        assert_eq!(
+            lists.classify("不对，重来一遍"),
+            FeedbackSignal::ExplicitNegative
+        );
+        assert_eq!(
+            lists.classify("That's not what I asked for"),
+            FeedbackSignal::ExplicitNegative
+        );
+        assert_eq!(
+            lists.classify("完美，谢谢"),
+            FeedbackSignal::ExplicitPositive
+        );
+        assert_eq!(
+            lists.classify("OK, thanks!"),
+            FeedbackSignal::ExplicitPositive
+        );
+        assert_eq!(
+            lists.classify("好的，但是格式错了"),
+            FeedbackSignal::ExplicitNegative
+        );
+        assert_eq!(
+            lists.classify("Now summarize the book"),
+            FeedbackSignal::Neutral
+        );
+    }
+
+    #[test]
+    fn refined_lists_round_trip_and_bad_files_fall_back_to_seed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut lists = FeedbackSignalLists::seed();
+        lists.confirmation.push("lgtm".to_string());
+        save_feedback_signals(dir.path(), &lists).unwrap();
+        assert_eq!(
+            classify_user_message(dir.path(), "lgtm"),
+            FeedbackSignal::ExplicitPositive
+        );
+
+        let mut overlapping = lists.clone();
+        overlapping.correction.push("lgtm".to_string());
+        assert!(save_feedback_signals(dir.path(), &overlapping).is_err());
+
+        std::fs::write(
+            feedback_signals_path(dir.path()),
+            serde_json::to_string(&overlapping).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            load_feedback_signals(dir.path()),
+            FeedbackSignalLists::seed()
+        );
+    }
+
+    #[test]
+    fn llm_labels_parse_loosely() {
+        assert_eq!(
+            parse_llm_label("Correction."),
+            FeedbackSignal::ExplicitNegative
+        );
+        assert_eq!(
+            parse_llm_label(" confirmation\n"),
+            FeedbackSignal::ExplicitPositive
+        );
+        assert_eq!(parse_llm_label("I think neutral"), FeedbackSignal::Neutral);
+    }
+}
@@ -1,8 +1,11 @@
 //! Path, template, size, and content gatekeepers for evolution writes.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use skilllite_core::path_validation::{resolve_under_roots, PathPolicy};
 
 use crate::error::bail;
+use crate::feedback_signals::FeedbackSignalLists;
 use crate::seed;
 use crate::Result;
 
@@ -11,24 +14,30 @@ use crate::Result;
 const ALLOWED_EVOLUTION_PATHS: &[&str] = &["prompts", "memory", "skills/_evolved"];
 
 /// L1 path gatekeeper. When skills_root is Some, also allows target under skills_root/_evolved
-/// (project-level skill evolution).
+/// (project-level skill evolution). `..` and symlinks pointing out of the allowed
+/// directories are rejected.
 pub fn gatekeeper_l1_path(chat_root: &Path, target: &Path, skills_root: Option<&Path>) -> bool {
-    for allowed in ALLOWED_EVOLUTION_PATHS {
-        let allowed_dir = chat_root.join(allowed);
-        if target.starts_with(&allowed_dir) {
-            return true;
-        }
-    }
-    if let Some(sr) = skills_root {
-        let evolved = sr.join("_evolved");
-        if target.starts_with(&evolved) {
-            return true;
-        }
-    }
-    false
+    let allowed: Vec<PathBuf> = ALLOWED_EVOLUTION_PATHS
+        .iter()
+        .map(|p| chat_root.join(p))
+        .chain(skills_root.map(|sr| sr.join("_evolved")))
+        .collect();
+    let roots: Vec<&Path> = allowed.iter().map(PathBuf::as_path).collect();
+    // Relative targets are relative to the cwd like the roots, not to each root.
+    let Ok(target) = std::path::absolute(target) else {
+        return false;
+    };
+    resolve_under_roots(target, &roots, PathPolicy::writable("Evolution target")).is_ok()
 }
 
 pub fn gatekeeper_l1_template_integrity(filename: &str, new_content: &str) -> Result<()> {
+    if let Err(e) = seed::template::check_syntax(new_content) {
+        bail!(
+            "Gatekeeper L1b: evolved template '{}' is malformed: {}",
+            filename,
+            e
+        );
+    }
     let missing = seed::validate_template(filename, new_content);
     if !missing.is_empty() {
         bail!(
@@ -40,8 +49,29 @@ pub fn gatekeeper_l1_template_integrity(filename: &str, new_content: &str) -> Re
     Ok(())
 }
 
+/// L2: new rules per evolution run.
+pub const L2_MAX_NEW_RULES: usize = 5;
+/// L2: new examples per evolution run.
+pub const L2_MAX_NEW_EXAMPLES: usize = 3;
+/// L2: new skills per evolution run.
+pub const L2_MAX_NEW_SKILLS: usize = 1;
+
+/// L2 size check; learners enforce it through [`crate::change_set::ChangeSet`].
 pub fn gatekeeper_l2_size(new_rules: usize, new_examples: usize, new_skills: usize) -> bool {
-    new_rules <= 5 && new_examples <= 3 && new_skills <= 1
+    new_rules <= L2_MAX_NEW_RULES
+        && new_examples <= L2_MAX_NEW_EXAMPLES
+        && new_skills <= L2_MAX_NEW_SKILLS
+}
+
+/// L2 cap on examples archived per cycle (`SKILLLITE_EVO_MAX_EXAMPLE_RETIREMENTS`, default 3).
+pub fn gatekeeper_l2_example_retirements() -> usize {
+    std::env::var(
+        skilllite_core::config::env_keys::evolution::SKILLLITE_EVO_MAX_EXAMPLE_RETIREMENTS,
+    )
+    .ok()
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(3)
+    .min(25)
 }
 
 const SENSITIVE_PATTERNS: &[&str] = &[
@@ -77,3 +107,50 @@ pub fn gatekeeper_l3_content(content: &str) -> Result<()> {
     }
     Ok(())
 }
+
+/// Upper bound on terms per feedback word list.
+const MAX_FEEDBACK_SIGNAL_TERMS: usize = 200;
+/// Longest accepted feedback term, in characters; longer entries are sentences, not signals.
+const MAX_FEEDBACK_SIGNAL_TERM_CHARS: usize = 24;
+
+/// Checks evolved `prompts/feedback_signals.json`: both lists non-empty and bounded, short
+/// terms, no term in both lists, and no sensitive content.
+pub fn gatekeeper_feedback_signals(lists: &FeedbackSignalLists) -> Result<()> {
+    for (name, terms) in [
+        ("correction", &lists.correction),
+        ("confirmation", &lists.confirmation),
+    ] {
+        if terms.is_empty() || terms.len() > MAX_FEEDBACK_SIGNAL_TERMS {
+            bail!(
+                "Gatekeeper L1c: feedback '{}' list must hold 1-{} terms (got {})",
+                name,
+                MAX_FEEDBACK_SIGNAL_TERMS,
+                terms.len()
+            );
+        }
+        if let Some(bad) = terms.iter().find(|t| {
+            let len = t.trim().chars().count();
+            len == 0 || len > MAX_FEEDBACK_SIGNAL_TERM_CHARS
+        }) {
+            bail!(
+                "Gatekeeper L1c: feedback '{}' term '{}' is empty or longer than {} chars",
+                name,
+                bad,
+                MAX_FEEDBACK_SIGNAL_TERM_CHARS
+            );
+        }
+        gatekeeper_l3_content(&terms.join("\n"))?;
+    }
+    if let Some(dup) = lists.correction.iter().find(|c| {
+        lists
+            .confirmation
+            .iter()
+            .any(|p| p.trim().eq_ignore_ascii_case(c.trim()))
+    }) {
+        bail!(
+            "Gatekeeper L1c: feedback term '{}' is both a correction and a confirmation",
+            dup
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,864 @@
+//! Write-ahead journal for one evolution txn (`_versions/{txn_id}/journal.json`).
+//!
+//! Learners do not write prompts, memory and skills files directly: every write goes through
+//! [`TxnWrites`], which appends the target (prior hash, new contents) to the txn's intent log
+//! (`_versions/{txn_id}/intents.jsonl`) and syncs it before the file is replaced with
+//! [`skilllite_fs::atomic_write`]. The journal records each phase of the txn:
+//!
+//! 1. [`JournalPhase::Prepared`]: snapshot taken; prior hashes of every tracked file.
+//! 2. [`JournalPhase::Applied`]: learners done; the journaled targets with their new contents,
+//!    plus the events, decisions and changelog entry the run is about to record.
+//! 3. [`JournalPhase::Logged`]: `evolution_log` rows and decision marks written.
+//! 4. [`JournalPhase::Recorded`]: changelog entry appended.
+//! 5. [`JournalPhase::Committed`]: txn complete.
+//!
+//! [`recover_uncommitted`] runs at startup and at the start of the next cycle: a txn that
+//! crashed before `Applied` is rolled back to its snapshot (files it created per the intent
+//! log are removed); later ones are rolled forward (targets re-applied where unchanged since,
+//! missing rows and changelog entry written). Each recovery logs a `recovered_txn` event.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::audit::{
+    decision_ids_for_txn, log_evolution_event, mark_decisions_evolved, record_decision_txn,
+};
+use crate::change_set::DroppedChange;
+use crate::changelog::append_changelog;
+use crate::feedback::{
+    open_evolution_db, EVOLUTION_LOG_TYPE_RUN_MATERIAL, EVOLUTION_LOG_TYPE_RUN_NOOP,
+};
+use crate::prompt_eval::PromptEvaluation;
+use crate::retention::{changelog_path, read_jsonl_lines};
+use crate::run::query_changes_by_txn;
+use crate::run_state::{open_run_lock, try_start_evolution, EvolutionRunning};
+use crate::snapshots::{restore_extended_snapshot, versions_dir, SNAPSHOT_PROMPT_FILES};
+use crate::Result;
+
+pub const JOURNAL_FILE: &str = "journal.json";
+pub const INTENTS_FILE: &str = "intents.jsonl";
+
+/// `evolution_log` type written when an interrupted txn is rolled back or forward.
+pub const EVOLUTION_LOG_TYPE_RECOVERED_TXN: &str = "recovered_txn";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalPhase {
+    Prepared,
+    Applied,
+    Logged,
+    Recorded,
+    Committed,
+}
+
+impl JournalPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Prepared => "prepared",
+            Self::Applied => "applied",
+            Self::Logged => "logged",
+            Self::Recorded => "recorded",
+            Self::Committed => "committed",
+        }
+    }
+}
+
+/// How [`recover_uncommitted`] settled an interrupted txn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    RolledBack,
+    RolledForward,
+}
+
+impl RecoveryAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RolledBack => "rolled_back",
+            Self::RolledForward => "rolled_forward",
+        }
+    }
+}
+
+/// One file the txn changed. `None` hashes mean the file did not exist (before / after).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalTarget {
+    pub path: PathBuf,
+    pub prior_hash: Option<String>,
+    pub new_hash: Option<String>,
+    /// New contents for roll-forward; absent for deletions and non-UTF-8 files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_content: Option<String>,
+}
+
+/// What the run records once its files are in place (replayed on roll-forward).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub changes: Vec<(String, String)>,
+    #[serde(default)]
+    pub dropped: Vec<DroppedChange>,
+    pub reason: String,
+    /// `files` of the changelog entry (prompts file names).
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub decision_ids: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evaluation: Option<PromptEvaluation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journal {
+    pub txn_id: String,
+    pub phase: JournalPhase,
+    pub started_at: String,
+    pub updated_at: String,
+    pub prompts: bool,
+    pub memory: bool,
+    pub skills: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skills_root: Option<PathBuf>,
+    /// Tracked files before the run: path → sha256.
+    #[serde(default)]
+    pub prior: BTreeMap<PathBuf, String>,
+    #[serde(default)]
+    pub targets: Vec<JournalTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record: Option<JournalRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<RecoveryAction>,
+}
+
+/// Called after each phase is persisted (except `Committed`). Tests install a hook that
+/// panics to simulate a crash between phases.
+type PhaseHook = fn(&Journal);
+
+static PHASE_HOOK: Mutex<Option<PhaseHook>> = Mutex::new(None);
+
+#[doc(hidden)]
+pub fn set_phase_hook(hook: Option<PhaseHook>) {
+    *PHASE_HOOK.lock().unwrap_or_else(|e| e.into_inner()) = hook;
+}
+
+fn run_phase_hook(journal: &Journal) {
+    let hook = *PHASE_HOOK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(hook) = hook {
+        hook(journal);
+    }
+}
+
+pub fn journal_path(chat_root: &Path, txn_id: &str) -> PathBuf {
+    versions_dir(chat_root).join(txn_id).join(JOURNAL_FILE)
+}
+
+impl Journal {
+    /// Start the journal right after the txn snapshot. `prompts/rules.json` is always tracked
+    /// (the external learner writes it whatever the scope) and copied into the snapshot when
+    /// the prompts snapshot did not already cover it.
+    pub fn prepare(
+        chat_root: &Path,
+        skills_root: Option<&Path>,
+        txn_id: &str,
+        prompts: bool,
+        memory: bool,
+        skills: bool,
+    ) -> Result<Self> {
+        let rules = chat_root.join("prompts").join("rules.json");
+        let snap_rules = versions_dir(chat_root).join(txn_id).join("rules.json");
+        if rules.is_file() && !snap_rules.exists() {
+            if let Some(parent) = snap_rules.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&rules, &snap_rules)?;
+        }
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut journal = Self {
+            txn_id: txn_id.to_string(),
+            phase: JournalPhase::Prepared,
+            started_at: now.clone(),
+            updated_at: now,
+            prompts,
+            memory,
+            skills,
+            skills_root: skills_root.map(Path::to_path_buf),
+            prior: BTreeMap::new(),
+            targets: Vec::new(),
+            record: None,
+            recovery: None,
+        };
+        journal.prior = journal
+            .tracked_files(chat_root)
+            .into_iter()
+            .filter_map(|p| file_hash(&p).map(|h| (p, h)))
+            .collect();
+        journal.advance(chat_root, JournalPhase::Prepared)?;
+        Ok(journal)
+    }
+
+    /// Fold the intent log into the txn's targets and persist them with the record plan.
+    /// Tracked files that changed without a journaled write are added as well (with a warning)
+    /// so recovery still covers them.
+    pub fn apply(&mut self, chat_root: &Path, record: JournalRecord) -> Result<()> {
+        let mut targets: BTreeMap<PathBuf, JournalTarget> = BTreeMap::new();
+        for intent in load_intents(chat_root, &self.txn_id) {
+            match targets.get_mut(&intent.path) {
+                Some(target) => {
+                    target.new_hash = intent.new_hash;
+                    target.new_content = intent.new_content;
+                }
+                None => {
+                    targets.insert(intent.path.clone(), intent);
+                }
+            }
+        }
+        let mut paths: Vec<PathBuf> = self.tracked_files(chat_root);
+        paths.extend(self.prior.keys().cloned());
+        paths.sort();
+        paths.dedup();
+        for path in paths {
+            if targets.contains_key(&path) {
+                continue;
+            }
+            let prior_hash = self.prior.get(&path).cloned();
+            let new_hash = file_hash(&path);
+            if prior_hash == new_hash {
+                continue;
+            }
+            tracing::debug!(
+                "Txn {}: {} changed outside the journal",
+                self.txn_id,
+                path.display()
+            );
+            let new_content = new_hash
+                .as_ref()
+                .and_then(|_| std::fs::read_to_string(&path).ok());
+            targets.insert(
+                path.clone(),
+                JournalTarget {
+                    path,
+                    prior_hash,
+                    new_hash,
+                    new_content,
+                },
+            );
+        }
+        self.targets = targets
+            .into_values()
+            .filter(|t| t.prior_hash != t.new_hash)
+            .collect();
+        self.record = Some(record);
+        self.advance(chat_root, JournalPhase::Applied)
+    }
+
+    pub fn advance(&mut self, chat_root: &Path, phase: JournalPhase) -> Result<()> {
+        self.phase = phase;
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+        self.persist(chat_root)?;
+        if phase != JournalPhase::Committed {
+            run_phase_hook(self);
+        }
+        Ok(())
+    }
+
+    pub fn load(chat_root: &Path, txn_id: &str) -> Option<Self> {
+        let text = std::fs::read_to_string(journal_path(chat_root, txn_id)).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    fn persist(&self, chat_root: &Path) -> Result<()> {
+        let path = journal_path(chat_root, &self.txn_id);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        skilllite_fs::atomic_write(&path, &serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Files the snapshot of this txn covers, as they exist now.
+    fn tracked_files(&self, chat_root: &Path) -> Vec<PathBuf> {
+        let prompts_dir = chat_root.join("prompts");
+        let mut files: Vec<PathBuf> = if self.prompts {
+            SNAPSHOT_PROMPT_FILES
+                .iter()
+                .map(|f| prompts_dir.join(f))
+                .collect()
+        } else {
+            vec![prompts_dir.join("rules.json")]
+        };
+        files.retain(|p| p.is_file());
+        if self.memory {
+            collect_files(&chat_root.join("memory").join("evolution"), &mut files);
+        }
+        if self.skills {
+            if let Some(sr) = &self.skills_root {
+                collect_files(&sr.join("_evolved"), &mut files);
+            }
+        }
+        files
+    }
+
+    fn roll_back(&self, chat_root: &Path) -> Result<()> {
+        if versions_dir(chat_root).join(&self.txn_id).is_dir() {
+            restore_extended_snapshot(chat_root, self.skills_root.as_deref(), &self.txn_id)?;
+        }
+        // Files the txn created are not in the snapshot; remove them.
+        for path in self.tracked_files(chat_root) {
+            if !self.prior.contains_key(&path) {
+                std::fs::remove_file(&path)?;
+            }
+        }
+        for intent in load_intents(chat_root, &self.txn_id) {
+            match &intent.prior_hash {
+                None if intent.path.is_file() => std::fs::remove_file(&intent.path)?,
+                Some(hash)
+                    if !self.prior.contains_key(&intent.path)
+                        && file_hash(&intent.path).as_ref() != Some(hash) =>
+                {
+                    tracing::warn!(
+                        "Rollback of txn {}: {} is outside the snapshot and stays changed",
+                        self.txn_id,
+                        intent.path.display()
+                    );
+                }
+                _ => {}
+            }
+        }
+        for (path, hash) in &self.prior {
+            if file_hash(path).as_ref() != Some(hash) {
+                tracing::warn!(
+                    "Rollback of txn {} could not restore {}",
+                    self.txn_id,
+                    path.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn roll_forward(&self, conn: &Connection, chat_root: &Path) -> Result<()> {
+        for target in &self.targets {
+            let current = file_hash(&target.path);
+            if current == target.new_hash {
+                continue;
+            }
+            if current != target.prior_hash {
+                tracing::warn!(
+                    "Txn {}: {} changed since the crash; leaving it as is",
+                    self.txn_id,
+                    target.path.display()
+                );
+                continue;
+            }
+            match (&target.new_hash, &target.new_content) {
+                (None, _) => std::fs::remove_file(&target.path)?,
+                (Some(_), Some(content)) => skilllite_fs::atomic_write(&target.path, content)?,
+                (Some(_), None) => tracing::warn!(
+                    "Txn {}: no journaled contents for {}",
+                    self.txn_id,
+                    target.path.display()
+                ),
+            }
+        }
+
+        let record = self.record.clone().unwrap_or_default();
+        if self.phase == JournalPhase::Applied {
+            self.replay_rows(conn, chat_root, &record)?;
+        }
+        if !record.changes.is_empty() && !changelog_has_txn(chat_root, &self.txn_id) {
+            append_changelog(
+                chat_root,
+                &self.txn_id,
+                &record.files,
+                &record.changes,
+                &record.reason,
+                record.evaluation.as_ref(),
+                &record.dropped,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the rows of the `Logged` phase that are not in `evolution_log` yet.
+    fn replay_rows(
+        &self,
+        conn: &Connection,
+        chat_root: &Path,
+        record: &JournalRecord,
+    ) -> Result<()> {
+        let txn = self.txn_id.as_str();
+        let logged: HashSet<(String, String)> =
+            query_changes_by_txn(conn, txn).into_iter().collect();
+        let log_once = |event_type: &str, target: &str, reason: &str| -> Result<()> {
+            if !logged.contains(&(event_type.to_string(), target.to_string())) {
+                log_evolution_event(conn, chat_root, event_type, target, reason, txn)?;
+            }
+            Ok(())
+        };
+        for (ctype, cid) in &record.changes {
+            log_once(ctype, cid, "prompt evolution")?;
+        }
+        for dropped in &record.dropped {
+            log_once("change_dropped", &dropped.id, &dropped.reason)?;
+        }
+        let deferred = record.dropped.iter().filter(|d| d.deferred()).count();
+        if deferred > 0 {
+            log_once(
+                "changes_deferred",
+                &deferred.to_string(),
+                "Gatekeeper L2: proposals over the per-run limit",
+            )?;
+        }
+        if record.changes.is_empty() {
+            log_once(EVOLUTION_LOG_TYPE_RUN_NOOP, "run", &record.reason)?;
+        } else {
+            log_once(EVOLUTION_LOG_TYPE_RUN_MATERIAL, "run", &record.reason)?;
+        }
+        mark_decisions_evolved(conn, &record.decision_ids)?;
+        if decision_ids_for_txn(conn, txn)?.is_empty() {
+            record_decision_txn(conn, txn, &record.decision_ids)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn intents_path(chat_root: &Path, txn_id: &str) -> PathBuf {
+    versions_dir(chat_root).join(txn_id).join(INTENTS_FILE)
+}
+
+/// Serializes intent-log appends of the learners running concurrently in one txn.
+static INTENT_LOG: Mutex<()> = Mutex::new(());
+
+/// Journaled writes of one txn: every change is synced to the txn's intent log before the
+/// file is touched, so a crash at any point leaves a journal that describes it.
+#[derive(Debug, Clone, Copy)]
+pub struct TxnWrites<'a> {
+    pub chat_root: &'a Path,
+    pub txn_id: &'a str,
+}
+
+impl<'a> TxnWrites<'a> {
+    pub fn new(chat_root: &'a Path, txn_id: &'a str) -> Self {
+        Self { chat_root, txn_id }
+    }
+
+    /// Replace `path` with `content` ([`skilllite_fs::atomic_write`]).
+    pub fn write(&self, path: &Path, content: &str) -> Result<()> {
+        log_intent(
+            self.chat_root,
+            self.txn_id,
+            JournalTarget {
+                path: path.to_path_buf(),
+                prior_hash: file_hash(path),
+                new_hash: Some(hex::encode(Sha256::digest(content.as_bytes()))),
+                new_content: Some(content.to_string()),
+            },
+        )?;
+        skilllite_fs::atomic_write(path, content)?;
+        Ok(())
+    }
+
+    /// Remove `path`; a missing file is not an error.
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        let Some(prior_hash) = file_hash(path) else {
+            return Ok(());
+        };
+        log_intent(
+            self.chat_root,
+            self.txn_id,
+            JournalTarget {
+                path: path.to_path_buf(),
+                prior_hash: Some(prior_hash),
+                new_hash: None,
+                new_content: None,
+            },
+        )?;
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// Remove `dir`, journaling each file in it as a deletion first.
+    pub fn remove_dir(&self, dir: &Path) -> Result<()> {
+        let mut files = Vec::new();
+        collect_files(dir, &mut files);
+        for file in files {
+            self.remove(&file)?;
+        }
+        if dir.exists() {
+            std::fs::remove_dir_all(dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write through `txn` inside an evolution txn, with a plain atomic write outside one.
+pub(crate) fn write_file(txn: Option<TxnWrites>, path: &Path, content: &str) -> Result<()> {
+    match txn {
+        Some(txn) => txn.write(path, content),
+        None => Ok(skilllite_fs::atomic_write(path, content)?),
+    }
+}
+
+fn log_intent(chat_root: &Path, txn_id: &str, target: JournalTarget) -> Result<()> {
+    use std::io::Write;
+
+    let path = intents_path(chat_root, txn_id);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(&target)?;
+    line.push('\n');
+    let _guard = INTENT_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    file.write_all(line.as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Targets journaled for `txn_id`, in write order. A torn last line (crash mid-append) is
+/// skipped: its write never started.
+pub fn load_intents(chat_root: &Path, txn_id: &str) -> Vec<JournalTarget> {
+    std::fs::read_to_string(intents_path(chat_root, txn_id))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+/// Journals under `_versions/` that never reached `Committed`, oldest first.
+pub fn list_uncommitted(chat_root: &Path) -> Vec<Journal> {
+    let mut journals: Vec<Journal> = std::fs::read_dir(versions_dir(chat_root))
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| Journal::load(chat_root, e.file_name().to_str()?))
+        .filter(|j| j.phase != JournalPhase::Committed)
+        .collect();
+    journals.sort_by(|a, b| a.txn_id.cmp(&b.txn_id));
+    journals
+}
+
+/// Settle every interrupted txn: roll back those that crashed before `Applied`, roll the
+/// rest forward. Returns `(txn_id, action)` per recovered txn. Callers hold `evolution.lock`.
+pub fn recover_uncommitted(
+    conn: &Connection,
+    chat_root: &Path,
+) -> Result<Vec<(String, RecoveryAction)>> {
+    let mut recovered = Vec::new();
+    for mut journal in list_uncommitted(chat_root) {
+        let from = journal.phase;
+        let action = if from == JournalPhase::Prepared {
+            journal.roll_back(chat_root)?;
+            RecoveryAction::RolledBack
+        } else {
+            journal.roll_forward(conn, chat_root)?;
+            RecoveryAction::RolledForward
+        };
+        journal.recovery = Some(action);
+        journal.advance(chat_root, JournalPhase::Committed)?;
+        log_evolution_event(
+            conn,
+            chat_root,
+            EVOLUTION_LOG_TYPE_RECOVERED_TXN,
+            &journal.txn_id,
+            &format!(
+                "{}: interrupted after phase '{}', {} target file(s)",
+                action.as_str(),
+                from.as_str(),
+                journal.targets.len()
+            ),
+            &journal.txn_id,
+        )?;
+        tracing::warn!(
+            "Recovered interrupted evolution txn {} ({})",
+            journal.txn_id,
+            action.as_str()
+        );
+        recovered.push((journal.txn_id, action));
+    }
+    Ok(recovered)
+}
+
+/// [`recover_uncommitted`] at process startup. Skipped while a run holds `evolution.lock`
+/// (in this process or another): its own txn is still in flight.
+pub fn recover_at_startup(chat_root: &Path) -> Result<Vec<(String, RecoveryAction)>> {
+    if list_uncommitted(chat_root).is_empty() || !try_start_evolution() {
+        return Ok(Vec::new());
+    }
+    let _running = EvolutionRunning;
+    let mut lock = open_run_lock(chat_root)?;
+    let Ok(_held) = lock.try_write() else {
+        return Ok(Vec::new());
+    };
+    let conn = open_evolution_db(chat_root)?;
+    recover_uncommitted(&conn, chat_root)
+}
+
+/// One `recovered_txn` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryEvent {
+    pub ts: String,
+    pub txn_id: String,
+    pub reason: String,
+}
+
+/// Latest `recovered_txn` events, newest first.
+pub fn recent_recoveries(conn: &Connection, limit: usize) -> Result<Vec<RecoveryEvent>> {
+    let mut stmt = conn.prepare(
+        "SELECT ts, target_id, reason FROM evolution_log
+         WHERE type = ?1 ORDER BY ts DESC LIMIT ?2",
+    )?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![EVOLUTION_LOG_TYPE_RECOVERED_TXN, limit as i64],
+            |row| {
+                Ok(RecoveryEvent {
+                    ts: row.get(0)?,
+                    txn_id: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                    reason: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                })
+            },
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+fn changelog_has_txn(chat_root: &Path, txn_id: &str) -> bool {
+    read_jsonl_lines(&changelog_path(chat_root))
+        .iter()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .any(|v| v["txn_id"] == txn_id)
+}
+
+fn file_hash(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    Some(hex::encode(Sha256::digest(&bytes)))
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => collect_files(&path, out),
+            Ok(t) if t.is_file() => out.push(path),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    fn seeded_root() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let chat_root = dir.path().join("chat");
+        write(&chat_root.join("prompts").join("rules.json"), "[\"old\"]");
+        write(&chat_root.join("prompts").join("examples.json"), "[]");
+        (dir, chat_root)
+    }
+
+    fn prepared(chat_root: &Path, txn_id: &str) -> Journal {
+        crate::snapshots::create_extended_snapshot(chat_root, None, txn_id, true, true, false)
+            .unwrap();
+        Journal::prepare(chat_root, None, txn_id, true, true, false).unwrap()
+    }
+
+    #[test]
+    fn crash_between_rule_and_example_writes_rolls_back() {
+        let (_dir, chat_root) = seeded_root();
+        let prompts = chat_root.join("prompts");
+        prepared(&chat_root, "evo_1");
+        // rules.json written, examples.json not yet; a new memory file appeared.
+        write(&prompts.join("rules.json"), "[\"old\",\"new\"]");
+        let knowledge = chat_root.join("memory").join("evolution").join("k.md");
+        write(&knowledge, "fact");
+
+        let conn = open_evolution_db(&chat_root).unwrap();
+        let recovered = recover_uncommitted(&conn, &chat_root).unwrap();
+
+        assert_eq!(
+            recovered,
+            vec![("evo_1".to_string(), RecoveryAction::RolledBack)]
+        );
+        assert_eq!(read(&prompts.join("rules.json")), "[\"old\"]");
+        assert_eq!(read(&prompts.join("examples.json")), "[]");
+        assert!(!knowledge.exists());
+        assert!(!prompts.join(JOURNAL_FILE).exists());
+        assert_eq!(
+            Journal::load(&chat_root, "evo_1").unwrap().recovery,
+            Some(RecoveryAction::RolledBack)
+        );
+        assert!(list_uncommitted(&chat_root).is_empty());
+        assert!(!changelog_has_txn(&chat_root, "evo_1"));
+    }
+
+    #[test]
+    fn applied_journal_rolls_forward_files_rows_and_changelog() {
+        let (_dir, chat_root) = seeded_root();
+        let prompts = chat_root.join("prompts");
+        let mut journal = prepared(&chat_root, "evo_2");
+        let txn = TxnWrites::new(&chat_root, "evo_2");
+        txn.write(&prompts.join("rules.json"), "[\"old\",\"new\"]")
+            .unwrap();
+        txn.write(&prompts.join("examples.json"), "[\"ex\"]")
+            .unwrap();
+        journal
+            .apply(
+                &chat_root,
+                JournalRecord {
+                    changes: vec![
+                        ("rule_added".into(), "new".into()),
+                        ("example_added".into(), "ex".into()),
+                    ],
+                    reason: "2 prompt changes".into(),
+                    files: vec!["rules.json".into(), "examples.json".into()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(journal.targets.len(), 2);
+        // Simulate a crash that lost the examples.json write (file back at its prior state).
+        write(&prompts.join("examples.json"), "[]");
+
+        let conn = open_evolution_db(&chat_root).unwrap();
+        let recovered = recover_uncommitted(&conn, &chat_root).unwrap();
+
+        assert_eq!(recovered[0].1, RecoveryAction::RolledForward);
+        assert_eq!(read(&prompts.join("examples.json")), "[\"ex\"]");
+        assert_eq!(read(&prompts.join("rules.json")), "[\"old\",\"new\"]");
+        assert!(changelog_has_txn(&chat_root, "evo_2"));
+        let rows = query_changes_by_txn(&conn, "evo_2");
+        assert!(rows.contains(&("rule_added".to_string(), "new".to_string())));
+        assert!(rows.contains(&(
+            EVOLUTION_LOG_TYPE_RECOVERED_TXN.to_string(),
+            "evo_2".to_string()
+        )));
+        let recoveries = recent_recoveries(&conn, 5).unwrap();
+        assert_eq!(recoveries[0].txn_id, "evo_2");
+        assert!(recoveries[0].reason.starts_with("rolled_forward"));
+
+        // A second pass finds nothing to do and writes nothing twice.
+        assert!(recover_uncommitted(&conn, &chat_root).unwrap().is_empty());
+        assert_eq!(
+            read_jsonl_lines(&changelog_path(&chat_root))
+                .iter()
+                .filter(|l| l.contains("evo_2"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn roll_forward_keeps_files_edited_after_the_crash() {
+        let (_dir, chat_root) = seeded_root();
+        let prompts = chat_root.join("prompts");
+        let mut journal = prepared(&chat_root, "evo_3");
+        write(&prompts.join("rules.json"), "[\"evolved\"]");
+        journal.apply(&chat_root, JournalRecord::default()).unwrap();
+        write(&prompts.join("rules.json"), "[\"hand edited\"]");
+
+        let conn = open_evolution_db(&chat_root).unwrap();
+        recover_uncommitted(&conn, &chat_root).unwrap();
+
+        assert_eq!(read(&prompts.join("rules.json")), "[\"hand edited\"]");
+    }
+
+    #[test]
+    fn writes_are_journaled_before_they_land() {
+        let (_dir, chat_root) = seeded_root();
+        let prompts = chat_root.join("prompts");
+        let mut journal = prepared(&chat_root, "evo_4");
+        let txn = TxnWrites::new(&chat_root, "evo_4");
+        // sources.json is outside the snapshot; only the intent log knows about it.
+        txn.write(&prompts.join("sources.json"), "{\"v\": 1}")
+            .unwrap();
+        txn.write(&prompts.join("rules.json"), "[\"a\"]").unwrap();
+        txn.write(&prompts.join("rules.json"), "[\"a\",\"b\"]")
+            .unwrap();
+
+        let intents = load_intents(&chat_root, "evo_4");
+        assert_eq!(intents.len(), 3);
+        assert_eq!(intents[0].prior_hash, None);
+        assert_eq!(intents[2].new_content.as_deref(), Some("[\"a\",\"b\"]"));
+
+        journal.apply(&chat_root, JournalRecord::default()).unwrap();
+        let rules = journal
+            .targets
+            .iter()
+            .find(|t| t.path.ends_with("rules.json"))
+            .unwrap();
+        assert_eq!(rules.prior_hash, file_hash_of("[\"old\"]"));
+        assert_eq!(rules.new_content.as_deref(), Some("[\"a\",\"b\"]"));
+        assert!(journal
+            .targets
+            .iter()
+            .any(|t| t.path.ends_with("sources.json")));
+
+        // Both writes lost in the crash: roll-forward replays them from the journal.
+        write(&prompts.join("rules.json"), "[\"old\"]");
+        std::fs::remove_file(prompts.join("sources.json")).unwrap();
+        let conn = open_evolution_db(&chat_root).unwrap();
+        recover_uncommitted(&conn, &chat_root).unwrap();
+
+        assert_eq!(read(&prompts.join("rules.json")), "[\"a\",\"b\"]");
+        assert_eq!(read(&prompts.join("sources.json")), "{\"v\": 1}");
+    }
+
+    #[test]
+    fn rollback_removes_files_created_outside_the_snapshot() {
+        let (_dir, chat_root) = seeded_root();
+        let prompts = chat_root.join("prompts");
+        prepared(&chat_root, "evo_5");
+        let txn = TxnWrites::new(&chat_root, "evo_5");
+        txn.write(&prompts.join("sources.json"), "{}").unwrap();
+        txn.write(&prompts.join("rules.json"), "[\"new\"]").unwrap();
+
+        let conn = open_evolution_db(&chat_root).unwrap();
+        let recovered = recover_uncommitted(&conn, &chat_root).unwrap();
+
+        assert_eq!(recovered[0].1, RecoveryAction::RolledBack);
+        assert!(!prompts.join("sources.json").exists());
+        assert_eq!(read(&prompts.join("rules.json")), "[\"old\"]");
+    }
+
+    #[test]
+    fn startup_recovery_waits_for_the_run_lock() {
+        let (_dir, chat_root) = seeded_root();
+        prepared(&chat_root, "evo_6");
+        let mut lock = open_run_lock(&chat_root).unwrap();
+        let held = lock.try_write().unwrap();
+        assert!(recover_at_startup(&chat_root).unwrap().is_empty());
+        assert_eq!(list_uncommitted(&chat_root).len(), 1);
+
+        drop(held);
+        assert_eq!(
+            recover_at_startup(&chat_root).unwrap(),
+            vec![("evo_6".to_string(), RecoveryAction::RolledBack)]
+        );
+    }
+
+    fn file_hash_of(content: &str) -> Option<String> {
+        Some(hex::encode(Sha256::digest(content.as_bytes())))
+    }
+}
@@ -8,34 +8,56 @@
 //! Interacts with the agent through the [`EvolutionLlm`] trait for LLM completion.
 
 pub mod audit;
+pub mod change_set;
 pub mod changelog;
 pub mod config;
+pub mod decisions_export;
 pub mod error;
 mod evolution_memory_rollup;
+pub mod explain;
 pub mod external_learner;
 pub mod feedback;
+pub mod feedback_signals;
 pub mod gatekeeper;
 pub mod growth_schedule;
+pub mod journal;
 pub mod lifecycle;
 pub mod llm;
 pub mod memory_learner;
+pub mod metrics_breakdown;
+pub mod prompt_eval;
 pub mod prompt_learner;
+pub mod replay_llm;
+pub mod retention;
 pub mod rollback;
+pub mod rule_conflict;
 pub mod run;
 pub mod run_state;
+pub mod schema;
 pub mod scope;
 pub mod seed;
 pub mod shallow_preflight;
+pub mod skill_removal;
 pub mod skill_synth;
 pub mod snapshots;
 
 pub use error::{Error, Result};
 
-pub use audit::{decision_ids_to_mark_after_run, log_evolution_event, mark_decisions_evolved};
+pub use audit::{
+    decision_ids_to_mark_after_run, expire_stale_decisions, log_evolution_event,
+    mark_decisions_evolved, verify_evolution_log,
+};
+pub use change_set::{ChangeKind, ChangeSet, DroppedChange};
 pub use changelog::append_changelog;
-pub use config::{EvolutionMode, EvolutionProfile, EvolutionThresholds, SkillAction};
+pub use config::{
+    EvolutionMode, EvolutionProfile, EvolutionThresholds, ExternalLearningBudget, LlmRetryPolicy,
+    PromptEvaluationMode, SkillAction,
+};
+pub use explain::{explain_target, explain_target_in, Explanation};
+pub use feedback_signals::{classify_user_message, load_feedback_signals, FeedbackSignalLists};
 pub use gatekeeper::{
-    gatekeeper_l1_path, gatekeeper_l1_template_integrity, gatekeeper_l2_size, gatekeeper_l3_content,
+    gatekeeper_feedback_signals, gatekeeper_l1_path, gatekeeper_l1_template_integrity,
+    gatekeeper_l2_example_retirements, gatekeeper_l2_size, gatekeeper_l3_content,
 };
 pub use growth_schedule::{
     growth_due, inspect_growth_due, seconds_since_last_evolution_run, signal_burst_due,
@@ -43,9 +65,10 @@ pub use growth_schedule::{
 };
 pub use lifecycle::on_shutdown;
 pub use llm::{
-    sanitize_visible_llm_text, strip_think_blocks, EvolutionLlm, EvolutionLlmOutput,
-    EvolutionMessage,
+    complete_json, complete_parsed, complete_with_retry, sanitize_visible_llm_text,
+    strip_think_blocks, EvolutionLlm, EvolutionLlmOutput, EvolutionMessage,
 };
+pub use replay_llm::{RecordingLlm, ReplayLlm};
 pub use rollback::check_auto_rollback;
 pub use run::{format_evolution_changes, query_changes_by_txn, run_evolution};
 pub use run_state::{finish_evolution, try_start_evolution, EvolutionRunResult};
@@ -107,6 +130,15 @@ mod lib_tests {
         assert_eq!(a.reasoning_content.as_deref(), Some("think"));
     }
 
+    #[test]
+    fn template_integrity_accepts_conditionals_and_rejects_unbalanced_blocks() {
+        let ok = "{{TODAY}}{{#if RULES_SECTION}}{{RULES_SECTION}}{{/if}}{{EXAMPLES_SECTION}}{{OUTPUT_DIR}}";
+        assert!(gatekeeper_l1_template_integrity("planning.md", ok).is_ok());
+        let unclosed = ok.replace("{{/if}}", "");
+        let err = gatekeeper_l1_template_integrity("planning.md", &unclosed).unwrap_err();
+        assert!(err.to_string().contains("malformed"));
+    }
+
     #[test]
     fn skill_action_maps_to_evolve_skills_generate_flag() {
         assert!(SkillAction::None.should_run_skill_generation_paths());
@@ -156,6 +188,9 @@ mod lib_tests {
         assert!(gatekeeper_l1_path(root, &target, None));
         let bad = Path::new("/etc/passwd");
         assert!(!gatekeeper_l1_path(root, bad, None));
+        let traversal = root.join("prompts/../../../../etc/passwd");
+        assert!(!gatekeeper_l1_path(root, &traversal, None));
+        assert!(!gatekeeper_l1_path(root, &root.join("prompts_old/x"), None));
     }
 
     #[test]
@@ -174,6 +209,20 @@ mod lib_tests {
         assert!(t.recent_days > 0);
     }
 
+    #[test]
+    fn decision_window_uses_shorter_of_recent_and_max_age() {
+        let mut t = EvolutionThresholds {
+            recent_days: 30,
+            decision_max_age_days: 14,
+            ..Default::default()
+        };
+        let cond = t.decision_window_condition();
+        assert!(cond.contains("'-14 days'"));
+        assert!(cond.contains("evolved <> 2"));
+        t.decision_max_age_days = 0;
+        assert!(t.decision_window_condition().contains("'-30 days'"));
+    }
+
     #[test]
     fn roi_score_penalizes_risk() {
         let low = compute_roi_score(1.0, 1.0, ProposalRiskLevel::Low);
@@ -13,7 +13,11 @@ pub fn on_shutdown(chat_root: &Path) {
     }
     if let Ok(conn) = feedback::open_evolution_db(chat_root) {
         let _ = feedback::update_daily_metrics(&conn);
-        // let _ = feedback::export_decisions_md(&conn, &chat_root.join("DECISIONS.md")); // Removed for refactor
+        let _ = crate::decisions_export::export_decisions(
+            &conn,
+            chat_root,
+            crate::decisions_export::workspace_export_enabled(),
+        );
     }
     finish_evolution();
 }
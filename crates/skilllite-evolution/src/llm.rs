@@ -1,5 +1,10 @@
-//! LLM message shape, completion trait, and think-block stripping.
+//! LLM message shape, completion trait, retry/JSON helpers, and think-block stripping.
 
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use crate::config::LlmRetryPolicy;
 use crate::Result;
 
 /// Result of a single non-streaming evolution LLM call.
@@ -75,6 +80,181 @@ pub trait EvolutionLlm: Send + Sync {
     ) -> Result<EvolutionLlmOutput>;
 }
 
+// ─── Retry / JSON helpers ────────────────────────────────────────────────────
+
+/// Sent once when a response cannot be parsed, before giving up.
+const JSON_CORRECTIVE_MESSAGE: &str =
+    "Your previous output was not valid JSON. Respond with only JSON, no prose or markdown.";
+
+/// Auth/permission failures will not heal on retry.
+fn is_retryable_llm_error(err: &crate::Error) -> bool {
+    let msg = err.to_string().to_ascii_lowercase();
+    ![
+        "401",
+        "403",
+        "unauthorized",
+        "forbidden",
+        "invalid api key",
+        "invalid_api_key",
+    ]
+    .iter()
+    .any(|m| msg.contains(m))
+}
+
+/// Exponential backoff for retry `attempt` (1-based), capped at `max_delay`, plus up to 50%
+/// jitter derived from `jitter_seed`.
+fn backoff_delay(policy: &LlmRetryPolicy, attempt: u32, jitter_seed: u64) -> Duration {
+    let exp = policy
+        .base_delay
+        .saturating_mul(1u32 << attempt.saturating_sub(1).min(16));
+    let delay = exp.min(policy.max_delay);
+    let jitter_range = delay.as_millis() as u64 / 2;
+    let jitter = if jitter_range == 0 {
+        0
+    } else {
+        jitter_seed % (jitter_range + 1)
+    };
+    delay + Duration::from_millis(jitter)
+}
+
+/// [`EvolutionLlm::complete`] with a per-attempt timeout and exponential backoff with jitter on
+/// transient failures (429s, network errors, timeouts). Auth errors fail immediately.
+pub async fn complete_with_retry<L: EvolutionLlm + ?Sized>(
+    llm: &L,
+    messages: &[EvolutionMessage],
+    model: &str,
+    temperature: f64,
+    policy: &LlmRetryPolicy,
+) -> Result<EvolutionLlmOutput> {
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        let err =
+            match tokio::time::timeout(policy.timeout, llm.complete(messages, model, temperature))
+                .await
+            {
+                Ok(Ok(out)) => return Ok(out),
+                Ok(Err(e)) => e,
+                Err(_) => crate::Error::Other(anyhow::anyhow!(
+                    "evolution LLM call timed out after {}s",
+                    policy.timeout.as_secs_f64()
+                )),
+            };
+        if attempt >= max_attempts || !is_retryable_llm_error(&err) {
+            return Err(err);
+        }
+        let delay = backoff_delay(policy, attempt, uuid::Uuid::new_v4().as_u128() as u64);
+        tracing::warn!(
+            "Evolution LLM call failed (attempt {}/{}), retrying in {}ms: {}",
+            attempt,
+            max_attempts,
+            delay.as_millis(),
+            err
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// [`complete_with_retry`], then `parse` the visible text. On a parse failure the model gets one
+/// corrective turn asking for JSON only; a second failure returns [`crate::Error::Validation`]
+/// with the parse error and a prefix of the raw output.
+pub async fn complete_parsed<T, L, F>(
+    llm: &L,
+    messages: &[EvolutionMessage],
+    model: &str,
+    temperature: f64,
+    policy: &LlmRetryPolicy,
+    parse: F,
+) -> Result<T>
+where
+    L: EvolutionLlm + ?Sized,
+    F: Fn(&str) -> Result<T>,
+{
+    let first = complete_with_retry(llm, messages, model, temperature, policy).await?;
+    let first_err = match parse(first.visible.trim()) {
+        Ok(v) => return Ok(v),
+        Err(e) => e,
+    };
+    tracing::info!(
+        "Evolution LLM output failed to parse, retrying with corrective message: {}",
+        first_err
+    );
+    let mut history = messages.to_vec();
+    first.push_assistant_replay(&mut history);
+    history.push(EvolutionMessage::user(JSON_CORRECTIVE_MESSAGE));
+    let second = complete_with_retry(llm, &history, model, temperature, policy).await?;
+    let raw = second.visible.trim();
+    parse(raw).map_err(|e| {
+        crate::Error::validation(format!(
+            "{} — raw: {}",
+            e,
+            raw.chars().take(200).collect::<String>()
+        ))
+    })
+}
+
+/// [`complete_parsed`] deserializing the first JSON value found in the response (fenced block,
+/// bare object, or bare array).
+pub async fn complete_json<T, L>(
+    llm: &L,
+    messages: &[EvolutionMessage],
+    model: &str,
+    temperature: f64,
+    policy: &LlmRetryPolicy,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    L: EvolutionLlm + ?Sized,
+{
+    complete_parsed(
+        llm,
+        messages,
+        model,
+        temperature,
+        policy,
+        parse_json_response,
+    )
+    .await
+}
+
+/// Deserialize `T` from an LLM response that may wrap JSON in think blocks, fences, or prose.
+pub fn parse_json_response<T: DeserializeOwned>(content: &str) -> Result<T> {
+    let content = strip_think_blocks(content.trim()).trim();
+    let fenced = content
+        .split("```")
+        .nth(1)
+        .map(|block| block.strip_prefix("json").unwrap_or(block).trim());
+    let object = content
+        .find('{')
+        .zip(content.rfind('}'))
+        .filter(|(a, b)| a < b)
+        .map(|(a, b)| &content[a..=b]);
+    let array = content
+        .find('[')
+        .zip(content.rfind(']'))
+        .filter(|(a, b)| a < b)
+        .map(|(a, b)| &content[a..=b]);
+
+    let mut first_err = None;
+    for candidate in std::iter::once(content)
+        .chain(fenced)
+        .chain(object)
+        .chain(array)
+    {
+        match serde_json::from_str::<T>(candidate) {
+            Ok(v) => return Ok(v),
+            Err(e) => {
+                first_err.get_or_insert(e);
+            }
+        }
+    }
+    Err(crate::Error::validation(format!(
+        "LLM output is not valid JSON: {}",
+        first_err.map(|e| e.to_string()).unwrap_or_default()
+    )))
+}
+
 // ─── LLM response post-processing ────────────────────────────────────────────
 
 fn strip_paired_xml_block(mut s: String, open: &str, close: &str) -> String {
@@ -242,3 +422,168 @@ mod sanitize_visible_tests {
         assert_eq!(sanitize_visible_llm_text(raw), "最终：完成。");
     }
 }
+
+#[cfg(test)]
+mod retry_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Fails `failures` times with `error`, then replays `responses` in order (last one repeats).
+    struct MockLlm {
+        failures: usize,
+        error: &'static str,
+        delay: Duration,
+        responses: Vec<&'static str>,
+        calls: AtomicUsize,
+        last_messages: Mutex<Vec<EvolutionMessage>>,
+    }
+
+    impl MockLlm {
+        fn new(failures: usize, responses: Vec<&'static str>) -> Self {
+            Self {
+                failures,
+                error: "429 Too Many Requests",
+                delay: Duration::ZERO,
+                responses,
+                calls: AtomicUsize::new(0),
+                last_messages: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EvolutionLlm for MockLlm {
+        async fn complete(
+            &self,
+            messages: &[EvolutionMessage],
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<EvolutionLlmOutput> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            *self.last_messages.lock().unwrap() = messages.to_vec();
+            if !self.delay.is_zero() {
+                tokio::time::sleep(self.delay).await;
+            }
+            if n < self.failures {
+                return Err(crate::Error::validation(self.error));
+            }
+            let idx = (n - self.failures).min(self.responses.len() - 1);
+            Ok(EvolutionLlmOutput {
+                visible: self.responses[idx].to_string(),
+                assistant_content: Some(self.responses[idx].to_string()),
+                assistant_reasoning: None,
+            })
+        }
+    }
+
+    fn fast_policy(max_attempts: u32) -> LlmRetryPolicy {
+        LlmRetryPolicy {
+            max_attempts,
+            timeout: Duration::from_secs(5),
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures_then_succeeds() {
+        let llm = MockLlm::new(2, vec!["ok"]);
+        let msgs = [EvolutionMessage::user("hi")];
+        let out = complete_with_retry(&llm, &msgs, "m", 0.0, &fast_policy(3))
+            .await
+            .expect("third attempt succeeds");
+        assert_eq!(out.visible, "ok");
+        assert_eq!(llm.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts_and_skips_auth_errors() {
+        let llm = MockLlm::new(5, vec!["ok"]);
+        let msgs = [EvolutionMessage::user("hi")];
+        assert!(complete_with_retry(&llm, &msgs, "m", 0.0, &fast_policy(3))
+            .await
+            .is_err());
+        assert_eq!(llm.calls(), 3);
+
+        let auth = MockLlm {
+            error: "401 Unauthorized: invalid api key",
+            ..MockLlm::new(5, vec!["ok"])
+        };
+        assert!(complete_with_retry(&auth, &msgs, "m", 0.0, &fast_policy(3))
+            .await
+            .is_err());
+        assert_eq!(auth.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn per_attempt_timeout_counts_as_retryable_failure() {
+        let slow = MockLlm {
+            delay: Duration::from_millis(200),
+            ..MockLlm::new(0, vec!["late"])
+        };
+        let policy = LlmRetryPolicy {
+            timeout: Duration::from_millis(10),
+            ..fast_policy(2)
+        };
+        let err = complete_with_retry(&slow, &[EvolutionMessage::user("hi")], "m", 0.0, &policy)
+            .await
+            .expect_err("times out");
+        assert!(err.to_string().contains("timed out"));
+        assert_eq!(slow.calls(), 2);
+    }
+
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    struct Answer {
+        value: i32,
+    }
+
+    #[tokio::test]
+    async fn complete_json_sends_corrective_message_after_malformed_output() {
+        let llm = MockLlm::new(
+            0,
+            vec!["Sure! The value is 7.", "```json\n{\"value\": 7}\n```"],
+        );
+        let msgs = [EvolutionMessage::user("give json")];
+        let answer: Answer = complete_json(&llm, &msgs, "m", 0.0, &fast_policy(1))
+            .await
+            .expect("second response parses");
+        assert_eq!(answer, Answer { value: 7 });
+        assert_eq!(llm.calls(), 2);
+        let last = llm.last_messages.lock().unwrap().clone();
+        assert_eq!(last.len(), 3);
+        assert_eq!(last[1].role, "assistant");
+        assert_eq!(last[2].content.as_deref(), Some(JSON_CORRECTIVE_MESSAGE));
+
+        let stubborn = MockLlm::new(0, vec!["still not json"]);
+        let err = complete_json::<Answer, _>(&stubborn, &msgs, "m", 0.0, &fast_policy(1))
+            .await
+            .expect_err("gives up after one corrective turn");
+        assert!(matches!(err, crate::Error::Validation(_)));
+        assert_eq!(stubborn.calls(), 2);
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_with_bounded_jitter() {
+        let policy = LlmRetryPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+            ..LlmRetryPolicy::default()
+        };
+        assert_eq!(backoff_delay(&policy, 1, 0), Duration::from_millis(100));
+        assert_eq!(backoff_delay(&policy, 3, 0), Duration::from_millis(400));
+        assert_eq!(backoff_delay(&policy, 10, 0), Duration::from_millis(1000));
+        assert!(backoff_delay(&policy, 1, u64::MAX) <= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn parse_json_response_accepts_arrays_and_prose() {
+        let v: Vec<i32> = parse_json_response("Here:\n[1, 2, 3]\nthanks").unwrap();
+        assert_eq!(v, vec![1, 2, 3]);
+        assert!(parse_json_response::<Answer>("nope").is_err());
+    }
+}
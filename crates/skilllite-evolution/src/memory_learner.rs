@@ -18,8 +18,10 @@ use crate::evolution_memory_rollup::rebuild_rollups_for_month;
 use crate::feedback::open_evolution_db;
 use crate::gatekeeper_l1_path;
 use crate::gatekeeper_l3_content;
+use crate::journal::TxnWrites;
 use crate::EvolutionLlm;
 use crate::EvolutionMessage;
+use crate::{complete_parsed, LlmRetryPolicy};
 
 const MEMORY_KNOWLEDGE_PROMPT: &str =
     include_str!("seed/evolution_prompts/memory_knowledge_extraction.seed.md");
@@ -173,12 +175,13 @@ fn category_title_zh(dir_name: &str) -> String {
 
 /// 将一轮抽取的非空正文追加到 `memory/evolution/<category>/<YYYY-MM>.md`。
 fn append_evolution_shard(
-    chat_root: &Path,
+    txn: TxnWrites,
     category_dir: &str,
     month: &str,
     run_heading: &str,
     body: &str,
 ) -> Result<()> {
+    let chat_root = txn.chat_root;
     let evolution = chat_root.join("memory").join("evolution");
     let sub = evolution.join(category_dir);
     skilllite_fs::create_dir_all(&sub)?;
@@ -208,12 +211,13 @@ fn append_evolution_shard(
             block_to_append.trim_end()
         )
     };
-    skilllite_fs::write_file(&shard, &final_content)?;
+    txn.write(&shard, &final_content)?;
     Ok(())
 }
 
 /// 各维 `entities.md` 等：列出该维下所有 `YYYY-MM.md` 分卷。
-fn regenerate_dimension_indexes(chat_root: &Path) -> Result<()> {
+fn regenerate_dimension_indexes(txn: TxnWrites) -> Result<()> {
+    let chat_root = txn.chat_root;
     let evolution = chat_root.join("memory").join("evolution");
     for (dir_name, title_zh) in EVOLUTION_CATEGORIES {
         let shard_dir = evolution.join(dir_name);
@@ -262,13 +266,14 @@ fn regenerate_dimension_indexes(chat_root: &Path) -> Result<()> {
             "# {title_zh}（索引）\n\n按月分卷；正文在 [`{dir_name}/`]({dir_name}/)。**去重汇总卷** `YYYY-MM.rollup.md` 在每次 Memory 进化写入分卷后自动重算。\n\n| 月份 | 分卷 | 去重汇总 |\n|------|------|----------|\n{table}\n",
         );
         gatekeeper_l3_content(&content)?;
-        skilllite_fs::write_file(&index_path, &content)?;
+        txn.write(&index_path, &content)?;
     }
     Ok(())
 }
 
 /// `memory/evolution/INDEX.md`：五维总览与跳转。
-fn regenerate_evolution_root_index(chat_root: &Path, last_run: &str) -> Result<()> {
+fn regenerate_evolution_root_index(txn: TxnWrites, last_run: &str) -> Result<()> {
+    let chat_root = txn.chat_root;
     let evolution = chat_root.join("memory").join("evolution");
     let index_path = evolution.join("INDEX.md");
     if !gatekeeper_l1_path(chat_root, &index_path, None) {
@@ -288,8 +293,7 @@ fn regenerate_evolution_root_index(chat_root: &Path, last_run: &str) -> Result<(
          历史单文件 [knowledge.md](knowledge.md) 若仍存在，会参与去重摘要；新增长期写入分卷。\n"
     );
     gatekeeper_l3_content(&content)?;
-    skilllite_fs::write_file(&index_path, &content)?;
-    Ok(())
+    txn.write(&index_path, &content)
 }
 
 /// 运行 memory 进化：从近期 decisions 抽取实体、关系、情节、倾向、模式，按五维 + 按月分卷写入 `memory/evolution/`，并刷新各维索引与 `INDEX.md`。
@@ -298,7 +302,7 @@ pub async fn evolve_memory<L: EvolutionLlm>(
     chat_root: &Path,
     llm: &L,
     model: &str,
-    _txn_id: &str,
+    txn_id: &str,
 ) -> Result<Vec<(String, String)>> {
     let summary = block_in_place(|| {
         let conn = open_evolution_db(chat_root)?;
@@ -316,21 +320,20 @@ pub async fn evolve_memory<L: EvolutionLlm>(
         .replace("{{decisions_summary}}", &summary)
         .replace("{{existing_knowledge_summary}}", existing_summary.trim());
     let messages = vec![EvolutionMessage::user(&prompt)];
-    let content = llm
-        .complete(&messages, model, 0.3)
-        .await?
-        .visible
-        .trim()
-        .to_string();
-
-    let parsed = match parse_knowledge_response(&content) {
+    let policy = LlmRetryPolicy::from_env();
+    let parsed = match complete_parsed(
+        llm,
+        &messages,
+        model,
+        0.3,
+        &policy,
+        parse_knowledge_response,
+    )
+    .await
+    {
         Ok(p) => p,
-        Err(e) => {
-            tracing::warn!(
-                "Memory knowledge extraction parse failed: {} — raw: {:.300}",
-                e,
-                content
-            );
+        Err(crate::Error::Validation(e)) => {
+            tracing::warn!("Memory knowledge extraction parse failed: {}", e);
             let _ = block_in_place(|| {
                 let conn = open_evolution_db(chat_root)?;
                 let _ = crate::log_evolution_event(
@@ -338,13 +341,14 @@ pub async fn evolve_memory<L: EvolutionLlm>(
                     chat_root,
                     "memory_extraction_parse_failed",
                     "",
-                    &format!("{}", e),
+                    &e,
                     "",
                 );
                 Ok::<_, anyhow::Error>(())
             });
             return Ok(Vec::new());
         }
+        Err(e) => return Err(e),
     };
 
     let has_any = !parsed.entities.is_empty()
@@ -456,39 +460,28 @@ pub async fn evolve_memory<L: EvolutionLlm>(
     let run_heading = evolution_run_heading();
     let memory_dir = chat_root.join("memory").join("evolution");
     skilllite_fs::create_dir_all(&memory_dir)?;
+    let txn = TxnWrites::new(chat_root, txn_id);
 
     if !entity_block.is_empty() {
-        append_evolution_shard(chat_root, "entities", &month, &run_heading, &entity_block)?;
+        append_evolution_shard(txn, "entities", &month, &run_heading, &entity_block)?;
     }
     if !relation_block.is_empty() {
-        append_evolution_shard(
-            chat_root,
-            "relations",
-            &month,
-            &run_heading,
-            &relation_block,
-        )?;
+        append_evolution_shard(txn, "relations", &month, &run_heading, &relation_block)?;
     }
     if !episode_block.is_empty() {
-        append_evolution_shard(chat_root, "episodes", &month, &run_heading, &episode_block)?;
+        append_evolution_shard(txn, "episodes", &month, &run_heading, &episode_block)?;
     }
     if !preference_block.is_empty() {
-        append_evolution_shard(
-            chat_root,
-            "preferences",
-            &month,
-            &run_heading,
-            &preference_block,
-        )?;
+        append_evolution_shard(txn, "preferences", &month, &run_heading, &preference_block)?;
     }
     if !pattern_block.is_empty() {
-        append_evolution_shard(chat_root, "patterns", &month, &run_heading, &pattern_block)?;
+        append_evolution_shard(txn, "patterns", &month, &run_heading, &pattern_block)?;
     }
 
-    rebuild_rollups_for_month(chat_root, &month)?;
+    rebuild_rollups_for_month(txn, &month)?;
 
-    regenerate_dimension_indexes(chat_root)?;
-    regenerate_evolution_root_index(chat_root, &run_heading)?;
+    regenerate_dimension_indexes(txn)?;
+    regenerate_evolution_root_index(txn, &run_heading)?;
 
     tracing::info!(
         "Memory evolution: wrote {} entities, {} relations, {} episodes, {} preferences, {} patterns (monthly shards under memory/evolution/)",
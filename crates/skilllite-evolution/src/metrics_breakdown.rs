@@ -0,0 +1,342 @@
+//! Per-workspace and per-skill daily aggregates (`evolution_metrics_breakdown`).
+//!
+//! Global `evolution_metrics` hide that one broken skill or one chaotic project can drag the
+//! daily rates down. The breakdown keeps the same counts grouped by workspace (a hash of
+//! `decisions.workspace`) and by skill (from the recorded tool calls), so
+//! [`crate::rollback::check_auto_rollback`] can tell a broad regression from a local one.
+
+use rusqlite::{params, Connection};
+use sha2::{Digest, Sha256};
+
+use crate::Result;
+
+/// `dimension` of workspace rows; `key` is [`workspace_key`] of the decision's workspace.
+pub const DIMENSION_WORKSPACE: &str = "workspace";
+/// `dimension` of skill rows; `key` is the skill name.
+pub const DIMENSION_SKILL: &str = "skill";
+
+/// Key of decisions recorded without a workspace.
+const UNKNOWN_WORKSPACE: &str = "unknown";
+
+/// Per-step thresholds shared with the global rollback check.
+pub const SUCCESS_RATE_DROP: f64 = 0.10;
+pub const CORRECTION_RATE_RISE: f64 = 0.20;
+
+/// Short stable hash of a workspace path, so the table does not store paths.
+pub fn workspace_key(path: &str) -> String {
+    hex::encode(Sha256::digest(path.as_bytes()))[..12].to_string()
+}
+
+/// Display label for a workspace: its last path component.
+fn workspace_label(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// One `evolution_metrics_breakdown` row with its derived rates.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct BreakdownRow {
+    pub date: String,
+    pub dimension: String,
+    pub key: String,
+    pub label: String,
+    pub decisions: i64,
+    pub successes: i64,
+    pub corrections: i64,
+    pub confirmations: i64,
+    pub tool_calls: i64,
+    pub tool_failures: i64,
+    pub first_success_rate: f64,
+    pub correction_rate: f64,
+}
+
+fn ratio(num: i64, den: i64) -> f64 {
+    if den > 0 {
+        num as f64 / den as f64
+    } else {
+        0.0
+    }
+}
+
+/// Rebuild the breakdown rows of `date`. A dimension whose raw rows were already pruned keeps
+/// its existing aggregates.
+pub(crate) fn upsert_breakdown_for_date(conn: &Connection, date: &str) -> Result<()> {
+    let workspaces: Vec<(Option<String>, [i64; 6])> = conn
+        .prepare(
+            "SELECT workspace, COUNT(*),
+                    COUNT(CASE WHEN task_completed = 1 AND feedback != 'neg' THEN 1 END),
+                    COALESCE(SUM(MAX(COALESCE(corrections, 0), feedback = 'neg')), 0),
+                    COALESCE(SUM(MAX(COALESCE(confirmations, 0), feedback = 'pos')), 0),
+                    COALESCE(SUM(total_tools), 0), COALESCE(SUM(failed_tools), 0)
+             FROM decisions WHERE date(ts) = ?1
+             GROUP BY workspace",
+        )?
+        .query_map(params![date], |row| {
+            Ok((
+                row.get(0)?,
+                [
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                ],
+            ))
+        })?
+        .collect::<std::result::Result<_, _>>()?;
+    if !workspaces.is_empty() {
+        // NULL and empty workspaces both land on the unknown key
+        let mut merged: std::collections::BTreeMap<String, (String, [i64; 6])> =
+            std::collections::BTreeMap::new();
+        for (path, counts) in workspaces {
+            let (key, label) = match path.as_deref().filter(|p| !p.is_empty()) {
+                Some(p) => (workspace_key(p), workspace_label(p)),
+                None => (UNKNOWN_WORKSPACE.to_string(), String::new()),
+            };
+            let entry = merged.entry(key).or_insert((label, [0; 6]));
+            for (acc, n) in entry.1.iter_mut().zip(counts) {
+                *acc += n;
+            }
+        }
+        replace_rows(conn, date, DIMENSION_WORKSPACE, merged)?;
+    }
+
+    let skills: Vec<(String, [i64; 6])> = conn
+        .prepare(
+            "SELECT t.skill, COUNT(*),
+                    COUNT(CASE WHEN d.task_completed = 1 AND d.feedback != 'neg' THEN 1 END),
+                    COALESCE(SUM(MAX(COALESCE(d.corrections, 0), d.feedback = 'neg')), 0),
+                    COALESCE(SUM(MAX(COALESCE(d.confirmations, 0), d.feedback = 'pos')), 0),
+                    SUM(t.calls), SUM(t.failures)
+             FROM decisions d
+             JOIN (SELECT decision_id, skill, COUNT(*) AS calls, SUM(is_error) AS failures
+                   FROM decision_tools WHERE skill IS NOT NULL AND skill != ''
+                   GROUP BY decision_id, skill) t ON t.decision_id = d.id
+             WHERE date(d.ts) = ?1
+             GROUP BY t.skill",
+        )?
+        .query_map(params![date], |row| {
+            Ok((
+                row.get(0)?,
+                [
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                ],
+            ))
+        })?
+        .collect::<std::result::Result<_, _>>()?;
+    if !skills.is_empty() {
+        let rows = skills
+            .into_iter()
+            .map(|(skill, counts)| (skill.clone(), (skill, counts)))
+            .collect();
+        replace_rows(conn, date, DIMENSION_SKILL, rows)?;
+    }
+    Ok(())
+}
+
+fn replace_rows(
+    conn: &Connection,
+    date: &str,
+    dimension: &str,
+    rows: std::collections::BTreeMap<String, (String, [i64; 6])>,
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM evolution_metrics_breakdown WHERE date = ?1 AND dimension = ?2",
+        params![date, dimension],
+    )?;
+    let mut stmt = conn.prepare(
+        "INSERT INTO evolution_metrics_breakdown (date, dimension, key, label, decisions,
+         successes, corrections, confirmations, tool_calls, tool_failures)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+    )?;
+    for (key, (label, [decisions, successes, corrections, confirmations, calls, failures])) in rows
+    {
+        stmt.execute(params![
+            date,
+            dimension,
+            key,
+            label,
+            decisions,
+            successes,
+            corrections,
+            confirmations,
+            calls,
+            failures
+        ])?;
+    }
+    Ok(())
+}
+
+/// Breakdown rows of the last `days` days, newest first, then by dimension and volume.
+pub fn query_breakdown(conn: &Connection, days: u32) -> Result<Vec<BreakdownRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT date, dimension, key, label, decisions, successes, corrections, confirmations,
+                tool_calls, tool_failures
+         FROM evolution_metrics_breakdown
+         WHERE date > date('now', ?1)
+         ORDER BY date DESC, dimension DESC, decisions DESC, key",
+    )?;
+    let rows = stmt.query_map(params![format!("-{} days", days)], row_to_breakdown)?;
+    Ok(rows.collect::<std::result::Result<_, _>>()?)
+}
+
+fn rows_for_date(conn: &Connection, date: &str, dimension: &str) -> Result<Vec<BreakdownRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT date, dimension, key, label, decisions, successes, corrections, confirmations,
+                tool_calls, tool_failures
+         FROM evolution_metrics_breakdown
+         WHERE date = ?1 AND dimension = ?2",
+    )?;
+    let rows = stmt.query_map(params![date, dimension], row_to_breakdown)?;
+    Ok(rows.collect::<std::result::Result<_, _>>()?)
+}
+
+fn row_to_breakdown(row: &rusqlite::Row<'_>) -> rusqlite::Result<BreakdownRow> {
+    let decisions: i64 = row.get(4)?;
+    let successes: i64 = row.get(5)?;
+    let corrections: i64 = row.get(6)?;
+    let confirmations: i64 = row.get(7)?;
+    Ok(BreakdownRow {
+        date: row.get(0)?,
+        dimension: row.get(1)?,
+        key: row.get(2)?,
+        label: row.get(3)?,
+        decisions,
+        successes,
+        corrections,
+        confirmations,
+        tool_calls: row.get(8)?,
+        tool_failures: row.get(9)?,
+        first_success_rate: ratio(successes, decisions),
+        correction_rate: ratio(corrections, corrections + confirmations),
+    })
+}
+
+/// Which global rate triggered the rollback check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegradedMetric {
+    FirstSuccessRate,
+    CorrectionRate,
+}
+
+impl DegradedMetric {
+    /// `(numerator, denominator)` counts of the rate.
+    fn counts(self, r: &BreakdownRow) -> (i64, i64) {
+        match self {
+            Self::FirstSuccessRate => (r.successes, r.decisions),
+            Self::CorrectionRate => (r.corrections, r.corrections + r.confirmations),
+        }
+    }
+
+    /// Whether the rate moved in the bad direction by more than one step's threshold.
+    fn degraded(self, before: f64, after: f64) -> bool {
+        match self {
+            Self::FirstSuccessRate => after < before - SUCCESS_RATE_DROP,
+            Self::CorrectionRate => after > before + CORRECTION_RATE_RISE,
+        }
+    }
+}
+
+/// Where a global degradation between two dates comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DegradationAttribution {
+    /// Workspace keys whose own rate degraded.
+    pub degraded_workspaces: Vec<String>,
+    /// A skill that had failures and without whose decisions the rest no longer degrades.
+    pub single_skill: Option<String>,
+}
+
+impl DegradationAttribution {
+    /// Broad-based: at least two workspaces degraded, or no single skill explains it.
+    pub fn is_broad(&self) -> bool {
+        self.degraded_workspaces.len() >= 2 || self.single_skill.is_none()
+    }
+}
+
+/// Attribute a degradation of `metric` from `oldest` to `newest`. `None` when either date has
+/// no breakdown rows (databases from before the breakdown, or pruned history).
+pub fn attribute_degradation(
+    conn: &Connection,
+    newest: &str,
+    oldest: &str,
+    metric: DegradedMetric,
+) -> Result<Option<DegradationAttribution>> {
+    let ws_new = rows_for_date(conn, newest, DIMENSION_WORKSPACE)?;
+    let ws_old = rows_for_date(conn, oldest, DIMENSION_WORKSPACE)?;
+    if ws_new.is_empty() || ws_old.is_empty() {
+        return Ok(None);
+    }
+
+    let rate = |r: &BreakdownRow| {
+        let (num, den) = metric.counts(r);
+        (den > 0).then(|| ratio(num, den))
+    };
+    let mut degraded_workspaces: Vec<String> = ws_new
+        .iter()
+        .filter_map(|new| {
+            let old = ws_old.iter().find(|o| o.key == new.key)?;
+            let (before, after) = (rate(old)?, rate(new)?);
+            metric.degraded(before, after).then(|| new.key.clone())
+        })
+        .collect();
+    degraded_workspaces.sort();
+
+    let total = |rows: &[BreakdownRow]| {
+        rows.iter().fold((0, 0), |(n, d), r| {
+            let (rn, rd) = metric.counts(r);
+            (n + rn, d + rd)
+        })
+    };
+    let (total_new, total_old) = (total(&ws_new), total(&ws_old));
+    let skills_new = rows_for_date(conn, newest, DIMENSION_SKILL)?;
+    let skills_old = rows_for_date(conn, oldest, DIMENSION_SKILL)?;
+
+    // Rate of everything except the skill's decisions; `None` when nothing is left.
+    let excluding = |(n, d): (i64, i64), skill: Option<&BreakdownRow>| {
+        let (sn, sd) = skill.map_or((0, 0), |s| metric.counts(s));
+        (d - sd > 0).then(|| ratio(n - sn, d - sd))
+    };
+    let single_skill = skills_new
+        .iter()
+        .filter(|s| s.tool_failures > 0)
+        .filter(|s| {
+            let old = skills_old.iter().find(|o| o.key == s.key);
+            match (excluding(total_old, old), excluding(total_new, Some(s))) {
+                (Some(before), Some(after)) => !metric.degraded(before, after),
+                // Every decision of the day used the skill
+                (_, None) => true,
+                (None, Some(_)) => false,
+            }
+        })
+        .max_by_key(|s| (s.tool_failures, std::cmp::Reverse(s.key.clone())))
+        .map(|s| s.key.clone());
+
+    Ok(Some(DegradationAttribution {
+        degraded_workspaces,
+        single_skill,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_key_is_stable_and_short() {
+        assert_eq!(workspace_key("/home/a/proj"), workspace_key("/home/a/proj"));
+        assert_ne!(
+            workspace_key("/home/a/proj"),
+            workspace_key("/home/a/other")
+        );
+        assert_eq!(workspace_key("/home/a/proj").len(), 12);
+        assert_eq!(workspace_label("/home/a/proj"), "proj");
+    }
+}
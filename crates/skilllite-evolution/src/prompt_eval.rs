@@ -0,0 +1,451 @@
+//! Holdout evaluation of candidate planning rules before they go live.
+//!
+//! With [`PromptEvaluationMode::Cheap`] or [`PromptEvaluationMode::Llm`], the prompt learner
+//! writes new rules to `prompts/_candidate/rules.json` instead of `prompts/rules.json`. The last
+//! K completed task descriptions are replayed through the planning prompt twice — once with the
+//! live rules, once with the candidate — and the candidate is promoted only when its plans do not
+//! score worse. The verdict lands in the txn's changelog entry and `evolution explain`.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use skilllite_core::config::env_keys::evolution as evo_keys;
+use skilllite_core::planning::PlanningRule;
+use tokio::task::block_in_place;
+
+use crate::config::PromptEvaluationMode;
+use crate::error::bail;
+use crate::journal::TxnWrites;
+use crate::prompt_learner::extract_json_block;
+use crate::seed;
+use crate::seed::template::{self, TemplateVars};
+use crate::{
+    complete_parsed, complete_with_retry, gatekeeper_l1_path, EvolutionLlm, EvolutionMessage,
+    LlmRetryPolicy, Result,
+};
+
+const PLAN_JUDGE_PROMPT: &str = include_str!("seed/evolution_prompts/plan_judge.seed.md");
+
+/// Candidate directory under `prompts/`; removed after every evaluation.
+pub const CANDIDATE_DIR: &str = "_candidate";
+/// Builtin planner tool hints (see `planning.seed.md`), valid regardless of history.
+const BUILTIN_TOOL_HINTS: &[&str] = &[
+    "file_list",
+    "file_read",
+    "file_write",
+    "file_edit",
+    "file_operation",
+    "preview",
+    "command",
+    "analysis",
+    "memory_search",
+];
+/// Replayed tasks kept verbatim in the changelog entry.
+const MAX_SAMPLES: usize = 3;
+/// Plan text kept per sample.
+const SAMPLE_PLAN_CHARS: usize = 400;
+
+pub const VERDICT_PROMOTED: &str = "promoted";
+pub const VERDICT_REJECTED: &str = "rejected";
+
+/// Outcome of one holdout evaluation, stored as `evaluation` in the changelog entry.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PromptEvaluation {
+    pub mode: String,
+    /// [`VERDICT_PROMOTED`] or [`VERDICT_REJECTED`].
+    pub verdict: String,
+    /// Mean plan score (0–1) with the live rules.
+    pub baseline_score: f32,
+    /// Mean plan score (0–1) with the candidate rules.
+    pub candidate_score: f32,
+    /// Tasks whose planning prompt differed between the two rule sets and were replayed.
+    pub replayed: usize,
+    /// Tasks where no candidate rule matched; both sides score the same without an LLM call.
+    pub unaffected: usize,
+    #[serde(default)]
+    pub samples: Vec<EvaluationSample>,
+}
+
+impl PromptEvaluation {
+    pub fn promoted(&self) -> bool {
+        self.verdict == VERDICT_PROMOTED
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EvaluationSample {
+    pub task: String,
+    pub baseline_score: f32,
+    pub candidate_score: f32,
+    pub baseline_plan: String,
+    pub candidate_plan: String,
+}
+
+pub fn candidate_dir(chat_root: &Path) -> PathBuf {
+    chat_root.join("prompts").join(CANDIDATE_DIR)
+}
+
+pub fn candidate_rules_path(chat_root: &Path) -> PathBuf {
+    candidate_dir(chat_root).join("rules.json")
+}
+
+fn evaluation_samples() -> usize {
+    std::env::var(evo_keys::SKILLLITE_EVO_PROMPT_EVALUATION_SAMPLES)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5)
+        .clamp(1, 20)
+}
+
+/// Write the full candidate rule set to `prompts/_candidate/rules.json`.
+pub fn write_candidate_rules(chat_root: &Path, rules: &[PlanningRule]) -> Result<()> {
+    let path = candidate_rules_path(chat_root);
+    if !gatekeeper_l1_path(chat_root, &path, None) {
+        bail!("Gatekeeper L1: candidate rules path outside allowed directories");
+    }
+    std::fs::create_dir_all(candidate_dir(chat_root))?;
+    seed::write_rules_checked(&path, rules, None)
+}
+
+/// Move the candidate rules over `prompts/rules.json` (journaled in the txn).
+pub fn promote_candidate(txn: TxnWrites) -> Result<()> {
+    let chat_root = txn.chat_root;
+    let live = chat_root.join("prompts").join("rules.json");
+    if !gatekeeper_l1_path(chat_root, &live, None) {
+        bail!("Gatekeeper L1: rules.json path outside allowed directories");
+    }
+    let content = std::fs::read_to_string(candidate_rules_path(chat_root))?;
+    txn.write(&live, &content)
+}
+
+pub fn discard_candidate(chat_root: &Path) {
+    let dir = candidate_dir(chat_root);
+    if dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            tracing::warn!("Failed to remove {}: {}", dir.display(), e);
+        }
+    }
+}
+
+/// Replay recent tasks with `baseline` vs `candidate` rules and decide whether to promote.
+pub async fn evaluate_candidate_rules<L: EvolutionLlm>(
+    chat_root: &Path,
+    llm: &L,
+    model: &str,
+    mode: PromptEvaluationMode,
+    baseline: &[PlanningRule],
+    candidate: &[PlanningRule],
+) -> Result<PromptEvaluation> {
+    let (tasks, seen_tools) = block_in_place(|| {
+        let conn = crate::feedback::open_evolution_db(chat_root)?;
+        replay_set(&conn, evaluation_samples())
+    })?;
+    let mut known_tools: HashSet<String> =
+        BUILTIN_TOOL_HINTS.iter().map(|s| s.to_string()).collect();
+    known_tools.extend(seen_tools);
+
+    let template = crate::seed::load_planning_template(chat_root);
+    let policy = LlmRetryPolicy::from_env();
+    let mut skills_info: Vec<&str> = known_tools
+        .iter()
+        .map(String::as_str)
+        .filter(|t| !BUILTIN_TOOL_HINTS.contains(t))
+        .collect();
+    skills_info.sort_unstable();
+
+    let mut baseline_total = 0.0_f32;
+    let mut candidate_total = 0.0_f32;
+    let mut replayed = 0;
+    let mut unaffected = 0;
+    let mut samples = Vec::new();
+
+    for task in &tasks {
+        let baseline_section = rules_section_for(baseline, task);
+        let candidate_section = rules_section_for(candidate, task);
+        if baseline_section == candidate_section {
+            unaffected += 1;
+            continue;
+        }
+        replayed += 1;
+
+        let mut scored = Vec::with_capacity(2);
+        for section in [&baseline_section, &candidate_section] {
+            let prompt = planning_prompt(&template, section, &skills_info);
+            let messages = vec![
+                EvolutionMessage::system(&prompt),
+                EvolutionMessage::user(task),
+            ];
+            let plan = complete_with_retry(llm, &messages, model, 0.0, &policy)
+                .await?
+                .visible;
+            let mut score = cheap_plan_score(&plan, &known_tools);
+            if mode == PromptEvaluationMode::Llm && score > 0.0 {
+                score *= judge_plan(llm, model, &policy, task, &plan).await;
+            }
+            scored.push((plan, score));
+        }
+        let (candidate_plan, candidate_score) = scored.pop().unwrap_or_default();
+        let (baseline_plan, baseline_score) = scored.pop().unwrap_or_default();
+        baseline_total += baseline_score;
+        candidate_total += candidate_score;
+        if samples.len() < MAX_SAMPLES {
+            samples.push(EvaluationSample {
+                task: truncate(task, SAMPLE_PLAN_CHARS),
+                baseline_score,
+                candidate_score,
+                baseline_plan: truncate(&baseline_plan, SAMPLE_PLAN_CHARS),
+                candidate_plan: truncate(&candidate_plan, SAMPLE_PLAN_CHARS),
+            });
+        }
+    }
+
+    // Unaffected tasks get the same prompt on both sides, so only replayed tasks move the means.
+    let (baseline_score, candidate_score) = if replayed == 0 {
+        (1.0, 1.0)
+    } else {
+        (
+            baseline_total / replayed as f32,
+            candidate_total / replayed as f32,
+        )
+    };
+    let verdict = if candidate_score >= baseline_score {
+        VERDICT_PROMOTED
+    } else {
+        VERDICT_REJECTED
+    };
+    tracing::info!(
+        "Prompt holdout evaluation ({}): baseline={:.2} candidate={:.2} replayed={} -> {}",
+        mode.as_str(),
+        baseline_score,
+        candidate_score,
+        replayed,
+        verdict
+    );
+
+    Ok(PromptEvaluation {
+        mode: mode.as_str().to_string(),
+        verdict: verdict.to_string(),
+        baseline_score,
+        candidate_score,
+        replayed,
+        unaffected,
+        samples,
+    })
+}
+
+/// Last `limit` completed task descriptions plus every tool name seen in decisions.
+fn replay_set(conn: &Connection, limit: usize) -> Result<(Vec<String>, HashSet<String>)> {
+    let mut stmt = conn.prepare(
+        "SELECT task_description FROM decisions
+         WHERE task_completed = 1 AND task_description IS NOT NULL AND task_description <> ''
+         ORDER BY ts DESC, id DESC LIMIT ?1",
+    )?;
+    let tasks = stmt
+        .query_map([limit as i64], |row| row.get::<_, String>(0))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut stmt =
+        conn.prepare("SELECT tools_detail FROM decisions WHERE tools_detail IS NOT NULL")?;
+    let mut tools = HashSet::new();
+    for detail in stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .filter_map(|r| r.ok())
+    {
+        let parsed: Vec<crate::feedback::ToolExecDetail> =
+            serde_json::from_str(&detail).unwrap_or_default();
+        tools.extend(parsed.into_iter().map(|d| d.tool));
+    }
+    Ok((tasks, tools))
+}
+
+/// Rules section as the agent's planner would render it for `task` (keyword filter + CRITICAL header).
+fn rules_section_for(rules: &[PlanningRule], task: &str) -> String {
+    let task_lower = task.to_lowercase();
+    let matches = |k: &String| task.contains(k.as_str()) || task_lower.contains(&k.to_lowercase());
+    let lines: Vec<&str> = rules
+        .iter()
+        .filter(|r| {
+            (r.keywords.is_empty() && r.context_keywords.is_empty())
+                || r.keywords.iter().any(matches)
+                || r.context_keywords.iter().any(matches)
+        })
+        .map(|r| r.instruction.trim())
+        .filter(|i| !i.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!(
+        "## CRITICAL: When user explicitly requests a Skill, ALWAYS use it\n\n{}",
+        lines.join("\n\n")
+    )
+}
+
+fn planning_prompt(template: &str, rules_section: &str, skills_info: &[&str]) -> String {
+    let today = chrono::Local::now().date_naive();
+    let yesterday = today - chrono::Duration::days(1);
+    let skills = if skills_info.is_empty() {
+        "(none)".to_string()
+    } else {
+        skills_info
+            .iter()
+            .map(|s| format!("- {}", s))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let vars = TemplateVars::new()
+        .set("TODAY", today.to_string())
+        .set("YESTERDAY", yesterday.to_string())
+        .set("RULES_SECTION", rules_section)
+        .set("EXAMPLES_SECTION", "")
+        .set("OUTPUT_DIR", "output")
+        .set("SKILLS_INFO", skills)
+        .set("SOUL_SCOPE_BLOCK", "");
+    template::render(template, &vars)
+}
+
+/// 0 when the plan is not a JSON task array; otherwise the share of tasks whose `tool_hint`
+/// is empty, a builtin hint, or a tool seen in past decisions (`[]` scores 1).
+fn cheap_plan_score(plan: &str, known_tools: &HashSet<String>) -> f32 {
+    let Some(tasks) = parse_plan(plan) else {
+        return 0.0;
+    };
+    if tasks.is_empty() {
+        return 1.0;
+    }
+    let valid = tasks
+        .iter()
+        .filter(|t| {
+            t.get("description").and_then(|d| d.as_str()).is_some()
+                && match t.get("tool_hint").and_then(|h| h.as_str()) {
+                    None | Some("") => true,
+                    Some(hint) => known_tools.contains(hint),
+                }
+        })
+        .count();
+    valid as f32 / tasks.len() as f32
+}
+
+fn parse_plan(plan: &str) -> Option<Vec<serde_json::Value>> {
+    let block = extract_json_block(plan);
+    if let Ok(serde_json::Value::Array(tasks)) = serde_json::from_str(&block) {
+        return Some(tasks);
+    }
+    let stripped = crate::strip_think_blocks(plan.trim());
+    let (start, end) = (stripped.find('[')?, stripped.rfind(']')?);
+    if start >= end {
+        return None;
+    }
+    match serde_json::from_str(&stripped[start..=end]) {
+        Ok(serde_json::Value::Array(tasks)) => Some(tasks),
+        _ => None,
+    }
+}
+
+/// LLM judge multiplier in 0–1; judge failures leave the cheap score unchanged.
+async fn judge_plan<L: EvolutionLlm>(
+    llm: &L,
+    model: &str,
+    policy: &LlmRetryPolicy,
+    task: &str,
+    plan: &str,
+) -> f32 {
+    let prompt = PLAN_JUDGE_PROMPT
+        .replace("{{task}}", task)
+        .replace("{{plan}}", plan);
+    let messages = vec![EvolutionMessage::user(&prompt)];
+    match complete_parsed(llm, &messages, model, 0.0, policy, parse_judge_score).await {
+        Ok(score) => score,
+        Err(e) => {
+            tracing::warn!("Plan judge failed, keeping cheap score: {}", e);
+            1.0
+        }
+    }
+}
+
+fn parse_judge_score(content: &str) -> Result<f32> {
+    let value: serde_json::Value = serde_json::from_str(&extract_json_block(content))
+        .map_err(|e| crate::Error::validation(format!("Failed to parse plan judge JSON: {}", e)))?;
+    let score = value
+        .get("score")
+        .and_then(|s| s.as_f64())
+        .ok_or_else(|| crate::Error::validation("No numeric 'score' in plan judge response"))?;
+    Ok((score as f32 / 10.0).clamp(0.0, 1.0))
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        s.to_string()
+    } else {
+        let mut out: String = s.chars().take(max_chars).collect();
+        out.push('…');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, keywords: &[&str], instruction: &str) -> PlanningRule {
+        PlanningRule {
+            id: id.to_string(),
+            priority: 60,
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            context_keywords: Vec::new(),
+            tool_hint: None,
+            instruction: instruction.to_string(),
+            mutable: true,
+            origin: "evolved".to_string(),
+            reusable: false,
+            effectiveness: None,
+            trigger_count: None,
+            provenance: None,
+            scope: None,
+        }
+    }
+
+    #[test]
+    fn cheap_score_requires_parseable_plan_with_known_tools() {
+        let known: HashSet<String> = ["file_read", "weather"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(cheap_plan_score("not json", &known), 0.0);
+        assert_eq!(cheap_plan_score("[]", &known), 1.0);
+        let plan = r#"```json
+[{"id":1,"description":"read","tool_hint":"file_read"},
+ {"id":2,"description":"fetch","tool_hint":"made_up_skill"}]
+```"#;
+        assert_eq!(cheap_plan_score(plan, &known), 0.5);
+        assert_eq!(
+            cheap_plan_score(
+                r#"[{"id":1,"description":"x","tool_hint":"weather"}]"#,
+                &known
+            ),
+            1.0
+        );
+    }
+
+    #[test]
+    fn rules_section_only_includes_matching_rules() {
+        let rules = vec![
+            rule("r_always", &[], "always do this"),
+            rule("r_csv", &["csv"], "use pandas for CSV"),
+        ];
+        let section = rules_section_for(&rules, "Summarize this CSV file");
+        assert!(section.contains("use pandas for CSV"));
+        assert!(section.contains("always do this"));
+        assert!(!rules_section_for(&rules, "write a poem").contains("pandas"));
+        assert!(rules_section_for(&[], "anything").is_empty());
+    }
+
+    #[test]
+    fn judge_score_is_normalized() {
+        assert_eq!(parse_judge_score(r#"{"score": 7}"#).unwrap(), 0.7);
+        assert_eq!(parse_judge_score(r#"{"score": 42}"#).unwrap(), 1.0);
+        assert!(parse_judge_score("great plan").is_err());
+    }
+}
@@ -8,12 +8,15 @@ use rusqlite::{params, Connection};
 use tokio::task::block_in_place;
 
 use skilllite_core::config::env_keys::evolution as evo_keys;
-use skilllite_core::planning::PlanningRule;
+use skilllite_core::planning::{PlanningRule, RuleScope};
 
+use crate::change_set::{ChangeKind, ChangeSet, DroppedChange};
+use crate::config::PromptEvaluationMode;
 use crate::feedback::compute_effectiveness;
-use crate::{
-    gatekeeper_l1_path, gatekeeper_l2_size, gatekeeper_l3_content, EvolutionLlm, EvolutionMessage,
-};
+use crate::journal::TxnWrites;
+use crate::prompt_eval::{self, PromptEvaluation};
+use crate::seed;
+use crate::{complete_parsed, gatekeeper_l1_path, EvolutionLlm, EvolutionMessage, LlmRetryPolicy};
 use skilllite_fs::atomic_write;
 
 const RULE_EXTRACTION_PROMPT: &str = include_str!("seed/evolution_prompts/rule_extraction.seed.md");
@@ -24,6 +27,14 @@ const EXAMPLE_GENERATION_PROMPT: &str =
 const RETIRE_EFFECTIVENESS_THRESHOLD: f32 = 0.3;
 /// Minimum trigger count before a rule is eligible for retirement (need enough data).
 const RETIRE_MIN_TRIGGER_COUNT: i64 = 5;
+/// Lookback window (days) for example usage attribution.
+const EXAMPLE_USAGE_WINDOW_DAYS: i64 = 30;
+/// Matches at which an example counts as fully "used" in its quality score.
+const EXAMPLE_USAGE_SATURATION: u32 = 10;
+/// Fraction of `task_pattern` tokens a task description must contain to count as a match.
+const EXAMPLE_MATCH_MIN_OVERLAP: f32 = 0.5;
+/// Upper bound on `rules.json` entries (seed + evolved).
+const MAX_RULES: usize = 50;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PlanningExample {
@@ -33,6 +44,17 @@ pub struct PlanningExample {
     pub key_insight: String,
     #[serde(default = "default_evolved_origin")]
     pub origin: String,
+    /// Recent decisions whose task matched `task_pattern` (usage window).
+    #[serde(default)]
+    pub match_count: u32,
+    /// Of those, completed without negative feedback.
+    #[serde(default)]
+    pub success_count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_score: Option<f32>,
+    /// Set when the example is moved to `examples_archive.json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retired_at: Option<String>,
 }
 
 fn default_evolved_origin() -> String {
@@ -47,6 +69,14 @@ fn prompt_example_min_tools() -> i64 {
         .clamp(1, 20)
 }
 
+fn prompt_example_token_budget() -> usize {
+    std::env::var(evo_keys::SKILLLITE_EVO_EXAMPLE_TOKEN_BUDGET)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1500)
+        .clamp(200, 20_000)
+}
+
 fn prompt_rule_summary_limit() -> i64 {
     std::env::var(evo_keys::SKILLLITE_EVO_PROMPT_RULE_SUMMARY_LIMIT)
         .ok()
@@ -55,21 +85,42 @@ fn prompt_rule_summary_limit() -> i64 {
         .clamp(1, 50)
 }
 
+/// Result of one prompt evolution pass.
+#[derive(Debug, Default)]
+pub struct PromptEvolution {
+    pub changes: Vec<(String, String)>,
+    /// Holdout verdict for new rules; `None` when evaluation is off or no rule was extracted.
+    pub evaluation: Option<PromptEvaluation>,
+    /// Rules / examples the gatekeepers kept out of this pass.
+    pub dropped: Vec<DroppedChange>,
+}
+
 pub async fn evolve_prompts<L: EvolutionLlm>(
     chat_root: &Path,
     llm: &L,
     model: &str,
     txn_id: &str,
-) -> Result<Vec<(String, String)>> {
+) -> Result<PromptEvolution> {
     let mut changes = Vec::new();
+    let eval_mode = PromptEvaluationMode::from_env();
+    let txn = TxnWrites::new(chat_root, txn_id);
 
     // Batch all DB operations in one block_in_place to reduce connection opens.
-    let (retired, extract_data, example_data) = block_in_place(|| {
+    let (retired, retired_examples, extract_data, example_data) = block_in_place(|| {
         let conn = crate::feedback::open_evolution_db(chat_root)?;
-        let retired = retire_low_effectiveness_rules_with_conn(chat_root, txn_id, &conn)?;
+        let mut retired = retire_low_effectiveness_rules_with_conn(chat_root, txn_id, &conn)?;
+        retired.extend(crate::skill_removal::retire_for_removed_skills_with_conn(
+            chat_root, txn_id, &conn,
+        )?);
+        let retired_examples = retire_stale_examples_with_conn(chat_root, txn_id, &conn)?;
         let rule_limit = prompt_rule_summary_limit();
         let successful = query_decisions_summary(&conn, true, rule_limit)?;
-        let failed = query_decisions_summary(&conn, false, rule_limit)?;
+        let mut failed = query_decisions_summary(&conn, false, rule_limit)?;
+        let patterns = query_tool_failure_patterns(&conn)?;
+        if !failed.is_empty() && !patterns.is_empty() {
+            failed.push_str("\n\n工具调用失败模式 (仅参数名，不含取值):\n");
+            failed.push_str(&patterns);
+        }
         let min_tools = prompt_example_min_tools();
         let example_sql = format!(
             "SELECT task_description, tools_detail, elapsed_ms
@@ -87,38 +138,105 @@ pub async fn evolve_prompts<L: EvolutionLlm>(
             ))
         });
         let example_data = example_candidate.ok();
-        Ok::<_, anyhow::Error>((retired, (successful, failed), example_data))
+        Ok::<_, anyhow::Error>((
+            retired,
+            retired_examples,
+            (successful, failed),
+            example_data,
+        ))
     })?;
 
     changes.extend(retired);
 
-    let rule_changes = extract_rules_from_data(chat_root, extract_data, llm, model).await?;
+    let (rule_changes, mut dropped) =
+        extract_rules_from_data(txn, extract_data, llm, model, eval_mode).await?;
+    let (rule_changes, evaluation) = if eval_mode != PromptEvaluationMode::Off {
+        evaluate_rule_candidate(txn, llm, model, eval_mode, rule_changes).await
+    } else {
+        (rule_changes, None)
+    };
     changes.extend(rule_changes);
 
-    let example_changes = generate_examples_from_data(chat_root, example_data, llm, model).await?;
+    let (example_changes, dropped_examples) =
+        generate_examples_from_data(txn, example_data, llm, model).await?;
     changes.extend(example_changes);
+    dropped.extend(dropped_examples);
 
-    let new_rules = changes.iter().filter(|(t, _)| t == "rule_added").count();
-    let new_examples = changes.iter().filter(|(t, _)| t == "example_added").count();
-    if !gatekeeper_l2_size(new_rules, new_examples, 0) {
-        tracing::warn!(
-            "Gatekeeper L2: evolution produced too many changes (rules={}, examples={}), truncating",
-            new_rules, new_examples
-        );
-        changes.truncate(5 + 3);
+    // Retirements are already capped by gatekeeper_l2_example_retirements()
+    changes.extend(retired_examples);
+
+    Ok(PromptEvolution {
+        changes,
+        evaluation,
+        dropped,
+    })
+}
+
+/// Promote or drop the rules staged in `prompts/_candidate/`; the candidate dir is always removed.
+/// Rejected rules come back as `rule_candidate_rejected` changes so the txn still records them.
+async fn evaluate_rule_candidate<L: EvolutionLlm>(
+    txn: TxnWrites<'_>,
+    llm: &L,
+    model: &str,
+    mode: PromptEvaluationMode,
+    rule_changes: Vec<(String, String)>,
+) -> (Vec<(String, String)>, Option<PromptEvaluation>) {
+    let chat_root = txn.chat_root;
+    if rule_changes.is_empty() {
+        prompt_eval::discard_candidate(chat_root);
+        return (rule_changes, None);
     }
+    let baseline = crate::seed::load_rules(chat_root);
+    let candidate: Vec<PlanningRule> =
+        std::fs::read_to_string(prompt_eval::candidate_rules_path(chat_root))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
 
-    Ok(changes)
+    let outcome = match prompt_eval::evaluate_candidate_rules(
+        chat_root, llm, model, mode, &baseline, &candidate,
+    )
+    .await
+    {
+        Ok(evaluation) if evaluation.promoted() => match prompt_eval::promote_candidate(txn) {
+            Ok(()) => {
+                tracing::info!("Promoted {} candidate rule(s)", rule_changes.len());
+                (rule_changes, Some(evaluation))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to promote candidate rules: {}", e);
+                (Vec::new(), None)
+            }
+        },
+        Ok(evaluation) => {
+            let rejected = rule_changes
+                .into_iter()
+                .map(|(_, id)| ("rule_candidate_rejected".to_string(), id))
+                .collect();
+            (rejected, Some(evaluation))
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Prompt holdout evaluation failed, dropping candidate rules: {}",
+                e
+            );
+            (Vec::new(), None)
+        }
+    };
+    prompt_eval::discard_candidate(chat_root);
+    outcome
 }
 
 async fn extract_rules_from_data<L: EvolutionLlm>(
-    chat_root: &Path,
+    txn: TxnWrites<'_>,
     (successful, failed): (String, String),
     llm: &L,
     model: &str,
-) -> Result<Vec<(String, String)>> {
+    eval_mode: PromptEvaluationMode,
+) -> Result<(Vec<(String, String)>, Vec<DroppedChange>)> {
+    let chat_root = txn.chat_root;
     if successful.is_empty() && failed.is_empty() {
-        return Ok(Vec::new());
+        return Ok(Default::default());
     }
 
     let existing_rules = crate::seed::load_rules(chat_root);
@@ -134,17 +252,19 @@ async fn extract_rules_from_data<L: EvolutionLlm>(
         .replace("{{failed_decisions}}", &failed);
 
     let messages = vec![EvolutionMessage::user(&prompt)];
-    let content = llm
-        .complete(&messages, model, 0.3)
-        .await?
-        .visible
-        .trim()
-        .to_string();
-
-    let parsed = match parse_rule_extraction_response(&content) {
+    let policy = LlmRetryPolicy::from_env();
+    let parsed = match complete_parsed(
+        llm,
+        &messages,
+        model,
+        0.3,
+        &policy,
+        parse_rule_extraction_response,
+    )
+    .await
+    {
         Ok(rules) => rules,
-        Err(e) => {
-            let detail = format!("{} — raw: {:.200}", e, content);
+        Err(crate::Error::Validation(detail)) => {
             tracing::warn!("Failed to parse LLM rule extraction output: {}", detail);
             let _ = block_in_place(|| {
                 let conn = crate::feedback::open_evolution_db(chat_root)?;
@@ -158,17 +278,18 @@ async fn extract_rules_from_data<L: EvolutionLlm>(
                 );
                 Ok::<_, anyhow::Error>(())
             });
-            return Ok(Vec::new());
+            return Ok(Default::default());
         }
+        Err(e) => return Err(e),
     };
     if parsed.is_empty() {
-        return Ok(Vec::new());
+        return Ok(Default::default());
     }
 
-    let mut valid_rules = Vec::new();
-    for rule in parsed {
-        if let Err(e) = gatekeeper_l3_content(&rule.instruction) {
-            tracing::warn!("L3 rejected rule {}: {}", rule.id, e);
+    let available_slots = MAX_RULES.saturating_sub(existing_rules.len());
+    let mut proposed = ChangeSet::new(ChangeKind::Rule).with_limit(available_slots);
+    for (mut rule, score) in parsed {
+        if existing_rules.iter().any(|r| r.id == rule.id) {
             continue;
         }
         if rule.priority < 50 || rule.priority > 79 {
@@ -177,46 +298,40 @@ async fn extract_rules_from_data<L: EvolutionLlm>(
                 rule.id,
                 rule.priority
             );
-            let mut r = rule;
-            r.priority = r.priority.clamp(50, 79);
-            valid_rules.push(r);
-        } else {
-            valid_rules.push(rule);
+            rule.priority = rule.priority.clamp(50, 79);
         }
+        let instruction = rule.instruction.clone();
+        proposed.propose(rule.id.clone(), score, &instruction, rule);
     }
-
-    if valid_rules.is_empty() {
-        return Ok(Vec::new());
-    }
+    let applied = proposed.finish();
 
     let mut all_rules = existing_rules;
     let mut changes = Vec::new();
-
-    let available_slots = 50_usize.saturating_sub(all_rules.len());
-    let to_add = valid_rules.into_iter().take(available_slots);
-
-    for new_rule in to_add {
-        if all_rules.iter().any(|r| r.id == new_rule.id) {
-            continue;
-        }
+    for new_rule in applied.accepted {
         changes.push(("rule_added".to_string(), new_rule.id.clone()));
         all_rules.push(new_rule);
     }
 
     if !changes.is_empty() {
+        if eval_mode != PromptEvaluationMode::Off {
+            prompt_eval::write_candidate_rules(chat_root, &all_rules)?;
+            tracing::info!("Staged {} candidate rules for evaluation", changes.len());
+            return Ok((changes, applied.dropped));
+        }
         let path = chat_root.join("prompts").join("rules.json");
         if !gatekeeper_l1_path(chat_root, &path, None) {
             bail!("Gatekeeper L1: rules.json path outside allowed directories");
         }
-        let json = serde_json::to_string_pretty(&all_rules)?;
-        atomic_write(&path, &json)?;
+        seed::write_rules_checked(&path, &all_rules, Some(txn))?;
         tracing::info!("Added {} new rules via evolution", changes.len());
     }
 
-    Ok(changes)
+    Ok((changes, applied.dropped))
 }
 
-fn parse_rule_extraction_response(content: &str) -> Result<Vec<PlanningRule>> {
+/// Parsed rules with their ranking score: the model's `confidence` (0-1) when given, else
+/// `priority / 100`.
+fn parse_rule_extraction_response(content: &str) -> Result<Vec<(PlanningRule, f32)>> {
     let json_str = extract_json_block(content);
 
     let parsed: serde_json::Value = serde_json::from_str(&json_str).map_err(|e| {
@@ -230,6 +345,14 @@ fn parse_rule_extraction_response(content: &str) -> Result<Vec<PlanningRule>> {
 
     let mut rules = Vec::new();
     for rule_val in rules_array {
+        if let Some(reason) = seed::rule_rejection(rule_val) {
+            tracing::warn!(
+                "Rejected evolved rule {}:\n{}",
+                rule_val.get("id").unwrap_or(&serde_json::Value::Null),
+                reason
+            );
+            continue;
+        }
         let id = rule_val
             .get("id")
             .and_then(|v| v.as_str())
@@ -273,8 +396,19 @@ fn parse_rule_extraction_response(content: &str) -> Result<Vec<PlanningRule>> {
             .and_then(|v| v.as_str())
             .filter(|s| !s.is_empty() && *s != "null")
             .map(String::from);
-
-        rules.push(PlanningRule {
+        let score = rule_val
+            .get("confidence")
+            .and_then(|v| v.as_f64())
+            .map(|c| c.clamp(0.0, 1.0) as f32)
+            .unwrap_or(priority as f32 / 100.0);
+        let scope = rule_val
+            .get("scope")
+            .filter(|v| v.is_object())
+            .and_then(|v| serde_json::from_value::<RuleScope>(v.clone()).ok())
+            .map(normalize_scope)
+            .filter(|s| !s.is_empty());
+
+        let rule = PlanningRule {
             id,
             priority,
             keywords,
@@ -286,26 +420,49 @@ fn parse_rule_extraction_response(content: &str) -> Result<Vec<PlanningRule>> {
             reusable: false,
             effectiveness: None,
             trigger_count: None,
-        });
+            provenance: None,
+            scope,
+        };
+        rules.push((rule, score));
     }
 
     Ok(rules)
 }
 
+/// Trim fields and drop blanks; languages are matched lowercase.
+fn normalize_scope(scope: RuleScope) -> RuleScope {
+    let non_blank = |v: Option<String>| {
+        v.map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty() && s != "null")
+    };
+    RuleScope {
+        workspace_glob: non_blank(scope.workspace_glob),
+        language: non_blank(scope.language).map(|l| l.to_lowercase()),
+        requires_skill: non_blank(scope.requires_skill),
+        task_keywords: scope
+            .task_keywords
+            .into_iter()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .collect(),
+    }
+}
+
 async fn generate_examples_from_data<L: EvolutionLlm>(
-    chat_root: &Path,
+    txn: TxnWrites<'_>,
     example_data: Option<(Option<String>, Option<String>, i64)>,
     llm: &L,
     model: &str,
-) -> Result<Vec<(String, String)>> {
+) -> Result<(Vec<(String, String)>, Vec<DroppedChange>)> {
+    let chat_root = txn.chat_root;
     let (task_desc, tools_json, elapsed_ms) = match example_data {
         Some(c) => c,
-        None => return Ok(Vec::new()),
+        None => return Ok(Default::default()),
     };
 
     let task_desc = task_desc.unwrap_or_default();
     if task_desc.is_empty() {
-        return Ok(Vec::new());
+        return Ok(Default::default());
     }
 
     let examples_path = chat_root.join("prompts").join("examples.json");
@@ -319,7 +476,7 @@ async fn generate_examples_from_data<L: EvolutionLlm>(
     };
 
     if existing_examples.len() >= 25 {
-        return Ok(Vec::new());
+        return Ok(Default::default());
     }
 
     let existing_summary = existing_examples
@@ -339,45 +496,45 @@ async fn generate_examples_from_data<L: EvolutionLlm>(
         .replace("{{elapsed_ms}}", &elapsed_ms.to_string());
 
     let messages = vec![EvolutionMessage::user(&prompt)];
-    let content = llm
-        .complete(&messages, model, 0.3)
-        .await?
-        .visible
-        .trim()
-        .to_string();
-
-    let example = match parse_example_response(&content) {
-        Ok(ex) => ex,
-        Err(e) => {
-            let detail = format!("{} — raw: {:.200}", e, content);
-            tracing::warn!("Failed to parse LLM example output: {}", detail);
-            let _ = block_in_place(|| {
-                let conn = crate::feedback::open_evolution_db(chat_root)?;
-                let _ = crate::log_evolution_event(
-                    &conn,
-                    chat_root,
-                    "example_generation_parse_failed",
-                    "",
-                    &detail,
-                    "",
-                );
-                Ok::<_, anyhow::Error>(())
-            });
-            return Ok(Vec::new());
-        }
-    };
+    let policy = LlmRetryPolicy::from_env();
+    let example =
+        match complete_parsed(llm, &messages, model, 0.3, &policy, parse_example_response).await {
+            Ok(ex) => ex,
+            Err(crate::Error::Validation(detail)) => {
+                tracing::warn!("Failed to parse LLM example output: {}", detail);
+                let _ = block_in_place(|| {
+                    let conn = crate::feedback::open_evolution_db(chat_root)?;
+                    let _ = crate::log_evolution_event(
+                        &conn,
+                        chat_root,
+                        "example_generation_parse_failed",
+                        "",
+                        &detail,
+                        "",
+                    );
+                    Ok::<_, anyhow::Error>(())
+                });
+                return Ok(Default::default());
+            }
+            Err(e) => return Err(e),
+        };
     let example = match example {
         Some(e) => e,
-        None => return Ok(Vec::new()),
+        None => return Ok(Default::default()),
     };
+    if existing_examples.iter().any(|e| e.id == example.id) {
+        return Ok(Default::default());
+    }
 
     let combined = format!(
         "{} {} {}",
         example.task_pattern, example.plan_template, example.key_insight
     );
-    if let Err(e) = gatekeeper_l3_content(&combined) {
-        tracing::warn!("L3 rejected example {}: {}", example.id, e);
-        return Ok(Vec::new());
+    let mut proposed = ChangeSet::new(ChangeKind::Example);
+    proposed.propose(example.id.clone(), 1.0, &combined, example);
+    let applied = proposed.finish();
+    if applied.accepted.is_empty() {
+        return Ok((Vec::new(), applied.dropped));
     }
 
     if !gatekeeper_l1_path(chat_root, &examples_path, None) {
@@ -385,18 +542,17 @@ async fn generate_examples_from_data<L: EvolutionLlm>(
     }
 
     let mut all_examples = existing_examples;
-    if all_examples.iter().any(|e| e.id == example.id) {
-        return Ok(Vec::new());
+    let mut changes = Vec::new();
+    for example in applied.accepted {
+        tracing::info!("Added new example: {}", example.id);
+        changes.push(("example_added".to_string(), example.id.clone()));
+        all_examples.push(example);
     }
 
-    let change_id = example.id.clone();
-    all_examples.push(example);
-
     let json = serde_json::to_string_pretty(&all_examples)?;
-    atomic_write(&examples_path, &json)?;
-    tracing::info!("Added new example: {}", change_id);
+    txn.write(&examples_path, &json)?;
 
-    Ok(vec![("example_added".to_string(), change_id)])
+    Ok((changes, applied.dropped))
 }
 
 fn parse_example_response(content: &str) -> Result<Option<PlanningExample>> {
@@ -446,9 +602,223 @@ fn parse_example_response(content: &str) -> Result<Option<PlanningExample>> {
         plan_template,
         key_insight,
         origin: "evolved".to_string(),
+        match_count: 0,
+        success_count: 0,
+        quality_score: None,
+        retired_at: None,
     }))
 }
 
+// ─── Example lifecycle ───────────────────────────────────────────────────────
+
+pub(crate) fn examples_path(chat_root: &Path) -> std::path::PathBuf {
+    chat_root.join("prompts").join("examples.json")
+}
+
+pub(crate) fn examples_archive_path(chat_root: &Path) -> std::path::PathBuf {
+    chat_root.join("prompts").join("examples_archive.json")
+}
+
+pub(crate) fn read_examples(path: &Path) -> Vec<PlanningExample> {
+    skilllite_fs::read_file(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Rough prompt cost: ~4 ASCII chars per token, one token per non-ASCII char.
+fn estimate_example_tokens(example: &PlanningExample) -> usize {
+    [
+        &example.task_pattern,
+        &example.plan_template,
+        &example.key_insight,
+    ]
+    .iter()
+    .map(|s| {
+        let ascii = s.chars().filter(char::is_ascii).count();
+        ascii.div_ceil(4) + (s.chars().count() - ascii)
+    })
+    .sum()
+}
+
+fn pattern_tokens(text: &str) -> std::collections::HashSet<String> {
+    let lower = text.to_lowercase();
+    let mut tokens: std::collections::HashSet<String> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.is_ascii() && w.len() >= 3)
+        .map(str::to_string)
+        .collect();
+    let cjk: Vec<char> = lower
+        .chars()
+        .filter(|c| !c.is_ascii() && c.is_alphanumeric())
+        .collect();
+    tokens.extend(cjk.windows(2).map(|w| w.iter().collect::<String>()));
+    tokens
+}
+
+/// Whether a task description exercises the example's `task_pattern`.
+fn example_matches_task(pattern: &std::collections::HashSet<String>, task: &str) -> bool {
+    if pattern.is_empty() {
+        return false;
+    }
+    let task = pattern_tokens(task);
+    let hits = pattern.iter().filter(|t| task.contains(*t)).count();
+    hits as f32 / pattern.len() as f32 >= EXAMPLE_MATCH_MIN_OVERLAP
+}
+
+/// Quality in [0, 1]: half smoothed success rate, half usage (saturating). Unused examples
+/// score 0.25; a frequently matched example that keeps failing drops below that.
+fn example_quality(match_count: u32, success_count: u32) -> f32 {
+    let success_rate = (success_count as f32 + 1.0) / (match_count as f32 + 2.0);
+    let usage = match_count.min(EXAMPLE_USAGE_SATURATION) as f32 / EXAMPLE_USAGE_SATURATION as f32;
+    0.5 * success_rate + 0.5 * usage
+}
+
+/// Attribute recent decisions to examples by task-pattern match and refresh their scores.
+fn score_examples_with_conn(conn: &Connection, examples: &mut [PlanningExample]) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "SELECT task_description, task_completed, feedback FROM decisions
+         WHERE task_description IS NOT NULL AND ts > datetime('now', ?1)",
+    )?;
+    let window = format!("-{} days", EXAMPLE_USAGE_WINDOW_DAYS);
+    let tasks: Vec<(String, bool)> = stmt
+        .query_map(params![window], |row| {
+            let completed: bool = row.get(1)?;
+            let feedback: Option<String> = row.get(2)?;
+            Ok((
+                row.get::<_, String>(0)?,
+                completed && feedback.as_deref() != Some("neg"),
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    for example in examples.iter_mut() {
+        let pattern = pattern_tokens(&example.task_pattern);
+        let (mut matched, mut succeeded) = (0u32, 0u32);
+        for (task, success) in &tasks {
+            if example_matches_task(&pattern, task) {
+                matched += 1;
+                succeeded += u32::from(*success);
+            }
+        }
+        example.match_count = matched;
+        example.success_count = succeeded;
+        example.quality_score = Some(example_quality(matched, succeeded));
+    }
+    Ok(())
+}
+
+/// Score examples and, while `examples.json` exceeds the token budget, archive the
+/// lowest-quality evolved examples (at most `gatekeeper_l2_example_retirements()` per cycle).
+fn retire_stale_examples_with_conn(
+    chat_root: &Path,
+    txn_id: &str,
+    conn: &Connection,
+) -> Result<Vec<(String, String)>> {
+    let path = examples_path(chat_root);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut examples = read_examples(&path);
+    if examples.is_empty() {
+        return Ok(Vec::new());
+    }
+    score_examples_with_conn(conn, &mut examples)?;
+
+    let budget = prompt_example_token_budget();
+    let mut total: usize = examples.iter().map(estimate_example_tokens).sum();
+    let max_retire = crate::gatekeeper_l2_example_retirements();
+
+    // Lowest score first; ties retire the oldest (earliest in file) first
+    let mut candidates: Vec<usize> = (0..examples.len())
+        .filter(|&i| examples[i].origin != "seed")
+        .collect();
+    candidates.sort_by(|&a, &b| {
+        let sa = examples[a].quality_score.unwrap_or(0.0);
+        let sb = examples[b].quality_score.unwrap_or(0.0);
+        sa.partial_cmp(&sb)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.cmp(&b))
+    });
+
+    let mut retire_idx = Vec::new();
+    for i in candidates {
+        if total <= budget || retire_idx.len() >= max_retire {
+            break;
+        }
+        total -= estimate_example_tokens(&examples[i]);
+        retire_idx.push(i);
+    }
+
+    if !gatekeeper_l1_path(chat_root, &path, None) {
+        bail!("Gatekeeper L1: examples.json path outside allowed directories");
+    }
+    let txn = TxnWrites::new(chat_root, txn_id);
+    if retire_idx.is_empty() {
+        // Persist refreshed usage stats only
+        txn.write(&path, &serde_json::to_string_pretty(&examples)?)?;
+        return Ok(Vec::new());
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let archive_path = examples_archive_path(chat_root);
+    let mut archive = read_examples(&archive_path);
+    let mut changes = Vec::new();
+    let mut kept = Vec::with_capacity(examples.len());
+    for (i, mut example) in examples.into_iter().enumerate() {
+        if !retire_idx.contains(&i) {
+            kept.push(example);
+            continue;
+        }
+        let reason = format!(
+            "quality {:.2} (matched {}, succeeded {} in {}d); examples over {} token budget",
+            example.quality_score.unwrap_or(0.0),
+            example.match_count,
+            example.success_count,
+            EXAMPLE_USAGE_WINDOW_DAYS,
+            budget
+        );
+        let _ = crate::log_evolution_event(
+            conn,
+            chat_root,
+            "example_retired",
+            &example.id,
+            &reason,
+            txn_id,
+        );
+        tracing::info!("Archived example '{}': {}", example.id, reason);
+        changes.push(("example_retired".to_string(), example.id.clone()));
+        example.retired_at = Some(now.clone());
+        archive.retain(|a| a.id != example.id);
+        archive.push(example);
+    }
+
+    txn.write(&archive_path, &serde_json::to_string_pretty(&archive)?)?;
+    txn.write(&path, &serde_json::to_string_pretty(&kept)?)?;
+    Ok(changes)
+}
+
+/// Move an archived example back into `examples.json` (re-promotion). Returns false when
+/// `id` is not in the archive.
+pub fn restore_archived_example(chat_root: &Path, id: &str) -> Result<bool> {
+    let archive_path = examples_archive_path(chat_root);
+    let mut archive = read_examples(&archive_path);
+    let Some(pos) = archive.iter().position(|e| e.id == id) else {
+        return Ok(false);
+    };
+    let mut example = archive.remove(pos);
+    example.retired_at = None;
+
+    let path = examples_path(chat_root);
+    let mut examples = read_examples(&path);
+    examples.retain(|e| e.id != id);
+    examples.push(example);
+    atomic_write(&path, &serde_json::to_string_pretty(&examples)?)?;
+    atomic_write(&archive_path, &serde_json::to_string_pretty(&archive)?)?;
+    Ok(true)
+}
+
 /// Retire rules with effectiveness below threshold and sufficient trigger history.
 /// Only mutable (evolved/external) rules are retired; seed rules are preserved.
 /// Returns `(change_type, rule_id)` pairs for changelog.
@@ -514,8 +884,7 @@ fn retire_low_effectiveness_rules_with_conn(
         return Ok(Vec::new());
     }
 
-    let json = serde_json::to_string_pretty(&kept)?;
-    atomic_write(&rules_path, &json)?;
+    seed::write_rules_checked(&rules_path, &kept, Some(TxnWrites::new(chat_root, txn_id)))?;
 
     Ok(to_retire)
 }
@@ -561,8 +930,7 @@ pub fn update_reusable_status(conn: &Connection, chat_root: &Path) -> Result<()>
     }
 
     if changed {
-        let json = serde_json::to_string_pretty(&rules)?;
-        atomic_write(&rules_path, &json)?;
+        seed::write_rules_checked(&rules_path, &rules, None)?;
     }
 
     Ok(())
@@ -603,7 +971,7 @@ pub(crate) fn decision_ids_read_for_prompt_evolution(conn: &Connection) -> Resul
     )?;
     push_ids(
         conn,
-        "evolved = 0 AND (replans > 0 OR failed_tools > 0)",
+        "evolved = 0 AND (replans > 0 OR failed_tools > 0 OR tool_loops > 0)",
         rule_limit,
         &mut seen,
         &mut out,
@@ -624,15 +992,44 @@ pub(crate) fn decision_ids_read_for_prompt_evolution(conn: &Connection) -> Resul
     Ok(out)
 }
 
+/// Tool/argument-shape patterns that most often appear in failed decisions.
+fn query_tool_failure_patterns(conn: &Connection) -> Result<String> {
+    let patterns = crate::feedback::failure_correlated_tool_patterns(conn, 3, 5)?;
+    let rows: Vec<String> = patterns
+        .iter()
+        .filter(|p| p.failure_rate >= 0.5)
+        .map(|p| {
+            let args = if p.args_present.is_empty() {
+                "(无参数)".to_string()
+            } else {
+                p.args_present.join(", ")
+            };
+            format!(
+                "- {} [参数: {}] | 调用: {} | 所在任务失败率: {:.0}% | 工具报错: {}",
+                p.tool,
+                args,
+                p.calls,
+                p.failure_rate * 100.0,
+                p.tool_failures
+            )
+        })
+        .collect();
+    Ok(rows.join("\n"))
+}
+
 fn query_decisions_summary(conn: &Connection, successful: bool, limit: i64) -> Result<String> {
     let condition = if successful {
         "evolved = 0 AND task_completed = 1 AND replans = 0 AND failed_tools = 0"
     } else {
-        "evolved = 0 AND (replans > 0 OR failed_tools > 0)"
+        "evolved = 0 AND (replans > 0 OR failed_tools > 0 OR tool_loops > 0)"
     };
 
     let sql = format!(
-        "SELECT task_description, total_tools, failed_tools, replans, elapsed_ms
+        "SELECT task_description, total_tools, failed_tools, replans, elapsed_ms, replan_reasons,
+                workspace,
+                (SELECT GROUP_CONCAT(DISTINCT t.skill) FROM decision_tools t
+                 WHERE t.decision_id = decisions.id AND t.skill IS NOT NULL),
+                tool_loops
          FROM decisions WHERE {} AND task_description IS NOT NULL
          ORDER BY ts DESC LIMIT {}",
         condition, limit
@@ -646,10 +1043,33 @@ fn query_decisions_summary(conn: &Connection, successful: bool, limit: i64) -> R
             let failed: i64 = row.get(2)?;
             let replans: i64 = row.get(3)?;
             let elapsed: i64 = row.get(4)?;
-            Ok(format!(
+            let reasons: Vec<String> = row
+                .get::<_, Option<String>>(5)?
+                .and_then(|j| serde_json::from_str(&j).ok())
+                .unwrap_or_default();
+            let mut line = format!(
                 "- 任务: {} | 工具调用: {} (失败: {}) | replan: {} | 耗时: {}ms",
                 desc, total, failed, replans, elapsed
-            ))
+            );
+            if !reasons.is_empty() {
+                line.push_str(&format!(" | replan 原因: {}", reasons.join("; ")));
+            }
+            let loops: i64 = row.get::<_, Option<i64>>(8)?.unwrap_or(0);
+            if loops > 0 {
+                line.push_str(&format!(" | 重复调用循环: {}", loops));
+            }
+            // Directory name only: enough for a `workspace_glob` scope, no home path in the prompt.
+            if let Some(ws) = row
+                .get::<_, Option<String>>(6)?
+                .as_deref()
+                .and_then(|w| Path::new(w).file_name())
+            {
+                line.push_str(&format!(" | 工作区: {}", ws.to_string_lossy()));
+            }
+            if let Some(skills) = row.get::<_, Option<String>>(7)? {
+                line.push_str(&format!(" | 技能: {}", skills));
+            }
+            Ok(line)
         })?
         .filter_map(|r| r.ok())
         .collect();
@@ -660,6 +1080,11 @@ fn query_decisions_summary(conn: &Connection, successful: bool, limit: i64) -> R
 pub fn extract_json_block(content: &str) -> String {
     let content = crate::strip_think_blocks(content.trim());
 
+    // A bare JSON reply may carry ``` fences inside its string values (e.g. SKILL.md examples).
+    if content.starts_with('{') && serde_json::from_str::<serde_json::Value>(content).is_ok() {
+        return content.to_string();
+    }
+
     if let Some(start) = content.find("```json") {
         let json_start = start + 7;
         if let Some(end) = content[json_start..].find("```") {
@@ -697,6 +1122,12 @@ mod extract_json_tests {
         assert_eq!(extract_json_block(s), "{\"a\":1}");
     }
 
+    #[test]
+    fn extract_json_block_bare_json_with_fences_in_strings() {
+        let s = "{\"md\": \"```json\\n{}\\n```\"}";
+        assert_eq!(extract_json_block(s), s);
+    }
+
     #[test]
     fn extract_json_block_brace_span() {
         let s = "prefix {\"x\": true} suffix";
@@ -709,3 +1140,331 @@ mod extract_json_tests {
         assert_eq!(extract_json_block(s), "{\"k\":\"v\"}");
     }
 }
+
+#[cfg(test)]
+mod rule_extraction_tests {
+    use super::*;
+
+    #[test]
+    fn scope_is_parsed_and_blank_scope_dropped() {
+        let content = r#"{"rules": [
+            {"id": "evo_venv_first", "instruction": "Create a venv before pip install",
+             "priority": 60, "keywords": ["install"],
+             "scope": {"workspace_glob": " data-* ", "language": "Python", "requires_skill": null,
+                       "task_keywords": ["pip", " "]}},
+            {"id": "evo_read_first", "instruction": "Read the file before editing it",
+             "scope": {"workspace_glob": "", "language": null, "task_keywords": []}}
+        ]}"#;
+        let rules = parse_rule_extraction_response(content).unwrap();
+        assert_eq!(
+            rules[0].0.scope,
+            Some(RuleScope {
+                workspace_glob: Some("data-*".to_string()),
+                language: Some("python".to_string()),
+                requires_skill: None,
+                task_keywords: vec!["pip".to_string()],
+            })
+        );
+        assert_eq!(rules[1].0.scope, None);
+    }
+}
+
+#[cfg(test)]
+mod example_lifecycle_tests {
+    use super::*;
+
+    fn example(id: &str, task_pattern: &str) -> PlanningExample {
+        PlanningExample {
+            id: id.to_string(),
+            task_pattern: task_pattern.to_string(),
+            plan_template: "step ".repeat(400),
+            key_insight: String::new(),
+            origin: "evolved".to_string(),
+            match_count: 0,
+            success_count: 0,
+            quality_score: None,
+            retired_at: None,
+        }
+    }
+
+    #[test]
+    fn quality_rewards_successful_usage() {
+        assert!(example_quality(10, 10) > example_quality(0, 0));
+        assert!(example_quality(10, 0) < example_quality(10, 10));
+        assert!((example_quality(0, 0) - 0.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn over_budget_examples_are_archived_lowest_quality_first() {
+        let tmp = tempfile::TempDir::new().expect("tempdir");
+        let chat_root = tmp.path();
+        std::fs::create_dir_all(chat_root.join("prompts")).expect("prompts dir");
+        let examples = vec![
+            example("ex_used", "deploy docker compose stack"),
+            example("ex_a", "convert spreadsheet invoices"),
+            example("ex_b", "summarize meeting transcripts"),
+            example("ex_c", "resize product photos"),
+            example("ex_d", "translate release notes"),
+        ];
+        atomic_write(
+            &examples_path(chat_root),
+            &serde_json::to_string(&examples).expect("serialize"),
+        )
+        .expect("write examples");
+
+        let conn = crate::feedback::open_evolution_db(chat_root).expect("db");
+        for _ in 0..3 {
+            conn.execute(
+                "INSERT INTO decisions (task_completed, task_description) VALUES (1, ?1)",
+                params!["Deploy the docker compose stack to staging"],
+            )
+            .expect("insert decision");
+        }
+
+        let changes = retire_stale_examples_with_conn(chat_root, "txn1", &conn).expect("retire");
+        assert_eq!(changes.len(), 3);
+        assert!(changes.iter().all(|(t, _)| t == "example_retired"));
+
+        let kept = read_examples(&examples_path(chat_root));
+        assert!(kept.iter().any(|e| e.id == "ex_used" && e.match_count == 3));
+        let archive = read_examples(&examples_archive_path(chat_root));
+        assert_eq!(archive.len(), 3);
+        assert!(archive.iter().all(|e| e.retired_at.is_some()));
+        assert!(!archive.iter().any(|e| e.id == "ex_used"));
+
+        let restored_id = archive[0].id.clone();
+        assert!(restore_archived_example(chat_root, &restored_id).expect("restore"));
+        assert!(read_examples(&examples_path(chat_root))
+            .iter()
+            .any(|e| e.id == restored_id && e.retired_at.is_none()));
+        assert_eq!(read_examples(&examples_archive_path(chat_root)).len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod holdout_tests {
+    use super::*;
+    use crate::EvolutionLlmOutput;
+
+    /// Plans with `candidate_plan` when the planning prompt carries the candidate rule.
+    struct PlannerLlm {
+        baseline_plan: &'static str,
+        candidate_plan: &'static str,
+    }
+
+    #[async_trait::async_trait]
+    impl EvolutionLlm for PlannerLlm {
+        async fn complete(
+            &self,
+            messages: &[EvolutionMessage],
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<EvolutionLlmOutput> {
+            let system = messages[0].content.as_deref().unwrap_or_default();
+            let plan = if system.contains("always export csv via pandas") {
+                self.candidate_plan
+            } else {
+                self.baseline_plan
+            };
+            Ok(EvolutionLlmOutput {
+                visible: plan.to_string(),
+                assistant_content: Some(plan.to_string()),
+                assistant_reasoning: None,
+            })
+        }
+    }
+
+    fn stage_candidate(chat_root: &Path) -> Vec<(String, String)> {
+        std::fs::create_dir_all(chat_root.join("prompts")).unwrap();
+        let conn = crate::feedback::open_evolution_db(chat_root).unwrap();
+        conn.execute(
+            "INSERT INTO decisions (task_completed, task_description, tools_detail)
+             VALUES (1, 'export the csv report', '[{\"tool\":\"read_file\",\"success\":true}]'),
+                    (1, 'write a poem', NULL)",
+            [],
+        )
+        .unwrap();
+        let mut rules = crate::seed::load_rules(chat_root);
+        let mut new_rule = rules[0].clone();
+        new_rule.id = "evo_csv_pandas".to_string();
+        new_rule.keywords = vec!["csv".to_string()];
+        new_rule.context_keywords = Vec::new();
+        new_rule.instruction = "always export csv via pandas".to_string();
+        rules.push(new_rule);
+        prompt_eval::write_candidate_rules(chat_root, &rules).unwrap();
+        vec![("rule_added".to_string(), "evo_csv_pandas".to_string())]
+    }
+
+    fn live_rule_ids(chat_root: &Path) -> Vec<String> {
+        crate::seed::load_rules(chat_root)
+            .into_iter()
+            .map(|r| r.id)
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn candidate_scoring_no_worse_is_promoted() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let chat_root = tmp.path();
+        let changes = stage_candidate(chat_root);
+        let llm = PlannerLlm {
+            baseline_plan: r#"[{"id":1,"description":"read","tool_hint":"file_read"}]"#,
+            candidate_plan: r#"[{"id":1,"description":"export","tool_hint":"command"}]"#,
+        };
+
+        let (changes, evaluation) = evaluate_rule_candidate(
+            TxnWrites::new(chat_root, "evo_test"),
+            &llm,
+            "m",
+            PromptEvaluationMode::Cheap,
+            changes,
+        )
+        .await;
+
+        let evaluation = evaluation.unwrap();
+        assert!(evaluation.promoted());
+        assert_eq!((evaluation.replayed, evaluation.unaffected), (1, 1));
+        assert_eq!(evaluation.samples[0].task, "export the csv report");
+        assert_eq!(changes[0].0, "rule_added");
+        assert!(live_rule_ids(chat_root).contains(&"evo_csv_pandas".to_string()));
+        assert!(!prompt_eval::candidate_dir(chat_root).exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn candidate_scoring_worse_is_rejected_and_discarded() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let chat_root = tmp.path();
+        let changes = stage_candidate(chat_root);
+        let llm = PlannerLlm {
+            baseline_plan: r#"[{"id":1,"description":"read","tool_hint":"file_read"}]"#,
+            candidate_plan: "I would use the pandas_magic skill",
+        };
+
+        let (changes, evaluation) = evaluate_rule_candidate(
+            TxnWrites::new(chat_root, "evo_test"),
+            &llm,
+            "m",
+            PromptEvaluationMode::Cheap,
+            changes,
+        )
+        .await;
+
+        let evaluation = evaluation.unwrap();
+        assert_eq!(evaluation.verdict, prompt_eval::VERDICT_REJECTED);
+        assert_eq!(
+            (evaluation.baseline_score, evaluation.candidate_score),
+            (1.0, 0.0)
+        );
+        assert_eq!(
+            changes,
+            vec![(
+                "rule_candidate_rejected".to_string(),
+                "evo_csv_pandas".to_string()
+            )]
+        );
+        assert!(!live_rule_ids(chat_root).contains(&"evo_csv_pandas".to_string()));
+        assert!(!prompt_eval::candidate_dir(chat_root).exists());
+    }
+}
+
+#[cfg(test)]
+mod change_limit_tests {
+    use super::*;
+    use crate::EvolutionLlmOutput;
+
+    /// Answers rule extraction with a fixed response.
+    struct FixedLlm(String);
+
+    #[async_trait::async_trait]
+    impl EvolutionLlm for FixedLlm {
+        async fn complete(
+            &self,
+            _messages: &[EvolutionMessage],
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<EvolutionLlmOutput> {
+            Ok(EvolutionLlmOutput {
+                visible: self.0.clone(),
+                assistant_content: Some(self.0.clone()),
+                assistant_reasoning: None,
+            })
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rules_over_l2_limit_keep_top_confidence_and_report_the_rest() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let chat_root = tmp.path();
+        crate::seed::ensure_seed_data(chat_root);
+        let seed_count = crate::seed::load_rules(chat_root).len();
+        let confidences = [0.3, 0.9, 0.1, 0.8, 0.7, 0.2, 0.95, 0.6];
+        let rules: Vec<serde_json::Value> = confidences
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                serde_json::json!({
+                    "id": format!("evo_rule_{}", i),
+                    "priority": 65,
+                    "keywords": ["report"],
+                    "instruction": format!("step {} before writing the report", i),
+                    "confidence": c,
+                })
+            })
+            .collect();
+        let llm = FixedLlm(serde_json::json!({ "rules": rules }).to_string());
+
+        let (changes, dropped) = extract_rules_from_data(
+            TxnWrites::new(chat_root, "evo_test"),
+            ("weekly report ok".to_string(), String::new()),
+            &llm,
+            "m",
+            PromptEvaluationMode::Off,
+        )
+        .await
+        .unwrap();
+
+        let kept = [
+            "evo_rule_1",
+            "evo_rule_3",
+            "evo_rule_4",
+            "evo_rule_6",
+            "evo_rule_7",
+        ];
+        let added: Vec<&str> = changes.iter().map(|(_, id)| id.as_str()).collect();
+        assert_eq!(added, kept);
+        let live = crate::seed::load_rules(chat_root);
+        assert_eq!(live.len(), seed_count + 5);
+        assert!(kept.iter().all(|id| live.iter().any(|r| r.id == *id)));
+
+        let dropped_ids: Vec<&str> = dropped.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(dropped_ids, ["evo_rule_0", "evo_rule_2", "evo_rule_5"]);
+        assert!(dropped.iter().all(DroppedChange::deferred));
+    }
+}
+
+#[cfg(test)]
+mod decision_summary_tests {
+    use super::*;
+
+    #[test]
+    fn looping_decisions_count_as_failed_and_report_the_loops() {
+        let tmp = tempfile::TempDir::new().expect("tempdir");
+        let conn = crate::feedback::open_evolution_db(tmp.path()).expect("db");
+        conn.execute(
+            "INSERT INTO decisions (task_completed, total_tools, task_description, tool_loops)
+             VALUES (1, 4, 'Fix the flaky parser test', 2)",
+            [],
+        )
+        .expect("insert decision");
+
+        let failed = query_decisions_summary(&conn, false, 10).expect("failed summary");
+        assert!(failed.contains("Fix the flaky parser test"));
+        assert!(failed.contains("重复调用循环: 2"));
+        assert_eq!(
+            decision_ids_read_for_prompt_evolution(&conn)
+                .expect("ids")
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Record/replay [`EvolutionLlm`] backends for running evolution cycles offline.
+//!
+//! [`RecordingLlm`] wraps a real client and writes every call to `<dir>/<key>.json`, where the
+//! key hashes the role and content of each message (model and temperature are ignored).
+//! [`ReplayLlm`] serves those fixtures back and fails on a miss. With fuzzy matching enabled a
+//! miss falls back to the recorded transcript best covered by the request, so small prompt
+//! template edits do not force a re-recording.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use skilllite_fs::atomic_write;
+
+use crate::error::bail;
+use crate::{EvolutionLlm, EvolutionLlmOutput, EvolutionMessage, Result};
+
+/// Minimum share of a fixture's tokens that must appear in the request for a fuzzy hit.
+pub const FUZZY_MIN_COVERAGE: f32 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// One recorded completion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmFixture {
+    pub key: String,
+    #[serde(default)]
+    pub recorded_at: String,
+    #[serde(default)]
+    pub model: String,
+    pub messages: Vec<FixtureMessage>,
+    pub response: String,
+    #[serde(default)]
+    pub assistant_content: Option<String>,
+}
+
+impl LlmFixture {
+    fn output(&self) -> EvolutionLlmOutput {
+        EvolutionLlmOutput {
+            visible: self.response.clone(),
+            assistant_content: Some(
+                self.assistant_content
+                    .clone()
+                    .unwrap_or_else(|| self.response.clone()),
+            ),
+            assistant_reasoning: None,
+        }
+    }
+}
+
+/// Stable FNV-1a hash of the conversation (roles and contents only).
+pub fn messages_key(messages: &[EvolutionMessage]) -> String {
+    let mut hash = 0xcbf29ce484222325u64;
+    for msg in messages {
+        let content = msg.content.as_deref().unwrap_or("");
+        for byte in msg
+            .role
+            .as_bytes()
+            .iter()
+            .chain(b"\n")
+            .chain(content.as_bytes())
+            .chain(b"\0")
+        {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+fn fixture_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}.json"))
+}
+
+// ─── Recording ───────────────────────────────────────────────────────────────
+
+/// Passes calls through to `inner` and saves each response as a fixture in `dir`.
+pub struct RecordingLlm<L> {
+    inner: L,
+    dir: PathBuf,
+}
+
+impl<L: EvolutionLlm> RecordingLlm<L> {
+    pub fn new(inner: L, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<L: EvolutionLlm> EvolutionLlm for RecordingLlm<L> {
+    async fn complete(
+        &self,
+        messages: &[EvolutionMessage],
+        model: &str,
+        temperature: f64,
+    ) -> Result<EvolutionLlmOutput> {
+        let out = self.inner.complete(messages, model, temperature).await?;
+        let key = messages_key(messages);
+        let fixture = LlmFixture {
+            key: key.clone(),
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            model: model.to_string(),
+            messages: messages
+                .iter()
+                .map(|m| FixtureMessage {
+                    role: m.role.clone(),
+                    content: m.content.clone(),
+                })
+                .collect(),
+            response: out.visible.clone(),
+            assistant_content: out.assistant_content.clone(),
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        atomic_write(
+            &fixture_path(&self.dir, &key),
+            &serde_json::to_string_pretty(&fixture)?,
+        )?;
+        Ok(out)
+    }
+}
+
+// ─── Replay ──────────────────────────────────────────────────────────────────
+
+/// Serves responses from fixtures recorded by [`RecordingLlm`].
+pub struct ReplayLlm {
+    fixtures: Vec<LlmFixture>,
+    fuzzy: bool,
+    served: Mutex<Vec<String>>,
+    misses: Mutex<Vec<String>>,
+}
+
+impl ReplayLlm {
+    /// Load every `*.json` fixture in `dir`. Fails when the directory holds none.
+    pub fn open(dir: &Path) -> Result<Self> {
+        let mut fixtures = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let fixture: LlmFixture = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            fixtures.push(fixture);
+        }
+        if fixtures.is_empty() {
+            bail!("No LLM fixtures found in {}", dir.display());
+        }
+        Ok(Self::from_fixtures(fixtures))
+    }
+
+    pub fn from_fixtures(fixtures: Vec<LlmFixture>) -> Self {
+        Self {
+            fixtures,
+            fuzzy: false,
+            served: Mutex::new(Vec::new()),
+            misses: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// On an exact miss, fall back to the closest fixture with the same number of messages.
+    pub fn with_fuzzy_match(mut self, fuzzy: bool) -> Self {
+        self.fuzzy = fuzzy;
+        self
+    }
+
+    /// Keys of the fixtures served so far, in call order.
+    pub fn served(&self) -> Vec<String> {
+        self.served.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Requests that matched no fixture, as `"<key>: <start of last message>"`.
+    pub fn misses(&self) -> Vec<String> {
+        self.misses.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+
+    fn lookup(&self, messages: &[EvolutionMessage]) -> Option<&LlmFixture> {
+        let key = messages_key(messages);
+        if let Some(hit) = self.fixtures.iter().find(|f| f.key == key) {
+            return Some(hit);
+        }
+        if !self.fuzzy {
+            return None;
+        }
+        let request = transcript_tokens(messages.iter().map(|m| m.content.as_deref()));
+        self.fixtures
+            .iter()
+            .filter(|f| f.messages.len() == messages.len())
+            .map(|f| {
+                let tokens = transcript_tokens(f.messages.iter().map(|m| m.content.as_deref()));
+                (f, coverage(&tokens, &request))
+            })
+            .filter(|(_, score)| *score >= FUZZY_MIN_COVERAGE)
+            .max_by(|(a, sa), (b, sb)| {
+                sa.partial_cmp(sb)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.recorded_at.cmp(&b.recorded_at))
+            })
+            .map(|(f, score)| {
+                tracing::debug!("Replay fuzzy hit {} (coverage {:.2})", f.key, score);
+                f
+            })
+    }
+}
+
+#[async_trait::async_trait]
+impl EvolutionLlm for ReplayLlm {
+    async fn complete(
+        &self,
+        messages: &[EvolutionMessage],
+        _model: &str,
+        _temperature: f64,
+    ) -> Result<EvolutionLlmOutput> {
+        match self.lookup(messages) {
+            Some(fixture) => {
+                if let Ok(mut served) = self.served.lock() {
+                    served.push(fixture.key.clone());
+                }
+                Ok(fixture.output())
+            }
+            None => {
+                let key = messages_key(messages);
+                let last = messages
+                    .last()
+                    .and_then(|m| m.content.as_deref())
+                    .unwrap_or("")
+                    .chars()
+                    .take(120)
+                    .collect::<String>();
+                if let Ok(mut misses) = self.misses.lock() {
+                    misses.push(format!("{}: {}", key, last));
+                }
+                Err(crate::Error::Other(anyhow::anyhow!(
+                    "LLM replay miss: no fixture for key {} (last message: {})",
+                    key,
+                    last
+                )))
+            }
+        }
+    }
+}
+
+/// Lowercase ASCII words (2+ chars) plus CJK character bigrams.
+fn transcript_tokens<'a>(contents: impl Iterator<Item = Option<&'a str>>) -> HashSet<String> {
+    let mut tokens = HashSet::new();
+    for text in contents.flatten() {
+        let lower = text.to_lowercase();
+        tokens.extend(
+            lower
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|w| w.len() >= 2)
+                .map(str::to_string),
+        );
+        let cjk: Vec<char> = lower
+            .chars()
+            .filter(|c| ('\u{4e00}'..='\u{9fff}').contains(c))
+            .collect();
+        tokens.extend(cjk.windows(2).map(|w| w.iter().collect::<String>()));
+    }
+    tokens
+}
+
+/// Share of `fixture` tokens present in `request`.
+fn coverage(fixture: &HashSet<String>, request: &HashSet<String>) -> f32 {
+    if fixture.is_empty() {
+        return 0.0;
+    }
+    fixture.intersection(request).count() as f32 / fixture.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoLlm;
+
+    #[async_trait::async_trait]
+    impl EvolutionLlm for EchoLlm {
+        async fn complete(
+            &self,
+            messages: &[EvolutionMessage],
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<EvolutionLlmOutput> {
+            let last = messages
+                .last()
+                .and_then(|m| m.content.clone())
+                .unwrap_or_default();
+            Ok(EvolutionLlmOutput {
+                visible: format!("echo: {}", last),
+                assistant_content: None,
+                assistant_reasoning: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn recorded_calls_replay_exactly_and_misses_fail() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = RecordingLlm::new(EchoLlm, dir.path());
+        let msgs = vec![
+            EvolutionMessage::system("You extract planning rules."),
+            EvolutionMessage::user("Summarize the failed tasks"),
+        ];
+        recorder.complete(&msgs, "model-a", 0.3).await.unwrap();
+
+        let replay = ReplayLlm::open(dir.path()).unwrap();
+        let out = replay.complete(&msgs, "model-b", 0.0).await.unwrap();
+        assert_eq!(out.visible, "echo: Summarize the failed tasks");
+        assert_eq!(replay.served(), vec![messages_key(&msgs)]);
+
+        let other = vec![EvolutionMessage::user("Something else entirely")];
+        let err = replay.complete(&other, "model-a", 0.3).await.unwrap_err();
+        assert!(err.to_string().contains("replay miss"));
+        assert_eq!(replay.misses().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fuzzy_match_tolerates_prompt_drift() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = RecordingLlm::new(EchoLlm, dir.path());
+        let rules = vec![EvolutionMessage::user(
+            "你是规则学习模块。分析以下任务执行记录，提取可复用的规划规则。 output strict json rules",
+        )];
+        let examples = vec![EvolutionMessage::user(
+            "你是示例学习模块。将成功的任务执行过程转化为规划示例。 output strict json example",
+        )];
+        recorder.complete(&rules, "m", 0.3).await.unwrap();
+        recorder.complete(&examples, "m", 0.3).await.unwrap();
+
+        let drifted = vec![EvolutionMessage::user(
+            "你是规则学习模块。请分析以下最近的任务执行记录，提取可复用的规划规则。 output strict json rules only",
+        )];
+        let strict = ReplayLlm::open(dir.path()).unwrap();
+        assert!(strict.complete(&drifted, "m", 0.3).await.is_err());
+
+        let fuzzy = ReplayLlm::open(dir.path()).unwrap().with_fuzzy_match(true);
+        let out = fuzzy.complete(&drifted, "m", 0.3).await.unwrap();
+        assert_eq!(
+            out.visible,
+            format!("echo: {}", rules[0].content.as_deref().unwrap())
+        );
+
+        let two_turns = vec![drifted[0].clone(), EvolutionMessage::user("again")];
+        assert!(fuzzy.complete(&two_turns, "m", 0.3).await.is_err());
+    }
+}
@@ -0,0 +1,313 @@
+//! Rotation of append-only evolution logs and `skilllite evolution gc` retention.
+//!
+//! `evolution.log` and `prompts/_versions/changelog.jsonl` rotate to `<name>.1`, `<name>.2`, …
+//! once they exceed `SKILLLITE_EVO_LOG_MAX_BYTES`; `.1` is always the newest archive. Readers go
+//! through [`read_jsonl_lines`] so rotated history stays visible.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use skilllite_core::config::env_keys::evolution as evo_keys;
+
+use crate::snapshots::versions_dir;
+use crate::Result;
+
+// ─── Rotation ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRotationConfig {
+    /// Live file size that triggers rotation; `0` disables rotation.
+    pub max_bytes: u64,
+    /// Rotated archives kept per log; older ones are deleted on rotation.
+    pub keep_archives: usize,
+}
+
+impl Default for LogRotationConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 5 * 1024 * 1024,
+            keep_archives: 5,
+        }
+    }
+}
+
+impl LogRotationConfig {
+    pub fn from_env() -> Self {
+        let base = Self::default();
+        let parse = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        Self {
+            max_bytes: parse(evo_keys::SKILLLITE_EVO_LOG_MAX_BYTES).unwrap_or(base.max_bytes),
+            keep_archives: parse(evo_keys::SKILLLITE_EVO_LOG_KEEP_ARCHIVES)
+                .map(|v| v as usize)
+                .unwrap_or(base.keep_archives),
+        }
+    }
+}
+
+pub fn evolution_log_path(chat_root: &Path) -> PathBuf {
+    chat_root.join("evolution.log")
+}
+
+pub fn changelog_path(chat_root: &Path) -> PathBuf {
+    versions_dir(chat_root).join("changelog.jsonl")
+}
+
+fn archive_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", index));
+    path.with_file_name(name)
+}
+
+/// Existing rotated archives of `path` as `(index, path)`, newest (`.1`) first.
+pub fn archive_paths(path: &Path) -> Vec<(usize, PathBuf)> {
+    let (Some(dir), Some(base)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", base);
+    let mut archives: Vec<(usize, PathBuf)> = std::fs::read_dir(dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name();
+            let index = name
+                .to_str()?
+                .strip_prefix(&prefix)?
+                .parse::<usize>()
+                .ok()?;
+            (index > 0).then(|| (index, e.path()))
+        })
+        .collect();
+    archives.sort_by_key(|(i, _)| *i);
+    archives
+}
+
+/// Rotate `path` when it exceeds `cfg.max_bytes`. Returns `true` when a rotation happened.
+pub fn rotate_if_needed(path: &Path, cfg: &LogRotationConfig) -> Result<bool> {
+    if cfg.max_bytes == 0 {
+        return Ok(false);
+    }
+    let size = match std::fs::metadata(path) {
+        Ok(m) => m.len(),
+        Err(_) => return Ok(false),
+    };
+    if size <= cfg.max_bytes {
+        return Ok(false);
+    }
+    for (index, archive) in archive_paths(path).into_iter().rev() {
+        if index >= cfg.keep_archives {
+            std::fs::remove_file(&archive)?;
+        } else {
+            std::fs::rename(&archive, archive_path(path, index + 1))?;
+        }
+    }
+    if cfg.keep_archives == 0 {
+        std::fs::remove_file(path)?;
+    } else {
+        std::fs::rename(path, archive_path(path, 1))?;
+    }
+    Ok(true)
+}
+
+/// `path`'s archives (oldest first) followed by `path` itself.
+pub fn chronological_files(path: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = archive_paths(path).into_iter().map(|(_, p)| p).collect();
+    files.reverse();
+    files.push(path.to_path_buf());
+    files
+}
+
+/// All lines of `path` and its archives in chronological order (oldest archive first).
+pub fn read_jsonl_lines(path: &Path) -> Vec<String> {
+    chronological_files(path)
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .flat_map(|text| {
+            text.lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// ─── GC ──────────────────────────────────────────────────────────────────────
+
+/// What `skilllite evolution gc` removed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GcReport {
+    pub archives_removed: usize,
+    pub snapshots_removed: usize,
+    pub log_rows_removed: usize,
+    pub bytes_reclaimed: u64,
+    /// Snapshot of the most recent non-rolled-back txn; never deleted.
+    pub protected_txn: Option<String>,
+}
+
+fn path_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| path_size(&e.path()))
+        .sum()
+}
+
+/// Most recent txn that was not rolled back and still has a snapshot directory.
+fn latest_active_snapshot_txn(conn: &Connection, chat_root: &Path) -> Result<Option<String>> {
+    let vdir = versions_dir(chat_root);
+    let mut stmt = conn.prepare(
+        "SELECT version FROM evolution_log
+         WHERE type NOT LIKE '%_rolled_back' AND version IS NOT NULL AND version <> ''
+         ORDER BY ts DESC",
+    )?;
+    let versions = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    for version in versions.flatten() {
+        if vdir.join(&version).is_dir() {
+            return Ok(Some(version));
+        }
+    }
+    Ok(None)
+}
+
+/// Prune rotated log archives and `evolution_log` rows older than `older_than_days`, and
+/// snapshot directories beyond `snapshot_keep` (`0` = keep all). The snapshot of the most
+/// recent non-rolled-back txn (and its log rows) is always kept.
+pub fn gc(
+    chat_root: &Path,
+    conn: &Connection,
+    older_than_days: i64,
+    snapshot_keep: usize,
+) -> Result<GcReport> {
+    let older_than_days = older_than_days.max(0);
+    let mut report = GcReport {
+        protected_txn: latest_active_snapshot_txn(conn, chat_root)?,
+        ..Default::default()
+    };
+    let cutoff = std::time::SystemTime::now()
+        - std::time::Duration::from_secs(older_than_days as u64 * 86_400);
+
+    for log in [evolution_log_path(chat_root), changelog_path(chat_root)] {
+        for (_, archive) in archive_paths(&log) {
+            let modified = std::fs::metadata(&archive).and_then(|m| m.modified());
+            if modified.map(|t| t < cutoff).unwrap_or(false) {
+                let size = path_size(&archive);
+                std::fs::remove_file(&archive)?;
+                report.archives_removed += 1;
+                report.bytes_reclaimed += size;
+            }
+        }
+    }
+
+    let vdir = versions_dir(chat_root);
+    if snapshot_keep > 0 && vdir.is_dir() {
+        let mut dirs: Vec<_> = std::fs::read_dir(&vdir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .collect();
+        dirs.sort_by_key(|e| e.file_name());
+        let excess = dirs.len().saturating_sub(snapshot_keep);
+        for entry in dirs.into_iter().take(excess) {
+            if report.protected_txn.as_deref() == entry.file_name().to_str() {
+                continue;
+            }
+            let size = path_size(&entry.path());
+            std::fs::remove_dir_all(entry.path())?;
+            report.snapshots_removed += 1;
+            report.bytes_reclaimed += size;
+        }
+    }
+
+    report.log_rows_removed = conn.execute(
+        &format!(
+            "DELETE FROM evolution_log
+             WHERE julianday(ts) < julianday('now', '-{} days')
+               AND (version IS NULL OR version <> ?1)",
+            older_than_days
+        ),
+        params![report.protected_txn.as_deref().unwrap_or("")],
+    )?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback;
+
+    #[test]
+    fn rotation_shifts_archives_and_readers_see_all_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("evolution.log");
+        let cfg = LogRotationConfig {
+            max_bytes: 7,
+            keep_archives: 2,
+        };
+        for i in 0..4 {
+            std::fs::write(&path, format!("{{\"n\":{}}}\n", i)).unwrap();
+            assert!(rotate_if_needed(&path, &cfg).unwrap());
+        }
+        // 7 bytes: at the limit, so no rotation.
+        std::fs::write(&path, "{\"n\":4}").unwrap();
+        assert!(!rotate_if_needed(&path, &cfg).unwrap());
+
+        let archives = archive_paths(&path);
+        assert_eq!(
+            archives.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            read_jsonl_lines(&path),
+            vec!["{\"n\":2}", "{\"n\":3}", "{\"n\":4}"]
+        );
+    }
+
+    #[test]
+    fn gc_keeps_latest_active_snapshot_and_prunes_old_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let chat_root = dir.path();
+        let conn = feedback::open_evolution_db(chat_root).unwrap();
+        let vdir = versions_dir(chat_root);
+        for txn in ["evo_1", "evo_2", "evo_3"] {
+            std::fs::create_dir_all(vdir.join(txn)).unwrap();
+            std::fs::write(vdir.join(txn).join("rules.json"), "[]").unwrap();
+        }
+        // evo_3 was rolled back, so evo_1 is the newest live txn even though it is the oldest dir.
+        conn.execute_batch(
+            "INSERT INTO evolution_log (ts, type, target_id, reason, version) VALUES
+             (datetime('now', '-200 days'), 'rule_added', 'r1', '', 'evo_1'),
+             (datetime('now', '-150 days'), 'rule_added_rolled_back', 'r2', '', 'evo_2'),
+             (datetime('now', '-100 days'), 'rule_added_rolled_back', 'r3', '', 'evo_3'),
+             (datetime('now', '-120 days'), 'evolution_run_outcome', 'run', '', ''),
+             (datetime('now', '-1 days'), 'evolution_run_outcome', 'run', '', '')",
+        )
+        .unwrap();
+        let report = gc(chat_root, &conn, 90, 1).unwrap();
+
+        assert_eq!(report.protected_txn.as_deref(), Some("evo_1"));
+        assert!(vdir.join("evo_1").is_dir());
+        assert!(!vdir.join("evo_2").exists());
+        assert!(vdir.join("evo_3").is_dir());
+        assert_eq!(report.snapshots_removed, 1);
+        assert_eq!(report.log_rows_removed, 3);
+        assert!(report.bytes_reclaimed > 0);
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM evolution_log", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(remaining, 2);
+    }
+}
@@ -5,11 +5,43 @@ use std::path::Path;
 use rusqlite::{params, Connection};
 
 use crate::audit::log_evolution_event;
+use crate::metrics_breakdown::{
+    attribute_degradation, DegradedMetric, CORRECTION_RATE_RISE, SUCCESS_RATE_DROP,
+};
 use crate::snapshots::restore_extended_snapshot;
 use crate::Result;
 
 // ─── Auto-rollback ───────────────────────────────────────────────────────────
 
+/// `evolution_log.type` when a single skill explains a metric decline instead of a rollback.
+pub const SKILL_FLAGGED: &str = "skill_flagged";
+
+/// Log a `skill_flagged` event unless the skill was already flagged today.
+fn flag_skill(conn: &Connection, chat_root: &Path, skill: &str, reason: &str) -> Result<()> {
+    let flagged_today: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM evolution_log
+         WHERE type = ?1 AND target_id = ?2 AND date(ts) = date('now'))",
+        params![SKILL_FLAGGED, skill],
+        |row| row.get(0),
+    )?;
+    if flagged_today {
+        return Ok(());
+    }
+    tracing::warn!(
+        "Evolution rollback suppressed: skill '{}' explains the decline ({})",
+        skill,
+        reason
+    );
+    log_evolution_event(
+        conn,
+        chat_root,
+        SKILL_FLAGGED,
+        skill,
+        &format!("{}; attributed to skill failures, prompts kept", reason),
+        "",
+    )
+}
+
 /// Executes the rollback actions (restoring snapshot, logging).
 fn execute_evolution_rollback(
     conn: &Connection,
@@ -36,6 +68,12 @@ fn execute_evolution_rollback(
     )?;
     Ok(())
 }
+/// Roll back the latest evolution txn when the global rates degrade for 3 consecutive days.
+///
+/// The degradation must be broad-based (see
+/// [`crate::metrics_breakdown::DegradationAttribution::is_broad`]). When a
+/// single failing skill explains it, prompts are kept and a `skill_flagged` event names the
+/// skill instead (once per skill and day).
 pub fn check_auto_rollback(
     conn: &Connection,
     chat_root: &Path,
@@ -56,22 +94,45 @@ pub fn check_auto_rollback(
         return Ok(false);
     }
 
-    let fsr_declining = metrics.windows(2).take(3).all(|w| w[0].1 < w[1].1 - 0.10);
-    let ucr_rising = metrics.windows(2).take(3).all(|w| w[0].2 > w[1].2 + 0.20);
+    let fsr_declining = metrics
+        .windows(2)
+        .take(3)
+        .all(|w| w[0].1 < w[1].1 - SUCCESS_RATE_DROP);
+    let ucr_rising = metrics
+        .windows(2)
+        .take(3)
+        .all(|w| w[0].2 > w[1].2 + CORRECTION_RATE_RISE);
 
     if fsr_declining || ucr_rising {
-        let reason = if fsr_declining {
-            "first_success_rate declined >10% for 3 consecutive days"
+        let (reason, metric) = if fsr_declining {
+            (
+                "first_success_rate declined >10% for 3 consecutive days",
+                DegradedMetric::FirstSuccessRate,
+            )
         } else {
-            "user_correction_rate rose >20% for 3 consecutive days"
+            (
+                "user_correction_rate rose >20% for 3 consecutive days",
+                DegradedMetric::CorrectionRate,
+            )
         };
 
+        let newest = &metrics[0].0;
+        let oldest = &metrics[metrics.len() - 1].0;
+        if let Some(attribution) = attribute_degradation(conn, newest, oldest, metric)? {
+            if !attribution.is_broad() {
+                if let Some(skill) = &attribution.single_skill {
+                    flag_skill(conn, chat_root, skill, reason)?;
+                }
+                return Ok(false);
+            }
+        }
+
         let last_txn: Option<String> = conn
             .query_row(
                 "SELECT DISTINCT version FROM evolution_log
-                 WHERE type NOT LIKE '%_rolled_back'
+                 WHERE type NOT LIKE '%_rolled_back' AND type != ?1
                  ORDER BY ts DESC LIMIT 1",
-                [],
+                params![SKILL_FLAGGED],
                 |row| row.get(0),
             )
             .ok();
@@ -84,3 +145,125 @@ pub fn check_auto_rollback(
 
     Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::{ensure_evolution_tables, rebuild_derived_tables};
+
+    /// One decision `days_ago` in `workspace`; `skill` adds a failed call of that skill.
+    fn seed(conn: &Connection, days_ago: u32, workspace: &str, ok: bool, skill: Option<&str>) {
+        conn.execute(
+            "INSERT INTO decisions (ts, total_tools, failed_tools, task_completed, workspace)
+             VALUES (datetime('now', ?1), 1, ?2, ?3, ?4)",
+            params![format!("-{} days", days_ago), !ok as i64, ok, workspace],
+        )
+        .unwrap();
+        if let Some(skill) = skill {
+            conn.execute(
+                "INSERT INTO decision_tools (decision_id, seq, ts, tool, skill, is_error)
+                 VALUES (?1, 0, datetime('now', ?2), ?3, ?3, 1)",
+                params![
+                    conn.last_insert_rowid(),
+                    format!("-{} days", days_ago),
+                    skill
+                ],
+            )
+            .unwrap();
+        }
+    }
+
+    /// In-memory DB with an evolution txn `evo_1` that has a (empty) snapshot to restore.
+    fn setup() -> (Connection, tempfile::TempDir) {
+        let conn = Connection::open_in_memory().unwrap();
+        ensure_evolution_tables(&conn).unwrap();
+        let chat_root = tempfile::tempdir().unwrap();
+        crate::snapshots::create_snapshot(chat_root.path(), "evo_1", &[]).unwrap();
+        conn.execute(
+            "INSERT INTO evolution_log (ts, type, target_id, version)
+             VALUES (datetime('now', '-4 days'), 'rule_added', 'r1', 'evo_1')",
+            [],
+        )
+        .unwrap();
+        (conn, chat_root)
+    }
+
+    fn events(conn: &Connection, event_type: &str) -> Vec<String> {
+        conn.prepare("SELECT target_id FROM evolution_log WHERE type = ?1")
+            .unwrap()
+            .query_map(params![event_type], |row| row.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap()
+    }
+
+    /// 10 decisions a day in one workspace for 4 days; `failing[i]` of them (days_ago = 3 - i)
+    /// fail, each in `failing_skill` when set.
+    fn seed_decline(conn: &Connection, workspaces: &[&str], failing_skill: Option<&str>) {
+        for (i, failing) in [0, 2, 4, 6].into_iter().enumerate() {
+            let days_ago = 3 - i as u32;
+            for n in 0..10 {
+                let ws = workspaces[n % workspaces.len()];
+                if n < failing {
+                    seed(conn, days_ago, ws, false, failing_skill);
+                } else {
+                    seed(conn, days_ago, ws, true, None);
+                }
+            }
+        }
+        rebuild_derived_tables(conn).unwrap();
+    }
+
+    #[test]
+    fn decline_explained_by_one_skill_flags_it_instead_of_rolling_back() {
+        let (conn, chat_root) = setup();
+        seed_decline(&conn, &["/ws/a"], Some("pdf-tool"));
+
+        assert!(!check_auto_rollback(&conn, chat_root.path(), None).unwrap());
+        assert!(!check_auto_rollback(&conn, chat_root.path(), None).unwrap());
+        assert_eq!(events(&conn, SKILL_FLAGGED), ["pdf-tool"]);
+        assert!(events(&conn, "auto_rollback").is_empty());
+    }
+
+    #[test]
+    fn skill_decline_across_two_workspaces_still_rolls_back() {
+        let (conn, chat_root) = setup();
+        seed_decline(&conn, &["/ws/a", "/ws/b"], Some("pdf-tool"));
+
+        assert!(check_auto_rollback(&conn, chat_root.path(), None).unwrap());
+        assert_eq!(events(&conn, "auto_rollback"), ["evo_1"]);
+        assert!(events(&conn, SKILL_FLAGGED).is_empty());
+    }
+
+    #[test]
+    fn decline_without_a_single_skill_rolls_back() {
+        let (conn, chat_root) = setup();
+        seed_decline(&conn, &["/ws/a"], None);
+
+        assert!(check_auto_rollback(&conn, chat_root.path(), None).unwrap());
+        assert_eq!(events(&conn, "auto_rollback"), ["evo_1"]);
+    }
+
+    #[test]
+    fn breakdown_rows_group_by_workspace_hash_and_skill() {
+        let (conn, _chat_root) = setup();
+        seed_decline(&conn, &["/ws/a", "/ws/b"], Some("pdf-tool"));
+
+        let rows = crate::metrics_breakdown::query_breakdown(&conn, 7).unwrap();
+        let today: Vec<_> = rows.iter().filter(|r| r.date == rows[0].date).collect();
+        let ws_a = today
+            .iter()
+            .find(|r| r.key == crate::metrics_breakdown::workspace_key("/ws/a"))
+            .unwrap();
+        assert_eq!(
+            (ws_a.label.as_str(), ws_a.decisions, ws_a.successes),
+            ("a", 5, 2)
+        );
+        let skill = today.iter().find(|r| r.dimension == "skill").unwrap();
+        assert_eq!(
+            (skill.key.as_str(), skill.decisions, skill.tool_failures),
+            ("pdf-tool", 6, 6)
+        );
+        assert_eq!(skill.first_success_rate, 0.0);
+    }
+}
@@ -0,0 +1,254 @@
+//! EVO-6: conflict detection for externally learned rules.
+//!
+//! Before an external rule is merged into rules.json it is compared with the existing rules.
+//! A candidate that covers the same topic as an existing rule (high token overlap) but points
+//! the other way (negation or antonym) is held in `prompts/pending_conflicts.json` and surfaced
+//! by `skilllite evolution status` instead of being applied.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use skilllite_core::planning::PlanningRule;
+
+use crate::journal::TxnWrites;
+use crate::Result;
+
+/// Token overlap (Jaccard) at or above which two rules address the same topic.
+pub const CONFLICT_SIMILARITY_THRESHOLD: f32 = 0.4;
+
+const PENDING_CONFLICTS_FILE: &str = "pending_conflicts.json";
+
+/// Markers that flip the prescriptive direction of a rule.
+const NEGATION_MARKERS: &[&str] = &[
+    "never",
+    "don't",
+    "do not",
+    "does not",
+    "must not",
+    "should not",
+    "shouldn't",
+    "avoid",
+    "no longer",
+    "不要",
+    "不应",
+    "不得",
+    "禁止",
+    "避免",
+    "切勿",
+    "勿",
+    "从不",
+];
+
+/// Word pairs that prescribe opposite actions.
+const ANTONYMS: &[(&str, &str)] = &[
+    ("always", "never"),
+    ("before", "after"),
+    ("enable", "disable"),
+    ("prefer", "avoid"),
+    ("increase", "decrease"),
+    ("include", "exclude"),
+    ("总是", "从不"),
+    ("之前", "之后"),
+    ("启用", "禁用"),
+    ("优先", "避免"),
+    ("增加", "减少"),
+];
+
+/// Direction words are excluded from similarity so "always X" and "never X" still match.
+const IGNORED_TOKENS: &[&str] = &[
+    "the", "and", "for", "with", "when", "that", "this", "use", "not", "don", "never", "always",
+    "avoid", "prefer", "before", "after", "should", "must", "does",
+];
+
+/// An external rule held back because it contradicts an existing rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRuleConflict {
+    pub rule: PlanningRule,
+    /// Id of the existing rule it contradicts.
+    pub conflicts_with: String,
+    pub conflicting_instruction: String,
+    pub similarity: f32,
+    pub detected_at: String,
+}
+
+pub fn pending_conflicts_path(chat_root: &Path) -> PathBuf {
+    chat_root.join("prompts").join(PENDING_CONFLICTS_FILE)
+}
+
+pub fn load_pending_conflicts(chat_root: &Path) -> Vec<PendingRuleConflict> {
+    std::fs::read_to_string(pending_conflicts_path(chat_root))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn save_pending_conflicts(
+    txn: TxnWrites,
+    conflicts: &[PendingRuleConflict],
+) -> Result<()> {
+    let path = pending_conflicts_path(txn.chat_root);
+    txn.write(&path, &serde_json::to_string_pretty(conflicts)?)
+}
+
+/// First existing rule that `candidate` contradicts, with the topic similarity.
+pub fn find_conflict<'a>(
+    candidate: &PlanningRule,
+    existing: &'a [PlanningRule],
+) -> Option<(&'a PlanningRule, f32)> {
+    let cand_tokens = rule_tokens(candidate);
+    existing
+        .iter()
+        .filter(|r| r.id != candidate.id)
+        .filter_map(|r| {
+            let sim = jaccard(&cand_tokens, &rule_tokens(r));
+            (sim >= CONFLICT_SIMILARITY_THRESHOLD
+                && opposite_direction(&candidate.instruction, &r.instruction))
+            .then_some((r, sim))
+        })
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+fn rule_tokens(rule: &PlanningRule) -> HashSet<String> {
+    let mut tokens = text_tokens(&rule.instruction);
+    for kw in &rule.keywords {
+        tokens.extend(text_tokens(kw));
+    }
+    tokens
+}
+
+/// Lowercase ASCII words (3+ chars) plus CJK character bigrams.
+fn text_tokens(text: &str) -> HashSet<String> {
+    let lower = text.to_lowercase();
+    let mut tokens: HashSet<String> = lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| w.len() >= 3 && !IGNORED_TOKENS.contains(w))
+        .map(str::to_string)
+        .collect();
+    let cjk: Vec<char> = lower.chars().filter(|c| is_cjk(*c)).collect();
+    tokens.extend(cjk.windows(2).map(|w| w.iter().collect::<String>()));
+    tokens
+}
+
+fn is_cjk(c: char) -> bool {
+    ('\u{4e00}'..='\u{9fff}').contains(&c)
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let inter = a.intersection(b).count() as f32;
+    let union = a.union(b).count() as f32;
+    inter / union
+}
+
+fn contains_marker(text: &str, marker: &str) -> bool {
+    if marker.is_ascii() {
+        // Whole-word match for ASCII markers ("not" must not hit "notify")
+        text.match_indices(marker).any(|(i, m)| {
+            let before = text[..i].chars().next_back();
+            let after = text[i + m.len()..].chars().next();
+            !before.is_some_and(|c| c.is_ascii_alphanumeric())
+                && !after.is_some_and(|c| c.is_ascii_alphanumeric())
+        })
+    } else {
+        text.contains(marker)
+    }
+}
+
+fn is_negated(text: &str) -> bool {
+    NEGATION_MARKERS.iter().any(|m| contains_marker(text, m))
+}
+
+/// Heuristic: the two instructions prescribe opposite actions.
+fn opposite_direction(a: &str, b: &str) -> bool {
+    let a = a.to_lowercase();
+    let b = b.to_lowercase();
+    if is_negated(&a) != is_negated(&b) {
+        return true;
+    }
+    ANTONYMS.iter().any(|(x, y)| {
+        (contains_marker(&a, x) && contains_marker(&b, y))
+            || (contains_marker(&a, y) && contains_marker(&b, x))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, instruction: &str, keywords: &[&str]) -> PlanningRule {
+        PlanningRule {
+            id: id.to_string(),
+            priority: 50,
+            keywords: keywords.iter().map(|s| s.to_string()).collect(),
+            context_keywords: vec![],
+            tool_hint: None,
+            instruction: instruction.to_string(),
+            mutable: true,
+            origin: "evolved".to_string(),
+            reusable: false,
+            effectiveness: None,
+            trigger_count: None,
+            provenance: None,
+            scope: None,
+        }
+    }
+
+    #[test]
+    fn detects_negated_rule_on_same_topic() {
+        let existing = vec![
+            rule(
+                "local_run_tests",
+                "Run the test suite before committing changes.",
+                &["test", "commit"],
+            ),
+            rule("local_logs", "Read recent log files first.", &["log"]),
+        ];
+        let candidate = rule(
+            "ext_skip_tests",
+            "Do not run the test suite before committing changes.",
+            &["test", "commit"],
+        );
+        let (hit, sim) = find_conflict(&candidate, &existing).expect("conflict");
+        assert_eq!(hit.id, "local_run_tests");
+        assert!(sim >= CONFLICT_SIMILARITY_THRESHOLD);
+    }
+
+    #[test]
+    fn antonyms_conflict_but_agreeing_or_unrelated_rules_do_not() {
+        let existing = vec![rule(
+            "local_backup",
+            "Always create a backup of config files when editing them.",
+            &["config", "backup"],
+        )];
+        let opposite = rule(
+            "ext_backup",
+            "Never create a backup of config files when editing them.",
+            &["config", "backup"],
+        );
+        assert!(find_conflict(&opposite, &existing).is_some());
+
+        let agreeing = rule(
+            "ext_backup_same",
+            "Always create a backup of config files before editing them.",
+            &["config", "backup"],
+        );
+        assert!(find_conflict(&agreeing, &existing).is_none());
+
+        let unrelated = rule(
+            "ext_docs",
+            "Never paste secrets into documentation examples.",
+            &["docs"],
+        );
+        assert!(find_conflict(&unrelated, &existing).is_none());
+    }
+
+    #[test]
+    fn ascii_markers_match_whole_words_only() {
+        assert!(!is_negated("notify the user when done"));
+        assert!(is_negated("never retry blindly"));
+        assert!(is_negated("不要重复执行"));
+    }
+}
@@ -4,16 +4,21 @@ use std::path::Path;
 
 use rusqlite::{params, Connection};
 
-use crate::audit::{decision_ids_to_mark_after_run, log_evolution_event, mark_decisions_evolved};
+use crate::audit::{
+    decision_ids_to_mark_after_run, expire_stale_decisions, log_evolution_event,
+    mark_decisions_evolved,
+};
+use crate::change_set::DroppedChange;
 use crate::changelog::append_changelog;
-use crate::config::{EvolutionMode, SkillAction};
+use crate::config::{EvolutionMode, EvolutionThresholds, SkillAction};
 use crate::external_learner;
 use crate::feedback;
+use crate::journal::{self, Journal, JournalPhase, JournalRecord};
 use crate::llm::EvolutionLlm;
 use crate::memory_learner;
 use crate::prompt_learner;
 use crate::rollback::check_auto_rollback;
-use crate::run_state::{finish_evolution, try_start_evolution, EvolutionRunResult};
+use crate::run_state::{open_run_lock, try_start_evolution, EvolutionRunResult, EvolutionRunning};
 use crate::scope::{
     auto_link_acceptance_status, build_evolution_proposals, coordinate_proposals,
     describe_empty_evolution_proposals, load_backlog_proposal_by_id,
@@ -31,11 +36,39 @@ fn try_log_evolution_run_outcome(chat_root: &Path, reason: &str) {
     }
 }
 
+/// Maintenance: expire pending decisions past `SKILLLITE_EVO_DECISION_MAX_AGE_DAYS` so scope
+/// queries stop rescanning them, and prune per-call tool rows over the same window. Best
+/// effort; failures are logged only.
+fn expire_stale_pending_decisions(conn: &Connection, chat_root: &Path) {
+    let max_age_days = EvolutionThresholds::from_env().decision_max_age_days;
+    match expire_stale_decisions(conn, max_age_days) {
+        Ok(0) => {}
+        Ok(n) => {
+            let _ = log_evolution_event(
+                conn,
+                chat_root,
+                "decisions_expired",
+                "decisions",
+                &format!(
+                    "{} pending decisions older than {} days expired",
+                    n, max_age_days
+                ),
+                "",
+            );
+        }
+        Err(e) => tracing::warn!("Failed to expire stale decisions: {}", e),
+    }
+    if let Err(e) = feedback::prune_decision_tools(conn, max_age_days) {
+        tracing::warn!("Failed to prune decision_tools: {}", e);
+    }
+}
+
 // ─── Run evolution (main entry point) ──────────────────────────────────────────
 
 /// Run a full evolution cycle.
 ///
-/// Returns [EvolutionRunResult]: SkippedBusy if another run in progress, NoScope if nothing to evolve, Completed(txn_id) otherwise.
+/// Returns [EvolutionRunResult]: SkippedBusy if another run in progress (this process, or another
+/// process holding [`crate::run_state::RUN_LOCK_FILE`]), NoScope if nothing to evolve, Completed(txn_id) otherwise.
 /// When force=true (manual trigger), bypass decision thresholds.
 /// skills_root: project-level skills dir. When None, skips skill evolution.
 pub async fn run_evolution<L: EvolutionLlm>(
@@ -54,11 +87,34 @@ pub async fn run_evolution<L: EvolutionLlm>(
         );
         return Ok(EvolutionRunResult::SkippedBusy);
     }
+    let _running = EvolutionRunning;
+
+    let mut run_lock = match open_run_lock(chat_root) {
+        Ok(lock) => Some(lock),
+        Err(e) => {
+            tracing::warn!("Failed to open evolution run lock: {}", e);
+            None
+        }
+    };
+    let _held = match run_lock.as_mut().map(|lock| lock.try_write()) {
+        Some(Ok(guard)) => Some(guard),
+        Some(Err(e)) if e.kind() == std::io::ErrorKind::WouldBlock => {
+            try_log_evolution_run_outcome(
+                chat_root,
+                "SkippedBusy: another process holds evolution.lock",
+            );
+            return Ok(EvolutionRunResult::SkippedBusy);
+        }
+        Some(Err(e)) => {
+            tracing::warn!("Failed to take evolution run lock: {}", e);
+            None
+        }
+        None => None,
+    };
 
     let result =
         run_evolution_inner(chat_root, skills_root, llm, api_base, api_key, model, force).await;
 
-    finish_evolution();
     if let Err(ref e) = result {
         try_log_evolution_run_outcome(chat_root, &format!("Error: {e}"));
     }
@@ -75,6 +131,10 @@ async fn run_evolution_inner<L: EvolutionLlm>(
     force: bool,
 ) -> Result<EvolutionRunResult> {
     let conn = feedback::open_evolution_db(chat_root)?;
+    if let Err(e) = journal::recover_uncommitted(&conn, chat_root) {
+        tracing::warn!("Failed to recover interrupted evolution txn: {}", e);
+    }
+    expire_stale_pending_decisions(&conn, chat_root);
     let forced_proposal_id =
         std::env::var(skilllite_core::config::env_keys::evolution::SKILLLITE_EVO_FORCE_PROPOSAL_ID)
             .ok()
@@ -234,12 +294,22 @@ async fn run_evolution_inner<L: EvolutionLlm>(
         scope.memory,
         scope.skills,
     )?;
+    let mut journal = Journal::prepare(
+        chat_root,
+        skills_root,
+        &txn_id,
+        scope.prompts,
+        scope.memory,
+        scope.skills,
+    )?;
 
     // Drop conn before async work (Connection is !Send, cannot hold across .await).
     drop(conn);
 
     let mut all_changes: Vec<(String, String)> = Vec::new();
     let mut reason_parts: Vec<String> = Vec::new();
+    let mut prompt_evaluation = None;
+    let mut all_dropped: Vec<DroppedChange> = Vec::new();
 
     // Run prompts / skills / memory evolution in parallel. Each module uses block_in_place
     // to batch its DB operations (one open per module), so we get both parallelism and fewer opens.
@@ -248,7 +318,7 @@ async fn run_evolution_inner<L: EvolutionLlm>(
             if scope.prompts {
                 prompt_learner::evolve_prompts(chat_root, llm, model, &txn_id).await
             } else {
-                Ok(Vec::new())
+                Ok(prompt_learner::PromptEvolution::default())
             }
         },
         async {
@@ -265,7 +335,7 @@ async fn run_evolution_inner<L: EvolutionLlm>(
                 )
                 .await
             } else {
-                Ok(Vec::new())
+                Ok(skill_synth::SkillEvolution::default())
             }
         },
         async {
@@ -279,22 +349,27 @@ async fn run_evolution_inner<L: EvolutionLlm>(
 
     if scope.prompts {
         match prompt_res {
-            Ok(changes) => {
+            Ok(evolution) => {
+                let changes = evolution.changes;
                 if !changes.is_empty() {
                     reason_parts.push(format!("{} prompt changes", changes.len()));
                 }
                 all_changes.extend(changes);
+                all_dropped.extend(evolution.dropped);
+                prompt_evaluation = evolution.evaluation;
             }
             Err(e) => tracing::warn!("Prompt evolution failed: {}", e),
         }
     }
     if scope.skills {
         match skills_res {
-            Ok(changes) => {
+            Ok(evolution) => {
+                let changes = evolution.changes;
                 if !changes.is_empty() {
                     reason_parts.push(format!("{} skill changes", changes.len()));
                 }
                 all_changes.extend(changes);
+                all_dropped.extend(evolution.dropped);
             }
             Err(e) => tracing::warn!("Skill evolution failed: {}", e),
         }
@@ -322,13 +397,69 @@ async fn run_evolution_inner<L: EvolutionLlm>(
         }
         Err(e) => tracing::warn!("EVO-6 external learning failed (non-fatal): {}", e),
     }
+    if !all_dropped.is_empty() {
+        reason_parts.push(format!("{} proposed change(s) dropped", all_dropped.len()));
+    }
 
     {
         let conn = feedback::open_evolution_db(chat_root)?;
 
+        let mut ids_to_mark = decision_ids_to_mark_after_run(&conn, &scope, force)?;
+        if ids_to_mark.is_empty() && !all_changes.is_empty() {
+            // Fallback: learners produced file/skill changes but id collection missed (e.g. refine-only paths).
+            ids_to_mark.clone_from(&scope.decision_ids);
+        }
+        let dir = scope.direction_label();
+        let reason = if all_changes.is_empty() {
+            // 即使无变更也记录一次，便于前端时间线展示进化运行记录（含本轮选择的进化方向）
+            if dir.is_empty() {
+                "进化运行完成，无新规则/技能产出".to_string()
+            } else {
+                format!("方向: {}；进化运行完成，无新规则/技能产出", dir)
+            }
+        } else if dir.is_empty() {
+            reason_parts.join("; ")
+        } else {
+            format!("方向: {}；{}", dir, reason_parts.join("; "))
+        };
+        let modified_files =
+            modified_prompt_files(chat_root, &txn_id, &snapshot_files, &all_changes);
+        journal.apply(
+            chat_root,
+            JournalRecord {
+                changes: all_changes.clone(),
+                dropped: all_dropped.clone(),
+                reason: reason.clone(),
+                files: modified_files.clone(),
+                decision_ids: ids_to_mark.clone(),
+                evaluation: prompt_evaluation.clone(),
+            },
+        )?;
+
         for (ctype, cid) in &all_changes {
             log_evolution_event(&conn, chat_root, ctype, cid, "prompt evolution", &txn_id)?;
         }
+        for dropped in &all_dropped {
+            let _ = log_evolution_event(
+                &conn,
+                chat_root,
+                "change_dropped",
+                &dropped.id,
+                &dropped.reason,
+                &txn_id,
+            );
+        }
+        let deferred = all_dropped.iter().filter(|d| d.deferred()).count();
+        if deferred > 0 {
+            let _ = log_evolution_event(
+                &conn,
+                chat_root,
+                "changes_deferred",
+                &deferred.to_string(),
+                "Gatekeeper L2: proposals over the per-run limit",
+                &txn_id,
+            );
+        }
 
         if scope.prompts {
             if let Err(e) = prompt_learner::update_reusable_status(&conn, chat_root) {
@@ -336,12 +467,10 @@ async fn run_evolution_inner<L: EvolutionLlm>(
             }
         }
 
-        let mut ids_to_mark = decision_ids_to_mark_after_run(&conn, &scope, force)?;
-        if ids_to_mark.is_empty() && !all_changes.is_empty() {
-            // Fallback: learners produced file/skill changes but id collection missed (e.g. refine-only paths).
-            ids_to_mark.clone_from(&scope.decision_ids);
-        }
         mark_decisions_evolved(&conn, &ids_to_mark)?;
+        if let Err(e) = crate::audit::record_decision_txn(&conn, &txn_id, &ids_to_mark) {
+            tracing::warn!("Failed to record decisions for txn {}: {}", txn_id, e);
+        }
         let _ = feedback::update_daily_metrics(&conn);
         let auto_rolled_back = check_auto_rollback(&conn, chat_root, skills_root)?;
         if auto_rolled_back {
@@ -387,13 +516,6 @@ async fn run_evolution_inner<L: EvolutionLlm>(
         }
 
         if all_changes.is_empty() {
-            // 即使无变更也记录一次，便于前端时间线展示进化运行记录（含本轮选择的进化方向）
-            let dir = scope.direction_label();
-            let reason = if dir.is_empty() {
-                "进化运行完成，无新规则/技能产出".to_string()
-            } else {
-                format!("方向: {}；进化运行完成，无新规则/技能产出", dir)
-            };
             let _ = log_evolution_event(
                 &conn,
                 chat_root,
@@ -402,6 +524,7 @@ async fn run_evolution_inner<L: EvolutionLlm>(
                 &reason,
                 &txn_id,
             );
+            journal.advance(chat_root, JournalPhase::Logged)?;
             let _ = set_backlog_status(
                 &conn,
                 &proposal.proposal_id,
@@ -409,15 +532,10 @@ async fn run_evolution_inner<L: EvolutionLlm>(
                 "not_met",
                 "Executed with no material changes",
             );
+            journal.advance(chat_root, JournalPhase::Committed)?;
             return Ok(EvolutionRunResult::Completed(None));
         }
 
-        let dir = scope.direction_label();
-        let reason = if dir.is_empty() {
-            reason_parts.join("; ")
-        } else {
-            format!("方向: {}；{}", dir, reason_parts.join("; "))
-        };
         // 记录本轮进化运行（含方向），便于前端时间线统一展示
         let _ = log_evolution_event(
             &conn,
@@ -427,43 +545,24 @@ async fn run_evolution_inner<L: EvolutionLlm>(
             &reason,
             &txn_id,
         );
+        journal.advance(chat_root, JournalPhase::Logged)?;
 
-        // 只记录内容真正发生变化的文件：用快照与当前版本逐一对比。
-        // snapshot_files 是进化前备份的全量清单，但实际修改的往往只是其中一部分
-        // （如 rules.json / examples.json），planning.md 等通常未被触碰。
-        let snap_dir = versions_dir(chat_root).join(&txn_id);
-        let prompts_dir = chat_root.join("prompts");
-        let mut modified_files: Vec<String> = snapshot_files
-            .iter()
-            .filter(|fname| {
-                let snap_path = snap_dir.join(fname);
-                let curr_path = prompts_dir.join(fname);
-                match (std::fs::read(&snap_path), std::fs::read(&curr_path)) {
-                    (Ok(old), Ok(new)) => old != new,
-                    _ => false,
-                }
-            })
-            .cloned()
-            .collect();
-
-        // External learner writes to prompts/rules.json; include it when external merged/promoted rules but snapshot didn't cover it (e.g. no scope.prompts).
-        if all_changes
-            .iter()
-            .any(|(t, _)| t == "external_rule_added" || t == "external_rule_promoted")
-        {
-            const EXTERNAL_RULES_FILE: &str = "rules.json";
-            if !modified_files.iter().any(|f| f == EXTERNAL_RULES_FILE) {
-                let rules_path = prompts_dir.join(EXTERNAL_RULES_FILE);
-                if rules_path.exists() {
-                    modified_files.push(EXTERNAL_RULES_FILE.to_string());
-                }
-            }
-        }
-
-        append_changelog(chat_root, &txn_id, &modified_files, &all_changes, &reason)?;
+        append_changelog(
+            chat_root,
+            &txn_id,
+            &modified_files,
+            &all_changes,
+            &reason,
+            prompt_evaluation.as_ref(),
+            &all_dropped,
+        )?;
+        journal.advance(chat_root, JournalPhase::Recorded)?;
 
-        let _decisions_path = chat_root.join("DECISIONS.md");
-        // let _ = feedback::export_decisions_md(&conn, &decisions_path); // Removed for refactor
+        let _ = crate::decisions_export::export_decisions(
+            &conn,
+            chat_root,
+            crate::decisions_export::workspace_export_enabled(),
+        );
         let _ = set_backlog_status(
             &conn,
             &proposal.proposal_id,
@@ -478,6 +577,7 @@ async fn run_evolution_inner<L: EvolutionLlm>(
                 e
             );
         }
+        journal.advance(chat_root, JournalPhase::Committed)?;
 
         tracing::info!("Evolution txn={} complete: {}", txn_id, reason);
     }
@@ -485,6 +585,47 @@ async fn run_evolution_inner<L: EvolutionLlm>(
     Ok(EvolutionRunResult::Completed(Some(txn_id)))
 }
 
+/// Prompts files whose content differs from the txn snapshot (the changelog `files`).
+fn modified_prompt_files(
+    chat_root: &Path,
+    txn_id: &str,
+    snapshot_files: &[String],
+    all_changes: &[(String, String)],
+) -> Vec<String> {
+    // 只记录内容真正发生变化的文件：用快照与当前版本逐一对比。
+    // snapshot_files 是进化前备份的全量清单，但实际修改的往往只是其中一部分
+    // （如 rules.json / examples.json），planning.md 等通常未被触碰。
+    let snap_dir = versions_dir(chat_root).join(txn_id);
+    let prompts_dir = chat_root.join("prompts");
+    let mut modified_files: Vec<String> = snapshot_files
+        .iter()
+        .filter(|fname| {
+            let snap_path = snap_dir.join(fname);
+            let curr_path = prompts_dir.join(fname);
+            match (std::fs::read(&snap_path), std::fs::read(&curr_path)) {
+                (Ok(old), Ok(new)) => old != new,
+                _ => false,
+            }
+        })
+        .cloned()
+        .collect();
+
+    // External learner writes to prompts/rules.json; include it when external merged/promoted rules but snapshot didn't cover it (e.g. no scope.prompts).
+    if all_changes
+        .iter()
+        .any(|(t, _)| t == "external_rule_added" || t == "external_rule_promoted")
+    {
+        const EXTERNAL_RULES_FILE: &str = "rules.json";
+        if !modified_files.iter().any(|f| f == EXTERNAL_RULES_FILE) {
+            let rules_path = prompts_dir.join(EXTERNAL_RULES_FILE);
+            if rules_path.exists() {
+                modified_files.push(EXTERNAL_RULES_FILE.to_string());
+            }
+        }
+    }
+    modified_files
+}
+
 pub fn query_changes_by_txn(conn: &Connection, txn_id: &str) -> Vec<(String, String)> {
     let mut stmt =
         match conn.prepare("SELECT type, target_id FROM evolution_log WHERE version = ?1") {
@@ -532,12 +673,22 @@ pub fn format_evolution_changes(changes: &[(String, String)]) -> Vec<String> {
                 "auto_rollback" => format!("\u{26a0}\u{fe0f} 检测到质量下降，已自动回滚: {}", id),
                 "reusable_promoted" => format!("\u{2b06}\u{fe0f} 规则晋升为通用: {}", id),
                 "reusable_demoted" => format!("\u{2b07}\u{fe0f} 规则降级为低效: {}", id),
+                "example_retired" => format!("\u{1f5c3}\u{fe0f} 已归档低质量示例: {}", id),
                 "external_rule_added" => format!("\u{1f310} 已从外部来源学习规则: {}", id),
+                "external_rule_conflict" => {
+                    format!("\u{2696}\u{fe0f} 外部规则与现有规则冲突，待审核: {}", id)
+                }
                 "external_rule_promoted" => format!("\u{2b06}\u{fe0f} 外部规则晋升为优质: {}", id),
                 "source_paused" => format!("\u{23f8}\u{fe0f} 信源可达性过低，已暂停: {}", id),
                 "source_retired" => format!("\u{1f5d1}\u{fe0f} 已退役低质量信源: {}", id),
                 "source_discovered" => format!("\u{1f50d} 发现新信源: {}", id),
                 "memory_knowledge_added" => format!("\u{1f4da} 已沉淀知识库（实体与关系）: {}", id),
+                "changes_deferred" => {
+                    format!(
+                        "\u{23f8}\u{fe0f} {} 项提议超出本轮上限，已推迟到后续进化",
+                        id
+                    )
+                }
                 _ => return None,
             };
             Some(msg)
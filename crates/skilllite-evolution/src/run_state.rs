@@ -1,5 +1,7 @@
 //! Global evolution run mutex and result type.
 
+use std::fs::{File, OpenOptions};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 // ─── Concurrency: evolution mutex ────────────────────────────────────────────
@@ -16,6 +18,33 @@ pub fn finish_evolution() {
     EVOLUTION_IN_PROGRESS.store(false, Ordering::SeqCst);
 }
 
+/// Releases the in-process mutex on drop, so a run whose task is aborted mid-cycle does not
+/// leave evolution marked as in progress for the rest of the process.
+pub(crate) struct EvolutionRunning;
+
+impl Drop for EvolutionRunning {
+    fn drop(&mut self) {
+        finish_evolution();
+    }
+}
+
+// ─── Concurrency: cross-process run lock ─────────────────────────────────────
+
+/// Advisory lock file under the chat root, held for the duration of `run_evolution` so the
+/// chat session's background triggers and a concurrent `skilllite evolution run` never
+/// overlap. The OS drops the lock with the process, so a crashed run leaves nothing stale.
+pub const RUN_LOCK_FILE: &str = "evolution.lock";
+
+pub fn open_run_lock(chat_root: &Path) -> std::io::Result<fd_lock::RwLock<File>> {
+    std::fs::create_dir_all(chat_root)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(chat_root.join(RUN_LOCK_FILE))?;
+    Ok(fd_lock::RwLock::new(file))
+}
+
 /// Result of attempting to run evolution. Distinguishes "skipped (busy)" from "no scope" from "ran (with or without changes)".
 #[derive(Debug, Clone)]
 pub enum EvolutionRunResult {
@@ -0,0 +1,568 @@
+//! Versioned schema for `feedback.sqlite`, tracked in `PRAGMA user_version`.
+//!
+//! [`crate::feedback::ensure_evolution_tables`] applies pending [`MIGRATIONS`] in order, each in
+//! its own transaction together with the version bump. Databases created before versioning
+//! (`user_version = 0` with existing tables) replay every step; steps are idempotent so columns
+//! that already exist are skipped. A `VACUUM INTO` backup is taken before any step that alters
+//! an existing table.
+
+use std::path::PathBuf;
+
+use rusqlite::{params, Connection};
+
+use crate::Result;
+
+/// Schema version written by this build.
+pub const SCHEMA_VERSION: i64 = 11;
+
+enum Step {
+    Sql(&'static str),
+    /// `ALTER TABLE .. ADD COLUMN` unless the column already exists.
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        decl: &'static str,
+    },
+    /// Rust data backfill run after the DDL of the same migration.
+    Backfill(fn(&Connection) -> Result<()>),
+}
+
+struct Migration {
+    version: i64,
+    description: &'static str,
+    steps: &'static [Step],
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "decisions, decision_rules, evolution_log, evolution_metrics",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS decisions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ts TEXT NOT NULL DEFAULT (datetime('now')),
+                session_id TEXT,
+                total_tools INTEGER DEFAULT 0,
+                failed_tools INTEGER DEFAULT 0,
+                replans INTEGER DEFAULT 0,
+                elapsed_ms INTEGER DEFAULT 0,
+                task_completed BOOLEAN DEFAULT 0,
+                feedback TEXT DEFAULT 'neutral',
+                evolved BOOLEAN DEFAULT 0,
+                task_description TEXT,
+                tools_detail TEXT
+            );
+
+            CREATE TABLE IF NOT EXISTS decision_rules (
+                decision_id INTEGER REFERENCES decisions(id) ON DELETE CASCADE,
+                rule_id TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS evolution_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ts TEXT NOT NULL DEFAULT (datetime('now')),
+                type TEXT NOT NULL,
+                target_id TEXT,
+                reason TEXT,
+                version TEXT
+            );
+
+            CREATE TABLE IF NOT EXISTS evolution_metrics (
+                date TEXT PRIMARY KEY,
+                first_success_rate REAL,
+                avg_replans REAL,
+                avg_tool_calls REAL,
+                user_correction_rate REAL,
+                effective_rules INTEGER DEFAULT 0,
+                egl REAL DEFAULT 0.0
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_decisions_evolved ON decisions(evolved);
+            CREATE INDEX IF NOT EXISTS idx_decisions_ts ON decisions(ts);
+            CREATE INDEX IF NOT EXISTS idx_dr_rule ON decision_rules(rule_id);
+            CREATE INDEX IF NOT EXISTS idx_dr_decision ON decision_rules(decision_id);
+            CREATE INDEX IF NOT EXISTS idx_evo_log_ts ON evolution_log(ts);
+            "#,
+        )],
+    },
+    Migration {
+        version: 2,
+        description: "decisions: tool_sequence_key, completion_type, completion_type_reported",
+        steps: &[
+            Step::AddColumn {
+                table: "decisions",
+                column: "tool_sequence_key",
+                decl: "TEXT",
+            },
+            Step::AddColumn {
+                table: "decisions",
+                column: "completion_type",
+                decl: "TEXT DEFAULT 'success'",
+            },
+            Step::AddColumn {
+                table: "decisions",
+                column: "completion_type_reported",
+                decl: "TEXT DEFAULT 'success'",
+            },
+            Step::Sql(
+                "CREATE INDEX IF NOT EXISTS idx_decisions_seq ON decisions(tool_sequence_key);",
+            ),
+            Step::Backfill(backfill_missing_sequence_keys),
+        ],
+    },
+    Migration {
+        version: 3,
+        description: "decisions: corrections, confirmations",
+        steps: &[
+            Step::AddColumn {
+                table: "decisions",
+                column: "corrections",
+                decl: "INTEGER DEFAULT 0",
+            },
+            Step::AddColumn {
+                table: "decisions",
+                column: "confirmations",
+                decl: "INTEGER DEFAULT 0",
+            },
+        ],
+    },
+    Migration {
+        version: 4,
+        description: "evolution_backlog",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS evolution_backlog (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                proposal_id TEXT NOT NULL UNIQUE,
+                source TEXT NOT NULL,
+                dedupe_key TEXT NOT NULL UNIQUE,
+                scope_json TEXT NOT NULL,
+                risk_level TEXT NOT NULL,
+                roi_score REAL NOT NULL DEFAULT 0.0,
+                expected_gain REAL NOT NULL DEFAULT 0.0,
+                effort REAL NOT NULL DEFAULT 1.0,
+                acceptance_criteria TEXT NOT NULL DEFAULT '[]',
+                status TEXT NOT NULL,
+                acceptance_status TEXT NOT NULL DEFAULT 'pending',
+                note TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_evo_backlog_status_roi ON evolution_backlog(status, roi_score DESC);
+            CREATE INDEX IF NOT EXISTS idx_evo_backlog_created_at ON evolution_backlog(created_at);
+            "#,
+        )],
+    },
+    Migration {
+        version: 5,
+        description: "decisions: model, fallback_model, replan_reasons",
+        steps: &[
+            Step::AddColumn {
+                table: "decisions",
+                column: "model",
+                decl: "TEXT",
+            },
+            Step::AddColumn {
+                table: "decisions",
+                column: "fallback_model",
+                decl: "TEXT",
+            },
+            Step::AddColumn {
+                table: "decisions",
+                column: "replan_reasons",
+                decl: "TEXT",
+            },
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "decision_txns",
+        // No FK: rows outlive decisions pruned later, so `evolution explain` can report how
+        // many went missing.
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS decision_txns (
+                decision_id INTEGER NOT NULL,
+                txn_id TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_decision_txns_txn ON decision_txns(txn_id);
+            "#,
+        )],
+    },
+    Migration {
+        version: 7,
+        description: "decision_tools, tool_metrics",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS decision_tools (
+                decision_id INTEGER NOT NULL REFERENCES decisions(id) ON DELETE CASCADE,
+                seq INTEGER NOT NULL,
+                ts TEXT NOT NULL DEFAULT (datetime('now')),
+                tool TEXT NOT NULL,
+                skill TEXT,
+                duration_ms INTEGER NOT NULL DEFAULT 0,
+                result_bytes INTEGER NOT NULL DEFAULT 0,
+                is_error BOOLEAN NOT NULL DEFAULT 0,
+                error_kind TEXT,
+                args_present TEXT NOT NULL DEFAULT '[]'
+            );
+            CREATE INDEX IF NOT EXISTS idx_decision_tools_decision ON decision_tools(decision_id);
+            CREATE INDEX IF NOT EXISTS idx_decision_tools_ts ON decision_tools(ts);
+            CREATE TABLE IF NOT EXISTS tool_metrics (
+                date TEXT NOT NULL,
+                tool TEXT NOT NULL,
+                calls INTEGER NOT NULL DEFAULT 0,
+                failures INTEGER NOT NULL DEFAULT 0,
+                total_duration_ms INTEGER NOT NULL DEFAULT 0,
+                max_duration_ms INTEGER NOT NULL DEFAULT 0,
+                total_result_bytes INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (date, tool)
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 8,
+        description: "decisions: workspace",
+        steps: &[
+            Step::AddColumn {
+                table: "decisions",
+                column: "workspace",
+                decl: "TEXT",
+            },
+            Step::Sql(
+                "CREATE INDEX IF NOT EXISTS idx_decisions_workspace ON decisions(workspace);",
+            ),
+        ],
+    },
+    Migration {
+        version: 9,
+        description: "evolution_metrics_breakdown",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS evolution_metrics_breakdown (
+                date TEXT NOT NULL,
+                dimension TEXT NOT NULL,
+                key TEXT NOT NULL,
+                label TEXT NOT NULL DEFAULT '',
+                decisions INTEGER NOT NULL DEFAULT 0,
+                successes INTEGER NOT NULL DEFAULT 0,
+                corrections INTEGER NOT NULL DEFAULT 0,
+                confirmations INTEGER NOT NULL DEFAULT 0,
+                tool_calls INTEGER NOT NULL DEFAULT 0,
+                tool_failures INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (date, dimension, key)
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 10,
+        description: "decision_rule_scope",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS decision_rule_scope (
+                decision_id INTEGER REFERENCES decisions(id) ON DELETE CASCADE,
+                rule_id TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_drs_rule ON decision_rule_scope(rule_id);
+            "#,
+        )],
+    },
+    Migration {
+        version: 11,
+        description: "decisions: tool_loops",
+        steps: &[Step::AddColumn {
+            table: "decisions",
+            column: "tool_loops",
+            decl: "INTEGER DEFAULT 0",
+        }],
+    },
+];
+
+/// What [`migrate`] did on open.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from: i64,
+    pub to: i64,
+    /// Descriptions of the applied migrations, oldest first.
+    pub applied: Vec<String>,
+    pub backup: Option<PathBuf>,
+}
+
+/// Current `PRAGMA user_version` of `conn`.
+pub fn schema_version(conn: &Connection) -> Result<i64> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Bring `conn` up to [`SCHEMA_VERSION`]. A database from a newer build is left untouched.
+pub fn migrate(conn: &Connection) -> Result<MigrationReport> {
+    let from = schema_version(conn)?;
+    let mut report = MigrationReport {
+        from,
+        to: from,
+        ..Default::default()
+    };
+    if from > SCHEMA_VERSION {
+        tracing::warn!(
+            "feedback.sqlite schema v{} is newer than this build (v{}); not migrating",
+            from,
+            SCHEMA_VERSION
+        );
+        return Ok(report);
+    }
+    let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|m| m.version > from).collect();
+    if pending.is_empty() {
+        return Ok(report);
+    }
+
+    let alters_existing = table_exists(conn, "decisions")?
+        && pending
+            .iter()
+            .any(|m| m.steps.iter().any(|s| matches!(s, Step::AddColumn { .. })));
+    if alters_existing {
+        report.backup = backup_before_migration(conn, from)?;
+    }
+
+    for migration in pending {
+        let tx = conn.unchecked_transaction()?;
+        for step in migration.steps {
+            match step {
+                Step::Sql(sql) => tx.execute_batch(sql)?,
+                Step::AddColumn {
+                    table,
+                    column,
+                    decl,
+                } => {
+                    if !column_exists(&tx, table, column)? {
+                        tx.execute_batch(&format!(
+                            "ALTER TABLE {} ADD COLUMN {} {}",
+                            table, column, decl
+                        ))?;
+                    }
+                }
+                Step::Backfill(f) => f(&tx)?,
+            }
+        }
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+        tracing::info!(
+            "feedback.sqlite migrated to v{}: {}",
+            migration.version,
+            migration.description
+        );
+        report.to = migration.version;
+        report.applied.push(migration.description.to_string());
+    }
+    Ok(report)
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool> {
+    let n: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        params![table],
+        |row| row.get(0),
+    )?;
+    Ok(n > 0)
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(names.iter().any(|n| n == column))
+}
+
+/// `VACUUM INTO <db>.v<from>.bak` next to a file-backed database.
+fn backup_before_migration(conn: &Connection, from: i64) -> Result<Option<PathBuf>> {
+    let Some(path) = conn.path().filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    let backup = PathBuf::from(format!("{}.v{}.bak", path, from));
+    if backup.exists() {
+        std::fs::remove_file(&backup)?;
+    }
+    conn.execute("VACUUM INTO ?1", params![backup.to_string_lossy()])?;
+    Ok(Some(backup))
+}
+
+/// Fill `tool_sequence_key` for decisions recorded before the column existed.
+fn backfill_missing_sequence_keys(conn: &Connection) -> Result<()> {
+    recompute_sequence_keys(conn, true).map(|_| ())
+}
+
+/// Recompute `tool_sequence_key` from `tools_detail`; returns the number of rows updated.
+pub(crate) fn recompute_sequence_keys(conn: &Connection, only_missing: bool) -> Result<usize> {
+    let sql = if only_missing {
+        "SELECT id, tools_detail FROM decisions WHERE tool_sequence_key IS NULL AND tools_detail IS NOT NULL"
+    } else {
+        "SELECT id, tools_detail FROM decisions WHERE tools_detail IS NOT NULL"
+    };
+    let rows: Vec<(i64, String)> = conn
+        .prepare(sql)?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+    let mut updated = 0;
+    for (id, detail) in rows {
+        let Ok(tools) = serde_json::from_str::<Vec<crate::feedback::ToolExecDetail>>(&detail)
+        else {
+            continue;
+        };
+        if let Some(key) = crate::feedback::compute_tool_sequence_key(&tools) {
+            updated += conn.execute(
+                "UPDATE decisions SET tool_sequence_key = ?1 WHERE id = ?2",
+                params![key, id],
+            )?;
+        }
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `feedback.sqlite` as written by the first release, before `user_version` was tracked
+    /// beyond 1.
+    const V1_FIXTURE: &str = r#"
+        CREATE TABLE decisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts TEXT NOT NULL DEFAULT (datetime('now')),
+            session_id TEXT,
+            total_tools INTEGER DEFAULT 0,
+            failed_tools INTEGER DEFAULT 0,
+            replans INTEGER DEFAULT 0,
+            elapsed_ms INTEGER DEFAULT 0,
+            task_completed BOOLEAN DEFAULT 0,
+            feedback TEXT DEFAULT 'neutral',
+            evolved BOOLEAN DEFAULT 0,
+            task_description TEXT,
+            tools_detail TEXT
+        );
+        CREATE TABLE decision_rules (
+            decision_id INTEGER REFERENCES decisions(id) ON DELETE CASCADE,
+            rule_id TEXT NOT NULL
+        );
+        CREATE TABLE evolution_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts TEXT NOT NULL DEFAULT (datetime('now')),
+            type TEXT NOT NULL,
+            target_id TEXT,
+            reason TEXT,
+            version TEXT
+        );
+        CREATE TABLE evolution_metrics (
+            date TEXT PRIMARY KEY,
+            first_success_rate REAL,
+            avg_replans REAL,
+            avg_tool_calls REAL,
+            user_correction_rate REAL,
+            effective_rules INTEGER DEFAULT 0,
+            egl REAL DEFAULT 0.0
+        );
+        INSERT INTO decisions (id, task_completed, feedback, task_description, tools_detail)
+        VALUES
+            (1, 1, 'pos', 'summarize csv',
+             '[{"tool":"read_file","success":true},{"tool":"write_output","success":true}]'),
+            (2, 0, 'neg', 'fetch page', NULL);
+        INSERT INTO decision_rules (decision_id, rule_id) VALUES (1, 'r1');
+        INSERT INTO evolution_log (type, target_id, reason, version)
+        VALUES ('rule_added', 'r1', 'seed', 'evo_1');
+        PRAGMA user_version = 1;
+    "#;
+
+    #[test]
+    fn v1_fixture_migrates_to_current_with_data_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("feedback.sqlite");
+        Connection::open(&db)
+            .unwrap()
+            .execute_batch(V1_FIXTURE)
+            .unwrap();
+
+        let conn = crate::feedback::open_evolution_db(dir.path()).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(dir.path().join("feedback.sqlite.v1.bak").is_file());
+
+        let (desc, key, model): (String, Option<String>, Option<String>) = conn
+            .query_row(
+                "SELECT task_description, tool_sequence_key, model FROM decisions WHERE id = 1",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(desc, "summarize csv");
+        assert_eq!(key.as_deref(), Some("read_file→write_output"));
+        assert_eq!(model, None);
+        let counts: (i64, i64, i64) = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM decisions), (SELECT COUNT(*) FROM decision_rules),
+                        (SELECT COUNT(*) FROM evolution_log)",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(counts, (2, 1, 1));
+
+        // New tables exist and the current write path works on the migrated DB.
+        crate::audit::record_decision_txn(&conn, "evo_2", &[1]).unwrap();
+        crate::feedback::insert_decision(
+            &conn,
+            Some("s1"),
+            &crate::feedback::DecisionInput {
+                model: Some("m".into()),
+                tools_detail: vec![crate::feedback::ToolExecDetail {
+                    tool: "read_file".into(),
+                    success: true,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            crate::feedback::FeedbackSignal::Neutral,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn unversioned_db_with_current_columns_is_adopted() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(V1_FIXTURE).unwrap();
+        conn.execute_batch(
+            "ALTER TABLE decisions ADD COLUMN tool_sequence_key TEXT;
+             ALTER TABLE decisions ADD COLUMN model TEXT;
+             PRAGMA user_version = 0;",
+        )
+        .unwrap();
+
+        let report = migrate(&conn).unwrap();
+        assert_eq!((report.from, report.to), (0, SCHEMA_VERSION));
+        assert_eq!(report.applied.len(), MIGRATIONS.len());
+        assert!(column_exists(&conn, "decisions", "replan_reasons").unwrap());
+        // In-memory databases have nowhere to put a backup.
+        assert_eq!(report.backup, None);
+    }
+
+    #[test]
+    fn reopening_current_db_is_a_noop_and_newer_db_is_left_alone() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        let report = migrate(&conn).unwrap();
+        assert!(report.applied.is_empty());
+
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        let report = migrate(&conn).unwrap();
+        assert_eq!(report.to, SCHEMA_VERSION + 1);
+        assert!(report.applied.is_empty());
+    }
+
+    #[test]
+    fn migration_versions_are_contiguous() {
+        for (i, m) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(m.version, i as i64 + 1);
+        }
+        assert_eq!(MIGRATIONS.last().unwrap().version, SCHEMA_VERSION);
+    }
+}
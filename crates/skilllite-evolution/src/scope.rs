@@ -1090,7 +1090,7 @@ fn should_evolve_impl(
         }
     }
 
-    let recent_condition = format!("ts >= datetime('now', '-{} days')", thresholds.recent_days);
+    let recent_condition = thresholds.decision_window_condition();
     let recent_limit = thresholds.recent_limit;
 
     let (meaningful, failures, replans): (i64, i64, i64) = conn.query_row(
@@ -1277,7 +1277,7 @@ pub fn passive_schedule_diagnostics(
         Some(last_evo_hours)
     };
 
-    let recent_condition = format!("ts >= datetime('now', '-{} days')", thresholds.recent_days);
+    let recent_condition = thresholds.decision_window_condition();
     let recent_limit = thresholds.recent_limit;
 
     let (meaningful, failures, replans): (i64, i64, i64) = conn.query_row(
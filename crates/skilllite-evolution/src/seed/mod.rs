@@ -2,9 +2,17 @@
 
 use std::path::{Path, PathBuf};
 
-use skilllite_core::planning::{PlanningRule, SourceRegistry};
+use skilllite_core::planning::{
+    lint_rules, lint_sources, validate_rule_value, validate_rules, validate_sources, PlanningRule,
+    SchemaError, SourceRegistry,
+};
 
-const SEED_VERSION: u32 = 3;
+use crate::journal::{self, TxnWrites};
+use crate::Result;
+
+pub mod template;
+
+const SEED_VERSION: u32 = 4;
 
 const SEED_RULES: &str = include_str!("rules.seed.json");
 const SEED_SOURCES: &str = include_str!("sources.seed.json");
@@ -12,6 +20,7 @@ const SEED_SYSTEM: &str = include_str!("system.seed.md");
 const SEED_PLANNING: &str = include_str!("planning.seed.md");
 const SEED_EXECUTION: &str = include_str!("execution.seed.md");
 const SEED_EXAMPLES: &str = include_str!("examples.seed.md");
+pub(crate) const SEED_FEEDBACK_SIGNALS: &str = include_str!("feedback_signals.seed.json");
 
 fn prompts_dir(chat_root: &Path) -> PathBuf {
     chat_root.join("prompts")
@@ -43,6 +52,7 @@ pub fn ensure_seed_data(chat_root: &Path) {
         write_seed_file(&dir, "planning.md", SEED_PLANNING);
         write_seed_file(&dir, "execution.md", SEED_EXECUTION);
         write_seed_file(&dir, "examples.md", SEED_EXAMPLES);
+        write_seed_file(&dir, "feedback_signals.json", SEED_FEEDBACK_SIGNALS);
     } else {
         merge_seed_rules(&dir);
         merge_seed_sources(&dir);
@@ -50,6 +60,10 @@ pub fn ensure_seed_data(chat_root: &Path) {
         write_if_unchanged(&dir, "planning.md", SEED_PLANNING);
         write_if_unchanged(&dir, "execution.md", SEED_EXECUTION);
         write_if_unchanged(&dir, "examples.md", SEED_EXAMPLES);
+        // Evolution may have refined the word lists; only seed them when missing.
+        if !dir.join("feedback_signals.json").exists() {
+            write_seed_file(&dir, "feedback_signals.json", SEED_FEEDBACK_SIGNALS);
+        }
     }
 
     let _ = std::fs::write(&version_file, SEED_VERSION.to_string());
@@ -68,6 +82,7 @@ pub fn ensure_seed_data_force(chat_root: &Path) {
     write_seed_file(&dir, "planning.md", SEED_PLANNING);
     write_seed_file(&dir, "execution.md", SEED_EXECUTION);
     write_seed_file(&dir, "examples.md", SEED_EXAMPLES);
+    write_seed_file(&dir, "feedback_signals.json", SEED_FEEDBACK_SIGNALS);
     let _ = std::fs::write(dir.join(".seed_version"), SEED_VERSION.to_string());
     tracing::info!("Seed data force-reset to v{}", SEED_VERSION);
 }
@@ -164,6 +179,10 @@ fn merge_seed_sources(dir: &Path) {
             .iter_mut()
             .find(|s| s.id == seed_src.id && !s.mutable)
         {
+            if existing.url != seed_src.url {
+                existing.etag = None;
+                existing.last_modified = None;
+            }
             existing.name = seed_src.name.clone();
             existing.url = seed_src.url.clone();
             existing.source_type = seed_src.source_type.clone();
@@ -179,29 +198,28 @@ fn merge_seed_sources(dir: &Path) {
     }
 }
 
+/// Load `prompts/rules.json`, falling back to the seed rules when it is missing, empty or
+/// fails schema validation (logged with every problem found).
 pub fn load_rules(chat_root: &Path) -> Vec<PlanningRule> {
     let path = prompts_dir(chat_root).join("rules.json");
-    if path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&path) {
-            if let Ok(rules) = serde_json::from_str::<Vec<PlanningRule>>(&content) {
-                if !rules.is_empty() {
-                    return rules;
-                }
-            }
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        match validate_rules(&content) {
+            Ok(rules) if !rules.is_empty() => return rules,
+            Ok(_) => {}
+            Err(errors) => warn_invalid(&path, &errors),
         }
     }
     serde_json::from_str(SEED_RULES).unwrap_or_default()
 }
 
+/// Load `prompts/sources.json` with the same fallback rules as [`load_rules`].
 pub fn load_sources(chat_root: &Path) -> SourceRegistry {
     let path = prompts_dir(chat_root).join("sources.json");
-    if path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&path) {
-            if let Ok(registry) = serde_json::from_str::<SourceRegistry>(&content) {
-                if !registry.sources.is_empty() {
-                    return registry;
-                }
-            }
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        match validate_sources(&content) {
+            Ok(registry) if !registry.sources.is_empty() => return registry,
+            Ok(_) => {}
+            Err(errors) => warn_invalid(&path, &errors),
         }
     }
     serde_json::from_str(SEED_SOURCES).unwrap_or_else(|_| SourceRegistry {
@@ -210,6 +228,89 @@ pub fn load_sources(chat_root: &Path) -> SourceRegistry {
     })
 }
 
+fn warn_invalid(path: &Path, errors: &[SchemaError]) {
+    tracing::warn!(
+        "⚠ {} failed schema validation; using the built-in seed data instead \
+(run `skilllite doctor` to re-check):\n{}",
+        path.display(),
+        format_problems(errors)
+    );
+}
+
+/// One problem per line, for logs and error messages.
+pub fn format_problems(problems: &[SchemaError]) -> String {
+    problems
+        .iter()
+        .map(|p| format!("  - {}", p))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Schema problems (warnings included) in the on-disk `rules.json` and `sources.json`, for
+/// `skilllite doctor`. Files that do not exist yet are skipped.
+pub fn doctor(chat_root: &Path) -> Vec<(&'static str, Vec<SchemaError>)> {
+    let dir = prompts_dir(chat_root);
+    let mut report = Vec::new();
+    if let Ok(content) = std::fs::read_to_string(dir.join("rules.json")) {
+        report.push(("rules.json", lint_rules(&content)));
+    }
+    if let Ok(content) = std::fs::read_to_string(dir.join("sources.json")) {
+        report.push(("sources.json", lint_sources(&content)));
+    }
+    report
+}
+
+/// Why an LLM-produced rule object must be rejected, or `None` when it passes the schema.
+pub(crate) fn rule_rejection(rule: &serde_json::Value) -> Option<String> {
+    let errors: Vec<SchemaError> = validate_rule_value(rule)
+        .into_iter()
+        .filter(|p| !p.is_warning())
+        .collect();
+    (!errors.is_empty()).then(|| format_problems(&errors))
+}
+
+/// Write `rules.json` (or a candidate copy) only if the serialized rules pass schema
+/// validation; otherwise log the reason and leave the file untouched. Inside an evolution
+/// txn the write goes through its journal.
+pub(crate) fn write_rules_checked(
+    path: &Path,
+    rules: &[PlanningRule],
+    txn: Option<TxnWrites>,
+) -> Result<()> {
+    let json = serde_json::to_string_pretty(rules)?;
+    if let Err(errors) = validate_rules(&json) {
+        return Err(reject_write(path, &errors));
+    }
+    journal::write_file(txn, path, &json)
+}
+
+/// [`write_rules_checked`] for `sources.json`.
+pub(crate) fn write_sources_checked(
+    path: &Path,
+    registry: &SourceRegistry,
+    txn: Option<TxnWrites>,
+) -> Result<()> {
+    let json = serde_json::to_string_pretty(registry)?;
+    if let Err(errors) = validate_sources(&json) {
+        return Err(reject_write(path, &errors));
+    }
+    journal::write_file(txn, path, &json)
+}
+
+fn reject_write(path: &Path, errors: &[SchemaError]) -> crate::Error {
+    let reason = format_problems(errors);
+    tracing::warn!(
+        "Rejected write to {}: schema validation failed:\n{}",
+        path.display(),
+        reason
+    );
+    crate::Error::validation(format!(
+        "{} failed schema validation:\n{}",
+        path.display(),
+        reason
+    ))
+}
+
 pub fn load_system_prompt(chat_root: &Path) -> String {
     load_prompt_file(chat_root, "system.md", SEED_SYSTEM)
 }
@@ -241,10 +342,13 @@ pub fn required_placeholders(name: &str) -> &'static [&'static str] {
     }
 }
 
+/// Required placeholders not substituted anywhere in `content`. A placeholder inside a
+/// `{{#if}}` body counts; a bare `{{#if VAR}}` condition does not (see [`template`]).
 pub fn validate_template(name: &str, content: &str) -> Vec<&'static str> {
+    let used = template::placeholders(content);
     required_placeholders(name)
         .iter()
-        .filter(|p| !content.contains(**p))
+        .filter(|p| !used.contains(p.trim_start_matches("{{").trim_end_matches("}}")))
         .copied()
         .collect()
 }
@@ -319,8 +423,77 @@ mod template_tests {
         assert!(ok.is_empty());
     }
 
+    #[test]
+    fn validate_template_understands_conditionals() {
+        let cases: &[(&str, &[&str])] = &[
+            (
+                "{{TODAY}}{{#if RULES_SECTION}}{{RULES_SECTION}}{{/if}}{{EXAMPLES_SECTION}}{{ OUTPUT_DIR }}",
+                &[],
+            ),
+            (
+                "{{TODAY}}{{#if RULES_SECTION}}rules{{/if}}{{EXAMPLES_SECTION}}{{OUTPUT_DIR}}",
+                &["{{RULES_SECTION}}"],
+            ),
+            (
+                "{{#if A}}{{#if B}}{{TODAY}}{{RULES_SECTION}}{{/if}}{{/if}}{{EXAMPLES_SECTION}}{{OUTPUT_DIR}}",
+                &[],
+            ),
+        ];
+        for (content, missing) in cases {
+            assert_eq!(validate_template("planning.md", content), *missing);
+        }
+    }
+
     #[test]
     fn validate_template_unknown_name_is_permissive() {
         assert!(validate_template("other.md", "").is_empty());
     }
 }
+
+#[cfg(test)]
+mod schema_tests {
+    use super::*;
+
+    #[test]
+    fn seed_files_pass_the_schema() {
+        assert_eq!(lint_rules(SEED_RULES), []);
+        assert_eq!(lint_sources(SEED_SOURCES), []);
+    }
+
+    #[test]
+    fn invalid_rules_fall_back_to_seed_and_show_in_doctor() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = prompts_dir(tmp.path());
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(doctor(tmp.path()).is_empty());
+
+        std::fs::write(
+            dir.join("rules.json"),
+            r#"[{"id": "mine", "instruction": "x", "priority": 150}]"#,
+        )
+        .unwrap();
+        let rules = load_rules(tmp.path());
+        assert!(rules.iter().all(|r| r.id != "mine"));
+        assert_eq!(rules.len(), validate_rules(SEED_RULES).unwrap().len());
+
+        let report = doctor(tmp.path());
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].0, "rules.json");
+        assert_eq!(report[0].1[0].path, "$[0].priority");
+    }
+
+    #[test]
+    fn checked_writes_reject_invalid_rules() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("rules.json");
+        let mut rules: Vec<PlanningRule> = serde_json::from_str(SEED_RULES).unwrap();
+        write_rules_checked(&path, &rules, None).unwrap();
+
+        rules.push(rules[0].clone());
+        let err = write_rules_checked(&path, &rules, None).unwrap_err();
+        assert!(err.to_string().contains("duplicate id"), "{}", err);
+        let on_disk: Vec<PlanningRule> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(on_disk.len(), rules.len() - 1);
+    }
+}
@@ -0,0 +1,290 @@
+//! Minimal prompt template dialect shared by the system, planning and execution prompts.
+//!
+//! - `{{VAR}}` is replaced by the variable's value. Unknown variables render as empty
+//!   (logged at debug) instead of leaking braces into the prompt.
+//! - `{{#if VAR}}...{{/if}}` keeps its body only when `VAR` is set and non-blank. Blocks nest.
+//! - Anything else between double braces (e.g. `{{"id": 1}}` in examples) stays literal.
+//!
+//! Built-in variables are set by the prompt builder; `<workspace>/.skilllite/prompt_vars.json`
+//! (a flat JSON object) is merged over them via [`TemplateVars::with_workspace`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Per-workspace variables, relative to the workspace root.
+pub const PROMPT_VARS_FILE: &str = ".skilllite/prompt_vars.json";
+
+/// Variables available to [`render`].
+#[derive(Debug, Clone, Default)]
+pub struct TemplateVars {
+    vars: BTreeMap<String, String>,
+}
+
+impl TemplateVars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style [`TemplateVars::insert`].
+    pub fn set(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    pub fn insert(&mut self, name: &str, value: impl Into<String>) {
+        self.vars.insert(name.to_string(), value.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+
+    /// Merge `<workspace>/.skilllite/prompt_vars.json` over the current variables.
+    pub fn with_workspace(mut self, workspace: Option<&Path>) -> Self {
+        if let Some(ws) = workspace {
+            self.vars.extend(load_workspace_vars(ws));
+        }
+        self
+    }
+
+    fn is_truthy(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|v| !v.trim().is_empty())
+    }
+}
+
+/// String values of `<workspace>/.skilllite/prompt_vars.json`; numbers and booleans are
+/// stringified, other values are skipped. A missing or malformed file yields no variables.
+pub fn load_workspace_vars(workspace: &Path) -> BTreeMap<String, String> {
+    let path = workspace.join(PROMPT_VARS_FILE);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return BTreeMap::new();
+    };
+    let map = match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&content) {
+        Ok(map) => map,
+        Err(e) => {
+            tracing::warn!("Ignoring {}: {}", path.display(), e);
+            return BTreeMap::new();
+        }
+    };
+    map.into_iter()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s,
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            is_var_name(&name).then_some((name, value))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Var(&'a str),
+    If(&'a str),
+    EndIf,
+}
+
+fn segments(template: &str) -> Vec<Segment<'_>> {
+    let mut out = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        if start > 0 {
+            out.push(Segment::Text(&rest[..start]));
+        }
+        let inner = rest[start + 2..end - 2].trim();
+        out.push(if inner == "/if" {
+            Segment::EndIf
+        } else if let Some(name) = inner.strip_prefix("#if ").map(str::trim) {
+            if is_var_name(name) {
+                Segment::If(name)
+            } else {
+                Segment::Text(&rest[start..end])
+            }
+        } else if is_var_name(inner) {
+            Segment::Var(inner)
+        } else {
+            Segment::Text(&rest[start..end])
+        });
+        rest = &rest[end..];
+    }
+    if !rest.is_empty() {
+        out.push(Segment::Text(rest));
+    }
+    out
+}
+
+fn is_var_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-')
+}
+
+/// Render `template` with `vars`. Values are inserted verbatim (never re-expanded); an
+/// unclosed `{{#if}}` runs to the end and a stray `{{/if}}` is dropped.
+pub fn render(template: &str, vars: &TemplateVars) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut conditions: Vec<bool> = Vec::new();
+    for segment in segments(template) {
+        let active = conditions.iter().all(|c| *c);
+        match segment {
+            Segment::If(name) => conditions.push(active && vars.is_truthy(name)),
+            Segment::EndIf => {
+                if conditions.pop().is_none() {
+                    tracing::debug!("Prompt template has a stray {{{{/if}}}}");
+                }
+            }
+            Segment::Text(text) if active => out.push_str(text),
+            Segment::Var(name) if active => match vars.get(name) {
+                Some(value) => out.push_str(value),
+                None => tracing::debug!("Prompt template variable {} is not set", name),
+            },
+            Segment::Text(_) | Segment::Var(_) => {}
+        }
+    }
+    if !conditions.is_empty() {
+        tracing::debug!(
+            "Prompt template has {} unclosed {{{{#if}}}}",
+            conditions.len()
+        );
+    }
+    out
+}
+
+/// Names substituted via `{{VAR}}` anywhere in `template`, including inside conditionals.
+/// Names that only appear as `{{#if VAR}}` conditions are not included.
+pub fn placeholders(template: &str) -> BTreeSet<&str> {
+    segments(template)
+        .into_iter()
+        .filter_map(|s| match s {
+            Segment::Var(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Reject unbalanced `{{#if}}` / `{{/if}}` blocks.
+pub fn check_syntax(template: &str) -> std::result::Result<(), String> {
+    let mut depth = 0usize;
+    for segment in segments(template) {
+        match segment {
+            Segment::If(_) => depth += 1,
+            Segment::EndIf if depth == 0 => return Err("{{/if}} without {{#if}}".to_string()),
+            Segment::EndIf => depth -= 1,
+            _ => {}
+        }
+    }
+    match depth {
+        0 => Ok(()),
+        n => Err(format!("{} unclosed {{{{#if}}}} block(s)", n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> TemplateVars {
+        TemplateVars::new()
+            .set("RULES_SECTION", "- rule")
+            .set("EMPTY", "  ")
+            .set("REPO", "skilllite")
+            .set("LANG", "Rust")
+    }
+
+    #[test]
+    fn renders_variables_and_conditionals() {
+        let cases: &[(&str, &str)] = &[
+            ("plain text", "plain text"),
+            ("repo={{REPO}}", "repo=skilllite"),
+            ("{{ REPO }}", "skilllite"),
+            (
+                "a{{#if RULES_SECTION}}[{{RULES_SECTION}}]{{/if}}b",
+                "a[- rule]b",
+            ),
+            ("a{{#if EMPTY}}hidden{{/if}}b", "ab"),
+            ("a{{#if MISSING}}hidden{{/if}}b", "ab"),
+            (
+                "{{#if REPO}}R{{#if LANG}}L{{/if}}{{#if EMPTY}}E{{/if}}{{/if}}",
+                "RL",
+            ),
+            ("{{#if MISSING}}{{#if REPO}}inner{{/if}}outer{{/if}}!", "!"),
+            ("unknown=[{{NOPE}}]", "unknown=[]"),
+            (r#"{{"id": 1}}"#, r#"{{"id": 1}}"#),
+            ("{{#if REPO}}unclosed", "unclosed"),
+            ("stray{{/if}}", "stray"),
+            ("open {{REPO", "open {{REPO"),
+        ];
+        for (template, expected) in cases {
+            assert_eq!(
+                render(template, &vars()),
+                *expected,
+                "template: {}",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn values_are_not_re_expanded() {
+        let v = TemplateVars::new().set("A", "{{B}}").set("B", "b");
+        assert_eq!(render("{{A}}", &v), "{{B}}");
+    }
+
+    #[test]
+    fn placeholders_include_conditional_bodies_but_not_conditions() {
+        let found = placeholders("{{TODAY}} {{#if RULES_SECTION}}{{RULES_SECTION}}{{/if}}");
+        assert!(found.contains("TODAY"));
+        assert!(found.contains("RULES_SECTION"));
+        let condition_only = placeholders("{{#if RULES_SECTION}}rules{{/if}}");
+        assert!(condition_only.is_empty());
+    }
+
+    #[test]
+    fn check_syntax_rejects_unbalanced_blocks() {
+        let cases: &[(&str, bool)] = &[
+            ("{{#if A}}{{#if B}}x{{/if}}{{/if}}", true),
+            ("{{#if A}}x", false),
+            ("x{{/if}}", false),
+            ("{{/if}}{{#if A}}", false),
+            (r#"{{"id": 1}}"#, true),
+        ];
+        for (template, ok) in cases {
+            assert_eq!(
+                check_syntax(template).is_ok(),
+                *ok,
+                "template: {}",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn workspace_vars_override_builtins() {
+        let ws = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(ws.path().join(".skilllite")).unwrap();
+        std::fs::write(
+            ws.path().join(PROMPT_VARS_FILE),
+            r#"{"REPO": "mine", "STARS": 3, "nested": {"x": 1}, "bad name": "x"}"#,
+        )
+        .unwrap();
+        let v = vars().with_workspace(Some(ws.path()));
+        assert_eq!(v.get("REPO"), Some("mine"));
+        assert_eq!(v.get("STARS"), Some("3"));
+        assert_eq!(v.get("LANG"), Some("Rust"));
+        assert_eq!(v.get("nested"), None);
+        assert_eq!(v.get("bad name"), None);
+
+        std::fs::write(ws.path().join(PROMPT_VARS_FILE), "not json").unwrap();
+        let v = vars().with_workspace(Some(ws.path()));
+        assert_eq!(v.get("REPO"), Some("skilllite"));
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! When A9 fires on **periodic** cadence with **no** decision backlog, running the full
 //! evolution pipeline still pays for snapshots and learner setup. This module skips that
-//! work when there is **no** weighted/unprocessed signal and no skill-tree / external work.
+//! work when there is **no** weighted/unprocessed signal and no skill-tree / external work
+//! (including no uninstalled skill whose rules still await retirement).
 //!
 //! Trade-off: when skipped, **prompt rule retirement** (which can run without new decisions)
 //! is also skipped for that tick. Disable via `SKILLLITE_EVO_SHALLOW_PREFLIGHT=0`.
@@ -49,6 +50,10 @@ pub fn shallow_skip_evolution_run(
     if external_learner::should_run_external_learning(conn) {
         return Ok(None);
     }
+    // Rules and examples naming an uninstalled skill are retired by the prompt pass.
+    if scope.prompts && !crate::skill_removal::pending_skill_removals(conn)?.is_empty() {
+        return Ok(None);
+    }
     // Skill retire / refine / generation consult DB + disk under `.skills`.
     if scope.skills && skills_root.is_some_and(|p| p.is_dir()) {
         return Ok(None);
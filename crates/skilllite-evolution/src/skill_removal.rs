@@ -0,0 +1,341 @@
+//! Evolved prompt content that depends on an uninstalled skill.
+//!
+//! Rules and examples name tools (`tool_hint`, instructions, plan templates), so after
+//! `skilllite remove` the planner would keep suggesting a tool that no longer exists.
+//! [`record_skill_removed`] logs a `skill_removed` event; the next prompt evolution pass
+//! retires the mutable rules and archives the evolved examples that still mention the skill,
+//! then logs `skill_removal_applied` so each removal is handled once. Seed content is only
+//! reported by [`find_skill_references`], never touched.
+
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use skilllite_core::planning::PlanningRule;
+use skilllite_core::skill::schema::sanitize_tool_name;
+
+use crate::audit::log_evolution_event;
+use crate::error::bail;
+use crate::gatekeeper_l1_path;
+use crate::journal::TxnWrites;
+use crate::prompt_learner::{examples_archive_path, examples_path, read_examples};
+use crate::Result;
+
+/// Logged by `skilllite remove`; `target_id` is the skill name.
+pub const EVOLUTION_LOG_TYPE_SKILL_REMOVED: &str = "skill_removed";
+/// Logged once the dependent rules / examples of a removed skill were retired.
+pub const EVOLUTION_LOG_TYPE_SKILL_REMOVAL_APPLIED: &str = "skill_removal_applied";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    Rule,
+    Example,
+}
+
+/// A rule or example in `prompts/` that mentions a skill or one of its tools.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkillReference {
+    pub kind: ReferenceKind,
+    pub id: String,
+    /// `false` for seed content, which is never retired automatically.
+    pub mutable: bool,
+}
+
+/// Whether `text` names the skill or one of its tools (`<tool>` or `<tool>__<script>`).
+/// Matches are case-insensitive and must not be part of a longer identifier.
+pub fn mentions_skill(text: &str, skill_name: &str) -> bool {
+    let text = text.to_lowercase();
+    let tool = sanitize_tool_name(skill_name);
+    let name = skill_name.trim().to_lowercase();
+    let found = [tool.as_str(), name.as_str()]
+        .into_iter()
+        .filter(|needle| !needle.is_empty())
+        .any(|needle| mentions_identifier(&text, needle));
+    found
+}
+
+fn mentions_identifier(haystack: &str, needle: &str) -> bool {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    haystack.match_indices(needle).any(|(i, _)| {
+        let before = haystack[..i].chars().next_back();
+        let rest = &haystack[i + needle.len()..];
+        !before.is_some_and(is_ident)
+            && (rest.starts_with("__") || !rest.chars().next().is_some_and(is_ident))
+    })
+}
+
+fn rule_mentions(rule: &PlanningRule, skill_name: &str) -> bool {
+    let requires = rule.scope.as_ref().and_then(|s| s.requires_skill.as_ref());
+    rule.tool_hint
+        .iter()
+        .chain(requires)
+        .any(|t| t.eq_ignore_ascii_case(skill_name) || mentions_skill(t, skill_name))
+        || mentions_skill(&rule.instruction, skill_name)
+        || rule
+            .keywords
+            .iter()
+            .chain(&rule.context_keywords)
+            .any(|k| mentions_skill(k, skill_name))
+}
+
+fn read_rules(chat_root: &Path) -> Vec<PlanningRule> {
+    skilllite_fs::read_file(&rules_path(chat_root))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn rules_path(chat_root: &Path) -> std::path::PathBuf {
+    chat_root.join("prompts").join("rules.json")
+}
+
+/// Rules in `rules.json` and examples in `examples.json` that mention `skill_name`.
+pub fn find_skill_references(chat_root: &Path, skill_name: &str) -> Vec<SkillReference> {
+    let rules = read_rules(chat_root)
+        .into_iter()
+        .filter(|r| rule_mentions(r, skill_name))
+        .map(|r| SkillReference {
+            kind: ReferenceKind::Rule,
+            id: r.id,
+            mutable: r.mutable,
+        });
+    let examples = read_examples(&examples_path(chat_root))
+        .into_iter()
+        .filter(|e| {
+            [&e.task_pattern, &e.plan_template, &e.key_insight]
+                .iter()
+                .any(|t| mentions_skill(t, skill_name))
+        })
+        .map(|e| SkillReference {
+            kind: ReferenceKind::Example,
+            mutable: e.origin != "seed",
+            id: e.id,
+        });
+    rules.chain(examples).collect()
+}
+
+/// Log that `skill_name` was uninstalled so the next cycle retires what depends on it.
+pub fn record_skill_removed(conn: &Connection, chat_root: &Path, skill_name: &str) -> Result<()> {
+    let references = find_skill_references(chat_root, skill_name);
+    let reason = format!(
+        "uninstalled; {} rule(s), {} example(s) reference it",
+        references
+            .iter()
+            .filter(|r| r.kind == ReferenceKind::Rule)
+            .count(),
+        references
+            .iter()
+            .filter(|r| r.kind == ReferenceKind::Example)
+            .count()
+    );
+    log_evolution_event(
+        conn,
+        chat_root,
+        EVOLUTION_LOG_TYPE_SKILL_REMOVED,
+        skill_name,
+        &reason,
+        "",
+    )
+}
+
+/// Removed skills whose dependents have not been retired yet.
+pub fn pending_skill_removals(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT r.target_id FROM evolution_log r
+         WHERE r.type = ?1 AND r.target_id IS NOT NULL
+           AND NOT EXISTS (
+               SELECT 1 FROM evolution_log a
+               WHERE a.type = ?2 AND a.target_id = r.target_id AND a.id > r.id)
+         ORDER BY r.target_id",
+    )?;
+    let names = stmt
+        .query_map(
+            params![
+                EVOLUTION_LOG_TYPE_SKILL_REMOVED,
+                EVOLUTION_LOG_TYPE_SKILL_REMOVAL_APPLIED
+            ],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(names)
+}
+
+/// Retire mutable rules and archive evolved examples that mention a pending removed skill.
+/// Returns `(change_type, id)` pairs for the changelog.
+pub(crate) fn retire_for_removed_skills_with_conn(
+    chat_root: &Path,
+    txn_id: &str,
+    conn: &Connection,
+) -> Result<Vec<(String, String)>> {
+    let pending = pending_skill_removals(conn)?;
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rules_path = rules_path(chat_root);
+    let examples_path = examples_path(chat_root);
+    for path in [&rules_path, &examples_path] {
+        if !gatekeeper_l1_path(chat_root, path, None) {
+            bail!(
+                "Gatekeeper L1: {} path outside allowed directories",
+                path.display()
+            );
+        }
+    }
+
+    let mut changes = Vec::new();
+    let mut rules = read_rules(chat_root);
+    let mut examples = read_examples(&examples_path);
+    let mut archive = read_examples(&examples_archive_path(chat_root));
+    let (rule_count, example_count) = (rules.len(), examples.len());
+    let now = chrono::Utc::now().to_rfc3339();
+
+    for skill in &pending {
+        let reason = format!("skill '{}' was removed", skill);
+        rules.retain(|rule| {
+            if !rule.mutable || !rule_mentions(rule, skill) {
+                return true;
+            }
+            let _ = log_evolution_event(conn, chat_root, "rule_retired", &rule.id, &reason, txn_id);
+            changes.push(("rule_retired".to_string(), rule.id.clone()));
+            false
+        });
+        let (gone, kept): (Vec<_>, Vec<_>) = examples.into_iter().partition(|e| {
+            e.origin != "seed"
+                && [&e.task_pattern, &e.plan_template, &e.key_insight]
+                    .iter()
+                    .any(|t| mentions_skill(t, skill))
+        });
+        examples = kept;
+        for mut example in gone {
+            let _ = log_evolution_event(
+                conn,
+                chat_root,
+                "example_retired",
+                &example.id,
+                &reason,
+                txn_id,
+            );
+            changes.push(("example_retired".to_string(), example.id.clone()));
+            example.retired_at = Some(now.clone());
+            archive.retain(|a| a.id != example.id);
+            archive.push(example);
+        }
+        log_evolution_event(
+            conn,
+            chat_root,
+            EVOLUTION_LOG_TYPE_SKILL_REMOVAL_APPLIED,
+            skill,
+            &reason,
+            txn_id,
+        )?;
+        tracing::info!(
+            "Retired prompt content referencing removed skill '{}'",
+            skill
+        );
+    }
+
+    let txn = TxnWrites::new(chat_root, txn_id);
+    if rules.len() != rule_count {
+        txn.write(&rules_path, &serde_json::to_string_pretty(&rules)?)?;
+    }
+    if examples.len() != example_count {
+        txn.write(
+            &examples_archive_path(chat_root),
+            &serde_json::to_string_pretty(&archive)?,
+        )?;
+        txn.write(&examples_path, &serde_json::to_string_pretty(&examples)?)?;
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(chat_root: &Path) -> Connection {
+        let prompts = chat_root.join("prompts");
+        std::fs::create_dir_all(&prompts).unwrap();
+        std::fs::write(
+            prompts.join("rules.json"),
+            r#"[
+              {"id": "seed_weather", "instruction": "Use weather_tool for forecasts", "mutable": false, "origin": "seed"},
+              {"id": "evo_weather", "tool_hint": "weather_tool__daily", "instruction": "Prefer the daily script", "mutable": true, "origin": "evolved"},
+              {"id": "evo_scoped", "instruction": "Cite the source", "mutable": true, "origin": "evolved", "scope": {"requires_skill": "weather-tool"}},
+              {"id": "evo_other", "instruction": "Use weather_tools_pro, not weather_toolbox", "mutable": true, "origin": "evolved"}
+            ]"#,
+        )
+        .unwrap();
+        std::fs::write(
+            prompts.join("examples.json"),
+            r#"[
+              {"id": "ex_weather", "task_pattern": "forecast", "plan_template": "1. weather_tool\n2. write_output", "key_insight": "", "origin": "evolved"},
+              {"id": "ex_csv", "task_pattern": "csv", "plan_template": "1. read_file", "key_insight": "", "origin": "evolved"}
+            ]"#,
+        )
+        .unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+        crate::feedback::ensure_evolution_tables(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn tool_names_match_whole_identifiers_only() {
+        assert!(mentions_skill("call weather_tool first", "weather-tool"));
+        assert!(mentions_skill(
+            "使用weather_tool__daily查询",
+            "weather-tool"
+        ));
+        assert!(mentions_skill("the Weather-Tool skill", "weather-tool"));
+        assert!(!mentions_skill("weather_toolbox", "weather-tool"));
+        assert!(!mentions_skill("my_weather_tool", "weather-tool"));
+    }
+
+    #[test]
+    fn references_cover_rules_examples_and_scope() {
+        let tmp = tempfile::tempdir().unwrap();
+        setup(tmp.path());
+        let refs = find_skill_references(tmp.path(), "weather-tool");
+        let ids: Vec<_> = refs.iter().map(|r| (r.id.as_str(), r.mutable)).collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("seed_weather", false),
+                ("evo_weather", true),
+                ("evo_scoped", true),
+                ("ex_weather", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn pending_removal_retires_dependents_once() {
+        let tmp = tempfile::tempdir().unwrap();
+        let conn = setup(tmp.path());
+        record_skill_removed(&conn, tmp.path(), "weather-tool").unwrap();
+        assert_eq!(pending_skill_removals(&conn).unwrap(), vec!["weather-tool"]);
+
+        let changes = retire_for_removed_skills_with_conn(tmp.path(), "evo_1", &conn).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                ("rule_retired".to_string(), "evo_weather".to_string()),
+                ("rule_retired".to_string(), "evo_scoped".to_string()),
+                ("example_retired".to_string(), "ex_weather".to_string()),
+            ]
+        );
+        let left: Vec<_> = read_rules(tmp.path()).into_iter().map(|r| r.id).collect();
+        assert_eq!(left, vec!["seed_weather", "evo_other"]);
+        let archived = read_examples(&examples_archive_path(tmp.path()));
+        assert_eq!(archived[0].id, "ex_weather");
+        assert!(archived[0].retired_at.is_some());
+
+        assert!(pending_skill_removals(&conn).unwrap().is_empty());
+        assert!(
+            retire_for_removed_skills_with_conn(tmp.path(), "evo_2", &conn)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -7,6 +7,7 @@ use crate::Result;
 use crate::feedback;
 use crate::gatekeeper_l1_path;
 use crate::gatekeeper_l3_content;
+use crate::journal::TxnWrites;
 use crate::EvolutionLlm;
 use crate::EvolutionMessage;
 
@@ -17,10 +18,10 @@ use super::refine;
 use super::repair;
 use super::scan;
 use super::validate;
-use super::SkillMeta;
 use super::MAX_EVOLVED_SKILLS;
 use super::SKILL_GENERATION_FROM_FAILURES_PROMPT;
 use super::SKILL_GENERATION_PROMPT;
+use super::{SkillMeta, SCAN_FAILED, SCAN_FIXED, SCAN_PASSED};
 
 /// Pre-fetched (patterns_display, executions) for success-driven generation. When `Some`, caller holds conn and passed data to avoid reopening DB.
 pub(super) type SuccessQueryData = (String, String);
@@ -166,6 +167,7 @@ pub(super) async fn generate_skill_inner<L: EvolutionLlm>(
     }
     std::fs::create_dir_all(&skill_dir)?;
 
+    let txn = TxnWrites::new(chat_root, txn_id);
     let script_path = skill_dir.join(&parsed.entry_point);
     let skill_md_path = skill_dir.join("SKILL.md");
 
@@ -196,15 +198,14 @@ pub(super) async fn generate_skill_inner<L: EvolutionLlm>(
                     &parsed.skill_md_content,
                     &fixed_script,
                     &parsed.name,
-                    txn_id,
-                    false,
+                    txn,
+                    SCAN_FIXED,
                 )?;
                 let (ep, ti) = infer::infer_skill_execution(llm, model, &skill_dir)
                     .await
                     .unwrap_or_else(|_| (parsed.entry_point.clone(), "{}".to_string()));
-                let _ =
-                    repair::repair_one_skill(llm, model, &skill_dir, &parsed.name, &ep, &ti, None)
-                        .await?;
+                let _ = repair::repair_skill_dir(Some(txn), llm, model, &skill_dir, &ep, &ti, None)
+                    .await?;
             }
             None => {
                 let final_script = parsed.script_content.clone();
@@ -215,8 +216,8 @@ pub(super) async fn generate_skill_inner<L: EvolutionLlm>(
                     &parsed.skill_md_content,
                     &final_script,
                     &parsed.name,
-                    txn_id,
-                    true,
+                    txn,
+                    SCAN_FAILED,
                 )?;
                 tracing::info!(
                     "Skill '{}' saved as draft (L4 未通过，需人工审核后 confirm)",
@@ -233,14 +234,13 @@ pub(super) async fn generate_skill_inner<L: EvolutionLlm>(
             &parsed.skill_md_content,
             &final_script,
             &parsed.name,
-            txn_id,
-            false,
+            txn,
+            SCAN_PASSED,
         )?;
         let (ep, ti) = infer::infer_skill_execution(llm, model, &skill_dir)
             .await
             .unwrap_or_else(|_| (parsed.entry_point.clone(), "{}".to_string()));
-        let _ =
-            repair::repair_one_skill(llm, model, &skill_dir, &parsed.name, &ep, &ti, None).await?;
+        let _ = repair::repair_skill_dir(Some(txn), llm, model, &skill_dir, &ep, &ti, None).await?;
     }
 
     Ok(Some(parsed.name))
@@ -318,11 +318,11 @@ fn write_skill_files(
     skill_md: &str,
     script: &str,
     name: &str,
-    txn_id: &str,
-    needs_review: bool,
+    txn: TxnWrites,
+    scan_status: &str,
 ) -> Result<()> {
-    skilllite_fs::write_file(skill_md_path, skill_md)?;
-    skilllite_fs::write_file(script_path, script)?;
+    txn.write(skill_md_path, skill_md)?;
+    txn.write(script_path, script)?;
 
     #[cfg(unix)]
     {
@@ -339,11 +339,10 @@ fn write_skill_files(
         call_count: 0,
         last_used: None,
         archived: false,
-        generation_txn: txn_id.to_string(),
-        needs_review,
+        generation_txn: txn.txn_id.to_string(),
+        needs_review: scan_status == SCAN_FAILED,
+        scan_status: scan_status.to_string(),
     };
     let meta_path = skill_dir.join(".meta.json");
-    skilllite_fs::write_file(&meta_path, &serde_json::to_string_pretty(&meta)?)?;
-
-    Ok(())
+    txn.write(&meta_path, &serde_json::to_string_pretty(&meta)?)
 }
@@ -15,6 +15,7 @@ use super::MAX_PARSE_RETRIES;
 use super::SKILL_EXECUTION_INFERENCE_PROMPT;
 use crate::EvolutionLlm;
 use crate::EvolutionMessage;
+use crate::{complete_with_retry, LlmRetryPolicy};
 
 /// 从文本中提取第一个平衡的 JSON 对象 {...}
 pub(super) fn extract_first_json_object(text: &str) -> Option<&str> {
@@ -270,7 +271,7 @@ pub(super) async fn infer_skill_execution<L: EvolutionLlm>(
         EvolutionMessage::system(SKILL_EXECUTION_INFERENCE_PROMPT),
         EvolutionMessage::user(&prompt),
     ];
-    let out1 = llm.complete(&messages, model, 0.0).await?;
+    let out1 = complete_with_retry(llm, &messages, model, 0.0, &LlmRetryPolicy::from_env()).await?;
     let trimmed = out1.visible.trim();
 
     #[derive(serde::Deserialize)]
@@ -304,7 +305,7 @@ pub(super) async fn infer_skill_execution<L: EvolutionLlm>(
         let mut msgs = messages.to_vec();
         out1.push_assistant_replay(&mut msgs);
         msgs.push(EvolutionMessage::user(&retry_msg));
-        let out2 = llm.complete(&msgs, model, 0.0).await?;
+        let out2 = complete_with_retry(llm, &msgs, model, 0.0, &LlmRetryPolicy::from_env()).await?;
         parsed = try_parse_infer(out2.visible.trim());
     }
 
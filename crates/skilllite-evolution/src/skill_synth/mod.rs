@@ -19,6 +19,7 @@ mod env_helper;
 mod generate;
 mod infer;
 mod parse;
+mod pending;
 mod query;
 mod refine;
 mod repair;
@@ -30,7 +31,9 @@ use std::path::Path;
 
 use rusqlite::Connection;
 
+use crate::change_set::{ChangeKind, ChangeSet, DroppedChange};
 use crate::error::bail;
+use crate::journal::TxnWrites;
 use crate::Result;
 use tokio::task::block_in_place;
 
@@ -53,6 +56,13 @@ pub(super) const MAX_PARSE_RETRIES: usize = 1;
 pub(super) const RETIRE_UNUSED_DAYS: i64 = 30;
 pub(super) const RETIRE_LOW_SUCCESS_RATE: f64 = 0.30;
 
+/// L4 scan outcome recorded in [`SkillMeta::scan_status`].
+pub const SCAN_PASSED: &str = "passed";
+/// Scan failed but the refine loop produced a script that passes.
+pub const SCAN_FIXED: &str = "fixed";
+/// Scan failed and refinement did not help; the skill is saved with `needs_review`.
+pub const SCAN_FAILED: &str = "failed";
+
 // ─── Skill metadata ───────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -70,6 +80,9 @@ pub struct SkillMeta {
     pub generation_txn: String,
     #[serde(default)]
     pub needs_review: bool,
+    /// Empty for skills generated before scan results were recorded.
+    #[serde(default)]
+    pub scan_status: String,
 }
 
 impl SkillMeta {
@@ -83,6 +96,14 @@ impl SkillMeta {
 
 // ─── Main entry: evolve skills ────────────────────────────────────────────────
 
+/// Result of [`evolve_skills`].
+#[derive(Debug, Default)]
+pub struct SkillEvolution {
+    pub changes: Vec<(String, String)>,
+    /// Generated skills the gatekeepers kept out; their pending dirs are removed.
+    pub dropped: Vec<DroppedChange>,
+}
+
 /// Run skill evolution: generate new skills or refine existing ones.
 pub async fn evolve_skills<L: EvolutionLlm>(
     chat_root: &Path,
@@ -92,11 +113,12 @@ pub async fn evolve_skills<L: EvolutionLlm>(
     txn_id: &str,
     generate: bool,
     force: bool,
-) -> Result<Vec<(String, String)>> {
+) -> Result<SkillEvolution> {
     let Some(skills_root) = skills_root else {
-        return Ok(Vec::new());
+        return Ok(SkillEvolution::default());
     };
     let mut changes = Vec::new();
+    let mut dropped = Vec::new();
 
     let try_generate = generate || force;
     let min_pattern_count: u32 =
@@ -126,6 +148,15 @@ pub async fn evolve_skills<L: EvolutionLlm>(
             ))
         })?;
         changes.extend(retired);
+
+        // Failure-driven skills fix something that broke, so they outrank success patterns.
+        let pending_dir = skills_root.join("_evolved").join("_pending");
+        let mut proposed = ChangeSet::new(ChangeKind::Skill);
+        let mut propose = |name: String, score: f32| {
+            let skill_md = std::fs::read_to_string(pending_dir.join(&name).join("SKILL.md"))
+                .unwrap_or_default();
+            proposed.propose(name.clone(), score, &skill_md, name);
+        };
         if let Ok(Some(name)) = generate::generate_skill_from_failures(
             chat_root,
             skills_root,
@@ -136,7 +167,7 @@ pub async fn evolve_skills<L: EvolutionLlm>(
         )
         .await
         {
-            changes.push(("skill_pending".to_string(), name));
+            propose(name, 1.0);
         }
         if let Ok(Some(name)) = generate::generate_skill(
             chat_root,
@@ -149,8 +180,22 @@ pub async fn evolve_skills<L: EvolutionLlm>(
         )
         .await
         {
-            changes.push(("skill_pending".to_string(), name));
+            propose(name, 0.5);
         }
+        let applied = proposed.finish();
+        for skipped in &applied.dropped {
+            let dir = pending_dir.join(&skipped.id);
+            if let Err(e) = TxnWrites::new(chat_root, txn_id).remove_dir(&dir) {
+                tracing::warn!("Failed to remove dropped skill {}: {}", dir.display(), e);
+            }
+        }
+        changes.extend(
+            applied
+                .accepted
+                .into_iter()
+                .map(|name| ("skill_pending".to_string(), name)),
+        );
+        dropped = applied.dropped;
         if changes.is_empty() {
             if let Ok(Some(name)) =
                 refine::refine_weakest_skill(chat_root, skills_root, llm, model, txn_id).await
@@ -182,7 +227,7 @@ pub async fn evolve_skills<L: EvolutionLlm>(
         }
     });
 
-    Ok(changes)
+    Ok(SkillEvolution { changes, dropped })
 }
 
 /// Decision ids whose rows are read for skill generation inputs (repeated successes + recent failures).
@@ -212,13 +257,6 @@ pub(crate) fn decision_ids_read_for_skill_evolution(
 
 // ─── A10: Pending skill confirmation ─────────────────────────────────────────
 
-pub fn list_pending_skills(skills_root: &Path) -> Vec<String> {
-    list_pending_skills_with_review(skills_root)
-        .into_iter()
-        .map(|(name, _)| name)
-        .collect()
-}
-
 pub fn list_pending_skills_with_review(skills_root: &Path) -> Vec<(String, bool)> {
     let pending_dir = skills_root.join("_evolved").join("_pending");
     if !pending_dir.exists() {
@@ -275,6 +313,7 @@ pub fn reject_pending_skill(skills_root: &Path, skill_name: &str) -> Result<()>
 
 // ─── Re-exports ──────────────────────────────────────────────────────────────
 
+pub use pending::{list_pending_skills, pending_stale_days, PendingSkill, PendingSkillFile};
 pub use repair::{repair_one_skill, repair_skills};
 pub use scan::track_skill_usage;
 pub use validate::{validate_skills, SkillValidation};
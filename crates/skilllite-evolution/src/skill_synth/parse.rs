@@ -6,6 +6,7 @@ use regex::Regex;
 use crate::prompt_learner;
 use crate::EvolutionLlm;
 use crate::EvolutionMessage;
+use crate::{complete_with_retry, LlmRetryPolicy};
 
 use super::infer;
 use super::MAX_PARSE_RETRIES;
@@ -115,7 +116,8 @@ pub(super) async fn parse_skill_generation_with_retry<L: EvolutionLlm>(
     messages: &[EvolutionMessage],
 ) -> Result<Option<GeneratedSkill>> {
     let mut history = messages.to_vec();
-    let mut last = llm.complete(&history, model, 0.3).await?;
+    let mut last =
+        complete_with_retry(llm, &history, model, 0.3, &LlmRetryPolicy::from_env()).await?;
 
     match parse_skill_generation_response(last.visible.trim()) {
         ok @ Ok(_) => ok,
@@ -133,7 +135,8 @@ pub(super) async fn parse_skill_generation_with_retry<L: EvolutionLlm>(
                 );
                 last.push_assistant_replay(&mut history);
                 history.push(EvolutionMessage::user(&retry_msg));
-                last = llm.complete(&history, model, 0.3).await?;
+                last = complete_with_retry(llm, &history, model, 0.3, &LlmRetryPolicy::from_env())
+                    .await?;
                 match parse_skill_generation_response(last.visible.trim()) {
                     ok @ Ok(_) => return ok,
                     Err(e2) => {
@@ -0,0 +1,269 @@
+//! A10: review data for skills waiting in `_evolved/_pending/`.
+//!
+//! One [`PendingSkill`] per pending directory: what the skill does (description excerpt),
+//! what it runs (entry point, language, network), what the L4 scan recorded, and the exact
+//! confirm / reject commands. Shared by `skilllite evolution status` and the desktop
+//! "review pending skills" card (via the status JSON).
+
+use std::path::Path;
+
+use serde::Serialize;
+use skilllite_core::config::env_keys::evolution as evo_env;
+use skilllite_core::skill::metadata::{detect_language, parse_skill_metadata};
+
+use super::SkillMeta;
+
+/// Lines of SKILL.md description shown per pending skill.
+const EXCERPT_MAX_LINES: usize = 5;
+const EXCERPT_LINE_MAX_CHARS: usize = 120;
+/// Default for `SKILLLITE_EVO_PENDING_STALE_DAYS`.
+const DEFAULT_STALE_DAYS: u64 = 14;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingSkillFile {
+    /// Relative to the skill directory, `/`-separated.
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingSkill {
+    pub name: String,
+    /// RFC 3339 generation time from `.meta.json`, else the directory mtime.
+    pub created_at: Option<String>,
+    pub generation_txn: Option<String>,
+    /// Up to 5 lines: the front-matter description, then the first lines of the body.
+    pub excerpt: Vec<String>,
+    pub entry_point: String,
+    pub language: String,
+    pub network: bool,
+    /// Declared outbound hosts; empty with `network` means any HTTP/HTTPS host.
+    pub network_domains: Vec<String>,
+    /// L4 scan outcome (`passed` / `fixed` / `failed`); `None` for skills generated before
+    /// scan results were recorded.
+    pub scan_status: Option<String>,
+    pub needs_review: bool,
+    pub files: Vec<PendingSkillFile>,
+    pub age_days: Option<u64>,
+    /// Pending longer than `SKILLLITE_EVO_PENDING_STALE_DAYS`: confirm or clean it up.
+    pub stale: bool,
+    pub confirm_command: String,
+    pub reject_command: String,
+}
+
+/// `SKILLLITE_EVO_PENDING_STALE_DAYS` (default 14; `0` never flags).
+pub fn pending_stale_days() -> u64 {
+    std::env::var(evo_env::SKILLLITE_EVO_PENDING_STALE_DAYS)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_STALE_DAYS)
+}
+
+/// Pending skills under `<skills_root>/_evolved/_pending/`, oldest first.
+pub fn list_pending_skills(skills_root: &Path) -> Vec<PendingSkill> {
+    let pending_dir = skills_root.join("_evolved").join("_pending");
+    let stale_days = pending_stale_days();
+    let now = chrono::Utc::now();
+    let mut skills: Vec<PendingSkill> = std::fs::read_dir(&pending_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir() && e.path().join("SKILL.md").is_file())
+        .map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            describe_pending_skill(&e.path(), name, stale_days, now)
+        })
+        .collect();
+    skills.sort_by(|a, b| {
+        a.created_at
+            .cmp(&b.created_at)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    skills
+}
+
+fn describe_pending_skill(
+    dir: &Path,
+    name: String,
+    stale_days: u64,
+    now: chrono::DateTime<chrono::Utc>,
+) -> PendingSkill {
+    let meta = std::fs::read_to_string(dir.join(".meta.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<SkillMeta>(&s).ok());
+    let created = meta
+        .as_ref()
+        .and_then(|m| chrono::DateTime::parse_from_rfc3339(&m.created_at).ok())
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .or_else(|| {
+            let modified = std::fs::metadata(dir).and_then(|m| m.modified()).ok()?;
+            Some(chrono::DateTime::<chrono::Utc>::from(modified))
+        });
+    let age_days = created.map(|t| (now - t).num_days().max(0) as u64);
+
+    let metadata = parse_skill_metadata(dir).ok();
+    let skill_md = std::fs::read_to_string(dir.join("SKILL.md")).unwrap_or_default();
+    let (entry_point, language, network, network_domains) = match &metadata {
+        Some(m) => (
+            m.entry_point.clone(),
+            detect_language(dir, m),
+            m.network.enabled,
+            m.network.declared_hosts(),
+        ),
+        None => (String::new(), "unknown".to_string(), false, Vec::new()),
+    };
+
+    PendingSkill {
+        created_at: created.map(|t| t.to_rfc3339()),
+        generation_txn: meta
+            .as_ref()
+            .map(|m| m.generation_txn.clone())
+            .filter(|t| !t.is_empty()),
+        excerpt: excerpt(
+            metadata.as_ref().and_then(|m| m.description.as_deref()),
+            &skill_md,
+        ),
+        entry_point,
+        language,
+        network,
+        network_domains,
+        scan_status: meta
+            .as_ref()
+            .map(|m| m.scan_status.clone())
+            .filter(|s| !s.is_empty()),
+        needs_review: meta.as_ref().is_some_and(|m| m.needs_review),
+        files: list_files(dir),
+        age_days,
+        stale: stale_days > 0 && age_days.is_some_and(|d| d >= stale_days),
+        confirm_command: format!("skilllite evolution confirm {}", name),
+        reject_command: format!("skilllite evolution reject {}", name),
+        name,
+    }
+}
+
+/// Description lines first, topped up from the SKILL.md body (headings and fences skipped).
+fn excerpt(description: Option<&str>, skill_md: &str) -> Vec<String> {
+    let body = skill_md
+        .strip_prefix("---")
+        .and_then(|rest| rest.split_once("\n---"))
+        .map(|(_, body)| body.split_once('\n').map_or("", |(_, b)| b))
+        .unwrap_or(skill_md);
+    description
+        .unwrap_or_default()
+        .lines()
+        .chain(
+            body.lines()
+                .filter(|l| !l.trim_start().starts_with('#') && !l.trim_start().starts_with("```")),
+        )
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .take(EXCERPT_MAX_LINES)
+        .map(|l| match l.char_indices().nth(EXCERPT_LINE_MAX_CHARS) {
+            Some((cut, _)) => format!("{}…", &l[..cut]),
+            None => l.to_string(),
+        })
+        .collect()
+}
+
+/// Every file in the skill except `.meta.json`, sorted by path.
+fn list_files(dir: &Path) -> Vec<PendingSkillFile> {
+    fn walk(root: &Path, dir: &Path, out: &mut Vec<PendingSkillFile>) {
+        for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                walk(root, &path, out);
+            } else if entry.file_name() != ".meta.json" {
+                let rel = path.strip_prefix(root).unwrap_or(&path);
+                out.push(PendingSkillFile {
+                    path: rel
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                    bytes: meta.len(),
+                });
+            }
+        }
+    }
+    let mut files = Vec::new();
+    walk(dir, dir, &mut files);
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_pending(skills_root: &Path, name: &str, created_at: &str, scan_status: &str) {
+        let dir = skills_root.join("_evolved").join("_pending").join(name);
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!(
+                "---\nname: {name}\ndescription: Fetch exchange rates\nentry_point: scripts/main.py\n\
+                 network:\n  outbound: [\"api.frankfurter.app\"]\n---\n\n# {name}\n\n\
+                 Looks up the latest rate.\nReturns JSON.\n"
+            ),
+        )
+        .unwrap();
+        std::fs::write(dir.join("scripts").join("main.py"), "print('{}')\n").unwrap();
+        let meta = SkillMeta {
+            name: name.to_string(),
+            source_session: "s".to_string(),
+            created_at: created_at.to_string(),
+            success_count: 0,
+            failure_count: 0,
+            call_count: 0,
+            last_used: None,
+            archived: false,
+            generation_txn: "evo_txn_1".to_string(),
+            needs_review: scan_status == super::super::SCAN_FAILED,
+            scan_status: scan_status.to_string(),
+        };
+        std::fs::write(
+            dir.join(".meta.json"),
+            serde_json::to_string(&meta).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn pending_skills_carry_review_details_and_staleness() {
+        let tmp = tempfile::tempdir().unwrap();
+        let old = (chrono::Utc::now() - chrono::Duration::days(40)).to_rfc3339();
+        let fresh = chrono::Utc::now().to_rfc3339();
+        write_pending(tmp.path(), "fx-rates", &fresh, "passed");
+        write_pending(tmp.path(), "old-helper", &old, "failed");
+
+        let skills = list_pending_skills(tmp.path());
+        let names: Vec<&str> = skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["old-helper", "fx-rates"]);
+
+        let fx = &skills[1];
+        assert_eq!(
+            fx.excerpt,
+            [
+                "Fetch exchange rates",
+                "Looks up the latest rate.",
+                "Returns JSON."
+            ]
+        );
+        assert_eq!(fx.generation_txn.as_deref(), Some("evo_txn_1"));
+        assert_eq!(fx.language, "python");
+        assert!(fx.network);
+        assert_eq!(fx.network_domains, ["api.frankfurter.app"]);
+        assert_eq!(fx.scan_status.as_deref(), Some("passed"));
+        let files: Vec<&str> = fx.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(files, ["SKILL.md", "scripts/main.py"]);
+        assert_eq!(fx.confirm_command, "skilllite evolution confirm fx-rates");
+        assert!(!fx.stale);
+
+        let old = &skills[0];
+        assert!(old.stale && old.needs_review);
+        assert!(old.age_days.unwrap() >= 40);
+    }
+}
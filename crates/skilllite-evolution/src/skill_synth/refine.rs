@@ -6,9 +6,11 @@ use crate::Result;
 
 use crate::feedback;
 use crate::gatekeeper_l3_content;
+use crate::journal::TxnWrites;
 use crate::log_evolution_event;
 use crate::EvolutionLlm;
 use crate::EvolutionMessage;
+use crate::{complete_with_retry, LlmRetryPolicy};
 
 use super::infer;
 use super::parse;
@@ -58,7 +60,8 @@ pub(super) async fn refine_loop<L: EvolutionLlm>(
             .replace("{{current_skill_md}}", "");
 
         let messages = vec![EvolutionMessage::user(&prompt)];
-        let out = llm.complete(&messages, model, 0.3).await?;
+        let out =
+            complete_with_retry(llm, &messages, model, 0.3, &LlmRetryPolicy::from_env()).await?;
         let content = out.visible.trim().to_string();
 
         let parsed = match parse::parse_refinement_response(&content) {
@@ -90,7 +93,9 @@ pub(super) async fn refine_loop<L: EvolutionLlm>(
                 let mut history = messages.to_vec();
                 out.push_assistant_replay(&mut history);
                 history.push(EvolutionMessage::user(&retry_msg));
-                let out2 = llm.complete(&history, model, 0.3).await?;
+                let out2 =
+                    complete_with_retry(llm, &history, model, 0.3, &LlmRetryPolicy::from_env())
+                        .await?;
                 let content2 = out2.visible.trim().to_string();
                 match parse::parse_refinement_response(&content2) {
                     Ok(Some(r)) => {
@@ -249,7 +254,7 @@ pub(super) async fn refine_weakest_skill<L: EvolutionLlm>(
     .await?;
 
     if let Some(fixed_script) = fixed {
-        skilllite_fs::write_file(&script_path, &fixed_script)?;
+        TxnWrites::new(chat_root, txn_id).write(&script_path, &fixed_script)?;
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
@@ -347,7 +352,8 @@ pub(super) fn retire_skills_with_conn(
 
         if let Some(reason) = should_retire {
             meta.archived = true;
-            let _ = skilllite_fs::write_file(&meta_path, &serde_json::to_string_pretty(&meta)?);
+            let _ = TxnWrites::new(chat_root, txn_id)
+                .write(&meta_path, &serde_json::to_string_pretty(&meta)?);
 
             let name = entry.file_name().to_string_lossy().to_string();
             tracing::info!("Retired skill '{}': {}", name, reason);
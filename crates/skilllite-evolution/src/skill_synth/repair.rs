@@ -5,8 +5,10 @@ use std::path::Path;
 use crate::Result;
 
 use crate::gatekeeper_l3_content;
+use crate::journal::{self, TxnWrites};
 use crate::EvolutionLlm;
 use crate::EvolutionMessage;
+use crate::{complete_with_retry, LlmRetryPolicy};
 
 use super::infer;
 use super::parse;
@@ -73,6 +75,19 @@ pub async fn repair_one_skill<L: EvolutionLlm>(
     entry_point: &str,
     test_input: &str,
     on_msg: Option<&(dyn Fn(&str) + Send + Sync)>,
+) -> Result<(bool, String)> {
+    repair_skill_dir(None, llm, model, skill_dir, entry_point, test_input, on_msg).await
+}
+
+/// [`repair_one_skill`]; inside an evolution txn the fixes are written through its journal.
+pub(super) async fn repair_skill_dir<L: EvolutionLlm>(
+    txn: Option<TxnWrites<'_>>,
+    llm: &L,
+    model: &str,
+    skill_dir: &Path,
+    entry_point: &str,
+    test_input: &str,
+    on_msg: Option<&(dyn Fn(&str) + Send + Sync)>,
 ) -> Result<(bool, String)> {
     let script_path = skill_dir.join(entry_point);
     let skill_md_path = skill_dir.join("SKILL.md");
@@ -137,7 +152,7 @@ pub async fn repair_one_skill<L: EvolutionLlm>(
             if gatekeeper_l3_content(md).is_ok()
                 && validate::check_skill_md_completeness_heuristic(md).is_none()
             {
-                skilllite_fs::write_file(&skill_md_path, md)?;
+                journal::write_file(txn, &skill_md_path, md)?;
             } else if gatekeeper_l3_content(md).is_ok() {
                 tracing::warn!(
                     "Repair returned fix_skill_md but still incomplete (missing Usage/Examples), skip applying"
@@ -146,7 +161,7 @@ pub async fn repair_one_skill<L: EvolutionLlm>(
         }
         if let Some(ref script) = parsed.fixed_script {
             if gatekeeper_l3_content(script).is_ok() {
-                skilllite_fs::write_file(&script_path, script)?;
+                journal::write_file(txn, &script_path, script)?;
                 #[cfg(unix)]
                 {
                     use std::os::unix::fs::PermissionsExt;
@@ -193,7 +208,8 @@ async fn llm_repair_call<L: EvolutionLlm>(
     error_trace: &str,
 ) -> Result<(Option<parse::RefinedSkill>, String)> {
     let mut history = messages.to_vec();
-    let mut last = llm.complete(&history, model, 0.3).await?;
+    let mut last =
+        complete_with_retry(llm, &history, model, 0.3, &LlmRetryPolicy::from_env()).await?;
     let mut last_raw = last.visible.trim().to_string();
 
     match parse::parse_refinement_response(&last_raw) {
@@ -209,7 +225,8 @@ async fn llm_repair_call<L: EvolutionLlm>(
             );
             last.push_assistant_replay(&mut history);
             history.push(EvolutionMessage::user(&retry_msg));
-            last = llm.complete(&history, model, 0.3).await?;
+            last =
+                complete_with_retry(llm, &history, model, 0.3, &LlmRetryPolicy::from_env()).await?;
             last_raw = last.visible.trim().to_string();
             if let Ok(Some(r)) = parse::parse_refinement_response(&last_raw) {
                 return Ok((Some(r), last_raw));
@@ -224,7 +241,7 @@ async fn llm_repair_call<L: EvolutionLlm>(
         );
         last.push_assistant_replay(&mut history);
         history.push(EvolutionMessage::user(&force_msg));
-        last = llm.complete(&history, model, 0.3).await?;
+        last = complete_with_retry(llm, &history, model, 0.3, &LlmRetryPolicy::from_env()).await?;
         last_raw = last.visible.trim().to_string();
         tracing::info!("Force retry {attempt}: raw len={}", last_raw.len());
         if let Ok(Some(r)) = parse::parse_refinement_response(&last_raw) {
@@ -9,6 +9,7 @@ use crate::Result;
 
 use crate::EvolutionLlm;
 use crate::EvolutionMessage;
+use crate::{complete_with_retry, LlmRetryPolicy};
 
 use super::infer;
 
@@ -39,7 +40,7 @@ pub(super) async fn check_skill_md_completeness<L: EvolutionLlm>(
     );
 
     let messages = vec![EvolutionMessage::user(&prompt)];
-    match llm.complete(&messages, model, 0.0).await {
+    match complete_with_retry(llm, &messages, model, 0.0, &LlmRetryPolicy::from_env()).await {
         Ok(response) => {
             let trimmed = response.visible.trim();
             if let Some(json_str) = infer::extract_first_json_object(trimmed) {
@@ -14,7 +14,7 @@ pub(crate) fn versions_dir(chat_root: &Path) -> std::path::PathBuf {
 
 /// How many evolution txn snapshot directories to keep under `prompts/_versions/`.
 /// `0` = keep all (no pruning). Default `10`. Invalid env falls back to default.
-fn evolution_snapshot_keep_count() -> usize {
+pub fn evolution_snapshot_keep_count() -> usize {
     match std::env::var(evo_keys::SKILLLITE_EVOLUTION_SNAPSHOT_KEEP)
         .ok()
         .as_deref()
@@ -24,6 +24,17 @@ fn evolution_snapshot_keep_count() -> usize {
     }
 }
 
+/// Prompts files backed up by [`create_extended_snapshot`].
+pub(crate) const SNAPSHOT_PROMPT_FILES: &[&str] = &[
+    "rules.json",
+    "examples.json",
+    "examples_archive.json",
+    "planning.md",
+    "execution.md",
+    "system.md",
+    "feedback_signals.json",
+];
+
 pub fn create_snapshot(chat_root: &Path, txn_id: &str, files: &[&str]) -> Result<Vec<String>> {
     let snap_dir = versions_dir(chat_root).join(txn_id);
     std::fs::create_dir_all(&snap_dir)?;
@@ -72,17 +83,7 @@ pub(crate) fn create_extended_snapshot(
 ) -> Result<Vec<String>> {
     let mut backed_up = Vec::new();
     if include_prompts {
-        backed_up.extend(create_snapshot(
-            chat_root,
-            txn_id,
-            &[
-                "rules.json",
-                "examples.json",
-                "planning.md",
-                "execution.md",
-                "system.md",
-            ],
-        )?);
+        backed_up.extend(create_snapshot(chat_root, txn_id, SNAPSHOT_PROMPT_FILES)?);
     } else {
         let snap_dir = versions_dir(chat_root).join(txn_id);
         std::fs::create_dir_all(&snap_dir)?;
@@ -121,7 +122,7 @@ pub fn restore_snapshot(chat_root: &Path, txn_id: &str) -> Result<()> {
     let prompts = chat_root.join("prompts");
     for entry in std::fs::read_dir(&snap_dir)? {
         let entry = entry?;
-        if entry.file_type()?.is_dir() {
+        if entry.file_type()?.is_dir() || entry.file_name() == crate::journal::JOURNAL_FILE {
             continue;
         }
         let dst = prompts.join(entry.file_name());
@@ -0,0 +1,357 @@
+//! End-to-end evolution cycles replayed from LLM fixtures (no network, no API key).
+//!
+//! Fixtures live in `tests/fixtures/llm/<cycle>/` and are served with fuzzy matching, so a
+//! wording change in a seed prompt template does not require re-recording them. To refresh a
+//! cycle, run it once with a real client wrapped in `RecordingLlm` pointed at that directory.
+
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+use skilllite_core::config::env_keys::evolution as evo_keys;
+use skilllite_core::planning::PlanningRule;
+use skilllite_evolution::feedback::{self, DecisionInput, FeedbackSignal, ToolExecDetail};
+use skilllite_evolution::journal::{self, Journal, JournalPhase, RecoveryAction};
+use skilllite_evolution::prompt_learner::PlanningExample;
+use skilllite_evolution::{
+    retention, run_evolution, run_state, seed, EvolutionRunResult, ReplayLlm,
+};
+
+/// `run_evolution` holds a process-wide mutex and reads its mode from env: one cycle at a time.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn fixture_llm(cycle: &str) -> ReplayLlm {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("llm")
+        .join(cycle);
+    ReplayLlm::open(&dir).unwrap().with_fuzzy_match(true)
+}
+
+fn record_decision(conn: &Connection, task: &str, tools: &[(&str, bool)], replans: usize) {
+    let failed_tools = tools.iter().filter(|(_, ok)| !ok).count();
+    let completed = failed_tools == 0 && replans == 0;
+    let outcome = if completed { "success" } else { "failure" };
+    let input = DecisionInput {
+        total_tools: tools.len(),
+        failed_tools,
+        replans,
+        elapsed_ms: 4200,
+        task_completed: completed,
+        completion_type: outcome.to_string(),
+        completion_type_reported: outcome.to_string(),
+        task_description: Some(task.to_string()),
+        rules_used: vec![],
+        rules_in_scope: vec![],
+        model: None,
+        fallback_model: None,
+        replan_reasons: vec![],
+        tool_loops: 0,
+        workspace: None,
+        tools_detail: tools
+            .iter()
+            .map(|(tool, success)| ToolExecDetail {
+                tool: tool.to_string(),
+                success: *success,
+                ..Default::default()
+            })
+            .collect(),
+    };
+    feedback::insert_decision(conn, Some("replay"), &input, FeedbackSignal::Neutral).unwrap();
+}
+
+fn run_cycle(
+    mode: &str,
+    chat_root: &Path,
+    skills_root: Option<&Path>,
+    llm: &ReplayLlm,
+) -> EvolutionRunResult {
+    run_cycle_crashing_after(None, mode, chat_root, skills_root, llm)
+}
+
+/// Like [`run_cycle`], but panics right after the journal persists `crash_after`.
+fn run_cycle_crashing_after(
+    crash_after: Option<JournalPhase>,
+    mode: &str,
+    chat_root: &Path,
+    skills_root: Option<&Path>,
+    llm: &ReplayLlm,
+) -> EvolutionRunResult {
+    let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    *CRASH_AFTER.lock().unwrap_or_else(|e| e.into_inner()) = crash_after;
+    journal::set_phase_hook(Some(crash_hook));
+    std::env::set_var(evo_keys::SKILLLITE_EVOLUTION, mode);
+    std::env::set_var(evo_keys::SKILLLITE_EVO_LLM_MAX_ATTEMPTS, "1");
+    std::env::remove_var(evo_keys::SKILLLITE_EXTERNAL_LEARNING);
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(run_evolution(
+            chat_root,
+            skills_root,
+            llm,
+            "",
+            "",
+            "fixture",
+            true,
+        ))
+        .unwrap()
+}
+
+/// Seed data plus the decisions the `prompt_cycle` fixtures were recorded against.
+fn seed_prompt_cycle(chat_root: &Path) {
+    seed::ensure_seed_data(chat_root);
+    let conn = feedback::open_evolution_db(chat_root).unwrap();
+    let report = [
+        ("read_file", true),
+        ("list_directory", true),
+        ("write_output", true),
+    ];
+    let fetch = [
+        ("http_request", false),
+        ("http_request", true),
+        ("write_output", true),
+    ];
+    for _ in 0..3 {
+        record_decision(&conn, "整理本周项目进展并写入周报", &report, 0);
+    }
+    for _ in 0..2 {
+        record_decision(&conn, "抓取发布说明网页并总结变更", &fetch, 1);
+    }
+}
+
+fn last_changelog_entry(chat_root: &Path) -> serde_json::Value {
+    let lines = retention::read_jsonl_lines(&retention::changelog_path(chat_root));
+    serde_json::from_str(lines.last().expect("changelog entry")).unwrap()
+}
+
+fn change_ids(entry: &serde_json::Value, change_type: &str) -> Vec<String> {
+    entry["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|c| c["type"] == change_type)
+        .map(|c| c["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+fn snapshot_dir(chat_root: &Path, txn_id: &str) -> PathBuf {
+    retention::changelog_path(chat_root)
+        .parent()
+        .unwrap()
+        .join(txn_id)
+}
+
+#[test]
+fn prompt_cycle_adds_rule_and_example_from_fixtures() {
+    let dir = tempfile::tempdir().unwrap();
+    let chat_root = dir.path();
+    seed_prompt_cycle(chat_root);
+    let llm = fixture_llm("prompt_cycle");
+
+    let result = run_cycle("prompts", chat_root, None, &llm);
+
+    assert!(llm.misses().is_empty(), "replay misses: {:?}", llm.misses());
+    assert_eq!(llm.served().len(), 2);
+    let txn_id = result.txn_id().expect("material evolution run").to_string();
+
+    let rules: Vec<PlanningRule> = serde_json::from_str(
+        &std::fs::read_to_string(chat_root.join("prompts").join("rules.json")).unwrap(),
+    )
+    .unwrap();
+    let added = rules
+        .iter()
+        .find(|r| r.id == "evo_weekly_report_read_then_write")
+        .expect("evolved rule");
+    assert_eq!(added.origin, "evolved");
+    assert!(rules.iter().any(|r| r.origin == "seed"));
+
+    let examples: Vec<PlanningExample> = serde_json::from_str(
+        &std::fs::read_to_string(chat_root.join("prompts").join("examples.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(examples.len(), 1);
+    assert_eq!(examples[0].id, "example_weekly_report");
+
+    let entry = last_changelog_entry(chat_root);
+    assert_eq!(entry["txn_id"], txn_id.as_str());
+    assert_eq!(
+        change_ids(&entry, "rule_added"),
+        vec!["evo_weekly_report_read_then_write"]
+    );
+    assert_eq!(
+        change_ids(&entry, "example_added"),
+        vec!["example_weekly_report"]
+    );
+    assert!(entry["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|f| f == "rules.json"));
+
+    // The snapshot holds the pre-run rules, so rollback can restore them.
+    let snapshot: Vec<PlanningRule> = serde_json::from_str(
+        &std::fs::read_to_string(snapshot_dir(chat_root, &txn_id).join("rules.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(snapshot.len() + 1, rules.len());
+    assert!(!snapshot
+        .iter()
+        .any(|r| r.id == "evo_weekly_report_read_then_write"));
+}
+
+#[test]
+fn skill_cycle_generates_pending_skill_from_fixtures() {
+    let dir = tempfile::tempdir().unwrap();
+    let chat_root = dir.path().join("chat");
+    let skills_root = dir.path().join("skills");
+    std::fs::create_dir_all(&skills_root).unwrap();
+    seed::ensure_seed_data(&chat_root);
+    {
+        let conn = feedback::open_evolution_db(&chat_root).unwrap();
+        let tools = [("read_file", true), ("write_output", true)];
+        for _ in 0..3 {
+            record_decision(&conn, "统计导出的 CSV 文件表头字段", &tools, 0);
+        }
+    }
+    let llm = fixture_llm("skill_cycle");
+
+    let result = run_cycle("skills", &chat_root, Some(&skills_root), &llm);
+
+    assert!(llm.misses().is_empty(), "replay misses: {:?}", llm.misses());
+    let txn_id = result.txn_id().expect("material evolution run").to_string();
+
+    let skill_dir = skills_root
+        .join("_evolved")
+        .join("_pending")
+        .join("csv-columns");
+    assert!(skill_dir.join("SKILL.md").is_file());
+    assert!(skill_dir.join("scripts").join("main.py").is_file());
+    let meta: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(skill_dir.join(".meta.json")).unwrap())
+            .unwrap();
+    assert_eq!(meta["generation_txn"], txn_id.as_str());
+    assert_eq!(meta["needs_review"], false);
+
+    let entry = last_changelog_entry(&chat_root);
+    assert_eq!(entry["txn_id"], txn_id.as_str());
+    assert_eq!(change_ids(&entry, "skill_pending"), vec!["csv-columns"]);
+    assert!(snapshot_dir(&chat_root, &txn_id).is_dir());
+}
+
+#[test]
+fn run_is_skipped_while_another_process_holds_the_run_lock() {
+    let dir = tempfile::tempdir().unwrap();
+    let chat_root = dir.path();
+    seed::ensure_seed_data(chat_root);
+    let mut other = run_state::open_run_lock(chat_root).unwrap();
+    let _held = other.try_write().unwrap();
+    let llm = fixture_llm("prompt_cycle");
+
+    let result = run_cycle("prompts", chat_root, None, &llm);
+
+    assert!(matches!(result, EvolutionRunResult::SkippedBusy));
+    assert!(llm.served().is_empty());
+    // The in-process mutex was released even though the run bailed out early.
+    let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    assert!(skilllite_evolution::try_start_evolution());
+    skilllite_evolution::finish_evolution();
+}
+
+static CRASH_AFTER: Mutex<Option<JournalPhase>> = Mutex::new(None);
+
+/// Journal phase hook: "kill" the process right after `CRASH_AFTER` is persisted. A crash
+/// after `Prepared` also leaves a half-written rules.json, as if a learner died mid-write.
+fn crash_hook(journal: &Journal) {
+    if *CRASH_AFTER.lock().unwrap_or_else(|e| e.into_inner()) != Some(journal.phase) {
+        return;
+    }
+    if journal.phase == JournalPhase::Prepared {
+        let rules = journal.prior.keys().find(|p| p.ends_with("rules.json"));
+        std::fs::write(rules.unwrap(), "[{\"id\": \"half").unwrap();
+    }
+    panic!("simulated crash after {}", journal.phase.as_str());
+}
+
+fn crash_prompt_cycle_after(phase: JournalPhase) {
+    let dir = tempfile::tempdir().unwrap();
+    let chat_root = dir.path();
+    seed_prompt_cycle(chat_root);
+    let rules_path = chat_root.join("prompts").join("rules.json");
+    let rules_before = std::fs::read_to_string(&rules_path).unwrap();
+    let llm = fixture_llm("prompt_cycle");
+
+    let crashed = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        run_cycle_crashing_after(Some(phase), "prompts", chat_root, None, &llm)
+    }));
+    assert!(crashed.is_err(), "cycle should crash after {:?}", phase);
+
+    let pending = journal::list_uncommitted(chat_root);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].phase, phase);
+    let txn_id = pending[0].txn_id.clone();
+
+    let conn = feedback::open_evolution_db(chat_root).unwrap();
+    let recovered = journal::recover_uncommitted(&conn, chat_root).unwrap();
+    let expected = if phase == JournalPhase::Prepared {
+        RecoveryAction::RolledBack
+    } else {
+        RecoveryAction::RolledForward
+    };
+    assert_eq!(recovered, vec![(txn_id.clone(), expected)]);
+    assert!(journal::list_uncommitted(chat_root).is_empty());
+
+    let rows = skilllite_evolution::query_changes_by_txn(&conn, &txn_id);
+    let count = |t: &str| rows.iter().filter(|(ty, _)| ty == t).count();
+    assert_eq!(count(journal::EVOLUTION_LOG_TYPE_RECOVERED_TXN), 1);
+    let entries: Vec<serde_json::Value> =
+        retention::read_jsonl_lines(&retention::changelog_path(chat_root))
+            .iter()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .filter(|e: &serde_json::Value| e["txn_id"] == txn_id.as_str())
+            .collect();
+    let rules_after = std::fs::read_to_string(&rules_path).unwrap();
+
+    if phase == JournalPhase::Prepared {
+        // Nothing was recorded, so nothing may remain on disk either.
+        assert_eq!(rules_after, rules_before);
+        assert!(entries.is_empty());
+        assert_eq!(count("rule_added"), 0);
+    } else {
+        assert!(rules_after.contains("evo_weekly_report_read_then_write"));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            change_ids(&entries[0], "rule_added"),
+            vec!["evo_weekly_report_read_then_write"]
+        );
+        assert_eq!(count("rule_added"), 1);
+        assert_eq!(count(feedback::EVOLUTION_LOG_TYPE_RUN_MATERIAL), 1);
+    }
+    assert!(!chat_root
+        .join("prompts")
+        .join(journal::JOURNAL_FILE)
+        .exists());
+}
+
+#[test]
+fn crash_after_prepare_rolls_back_to_snapshot() {
+    crash_prompt_cycle_after(JournalPhase::Prepared);
+}
+
+#[test]
+fn crash_before_log_rows_rolls_forward() {
+    crash_prompt_cycle_after(JournalPhase::Applied);
+}
+
+#[test]
+fn crash_before_changelog_rolls_forward() {
+    crash_prompt_cycle_after(JournalPhase::Logged);
+}
+
+#[test]
+fn crash_before_commit_rolls_forward() {
+    crash_prompt_cycle_after(JournalPhase::Recorded);
+}
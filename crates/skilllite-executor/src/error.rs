@@ -36,6 +36,10 @@ pub enum Error {
     #[error("{0}")]
     Validation(String),
 
+    /// Another writer held the lock on `path` for longer than the wait budget. Retryable.
+    #[error("Timed out after {waited_ms} ms waiting for the lock on {}", path.display())]
+    LockTimeout { path: PathBuf, waited_ms: u64 },
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -47,6 +51,11 @@ impl Error {
     pub fn validation(msg: impl Into<String>) -> Self {
         Error::Validation(msg.into())
     }
+
+    /// Lock contention on a session or plan file; the caller may retry.
+    pub fn is_lock_timeout(&self) -> bool {
+        matches!(self, Error::LockTimeout { .. })
+    }
 }
 
 macro_rules! bail {
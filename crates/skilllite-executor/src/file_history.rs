@@ -0,0 +1,495 @@
+//! File history: per-session pre-images of files edited by builtin tools.
+//!
+//! Layout under chat root: `.file_history/{session_key}/index.jsonl` (one [`PreImageRecord`]
+//! per capture, append order) and `blobs/{id}` (file bytes before the edit). Lets the desktop
+//! UI show what changed during a session and restore the pre-session version without git.
+//!
+//! Caps: at most [`MAX_PREIMAGES_PER_FILE`] records per file (the first one — the pre-session
+//! baseline — is always kept), files above [`MAX_PREIMAGE_FILE_BYTES`] and captures past
+//! [`MAX_SESSION_BYTES`] are recorded without content (listed, but no diff / revert).
+
+use crate::error::{bail, Result};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const MAX_PREIMAGES_PER_FILE: usize = 20;
+pub const MAX_PREIMAGE_FILE_BYTES: u64 = 2 * 1024 * 1024;
+pub const MAX_SESSION_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Lines of context around each hunk in [`unified_diff`].
+const DIFF_CONTEXT: usize = 3;
+/// Above this many `old × new` line pairs the diff degrades to a single replace hunk.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PreImageRecord {
+    /// Unix seconds of the capture
+    pub ts: u64,
+    /// Absolute path of the edited file
+    pub path: String,
+    /// Whether the file existed before the edit (`false` = created during the session)
+    pub existed: bool,
+    /// Blob id under `blobs/`; `None` when the file did not exist or exceeded the caps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+    #[serde(default)]
+    pub size: u64,
+}
+
+/// One file changed during a session, as returned by [`changes_since`].
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    /// `added` | `modified` | `deleted`
+    pub status: String,
+    pub first_changed: u64,
+    pub last_changed: u64,
+    /// Number of captured edits since `since_ts`
+    pub edits: usize,
+    /// Unified diff (pre-session → current); `None` for binary files or dropped pre-images
+    pub diff: Option<String>,
+    /// Pre-image content is available, so [`revert_file`] can restore it
+    pub revertible: bool,
+}
+
+/// What [`revert_file`] did.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RevertOutcome {
+    /// Pre-session content written back
+    Restored,
+    /// File did not exist before the session and was removed
+    Removed,
+}
+
+pub fn history_dir(chat_root: &Path, session_key: &str) -> PathBuf {
+    chat_root.join(".file_history").join(session_key)
+}
+
+/// Stable record key for `path`: canonical when the file (or at least its parent) exists, so
+/// the same file is matched regardless of how the workspace path was spelled.
+fn path_key(path: &Path) -> String {
+    let canonical = path.canonicalize().ok().or_else(|| {
+        let parent = path.parent()?.canonicalize().ok()?;
+        Some(parent.join(path.file_name()?))
+    });
+    canonical
+        .unwrap_or_else(|| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn index_path(dir: &Path) -> PathBuf {
+    dir.join("index.jsonl")
+}
+
+pub fn read_records(chat_root: &Path, session_key: &str) -> Result<Vec<PreImageRecord>> {
+    let path = index_path(&history_dir(chat_root, session_key));
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read file history: {}", path.display()))?;
+    // Skip malformed lines rather than losing the whole history
+    Ok(content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect())
+}
+
+fn write_records(dir: &Path, records: &[PreImageRecord]) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let path = index_path(dir);
+    let tmp = dir.join("index.jsonl.tmp");
+    let mut out = String::new();
+    for r in records {
+        out.push_str(&serde_json::to_string(r)?);
+        out.push('\n');
+    }
+    let mut f = fs::File::create(&tmp)
+        .with_context(|| format!("Failed to write file history: {}", tmp.display()))?;
+    f.write_all(out.as_bytes())?;
+    drop(f);
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Record `path`'s current content before a builtin tool overwrites it.
+pub fn record_pre_image(chat_root: &Path, session_key: &str, path: &Path) -> Result<()> {
+    let dir = history_dir(chat_root, session_key);
+    let mut records = read_records(chat_root, session_key)?;
+    let path_str = path_key(path);
+    let meta = fs::metadata(path).ok().filter(|m| m.is_file());
+    let size = meta.as_ref().map(|m| m.len()).unwrap_or(0);
+
+    let stored: u64 = records
+        .iter()
+        .filter(|r| r.blob.is_some())
+        .map(|r| r.size)
+        .sum();
+    let blob = if meta.is_some()
+        && size <= MAX_PREIMAGE_FILE_BYTES
+        && stored + size <= MAX_SESSION_BYTES
+    {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let blobs = dir.join("blobs");
+        fs::create_dir_all(&blobs)?;
+        fs::copy(path, blobs.join(&id))
+            .with_context(|| format!("Failed to store pre-image of {}", path.display()))?;
+        Some(id)
+    } else {
+        None
+    };
+
+    records.push(PreImageRecord {
+        ts: now_secs(),
+        path: path_str.clone(),
+        existed: meta.is_some(),
+        blob,
+        size,
+    });
+
+    // Per-file cap: drop the oldest captures after the baseline
+    let positions: Vec<usize> = records
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.path == path_str)
+        .map(|(i, _)| i)
+        .collect();
+    if positions.len() > MAX_PREIMAGES_PER_FILE {
+        let excess = positions.len() - MAX_PREIMAGES_PER_FILE;
+        for &i in positions[1..=excess].iter().rev() {
+            let dropped = records.remove(i);
+            remove_blob(&dir, dropped.blob.as_deref());
+        }
+    }
+    write_records(&dir, &records)
+}
+
+fn remove_blob(dir: &Path, blob: Option<&str>) {
+    if let Some(id) = blob {
+        let _ = fs::remove_file(dir.join("blobs").join(id));
+    }
+}
+
+fn read_blob(dir: &Path, blob: &str) -> Result<Vec<u8>> {
+    let path = dir.join("blobs").join(blob);
+    Ok(fs::read(&path).with_context(|| format!("Missing pre-image blob: {}", path.display()))?)
+}
+
+/// Files under `workspace` changed since `since_ts` (unix secs), oldest first. Each diff is
+/// against the first pre-image captured at or after `since_ts`; files whose content is back
+/// to that pre-image are omitted.
+pub fn changes_since(
+    chat_root: &Path,
+    session_key: &str,
+    workspace: &Path,
+    since_ts: u64,
+) -> Result<Vec<FileChange>> {
+    let dir = history_dir(chat_root, session_key);
+    let records = read_records(chat_root, session_key)?;
+    let workspace = PathBuf::from(path_key(workspace));
+    let mut order: Vec<&str> = Vec::new();
+    for r in records.iter().filter(|r| r.ts >= since_ts) {
+        if Path::new(&r.path).starts_with(&workspace) && !order.contains(&r.path.as_str()) {
+            order.push(&r.path);
+        }
+    }
+
+    let mut changes = Vec::new();
+    for path in order {
+        let edits: Vec<&PreImageRecord> = records
+            .iter()
+            .filter(|r| r.ts >= since_ts && r.path == path)
+            .collect();
+        let baseline = edits[0];
+        let current = fs::read(path).ok();
+        let before = match &baseline.blob {
+            Some(id) => Some(read_blob(&dir, id)?),
+            None => None,
+        };
+        let status = match (baseline.existed, current.is_some()) {
+            (false, true) => "added",
+            (true, false) => "deleted",
+            (false, false) => continue,
+            (true, true) => "modified",
+        };
+        if baseline.existed && before.is_some() && before == current {
+            continue;
+        }
+        let before_text = match &before {
+            Some(b) => std::str::from_utf8(b).ok(),
+            None if !baseline.existed => Some(""),
+            None => None,
+        };
+        let after_text = match &current {
+            Some(c) => std::str::from_utf8(c).ok(),
+            None => Some(""),
+        };
+        let diff = match (before_text, after_text) {
+            (Some(a), Some(b)) => Some(unified_diff(a, b, path)),
+            _ => None,
+        };
+        changes.push(FileChange {
+            path: path.to_string(),
+            status: status.to_string(),
+            first_changed: baseline.ts,
+            last_changed: edits.last().map(|r| r.ts).unwrap_or(baseline.ts),
+            edits: edits.len(),
+            diff,
+            revertible: !baseline.existed || baseline.blob.is_some(),
+        });
+    }
+    Ok(changes)
+}
+
+/// Restore `path` to its pre-session version (first pre-image) and forget its history.
+pub fn revert_file(chat_root: &Path, session_key: &str, path: &Path) -> Result<RevertOutcome> {
+    let dir = history_dir(chat_root, session_key);
+    let mut records = read_records(chat_root, session_key)?;
+    let path_str = path_key(path);
+    let Some(baseline) = records.iter().find(|r| r.path == path_str).cloned() else {
+        bail!(
+            "No recorded changes for {} in session {}",
+            path.display(),
+            session_key
+        );
+    };
+    let outcome = match (&baseline.blob, baseline.existed) {
+        (Some(id), _) => {
+            let bytes = read_blob(&dir, id)?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, bytes)
+                .with_context(|| format!("Failed to restore {}", path.display()))?;
+            RevertOutcome::Restored
+        }
+        (None, false) => {
+            if path.exists() {
+                fs::remove_file(path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+            RevertOutcome::Removed
+        }
+        (None, true) => bail!(
+            "Pre-session version of {} was not retained ({} bytes exceeds the file history cap)",
+            path.display(),
+            baseline.size
+        ),
+    };
+    records.retain(|r| {
+        let keep = r.path != path_str;
+        if !keep {
+            remove_blob(&dir, r.blob.as_deref());
+        }
+        keep
+    });
+    write_records(&dir, &records)?;
+    Ok(outcome)
+}
+
+/// Remove a session's file history (e.g. when the session is deleted).
+pub fn remove_history(chat_root: &Path, session_key: &str) -> Result<()> {
+    let dir = history_dir(chat_root, session_key);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)
+            .with_context(|| format!("Failed to remove file history: {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+/// Minimal line-based unified diff (LCS) with `a/` `b/` headers.
+pub fn unified_diff(before: &str, after: &str, label: &str) -> String {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+    let ops = diff_ops(&a, &b);
+
+    let mut out = format!("--- a/{}\n+++ b/{}\n", label, label);
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(..)))
+        .map(|(i, _)| i)
+        .collect();
+    let mut idx = 0;
+    while idx < changed.len() {
+        let start = changed[idx].saturating_sub(DIFF_CONTEXT);
+        let mut end = changed[idx];
+        while idx < changed.len() && changed[idx] <= end + 2 * DIFF_CONTEXT {
+            end = changed[idx];
+            idx += 1;
+        }
+        let end = (end + DIFF_CONTEXT + 1).min(ops.len());
+        let hunk = &ops[start..end];
+        let (old_start, new_start) = position_before(&ops[..start]);
+        let old_len = hunk.iter().filter(|o| !matches!(o, Op::Insert(_))).count();
+        let new_len = hunk.iter().filter(|o| !matches!(o, Op::Delete(_))).count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + usize::from(old_len > 0),
+            old_len,
+            new_start + usize::from(new_len > 0),
+            new_len
+        ));
+        for op in hunk {
+            match op {
+                Op::Equal(i) => out.push_str(&format!(" {}\n", a[*i])),
+                Op::Delete(i) => out.push_str(&format!("-{}\n", a[*i])),
+                Op::Insert(j) => out.push_str(&format!("+{}\n", b[*j])),
+            }
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    /// Line index in the old text.
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+fn position_before(ops: &[Op]) -> (usize, usize) {
+    ops.iter().fold((0, 0), |(o, n), op| match op {
+        Op::Equal(..) => (o + 1, n + 1),
+        Op::Delete(_) => (o + 1, n),
+        Op::Insert(_) => (o, n + 1),
+    })
+}
+
+fn diff_ops(a: &[&str], b: &[&str]) -> Vec<Op> {
+    // Trim common prefix / suffix so the LCS table only covers the changed middle
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (am, bm) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut ops: Vec<Op> = (0..prefix).map(Op::Equal).collect();
+    if am.len().saturating_mul(bm.len()) > MAX_DIFF_CELLS {
+        ops.extend((0..am.len()).map(|i| Op::Delete(prefix + i)));
+        ops.extend((0..bm.len()).map(|j| Op::Insert(prefix + j)));
+    } else {
+        let (n, m) = (am.len(), bm.len());
+        let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i * (m + 1) + j] = if am[i] == bm[j] {
+                    lcs[(i + 1) * (m + 1) + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * (m + 1) + j].max(lcs[i * (m + 1) + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && am[i] == bm[j] {
+                ops.push(Op::Equal(prefix + i));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lcs[(i + 1) * (m + 1) + j] >= lcs[i * (m + 1) + j + 1]) {
+                // Deletions before insertions within a change block
+                ops.push(Op::Delete(prefix + i));
+                i += 1;
+            } else {
+                ops.push(Op::Insert(prefix + j));
+                j += 1;
+            }
+        }
+    }
+    let a_tail = a.len() - suffix;
+    ops.extend((0..suffix).map(|k| Op::Equal(a_tail + k)));
+    ops
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_diff_marks_changed_lines_with_context() {
+        let diff = unified_diff("a\nb\nc\nd\n", "a\nB\nc\nd\ne\n", "f.txt");
+        assert!(diff.starts_with("--- a/f.txt\n+++ b/f.txt\n@@ -1,4 +1,5 @@\n"));
+        assert!(diff.contains("-b\n+B\n"));
+        assert!(diff.contains("+e\n"));
+        assert!(diff.contains(" a\n"));
+    }
+
+    #[test]
+    fn records_diffs_and_reverts_to_pre_session_version() {
+        let tmp = tempfile::tempdir().unwrap();
+        let chat = tmp.path().join("chat");
+        let ws = tmp.path().join("ws");
+        fs::create_dir_all(&ws).unwrap();
+        let edited = ws.join("notes.txt");
+        let created = ws.join("new.txt");
+        fs::write(&edited, "one\ntwo\n").unwrap();
+
+        record_pre_image(&chat, "s1", &edited).unwrap();
+        fs::write(&edited, "one\nTWO\n").unwrap();
+        record_pre_image(&chat, "s1", &edited).unwrap();
+        fs::write(&edited, "one\nTWO\nthree\n").unwrap();
+        record_pre_image(&chat, "s1", &created).unwrap();
+        fs::write(&created, "hello\n").unwrap();
+
+        let changes = changes_since(&chat, "s1", &ws, 0).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].status, "modified");
+        assert_eq!(changes[0].edits, 2);
+        let diff = changes[0].diff.as_deref().unwrap();
+        assert!(diff.contains("-two\n+TWO\n+three\n"), "{}", diff);
+        assert_eq!(changes[1].status, "added");
+        assert!(changes[1].revertible);
+
+        assert_eq!(
+            revert_file(&chat, "s1", &edited).unwrap(),
+            RevertOutcome::Restored
+        );
+        assert_eq!(fs::read_to_string(&edited).unwrap(), "one\ntwo\n");
+        assert_eq!(
+            revert_file(&chat, "s1", &created).unwrap(),
+            RevertOutcome::Removed
+        );
+        assert!(!created.exists());
+        assert!(changes_since(&chat, "s1", &ws, 0).unwrap().is_empty());
+        assert!(revert_file(&chat, "s1", &edited).is_err());
+    }
+
+    #[test]
+    fn per_file_cap_keeps_baseline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let chat = tmp.path().join("chat");
+        let file = tmp.path().join("f.txt");
+        for i in 0..MAX_PREIMAGES_PER_FILE + 5 {
+            fs::write(&file, format!("v{}\n", i)).unwrap();
+            record_pre_image(&chat, "s", &file).unwrap();
+        }
+        let records = read_records(&chat, "s").unwrap();
+        assert_eq!(records.len(), MAX_PREIMAGES_PER_FILE);
+        let blobs = fs::read_dir(history_dir(&chat, "s").join("blobs"))
+            .unwrap()
+            .count();
+        assert_eq!(blobs, MAX_PREIMAGES_PER_FILE);
+        assert_eq!(
+            revert_file(&chat, "s", &file).unwrap(),
+            RevertOutcome::Restored
+        );
+        assert_eq!(fs::read_to_string(&file).unwrap(), "v0\n");
+    }
+}
@@ -1,7 +1,10 @@
 pub mod error;
+pub mod file_history;
+pub mod lock;
 pub mod memory;
 pub mod plan;
 pub mod rpc;
+pub mod run_checkpoints;
 pub mod session;
 pub mod transcript;
 
@@ -0,0 +1,87 @@
+//! Advisory file locks around read-modify-write cycles on chat data.
+//!
+//! `sessions.json` and the plan files are written by the CLI chat, the desktop assistant and
+//! the stdio daemon, sometimes at the same time. Each cycle holds an exclusive lock on a
+//! `<file>.lock` sibling (the data file itself is replaced by rename, so it cannot carry the
+//! lock). Waiting is bounded: a holder that does not let go surfaces as
+//! [`Error::LockTimeout`] instead of a hang.
+
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+
+/// How long a writer waits for the lock before giving up.
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Lock file guarding `path`.
+pub fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+/// Run `f` while holding the exclusive lock for `path`, waiting at most [`LOCK_TIMEOUT`].
+pub fn with_lock<T>(path: &Path, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    with_lock_timeout(path, LOCK_TIMEOUT, f)
+}
+
+/// [`with_lock`] with an explicit wait budget.
+pub fn with_lock_timeout<T>(
+    path: &Path,
+    timeout: Duration,
+    f: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let lock_file = lock_path(path);
+    if let Some(parent) = lock_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_file)?;
+    let mut lock = fd_lock::RwLock::new(file);
+    let started = Instant::now();
+    loop {
+        match lock.try_write() {
+            Ok(_guard) => return f(),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                if started.elapsed() >= timeout {
+                    return Err(Error::LockTimeout {
+                        path: path.to_path_buf(),
+                        waited_ms: started.elapsed().as_millis() as u64,
+                    });
+                }
+                std::thread::sleep(RETRY_INTERVAL);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contention_times_out_with_a_distinct_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("sessions.json");
+        assert_eq!(lock_path(&path), tmp.path().join("sessions.json.lock"));
+
+        let err = with_lock(&path, || {
+            with_lock_timeout(&path, Duration::from_millis(50), || Ok(()))
+        })
+        .unwrap_err();
+        assert!(err.is_lock_timeout(), "{}", err);
+        assert!(err.to_string().contains("sessions.json"), "{}", err);
+
+        // Released once the outer cycle ends.
+        with_lock_timeout(&path, Duration::from_millis(50), || Ok(())).unwrap();
+    }
+}
@@ -56,84 +56,140 @@ pub fn ensure_index(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
-/// Ensure vec0 table exists for vector search. Call after ensure_index.
-/// If dimension changed (e.g. switched from Qwen 1024 to OpenAI 1536), drops old table
-/// and recreates with new dimension. Vec index will be empty until memory_write repopulates.
-#[cfg(feature = "memory_vector")]
-pub fn ensure_vec0_table(conn: &Connection, dimension: usize) -> Result<()> {
-    ensure_vec_extension_loaded();
+/// Vector-space identity stored alongside the vec0 index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VecIndexMeta {
+    pub dimension: usize,
+    /// `backend:model:dimension` (see `EmbeddingConfig::fingerprint`). `None` for indexes
+    /// written before fingerprints were recorded.
+    pub fingerprint: Option<String>,
+}
 
-    // Create metadata table to track dimension
+impl VecIndexMeta {
+    /// Whether vectors in this index can be compared with ones from `dimension`/`fingerprint`.
+    /// Legacy indexes without a fingerprint are accepted when the dimension matches.
+    pub fn is_compatible(&self, dimension: usize, fingerprint: &str) -> bool {
+        self.dimension == dimension && self.fingerprint.as_deref().is_none_or(|f| f == fingerprint)
+    }
+}
+
+#[cfg(any(feature = "memory_vector", test))]
+fn ensure_vec_meta_tables(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         r#"
         CREATE TABLE IF NOT EXISTS _memory_vec_meta (
             k TEXT PRIMARY KEY,
             v INTEGER NOT NULL
         );
+        CREATE TABLE IF NOT EXISTS _memory_vec_model (
+            k TEXT PRIMARY KEY,
+            v TEXT NOT NULL
+        );
         "#,
     )?;
+    Ok(())
+}
 
-    let stored_dim: Option<i64> = conn
+/// Read the stored dimension/fingerprint of the vector index, if any.
+pub fn read_vec_meta(conn: &Connection) -> Option<VecIndexMeta> {
+    let dimension: i64 = conn
         .query_row(
             "SELECT v FROM _memory_vec_meta WHERE k = 'dimension'",
             [],
             |row| row.get(0),
         )
+        .ok()?;
+    let fingerprint: Option<String> = conn
+        .query_row(
+            "SELECT v FROM _memory_vec_model WHERE k = 'fingerprint'",
+            [],
+            |row| row.get(0),
+        )
         .ok();
+    Some(VecIndexMeta {
+        dimension: dimension as usize,
+        fingerprint,
+    })
+}
 
-    let need_recreate = !matches!(stored_dim, Some(d) if d as usize == dimension);
-
-    if need_recreate {
-        conn.execute_batch("DROP TABLE IF EXISTS memory_vec")?;
-        conn.execute(
-            "INSERT OR REPLACE INTO _memory_vec_meta (k, v) VALUES ('dimension', ?)",
-            rusqlite::params![dimension as i64],
-        )?;
+#[cfg(any(feature = "memory_vector", test))]
+fn write_vec_meta(conn: &Connection, dimension: usize, fingerprint: &str) -> Result<()> {
+    ensure_vec_meta_tables(conn)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO _memory_vec_meta (k, v) VALUES ('dimension', ?)",
+        rusqlite::params![dimension as i64],
+    )?;
+    conn.execute(
+        "INSERT OR REPLACE INTO _memory_vec_model (k, v) VALUES ('fingerprint', ?)",
+        rusqlite::params![fingerprint],
+    )?;
+    Ok(())
+}
 
-        let sql = format!(
-            r#"CREATE VIRTUAL TABLE memory_vec USING vec0(
-                embedding float[{}],
-                path text,
-                chunk_index int,
-                +content text
-            )"#,
+/// Reject embeddings whose length differs from the index dimension.
+pub fn check_embedding_dimensions(embeddings: &[Vec<f32>], dimension: usize) -> Result<()> {
+    if let Some(bad) = embeddings.iter().find(|e| e.len() != dimension) {
+        return Err(crate::Error::validation(format!(
+            "Embedding has {} dimensions but the memory index expects {} \
+             (check SKILLLITE_EMBEDDING_MODEL / SKILLLITE_EMBEDDING_DIMENSION)",
+            bad.len(),
             dimension
-        );
-        conn.execute_batch(&sql)?;
+        )));
+    }
+    Ok(())
+}
+
+/// Ensure vec0 table exists for vector search. Call after ensure_index.
+///
+/// The index records the embedding dimension and backend/model fingerprint. When either
+/// changed (e.g. switched from a 1536-dim HTTP model to the 384-dim local model), the old table
+/// is dropped and recreated empty. Returns `true` in that case: existing memory files must be
+/// re-embedded (see `skilllite reindex --memory`).
+#[cfg(feature = "memory_vector")]
+pub fn ensure_vec0_table(conn: &Connection, dimension: usize, fingerprint: &str) -> Result<bool> {
+    ensure_vec_extension_loaded();
+    ensure_vec_meta_tables(conn)?;
+
+    let exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='memory_vec'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|n| n > 0)
+        .unwrap_or(false);
+    let stored = read_vec_meta(conn);
+    let compatible = stored
+        .as_ref()
+        .is_some_and(|m| m.is_compatible(dimension, fingerprint));
+
+    if exists && compatible {
+        if stored.is_some_and(|m| m.fingerprint.is_none()) {
+            write_vec_meta(conn, dimension, fingerprint)?;
+        }
+        return Ok(false);
+    }
+
+    conn.execute_batch("DROP TABLE IF EXISTS memory_vec")?;
+    write_vec_meta(conn, dimension, fingerprint)?;
+    let sql = format!(
+        r#"CREATE VIRTUAL TABLE memory_vec USING vec0(
+            embedding float[{}],
+            path text,
+            chunk_index int,
+            +content text
+        )"#,
+        dimension
+    );
+    conn.execute_batch(&sql)?;
+    if exists {
         tracing::info!(
             dimension,
-            "memory_vec table recreated for new embedding dimension"
+            fingerprint,
+            "memory_vec table recreated for new embedding model; re-embedding required"
         );
-    } else {
-        // Table may not exist yet (first run)
-        let exists: bool = conn
-            .query_row(
-                "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='memory_vec'",
-                [],
-                |row| row.get::<_, i64>(0),
-            )
-            .map(|n| n > 0)
-            .unwrap_or(false);
-
-        if !exists {
-            conn.execute(
-                "INSERT OR REPLACE INTO _memory_vec_meta (k, v) VALUES ('dimension', ?)",
-                rusqlite::params![dimension as i64],
-            )?;
-            let sql = format!(
-                r#"CREATE VIRTUAL TABLE memory_vec USING vec0(
-                    embedding float[{}],
-                    path text,
-                    chunk_index int,
-                    +content text
-                )"#,
-                dimension
-            );
-            conn.execute_batch(&sql)?;
-        }
     }
-
-    Ok(())
+    Ok(exists)
 }
 
 /// Chunk markdown content by paragraphs, target ~400 tokens per chunk.
@@ -190,6 +246,29 @@ pub fn index_file(conn: &Connection, path: &str, content: &str) -> Result<()> {
     Ok(())
 }
 
+/// Drop every indexed chunk (BM25 and, when present, vector) for a memory file.
+pub fn remove_indexed_path(conn: &Connection, path: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM memory_fts WHERE path = ?",
+        rusqlite::params![path],
+    )?;
+    let has_vec_table: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = 'memory_vec'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|n| n > 0)
+        .unwrap_or(false);
+    if has_vec_table {
+        conn.execute(
+            "DELETE FROM memory_vec WHERE path = ?",
+            rusqlite::params![path],
+        )?;
+    }
+    Ok(())
+}
+
 /// Index chunks with embeddings into vec0. Removes existing rows for this path first.
 #[cfg(feature = "memory_vector")]
 pub fn index_file_vec(
@@ -206,6 +285,9 @@ pub fn index_file_vec(
             embeddings.len()
         );
     }
+    if let Some(meta) = read_vec_meta(conn) {
+        check_embedding_dimensions(embeddings, meta.dimension)?;
+    }
     conn.execute(
         "DELETE FROM memory_vec WHERE path = ?",
         rusqlite::params![path],
@@ -255,6 +337,9 @@ pub fn search_vec(
     limit: i64,
 ) -> Result<Vec<MemoryHit>> {
     use zerocopy::AsBytes;
+    if let Some(meta) = read_vec_meta(conn) {
+        check_embedding_dimensions(&[query_embedding.to_vec()], meta.dimension)?;
+    }
     let mut stmt = conn.prepare(
         r#"
         SELECT path, chunk_index, content, distance
@@ -318,6 +403,30 @@ pub fn reindex_memory_markdown_files(
     Ok(indexed)
 }
 
+/// All Markdown files under `chat_root/memory/`, as paths relative to it (sorted).
+pub fn list_memory_markdown_files(chat_root: &Path) -> Result<Vec<String>> {
+    let memory_dir = chat_root.join("memory");
+    let mut out = Vec::new();
+    if !memory_dir.is_dir() {
+        return Ok(out);
+    }
+    let mut stack = vec![memory_dir.clone()];
+    while let Some(dir) = stack.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if path.extension().is_some_and(|e| e == "md") {
+                if let Ok(rel) = path.strip_prefix(&memory_dir) {
+                    out.push(rel.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
 /// Check if vec0 table has any rows (vector index is populated).
 #[cfg(feature = "memory_vector")]
 pub fn has_vec_index(conn: &Connection) -> bool {
@@ -362,4 +471,68 @@ mod tests {
             hits
         );
     }
+
+    #[test]
+    fn remove_indexed_path_drops_only_that_file() {
+        let conn = Connection::open_in_memory().unwrap();
+        ensure_index(&conn).unwrap();
+        index_file(&conn, "auto/s1.md", "user prefers pnpm").unwrap();
+        index_file(&conn, "notes.md", "user prefers tabs").unwrap();
+
+        remove_indexed_path(&conn, "auto/s1.md").unwrap();
+        let hits = search_bm25(&conn, "prefers", 5).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, "notes.md");
+    }
+
+    #[test]
+    fn vec_meta_round_trips_and_detects_model_switch() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(read_vec_meta(&conn).is_none());
+
+        write_vec_meta(&conn, 1536, "http:text-embedding-3-small:1536").unwrap();
+        let meta = read_vec_meta(&conn).unwrap();
+        assert_eq!(meta.dimension, 1536);
+        assert!(meta.is_compatible(1536, "http:text-embedding-3-small:1536"));
+        // Switching to the local backend changes dimension and fingerprint.
+        assert!(!meta.is_compatible(384, "local:intfloat/multilingual-e5-small:384"));
+        // Same dimension, different model: vectors are still incomparable.
+        assert!(!meta.is_compatible(1536, "http:other-model:1536"));
+    }
+
+    #[test]
+    fn legacy_index_without_fingerprint_matches_on_dimension_only() {
+        let conn = Connection::open_in_memory().unwrap();
+        ensure_vec_meta_tables(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO _memory_vec_meta (k, v) VALUES ('dimension', 1024)",
+            [],
+        )
+        .unwrap();
+        let meta = read_vec_meta(&conn).unwrap();
+        assert_eq!(meta.fingerprint, None);
+        assert!(meta.is_compatible(1024, "http:text-embedding-v3:1024"));
+        assert!(!meta.is_compatible(384, "local:intfloat/multilingual-e5-small:384"));
+    }
+
+    #[test]
+    fn embeddings_with_wrong_dimension_are_rejected() {
+        assert!(check_embedding_dimensions(&[vec![0.0; 384], vec![0.0; 384]], 384).is_ok());
+        let err = check_embedding_dimensions(&[vec![0.0; 384], vec![0.0; 768]], 384).unwrap_err();
+        assert!(err.to_string().contains("768 dimensions"));
+    }
+
+    #[test]
+    fn list_memory_markdown_files_walks_subdirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let memory_dir = tmp.path().join("memory");
+        fs::create_dir_all(memory_dir.join("prefs")).unwrap();
+        fs::write(memory_dir.join("MEMORY.md"), "a").unwrap();
+        fs::write(memory_dir.join("prefs/theme.md"), "b").unwrap();
+        fs::write(memory_dir.join("default.sqlite"), "").unwrap();
+        assert_eq!(
+            list_memory_markdown_files(tmp.path()).unwrap(),
+            vec!["MEMORY.md".to_string(), "prefs/theme.md".to_string()]
+        );
+    }
 }
@@ -2,12 +2,14 @@
 //!
 //! Each plan is appended as a JSON line. Supports reading latest plan.
 //! Backward compatible: can still read legacy single .json files.
+//! Appends hold the file's advisory lock (see [`crate::lock`]) and rewrite it atomically, so
+//! readers never see a half-written line.
 
 use crate::error::Result;
+use crate::lock;
 use anyhow::Context;
 use serde_json::Value;
-use std::fs::OpenOptions;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
 fn date_today() -> String {
@@ -26,20 +28,30 @@ pub fn plan_path_legacy(plans_dir: &Path, session_key: &str, date: Option<&str>)
     plans_dir.join(format!("{}-{}.json", session_key, date_str))
 }
 
-/// Append a plan entry to the jsonl file.
+/// Append a plan entry to the jsonl file. Fails with [`crate::Error::LockTimeout`] when
+/// another writer holds the file for too long.
 pub fn append_plan(plans_dir: &Path, session_key: &str, plan_json: &Value) -> Result<()> {
     let path = plan_path_jsonl(plans_dir, session_key, None);
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&path)
-        .with_context(|| format!("Failed to open plan: {}", path.display()))?;
     let line = serde_json::to_string(plan_json)?;
-    writeln!(file, "{}", line)?;
-    Ok(())
+    lock::with_lock(&path, || {
+        let mut content = match std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(anyhow::Error::new(e)
+                    .context(format!("Failed to read plan: {}", path.display()))
+                    .into())
+            }
+        };
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&line);
+        content.push('\n');
+        skilllite_fs::atomic_write(&path, &content)
+            .with_context(|| format!("Failed to write plan: {}", path.display()))?;
+        Ok(())
+    })
 }
 
 /// Read plan entries from jsonl. Returns all entries in order.
@@ -24,20 +24,14 @@ pub fn handle_session_create(params: &Value) -> Result<Value> {
     let workspace_path = p.get("workspace_path").and_then(|v| v.as_str());
 
     let root = chat_root_for_rpc(workspace_path)?;
-    let sessions_path = root.join("sessions.json");
-
-    let mut store = SessionStore::load(&sessions_path)?;
-    let entry = store.create_or_get(session_key);
-    let session_id = entry.session_id.clone();
-    let session_key_out = entry.session_key.clone();
-    let updated_at = entry.updated_at.clone();
-    store.save(&sessions_path)?;
-
-    Ok(json!({
-        "session_id": session_id,
-        "session_key": session_key_out,
-        "updated_at": updated_at,
-    }))
+    SessionStore::modify(&root.join("sessions.json"), |store| {
+        let entry = store.create_or_get(session_key);
+        Ok(json!({
+            "session_id": entry.session_id,
+            "session_key": entry.session_key,
+            "updated_at": entry.updated_at,
+        }))
+    })
 }
 
 pub fn handle_session_get(params: &Value) -> Result<Value> {
@@ -73,27 +67,25 @@ pub fn handle_session_update(params: &Value) -> Result<Value> {
     let workspace_path = p.get("workspace_path").and_then(|v| v.as_str());
 
     let root = chat_root_for_rpc(workspace_path)?;
-    let sessions_path = root.join("sessions.json");
-    let mut store = SessionStore::load(&sessions_path)?;
-
-    store.update(session_key, |e| {
-        if let Some(v) = p.get("input_tokens").and_then(|v| v.as_u64()) {
-            e.input_tokens = v;
-        }
-        if let Some(v) = p.get("output_tokens").and_then(|v| v.as_u64()) {
-            e.output_tokens = v;
-        }
-        if let Some(v) = p.get("total_tokens").and_then(|v| v.as_u64()) {
-            e.total_tokens = v;
-        }
-        if let Some(v) = p.get("context_tokens").and_then(|v| v.as_u64()) {
-            e.context_tokens = v;
-        }
-        if let Some(v) = p.get("compaction_count").and_then(|v| v.as_u64()) {
-            e.compaction_count = v as u32;
-        }
+    SessionStore::modify(&root.join("sessions.json"), |store| {
+        store.update(session_key, |e| {
+            if let Some(v) = p.get("input_tokens").and_then(|v| v.as_u64()) {
+                e.input_tokens = v;
+            }
+            if let Some(v) = p.get("output_tokens").and_then(|v| v.as_u64()) {
+                e.output_tokens = v;
+            }
+            if let Some(v) = p.get("total_tokens").and_then(|v| v.as_u64()) {
+                e.total_tokens = v;
+            }
+            if let Some(v) = p.get("context_tokens").and_then(|v| v.as_u64()) {
+                e.context_tokens = v;
+            }
+            if let Some(v) = p.get("compaction_count").and_then(|v| v.as_u64()) {
+                e.compaction_count = v as u32;
+            }
+        })
     })?;
-    store.save(&sessions_path)?;
 
     Ok(json!({"ok": true}))
 }
@@ -385,16 +377,32 @@ pub fn handle_memory_search(params: &Value) -> Result<Value> {
     Ok(json!(arr))
 }
 
+/// Count tokens for `text` (string or array of strings) with the tokenizer of `model`.
+/// Returns `{"tokens": n | [n, ...], "model_family": "...", "approximate": bool}`;
+/// `approximate` is true when the chars/4 estimate was used instead of the real vocabulary.
 pub fn handle_token_count(params: &Value) -> Result<Value> {
     let p = params.as_object().context("params must be object")?;
-    let text = p
-        .get("text")
-        .and_then(|v| v.as_str())
-        .context("text required")?;
-
-    // Approximate: ~4 chars per token
-    let count = (text.len() as f64 / 4.0).ceil() as u64;
-    Ok(json!({"tokens": count}))
+    let text = p.get("text").context("text required")?;
+    let model = p.get("model").and_then(|v| v.as_str()).unwrap_or("");
+    let tokenizer = skilllite_core::tokens::for_model(model);
+
+    let tokens = match text {
+        Value::String(s) => json!(tokenizer.count(s)),
+        Value::Array(items) => {
+            let counts = items
+                .iter()
+                .map(|v| v.as_str().map(|s| tokenizer.count(s)))
+                .collect::<Option<Vec<_>>>()
+                .context("text array must contain only strings")?;
+            json!(counts)
+        }
+        _ => bail!("text must be a string or an array of strings"),
+    };
+    Ok(json!({
+        "tokens": tokens,
+        "model_family": tokenizer.family().as_str(),
+        "approximate": tokenizer.is_approximate(),
+    }))
 }
 
 /// Convert plan (task list) JSON to human-readable text.
@@ -406,3 +414,125 @@ pub fn handle_plan_textify(params: &Value) -> Result<Value> {
     let text = plan_textify_inner(tasks)?;
     Ok(json!({"text": text}))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    const THREADS: usize = 8;
+    const ROUNDS: u64 = 25;
+
+    #[test]
+    fn concurrent_session_updates_lose_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().to_string_lossy().to_string();
+        let sessions_path = tmp.path().join("chat").join("sessions.json");
+        let done = Arc::new(AtomicBool::new(false));
+
+        // Readers must always see a complete document while writers race.
+        let reader = {
+            let path = sessions_path.clone();
+            let done = Arc::clone(&done);
+            std::thread::spawn(move || {
+                let mut reads = 0;
+                while !done.load(Ordering::SeqCst) {
+                    if let Ok(content) = fs::read_to_string(&path) {
+                        serde_json::from_str::<SessionStore>(&content)
+                            .unwrap_or_else(|e| panic!("corrupt sessions.json: {}", e));
+                        reads += 1;
+                    }
+                }
+                reads
+            })
+        };
+
+        let writers: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let workspace = workspace.clone();
+                std::thread::spawn(move || {
+                    let key = format!("s{}", t);
+                    let params = json!({"session_key": key, "workspace_path": workspace});
+                    handle_session_create(&params).unwrap();
+                    for round in 1..=ROUNDS {
+                        handle_session_update(&json!({
+                            "session_key": key,
+                            "workspace_path": workspace,
+                            "input_tokens": round,
+                            "total_tokens": round * 2,
+                        }))
+                        .unwrap();
+                        // Interleave a create of a neighbour key (no-op once it exists).
+                        handle_session_create(&json!({
+                            "session_key": format!("s{}", (t + 1) % THREADS),
+                            "workspace_path": workspace,
+                        }))
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for w in writers {
+            w.join().unwrap();
+        }
+        done.store(true, Ordering::SeqCst);
+        assert!(reader.join().unwrap() > 0);
+
+        let store = SessionStore::load(&sessions_path).unwrap();
+        assert_eq!(store.sessions.len(), THREADS);
+        for t in 0..THREADS {
+            let entry = store.get(&format!("s{}", t)).unwrap();
+            assert_eq!(entry.input_tokens, ROUNDS, "s{}", t);
+            assert_eq!(entry.total_tokens, ROUNDS * 2, "s{}", t);
+        }
+    }
+
+    #[test]
+    fn concurrent_plan_writes_keep_every_line() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().to_string_lossy().to_string();
+        let writers: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let workspace = workspace.clone();
+                std::thread::spawn(move || {
+                    for round in 0..ROUNDS {
+                        handle_plan_write(&json!({
+                            "session_key": "shared",
+                            "workspace_path": workspace,
+                            "task": format!("writer {} round {}", t, round),
+                            "steps": [{"id": 1, "description": "step", "completed": false}],
+                        }))
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for w in writers {
+            w.join().unwrap();
+        }
+
+        let path = crate::plan::plan_path_jsonl(&tmp.path().join("chat/plans"), "shared", None);
+        let content = fs::read_to_string(path).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), THREADS * ROUNDS as usize);
+    }
+
+    #[test]
+    fn session_update_reports_contention_as_lock_timeout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let params = json!({
+            "session_key": "s",
+            "workspace_path": tmp.path().to_string_lossy(),
+        });
+        handle_session_create(&params).unwrap();
+
+        let sessions_path = tmp.path().join("chat").join("sessions.json");
+        let err =
+            crate::lock::with_lock(&sessions_path, || handle_session_update(&params)).unwrap_err();
+        assert!(err.is_lock_timeout(), "{}", err);
+    }
+}
@@ -0,0 +1,333 @@
+//! Run-mode checkpoints in SQLite: one row per `skilllite run`, keyed by run id.
+//!
+//! The executor stores the agent's state as an opaque JSON blob next to the columns needed
+//! for listing (goal, workspace, task progress, token counters). Rows whose last update is
+//! older than `SKILLLITE_RUN_CHECKPOINT_MAX_AGE_DAYS` are removed when the store is opened.
+
+use crate::error::{bail, Result};
+use chrono::{Duration, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+const CHECKPOINT_DIR: &str = "run_checkpoints";
+const CHECKPOINT_DB: &str = "checkpoints.sqlite";
+const DEFAULT_MAX_AGE_DAYS: u64 = 14;
+
+/// Listing columns of a checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointInfo {
+    pub id: String,
+    pub goal: String,
+    pub workspace: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub tasks_total: usize,
+    pub tasks_done: usize,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// A checkpoint with its serialized run state.
+#[derive(Debug, Clone)]
+pub struct CheckpointRecord {
+    pub info: CheckpointInfo,
+    pub state: String,
+}
+
+/// Path of the checkpoint DB under `chat_root`.
+pub fn db_path(chat_root: &Path) -> PathBuf {
+    chat_root.join(CHECKPOINT_DIR).join(CHECKPOINT_DB)
+}
+
+/// Checkpoints older than this many days are expired; `0` keeps them forever.
+pub fn max_age_days() -> u64 {
+    use skilllite_core::config::env_keys::executor as exe_keys;
+    std::env::var(exe_keys::SKILLLITE_RUN_CHECKPOINT_MAX_AGE_DAYS)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MAX_AGE_DAYS)
+}
+
+/// RFC 3339 in UTC with second precision, so timestamps sort as strings.
+pub fn now_timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub struct CheckpointStore {
+    conn: Connection,
+}
+
+impl CheckpointStore {
+    /// Open (or create) the store under `chat_root` and expire stale checkpoints.
+    pub fn open(chat_root: &Path) -> Result<Self> {
+        let path = db_path(chat_root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let store = Self::from_connection(Connection::open(&path)?)?;
+        let expired = store.expire(max_age_days())?;
+        if expired > 0 {
+            tracing::debug!("Expired {} run checkpoint(s)", expired);
+        }
+        Ok(store)
+    }
+
+    /// Use an existing connection (tests use an in-memory DB). Does not expire anything.
+    pub fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS run_checkpoints (
+                id TEXT PRIMARY KEY,
+                goal TEXT NOT NULL,
+                workspace TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                tasks_total INTEGER NOT NULL DEFAULT 0,
+                tasks_done INTEGER NOT NULL DEFAULT 0,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                state TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_run_checkpoints_workspace
+                ON run_checkpoints(workspace, updated_at);
+            "#,
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Insert or update a checkpoint. Goal, workspace and `created_at` of an existing row are
+    /// kept, so a resumed run stays one entry.
+    pub fn save(&self, info: &CheckpointInfo, state: &str) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO run_checkpoints (
+                id, goal, workspace, created_at, updated_at,
+                tasks_total, tasks_done, prompt_tokens, completion_tokens, state
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            ON CONFLICT(id) DO UPDATE SET
+                updated_at = excluded.updated_at,
+                tasks_total = excluded.tasks_total,
+                tasks_done = excluded.tasks_done,
+                prompt_tokens = excluded.prompt_tokens,
+                completion_tokens = excluded.completion_tokens,
+                state = excluded.state
+            "#,
+            params![
+                info.id,
+                info.goal,
+                info.workspace,
+                info.created_at,
+                info.updated_at,
+                info.tasks_total as i64,
+                info.tasks_done as i64,
+                info.prompt_tokens as i64,
+                info.completion_tokens as i64,
+                state,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Look up a checkpoint by full id or by a prefix that matches exactly one id.
+    pub fn get(&self, id: &str) -> Result<Option<CheckpointRecord>> {
+        let id = id.trim();
+        if id.is_empty() {
+            return Ok(None);
+        }
+        if let Some(record) = self.query_one("WHERE id = ?1", &id)? {
+            return Ok(Some(record));
+        }
+        let pattern = format!("{}%", escape_like(id));
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM run_checkpoints WHERE id LIKE ?1 ESCAPE '\\' ORDER BY id LIMIT 2",
+        )?;
+        let ids: Vec<String> = stmt
+            .query_map([pattern], |row| row.get(0))?
+            .collect::<std::result::Result<_, _>>()?;
+        match ids.as_slice() {
+            [] => Ok(None),
+            [only] => self.query_one("WHERE id = ?1", only),
+            _ => bail!("Checkpoint id prefix '{}' is ambiguous", id),
+        }
+    }
+
+    /// The most recently updated checkpoint of `workspace`.
+    pub fn latest_for_workspace(&self, workspace: &str) -> Result<Option<CheckpointRecord>> {
+        self.query_one(
+            "WHERE workspace = ?1 ORDER BY updated_at DESC, rowid DESC LIMIT 1",
+            &workspace,
+        )
+    }
+
+    /// All checkpoints, most recently updated first.
+    pub fn list(&self) -> Result<Vec<CheckpointInfo>> {
+        let sql = format!(
+            "SELECT {} FROM run_checkpoints ORDER BY updated_at DESC, rowid DESC",
+            INFO_COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map([], info_from_row)?
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(rows)
+    }
+
+    /// Delete one checkpoint by full id. Returns whether it existed.
+    pub fn discard(&self, id: &str) -> Result<bool> {
+        let n = self
+            .conn
+            .execute("DELETE FROM run_checkpoints WHERE id = ?1", [id])?;
+        Ok(n > 0)
+    }
+
+    /// Delete checkpoints not updated for `max_age_days`; `0` keeps everything.
+    pub fn expire(&self, max_age_days: u64) -> Result<usize> {
+        if max_age_days == 0 {
+            return Ok(0);
+        }
+        let days = max_age_days.min(36_500) as i64;
+        let cutoff = Utc::now()
+            .checked_sub_signed(Duration::days(days))
+            .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_default();
+        let n = self.conn.execute(
+            "DELETE FROM run_checkpoints WHERE updated_at < ?1",
+            [cutoff],
+        )?;
+        Ok(n)
+    }
+
+    fn query_one(
+        &self,
+        clause: &str,
+        param: &dyn rusqlite::ToSql,
+    ) -> Result<Option<CheckpointRecord>> {
+        let sql = format!(
+            "SELECT {}, state FROM run_checkpoints {}",
+            INFO_COLUMNS, clause
+        );
+        let record = self
+            .conn
+            .query_row(&sql, [param], |row| {
+                Ok(CheckpointRecord {
+                    info: info_from_row(row)?,
+                    state: row.get(9)?,
+                })
+            })
+            .optional()?;
+        Ok(record)
+    }
+}
+
+const INFO_COLUMNS: &str = "id, goal, workspace, created_at, updated_at, \
+     tasks_total, tasks_done, prompt_tokens, completion_tokens";
+
+fn info_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CheckpointInfo> {
+    Ok(CheckpointInfo {
+        id: row.get(0)?,
+        goal: row.get(1)?,
+        workspace: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+        tasks_total: row.get::<_, i64>(5)?.max(0) as usize,
+        tasks_done: row.get::<_, i64>(6)?.max(0) as usize,
+        prompt_tokens: row.get::<_, i64>(7)?.max(0) as u64,
+        completion_tokens: row.get::<_, i64>(8)?.max(0) as u64,
+    })
+}
+
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(id: &str, workspace: &str, updated_at: &str, done: usize) -> CheckpointInfo {
+        CheckpointInfo {
+            id: id.to_string(),
+            goal: format!("goal of {}", id),
+            workspace: workspace.to_string(),
+            created_at: updated_at.to_string(),
+            updated_at: updated_at.to_string(),
+            tasks_total: 7,
+            tasks_done: done,
+            prompt_tokens: 100,
+            completion_tokens: 20,
+        }
+    }
+
+    fn store() -> CheckpointStore {
+        CheckpointStore::from_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn save_upserts_and_keeps_goal_and_created_at() {
+        let store = store();
+        store
+            .save(&info("a1", "/ws", "2026-01-01T00:00:00Z", 1), "{}")
+            .unwrap();
+        let mut update = info("a1", "/other", "2026-01-02T00:00:00Z", 3);
+        update.goal = "changed".to_string();
+        update.created_at = "2026-01-02T00:00:00Z".to_string();
+        store.save(&update, r#"{"step":3}"#).unwrap();
+
+        let record = store.get("a1").unwrap().unwrap();
+        assert_eq!(record.info.goal, "goal of a1");
+        assert_eq!(record.info.workspace, "/ws");
+        assert_eq!(record.info.created_at, "2026-01-01T00:00:00Z");
+        assert_eq!(record.info.updated_at, "2026-01-02T00:00:00Z");
+        assert_eq!(record.info.tasks_done, 3);
+        assert_eq!(record.state, r#"{"step":3}"#);
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn prefix_lookup_and_latest_per_workspace() {
+        let store = store();
+        store
+            .save(&info("abc-1", "/ws", "2026-01-01T00:00:00Z", 1), "{}")
+            .unwrap();
+        store
+            .save(&info("abd-2", "/ws", "2026-01-03T00:00:00Z", 2), "{}")
+            .unwrap();
+        store
+            .save(
+                &info("xyz-3", "/elsewhere", "2026-01-05T00:00:00Z", 0),
+                "{}",
+            )
+            .unwrap();
+
+        assert_eq!(store.get("abc").unwrap().unwrap().info.id, "abc-1");
+        assert!(store.get("ab").is_err());
+        assert!(store.get("zzz").unwrap().is_none());
+        assert!(store.get("a_c").unwrap().is_none());
+
+        let latest = store.latest_for_workspace("/ws").unwrap().unwrap();
+        assert_eq!(latest.info.id, "abd-2");
+        let ids: Vec<String> = store.list().unwrap().into_iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec!["xyz-3", "abd-2", "abc-1"]);
+    }
+
+    #[test]
+    fn discard_and_expire() {
+        let store = store();
+        store
+            .save(&info("old", "/ws", "2000-01-01T00:00:00Z", 1), "{}")
+            .unwrap();
+        store
+            .save(&info("new", "/ws", &now_timestamp(), 1), "{}")
+            .unwrap();
+
+        assert_eq!(store.expire(0).unwrap(), 0);
+        assert_eq!(store.expire(14).unwrap(), 1);
+        assert!(store.get("old").unwrap().is_none());
+
+        assert!(store.discard("new").unwrap());
+        assert!(!store.discard("new").unwrap());
+        assert!(store.list().unwrap().is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 //! Session store: sessions.json
 //!
 //! Schema aligned with OpenClaw: sessionId, sessionKey, token counts, compaction state.
+//! Read-modify-write cycles go through [`SessionStore::modify`], which holds the file's
+//! advisory lock (see [`crate::lock`]) and replaces the file atomically.
 
 use crate::error::Result;
 use anyhow::Context;
@@ -9,6 +11,15 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+use crate::lock;
+use crate::transcript;
+
+/// Extra field holding a user-chosen session title (shared with the desktop assistant).
+const DISPLAY_NAME_KEY: &str = "display_name";
+
+/// Characters of the first user message used as a fallback title.
+const TITLE_PREVIEW_CHARS: usize = 40;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionEntry {
     pub session_id: String,
@@ -32,6 +43,16 @@ pub struct SessionEntry {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+impl SessionEntry {
+    /// User-chosen title, if the session was renamed.
+    pub fn display_name(&self) -> Option<&str> {
+        self.extra
+            .get(DISPLAY_NAME_KEY)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SessionStore {
     pub sessions: HashMap<String, SessionEntry>,
@@ -47,13 +68,23 @@ impl SessionStore {
         Ok(serde_json::from_str(&content).with_context(|| "Invalid sessions.json")?)
     }
 
+    /// Write via temp file + rename, so readers never see a truncated store.
     pub fn save(&self, path: &Path) -> Result<()> {
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
         let content = serde_json::to_string_pretty(self)?;
-        Ok(fs::write(path, content)
-            .with_context(|| format!("Failed to write session store: {}", path.display()))?)
+        skilllite_fs::atomic_write(path, &content)
+            .with_context(|| format!("Failed to write session store: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Load, change and save `path` while holding its lock, so concurrent writers never
+    /// lose each other's updates. Fails with [`crate::Error::LockTimeout`] on contention.
+    pub fn modify<T>(path: &Path, f: impl FnOnce(&mut SessionStore) -> Result<T>) -> Result<T> {
+        lock::with_lock(path, || {
+            let mut store = Self::load(path)?;
+            let out = f(&mut store)?;
+            store.save(path)?;
+            Ok(out)
+        })
     }
 
     pub fn get(&self, session_key: &str) -> Option<&SessionEntry> {
@@ -92,6 +123,19 @@ impl SessionStore {
         Ok(())
     }
 
+    /// Persist a display title; creates the entry when the session only exists as a transcript.
+    pub fn set_display_name(&mut self, session_key: &str, title: &str) {
+        let entry = self.create_or_get(session_key);
+        entry.extra.insert(
+            DISPLAY_NAME_KEY.to_string(),
+            serde_json::Value::String(title.trim().to_string()),
+        );
+    }
+
+    pub fn remove(&mut self, session_key: &str) -> Option<SessionEntry> {
+        self.sessions.remove(session_key)
+    }
+
     /// Reset compaction-related fields for a fresh session (e.g. after /new or clear).
     pub fn reset_compaction_state(&mut self, session_key: &str) {
         if let Some(entry) = self.sessions.get_mut(session_key) {
@@ -103,6 +147,137 @@ impl SessionStore {
     }
 }
 
+/// One row of [`list_sessions`].
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SessionSummary {
+    pub session_key: String,
+    /// Display title if renamed, else the first user message (truncated)
+    pub title: Option<String>,
+    /// Whether `title` is a user-chosen display title
+    pub renamed: bool,
+    /// Unix seconds of the latest store update or transcript write
+    pub last_activity: u64,
+    /// `user` + `assistant` messages in live transcripts
+    pub message_count: usize,
+}
+
+/// Every session under `chat_root`: entries in sessions.json plus sessions that only
+/// have transcripts. Newest activity first.
+pub fn list_sessions(chat_root: &Path) -> Result<Vec<SessionSummary>> {
+    let store = SessionStore::load(&chat_root.join("sessions.json"))?;
+    let transcripts_dir = chat_root.join("transcripts");
+    let mut keys: Vec<String> = store.sessions.keys().cloned().collect();
+    for key in transcript::list_session_keys(&transcripts_dir)? {
+        if !store.sessions.contains_key(&key) {
+            keys.push(key);
+        }
+    }
+
+    let mut out = Vec::with_capacity(keys.len());
+    for key in keys {
+        let stats = transcript::transcript_stats(&transcripts_dir, &key)?;
+        let entry = store.get(&key);
+        let display_name = entry.and_then(|e| e.display_name());
+        let title = display_name
+            .map(str::to_string)
+            .or_else(|| stats.first_user_message.as_deref().map(truncate_title));
+        let updated_at = entry.and_then(|e| e.updated_at.parse::<u64>().ok());
+        out.push(SessionSummary {
+            session_key: key,
+            title,
+            renamed: display_name.is_some(),
+            last_activity: updated_at.max(stats.last_modified).unwrap_or(0),
+            message_count: stats.message_count,
+        });
+    }
+    out.sort_by(|a, b| {
+        b.last_activity
+            .cmp(&a.last_activity)
+            .then_with(|| a.session_key.cmp(&b.session_key))
+    });
+    Ok(out)
+}
+
+/// Add a new titled session (`s-<millis hex>` key) to sessions.json.
+pub fn create_session(chat_root: &Path, title: &str) -> Result<SessionSummary> {
+    if title.trim().is_empty() {
+        crate::error::bail!("Session title must not be empty");
+    }
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let session_key = SessionStore::modify(&chat_root.join("sessions.json"), |store| {
+        let mut stamp = millis;
+        while store.sessions.contains_key(&format!("s-{:x}", stamp)) {
+            stamp += 1;
+        }
+        let session_key = format!("s-{:x}", stamp);
+        store.set_display_name(&session_key, title);
+        Ok(session_key)
+    })?;
+    Ok(SessionSummary {
+        session_key,
+        title: Some(title.trim().to_string()),
+        renamed: true,
+        last_activity: (millis / 1000) as u64,
+        message_count: 0,
+    })
+}
+
+/// Set a session's display title in sessions.json.
+pub fn rename_session(chat_root: &Path, session_key: &str, title: &str) -> Result<()> {
+    if title.trim().is_empty() {
+        crate::error::bail!("Session title must not be empty");
+    }
+    SessionStore::modify(&chat_root.join("sessions.json"), |store| {
+        store.set_display_name(session_key, title);
+        Ok(())
+    })
+}
+
+/// Remove a session's store entry, transcripts (including archived copies), plans and file
+/// history.
+/// Returns the number of files removed.
+pub fn delete_session(chat_root: &Path, session_key: &str) -> Result<usize> {
+    SessionStore::modify(&chat_root.join("sessions.json"), |store| {
+        store.remove(session_key);
+        Ok(())
+    })?;
+    let mut removed = 0;
+    for dir in [chat_root.join("transcripts"), chat_root.join("plans")] {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for e in entries.flatten() {
+            let name = e.file_name();
+            let belongs = transcript::session_key_from_file_name(&name.to_string_lossy())
+                .is_some_and(|(key, _)| key == session_key);
+            if belongs && e.path().is_file() {
+                fs::remove_file(e.path())
+                    .with_context(|| format!("Failed to remove {}", e.path().display()))?;
+                removed += 1;
+            }
+        }
+    }
+    crate::file_history::remove_history(chat_root, session_key)?;
+    Ok(removed)
+}
+
+fn truncate_title(text: &str) -> String {
+    let line = text
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("")
+        .trim();
+    if line.chars().count() > TITLE_PREVIEW_CHARS {
+        let head: String = line.chars().take(TITLE_PREVIEW_CHARS).collect();
+        format!("{}…", head)
+    } else {
+        line.to_string()
+    }
+}
+
 fn chrono_now() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
     let secs = SystemTime::now()
@@ -120,3 +295,89 @@ fn uuid_short() -> String {
         .unwrap_or(0);
     format!("{:x}", t % 0xFFFF_FFFF)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_transcript(dir: &Path, file: &str, user_text: &str) {
+        fs::create_dir_all(dir).unwrap();
+        let rows = [
+            serde_json::json!({"type": "session", "id": "s", "cwd": null, "timestamp": "0"}),
+            serde_json::json!({"type": "message", "id": "1", "parent_id": null, "role": "user", "content": user_text}),
+            serde_json::json!({"type": "message", "id": "2", "parent_id": "1", "role": "assistant", "content": "ok"}),
+        ];
+        let body: Vec<String> = rows.iter().map(|r| r.to_string()).collect();
+        fs::write(dir.join(file), body.join("\n")).unwrap();
+    }
+
+    #[test]
+    fn lists_store_and_transcript_only_sessions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let transcripts = root.join("transcripts");
+        write_transcript(&transcripts, "s1-2026-01-01.jsonl", "first question");
+        write_transcript(&transcripts, "s1-2026-01-02.jsonl", "second question");
+        write_transcript(&transcripts, "s10.jsonl", &"x".repeat(60));
+
+        let mut store = SessionStore::default();
+        store.create_or_get("empty");
+        store.save(&root.join("sessions.json")).unwrap();
+
+        let list = list_sessions(root).unwrap();
+        let by_key = |k: &str| list.iter().find(|s| s.session_key == k).unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(by_key("s1").message_count, 4);
+        assert_eq!(by_key("s1").title.as_deref(), Some("first question"));
+        assert_eq!(by_key("s10").message_count, 2);
+        assert!(by_key("s10").title.as_deref().unwrap().ends_with('…'));
+        assert_eq!(by_key("empty").title, None);
+
+        rename_session(root, "s1", "  Trip plans ").unwrap();
+        let s1 = list_sessions(root)
+            .unwrap()
+            .into_iter()
+            .find(|s| s.session_key == "s1")
+            .unwrap();
+        assert_eq!(s1.title.as_deref(), Some("Trip plans"));
+        assert!(s1.renamed);
+        assert!(rename_session(root, "s1", " ").is_err());
+    }
+
+    #[test]
+    fn create_adds_titled_sessions_with_distinct_keys() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let a = create_session(root, " Notes ").unwrap();
+        let b = create_session(root, "Notes").unwrap();
+        assert_ne!(a.session_key, b.session_key);
+        assert_eq!(a.title.as_deref(), Some("Notes"));
+
+        let list = list_sessions(root).unwrap();
+        assert_eq!(list.len(), 2);
+        assert!(list.iter().all(|s| s.renamed && s.message_count == 0));
+        assert!(create_session(root, "  ").is_err());
+    }
+
+    #[test]
+    fn delete_removes_only_matching_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let transcripts = root.join("transcripts");
+        write_transcript(&transcripts, "s1-2026-01-01.jsonl", "a");
+        write_transcript(&transcripts, "s1-2026-01-01.jsonl.archived.1700000000", "a");
+        write_transcript(&transcripts, "s10-2026-01-01.jsonl", "b");
+        fs::create_dir_all(root.join("plans")).unwrap();
+        fs::write(root.join("plans/s1-2026-01-01.jsonl"), "").unwrap();
+        rename_session(root, "s1", "doomed").unwrap();
+
+        assert_eq!(delete_session(root, "s1").unwrap(), 3);
+        let keys: Vec<String> = list_sessions(root)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_key)
+            .collect();
+        assert_eq!(keys, vec!["s10".to_string()]);
+        assert!(transcripts.join("s10-2026-01-01.jsonl").exists());
+    }
+}
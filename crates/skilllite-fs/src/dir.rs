@@ -5,7 +5,7 @@ use std::time::SystemTime;
 
 use anyhow::Context;
 
-use crate::{Error, Result};
+use crate::{util, Error, Result};
 
 /// 路径类型
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -249,3 +249,257 @@ fn write_tree_children(
     }
     Ok(())
 }
+
+// ─── Filtered listing (list_directory tool) ─────────────────────────────────
+
+/// Sort order for [`walk_directory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListSortBy {
+    /// Depth-first by name (tree order).
+    #[default]
+    Name,
+    /// Newest first.
+    Mtime,
+    /// Largest first; directories last.
+    Size,
+}
+
+impl ListSortBy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "name" => Some(Self::Name),
+            "mtime" | "modified" => Some(Self::Mtime),
+            "size" => Some(Self::Size),
+            _ => None,
+        }
+    }
+}
+
+/// Options for [`walk_directory`].
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Levels to descend; `Some(1)` lists direct children only, `None` is unbounded.
+    pub max_depth: Option<usize>,
+    /// Glob on the relative path (`*`, `**`, `?`). Without a `/` it matches the file name at
+    /// any depth.
+    pub pattern: Option<String>,
+    pub sort_by: ListSortBy,
+    pub dirs_only: bool,
+    pub files_only: bool,
+    /// Descend into dot-directories (`.git`, `.venv`, …). Other heavy dirs stay unwalked.
+    pub include_hidden: bool,
+    /// Entries kept after sorting; `0` keeps all.
+    pub max_entries: usize,
+}
+
+impl ListOptions {
+    /// Plain tree output applies: no filter and name order.
+    pub fn is_tree(&self) -> bool {
+        self.pattern.is_none()
+            && self.sort_by == ListSortBy::Name
+            && !self.dirs_only
+            && !self.files_only
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ListedEntry {
+    /// Path relative to the listed directory, `/`-separated.
+    pub rel_path: String,
+    pub name: String,
+    /// 1 for direct children.
+    pub depth: usize,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// No later entry shares its parent in the uncapped listing (drawn as `└──`).
+    pub last_sibling: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DirListing {
+    pub entries: Vec<ListedEntry>,
+    /// Matching entries before the `max_entries` cap.
+    pub total: usize,
+    pub truncated: bool,
+}
+
+/// Walk `path` with depth, glob and type filters, then sort and cap the result.
+pub fn walk_directory(path: &Path, opts: &ListOptions) -> Result<DirListing> {
+    if !path.exists() {
+        return Err(Error::validation(format!(
+            "Directory not found: {}",
+            path.display()
+        )));
+    }
+    if !path.is_dir() {
+        return Err(Error::validation(format!(
+            "Path is not a directory: {}",
+            path.display()
+        )));
+    }
+    let mut entries = Vec::new();
+    walk_impl(path, "", 1, opts, &mut entries)?;
+    match opts.sort_by {
+        ListSortBy::Name => {}
+        ListSortBy::Mtime => entries.sort_by_key(|e| std::cmp::Reverse(e.modified)),
+        ListSortBy::Size => {
+            entries.sort_by(|a, b| a.is_dir.cmp(&b.is_dir).then(b.size.cmp(&a.size)))
+        }
+    }
+    // Decided before the cap so a truncated tree does not draw a cut-off sibling as the last one
+    let mut last_child: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for (i, e) in entries.iter().enumerate() {
+        last_child.insert(parent_of(&e.rel_path), i);
+    }
+    let last: Vec<usize> = last_child.into_values().collect();
+    for i in last {
+        entries[i].last_sibling = true;
+    }
+    let total = entries.len();
+    let truncated = opts.max_entries > 0 && total > opts.max_entries;
+    if truncated {
+        entries.truncate(opts.max_entries);
+    }
+    Ok(DirListing {
+        entries,
+        total,
+        truncated,
+    })
+}
+
+fn walk_impl(
+    dir: &Path,
+    rel_prefix: &str,
+    depth: usize,
+    opts: &ListOptions,
+    out: &mut Vec<ListedEntry>,
+) -> Result<()> {
+    let mut items: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read dir: {}", dir.display()))?
+        .filter_map(|e| e.ok())
+        .collect();
+    items.sort_by_key(|e| e.file_name());
+
+    for entry in items {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let entry_path = entry.path();
+        let is_dir = entry_path.is_dir();
+        let meta = entry.metadata().ok();
+        let rel_path = format!("{}{}", rel_prefix, name);
+
+        let keep = (!opts.dirs_only || is_dir)
+            && (!opts.files_only || !is_dir)
+            && opts
+                .pattern
+                .as_deref()
+                .is_none_or(|p| util::matches_path_glob(&rel_path, p));
+        if keep {
+            out.push(ListedEntry {
+                rel_path: rel_path.clone(),
+                name: name.clone(),
+                depth,
+                is_dir,
+                size: if is_dir {
+                    0
+                } else {
+                    meta.as_ref().map(|m| m.len()).unwrap_or(0)
+                },
+                modified: meta.as_ref().and_then(|m| m.modified().ok()),
+                last_sibling: false,
+            });
+        }
+
+        let hidden = name.starts_with('.');
+        let descend = is_dir
+            && opts.max_depth.is_none_or(|max| depth < max)
+            && if hidden {
+                opts.include_hidden
+            } else {
+                !TREE_SKIP_DIRS.contains(&name.as_str())
+            };
+        if descend {
+            walk_impl(&entry_path, &format!("{}/", rel_path), depth + 1, opts, out)?;
+        }
+    }
+    Ok(())
+}
+
+impl DirListing {
+    /// Human-readable listing: an ASCII tree rooted at `root_label` for [`ListOptions::is_tree`],
+    /// otherwise one relative path per line (with age when sorted by mtime).
+    pub fn render(&self, root_label: &str, opts: &ListOptions) -> String {
+        let mut out = String::new();
+        if opts.is_tree() {
+            out.push_str(root_label);
+            out.push_str("/\n");
+            self.render_tree(&mut out);
+        } else {
+            let now = SystemTime::now();
+            for e in &self.entries {
+                if e.is_dir {
+                    out.push_str(&format!("{}/", e.rel_path));
+                } else if opts.sort_by == ListSortBy::Mtime {
+                    let age = e
+                        .modified
+                        .and_then(|m| now.duration_since(m).ok())
+                        .map(|d| format_age(d.as_secs()))
+                        .unwrap_or_else(|| "?".to_string());
+                    out.push_str(&format!(
+                        "{} ({}, {} ago)",
+                        e.rel_path,
+                        format_size(e.size),
+                        age
+                    ));
+                } else {
+                    out.push_str(&format!("{} ({})", e.rel_path, format_size(e.size)));
+                }
+                out.push('\n');
+            }
+            if self.entries.is_empty() {
+                out.push_str("[No matching entries]\n");
+            }
+        }
+        out
+    }
+
+    fn render_tree(&self, out: &mut String) {
+        // ancestors[d] = whether the ancestor at depth d+1 was the last of its siblings
+        let mut ancestors: Vec<bool> = Vec::new();
+        for e in &self.entries {
+            let is_last = e.last_sibling;
+            ancestors.truncate(e.depth.saturating_sub(1));
+            for last in &ancestors {
+                out.push_str(if *last { "    " } else { "│   " });
+            }
+            out.push_str(if is_last { "└── " } else { "├── " });
+            if e.is_dir {
+                out.push_str(&e.name);
+                out.push('/');
+                ancestors.push(is_last);
+            } else {
+                out.push_str(&format!("{} ({})", e.name, format_size(e.size)));
+            }
+            out.push('\n');
+        }
+    }
+}
+
+fn parent_of(rel_path: &str) -> &str {
+    rel_path
+        .rsplit_once('/')
+        .map(|(p, _)| p)
+        .unwrap_or_default()
+}
+
+fn format_age(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else if secs < 86_400 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}d", secs / 86_400)
+    }
+}
@@ -1,12 +1,12 @@
 //! SkillLite FS: 中心化文件操作
 //!
 //! 模块：
-//! - read_write: read_file, write_file, append_file, atomic_write
-//! - dir: read_dir, list_directory, file_exists, create_dir_all, copy, rename, remove_file, modified_time
+//! - read_write: read_file, write_file, append_file, atomic_write, atomic_write_with_mode
+//! - dir: read_dir, list_directory, walk_directory, file_exists, create_dir_all, copy, rename, remove_file, modified_time
 //! - grep: grep_directory
 //! - search_replace: apply_search_replace, apply_replace_fuzzy, insert_lines_at
 //! - backup: backup_file, prune_oldest_files
-//! - util: is_likely_binary, matches_glob
+//! - util: is_likely_binary, matches_glob, matches_path_glob
 
 pub mod env_keys;
 pub mod error;
@@ -24,18 +24,19 @@ pub use error::{Error, Result};
 pub use backup::{backup_file, prune_oldest_files};
 pub use dir::{
     copy, create_dir_all, directory_tree, file_exists, list_directory, modified_time, read_dir,
-    remove_file, rename, PathKind,
+    remove_file, rename, walk_directory, DirListing, ListOptions, ListSortBy, ListedEntry,
+    PathKind,
 };
 pub use grep::{grep_directory, GrepMatch, SKIP_DIRS};
 pub use read_write::{
-    append_file, atomic_write, read_bytes, read_bytes_limit, read_file,
+    append_file, atomic_write, atomic_write_with_mode, read_bytes, read_bytes_limit, read_file,
     search_replace as search_replace_file, write_file,
 };
 pub use search_replace::{
     apply_replace_fuzzy, apply_replace_normalize_whitespace, apply_search_replace,
     build_failure_hint, insert_lines_at, line_byte_offsets, safe_excerpt, FuzzyReplaceResult,
 };
-pub use util::{is_likely_binary, matches_glob};
+pub use util::{is_likely_binary, matches_glob, matches_path_glob};
 
 #[cfg(test)]
 mod tests {
@@ -82,6 +83,15 @@ mod tests {
         assert!(s.contains("b.txt"));
     }
 
+    #[test]
+    fn test_matches_path_glob() {
+        assert!(matches_path_glob("data/2024/q1.csv", "*.csv"));
+        assert!(matches_path_glob("data/2024/q1.csv", "data/**/*.csv"));
+        assert!(matches_path_glob("data/q1.csv", "data/**/*.csv"));
+        assert!(!matches_path_glob("data/2024/q1.csv", "data/*.csv"));
+        assert!(matches_path_glob("src/a.rs", "src/?.rs"));
+    }
+
     #[test]
     fn test_apply_search_replace_once() {
         let content = "hello world\nhello rust";
@@ -115,4 +125,20 @@ mod tests {
         let content = read_file(path).unwrap();
         assert_eq!(content, "baz bar baz");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_atomic_write_with_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("secret.toml");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        // A leftover temp file with wider permissions is not reused
+        std::fs::write(path.with_extension("tmp"), "stale").unwrap();
+        atomic_write_with_mode(&path, "k = 1", 0o600).unwrap();
+        assert_eq!(read_file(&path).unwrap(), "k = 1");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!path.with_extension("tmp").exists());
+    }
 }
@@ -1,4 +1,4 @@
-//! 读写原语：read_file, write_file, append_file, atomic_write, search_replace
+//! 读写原语：read_file, write_file, append_file, atomic_write(_with_mode), search_replace
 
 use std::path::Path;
 
@@ -67,6 +67,35 @@ pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
     Ok(())
 }
 
+/// 原子写入并指定权限（Unix，如 0o600）；非 Unix 平台忽略 `mode`，等同 [`atomic_write`]
+pub fn atomic_write_with_mode(path: &Path, content: &str, mode: u32) -> Result<()> {
+    use std::io::Write;
+    if let Some(parent) = path.parent() {
+        dir::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    // mode 只在创建时生效，不复用权限更宽的残留文件
+    let _ = std::fs::remove_file(&tmp);
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(mode);
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    let mut file = opts
+        .open(&tmp)
+        .with_context(|| format!("Failed to create temp file: {}", tmp.display()))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .with_context(|| format!("Failed to write temp file: {}", tmp.display()))?;
+    drop(file);
+    dir::rename(&tmp, path)?;
+    Ok(())
+}
+
 /// 在文件内做精确 search_replace，返回替换次数
 pub fn search_replace(
     path: &Path,
@@ -1,4 +1,4 @@
-//! 通用工具：is_likely_binary, matches_glob
+//! 通用工具：is_likely_binary, matches_glob, matches_path_glob
 
 use std::path::Path;
 
@@ -20,3 +20,34 @@ pub fn matches_glob(name: &str, pattern: &str) -> bool {
         name == pattern
     }
 }
+
+/// 相对路径 glob：`*` / `?` 不跨 `/`，`**` 匹配任意层级；不含 `/` 的模式只匹配文件名
+pub fn matches_path_glob(rel_path: &str, pattern: &str) -> bool {
+    let pattern = pattern.trim().trim_start_matches("./");
+    if pattern.contains('/') {
+        glob_match(pattern.as_bytes(), rel_path.as_bytes())
+    } else {
+        let name = rel_path.rsplit('/').next().unwrap_or(rel_path);
+        glob_match(pattern.as_bytes(), name.as_bytes())
+    }
+}
+
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) if rest.first() == Some(&b'*') => {
+            // `**/` also matches zero directories
+            let rest = &rest[1..];
+            let after_slash = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=text.len())
+                .any(|i| glob_match(rest, &text[i..]) || glob_match(after_slash, &text[i..]))
+        }
+        Some((b'*', rest)) => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != b'/')
+            .any(|i| glob_match(rest, &text[i..])),
+        Some((b'?', rest)) => {
+            text.first().is_some_and(|c| *c != b'/') && glob_match(rest, &text[1..])
+        }
+        Some((c, rest)) => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
@@ -49,11 +49,16 @@ fn run() -> Result<(), String> {
         language: "python".to_string(),
         network_enabled: false,
         network_outbound: Vec::new(),
+        network_deny: Vec::new(),
         uses_playwright: false,
+        script_args: Vec::new(),
+        results_dir: None,
+        enforce_isolation: false,
     };
     let limits = ResourceLimits {
         max_memory_mb: 256,
         timeout_secs: 5,
+        max_cpu_percent: None,
     };
 
     for _ in 0..config.warmup {
@@ -0,0 +1,412 @@
+//! Bash command validator for bash-tool skills.
+//!
+//! This module provides security validation for bash commands issued by LLMs
+//! when executing bash-tool skills (e.g. `agent-browser`). All validation runs
+//! in compiled Rust code and cannot be bypassed from the Python SDK layer.
+//!
+//! ## Security Layers
+//!
+//! 1. **Unicode NFKC normalization** — applied before validation to prevent
+//!    Unicode homoglyph/confusable bypass (e.g. ｒｍ vs rm).
+//! 2. **Shell-word parsing** — the command line is split into a sequence of
+//!    pipelines (`;`, `&&`, `||`, newlines) of simple commands (`|`); `$(...)`
+//!    bodies are parsed recursively. Constructs the patterns cannot reason about
+//!    (backticks, process substitution, subshells, heredocs, `${...}`, background
+//!    jobs, env assignments) are rejected with the offending segment.
+//! 3. **Blocked prefix check** — dangerous commands (rm, sudo, sh, curl, etc.)
+//!    are always rejected in any segment, regardless of allowed patterns.
+//! 4. **Per-command pattern matching** — every simple command, including those
+//!    inside `$(...)`, must match one of the `allowed-tools: Bash(...)` patterns.
+//! 5. **Redirection targets** — absolute paths must stay under the allowed roots
+//!    (workspace / cwd), relative paths may not climb out with `..`.
+//!
+//! ## Pattern syntax
+//!
+//! `Bash(<words>[:<args>])`, matched word by word against each simple command:
+//!
+//! - `agent-browser:*` — `agent-browser` with any arguments.
+//! - `npm run *:*` — `npm run <one word>` followed by any arguments; a bare `*`
+//!   before the colon matches exactly one argument, `a-*` globs within one word.
+//! - `git status:` — exactly `git status`, no further arguments.
+//! - Without a colon, `tool` and `tool *` allow any arguments (legacy forms);
+//!   `git status` allows exactly those words.
+
+mod parser;
+
+use std::path::{Component, Path, PathBuf};
+
+use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+
+use parser::{CommandList, ParseError, Redirect, SimpleCommand, Word};
+
+/// Parsed pattern from `allowed-tools: Bash(prefix:*)`.
+///
+/// Defined locally so that the sandbox module does not depend on `skill::metadata`.
+/// Callers convert from `skill::metadata::BashToolPattern` at the call site.
+#[derive(Debug, Clone)]
+pub struct BashToolPattern {
+    /// Command prefix, e.g. "agent-browser"
+    pub command_prefix: String,
+    /// Raw pattern string, e.g. "agent-browser:*"
+    pub raw_pattern: String,
+}
+
+/// Errors returned by bash command validation.
+#[derive(Debug, Error)]
+pub enum BashValidationError {
+    #[error("Command contains chain operator '{0}' — potential injection")]
+    ChainOperator(String),
+
+    #[error("Command '{cmd}' does not match any allowed pattern (allowed: {allowed})")]
+    NoMatchingPattern { cmd: String, allowed: String },
+
+    #[error("Command starts with blocked prefix '{0}'")]
+    BlockedPrefix(String),
+
+    #[error("Empty command")]
+    EmptyCommand,
+
+    #[error("Unsupported shell construct: {construct} (in '{segment}')")]
+    UnsupportedConstruct { construct: String, segment: String },
+
+    #[error("Redirection target '{target}' is outside the workspace (in '{segment}')")]
+    RedirectOutsideWorkspace { target: String, segment: String },
+
+    #[error("Cannot parse command: {0}")]
+    Syntax(String),
+}
+
+impl From<ParseError> for BashValidationError {
+    fn from(e: ParseError) -> Self {
+        match e {
+            ParseError::Unsupported { construct, segment } => {
+                BashValidationError::UnsupportedConstruct { construct, segment }
+            }
+            ParseError::Syntax(msg) => BashValidationError::Syntax(msg),
+        }
+    }
+}
+
+/// Characters rejected before parsing: they either split lines in ways `sh` and the
+/// parser could disagree on, or truncate the command at the C boundary.
+const REJECTED_CHARS: &[(char, &str)] = &[('\r', "\\r"), ('\0', "\\0")];
+
+/// Redirection targets that are always allowed.
+const SAFE_REDIRECT_TARGETS: &[&str] = &["/dev/null", "/dev/stdout", "/dev/stderr"];
+
+/// Command prefixes that are always blocked, regardless of `allowed-tools`.
+const BLOCKED_PREFIXES: &[&str] = &[
+    "rm",
+    "sudo",
+    "su",
+    "sh",
+    "bash",
+    "zsh",
+    "fish",
+    "dash",
+    "curl",
+    "wget",
+    "chmod",
+    "chown",
+    "chgrp",
+    "mkfs",
+    "dd",
+    "kill",
+    "killall",
+    "pkill",
+    "reboot",
+    "shutdown",
+    "halt",
+    "poweroff",
+    "mount",
+    "umount",
+    "fdisk",
+    "nc",
+    "ncat",
+    "netcat",
+    "ssh",
+    "scp",
+    "rsync",
+    "eval",
+    "exec",
+    "source",
+    "env",
+    "nohup",
+    "xargs",
+    "osascript",
+];
+
+/// Validate a bash command against the allowed patterns from SKILL.md.
+///
+/// Returns `Ok(())` if the command passes all checks, or a descriptive error
+/// explaining why the command was rejected. Redirections to absolute paths are
+/// rejected (except `/dev/null` and friends); use [`validate_bash_command_in`]
+/// to allow paths under the workspace.
+///
+/// # Arguments
+///
+/// * `cmd` — The raw bash command string from the LLM.
+/// * `allowed_patterns` — Parsed `BashToolPattern` items from `allowed-tools`.
+pub fn validate_bash_command(
+    cmd: &str,
+    allowed_patterns: &[BashToolPattern],
+) -> Result<(), BashValidationError> {
+    validate_bash_command_in(cmd, allowed_patterns, &[])
+}
+
+/// Like [`validate_bash_command`], additionally allowing redirections to absolute
+/// paths under `allowed_roots` (the workspace and the command's working directory).
+pub fn validate_bash_command_in(
+    cmd: &str,
+    allowed_patterns: &[BashToolPattern],
+    allowed_roots: &[&Path],
+) -> Result<(), BashValidationError> {
+    // G3: NFKC normalization to prevent Unicode homoglyph bypass (e.g. ｒｍ vs rm)
+    let normalized = cmd.nfkc().collect::<String>();
+    let trimmed = normalized.trim();
+
+    // 0. Reject empty commands
+    if trimmed.is_empty() {
+        return Err(BashValidationError::EmptyCommand);
+    }
+    for (ch, label) in REJECTED_CHARS {
+        if trimmed.contains(*ch) {
+            return Err(BashValidationError::ChainOperator(label.to_string()));
+        }
+    }
+
+    // 1. Parse into pipelines of simple commands
+    let list = parser::parse(trimmed)?;
+
+    // 2-4. Validate every simple command independently
+    let roots: Vec<PathBuf> = allowed_roots.iter().map(|r| normalize(r)).collect();
+    validate_list(&list, allowed_patterns, &roots)
+}
+
+fn validate_list(
+    list: &CommandList,
+    patterns: &[BashToolPattern],
+    roots: &[PathBuf],
+) -> Result<(), BashValidationError> {
+    for pipeline in &list.pipelines {
+        for command in &pipeline.commands {
+            validate_simple(command, patterns, roots)?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_simple(
+    command: &SimpleCommand,
+    patterns: &[BashToolPattern],
+    roots: &[PathBuf],
+) -> Result<(), BashValidationError> {
+    let segment = || command.text.chars().take(80).collect::<String>();
+
+    // Substitutions run first in the shell; validate them as commands of their own.
+    let words = command
+        .words
+        .iter()
+        .chain(command.redirects.iter().map(|r| &r.target));
+    for word in words {
+        for inner in &word.substitutions {
+            validate_list(inner, patterns, roots)?;
+        }
+    }
+
+    let Some(first) = command.words.first() else {
+        return Err(BashValidationError::EmptyCommand);
+    };
+    if first.assignment {
+        return Err(BashValidationError::UnsupportedConstruct {
+            construct: format!(
+                "environment assignment `{}`",
+                first.value.split('=').next().unwrap_or_default()
+            ),
+            segment: segment(),
+        });
+    }
+    if first.expands {
+        return Err(BashValidationError::UnsupportedConstruct {
+            construct: format!("expansion in command name `{}`", first.value),
+            segment: segment(),
+        });
+    }
+
+    let basename = first.value.rsplit('/').next().unwrap_or(&first.value);
+    for blocked in BLOCKED_PREFIXES {
+        if first.value == *blocked {
+            return Err(BashValidationError::BlockedPrefix(blocked.to_string()));
+        }
+        // Also block absolute paths to blocked commands (e.g. /bin/rm, /usr/bin/sudo)
+        if basename == *blocked {
+            return Err(BashValidationError::BlockedPrefix(first.value.clone()));
+        }
+    }
+
+    for redirect in &command.redirects {
+        check_redirect(redirect, roots, &segment)?;
+    }
+
+    if !patterns.iter().any(|p| p.matches(&command.words)) {
+        let allowed = patterns
+            .iter()
+            .map(|p| p.raw_pattern.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(BashValidationError::NoMatchingPattern {
+            cmd: segment(),
+            allowed,
+        });
+    }
+    Ok(())
+}
+
+fn check_redirect(
+    redirect: &Redirect,
+    roots: &[PathBuf],
+    segment: &dyn Fn() -> String,
+) -> Result<(), BashValidationError> {
+    if redirect.is_fd_duplication() {
+        return Ok(());
+    }
+    let target = &redirect.target;
+    if target.expands {
+        return Err(BashValidationError::UnsupportedConstruct {
+            construct: format!("expansion in redirection target `{}`", target.value),
+            segment: segment(),
+        });
+    }
+    if SAFE_REDIRECT_TARGETS.contains(&target.value.as_str()) {
+        return Ok(());
+    }
+    let path = Path::new(&target.value);
+    let inside = if path.is_absolute() {
+        let normalized = normalize(path);
+        roots.iter().any(|root| normalized.starts_with(root))
+    } else {
+        !path.components().any(|c| c == Component::ParentDir)
+    };
+    if inside {
+        Ok(())
+    } else {
+        Err(BashValidationError::RedirectOutsideWorkspace {
+            target: target.value.clone(),
+            segment: segment(),
+        })
+    }
+}
+
+/// Lexical normalization (`.` and `..`), without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// One word of a pattern's fixed part.
+enum PatternWord<'a> {
+    /// Bare `*`: exactly one argument.
+    Any,
+    Glob(&'a str),
+    Literal(&'a str),
+}
+
+impl PatternWord<'_> {
+    fn matches(&self, word: &Word) -> bool {
+        // Words the shell may rewrite (globs, `$VAR`, `$(...)`) could split into
+        // several arguments, so they only ever match the trailing wildcard.
+        if word.expands {
+            return false;
+        }
+        match self {
+            PatternWord::Any => true,
+            PatternWord::Glob(glob) => glob_match(glob, &word.value),
+            PatternWord::Literal(lit) => *lit == word.value,
+        }
+    }
+}
+
+impl BashToolPattern {
+    /// Split `raw_pattern` into fixed words and whether further arguments are allowed.
+    fn spec(&self) -> (Vec<PatternWord<'_>>, bool) {
+        let raw = self.raw_pattern.trim();
+        let (head, tail) = match raw.find(':') {
+            Some(idx) => (&raw[..idx], Some(raw[idx + 1..].trim())),
+            None => (raw, None),
+        };
+        let mut words: Vec<&str> = head.split_whitespace().collect();
+        let rest = match tail {
+            Some("*") => true,
+            Some("") => false,
+            Some(more) => {
+                words.extend(more.split_whitespace());
+                pop_trailing_star(&mut words)
+            }
+            None if words.len() <= 1 => true,
+            None => pop_trailing_star(&mut words),
+        };
+        if words.is_empty() {
+            return (vec![PatternWord::Literal(self.command_prefix.trim())], true);
+        }
+        let words = words
+            .into_iter()
+            .map(|w| match w {
+                "*" => PatternWord::Any,
+                w if w.contains('*') => PatternWord::Glob(w),
+                w => PatternWord::Literal(w),
+            })
+            .collect();
+        (words, rest)
+    }
+
+    fn matches(&self, words: &[Word]) -> bool {
+        let (fixed, rest) = self.spec();
+        if words.len() < fixed.len() || (!rest && words.len() != fixed.len()) {
+            return false;
+        }
+        fixed.iter().zip(words).all(|(p, w)| p.matches(w))
+    }
+}
+
+/// Drop a trailing bare `*` (legacy "any further arguments"); true if there was one.
+fn pop_trailing_star(words: &mut Vec<&str>) -> bool {
+    if words.last() == Some(&"*") {
+        words.pop();
+        true
+    } else {
+        false
+    }
+}
+
+/// `*` matches any run of characters within one word.
+fn glob_match(glob: &str, value: &str) -> bool {
+    let mut parts = glob.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests;
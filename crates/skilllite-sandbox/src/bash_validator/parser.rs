@@ -0,0 +1,518 @@
+//! Minimal shell-word parser for the bash validator.
+//!
+//! Understands just enough POSIX shell to split a command line into simple commands:
+//! quoting, `|`, `&&`, `||`, `;`, newlines, comments, `$(...)` and redirections.
+//! Constructs that change what runs in ways patterns cannot reason about (backticks,
+//! process substitution, subshells, groups, heredocs, `${...}`, background jobs) are
+//! reported as [`ParseError::Unsupported`] instead of being guessed at.
+
+/// Maximum nesting of `$(...)` inside `$(...)`.
+const MAX_SUBSTITUTION_DEPTH: usize = 3;
+
+/// Shell keywords that open compound commands; rejected in command position.
+const COMPOUND_KEYWORDS: &[&str] = &[
+    "{", "}", "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case",
+    "esac", "function", "select", "[[", "]]", "coproc",
+];
+
+/// Commands joined by `&&`, `||`, `;` or newlines.
+#[derive(Debug, Clone, Default)]
+pub struct CommandList {
+    pub pipelines: Vec<Pipeline>,
+    /// `connectors[i]` joins `pipelines[i]` and `pipelines[i + 1]`.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub connectors: Vec<Connector>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connector {
+    And,
+    Or,
+    Seq,
+}
+
+/// Commands joined by `|` (or `|&`).
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+    pub commands: Vec<SimpleCommand>,
+}
+
+/// One command with its arguments and redirections.
+#[derive(Debug, Clone, Default)]
+pub struct SimpleCommand {
+    pub words: Vec<Word>,
+    pub redirects: Vec<Redirect>,
+    /// Source text of this command, for error messages.
+    pub text: String,
+}
+
+/// A shell word after quote removal.
+#[derive(Debug, Clone, Default)]
+pub struct Word {
+    /// Unquoted value; `$NAME` and `$(...)` are kept verbatim.
+    pub value: String,
+    /// Contains an unquoted glob, `~`, brace, `$NAME` or `$(...)` — the shell may rewrite it.
+    pub expands: bool,
+    /// `NAME=value` in command position.
+    pub assignment: bool,
+    /// Parsed `$(...)` bodies inside this word.
+    pub substitutions: Vec<CommandList>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    /// Operator without fd prefix: `>`, `>>`, `<`, `<>`, `>|`, `>&`, `<&`, `&>`, `&>>`.
+    pub op: String,
+    pub target: Word,
+}
+
+impl Redirect {
+    /// `2>&1`, `>&-` — duplicates a descriptor instead of opening a path.
+    pub fn is_fd_duplication(&self) -> bool {
+        (self.op == ">&" || self.op == "<&")
+            && !self.target.expands
+            && (self.target.value == "-" || self.target.value.chars().all(|c| c.is_ascii_digit()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Unsupported { construct: String, segment: String },
+    Syntax(String),
+}
+
+/// Parse a full command line.
+pub fn parse(src: &str) -> Result<CommandList, ParseError> {
+    let mut parser = Parser {
+        src,
+        chars: src.char_indices().collect(),
+        pos: 0,
+        depth: 0,
+        cmd_start: 0,
+    };
+    let list = parser.parse_list(false)?;
+    if parser.pos < parser.chars.len() {
+        return Err(parser.unsupported("unexpected `)`"));
+    }
+    Ok(list)
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    chars: Vec<(usize, char)>,
+    pos: usize,
+    depth: usize,
+    /// Char index where the current simple command started.
+    cmd_start: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).map(|&(_, c)| c)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).map(|&(_, c)| c)
+    }
+
+    fn byte_offset(&self, pos: usize) -> usize {
+        self.chars
+            .get(pos)
+            .map(|&(b, _)| b)
+            .unwrap_or(self.src.len())
+    }
+
+    /// Spaces, tabs and `\`-newline line continuations.
+    fn skip_blanks(&mut self) {
+        loop {
+            match (self.peek(), self.peek_at(1)) {
+                (Some(' ' | '\t'), _) => self.pos += 1,
+                (Some('\\'), Some('\n')) => self.pos += 2,
+                _ => break,
+            }
+        }
+    }
+
+    /// Blanks, newlines and comments (allowed after `|`, `&&`, `||`, `;`).
+    fn skip_blank_lines(&mut self) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\n') => self.pos += 1,
+                Some('\\') if self.peek_at(1) == Some('\n') => self.pos += 2,
+                Some('#') => self.skip_comment(),
+                _ => break,
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        while !matches!(self.peek(), None | Some('\n')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Source of the current command from its start to the end of the line.
+    fn segment(&self) -> String {
+        let start = self.byte_offset(self.cmd_start);
+        self.src[start..]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .chars()
+            .take(80)
+            .collect()
+    }
+
+    fn unsupported(&self, construct: &str) -> ParseError {
+        ParseError::Unsupported {
+            construct: construct.to_string(),
+            segment: self.segment(),
+        }
+    }
+
+    fn parse_list(&mut self, in_substitution: bool) -> Result<CommandList, ParseError> {
+        let mut list = CommandList::default();
+        self.skip_blank_lines();
+        if in_substitution && self.peek() == Some(')') {
+            return Err(ParseError::Syntax(
+                "empty command substitution `$()`".into(),
+            ));
+        }
+        loop {
+            list.pipelines.push(self.parse_pipeline()?);
+            self.skip_blanks();
+            if self.peek() == Some('#') {
+                self.skip_comment();
+            }
+            let connector = match (self.peek(), self.peek_at(1)) {
+                (None, _) => {
+                    if in_substitution {
+                        return Err(ParseError::Syntax("unterminated `$(`".into()));
+                    }
+                    break;
+                }
+                (Some(')'), _) => {
+                    if in_substitution {
+                        self.pos += 1;
+                        break;
+                    }
+                    return Err(self.unsupported("unexpected `)`"));
+                }
+                (Some('&'), Some('&')) => {
+                    self.pos += 2;
+                    Connector::And
+                }
+                (Some('|'), Some('|')) => {
+                    self.pos += 2;
+                    Connector::Or
+                }
+                (Some(';'), Some(';')) => return Err(self.unsupported("case terminator `;;`")),
+                (Some(';' | '\n'), _) => {
+                    self.pos += 1;
+                    Connector::Seq
+                }
+                (Some('&'), _) => return Err(self.unsupported("background job `&`")),
+                (Some(c), _) => {
+                    return Err(ParseError::Syntax(format!("unexpected '{}'", c)));
+                }
+            };
+            self.skip_blank_lines();
+            // A trailing `;` or newline ends the list; `&&` / `||` need a right-hand side.
+            let at_end = match self.peek() {
+                None => !in_substitution,
+                Some(')') => in_substitution,
+                _ => false,
+            };
+            if at_end {
+                if connector != Connector::Seq {
+                    return Err(ParseError::Syntax(
+                        "`&&` / `||` without a following command".into(),
+                    ));
+                }
+                if in_substitution {
+                    self.pos += 1;
+                }
+                break;
+            }
+            list.connectors.push(connector);
+        }
+        Ok(list)
+    }
+
+    fn parse_pipeline(&mut self) -> Result<Pipeline, ParseError> {
+        let mut pipeline = Pipeline {
+            commands: vec![self.parse_simple()?],
+        };
+        loop {
+            self.skip_blanks();
+            if self.peek() == Some('|') && self.peek_at(1) != Some('|') {
+                self.pos += if self.peek_at(1) == Some('&') { 2 } else { 1 };
+                self.skip_blank_lines();
+                pipeline.commands.push(self.parse_simple()?);
+            } else {
+                break;
+            }
+        }
+        Ok(pipeline)
+    }
+
+    fn parse_simple(&mut self) -> Result<SimpleCommand, ParseError> {
+        self.skip_blanks();
+        let start = self.pos;
+        self.cmd_start = start;
+        let mut cmd = SimpleCommand::default();
+        loop {
+            self.skip_blanks();
+            let Some(c) = self.peek() else { break };
+            match c {
+                '\n' | ';' | '|' | ')' => break,
+                '&' if self.peek_at(1) == Some('>') => {
+                    let redirect = self.parse_redirect()?;
+                    cmd.redirects.push(redirect);
+                }
+                '&' => break,
+                '#' => {
+                    self.skip_comment();
+                    break;
+                }
+                '(' => return Err(self.unsupported("subshell `( ... )`")),
+                '<' | '>' => {
+                    let redirect = self.parse_redirect()?;
+                    cmd.redirects.push(redirect);
+                }
+                c if c.is_ascii_digit() && self.fd_prefix_len() > 0 => {
+                    self.pos += self.fd_prefix_len();
+                    let redirect = self.parse_redirect()?;
+                    cmd.redirects.push(redirect);
+                }
+                _ => {
+                    let command_position = cmd.words.iter().all(|w| w.assignment);
+                    let word = self.read_word()?;
+                    if command_position && !word.assignment {
+                        if let Some(kw) = COMPOUND_KEYWORDS.iter().find(|kw| **kw == word.value) {
+                            return Err(self.unsupported(&format!("compound command `{}`", kw)));
+                        }
+                    }
+                    cmd.words.push(word);
+                }
+            }
+        }
+        let (from, to) = (self.byte_offset(start), self.byte_offset(self.pos));
+        cmd.text = self.src[from..to].trim().to_string();
+        if cmd.words.is_empty() {
+            return Err(ParseError::Syntax(if cmd.redirects.is_empty() {
+                "empty command (stray `|`, `;`, `&&` or `||`)".to_string()
+            } else {
+                format!("redirection without a command: '{}'", cmd.text)
+            }));
+        }
+        Ok(cmd)
+    }
+
+    /// Length of a `2>`-style fd prefix at the cursor, or 0.
+    fn fd_prefix_len(&self) -> usize {
+        let mut n = 0;
+        while self.peek_at(n).is_some_and(|c| c.is_ascii_digit()) {
+            n += 1;
+        }
+        if n > 0 && matches!(self.peek_at(n), Some('<' | '>')) {
+            n
+        } else {
+            0
+        }
+    }
+
+    fn parse_redirect(&mut self) -> Result<Redirect, ParseError> {
+        let op = match (self.peek(), self.peek_at(1), self.peek_at(2)) {
+            (Some('<'), Some('<'), _) => return Err(self.unsupported("here-document `<<`")),
+            (Some('<' | '>'), Some('('), _) => {
+                return Err(self.unsupported("process substitution `<(...)` / `>(...)`"))
+            }
+            (Some('&'), Some('>'), Some('>')) => "&>>",
+            (Some('&'), Some('>'), _) => "&>",
+            (Some('<'), Some('&'), _) => "<&",
+            (Some('<'), Some('>'), _) => "<>",
+            (Some('<'), _, _) => "<",
+            (Some('>'), Some('>'), _) => ">>",
+            (Some('>'), Some('&'), _) => ">&",
+            (Some('>'), Some('|'), _) => ">|",
+            (Some('>'), _, _) => ">",
+            _ => return Err(ParseError::Syntax("expected redirection".into())),
+        };
+        self.pos += op.chars().count();
+        self.skip_blanks();
+        if matches!(self.peek(), Some('<' | '>')) && self.peek_at(1) == Some('(') {
+            return Err(self.unsupported("process substitution `<(...)` / `>(...)`"));
+        }
+        if matches!(
+            self.peek(),
+            None | Some('\n' | ';' | '|' | '&' | '<' | '>' | '(' | ')')
+        ) {
+            return Err(ParseError::Syntax(format!(
+                "missing target for redirection `{}`",
+                op
+            )));
+        }
+        let target = self.read_word()?;
+        Ok(Redirect {
+            op: op.to_string(),
+            target,
+        })
+    }
+
+    fn read_word(&mut self) -> Result<Word, ParseError> {
+        let mut word = Word::default();
+        let mut quoted = false;
+        let mut first = true;
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\n' | ';' | '&' | '|' | '<' | '>' | '(' | ')' => break,
+                '\\' => {
+                    match self.peek_at(1) {
+                        // Line continuation.
+                        Some('\n') => {}
+                        Some(next) => word.value.push(next),
+                        None => return Err(ParseError::Syntax("trailing backslash".into())),
+                    }
+                    quoted = true;
+                    self.pos += 2;
+                }
+                '\'' => {
+                    self.pos += 1;
+                    loop {
+                        match self.peek() {
+                            Some('\'') => break,
+                            Some(ch) => {
+                                word.value.push(ch);
+                                self.pos += 1;
+                            }
+                            None => return Err(ParseError::Syntax("unterminated `'`".into())),
+                        }
+                    }
+                    self.pos += 1;
+                    quoted = true;
+                }
+                '"' => {
+                    self.pos += 1;
+                    self.read_double_quoted(&mut word)?;
+                    quoted = true;
+                }
+                '`' => return Err(self.unsupported("backtick command substitution")),
+                '$' => self.read_dollar(&mut word)?,
+                '=' if !quoted && !word.assignment && is_name(&word.value) => {
+                    word.assignment = true;
+                    word.value.push('=');
+                    self.pos += 1;
+                }
+                '*' | '?' | '[' | '{' => {
+                    word.expands = true;
+                    word.value.push(c);
+                    self.pos += 1;
+                }
+                '~' if first => {
+                    word.expands = true;
+                    word.value.push(c);
+                    self.pos += 1;
+                }
+                _ => {
+                    word.value.push(c);
+                    self.pos += 1;
+                }
+            }
+            first = false;
+        }
+        Ok(word)
+    }
+
+    fn read_double_quoted(&mut self, word: &mut Word) -> Result<(), ParseError> {
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some('\\') => {
+                    match self.peek_at(1) {
+                        Some('$' | '`' | '"' | '\\') => word.value.push(self.chars[self.pos + 1].1),
+                        Some('\n') => {}
+                        Some(other) => {
+                            word.value.push('\\');
+                            word.value.push(other);
+                        }
+                        None => return Err(ParseError::Syntax("unterminated `\"`".into())),
+                    }
+                    self.pos += 2;
+                }
+                Some('`') => return Err(self.unsupported("backtick command substitution")),
+                Some('$') => self.read_dollar(word)?,
+                Some(ch) => {
+                    word.value.push(ch);
+                    self.pos += 1;
+                }
+                None => return Err(ParseError::Syntax("unterminated `\"`".into())),
+            }
+        }
+    }
+
+    /// `$(...)`, `$NAME`, `$1`, `$?`; rejects `${...}`, `$((...))` and `$'...'`.
+    fn read_dollar(&mut self, word: &mut Word) -> Result<(), ParseError> {
+        match self.peek_at(1) {
+            Some('(') if self.peek_at(2) == Some('(') => {
+                Err(self.unsupported("arithmetic expansion `$((...))`"))
+            }
+            Some('(') => {
+                if self.depth >= MAX_SUBSTITUTION_DEPTH {
+                    return Err(self.unsupported("command substitution nested too deeply"));
+                }
+                let open = self.pos;
+                let outer_start = self.cmd_start;
+                self.pos += 2;
+                self.depth += 1;
+                let inner = self.parse_list(true)?;
+                self.depth -= 1;
+                self.cmd_start = outer_start;
+                let (from, to) = (self.byte_offset(open), self.byte_offset(self.pos));
+                word.value.push_str(&self.src[from..to]);
+                word.substitutions.push(inner);
+                word.expands = true;
+                Ok(())
+            }
+            Some('{') => Err(self.unsupported("parameter expansion `${...}`")),
+            Some('\'') => Err(self.unsupported("ANSI-C quoting `$'...'`")),
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                word.value.push('$');
+                self.pos += 1;
+                while let Some(ch) = self
+                    .peek()
+                    .filter(|ch| ch.is_ascii_alphanumeric() || *ch == '_')
+                {
+                    word.value.push(ch);
+                    self.pos += 1;
+                }
+                word.expands = true;
+                Ok(())
+            }
+            Some(c) if c.is_ascii_digit() || "?#@*!$-".contains(c) => {
+                word.value.push('$');
+                word.value.push(c);
+                self.pos += 2;
+                word.expands = true;
+                Ok(())
+            }
+            _ => {
+                word.value.push('$');
+                self.pos += 1;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
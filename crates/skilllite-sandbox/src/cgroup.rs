@@ -0,0 +1,397 @@
+//! cgroup v2 Resource Enforcement (Linux only)
+//!
+//! Each run gets a transient `skilllite-<pid>-<n>` cgroup with `memory.max`, `pids.max` and
+//! `cpu.max` set from [`ResourceLimits`]. The child joins it before exec, so everything it
+//! forks is limited as one unit and the kernel OOM killer (reported via `memory.events`)
+//! replaces RSS polling of the direct child.
+//!
+//! Run cgroups are only created under a parent delegated to us: `SKILLLITE_SANDBOX_CGROUP_PARENT`,
+//! or our own cgroup when systemd delegated it (`systemd-run --scope -p Delegate=yes`, marked
+//! with the `trusted.delegate` / `user.delegate` xattr). In the latter case the process first
+//! moves itself into a `skilllite-supervisor` leaf, since a cgroup holding processes cannot
+//! enable controllers for its children. We never touch cgroups we do not own (such as the
+//! parent of our own). Without a delegated parent, callers fall back to rlimits + RSS polling
+//! ([`crate::common::wait_with_timeout`]) and a warning is logged once.
+
+#![cfg(target_os = "linux")]
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Once, OnceLock};
+use std::time::Duration;
+
+use skilllite_core::config::env_keys::sandbox as sb_keys;
+
+use crate::common::effective_max_processes;
+use crate::runner::ResourceLimits;
+
+/// Mount point of the unified cgroup v2 hierarchy.
+const CGROUP2_MOUNT: &str = "/sys/fs/cgroup";
+
+/// `cpu.max` period in microseconds.
+const CPU_PERIOD_USEC: u64 = 100_000;
+
+/// Controllers we try to enable for child cgroups; only `memory` is required.
+const CONTROLLERS: &[&str] = &["memory", "pids", "cpu"];
+
+/// Leaf our own process moves into when our delegated cgroup becomes the run parent.
+const SUPERVISOR_LEAF: &str = "skilllite-supervisor";
+
+/// xattrs systemd sets on cgroups it delegated.
+const DELEGATE_XATTRS: &[&str] = &["trusted.delegate", "user.delegate"];
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A transient per-run cgroup. Dropping it kills any remaining members and removes it.
+#[derive(Debug)]
+pub struct Cgroup {
+    dir: PathBuf,
+    /// `cgroup.procs`, opened before fork so the child can join with a single `write(2)`.
+    procs: File,
+}
+
+impl Cgroup {
+    /// Create a cgroup limited by `limits`, or `None` when cgroup v2 is unavailable, not
+    /// writable, or disabled with `SKILLLITE_SANDBOX_CGROUP=0`.
+    pub fn create(limits: &ResourceLimits) -> Option<Self> {
+        if !cgroup_enabled() {
+            return None;
+        }
+        match Self::try_create(limits) {
+            Ok(cgroup) => Some(cgroup),
+            Err(e) => {
+                static WARNED: Once = Once::new();
+                WARNED.call_once(|| {
+                    tracing::warn!(
+                        "cgroup v2 limits not enforced, falling back to rlimit polling: {}",
+                        e
+                    );
+                });
+                None
+            }
+        }
+    }
+
+    fn try_create(limits: &ResourceLimits) -> io::Result<Self> {
+        let parent = parent_dir(Path::new(CGROUP2_MOUNT))?;
+        enable_controllers(&parent)?;
+
+        let dir = parent.join(format!(
+            "skilllite-{}-{}",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir(&dir)?;
+        let opened = write_limits(&dir, limits).and_then(|()| {
+            OpenOptions::new()
+                .write(true)
+                .open(dir.join("cgroup.procs"))
+        });
+        match opened {
+            Ok(procs) => Ok(Self { dir, procs }),
+            Err(e) => {
+                let _ = fs::remove_dir(&dir);
+                Err(e)
+            }
+        }
+    }
+
+    /// Move the spawned child into this cgroup before exec. A failed move leaves the child
+    /// outside; [`Cgroup::contains`] tells the caller whether to fall back to polling.
+    pub fn attach_on_exec(&self, cmd: &mut Command) {
+        let fd = self.procs.as_raw_fd();
+        unsafe {
+            cmd.pre_exec(move || {
+                // "0" means the writing process. Only async-signal-safe calls after fork.
+                libc::write(fd, b"0".as_ptr().cast(), 1);
+                Ok(())
+            });
+        }
+    }
+
+    /// Whether `pid` is a member of this cgroup.
+    pub fn contains(&self, pid: u32) -> bool {
+        fs::read_to_string(self.dir.join("cgroup.procs"))
+            .map(|procs| procs.lines().any(|l| l.trim() == pid.to_string()))
+            .unwrap_or(false)
+    }
+
+    /// Number of processes the kernel OOM killer has killed in this cgroup.
+    pub fn oom_kills(&self) -> u64 {
+        fs::read_to_string(self.dir.join("memory.events"))
+            .map(|events| parse_oom_kills(&events))
+            .unwrap_or(0)
+    }
+
+    /// SIGKILL every remaining member (`cgroup.kill`, or one by one on kernels before 5.14).
+    pub fn kill_all(&self) {
+        if fs::write(self.dir.join("cgroup.kill"), "1").is_ok() {
+            return;
+        }
+        if let Ok(procs) = fs::read_to_string(self.dir.join("cgroup.procs")) {
+            for pid in procs.lines().filter_map(|l| l.trim().parse::<i32>().ok()) {
+                let _ = nix::sys::signal::kill(
+                    nix::unistd::Pid::from_raw(pid),
+                    nix::sys::signal::Signal::SIGKILL,
+                );
+            }
+        }
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        self.kill_all();
+        // rmdir fails with EBUSY until the killed members are reaped.
+        for _ in 0..50 {
+            if fs::remove_dir(&self.dir).is_ok() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        tracing::debug!("Failed to remove cgroup {}", self.dir.display());
+    }
+}
+
+fn cgroup_enabled() -> bool {
+    !matches!(
+        std::env::var(sb_keys::SKILLLITE_SANDBOX_CGROUP).as_deref(),
+        Ok("0") | Ok("false") | Ok("off")
+    )
+}
+
+/// Directory new cgroups are created in: `SKILLLITE_SANDBOX_CGROUP_PARENT` (relative to the
+/// mount), else our own cgroup if systemd delegated it to us. Anything else is refused.
+fn parent_dir(mount: &Path) -> io::Result<PathBuf> {
+    if !mount.join("cgroup.controllers").exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no cgroup v2 hierarchy mounted",
+        ));
+    }
+    if let Ok(parent) = std::env::var(sb_keys::SKILLLITE_SANDBOX_CGROUP_PARENT) {
+        if !parent.trim().is_empty() {
+            return Ok(mount.join(parent.trim().trim_start_matches('/')));
+        }
+    }
+    let self_cgroup = fs::read_to_string("/proc/self/cgroup")?;
+    let own = own_cgroup(&self_cgroup).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::Unsupported, "process is not in a cgroup v2")
+    })?;
+    let mut own = mount.join(own.trim_start_matches('/'));
+    // Already moved into our supervisor leaf by an earlier run.
+    if own.file_name().is_some_and(|n| n == SUPERVISOR_LEAF) {
+        own.pop();
+    }
+    if !is_delegated(&own) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is not delegated; set {} or run under `systemd-run --scope -p Delegate=yes`",
+                own.display(),
+                sb_keys::SKILLLITE_SANDBOX_CGROUP_PARENT
+            ),
+        ));
+    }
+    static VACATED: OnceLock<Result<(), String>> = OnceLock::new();
+    VACATED
+        .get_or_init(|| vacate(&own).map_err(|e| e.to_string()))
+        .clone()
+        .map_err(io::Error::other)?;
+    Ok(own)
+}
+
+/// Whether systemd marked `dir` as delegated to its owner.
+fn is_delegated(dir: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(path) = CString::new(dir.as_os_str().as_bytes()) else {
+        return false;
+    };
+    DELEGATE_XATTRS.iter().any(|name| {
+        let Ok(name) = CString::new(*name) else {
+            return false;
+        };
+        let mut value = [0u8; 8];
+        // SAFETY: both strings are NUL-terminated and `value` outlives the call.
+        let len = unsafe {
+            libc::getxattr(
+                path.as_ptr(),
+                name.as_ptr(),
+                value.as_mut_ptr().cast(),
+                value.len(),
+            )
+        };
+        len > 0 && &value[..len as usize] == b"1"
+    })
+}
+
+/// Move this process into `<own>/skilllite-supervisor` so `own` holds no processes and may
+/// enable controllers for the run cgroups next to the leaf.
+fn vacate(own: &Path) -> io::Result<()> {
+    let leaf = own.join(SUPERVISOR_LEAF);
+    match fs::create_dir(&leaf) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(e),
+    }
+    fs::write(leaf.join("cgroup.procs"), std::process::id().to_string())
+}
+
+/// Enable [`CONTROLLERS`] for children of `parent`; fails unless `memory` ends up enabled.
+fn enable_controllers(parent: &Path) -> io::Result<()> {
+    let available = fs::read_to_string(parent.join("cgroup.controllers"))?;
+    let subtree = parent.join("cgroup.subtree_control");
+    let enabled = fs::read_to_string(&subtree)?;
+    for controller in CONTROLLERS {
+        if has_word(&available, controller) && !has_word(&enabled, controller) {
+            let _ = fs::write(&subtree, format!("+{}", controller));
+        }
+    }
+    if has_word(&fs::read_to_string(&subtree)?, "memory") {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("memory controller not delegated to {}", parent.display()),
+        ))
+    }
+}
+
+fn write_limits(dir: &Path, limits: &ResourceLimits) -> io::Result<()> {
+    fs::write(
+        dir.join("memory.max"),
+        limits.max_memory_bytes().to_string(),
+    )?;
+    // Best-effort: absent without swap accounting; the group OOM kill takes the whole run.
+    let _ = fs::write(dir.join("memory.swap.max"), "0");
+    let _ = fs::write(dir.join("memory.oom.group"), "1");
+    if dir.join("pids.max").exists() {
+        fs::write(dir.join("pids.max"), effective_max_processes().to_string())?;
+    }
+    if let Some(percent) = limits.max_cpu_percent.filter(|p| *p > 0) {
+        if dir.join("cpu.max").exists() {
+            fs::write(dir.join("cpu.max"), cpu_max(percent))?;
+        }
+    }
+    Ok(())
+}
+
+/// `cpu.max` value for `percent` of one core.
+fn cpu_max(percent: u32) -> String {
+    let quota = (CPU_PERIOD_USEC * u64::from(percent) / 100).max(1000);
+    format!("{} {}", quota, CPU_PERIOD_USEC)
+}
+
+/// Our cgroup v2 path from `/proc/self/cgroup` (the `0::<path>` line).
+fn own_cgroup(proc_self_cgroup: &str) -> Option<&str> {
+    proc_self_cgroup
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(str::trim)
+        .filter(|path| path.starts_with('/'))
+}
+
+/// `oom_kill` counter from `memory.events`.
+fn parse_oom_kills(events: &str) -> u64 {
+    events
+        .lines()
+        .find_map(|line| line.strip_prefix("oom_kill "))
+        .and_then(|n| n.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn has_word(list: &str, word: &str) -> bool {
+    list.split_whitespace().any(|w| w == word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{pipe_stdio, spawn_write_and_wait};
+    use crate::runner::LimitEnforcement;
+
+    #[test]
+    fn parses_own_cgroup_and_oom_kills() {
+        let hybrid = "4:memory:/user.slice\n1:name=systemd:/user.slice\n0::/user.slice/app.scope\n";
+        assert_eq!(own_cgroup(hybrid), Some("/user.slice/app.scope"));
+        assert_eq!(own_cgroup("4:memory:/user.slice\n"), None);
+
+        let events = "low 0\nhigh 0\nmax 12\noom 3\noom_kill 2\noom_group_kill 1\n";
+        assert_eq!(parse_oom_kills(events), 2);
+        assert_eq!(parse_oom_kills(""), 0);
+    }
+
+    #[test]
+    fn cpu_max_scales_quota_to_period() {
+        assert_eq!(cpu_max(50), "50000 100000");
+        assert_eq!(cpu_max(200), "200000 100000");
+        assert_eq!(cpu_max(0), "1000 100000");
+    }
+
+    #[test]
+    fn parent_dir_requires_a_cgroup2_mount() {
+        let tmp = tempfile::tempdir().unwrap();
+        let err = parent_dir(tmp.path()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn undelegated_own_cgroup_is_not_used_as_parent() {
+        if std::env::var_os(sb_keys::SKILLLITE_SANDBOX_CGROUP_PARENT).is_some()
+            || fs::read_to_string("/proc/self/cgroup")
+                .ok()
+                .and_then(|c| own_cgroup(&c).map(str::to_string))
+                .is_none()
+        {
+            eprintln!("skipping: explicit parent set or not in a cgroup v2");
+            return;
+        }
+        // A fake mount: our cgroup path under it exists but carries no delegate xattr.
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("cgroup.controllers"), "memory pids cpu").unwrap();
+        let err = parent_dir(tmp.path()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains("Delegate=yes"), "{}", err);
+    }
+
+    /// A parent and its forked child each stay under the limit but together exceed it:
+    /// only per-cgroup accounting catches that.
+    #[test]
+    fn forked_memory_hog_is_oom_killed_as_a_unit() {
+        let limits = ResourceLimits {
+            max_memory_mb: 64,
+            timeout_secs: 20,
+            max_cpu_percent: None,
+        };
+        if Cgroup::create(&limits).is_none() || which::which("python3").is_err() {
+            eprintln!("skipping: cgroup v2 not writable or python3 missing");
+            return;
+        }
+        let hog = r#"
+import os, time
+block = b"x" * (40 * 1024 * 1024)
+if os.fork() == 0:
+    child = b"y" * (40 * 1024 * 1024)
+    time.sleep(5)
+    os._exit(0)
+os.wait()
+print("{}")
+"#;
+        let mut cmd = Command::new("python3");
+        cmd.args(["-c", hog]);
+        pipe_stdio(&mut cmd);
+        let (result, was_killed, kill_reason) =
+            spawn_write_and_wait(&mut cmd, "{}", &limits, false, "spawn memory hog").unwrap();
+        assert_eq!(result.enforcement, LimitEnforcement::CgroupV2);
+        assert!(was_killed, "stderr: {}", result.stderr);
+        assert_eq!(kill_reason.as_deref(), Some("oom_kill"));
+        assert!(result.stderr.contains(crate::error::KILLED_OOM_PREFIX));
+    }
+}
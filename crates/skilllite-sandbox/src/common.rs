@@ -13,7 +13,7 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::network_proxy::{ProxyConfig, ProxyManager};
-use crate::runner::{ExecutionResult, ResourceLimits};
+use crate::runner::{ExecutionResult, LimitEnforcement, ResourceLimits};
 use crate::security::policy::{self as security_policy, ResolvedNetworkPolicy};
 
 // ============================================================
@@ -180,6 +180,20 @@ pub fn wait_with_timeout(
     timeout_secs: u64,
     memory_limit_bytes: u64,
     stream_stderr: bool,
+) -> Result<(String, String, i32, bool, Option<String>)> {
+    wait_with_memory_enforcer(child, timeout_secs, memory_limit_bytes, stream_stderr, None)
+}
+
+/// [`wait_with_timeout`] for a child whose memory is already capped by the kernel (cgroup v2).
+///
+/// With `oom_killed` set, RSS polling and the post-exit peak check are skipped; after exit the
+/// callback reports whether the OOM killer fired, which yields kill_reason `oom_kill`.
+fn wait_with_memory_enforcer(
+    child: &mut Child,
+    timeout_secs: u64,
+    memory_limit_bytes: u64,
+    stream_stderr: bool,
+    oom_killed: Option<&dyn Fn() -> bool>,
 ) -> Result<(String, String, i32, bool, Option<String>)> {
     let start = Instant::now();
     let timeout = Duration::from_secs(timeout_secs);
@@ -230,6 +244,23 @@ pub fn wait_with_timeout(
                     .map(|h| h.join().unwrap_or_default())
                     .unwrap_or_default();
 
+                if let Some(oom_killed) = oom_killed {
+                    if oom_killed() {
+                        return Ok((
+                            String::new(),
+                            format!(
+                                "{} (limit {} MB)",
+                                crate::error::KILLED_OOM_PREFIX,
+                                memory_limit_bytes / (1024 * 1024)
+                            ),
+                            -1,
+                            true,
+                            Some("oom_kill".to_string()),
+                        ));
+                    }
+                    return Ok((stdout, stderr, status.code().unwrap_or(-1), false, None));
+                }
+
                 // Post-exit memory check via getrusage(RUSAGE_CHILDREN).
                 // On macOS RLIMIT_AS is not enforced by the kernel, so a
                 // fast-allocating script can finish before the RSS polling
@@ -270,7 +301,8 @@ pub fn wait_with_timeout(
             return Ok((
                 String::new(),
                 format!(
-                    "Process killed: exceeded timeout of {} seconds",
+                    "{} of {} seconds",
+                    crate::error::KILLED_TIMEOUT_PREFIX,
                     timeout_secs
                 ),
                 -1,
@@ -279,7 +311,7 @@ pub fn wait_with_timeout(
             ));
         }
 
-        if let Some(memory) = get_process_memory(child.id()) {
+        if let Some(memory) = get_process_memory(child.id()).filter(|_| oom_killed.is_none()) {
             if memory > memory_limit_bytes {
                 let _ = child.kill();
                 let _ = child.wait();
@@ -290,8 +322,10 @@ pub fn wait_with_timeout(
                 return Ok((
                     String::new(),
                     format!(
-                        "Process killed: memory usage ({} MB) exceeded limit ({} MB)",
-                        memory_mb, limit_mb
+                        "{} ({} MB) exceeded limit ({} MB)",
+                        crate::error::KILLED_MEMORY_PREFIX,
+                        memory_mb,
+                        limit_mb
                     ),
                     -1,
                     true,
@@ -485,11 +519,13 @@ pub unsafe fn set_rlimits_pre_exec(cmd: &mut Command, limits: &ResourceLimits) {
 /// Returns `None` if no proxy is needed or if proxy creation/start fails.
 pub fn start_network_proxy(network_policy: &ResolvedNetworkPolicy) -> Option<ProxyManager> {
     if security_policy::should_use_proxy(network_policy) {
-        let domains = match network_policy {
-            ResolvedNetworkPolicy::ProxyFiltered { domains } => domains.clone(),
-            _ => vec![],
+        let proxy_config = match network_policy {
+            ResolvedNetworkPolicy::ProxyFiltered { domains, denied } => ProxyConfig {
+                denied_domains: denied.clone(),
+                ..ProxyConfig::with_allowed_domains(domains.clone())
+            },
+            _ => ProxyConfig::with_allowed_domains(vec![]),
         };
-        let proxy_config = ProxyConfig::with_allowed_domains(domains);
         match ProxyManager::new(proxy_config) {
             Ok(mut manager) => {
                 if let Err(e) = manager.start() {
@@ -526,6 +562,9 @@ pub fn start_network_proxy(network_policy: &ResolvedNetworkPolicy) -> Option<Pro
 /// The command must have `stdin(Stdio::piped())`, `stdout(Stdio::piped())`,
 /// `stderr(Stdio::piped())` already set.
 ///
+/// On Linux the child runs in a transient [`crate::cgroup::Cgroup`] when cgroup v2 is
+/// writable; otherwise memory is enforced by rlimits and RSS polling.
+///
 /// Returns `(ExecutionResult, was_killed, kill_reason)`.
 pub fn spawn_write_and_wait(
     cmd: &mut Command,
@@ -534,19 +573,41 @@ pub fn spawn_write_and_wait(
     stream_stderr: bool,
     spawn_context: &str,
 ) -> Result<(ExecutionResult, bool, Option<String>)> {
+    #[cfg(target_os = "linux")]
+    let cgroup = crate::cgroup::Cgroup::create(limits);
+    #[cfg(target_os = "linux")]
+    if let Some(cgroup) = &cgroup {
+        cgroup.attach_on_exec(cmd);
+    }
+
     let mut child = cmd.spawn().with_context(|| spawn_context.to_string())?;
 
+    #[cfg(target_os = "linux")]
+    let cgroup = cgroup.filter(|cgroup| cgroup.contains(child.id()));
+    #[cfg(target_os = "linux")]
+    let oom_check = cgroup.as_ref().map(|cgroup| move || cgroup.oom_kills() > 0);
+    #[cfg(target_os = "linux")]
+    let oom_killed = oom_check.as_ref().map(|f| f as &dyn Fn() -> bool);
+    #[cfg(not(target_os = "linux"))]
+    let oom_killed: Option<&dyn Fn() -> bool> = None;
+
     if let Some(mut stdin) = child.stdin.take() {
         stdin
             .write_all(input_json.as_bytes())
             .with_context(|| "Failed to write to stdin")?;
     }
 
-    let (stdout, stderr, exit_code, was_killed, kill_reason) = wait_with_timeout(
+    let enforcement = if oom_killed.is_some() {
+        LimitEnforcement::CgroupV2
+    } else {
+        LimitEnforcement::RlimitPolling
+    };
+    let (stdout, stderr, exit_code, was_killed, kill_reason) = wait_with_memory_enforcer(
         &mut child,
         limits.timeout_secs,
         limits.max_memory_bytes(),
         stream_stderr,
+        oom_killed,
     )?;
 
     Ok((
@@ -554,6 +615,9 @@ pub fn spawn_write_and_wait(
             stdout,
             stderr,
             exit_code,
+            enforcement,
+            syscall_violations: Vec::new(),
+            seccomp_profile: None,
         },
         was_killed,
         kill_reason,
@@ -582,7 +646,29 @@ pub fn hide_child_console(_cmd: &mut Command) {}
 // Script Arguments Helper
 // ============================================================
 
+/// Script arguments for one execution: [`SandboxConfig::script_args`], or — deprecated — the
+/// process-wide SKILLLITE_SCRIPT_ARGS / SKILLBOX_SCRIPT_ARGS when the config carries none.
+///
+/// [`SandboxConfig::script_args`]: crate::runner::SandboxConfig::script_args
+pub fn resolve_script_args(config: &crate::runner::SandboxConfig) -> Vec<String> {
+    if !config.script_args.is_empty() {
+        return config.script_args.clone();
+    }
+    let from_env = get_script_args_from_env();
+    if !from_env.is_empty() {
+        static WARN_ONCE: std::sync::Once = std::sync::Once::new();
+        WARN_ONCE.call_once(|| {
+            tracing::warn!(
+                "SKILLLITE_SCRIPT_ARGS is deprecated and will be removed in the next release; \
+                 pass script args via SandboxConfig::script_args (`skilllite exec --args`) instead"
+            );
+        });
+    }
+    from_env
+}
+
 /// Read script arguments from environment config (SKILLLITE_SCRIPT_ARGS / SKILLBOX_SCRIPT_ARGS).
+/// Deprecated fallback only; see [`resolve_script_args`].
 pub fn get_script_args_from_env() -> Vec<String> {
     if let Some(ref script_args) = skilllite_core::config::SandboxEnvConfig::from_env().script_args
     {
@@ -609,6 +695,30 @@ pub fn execute_unsandboxed(
     config: &crate::runner::SandboxConfig,
     input_json: &str,
     limits: ResourceLimits,
+) -> Result<ExecutionResult> {
+    let temp_dir = tempfile::TempDir::new()?;
+    execute_unsandboxed_with(
+        skill_dir,
+        runtime,
+        config,
+        input_json,
+        limits,
+        temp_dir.path(),
+        &[],
+    )
+}
+
+/// [`execute_unsandboxed`] with a caller-owned `work_dir` and an optional `prefix` command that
+/// wraps the interpreter (e.g. `strace -f -o <log>` for [`crate::sandbox_audit`]).
+#[cfg(unix)]
+pub(crate) fn execute_unsandboxed_with(
+    skill_dir: &Path,
+    runtime: &crate::runner::RuntimePaths,
+    config: &crate::runner::SandboxConfig,
+    input_json: &str,
+    limits: ResourceLimits,
+    work_dir: &Path,
+    prefix: &[std::ffi::OsString],
 ) -> Result<ExecutionResult> {
     use crate::runtime_resolver::RuntimeResolver;
 
@@ -616,22 +726,25 @@ pub fn execute_unsandboxed(
         crate::Error::validation(format!("Unsupported language: {}", config.language))
     })?;
 
-    let temp_dir = tempfile::TempDir::new()?;
-    let work_dir = temp_dir.path();
-
-    let mut cmd = Command::new(&resolved.interpreter);
+    let mut cmd = match prefix.split_first() {
+        Some((program, args)) => {
+            let mut cmd = Command::new(program);
+            cmd.args(args).arg(&resolved.interpreter);
+            cmd
+        }
+        None => Command::new(&resolved.interpreter),
+    };
     cmd.arg(&config.entry_point);
     for (k, v) in &resolved.extra_env {
         cmd.env(k, v);
     }
-    for arg in get_script_args_from_env() {
-        cmd.arg(arg);
-    }
+    cmd.args(resolve_script_args(config));
 
     cmd.current_dir(skill_dir);
     pipe_stdio(&mut cmd);
 
     apply_standard_execution_env(&mut cmd, false, work_dir, config.network_enabled, false);
+    apply_results_dir_env(&mut cmd, config);
 
     unsafe { set_rlimits_pre_exec(&mut cmd, &limits) };
 
@@ -683,6 +796,18 @@ pub fn apply_standard_execution_env(
     }
 }
 
+/// Export [`SandboxConfig::results_dir`] as `SKILLLITE_RESULTS_DIR` (see [`crate::results`]).
+///
+/// [`SandboxConfig::results_dir`]: crate::runner::SandboxConfig::results_dir
+pub fn apply_results_dir_env(cmd: &mut Command, config: &crate::runner::SandboxConfig) {
+    if let Some(ref dir) = config.results_dir {
+        cmd.env(
+            skilllite_core::config::env_keys::paths::SKILLLITE_RESULTS_DIR,
+            dir,
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
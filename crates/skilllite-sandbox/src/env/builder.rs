@@ -11,11 +11,17 @@ use crate::error::bail;
 use crate::Result;
 use skilllite_core::config;
 use skilllite_core::EnvSpec;
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::common::hide_child_console;
+use crate::env::interpreters::{self, RuntimeChoice};
+use crate::env::lockfile::{self, SkillLock, SKILL_LOCK_FILE};
 use crate::env::runtime_deps::{self, RuntimeConfirmDownloadFn, RuntimeProgressFn};
+use crate::env::shared_cache;
 use crate::runner::RuntimePaths;
 
 /// Return the cache directory for skill environments.
@@ -31,12 +37,32 @@ pub fn get_cache_dir(override_dir: Option<&str>) -> Option<PathBuf> {
     Some(base.join("envs"))
 }
 
+/// How long to wait for another process building the same shared environment.
+const ENV_BUILD_LOCK_TIMEOUT: Duration = Duration::from_secs(20 * 60);
+/// Default for `SKILLLITE_ENV_SETUP_TIMEOUT_SECS`.
+const DEFAULT_ENV_SETUP_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+const STEP_POLL: Duration = Duration::from_millis(100);
+
 /// Ensure an isolated environment exists for the skill (venv or node_modules).
 /// Returns the environment directory path (empty PathBuf if no env needed, e.g. bash-only).
-/// P0: If system Python/Node is missing or too old, progress callback reports reason and
-/// provisioning progress (pass None to skip). Desktop can pass e.g.
-/// `Some(Box::new(|msg| { /* show in UI */ }))` for transparent UX.
+/// The progress callback receives setup phases ("Creating Python virtual environment",
+/// "Installing 14 Python package(s)", pip's per-package lines) and, when system Python/Node
+/// is missing or too old, runtime provisioning progress (pass None to skip). Desktop can pass
+/// e.g. `Some(Box::new(|msg| { /* show in UI */ }))` for transparent UX.
 /// Pass `confirm_download` to ask user before downloading a runtime; if it returns false, provisioning is aborted.
+///
+/// Environments are shared between skills with the same dependency set and runtime
+/// version (see [`super::shared_cache`]). A legacy per-skill env from an earlier
+/// version is reused as-is when present, unless the skill declares a runtime version
+/// constraint (see [`super::interpreters`]), which always resolves a matching interpreter.
+///
+/// Dependencies are pinned by `skill.lock` (see [`super::lockfile`]): resolved and written on
+/// first use, then installed strictly from it. A lock that conflicts with the declared
+/// dependencies or fails its checksum is an error.
+///
+/// The whole setup phase is bounded by `SKILLLITE_ENV_SETUP_TIMEOUT_SECS`, independent of the
+/// skill execution timeout. A build interrupted before its completion marker was written is
+/// wiped and rebuilt; a build lock left by a process that no longer runs is taken over.
 pub fn ensure_environment(
     skill_dir: &Path,
     spec: &EnvSpec,
@@ -54,32 +80,423 @@ pub fn ensure_environment(
     });
     std::fs::create_dir_all(&base).context("Create cache dir")?;
 
-    let key = cache_key(skill_dir, spec, lang)?;
-    let env_path = base.join(key);
+    if lang != "python" && lang != "node" {
+        return Ok(PathBuf::new());
+    }
+    let choice = interpreters::resolve_runtime(spec, cache_dir)?;
+    let mut ctx = BuildContext::new(cache_dir, &choice, progress, confirm_download);
+    let lock = prepare_lock(skill_dir, spec, &mut ctx)?;
+    let build = |env_path: &Path, ctx: &mut BuildContext| {
+        if lang == "python" {
+            ensure_python_env(skill_dir, spec, env_path, lock.as_ref(), ctx)
+        } else {
+            ensure_node_env(skill_dir, spec, env_path, lock.as_ref(), ctx)
+        }
+    };
 
-    if lang == "python" {
-        ensure_python_env(
-            skill_dir,
-            spec,
-            &env_path,
+    let legacy_path = base.join(cache_key(skill_dir, spec, lang)?);
+    if choice == RuntimeChoice::Default && lock.is_none() && legacy_env_ready(&legacy_path, lang) {
+        build(&legacy_path, &mut ctx)?;
+        return Ok(legacy_path);
+    }
+
+    let runtime_key = runtime_tag(lang, &choice);
+    let hash = shared_cache::dependency_hash(skill_dir, spec, &runtime_key);
+    let env_path = base.join(&hash);
+
+    let _lock = if shared_cache::is_ready(&env_path) {
+        None
+    } else {
+        Some(shared_cache::EnvBuildLock::acquire(
+            &base,
+            &hash,
+            ctx.remaining().min(ENV_BUILD_LOCK_TIMEOUT),
+            || ctx.report("Waiting for another process to finish building this environment"),
+        )?)
+    };
+    let fresh = !shared_cache::is_ready(&env_path);
+    if fresh && env_path.exists() {
+        ctx.report("Removing an incomplete environment left by an interrupted build");
+        std::fs::remove_dir_all(&env_path).context("Remove incomplete environment")?;
+    }
+    build(&env_path, &mut ctx)?;
+    if fresh {
+        ctx.report("Environment ready");
+        let interpreter = match &choice {
+            RuntimeChoice::Selected(i) => Some(i.path.as_path()),
+            _ => None,
+        };
+        shared_cache::write_marker(&env_path, lang, &runtime_key, interpreter)?;
+    }
+    shared_cache::record_reference(&base, skill_dir, lang, &hash);
+
+    Ok(env_path)
+}
+
+/// The legacy per-skill env of `skill_dir`, if one exists (call while the skill still exists:
+/// its key covers the canonical skill path and dependency files).
+pub fn legacy_env_dir(
+    skill_dir: &Path,
+    spec: &EnvSpec,
+    cache_dir: Option<&str>,
+) -> Option<PathBuf> {
+    let base = get_cache_dir(cache_dir)?;
+    let path = base.join(cache_key(skill_dir, spec, &spec.language).ok()?);
+    (path.is_dir() && !shared_cache::is_ready(&path)).then_some(path)
+}
+
+/// Resolve (and write) `skill.lock` from scratch, replacing any existing lock.
+/// Backs `skilllite verify --deps --relock`; the next [`ensure_environment`] builds from it.
+pub fn relock(
+    skill_dir: &Path,
+    spec: &EnvSpec,
+    cache_dir: Option<&str>,
+    progress: RuntimeProgressFn,
+    confirm_download: RuntimeConfirmDownloadFn,
+) -> Result<Option<SkillLock>> {
+    let declared = lockfile::declared_dependencies(skill_dir, spec)?;
+    if declared.is_empty() {
+        let path = lockfile::lock_path(skill_dir);
+        if path.exists() {
+            std::fs::remove_file(&path).context("Remove skill.lock")?;
+        }
+        return Ok(None);
+    }
+    let choice = interpreters::resolve_runtime(spec, cache_dir)?;
+    let mut ctx = BuildContext::new(cache_dir, &choice, progress, confirm_download);
+    let lock = resolve_lock(skill_dir, spec, declared, &mut ctx)?;
+    lockfile::write(skill_dir, &lock)?;
+    Ok(Some(lock))
+}
+
+type SharedProgress = Arc<Mutex<Box<dyn Fn(&str) + Send>>>;
+
+/// State shared by one environment setup: interpreters resolved at most once, the progress
+/// callback (also handed to runtime provisioning) and the setup deadline.
+struct BuildContext<'a> {
+    cache_dir: Option<&'a str>,
+    choice: &'a RuntimeChoice,
+    progress: Option<SharedProgress>,
+    confirm_download: RuntimeConfirmDownloadFn,
+    python: Option<PythonCommand>,
+    node: Option<(PathBuf, PathBuf)>,
+    timeout: Duration,
+    deadline: Instant,
+}
+
+impl<'a> BuildContext<'a> {
+    fn new(
+        cache_dir: Option<&'a str>,
+        choice: &'a RuntimeChoice,
+        progress: RuntimeProgressFn,
+        confirm_download: RuntimeConfirmDownloadFn,
+    ) -> Self {
+        let timeout = env_setup_timeout();
+        Self {
             cache_dir,
-            progress,
+            choice,
+            progress: progress.map(|f| Arc::new(Mutex::new(f))),
             confirm_download,
+            python: None,
+            node: None,
+            timeout,
+            deadline: Instant::now() + timeout,
+        }
+    }
+
+    fn report(&self, message: &str) {
+        if let Some(progress) = &self.progress {
+            if let Ok(f) = progress.lock() {
+                f(message);
+            }
+        }
+    }
+
+    /// A [`RuntimeProgressFn`] forwarding to this build's callback.
+    fn runtime_progress(&self) -> RuntimeProgressFn {
+        let progress = self.progress.clone()?;
+        Some(Box::new(move |message: &str| {
+            if let Ok(f) = progress.lock() {
+                f(message);
+            }
+        }))
+    }
+
+    fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    fn python(&mut self) -> Result<PythonCommand> {
+        if let Some(python) = &self.python {
+            return Ok(python.clone());
+        }
+        let python = resolve_python(
+            self.cache_dir,
+            self.choice,
+            self.runtime_progress(),
+            self.confirm_download.take(),
         )?;
-    } else if lang == "node" {
-        ensure_node_env(
-            skill_dir,
-            spec,
-            &env_path,
-            cache_dir,
-            progress,
-            confirm_download,
+        self.python = Some(python.clone());
+        Ok(python)
+    }
+
+    /// `(node, npm)`
+    fn node(&mut self) -> Result<(PathBuf, PathBuf)> {
+        if let Some(node) = &self.node {
+            return Ok(node.clone());
+        }
+        let node = resolve_node(
+            self.cache_dir,
+            self.choice,
+            self.runtime_progress(),
+            self.confirm_download.take(),
         )?;
+        self.node = Some(node.clone());
+        Ok(node)
+    }
+
+    /// Run one setup command within the setup deadline, forwarding the stdout lines that
+    /// `progress_line` picks out as progress. On timeout the command is killed.
+    fn run(
+        &self,
+        cmd: &mut Command,
+        step: &str,
+        progress_line: fn(&str) -> Option<String>,
+    ) -> Result<Output> {
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = cmd.spawn().with_context(|| step.to_string())?;
+        let (tx, rx) = mpsc::channel::<String>();
+        let stdout = child.stdout.take();
+        let stdout_reader = std::thread::spawn(move || {
+            let mut all = Vec::new();
+            for line in stdout
+                .map(|s| BufReader::new(s).split(b'\n'))
+                .into_iter()
+                .flatten()
+                .map_while(std::io::Result::ok)
+            {
+                if let Some(message) = progress_line(&String::from_utf8_lossy(&line)) {
+                    let _ = tx.send(message);
+                }
+                all.extend_from_slice(&line);
+                all.push(b'\n');
+            }
+            all
+        });
+        let stderr = child.stderr.take();
+        let stderr_reader = std::thread::spawn(move || {
+            let mut all = Vec::new();
+            if let Some(mut s) = stderr {
+                let _ = s.read_to_end(&mut all);
+            }
+            all
+        });
+        let status = loop {
+            for message in rx.try_iter() {
+                self.report(&message);
+            }
+            if let Some(status) = child.try_wait().with_context(|| step.to_string())? {
+                break status;
+            }
+            if Instant::now() >= self.deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                // Readers are left detached: grandchildren may still hold the pipes
+                bail!(
+                    "{} timed out: environment setup exceeded {}s (set {} to allow longer)",
+                    step,
+                    self.timeout.as_secs(),
+                    config::env_keys::sandbox::SKILLLITE_ENV_SETUP_TIMEOUT_SECS
+                );
+            }
+            std::thread::sleep(STEP_POLL);
+        };
+        let stdout = stdout_reader.join().unwrap_or_default();
+        let stderr = stderr_reader.join().unwrap_or_default();
+        for message in rx.try_iter() {
+            self.report(&message);
+        }
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+}
+
+/// `SKILLLITE_ENV_SETUP_TIMEOUT_SECS`, default [`DEFAULT_ENV_SETUP_TIMEOUT`].
+fn env_setup_timeout() -> Duration {
+    std::env::var(config::env_keys::sandbox::SKILLLITE_ENV_SETUP_TIMEOUT_SECS)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|&secs| secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_ENV_SETUP_TIMEOUT)
+}
+
+fn no_progress(_line: &str) -> Option<String> {
+    None
+}
+
+/// pip's per-package lines (`Collecting requests`, `Downloading ...`, `Installing collected
+/// packages: ...`).
+fn pip_progress(line: &str) -> Option<String> {
+    let line = line.trim();
+    [
+        "Collecting ",
+        "Downloading ",
+        "Installing collected packages",
+    ]
+    .iter()
+    .any(|prefix| line.starts_with(prefix))
+    .then(|| line.to_string())
+}
+
+/// npm's summary (`added 14 packages in 3s`) and Playwright's browser download lines.
+fn npm_progress(line: &str) -> Option<String> {
+    let line = line.trim();
+    (line.starts_with("added ") || line.starts_with("Downloading ")).then(|| line.to_string())
+}
+
+/// Load and check `skill.lock`, resolving and writing it when the skill declares
+/// dependencies but has no lock yet. If resolution fails (offline, unhashable
+/// requirement) the environment is built unlocked, as before locks existed.
+fn prepare_lock(
+    skill_dir: &Path,
+    spec: &EnvSpec,
+    ctx: &mut BuildContext,
+) -> Result<Option<SkillLock>> {
+    let declared = lockfile::declared_dependencies(skill_dir, spec)?;
+    if let Some(lock) = lockfile::load(skill_dir)? {
+        lockfile::check_declared(skill_dir, &lock, &declared)?;
+        return Ok(Some(lock));
+    }
+    if declared.is_empty() {
+        return Ok(None);
+    }
+    // Resolve the interpreter first: a declined runtime download must fail the build
+    if spec.language == "python" {
+        ctx.python()?;
     } else {
-        return Ok(PathBuf::new());
+        ctx.node()?;
     }
+    ctx.report("Resolving exact dependency versions for skill.lock");
+    match resolve_lock(skill_dir, spec, declared, ctx) {
+        Ok(lock) => {
+            if let Err(e) = lockfile::write(skill_dir, &lock) {
+                tracing::warn!("Failed to write {}: {}", SKILL_LOCK_FILE, e);
+            }
+            Ok(Some(lock))
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Could not lock dependencies of {}, installing unlocked: {}",
+                skill_dir.display(),
+                e
+            );
+            Ok(None)
+        }
+    }
+}
 
-    Ok(env_path)
+/// Resolve exact versions and integrity hashes without installing: `pip install --dry-run
+/// --report` in a scratch venv, or `npm install --package-lock-only` in a scratch dir.
+fn resolve_lock(
+    skill_dir: &Path,
+    spec: &EnvSpec,
+    declared: Vec<String>,
+    ctx: &mut BuildContext,
+) -> Result<SkillLock> {
+    let scratch = tempfile::tempdir().context("Create lock resolution dir")?;
+    let packages = if spec.language == "python" {
+        let python = ctx.python()?;
+        let venv = scratch.path().join("venv");
+        let mut cmd = Command::new(&python.program);
+        hide_child_console(&mut cmd);
+        cmd.args(&python.args).arg("-m").arg("venv").arg(&venv);
+        let out = ctx.run(&mut cmd, "Create venv", no_progress)?;
+        if !out.status.success() {
+            bail!("venv failed: {}", String::from_utf8_lossy(&out.stderr));
+        }
+        let report = scratch.path().join("report.json");
+        let mut cmd = Command::new(python_path_in_env(&venv));
+        hide_child_console(&mut cmd);
+        cmd.args(["-m", "pip", "install", "--dry-run", "--ignore-installed"])
+            .args(["--quiet", "--report"])
+            .arg(&report)
+            .args(collect_python_packages(skill_dir, spec)?)
+            .current_dir(skill_dir);
+        let out = ctx.run(&mut cmd, "pip install --dry-run", no_progress)?;
+        if !out.status.success() {
+            bail!(
+                "pip dependency resolution failed: {}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+        }
+        let content = std::fs::read_to_string(&report).context("Read pip report")?;
+        lockfile::packages_from_pip_report(&content)?
+    } else {
+        let (_, npm) = ctx.node()?;
+        let Some(manifest) = node_env_manifest(skill_dir, spec)? else {
+            bail!("no Node dependencies to lock");
+        };
+        write_node_manifest(scratch.path(), &manifest)?;
+        let existing_lock = skill_dir.join("package-lock.json");
+        if existing_lock.exists() {
+            let _ = std::fs::copy(&existing_lock, scratch.path().join("package-lock.json"));
+        }
+        let mut cmd = Command::new(&npm);
+        hide_child_console(&mut cmd);
+        cmd.args(["install", "--package-lock-only", "--ignore-scripts"])
+            .args(["--no-audit", "--no-fund"])
+            .current_dir(scratch.path());
+        let out = ctx.run(&mut cmd, "npm install --package-lock-only", no_progress)?;
+        if !out.status.success() {
+            bail!(
+                "npm dependency resolution failed: {}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+        }
+        let content = std::fs::read_to_string(scratch.path().join("package-lock.json"))
+            .context("Read package-lock.json")?;
+        lockfile::packages_from_package_lock(&content)?
+    };
+    Ok(SkillLock::new(&spec.language, declared, packages))
+}
+
+/// A per-skill env built before shared environments existed.
+fn legacy_env_ready(env_path: &Path, lang: &str) -> bool {
+    match lang {
+        "python" => python_path_in_env(env_path).exists(),
+        "node" => env_path.join("node_modules").exists(),
+        _ => false,
+    }
+}
+
+/// Runtime version part of the shared env key: the interpreter a build would use
+/// (the constraint-selected one, system if new enough, otherwise the provisioned runtime).
+fn runtime_tag(lang: &str, choice: &RuntimeChoice) -> String {
+    match choice {
+        RuntimeChoice::Selected(i) if lang == "python" => {
+            return format!("python-{}.{}", i.version.0, i.version.1)
+        }
+        RuntimeChoice::Selected(i) => return format!("node-{}", i.version.0),
+        RuntimeChoice::Provision if lang == "python" => return "python-3.12".to_string(),
+        RuntimeChoice::Provision => return "node-20".to_string(),
+        RuntimeChoice::Default => {}
+    }
+    if lang == "python" {
+        usable_system_python()
+            .map(|(_, (major, minor))| format!("python-{}.{}", major, minor))
+            .unwrap_or_else(|| "python-3.12".to_string())
+    } else {
+        runtime_deps::which_node()
+            .and_then(|node| runtime_deps::node_major_version(&node))
+            .map(|major| format!("node-{}", major))
+            .unwrap_or_else(|| "node-20".to_string())
+    }
 }
 
 /// Build RuntimePaths from an environment directory (or empty for system interpreters).
@@ -163,9 +580,8 @@ fn ensure_python_env(
     skill_dir: &Path,
     spec: &EnvSpec,
     env_path: &Path,
-    cache_dir: Option<&str>,
-    progress: RuntimeProgressFn,
-    confirm_download: RuntimeConfirmDownloadFn,
+    lock: Option<&SkillLock>,
+    ctx: &mut BuildContext,
 ) -> Result<()> {
     let packages = collect_python_packages(skill_dir, spec)?;
     let python_path = python_path_in_env(env_path);
@@ -174,12 +590,13 @@ fn ensure_python_env(
     if !env_exists {
         std::fs::create_dir_all(env_path).context("Create venv dir")?;
 
-        let python = resolve_python(cache_dir, progress, confirm_download)?;
+        let python = ctx.python()?;
+        ctx.report("Creating Python virtual environment");
         let mut cmd = Command::new(&python.program);
         hide_child_console(&mut cmd);
         cmd.args(&python.args).arg("-m").arg("venv").arg(env_path);
         cmd.current_dir(skill_dir);
-        let out = cmd.output().context("Create venv")?;
+        let out = ctx.run(&mut cmd, "Create venv", no_progress)?;
         if !out.status.success() {
             bail!("venv failed: {}", String::from_utf8_lossy(&out.stderr));
         }
@@ -197,8 +614,26 @@ fn ensure_python_env(
                 c.arg("install");
                 c
             };
-            cmd.args(&packages).current_dir(skill_dir);
-            let out = cmd.output().context("pip install")?;
+            // Locked: exactly the pinned closure, each archive checked against its hash
+            let requirements = match lock {
+                Some(lock) => {
+                    let file = tempfile::NamedTempFile::new().context("Create requirements")?;
+                    std::fs::write(file.path(), lockfile::pip_requirements(lock))
+                        .context("Write requirements")?;
+                    cmd.args(["--require-hashes", "--no-deps", "-r"])
+                        .arg(file.path());
+                    Some(file)
+                }
+                None => {
+                    cmd.args(&packages);
+                    None
+                }
+            };
+            cmd.current_dir(skill_dir);
+            let count = lock.map_or(packages.len(), |l| l.packages.len());
+            ctx.report(&format!("Installing {} Python package(s)", count));
+            let out = ctx.run(&mut cmd, "pip install", pip_progress)?;
+            drop(requirements);
             if !out.status.success() {
                 bail!(
                     "pip install failed: {}",
@@ -209,7 +644,7 @@ fn ensure_python_env(
     }
 
     if requests_playwright_browsers(&packages) {
-        install_playwright_browsers_for_python(skill_dir, env_path)?;
+        install_playwright_browsers_for_python(skill_dir, env_path, ctx)?;
     }
 
     Ok(())
@@ -219,63 +654,65 @@ fn ensure_node_env(
     skill_dir: &Path,
     spec: &EnvSpec,
     env_path: &Path,
-    cache_dir: Option<&str>,
-    progress: RuntimeProgressFn,
-    confirm_download: RuntimeConfirmDownloadFn,
+    lock: Option<&SkillLock>,
+    ctx: &mut BuildContext,
 ) -> Result<()> {
     let packages = collect_node_packages(skill_dir, spec)?;
     let env_exists = env_path.join("node_modules").exists();
 
     // Always ensure node/npm are available (even if no deps to install),
     // so build_runtime_paths can find the bundled node binary later.
-    let (node_bin, _npm_path) = resolve_node(cache_dir, progress, confirm_download)?;
+    let (node_bin, npm_path) = ctx.node()?;
 
     // Write a marker so build_runtime_paths knows where node lives
     std::fs::create_dir_all(env_path).context("Create node env dir")?;
     let node_bin_marker = env_path.join(".skilllite_node_bin");
-    std::fs::write(&node_bin_marker, node_bin.to_string_lossy().as_bytes())
-        .context("Write node bin marker")?;
+    let node_bin_str = node_bin.to_string_lossy();
+    // Shared envs are read concurrently; skip the rewrite when nothing changed
+    if std::fs::read_to_string(&node_bin_marker).ok().as_deref() != Some(node_bin_str.as_ref()) {
+        std::fs::write(&node_bin_marker, node_bin_str.as_bytes())
+            .context("Write node bin marker")?;
+    }
 
     if !env_exists {
-        let package_json = skill_dir.join("package.json");
-        let has_deps = if package_json.exists() {
-            std::fs::copy(&package_json, env_path.join("package.json"))
-                .context("Copy package.json")?;
-            true
-        } else if let Some(ref pkgs) = spec.resolved_packages {
-            let deps: std::collections::HashMap<String, String> =
-                pkgs.iter().map(|p| (p.clone(), "*".to_string())).collect();
-            let pkg = serde_json::json!({
-                "name": "skill-env",
-                "version": "1.0.0",
-                "private": true,
-                "dependencies": deps
-            });
-            std::fs::write(
-                env_path.join("package.json"),
-                serde_json::to_string_pretty(&pkg).context("Serialize package.json")?,
-            )
-            .context("Write package.json")?;
-            true
-        } else {
-            false
-        };
-
-        if has_deps {
-            let lock = skill_dir.join("package-lock.json");
-            if lock.exists() {
-                let _ = std::fs::copy(&lock, env_path.join("package-lock.json"));
-            }
-            let mut npm_cmd = Command::new(&_npm_path);
+        if let Some(manifest) = node_env_manifest(skill_dir, spec)? {
+            write_node_manifest(env_path, &manifest)?;
+            let args: &[&str] = match lock {
+                Some(lock) => {
+                    // Locked: `npm ci` installs the pinned tree and verifies integrity hashes
+                    let npm_lock = lockfile::package_lock_json(lock, &manifest);
+                    std::fs::write(
+                        env_path.join("package-lock.json"),
+                        serde_json::to_string_pretty(&npm_lock)
+                            .context("Serialize package-lock.json")?,
+                    )
+                    .context("Write package-lock.json")?;
+                    &["ci", "--omit=dev"]
+                }
+                None => {
+                    let existing_lock = skill_dir.join("package-lock.json");
+                    if existing_lock.exists() {
+                        let _ = std::fs::copy(&existing_lock, env_path.join("package-lock.json"));
+                    }
+                    &["install", "--omit=dev"]
+                }
+            };
+            let count = match lock {
+                Some(lock) => lock.packages.len(),
+                None => packages.len(),
+            };
+            ctx.report(&format!(
+                "Installing {} Node package(s) (npm {})",
+                count, args[0]
+            ));
+            let mut npm_cmd = Command::new(&npm_path);
             hide_child_console(&mut npm_cmd);
-            let out = npm_cmd
-                .args(["install", "--omit=dev"])
-                .current_dir(env_path)
-                .output()
-                .context("npm install")?;
+            npm_cmd.args(args).current_dir(env_path);
+            let out = ctx.run(&mut npm_cmd, &format!("npm {}", args[0]), npm_progress)?;
             if !out.status.success() {
                 bail!(
-                    "npm install failed: {}",
+                    "npm {} failed: {}",
+                    args[0],
                     String::from_utf8_lossy(&out.stderr)
                 );
             }
@@ -283,13 +720,51 @@ fn ensure_node_env(
     }
 
     if requests_playwright_browsers(&packages) {
-        install_playwright_browsers_for_node(env_path)?;
+        install_playwright_browsers_for_node(env_path, ctx)?;
     }
 
     Ok(())
 }
 
-fn collect_python_packages(skill_dir: &Path, spec: &EnvSpec) -> Result<Vec<String>> {
+/// `package.json` for the env: the skill's dependency sections (no name, no lifecycle
+/// scripts, matching the shared env key), or `resolved_packages` at any version.
+/// `None` when the skill has no Node dependencies.
+fn node_env_manifest(skill_dir: &Path, spec: &EnvSpec) -> Result<Option<serde_json::Value>> {
+    let mut pkg = serde_json::json!({
+        "name": "skill-env",
+        "version": "1.0.0",
+        "private": true,
+    });
+    let package_json = skill_dir.join("package.json");
+    if package_json.exists() {
+        let content = std::fs::read_to_string(&package_json).context("Read package.json")?;
+        let manifest: serde_json::Value =
+            serde_json::from_str(&content).context("Parse package.json")?;
+        for section in shared_cache::node_manifest_sections() {
+            if let Some(value) = manifest.get(section) {
+                pkg[section] = value.clone();
+            }
+        }
+    } else if let Some(ref pkgs) = spec.resolved_packages {
+        let deps: std::collections::HashMap<String, String> =
+            pkgs.iter().map(|p| (p.clone(), "*".to_string())).collect();
+        pkg["dependencies"] = serde_json::json!(deps);
+    } else {
+        return Ok(None);
+    }
+    Ok(Some(pkg))
+}
+
+fn write_node_manifest(dir: &Path, manifest: &serde_json::Value) -> Result<()> {
+    std::fs::write(
+        dir.join("package.json"),
+        serde_json::to_string_pretty(manifest).context("Serialize package.json")?,
+    )
+    .context("Write package.json")?;
+    Ok(())
+}
+
+pub(crate) fn collect_python_packages(skill_dir: &Path, spec: &EnvSpec) -> Result<Vec<String>> {
     if let Some(ref pkgs) = spec.resolved_packages {
         return Ok(pkgs.clone());
     }
@@ -367,7 +842,11 @@ fn requests_playwright_browsers(packages: &[String]) -> bool {
     })
 }
 
-fn install_playwright_browsers_for_python(skill_dir: &Path, env_path: &Path) -> Result<()> {
+fn install_playwright_browsers_for_python(
+    skill_dir: &Path,
+    env_path: &Path,
+    ctx: &BuildContext,
+) -> Result<()> {
     let python = python_path_in_env(env_path);
     if !python.exists() {
         bail!("playwright browser install skipped: python env missing");
@@ -375,11 +854,10 @@ fn install_playwright_browsers_for_python(skill_dir: &Path, env_path: &Path) ->
 
     let mut py_play = Command::new(&python);
     hide_child_console(&mut py_play);
-    let out = py_play
+    py_play
         .args(["-m", "playwright", "install", "chromium"])
-        .current_dir(skill_dir)
-        .output()
-        .context("playwright install chromium")?;
+        .current_dir(skill_dir);
+    let out = ctx.run(&mut py_play, "playwright install chromium", npm_progress)?;
     if !out.status.success() {
         bail!(
             "playwright install chromium failed: {}",
@@ -390,7 +868,7 @@ fn install_playwright_browsers_for_python(skill_dir: &Path, env_path: &Path) ->
     Ok(())
 }
 
-fn install_playwright_browsers_for_node(env_path: &Path) -> Result<()> {
+fn install_playwright_browsers_for_node(env_path: &Path, ctx: &BuildContext) -> Result<()> {
     let unix_cli = env_path
         .join("node_modules")
         .join(".bin")
@@ -417,10 +895,8 @@ fn install_playwright_browsers_for_node(env_path: &Path) -> Result<()> {
         cmd
     };
 
-    let out = cmd
-        .current_dir(env_path)
-        .output()
-        .context("playwright install chromium")?;
+    cmd.current_dir(env_path);
+    let out = ctx.run(&mut cmd, "playwright install chromium", npm_progress)?;
     if !out.status.success() {
         bail!(
             "playwright install chromium failed: {}",
@@ -464,27 +940,40 @@ fn which_python() -> Result<PythonCommand> {
     bail!("no usable Python launcher found in PATH")
 }
 
-/// Prefer system Python (if version >= MIN_PYTHON_VERSION); otherwise provision to ~/.skilllite/runtime/.
+/// System Python and its (major, minor) version, if it meets MIN_PYTHON_VERSION.
+fn usable_system_python() -> Option<(PythonCommand, (u32, u32))> {
+    let cmd = which_python().ok()?;
+    let mut ver_cmd = Command::new(&cmd.program);
+    hide_child_console(&mut ver_cmd);
+    let out = ver_cmd.args(&cmd.args).arg("--version").output().ok()?;
+    let ver = std::str::from_utf8(&out.stdout)
+        .or_else(|_| std::str::from_utf8(&out.stderr))
+        .ok()
+        .and_then(runtime_deps::parse_python_version)?;
+    runtime_deps::python_version_meets_minimum(ver.0, ver.1).then_some((cmd, ver))
+}
+
+/// Use the constraint-selected interpreter; otherwise prefer system Python (if version >=
+/// MIN_PYTHON_VERSION) and fall back to provisioning to ~/.skilllite/runtime/.
 fn resolve_python(
     cache_dir: Option<&str>,
+    choice: &RuntimeChoice,
     progress: RuntimeProgressFn,
     confirm_download: RuntimeConfirmDownloadFn,
 ) -> Result<PythonCommand> {
-    if let Ok(cmd) = which_python() {
-        let mut ver_cmd = Command::new(&cmd.program);
-        hide_child_console(&mut ver_cmd);
-        let out = ver_cmd.args(&cmd.args).arg("--version").output();
-        if let Ok(ref o) = out {
-            if let Some(ver) = std::str::from_utf8(&o.stdout)
-                .or_else(|_| std::str::from_utf8(&o.stderr))
-                .ok()
-                .and_then(runtime_deps::parse_python_version)
-            {
-                if runtime_deps::python_version_meets_minimum(ver.0, ver.1) {
-                    return Ok(cmd);
-                }
+    match choice {
+        RuntimeChoice::Selected(i) => {
+            return Ok(PythonCommand {
+                program: i.path.clone(),
+                args: Vec::new(),
+            })
+        }
+        RuntimeChoice::Default => {
+            if let Some((cmd, _)) = usable_system_python() {
+                return Ok(cmd);
             }
         }
+        RuntimeChoice::Provision => {}
     }
     let runtime_dir = runtime_deps::get_runtime_dir(cache_dir)
         .context("Cannot determine runtime dir for Python")?;
@@ -508,14 +997,33 @@ fn resolve_python(
     })
 }
 
-/// Prefer system Node/npm (if version >= MIN_NODE_MAJOR); otherwise provision to ~/.skilllite/runtime/.
+/// Use the constraint-selected node (with its sibling npm); otherwise prefer system Node/npm
+/// (if version >= MIN_NODE_MAJOR) and fall back to provisioning to ~/.skilllite/runtime/.
 fn resolve_node(
     cache_dir: Option<&str>,
+    choice: &RuntimeChoice,
     progress: RuntimeProgressFn,
     confirm_download: RuntimeConfirmDownloadFn,
 ) -> Result<(PathBuf, PathBuf)> {
-    if let (Some(node), Some(npm)) = (runtime_deps::which_node(), runtime_deps::which_npm()) {
-        return Ok((node, npm));
+    match choice {
+        RuntimeChoice::Selected(i) => {
+            let npm_name = if cfg!(windows) { "npm.cmd" } else { "npm" };
+            let npm = i
+                .path
+                .parent()
+                .map(|dir| dir.join(npm_name))
+                .filter(|p| p.is_file())
+                .or_else(|| which::which("npm").ok())
+                .context("npm not found next to the selected node")?;
+            return Ok((i.path.clone(), npm));
+        }
+        RuntimeChoice::Default => {
+            if let (Some(node), Some(npm)) = (runtime_deps::which_node(), runtime_deps::which_npm())
+            {
+                return Ok((node, npm));
+            }
+        }
+        RuntimeChoice::Provision => {}
     }
     let runtime_dir = runtime_deps::get_runtime_dir(cache_dir)
         .context("Cannot determine runtime dir for Node")?;
@@ -578,6 +1086,53 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    fn recording_progress() -> (RuntimeProgressFn, Arc<Mutex<Vec<String>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let progress: RuntimeProgressFn = Some(Box::new(move |msg: &str| {
+            sink.lock().unwrap().push(msg.to_string());
+        }));
+        (progress, seen)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn setup_step_forwards_progress_lines_and_keeps_output() {
+        let choice = RuntimeChoice::Default;
+        let (progress, seen) = recording_progress();
+        let ctx = BuildContext::new(None, &choice, progress, None);
+        let mut cmd = Command::new("sh");
+        cmd.args([
+            "-c",
+            "echo 'Collecting requests'; echo 'noise'; echo '  Downloading idna-3.7.whl (66 kB)'; echo oops >&2",
+        ]);
+        let out = ctx.run(&mut cmd, "pip install", pip_progress).unwrap();
+        assert!(out.status.success());
+        assert!(String::from_utf8_lossy(&out.stdout).contains("noise"));
+        assert_eq!(String::from_utf8_lossy(&out.stderr).trim(), "oops");
+        assert_eq!(
+            *seen.lock().unwrap(),
+            ["Collecting requests", "Downloading idna-3.7.whl (66 kB)"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn setup_step_is_killed_at_the_deadline() {
+        let choice = RuntimeChoice::Default;
+        let mut ctx = BuildContext::new(None, &choice, None, None);
+        ctx.timeout = Duration::from_millis(300);
+        ctx.deadline = Instant::now() + ctx.timeout;
+        let started = Instant::now();
+        let mut cmd = Command::new("sleep");
+        cmd.arg("30");
+        let err = ctx.run(&mut cmd, "npm ci", no_progress).unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let msg = err.to_string();
+        assert!(msg.contains("npm ci timed out"), "{}", msg);
+        assert!(msg.contains("SKILLLITE_ENV_SETUP_TIMEOUT_SECS"), "{}", msg);
+    }
+
     #[test]
     fn test_default_system_python_command_matches_platform() {
         if cfg!(windows) {
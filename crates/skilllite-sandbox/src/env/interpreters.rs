@@ -0,0 +1,316 @@
+//! Pick a Python / Node interpreter that satisfies a skill's declared version constraint.
+//!
+//! Candidates are probed in order: PATH entries (`python3`, `python`, `python3.N`, `node`),
+//! pyenv / asdf / nvm install dirs, common install locations, the provisioned runtime, and
+//! `py -0p` on Windows. The first one whose `--version` satisfies the constraint wins, so a
+//! skill whose constraint the PATH default already meets resolves exactly as before.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use skilllite_core::runtime_constraint::RuntimeConstraint;
+use skilllite_core::EnvSpec;
+
+use crate::common::hide_child_console;
+use crate::env::runtime_deps;
+use crate::error::SandboxError;
+use crate::Result;
+
+/// Versions installed by [`runtime_deps`] when nothing suitable is on the system.
+const PROVISIONED_PYTHON: (u32, u32, u32) = (3, 12, 0);
+const PROVISIONED_NODE: (u32, u32, u32) = (20, 0, 0);
+
+/// A probed interpreter binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpreter {
+    pub path: PathBuf,
+    /// (major, minor, patch) reported by `--version`
+    pub version: (u32, u32, u32),
+}
+
+impl Interpreter {
+    pub fn version_string(&self) -> String {
+        format!("{}.{}.{}", self.version.0, self.version.1, self.version.2)
+    }
+}
+
+/// Outcome of runtime resolution for one skill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeChoice {
+    /// No constraint declared: system runtime if new enough, otherwise provision
+    Default,
+    /// A discovered interpreter satisfies the constraint
+    Selected(Interpreter),
+    /// Nothing installed satisfies the constraint but the provisioned runtime does
+    Provision,
+}
+
+/// Resolve the runtime for `spec`. Fails when no installed or provisionable runtime
+/// satisfies the skill's `compatibility` constraint.
+pub fn resolve_runtime(spec: &EnvSpec, cache_dir: Option<&str>) -> Result<RuntimeChoice> {
+    let Some(constraint) = spec.runtime_constraint() else {
+        return Ok(RuntimeChoice::Default);
+    };
+    let dirs = candidate_dirs(&spec.language, cache_dir);
+    let mut found = discover(&spec.language, &dirs);
+    if cfg!(windows) && spec.language == "python" {
+        found.extend(windows_py_launcher_interpreters());
+    }
+    choose(&constraint, found)
+}
+
+fn choose(constraint: &RuntimeConstraint, found: Vec<Interpreter>) -> Result<RuntimeChoice> {
+    if let Some(i) = found.iter().find(|i| constraint.matches(i.version)) {
+        return Ok(RuntimeChoice::Selected(i.clone()));
+    }
+    let provisioned = if constraint.language == "python" {
+        PROVISIONED_PYTHON
+    } else {
+        PROVISIONED_NODE
+    };
+    if constraint.matches(provisioned) {
+        return Ok(RuntimeChoice::Provision);
+    }
+    let found_list = (!found.is_empty()).then(|| {
+        found
+            .iter()
+            .map(|i| format!("{} ({})", i.version_string(), i.path.display()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    });
+    let found_desc = found_list.as_deref().unwrap_or("none");
+    let hint = if constraint.language == "python" {
+        "install a matching Python (e.g. `pyenv install`) and make sure it is on PATH"
+    } else {
+        "install a matching Node.js (e.g. `nvm install`) and make sure it is on PATH"
+    };
+    let message = format!(
+        "Skill requires {}, but no matching interpreter was found (found: {}). Hint: {}",
+        constraint, found_desc, hint
+    );
+    Err(crate::Error::run(
+        SandboxError::MissingRuntime {
+            need: constraint.to_string(),
+            found: found_list,
+        },
+        message,
+    ))
+}
+
+/// Directories to search, in priority order (PATH first).
+pub fn candidate_dirs(language: &str, cache_dir: Option<&str>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|p| std::env::split_paths(&p).collect())
+        .unwrap_or_default();
+    let home = dirs::home_dir();
+    if language == "python" {
+        let pyenv_root = std::env::var_os("PYENV_ROOT")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|h| h.join(".pyenv")));
+        if let Some(root) = pyenv_root {
+            dirs.extend(version_bin_dirs(&root.join("versions")));
+        }
+        if let Some(asdf) = asdf_root(home.as_deref()) {
+            dirs.extend(version_bin_dirs(&asdf.join("installs").join("python")));
+        }
+    } else {
+        if let Some(home) = home.as_deref() {
+            dirs.extend(version_bin_dirs(
+                &home.join(".nvm").join("versions").join("node"),
+            ));
+        }
+        if let Some(asdf) = asdf_root(home.as_deref()) {
+            dirs.extend(version_bin_dirs(&asdf.join("installs").join("nodejs")));
+        }
+    }
+    for common in ["/usr/local/bin", "/opt/homebrew/bin", "/usr/bin"] {
+        dirs.push(PathBuf::from(common));
+    }
+    if language == "python" {
+        dirs.extend(version_bin_dirs(Path::new(
+            "/Library/Frameworks/Python.framework/Versions",
+        )));
+    }
+    if let Some(runtime_dir) = runtime_deps::get_runtime_dir(cache_dir) {
+        let root = if language == "python" {
+            runtime_dir.join("python-3.12")
+        } else {
+            runtime_dir.join("node-20")
+        };
+        dirs.push(root.join("bin"));
+        dirs.push(root);
+    }
+    dirs
+}
+
+fn asdf_root(home: Option<&Path>) -> Option<PathBuf> {
+    std::env::var_os("ASDF_DATA_DIR")
+        .map(PathBuf::from)
+        .or_else(|| home.map(|h| h.join(".asdf")))
+}
+
+/// `<root>/<version>/bin` for each installed version, newest name first.
+fn version_bin_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path().join("bin"))
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+    dirs.reverse();
+    dirs
+}
+
+fn binary_names(language: &str) -> Vec<String> {
+    let mut names = if language == "python" {
+        let mut n = vec!["python3".to_string(), "python".to_string()];
+        n.extend((8..=14).rev().map(|minor| format!("python3.{}", minor)));
+        n
+    } else {
+        vec!["node".to_string()]
+    };
+    if cfg!(windows) {
+        for n in &mut names {
+            n.push_str(".exe");
+        }
+    }
+    names
+}
+
+/// Probe every candidate binary in `dirs`; duplicates (same canonical path) are skipped.
+pub fn discover(language: &str, dirs: &[PathBuf]) -> Vec<Interpreter> {
+    let names = binary_names(language);
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for dir in dirs {
+        for name in &names {
+            let path = dir.join(name);
+            if !path.is_file() {
+                continue;
+            }
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if !seen.insert(canonical) {
+                continue;
+            }
+            if let Some(version) = probe_version(&path) {
+                found.push(Interpreter { path, version });
+            }
+        }
+    }
+    found
+}
+
+/// Run `<binary> --version` and parse "Python 3.11.4" / "v20.11.0".
+pub fn probe_version(binary: &Path) -> Option<(u32, u32, u32)> {
+    let mut cmd = Command::new(binary);
+    hide_child_console(&mut cmd);
+    let out = cmd.arg("--version").output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    // Python 2 printed its version on stderr
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    parse_version_triple(&text)
+}
+
+fn parse_version_triple(s: &str) -> Option<(u32, u32, u32)> {
+    let s = s.trim();
+    let s = s
+        .strip_prefix("Python ")
+        .or_else(|| s.strip_prefix('v'))
+        .unwrap_or(s);
+    let mut parts = s.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// Interpreters registered with the Windows `py` launcher (`py -0p`).
+fn windows_py_launcher_interpreters() -> Vec<Interpreter> {
+    let mut cmd = Command::new("py");
+    hide_child_console(&mut cmd);
+    let Ok(out) = cmd.arg("-0p").output() else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| line.split_whitespace().last())
+        .map(PathBuf::from)
+        .filter(|p| p.is_file())
+        .filter_map(|path| probe_version(&path).map(|version| Interpreter { path, version }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skilllite_core::runtime_constraint::parse_runtime_constraint;
+
+    #[test]
+    fn parses_version_output() {
+        assert_eq!(parse_version_triple("Python 3.11.4\n"), Some((3, 11, 4)));
+        assert_eq!(parse_version_triple("Python 3.13.0rc1"), Some((3, 13, 0)));
+        assert_eq!(parse_version_triple("v20.11.1"), Some((20, 11, 1)));
+        assert_eq!(parse_version_triple("garbage"), None);
+    }
+
+    #[cfg(unix)]
+    fn fake_interpreter(dir: &Path, name: &str, output: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\necho '{}'\n", output)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn selects_first_interpreter_satisfying_constraint() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path_dir = tmp.path().join("path");
+        let pyenv_dir = tmp.path().join("pyenv/3.11.9/bin");
+        fake_interpreter(&path_dir, "python3", "Python 3.9.18");
+        fake_interpreter(&pyenv_dir, "python3", "Python 3.11.9");
+
+        let found = discover("python", &[path_dir.clone(), pyenv_dir.clone()]);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].version, (3, 9, 18));
+
+        let constraint = parse_runtime_constraint("python>=3.11", "python").unwrap();
+        match choose(&constraint, found).unwrap() {
+            RuntimeChoice::Selected(i) => {
+                assert_eq!(i.path, pyenv_dir.join("python3"));
+                assert_eq!(i.version, (3, 11, 9));
+            }
+            other => panic!("unexpected choice {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn falls_back_to_provision_or_reports_mismatch() {
+        let tmp = tempfile::tempdir().unwrap();
+        fake_interpreter(tmp.path(), "node", "v16.20.2");
+        let found = discover("node", &[tmp.path().to_path_buf()]);
+
+        let provisionable = parse_runtime_constraint("node>=18", "node").unwrap();
+        assert_eq!(
+            choose(&provisionable, found.clone()).unwrap(),
+            RuntimeChoice::Provision
+        );
+
+        let impossible = parse_runtime_constraint("node>=99", "node").unwrap();
+        let err = choose(&impossible, found).unwrap_err().to_string();
+        assert!(err.contains("requires node>=99"), "{}", err);
+        assert!(err.contains("16.20.2"), "{}", err);
+        assert!(err.contains("Hint:"), "{}", err);
+    }
+}
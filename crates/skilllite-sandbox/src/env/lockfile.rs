@@ -0,0 +1,658 @@
+//! `skill.lock`: every package of a skill's environment pinned to an exact version and
+//! integrity hash.
+//!
+//! The lock is resolved once (first `skilllite add` / `ensure_environment`) and written into
+//! the skill directory; later builds install strictly from it (`pip --require-hashes` /
+//! `npm ci`). A lock whose checksum does not match its content is rejected, and a lock that
+//! no longer matches the skill's declared dependencies fails the build until regenerated
+//! with `skilllite verify --deps --relock`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use skilllite_core::EnvSpec;
+
+use crate::error::bail;
+use crate::Result;
+
+use super::shared_cache;
+
+/// Lock file name inside the skill directory.
+pub const SKILL_LOCK_FILE: &str = "skill.lock";
+const LOCK_FORMAT_VERSION: u32 = 1;
+
+/// One resolved package.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// `sha256:<hex>` for PyPI archives, the npm SRI string (`sha512-...`) for npm
+    pub integrity: String,
+    /// npm: install location inside the env (`node_modules/a/node_modules/b`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// npm: the original `package-lock.json` entry, replayed for `npm ci`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub npm_entry: Option<serde_json::Value>,
+}
+
+/// Contents of `skill.lock`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillLock {
+    pub version: u32,
+    pub language: String,
+    /// Dependency declarations the lock was resolved from (sorted)
+    pub declared: Vec<String>,
+    pub packages: Vec<LockedPackage>,
+    pub locked_at: String,
+    /// sha256 over language, declarations and packages
+    pub checksum: String,
+}
+
+impl SkillLock {
+    pub fn new(language: &str, declared: Vec<String>, packages: Vec<LockedPackage>) -> Self {
+        let checksum = compute_checksum(language, &declared, &packages);
+        Self {
+            version: LOCK_FORMAT_VERSION,
+            language: language.to_string(),
+            declared,
+            packages,
+            locked_at: chrono::Utc::now().to_rfc3339(),
+            checksum,
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<&LockedPackage> {
+        if self.language == "node" {
+            let top = format!("node_modules/{}", name);
+            self.packages
+                .iter()
+                .find(|p| p.path.as_deref() == Some(top.as_str()))
+        } else {
+            let name = normalize_pypi_name(name);
+            self.packages
+                .iter()
+                .find(|p| normalize_pypi_name(&p.name) == name)
+        }
+    }
+}
+
+fn compute_checksum(language: &str, declared: &[String], packages: &[LockedPackage]) -> String {
+    let body = serde_json::json!({
+        "language": language,
+        "declared": declared,
+        "packages": packages,
+    });
+    hex::encode(Sha256::digest(body.to_string().as_bytes()))
+}
+
+pub fn lock_path(skill_dir: &Path) -> PathBuf {
+    skill_dir.join(SKILL_LOCK_FILE)
+}
+
+/// Read the skill's lock. `Ok(None)` when there is none; an error when it is malformed or
+/// was modified after it was generated.
+pub fn load(skill_dir: &Path) -> Result<Option<SkillLock>> {
+    let path = lock_path(skill_dir);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(anyhow::Error::new(e).context("Read skill.lock").into()),
+    };
+    let lock: SkillLock = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not a valid skill lock", path.display()))?;
+    if lock.version != LOCK_FORMAT_VERSION {
+        bail!(
+            "{} has unsupported format version {} (expected {})",
+            path.display(),
+            lock.version,
+            LOCK_FORMAT_VERSION
+        );
+    }
+    if compute_checksum(&lock.language, &lock.declared, &lock.packages) != lock.checksum {
+        bail!(
+            "{} checksum mismatch: the lock was modified after it was generated. \
+             Regenerate it with `skilllite verify {} --deps --relock`",
+            path.display(),
+            skill_dir.display()
+        );
+    }
+    Ok(Some(lock))
+}
+
+pub fn write(skill_dir: &Path, lock: &SkillLock) -> Result<()> {
+    let content = serde_json::to_string_pretty(lock).context("Serialize skill.lock")?;
+    std::fs::write(lock_path(skill_dir), content + "\n").context("Write skill.lock")?;
+    Ok(())
+}
+
+/// The skill's dependency declarations, sorted: requirement lines for Python,
+/// `name@range` for Node (`package.json` sections, else `resolved_packages`).
+pub fn declared_dependencies(skill_dir: &Path, spec: &EnvSpec) -> Result<Vec<String>> {
+    let mut declared = match spec.language.as_str() {
+        "python" => super::builder::collect_python_packages(skill_dir, spec)?,
+        "node" => node_declarations(skill_dir, spec)?,
+        _ => Vec::new(),
+    };
+    declared.sort();
+    declared.dedup();
+    Ok(declared)
+}
+
+fn node_declarations(skill_dir: &Path, spec: &EnvSpec) -> Result<Vec<String>> {
+    let package_json = skill_dir.join("package.json");
+    if !package_json.exists() {
+        return Ok(spec
+            .resolved_packages
+            .iter()
+            .flatten()
+            .map(|p| format!("{}@*", p))
+            .collect());
+    }
+    let content = std::fs::read_to_string(&package_json).context("Read package.json")?;
+    let manifest: serde_json::Value =
+        serde_json::from_str(&content).context("Parse package.json")?;
+    let mut declared = Vec::new();
+    for section in ["dependencies", "devDependencies", "optionalDependencies"] {
+        if let Some(map) = manifest.get(section).and_then(|v| v.as_object()) {
+            declared.extend(
+                map.iter()
+                    .map(|(name, range)| format!("{}@{}", name, range.as_str().unwrap_or("*"))),
+            );
+        }
+    }
+    Ok(declared)
+}
+
+/// Fail when `lock` does not cover `declared`: a declared package missing from the lock,
+/// an exact pin resolving to another version, or declarations changed since locking.
+pub fn check_declared(skill_dir: &Path, lock: &SkillLock, declared: &[String]) -> Result<()> {
+    let conflict = |detail: String| -> Result<()> {
+        bail!(
+            "{} conflicts with the declared dependencies: {}. Regenerate it with \
+             `skilllite verify {} --deps --relock`",
+            lock_path(skill_dir).display(),
+            detail,
+            skill_dir.display()
+        )
+    };
+    for decl in declared {
+        let (name, pin) = if lock.language == "node" {
+            split_node_declaration(decl)
+        } else {
+            split_python_requirement(decl)
+        };
+        let Some(pkg) = lock.find(name) else {
+            return conflict(format!("'{}' is declared but not locked", name));
+        };
+        if let Some(pin) = pin {
+            if pkg.version != pin {
+                return conflict(format!(
+                    "'{}' is pinned to {} but locked at {}",
+                    name, pin, pkg.version
+                ));
+            }
+        }
+    }
+    if lock.declared != declared {
+        return conflict(format!(
+            "declarations changed since locking (locked from [{}], now [{}])",
+            lock.declared.join(", "),
+            declared.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// `requests==2.31.0 ; python_version>"3"` → (`requests`, Some(`2.31.0`)).
+fn split_python_requirement(req: &str) -> (&str, Option<&str>) {
+    let req = req.split(';').next().unwrap_or(req).trim();
+    let end = req
+        .find(|c: char| "=<>!~[ @".contains(c))
+        .unwrap_or(req.len());
+    let name = req[..end].trim();
+    let pin = req[end..]
+        .trim_start()
+        .strip_prefix("==")
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && !v.contains([',', '*']));
+    (name, pin)
+}
+
+/// `@scope/pkg@1.2.3` → (`@scope/pkg`, Some(`1.2.3`)); ranges are not treated as pins.
+fn split_node_declaration(decl: &str) -> (&str, Option<&str>) {
+    let (name, range) = match decl.rfind('@') {
+        Some(i) if i > 0 => (&decl[..i], &decl[i + 1..]),
+        _ => (decl, ""),
+    };
+    let exact = !range.is_empty()
+        && range.starts_with(|c: char| c.is_ascii_digit())
+        && range
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ".-+".contains(c));
+    (name, exact.then_some(range))
+}
+
+fn normalize_pypi_name(name: &str) -> String {
+    name.trim().to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Packages from a `pip install --dry-run --report` JSON report.
+pub(crate) fn packages_from_pip_report(report: &str) -> Result<Vec<LockedPackage>> {
+    let report: serde_json::Value = serde_json::from_str(report).context("Parse pip report")?;
+    let Some(items) = report.get("install").and_then(|v| v.as_array()) else {
+        bail!("pip report has no 'install' list");
+    };
+    let mut packages = Vec::new();
+    for item in items {
+        let meta = item.get("metadata");
+        let field = |key: &str| {
+            meta.and_then(|m| m.get(key))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let (name, version) = (field("name"), field("version"));
+        if name.is_empty() || version.is_empty() {
+            bail!("pip report entry without name/version");
+        }
+        let archive = item
+            .get("download_info")
+            .and_then(|d| d.get("archive_info"));
+        let sha256 = archive
+            .and_then(|a| a.pointer("/hashes/sha256"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| {
+                archive
+                    .and_then(|a| a.get("hash"))
+                    .and_then(|v| v.as_str())
+                    .and_then(|h| h.strip_prefix("sha256="))
+                    .map(str::to_string)
+            });
+        let Some(sha256) = sha256 else {
+            bail!(
+                "cannot lock {} {}: no sha256 archive hash (local directory or VCS requirement)",
+                name,
+                version
+            );
+        };
+        packages.push(LockedPackage {
+            name,
+            version,
+            integrity: format!("sha256:{}", sha256),
+            path: None,
+            npm_entry: None,
+        });
+    }
+    packages.sort_by_key(|p| normalize_pypi_name(&p.name));
+    Ok(packages)
+}
+
+/// Packages from an npm `package-lock.json` (lockfileVersion 2/3).
+pub(crate) fn packages_from_package_lock(content: &str) -> Result<Vec<LockedPackage>> {
+    let lock: serde_json::Value =
+        serde_json::from_str(content).context("Parse package-lock.json")?;
+    let Some(entries) = lock.get("packages").and_then(|v| v.as_object()) else {
+        bail!("package-lock.json has no 'packages' map (lockfileVersion 2 or later required)");
+    };
+    let mut packages = Vec::new();
+    for (path, entry) in entries {
+        let Some(idx) = path.rfind("node_modules/") else {
+            continue;
+        };
+        if entry.get("link").and_then(|v| v.as_bool()) == Some(true) {
+            continue;
+        }
+        let name = entry
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&path[idx + "node_modules/".len()..])
+            .to_string();
+        let str_field = |key: &str| entry.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let Some(version) = str_field("version") else {
+            bail!("cannot lock {}: no version in package-lock.json", path);
+        };
+        let Some(integrity) = str_field("integrity") else {
+            bail!(
+                "cannot lock {} {}: no integrity hash (git or local dependency)",
+                name,
+                version
+            );
+        };
+        packages.push(LockedPackage {
+            name,
+            version,
+            integrity,
+            path: Some(path.clone()),
+            npm_entry: Some(entry.clone()),
+        });
+    }
+    packages.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(packages)
+}
+
+/// `requirements.txt` for `pip install --require-hashes --no-deps -r`.
+pub(crate) fn pip_requirements(lock: &SkillLock) -> String {
+    lock.packages
+        .iter()
+        .map(|p| format!("{}=={} --hash={}\n", p.name, p.version, p.integrity))
+        .collect()
+}
+
+/// `package-lock.json` for `npm ci`, rooted at the env's generated `package.json`.
+pub(crate) fn package_lock_json(
+    lock: &SkillLock,
+    manifest: &serde_json::Value,
+) -> serde_json::Value {
+    let mut root = serde_json::json!({ "name": "skill-env", "version": "1.0.0" });
+    for section in shared_cache::node_manifest_sections() {
+        if section == "overrides" {
+            continue;
+        }
+        if let Some(value) = manifest.get(section) {
+            root[section] = value.clone();
+        }
+    }
+    let mut packages = serde_json::Map::new();
+    packages.insert(String::new(), root);
+    for p in &lock.packages {
+        if let (Some(path), Some(entry)) = (&p.path, &p.npm_entry) {
+            packages.insert(path.clone(), entry.clone());
+        }
+    }
+    serde_json::json!({
+        "name": "skill-env",
+        "version": "1.0.0",
+        "lockfileVersion": 3,
+        "requires": true,
+        "packages": packages,
+    })
+}
+
+/// Differences between the packages installed in `env_path` and the lock.
+pub fn check_environment(env_path: &Path, lock: &SkillLock) -> Vec<String> {
+    let mut problems = Vec::new();
+    if lock.language == "node" {
+        for p in &lock.packages {
+            let Some(path) = &p.path else { continue };
+            let installed = std::fs::read_to_string(env_path.join(path).join("package.json"))
+                .ok()
+                .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+                .and_then(|m| m.get("version")?.as_str().map(str::to_string));
+            let dev_or_optional = p.npm_entry.as_ref().is_some_and(|e| {
+                e.get("dev").and_then(|v| v.as_bool()) == Some(true)
+                    || e.get("optional").and_then(|v| v.as_bool()) == Some(true)
+            });
+            match installed {
+                Some(v) if v != p.version => {
+                    problems.push(format!("{}: installed {}, locked {}", p.name, v, p.version))
+                }
+                None if !dev_or_optional => {
+                    problems.push(format!("{}: not installed (locked {})", p.name, p.version))
+                }
+                _ => {}
+            }
+        }
+        return problems;
+    }
+
+    let installed = installed_python_packages(env_path);
+    for p in &lock.packages {
+        match installed
+            .iter()
+            .find(|(name, _)| *name == normalize_pypi_name(&p.name))
+        {
+            Some((_, v)) if *v != p.version => {
+                problems.push(format!("{}: installed {}, locked {}", p.name, v, p.version))
+            }
+            None => problems.push(format!("{}: not installed (locked {})", p.name, p.version)),
+            _ => {}
+        }
+    }
+    problems
+}
+
+/// (normalized name, version) from the venv's `*.dist-info` directories.
+fn installed_python_packages(env_path: &Path) -> Vec<(String, String)> {
+    let lib_dirs: Vec<PathBuf> = std::fs::read_dir(env_path.join("lib"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path().join("site-packages"))
+        .chain(std::iter::once(env_path.join("Lib").join("site-packages")))
+        .collect();
+    lib_dirs
+        .iter()
+        .flat_map(|dir| std::fs::read_dir(dir).into_iter().flatten().flatten())
+        .filter_map(|e| {
+            let file_name = e.file_name().to_string_lossy().into_owned();
+            let stem = file_name.strip_suffix(".dist-info")?;
+            let (name, version) = stem.rsplit_once('-')?;
+            Some((normalize_pypi_name(name), version.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PIP_REPORT: &str = r#"{
+  "version": "1",
+  "install": [
+    {
+      "download_info": {
+        "url": "https://files.pythonhosted.org/packages/requests-2.31.0-py3-none-any.whl",
+        "archive_info": {
+          "hash": "sha256=58cd2187c01e70e6e26505bca751777aa9f2ee0b7f4300988b709f44e013003f",
+          "hashes": {"sha256": "58cd2187c01e70e6e26505bca751777aa9f2ee0b7f4300988b709f44e013003f"}
+        }
+      },
+      "requested": true,
+      "metadata": {"name": "requests", "version": "2.31.0"}
+    },
+    {
+      "download_info": {
+        "url": "https://files.pythonhosted.org/packages/idna-3.6-py3-none-any.whl",
+        "archive_info": {"hash": "sha256=c05567e9c24a6b9faaa835c4821bad0590fbb9d5779e7caa6e1cc4978e7eb24f"}
+      },
+      "requested": false,
+      "metadata": {"name": "idna", "version": "3.6"}
+    }
+  ]
+}"#;
+
+    const PACKAGE_LOCK: &str = r#"{
+  "name": "skill-env",
+  "version": "1.0.0",
+  "lockfileVersion": 3,
+  "requires": true,
+  "packages": {
+    "": {"name": "skill-env", "version": "1.0.0", "dependencies": {"left-pad": "^1.3.0", "ms": "2.1.3"}},
+    "node_modules/left-pad": {
+      "version": "1.3.0",
+      "resolved": "https://registry.npmjs.org/left-pad/-/left-pad-1.3.0.tgz",
+      "integrity": "sha512-XI5MPzVNApjAyhQzphX8BkmKsKUxD4LdyK24iZeQEQlbqWuRh9I5aqDeyHmCbrPANWWiyCktRd9tk9NgDEP/MQ=="
+    },
+    "node_modules/ms": {
+      "version": "2.1.3",
+      "resolved": "https://registry.npmjs.org/ms/-/ms-2.1.3.tgz",
+      "integrity": "sha512-6FlzubTLZG3J2a/NVCAleEhjzq5oxgHyaCU9yYXvcLsvoVaHJq/s5xXI6/XXP6tz7R9xAOtHnSO/tXtF3WRTlA=="
+    }
+  }
+}"#;
+
+    fn python_skill(requirements: &str) -> (TempDir, EnvSpec) {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("requirements.txt"), requirements).unwrap();
+        let spec = EnvSpec {
+            language: "python".to_string(),
+            name: Some("fetch".to_string()),
+            compatibility: None,
+            resolved_packages: None,
+        };
+        (dir, spec)
+    }
+
+    fn node_skill(package_json: &str) -> (TempDir, EnvSpec) {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("package.json"), package_json).unwrap();
+        let spec = EnvSpec {
+            language: "node".to_string(),
+            name: Some("pad".to_string()),
+            compatibility: None,
+            resolved_packages: None,
+        };
+        (dir, spec)
+    }
+
+    #[test]
+    fn python_lock_from_pip_report_round_trips_and_installs_by_hash() {
+        let (skill, spec) = python_skill("requests\n# http\n");
+        let declared = declared_dependencies(skill.path(), &spec).unwrap();
+        assert_eq!(declared, ["requests"]);
+        let lock = SkillLock::new(
+            "python",
+            declared.clone(),
+            packages_from_pip_report(PIP_REPORT).unwrap(),
+        );
+        write(skill.path(), &lock).unwrap();
+
+        let loaded = load(skill.path()).unwrap().unwrap();
+        assert_eq!(loaded, lock);
+        assert_eq!(loaded.packages[0].name, "idna");
+        assert_eq!(
+            loaded.packages[1].integrity,
+            "sha256:58cd2187c01e70e6e26505bca751777aa9f2ee0b7f4300988b709f44e013003f"
+        );
+        check_declared(skill.path(), &loaded, &declared).unwrap();
+        assert_eq!(
+            pip_requirements(&loaded).lines().nth(1),
+            Some(
+                "requests==2.31.0 \
+                 --hash=sha256:58cd2187c01e70e6e26505bca751777aa9f2ee0b7f4300988b709f44e013003f"
+            )
+        );
+    }
+
+    #[test]
+    fn node_lock_from_package_lock_rebuilds_npm_ci_input() {
+        let (skill, spec) =
+            node_skill(r#"{"name": "pad", "dependencies": {"left-pad": "^1.3.0", "ms": "2.1.3"}}"#);
+        let declared = declared_dependencies(skill.path(), &spec).unwrap();
+        assert_eq!(declared, ["left-pad@^1.3.0", "ms@2.1.3"]);
+        let lock = SkillLock::new(
+            "node",
+            declared.clone(),
+            packages_from_package_lock(PACKAGE_LOCK).unwrap(),
+        );
+        write(skill.path(), &lock).unwrap();
+        let loaded = load(skill.path()).unwrap().unwrap();
+        check_declared(skill.path(), &loaded, &declared).unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(r#"{"dependencies": {"left-pad": "^1.3.0", "ms": "2.1.3"}}"#)
+                .unwrap();
+        let npm_lock = package_lock_json(&loaded, &manifest);
+        assert_eq!(
+            npm_lock.pointer("/packages/node_modules~1ms/integrity"),
+            Some(&serde_json::json!(
+                "sha512-6FlzubTLZG3J2a/NVCAleEhjzq5oxgHyaCU9yYXvcLsvoVaHJq/s5xXI6/XXP6tz7R9xAOtHnSO/tXtF3WRTlA=="
+            ))
+        );
+        assert_eq!(
+            npm_lock.pointer("/packages//dependencies/left-pad"),
+            Some(&serde_json::json!("^1.3.0"))
+        );
+    }
+
+    #[test]
+    fn lock_conflicting_with_declared_dependencies_is_rejected() {
+        let (skill, _) = python_skill("requests\n");
+        let lock = SkillLock::new(
+            "python",
+            vec!["requests".to_string()],
+            packages_from_pip_report(PIP_REPORT).unwrap(),
+        );
+
+        let pinned = ["requests==2.32.0".to_string()];
+        let err = check_declared(skill.path(), &lock, &pinned).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("pinned to 2.32.0 but locked at 2.31.0"));
+        assert!(err.to_string().contains("--relock"));
+
+        let added = ["pyyaml".to_string(), "requests".to_string()];
+        let err = check_declared(skill.path(), &lock, &added).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("'pyyaml' is declared but not locked"));
+
+        let node = SkillLock::new(
+            "node",
+            vec!["ms@2.1.3".to_string()],
+            packages_from_package_lock(PACKAGE_LOCK).unwrap(),
+        );
+        let err = check_declared(skill.path(), &node, &["ms@2.1.2".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("locked at 2.1.3"));
+    }
+
+    #[test]
+    fn tampered_lock_is_rejected() {
+        let (skill, _) = python_skill("requests\n");
+        let lock = SkillLock::new(
+            "python",
+            vec!["requests".to_string()],
+            packages_from_pip_report(PIP_REPORT).unwrap(),
+        );
+        write(skill.path(), &lock).unwrap();
+
+        let content = std::fs::read_to_string(lock_path(skill.path())).unwrap();
+        std::fs::write(
+            lock_path(skill.path()),
+            content.replace("\"2.31.0\"", "\"2.19.0\""),
+        )
+        .unwrap();
+        let err = load(skill.path()).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{}", err);
+    }
+
+    #[test]
+    fn unhashed_packages_cannot_be_locked() {
+        let report = r#"{"install": [{"metadata": {"name": "tool", "version": "0.1"},
+                          "download_info": {"url": "file:///src/tool", "dir_info": {}}}]}"#;
+        assert!(packages_from_pip_report(report).is_err());
+        let lock = r#"{"lockfileVersion": 3, "packages": {"node_modules/x":
+                        {"version": "1.0.0", "resolved": "git+ssh://git@example.com/x.git"}}}"#;
+        assert!(packages_from_package_lock(lock).is_err());
+    }
+
+    #[test]
+    fn environment_drift_is_reported() {
+        let lock = SkillLock::new(
+            "python",
+            vec!["requests".to_string()],
+            packages_from_pip_report(PIP_REPORT).unwrap(),
+        );
+        let env = TempDir::new().unwrap();
+        let site = env
+            .path()
+            .join("lib")
+            .join("python3.12")
+            .join("site-packages");
+        std::fs::create_dir_all(site.join("requests-2.30.0.dist-info")).unwrap();
+        std::fs::create_dir_all(site.join("pip-24.0.dist-info")).unwrap();
+
+        assert_eq!(
+            check_environment(env.path(), &lock),
+            [
+                "idna: not installed (locked 3.6)",
+                "requests: installed 2.30.0, locked 2.31.0"
+            ]
+        );
+    }
+}
@@ -5,4 +5,7 @@
 //! P0: 优先用系统 Python/Node，系统没有则首次下载到 ~/.skilllite/runtime/，过程透明。
 
 pub mod builder;
+pub mod interpreters;
+pub mod lockfile;
 pub mod runtime_deps;
+pub mod shared_cache;
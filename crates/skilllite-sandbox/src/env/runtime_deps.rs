@@ -88,6 +88,16 @@ impl RuntimeDownloadRequest {
 /// without confirmation (or use env `SKILLLITE_AUTO_APPROVE_RUNTIME=1` when using a prompt).
 pub type RuntimeConfirmDownloadFn = Option<Box<dyn Fn(&RuntimeDownloadRequest) -> bool + Send>>;
 
+/// Build a default CLI progress callback: prints environment-setup steps to stderr as
+/// `⏳ <step>` lines, only when stderr is a terminal (piped runs stay quiet).
+pub fn cli_progress() -> RuntimeProgressFn {
+    use std::io::IsTerminal;
+    if !std::io::stderr().is_terminal() {
+        return None;
+    }
+    Some(Box::new(|msg: &str| eprintln!("⏳ {}", msg)))
+}
+
 /// Build a default CLI confirm callback: prints a prompt to stderr, reads Y/n from stdin.
 /// If `SKILLLITE_AUTO_APPROVE_RUNTIME=1` is set, auto-approves without prompting.
 pub fn cli_confirm_download() -> RuntimeConfirmDownloadFn {
@@ -657,6 +667,14 @@ pub fn which_node() -> Option<PathBuf> {
     }
 }
 
+/// Major version reported by `<node> --version`.
+pub fn node_major_version(node: &Path) -> Option<u32> {
+    let mut cmd = Command::new(node);
+    hide_child_console(&mut cmd);
+    let out = cmd.arg("--version").output().ok()?;
+    parse_node_version(std::str::from_utf8(&out.stdout).ok()?)
+}
+
 /// Returns system npm path if present (and node is usable); otherwise None.
 pub fn which_npm() -> Option<PathBuf> {
     which_node()?;
@@ -0,0 +1,588 @@
+//! Content-addressed environment cache shared across skills.
+//!
+//! Layout under the envs cache dir:
+//!
+//! ```text
+//! envs/<hash>/                  shared venv / node_modules (hash of deps + runtime version)
+//! envs/<hash>/.skilllite_env.json  completion marker, written last
+//! envs/.refs/<skill-key>.json   which env a skill last resolved to
+//! envs/.locks/<hash>.lock       held while an env is being built
+//! ```
+//!
+//! Directories without a marker are legacy per-skill envs (keyed by skill path); they
+//! keep working and are never touched by [`unused_envs`].
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use skilllite_core::EnvSpec;
+
+use crate::error::bail;
+use crate::Result;
+
+/// Completion marker inside a shared env.
+pub const ENV_MARKER: &str = ".skilllite_env.json";
+const REFS_DIR: &str = ".refs";
+const LOCKS_DIR: &str = ".locks";
+/// A build lock not refreshed for this long is assumed to belong to a crashed process.
+/// Holders refresh it every [`LOCK_HEARTBEAT`], so this stays well below the builder's wait
+/// timeout however long the build itself takes.
+const STALE_LOCK_AFTER: Duration = Duration::from_secs(2 * 60);
+const LOCK_HEARTBEAT: Duration = Duration::from_secs(20);
+const LOCK_POLL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvMarker {
+    pub language: String,
+    pub runtime: String,
+    /// Interpreter selected for a skill's version constraint (absent for the default runtime)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<PathBuf>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EnvRef {
+    skill_dir: PathBuf,
+    language: String,
+    env: String,
+}
+
+/// Hash the resolved dependency set: language, runtime version, the normalized
+/// dependency declarations and the `skill.lock` checksum. The skill path is deliberately
+/// not part of the key.
+pub fn dependency_hash(skill_dir: &Path, spec: &EnvSpec, runtime: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"skilllite-env-v1\0");
+    hasher.update(spec.language.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(runtime.as_bytes());
+    hasher.update(b"\0");
+    for line in dependency_lines(skill_dir, spec) {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Sorted, comment-free dependency declarations for the hash.
+fn dependency_lines(skill_dir: &Path, spec: &EnvSpec) -> Vec<String> {
+    let read = |name: &str| std::fs::read_to_string(skill_dir.join(name)).ok();
+    let read_json =
+        |name: &str| read(name).and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok());
+    let mut lines: Vec<String> = spec
+        .resolved_packages
+        .iter()
+        .flatten()
+        .map(|p| format!("pkg:{}", p.trim()))
+        .collect();
+    if spec.language == "python" {
+        if let Some(content) = read("requirements.txt") {
+            lines.extend(
+                content
+                    .lines()
+                    .map(|l| l.split('#').next().unwrap_or("").trim())
+                    .filter(|l| !l.is_empty())
+                    .map(|l| format!("req:{}", l)),
+            );
+        }
+    } else if spec.language == "node" {
+        if let Some(manifest) = read_json("package.json") {
+            for section in node_manifest_sections() {
+                if let Some(value) = manifest.get(section) {
+                    lines.push(format!("{}:{}", section, value));
+                }
+            }
+        }
+        if let Some(mut lock) = read_json("package-lock.json") {
+            // The root entry carries the skill's own name/version; only installed packages matter
+            if let Some(obj) = lock.as_object_mut() {
+                obj.remove("name");
+                obj.remove("version");
+                if let Some(packages) = obj.get_mut("packages").and_then(|p| p.as_object_mut()) {
+                    packages.remove("");
+                }
+            }
+            lines.push(format!("lock:{}", lock));
+        }
+    }
+    // Skills locked to different package sets must not share an env
+    if let Ok(Some(lock)) = super::lockfile::load(skill_dir) {
+        lines.push(format!("skill.lock:{}", lock.checksum));
+    }
+    lines.sort();
+    lines.dedup();
+    lines
+}
+
+/// `package.json` fields that affect what `npm install` produces.
+pub(crate) fn node_manifest_sections() -> [&'static str; 4] {
+    [
+        "dependencies",
+        "devDependencies",
+        "optionalDependencies",
+        "overrides",
+    ]
+}
+
+/// `true` once the env at `env_path` finished building.
+pub fn is_ready(env_path: &Path) -> bool {
+    env_path.join(ENV_MARKER).is_file()
+}
+
+pub(crate) fn write_marker(
+    env_path: &Path,
+    language: &str,
+    runtime: &str,
+    interpreter: Option<&Path>,
+) -> Result<()> {
+    let marker = EnvMarker {
+        language: language.to_string(),
+        runtime: runtime.to_string(),
+        interpreter: interpreter.map(Path::to_path_buf),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    std::fs::write(
+        env_path.join(ENV_MARKER),
+        serde_json::to_vec_pretty(&marker).context("Serialize env marker")?,
+    )
+    .context("Write env marker")?;
+    Ok(())
+}
+
+/// Exclusive build lock for one env hash (`create_new` lock file holding the owner pid,
+/// its mtime refreshed by a heartbeat thread while held, removed on drop).
+pub(crate) struct EnvBuildLock {
+    path: PathBuf,
+    heartbeat: Option<(mpsc::Sender<()>, std::thread::JoinHandle<()>)>,
+}
+
+impl EnvBuildLock {
+    /// Wait until no other process is building `hash`, then take the lock. `on_wait` is
+    /// called once if the lock is held by someone else.
+    pub(crate) fn acquire(
+        envs_dir: &Path,
+        hash: &str,
+        timeout: Duration,
+        on_wait: impl FnOnce(),
+    ) -> Result<Self> {
+        let dir = envs_dir.join(LOCKS_DIR);
+        std::fs::create_dir_all(&dir).context("Create env lock dir")?;
+        let path = dir.join(format!("{}.lock", hash));
+        let start = Instant::now();
+        let mut on_wait = Some(on_wait);
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut f) => {
+                    let _ = write!(f, "{}", std::process::id());
+                    let heartbeat = spawn_heartbeat(f);
+                    return Ok(Self { path, heartbeat });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if lock_is_stale(&path) {
+                        tracing::warn!("Removing stale env build lock {}", path.display());
+                        let _ = std::fs::remove_file(&path);
+                        continue;
+                    }
+                    if let Some(on_wait) = on_wait.take() {
+                        on_wait();
+                    }
+                    if start.elapsed() >= timeout {
+                        bail!(
+                            "Timed out waiting for another process to build environment {}",
+                            hash
+                        );
+                    }
+                    std::thread::sleep(LOCK_POLL);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for EnvBuildLock {
+    fn drop(&mut self) {
+        if let Some((stop, handle)) = self.heartbeat.take() {
+            drop(stop);
+            let _ = handle.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Touch the lock file every [`LOCK_HEARTBEAT`] until the returned sender is dropped.
+/// Without the thread the lock still works, it just looks stale after [`STALE_LOCK_AFTER`].
+fn spawn_heartbeat(file: File) -> Option<(mpsc::Sender<()>, std::thread::JoinHandle<()>)> {
+    let (stop, stopped) = mpsc::channel::<()>();
+    let handle = std::thread::Builder::new()
+        .name("env-lock-heartbeat".into())
+        .spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(LOCK_HEARTBEAT) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+        })
+        .ok()?;
+    Some((stop, handle))
+}
+
+/// Stale when its owner process is gone (interrupted build) or it has not been refreshed
+/// for [`STALE_LOCK_AFTER`] (owner hung, or a platform without a liveness check).
+fn lock_is_stale(path: &Path) -> bool {
+    let owner_gone = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|pid| pid.trim().parse::<u32>().ok())
+        .is_some_and(|pid| !process_alive(pid));
+    owner_gone
+        || std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| SystemTime::now().duration_since(t).ok())
+            .map(|age| age > STALE_LOCK_AFTER)
+            .unwrap_or(false)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn process_alive(pid: u32) -> bool {
+    use nix::errno::Errno;
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+    let Ok(pid) = i32::try_from(pid) else {
+        return true;
+    };
+    !matches!(kill(Pid::from_raw(pid), None), Err(Errno::ESRCH))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+fn ref_path(envs_dir: &Path, skill_dir: &Path, language: &str) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(canonical(skill_dir).to_string_lossy().as_bytes());
+    hasher.update(language.as_bytes());
+    let key = hex::encode(hasher.finalize());
+    envs_dir.join(REFS_DIR).join(format!("{}.json", &key[..16]))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Record that `skill_dir` uses the shared env `hash` (skipped when unchanged).
+pub(crate) fn record_reference(envs_dir: &Path, skill_dir: &Path, language: &str, hash: &str) {
+    let path = ref_path(envs_dir, skill_dir, language);
+    let current = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<EnvRef>(&c).ok());
+    if current.as_ref().map(|r| r.env.as_str()) == Some(hash) {
+        return;
+    }
+    let r = EnvRef {
+        skill_dir: canonical(skill_dir),
+        language: language.to_string(),
+        env: hash.to_string(),
+    };
+    let written = path
+        .parent()
+        .map(std::fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| std::fs::write(&path, serde_json::to_vec(&r).unwrap_or_default()));
+    if let Err(e) = written {
+        tracing::debug!("Failed to record env reference {}: {}", path.display(), e);
+    }
+}
+
+/// The env `skill_dir` last resolved to, if it still exists.
+pub fn referenced_env(envs_dir: &Path, skill_dir: &Path, language: &str) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(ref_path(envs_dir, skill_dir, language)).ok()?;
+    let r = serde_json::from_str::<EnvRef>(&content).ok()?;
+    let path = envs_dir.join(r.env);
+    path.is_dir().then_some(path)
+}
+
+/// One cached environment directory.
+#[derive(Debug, Clone)]
+pub struct CachedEnv {
+    pub path: PathBuf,
+    /// `true` for content-addressed envs, `false` for legacy per-skill envs
+    pub shared: bool,
+    /// Skill directories (still present on disk) whose last resolution used this env
+    pub referenced_by: Vec<PathBuf>,
+}
+
+/// List env directories under `envs_dir` (internal `.refs` / `.locks` excluded).
+pub fn list_envs(envs_dir: &Path) -> Vec<CachedEnv> {
+    let refs = live_references(envs_dir);
+    let mut envs: Vec<CachedEnv> = std::fs::read_dir(envs_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter(|p| {
+            !p.file_name()
+                .map(|n| n.to_string_lossy().starts_with('.'))
+                .unwrap_or(true)
+        })
+        .map(|path| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let referenced_by = refs
+                .iter()
+                .filter(|r| r.env == name)
+                .map(|r| r.skill_dir.clone())
+                .collect();
+            CachedEnv {
+                shared: is_ready(&path),
+                path,
+                referenced_by,
+            }
+        })
+        .collect();
+    envs.sort_by(|a, b| a.path.cmp(&b.path));
+    envs
+}
+
+/// Shared envs not referenced by any skill that still exists. Legacy envs are never
+/// returned since their owning skill is unknown.
+pub fn unused_envs(envs_dir: &Path) -> Vec<CachedEnv> {
+    list_envs(envs_dir)
+        .into_iter()
+        .filter(|e| e.shared && e.referenced_by.is_empty())
+        .collect()
+}
+
+/// A shared environment a skill resolved to, as seen before uninstalling it.
+#[derive(Debug, Clone)]
+pub struct SkillEnv {
+    pub path: PathBuf,
+    pub language: String,
+    /// Other existing skills whose last resolution also used this env; it must be kept
+    pub shared_with: Vec<PathBuf>,
+}
+
+/// Shared envs referenced by `skill_dir` (call while the skill still exists).
+pub fn skill_envs(envs_dir: &Path, skill_dir: &Path) -> Vec<SkillEnv> {
+    let own = canonical(skill_dir);
+    let live = live_references(envs_dir);
+    read_references(envs_dir)
+        .into_iter()
+        .map(|(_, r)| r)
+        .filter(|r| r.skill_dir == own)
+        .filter_map(|r| {
+            let path = envs_dir.join(&r.env);
+            path.is_dir().then(|| SkillEnv {
+                shared_with: live
+                    .iter()
+                    .filter(|o| o.env == r.env && o.skill_dir != own)
+                    .map(|o| o.skill_dir.clone())
+                    .collect(),
+                path,
+                language: r.language,
+            })
+        })
+        .collect()
+}
+
+/// Delete reference records whose skill directory no longer exists; returns how many.
+pub fn prune_references(envs_dir: &Path) -> usize {
+    let mut removed = 0;
+    for (path, r) in read_references(envs_dir) {
+        if !r.skill_dir.exists() && std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+fn live_references(envs_dir: &Path) -> Vec<EnvRef> {
+    read_references(envs_dir)
+        .into_iter()
+        .map(|(_, r)| r)
+        .filter(|r| r.skill_dir.exists())
+        .collect()
+}
+
+fn read_references(envs_dir: &Path) -> Vec<(PathBuf, EnvRef)> {
+    std::fs::read_dir(envs_dir.join(REFS_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let path = e.path();
+            let content = std::fs::read_to_string(&path).ok()?;
+            let r = serde_json::from_str::<EnvRef>(&content).ok()?;
+            Some((path, r))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn spec(language: &str) -> EnvSpec {
+        EnvSpec {
+            language: language.to_string(),
+            name: None,
+            compatibility: None,
+            resolved_packages: None,
+        }
+    }
+
+    #[test]
+    fn hash_ignores_skill_path_comments_and_order() {
+        let a = TempDir::new().unwrap();
+        let b = TempDir::new().unwrap();
+        std::fs::write(
+            a.path().join("requirements.txt"),
+            "requests==2.31.0\nbeautifulsoup4\n",
+        )
+        .unwrap();
+        std::fs::write(
+            b.path().join("requirements.txt"),
+            "# scraping\nbeautifulsoup4\nrequests==2.31.0  # http\n",
+        )
+        .unwrap();
+        let py = spec("python");
+        assert_eq!(
+            dependency_hash(a.path(), &py, "3.12"),
+            dependency_hash(b.path(), &py, "3.12")
+        );
+        assert_ne!(
+            dependency_hash(a.path(), &py, "3.12"),
+            dependency_hash(a.path(), &py, "3.11")
+        );
+    }
+
+    #[test]
+    fn node_hash_ignores_package_name() {
+        let a = TempDir::new().unwrap();
+        let b = TempDir::new().unwrap();
+        std::fs::write(
+            a.path().join("package.json"),
+            r#"{"name": "skill-a", "dependencies": {"axios": "^1.6.0"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            b.path().join("package.json"),
+            r#"{"name": "skill-b", "dependencies": {"axios": "^1.6.0"}}"#,
+        )
+        .unwrap();
+        let node = spec("node");
+        assert_eq!(
+            dependency_hash(a.path(), &node, "20"),
+            dependency_hash(b.path(), &node, "20")
+        );
+    }
+
+    #[test]
+    fn build_lock_is_exclusive() {
+        let envs = TempDir::new().unwrap();
+        let short = Duration::from_millis(300);
+        let held =
+            EnvBuildLock::acquire(envs.path(), "abc", Duration::from_secs(1), || {}).unwrap();
+        let mut waited = false;
+        assert!(EnvBuildLock::acquire(envs.path(), "abc", short, || waited = true).is_err());
+        assert!(waited);
+        assert!(EnvBuildLock::acquire(envs.path(), "other", short, || {}).is_ok());
+        drop(held);
+        assert!(EnvBuildLock::acquire(envs.path(), "abc", short, || {}).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn build_lock_of_dead_process_is_taken_over() {
+        let envs = TempDir::new().unwrap();
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        let dir = envs.path().join(LOCKS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("abc.lock"), pid.to_string()).unwrap();
+        assert!(
+            EnvBuildLock::acquire(envs.path(), "abc", Duration::from_millis(300), || {}).is_ok()
+        );
+    }
+
+    #[test]
+    fn build_lock_without_heartbeat_is_taken_over() {
+        let envs = TempDir::new().unwrap();
+        let dir = envs.path().join(LOCKS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("abc.lock");
+        std::fs::write(&path, std::process::id().to_string()).unwrap();
+        let old = SystemTime::now() - STALE_LOCK_AFTER - Duration::from_secs(1);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        assert!(
+            EnvBuildLock::acquire(envs.path(), "abc", Duration::from_millis(300), || {}).is_ok()
+        );
+    }
+
+    #[test]
+    fn unused_envs_respects_references_and_skips_legacy() {
+        let envs = TempDir::new().unwrap();
+        let skill = TempDir::new().unwrap();
+        for name in ["used", "orphan"] {
+            let dir = envs.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            write_marker(&dir, "python", "3.12", None).unwrap();
+        }
+        std::fs::create_dir_all(envs.path().join("legacy")).unwrap();
+        record_reference(envs.path(), skill.path(), "python", "used");
+
+        let all = list_envs(envs.path());
+        assert_eq!(all.len(), 3);
+        let unused: Vec<_> = unused_envs(envs.path())
+            .into_iter()
+            .map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(unused, vec!["orphan".to_string()]);
+
+        // Once the skill is gone its env becomes unused and the reference prunable
+        let skill_path = skill.path().to_path_buf();
+        drop(skill);
+        assert!(!skill_path.exists());
+        assert_eq!(unused_envs(envs.path()).len(), 2);
+        assert_eq!(prune_references(envs.path()), 1);
+    }
+
+    #[test]
+    fn skill_envs_reports_other_users() {
+        let envs = TempDir::new().unwrap();
+        let a = TempDir::new().unwrap();
+        let b = TempDir::new().unwrap();
+        for name in ["mine", "common"] {
+            let dir = envs.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            write_marker(&dir, "python", "3.12", None).unwrap();
+        }
+        record_reference(envs.path(), a.path(), "python", "mine");
+        record_reference(envs.path(), a.path(), "node", "common");
+        record_reference(envs.path(), b.path(), "node", "common");
+
+        let mut found = skill_envs(envs.path(), a.path());
+        found.sort_by(|x, y| x.path.cmp(&y.path));
+        assert_eq!(found.len(), 2);
+        assert!(found[0].path.ends_with("common"));
+        assert_eq!(found[0].shared_with, vec![canonical(b.path())]);
+        assert!(found[1].path.ends_with("mine"));
+        assert!(found[1].shared_with.is_empty());
+    }
+}
@@ -1,8 +1,10 @@
 //! Crate-level error type for `skilllite-sandbox`.
 
+use serde::Serialize;
 use thiserror::Error;
 
 use crate::bash_validator::BashValidationError;
+use crate::runner::LimitEnforcement;
 
 /// Unified error for sandbox operations.
 #[derive(Debug, Error)]
@@ -24,6 +26,14 @@ pub enum Error {
     #[error("{0}")]
     Validation(String),
 
+    /// A skill run that failed for a classified reason. `message` is the legacy human-readable
+    /// text (unchanged, so string-matching callers keep working); `error` is the structured kind.
+    #[error("{message}")]
+    Run {
+        error: SandboxError,
+        message: String,
+    },
+
     /// Catch-all for internal `anyhow` usage during gradual migration.
     #[error(transparent)]
     Other(#[from] anyhow::Error),
@@ -36,6 +46,189 @@ impl Error {
     pub fn validation(msg: impl Into<String>) -> Self {
         Error::Validation(msg.into())
     }
+
+    pub fn run(error: SandboxError, message: impl Into<String>) -> Self {
+        Error::Run {
+            error,
+            message: message.into(),
+        }
+    }
+
+    /// Structured kind of a failed skill run, if this error carries one.
+    pub fn sandbox_error(&self) -> Option<&SandboxError> {
+        match self {
+            Error::Run { error, .. } => Some(error),
+            Error::Other(e) => e.downcast_ref::<Error>().and_then(Error::sandbox_error),
+            _ => None,
+        }
+    }
+}
+
+/// Prefix of the stderr line written when the runner kills a skill for exceeding its timeout.
+pub const KILLED_TIMEOUT_PREFIX: &str = "Process killed: exceeded timeout";
+/// Prefix of the stderr line written when the runner kills a skill for exceeding its memory limit.
+pub const KILLED_MEMORY_PREFIX: &str = "Process killed: memory usage";
+/// Prefix of the stderr line written when the kernel OOM killer ended a run inside its cgroup.
+pub const KILLED_OOM_PREFIX: &str = "Process killed: cgroup OOM killer";
+
+/// Max chars of stderr / stdout kept in [`SandboxError`] details.
+const DETAIL_TAIL_CHARS: usize = 500;
+
+/// Why a skill run failed. Serialized as `{"kind": "...", "details": {...}}` into the
+/// `error` field of CLI / stdio-RPC / MCP results.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "details", rename_all = "snake_case")]
+pub enum SandboxError {
+    /// Pre-spawn static scan found Critical issues in the entry script.
+    ScanBlocked { findings: Vec<String> },
+    /// Pre-launch check found links escaping the skill/env dirs or an entry script swapped
+    /// after the scan (see [`crate::move_protection`]).
+    IntegrityViolation { findings: Vec<String> },
+    /// The user (or a non-TTY stdin) declined the precheck confirmation.
+    ConsentDenied,
+    /// Outbound network was refused (proxy allowlist or network disabled).
+    NetworkDenied { host: Option<String> },
+    /// Killed after `limit_secs` seconds.
+    Timeout { limit_secs: u64 },
+    /// Killed for exceeding `limit_mb` MB. `kill_reason` is `oom_kill` (cgroup) or
+    /// `memory_limit` (RSS polling); `enforcement` names the backend that applied the limit.
+    /// `stdout_tail` keeps whatever the script printed before it was killed.
+    MemoryExceeded {
+        limit_mb: u64,
+        kill_reason: &'static str,
+        enforcement: LimitEnforcement,
+        #[serde(skip_serializing_if = "String::is_empty")]
+        stdout_tail: String,
+    },
+    /// No interpreter satisfies the skill's runtime constraint.
+    MissingRuntime { need: String, found: Option<String> },
+    /// The configured entry script does not exist.
+    EntryPointMissing { entry_point: String },
+    /// The script exited non-zero for any other reason.
+    NonZeroExit { code: i32, stderr_tail: String },
+    /// The script exited 0 but stdout was not valid JSON.
+    InvalidOutput { output_tail: String },
+}
+
+impl SandboxError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SandboxError::ScanBlocked { .. } => "scan_blocked",
+            SandboxError::IntegrityViolation { .. } => "integrity_violation",
+            SandboxError::ConsentDenied => "consent_denied",
+            SandboxError::NetworkDenied { .. } => "network_denied",
+            SandboxError::Timeout { .. } => "timeout",
+            SandboxError::MemoryExceeded { .. } => "memory_exceeded",
+            SandboxError::MissingRuntime { .. } => "missing_runtime",
+            SandboxError::EntryPointMissing { .. } => "entry_point_missing",
+            SandboxError::NonZeroExit { .. } => "non_zero_exit",
+            SandboxError::InvalidOutput { .. } => "invalid_output",
+        }
+    }
+
+    /// `{"kind", "details"}` object for JSON outputs.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({ "kind": self.kind() }))
+    }
+
+    /// Classify a finished run that exited non-zero from its exit code and stderr.
+    pub fn from_failed_run(
+        exit_code: i32,
+        stdout: &str,
+        stderr: &str,
+        timeout_secs: u64,
+        max_memory_mb: u64,
+        network_enabled: bool,
+        enforcement: LimitEnforcement,
+    ) -> Self {
+        if stderr.contains(KILLED_TIMEOUT_PREFIX) {
+            return SandboxError::Timeout {
+                limit_secs: timeout_secs,
+            };
+        }
+        if stderr.contains(KILLED_OOM_PREFIX) {
+            return SandboxError::MemoryExceeded {
+                limit_mb: max_memory_mb,
+                kill_reason: "oom_kill",
+                enforcement,
+                stdout_tail: tail_chars(stdout.trim(), DETAIL_TAIL_CHARS),
+            };
+        }
+        if stderr.contains(KILLED_MEMORY_PREFIX) {
+            return SandboxError::MemoryExceeded {
+                limit_mb: max_memory_mb,
+                kill_reason: "memory_limit",
+                enforcement,
+                stdout_tail: tail_chars(stdout.trim(), DETAIL_TAIL_CHARS),
+            };
+        }
+        if stderr.contains("Domain not in allowlist")
+            || (!network_enabled && looks_like_network_failure(stderr))
+        {
+            return SandboxError::NetworkDenied {
+                host: extract_host(stderr),
+            };
+        }
+        SandboxError::NonZeroExit {
+            code: exit_code,
+            stderr_tail: tail_chars(stderr.trim(), DETAIL_TAIL_CHARS),
+        }
+    }
+
+    pub fn invalid_output(output: &str) -> Self {
+        SandboxError::InvalidOutput {
+            output_tail: tail_chars(output, DETAIL_TAIL_CHARS),
+        }
+    }
+
+    /// One finding per non-empty line of the precheck report.
+    pub fn scan_blocked(review_text: Option<&str>) -> Self {
+        SandboxError::ScanBlocked {
+            findings: review_text
+                .unwrap_or_default()
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+fn looks_like_network_failure(stderr: &str) -> bool {
+    const PATTERNS: &[&str] = &[
+        "Name or service not known",
+        "Temporary failure in name resolution",
+        "nodename nor servname provided",
+        "getaddrinfo ENOTFOUND",
+        "getaddrinfo EAI_AGAIN",
+        "Network is unreachable",
+        "ECONNREFUSED",
+        "Tunnel connection failed: 403",
+    ];
+    PATTERNS.iter().any(|p| stderr.contains(p))
+}
+
+/// Best-effort host from common Python (`host='x'`) and Node (`ENOTFOUND x`) error texts.
+fn extract_host(stderr: &str) -> Option<String> {
+    let host = if let Some(i) = stderr.find("host='") {
+        stderr[i + 6..].split('\'').next()
+    } else if let Some(i) = stderr.find("ENOTFOUND ") {
+        stderr[i + 10..].split_whitespace().next()
+    } else {
+        None
+    }?;
+    let host = host.trim_matches(|c: char| !(c.is_alphanumeric() || c == '.' || c == '-'));
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+fn tail_chars(s: &str, max_chars: usize) -> String {
+    let count = s.chars().count();
+    if count <= max_chars {
+        return s.to_string();
+    }
+    let skip = count - max_chars;
+    format!("...{}", s.chars().skip(skip).collect::<String>())
 }
 
 macro_rules! bail {
@@ -44,3 +237,120 @@ macro_rules! bail {
     };
 }
 pub(crate) use bail;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_runs_are_classified_by_kill_reason_and_network_hints() {
+        let timeout = SandboxError::from_failed_run(
+            -1,
+            "",
+            "Process killed: exceeded timeout of 30 seconds",
+            30,
+            256,
+            false,
+            LimitEnforcement::RlimitPolling,
+        );
+        assert_eq!(timeout, SandboxError::Timeout { limit_secs: 30 });
+        let memory = SandboxError::from_failed_run(
+            -1,
+            "",
+            "Process killed: memory usage (300 MB) exceeded limit (256 MB)",
+            30,
+            256,
+            false,
+            LimitEnforcement::RlimitPolling,
+        );
+        assert_eq!(
+            memory,
+            SandboxError::MemoryExceeded {
+                limit_mb: 256,
+                kill_reason: "memory_limit",
+                enforcement: LimitEnforcement::RlimitPolling,
+                stdout_tail: String::new(),
+            }
+        );
+        let oom = SandboxError::from_failed_run(
+            -1,
+            "{\"progress\": 3}\n",
+            "Process killed: cgroup OOM killer (limit 256 MB)",
+            30,
+            256,
+            false,
+            LimitEnforcement::CgroupV2,
+        );
+        let json = oom.to_json();
+        assert_eq!(json["kind"], "memory_exceeded");
+        assert_eq!(json["details"]["kill_reason"], "oom_kill");
+        assert_eq!(json["details"]["enforcement"], "cgroup_v2");
+        assert_eq!(json["details"]["stdout_tail"], "{\"progress\": 3}");
+
+        let py = "urllib3.exceptions.MaxRetryError: HTTPSConnectionPool(host='api.example.com', port=443): \
+                  Failed to resolve (Temporary failure in name resolution)";
+        assert_eq!(
+            SandboxError::from_failed_run(
+                1,
+                "",
+                py,
+                30,
+                256,
+                false,
+                LimitEnforcement::RlimitPolling
+            ),
+            SandboxError::NetworkDenied {
+                host: Some("api.example.com".to_string())
+            }
+        );
+        // With network enabled a DNS failure is just a failed run.
+        assert_eq!(
+            SandboxError::from_failed_run(
+                1,
+                "",
+                py,
+                30,
+                256,
+                true,
+                LimitEnforcement::RlimitPolling
+            )
+            .kind(),
+            "non_zero_exit"
+        );
+    }
+
+    #[test]
+    fn non_zero_exit_keeps_utf8_safe_stderr_tail_and_serializes_kind_details() {
+        let stderr = "错误".repeat(400);
+        let err = SandboxError::from_failed_run(
+            2,
+            "",
+            &stderr,
+            30,
+            256,
+            false,
+            LimitEnforcement::Unenforced,
+        );
+        let SandboxError::NonZeroExit { code, stderr_tail } = &err else {
+            panic!("expected NonZeroExit, got {:?}", err);
+        };
+        assert_eq!(*code, 2);
+        assert!(stderr_tail.starts_with("..."));
+        assert_eq!(stderr_tail.chars().count(), DETAIL_TAIL_CHARS + 3);
+
+        let json = SandboxError::NetworkDenied {
+            host: Some("example.com".to_string()),
+        }
+        .to_json();
+        assert_eq!(json["kind"], "network_denied");
+        assert_eq!(json["details"]["host"], "example.com");
+        assert_eq!(
+            SandboxError::ConsentDenied.to_json()["kind"],
+            "consent_denied"
+        );
+
+        let e = Error::run(SandboxError::ConsentDenied, "blocked");
+        assert_eq!(e.to_string(), "blocked");
+        assert_eq!(e.sandbox_error(), Some(&SandboxError::ConsentDenied));
+    }
+}
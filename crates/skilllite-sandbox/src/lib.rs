@@ -2,13 +2,15 @@ pub mod bash_validator;
 pub mod common;
 pub mod error;
 
-pub use error::{Error, Result};
+pub use error::{Error, Result, SandboxError};
 pub mod env;
 pub mod log;
 pub mod move_protection;
 pub mod network_proxy;
+pub mod results;
 pub mod runner;
 pub mod runtime_resolver;
+pub mod sandbox_audit;
 pub mod sandbox_backend;
 pub mod seatbelt;
 pub mod security;
@@ -17,11 +19,15 @@ pub mod security;
 pub use env::runtime_deps::RuntimeProgressFn;
 /// 下载前确认回调与请求类型。传 `Some` 时会在下载 Python/Node 前调用，返回 `false` 则中止。
 pub use env::runtime_deps::{
-    cli_confirm_download, get_runtime_dir, probe_runtime_for_ui, provision_runtimes_to_cache,
-    ProvisionRuntimeItem, ProvisionRuntimesResult, RuntimeConfirmDownloadFn, RuntimeDownloadKind,
-    RuntimeDownloadRequest, RuntimeUiLine, RuntimeUiSnapshot,
+    cli_confirm_download, cli_progress, get_runtime_dir, probe_runtime_for_ui,
+    provision_runtimes_to_cache, ProvisionRuntimeItem, ProvisionRuntimesResult,
+    RuntimeConfirmDownloadFn, RuntimeDownloadKind, RuntimeDownloadRequest, RuntimeUiLine,
+    RuntimeUiSnapshot,
 };
 
+#[cfg(target_os = "linux")]
+pub mod cgroup;
+
 #[cfg(target_os = "linux")]
 pub mod linux;
 
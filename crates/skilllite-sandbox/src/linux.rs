@@ -1,13 +1,16 @@
 #![cfg(target_os = "linux")]
 
 use crate::common::{
-    self, apply_standard_execution_env, pipe_stdio, resolve_command_path, resolve_which,
-    spawn_write_and_wait, start_network_proxy,
+    self, apply_results_dir_env, apply_standard_execution_env, pipe_stdio, resolve_command_path,
+    resolve_script_args, resolve_which, spawn_write_and_wait, start_network_proxy,
 };
 use crate::error::bail;
-use crate::runner::{ExecutionResult, ResourceLimits, RuntimePaths, SandboxConfig};
+use crate::runner::{
+    ExecutionResult, LimitEnforcement, ResourceLimits, RuntimePaths, SandboxConfig, SandboxLevel,
+};
 use crate::runtime_resolver::{ResolvedRuntime, RuntimeResolver};
 use crate::seatbelt::{generate_firejail_blacklist_args, MANDATORY_DENY_DIRECTORIES};
+use crate::seccomp::{self, NotifySupervisor, SeccompAction, SeccompProfile, SeccompProfiles};
 use crate::security::policy::{self as security_policy};
 use anyhow::Context;
 
@@ -30,12 +33,33 @@ pub fn execute_with_limits(
     input_json: &str,
     limits: crate::runner::ResourceLimits,
 ) -> Result<ExecutionResult> {
-    if skilllite_core::config::SandboxEnvConfig::from_env().no_sandbox {
+    execute_at_level(
+        skill_dir,
+        runtime,
+        config,
+        input_json,
+        limits,
+        SandboxLevel::default(),
+    )
+}
+
+/// [`execute_with_limits`] with the seccomp profile that `level` maps to
+/// (see [`crate::seccomp::SeccompProfiles`]).
+pub fn execute_at_level(
+    skill_dir: &Path,
+    runtime: &RuntimePaths,
+    config: &SandboxConfig,
+    input_json: &str,
+    limits: crate::runner::ResourceLimits,
+    level: SandboxLevel,
+) -> Result<ExecutionResult> {
+    if skilllite_core::config::SandboxEnvConfig::from_env().no_sandbox && !config.enforce_isolation
+    {
         tracing::warn!("Sandbox disabled via SKILLLITE_NO_SANDBOX - running without protection");
         return execute_simple_with_limits(skill_dir, runtime, config, input_json, limits);
     }
 
-    match execute_with_seccomp(skill_dir, runtime, config, input_json, limits) {
+    match execute_with_seccomp(skill_dir, runtime, config, input_json, limits, level) {
         Ok(result) => Ok(result),
         Err(e) => {
             let allow_fallback =
@@ -91,6 +115,8 @@ struct LinuxSandboxInvocation<'a> {
     entry_point: &'a Path,
     work_dir: &'a Path,
     limits: ResourceLimits,
+    /// Profile for the run's sandbox level; `None` when the level maps to `"none"`.
+    seccomp: Option<&'a SeccompProfile>,
 }
 
 /// Execute with seccomp-based sandbox (works without root privileges)
@@ -101,6 +127,7 @@ fn execute_with_seccomp(
     config: &SandboxConfig,
     input_json: &str,
     limits: crate::runner::ResourceLimits,
+    level: SandboxLevel,
 ) -> Result<ExecutionResult> {
     let language = &config.language;
     let entry_point = skill_dir.join(&config.entry_point);
@@ -109,6 +136,10 @@ fn execute_with_seccomp(
         .resolve(language)
         .ok_or_else(|| crate::Error::validation(format!("Unsupported language: {}", language)))?;
 
+    // A broken profiles.toml must not silently weaken the sandbox.
+    let profiles = SeccompProfiles::load()
+        .map_err(|e| crate::Error::validation(format!("Invalid seccomp profiles: {}", e)))?;
+
     // Create temporary directory for execution
     let temp_dir = TempDir::new()?;
     let work_dir = temp_dir.path();
@@ -122,6 +153,7 @@ fn execute_with_seccomp(
         entry_point: &entry_point,
         work_dir,
         limits,
+        seccomp: profiles.for_level(level),
     };
 
     let bwrap_path = resolve_which(Path::new("bwrap"));
@@ -155,8 +187,11 @@ fn execute_with_bwrap(
 
     let env_path = &runtime.env_dir;
     let interpreter_path = resolve_command_path(&resolved.interpreter);
-    let network_policy =
-        security_policy::resolve_network_policy(config.network_enabled, &config.network_outbound);
+    let network_policy = security_policy::resolve_network_policy(
+        config.network_enabled,
+        &config.network_outbound,
+        &config.network_deny,
+    );
 
     let proxy_manager = start_network_proxy(&network_policy);
 
@@ -236,6 +271,17 @@ fn execute_with_bwrap(
     let work_dir_str = work_dir.to_string_lossy();
     cmd.args(["--bind", &work_dir_str, "/tmp"]);
 
+    // Mount the per-run results directory read-write at the same path
+    if let Some(ref results_dir) = config.results_dir {
+        let results_dir_str = results_dir.to_string_lossy();
+        cmd.args(["--bind", &results_dir_str, &results_dir_str]);
+        cmd.args([
+            "--setenv",
+            skilllite_core::config::env_keys::paths::SKILLLITE_RESULTS_DIR,
+            &results_dir_str,
+        ]);
+    }
+
     // Create minimal /dev
     cmd.args(["--dev", "/dev"]);
 
@@ -298,39 +344,64 @@ fn execute_with_bwrap(
         }
     }
 
-    // Generate seccomp BPF filter file for Unix socket blocking.
+    // Write the level's seccomp profile as a BPF program.
     //
     // bwrap --seccomp FD expects an *open file descriptor* (integer), not a
     // path.  We open the BPF file here, keep the raw fd alive across the
     // fork (into_raw_fd intentionally leaks ownership), and in pre_exec we
     // dup2 it to the well-known slot 3 so that bwrap can read it.
-    let seccomp_filter_path = work_dir.join("seccomp.bpf");
-    let seccomp_raw_fd: Option<i32> = match generate_seccomp_bpf_file(&seccomp_filter_path) {
-        Err(e) => {
-            tracing::warn!("Failed to generate seccomp filter: {}", e);
+    //
+    // With user notification the filter is installed by the exec shim after
+    // bwrap's own setup instead, and the listener comes back over slot 4.
+    let supervisor = invocation.seccomp.and_then(start_notify_supervisor);
+    let seccomp_raw_fd: Option<i32> = match invocation.seccomp {
+        None => {
+            tracing::warn!(skill = %config.name, "No seccomp profile for this sandbox level");
             None
         }
-        Ok(()) => {
-            use std::os::unix::io::IntoRawFd;
-            match fs::File::open(&seccomp_filter_path) {
-                Ok(f) => Some(f.into_raw_fd()),
+        Some(profile) => {
+            let path = work_dir.join("seccomp.bpf");
+            match fs::write(&path, profile.filter_bytes(supervisor.is_some())) {
                 Err(e) => {
-                    tracing::warn!("Failed to open seccomp BPF file: {}", e);
+                    tracing::warn!("Failed to generate seccomp filter: {}", e);
                     None
                 }
+                Ok(()) => {
+                    use std::os::unix::io::IntoRawFd;
+                    match fs::File::open(&path) {
+                        Ok(f) => Some(f.into_raw_fd()),
+                        Err(e) => {
+                            tracing::warn!("Failed to open seccomp BPF file: {}", e);
+                            None
+                        }
+                    }
+                }
             }
         }
     };
-
-    // Only add --seccomp when we successfully opened the BPF file.
-    if seccomp_raw_fd.is_some() {
+    // Without a filter fd the shim has nothing to install.
+    let supervisor = supervisor.filter(|_| seccomp_raw_fd.is_some());
+    let socket_raw_fd = supervisor.as_ref().map(|(_, s)| s.child_socket());
+
+    if let Some((shim, _)) = &supervisor {
+        let shim_str = shim.to_string_lossy();
+        cmd.args(["--ro-bind", &shim_str, seccomp::EXEC_SHIM_SANDBOX_PATH]);
+    } else if seccomp_raw_fd.is_some() {
+        // Only add --seccomp when we successfully opened the BPF file.
         cmd.args(["--seccomp", "3"]);
     }
 
     // Add the program and arguments
     cmd.arg("--");
+    if supervisor.is_some() {
+        cmd.arg(seccomp::EXEC_SHIM_SANDBOX_PATH)
+            .arg(seccomp::EXEC_SHIM_ARG)
+            .arg(seccomp::SHIM_FILTER_FD.to_string())
+            .arg(seccomp::SHIM_SOCKET_FD.to_string());
+    }
     cmd.arg(&interpreter_path);
     cmd.arg(entry_point);
+    cmd.args(resolve_script_args(config));
 
     // Set working directory
     cmd.current_dir(skill_dir);
@@ -344,7 +415,7 @@ fn execute_with_bwrap(
 
     unsafe {
         cmd.pre_exec(move || {
-            use nix::libc::{close, dup2, fcntl, FD_CLOEXEC, F_GETFD, F_SETFD};
+            use nix::libc::{close, dup2, fcntl, FD_CLOEXEC, F_DUPFD_CLOEXEC, F_GETFD, F_SETFD};
 
             common::apply_rlimits(
                 memory_limit_mb,
@@ -354,12 +425,20 @@ fn execute_with_bwrap(
             );
 
             // If we have an open seccomp BPF fd, dup2 it to FD 3 and clear
-            // CLOEXEC so bwrap can read it after execve.
-            if let Some(src_fd) = seccomp_raw_fd {
-                const SECCOMP_BWRAP_FD: i32 = 3;
-                if dup2(src_fd, SECCOMP_BWRAP_FD) >= 0 {
-                    let flags = fcntl(SECCOMP_BWRAP_FD, F_GETFD, 0);
-                    fcntl(SECCOMP_BWRAP_FD, F_SETFD, flags & !FD_CLOEXEC);
+            // CLOEXEC so bwrap (or the shim) can read it after execve. The
+            // supervisor socket goes to FD 4 the same way. Both are first moved
+            // above the target slots so one dup2 cannot clobber the other source.
+            let lift = |fd: Option<i32>| fd.map(|fd| fcntl(fd, F_DUPFD_CLOEXEC, 10));
+            for (src_fd, target_fd) in [
+                (lift(seccomp_raw_fd), seccomp::SHIM_FILTER_FD),
+                (lift(socket_raw_fd), seccomp::SHIM_SOCKET_FD),
+            ] {
+                let Some(src_fd) = src_fd.filter(|fd| *fd >= 0) else {
+                    continue;
+                };
+                if dup2(src_fd, target_fd) >= 0 {
+                    let flags = fcntl(target_fd, F_GETFD, 0);
+                    fcntl(target_fd, F_SETFD, flags & !FD_CLOEXEC);
                     close(src_fd);
                 }
             }
@@ -368,151 +447,56 @@ fn execute_with_bwrap(
         });
     }
 
-    let (result, _, _) = spawn_write_and_wait(
+    let spawned = spawn_write_and_wait(
         &mut cmd,
         input_json,
         &limits,
         true,
         "Failed to spawn bwrap sandbox",
-    )?;
+    );
+    if let Some(fd) = seccomp_raw_fd {
+        unsafe { nix::libc::close(fd) };
+    }
+    let violations = supervisor
+        .map(|(_, supervisor)| supervisor.finish())
+        .unwrap_or_default();
+    let (mut result, _, _) = spawned?;
+    if let Some(note) = seccomp::denied_note(&violations) {
+        result.stderr.push_str(&note);
+    }
+    result.syscall_violations = violations;
+    result.seccomp_profile = Some(
+        invocation
+            .seccomp
+            .filter(|_| seccomp_raw_fd.is_some())
+            .map_or_else(|| seccomp::NO_PROFILE.to_string(), |p| p.name.clone()),
+    );
 
     drop(proxy_manager);
     Ok(result)
 }
 
-/// Generate a seccomp BPF filter file for bwrap's `--seccomp` option.
+/// Start a listener for `profile` when the kernel and host binary support the exec shim.
 ///
-/// Blocks the same set of dangerous syscalls as `seccomp.rs::build_sandbox_filter`:
-/// ptrace, mount, umount2, keyctl, kexec_load, kexec_file_load, pivot_root, chroot,
-/// socket(AF_UNIX), clone(CLONE_NEWUSER), unshare(CLONE_NEWUSER).
-fn generate_seccomp_bpf_file(path: &Path) -> Result<()> {
-    use std::io::Write;
-
-    #[cfg(target_arch = "x86_64")]
-    mod nr {
-        pub const SOCKET: u32 = 41;
-        pub const PTRACE: u32 = 101;
-        pub const MOUNT: u32 = 165;
-        pub const UMOUNT2: u32 = 166;
-        pub const CLONE: u32 = 56;
-        pub const KEYCTL: u32 = 250;
-        pub const KEXEC_LOAD: u32 = 246;
-        pub const KEXEC_FILE_LOAD: u32 = 320;
-        pub const PIVOT_ROOT: u32 = 155;
-        pub const CHROOT: u32 = 161;
-        pub const UNSHARE: u32 = 272;
-    }
-
-    #[cfg(target_arch = "aarch64")]
-    mod nr {
-        pub const SOCKET: u32 = 198;
-        pub const PTRACE: u32 = 117;
-        pub const MOUNT: u32 = 40;
-        pub const UMOUNT2: u32 = 39;
-        pub const CLONE: u32 = 220;
-        pub const KEYCTL: u32 = 219;
-        pub const KEXEC_LOAD: u32 = 104;
-        pub const KEXEC_FILE_LOAD: u32 = 294;
-        pub const PIVOT_ROOT: u32 = 41;
-        pub const CHROOT: u32 = 51;
-        pub const UNSHARE: u32 = 97;
-    }
-
-    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
-    mod nr {
-        pub const SOCKET: u32 = 0;
-        pub const PTRACE: u32 = 0;
-        pub const MOUNT: u32 = 0;
-        pub const UMOUNT2: u32 = 0;
-        pub const CLONE: u32 = 0;
-        pub const KEYCTL: u32 = 0;
-        pub const KEXEC_LOAD: u32 = 0;
-        pub const KEXEC_FILE_LOAD: u32 = 0;
-        pub const PIVOT_ROOT: u32 = 0;
-        pub const CHROOT: u32 = 0;
-        pub const UNSHARE: u32 = 0;
-    }
-
-    const AF_UNIX: u32 = 1;
-    const CLONE_NEWUSER: u32 = 0x10000000;
-
-    const SECCOMP_RET_ALLOW: u32 = 0x7fff0000;
-    const SECCOMP_RET_ERRNO: u32 = 0x00050000;
-    const EPERM: u32 = 1;
-
-    const BPF_LD: u16 = 0x00;
-    const BPF_W: u16 = 0x00;
-    const BPF_ABS: u16 = 0x20;
-    const BPF_JMP: u16 = 0x05;
-    const BPF_JEQ: u16 = 0x10;
-    const BPF_K: u16 = 0x00;
-    const BPF_RET: u16 = 0x06;
-    const BPF_ALU: u16 = 0x04;
-    const BPF_AND: u16 = 0x50;
-
-    const SECCOMP_DATA_NR: u32 = 0;
-    const SECCOMP_DATA_ARGS: u32 = 16;
-
-    type Inst = (u16, u8, u8, u32);
-    let deny: Inst = (BPF_RET | BPF_K, 0, 0, SECCOMP_RET_ERRNO | EPERM);
-    let allow: Inst = (BPF_RET | BPF_K, 0, 0, SECCOMP_RET_ALLOW);
-
-    let mut filter: Vec<Inst> = Vec::with_capacity(36);
-
-    // Load syscall number
-    filter.push((BPF_LD | BPF_W | BPF_ABS, 0, 0, SECCOMP_DATA_NR));
-
-    // Unconditional blocks
-    for syscall in [
-        nr::PTRACE,
-        nr::MOUNT,
-        nr::UMOUNT2,
-        nr::KEYCTL,
-        nr::KEXEC_LOAD,
-        nr::KEXEC_FILE_LOAD,
-        nr::PIVOT_ROOT,
-        nr::CHROOT,
-    ] {
-        filter.push((BPF_JMP | BPF_JEQ | BPF_K, 0, 1, syscall));
-        filter.push(deny);
-    }
-
-    // socket(AF_UNIX) block
-    filter.push((BPF_JMP | BPF_JEQ | BPF_K, 0, 3, nr::SOCKET));
-    filter.push((BPF_LD | BPF_W | BPF_ABS, 0, 0, SECCOMP_DATA_ARGS));
-    filter.push((BPF_JMP | BPF_JEQ | BPF_K, 0, 1, AF_UNIX));
-    filter.push(deny);
-    // Reload syscall number
-    filter.push((BPF_LD | BPF_W | BPF_ABS, 0, 0, SECCOMP_DATA_NR));
-
-    // clone(CLONE_NEWUSER) block
-    filter.push((BPF_JMP | BPF_JEQ | BPF_K, 0, 4, nr::CLONE));
-    filter.push((BPF_LD | BPF_W | BPF_ABS, 0, 0, SECCOMP_DATA_ARGS));
-    filter.push((BPF_ALU | BPF_AND | BPF_K, 0, 0, CLONE_NEWUSER));
-    filter.push((BPF_JMP | BPF_JEQ | BPF_K, 0, 1, CLONE_NEWUSER));
-    filter.push(deny);
-    // Reload syscall number
-    filter.push((BPF_LD | BPF_W | BPF_ABS, 0, 0, SECCOMP_DATA_NR));
-
-    // unshare(CLONE_NEWUSER) block
-    filter.push((BPF_JMP | BPF_JEQ | BPF_K, 0, 4, nr::UNSHARE));
-    filter.push((BPF_LD | BPF_W | BPF_ABS, 0, 0, SECCOMP_DATA_ARGS));
-    filter.push((BPF_ALU | BPF_AND | BPF_K, 0, 0, CLONE_NEWUSER));
-    filter.push((BPF_JMP | BPF_JEQ | BPF_K, 0, 1, CLONE_NEWUSER));
-    filter.push(deny);
-
-    // Allow everything else
-    filter.push(allow);
-
-    let mut file = fs::File::create(path)?;
-    for (code, jt, jf, k) in filter {
-        file.write_all(&code.to_ne_bytes())?;
-        file.write_all(&[jt])?;
-        file.write_all(&[jf])?;
-        file.write_all(&k.to_ne_bytes())?;
+/// Returns `None` (plain bwrap `--seccomp` filter, violations not attributed) otherwise.
+fn start_notify_supervisor(profile: &SeccompProfile) -> Option<(PathBuf, NotifySupervisor)> {
+    let Some(shim) = seccomp::exec_shim_binary().filter(|_| seccomp::notify_supported()) else {
+        if profile.action == SeccompAction::Log {
+            tracing::warn!(
+                profile = %profile.name,
+                "seccomp log mode needs user notification (Linux 5.5+); \
+                 violations go to the kernel audit log only"
+            );
+        }
+        return None;
+    };
+    match NotifySupervisor::start(profile) {
+        Ok(supervisor) => Some((shim, supervisor)),
+        Err(e) => {
+            tracing::warn!("Failed to start seccomp supervisor: {}", e);
+            None
+        }
     }
-
-    Ok(())
 }
 
 /// Execute with firejail sandbox with network proxy support
@@ -530,8 +514,11 @@ fn execute_with_firejail(
 
     let env_path = &runtime.env_dir;
     let interpreter_path = resolve_command_path(&resolved.interpreter);
-    let network_policy =
-        security_policy::resolve_network_policy(config.network_enabled, &config.network_outbound);
+    let network_policy = security_policy::resolve_network_policy(
+        config.network_enabled,
+        &config.network_outbound,
+        &config.network_deny,
+    );
 
     let proxy_manager = start_network_proxy(&network_policy);
 
@@ -545,7 +532,25 @@ fn execute_with_firejail(
     cmd.args(["--private-dev"]); // Minimal /dev
     cmd.args(["--noroot"]); // No root in sandbox
     cmd.args(["--caps.drop=all"]); // Drop all capabilities
-    cmd.args(["--seccomp"]); // Enable seccomp (includes Unix socket blocking)
+    if let Some(profile) = invocation.seccomp {
+        // firejail's default list plus the profile's unconditional rules;
+        // argument-checked rules and log mode are not expressible here.
+        let extra: Vec<&str> = profile
+            .deny
+            .iter()
+            .map(|rule| rule.spec.as_str())
+            .filter(|spec| !spec.contains('('))
+            .collect();
+        if profile.action == SeccompAction::Log {
+            tracing::warn!(profile = %profile.name, "firejail: seccomp log mode unsupported, enforcing");
+        }
+        // Enable seccomp (includes Unix socket blocking)
+        if extra.is_empty() {
+            cmd.arg("--seccomp");
+        } else {
+            cmd.arg(format!("--seccomp={}", extra.join(",")));
+        }
+    }
 
     // File system restrictions
     cmd.args(["--read-only=/usr"]);
@@ -571,6 +576,11 @@ fn execute_with_firejail(
         cmd.args([&format!("--read-only={}", env_path_str)]);
     }
 
+    // Whitelist the per-run results directory (read-write)
+    if let Some(ref results_dir) = config.results_dir {
+        cmd.args([&format!("--whitelist={}", results_dir.to_string_lossy())]);
+    }
+
     // Network isolation (from security_policy - aligns with macOS)
     if security_policy::is_network_blocked(&network_policy) {
         cmd.args(["--net=none"]);
@@ -593,11 +603,13 @@ fn execute_with_firejail(
     cmd.arg("--");
     cmd.arg(&interpreter_path);
     cmd.arg(entry_point);
+    cmd.args(resolve_script_args(config));
 
     cmd.current_dir(skill_dir);
     pipe_stdio(&mut cmd);
 
     apply_standard_execution_env(&mut cmd, true, work_dir, config.network_enabled, true);
+    apply_results_dir_env(&mut cmd, config);
     for (k, v) in &resolved.extra_env {
         cmd.env(k, v);
     }
@@ -627,6 +639,13 @@ fn execute_with_firejail(
         stdout: String::from_utf8_lossy(&output.stdout).to_string(),
         stderr: String::from_utf8_lossy(&output.stderr).to_string(),
         exit_code: output.status.code().unwrap_or(-1),
+        enforcement: LimitEnforcement::Unenforced,
+        syscall_violations: Vec::new(),
+        seccomp_profile: Some(
+            invocation
+                .seccomp
+                .map_or_else(|| seccomp::NO_PROFILE.to_string(), |p| p.name.clone()),
+        ),
     })
 }
 
@@ -650,6 +669,7 @@ fn execute_with_namespaces(
 
     let mut cmd = Command::new(&resolved.interpreter);
     cmd.arg(&entry_point);
+    cmd.args(resolve_script_args(config));
     for (k, v) in &resolved.extra_env {
         cmd.env(k, v);
     }
@@ -658,6 +678,7 @@ fn execute_with_namespaces(
     cmd.current_dir(skill_dir);
 
     apply_standard_execution_env(&mut cmd, true, work_dir, config.network_enabled, true);
+    apply_results_dir_env(&mut cmd, config);
 
     unsafe {
         cmd.pre_exec(|| {
@@ -667,13 +688,14 @@ fn execute_with_namespaces(
         });
     }
 
-    let (result, _, _) = spawn_write_and_wait(
+    let (mut result, _, _) = spawn_write_and_wait(
         &mut cmd,
         input_json,
         &limits,
         true,
         "Failed to spawn skill process",
     )?;
+    result.seccomp_profile = Some(seccomp::NO_PROFILE.to_string());
     Ok(result)
 }
 /// Set up mount namespace with read-only binds
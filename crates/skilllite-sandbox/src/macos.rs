@@ -1,6 +1,6 @@
 use crate::common::{
-    self, apply_standard_execution_env, get_script_args_from_env, pipe_stdio, resolve_which,
-    spawn_write_and_wait, start_network_proxy,
+    self, apply_results_dir_env, apply_standard_execution_env, pipe_stdio, resolve_script_args,
+    resolve_which, spawn_write_and_wait, start_network_proxy,
 };
 use crate::error::bail;
 use crate::move_protection::{generate_log_tag, generate_move_blocking_rules, get_session_suffix};
@@ -23,7 +23,8 @@ pub fn execute_with_limits(
     input_json: &str,
     limits: crate::runner::ResourceLimits,
 ) -> Result<ExecutionResult> {
-    if skilllite_core::config::SandboxEnvConfig::from_env().no_sandbox {
+    if skilllite_core::config::SandboxEnvConfig::from_env().no_sandbox && !config.enforce_isolation
+    {
         tracing::warn!("Sandbox disabled via SKILLLITE_NO_SANDBOX - running without protection");
         crate::info_log!("[INFO] using simple execution (no sandbox-exec)");
         return execute_simple_with_limits(skill_dir, runtime, config, input_json, limits);
@@ -78,8 +79,11 @@ fn execute_with_sandbox(
     let temp_dir = TempDir::new()?;
     let work_dir = temp_dir.path();
 
-    let network_policy =
-        security_policy::resolve_network_policy(config.network_enabled, &config.network_outbound);
+    let network_policy = security_policy::resolve_network_policy(
+        config.network_enabled,
+        &config.network_outbound,
+        &config.network_deny,
+    );
 
     let proxy_manager = start_network_proxy(&network_policy);
 
@@ -102,7 +106,7 @@ fn execute_with_sandbox(
     fs::write(&profile_path, &profile_content)?;
 
     let mut args = vec![config.entry_point.to_string()];
-    args.extend(get_script_args_from_env());
+    args.extend(resolve_script_args(config));
 
     let mut cmd = Command::new("sandbox-exec");
     cmd.arg("-f").arg(&profile_path);
@@ -113,6 +117,7 @@ fn execute_with_sandbox(
     pipe_stdio(&mut cmd);
 
     apply_standard_execution_env(&mut cmd, true, work_dir, config.network_enabled, true);
+    apply_results_dir_env(&mut cmd, config);
 
     for (k, v) in &resolved.extra_env {
         cmd.env(k, v);
@@ -368,6 +373,14 @@ fn generate_sandbox_profile_with_proxy(
         work_dir_str
     ));
 
+    // Allow writing to the per-run results directory (result.json + output files)
+    if let Some(ref results_dir) = config.results_dir {
+        profile.push_str(&format!(
+            "(allow file-write* (subpath \"{}\"))\n",
+            results_dir.to_string_lossy()
+        ));
+    }
+
     // Allow writing to project root (parent of .skills) for skill outputs (e.g. xiaohongshu_thumbnail.png)
     if let Some(project_root) = skill_dir.parent().and_then(|p| p.parent()) {
         let project_root_str = project_root.to_string_lossy();
@@ -588,6 +601,14 @@ fn generate_sandbox_profile(
         work_dir_str
     ));
 
+    // Allow writing to the per-run results directory (result.json + output files)
+    if let Some(ref results_dir) = config.results_dir {
+        profile.push_str(&format!(
+            "(allow file-write* (subpath \"{}\"))\n",
+            results_dir.to_string_lossy()
+        ));
+    }
+
     // Allow writing to project root (parent of .skills) for skill outputs (e.g. xiaohongshu_thumbnail.png)
     if let Some(project_root) = skill_dir.parent().and_then(|p| p.parent()) {
         let project_root_str = project_root.to_string_lossy();
@@ -742,7 +763,11 @@ mod tests {
             language: "python".to_string(),
             network_enabled: false,
             network_outbound: Vec::new(),
+            network_deny: Vec::new(),
             uses_playwright: false,
+            script_args: Vec::new(),
+            results_dir: None,
+            enforce_isolation: false,
         };
 
         let interpreter = Path::new("/usr/bin/python3");
@@ -4,8 +4,13 @@
 //! - Move blocking rules to prevent bypass via mv/rename (P0)
 //! - LogTag mechanism for precise violation tracking (P1)
 //! - Glob to regex conversion for flexible path matching
+//! - Launch integrity: symlink / hardlink escapes and entry script swaps between scan and exec
 
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
 use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -269,6 +274,446 @@ pub fn generate_move_blocking_rules(path_patterns: &[String], log_tag: &str) ->
     rules
 }
 
+// ============================================================================
+// Launch Integrity - Symlink / Hardlink / Entry Swap Checks (P0)
+// ============================================================================
+
+/// Error message prefix when a launch is refused by [`find_link_violations`] or
+/// [`EntryFingerprint::verify`].
+pub const LAUNCH_INTEGRITY_BLOCKED: &str =
+    "Execution blocked: skill files link outside the skill/env directories or changed after the scan.";
+
+/// Entries walked per root before the link check stops. Reaching it is a violation (links
+/// past the cap would otherwise go unchecked), except for env roots that passed a complete
+/// check once, see [`verify_env_root`].
+const MAX_WALK_ENTRIES: usize = 50_000;
+
+/// Written into an env root after a complete link check of it passed.
+pub const ENV_LINK_CHECK_RECORD: &str = ".skilllite_link_check.json";
+
+/// A link or swap that would let the sandboxed child reach files outside its roots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityViolation {
+    /// A symlink under a root resolves (or, if dangling, points) outside every root.
+    SymlinkEscape { link: PathBuf, target: PathBuf },
+    /// A hard-linked file has `links` names but only `seen` of them are under the roots.
+    HardlinkOutside {
+        path: PathBuf,
+        links: u64,
+        seen: u64,
+    },
+    /// The entry script is no longer the file that was fingerprinted at scan time.
+    EntrySwapped { path: PathBuf, reason: String },
+    /// The root holds more than `limit` entries, so it could not be checked completely.
+    WalkLimitExceeded { root: PathBuf, limit: usize },
+    /// A directory or entry under a root could not be read, so it was not checked.
+    Unreadable { path: PathBuf, error: String },
+}
+
+impl IntegrityViolation {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            IntegrityViolation::SymlinkEscape { .. } => "symlink_escape",
+            IntegrityViolation::HardlinkOutside { .. } => "hardlink_outside",
+            IntegrityViolation::EntrySwapped { .. } => "entry_swapped",
+            IntegrityViolation::WalkLimitExceeded { .. } => "walk_limit_exceeded",
+            IntegrityViolation::Unreadable { .. } => "unreadable",
+        }
+    }
+}
+
+impl std::fmt::Display for IntegrityViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityViolation::SymlinkEscape { link, target } => write!(
+                f,
+                "[{}] {} -> {}",
+                self.kind(),
+                link.display(),
+                target.display()
+            ),
+            IntegrityViolation::HardlinkOutside { path, links, seen } => write!(
+                f,
+                "[{}] {} has {} links, {} inside the skill/env directories",
+                self.kind(),
+                path.display(),
+                links,
+                seen
+            ),
+            IntegrityViolation::EntrySwapped { path, reason } => {
+                write!(f, "[{}] {}: {}", self.kind(), path.display(), reason)
+            }
+            IntegrityViolation::WalkLimitExceeded { root, limit } => write!(
+                f,
+                "[{}] {} has more than {} entries; links beyond that were not checked",
+                self.kind(),
+                root.display(),
+                limit
+            ),
+            IntegrityViolation::Unreadable { path, error } => write!(
+                f,
+                "[{}] {}: {}; links below it were not checked",
+                self.kind(),
+                path.display(),
+                error
+            ),
+        }
+    }
+}
+
+/// Walk `roots` without following symlinks and report links that escape them.
+///
+/// A symlink is fine when it resolves inside any root or to one of `allowed_targets`
+/// (canonical interpreter binaries, e.g. a venv's `bin/python -> /usr/bin/python3.12`).
+/// Dangling links are judged by their lexical target, since a later write could complete
+/// them. On Unix, a regular file with more hard links than were found under the roots has
+/// a name elsewhere and is reported too. A root with more than `MAX_WALK_ENTRIES`
+/// entries is reported as [`IntegrityViolation::WalkLimitExceeded`], anything that cannot
+/// be read as [`IntegrityViolation::Unreadable`].
+pub fn find_link_violations(
+    roots: &[PathBuf],
+    allowed_targets: &[PathBuf],
+) -> Vec<IntegrityViolation> {
+    find_link_violations_with_limit(roots, allowed_targets, MAX_WALK_ENTRIES)
+}
+
+/// [`find_link_violations`] with an explicit per-root entry cap.
+pub fn find_link_violations_with_limit(
+    roots: &[PathBuf],
+    allowed_targets: &[PathBuf],
+    max_entries: usize,
+) -> Vec<IntegrityViolation> {
+    walk_for_violations(roots, allowed_targets, max_entries, &[])
+}
+
+/// Pre-launch link check of the skill dir and its (optional) env dir.
+///
+/// The env is checked completely on its first launch ([`verify_env_root`]); after that only
+/// the skill dir is held to `MAX_WALK_ENTRIES`, so large environments keep working while
+/// the first entries of both are re-checked on every launch.
+pub fn find_launch_violations(
+    skill_dir: &Path,
+    env_dir: Option<&Path>,
+    allowed_targets: &[PathBuf],
+) -> Vec<IntegrityViolation> {
+    find_launch_violations_with_limit(skill_dir, env_dir, allowed_targets, MAX_WALK_ENTRIES)
+}
+
+/// [`find_launch_violations`] with an explicit per-root entry cap.
+pub fn find_launch_violations_with_limit(
+    skill_dir: &Path,
+    env_dir: Option<&Path>,
+    allowed_targets: &[PathBuf],
+    max_entries: usize,
+) -> Vec<IntegrityViolation> {
+    let skill_only = [skill_dir.to_path_buf()];
+    let Some(env_dir) = env_dir else {
+        return find_link_violations_with_limit(&skill_only, allowed_targets, max_entries);
+    };
+    let env_violations = verify_env_root(env_dir, allowed_targets);
+    if !env_violations.is_empty() {
+        let mut violations =
+            find_link_violations_with_limit(&skill_only, allowed_targets, max_entries);
+        violations.extend(env_violations);
+        return violations;
+    }
+    let verified: Vec<PathBuf> = fs::canonicalize(env_dir).into_iter().collect();
+    walk_for_violations(
+        &[skill_dir.to_path_buf(), env_dir.to_path_buf()],
+        allowed_targets,
+        max_entries,
+        &verified,
+    )
+}
+
+/// Check `env_dir` without an entry cap unless an earlier check recorded it as clean, and
+/// record a clean result in [`ENV_LINK_CHECK_RECORD`].
+///
+/// Only env roots get this treatment: they are built by SkillLite and mounted read-only in
+/// the sandbox, whereas a record inside a skill dir would be under the skill author's control.
+pub fn verify_env_root(env_dir: &Path, allowed_targets: &[PathBuf]) -> Vec<IntegrityViolation> {
+    let record = env_dir.join(ENV_LINK_CHECK_RECORD);
+    if record.is_file() {
+        return Vec::new();
+    }
+    let violations =
+        find_link_violations_with_limit(&[env_dir.to_path_buf()], allowed_targets, usize::MAX);
+    if violations.is_empty() {
+        let checked_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let content = serde_json::json!({ "checked_at": checked_at }).to_string();
+        if let Err(e) = skilllite_fs::atomic_write(&record, &content) {
+            tracing::warn!(
+                "Failed to record link check of {}: {}",
+                env_dir.display(),
+                e
+            );
+        }
+    }
+    violations
+}
+
+/// Walk `roots`; a root in `verified` that exceeds `max_entries` stops quietly instead of
+/// reporting [`IntegrityViolation::WalkLimitExceeded`].
+fn walk_for_violations(
+    roots: &[PathBuf],
+    allowed_targets: &[PathBuf],
+    max_entries: usize,
+    verified: &[PathBuf],
+) -> Vec<IntegrityViolation> {
+    let mut canonical: Vec<PathBuf> = roots
+        .iter()
+        .filter_map(|r| fs::canonicalize(r).ok())
+        .collect();
+    canonical.sort();
+    canonical.dedup();
+    // Nested roots are walked as part of their parent.
+    let walk_roots: Vec<PathBuf> = canonical
+        .iter()
+        .filter(|r| !canonical.iter().any(|o| o != *r && r.starts_with(o)))
+        .cloned()
+        .collect();
+
+    let mut violations = Vec::new();
+    // (dev, ino) -> (first path, link count, names seen under the roots)
+    let mut inodes: HashMap<(u64, u64), (PathBuf, u64, u64)> = HashMap::new();
+    for root in &walk_roots {
+        let mut stack = vec![root.clone()];
+        let mut walked = 0usize;
+        while let Some(dir) = stack.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    violations.push(IntegrityViolation::Unreadable {
+                        path: dir,
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            for entry in entries {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        violations.push(IntegrityViolation::Unreadable {
+                            path: dir.clone(),
+                            error: e.to_string(),
+                        });
+                        break;
+                    }
+                };
+                walked += 1;
+                if walked > max_entries && verified.contains(root) {
+                    tracing::debug!(
+                        "Link check of verified env {} stopped after {} entries",
+                        root.display(),
+                        max_entries
+                    );
+                    stack.clear();
+                    break;
+                }
+                if walked > max_entries {
+                    tracing::warn!(
+                        "Link check stopped after {} entries under {}",
+                        max_entries,
+                        root.display()
+                    );
+                    violations.push(IntegrityViolation::WalkLimitExceeded {
+                        root: root.clone(),
+                        limit: max_entries,
+                    });
+                    stack.clear();
+                    break;
+                }
+                let path = entry.path();
+                let meta = match fs::symlink_metadata(&path) {
+                    Ok(meta) => meta,
+                    // Removed since it was listed: nothing left to follow.
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(e) => {
+                        violations.push(IntegrityViolation::Unreadable {
+                            path,
+                            error: e.to_string(),
+                        });
+                        continue;
+                    }
+                };
+                let file_type = meta.file_type();
+                if file_type.is_symlink() {
+                    let target = resolve_link(&path);
+                    let inside = canonical.iter().any(|r| target.starts_with(r));
+                    if !inside && !allowed_targets.contains(&target) {
+                        violations.push(IntegrityViolation::SymlinkEscape { link: path, target });
+                    }
+                } else if file_type.is_dir() {
+                    stack.push(path);
+                } else if file_type.is_file() {
+                    record_hardlink(&meta, path, &mut inodes);
+                }
+            }
+        }
+    }
+
+    let mut outside: Vec<IntegrityViolation> = inodes
+        .into_values()
+        .filter(|(_, links, seen)| seen < links)
+        .map(|(path, links, seen)| IntegrityViolation::HardlinkOutside { path, links, seen })
+        .collect();
+    outside.sort_by_key(|a| a.to_string());
+    violations.extend(outside);
+    violations
+}
+
+#[cfg(unix)]
+fn record_hardlink(
+    meta: &fs::Metadata,
+    path: PathBuf,
+    inodes: &mut HashMap<(u64, u64), (PathBuf, u64, u64)>,
+) {
+    use std::os::unix::fs::MetadataExt;
+    if meta.nlink() > 1 {
+        inodes
+            .entry((meta.dev(), meta.ino()))
+            .or_insert((path, meta.nlink(), 0))
+            .2 += 1;
+    }
+}
+
+#[cfg(not(unix))]
+fn record_hardlink(
+    _meta: &fs::Metadata,
+    _path: PathBuf,
+    _inodes: &mut HashMap<(u64, u64), (PathBuf, u64, u64)>,
+) {
+}
+
+/// Canonical target of `link`, or its lexically normalized target when it dangles.
+fn resolve_link(link: &Path) -> PathBuf {
+    if let Ok(target) = fs::canonicalize(link) {
+        return target;
+    }
+    let target = fs::read_link(link).unwrap_or_default();
+    let joined = match link.parent() {
+        Some(parent) if !target.is_absolute() => parent.join(target),
+        _ => target,
+    };
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Identity of the entry script taken at scan time and re-checked right before exec.
+///
+/// The file stays open for the lifetime of the fingerprint, so its inode cannot be
+/// recycled for a different file. [`EntryFingerprint::verify`] checks that the path still
+/// resolves to the same place and inode and that the content read through the held handle
+/// is unchanged.
+///
+/// The held descriptor is deliberately not what gets executed (no `fexecve`, no
+/// `/proc/self/fd/N`): entry scripts are not executables but arguments to an interpreter,
+/// which opens them by path and derives `__file__`, `import.meta.url` or `$0` from it to find
+/// sibling modules and data files; a `/proc` path would break those lookups. The path is
+/// bound read-only into the L2/L3 sandboxes, so after `verify` only processes outside the
+/// sandbox could still swap it before the interpreter opens it.
+#[derive(Debug)]
+pub struct EntryFingerprint {
+    path: PathBuf,
+    resolved: PathBuf,
+    file: File,
+    len: u64,
+    digest: [u8; 32],
+}
+
+impl EntryFingerprint {
+    /// Open `path` once and fingerprint its resolved location, inode and content.
+    pub fn capture(path: &Path) -> std::io::Result<Self> {
+        let resolved = fs::canonicalize(path)?;
+        let file = File::open(&resolved)?;
+        let (len, digest) = hash_file(&file)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            resolved,
+            file,
+            len,
+            digest,
+        })
+    }
+
+    /// Hex SHA-256 of the content at capture time.
+    pub fn digest_hex(&self) -> String {
+        hex::encode(self.digest)
+    }
+
+    /// Fail if the entry was moved, replaced (rename, symlink swap) or edited since capture.
+    pub fn verify(&self) -> std::result::Result<(), IntegrityViolation> {
+        let swapped = |reason: String| IntegrityViolation::EntrySwapped {
+            path: self.path.clone(),
+            reason,
+        };
+        let resolved = fs::canonicalize(&self.path)
+            .map_err(|e| swapped(format!("no longer resolvable ({})", e)))?;
+        if resolved != self.resolved {
+            return Err(swapped(format!(
+                "now resolves to {} (scanned {})",
+                resolved.display(),
+                self.resolved.display()
+            )));
+        }
+        let current = fs::metadata(&resolved).map_err(|e| swapped(e.to_string()))?;
+        let held = self.file.metadata().map_err(|e| swapped(e.to_string()))?;
+        if !same_file(&current, &held) {
+            return Err(swapped("replaced by a different file".to_string()));
+        }
+        let (len, digest) = hash_file(&self.file).map_err(|e| swapped(e.to_string()))?;
+        if len != self.len || digest != self.digest {
+            return Err(swapped(format!(
+                "content changed since the scan (sha256 {} -> {})",
+                self.digest_hex(),
+                hex::encode(digest)
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn hash_file(mut file: &File) -> std::io::Result<(u64, [u8; 32])> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    let mut len = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        len += n as u64;
+    }
+    Ok((len, hasher.finalize().into()))
+}
+
+#[cfg(unix)]
+fn same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+/// Without inode numbers, the resolved path and content hash carry the check.
+#[cfg(not(unix))]
+fn same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    a.len() == b.len()
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -1,13 +1,21 @@
 //! Proxy configuration and domain allowlist/denylist logic.
+//!
+//! Entries use the outbound whitelist grammar of
+//! [`skilllite_core::skill::network_rules`]: host names, `*.suffix` wildcards,
+//! IP literals, CIDR ranges and optional `:port` / `:lo-hi` suffixes.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+
+use skilllite_core::skill::network_rules::{HostPattern, OutboundRule};
 
 use super::dns;
 
 /// Configuration for the network proxy
 #[derive(Debug, Clone)]
 pub struct ProxyConfig {
-    /// Allowed domains (supports wildcards like *.github.com)
+    /// Allowed entries (e.g. `*.github.com:443`, `10.0.0.0/8`)
     pub allowed_domains: Vec<String>,
-    /// Denied domains (takes precedence over allowed)
+    /// Denied entries (same grammar, take precedence over allowed)
     pub denied_domains: Vec<String>,
     /// Whether to allow all domains if allowlist is empty
     pub allow_all_if_empty: bool,
@@ -28,6 +36,29 @@ impl Default for ProxyConfig {
     }
 }
 
+/// How a requested host name may be turned into addresses, see [`ProxyConfig::name_lookup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NameLookup {
+    /// A name rule could cover it: resolve through DNS.
+    Dns,
+    /// Only address rules (or the loopback default) could cover it: use the hosts file,
+    /// never DNS, so the name itself cannot leak data to a resolver.
+    HostsFile,
+    /// Denied, or nothing could cover it.
+    Refused,
+}
+
+/// Outcome of [`ProxyConfig::authorize`].
+#[derive(Debug)]
+pub(super) enum Authorization {
+    /// Connect to exactly this address — the one the decision was made for.
+    Allowed(SocketAddr),
+    /// Refused; the reason is recorded by the security log.
+    Blocked(&'static str),
+    /// The host did not resolve.
+    Unresolved(std::io::Error),
+}
+
 impl ProxyConfig {
     /// Create a config that blocks all network access
     pub fn block_all() -> Self {
@@ -49,26 +80,132 @@ impl ProxyConfig {
         }
     }
 
+    /// Decide a connection to `host:port` and pick the address to dial.
+    ///
+    /// The host is resolved once and each candidate address is checked together with
+    /// the requested name; the caller must connect to the returned address so that
+    /// the address that was authorized is the one used (no re-resolution between
+    /// check and connect). Only names a name rule could cover are sent to DNS.
+    pub(super) fn authorize(&self, host: &str, port: u16) -> Authorization {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let is_ip = host.parse::<IpAddr>().is_ok();
+        let blocked = if is_ip {
+            "ip_direct_connection_blocked"
+        } else {
+            "domain_not_in_allowlist"
+        };
+        let lookup = if is_ip {
+            NameLookup::Dns
+        } else {
+            self.name_lookup(host, port)
+        };
+        let addrs = match lookup {
+            NameLookup::Refused => return Authorization::Blocked(blocked),
+            NameLookup::HostsFile => {
+                let mut ips = dns::hosts_file_lookup(host);
+                if ips.is_empty() && Self::is_loopback_domain(host) {
+                    // RFC 6761: `.localhost` names are loopback without asking a resolver.
+                    ips = vec![Ipv4Addr::LOCALHOST.into(), Ipv6Addr::LOCALHOST.into()];
+                }
+                if ips.is_empty() {
+                    return Authorization::Blocked(blocked);
+                }
+                ips.into_iter()
+                    .map(|ip| SocketAddr::new(ip, port))
+                    .collect()
+            }
+            NameLookup::Dns => match (host, port).to_socket_addrs() {
+                Ok(addrs) => addrs.collect::<Vec<_>>(),
+                Err(e) => return Authorization::Unresolved(e),
+            },
+        };
+        if addrs.is_empty() {
+            return Authorization::Unresolved(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not resolve host",
+            ));
+        }
+        addrs
+            .into_iter()
+            .find(|addr| self.is_connection_allowed(host, port, addr.ip()))
+            .map_or(Authorization::Blocked(blocked), Authorization::Allowed)
+    }
+
+    /// Whether a connection requested as `host` (name or IP literal) to `port`,
+    /// dialing `ip`, is allowed.
+    pub fn is_connection_allowed(&self, host: &str, port: u16, ip: IpAddr) -> bool {
+        if self.is_denied(host, Some(ip), port) {
+            return false;
+        }
+
+        // Loopback addresses allowed by default — traffic stays on the machine,
+        // not a data-exfiltration vector.
+        if self.allow_loopback && ip.to_canonical().is_loopback() {
+            return true;
+        }
+
+        if self.allowed_domains.is_empty() {
+            return self.allow_all_if_empty;
+        }
+
+        if Self::rules(&self.allowed_domains)
+            .any(|r| r.matches_port(port) && (r.matches_name(host) || r.matches_ip(ip)))
+        {
+            return true;
+        }
+
+        // Raw IP with no address rule: fall back to its PTR name (fail-secure).
+        if host.parse::<IpAddr>().is_ok() {
+            if let Some(name) = dns::reverse_dns_lookup(&ip) {
+                return !self.is_denied(&name, None, port)
+                    && Self::rules(&self.allowed_domains)
+                        .any(|r| r.matches_port(port) && r.matches_name(&name));
+            }
+        }
+        false
+    }
+
+    /// How `host:port` may be resolved: the deny list first, then name rules (DNS), then
+    /// address rules and the loopback default (hosts file only).
+    fn name_lookup(&self, host: &str, port: u16) -> NameLookup {
+        if self.is_denied(host, None, port) {
+            return NameLookup::Refused;
+        }
+        if self.allowed_domains.is_empty() && self.allow_all_if_empty {
+            return NameLookup::Dns;
+        }
+        let rules: Vec<OutboundRule> = Self::rules(&self.allowed_domains)
+            .filter(|r| r.matches_port(port))
+            .collect();
+        if rules.iter().any(|r| r.matches_name(host)) {
+            NameLookup::Dns
+        } else if (self.allow_loopback && Self::is_loopback_domain(host))
+            || rules
+                .iter()
+                .any(|r| matches!(r.host, HostPattern::Cidr(..)))
+        {
+            NameLookup::HostsFile
+        } else {
+            NameLookup::Refused
+        }
+    }
+
     /// Whether `domain` is a loopback name (RFC 6761 ".localhost" TLD).
     fn is_loopback_domain(domain: &str) -> bool {
         let d = domain.to_lowercase();
         d == "localhost" || d.ends_with(".localhost")
     }
 
-    /// Check if a domain is allowed
+    /// Check if a domain is allowed on some port (name rules only, ports ignored)
     pub fn is_domain_allowed(&self, domain: &str) -> bool {
-        let domain_lower = domain.to_lowercase();
-
         // Check denied list first (takes precedence)
-        for denied in &self.denied_domains {
-            if Self::domain_matches(&domain_lower, denied) {
-                return false;
-            }
+        if Self::parsed(&self.denied_domains).any(|r| r.is_none_or(|r| r.matches_name(domain))) {
+            return false;
         }
 
         // Loopback domains (localhost, *.localhost) allowed by default —
         // traffic stays on the machine, not a data-exfiltration vector.
-        if self.allow_loopback && Self::is_loopback_domain(&domain_lower) {
+        if self.allow_loopback && Self::is_loopback_domain(domain) {
             return true;
         }
 
@@ -77,38 +214,28 @@ impl ProxyConfig {
             return self.allow_all_if_empty;
         }
 
-        // Check allowed list
-        for allowed in &self.allowed_domains {
-            if Self::domain_matches(&domain_lower, allowed) {
-                return true;
-            }
-        }
-
-        false
+        Self::rules(&self.allowed_domains).any(|r| r.matches_name(domain))
     }
 
-    /// Check if a direct IP connection should be allowed.
+    /// Check if a direct IP connection should be allowed on some port.
     ///
-    /// When domain filtering is active, raw IP addresses cannot be matched
-    /// against domain patterns. This method attempts reverse DNS (PTR lookup)
-    /// to resolve the IP to a hostname, then checks that hostname against the
-    /// allowlist. If reverse DNS fails, the connection is blocked (fail-secure).
+    /// Address and CIDR entries are matched directly. Otherwise raw IP addresses
+    /// cannot be matched against domain patterns, so this attempts reverse DNS
+    /// (PTR lookup) and checks that hostname against the allowlist. If reverse DNS
+    /// fails, the connection is blocked (fail-secure).
     pub fn is_ip_connection_allowed(&self, ip_str: &str) -> bool {
-        // Check denied list with the raw IP first
-        for denied in &self.denied_domains {
-            if Self::domain_matches(ip_str, denied) {
-                return false;
-            }
+        let ip: IpAddr = match ip_str.parse() {
+            Ok(ip) => ip,
+            Err(_) => return false,
+        };
+        if Self::parsed(&self.denied_domains).any(|r| r.is_none_or(|r| r.matches_ip(ip))) {
+            return false;
         }
 
         // Loopback IPs (127.0.0.0/8, ::1) allowed by default — same
         // rationale as loopback domains: traffic never leaves the host.
-        if self.allow_loopback {
-            if let Ok(ip) = ip_str.parse::<std::net::IpAddr>() {
-                if ip.is_loopback() {
-                    return true;
-                }
-            }
+        if self.allow_loopback && ip.to_canonical().is_loopback() {
+            return true;
         }
 
         // No specific domain filtering → fall back to standard logic
@@ -116,52 +243,41 @@ impl ProxyConfig {
             return self.allow_all_if_empty;
         }
 
-        // Wildcard "*" allows all — no reverse DNS needed
-        if self.allowed_domains.iter().any(|d| d.trim() == "*") {
+        if Self::rules(&self.allowed_domains).any(|r| r.matches_ip(ip)) {
             return true;
         }
 
         // Domain filtering is active — attempt reverse DNS
-        let ip: std::net::IpAddr = match ip_str.parse() {
-            Ok(ip) => ip,
-            Err(_) => return false,
-        };
-
         match dns::reverse_dns_lookup(&ip) {
             Some(ref domain) => self.is_domain_allowed(domain),
             None => false, // Fail-secure: no PTR record → block
         }
     }
 
-    /// Check if a domain matches a pattern (supports wildcards)
-    /// Pattern may include an optional `:port` suffix which is stripped before matching.
-    /// e.g. "*:80" matches all domains, "*.github.com:443" matches sub.github.com
-    fn domain_matches(domain: &str, pattern: &str) -> bool {
-        let pattern_lower = pattern.to_lowercase().trim().to_string();
-
-        // Strip :port suffix if present (e.g. "*:80" → "*", "*.example.com:443" → "*.example.com")
-        let pattern_clean = if let Some(colon_pos) = pattern_lower.rfind(':') {
-            let after_colon = &pattern_lower[colon_pos + 1..];
-            if !after_colon.is_empty() && after_colon.chars().all(|c| c.is_ascii_digit()) {
-                &pattern_lower[..colon_pos]
-            } else {
-                &pattern_lower
-            }
-        } else {
-            &pattern_lower
-        };
+    /// Whether a deny entry covers the connection. An unparseable deny entry
+    /// denies everything rather than silently widening access.
+    fn is_denied(&self, host: &str, ip: Option<IpAddr>, port: u16) -> bool {
+        Self::parsed(&self.denied_domains).any(|rule| {
+            rule.is_none_or(|r| {
+                r.matches_port(port)
+                    && (r.matches_name(host) || ip.is_some_and(|ip| r.matches_ip(ip)))
+            })
+        })
+    }
 
-        // Single "*" matches all domains
-        if pattern_clean == "*" {
-            return true;
-        }
+    /// Entries parsed with the outbound grammar; `None` for invalid ones.
+    fn parsed(entries: &[String]) -> impl Iterator<Item = Option<OutboundRule>> + '_ {
+        entries.iter().map(|e| match OutboundRule::parse(e) {
+            Ok(rule) => Some(rule),
+            Err(err) => {
+                tracing::warn!("[Proxy] Invalid network entry '{}': {}", e, err);
+                None
+            }
+        })
+    }
 
-        if let Some(base) = pattern_clean.strip_prefix("*.") {
-            // Wildcard pattern: *.example.com matches sub.example.com and example.com
-            let suffix = format!(".{}", base);
-            domain.ends_with(&suffix) || domain == base
-        } else {
-            domain == pattern_clean
-        }
+    /// Valid entries only (invalid allow entries grant nothing).
+    fn rules(entries: &[String]) -> impl Iterator<Item = OutboundRule> + '_ {
+        Self::parsed(entries).flatten()
     }
 }
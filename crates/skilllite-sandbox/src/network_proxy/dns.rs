@@ -1,12 +1,13 @@
-//! Reverse DNS lookup for IP direct-connect blocking (F5).
+//! Reverse DNS lookup for IP direct-connect blocking (F5), and hosts-file lookups for names
+//! that must not reach DNS.
+
+use std::net::IpAddr;
 
 /// Attempt reverse DNS lookup for an IP address using system `getnameinfo`.
 /// Returns the resolved hostname, or `None` if lookup fails or returns the
 /// raw IP string (no PTR record).
 #[cfg(unix)]
 pub(super) fn reverse_dns_lookup(ip: &std::net::IpAddr) -> Option<String> {
-    use std::net::IpAddr;
-
     unsafe {
         let mut host_buf = [0u8; 1025]; // NI_MAXHOST
 
@@ -54,3 +55,34 @@ pub(super) fn reverse_dns_lookup(ip: &std::net::IpAddr) -> Option<String> {
 pub(super) fn reverse_dns_lookup(_ip: &std::net::IpAddr) -> Option<String> {
     None
 }
+
+/// Addresses the system hosts file lists for `name`. No DNS query is sent, so the name
+/// cannot carry data out.
+pub(super) fn hosts_file_lookup(name: &str) -> Vec<IpAddr> {
+    std::fs::read_to_string(hosts_file_path())
+        .map(|content| hosts_entries(&content, name))
+        .unwrap_or_default()
+}
+
+#[cfg(not(windows))]
+fn hosts_file_path() -> std::path::PathBuf {
+    std::path::PathBuf::from("/etc/hosts")
+}
+
+#[cfg(windows)]
+fn hosts_file_path() -> std::path::PathBuf {
+    let root = std::env::var_os("SystemRoot").unwrap_or_else(|| "C:\\Windows".into());
+    std::path::Path::new(&root).join(r"System32\drivers\etc\hosts")
+}
+
+/// Addresses of `name` in hosts-file `content` (`<ip> <name> [aliases...]` lines).
+pub(super) fn hosts_entries(content: &str, name: &str) -> Vec<IpAddr> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('#').next()?.split_whitespace();
+            let ip = fields.next()?.parse::<IpAddr>().ok()?;
+            fields.any(|n| n.eq_ignore_ascii_case(name)).then_some(ip)
+        })
+        .collect()
+}
@@ -1,7 +1,7 @@
 //! HTTP Proxy server for filtering HTTP/HTTPS traffic.
 
 use std::io::{BufRead, BufReader, Write};
-use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 use std::thread;
@@ -9,7 +9,7 @@ use std::time::Duration;
 
 use skilllite_core::observability;
 
-use super::config::ProxyConfig;
+use super::config::{Authorization, ProxyConfig};
 use super::tunnel;
 
 /// HTTP Proxy server for filtering HTTP/HTTPS traffic
@@ -129,21 +129,9 @@ impl HttpProxy {
         config: &Arc<RwLock<ProxyConfig>>,
     ) -> std::io::Result<()> {
         let (host, port) = Self::parse_host_port(target, 443)?;
-
-        {
-            let cfg = config
-                .read()
-                .map_err(|e| std::io::Error::other(format!("proxy config lock: {}", e)))?;
-            if !cfg.is_domain_allowed(&host) {
-                let blocked_target = format!("{}:{}", host, port);
-                observability::security_blocked_network(
-                    "unknown",
-                    &blocked_target,
-                    "domain_not_in_allowlist",
-                );
-                return Self::send_error(client, 403, "Forbidden - Domain not in allowlist");
-            }
-        }
+        let Some(addr) = Self::authorize(client, config, &host, port)? else {
+            return Ok(());
+        };
 
         loop {
             let mut line = String::new();
@@ -153,16 +141,10 @@ impl HttpProxy {
             }
         }
 
-        let target_addr = format!("{}:{}", host, port);
-        let mut target_stream = match TcpStream::connect_timeout(
-            &target_addr.to_socket_addrs()?.next().ok_or_else(|| {
-                std::io::Error::new(std::io::ErrorKind::NotFound, "Could not resolve host")
-            })?,
-            Duration::from_secs(30),
-        ) {
+        let mut target_stream = match TcpStream::connect_timeout(&addr, Duration::from_secs(30)) {
             Ok(s) => s,
             Err(e) => {
-                tracing::warn!("[HTTP Proxy] Failed to connect to {}: {}", target_addr, e);
+                tracing::warn!("[HTTP Proxy] Failed to connect to {}:{}: {}", host, port, e);
                 return Self::send_error(client, 502, &format!("Bad Gateway - {}", e));
             }
         };
@@ -192,31 +174,13 @@ impl HttpProxy {
         _request_line: &str,
         config: &Arc<RwLock<ProxyConfig>>,
     ) -> std::io::Result<()> {
-        let host = if let Some(url) = target.strip_prefix("http://") {
-            url.split('/')
-                .next()
-                .unwrap_or("")
-                .split(':')
-                .next()
-                .unwrap_or("")
-                .to_string()
-        } else {
+        if !target.starts_with("http://") {
             return Self::send_error(client, 400, "Bad Request - Invalid URL");
-        };
-
-        {
-            let cfg = config
-                .read()
-                .map_err(|e| std::io::Error::other(format!("proxy config lock: {}", e)))?;
-            if !cfg.is_domain_allowed(&host) {
-                observability::security_blocked_network(
-                    "unknown",
-                    &host,
-                    "domain_not_in_allowlist",
-                );
-                return Self::send_error(client, 403, "Forbidden - Domain not in allowlist");
-            }
         }
+        let (host, port) = Self::parse_url_host_port(target)?;
+        let Some(addr) = Self::authorize(client, config, &host, port)? else {
+            return Ok(());
+        };
 
         let mut headers = Vec::new();
         loop {
@@ -230,15 +194,7 @@ impl HttpProxy {
             }
         }
 
-        let (target_host, target_port) = Self::parse_url_host_port(target)?;
-        let target_addr = format!("{}:{}", target_host, target_port);
-
-        let mut target_stream = match TcpStream::connect_timeout(
-            &target_addr.to_socket_addrs()?.next().ok_or_else(|| {
-                std::io::Error::new(std::io::ErrorKind::NotFound, "Could not resolve host")
-            })?,
-            Duration::from_secs(30),
-        ) {
+        let mut target_stream = match TcpStream::connect_timeout(&addr, Duration::from_secs(30)) {
             Ok(s) => s,
             Err(e) => {
                 return Self::send_error(client, 502, &format!("Bad Gateway - {}", e));
@@ -272,6 +228,33 @@ impl HttpProxy {
         )
     }
 
+    /// Resolve and check `host:port` once; `None` means an error response was sent.
+    fn authorize(
+        client: &mut TcpStream,
+        config: &Arc<RwLock<ProxyConfig>>,
+        host: &str,
+        port: u16,
+    ) -> std::io::Result<Option<SocketAddr>> {
+        let cfg = config
+            .read()
+            .map_err(|e| std::io::Error::other(format!("proxy config lock: {}", e)))?
+            .clone();
+        match cfg.authorize(host, port) {
+            Authorization::Allowed(addr) => Ok(Some(addr)),
+            Authorization::Blocked(reason) => {
+                let blocked_target = format!("{}:{}", host, port);
+                observability::security_blocked_network("unknown", &blocked_target, reason);
+                Self::send_error(client, 403, "Forbidden - Domain not in allowlist")?;
+                Ok(None)
+            }
+            Authorization::Unresolved(e) => {
+                tracing::warn!("[HTTP Proxy] Failed to resolve {}: {}", host, e);
+                Self::send_error(client, 502, &format!("Bad Gateway - {}", e))?;
+                Ok(None)
+            }
+        }
+    }
+
     fn send_error(client: &mut TcpStream, code: u16, message: &str) -> std::io::Result<()> {
         let response = format!(
             "HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\n{}\r\n",
@@ -282,7 +265,20 @@ impl HttpProxy {
         Ok(())
     }
 
+    /// Split `host[:port]`; IPv6 literals must be bracketed (`[::1]:443`).
     fn parse_host_port(s: &str, default_port: u16) -> std::io::Result<(String, u16)> {
+        if let Some(rest) = s.strip_prefix('[') {
+            let (host, after) = rest.split_once(']').ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid IPv6 host")
+            })?;
+            let port = match after.strip_prefix(':') {
+                Some(p) => p.parse::<u16>().map_err(|_| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid port")
+                })?,
+                None => default_port,
+            };
+            return Ok((host.to_string(), port));
+        }
         if let Some(pos) = s.rfind(':') {
             let host = s[..pos].to_string();
             let port = s[pos + 1..].parse::<u16>().map_err(|_| {
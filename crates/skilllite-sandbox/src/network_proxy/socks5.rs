@@ -1,7 +1,7 @@
 //! SOCKS5 Proxy server for filtering other TCP traffic.
 
 use std::io::{Read, Write};
-use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 use std::thread;
@@ -9,7 +9,7 @@ use std::time::Duration;
 
 use skilllite_core::observability;
 
-use super::config::ProxyConfig;
+use super::config::{Authorization, ProxyConfig};
 use super::tunnel;
 
 /// SOCKS5 Proxy server for filtering other TCP traffic
@@ -181,51 +181,28 @@ impl Socks5Proxy {
             }
         };
 
-        {
-            let cfg = config
-                .read()
-                .map_err(|e| std::io::Error::other(format!("proxy config lock: {}", e)))?;
-            let allowed = if atyp == 0x01 || atyp == 0x04 {
-                cfg.is_ip_connection_allowed(&host)
-            } else {
-                cfg.is_domain_allowed(&host)
-            };
-
-            if !allowed {
+        let cfg = config
+            .read()
+            .map_err(|e| std::io::Error::other(format!("proxy config lock: {}", e)))?
+            .clone();
+        let target_stream = match cfg.authorize(&host, port) {
+            Authorization::Allowed(addr) => {
+                match TcpStream::connect_timeout(&addr, Duration::from_secs(30)) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        tracing::warn!("[SOCKS5 Proxy] Failed to connect to {}: {}", addr, e);
+                        Self::send_reply(&mut client, 0x05)?;
+                        return Ok(());
+                    }
+                }
+            }
+            Authorization::Blocked(reason) => {
                 let blocked_target = format!("{}:{}", host, port);
-                let reason = if atyp == 0x01 || atyp == 0x04 {
-                    "ip_direct_connection_blocked"
-                } else {
-                    "domain_not_in_allowlist"
-                };
                 observability::security_blocked_network("unknown", &blocked_target, reason);
                 Self::send_reply(&mut client, 0x02)?;
                 return Ok(());
             }
-        }
-
-        let target_addr = format!("{}:{}", host, port);
-        let target_stream = match target_addr.as_str().to_socket_addrs() {
-            Ok(mut addrs) => {
-                if let Some(addr) = addrs.next() {
-                    match TcpStream::connect_timeout(&addr, Duration::from_secs(30)) {
-                        Ok(s) => s,
-                        Err(e) => {
-                            tracing::warn!(
-                                "[SOCKS5 Proxy] Failed to connect to {}: {}",
-                                target_addr,
-                                e
-                            );
-                            Self::send_reply(&mut client, 0x05)?;
-                            return Ok(());
-                        }
-                    }
-                } else {
-                    Self::send_reply(&mut client, 0x04)?;
-                    return Ok(());
-                }
-            }
-            Err(e) => {
+            Authorization::Unresolved(e) => {
                 tracing::warn!("[SOCKS5 Proxy] Failed to resolve {}: {}", host, e);
                 Self::send_reply(&mut client, 0x04)?;
                 return Ok(());
@@ -1,6 +1,9 @@
 //! Network proxy tests.
 
-use super::config::ProxyConfig;
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+
+use super::config::{Authorization, ProxyConfig};
 use super::http::HttpProxy;
 use super::manager::ProxyManager;
 use super::socks5::Socks5Proxy;
@@ -145,3 +148,312 @@ fn test_loopback_ip_denied_by_ip_pattern() {
     assert!(!config.is_ip_connection_allowed("127.0.0.1"));
     assert!(config.is_ip_connection_allowed("127.0.0.2"));
 }
+
+fn filtered(allowed: &[&str], denied: &[&str]) -> ProxyConfig {
+    ProxyConfig {
+        allowed_domains: allowed.iter().map(|s| s.to_string()).collect(),
+        denied_domains: denied.iter().map(|s| s.to_string()).collect(),
+        allow_all_if_empty: false,
+        allow_loopback: false,
+    }
+}
+
+/// (allowed, denied, requested host, port, dialed address, expected)
+type GrammarCase<'a> = (&'a [&'a str], &'a [&'a str], &'a str, u16, &'a str, bool);
+
+#[test]
+fn test_whitelist_grammar_table() {
+    let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+    let cases: &[GrammarCase] = &[
+        // exact host
+        (
+            &["api.github.com"],
+            &[],
+            "api.github.com",
+            443,
+            "192.0.2.1",
+            true,
+        ),
+        (
+            &["api.github.com"],
+            &[],
+            "github.com",
+            443,
+            "192.0.2.1",
+            false,
+        ),
+        // `*.suffix`: one or more labels, not the apex
+        (
+            &["*.github.com"],
+            &[],
+            "api.github.com",
+            443,
+            "192.0.2.1",
+            true,
+        ),
+        (
+            &["*.github.com"],
+            &[],
+            "a.b.github.com",
+            443,
+            "192.0.2.1",
+            true,
+        ),
+        (
+            &["*.github.com"],
+            &[],
+            "github.com",
+            443,
+            "192.0.2.1",
+            false,
+        ),
+        (
+            &["*.github.com"],
+            &[],
+            "evilgithub.com",
+            443,
+            "192.0.2.1",
+            false,
+        ),
+        // port and port range
+        (
+            &["api.github.com:443"],
+            &[],
+            "api.github.com",
+            443,
+            "192.0.2.1",
+            true,
+        ),
+        (
+            &["api.github.com:443"],
+            &[],
+            "api.github.com",
+            80,
+            "192.0.2.1",
+            false,
+        ),
+        (&["*:443-450"], &[], "example.com", 450, "192.0.2.1", true),
+        (&["*:443-450"], &[], "example.com", 451, "192.0.2.1", false),
+        // CIDR, checked against the resolved address
+        (&["10.0.0.0/8"], &[], "db.internal", 5432, "10.1.2.3", true),
+        (&["10.0.0.0/8"], &[], "db.internal", 5432, "11.1.2.3", false),
+        (
+            &["10.0.0.0/8:5432"],
+            &[],
+            "10.9.9.9",
+            5432,
+            "10.9.9.9",
+            true,
+        ),
+        (
+            &["203.0.113.7"],
+            &[],
+            "203.0.113.7",
+            22,
+            "203.0.113.7",
+            true,
+        ),
+        // deny list evaluated before allows
+        (
+            &["*.github.com"],
+            &["gist.github.com"],
+            "gist.github.com",
+            443,
+            "192.0.2.1",
+            false,
+        ),
+        (
+            &["*.github.com"],
+            &["gist.github.com"],
+            "api.github.com",
+            443,
+            "192.0.2.1",
+            true,
+        ),
+        (
+            &["*"],
+            &["10.0.0.0/8"],
+            "rebind.example",
+            80,
+            "10.0.0.1",
+            false,
+        ),
+        (
+            &["*"],
+            &["example.com:22"],
+            "example.com",
+            443,
+            "192.0.2.1",
+            true,
+        ),
+        // an invalid deny entry fails closed
+        (
+            &["*"],
+            &["bad*entry"],
+            "example.com",
+            443,
+            "192.0.2.1",
+            false,
+        ),
+        // IPv6 literals and ranges
+        (
+            &["[2001:db8::/32]:443"],
+            &[],
+            "2001:db8::1",
+            443,
+            "2001:db8::1",
+            true,
+        ),
+        (
+            &["[2001:db8::/32]:443"],
+            &[],
+            "2001:db8::1",
+            80,
+            "2001:db8::1",
+            false,
+        ),
+        (
+            &["[2001:db8::/32]:443"],
+            &[],
+            "v6.example",
+            443,
+            "2001:db9::1",
+            false,
+        ),
+        (
+            &["::ffff:0:0/96"],
+            &[],
+            "mapped",
+            80,
+            "::ffff:192.0.2.1",
+            false,
+        ),
+        (
+            &["192.0.2.0/24"],
+            &[],
+            "mapped",
+            80,
+            "::ffff:192.0.2.1",
+            true,
+        ),
+    ];
+    for (allowed, denied, host, port, addr, expected) in cases {
+        let config = filtered(allowed, denied);
+        assert_eq!(
+            config.is_connection_allowed(host, *port, ip(addr)),
+            *expected,
+            "allow {:?} deny {:?}: {}:{} via {}",
+            allowed,
+            denied,
+            host,
+            port,
+            addr
+        );
+    }
+}
+
+#[test]
+fn test_authorize_pins_the_checked_address() {
+    // `localhost` is only allowed through its resolved loopback address.
+    let config = filtered(&["127.0.0.0/8:8080", "[::1]:8080"], &[]);
+    match config.authorize("localhost", 8080) {
+        Authorization::Allowed(addr) => {
+            assert!(addr.ip().is_loopback());
+            assert_eq!(addr.port(), 8080);
+        }
+        other => panic!("expected Allowed, got {:?}", other),
+    }
+    assert!(matches!(
+        config.authorize("localhost", 8081),
+        Authorization::Blocked("domain_not_in_allowlist")
+    ));
+    assert!(matches!(
+        config.authorize("192.0.2.1", 8080),
+        Authorization::Blocked("ip_direct_connection_blocked")
+    ));
+    // Denied names are refused before resolution.
+    let config = filtered(&["*"], &["name.invalid"]);
+    assert!(matches!(
+        config.authorize("name.invalid", 80),
+        Authorization::Blocked(_)
+    ));
+}
+
+#[test]
+fn test_address_rules_never_send_names_to_dns() {
+    // Only a CIDR rule: an arbitrary name is refused without a lookup (a DNS query would
+    // already carry `secret-data` to the resolver).
+    let config = filtered(&["10.0.0.0/8"], &[]);
+    assert!(matches!(
+        config.authorize("secret-data.attacker.example", 80),
+        Authorization::Blocked("domain_not_in_allowlist")
+    ));
+}
+
+#[test]
+fn test_loopback_names_honor_the_deny_list() {
+    let config = ProxyConfig {
+        allowed_domains: vec!["github.com".to_string()],
+        denied_domains: vec!["*.localhost".to_string()],
+        allow_all_if_empty: false,
+        allow_loopback: true,
+    };
+    assert!(matches!(
+        config.authorize("app.localhost", 8080),
+        Authorization::Blocked("domain_not_in_allowlist")
+    ));
+    // Not denied: mapped to loopback without a resolver.
+    let config = ProxyConfig::with_allowed_domains(vec!["github.com".to_string()]);
+    match config.authorize("app.localhost", 8080) {
+        Authorization::Allowed(addr) => assert!(addr.ip().is_loopback()),
+        other => panic!("expected Allowed, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_hosts_file_entries() {
+    let hosts = "127.0.0.1 localhost\n# 10.0.0.9 db.internal\n10.0.0.5 db.internal db  # lab\n::1 localhost ip6-localhost\n";
+    assert_eq!(
+        super::dns::hosts_entries(hosts, "DB.internal"),
+        vec!["10.0.0.5".parse::<IpAddr>().unwrap()]
+    );
+    assert_eq!(super::dns::hosts_entries(hosts, "localhost").len(), 2);
+    assert!(super::dns::hosts_entries(hosts, "missing").is_empty());
+}
+
+#[test]
+fn test_http_proxy_connect_ipv6_literal() {
+    let Ok(target) = TcpListener::bind("[::1]:0") else {
+        return; // no IPv6 loopback in this environment
+    };
+    let port = target.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = target.accept() {
+            let _ = stream.write_all(b"pong");
+        }
+    });
+
+    let config = filtered(&[&format!("[::1]:{}", port)], &[]);
+    let mut proxy = HttpProxy::new(config).unwrap();
+    let proxy_port = proxy.port();
+    proxy.start().unwrap();
+
+    let connect = |target: String| {
+        let mut stream = TcpStream::connect(("127.0.0.1", proxy_port)).unwrap();
+        stream
+            .write_all(format!("CONNECT {} HTTP/1.1\r\n\r\n", target).as_bytes())
+            .unwrap();
+        let mut buf = [0u8; 256];
+        let n = stream.read(&mut buf).unwrap();
+        String::from_utf8_lossy(&buf[..n]).to_string()
+    };
+    let denied = connect(format!("[::1]:{}", port.wrapping_add(1).max(1)));
+    assert!(denied.contains("403"), "{}", denied);
+    let allowed = connect(format!("[::1]:{}", port));
+    assert!(
+        allowed.contains("200 Connection Established"),
+        "{}",
+        allowed
+    );
+    proxy.stop();
+}
@@ -0,0 +1,263 @@
+//! Structured skill results: `result.json` written into a per-run results directory.
+//!
+//! Besides printing JSON to stdout, a skill may write
+//! `$SKILLLITE_RESULTS_DIR/result.json`:
+//!
+//! ```json
+//! {"outputs": [{"path": "chart.png", "type": "image/png", "description": "..."}], "data": {}}
+//! ```
+//!
+//! Relative output paths resolve against the results directory; every path must stay inside
+//! the results directory or the output directory. When the file exists, the runner returns
+//! `{"stdout": <stdout JSON>, "outputs": [...], "data": {...}}` instead of bare stdout.
+//! Skills that never write `result.json` keep their stdout-only output.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::error::bail;
+use crate::{Result, SandboxError};
+
+/// File name the runner looks for inside the results directory.
+pub const RESULT_FILE: &str = "result.json";
+
+/// Subdirectory of the output directory holding one results directory per run.
+const RESULTS_SUBDIR: &str = "skill-results";
+
+/// One file produced by a skill run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillOutput {
+    /// Absolute path after validation.
+    pub path: PathBuf,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Parsed and validated `result.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SkillResult {
+    #[serde(default)]
+    pub outputs: Vec<SkillOutput>,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl SkillResult {
+    /// Extract the `outputs` list from a merged runner output (see [`ResultsDir::merge`]).
+    /// Returns an empty list for plain stdout-only outputs.
+    pub fn outputs_from_run_output(output: &str) -> Vec<SkillOutput> {
+        serde_json::from_str::<serde_json::Value>(output)
+            .ok()
+            .filter(|v| v.get("stdout").is_some())
+            .and_then(|v| v.get("outputs").cloned())
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Results directory for one skill run. Removed on drop if the skill left it empty.
+#[derive(Debug)]
+pub struct ResultsDir {
+    path: PathBuf,
+    output_root: Option<PathBuf>,
+}
+
+impl ResultsDir {
+    /// Create `<output_dir>/skill-results/<skill>-<timestamp>` (system temp dir when no
+    /// output directory is configured).
+    pub fn create(skill_name: &str) -> Result<Self> {
+        let output_root = skilllite_core::config::PathsConfig::from_env()
+            .output_dir
+            .map(PathBuf::from);
+        let base = match &output_root {
+            Some(dir) => dir.join(RESULTS_SUBDIR),
+            None => std::env::temp_dir().join("skilllite").join(RESULTS_SUBDIR),
+        };
+        Self::create_in(&base, skill_name, output_root.as_deref())
+    }
+
+    fn create_in(base: &Path, skill_name: &str, output_root: Option<&Path>) -> Result<Self> {
+        let slug: String = skill_name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f");
+        let path = base.join(format!("{}-{}-{}", slug, stamp, std::process::id()));
+        std::fs::create_dir_all(&path)?;
+        let path = path.canonicalize()?;
+        let output_root = output_root.and_then(|p| p.canonicalize().ok());
+        Ok(Self { path, output_root })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read and validate `result.json`, if the skill wrote one. The file itself is removed
+    /// once read; the output files stay.
+    pub fn collect(&self) -> Result<Option<SkillResult>> {
+        let file = self.path.join(RESULT_FILE);
+        if !file.is_file() {
+            return Ok(None);
+        }
+        let raw = std::fs::read_to_string(&file)?;
+        let _ = std::fs::remove_file(&file);
+        let mut result: SkillResult = serde_json::from_str(&raw).map_err(|e| {
+            crate::Error::run(
+                SandboxError::invalid_output(&raw),
+                format!("Invalid {}: {}", RESULT_FILE, e),
+            )
+        })?;
+        for output in &mut result.outputs {
+            output.path = self.resolve_output(&output.path)?;
+        }
+        if result.data.is_null() {
+            result.data = serde_json::json!({});
+        }
+        Ok(Some(result))
+    }
+
+    fn resolve_output(&self, path: &Path) -> Result<PathBuf> {
+        let joined = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.path.join(path)
+        };
+        let Ok(resolved) = joined.canonicalize() else {
+            bail!(
+                "{} lists an output that does not exist: {}",
+                RESULT_FILE,
+                path.display()
+            );
+        };
+        let inside = resolved.starts_with(&self.path)
+            || self
+                .output_root
+                .as_deref()
+                .is_some_and(|root| resolved.starts_with(root));
+        if !inside {
+            bail!(
+                "{} output escapes the results/output directory: {}",
+                RESULT_FILE,
+                path.display()
+            );
+        }
+        Ok(resolved)
+    }
+
+    /// Collect `result.json` and merge it with the skill's stdout JSON.
+    /// Returns `stdout` unchanged when the skill wrote no `result.json`.
+    pub fn merge(&self, stdout: &str) -> Result<String> {
+        let Some(result) = self.collect()? else {
+            return Ok(stdout.to_string());
+        };
+        let stdout_value = serde_json::from_str::<serde_json::Value>(stdout)
+            .unwrap_or_else(|_| serde_json::Value::String(stdout.to_string()));
+        Ok(serde_json::json!({
+            "stdout": stdout_value,
+            "outputs": result.outputs,
+            "data": result.data,
+        })
+        .to_string())
+    }
+}
+
+impl Drop for ResultsDir {
+    fn drop(&mut self) {
+        // Only succeeds when empty: skills without result.json leave nothing behind.
+        let _ = std::fs::remove_dir(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results_dir(root: &Path) -> ResultsDir {
+        ResultsDir::create_in(&root.join(RESULTS_SUBDIR), "chart skill", Some(root)).unwrap()
+    }
+
+    #[test]
+    fn without_result_json_stdout_is_unchanged_and_dir_removed() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = results_dir(root.path());
+        let path = dir.path().to_path_buf();
+        assert!(path.starts_with(root.path().canonicalize().unwrap()));
+        assert_eq!(dir.merge(r#"{"ok":true}"#).unwrap(), r#"{"ok":true}"#);
+        drop(dir);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn result_json_is_merged_with_stdout() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = results_dir(root.path());
+        std::fs::write(dir.path().join("a.png"), b"png").unwrap();
+        std::fs::write(root.path().join("report.md"), "# r").unwrap();
+        let report = root.path().join("report.md");
+        std::fs::write(
+            dir.path().join(RESULT_FILE),
+            serde_json::json!({
+                "outputs": [
+                    {"path": "a.png", "type": "image/png", "description": "first chart"},
+                    {"path": report},
+                ],
+                "data": {"rows": 3},
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let merged: serde_json::Value =
+            serde_json::from_str(&dir.merge(r#"{"ok":true}"#).unwrap()).unwrap();
+        assert_eq!(merged["stdout"], serde_json::json!({"ok": true}));
+        assert_eq!(merged["data"]["rows"], 3);
+        let outputs = SkillResult::outputs_from_run_output(&merged.to_string());
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].path, dir.path().join("a.png"));
+        assert_eq!(outputs[0].kind.as_deref(), Some("image/png"));
+        assert_eq!(outputs[1].path, report.canonicalize().unwrap());
+        assert!(!dir.path().join(RESULT_FILE).exists());
+    }
+
+    #[test]
+    fn outputs_outside_results_and_output_dir_are_rejected() {
+        let root = tempfile::tempdir().unwrap();
+        let elsewhere = tempfile::tempdir().unwrap();
+        std::fs::write(elsewhere.path().join("secret.txt"), "x").unwrap();
+        let dir = results_dir(root.path());
+
+        for path in [
+            serde_json::json!(elsewhere.path().join("secret.txt")),
+            serde_json::json!("../../../secret.txt"),
+            serde_json::json!("missing.png"),
+        ] {
+            std::fs::write(
+                dir.path().join(RESULT_FILE),
+                serde_json::json!({"outputs": [{"path": path}]}).to_string(),
+            )
+            .unwrap();
+            assert!(dir.merge("{}").is_err(), "{path} should be rejected");
+        }
+    }
+
+    #[test]
+    fn malformed_result_json_is_invalid_output() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = results_dir(root.path());
+        std::fs::write(dir.path().join(RESULT_FILE), "not json").unwrap();
+        let err = dir.merge("{}").unwrap_err();
+        assert_eq!(
+            err.sandbox_error().map(|e| e.kind()),
+            Some("invalid_output")
+        );
+    }
+}
@@ -1,6 +1,13 @@
-use crate::error::bail;
+use crate::common::resolve_script_args;
+use crate::error::SandboxError;
+use crate::move_protection::{
+    find_launch_violations, EntryFingerprint, IntegrityViolation, LAUNCH_INTEGRITY_BLOCKED,
+};
+use crate::results::ResultsDir;
+use crate::sandbox_audit;
 use crate::security::{run_skill_precheck, SKILL_PRECHECK_CRITICAL_BLOCKED};
 use crate::Result;
+use serde::Serialize;
 use skilllite_core::observability;
 use std::io::{self, IsTerminal, Write};
 use std::path::Path;
@@ -12,6 +19,45 @@ pub struct ExecutionResult {
     pub stdout: String,
     pub stderr: String,
     pub exit_code: i32,
+    /// Mechanism that enforced [`ResourceLimits`] for this run.
+    pub enforcement: LimitEnforcement,
+    /// Syscalls the seccomp profile trapped (Linux user-notification mode only).
+    pub syscall_violations: Vec<SyscallViolation>,
+    /// Seccomp profile the run was filtered with; `"none"` when a Linux sandbox ran without
+    /// one, `None` where seccomp does not apply (other platforms, level 1).
+    pub seccomp_profile: Option<String>,
+}
+
+/// Key of the run metadata object (limit enforcement, seccomp profile) added to successful
+/// results.
+pub const RUN_METADATA_KEY: &str = "sandbox";
+
+/// A syscall denied by the run's seccomp profile, see [`crate::seccomp`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyscallViolation {
+    /// Profile rule that matched, e.g. `ptrace` or `socket(AF_UNIX)`.
+    pub syscall: String,
+    /// Caller pid, as seen from outside the sandbox.
+    pub pid: u32,
+    pub profile: String,
+    /// `true` when the call failed with EPERM (`enforce`), `false` when it ran (`log`).
+    pub blocked: bool,
+}
+
+/// How memory / process limits were enforced for a run. Reported as `enforcement` under
+/// [`RUN_METADATA_KEY`] in successful results and in [`SandboxError::MemoryExceeded`] details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitEnforcement {
+    /// Linux transient cgroup (`memory.max` / `pids.max` / `cpu.max`), see [`crate::cgroup`].
+    /// The child and everything it forks are limited as one unit.
+    CgroupV2,
+    /// Windows Job Object.
+    JobObject,
+    /// rlimits plus RSS polling of the direct child (Unix fallback).
+    RlimitPolling,
+    /// No memory limit applied by SkillLite (e.g. WSL, firejail).
+    Unenforced,
 }
 
 /// Resolved runtime paths for sandbox execution.
@@ -47,8 +93,17 @@ pub struct SandboxConfig {
     pub network_enabled: bool,
     /// Allowed outbound hosts (e.g. ["*"] for wildcard)
     pub network_outbound: Vec<String>,
+    /// Outbound entries refused before `network_outbound` is consulted
+    pub network_deny: Vec<String>,
     /// Whether the skill uses Playwright (requires relaxed sandbox on macOS)
     pub uses_playwright: bool,
+    /// Extra command-line arguments appended after the entry point (`skilllite exec --args`).
+    pub script_args: Vec<String>,
+    /// Per-run results directory (`SKILLLITE_RESULTS_DIR`), writable inside the sandbox.
+    /// Set by the runner; see [`crate::results`].
+    pub results_dir: Option<std::path::PathBuf>,
+    /// Ignore `SKILLLITE_NO_SANDBOX` (evolution-generated skills must stay isolated).
+    pub enforce_isolation: bool,
 }
 
 /// Sandbox security levels
@@ -66,7 +121,7 @@ pub enum SandboxLevel {
 impl SandboxLevel {
     /// Parse sandbox level from string or config (CLI overrides env/config)
     pub fn from_env_or_cli(cli_level: Option<u8>) -> Self {
-        // Priority: CLI > Config (SKILLLITE_* / SKILLBOX_*) > Default (Level 3)
+        // Priority: CLI > env (SKILLLITE_* / SKILLBOX_*) > config files > Default (Level 3)
         if let Some(level) = cli_level {
             return match level {
                 1 => Self::Level1,
@@ -78,8 +133,7 @@ impl SandboxLevel {
                 }
             };
         }
-        let cfg = skilllite_core::config::SandboxEnvConfig::from_env();
-        match cfg.sandbox_level {
+        match skilllite_core::config::Settings::load().sandbox_level() {
             1 => Self::Level1,
             2 => Self::Level2,
             3 => Self::Level3,
@@ -87,6 +141,15 @@ impl SandboxLevel {
         }
     }
 
+    /// Numeric level (1/2/3), as used by `SKILLLITE_SANDBOX_LEVEL`.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Self::Level1 => 1,
+            Self::Level2 => 2,
+            Self::Level3 => 3,
+        }
+    }
+
     /// Check if sandbox should be used
     pub fn use_sandbox(&self) -> bool {
         !matches!(self, Self::Level1)
@@ -109,6 +172,9 @@ pub struct ResourceLimits {
     pub max_memory_mb: u64,
     /// Execution timeout in seconds (default: 30)
     pub timeout_secs: u64,
+    /// CPU quota in percent of one core (default: unlimited). Only enforced by the
+    /// Linux cgroup v2 backend (`cpu.max`).
+    pub max_cpu_percent: Option<u32>,
 }
 
 impl Default for ResourceLimits {
@@ -118,12 +184,19 @@ impl Default for ResourceLimits {
 }
 
 /// Optional behavior for [`run_in_sandbox_with_limits_and_level_opt`].
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Default)]
 pub struct SandboxRunOptions {
     /// When `true`, skip the unified skill precheck (`SKILL.md` + entry [`ScriptScanner`]) before spawn.
     /// Use when the caller (e.g. agent or MCP Level 3) already ran [`crate::security::run_skill_precheck`]
     /// with the same policy and obtained consent.
     pub skip_skill_precheck: bool,
+    /// When `true`, run the skill unconfined and attach a [`crate::sandbox_audit`] report of the
+    /// operations `level` would have blocked (`skilllite run --audit`).
+    pub audit: bool,
+    /// Entry fingerprint taken when the caller scanned the skill (set together with
+    /// `skip_skill_precheck`). The runner verifies the launch against it; when `None` it
+    /// fingerprints the entry itself before its own precheck.
+    pub entry_fingerprint: Option<EntryFingerprint>,
 }
 
 impl ResourceLimits {
@@ -132,12 +205,13 @@ impl ResourceLimits {
         self.max_memory_mb * 1024 * 1024
     }
 
-    /// Load resource limits from config (SKILLLITE_* / SKILLBOX_* 统一走 config)
+    /// Load resource limits from layered settings (config files, SKILLLITE_* / SKILLBOX_* env, CLI)
     pub fn from_env() -> Self {
-        let cfg = skilllite_core::config::SandboxEnvConfig::from_env();
+        let settings = skilllite_core::config::Settings::load();
         Self {
-            max_memory_mb: cfg.max_memory_mb,
-            timeout_secs: cfg.timeout_secs,
+            max_memory_mb: settings.max_memory_mb(),
+            timeout_secs: settings.timeout_secs(),
+            max_cpu_percent: settings.max_cpu_percent(),
         }
     }
 
@@ -227,7 +301,10 @@ fn skill_precheck_enforce_interactive_consent(skill_id: &str, report: &str) -> R
     };
 
     if !approved {
-        bail!("Script execution blocked: User denied authorization after skill static precheck");
+        return Err(crate::Error::run(
+            SandboxError::ConsentDenied,
+            "Script execution blocked: User denied authorization after skill static precheck",
+        ));
     }
     Ok(())
 }
@@ -272,8 +349,28 @@ pub fn run_in_sandbox_with_limits_and_level_opt(
             SandboxLevel::Level3 => "Sandbox isolation + static code scanning",
         },
         skip_skill_precheck = options.skip_skill_precheck,
+        audit = options.audit,
         "Sandbox execution start"
     );
+    let span = tracing::info_span!(
+        "sandbox.run",
+        skill = %config.name,
+        sandbox_level = ?level,
+        timeout_secs = limits.timeout_secs,
+        max_memory_mb = limits.max_memory_mb,
+        exit_code = tracing::field::Empty,
+        error_kind = tracing::field::Empty,
+        kill_reason = tracing::field::Empty,
+    );
+    let _span = span.enter();
+
+    // Fingerprint the entry before the precheck reads it (unless the caller already did at its
+    // scan); re-verified right before launch. An entry that cannot be fingerprinted fails the
+    // launch there rather than skipping the check.
+    let entry_fingerprint = match options.entry_fingerprint {
+        Some(fingerprint) => Ok(fingerprint),
+        None => capture_entry(skill_dir, config)?,
+    };
 
     // Pre-spawn static precheck: SKILL.md + entry script (all levels L1–L3). Skip when the caller
     // already gated (agent desktop, MCP Level 3).
@@ -286,43 +383,98 @@ pub fn run_in_sandbox_with_limits_and_level_opt(
                 .filter(|s| !s.trim().is_empty())
                 .map(|s| format!("\n\n{}", s))
                 .unwrap_or_default();
-            bail!("{}{}", SKILL_PRECHECK_CRITICAL_BLOCKED, tail);
+            return Err(crate::Error::run(
+                SandboxError::scan_blocked(summary.review_text.as_deref()),
+                format!("{}{}", SKILL_PRECHECK_CRITICAL_BLOCKED, tail),
+            ));
         }
         if let Some(ref report) = summary.review_text {
             skill_precheck_enforce_interactive_consent(&config.name, report)?;
         }
     }
 
+    verify_launch_integrity(skill_dir, runtime, config, &entry_fingerprint)?;
+
+    // Per-run results directory for the optional `result.json` contract (crate::results).
+    let results = match ResultsDir::create(&config.name) {
+        Ok(dir) => Some(dir),
+        Err(e) => {
+            tracing::warn!("Failed to create skill results directory: {}", e);
+            None
+        }
+    };
+    let config = &SandboxConfig {
+        results_dir: results.as_ref().map(|d| d.path().to_path_buf()),
+        ..config.clone()
+    };
+    let finish = |output: &str, result: &ExecutionResult| {
+        let merged = match &results {
+            Some(dir) => dir.merge(output)?,
+            None => output.to_string(),
+        };
+        Ok(attach_run_metadata(&merged, result))
+    };
+
+    // Audit mode: execute unconfined, report what `level` would have blocked
+    if options.audit {
+        tracing::warn!(
+            "Sandbox audit mode - running without isolation to record would-be violations"
+        );
+        let script_args = resolve_script_args(config);
+        let audit_args: Vec<&str> = script_args.iter().map(String::as_str).collect();
+        observability::audit_command_invoked(
+            &config.name,
+            &config.entry_point,
+            &audit_args,
+            skill_dir.to_string_lossy().as_ref(),
+        );
+        let start = Instant::now();
+        let (result, report) =
+            sandbox_audit::execute_audited(skill_dir, runtime, config, input_json, limits, level)?;
+
+        // The report is not part of a failed run's error; keep it visible in the log.
+        check_run_output(&result, config, limits)
+            .inspect_err(|_| tracing::warn!("{}", report.format_report()))?;
+        let output = result.stdout.trim();
+
+        observability::audit_execution_completed(
+            &config.name,
+            result.exit_code,
+            start.elapsed().as_millis() as u64,
+            result.stdout.len(),
+        );
+        observability::audit_skill_invocation(
+            &config.name,
+            &config.entry_point,
+            skill_dir.to_string_lossy().as_ref(),
+            input_json,
+            output,
+            result.exit_code,
+            start.elapsed().as_millis() as u64,
+        );
+        return finish(output, &result)
+            .map(|merged| sandbox_audit::attach_report(&merged, &report));
+    }
+
     // Level 1: Execute without sandbox
     if !level.use_sandbox() {
         tracing::warn!(
             "Running without sandbox (Level 1) - no isolation, but with resource limits"
         );
+        let script_args = resolve_script_args(config);
+        let audit_args: Vec<&str> = script_args.iter().map(String::as_str).collect();
         observability::audit_command_invoked(
             &config.name,
             &config.entry_point,
-            &[],
+            &audit_args,
             skill_dir.to_string_lossy().as_ref(),
         );
         let start = Instant::now();
         let result =
             execute_simple_without_sandbox(skill_dir, runtime, config, input_json, limits)?;
 
-        if result.exit_code != 0 {
-            bail!(
-                "Skill execution failed with exit code {}: {}",
-                result.exit_code,
-                result.stderr
-            );
-        }
-
+        check_run_output(&result, config, limits)?;
         let output = result.stdout.trim();
-        let _: serde_json::Value = serde_json::from_str(output).map_err(|e| {
-            crate::Error::validation(format!(
-                "Skill output is not valid JSON: {} - Output: {}",
-                e, output
-            ))
-        })?;
 
         observability::audit_execution_completed(
             &config.name,
@@ -339,35 +491,33 @@ pub fn run_in_sandbox_with_limits_and_level_opt(
             result.exit_code,
             start.elapsed().as_millis() as u64,
         );
-        return Ok(output.to_string());
+        return finish(output, &result);
     }
 
     // Level 2 & 3: Execute with sandbox
+    let script_args = resolve_script_args(config);
+    let audit_args: Vec<&str> = script_args.iter().map(String::as_str).collect();
     observability::audit_command_invoked(
         &config.name,
         &config.entry_point,
-        &[] as &[&str],
+        &audit_args,
         skill_dir.to_string_lossy().as_ref(),
     );
     let start = Instant::now();
-    let result =
-        execute_platform_sandbox_with_limits(skill_dir, runtime, config, input_json, limits)?;
-
-    if result.exit_code != 0 {
-        bail!(
-            "Skill execution failed with exit code {}: {}",
-            result.exit_code,
-            result.stderr
+    let result = execute_platform_sandbox_with_limits(
+        skill_dir, runtime, config, input_json, limits, level,
+    )?;
+    for violation in &result.syscall_violations {
+        observability::security_seccomp_violation(
+            &config.name,
+            &violation.syscall,
+            &violation.profile,
+            violation.blocked,
         );
     }
 
+    check_run_output(&result, config, limits)?;
     let output = result.stdout.trim();
-    let _: serde_json::Value = serde_json::from_str(output).map_err(|e| {
-        crate::Error::validation(format!(
-            "Skill output is not valid JSON: {} - Output: {}",
-            e, output
-        ))
-    })?;
 
     observability::audit_execution_completed(
         &config.name,
@@ -384,7 +534,128 @@ pub fn run_in_sandbox_with_limits_and_level_opt(
         result.exit_code,
         start.elapsed().as_millis() as u64,
     );
-    Ok(output.to_string())
+    finish(output, &result)
+}
+
+/// Merge run metadata into `output` under [`RUN_METADATA_KEY`]: objects gain the field,
+/// other values are wrapped as `{"stdout": ..., "sandbox": ...}`.
+fn attach_run_metadata(output: &str, result: &ExecutionResult) -> String {
+    let mut metadata = serde_json::json!({ "enforcement": result.enforcement });
+    if let Some(profile) = &result.seccomp_profile {
+        metadata["seccomp_profile"] = serde_json::Value::String(profile.clone());
+    }
+    match serde_json::from_str::<serde_json::Value>(output) {
+        Ok(serde_json::Value::Object(mut map)) => {
+            map.insert(RUN_METADATA_KEY.to_string(), metadata);
+            serde_json::Value::Object(map)
+        }
+        Ok(other) => serde_json::json!({ "stdout": other, RUN_METADATA_KEY: metadata }),
+        Err(_) => serde_json::json!({ "stdout": output, RUN_METADATA_KEY: metadata }),
+    }
+    .to_string()
+}
+
+/// Fingerprint the configured entry script. A missing entry is [`SandboxError::EntryPointMissing`];
+/// any other failure becomes the violation [`verify_launch_integrity`] refuses the launch with.
+fn capture_entry(
+    skill_dir: &Path,
+    config: &SandboxConfig,
+) -> Result<std::result::Result<EntryFingerprint, IntegrityViolation>> {
+    let path = skill_dir.join(&config.entry_point);
+    match EntryFingerprint::capture(&path) {
+        Ok(fingerprint) => Ok(Ok(fingerprint)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(crate::Error::run(
+            SandboxError::EntryPointMissing {
+                entry_point: config.entry_point.clone(),
+            },
+            format!("Entry point not found: {}", path.display()),
+        )),
+        Err(e) => Ok(Err(IntegrityViolation::EntrySwapped {
+            path,
+            reason: format!("cannot be fingerprinted ({})", e),
+        })),
+    }
+}
+
+/// Refuse to launch when the skill dir or reused env dir holds links escaping them, or the
+/// entry script is not the file that was scanned. Violations are audited.
+fn verify_launch_integrity(
+    skill_dir: &Path,
+    runtime: &RuntimePaths,
+    config: &SandboxConfig,
+    entry: &std::result::Result<EntryFingerprint, IntegrityViolation>,
+) -> Result<()> {
+    let env_dir = Some(runtime.env_dir.as_path()).filter(|p| !p.as_os_str().is_empty());
+    // Venv interpreters are symlinks to the base runtime outside the env dir.
+    let interpreters: Vec<std::path::PathBuf> = [&runtime.python, &runtime.node]
+        .into_iter()
+        .filter_map(|p| which::which(p).ok())
+        .filter_map(|p| std::fs::canonicalize(p).ok())
+        .collect();
+
+    let mut violations = find_launch_violations(skill_dir, env_dir, &interpreters);
+    if let Err(v) = entry
+        .as_ref()
+        .map_err(Clone::clone)
+        .and_then(EntryFingerprint::verify)
+    {
+        violations.push(v);
+    }
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let findings: Vec<String> = violations.iter().map(ToString::to_string).collect();
+    observability::security_integrity_violation(&config.name, &findings);
+    tracing::Span::current().record("error_kind", "integrity_violation");
+    Err(crate::Error::run(
+        SandboxError::IntegrityViolation {
+            findings: findings.clone(),
+        },
+        format!("{}\n\n{}", LAUNCH_INTEGRITY_BLOCKED, findings.join("\n")),
+    ))
+}
+
+/// Fail with a classified [`SandboxError`] on non-zero exit or non-JSON stdout.
+/// The message text matches what callers have always matched on.
+fn check_run_output(
+    result: &ExecutionResult,
+    config: &SandboxConfig,
+    limits: ResourceLimits,
+) -> Result<()> {
+    let span = tracing::Span::current();
+    span.record("exit_code", result.exit_code);
+    if result.exit_code != 0 {
+        let error = SandboxError::from_failed_run(
+            result.exit_code,
+            &result.stdout,
+            &result.stderr,
+            limits.timeout_secs,
+            limits.max_memory_mb,
+            config.network_enabled,
+            result.enforcement,
+        );
+        span.record("error_kind", error.kind());
+        if let SandboxError::MemoryExceeded { kill_reason, .. } = &error {
+            span.record("kill_reason", *kill_reason);
+        } else if let SandboxError::Timeout { .. } = error {
+            span.record("kill_reason", error.kind());
+        }
+        return Err(crate::Error::run(
+            error,
+            format!(
+                "Skill execution failed with exit code {}: {}",
+                result.exit_code, result.stderr
+            ),
+        ));
+    }
+    let output = result.stdout.trim();
+    if let Err(e) = serde_json::from_str::<serde_json::Value>(output) {
+        return Err(crate::Error::run(
+            SandboxError::invalid_output(output),
+            format!("Skill output is not valid JSON: {} - Output: {}", e, output),
+        ));
+    }
+    Ok(())
 }
 
 #[cfg(target_os = "linux")]
@@ -394,8 +665,9 @@ fn execute_platform_sandbox_with_limits(
     config: &SandboxConfig,
     input_json: &str,
     limits: ResourceLimits,
+    level: SandboxLevel,
 ) -> Result<ExecutionResult> {
-    super::linux::execute_with_limits(skill_dir, runtime, config, input_json, limits)
+    super::linux::execute_at_level(skill_dir, runtime, config, input_json, limits, level)
 }
 
 #[cfg(target_os = "macos")]
@@ -405,6 +677,7 @@ fn execute_platform_sandbox_with_limits(
     config: &SandboxConfig,
     input_json: &str,
     limits: ResourceLimits,
+    _level: SandboxLevel,
 ) -> Result<ExecutionResult> {
     super::macos::execute_with_limits(skill_dir, runtime, config, input_json, limits)
 }
@@ -416,6 +689,7 @@ fn execute_platform_sandbox_with_limits(
     config: &SandboxConfig,
     input_json: &str,
     limits: ResourceLimits,
+    _level: SandboxLevel,
 ) -> Result<ExecutionResult> {
     super::windows::execute_with_limits(skill_dir, runtime, config, input_json, limits)
 }
@@ -427,8 +701,9 @@ fn execute_platform_sandbox_with_limits(
     _config: &SandboxConfig,
     _input_json: &str,
     _limits: ResourceLimits,
+    _level: SandboxLevel,
 ) -> Result<ExecutionResult> {
-    bail!("Unsupported platform. Only Linux, macOS, and Windows are supported.")
+    crate::error::bail!("Unsupported platform. Only Linux, macOS, and Windows are supported.")
 }
 
 /// Execute without any sandbox (Level 1)
@@ -455,13 +730,33 @@ fn execute_simple_without_sandbox(
     );
 
     #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
-    bail!("Unsupported platform. Only Linux, macOS, and Windows are supported.")
+    crate::error::bail!("Unsupported platform. Only Linux, macOS, and Windows are supported.")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn run_metadata_reports_enforcement_and_seccomp_profile() {
+        let result = ExecutionResult {
+            stdout: String::new(),
+            stderr: String::new(),
+            exit_code: 0,
+            enforcement: LimitEnforcement::CgroupV2,
+            syscall_violations: Vec::new(),
+            seccomp_profile: Some("none".to_string()),
+        };
+        let merged: serde_json::Value =
+            serde_json::from_str(&attach_run_metadata(r#"{"ok": true}"#, &result)).unwrap();
+        assert_eq!(merged["ok"], true);
+        assert_eq!(merged[RUN_METADATA_KEY]["enforcement"], "cgroup_v2");
+        assert_eq!(merged[RUN_METADATA_KEY]["seccomp_profile"], "none");
+        let wrapped: serde_json::Value =
+            serde_json::from_str(&attach_run_metadata("[1]", &result)).unwrap();
+        assert_eq!(wrapped["stdout"], serde_json::json!([1]));
+    }
+
     #[test]
     fn sandbox_level_from_cli_maps_1_2_3() {
         assert_eq!(SandboxLevel::from_env_or_cli(Some(1)), SandboxLevel::Level1);
@@ -490,6 +785,7 @@ mod tests {
         let lim = ResourceLimits {
             max_memory_mb: 128,
             timeout_secs: 10,
+            max_cpu_percent: None,
         };
         assert_eq!(lim.max_memory_bytes(), 128 * 1024 * 1024);
     }
@@ -499,6 +795,7 @@ mod tests {
         let base = ResourceLimits {
             max_memory_mb: 100,
             timeout_secs: 20,
+            max_cpu_percent: None,
         };
         let o = base.with_cli_overrides(Some(512), Some(60));
         assert_eq!(o.max_memory_mb, 512);
@@ -512,5 +809,7 @@ mod tests {
     fn sandbox_run_options_default_does_not_skip_skill_precheck() {
         let o = SandboxRunOptions::default();
         assert!(!o.skip_skill_precheck);
+        assert!(!o.audit);
+        assert!(o.entry_fingerprint.is_none());
     }
 }
@@ -0,0 +1,704 @@
+//! Sandbox audit mode: run a skill unconfined and report what the sandbox would have blocked.
+//!
+//! Audit mode is a trial run for authors who suspect the sandbox is too strict (or not strict
+//! enough). The skill executes without isolation, every observed operation is checked against
+//! the same policy the platform sandboxes enforce ([`crate::security::policy`]), and the
+//! would-be violations are returned as a `sandbox_audit` section in the result JSON.
+//!
+//! Backends:
+//! - **Linux**: `strace -f` over file, network and process syscalls (when `strace` is on `PATH`).
+//! - **Everywhere else** (and Linux without `strace`): a snapshot diff of the skill directory,
+//!   which catches writes the read-only skill mount would have refused. Writes elsewhere,
+//!   sensitive reads, network and process activity are not traced there; the report lists
+//!   those categories in `not_captured` so an empty section is not mistaken for a clean run.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::runner::{ExecutionResult, ResourceLimits, RuntimePaths, SandboxConfig, SandboxLevel};
+use crate::security::policy::{self as security_policy, HomePathStyle, ResolvedNetworkPolicy};
+use crate::Result;
+
+/// Key of the audit section in the result JSON.
+pub const SANDBOX_AUDIT_KEY: &str = "sandbox_audit";
+
+/// Example paths/hosts kept per category.
+const MAX_EXAMPLES: usize = 5;
+
+/// Files visited when snapshotting the skill directory (guards against huge trees).
+const MAX_SNAPSHOT_FILES: usize = 20_000;
+
+/// Kind of operation the sandbox would have blocked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditCategory {
+    /// Write outside the work/results directories (including the read-only skill directory).
+    FileWrite,
+    /// Read of a sensitive path (`~/.ssh`, `~/.aws`, ...).
+    FileRead,
+    /// Outbound connection not allowed by the network policy, or any Unix socket.
+    Network,
+    /// Spawn of a denylisted program (shells, curl, ssh, ...).
+    Process,
+}
+
+impl AuditCategory {
+    fn key(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default()
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::FileWrite => "File writes outside the sandbox",
+            Self::FileRead => "Sensitive file reads",
+            Self::Network => "Network connections",
+            Self::Process => "Blocked process launches",
+        }
+    }
+}
+
+/// One operation observed while the skill ran unconfined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditEvent {
+    Read(PathBuf),
+    Write(PathBuf),
+    /// `ip:port`, `[ipv6]:port` or `unix:<path>`.
+    Connect(String),
+    Exec(PathBuf),
+}
+
+/// Would-block rules derived from the policy the platform sandboxes enforce.
+#[derive(Debug, Clone)]
+pub struct AuditPolicy {
+    skill_dir: PathBuf,
+    writable: Vec<PathBuf>,
+    sensitive_reads: Vec<PathBuf>,
+    network: ResolvedNetworkPolicy,
+    exec_denylist: Vec<&'static str>,
+}
+
+impl AuditPolicy {
+    /// Policy for `config` at `level` (Level 2 uses the relaxed process denylist).
+    pub fn new(skill_dir: &Path, config: &SandboxConfig, level: SandboxLevel) -> Self {
+        let platform = if cfg!(target_os = "macos") {
+            HomePathStyle::MacOS
+        } else {
+            HomePathStyle::Linux
+        };
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let mut sensitive_reads = Vec::new();
+        if let Some(ref home) = home {
+            for rel in security_policy::get_sensitive_read_home_relative_paths() {
+                sensitive_reads.push(home.join(rel));
+            }
+            for rel in security_policy::MANDATORY_DENY_DIRECTORIES {
+                sensitive_reads.push(home.join(rel));
+            }
+        }
+        Self {
+            skill_dir: skill_dir.to_path_buf(),
+            writable: config.results_dir.iter().cloned().collect(),
+            sensitive_reads,
+            network: security_policy::resolve_network_policy(
+                config.network_enabled,
+                &config.network_outbound,
+                &config.network_deny,
+            ),
+            exec_denylist: security_policy::get_process_exec_denylist(
+                level == SandboxLevel::Level2,
+                platform,
+            ),
+        }
+    }
+
+    /// Treat `dir` as writable (the per-run work directory mounted at `/tmp`).
+    pub fn with_writable(mut self, dir: &Path) -> Self {
+        self.writable.push(dir.to_path_buf());
+        self
+    }
+
+    /// Category the sandbox would have blocked `event` under, if any.
+    pub fn would_block(&self, event: &AuditEvent) -> Option<AuditCategory> {
+        match event {
+            AuditEvent::Read(path) => {
+                let path = self.absolute(path);
+                self.sensitive_reads
+                    .iter()
+                    .any(|p| path.starts_with(p))
+                    .then_some(AuditCategory::FileRead)
+            }
+            AuditEvent::Write(path) => {
+                let path = self.absolute(path);
+                let allowed = path.starts_with("/dev")
+                    || path.starts_with("/proc/self")
+                    || self.writable.iter().any(|w| path.starts_with(w));
+                (!allowed).then_some(AuditCategory::FileWrite)
+            }
+            AuditEvent::Connect(addr) => {
+                if addr.starts_with("unix:") {
+                    // AF_UNIX is refused by the seccomp filter regardless of network policy.
+                    return Some(AuditCategory::Network);
+                }
+                match self.network {
+                    ResolvedNetworkPolicy::AllowAll => None,
+                    // Direct connections bypass the filtering proxy; they are listed for review.
+                    ResolvedNetworkPolicy::BlockAll
+                    | ResolvedNetworkPolicy::ProxyFiltered { .. } => Some(AuditCategory::Network),
+                }
+            }
+            AuditEvent::Exec(program) => {
+                let name = program.file_name();
+                self.exec_denylist
+                    .iter()
+                    .any(|d| program == Path::new(d) || name == Path::new(d).file_name())
+                    .then_some(AuditCategory::Process)
+            }
+        }
+    }
+
+    fn absolute(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.skill_dir.join(path)
+        }
+    }
+}
+
+/// Would-be violations for one category.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CategorySummary {
+    /// Distinct paths/hosts/programs.
+    pub count: usize,
+    /// First few targets, in observation order.
+    pub examples: Vec<String>,
+    #[serde(skip)]
+    seen: BTreeSet<String>,
+}
+
+/// Result of an audit-mode run, merged into the output as [`SANDBOX_AUDIT_KEY`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SandboxAuditReport {
+    /// How operations were observed: `strace`, `snapshot`.
+    pub backend: String,
+    /// Sandbox level the skill would normally run at.
+    pub level: u8,
+    pub categories: BTreeMap<AuditCategory, CategorySummary>,
+    /// Categories the backend could not observe at all; their absence from `categories`
+    /// says nothing about the skill.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_captured: Vec<AuditCategory>,
+    /// Backend limitations and caveats (e.g. what was not traced).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+impl SandboxAuditReport {
+    pub fn new(backend: &str, level: SandboxLevel) -> Self {
+        Self {
+            backend: backend.to_string(),
+            level: level.as_u8(),
+            categories: BTreeMap::new(),
+            not_captured: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Record a would-be violation; repeated targets are counted once.
+    pub fn record(&mut self, category: AuditCategory, target: &str) {
+        let summary = self.categories.entry(category).or_default();
+        if summary.seen.insert(target.to_string()) {
+            summary.count += 1;
+            if summary.examples.len() < MAX_EXAMPLES {
+                summary.examples.push(target.to_string());
+            }
+        }
+    }
+
+    /// Check `event` against `policy` and record it when it would have been blocked.
+    pub fn observe(&mut self, policy: &AuditPolicy, event: &AuditEvent) {
+        if let Some(category) = policy.would_block(event) {
+            let target = match event {
+                AuditEvent::Read(p) | AuditEvent::Write(p) | AuditEvent::Exec(p) => {
+                    p.display().to_string()
+                }
+                AuditEvent::Connect(addr) => addr.clone(),
+            };
+            self.record(category, &target);
+        }
+    }
+
+    pub fn note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+
+    /// Total distinct would-be violations across categories.
+    pub fn total(&self) -> usize {
+        self.categories.values().map(|c| c.count).sum()
+    }
+
+    /// Parse the audit section back out of a run's output JSON.
+    pub fn from_output(output: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(output).ok()?;
+        serde_json::from_value(value.get(SANDBOX_AUDIT_KEY)?.clone()).ok()
+    }
+
+    /// Human-readable report in the style of the security scan output.
+    pub fn format_report(&self) -> String {
+        let mut output = if self.total() == 0 && self.not_captured.is_empty() {
+            format!(
+                "✅ Sandbox audit (L{}, {}): nothing would have been blocked.\n",
+                self.level, self.backend
+            )
+        } else if self.total() == 0 {
+            format!(
+                "✅ Sandbox audit (L{}, {}): nothing observed would have been blocked.\n",
+                self.level, self.backend
+            )
+        } else {
+            format!(
+                "📋 Sandbox audit (L{}, {}): {} operation(s) would have been blocked\n\n",
+                self.level,
+                self.backend,
+                self.total()
+            )
+        };
+        for (category, summary) in &self.categories {
+            output.push_str(&format!(
+                "  🟠 {}× {} [{}]\n",
+                summary.count,
+                category.label(),
+                category.key()
+            ));
+            for (idx, example) in summary.examples.iter().enumerate() {
+                let branch = if idx + 1 == summary.examples.len() {
+                    "└─"
+                } else {
+                    "├─"
+                };
+                output.push_str(&format!("     {} {}\n", branch, example));
+            }
+        }
+        if !self.not_captured.is_empty() {
+            let keys: Vec<String> = self.not_captured.iter().map(|c| c.key()).collect();
+            output.push_str(&format!(
+                "\n⚠️ Not captured by the {} backend: {}",
+                self.backend,
+                keys.join(", ")
+            ));
+        }
+        for note in &self.notes {
+            output.push_str(&format!("\nℹ️ {}", note));
+        }
+        output
+    }
+}
+
+/// Merge `report` into the run output: objects gain a [`SANDBOX_AUDIT_KEY`] field, other values
+/// are wrapped as `{"stdout": ..., "sandbox_audit": ...}`.
+pub fn attach_report(output: &str, report: &SandboxAuditReport) -> String {
+    let audit = serde_json::to_value(report).unwrap_or(Value::Null);
+    let merged = match serde_json::from_str::<Value>(output) {
+        Ok(Value::Object(mut map)) => {
+            map.insert(SANDBOX_AUDIT_KEY.to_string(), audit);
+            Value::Object(map)
+        }
+        Ok(other) => serde_json::json!({ "stdout": other, SANDBOX_AUDIT_KEY: audit }),
+        Err(_) => serde_json::json!({ "stdout": output, SANDBOX_AUDIT_KEY: audit }),
+    };
+    merged.to_string()
+}
+
+/// Run the skill unconfined and collect an audit report with the best available backend.
+pub fn execute_audited(
+    skill_dir: &Path,
+    runtime: &RuntimePaths,
+    config: &SandboxConfig,
+    input_json: &str,
+    limits: ResourceLimits,
+    level: SandboxLevel,
+) -> Result<(ExecutionResult, SandboxAuditReport)> {
+    let policy = AuditPolicy::new(skill_dir, config, level);
+
+    #[cfg(target_os = "linux")]
+    if let Some(strace) = crate::common::resolve_which(Path::new("strace")) {
+        return execute_with_strace(
+            &strace, skill_dir, runtime, config, input_json, limits, level, policy,
+        );
+    }
+
+    execute_with_snapshot(
+        skill_dir, runtime, config, input_json, limits, level, &policy,
+    )
+}
+
+#[cfg(target_os = "linux")]
+#[allow(clippy::too_many_arguments)]
+fn execute_with_strace(
+    strace: &Path,
+    skill_dir: &Path,
+    runtime: &RuntimePaths,
+    config: &SandboxConfig,
+    input_json: &str,
+    limits: ResourceLimits,
+    level: SandboxLevel,
+    policy: AuditPolicy,
+) -> Result<(ExecutionResult, SandboxAuditReport)> {
+    let temp_dir = tempfile::TempDir::new()?;
+    // Keep the trace log outside the work dir so the skill cannot tamper with it.
+    let log_dir = tempfile::TempDir::new()?;
+    let log_path = log_dir.path().join("strace.log");
+    let prefix = [
+        strace.as_os_str().to_os_string(),
+        "-f".into(),
+        "-qq".into(),
+        "-e".into(),
+        "trace=%file,%network,%process".into(),
+        "-o".into(),
+        log_path.as_os_str().to_os_string(),
+    ];
+    let result = crate::common::execute_unsandboxed_with(
+        skill_dir,
+        runtime,
+        config,
+        input_json,
+        limits,
+        temp_dir.path(),
+        &prefix,
+    )?;
+
+    let policy = policy.with_writable(temp_dir.path());
+    let mut report = SandboxAuditReport::new("strace", level);
+    match std::fs::read_to_string(&log_path) {
+        Ok(log) => {
+            for event in log.lines().filter_map(parse_strace_line) {
+                report.observe(&policy, &event);
+            }
+        }
+        Err(e) => report.note(format!("strace log unavailable: {}", e)),
+    }
+    if matches!(policy.network, ResolvedNetworkPolicy::ProxyFiltered { .. }) {
+        report.note(
+            "Outbound domains are filtered by the sandbox proxy; direct connections are listed \
+             for review and may be allowed through the proxy.",
+        );
+    }
+    Ok((result, report))
+}
+
+fn execute_with_snapshot(
+    skill_dir: &Path,
+    runtime: &RuntimePaths,
+    config: &SandboxConfig,
+    input_json: &str,
+    limits: ResourceLimits,
+    level: SandboxLevel,
+    policy: &AuditPolicy,
+) -> Result<(ExecutionResult, SandboxAuditReport)> {
+    let before = snapshot(skill_dir);
+    let result = execute_unconfined(skill_dir, runtime, config, input_json, limits)?;
+    let after = snapshot(skill_dir);
+
+    let mut report = SandboxAuditReport::new("snapshot", level);
+    for (path, stamp) in &after {
+        if before.get(path) != Some(stamp) {
+            report.observe(policy, &AuditEvent::Write(path.clone()));
+        }
+    }
+    for path in before.keys().filter(|p| !after.contains_key(*p)) {
+        report.observe(policy, &AuditEvent::Write(path.clone()));
+    }
+    report.not_captured = vec![
+        AuditCategory::FileRead,
+        AuditCategory::Network,
+        AuditCategory::Process,
+    ];
+    report.note(format!(
+        "{}file_write covers the skill directory only ({}); writes elsewhere, sensitive reads, \
+         network connections and process launches were not observed.",
+        if cfg!(target_os = "linux") {
+            "strace not found on PATH; "
+        } else {
+            ""
+        },
+        skill_dir.display()
+    ));
+    Ok((result, report))
+}
+
+fn execute_unconfined(
+    skill_dir: &Path,
+    runtime: &RuntimePaths,
+    config: &SandboxConfig,
+    input_json: &str,
+    limits: ResourceLimits,
+) -> Result<ExecutionResult> {
+    #[cfg(unix)]
+    return crate::common::execute_unsandboxed(skill_dir, runtime, config, input_json, limits);
+
+    #[cfg(target_os = "windows")]
+    return crate::windows::execute_simple_with_limits(
+        skill_dir, runtime, config, input_json, limits,
+    );
+
+    #[cfg(not(any(unix, target_os = "windows")))]
+    crate::error::bail!("Unsupported platform. Only Linux, macOS, and Windows are supported.")
+}
+
+/// `path -> (len, mtime)` for every file under `dir` (bounded by [`MAX_SNAPSHOT_FILES`]).
+fn snapshot(dir: &Path) -> BTreeMap<PathBuf, (u64, Option<SystemTime>)> {
+    let mut files = BTreeMap::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(current) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if files.len() >= MAX_SNAPSHOT_FILES {
+                return files;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                stack.push(entry.path());
+            } else {
+                files.insert(entry.path(), (meta.len(), meta.modified().ok()));
+            }
+        }
+    }
+    files
+}
+
+/// Parse one `strace -f` output line into an [`AuditEvent`].
+///
+/// Recognizes `open`/`openat`/`creat`, path-mutating calls (`mkdir`, `unlink`, `rename`, ...),
+/// `execve` and `connect`; everything else (including `<... resumed>` tails) yields `None`.
+pub fn parse_strace_line(line: &str) -> Option<AuditEvent> {
+    let line = line.trim_start();
+    // Strip the leading pid that `-f` adds when writing to a file.
+    let rest = match line.split_once(char::is_whitespace) {
+        Some((pid, rest)) if pid.chars().all(|c| c.is_ascii_digit()) => rest.trim_start(),
+        _ => line,
+    };
+    let (name, args) = rest.split_once('(')?;
+    match name {
+        "open" | "openat" | "openat2" => {
+            let path = first_quoted(args)?;
+            let is_write = ["O_WRONLY", "O_RDWR", "O_CREAT", "O_TRUNC", "O_APPEND"]
+                .iter()
+                .any(|flag| args.contains(flag));
+            Some(if is_write {
+                AuditEvent::Write(PathBuf::from(path))
+            } else {
+                AuditEvent::Read(PathBuf::from(path))
+            })
+        }
+        "creat" | "mkdir" | "mkdirat" | "unlink" | "unlinkat" | "rmdir" | "rename" | "renameat"
+        | "renameat2" | "truncate" | "chmod" | "fchmodat" | "chown" | "fchownat" | "link"
+        | "linkat" | "symlink" | "symlinkat" => {
+            first_quoted(args).map(|p| AuditEvent::Write(PathBuf::from(p)))
+        }
+        "execve" | "execveat" => first_quoted(args).map(|p| AuditEvent::Exec(PathBuf::from(p))),
+        "connect" => parse_connect_address(args).map(AuditEvent::Connect),
+        _ => None,
+    }
+}
+
+fn parse_connect_address(args: &str) -> Option<String> {
+    if args.contains("sa_family=AF_UNIX") {
+        let path = args
+            .split_once("sun_path=")
+            .and_then(|(_, rest)| first_quoted(rest))
+            .unwrap_or_default();
+        return Some(format!("unix:{}", path));
+    }
+    let port = args
+        .split_once("port=htons(")
+        .and_then(|(_, rest)| rest.split_once(')'))
+        .map(|(port, _)| port)?;
+    if args.contains("sa_family=AF_INET6") {
+        let ip = args
+            .split_once("inet_pton(AF_INET6, ")
+            .and_then(|(_, rest)| first_quoted(rest))?;
+        return Some(format!("[{}]:{}", ip, port));
+    }
+    if args.contains("sa_family=AF_INET") {
+        let ip = args
+            .split_once("inet_addr(")
+            .and_then(|(_, rest)| first_quoted(rest))?;
+        return Some(format!("{}:{}", ip, port));
+    }
+    None
+}
+
+/// First double-quoted string in `s` (strace escapes embedded quotes as `\"`).
+fn first_quoted(s: &str) -> Option<String> {
+    let start = s.find('"')? + 1;
+    let mut out = String::new();
+    let mut chars = s[start..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push(chars.next()?),
+            '"' => return Some(out),
+            _ => out.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(network_enabled: bool, outbound: &[&str]) -> SandboxConfig {
+        SandboxConfig {
+            name: "audit-test".to_string(),
+            entry_point: "main.py".to_string(),
+            language: "python".to_string(),
+            network_enabled,
+            network_outbound: outbound.iter().map(|s| s.to_string()).collect(),
+            network_deny: Vec::new(),
+            uses_playwright: false,
+            script_args: Vec::new(),
+            results_dir: Some(PathBuf::from("/out/results")),
+            enforce_isolation: false,
+        }
+    }
+
+    #[test]
+    fn parse_strace_file_ops() {
+        assert_eq!(
+            parse_strace_line(
+                r#"4242  openat(AT_FDCWD, "/home/u/.ssh/id_rsa", O_RDONLY|O_CLOEXEC) = 3"#
+            ),
+            Some(AuditEvent::Read(PathBuf::from("/home/u/.ssh/id_rsa")))
+        );
+        assert_eq!(
+            parse_strace_line(r#"openat(AT_FDCWD, "out.txt", O_WRONLY|O_CREAT|O_TRUNC, 0666) = 3"#),
+            Some(AuditEvent::Write(PathBuf::from("out.txt")))
+        );
+        assert_eq!(
+            parse_strace_line(r#"77 unlink("/etc/x\"y") = -1 EACCES (Permission denied)"#),
+            Some(AuditEvent::Write(PathBuf::from("/etc/x\"y")))
+        );
+        assert_eq!(
+            parse_strace_line(
+                r#"77 execve("/usr/bin/curl", ["curl", "x"], 0x7ff /* 9 vars */) = 0"#
+            ),
+            Some(AuditEvent::Exec(PathBuf::from("/usr/bin/curl")))
+        );
+        assert_eq!(parse_strace_line("77 <... openat resumed>) = 3"), None);
+        assert_eq!(parse_strace_line("77 +++ exited with 0 +++"), None);
+    }
+
+    #[test]
+    fn parse_strace_connect() {
+        assert_eq!(
+            parse_strace_line(
+                r#"9 connect(3, {sa_family=AF_INET, sin_port=htons(443), sin_addr=inet_addr("93.184.216.34")}, 16) = -1 EINPROGRESS"#
+            ),
+            Some(AuditEvent::Connect("93.184.216.34:443".to_string()))
+        );
+        assert_eq!(
+            parse_strace_line(
+                r#"connect(3, {sa_family=AF_INET6, sin6_port=htons(80), sin6_flowinfo=htonl(0), inet_pton(AF_INET6, "::1", &sin6_addr), sin6_scope_id=0}, 28) = 0"#
+            ),
+            Some(AuditEvent::Connect("[::1]:80".to_string()))
+        );
+        assert_eq!(
+            parse_strace_line(
+                r#"connect(4, {sa_family=AF_UNIX, sun_path="/var/run/docker.sock"}, 110) = 0"#
+            ),
+            Some(AuditEvent::Connect("unix:/var/run/docker.sock".to_string()))
+        );
+        assert_eq!(
+            parse_strace_line(r#"connect(5, {sa_family=AF_NETLINK, nl_pid=0}, 12) = 0"#),
+            None
+        );
+    }
+
+    #[test]
+    fn policy_flags_writes_outside_work_and_results_dirs() {
+        let policy = AuditPolicy::new(
+            Path::new("/skills/demo"),
+            &config(false, &[]),
+            SandboxLevel::Level3,
+        )
+        .with_writable(Path::new("/tmp/work"));
+        let write = |p: &str| policy.would_block(&AuditEvent::Write(PathBuf::from(p)));
+        assert_eq!(write("/tmp/work/a.txt"), None);
+        assert_eq!(write("/out/results/result.json"), None);
+        assert_eq!(write("/dev/null"), None);
+        assert_eq!(write("cache.db"), Some(AuditCategory::FileWrite));
+        assert_eq!(write("/home/u/notes.txt"), Some(AuditCategory::FileWrite));
+    }
+
+    #[test]
+    fn policy_network_and_process_rules() {
+        let blocked = AuditPolicy::new(Path::new("/s"), &config(false, &[]), SandboxLevel::Level3);
+        let open = AuditPolicy::new(Path::new("/s"), &config(true, &["*"]), SandboxLevel::Level3);
+        let connect = AuditEvent::Connect("1.2.3.4:443".to_string());
+        let unix = AuditEvent::Connect("unix:/run/x.sock".to_string());
+        assert_eq!(blocked.would_block(&connect), Some(AuditCategory::Network));
+        assert_eq!(open.would_block(&connect), None);
+        assert_eq!(open.would_block(&unix), Some(AuditCategory::Network));
+
+        let exec = |p: &str| blocked.would_block(&AuditEvent::Exec(PathBuf::from(p)));
+        assert_eq!(exec("/usr/bin/curl"), Some(AuditCategory::Process));
+        assert_eq!(exec("/usr/bin/bash"), Some(AuditCategory::Process));
+        assert_eq!(exec("/usr/bin/python3"), None);
+    }
+
+    #[test]
+    fn report_counts_distinct_targets_and_round_trips() {
+        let mut report = SandboxAuditReport::new("strace", SandboxLevel::Level3);
+        report.record(AuditCategory::FileWrite, "/a");
+        report.record(AuditCategory::FileWrite, "/a");
+        report.record(AuditCategory::FileWrite, "/b");
+        report.record(AuditCategory::Network, "1.2.3.4:443");
+        assert_eq!(report.total(), 3);
+        assert_eq!(
+            report.categories[&AuditCategory::FileWrite].examples,
+            vec!["/a", "/b"]
+        );
+
+        let merged = attach_report(r#"{"ok": true}"#, &report);
+        let value: Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(value["ok"], true);
+        assert_eq!(
+            value[SANDBOX_AUDIT_KEY]["categories"]["file_write"]["count"],
+            2
+        );
+        assert_eq!(SandboxAuditReport::from_output(&merged).unwrap().total(), 3);
+
+        let wrapped: Value = serde_json::from_str(&attach_report("[1]", &report)).unwrap();
+        assert_eq!(wrapped["stdout"], serde_json::json!([1]));
+
+        let text = report.format_report();
+        assert!(text.contains("3 operation(s) would have been blocked"));
+        assert!(text.contains("2× File writes outside the sandbox [file_write]"));
+    }
+
+    #[test]
+    fn partial_coverage_is_stated() {
+        let mut report = SandboxAuditReport::new("snapshot", SandboxLevel::Level3);
+        report.not_captured = vec![AuditCategory::Network, AuditCategory::Process];
+        let text = report.format_report();
+        assert!(text.contains("nothing observed would have been blocked"));
+        assert!(text.contains("Not captured by the snapshot backend: network, process"));
+
+        let value: Value = serde_json::from_str(&attach_report("{}", &report)).unwrap();
+        assert_eq!(
+            value[SANDBOX_AUDIT_KEY]["not_captured"],
+            serde_json::json!(["network", "process"])
+        );
+    }
+}
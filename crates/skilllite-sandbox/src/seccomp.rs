@@ -1,106 +1,216 @@
-//! Seccomp BPF Module for Sandbox Hardening (Linux only)
+//! Seccomp BPF Profiles for Sandbox Hardening (Linux only)
 //!
-//! This module implements seccomp-bpf filters to block dangerous syscalls
-//! at the kernel level. Provides defense-in-depth alongside namespace
-//! isolation (bwrap) and resource limits (rlimit).
+//! Each [`SandboxLevel`] maps to a named [`SeccompProfile`]: a list of denied syscalls
+//! (optionally argument-checked, e.g. `socket(AF_UNIX)`) and an [`SeccompAction`].
+//! Provides defense-in-depth alongside namespace isolation (bwrap) and resource limits.
 //!
-//! Blocked syscalls:
-//! - socket(AF_UNIX, ...) — prevent local IPC escape
-//! - ptrace — prevent process debugging/injection
-//! - mount/umount2 — prevent filesystem manipulation
-//! - clone (with CLONE_NEWUSER) — prevent user namespace escape
-//! - keyctl — prevent kernel keyring access
-//! - kexec_load/kexec_file_load — prevent kernel replacement
-//! - pivot_root/chroot — prevent filesystem root manipulation
-//! - unshare (with CLONE_NEWUSER) — prevent user namespace creation
+//! Built-in profiles:
+//! - `standard` (levels 2 and 3): ptrace, mount/umount2, keyctl, kexec_load/kexec_file_load,
+//!   pivot_root/chroot, socket(AF_UNIX), clone(CLONE_NEWUSER), unshare(CLONE_NEWUSER);
+//!   clone3 returns ENOSYS so libc falls back to the argument-checked clone
+//! - `strict`: `standard` plus bpf, perf_event_open, userfaultfd, io_uring, kernel
+//!   module / keyring / clock / namespace-join syscalls
+//!
+//! `<data_root>/seccomp/profiles.toml` adds or replaces profiles and remaps levels:
+//!
+//! ```toml
+//! [levels]
+//! level2 = "standard"
+//! level3 = "audit"
+//!
+//! [profiles.audit]
+//! action = "log"               # "enforce" (default) or "log"
+//! deny = ["ptrace", "socket(AF_UNIX)", "bpf"]
+//! enosys = ["clone3"]
+//! ```
+//!
+//! `SKILLLITE_SECCOMP_MODE=enforce|log` overrides the action of every profile.
+//!
+//! When the kernel supports user notification (5.5+) and the host binary calls
+//! [`run_exec_shim_if_requested`], a shim installs the filter inside bwrap with
+//! `SECCOMP_RET_USER_NOTIF` and a [`NotifySupervisor`] thread answers each denied syscall:
+//! `enforce` fails it with EPERM (the syscall name is appended to stderr), `log` lets it
+//! continue. Both are collected into [`ExecutionResult::syscall_violations`]. Otherwise bwrap
+//! loads a plain filter (EPERM, or `SECCOMP_RET_LOG` for `log`) and violations are not
+//! attributed.
 //!
 //! Architecture support: x86_64 and aarch64
+//!
+//! [`ExecutionResult::syscall_violations`]: crate::runner::ExecutionResult::syscall_violations
 
 #![cfg(target_os = "linux")]
 
-use std::io;
+use crate::runner::{SandboxLevel, SyscallViolation};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Read};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 
 // ============================================================================
 // Seccomp Constants
 // ============================================================================
 
-/// AF_UNIX socket family constant
-const AF_UNIX: u32 = 1;
-
-/// CLONE_NEWUSER flag for clone/unshare argument filtering
-const CLONE_NEWUSER: u32 = 0x10000000;
-
-// Syscall numbers per architecture
-// Reference: https://filippo.io/linux-syscall-table/ and kernel headers
-
-#[cfg(target_arch = "x86_64")]
-mod syscall_nr {
-    pub const SOCKET: u32 = 41;
-    pub const PTRACE: u32 = 101;
-    pub const MOUNT: u32 = 165;
-    pub const UMOUNT2: u32 = 166;
-    pub const CLONE: u32 = 56;
-    pub const KEYCTL: u32 = 250;
-    pub const KEXEC_LOAD: u32 = 246;
-    pub const KEXEC_FILE_LOAD: u32 = 320;
-    pub const PIVOT_ROOT: u32 = 155;
-    pub const CHROOT: u32 = 161;
-    pub const UNSHARE: u32 = 272;
-}
-
-#[cfg(target_arch = "aarch64")]
-mod syscall_nr {
-    pub const SOCKET: u32 = 198;
-    pub const PTRACE: u32 = 117;
-    pub const MOUNT: u32 = 40;
-    pub const UMOUNT2: u32 = 39;
-    pub const CLONE: u32 = 220;
-    pub const KEYCTL: u32 = 219;
-    pub const KEXEC_LOAD: u32 = 104;
-    pub const KEXEC_FILE_LOAD: u32 = 294;
-    pub const PIVOT_ROOT: u32 = 41;
-    pub const CHROOT: u32 = 51;
-    pub const UNSHARE: u32 = 97;
-}
+/// AF_* / CLONE_* qualifiers accepted in rules such as `socket(AF_UNIX)`.
+const ARG_QUALIFIERS: &[(&str, ArgMatch)] = &[
+    ("AF_UNIX", ArgMatch::Equals(1)),
+    ("AF_NETLINK", ArgMatch::Equals(16)),
+    ("AF_PACKET", ArgMatch::Equals(17)),
+    ("CLONE_NEWNS", ArgMatch::HasFlag(0x0002_0000)),
+    ("CLONE_NEWUSER", ArgMatch::HasFlag(0x1000_0000)),
+    ("CLONE_NEWNET", ArgMatch::HasFlag(0x4000_0000)),
+];
+
+/// Syscalls that may appear in a profile. Names follow the kernel's syscall table.
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+const SYSCALLS: &[(&str, libc::c_long)] = &[
+    ("acct", libc::SYS_acct),
+    ("add_key", libc::SYS_add_key),
+    ("adjtimex", libc::SYS_adjtimex),
+    ("bpf", libc::SYS_bpf),
+    ("chroot", libc::SYS_chroot),
+    ("clock_adjtime", libc::SYS_clock_adjtime),
+    ("clock_settime", libc::SYS_clock_settime),
+    ("clone", libc::SYS_clone),
+    ("clone3", libc::SYS_clone3),
+    ("delete_module", libc::SYS_delete_module),
+    ("fanotify_init", libc::SYS_fanotify_init),
+    ("finit_module", libc::SYS_finit_module),
+    ("init_module", libc::SYS_init_module),
+    ("io_uring_enter", libc::SYS_io_uring_enter),
+    ("io_uring_register", libc::SYS_io_uring_register),
+    ("io_uring_setup", libc::SYS_io_uring_setup),
+    ("kexec_file_load", libc::SYS_kexec_file_load),
+    ("kexec_load", libc::SYS_kexec_load),
+    ("keyctl", libc::SYS_keyctl),
+    ("mount", libc::SYS_mount),
+    ("name_to_handle_at", libc::SYS_name_to_handle_at),
+    ("open_by_handle_at", libc::SYS_open_by_handle_at),
+    ("perf_event_open", libc::SYS_perf_event_open),
+    ("pivot_root", libc::SYS_pivot_root),
+    ("process_vm_readv", libc::SYS_process_vm_readv),
+    ("process_vm_writev", libc::SYS_process_vm_writev),
+    ("ptrace", libc::SYS_ptrace),
+    ("quotactl", libc::SYS_quotactl),
+    ("reboot", libc::SYS_reboot),
+    ("request_key", libc::SYS_request_key),
+    ("setdomainname", libc::SYS_setdomainname),
+    ("sethostname", libc::SYS_sethostname),
+    ("setns", libc::SYS_setns),
+    ("settimeofday", libc::SYS_settimeofday),
+    ("socket", libc::SYS_socket),
+    ("swapoff", libc::SYS_swapoff),
+    ("swapon", libc::SYS_swapon),
+    ("syslog", libc::SYS_syslog),
+    ("umount2", libc::SYS_umount2),
+    ("uname", libc::SYS_uname),
+    ("unshare", libc::SYS_unshare),
+    ("userfaultfd", libc::SYS_userfaultfd),
+];
 
 #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
-mod syscall_nr {
-    pub const SOCKET: u32 = 0;
-    pub const PTRACE: u32 = 0;
-    pub const MOUNT: u32 = 0;
-    pub const UMOUNT2: u32 = 0;
-    pub const CLONE: u32 = 0;
-    pub const KEYCTL: u32 = 0;
-    pub const KEXEC_LOAD: u32 = 0;
-    pub const KEXEC_FILE_LOAD: u32 = 0;
-    pub const PIVOT_ROOT: u32 = 0;
-    pub const CHROOT: u32 = 0;
-    pub const UNSHARE: u32 = 0;
-}
+const SYSCALLS: &[(&str, libc::c_long)] = &[];
+
+const STANDARD_DENY: &[&str] = &[
+    "ptrace",
+    "mount",
+    "umount2",
+    "keyctl",
+    "kexec_load",
+    "kexec_file_load",
+    "pivot_root",
+    "chroot",
+    "socket(AF_UNIX)",
+    "clone(CLONE_NEWUSER)",
+    "unshare(CLONE_NEWUSER)",
+];
+
+const STRICT_EXTRA_DENY: &[&str] = &[
+    "add_key",
+    "request_key",
+    "setns",
+    "bpf",
+    "perf_event_open",
+    "userfaultfd",
+    "io_uring_setup",
+    "io_uring_enter",
+    "io_uring_register",
+    "init_module",
+    "finit_module",
+    "delete_module",
+    "reboot",
+    "swapon",
+    "swapoff",
+    "acct",
+    "settimeofday",
+    "clock_settime",
+    "clock_adjtime",
+    "adjtimex",
+    "syslog",
+    "quotactl",
+    "open_by_handle_at",
+    "name_to_handle_at",
+    "process_vm_readv",
+    "process_vm_writev",
+    "fanotify_init",
+    "sethostname",
+    "setdomainname",
+];
+
+/// clone3 passes its flags in a struct the filter cannot inspect; ENOSYS makes libc use clone.
+const DEFAULT_ENOSYS: &[&str] = &["clone3"];
 
 /// Seccomp action: Allow the syscall
 const SECCOMP_RET_ALLOW: u32 = 0x7fff0000;
 
+/// Seccomp action: Allow the syscall and log it to the kernel audit log
+const SECCOMP_RET_LOG: u32 = 0x7ffc0000;
+
+/// Seccomp action: Ask the listener (see [`NotifySupervisor`])
+const SECCOMP_RET_USER_NOTIF: u32 = 0x7fc00000;
+
 /// Seccomp action: Return errno
 const SECCOMP_RET_ERRNO: u32 = 0x00050000;
 
-/// EPERM error code
 const EPERM: u32 = 1;
+const ENOSYS: u32 = 38;
 
 /// Seccomp operation: Set mode filter
 const SECCOMP_SET_MODE_FILTER: u32 = 1;
 
+/// Return a user-notification listener fd from `seccomp(2)` (Linux 5.0+)
+const SECCOMP_FILTER_FLAG_NEW_LISTENER: u32 = 1 << 3;
+
+/// Listener reply flag: run the syscall as if it had been allowed (Linux 5.5+)
+const SECCOMP_USER_NOTIF_FLAG_CONTINUE: u32 = 1;
+
+/// `_IOWR('!', 0, struct seccomp_notif)`
+const SECCOMP_IOCTL_NOTIF_RECV: u64 = 0xc050_2100;
+
+/// `_IOWR('!', 1, struct seccomp_notif_resp)`
+const SECCOMP_IOCTL_NOTIF_SEND: u64 = 0xc018_2101;
+
 /// PR_SET_NO_NEW_PRIVS
 const PR_SET_NO_NEW_PRIVS: i32 = 38;
 
+/// How often the supervisor re-checks whether the run has finished.
+const POLL_INTERVAL_MS: i32 = 100;
+
+/// Violations kept per run; a skill looping on a denied syscall must not grow memory.
+const MAX_VIOLATIONS: usize = 256;
+
 // ============================================================================
 // BPF Filter Structures
 // ============================================================================
 
 /// BPF instruction
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
-struct SockFilter {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SockFilter {
     code: u16,
     jt: u8,
     jf: u8,
@@ -111,6 +221,19 @@ impl SockFilter {
     const fn new(code: u16, jt: u8, jf: u8, k: u32) -> Self {
         Self { code, jt, jf, k }
     }
+
+    const fn ret(k: u32) -> Self {
+        Self::new(BPF_RET | BPF_K, 0, 0, k)
+    }
+
+    const fn load(offset: u32) -> Self {
+        Self::new(BPF_LD | BPF_W | BPF_ABS, 0, 0, offset)
+    }
+
+    /// If the accumulator equals `k` fall through, otherwise skip `skip` instructions.
+    const fn jeq(k: u32, skip: u8) -> Self {
+        Self::new(BPF_JMP | BPF_JEQ | BPF_K, 0, skip, k)
+    }
 }
 
 /// BPF program
@@ -124,6 +247,8 @@ struct SockFprog {
 const BPF_LD: u16 = 0x00;
 const BPF_W: u16 = 0x00;
 const BPF_ABS: u16 = 0x20;
+const BPF_ALU: u16 = 0x04;
+const BPF_AND: u16 = 0x50;
 const BPF_JMP: u16 = 0x05;
 const BPF_JEQ: u16 = 0x10;
 const BPF_K: u16 = 0x00;
@@ -133,6 +258,438 @@ const BPF_RET: u16 = 0x06;
 const SECCOMP_DATA_NR: u32 = 0; // Syscall number offset
 const SECCOMP_DATA_ARGS: u32 = 16; // Args offset (args[0] is at offset 16)
 
+/// `struct seccomp_data`
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct SeccompData {
+    nr: i32,
+    arch: u32,
+    instruction_pointer: u64,
+    args: [u64; 6],
+}
+
+/// `struct seccomp_notif`
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct SeccompNotif {
+    id: u64,
+    pid: u32,
+    flags: u32,
+    data: SeccompData,
+}
+
+/// `struct seccomp_notif_resp`
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct SeccompNotifResp {
+    id: u64,
+    val: i64,
+    error: i32,
+    flags: u32,
+}
+
+// ============================================================================
+// Profiles
+// ============================================================================
+
+/// What happens when a skill makes a denied syscall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeccompAction {
+    /// Fail the syscall with EPERM.
+    #[default]
+    Enforce,
+    /// Let the syscall run and report it as a violation.
+    Log,
+}
+
+impl SeccompAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Enforce => "enforce",
+            Self::Log => "log",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "enforce" => Some(Self::Enforce),
+            "log" => Some(Self::Log),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgMatch {
+    /// First argument equals the value (socket domain).
+    Equals(u32),
+    /// First argument has all bits of the value set (clone / unshare flags).
+    HasFlag(u32),
+}
+
+/// One syscall rule, e.g. `ptrace` or `socket(AF_UNIX)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyscallRule {
+    /// Rule as written in the profile; reported in violations.
+    pub spec: String,
+    nr: u32,
+    arg0: Option<ArgMatch>,
+}
+
+impl SyscallRule {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let (name, qualifier) = match spec.split_once('(') {
+            Some((name, rest)) => {
+                let qualifier = rest
+                    .strip_suffix(')')
+                    .ok_or_else(|| format!("`{}`: missing `)`", spec))?;
+                (name.trim(), Some(qualifier.trim()))
+            }
+            None => (spec, None),
+        };
+        let nr = syscall_number(name).ok_or_else(|| format!("unknown syscall `{}`", name))?;
+        let arg0 = match qualifier {
+            None => None,
+            Some(q) => Some(
+                ARG_QUALIFIERS
+                    .iter()
+                    .find(|(known, _)| *known == q)
+                    .map(|(_, m)| *m)
+                    .ok_or_else(|| {
+                        let known: Vec<&str> = ARG_QUALIFIERS.iter().map(|(n, _)| *n).collect();
+                        format!(
+                            "`{}`: unknown argument `{}` (known: {})",
+                            spec,
+                            q,
+                            known.join(", ")
+                        )
+                    })?,
+            ),
+        };
+        Ok(Self {
+            spec: match qualifier {
+                Some(q) => format!("{}({})", name, q),
+                None => name.to_string(),
+            },
+            nr,
+            arg0,
+        })
+    }
+
+    fn matches(&self, nr: i32, arg0: u64) -> bool {
+        self.nr as i32 == nr
+            && match self.arg0 {
+                None => true,
+                Some(ArgMatch::Equals(value)) => arg0 as u32 == value,
+                Some(ArgMatch::HasFlag(flag)) => arg0 as u32 & flag == flag,
+            }
+    }
+
+    fn push_to(&self, f: &mut Vec<SockFilter>, ret: u32) {
+        match self.arg0 {
+            None => {
+                f.push(SockFilter::jeq(self.nr, 1));
+                f.push(SockFilter::ret(ret));
+            }
+            Some(m) => {
+                let masked = matches!(m, ArgMatch::HasFlag(_));
+                // Not this syscall: skip to the reload of the syscall number
+                f.push(SockFilter::jeq(self.nr, if masked { 4 } else { 3 }));
+                f.push(SockFilter::load(SECCOMP_DATA_ARGS));
+                match m {
+                    ArgMatch::Equals(value) => f.push(SockFilter::jeq(value, 1)),
+                    ArgMatch::HasFlag(flag) => {
+                        f.push(SockFilter::new(BPF_ALU | BPF_AND | BPF_K, 0, 0, flag));
+                        f.push(SockFilter::jeq(flag, 1));
+                    }
+                }
+                f.push(SockFilter::ret(ret));
+                // Reload syscall number (destroyed by arg load above)
+                f.push(SockFilter::load(SECCOMP_DATA_NR));
+            }
+        }
+    }
+}
+
+fn syscall_number(name: &str) -> Option<u32> {
+    SYSCALLS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, nr)| *nr as u32)
+}
+
+/// A named set of denied syscalls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeccompProfile {
+    pub name: String,
+    pub action: SeccompAction,
+    /// Denied syscalls: EPERM (`enforce`) or allowed and reported (`log`).
+    pub deny: Vec<SyscallRule>,
+    /// Syscalls that always fail with ENOSYS so callers fall back (not violations).
+    pub enosys: Vec<SyscallRule>,
+}
+
+impl SeccompProfile {
+    pub fn new<S: AsRef<str>>(
+        name: &str,
+        action: SeccompAction,
+        deny: &[S],
+        enosys: &[S],
+    ) -> Result<Self, String> {
+        let parse = |specs: &[S]| -> Result<Vec<SyscallRule>, String> {
+            specs
+                .iter()
+                .map(|s| SyscallRule::parse(s.as_ref()))
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("profile `{}`: {}", name, e))
+        };
+        Ok(Self {
+            name: name.to_string(),
+            action,
+            deny: parse(deny)?,
+            enosys: parse(enosys)?,
+        })
+    }
+
+    /// The deny rule a trapped syscall matched.
+    pub fn rule_for(&self, nr: i32, arg0: u64) -> Option<&SyscallRule> {
+        self.deny.iter().find(|rule| rule.matches(nr, arg0))
+    }
+
+    /// One-line description for `skilllite doctor`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} ({}, {} denied)",
+            self.name,
+            self.action.as_str(),
+            self.deny.len()
+        )
+    }
+
+    /// Build the BPF program. With `notify`, denied syscalls go to the listener
+    /// ([`NotifySupervisor`]); otherwise they fail with EPERM (`enforce`) or are
+    /// allowed and audit-logged by the kernel (`log`).
+    pub(crate) fn filter(&self, notify: bool) -> Vec<SockFilter> {
+        let deny_ret = match (notify, self.action) {
+            (true, _) => SECCOMP_RET_USER_NOTIF,
+            (false, SeccompAction::Enforce) => SECCOMP_RET_ERRNO | EPERM,
+            (false, SeccompAction::Log) => SECCOMP_RET_LOG,
+        };
+        let mut f = vec![SockFilter::load(SECCOMP_DATA_NR)];
+        for rule in &self.enosys {
+            rule.push_to(&mut f, SECCOMP_RET_ERRNO | ENOSYS);
+        }
+        for rule in &self.deny {
+            rule.push_to(&mut f, deny_ret);
+        }
+        // Allow everything else
+        f.push(SockFilter::ret(SECCOMP_RET_ALLOW));
+        f
+    }
+
+    /// [`Self::filter`] in the binary layout bwrap's `--seccomp FD` and the exec shim read.
+    pub fn filter_bytes(&self, notify: bool) -> Vec<u8> {
+        let filter = self.filter(notify);
+        let mut bytes = Vec::with_capacity(filter.len() * 8);
+        for inst in filter {
+            bytes.extend_from_slice(&inst.code.to_ne_bytes());
+            bytes.push(inst.jt);
+            bytes.push(inst.jf);
+            bytes.extend_from_slice(&inst.k.to_ne_bytes());
+        }
+        bytes
+    }
+}
+
+fn decode_filter(bytes: &[u8]) -> io::Result<Vec<SockFilter>> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(8) || bytes.len() / 8 > u16::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed seccomp filter ({} bytes)", bytes.len()),
+        ));
+    }
+    Ok(bytes
+        .chunks_exact(8)
+        .map(|c| {
+            SockFilter::new(
+                u16::from_ne_bytes([c[0], c[1]]),
+                c[2],
+                c[3],
+                u32::from_ne_bytes([c[4], c[5], c[6], c[7]]),
+            )
+        })
+        .collect())
+}
+
+/// `profiles.toml` layout.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfilesFile {
+    #[serde(default)]
+    levels: BTreeMap<String, String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, ProfileEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileEntry {
+    #[serde(default)]
+    action: SeccompAction,
+    deny: Vec<String>,
+    #[serde(default)]
+    enosys: Vec<String>,
+}
+
+/// Level value in `profiles.toml` that disables the seccomp filter; also reported as the
+/// profile of runs that had none.
+pub const NO_PROFILE: &str = "none";
+
+/// All known profiles and which one each sandbox level uses.
+#[derive(Debug, Clone)]
+pub struct SeccompProfiles {
+    profiles: BTreeMap<String, SeccompProfile>,
+    level2: Option<String>,
+    level3: Option<String>,
+    source: Option<PathBuf>,
+}
+
+impl SeccompProfiles {
+    /// `standard` and `strict`; levels 2 and 3 use `standard`.
+    pub fn builtin() -> Self {
+        let strict_deny: Vec<&str> = STANDARD_DENY
+            .iter()
+            .chain(STRICT_EXTRA_DENY)
+            .copied()
+            .collect();
+        let profiles: BTreeMap<String, SeccompProfile> = [
+            SeccompProfile::new(
+                "standard",
+                SeccompAction::Enforce,
+                STANDARD_DENY,
+                DEFAULT_ENOSYS,
+            ),
+            SeccompProfile::new(
+                "strict",
+                SeccompAction::Enforce,
+                strict_deny.as_slice(),
+                DEFAULT_ENOSYS,
+            ),
+        ]
+        .into_iter()
+        // Unsupported architectures have no syscall table and get no profiles.
+        .filter_map(|p| p.ok())
+        .map(|p| (p.name.clone(), p))
+        .collect();
+        let standard = profiles.contains_key("standard").then(|| "standard".into());
+        Self {
+            profiles,
+            level2: standard.clone(),
+            level3: standard,
+            source: None,
+        }
+    }
+
+    /// Built-in profiles, overridden by [`profiles_path`] when it exists, with
+    /// `SKILLLITE_SECCOMP_MODE` applied.
+    pub fn load() -> Result<Self, String> {
+        let mut set = Self::builtin();
+        let path = profiles_path();
+        if path.is_file() {
+            let text =
+                std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            set.merge_toml(&text)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            set.source = Some(path);
+        }
+        if let Ok(mode) =
+            std::env::var(skilllite_core::config::env_keys::sandbox::SKILLLITE_SECCOMP_MODE)
+        {
+            if !mode.trim().is_empty() {
+                let action = SeccompAction::parse(&mode).ok_or_else(|| {
+                    format!(
+                        "SKILLLITE_SECCOMP_MODE must be `enforce` or `log`, got `{}`",
+                        mode
+                    )
+                })?;
+                for profile in set.profiles.values_mut() {
+                    profile.action = action;
+                }
+            }
+        }
+        Ok(set)
+    }
+
+    /// Apply a `profiles.toml` document on top of the current set.
+    pub fn merge_toml(&mut self, text: &str) -> Result<(), String> {
+        let file: ProfilesFile = toml::from_str(text).map_err(|e| e.to_string())?;
+        for (name, entry) in file.profiles {
+            if name == NO_PROFILE {
+                return Err(format!(
+                    "`{}` is reserved and cannot name a profile",
+                    NO_PROFILE
+                ));
+            }
+            let profile = SeccompProfile::new(
+                &name,
+                entry.action,
+                entry.deny.as_slice(),
+                entry.enosys.as_slice(),
+            )?;
+            self.profiles.insert(name, profile);
+        }
+        for (level, name) in file.levels {
+            let profile = if name == NO_PROFILE {
+                None
+            } else if self.profiles.contains_key(&name) {
+                Some(name)
+            } else {
+                return Err(format!(
+                    "levels.{}: unknown profile `{}` (known: {})",
+                    level,
+                    name,
+                    self.names().join(", ")
+                ));
+            };
+            match level.as_str() {
+                "level2" => self.level2 = profile,
+                "level3" => self.level3 = profile,
+                "level1" => return Err("levels.level1: level 1 runs without a sandbox".into()),
+                other => return Err(format!("levels.{}: expected `level2` or `level3`", other)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Profile applied at `level`; `None` for level 1 or when mapped to `"none"`.
+    pub fn for_level(&self, level: SandboxLevel) -> Option<&SeccompProfile> {
+        let name = match level {
+            SandboxLevel::Level1 => None,
+            SandboxLevel::Level2 => self.level2.as_ref(),
+            SandboxLevel::Level3 => self.level3.as_ref(),
+        }?;
+        self.profiles.get(name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.profiles.keys().map(String::as_str).collect()
+    }
+
+    /// The `profiles.toml` that was merged, if any.
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+}
+
+/// `<data_root>/seccomp/profiles.toml`
+pub fn profiles_path() -> PathBuf {
+    skilllite_core::paths::data_root()
+        .join("seccomp")
+        .join("profiles.toml")
+}
+
 // ============================================================================
 // Unix Socket Filter Configuration
 // ============================================================================
@@ -168,203 +725,50 @@ impl SeccompConfig {
 // Seccomp Filter Application
 // ============================================================================
 
-/// Apply seccomp filter to block Unix socket creation
+/// Set NO_NEW_PRIVS and install `filter`; with `flags` = NEW_LISTENER returns the listener fd.
 ///
-/// This function must be called in the child process before exec.
-/// It sets PR_SET_NO_NEW_PRIVS and applies a BPF filter that blocks
-/// socket(AF_UNIX, ...) syscalls.
+/// Does not allocate, so it is safe to call from a `pre_exec` hook.
+fn install_filter(filter: &[SockFilter], flags: u32) -> io::Result<RawFd> {
+    let ret = unsafe { libc::prctl(PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let prog = SockFprog {
+        len: filter.len() as u16,
+        filter: filter.as_ptr(),
+    };
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_seccomp,
+            SECCOMP_SET_MODE_FILTER as libc::c_ulong,
+            flags as libc::c_ulong,
+            &prog as *const SockFprog as libc::c_ulong,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(ret as RawFd)
+}
+
+/// Apply the built-in `standard` profile (EPERM) to the calling process.
 ///
 /// # Safety
 /// This function uses unsafe syscalls and should only be called
 /// in a forked child process before exec.
 pub fn apply_unix_socket_filter() -> io::Result<()> {
-    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
-    {
-        return Err(io::Error::new(
+    let profiles = SeccompProfiles::builtin();
+    let profile = profiles.for_level(SandboxLevel::Level2).ok_or_else(|| {
+        io::Error::new(
             io::ErrorKind::Unsupported,
             "Seccomp Unix socket blocking is only supported on x86_64 and aarch64",
-        ));
-    }
-
-    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
-    {
-        // First, set NO_NEW_PRIVS to allow unprivileged seccomp
-        let ret = unsafe { libc::prctl(PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
-        if ret != 0 {
-            return Err(io::Error::last_os_error());
-        }
-
-        // Build the BPF filter
-        let filter = build_sandbox_filter();
-
-        // Apply the filter
-        let prog = SockFprog {
-            len: filter.len() as u16,
-            filter: filter.as_ptr(),
-        };
-
-        let ret = unsafe {
-            libc::syscall(
-                libc::SYS_seccomp,
-                SECCOMP_SET_MODE_FILTER as libc::c_ulong,
-                0 as libc::c_ulong,
-                &prog as *const SockFprog as libc::c_ulong,
-            )
-        };
-
-        if ret != 0 {
-            return Err(io::Error::last_os_error());
-        }
-
-        Ok(())
-    }
+        )
+    })?;
+    let mut profile = profile.clone();
+    profile.action = SeccompAction::Enforce;
+    install_filter(&profile.filter(false), 0).map(|_| ())
 }
 
-/// Build a BPF filter that blocks dangerous syscalls.
-///
-/// Unconditionally blocked: ptrace, mount, umount2, keyctl,
-/// kexec_load, kexec_file_load, pivot_root, chroot.
-///
-/// Conditionally blocked (argument-checked):
-/// - socket(AF_UNIX, ...) — blocks only AF_UNIX domain
-/// - clone(..., CLONE_NEWUSER, ...) — blocks only CLONE_NEWUSER flag
-/// - unshare(CLONE_NEWUSER) — blocks only CLONE_NEWUSER flag
-#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
-fn build_sandbox_filter() -> Vec<SockFilter> {
-    use syscall_nr::*;
-
-    // BPF_AND instruction for flag masking
-    const BPF_ALU: u16 = 0x04;
-    const BPF_AND: u16 = 0x50;
-
-    // Jump offset calculations:
-    // Each unconditional-block check = 2 instructions (JEQ + RET)
-    // socket check = 4 instructions (JEQ + LD arg + JEQ + RET)
-    // clone check = 4 instructions (JEQ + LD arg + JEQ_masked + RET)
-    // unshare check = 4 instructions (JEQ + LD arg + JEQ_masked + RET)
-    // final ALLOW = 1 instruction
-    //
-    // Total after the initial LD:
-    //   8 unconditional blocks (ptrace, mount, umount2, keyctl,
-    //     kexec_load, kexec_file_load, pivot_root, chroot) × 2 = 16
-    //   + socket block = 4
-    //   + clone block = 5 (JEQ + LD + ALU_AND + JEQ + RET)
-    //   + unshare block = 5 (JEQ + LD + ALU_AND + JEQ + RET)
-    //   + ALLOW = 1
-    //   Total = 31 instructions after LD
-
-    let deny = SockFilter::new(BPF_RET | BPF_K, 0, 0, SECCOMP_RET_ERRNO | EPERM);
-    let allow = SockFilter::new(BPF_RET | BPF_K, 0, 0, SECCOMP_RET_ALLOW);
-
-    let mut f: Vec<SockFilter> = Vec::with_capacity(34);
-
-    // [0] Load syscall number
-    f.push(SockFilter::new(
-        BPF_LD | BPF_W | BPF_ABS,
-        0,
-        0,
-        SECCOMP_DATA_NR,
-    ));
-
-    // Unconditional blocks — pattern: if syscall == X, deny; else fall through
-    // "jt=0" means jump 0 forward (= next instruction = deny), "jf=1" means skip deny
-    let unconditional = [
-        PTRACE,
-        MOUNT,
-        UMOUNT2,
-        KEYCTL,
-        KEXEC_LOAD,
-        KEXEC_FILE_LOAD,
-        PIVOT_ROOT,
-        CHROOT,
-    ];
-    for nr in unconditional {
-        f.push(SockFilter::new(BPF_JMP | BPF_JEQ | BPF_K, 0, 1, nr));
-        f.push(deny);
-    }
-
-    // --- socket(AF_UNIX) block ---
-    // remaining after this point: clone(5) + unshare(5) + allow(1) = 11
-    f.push(SockFilter::new(BPF_JMP | BPF_JEQ | BPF_K, 0, 3, SOCKET)); // if not socket, skip 3
-    f.push(SockFilter::new(
-        BPF_LD | BPF_W | BPF_ABS,
-        0,
-        0,
-        SECCOMP_DATA_ARGS,
-    )); // load arg0 (domain)
-    f.push(SockFilter::new(BPF_JMP | BPF_JEQ | BPF_K, 0, 1, AF_UNIX)); // if AF_UNIX -> deny
-    f.push(deny);
-    // Reload syscall number (destroyed by arg load above)
-    f.push(SockFilter::new(
-        BPF_LD | BPF_W | BPF_ABS,
-        0,
-        0,
-        SECCOMP_DATA_NR,
-    ));
-
-    // --- clone(CLONE_NEWUSER) block ---
-    // clone's flags argument: arg0 on x86_64, arg0 on aarch64 (clone3 uses struct but clone uses register)
-    // remaining after this: unshare(5) + allow(1) = 6
-    f.push(SockFilter::new(BPF_JMP | BPF_JEQ | BPF_K, 0, 4, CLONE)); // if not clone, skip 4
-    f.push(SockFilter::new(
-        BPF_LD | BPF_W | BPF_ABS,
-        0,
-        0,
-        SECCOMP_DATA_ARGS,
-    )); // load arg0 (flags)
-    f.push(SockFilter::new(
-        BPF_ALU | BPF_AND | BPF_K,
-        0,
-        0,
-        CLONE_NEWUSER,
-    )); // flags & CLONE_NEWUSER
-    f.push(SockFilter::new(
-        BPF_JMP | BPF_JEQ | BPF_K,
-        0,
-        1,
-        CLONE_NEWUSER,
-    )); // if set -> deny
-    f.push(deny);
-    // Reload syscall number
-    f.push(SockFilter::new(
-        BPF_LD | BPF_W | BPF_ABS,
-        0,
-        0,
-        SECCOMP_DATA_NR,
-    ));
-
-    // --- unshare(CLONE_NEWUSER) block ---
-    f.push(SockFilter::new(BPF_JMP | BPF_JEQ | BPF_K, 0, 4, UNSHARE)); // if not unshare, skip 4
-    f.push(SockFilter::new(
-        BPF_LD | BPF_W | BPF_ABS,
-        0,
-        0,
-        SECCOMP_DATA_ARGS,
-    )); // load arg0 (flags)
-    f.push(SockFilter::new(
-        BPF_ALU | BPF_AND | BPF_K,
-        0,
-        0,
-        CLONE_NEWUSER,
-    )); // flags & CLONE_NEWUSER
-    f.push(SockFilter::new(
-        BPF_JMP | BPF_JEQ | BPF_K,
-        0,
-        1,
-        CLONE_NEWUSER,
-    )); // if set -> deny
-    f.push(deny);
-
-    // Allow everything else
-    f.push(allow);
-
-    f
-}
-
-// ============================================================================
-// Pre-exec Hook for Sandbox
-// ============================================================================
-
 /// Apply seccomp filter in a pre_exec hook
 ///
 /// This is designed to be used with Command::pre_exec() in the sandbox.
@@ -385,6 +789,347 @@ pub fn apply_unix_socket_filter_pre_exec() -> io::Result<()> {
     apply_unix_socket_filter()
 }
 
+// ============================================================================
+// User Notification (log mode / syscall attribution)
+// ============================================================================
+
+/// Hidden first argument that turns the host binary into the exec shim.
+pub const EXEC_SHIM_ARG: &str = "__seccomp-exec";
+
+/// Where bwrap mounts the shim inside the sandbox.
+pub(crate) const EXEC_SHIM_SANDBOX_PATH: &str = "/.skilllite/seccomp-exec";
+
+/// Fds the shim receives: the BPF program and its end of the supervisor socket.
+pub(crate) const SHIM_FILTER_FD: RawFd = 3;
+pub(crate) const SHIM_SOCKET_FD: RawFd = 4;
+
+static EXEC_SHIM_AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// Call first thing in `main` of any binary that runs sandboxed skills.
+///
+/// Invoked as `<exe> __seccomp-exec <filter-fd> <socket-fd> <program> [args...]` (inside
+/// bwrap) it installs the filter with a notification listener, hands the listener to the
+/// parent's [`NotifySupervisor`] and execs `program`; it never returns. Otherwise it only
+/// records that this binary can act as the shim, enabling log mode and syscall attribution.
+pub fn run_exec_shim_if_requested() {
+    EXEC_SHIM_AVAILABLE.store(true, Ordering::Relaxed);
+    let mut args = std::env::args_os().skip(1);
+    if args.next().as_deref() != Some(OsStr::new(EXEC_SHIM_ARG)) {
+        return;
+    }
+    let rest: Vec<OsString> = args.collect();
+    let err = exec_shim(&rest);
+    eprintln!("skilllite seccomp shim: {}", err);
+    // Never run the skill unfiltered.
+    std::process::exit(126);
+}
+
+fn exec_shim(args: &[OsString]) -> io::Error {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidInput, msg.to_string());
+    let fd_arg = |arg: Option<&OsString>| -> io::Result<RawFd> {
+        arg.and_then(|a| a.to_str())
+            .and_then(|a| a.parse().ok())
+            .ok_or_else(|| invalid("expected <filter-fd> <socket-fd> <program> [args...]"))
+    };
+    let run = || -> io::Result<io::Error> {
+        let filter_fd = fd_arg(args.first())?;
+        let socket = unsafe { OwnedFd::from_raw_fd(fd_arg(args.get(1))?) };
+        let program = args.get(2).ok_or_else(|| invalid("missing program"))?;
+        let mut bytes = Vec::new();
+        unsafe { std::fs::File::from_raw_fd(filter_fd) }.read_to_end(&mut bytes)?;
+        let filter = decode_filter(&bytes)?;
+        let listener = unsafe {
+            OwnedFd::from_raw_fd(install_filter(&filter, SECCOMP_FILTER_FLAG_NEW_LISTENER)?)
+        };
+        send_fd(socket.as_raw_fd(), listener.as_raw_fd())?;
+        drop(listener);
+        drop(socket);
+        Ok(std::process::Command::new(program).args(&args[3..]).exec())
+    };
+    run().unwrap_or_else(|e| e)
+}
+
+/// Path of the host binary to mount as the shim, when this process registered one.
+pub(crate) fn exec_shim_binary() -> Option<PathBuf> {
+    if !EXEC_SHIM_AVAILABLE.load(Ordering::Relaxed) {
+        return None;
+    }
+    std::env::current_exe().ok().filter(|p| p.is_file())
+}
+
+/// Whether the kernel can hand out listener fds and continue notified syscalls (5.5+).
+pub fn notify_supported() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        kernel_at_least(5, 5)
+            && std::fs::read_to_string("/proc/sys/kernel/seccomp/actions_avail")
+                .is_ok_and(|s| s.split_whitespace().any(|a| a == "user_notif"))
+            && probe_listener()
+    })
+}
+
+fn kernel_at_least(major: u32, minor: u32) -> bool {
+    let mut uts: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut uts) } != 0 {
+        return false;
+    }
+    let release = unsafe { std::ffi::CStr::from_ptr(uts.release.as_ptr()) }.to_string_lossy();
+    let mut parts = release
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|p| p.parse::<u32>().ok());
+    let version = (parts.next().unwrap_or(0), parts.next().unwrap_or(0));
+    version >= (major, minor)
+}
+
+/// Containers and nested sandboxes may refuse a second listener; try one in a throwaway child.
+fn probe_listener() -> bool {
+    let filter = [SockFilter::ret(SECCOMP_RET_ALLOW)];
+    let mut cmd = std::process::Command::new("true");
+    cmd.stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    unsafe {
+        cmd.pre_exec(move || {
+            install_filter(&filter, SECCOMP_FILTER_FLAG_NEW_LISTENER).map(|fd| {
+                libc::close(fd);
+            })
+        });
+    }
+    cmd.status().is_ok_and(|s| s.success())
+}
+
+/// Answers notifications for one run: EPERM (`enforce`) or continue (`log`), recording each.
+pub struct NotifySupervisor {
+    child_socket: OwnedFd,
+    stop: Arc<AtomicBool>,
+    handle: std::thread::JoinHandle<Vec<SyscallViolation>>,
+}
+
+impl NotifySupervisor {
+    pub fn start(profile: &SeccompProfile) -> io::Result<Self> {
+        let mut fds = [0 as RawFd; 2];
+        let ret = unsafe {
+            libc::socketpair(
+                libc::AF_UNIX,
+                libc::SOCK_STREAM | libc::SOCK_CLOEXEC,
+                0,
+                fds.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let (parent, child) =
+            unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let profile = profile.clone();
+        let handle = std::thread::Builder::new()
+            .name("seccomp-notify".into())
+            .spawn(move || supervise(parent, &profile, &thread_stop))?;
+        Ok(Self {
+            child_socket: child,
+            stop,
+            handle,
+        })
+    }
+
+    /// The socket end the child sends its listener fd over.
+    pub fn child_socket(&self) -> RawFd {
+        self.child_socket.as_raw_fd()
+    }
+
+    /// Call after the child exited; returns the violations in the order they happened.
+    pub fn finish(self) -> Vec<SyscallViolation> {
+        drop(self.child_socket);
+        self.stop.store(true, Ordering::SeqCst);
+        self.handle.join().unwrap_or_default()
+    }
+}
+
+fn supervise(
+    socket: OwnedFd,
+    profile: &SeccompProfile,
+    stop: &AtomicBool,
+) -> Vec<SyscallViolation> {
+    let listener = loop {
+        if stop.load(Ordering::SeqCst) {
+            return Vec::new();
+        }
+        match poll_in(socket.as_raw_fd()) {
+            Ok(0) => continue,
+            Ok(_) => match recv_fd(socket.as_raw_fd()) {
+                Ok(Some(fd)) => break fd,
+                // The child exited before installing the filter.
+                Ok(None) => return Vec::new(),
+                Err(e) => {
+                    tracing::debug!("seccomp listener not received: {}", e);
+                    return Vec::new();
+                }
+            },
+            Err(e) => {
+                tracing::debug!("seccomp supervisor poll failed: {}", e);
+                return Vec::new();
+            }
+        }
+    };
+    drop(socket);
+
+    let mut violations = Vec::new();
+    while let Ok(revents) = poll_in(listener.as_raw_fd()) {
+        if revents & libc::POLLIN != 0 {
+            if let Some(violation) = answer(listener.as_raw_fd(), profile) {
+                if violations.len() < MAX_VIOLATIONS {
+                    violations.push(violation);
+                }
+            }
+        } else if revents & (libc::POLLHUP | libc::POLLERR) != 0 || stop.load(Ordering::SeqCst) {
+            // POLLHUP: every filtered process has exited.
+            break;
+        }
+    }
+    violations
+}
+
+fn answer(listener: RawFd, profile: &SeccompProfile) -> Option<SyscallViolation> {
+    let mut notif: SeccompNotif = unsafe { std::mem::zeroed() };
+    if unsafe {
+        libc::ioctl(
+            listener,
+            SECCOMP_IOCTL_NOTIF_RECV as _,
+            &mut notif as *mut SeccompNotif,
+        )
+    } < 0
+    {
+        // ENOENT: the caller was killed before we picked the notification up.
+        return None;
+    }
+    let blocked = profile.action == SeccompAction::Enforce;
+    let resp = SeccompNotifResp {
+        id: notif.id,
+        val: 0,
+        error: if blocked { -(EPERM as i32) } else { 0 },
+        flags: if blocked {
+            0
+        } else {
+            SECCOMP_USER_NOTIF_FLAG_CONTINUE
+        },
+    };
+    unsafe {
+        libc::ioctl(
+            listener,
+            SECCOMP_IOCTL_NOTIF_SEND as _,
+            &resp as *const SeccompNotifResp,
+        )
+    };
+    let syscall = profile
+        .rule_for(notif.data.nr, notif.data.args[0])
+        .map(|rule| rule.spec.clone())
+        .unwrap_or_else(|| format!("syscall {}", notif.data.nr));
+    Some(SyscallViolation {
+        syscall,
+        pid: notif.pid,
+        profile: profile.name.clone(),
+        blocked,
+    })
+}
+
+/// Line appended to stderr when an `enforce` profile denied syscalls.
+pub(crate) fn denied_note(violations: &[SyscallViolation]) -> Option<String> {
+    let mut names: Vec<&str> = violations
+        .iter()
+        .filter(|v| v.blocked)
+        .map(|v| v.syscall.as_str())
+        .collect();
+    let profile = &violations.first()?.profile;
+    names.sort_unstable();
+    names.dedup();
+    if names.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "\n[skilllite] seccomp profile `{}` denied syscall(s) with EPERM: {}\n",
+        profile,
+        names.join(", ")
+    ))
+}
+
+/// Wait up to [`POLL_INTERVAL_MS`] for `fd` to become readable; returns `revents`.
+fn poll_in(fd: RawFd) -> io::Result<i16> {
+    let mut pfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    match unsafe { libc::poll(&mut pfd, 1, POLL_INTERVAL_MS) } {
+        n if n < 0 => {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                Ok(0)
+            } else {
+                Err(err)
+            }
+        }
+        0 => Ok(0),
+        _ => Ok(pfd.revents),
+    }
+}
+
+/// Send `fd` over a Unix socket as SCM_RIGHTS. Does not allocate.
+fn send_fd(socket: RawFd, fd: RawFd) -> io::Result<()> {
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let mut control = [0u64; 8];
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = unsafe { libc::CMSG_SPACE(std::mem::size_of::<RawFd>() as u32) } as _;
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(std::mem::size_of::<RawFd>() as u32) as _;
+        std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>(), fd);
+    }
+    if unsafe { libc::sendmsg(socket, &msg, 0) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Receive one SCM_RIGHTS fd; `None` on EOF.
+fn recv_fd(socket: RawFd) -> io::Result<Option<OwnedFd>> {
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let mut control = [0u64; 8];
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = std::mem::size_of_val(&control) as _;
+    if unsafe { libc::recvmsg(socket, &mut msg, libc::MSG_CMSG_CLOEXEC) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+    if cmsg.is_null() {
+        return Ok(None);
+    }
+    unsafe {
+        if (*cmsg).cmsg_level != libc::SOL_SOCKET || (*cmsg).cmsg_type != libc::SCM_RIGHTS {
+            return Ok(None);
+        }
+        let fd = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>());
+        Ok(Some(OwnedFd::from_raw_fd(fd)))
+    }
+}
+
 // ============================================================================
 // Utility Functions
 // ============================================================================
@@ -431,6 +1176,18 @@ pub fn get_architecture() -> &'static str {
 mod tests {
     use super::*;
 
+    /// Install `filter` with a listener in a `pre_exec` hook and send the listener over
+    /// `socket`: the shim's job in bwrap runs, done in-process here since no bwrap is involved.
+    fn install_and_send_listener(filter: &[SockFilter], socket: RawFd) -> io::Result<()> {
+        let listener = install_filter(filter, SECCOMP_FILTER_FLAG_NEW_LISTENER)?;
+        let sent = send_fd(socket, listener);
+        unsafe {
+            libc::close(listener);
+            libc::close(socket);
+        }
+        sent
+    }
+
     #[test]
     fn test_seccomp_config() {
         let config = SeccompConfig::block_all_unix_sockets();
@@ -454,13 +1211,152 @@ mod tests {
     #[test]
     #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
     fn test_filter_generation() {
-        let filter = build_sandbox_filter();
-        assert!(!filter.is_empty());
-        // 1 (LD) + 16 (8 unconditional × 2) + 5 (socket) + 6 (clone) + 6 (unshare) + 1 (ALLOW) = 35
-        assert!(
-            filter.len() > 6,
-            "expanded filter should have more instructions than the old 6-instruction filter"
-        );
+        let profiles = SeccompProfiles::builtin();
+        let standard = profiles.for_level(SandboxLevel::Level3).unwrap();
+        assert_eq!(standard.name, "standard");
+        let filter = standard.filter(false);
+        // 1 (LD) + 2 (clone3) + 8 unconditional × 2 + 5 (socket) + 6 (clone) + 6 (unshare) + 1 (ALLOW)
+        assert_eq!(filter.len(), 37);
+        assert_eq!(filter[0], SockFilter::load(SECCOMP_DATA_NR));
+        assert_eq!(*filter.last().unwrap(), SockFilter::ret(SECCOMP_RET_ALLOW));
+        assert!(filter.contains(&SockFilter::ret(SECCOMP_RET_ERRNO | EPERM)));
+        assert!(filter.contains(&SockFilter::ret(SECCOMP_RET_ERRNO | ENOSYS)));
+
+        let notify = standard.filter(true);
+        assert!(notify.contains(&SockFilter::ret(SECCOMP_RET_USER_NOTIF)));
+        assert!(!notify.contains(&SockFilter::ret(SECCOMP_RET_ERRNO | EPERM)));
+
+        let bytes = standard.filter_bytes(true);
+        assert_eq!(decode_filter(&bytes).unwrap(), notify);
+    }
+
+    #[test]
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn test_rule_matching() {
+        let rule = SyscallRule::parse("socket( AF_UNIX )").unwrap();
+        assert_eq!(rule.spec, "socket(AF_UNIX)");
+        assert!(rule.matches(libc::SYS_socket as i32, 1));
+        assert!(!rule.matches(libc::SYS_socket as i32, 2));
+
+        let rule = SyscallRule::parse("clone(CLONE_NEWUSER)").unwrap();
+        assert!(rule.matches(libc::SYS_clone as i32, 0x1000_0000 | 0x11));
+        assert!(!rule.matches(libc::SYS_clone as i32, 0x11));
+
+        assert!(SyscallRule::parse("not_a_syscall")
+            .unwrap_err()
+            .contains("unknown syscall"));
+        assert!(SyscallRule::parse("socket(AF_BOGUS)")
+            .unwrap_err()
+            .contains("AF_UNIX"));
+    }
+
+    #[test]
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn test_profiles_toml_override() {
+        let mut profiles = SeccompProfiles::builtin();
+        profiles
+            .merge_toml(
+                r#"
+[levels]
+level2 = "none"
+level3 = "audit"
+
+[profiles.audit]
+action = "log"
+deny = ["ptrace", "socket(AF_UNIX)"]
+"#,
+            )
+            .unwrap();
+        assert!(profiles.for_level(SandboxLevel::Level1).is_none());
+        assert!(profiles.for_level(SandboxLevel::Level2).is_none());
+        let audit = profiles.for_level(SandboxLevel::Level3).unwrap();
+        assert_eq!(audit.action, SeccompAction::Log);
+        assert_eq!(audit.deny.len(), 2);
+        assert!(audit.enosys.is_empty());
+        assert_eq!(profiles.names(), vec!["audit", "standard", "strict"]);
+
+        let err = SeccompProfiles::builtin()
+            .merge_toml("[levels]\nlevel3 = \"missing\"\n")
+            .unwrap_err();
+        assert!(err.contains("unknown profile `missing`"), "{}", err);
+        let err = SeccompProfiles::builtin()
+            .merge_toml("[profiles.bad]\ndeny = [\"frobnicate\"]\n")
+            .unwrap_err();
+        assert!(err.contains("profile `bad`"), "{}", err);
+        assert!(SeccompProfiles::builtin()
+            .merge_toml("[levels]\nlevel1 = \"standard\"\n")
+            .is_err());
+    }
+
+    #[test]
+    fn test_denied_note_lists_each_syscall_once() {
+        let v = |syscall: &str, blocked| SyscallViolation {
+            syscall: syscall.to_string(),
+            pid: 1,
+            profile: "standard".to_string(),
+            blocked,
+        };
+        let note = denied_note(&[
+            v("ptrace", true),
+            v("socket(AF_UNIX)", true),
+            v("ptrace", true),
+        ])
+        .unwrap();
+        assert!(note.contains("`standard`"));
+        assert!(note.contains("EPERM: ptrace, socket(AF_UNIX)"), "{}", note);
+        assert!(denied_note(&[v("ptrace", false)]).is_none());
+        assert!(denied_note(&[]).is_none());
+    }
+
+    /// Run the denied-syscall fixture under a profile that denies `uname`.
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn run_fixture(action: SeccompAction) -> Option<(String, Vec<SyscallViolation>)> {
+        if !notify_supported() || which::which("python3").is_err() {
+            eprintln!("skipping: seccomp user notification or python3 unavailable");
+            return None;
+        }
+        let profile = SeccompProfile::new("test", action, &["uname"], &[]).unwrap();
+        let filter = profile.filter(true);
+        let supervisor = NotifySupervisor::start(&profile).unwrap();
+        let socket = supervisor.child_socket();
+        let mut cmd = std::process::Command::new("python3");
+        cmd.args(["-S", "-c"])
+            .arg(include_str!("../tests/fixtures/seccomp/denied_syscall.py"));
+        unsafe {
+            cmd.pre_exec(move || install_and_send_listener(&filter, socket));
+        }
+        let output = cmd.output().unwrap();
+        let violations = supervisor.finish();
+        Some((
+            String::from_utf8_lossy(&output.stdout).into_owned(),
+            violations,
+        ))
+    }
+
+    #[test]
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn test_enforce_mode_denies_and_records() {
+        let Some((stdout, violations)) = run_fixture(SeccompAction::Enforce) else {
+            return;
+        };
+        assert_eq!(stdout.trim(), "denied errno=1");
+        assert!(!violations.is_empty());
+        assert!(violations.iter().all(|v| v.syscall == "uname" && v.blocked));
+        assert!(denied_note(&violations).unwrap().contains("uname"));
+    }
+
+    #[test]
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn test_log_mode_allows_and_records() {
+        let Some((stdout, violations)) = run_fixture(SeccompAction::Log) else {
+            return;
+        };
+        assert_eq!(stdout.trim(), "allowed");
+        assert!(!violations.is_empty());
+        assert!(violations
+            .iter()
+            .all(|v| v.syscall == "uname" && !v.blocked));
+        assert!(denied_note(&violations).is_none());
     }
 
     #[test]
@@ -3,52 +3,98 @@
 use std::collections::HashSet;
 use std::path::Path;
 
+use crate::env::lockfile::SKILL_LOCK_FILE;
 use crate::Result;
 
 use super::super::malicious_packages::{check_malicious_packages, MaliciousPackageHit};
 use super::backends::{query_osv_batch, query_pypi};
-use super::config::{self, get_custom_api, get_osv_api_base, get_pypi_base, make_agent};
-use super::parsers::{parse_lock_file, parse_package_json, parse_requirements_txt};
+use super::cache::AuditCache;
+use super::config::{
+    self, default_cache_dir, get_cache_ttl_secs, get_custom_api, get_osv_api_base, get_pypi_base,
+    make_agent,
+};
+use super::parsers::{
+    normalize_pypi_name, parse_lock_file, parse_package_json, parse_package_lock,
+    parse_poetry_lock, parse_pyproject_dependency_names, parse_requirements_txt, parse_skill_lock,
+};
 use super::resolve::resolve_from_metadata_fields;
 use super::types::{
-    AuditBackend, Dependency, DependencyAuditResult, MetadataHint, PackageAuditEntry,
+    AuditBackend, AuditOptions, Dependency, DependencyAuditResult, MetadataHint, PackageAuditEntry,
 };
 
-/// Collect all dependencies from a skill directory.
+fn dep_key(dep: &Dependency) -> (String, String) {
+    let name = match dep.ecosystem.as_str() {
+        "PyPI" => normalize_pypi_name(&dep.name),
+        _ => dep.name.to_lowercase(),
+    };
+    (name, dep.ecosystem.clone())
+}
+
+/// Merge lockfile packages: the lockfile supersedes manifest pins for the packages it
+/// lists, and a locked package counts as direct when a manifest declares it.
+fn merge_locked(
+    deps: &mut Vec<Dependency>,
+    locked: Vec<Dependency>,
+    declared: &HashSet<(String, String)>,
+) {
+    if locked.is_empty() {
+        return;
+    }
+    let locked_keys: HashSet<_> = locked.iter().map(dep_key).collect();
+    deps.retain(|d| !locked_keys.contains(&dep_key(d)));
+    let mut seen = HashSet::new();
+    for mut dep in locked {
+        let key = dep_key(&dep);
+        dep.direct = dep.direct || declared.contains(&key);
+        if seen.insert((key, dep.version.clone())) {
+            deps.push(dep);
+        }
+    }
+}
+
+/// Collect all dependencies from a skill directory: manifests, then lockfiles
+/// (`package-lock.json`, `poetry.lock`, `skill.lock`) for transitive packages.
 pub(super) fn collect_dependencies(
     skill_dir: &Path,
     metadata_hint: Option<&MetadataHint>,
 ) -> Vec<Dependency> {
     let mut deps = Vec::new();
-    let mut seen = HashSet::new();
+    let read = |name: &str| std::fs::read_to_string(skill_dir.join(name)).ok();
 
-    let req_txt = skill_dir.join("requirements.txt");
-    if req_txt.exists() {
-        if let Ok(content) = std::fs::read_to_string(&req_txt) {
-            for dep in parse_requirements_txt(&content) {
-                seen.insert((dep.name.to_lowercase(), dep.ecosystem.clone()));
-                deps.push(dep);
-            }
-        }
+    if let Some(content) = read("requirements.txt") {
+        deps.extend(parse_requirements_txt(&content));
+    }
+    if let Some(content) = read("package.json") {
+        deps.extend(parse_package_json(&content));
     }
 
-    let pkg_json = skill_dir.join("package.json");
-    if pkg_json.exists() {
-        if let Ok(content) = std::fs::read_to_string(&pkg_json) {
-            for dep in parse_package_json(&content) {
-                seen.insert((dep.name.to_lowercase(), dep.ecosystem.clone()));
-                deps.push(dep);
-            }
-        }
+    let mut declared: HashSet<(String, String)> =
+        deps.iter().filter(|d| d.direct).map(dep_key).collect();
+    if let Some(content) = read("pyproject.toml") {
+        declared.extend(
+            parse_pyproject_dependency_names(&content)
+                .into_iter()
+                .map(|name| (name, "PyPI".to_string())),
+        );
+    }
+    if let Some(content) = read("package-lock.json") {
+        merge_locked(&mut deps, parse_package_lock(&content), &declared);
+    }
+    if let Some(content) = read("poetry.lock") {
+        merge_locked(&mut deps, parse_poetry_lock(&content), &declared);
     }
+    // skill.lock pins what the environment actually installs, so it wins over the others
+    if let Some(content) = read(SKILL_LOCK_FILE) {
+        merge_locked(&mut deps, parse_skill_lock(&content), &declared);
+    }
+
+    let mut seen: HashSet<_> = deps.iter().map(dep_key).collect();
 
     let lock_path = skill_dir.join(".skilllite.lock");
     if lock_path.exists() {
         if let Some(lock_deps) = parse_lock_file(&lock_path) {
             for dep in lock_deps {
-                let key = (dep.name.to_lowercase(), dep.ecosystem.clone());
-                if !seen.contains(&key) {
-                    seen.insert(key);
+                if seen.insert(dep_key(&dep)) {
                     deps.push(dep);
                 }
             }
@@ -66,9 +112,7 @@ pub(super) fn collect_dependencies(
                 &hint.entry_point,
             );
             for dep in inferred {
-                let key = (dep.name.to_lowercase(), dep.ecosystem.clone());
-                if !seen.contains(&key) {
-                    seen.insert(key);
+                if seen.insert(dep_key(&dep)) {
                     deps.push(dep);
                 }
             }
@@ -78,10 +122,19 @@ pub(super) fn collect_dependencies(
     deps
 }
 
-/// Run a full dependency audit on a skill directory.
+/// Run a full dependency audit on a skill directory (online, cache-backed).
 pub fn audit_skill_dependencies(
     skill_dir: &Path,
     metadata_hint: Option<&MetadataHint>,
+) -> Result<DependencyAuditResult> {
+    audit_skill_dependencies_with_options(skill_dir, metadata_hint, &AuditOptions::default())
+}
+
+/// Run a dependency audit with explicit options (offline mode, cache location).
+pub fn audit_skill_dependencies_with_options(
+    skill_dir: &Path,
+    metadata_hint: Option<&MetadataHint>,
+    options: &AuditOptions,
 ) -> Result<DependencyAuditResult> {
     let deps = collect_dependencies(skill_dir, metadata_hint);
 
@@ -98,85 +151,174 @@ pub fn audit_skill_dependencies(
         }
     }
 
+    let custom_url = get_custom_api();
+    let backend = match &custom_url {
+        Some(url) => AuditBackend::Custom(url.clone()),
+        None => AuditBackend::Native,
+    };
+
     if deps.is_empty() {
-        return Ok(DependencyAuditResult {
-            scanned: 0,
-            vulnerable_count: 0,
-            total_vulns: 0,
-            backend: AuditBackend::Native,
-            entries: Vec::new(),
-            malicious: malicious_hits,
-        });
+        return Ok(finish(
+            build_result(Vec::new(), backend, malicious_hits),
+            Vec::new(),
+            0,
+            options.offline,
+        ));
+    }
+
+    let cache = options
+        .cache_dir
+        .clone()
+        .or_else(default_cache_dir)
+        .map(|root| AuditCache::new(root, get_cache_ttl_secs()));
+
+    let mut all_entries = Vec::new();
+    let mut pending = Vec::new();
+    for dep in deps {
+        let cached = cache
+            .as_ref()
+            .filter(|_| dep.pinned)
+            .and_then(|c| c.get(&dep, options.offline));
+        match cached {
+            Some(entry) => all_entries.push(entry),
+            None => pending.push(dep),
+        }
     }
+    let from_cache = all_entries.len();
 
-    let agent = make_agent();
+    if options.offline {
+        if !pending.is_empty() {
+            tracing::info!(
+                "Offline: {} dependencies not in audit cache, reported as unverified",
+                pending.len()
+            );
+        }
+        return Ok(finish(
+            build_result(all_entries, backend, malicious_hits),
+            pending,
+            from_cache,
+            true,
+        ));
+    }
 
-    if let Some(custom_url) = get_custom_api() {
+    let mut unverified = Vec::new();
+    let mut live_entries = Vec::new();
+    if let Some(custom_url) = custom_url.filter(|_| !pending.is_empty()) {
         tracing::info!(
             "Scanning {} dependencies via custom API ({})...",
-            deps.len(),
+            pending.len(),
             custom_url
         );
-        let entries = query_osv_batch(&agent, &deps, &custom_url)?;
-        return Ok(build_result(
-            entries,
-            AuditBackend::Custom(custom_url),
-            malicious_hits,
-        ));
-    }
-
-    let pypi_deps: Vec<_> = deps
-        .iter()
-        .filter(|d| d.ecosystem == "PyPI")
-        .cloned()
-        .collect();
-    let npm_deps: Vec<_> = deps
-        .iter()
-        .filter(|d| d.ecosystem == "npm")
-        .cloned()
-        .collect();
+        match query_osv_batch(&make_agent(), &pending, &custom_url) {
+            Ok(entries) => live_entries = entries,
+            Err(e) => {
+                tracing::warn!(
+                    "Custom audit API unreachable, {} dependencies unverified: {}",
+                    pending.len(),
+                    e
+                );
+                unverified.extend(pending.iter().cloned());
+            }
+        }
+    } else if !pending.is_empty() {
+        let agent = make_agent();
+        let pypi_deps: Vec<_> = pending
+            .iter()
+            .filter(|d| d.ecosystem == "PyPI")
+            .cloned()
+            .collect();
+        let npm_deps: Vec<_> = pending
+            .iter()
+            .filter(|d| d.ecosystem == "npm")
+            .cloned()
+            .collect();
 
-    let mut all_entries = Vec::new();
+        if !pypi_deps.is_empty() {
+            let pypi_base = get_pypi_base();
+            let mirror_note = if pypi_base != config::DEFAULT_PYPI_BASE {
+                format!(" (via {})", pypi_base)
+            } else {
+                String::new()
+            };
+            tracing::info!(
+                "Scanning {} Python dependencies via PyPI{}...",
+                pypi_deps.len(),
+                mirror_note
+            );
+            let (pypi_entries, failed) = query_pypi(&agent, &pypi_deps, &pypi_base)?;
+            live_entries.extend(pypi_entries);
+            unverified.extend(failed);
+        }
 
-    if !pypi_deps.is_empty() {
-        let pypi_base = get_pypi_base();
-        let mirror_note = if pypi_base != config::DEFAULT_PYPI_BASE {
-            format!(" (via {})", pypi_base)
-        } else {
-            String::new()
-        };
-        tracing::info!(
-            "Scanning {} Python dependencies via PyPI{}...",
-            pypi_deps.len(),
-            mirror_note
-        );
-        let pypi_entries = query_pypi(&agent, &pypi_deps, &pypi_base)?;
-        all_entries.extend(pypi_entries);
+        if !npm_deps.is_empty() {
+            let osv_base = get_osv_api_base();
+            let mirror_note = if osv_base != config::DEFAULT_OSV_API_BASE {
+                format!(" (via {})", osv_base)
+            } else {
+                String::new()
+            };
+            tracing::info!(
+                "Scanning {} npm dependencies via OSV{}...",
+                npm_deps.len(),
+                mirror_note
+            );
+            match query_osv_batch(&agent, &npm_deps, &osv_base) {
+                Ok(osv_entries) => live_entries.extend(osv_entries),
+                Err(e) => {
+                    tracing::warn!(
+                        "OSV unreachable, {} npm dependencies unverified: {}",
+                        npm_deps.len(),
+                        e
+                    );
+                    unverified.extend(npm_deps);
+                }
+            }
+        }
     }
 
-    if !npm_deps.is_empty() {
-        let osv_base = get_osv_api_base();
-        let mirror_note = if osv_base != config::DEFAULT_OSV_API_BASE {
-            format!(" (via {})", osv_base)
-        } else {
-            String::new()
-        };
-        tracing::info!(
-            "Scanning {} npm dependencies via OSV{}...",
-            npm_deps.len(),
-            mirror_note
-        );
-        let osv_entries = query_osv_batch(&agent, &npm_deps, &osv_base)?;
-        all_entries.extend(osv_entries);
+    if let Some(cache) = &cache {
+        // Only exact versions are cached; unversioned ones resolve "latest" at query time and a
+        // range's lower bound says nothing about the version that gets installed
+        let pinned: HashSet<_> = pending
+            .iter()
+            .filter(|d| d.pinned && !d.version.is_empty())
+            .map(|d| (dep_key(d), d.version.clone()))
+            .collect();
+        for entry in &live_entries {
+            let dep = Dependency {
+                name: entry.name.clone(),
+                version: entry.version.clone(),
+                ecosystem: entry.ecosystem.clone(),
+                direct: entry.direct,
+                pinned: true,
+            };
+            if pinned.contains(&(dep_key(&dep), dep.version.clone())) {
+                cache.put(entry);
+            }
+        }
     }
+    all_entries.extend(live_entries);
 
-    Ok(build_result(
-        all_entries,
-        AuditBackend::Native,
-        malicious_hits,
+    Ok(finish(
+        build_result(all_entries, backend, malicious_hits),
+        unverified,
+        from_cache,
+        false,
     ))
 }
 
+fn finish(
+    mut result: DependencyAuditResult,
+    unverified: Vec<Dependency>,
+    from_cache: usize,
+    offline: bool,
+) -> DependencyAuditResult {
+    result.unverified = unverified;
+    result.from_cache = from_cache;
+    result.offline = offline;
+    result
+}
+
 pub(crate) fn build_result(
     entries: Vec<PackageAuditEntry>,
     backend: AuditBackend,
@@ -192,5 +334,8 @@ pub(crate) fn build_result(
         backend,
         entries,
         malicious,
+        unverified: Vec::new(),
+        from_cache: 0,
+        offline: false,
     }
 }
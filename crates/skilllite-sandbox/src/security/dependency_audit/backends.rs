@@ -72,6 +72,7 @@ pub(super) fn query_osv_batch(
                 name: dep.name.clone(),
                 version: dep.version.clone(),
                 ecosystem: dep.ecosystem.clone(),
+                direct: dep.direct,
                 vulns: result
                     .vulns
                     .into_iter()
@@ -119,12 +120,14 @@ struct PypiInfo {
 }
 
 /// Query PyPI JSON API for vulnerabilities on a list of Python packages.
+/// Packages whose query failed (other than 404) are returned separately as unverified.
 pub(super) fn query_pypi(
     agent: &ureq::Agent,
     deps: &[Dependency],
     pypi_base: &str,
-) -> Result<Vec<PackageAuditEntry>> {
+) -> Result<(Vec<PackageAuditEntry>, Vec<Dependency>)> {
     let mut entries = Vec::new();
+    let mut unverified = Vec::new();
 
     for dep in deps {
         let (url, has_version) = if dep.version.is_empty() {
@@ -148,6 +151,7 @@ pub(super) fn query_pypi(
                         name: dep.name.clone(),
                         version: dep.version.clone(),
                         ecosystem: dep.ecosystem.clone(),
+                        direct: dep.direct,
                         vulns: pypi
                             .vulnerabilities
                             .into_iter()
@@ -176,6 +180,7 @@ pub(super) fn query_pypi(
                         name: dep.name.clone(),
                         version: resolved_version,
                         ecosystem: dep.ecosystem.clone(),
+                        direct: dep.direct,
                         vulns: pypi
                             .vulnerabilities
                             .into_iter()
@@ -198,6 +203,7 @@ pub(super) fn query_pypi(
                     name: dep.name.clone(),
                     version,
                     ecosystem: dep.ecosystem.clone(),
+                    direct: dep.direct,
                     vulns: Vec::new(),
                 });
             }
@@ -213,15 +219,10 @@ pub(super) fn query_pypi(
                     version_display,
                     e
                 );
-                entries.push(PackageAuditEntry {
-                    name: dep.name.clone(),
-                    version: dep.version.clone(),
-                    ecosystem: dep.ecosystem.clone(),
-                    vulns: Vec::new(),
-                });
+                unverified.push(dep.clone());
             }
         }
     }
 
-    Ok(entries)
+    Ok((entries, unverified))
 }
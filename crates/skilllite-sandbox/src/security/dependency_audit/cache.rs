@@ -0,0 +1,104 @@
+//! On-disk cache of audit responses, keyed by ecosystem + package + version.
+//!
+//! Layout: `<root>/<ecosystem>/<name>@<version>.json`. Only pinned versions are
+//! cached; unversioned queries resolve "latest" and would go stale silently.
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::types::{Dependency, PackageAuditEntry, VulnRef};
+
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    fetched_at: u64,
+    vulns: Vec<VulnRef>,
+}
+
+pub(super) struct AuditCache {
+    root: PathBuf,
+    ttl_secs: u64,
+}
+
+impl AuditCache {
+    pub(super) fn new(root: PathBuf, ttl_secs: u64) -> Self {
+        Self { root, ttl_secs }
+    }
+
+    /// Look up a cached entry. `allow_stale` ignores the TTL (offline mode).
+    pub(super) fn get(&self, dep: &Dependency, allow_stale: bool) -> Option<PackageAuditEntry> {
+        let path = self.entry_path(dep)?;
+        let content = std::fs::read_to_string(path).ok()?;
+        let cached: CachedResponse = serde_json::from_str(&content).ok()?;
+        if !allow_stale && now_secs().saturating_sub(cached.fetched_at) >= self.ttl_secs {
+            return None;
+        }
+        Some(PackageAuditEntry {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            ecosystem: dep.ecosystem.clone(),
+            direct: dep.direct,
+            vulns: cached.vulns,
+        })
+    }
+
+    /// Store a live response. Write failures are logged, never fatal.
+    pub(super) fn put(&self, entry: &PackageAuditEntry) {
+        let dep = Dependency {
+            name: entry.name.clone(),
+            version: entry.version.clone(),
+            ecosystem: entry.ecosystem.clone(),
+            direct: entry.direct,
+            pinned: true,
+        };
+        let Some(path) = self.entry_path(&dep) else {
+            return;
+        };
+        let cached = CachedResponse {
+            fetched_at: now_secs(),
+            vulns: entry.vulns.clone(),
+        };
+        let Ok(json) = serde_json::to_string(&cached) else {
+            return;
+        };
+        if let Err(e) = skilllite_fs::atomic_write(&path, &json) {
+            tracing::debug!("Failed to write audit cache {}: {}", path.display(), e);
+        }
+    }
+
+    fn entry_path(&self, dep: &Dependency) -> Option<PathBuf> {
+        if dep.version.is_empty() {
+            return None;
+        }
+        let name = match dep.ecosystem.as_str() {
+            // PEP 503 normalization so `Foo_Bar` and `foo-bar` share an entry
+            "PyPI" => dep.name.to_lowercase().replace(['_', '.'], "-"),
+            _ => dep.name.clone(),
+        };
+        Some(self.root.join(sanitize(&dep.ecosystem)).join(format!(
+            "{}@{}.json",
+            sanitize(&name),
+            sanitize(&dep.version)
+        )))
+    }
+}
+
+fn sanitize(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '@') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -2,6 +2,7 @@
 
 pub(super) const DEFAULT_PYPI_BASE: &str = "https://pypi.org";
 pub(super) const DEFAULT_OSV_API_BASE: &str = "https://api.osv.dev";
+pub(super) const DEFAULT_CACHE_TTL_SECS: u64 = 24 * 60 * 60;
 
 /// Get custom audit API URL, if configured.
 pub(super) fn get_custom_api() -> Option<String> {
@@ -37,6 +38,26 @@ pub(super) fn get_osv_api_base() -> String {
     .to_string()
 }
 
+/// Max age of a cached audit response before it is re-queried (0 = always re-query online).
+pub(super) fn get_cache_ttl_secs() -> u64 {
+    skilllite_core::config::load_dotenv();
+    skilllite_core::config::env_optional(
+        skilllite_core::config::env_keys::misc::SKILLLITE_AUDIT_CACHE_TTL_SECS,
+        &[],
+    )
+    .and_then(|s| s.trim().parse().ok())
+    .unwrap_or(DEFAULT_CACHE_TTL_SECS)
+}
+
+/// Root of the audit response cache: `<cache>/skilllite/audit/osv`.
+pub(super) fn default_cache_dir() -> Option<std::path::PathBuf> {
+    skilllite_core::config::load_dotenv();
+    let base = skilllite_core::config::CacheConfig::cache_dir()
+        .map(std::path::PathBuf::from)
+        .or_else(|| dirs::cache_dir().map(|d| d.join("skilllite")))?;
+    Some(base.join("audit").join("osv"))
+}
+
 pub(super) fn make_agent() -> ureq::Agent {
     ureq::AgentBuilder::new()
         .timeout_connect(std::time::Duration::from_secs(10))
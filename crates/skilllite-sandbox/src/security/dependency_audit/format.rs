@@ -1,9 +1,53 @@
 //! Human-readable and JSON formatting of audit results.
 
-use super::types::DependencyAuditResult;
+use super::types::{DependencyAuditResult, PackageAuditEntry};
 
 /// Format audit result for human-readable terminal display.
 pub fn format_audit_result(result: &DependencyAuditResult) -> String {
+    let unverified = format_unverified(result);
+    if result.scanned == 0 && result.malicious.is_empty() && !unverified.is_empty() {
+        return unverified;
+    }
+    let mut out = format_findings(result);
+    if result.from_cache > 0 {
+        out.push_str(&format!(
+            "\n📦 {} result(s) from audit cache.",
+            result.from_cache
+        ));
+    }
+    if !unverified.is_empty() {
+        out.push_str("\n\n");
+        out.push_str(&unverified);
+    }
+    out
+}
+
+fn format_unverified(result: &DependencyAuditResult) -> String {
+    if result.unverified.is_empty() {
+        return String::new();
+    }
+    let why = if result.offline {
+        "offline, not in audit cache"
+    } else {
+        "vulnerability database unreachable"
+    };
+    let mut lines = vec![format!(
+        "❔ Audit incomplete: {} package(s) unverified ({})",
+        result.unverified.len(),
+        why
+    )];
+    for dep in &result.unverified {
+        let version = if dep.version.is_empty() {
+            "(unpinned)"
+        } else {
+            &dep.version
+        };
+        lines.push(format!("  · {} {} [{}]", dep.name, version, dep.ecosystem));
+    }
+    lines.join("\n")
+}
+
+fn format_findings(result: &DependencyAuditResult) -> String {
     let mut lines: Vec<String> = Vec::new();
 
     if !result.malicious.is_empty() {
@@ -52,9 +96,10 @@ pub fn format_audit_result(result: &DependencyAuditResult) -> String {
         if entry.vulns.is_empty() {
             continue;
         }
+        let transitive = if entry.direct { "" } else { " (transitive)" };
         lines.push(format!(
-            "  🔴 {} {} [{}]",
-            entry.name, entry.version, entry.ecosystem
+            "  🔴 {} {} [{}]{}",
+            entry.name, entry.version, entry.ecosystem, transitive
         ));
         for vuln in entry.vulns.iter().take(10) {
             let fix = if vuln.fixed_in.is_empty() {
@@ -92,6 +137,28 @@ pub fn format_audit_result(result: &DependencyAuditResult) -> String {
 }
 
 /// Format audit result as structured JSON.
+///
+/// Besides the raw result, vulnerable packages are split into `direct_findings`
+/// and `transitive_findings`, and `complete` is `false` when packages went unverified.
 pub fn format_audit_result_json(result: &DependencyAuditResult) -> String {
-    serde_json::to_string_pretty(result).unwrap_or_else(|_| "{}".to_string())
+    let mut value = match serde_json::to_value(result) {
+        Ok(v) => v,
+        Err(_) => return "{}".to_string(),
+    };
+    let findings = |direct: bool| -> Vec<&PackageAuditEntry> {
+        result
+            .entries
+            .iter()
+            .filter(|e| e.direct == direct && !e.vulns.is_empty())
+            .collect()
+    };
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("direct_findings".into(), serde_json::json!(findings(true)));
+        obj.insert(
+            "transitive_findings".into(),
+            serde_json::json!(findings(false)),
+        );
+        obj.insert("complete".into(), result.is_complete().into());
+    }
+    serde_json::to_string_pretty(&value).unwrap_or_else(|_| "{}".to_string())
 }
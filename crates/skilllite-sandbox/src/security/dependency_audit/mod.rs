@@ -1,7 +1,15 @@
 //! Supply chain vulnerability scanning — multi-backend architecture.
 //!
-//! Parses dependency files (requirements.txt, package.json) from skill directories
-//! and queries vulnerability databases for known issues.
+//! Parses dependency files (requirements.txt, package.json) and lockfiles
+//! (package-lock.json, poetry.lock, skill.lock, pip-compile output) from skill directories and
+//! queries vulnerability databases for known issues, direct and transitive.
+//!
+//! # Cache and offline mode
+//!
+//! Responses for pinned packages are cached under `<cache>/skilllite/audit/osv/`
+//! and reused for `SKILLLITE_AUDIT_CACHE_TTL_SECS`. With [`AuditOptions::offline`]
+//! only the cache is consulted (regardless of age) and uncached packages are
+//! reported as unverified instead of failing the audit.
 //!
 //! # Backend priority
 //!
@@ -16,9 +24,11 @@
 //! | `SKILLLITE_AUDIT_API` | *(none)* | Custom security API (overrides all other backends) |
 //! | `PYPI_MIRROR_URL` | `https://pypi.org` | PyPI mirror for Python vulnerability queries |
 //! | `OSV_API_URL` | `https://api.osv.dev` | OSV API for npm / fallback queries |
+//! | `SKILLLITE_AUDIT_CACHE_TTL_SECS` | `86400` | Cache freshness for online audits (0 = always query) |
 
 mod audit;
 mod backends;
+mod cache;
 mod config;
 mod format;
 mod parsers;
@@ -28,8 +38,9 @@ mod types;
 #[cfg(test)]
 mod tests;
 
-pub use audit::audit_skill_dependencies;
+pub use audit::{audit_skill_dependencies, audit_skill_dependencies_with_options};
 pub use format::{format_audit_result, format_audit_result_json};
 pub use types::{
-    AuditBackend, Dependency, DependencyAuditResult, MetadataHint, PackageAuditEntry, VulnRef,
+    AuditBackend, AuditOptions, Dependency, DependencyAuditResult, MetadataHint, PackageAuditEntry,
+    VulnRef,
 };
@@ -1,34 +1,86 @@
-//! Dependency file parsers.
+//! Dependency file parsers: manifests and lockfiles.
 
+use std::collections::HashSet;
 use std::path::Path;
 
 use super::types::Dependency;
+use crate::env::lockfile::SkillLock;
 
 /// Parse Python `requirements.txt` / `pip freeze` output.
+///
+/// pip-compile annotations (`# via requests`) mark a pin as transitive; pins
+/// annotated `# via -r requirements.in` (or not annotated at all) stay direct.
 pub fn parse_requirements_txt(content: &str) -> Vec<Dependency> {
     let mut deps = Vec::new();
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+    // Per dependency: the `# via` targets seen so far
+    let mut via: Vec<Vec<String>> = Vec::new();
+    let mut in_via_block = false;
+    for raw in content.lines() {
+        let line = raw.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.trim();
+            if let Some(rest) = comment.strip_prefix("via") {
+                in_via_block = true;
+                if let Some(targets) = via.last_mut() {
+                    targets.extend(parse_via_targets(rest));
+                }
+            } else if in_via_block && !comment.is_empty() && raw.starts_with(char::is_whitespace) {
+                if let Some(targets) = via.last_mut() {
+                    targets.push(comment.to_string());
+                }
+            } else {
+                in_via_block = false;
+            }
             continue;
         }
-        let line = line.split('#').next().unwrap_or(line).trim();
-
-        if let Some((name, version)) = line.split_once("==") {
-            push_if_valid(&mut deps, name, version, "PyPI");
+        in_via_block = false;
+        if line.is_empty() || line.starts_with('-') {
             continue;
         }
-        if let Some(idx) = line.find(['>', '<', '~', '!']) {
-            let name = &line[..idx];
-            let rest = &line[idx..];
+        let (spec, inline_comment) = match line.split_once('#') {
+            Some((spec, comment)) => (spec.trim(), Some(comment.trim())),
+            None => (line, None),
+        };
+        // Drop environment markers and `\` continuations (`--hash` lines follow)
+        let spec = spec.split(';').next().unwrap_or(spec);
+        let spec = spec.trim_end_matches('\\').trim();
+
+        let before = deps.len();
+        if let Some((name, version)) = spec.split_once("==") {
+            let version = version.split_whitespace().next().unwrap_or("");
+            push_if_valid(&mut deps, name, version, "PyPI", true);
+        } else if let Some(idx) = spec.find(['>', '<', '~', '!']) {
+            let name = &spec[..idx];
+            let rest = &spec[idx..];
             let version = rest.trim_start_matches(['>', '<', '~', '!', '=']);
             let version = version.split(',').next().unwrap_or("").trim();
-            push_if_valid(&mut deps, name, version, "PyPI");
+            push_if_valid(&mut deps, name, version, "PyPI", false);
+        }
+        if deps.len() > before {
+            let mut targets = Vec::new();
+            if let Some(rest) = inline_comment.and_then(|c| c.strip_prefix("via")) {
+                targets.extend(parse_via_targets(rest));
+            }
+            via.push(targets);
+        }
+    }
+    for (dep, targets) in deps.iter_mut().zip(&via) {
+        if !targets.is_empty() {
+            dep.direct = targets
+                .iter()
+                .any(|t| t.starts_with("-r") || t.starts_with("-c"));
         }
     }
     deps
 }
 
+fn parse_via_targets(rest: &str) -> impl Iterator<Item = String> + '_ {
+    rest.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+}
+
 /// Parse Node.js `package.json` dependencies.
 pub fn parse_package_json(content: &str) -> Vec<Dependency> {
     let mut deps = Vec::new();
@@ -63,6 +115,8 @@ pub fn parse_package_json(content: &str) -> Vec<Dependency> {
                         name: name.clone(),
                         version: version.to_string(),
                         ecosystem: "npm".to_string(),
+                        direct: true,
+                        pinned: version == version_str.trim(),
                     });
                 }
             }
@@ -71,6 +125,207 @@ pub fn parse_package_json(content: &str) -> Vec<Dependency> {
     deps
 }
 
+/// Parse npm `package-lock.json` (lockfileVersion 1–3) into every installed package.
+///
+/// With v2/v3 lockfiles a package is direct when it is hoisted to the top level and
+/// listed by the root package; v1 lockfiles carry no such info, so all entries are
+/// transitive until matched against `package.json`.
+pub fn parse_package_lock(content: &str) -> Vec<Dependency> {
+    let parsed: serde_json::Value = match serde_json::from_str(content) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let mut deps = Vec::new();
+    let mut seen = HashSet::new();
+
+    if let Some(packages) = parsed.get("packages").and_then(|v| v.as_object()) {
+        let root_names: HashSet<&str> = packages
+            .get("")
+            .map(|root| {
+                ["dependencies", "devDependencies", "optionalDependencies"]
+                    .iter()
+                    .filter_map(|s| root.get(*s).and_then(|v| v.as_object()))
+                    .flat_map(|obj| obj.keys().map(String::as_str))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (key, pkg) in packages {
+            let Some(idx) = key.rfind("node_modules/") else {
+                continue;
+            };
+            if pkg.get("link").and_then(|v| v.as_bool()) == Some(true) {
+                continue;
+            }
+            let name = &key[idx + "node_modules/".len()..];
+            let Some(version) = pkg.get("version").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let direct = idx == 0 && root_names.contains(name);
+            if seen.insert((name.to_string(), version.to_string())) {
+                deps.push(Dependency {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    ecosystem: "npm".to_string(),
+                    direct,
+                    pinned: true,
+                });
+            }
+        }
+        return deps;
+    }
+
+    if let Some(obj) = parsed.get("dependencies").and_then(|v| v.as_object()) {
+        walk_v1(obj, &mut deps, &mut seen);
+    }
+    deps
+}
+
+/// Recurse through a v1 lockfile `dependencies` tree.
+fn walk_v1(
+    obj: &serde_json::Map<String, serde_json::Value>,
+    deps: &mut Vec<Dependency>,
+    seen: &mut HashSet<(String, String)>,
+) {
+    for (name, pkg) in obj {
+        if let Some(version) = pkg.get("version").and_then(|v| v.as_str()) {
+            if seen.insert((name.clone(), version.to_string())) {
+                deps.push(Dependency {
+                    name: name.clone(),
+                    version: version.to_string(),
+                    ecosystem: "npm".to_string(),
+                    direct: false,
+                    pinned: true,
+                });
+            }
+        }
+        if let Some(nested) = pkg.get("dependencies").and_then(|v| v.as_object()) {
+            walk_v1(nested, deps, seen);
+        }
+    }
+}
+
+/// Parse `poetry.lock` `[[package]]` tables. All entries are returned as transitive;
+/// direct ones are identified via `pyproject.toml` by the caller.
+pub fn parse_poetry_lock(content: &str) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut in_package = false;
+    let mut name: Option<String> = None;
+    let mut version: Option<String> = None;
+
+    let mut flush = |name: &mut Option<String>, version: &mut Option<String>| {
+        if let (Some(n), Some(v)) = (name.take(), version.take()) {
+            push_if_valid(&mut deps, &n, &v, "PyPI", true);
+            if let Some(dep) = deps.last_mut() {
+                dep.direct = false;
+            }
+        }
+    };
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            flush(&mut name, &mut version);
+            in_package = line == "[[package]]";
+            continue;
+        }
+        if !in_package {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"').to_string();
+            match key.trim() {
+                "name" => name = Some(value),
+                "version" => version = Some(value),
+                _ => {}
+            }
+        }
+    }
+    flush(&mut name, &mut version);
+    deps
+}
+
+/// Names declared in `pyproject.toml` (`[project] dependencies` and
+/// `[tool.poetry.*dependencies]`), PEP 503-normalized.
+pub fn parse_pyproject_dependency_names(content: &str) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut section = String::new();
+    let mut in_project_array = false;
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        if in_project_array {
+            names.extend(quoted_requirement_names(line));
+            if line.contains(']') {
+                in_project_array = false;
+            }
+            continue;
+        }
+        if line.starts_with('[') {
+            section = line.trim_matches(['[', ']']).trim().to_string();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().trim_matches('"');
+        if section == "project" && key == "dependencies" {
+            names.extend(quoted_requirement_names(value));
+            in_project_array = !value.contains(']');
+        } else if section.starts_with("tool.poetry")
+            && section.ends_with("dependencies")
+            && key != "python"
+        {
+            names.insert(normalize_pypi_name(key));
+        }
+    }
+    names
+}
+
+fn quoted_requirement_names(s: &str) -> Vec<String> {
+    s.split('"')
+        .skip(1)
+        .step_by(2)
+        .filter_map(|req| {
+            let end = req
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+                .unwrap_or(req.len());
+            let name = &req[..end];
+            (!name.is_empty()).then(|| normalize_pypi_name(name))
+        })
+        .collect()
+}
+
+/// PEP 503 name normalization.
+pub(super) fn normalize_pypi_name(name: &str) -> String {
+    name.trim().to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Parse `skill.lock` (see [`crate::env::lockfile`]) into every locked package, all
+/// transitive until matched against the manifests by the caller.
+pub fn parse_skill_lock(content: &str) -> Vec<Dependency> {
+    let Ok(lock) = serde_json::from_str::<SkillLock>(content) else {
+        return Vec::new();
+    };
+    let ecosystem = match lock.language.as_str() {
+        "python" => "PyPI",
+        "node" => "npm",
+        _ => return Vec::new(),
+    };
+    lock.packages
+        .into_iter()
+        .map(|p| Dependency {
+            name: p.name,
+            version: p.version,
+            ecosystem: ecosystem.to_string(),
+            direct: false,
+            pinned: true,
+        })
+        .collect()
+}
+
 /// Parse `.skilllite.lock` JSON for resolved packages.
 pub(super) fn parse_lock_file(lock_path: &Path) -> Option<Vec<Dependency>> {
     let content = std::fs::read_to_string(lock_path).ok()?;
@@ -101,6 +356,7 @@ pub(super) fn push_if_valid(
     name: &str,
     version: &str,
     ecosystem: &str,
+    pinned: bool,
 ) {
     let name = name.trim();
     let version = version.trim();
@@ -109,6 +365,8 @@ pub(super) fn push_if_valid(
             name: name.to_string(),
             version: version.to_string(),
             ecosystem: ecosystem.to_string(),
+            direct: true,
+            pinned,
         });
     }
 }
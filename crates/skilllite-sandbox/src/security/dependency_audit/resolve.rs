@@ -37,12 +37,16 @@ pub(super) fn resolve_from_metadata_fields(
                         name: name.trim().to_string(),
                         version: ver.trim().to_string(),
                         ecosystem: ecosystem.to_string(),
+                        direct: true,
+                        pinned: true,
                     }
                 } else {
                     Dependency {
                         name: pkg.trim().to_string(),
                         version: String::new(),
                         ecosystem: ecosystem.to_string(),
+                        direct: true,
+                        pinned: false,
                     }
                 }
             })
@@ -65,6 +69,8 @@ pub(super) fn resolve_from_metadata_fields(
                     name,
                     version: String::new(),
                     ecosystem: ecosystem.to_string(),
+                    direct: true,
+                    pinned: false,
                 })
                 .collect();
         }
@@ -1,9 +1,14 @@
 //! Dependency audit tests.
 
-use super::audit::build_result;
+use super::audit::{audit_skill_dependencies_with_options, build_result, collect_dependencies};
+use super::cache::AuditCache;
 use super::config::get_custom_api;
-use super::parsers::{parse_package_json, parse_requirements_txt};
-use super::types::{AuditBackend, PackageAuditEntry, VulnRef};
+use super::format::format_audit_result_json;
+use super::parsers::{
+    parse_package_json, parse_package_lock, parse_poetry_lock, parse_pyproject_dependency_names,
+    parse_requirements_txt,
+};
+use super::types::{AuditBackend, AuditOptions, Dependency, PackageAuditEntry, VulnRef};
 
 #[test]
 fn test_parse_requirements_txt_exact() {
@@ -25,6 +30,8 @@ fn test_parse_requirements_txt_operators() {
     assert_eq!(deps[0].version, "2.25.0");
     assert_eq!(deps[1].version, "2.0");
     assert_eq!(deps[2].version, "2.0");
+    assert!(deps.iter().all(|d| !d.pinned));
+    assert!(parse_requirements_txt("requests==2.31.0\n")[0].pinned);
 }
 
 #[test]
@@ -66,6 +73,8 @@ fn test_parse_package_json() {
     assert!(deps
         .iter()
         .any(|d| d.name == "jest" && d.version == "29.0.0"));
+    assert!(deps.iter().all(|d| !d.pinned));
+    assert!(parse_package_json(r#"{"dependencies": {"valid": "1.0.0"}}"#)[0].pinned);
 }
 
 #[test]
@@ -94,6 +103,7 @@ fn test_build_result_counts() {
             name: "a".into(),
             version: "1.0".into(),
             ecosystem: "PyPI".into(),
+            direct: true,
             vulns: vec![VulnRef {
                 id: "V-1".into(),
                 summary: "test".into(),
@@ -104,6 +114,7 @@ fn test_build_result_counts() {
             name: "b".into(),
             version: "2.0".into(),
             ecosystem: "npm".into(),
+            direct: true,
             vulns: vec![],
         },
     ];
@@ -113,3 +124,272 @@ fn test_build_result_counts() {
     assert_eq!(result.total_vulns, 1);
     assert!(result.malicious.is_empty());
 }
+
+#[test]
+fn test_parse_requirements_txt_pip_compile_annotations() {
+    let content = "\
+certifi==2024.2.2
+    # via requests
+idna==3.7
+    # via
+    #   anyio
+    #   requests
+requests==2.31.0 \\
+    --hash=sha256:abc
+    # via -r requirements.in
+urllib3==2.2.1 ; python_version >= \"3.8\"  # via requests
+";
+    let deps = parse_requirements_txt(content);
+    let by_name = |n: &str| deps.iter().find(|d| d.name == n).unwrap();
+    assert_eq!(deps.len(), 4);
+    assert!(!by_name("certifi").direct);
+    assert!(!by_name("idna").direct);
+    assert!(by_name("requests").direct);
+    assert_eq!(by_name("requests").version, "2.31.0");
+    assert!(!by_name("urllib3").direct);
+    assert_eq!(by_name("urllib3").version, "2.2.1");
+}
+
+#[test]
+fn test_parse_package_lock_v3() {
+    let content = r#"{
+        "lockfileVersion": 3,
+        "packages": {
+            "": { "dependencies": { "express": "^4.18.2" } },
+            "node_modules/express": { "version": "4.19.2" },
+            "node_modules/qs": { "version": "6.11.0" },
+            "node_modules/express/node_modules/qs": { "version": "6.10.0" },
+            "node_modules/@types/node": { "version": "20.1.0" },
+            "node_modules/local": { "link": true }
+        }
+    }"#;
+    let deps = parse_package_lock(content);
+    assert_eq!(deps.len(), 4);
+    assert!(deps
+        .iter()
+        .any(|d| d.name == "express" && d.version == "4.19.2" && d.direct));
+    assert!(deps
+        .iter()
+        .any(|d| d.name == "qs" && d.version == "6.10.0" && !d.direct));
+    assert!(deps.iter().any(|d| d.name == "@types/node" && !d.direct));
+}
+
+#[test]
+fn test_parse_package_lock_v1() {
+    let content = r#"{
+        "lockfileVersion": 1,
+        "dependencies": {
+            "a": { "version": "1.0.0", "dependencies": { "b": { "version": "2.0.0" } } }
+        }
+    }"#;
+    let deps = parse_package_lock(content);
+    assert_eq!(deps.len(), 2);
+    assert!(deps.iter().all(|d| !d.direct && d.ecosystem == "npm"));
+}
+
+#[test]
+fn test_parse_poetry_lock_and_pyproject() {
+    let lock = r#"
+[[package]]
+name = "requests"
+version = "2.31.0"
+files = [
+    {file = "requests-2.31.0.tar.gz", hash = "sha256:abc"},
+]
+
+[package.dependencies]
+urllib3 = ">=1.21.1,<3"
+
+[[package]]
+name = "urllib3"
+version = "2.2.1"
+
+[metadata]
+lock-version = "2.0"
+"#;
+    let deps = parse_poetry_lock(lock);
+    assert_eq!(deps.len(), 2);
+    assert_eq!(deps[0].name, "requests");
+    assert_eq!(deps[1].version, "2.2.1");
+    assert!(deps.iter().all(|d| !d.direct));
+
+    let pyproject = r#"
+[project]
+dependencies = [
+    "Beautiful_Soup4>=4.12",
+]
+
+[tool.poetry.dependencies]
+python = "^3.10"
+requests = "^2.31"
+"#;
+    let names = parse_pyproject_dependency_names(pyproject);
+    assert!(names.contains("requests"));
+    assert!(names.contains("beautiful-soup4"));
+    assert!(!names.contains("python"));
+}
+
+#[test]
+fn test_collect_dependencies_lockfile_marks_transitive() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("package.json"),
+        r#"{"dependencies": {"express": "^4.18.2"}}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("package-lock.json"),
+        r#"{"lockfileVersion": 1, "dependencies": {
+            "express": {"version": "4.19.2"},
+            "qs": {"version": "6.11.0"}
+        }}"#,
+    )
+    .unwrap();
+    let deps = collect_dependencies(dir.path(), None);
+    assert_eq!(deps.len(), 2);
+    // Lockfile version supersedes the manifest range; package.json marks it direct
+    assert!(deps
+        .iter()
+        .any(|d| d.name == "express" && d.version == "4.19.2" && d.direct));
+    assert!(deps.iter().any(|d| d.name == "qs" && !d.direct));
+}
+
+#[test]
+fn test_collect_dependencies_prefers_skill_lock_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("requirements.txt"), "requests>=2.0\n").unwrap();
+    let lock = crate::env::lockfile::SkillLock::new(
+        "python",
+        vec!["requests>=2.0".to_string()],
+        ["requests:2.31.0", "urllib3:2.2.1"]
+            .iter()
+            .map(|p| {
+                let (name, version) = p.split_once(':').unwrap();
+                crate::env::lockfile::LockedPackage {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    integrity: "sha256:00".to_string(),
+                    path: None,
+                    npm_entry: None,
+                }
+            })
+            .collect(),
+    );
+    crate::env::lockfile::write(dir.path(), &lock).unwrap();
+
+    let deps = collect_dependencies(dir.path(), None);
+    assert_eq!(deps.len(), 2);
+    assert!(deps
+        .iter()
+        .any(|d| d.name == "requests" && d.version == "2.31.0" && d.direct));
+    assert!(deps
+        .iter()
+        .any(|d| d.name == "urllib3" && d.version == "2.2.1" && !d.direct));
+}
+
+fn dep(name: &str, version: &str) -> Dependency {
+    Dependency {
+        name: name.into(),
+        version: version.into(),
+        ecosystem: "PyPI".into(),
+        direct: true,
+        pinned: true,
+    }
+}
+
+#[test]
+fn test_cache_roundtrip_and_ttl() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = AuditCache::new(dir.path().to_path_buf(), 3600);
+    let d = dep("Foo_Bar", "1.0");
+    assert!(cache.get(&d, false).is_none());
+    cache.put(&PackageAuditEntry {
+        name: d.name.clone(),
+        version: d.version.clone(),
+        ecosystem: d.ecosystem.clone(),
+        direct: true,
+        vulns: vec![VulnRef {
+            id: "PYSEC-1".into(),
+            summary: String::new(),
+            fixed_in: vec![],
+        }],
+    });
+    // PEP 503 normalized lookup
+    let hit = cache.get(&dep("foo-bar", "1.0"), false).unwrap();
+    assert_eq!(hit.vulns[0].id, "PYSEC-1");
+    assert!(cache.get(&dep("foo-bar", "2.0"), false).is_none());
+    assert!(cache.get(&dep("foo-bar", ""), true).is_none());
+
+    let expired = AuditCache::new(dir.path().to_path_buf(), 0);
+    assert!(expired.get(&d, false).is_none());
+    assert!(expired.get(&d, true).is_some());
+}
+
+#[test]
+fn test_offline_audit_uses_cache_and_reports_unverified() {
+    let skill = tempfile::tempdir().unwrap();
+    let cache_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        skill.path().join("requirements.txt"),
+        "requests==2.31.0\nurllib3==2.2.1\n    # via requests\n",
+    )
+    .unwrap();
+    AuditCache::new(cache_dir.path().to_path_buf(), 3600).put(&PackageAuditEntry {
+        name: "urllib3".into(),
+        version: "2.2.1".into(),
+        ecosystem: "PyPI".into(),
+        direct: false,
+        vulns: vec![VulnRef {
+            id: "GHSA-x".into(),
+            summary: String::new(),
+            fixed_in: vec![],
+        }],
+    });
+
+    let options = AuditOptions {
+        offline: true,
+        cache_dir: Some(cache_dir.path().to_path_buf()),
+    };
+    let result = audit_skill_dependencies_with_options(skill.path(), None, &options).unwrap();
+    assert!(result.offline);
+    assert!(!result.is_complete());
+    assert_eq!(result.from_cache, 1);
+    assert_eq!(result.vulnerable_count, 1);
+    assert_eq!(result.unverified.len(), 1);
+    assert_eq!(result.unverified[0].name, "requests");
+
+    let json: serde_json::Value = serde_json::from_str(&format_audit_result_json(&result)).unwrap();
+    assert_eq!(json["complete"], false);
+    assert_eq!(json["direct_findings"].as_array().unwrap().len(), 0);
+    assert_eq!(json["transitive_findings"][0]["name"], "urllib3");
+}
+
+#[test]
+fn test_unreachable_backend_reports_unverified() {
+    let skill = tempfile::tempdir().unwrap();
+    let cache_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        skill.path().join("package.json"),
+        r#"{"dependencies": {"left-pad": "1.3.0"}}"#,
+    )
+    .unwrap();
+    let key = skilllite_core::config::env_keys::misc::OSV_API_URL;
+    let saved = std::env::var(key).ok();
+    // Nothing listens on the discard port
+    std::env::set_var(key, "http://127.0.0.1:9");
+    let options = AuditOptions {
+        offline: false,
+        cache_dir: Some(cache_dir.path().to_path_buf()),
+    };
+    let result = audit_skill_dependencies_with_options(skill.path(), None, &options);
+    match saved {
+        Some(v) => std::env::set_var(key, v),
+        None => std::env::remove_var(key),
+    }
+
+    let result = result.expect("network failure is not a hard error");
+    assert!(!result.is_complete());
+    assert_eq!(result.unverified.len(), 1);
+    assert_eq!(result.unverified[0].name, "left-pad");
+    assert_eq!(result.scanned, 0);
+}
@@ -11,6 +11,13 @@ pub struct Dependency {
     pub version: String,
     /// Ecosystem identifier: "PyPI" or "npm".
     pub ecosystem: String,
+    /// Declared in a manifest (`requirements.txt`, `package.json`, `pyproject.toml`);
+    /// `false` for packages only pulled in through a lockfile.
+    pub direct: bool,
+    /// `version` is exact (`==`, a bare version, or a lockfile entry) rather than the bound of
+    /// a range; only exact versions are cached.
+    #[serde(skip)]
+    pub pinned: bool,
 }
 
 /// Vulnerability reference.
@@ -29,6 +36,8 @@ pub struct PackageAuditEntry {
     pub name: String,
     pub version: String,
     pub ecosystem: String,
+    /// See [`Dependency::direct`].
+    pub direct: bool,
     pub vulns: Vec<VulnRef>,
 }
 
@@ -51,6 +60,28 @@ pub struct DependencyAuditResult {
     pub entries: Vec<PackageAuditEntry>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub malicious: Vec<MaliciousPackageHit>,
+    /// Packages that could not be checked (offline and not cached, or backend unreachable).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unverified: Vec<Dependency>,
+    /// Entries answered from the on-disk cache instead of a live query.
+    pub from_cache: usize,
+    pub offline: bool,
+}
+
+impl DependencyAuditResult {
+    /// `false` when some packages could not be verified.
+    pub fn is_complete(&self) -> bool {
+        self.unverified.is_empty()
+    }
+}
+
+/// Options for [`super::audit_skill_dependencies_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct AuditOptions {
+    /// Answer only from the cache; uncached packages are reported as unverified.
+    pub offline: bool,
+    /// Cache root override (defaults to `SKILLLITE_CACHE_DIR` or the platform cache dir).
+    pub cache_dir: Option<std::path::PathBuf>,
 }
 
 /// Metadata hint for dependency inference when no explicit dependency files exist.
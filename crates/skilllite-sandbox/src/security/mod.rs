@@ -17,6 +17,9 @@
 //! - **rules**: Rule definitions and configuration loading
 //! - **default_rules**: Built-in security rules for Python and JavaScript
 //! - **scanner**: The main ScriptScanner implementation
+//! - **taint**: Dataflow pass for download-then-execute chains (network source → exec sink)
+//! - **sarif**: SARIF 2.1.0 reports of scan and dependency audit findings for CI
+//! - **suppression**: Inline `skilllite-allow:` comments that justify non-critical findings
 //! - **dependency_audit**: Supply chain vulnerability scanning via OSV API
 //! - **policy**: Canonical sandbox runtime policy (paths, processes, network)
 //!
@@ -58,8 +61,11 @@ pub mod dependency_audit;
 pub mod malicious_packages;
 pub mod policy;
 pub mod rules;
+pub mod sarif;
 pub mod scanner;
 pub mod skill_precheck;
+pub mod suppression;
+mod taint;
 pub mod types;
 
 // Re-export commonly used items for public API
@@ -71,12 +77,16 @@ pub use default_rules::{
 #[allow(unused_imports)]
 pub use rules::{RulesConfig, SecurityRule, CONFIG_FILE_NAMES};
 #[allow(unused_imports)]
+pub use sarif::SarifReport;
+#[allow(unused_imports)]
 pub use scanner::{
     format_scan_result, format_scan_result_compact, format_scan_result_json, scan_shell_command,
-    ScriptScanner,
+    RuleInfo, ScriptScanner,
 };
 #[allow(unused_imports)]
-pub use types::{ScanResult, SecurityIssue, SecurityIssueType, SecuritySeverity};
+pub use suppression::{parse_suppressions, parse_suppressions_for_path, Suppression};
+#[allow(unused_imports)]
+pub use types::{ScanResult, SecurityIssue, SecurityIssueType, SecuritySeverity, SuppressedIssue};
 
 pub use skill_precheck::{
     run_skill_precheck, run_skill_precheck_for_metadata, skill_precheck_display_report,
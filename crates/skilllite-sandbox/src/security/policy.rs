@@ -291,47 +291,38 @@ pub const IOKIT_DENY_ALWAYS: &[&str] = &["iokit-open"];
 pub enum ResolvedNetworkPolicy {
     /// Network disabled - block all
     BlockAll,
-    /// Wildcard "*" in outbound - allow all without proxy
+    /// Wildcard "*" in outbound and no deny entries - allow all without proxy
     AllowAll,
-    /// Use proxy to filter by outbound domains
-    ProxyFiltered { domains: Vec<String> },
-}
-
-/// Extract domain part from an outbound rule, stripping optional `:port` suffix.
-/// e.g. "*:80" → "*", "*.github.com:443" → "*.github.com", "example.com" → "example.com"
-fn strip_port_suffix(rule: &str) -> &str {
-    if let Some(colon_pos) = rule.rfind(':') {
-        let after_colon = &rule[colon_pos + 1..];
-        if !after_colon.is_empty() && after_colon.chars().all(|c| c.is_ascii_digit()) {
-            return &rule[..colon_pos];
-        }
-    }
-    rule
+    /// Use proxy to filter by outbound entries (grammar in
+    /// `skilllite_core::skill::network_rules`); `denied` is checked first
+    ProxyFiltered {
+        domains: Vec<String>,
+        denied: Vec<String>,
+    },
 }
 
 /// Resolve network policy from metadata
-pub fn resolve_network_policy(network_enabled: bool, outbound: &[String]) -> ResolvedNetworkPolicy {
+pub fn resolve_network_policy(
+    network_enabled: bool,
+    outbound: &[String],
+    deny: &[String],
+) -> ResolvedNetworkPolicy {
     if !network_enabled {
         return ResolvedNetworkPolicy::BlockAll;
     }
-    // Only exact "*" (without port) bypasses proxy entirely (AllowAll).
-    // "*:80" / "*:443" still go through the proxy — the domain part "*" is
-    // handled by ProxyConfig::domain_matches which allows all domains, but
-    // traffic remains observable and controllable through the proxy layer.
+    // Only exact "*" (without port) bypasses proxy entirely (AllowAll), and only
+    // when nothing is denied. "*:80" / "*:443" still go through the proxy, which
+    // enforces the port while allowing every host.
     let has_wildcard = outbound.iter().any(|d| d.trim() == "*");
-    if has_wildcard {
+    if has_wildcard && deny.is_empty() {
         return ResolvedNetworkPolicy::AllowAll;
     }
     if outbound.is_empty() {
         return ResolvedNetworkPolicy::BlockAll;
     }
-    // Extract domain parts (strip :port if present) for proxy filtering.
-    // e.g. "*:80" → "*", "*.github.com:443" → "*.github.com"
     ResolvedNetworkPolicy::ProxyFiltered {
-        domains: outbound
-            .iter()
-            .map(|s| strip_port_suffix(s.trim()).to_string())
-            .collect(),
+        domains: outbound.iter().map(|s| s.trim().to_string()).collect(),
+        denied: deny.iter().map(|s| s.trim().to_string()).collect(),
     }
 }
 
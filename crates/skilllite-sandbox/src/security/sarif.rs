@@ -0,0 +1,602 @@
+//! SARIF 2.1.0 output for the static scanner and the dependency audit.
+//!
+//! CI systems (GitHub code scanning, GitLab) turn SARIF results into code annotations.
+//! [`SarifReport`] aggregates any number of scanned files into one run: every detector known
+//! to the scanner is listed under `tool.driver.rules`, each finding becomes a result with a
+//! file/line/column location, and findings silenced by `skilllite-allow:` comments are kept
+//! as results carrying an `inSource` suppression.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::scanner::RuleInfo;
+use super::types::{ScanResult, SecurityIssue, SecurityIssueType, SecuritySeverity};
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str =
+    "https://docs.oasis-open.org/sarif/sarif/v2.1.0/errata01/os/schemas/sarif-schema-2.1.0.json";
+
+const TOOL_NAME: &str = "skilllite";
+const TOOL_INFORMATION_URI: &str = "https://github.com/EXboys/skilllite";
+/// Help page for the static scanner's rules.
+const SCANNER_HELP_URI: &str =
+    "https://github.com/EXboys/skilllite/blob/main/docs/en/ARCHITECTURE.md#29-static-code-scanning-skilllite-sandboxsecurity";
+
+/// Top-level SARIF document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub version: String,
+    pub runs: Vec<SarifRun>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    pub results: Vec<SarifResult>,
+    /// Columns count Unicode code points, matching [`SecurityIssue::column`].
+    pub column_kind: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    pub name: String,
+    pub version: String,
+    pub information_uri: String,
+    pub rules: Vec<SarifRule>,
+}
+
+/// A `reportingDescriptor`: rule metadata shown next to each result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub short_description: SarifMessage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help_uri: Option<String>,
+    pub default_configuration: SarifRuleConfiguration,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifRuleConfiguration {
+    pub level: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: String,
+    pub message: SarifMessage,
+    pub locations: Vec<SarifLocation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressions: Vec<SarifSuppression>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    pub physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<SarifRegion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifArtifactLocation {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    pub start_line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<SarifMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifSuppression {
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+}
+
+/// SARIF `level` for a severity: Critical/High → error, Medium → warning, Low → note.
+pub fn sarif_level(severity: &SecuritySeverity) -> &'static str {
+    match severity {
+        SecuritySeverity::Critical | SecuritySeverity::High => "error",
+        SecuritySeverity::Medium => "warning",
+        SecuritySeverity::Low => "note",
+    }
+}
+
+/// GitHub's `security-severity` score (0–10) used to bucket results.
+fn security_severity(severity: &SecuritySeverity) -> &'static str {
+    match severity {
+        SecuritySeverity::Critical => "9.5",
+        SecuritySeverity::High => "8.0",
+        SecuritySeverity::Medium => "5.5",
+        SecuritySeverity::Low => "2.0",
+    }
+}
+
+/// Artifact URI for a scanned path: relative paths keep their form with `/` separators,
+/// absolute ones become `file://` URIs.
+pub fn artifact_uri(path: &Path) -> String {
+    let s = path
+        .to_string_lossy()
+        .replace('\\', "/")
+        .replace(' ', "%20");
+    if path.is_absolute() {
+        let s = s.trim_start_matches('/');
+        format!("file:///{}", s)
+    } else {
+        s.trim_start_matches("./").to_string()
+    }
+}
+
+/// Collects findings from one or more scans into a single SARIF run.
+#[derive(Debug, Default)]
+pub struct SarifReport {
+    rules: Vec<SarifRule>,
+    rule_index: HashMap<String, usize>,
+    results: Vec<SarifResult>,
+}
+
+impl SarifReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// List `rules` under `tool.driver.rules` (duplicates are ignored).
+    pub fn with_rules(mut self, rules: &[RuleInfo]) -> Self {
+        for rule in rules {
+            self.add_rule(scanner_rule(
+                &rule.id,
+                &rule.description,
+                &rule.severity,
+                &rule.issue_type,
+            ));
+        }
+        self
+    }
+
+    /// Add the findings of one scanned file; `path` is reported as given (see [`artifact_uri`]).
+    pub fn add_scan_result(&mut self, path: &Path, result: &ScanResult) {
+        let uri = artifact_uri(path);
+        for issue in &result.issues {
+            self.add_issue(&uri, issue, None);
+        }
+        for suppressed in &result.suppressed {
+            self.add_issue(&uri, &suppressed.issue, Some(&suppressed.reason));
+        }
+    }
+
+    pub fn result_count(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn into_log(self) -> SarifLog {
+        SarifLog {
+            schema: SARIF_SCHEMA.to_string(),
+            version: SARIF_VERSION.to_string(),
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: TOOL_NAME.to_string(),
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                        information_uri: TOOL_INFORMATION_URI.to_string(),
+                        rules: self.rules,
+                    },
+                },
+                results: self.results,
+                column_kind: "unicodeCodePoints".to_string(),
+            }],
+        }
+    }
+
+    /// Pretty-printed SARIF JSON.
+    pub fn to_json(self) -> String {
+        serde_json::to_string_pretty(&self.into_log()).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    fn add_rule(&mut self, rule: SarifRule) -> usize {
+        if let Some(&i) = self.rule_index.get(&rule.id) {
+            return i;
+        }
+        let i = self.rules.len();
+        self.rule_index.insert(rule.id.clone(), i);
+        self.rules.push(rule);
+        i
+    }
+
+    fn add_issue(&mut self, uri: &str, issue: &SecurityIssue, suppressed_reason: Option<&str>) {
+        // Findings from rules outside the catalog (custom callers) get a descriptor of their own.
+        let rule_index = self.add_rule(scanner_rule(
+            &issue.rule_id,
+            &issue.description,
+            &issue.severity,
+            &issue.issue_type,
+        ));
+        let mut message = issue.description.clone();
+        if let Some(source_line) = issue.source_line {
+            message.push_str(&format!(" (source line {})", source_line));
+        }
+        let region = (issue.line_number > 0).then(|| SarifRegion {
+            start_line: issue.line_number,
+            start_column: issue.column,
+            snippet: (!issue.code_snippet.is_empty()).then(|| SarifMessage {
+                text: issue.code_snippet.clone(),
+            }),
+        });
+        self.results.push(SarifResult {
+            rule_id: issue.rule_id.clone(),
+            rule_index,
+            level: sarif_level(&issue.severity).to_string(),
+            message: SarifMessage { text: message },
+            locations: vec![location(uri, region)],
+            suppressions: suppressed_reason
+                .map(|reason| SarifSuppression {
+                    kind: "inSource".to_string(),
+                    justification: Some(reason.to_string()),
+                })
+                .into_iter()
+                .collect(),
+        });
+    }
+}
+
+fn location(uri: &str, region: Option<SarifRegion>) -> SarifLocation {
+    SarifLocation {
+        physical_location: SarifPhysicalLocation {
+            artifact_location: SarifArtifactLocation {
+                uri: uri.to_string(),
+            },
+            region,
+        },
+    }
+}
+
+fn scanner_rule(
+    id: &str,
+    description: &str,
+    severity: &SecuritySeverity,
+    issue_type: &SecurityIssueType,
+) -> SarifRule {
+    SarifRule {
+        id: id.to_string(),
+        name: Some(issue_type.to_string()),
+        short_description: SarifMessage {
+            text: description.to_string(),
+        },
+        help_uri: Some(SCANNER_HELP_URI.to_string()),
+        default_configuration: SarifRuleConfiguration {
+            level: sarif_level(severity).to_string(),
+        },
+        properties: Some(serde_json::json!({
+            "tags": ["security"],
+            "security-severity": security_severity(severity),
+        })),
+    }
+}
+
+#[cfg(feature = "audit")]
+mod audit {
+    use super::*;
+    use crate::security::dependency_audit::DependencyAuditResult;
+
+    /// Files searched, in order, for the line declaring a vulnerable package.
+    const MANIFESTS: &[&str] = &[
+        "requirements.txt",
+        "package.json",
+        "pyproject.toml",
+        "package-lock.json",
+        "poetry.lock",
+        crate::env::lockfile::SKILL_LOCK_FILE,
+        ".skilllite.lock",
+    ];
+
+    impl SarifReport {
+        /// Add dependency audit findings for `skill_dir`: one result per known vulnerability
+        /// (rule = advisory id) and per malicious package, located at the manifest line that
+        /// names the package.
+        pub fn add_dependency_audit(&mut self, skill_dir: &Path, result: &DependencyAuditResult) {
+            for entry in &result.entries {
+                for vuln in &entry.vulns {
+                    let summary = if vuln.summary.is_empty() {
+                        vuln.id.clone()
+                    } else {
+                        vuln.summary.clone()
+                    };
+                    let rule_index = self.add_rule(SarifRule {
+                        id: vuln.id.clone(),
+                        name: None,
+                        short_description: SarifMessage {
+                            text: summary.clone(),
+                        },
+                        help_uri: Some(format!("https://osv.dev/vulnerability/{}", vuln.id)),
+                        default_configuration: SarifRuleConfiguration {
+                            level: "error".to_string(),
+                        },
+                        properties: Some(serde_json::json!({
+                            "tags": ["security", "dependency"],
+                        })),
+                    });
+                    let mut message = format!(
+                        "{} {} ({}, {}) is affected by {}: {}",
+                        entry.name,
+                        entry.version,
+                        entry.ecosystem,
+                        if entry.direct { "direct" } else { "transitive" },
+                        vuln.id,
+                        summary
+                    );
+                    if !vuln.fixed_in.is_empty() {
+                        message.push_str(&format!(". Fixed in: {}", vuln.fixed_in.join(", ")));
+                    }
+                    self.results.push(SarifResult {
+                        rule_id: vuln.id.clone(),
+                        rule_index,
+                        level: "error".to_string(),
+                        message: SarifMessage { text: message },
+                        locations: vec![locate_package(skill_dir, &entry.name)],
+                        suppressions: Vec::new(),
+                    });
+                }
+            }
+            for hit in &result.malicious {
+                let rule_index = self.add_rule(scanner_rule(
+                    "malicious-package",
+                    "Package matches the offline malicious-package library",
+                    &SecuritySeverity::Critical,
+                    &SecurityIssueType::MaliciousPackage,
+                ));
+                self.results.push(SarifResult {
+                    rule_id: "malicious-package".to_string(),
+                    rule_index,
+                    level: "error".to_string(),
+                    message: SarifMessage {
+                        text: format!(
+                            "{} ({}) is a known malicious package: {}",
+                            hit.name, hit.ecosystem, hit.reason
+                        ),
+                    },
+                    locations: vec![locate_package(skill_dir, &hit.name)],
+                    suppressions: Vec::new(),
+                });
+            }
+        }
+    }
+
+    /// First manifest line mentioning `name`; falls back to `SKILL.md`, then the directory.
+    fn locate_package(skill_dir: &Path, name: &str) -> SarifLocation {
+        let needle = name.to_lowercase();
+        for manifest in MANIFESTS {
+            let path = skill_dir.join(manifest);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            for (idx, line) in content.lines().enumerate() {
+                let lower = line.to_lowercase();
+                if let Some(pos) = lower.find(&needle) {
+                    let column = lower[..pos].chars().count() + 1;
+                    return location(
+                        &artifact_uri(&path),
+                        Some(SarifRegion {
+                            start_line: idx + 1,
+                            start_column: Some(column),
+                            snippet: Some(SarifMessage {
+                                text: line.trim().to_string(),
+                            }),
+                        }),
+                    );
+                }
+            }
+        }
+        let skill_md = skill_dir.join("SKILL.md");
+        if skill_md.is_file() {
+            return location(
+                &artifact_uri(&skill_md),
+                Some(SarifRegion {
+                    start_line: 1,
+                    start_column: None,
+                    snippet: None,
+                }),
+            );
+        }
+        location(&artifact_uri(skill_dir), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::scanner::ScriptScanner;
+
+    fn scan(content: &str, name: &str) -> ScanResult {
+        ScriptScanner::new()
+            .scan_content(content, Path::new(name))
+            .unwrap()
+    }
+
+    #[test]
+    fn aggregates_files_into_one_run_with_rule_metadata() {
+        let scanner = ScriptScanner::new();
+        let mut report = SarifReport::new().with_rules(&scanner.rule_catalog());
+        report.add_scan_result(
+            Path::new("skill/scripts/a.py"),
+            &scan("import os\n    eval(user_input)\n", "a.py"),
+        );
+        report.add_scan_result(
+            Path::new("./skill/scripts/b.py"),
+            &scan(
+                "subprocess.run([\"ls\"])  # skilllite-allow: process-exec reason=\"lists files\"\n",
+                "b.py",
+            ),
+        );
+
+        let json = report.to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], "2.1.0");
+        assert!(value["$schema"]
+            .as_str()
+            .unwrap()
+            .contains("sarif-schema-2.1.0"));
+        let runs = value["runs"].as_array().unwrap();
+        assert_eq!(runs.len(), 1);
+        let driver = &runs[0]["tool"]["driver"];
+        assert_eq!(driver["name"], "skilllite");
+        let rules = driver["rules"].as_array().unwrap();
+        assert!(rules.iter().any(|r| r["id"] == "entropy-obfuscation"));
+        for rule in rules {
+            assert!(rule["shortDescription"]["text"].is_string());
+            assert!(rule["helpUri"].is_string());
+            assert!(rule["defaultConfiguration"]["level"].is_string());
+        }
+
+        let results = runs[0]["results"].as_array().unwrap();
+        let eval = results
+            .iter()
+            .find(|r| {
+                let location = &r["locations"][0]["physicalLocation"];
+                location["artifactLocation"]["uri"] == "skill/scripts/a.py"
+                    && location["region"]["startLine"] == 2
+            })
+            .expect("eval result for a.py");
+        let region = &eval["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 2);
+        assert_eq!(region["startColumn"], 5);
+        assert_eq!(eval["level"], "error");
+        let index = eval["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(rules[index]["id"], eval["ruleId"]);
+
+        let suppressed = results
+            .iter()
+            .find(|r| {
+                r["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+                    == "skill/scripts/b.py"
+            })
+            .expect("result for b.py");
+        assert_eq!(suppressed["suppressions"][0]["kind"], "inSource");
+        assert_eq!(
+            suppressed["suppressions"][0]["justification"],
+            "lists files"
+        );
+    }
+
+    #[test]
+    fn result_round_trips_through_serde() {
+        let mut report = SarifReport::new();
+        report.add_scan_result(Path::new("x.py"), &scan("eval(data)\n", "x.py"));
+        assert!(report.result_count() > 0);
+        let log = report.into_log();
+
+        let json = serde_json::to_string(&log).unwrap();
+        let parsed: SarifLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.version, SARIF_VERSION);
+        let original = &log.runs[0].results[0];
+        let round_tripped = &parsed.runs[0].results[0];
+        assert_eq!(round_tripped.rule_id, original.rule_id);
+        assert_eq!(round_tripped.message, original.message);
+        assert_eq!(
+            round_tripped.locations[0]
+                .physical_location
+                .region
+                .as_ref()
+                .map(|r| r.start_line),
+            Some(1)
+        );
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[cfg(feature = "audit")]
+    #[test]
+    fn dependency_vulns_point_at_the_manifest_line() {
+        use crate::security::dependency_audit::{
+            AuditBackend, DependencyAuditResult, PackageAuditEntry, VulnRef,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("requirements.txt"),
+            "# pinned\nflask==2.0.0\nRequests==2.19.0\n",
+        )
+        .unwrap();
+        let result = DependencyAuditResult {
+            scanned: 2,
+            vulnerable_count: 1,
+            total_vulns: 1,
+            backend: AuditBackend::Native,
+            entries: vec![PackageAuditEntry {
+                name: "requests".to_string(),
+                version: "2.19.0".to_string(),
+                ecosystem: "PyPI".to_string(),
+                direct: true,
+                vulns: vec![VulnRef {
+                    id: "PYSEC-2018-28".to_string(),
+                    summary: "Credentials leak on redirect".to_string(),
+                    fixed_in: vec!["2.20.0".to_string()],
+                }],
+            }],
+            malicious: Vec::new(),
+            unverified: Vec::new(),
+            from_cache: 0,
+            offline: false,
+        };
+
+        let mut report = SarifReport::new();
+        report.add_dependency_audit(dir.path(), &result);
+        let log = report.into_log();
+        let run = &log.runs[0];
+        assert_eq!(run.tool.driver.rules[0].id, "PYSEC-2018-28");
+        assert_eq!(
+            run.tool.driver.rules[0].help_uri.as_deref(),
+            Some("https://osv.dev/vulnerability/PYSEC-2018-28")
+        );
+        let location = &run.results[0].locations[0].physical_location;
+        assert!(location
+            .artifact_location
+            .uri
+            .ends_with("/requirements.txt"));
+        let region = location.region.as_ref().unwrap();
+        assert_eq!((region.start_line, region.start_column), (3, Some(1)));
+        assert!(run.results[0].message.text.contains("Fixed in: 2.20.0"));
+    }
+
+    #[test]
+    fn severities_map_to_sarif_levels() {
+        assert_eq!(sarif_level(&SecuritySeverity::Critical), "error");
+        assert_eq!(sarif_level(&SecuritySeverity::High), "error");
+        assert_eq!(sarif_level(&SecuritySeverity::Medium), "warning");
+        assert_eq!(sarif_level(&SecuritySeverity::Low), "note");
+        assert_eq!(artifact_uri(Path::new("./a b/c.py")), "a%20b/c.py");
+    }
+}
@@ -7,6 +7,8 @@
 
 use super::default_rules::get_default_rules;
 use super::rules::{RulesConfig, SecurityRule};
+use super::suppression::{apply_suppressions, parse_suppressions};
+use super::taint::scan_taint_flow;
 use super::types::{ScanResult, SecurityIssue, SecurityIssueType, SecuritySeverity};
 use anyhow::Context;
 
@@ -88,6 +90,56 @@ static DECODE_RE_SH: LazyLock<Regex> = LazyLock::new(|| {
         .expect("DECODE_RE_SH is valid")
 });
 
+/// Detectors built into the scanner besides the regex rules: (id, severity, type, description).
+/// Severity is the usual one; some findings escalate (e.g. a decoded literal that is itself dangerous).
+const HEURISTIC_DETECTORS: &[(&str, SecuritySeverity, SecurityIssueType, &str)] = &[
+    (
+        "entropy-obfuscation",
+        SecuritySeverity::Medium,
+        SecurityIssueType::ObfuscatedCode,
+        "High-entropy line — possible obfuscated or encoded payload",
+    ),
+    (
+        "base64-encoded-payload",
+        SecuritySeverity::High,
+        SecurityIssueType::EncodedPayload,
+        "Base64 decode call with an embedded literal — possible encoded payload",
+    ),
+    (
+        "base64-decode-call",
+        SecuritySeverity::Medium,
+        SecurityIssueType::EncodedPayload,
+        "Base64/codec decode call — verify the decoded content is safe",
+    ),
+    (
+        "base64-literal",
+        SecuritySeverity::Medium,
+        SecurityIssueType::EncodedPayload,
+        "Long base64-encoded string literal — possible encoded payload",
+    ),
+    (
+        "multistage-payload",
+        SecuritySeverity::High,
+        SecurityIssueType::MultiStagePayload,
+        "Download, decode and execute stages in one file — possible multi-stage payload",
+    ),
+    (
+        "taint-download-exec",
+        SecuritySeverity::Critical,
+        SecurityIssueType::TaintedExecution,
+        "Network-downloaded data flows into code execution or an autostart file",
+    ),
+];
+
+/// One detector of a [`ScriptScanner`], for reports that list their rules (e.g. SARIF).
+#[derive(Debug, Clone)]
+pub struct RuleInfo {
+    pub id: String,
+    pub severity: SecuritySeverity,
+    pub issue_type: SecurityIssueType,
+    pub description: String,
+}
+
 /// Script scanner for detecting security issues
 pub struct ScriptScanner {
     /// Whether to allow network operations
@@ -185,6 +237,35 @@ impl ScriptScanner {
         self
     }
 
+    /// Every detector this scanner runs: enabled regex rules, then the built-in heuristics.
+    pub fn rule_catalog(&self) -> Vec<RuleInfo> {
+        let rules = self
+            .rules
+            .iter()
+            .map(|(rule, _)| rule)
+            .filter(|rule| !self.disabled_rules.contains(&rule.id))
+            .map(|rule| RuleInfo {
+                id: rule.id.clone(),
+                severity: rule.severity.clone(),
+                issue_type: rule.issue_type.clone(),
+                description: rule.description.clone(),
+            });
+        let heuristics =
+            HEURISTIC_DETECTORS
+                .iter()
+                .map(|(id, severity, issue_type, description)| RuleInfo {
+                    id: id.to_string(),
+                    severity: severity.clone(),
+                    issue_type: issue_type.clone(),
+                    description: description.to_string(),
+                });
+        let mut seen = std::collections::HashSet::new();
+        rules
+            .chain(heuristics)
+            .filter(|r| seen.insert(r.id.clone()))
+            .collect()
+    }
+
     /// Scan a script file for security issues
     pub fn scan_file(&self, script_path: &Path) -> Result<ScanResult> {
         let content = fs::read_to_string(script_path)
@@ -202,12 +283,20 @@ impl ScriptScanner {
         self.scan_entropy(content, &language, &mut issues);
         self.scan_base64(content, &language, &mut issues);
         self.scan_multistage_payload(content, &language, &mut issues);
+        issues.extend(scan_taint_flow(content, &language));
+
+        let (issues, suppressed) =
+            apply_suppressions(issues, &parse_suppressions(content, &language));
 
         let is_safe = issues
             .iter()
             .all(|issue| matches!(issue.severity, SecuritySeverity::Low));
 
-        Ok(ScanResult { is_safe, issues })
+        Ok(ScanResult {
+            is_safe,
+            issues,
+            suppressed,
+        })
     }
 
     /// Scan content using the configured rules
@@ -232,7 +321,7 @@ impl ScriptScanner {
                     continue;
                 }
 
-                if regex.is_match(line) {
+                if let Some(m) = regex.find(line) {
                     // Check if this should be allowed based on scanner settings
                     let should_report = match rule.issue_type {
                         SecurityIssueType::NetworkRequest => !self.allow_network,
@@ -247,8 +336,10 @@ impl ScriptScanner {
                             severity: rule.severity.clone(),
                             issue_type: rule.issue_type.clone(),
                             line_number: line_idx + 1,
+                            column: Some(match_column(line, m.start(), m.as_str())),
                             description: rule.description.clone(),
                             code_snippet: trimmed.to_string(),
+                            source_line: None,
                         });
                     }
                 }
@@ -344,8 +435,10 @@ impl ScriptScanner {
                         severity,
                         issue_type: SecurityIssueType::EncodedPayload,
                         line_number: line_idx + 1,
+                        column: Some(indent_column(line)),
                         description: detail,
                         code_snippet: trimmed.chars().take(120).collect(),
+                        source_line: None,
                     });
                 }
                 // decode call without visible literal
@@ -355,10 +448,12 @@ impl ScriptScanner {
                         severity: SecuritySeverity::Medium,
                         issue_type: SecurityIssueType::EncodedPayload,
                         line_number: line_idx + 1,
+                        column: Some(indent_column(line)),
                         description:
                             "Base64/codec decode call detected — verify the decoded content is safe"
                                 .to_string(),
                         code_snippet: trimmed.chars().take(120).collect(),
+                        source_line: None,
                     });
                 }
                 // long base64 literal without an explicit decode call on this line
@@ -369,11 +464,13 @@ impl ScriptScanner {
                         severity: SecuritySeverity::Medium,
                         issue_type: SecurityIssueType::EncodedPayload,
                         line_number: line_idx + 1,
+                        column: Some(indent_column(line)),
                         description: format!(
                             "Long base64-encoded string literal ({} chars) — possible encoded payload",
                             b64_str.len()
                         ),
                         code_snippet: trimmed.chars().take(120).collect(),
+                        source_line: None,
                     });
                 }
                 (false, None) => {}
@@ -459,6 +556,7 @@ impl ScriptScanner {
                 severity,
                 issue_type: SecurityIssueType::MultiStagePayload,
                 line_number: first_line,
+                column: None,
                 description,
                 code_snippet: format!(
                     "stages: {}",
@@ -468,6 +566,7 @@ impl ScriptScanner {
                         .collect::<Vec<_>>()
                         .join(", ")
                 ),
+                source_line: None,
             });
         }
     }
@@ -519,18 +618,37 @@ impl ScriptScanner {
                     severity: SecuritySeverity::Medium,
                     issue_type: SecurityIssueType::ObfuscatedCode,
                     line_number: line_idx + 1,
+                    column: Some(indent_column(line)),
                     description: format!(
                         "High-entropy line ({:.2} bits/char > {:.1} threshold) — possible obfuscated or encoded payload",
                         entropy,
                         threshold,
                     ),
                     code_snippet: trimmed.chars().take(120).collect(),
+                    source_line: None,
                 });
             }
         }
     }
 }
 
+/// 1-based column of a rule match. Patterns anchored with a `(?:^|[^.\w])` guard consume the
+/// character before the identifier; point at the identifier instead.
+fn match_column(line: &str, start: usize, matched: &str) -> usize {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut chars = matched.chars();
+    let skip = match (chars.next(), chars.next()) {
+        (Some(first), Some(second)) if !is_word(first) && is_word(second) => 1,
+        _ => 0,
+    };
+    line[..start].chars().count() + skip + 1
+}
+
+/// 1-based column of the first non-whitespace character of `line`.
+fn indent_column(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).count() + 1
+}
+
 /// True if `haystack` contains `needle` using ASCII case-insensitive byte comparison.
 fn bytes_contains_ci(haystack: &[u8], needle: &[u8]) -> bool {
     if needle.is_empty() || needle.len() > haystack.len() {
@@ -672,7 +790,7 @@ fn analyze_decoded_base64(b64: &str) -> Option<&'static str> {
 }
 
 /// Detect programming language from file extension
-fn detect_language(script_path: &Path) -> String {
+pub(super) fn detect_language(script_path: &Path) -> String {
     script_path
         .extension()
         .and_then(|e| e.to_str())
@@ -698,9 +816,32 @@ pub fn format_scan_result_compact(result: &ScanResult) -> String {
     format_scan_result_impl(result, true)
 }
 
+/// "ℹ️ N finding(s) suppressed with justification", optionally followed by one line per finding.
+fn format_suppressed(result: &ScanResult, detailed: bool) -> String {
+    if result.suppressed.is_empty() {
+        return String::new();
+    }
+    let mut output = format!(
+        "\n\nℹ️ {} finding(s) suppressed with justification",
+        result.suppressed.len()
+    );
+    if detailed {
+        for s in &result.suppressed {
+            output.push_str(&format!(
+                "\n     • Line {} [{}]: {}",
+                s.issue.line_number, s.issue.rule_id, s.reason
+            ));
+        }
+    }
+    output
+}
+
 fn format_scan_result_impl(result: &ScanResult, compact: bool) -> String {
     if result.issues.is_empty() {
-        return "✅ No security issues found. Script is safe to execute.".to_string();
+        return format!(
+            "✅ No security issues found. Script is safe to execute.{}",
+            format_suppressed(result, !compact)
+        );
     }
 
     let use_compact = compact && result.issues.len() > 5;
@@ -743,6 +884,7 @@ fn format_scan_result_impl(result: &ScanResult, compact: bool) -> String {
         } else {
             output.push_str("\n📝 Review complete. Awaiting your approval to proceed.");
         }
+        output.push_str(&format_suppressed(result, false));
         return output;
     }
 
@@ -785,6 +927,7 @@ fn format_scan_result_impl(result: &ScanResult, compact: bool) -> String {
     } else {
         output.push_str("📝 Review complete. Awaiting your approval to proceed.");
     }
+    output.push_str(&format_suppressed(result, !compact));
 
     output
 }
@@ -804,14 +947,18 @@ pub fn format_scan_result_json(result: &ScanResult) -> String {
         .issues
         .iter()
         .map(|issue| {
-            serde_json::json!({
+            let mut v = serde_json::json!({
                 "rule_id": issue.rule_id,
                 "severity": severity_str(&issue.severity),
                 "issue_type": issue.issue_type.to_string(),
                 "line_number": issue.line_number,
                 "description": issue.description,
                 "code_snippet": issue.code_snippet,
-            })
+            });
+            if let Some(source_line) = issue.source_line {
+                v["source_line_number"] = serde_json::json!(source_line);
+            }
+            v
         })
         .collect();
 
@@ -836,12 +983,30 @@ pub fn format_scan_result_json(result: &ScanResult) -> String {
         .filter(|i| matches!(i.severity, SecuritySeverity::Low))
         .count();
 
+    let suppressed_json: Vec<serde_json::Value> = result
+        .suppressed
+        .iter()
+        .map(|s| {
+            serde_json::json!({
+                "rule_id": s.issue.rule_id,
+                "severity": "Info",
+                "original_severity": severity_str(&s.issue.severity),
+                "issue_type": s.issue.issue_type.to_string(),
+                "line_number": s.issue.line_number,
+                "description": s.issue.description,
+                "reason": s.reason,
+            })
+        })
+        .collect();
+
     let output = serde_json::json!({
         "is_safe": result.is_safe,
         "issues": issues_json,
+        "suppressed": suppressed_json,
         "high_severity_count": high_count,
         "medium_severity_count": medium_count,
         "low_severity_count": low_count,
+        "suppressed_count": result.suppressed.len(),
     });
 
     serde_json::to_string(&output).unwrap_or_else(|_| "{}".to_string())
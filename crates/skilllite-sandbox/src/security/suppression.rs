@@ -0,0 +1,221 @@
+//! Inline suppression comments for scanner findings
+//!
+//! A flagged line may carry a justification comment:
+//!
+//! ```text
+//! subprocess.run(["ffmpeg", "-i", src, dst])  # skilllite-allow: process-exec reason="invokes bundled ffmpeg"
+//! execFileSync(ffmpeg, args); // skilllite-allow: process-exec reason="invokes bundled ffmpeg"
+//! ```
+//!
+//! The category matches a rule id (`py-subprocess`), an issue type in kebab-case
+//! (`process-execution`), or one of the short aliases (`process-exec`, `file-ops`, `network`).
+//! Only findings on the same line are affected, a `reason` is mandatory, and Critical
+//! findings are never suppressed.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::taint::strip_strings;
+use super::types::{SecurityIssue, SecuritySeverity, SuppressedIssue};
+
+static ALLOW_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"skilllite-allow:\s*([A-Za-z0-9_-]+)(?:\s+reason\s*=\s*"([^"]*)")?"#)
+        .expect("ALLOW_RE is valid")
+});
+
+/// One `skilllite-allow:` comment found in a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    /// 1-based line carrying the comment (the suppressed finding must be on this line)
+    pub line_number: usize,
+    /// Rule id, issue-type slug, or alias the comment applies to
+    pub category: String,
+    /// Justification; `None` when missing or blank (such comments suppress nothing)
+    pub reason: Option<String>,
+}
+
+/// Collect suppression comments. Only the language's line-comment marker is recognised
+/// (`#` for Python / shell, `//` for JavaScript); markers inside string literals are skipped.
+pub fn parse_suppressions(content: &str, language: &str) -> Vec<Suppression> {
+    let marker = match language {
+        "python" | "shell" => "#",
+        "javascript" | "node" => "//",
+        _ => return Vec::new(),
+    };
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let comment_start = strip_strings(line).find(marker)?;
+            let cap = ALLOW_RE.captures(&line[comment_start..])?;
+            let reason = cap
+                .get(2)
+                .map(|m| m.as_str().trim().to_string())
+                .filter(|r| !r.is_empty());
+            Some(Suppression {
+                line_number: idx + 1,
+                category: cap[1].to_lowercase(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// [`parse_suppressions`] with the language inferred from the file extension (as the scanner does).
+pub fn parse_suppressions_for_path(content: &str, path: &Path) -> Vec<Suppression> {
+    parse_suppressions(content, &super::scanner::detect_language(path))
+}
+
+/// Move issues covered by a justified suppression out of `issues`.
+/// Critical issues and suppressions without a reason are left untouched.
+pub(super) fn apply_suppressions(
+    issues: Vec<SecurityIssue>,
+    suppressions: &[Suppression],
+) -> (Vec<SecurityIssue>, Vec<SuppressedIssue>) {
+    if suppressions.is_empty() {
+        return (issues, Vec::new());
+    }
+    let mut kept = Vec::new();
+    let mut suppressed = Vec::new();
+    for issue in issues {
+        let reason = if issue.severity == SecuritySeverity::Critical {
+            None
+        } else {
+            suppressions
+                .iter()
+                .filter(|s| {
+                    s.line_number == issue.line_number && category_matches(&s.category, &issue)
+                })
+                .find_map(|s| s.reason.clone())
+        };
+        match reason {
+            Some(reason) => suppressed.push(SuppressedIssue { issue, reason }),
+            None => kept.push(issue),
+        }
+    }
+    (kept, suppressed)
+}
+
+fn category_matches(category: &str, issue: &SecurityIssue) -> bool {
+    if category == issue.rule_id {
+        return true;
+    }
+    let slug = serde_json::to_value(&issue.issue_type)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.replace('_', "-")))
+        .unwrap_or_default();
+    let alias = match category {
+        "process-exec" => "process-execution",
+        "file-ops" => "file-operation",
+        "network" => "network-request",
+        other => other,
+    };
+    alias == slug
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::parse_suppressions;
+    use crate::security::{ScriptScanner, SecuritySeverity};
+
+    #[test]
+    fn python_comment_suppresses_with_reason() {
+        let py = "import subprocess\nsubprocess.run(['ffmpeg', '-version'])  # skilllite-allow: process-exec reason=\"invokes bundled ffmpeg\"\n";
+        let r = ScriptScanner::new()
+            .scan_content(py, Path::new("t.py"))
+            .unwrap();
+        assert!(
+            r.issues
+                .iter()
+                .all(|i| !(i.line_number == 2 && i.rule_id.contains("subprocess"))),
+            "{:?}",
+            r.issues
+        );
+        assert!(r
+            .suppressed
+            .iter()
+            .any(|s| s.issue.line_number == 2 && s.reason == "invokes bundled ffmpeg"));
+    }
+
+    #[test]
+    fn javascript_comment_suppresses_with_reason() {
+        let js = "const child_process = require('child_process');\nchild_process.execSync('ffmpeg -version'); // skilllite-allow: process-exec reason=\"bundled ffmpeg\"\n";
+        let flagged = |issues: &[crate::security::SecurityIssue]| {
+            issues.iter().any(|i| i.rule_id == "js-child-process")
+        };
+        let plain = ScriptScanner::new()
+            .scan_content(&js.replace("skilllite-allow", "note"), Path::new("t.js"))
+            .unwrap();
+        assert!(flagged(&plain.issues));
+
+        let r = ScriptScanner::new()
+            .scan_content(js, Path::new("t.js"))
+            .unwrap();
+        assert!(!flagged(&r.issues), "{:?}", r.issues);
+        assert!(r
+            .suppressed
+            .iter()
+            .any(|s| s.issue.rule_id == "js-child-process" && s.reason == "bundled ffmpeg"));
+    }
+
+    #[test]
+    fn missing_reason_does_not_suppress() {
+        let py = "import subprocess\nsubprocess.run(['ls'])  # skilllite-allow: process-exec\n";
+        let r = ScriptScanner::new()
+            .scan_content(py, Path::new("t.py"))
+            .unwrap();
+        assert!(r.suppressed.is_empty());
+        assert!(r.issues.iter().any(|i| i.line_number == 2));
+        assert_eq!(parse_suppressions(py, "python")[0].reason, None);
+    }
+
+    #[test]
+    fn critical_findings_are_not_suppressible() {
+        let py = "import requests\nexec(requests.get(u).text)  # skilllite-allow: tainted-execution reason=\"trust me\"\n";
+        let r = ScriptScanner::new()
+            .scan_content(py, Path::new("t.py"))
+            .unwrap();
+        assert!(r
+            .issues
+            .iter()
+            .any(|i| i.severity == SecuritySeverity::Critical && i.line_number == 2));
+        assert!(r
+            .suppressed
+            .iter()
+            .all(|s| s.issue.severity != SecuritySeverity::Critical));
+        assert!(!r.is_safe);
+    }
+
+    #[test]
+    fn marker_inside_string_literal_is_not_a_comment() {
+        let py = "import subprocess\nsubprocess.run(['echo', '# skilllite-allow: process-exec reason=\"fake\"'])\n";
+        assert!(parse_suppressions(py, "python").is_empty());
+        let r = ScriptScanner::new()
+            .scan_content(py, Path::new("t.py"))
+            .unwrap();
+        assert!(r.suppressed.is_empty());
+        assert!(r.issues.iter().any(|i| i.line_number == 2));
+
+        let js = "const child_process = require('child_process');\nchild_process.execSync(\"echo // skilllite-allow: process-exec reason='fake'\");\n";
+        assert!(parse_suppressions(js, "javascript").is_empty());
+
+        // A real comment after a string that contains the marker still counts
+        let mixed = "run('#x')  # skilllite-allow: process-exec reason=\"bundled ffmpeg\"\n";
+        let parsed = parse_suppressions(mixed, "python");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].reason.as_deref(), Some("bundled ffmpeg"));
+    }
+
+    #[test]
+    fn shell_and_unknown_languages() {
+        let sh = "curl -s x  # skilllite-allow: network reason=\"health probe\"\n";
+        assert_eq!(parse_suppressions(sh, "shell").len(), 1);
+        assert!(parse_suppressions(sh, "unknown").is_empty());
+        // JS marker required for JS sources
+        assert!(parse_suppressions(sh, "javascript").is_empty());
+    }
+}
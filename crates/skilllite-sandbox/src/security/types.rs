@@ -15,10 +15,14 @@ pub struct SecurityIssue {
     pub issue_type: SecurityIssueType,
     /// Line number where issue was found
     pub line_number: usize,
+    /// 1-based column (in characters) on `line_number`, when the detector knows it
+    pub column: Option<usize>,
     /// Description of the issue
     pub description: String,
     /// The code snippet that triggered the issue
     pub code_snippet: String,
+    /// Dataflow findings only: line where the flagged value originated (`line_number` is the sink)
+    pub source_line: Option<usize>,
 }
 
 /// Severity levels for security issues
@@ -50,6 +54,8 @@ pub enum SecurityIssueType {
     MultiStagePayload,
     /// Package name matches the offline malicious-package library (B4)
     MaliciousPackage,
+    /// Network-downloaded value flows into exec/eval/shell or an autostart file (B5)
+    TaintedExecution,
     /// Scan process failed (timeout, IO error, etc.) — fail-secure
     ScanError,
 }
@@ -68,11 +74,21 @@ impl std::fmt::Display for SecurityIssueType {
             SecurityIssueType::EncodedPayload => write!(f, "Encoded Payload"),
             SecurityIssueType::MultiStagePayload => write!(f, "Multi-Stage Payload"),
             SecurityIssueType::MaliciousPackage => write!(f, "Malicious Package"),
+            SecurityIssueType::TaintedExecution => write!(f, "Tainted Execution"),
             SecurityIssueType::ScanError => write!(f, "Scan Error"),
         }
     }
 }
 
+/// Finding downgraded to informational by an inline `skilllite-allow:` comment
+#[derive(Debug, Clone)]
+pub struct SuppressedIssue {
+    /// The original finding (severity kept for reporting)
+    pub issue: SecurityIssue,
+    /// Justification from the comment's `reason="..."`
+    pub reason: String,
+}
+
 /// Result of scanning a script
 #[derive(Debug, Clone)]
 pub struct ScanResult {
@@ -80,4 +96,6 @@ pub struct ScanResult {
     pub is_safe: bool,
     /// List of security issues found
     pub issues: Vec<SecurityIssue>,
+    /// Findings suppressed with a justification; they do not affect `is_safe`
+    pub suppressed: Vec<SuppressedIssue>,
 }
@@ -12,10 +12,16 @@
 
 #![cfg(target_os = "windows")]
 
+use crate::common::resolve_script_args;
 use crate::error::bail;
-use crate::runner::{ExecutionResult, ResourceLimits, RuntimePaths, SandboxConfig};
+use crate::runner::{
+    ExecutionResult, LimitEnforcement, ResourceLimits, RuntimePaths, SandboxConfig,
+};
 use crate::runtime_resolver::RuntimeResolver;
-use crate::{common::apply_standard_execution_env, common::hide_child_console, common::pipe_stdio};
+use crate::{
+    common::apply_results_dir_env, common::apply_standard_execution_env,
+    common::hide_child_console, common::pipe_stdio,
+};
 use anyhow::Context;
 
 use crate::Result;
@@ -32,7 +38,8 @@ pub fn execute_with_limits(
     input_json: &str,
     limits: ResourceLimits,
 ) -> Result<ExecutionResult> {
-    if skilllite_core::config::SandboxEnvConfig::from_env().no_sandbox {
+    if skilllite_core::config::SandboxEnvConfig::from_env().no_sandbox && !config.enforce_isolation
+    {
         tracing::warn!("Sandbox disabled via SKILLLITE_NO_SANDBOX - running without protection");
         return execute_simple_with_limits(skill_dir, runtime, config, input_json, limits);
     }
@@ -205,6 +212,9 @@ fn execute_via_wsl2(
                     stdout: String::from_utf8_lossy(&output.stdout).to_string(),
                     stderr: String::from_utf8_lossy(&output.stderr).to_string(),
                     exit_code: status.code().unwrap_or(-1),
+                    enforcement: LimitEnforcement::Unenforced,
+                    syscall_violations: Vec::new(),
+                    seccomp_profile: None,
                 });
             }
             Ok(None) => {
@@ -270,10 +280,12 @@ fn execute_with_native_isolation(
     let mut cmd = Command::new(&resolved.interpreter);
     hide_child_console(&mut cmd);
     cmd.arg(&entry_point);
+    cmd.args(resolve_script_args(config));
     cmd.current_dir(skill_dir);
 
     // Sanitized environment: only pass what the skill needs
     apply_standard_execution_env(&mut cmd, true, work_dir, config.network_enabled, false);
+    apply_results_dir_env(&mut cmd, config);
 
     for (k, v) in &resolved.extra_env {
         cmd.env(k, v);
@@ -291,6 +303,11 @@ fn execute_with_native_isolation(
             e
         );
     }
+    let enforcement = if job_handle.is_ok() {
+        LimitEnforcement::JobObject
+    } else {
+        LimitEnforcement::Unenforced
+    };
 
     if let Some(mut stdin) = child.stdin.take() {
         let _ = stdin.write_all(input_json.as_bytes());
@@ -315,6 +332,9 @@ fn execute_with_native_isolation(
                     stdout,
                     stderr,
                     exit_code: status.code().unwrap_or(-1),
+                    enforcement,
+                    syscall_violations: Vec::new(),
+                    seccomp_profile: None,
                 });
             }
             Ok(None) => {
@@ -323,10 +343,14 @@ fn execute_with_native_isolation(
                     return Ok(ExecutionResult {
                         stdout: String::new(),
                         stderr: format!(
-                            "Process killed: exceeded timeout of {}s",
+                            "{} of {}s",
+                            crate::error::KILLED_TIMEOUT_PREFIX,
                             limits.timeout_secs
                         ),
                         exit_code: -1,
+                        enforcement,
+                        syscall_violations: Vec::new(),
+                        seccomp_profile: None,
                     });
                 }
                 std::thread::sleep(std::time::Duration::from_millis(100));
@@ -445,6 +469,7 @@ pub fn execute_simple_with_limits(
     let mut cmd = Command::new(&resolved.interpreter);
     hide_child_console(&mut cmd);
     cmd.arg(&entry_point)
+        .args(resolve_script_args(config))
         .current_dir(skill_dir)
         .env("SKILL_INPUT_FILE", &input_file)
         .env("SKILL_INPUT", input_json);
@@ -455,6 +480,7 @@ pub fn execute_simple_with_limits(
         config.network_enabled,
         false,
     );
+    apply_results_dir_env(&mut cmd, config);
     for (k, v) in &resolved.extra_env {
         cmd.env(k, v);
     }
@@ -462,12 +488,16 @@ pub fn execute_simple_with_limits(
 
     let mut child = cmd.spawn().context("Failed to execute skill")?;
 
-    if let Err(e) = attach_job_object(&child, &limits) {
-        tracing::warn!(
-            "Failed to attach Job Object: {}. Resource limits not enforced.",
-            e
-        );
-    }
+    let enforcement = match attach_job_object(&child, &limits) {
+        Ok(()) => LimitEnforcement::JobObject,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to attach Job Object: {}. Resource limits not enforced.",
+                e
+            );
+            LimitEnforcement::Unenforced
+        }
+    };
 
     if let Some(mut stdin) = child.stdin.take() {
         let _ = stdin.write_all(input_json.as_bytes());
@@ -492,6 +522,9 @@ pub fn execute_simple_with_limits(
                     stdout,
                     stderr,
                     exit_code: status.code().unwrap_or(-1),
+                    enforcement,
+                    syscall_violations: Vec::new(),
+                    seccomp_profile: None,
                 });
             }
             Ok(None) => {
@@ -500,10 +533,14 @@ pub fn execute_simple_with_limits(
                     return Ok(ExecutionResult {
                         stdout: String::new(),
                         stderr: format!(
-                            "Process killed: exceeded timeout of {}s",
+                            "{} of {}s",
+                            crate::error::KILLED_TIMEOUT_PREFIX,
                             limits.timeout_secs
                         ),
                         exit_code: -1,
+                        enforcement,
+                        syscall_violations: Vec::new(),
+                        seccomp_profile: None,
                     });
                 }
                 std::thread::sleep(std::time::Duration::from_millis(100));
@@ -546,6 +583,9 @@ fn execute_bash_via_wsl(
         stdout: String::from_utf8_lossy(&output.stdout).to_string(),
         stderr: String::from_utf8_lossy(&output.stderr).to_string(),
         exit_code: output.status.code().unwrap_or(-1),
+        enforcement: LimitEnforcement::Unenforced,
+        syscall_violations: Vec::new(),
+        seccomp_profile: None,
     })
 }
 
@@ -0,0 +1,8 @@
+# Calls uname(2), which the seccomp test profile denies.
+import os
+
+try:
+    os.uname()
+    print("allowed")
+except OSError as e:
+    print("denied errno=%d" % e.errno)
@@ -63,11 +63,16 @@ fn macos_seatbelt_level2_executes_minimal_python_skill() {
         language: "python".to_string(),
         network_enabled: false,
         network_outbound: Vec::new(),
+        network_deny: Vec::new(),
         uses_playwright: false,
+        script_args: Vec::new(),
+        results_dir: None,
+        enforce_isolation: false,
     };
     let limits = ResourceLimits {
         max_memory_mb: 256,
         timeout_secs: 30,
+        max_cpu_percent: None,
     };
 
     let output = run_in_sandbox_with_limits_and_level(
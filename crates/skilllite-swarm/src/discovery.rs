@@ -2,6 +2,9 @@
 //!
 //! Uses `_skilllite-swarm._udp.local.` service type for SkillLite P2P nodes.
 //! TXT record `capabilities` = JSON array of capability tags.
+//!
+//! Peers outside the local multicast domain are configured statically (see
+//! [`crate::static_peers`]) and merged with the mDNS results.
 
 use crate::Result;
 use anyhow::Context;
@@ -12,8 +15,19 @@ use std::sync::Arc;
 /// SkillLite swarm mDNS service type (RFC 6763: _service._proto.local.)
 pub const SERVICE_TYPE: &str = "_skilllite-swarm._udp.local.";
 
+/// How a peer became known to this node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerSource {
+    /// Announced on the LAN via mDNS.
+    #[default]
+    Mdns,
+    /// Configured with `--peer` or `peers.toml` and health-checked via `GET /capabilities`.
+    Static,
+}
+
 /// Discovered peer node info.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct PeerInfo {
     /// Instance name (unique per node, e.g. hostname or UUID)
     pub instance_name: String,
@@ -21,6 +35,12 @@ pub struct PeerInfo {
     pub addr: String,
     /// Capability tags advertised by the peer
     pub capabilities: Vec<String>,
+    /// mDNS or static configuration
+    pub source: PeerSource,
+    /// Round-trip time of the last successful health check (static peers only)
+    pub latency_ms: Option<u64>,
+    /// Unix seconds when the peer was last resolved or answered a health check
+    pub last_seen: Option<u64>,
 }
 
 /// mDNS Discovery: register self and browse for peers.
@@ -7,6 +7,9 @@ pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
 
+    #[error(transparent)]
+    Core(#[from] skilllite_core::Error),
+
     #[error("{0}")]
     Validation(String),
 
@@ -2,13 +2,16 @@
 //!
 //! Phase 3 routing:
 //! - POST /task: receive NodeTask, match capabilities, execute locally or forward to peer.
+//! - GET /skill/{name}: skill package for replication (only with `--allow-skill-replication`).
+//! - GET /capabilities: handshake for static peers (no mDNS TXT record to read).
+//! - GET /peers: mDNS and static peers with capabilities, health and last-seen.
 
 use crate::error::bail;
 use crate::Result;
 use anyhow::Context;
 use axum::{
     body::Body,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
@@ -24,7 +27,9 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::discovery::{parse_capabilities_from_txt, Discovery};
+use crate::replication::{self, SkillReplication};
 use crate::routing::{capabilities_match, route_task, RouteTarget, TaskExecutor};
+use crate::static_peers::{self, CapabilitiesResponse, HealthConfig, StaticPeerTable};
 
 /// Parse listen address `host:port`, `:port`, or `port` into `(host, port)`.
 ///
@@ -45,7 +50,10 @@ fn parse_listen_addr(addr: &str) -> Result<(String, u16)> {
     Ok((host.to_string(), port))
 }
 
-fn reqwest_swarm_auth(b: reqwest::RequestBuilder, token: Option<&str>) -> reqwest::RequestBuilder {
+pub(crate) fn reqwest_swarm_auth(
+    b: reqwest::RequestBuilder,
+    token: Option<&str>,
+) -> reqwest::RequestBuilder {
     match token {
         Some(t) if !t.is_empty() => b.header(header::AUTHORIZATION, format!("Bearer {}", t)),
         _ => b,
@@ -58,12 +66,31 @@ struct AppState {
     /// This node's instance name (for "I can" response in can-do).
     instance_name: String,
     local_capabilities: Vec<String>,
+    /// Peers discovered via mDNS.
     peers: Arc<std::sync::Mutex<Vec<crate::discovery::PeerInfo>>>,
+    /// Peers from `--peer` / `peers.toml` with their health-check state.
+    static_peers: StaticPeerTable,
     executor: Option<Arc<dyn TaskExecutor>>,
     /// Current task being executed (for GET /status feedback).
     current_task: Arc<std::sync::Mutex<Option<String>>>,
     /// When set, all HTTP routes require `Authorization: Bearer <token>`; forwarded peer requests include it.
     swarm_token: Option<Arc<str>>,
+    /// Local skill dirs served by GET /skill/{name}.
+    skill_dirs: Arc<Vec<std::path::PathBuf>>,
+    /// Set by `--allow-skill-replication`: fetch skills from peers instead of forwarding,
+    /// and serve local skills to peers.
+    replication: Option<SkillReplication>,
+}
+
+impl AppState {
+    /// mDNS peers merged with the reachable static peers.
+    fn routable_peers(&self) -> Vec<crate::discovery::PeerInfo> {
+        let mdns = self.peers.lock().map(|p| p.clone()).unwrap_or_default();
+        match self.static_peers.lock() {
+            Ok(statics) => static_peers::merge_peers(&mdns, &statics),
+            Err(_) => mdns,
+        }
+    }
 }
 
 /// GET /status — execution status for client polling (avoids "empty wait" UX).
@@ -122,6 +149,119 @@ async fn handle_can_do(
         .into_response()
 }
 
+/// GET /capabilities — handshake and health check for static peers.
+async fn handle_capabilities(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(resp) =
+        crate::swarm_auth::reject_if_unauthorized(state.swarm_token.as_deref(), &headers)
+    {
+        return resp;
+    }
+    (
+        StatusCode::OK,
+        Json(CapabilitiesResponse {
+            instance_name: state.instance_name.clone(),
+            capabilities: state.local_capabilities.clone(),
+        }),
+    )
+        .into_response()
+}
+
+/// GET /peers — known peers (mDNS and static) with address, capabilities, status and last-seen.
+async fn handle_peers(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(resp) =
+        crate::swarm_auth::reject_if_unauthorized(state.swarm_token.as_deref(), &headers)
+    {
+        return resp;
+    }
+    let mdns = state.peers.lock().map(|p| p.clone()).unwrap_or_default();
+    let listing = match state.static_peers.lock() {
+        Ok(statics) => static_peers::peer_listing(&mdns, &statics),
+        Err(_) => static_peers::peer_listing(&mdns, &[]),
+    };
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "peers": listing })),
+    )
+        .into_response()
+}
+
+/// GET /skill/{name} — gzipped tar of a local skill for peers replicating it.
+/// Query: ?required=tag1,tag2 picks a skill by capability when no skill is called `name`.
+async fn handle_get_skill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(q): Query<CanDoQuery>,
+) -> Response {
+    if let Some(resp) =
+        crate::swarm_auth::reject_if_unauthorized(state.swarm_token.as_deref(), &headers)
+    {
+        return resp;
+    }
+    if state.replication.is_none() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "replication_disabled",
+                "message": "Start the node with --allow-skill-replication to serve skills"
+            })),
+        )
+            .into_response();
+    }
+    let required: Vec<String> = q
+        .required
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let skill_dirs = state.skill_dirs.clone();
+    let packed = tokio::task::spawn_blocking(move || {
+        let Some((skill_name, dir)) = replication::find_local_skill(&skill_dirs, &name, &required)
+        else {
+            return Ok(None);
+        };
+        let hash = skilllite_core::skill::manifest::compute_skill_fingerprint(&dir)?;
+        let package = replication::pack_skill(&dir)?;
+        Ok::<_, crate::Error>(Some((skill_name, hash, package)))
+    })
+    .await
+    .map_err(|e| crate::Error::validation(format!("{:?}", e)))
+    .and_then(|r| r);
+    match packed {
+        Ok(Some((skill_name, hash, package))) => {
+            tracing::info!(skill = %skill_name, bytes = package.len(), "Serving skill package to peer");
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "application/gzip".to_string()),
+                    (
+                        header::HeaderName::from_static(replication::SKILL_NAME_HEADER),
+                        skill_name,
+                    ),
+                    (
+                        header::HeaderName::from_static(replication::SKILL_HASH_HEADER),
+                        hash,
+                    ),
+                ],
+                package,
+            )
+                .into_response()
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "skill_not_found"})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "internal", "message": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
 #[derive(serde::Deserialize, Default)]
 struct TaskQuery {
     #[serde(rename = "stream")]
@@ -142,7 +282,7 @@ async fn handle_task(
         return resp;
     }
     let token = state.swarm_token.as_deref();
-    let peers = state.peers.lock().map(|p| p.clone()).unwrap_or_default();
+    let peers = state.routable_peers();
 
     // When required_capabilities is empty, optionally infer via LLM (SKILLLITE_SWARM_LLM_ROUTING=1)
     let required = if task.context.required_capabilities.is_empty() {
@@ -213,6 +353,40 @@ async fn handle_task(
         }
     }
 
+    // Replication: fetch the skill from the peer that has it and execute locally instead.
+    if let (RouteTarget::Forward(candidates), Some(repl), Some(_)) =
+        (&target, &state.replication, &state.executor)
+    {
+        let hint = task.tool_hint.clone().or_else(|| required.first().cloned());
+        if let Some(hint) = hint {
+            for peer in candidates {
+                match repl
+                    .replicate_from_peer(peer, &hint, &required, token)
+                    .await
+                {
+                    Ok(dir) => {
+                        tracing::info!(
+                            task_id = %task.id,
+                            peer = %peer.instance_name,
+                            skill_dir = %dir.display(),
+                            "Routing: replicated skill from peer → LOCAL"
+                        );
+                        target = RouteTarget::Local;
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            task_id = %task.id,
+                            peer = %peer.instance_name,
+                            err = %e,
+                            "Skill replication failed, falling back to forwarding"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     // Log routing decision for observability
     match &target {
         RouteTarget::Local => {
@@ -424,9 +598,24 @@ async fn handle_task(
     }
 }
 
+fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/task", post(handle_task))
+        .route("/status", get(handle_status))
+        .route("/can-do", get(handle_can_do))
+        .route("/capabilities", get(handle_capabilities))
+        .route("/peers", get(handle_peers))
+        .route("/skill/:name", get(handle_get_skill))
+        .with_state(state)
+}
+
 /// Run the swarm daemon: register via mDNS (unless bind is loopback-only), browse for peers, serve HTTP task API, block until Ctrl+C.
 ///
 /// - `executor`: Optional. When set, local tasks are executed via this; otherwise returns 503.
+/// - `peer_addrs`: `--peer host:port` entries, merged with `<data_root>/peers.toml`; they are
+///   health-checked via `GET /capabilities` and routed to like mDNS peers.
+/// - `replication`: Optional (`--allow-skill-replication`). When set, tasks routed to a peer are
+///   run locally after replicating the skill from it, and local skills are served to peers.
 /// - Sets `SKILLLITE_SWARM_URL` so agent's delegate_to_swarm can route to this swarm (skill sharing).
 /// - When `skills_dir` is set, loads .env from its parent (project root) so OPENAI_API_KEY is available for LLM routing.
 /// - When `SKILLLITE_SWARM_TOKEN` is set (after dotenv), all HTTP routes require `Authorization: Bearer`; peer forwards include it.
@@ -434,7 +623,9 @@ pub fn serve_swarm(
     listen_addr: &str,
     capability_tags: Vec<String>,
     skills_dir: Option<&[String]>,
+    peer_addrs: &[String],
     executor: Option<Arc<dyn TaskExecutor>>,
+    replication: Option<SkillReplication>,
 ) -> Result<()> {
     // Load .env from project root (parent of skills_dir) so LLM routing works when started from different cwd
     if let Some(dirs) = skills_dir.and_then(|d| d.first()) {
@@ -458,6 +649,8 @@ pub fn serve_swarm(
     }
 
     let (host, port) = parse_listen_addr(listen_addr)?;
+    let static_specs =
+        static_peers::load_static_peers(peer_addrs, &skilllite_core::paths::data_root())?;
     let bind_addr = format!("{}:{}", host, port);
     let swarm_token = crate::swarm_auth::swarm_token_from_env().map(Arc::<str>::from);
     let instance_name = uuid::Uuid::new_v4().to_string();
@@ -524,6 +717,11 @@ pub fn serve_swarm(
                         instance_name: instance_name.clone(),
                         addr,
                         capabilities: caps.clone(),
+                        last_seen: std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .ok()
+                            .map(|d| d.as_secs()),
+                        ..Default::default()
                     };
                     if let Ok(mut p) = peers_browse.lock() {
                         if let Some(existing) =
@@ -552,20 +750,35 @@ pub fn serve_swarm(
         }
     });
 
+    let skill_dirs = skills_dir
+        .map(|d| d.to_vec())
+        .unwrap_or_else(|| vec![".skills".into(), "skills".into()])
+        .into_iter()
+        .map(std::path::PathBuf::from)
+        .collect();
+    if let Some(ref repl) = replication {
+        tracing::info!(
+            quarantine = %repl.quarantine_dir().display(),
+            "Skill replication enabled: skills are fetched from peers instead of forwarding tasks"
+        );
+    }
+    if !static_specs.is_empty() {
+        tracing::info!(peers = ?static_specs, "Static peers configured; health-checking via GET /capabilities");
+    }
+    let static_table = static_peers::new_table(&static_specs);
     let state = AppState {
         instance_name: instance_name.clone(),
         local_capabilities: capability_tags.clone(),
         peers,
+        static_peers: static_table.clone(),
         executor,
         current_task: Arc::new(std::sync::Mutex::new(None)),
         swarm_token: swarm_token.clone(),
+        skill_dirs: Arc::new(skill_dirs),
+        replication,
     };
 
-    let app = Router::new()
-        .route("/task", post(handle_task))
-        .route("/status", get(handle_status))
-        .route("/can-do", get(handle_can_do))
-        .with_state(state);
+    let app = router(state);
 
     let llm_routing =
         std::env::var(skilllite_core::config::env_keys::swarm::SKILLLITE_SWARM_LLM_ROUTING)
@@ -588,6 +801,14 @@ pub fn serve_swarm(
 
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
+        if !static_specs.is_empty() {
+            tokio::spawn(static_peers::run_health_checks(
+                static_table,
+                swarm_token.clone(),
+                HealthConfig::from_env(),
+                instance_name.clone(),
+            ));
+        }
         let std_listener =
             std::net::TcpListener::bind(&bind_addr).context("Failed to bind TCP listener")?;
         let listener = tokio::net::TcpListener::from_std(std_listener)?;
@@ -624,3 +845,326 @@ mod listen_addr_tests {
         assert_eq!(p, 7700);
     }
 }
+
+#[cfg(test)]
+mod replication_tests {
+    use super::*;
+    use crate::discovery::PeerInfo;
+    use crate::replication::SkillVerifier;
+    use skilllite_core::protocol::NodeContext;
+    use std::path::{Path, PathBuf};
+
+    /// Answers with the node's label so tests can tell where a task ran.
+    #[derive(Debug)]
+    pub(super) struct LabelExecutor(pub(super) &'static str);
+
+    impl TaskExecutor for LabelExecutor {
+        fn execute(
+            &self,
+            task: NodeTask,
+        ) -> std::result::Result<NodeResult, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(NodeResult {
+                task_id: task.id,
+                response: self.0.to_string(),
+                task_completed: true,
+                tool_calls: 0,
+                new_skill: None,
+            })
+        }
+    }
+
+    /// Stand-in for the `skilllite add` offline scan (skilllite-commands): refuses `exec(`.
+    #[derive(Debug)]
+    struct CriticalPatternVerifier;
+
+    impl SkillVerifier for CriticalPatternVerifier {
+        fn verify(
+            &self,
+            name: &str,
+            skill_dir: &Path,
+        ) -> std::result::Result<String, Box<dyn std::error::Error + Send + Sync>> {
+            let script = std::fs::read_to_string(skill_dir.join("scripts").join("main.py"))?;
+            if script.contains("exec(") {
+                return Err(format!("{name}: critical finding (exec)").into());
+            }
+            Ok("safe".to_string())
+        }
+    }
+
+    fn write_calc_skill(skills: &Path, script: &str) {
+        let dir = skills.join("calc");
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            "---\nname: calc\ndescription: Adds numbers.\ncapabilities: [math]\n---\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("scripts").join("main.py"), script).unwrap();
+    }
+
+    async fn spawn_node(
+        label: &'static str,
+        skills: &Path,
+        capabilities: Vec<String>,
+        peers: Vec<PeerInfo>,
+    ) -> String {
+        let state = AppState {
+            instance_name: label.to_string(),
+            local_capabilities: capabilities,
+            peers: Arc::new(std::sync::Mutex::new(peers)),
+            static_peers: static_peers::new_table(&[]),
+            executor: Some(Arc::new(LabelExecutor(label))),
+            current_task: Arc::new(std::sync::Mutex::new(None)),
+            swarm_token: None,
+            skill_dirs: Arc::new(vec![skills.to_path_buf()]),
+            replication: Some(SkillReplication::new(
+                skills,
+                Arc::new(CriticalPatternVerifier),
+            )),
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+        addr
+    }
+
+    /// Node B has `calc` (capability `math`); node A has nothing and knows B as a peer.
+    /// Returns A's response to a `math` task and A's skills dir.
+    async fn run_math_task_on_a(script: &str) -> (String, PathBuf, tempfile::TempDir) {
+        let tmp = tempfile::tempdir().unwrap();
+        let (skills_a, skills_b) = (tmp.path().join("a"), tmp.path().join("b"));
+        std::fs::create_dir_all(&skills_a).unwrap();
+        write_calc_skill(&skills_b, script);
+        std::fs::create_dir_all(skills_b.join("calc").join(".venv")).unwrap();
+        std::fs::write(skills_b.join("calc").join(".venv").join("pyvenv.cfg"), "").unwrap();
+
+        let addr_b = spawn_node("node-b", &skills_b, vec!["math".into()], vec![]).await;
+        let peer_b = PeerInfo {
+            instance_name: "node-b".into(),
+            addr: addr_b,
+            capabilities: vec!["math".into()],
+            ..Default::default()
+        };
+        let addr_a = spawn_node("node-a", &skills_a, vec![], vec![peer_b]).await;
+
+        let task = NodeTask {
+            id: "t1".into(),
+            description: "add 1 and 2".into(),
+            context: NodeContext {
+                workspace: ".".into(),
+                session_key: "test".into(),
+                required_capabilities: vec!["math".into()],
+            },
+            tool_hint: None,
+        };
+        let result: NodeResult = reqwest::Client::new()
+            .post(format!("http://{addr_a}/task"))
+            .json(&task)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        (result.response, skills_a, tmp)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn replicates_skill_from_peer_and_executes_locally() {
+        let (ran_on, skills_a, _tmp) = run_math_task_on_a("print(1 + 2)\n").await;
+        assert_eq!(ran_on, "node-a");
+
+        let installed = skills_a.join("_replicated").join("calc");
+        assert!(installed.join("scripts").join("main.py").is_file());
+        assert!(!installed.join(".venv").exists());
+        let manifest =
+            skilllite_core::skill::manifest::load_manifest(&skills_a.join("_replicated")).unwrap();
+        let entry = &manifest.skills["calc"];
+        assert!(entry.source.starts_with("swarm:node-b@127.0.0.1:"));
+        assert_eq!(
+            entry.hash,
+            skilllite_core::skill::manifest::compute_skill_fingerprint(&installed).unwrap()
+        );
+        assert_eq!(entry.admission_risk.as_deref(), Some("safe"));
+        assert_eq!(
+            skilllite_core::skill::origin::detect_skill_origin(&installed),
+            skilllite_core::skill::origin::SkillOrigin::Replicated
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn scan_rejection_falls_back_to_forwarding() {
+        let (ran_on, skills_a, _tmp) =
+            run_math_task_on_a("import sys\nexec(sys.stdin.read())\n").await;
+        assert_eq!(ran_on, "node-b");
+        let quarantine = skills_a.join("_replicated");
+        assert!(!quarantine.join("calc").exists());
+        // The staging dir is cleaned up.
+        let leftovers: Vec<_> = std::fs::read_dir(&quarantine).unwrap().flatten().collect();
+        assert!(leftovers.is_empty(), "{leftovers:?}");
+    }
+}
+
+#[cfg(test)]
+mod static_peer_tests {
+    use super::replication_tests::LabelExecutor;
+    use super::*;
+    use skilllite_core::protocol::NodeContext;
+    use std::time::Instant;
+
+    fn node_state(
+        label: &'static str,
+        capabilities: &[&str],
+        statics: StaticPeerTable,
+    ) -> AppState {
+        AppState {
+            instance_name: label.to_string(),
+            local_capabilities: capabilities.iter().map(|c| c.to_string()).collect(),
+            peers: Arc::new(std::sync::Mutex::new(vec![])),
+            static_peers: statics,
+            executor: Some(Arc::new(LabelExecutor(label))),
+            current_task: Arc::new(std::sync::Mutex::new(None)),
+            swarm_token: None,
+            skill_dirs: Arc::new(vec![]),
+            replication: None,
+        }
+    }
+
+    async fn serve_on(listener: tokio::net::TcpListener, state: AppState) -> String {
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+        addr
+    }
+
+    fn health_config() -> HealthConfig {
+        HealthConfig {
+            interval: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(8),
+            timeout: Duration::from_secs(2),
+        }
+    }
+
+    /// Sends a `math` task to `addr`; returns the HTTP status and the label of the node that ran it.
+    async fn send_math_task(addr: &str) -> (StatusCode, Option<String>) {
+        let task = NodeTask {
+            id: "t1".into(),
+            description: "add 1 and 2".into(),
+            context: NodeContext {
+                workspace: ".".into(),
+                session_key: "test".into(),
+                required_capabilities: vec!["math".into()],
+            },
+            tool_hint: None,
+        };
+        let resp = reqwest::Client::new()
+            .post(format!("http://{addr}/task"))
+            .json(&task)
+            .send()
+            .await
+            .unwrap();
+        let status = StatusCode::from_u16(resp.status().as_u16()).unwrap();
+        let ran_on = resp.json::<NodeResult>().await.ok().map(|r| r.response);
+        (status, ran_on)
+    }
+
+    async fn peers_of(addr: &str) -> Vec<serde_json::Value> {
+        let body: serde_json::Value = reqwest::get(format!("http://{addr}/peers"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        body["peers"].as_array().unwrap().clone()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn static_peer_is_handshaken_listed_and_routed_to() {
+        let listener_b = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr_b = serve_on(
+            listener_b,
+            node_state("node-b", &["math"], static_peers::new_table(&[])),
+        )
+        .await;
+        let table = static_peers::new_table(std::slice::from_ref(&addr_b));
+        let listener_a = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr_a = serve_on(listener_a, node_state("node-a", &[], table.clone())).await;
+
+        let client = reqwest::Client::new();
+        static_peers::check_due(
+            &table,
+            &client,
+            None,
+            &health_config(),
+            "node-a",
+            Instant::now(),
+        )
+        .await;
+
+        let peers = peers_of(&addr_a).await;
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0]["instance_name"], "node-b");
+        assert_eq!(peers[0]["addr"], addr_b.as_str());
+        assert_eq!(peers[0]["capabilities"], serde_json::json!(["math"]));
+        assert_eq!(peers[0]["source"], "static");
+        assert_eq!(peers[0]["status"], "up");
+        assert!(peers[0]["latency_ms"].is_u64());
+        assert!(peers[0]["last_seen"].is_u64());
+
+        let (status, ran_on) = send_math_task(&addr_a).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ran_on.as_deref(), Some("node-b"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn unreachable_static_peer_backs_off_and_recovers() {
+        // Reserve a port for B, then leave it closed until the recovery step.
+        let addr_b = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let spec = format!("localhost:{}", addr_b.port());
+        let table = static_peers::new_table(std::slice::from_ref(&spec));
+        let listener_a = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr_a = serve_on(listener_a, node_state("node-a", &[], table.clone())).await;
+        let client = reqwest::Client::new();
+        let config = health_config();
+        let start = Instant::now();
+
+        static_peers::check_due(&table, &client, None, &config, "node-a", start).await;
+        let peers = peers_of(&addr_a).await;
+        assert_eq!(peers[0]["status"], "down");
+        assert_eq!(peers[0]["consecutive_failures"], 1);
+        assert_eq!(peers[0]["configured_addr"], spec.as_str());
+        assert!(peers[0]["last_error"].is_string());
+        let (status, _) = send_math_task(&addr_a).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        // Backing off: not re-checked before the delay elapses.
+        static_peers::check_due(&table, &client, None, &config, "node-a", start).await;
+        assert_eq!(table.lock().unwrap()[0].consecutive_failures, 1);
+
+        let listener_b = tokio::net::TcpListener::bind(addr_b).await.unwrap();
+        serve_on(
+            listener_b,
+            node_state("node-b", &["math"], static_peers::new_table(&[])),
+        )
+        .await;
+        static_peers::check_due(
+            &table,
+            &client,
+            None,
+            &config,
+            "node-a",
+            start + config.max_backoff,
+        )
+        .await;
+
+        let peers = peers_of(&addr_a).await;
+        assert_eq!(peers[0]["status"], "up");
+        assert_eq!(peers[0]["consecutive_failures"], 0);
+        assert_eq!(peers[0]["addr"], addr_b.to_string().as_str());
+        let (status, ran_on) = send_math_task(&addr_a).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ran_on.as_deref(), Some("node-b"));
+    }
+}
@@ -1,18 +1,24 @@
 //! SkillLite P2P Swarm — mDNS discovery, peer mesh, task routing.
 //!
 //! This crate implements the swarm daemon for `skilllite swarm --listen <ADDR>`:
-//! - **Discovery**: mDNS service registration and browsing for peer nodes
+//! - **Discovery**: mDNS service registration and browsing for peer nodes, plus static
+//!   peers (`--peer`, `peers.toml`) health-checked over HTTP for networks mDNS cannot span
 //! - **Routing**: Match required_capabilities with local/neighbor capabilities
 //! - **HTTP /task**: Receive NodeTask, execute locally or forward to peer
+//! - **Replication** (opt-in): fetch a peer's skill into `_replicated/` and execute locally
 
 mod discovery;
 pub mod error;
 mod handler;
 mod llm_routing;
+mod replication;
 mod routing;
+mod static_peers;
 mod swarm_auth;
 
-pub use discovery::{Discovery, PeerInfo};
+pub use discovery::{Discovery, PeerInfo, PeerSource};
 pub use error::{Error, Result};
 pub use handler::serve_swarm;
+pub use replication::{SkillReplication, SkillVerifier};
 pub use routing::{capabilities_match, route_task, RouteTarget, TaskExecutor};
+pub use static_peers::{load_static_peers, PEERS_FILE};
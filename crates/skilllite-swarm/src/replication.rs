@@ -0,0 +1,418 @@
+//! Skill replication (`skilllite swarm --allow-skill-replication`).
+//!
+//! When routing would forward a task to a peer advertising the required capability, a node
+//! with replication enabled instead fetches the skill from that peer and runs the task
+//! locally:
+//! - `GET /skill/{name}` serves a skill as a gzipped tar (env / venv / cache dirs excluded),
+//!   with its name and content fingerprint in response headers.
+//! - The receiver unpacks into a staging dir, checks the fingerprint, runs the injected
+//!   [`SkillVerifier`] (the offline `skilllite add` admission scan) and installs into the
+//!   quarantined `<skills>/_replicated/<name>/`. The manifest there records the origin peer
+//!   and content hash. Replicated skills run under the evolved-skill sandbox policy.
+
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use skilllite_core::skill::{manifest, metadata, origin::REPLICATED_DIR};
+
+use crate::discovery::PeerInfo;
+use crate::error::bail;
+use crate::handler::reqwest_swarm_auth;
+use crate::routing::capabilities_match;
+use crate::Result;
+
+/// Response header carrying the served skill's name.
+pub const SKILL_NAME_HEADER: &str = "x-skilllite-skill-name";
+/// Response header carrying the served skill's content fingerprint (manifest hash).
+pub const SKILL_HASH_HEADER: &str = "x-skilllite-skill-hash";
+
+/// Packages larger than this (compressed or unpacked) are refused.
+const MAX_PACKAGE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Same exclusions as the manifest fingerprint, so the hash survives the round trip.
+const EXCLUDED_DIRS: &[&str] = &[
+    ".git",
+    "__pycache__",
+    "node_modules",
+    "dist",
+    "build",
+    ".venv",
+    "venv",
+];
+
+/// Admission check for a fetched skill, implemented by the skilllite binary (the offline
+/// `skilllite add` scan lives in skilllite-commands).
+pub trait SkillVerifier: Send + Sync + std::fmt::Debug {
+    /// Check the unpacked skill at `skill_dir` before it is installed. `Ok` carries the
+    /// admission risk recorded in the manifest; `Err` refuses the skill.
+    fn verify(
+        &self,
+        name: &str,
+        skill_dir: &Path,
+    ) -> std::result::Result<String, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Replication settings; `None` in the daemon state means replication is off.
+#[derive(Debug, Clone)]
+pub struct SkillReplication {
+    /// Skills root; replicated skills are installed under `<root>/_replicated/`.
+    pub skills_root: PathBuf,
+    pub verifier: Arc<dyn SkillVerifier>,
+}
+
+impl SkillReplication {
+    pub fn new(skills_root: impl Into<PathBuf>, verifier: Arc<dyn SkillVerifier>) -> Self {
+        Self {
+            skills_root: skills_root.into(),
+            verifier,
+        }
+    }
+
+    /// `<root>/_replicated/`
+    pub fn quarantine_dir(&self) -> PathBuf {
+        self.skills_root.join(REPLICATED_DIR)
+    }
+
+    /// Fetch the skill for `hint` (tool hint or capability) from `peer`, verify and install
+    /// it. Returns the installed skill dir.
+    pub(crate) async fn replicate_from_peer(
+        &self,
+        peer: &PeerInfo,
+        hint: &str,
+        required: &[String],
+        token: Option<&str>,
+    ) -> Result<PathBuf> {
+        let url = format!(
+            "http://{}/skill/{}?required={}",
+            peer.addr,
+            urlencoding::encode(hint),
+            urlencoding::encode(&required.join(","))
+        );
+        let resp = reqwest_swarm_auth(
+            reqwest::Client::new()
+                .get(&url)
+                .timeout(Duration::from_secs(60)),
+            token,
+        )
+        .send()
+        .await
+        .with_context(|| format!("GET {} failed", url))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            bail!(
+                "Peer refused skill '{}': status={} body={}",
+                hint,
+                status,
+                body
+            );
+        }
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let (Some(name), Some(hash)) = (header(SKILL_NAME_HEADER), header(SKILL_HASH_HEADER))
+        else {
+            bail!(
+                "Peer response for skill '{}' lacks name / hash headers",
+                hint
+            );
+        };
+        if resp.content_length().unwrap_or(0) > MAX_PACKAGE_BYTES {
+            bail!(
+                "Skill package '{}' exceeds {} bytes",
+                name,
+                MAX_PACKAGE_BYTES
+            );
+        }
+        let package = resp.bytes().await.context("Failed to read skill package")?;
+        let source = format!("swarm:{}@{}", peer.instance_name, peer.addr);
+        let this = self.clone();
+        let required = required.to_vec();
+        tokio::task::spawn_blocking(move || {
+            this.install_package(&name, &hash, &package, &source, &required)
+        })
+        .await
+        .map_err(|e| crate::Error::validation(format!("{:?}", e)))?
+    }
+
+    /// Unpack, verify and install a fetched package. Blocking (runs the admission scan).
+    pub(crate) fn install_package(
+        &self,
+        name: &str,
+        expected_hash: &str,
+        package: &[u8],
+        source: &str,
+        required: &[String],
+    ) -> Result<PathBuf> {
+        validate_skill_name(name)?;
+        if package.len() as u64 > MAX_PACKAGE_BYTES {
+            bail!(
+                "Skill package '{}' exceeds {} bytes",
+                name,
+                MAX_PACKAGE_BYTES
+            );
+        }
+        let quarantine = self.quarantine_dir();
+        std::fs::create_dir_all(&quarantine)
+            .with_context(|| format!("Failed to create {}", quarantine.display()))?;
+        let staging = quarantine.join(format!(".staging-{}", uuid::Uuid::new_v4()));
+        let result = self.verify_and_move(name, expected_hash, package, source, required, &staging);
+        let _ = std::fs::remove_dir_all(&staging);
+        result
+    }
+
+    fn verify_and_move(
+        &self,
+        name: &str,
+        expected_hash: &str,
+        package: &[u8],
+        source: &str,
+        required: &[String],
+        staging: &Path,
+    ) -> Result<PathBuf> {
+        let staged = staging.join(name);
+        unpack_skill(package, &staged)?;
+        if !staged.join("SKILL.md").is_file() {
+            bail!("Skill package '{}' has no SKILL.md", name);
+        }
+        let hash = manifest::compute_skill_fingerprint(&staged)?;
+        if !hash.eq_ignore_ascii_case(expected_hash) {
+            bail!(
+                "Skill package '{}' hash mismatch: peer advertised {}, got {}",
+                name,
+                expected_hash,
+                hash
+            );
+        }
+        let meta = metadata::parse_skill_metadata(&staged)?;
+        if !capabilities_match(required, &meta.capabilities) {
+            bail!(
+                "Skill '{}' does not provide the required capabilities {:?}",
+                name,
+                required
+            );
+        }
+        let risk = self
+            .verifier
+            .verify(name, &staged)
+            .map_err(|e| crate::Error::validation(e.to_string()))?;
+
+        let quarantine = self.quarantine_dir();
+        let dest = quarantine.join(name);
+        if dest.exists() {
+            std::fs::remove_dir_all(&dest)
+                .with_context(|| format!("Failed to replace {}", dest.display()))?;
+        }
+        std::fs::rename(&staged, &dest)
+            .with_context(|| format!("Failed to install {}", dest.display()))?;
+        manifest::upsert_installed_skill_with_admission(&quarantine, &dest, source, Some(&risk))?;
+        tracing::info!(
+            skill = %name,
+            source = %source,
+            hash = %hash,
+            admission = %risk,
+            "Replicated skill installed to {}",
+            dest.display()
+        );
+        Ok(dest)
+    }
+}
+
+/// Skill names become directory names under `_replicated/`.
+fn validate_skill_name(name: &str) -> Result<()> {
+    let ok = !name.is_empty()
+        && name.len() <= 128
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !ok {
+        bail!("Invalid skill name: {:?}", name);
+    }
+    Ok(())
+}
+
+/// Resolve `GET /skill/{name}`: a skill called `name` (or in a dir called `name`), else the
+/// first skill whose capabilities cover `required`. Only top-level skills are served —
+/// evolved and replicated ones stay on the node that has them.
+pub(crate) fn find_local_skill(
+    skill_dirs: &[PathBuf],
+    name: &str,
+    required: &[String],
+) -> Option<(String, PathBuf)> {
+    let mut skills = Vec::new();
+    for dir in skill_dirs {
+        if dir.join("SKILL.md").is_file() {
+            skills.push(dir.clone());
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut children: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.join("SKILL.md").is_file())
+            .collect();
+        children.sort();
+        skills.extend(children);
+    }
+    let parsed: Vec<(String, PathBuf, Vec<String>)> = skills
+        .into_iter()
+        .filter_map(|p| {
+            let meta = metadata::parse_skill_metadata(&p).ok()?;
+            let skill_name = if meta.name.is_empty() {
+                p.file_name()?.to_string_lossy().into_owned()
+            } else {
+                meta.name
+            };
+            Some((skill_name, p, meta.capabilities))
+        })
+        .collect();
+    let by_name = parsed
+        .iter()
+        .find(|(n, p, _)| n == name || p.file_name().is_some_and(|f| f == name));
+    let found = by_name.or_else(|| {
+        (!required.is_empty())
+            .then(|| {
+                parsed
+                    .iter()
+                    .find(|(_, _, caps)| capabilities_match(required, caps))
+            })
+            .flatten()
+    })?;
+    Some((found.0.clone(), found.1.clone()))
+}
+
+/// Tar + gzip `skill_dir` (paths relative to it), skipping env / venv / cache dirs and
+/// symlinks.
+pub(crate) fn pack_skill(skill_dir: &Path) -> Result<Vec<u8>> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    append_dir(&mut builder, skill_dir, skill_dir)?;
+    let encoder = builder.into_inner().context("Failed to finish skill tar")?;
+    Ok(encoder
+        .finish()
+        .context("Failed to compress skill package")?)
+}
+
+fn append_dir<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    root: &Path,
+    dir: &Path,
+) -> Result<()> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .flatten()
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let rel = path.strip_prefix(root).unwrap_or(&path);
+        if file_type.is_dir() {
+            let name = entry.file_name();
+            if EXCLUDED_DIRS.iter().any(|d| name == *d) {
+                continue;
+            }
+            append_dir(builder, root, &path)?;
+        } else if file_type.is_file() {
+            builder
+                .append_path_with_name(&path, rel)
+                .with_context(|| format!("Failed to pack {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Unpack a package into `dest`. Only regular files and directories with plain relative
+/// paths are accepted.
+fn unpack_skill(package: &[u8], dest: &Path) -> Result<()> {
+    std::fs::create_dir_all(dest)?;
+    let decoder = flate2::read::GzDecoder::new(package).take(MAX_PACKAGE_BYTES + 1);
+    let mut archive = tar::Archive::new(decoder);
+    let mut unpacked = 0u64;
+    for entry in archive.entries().context("Invalid skill package")? {
+        let mut entry = entry.context("Invalid skill package entry")?;
+        let path = entry.path().context("Invalid path in skill package")?;
+        if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            bail!("Unsafe path in skill package: {}", path.display());
+        }
+        let kind = entry.header().entry_type();
+        if !(kind.is_file() || kind.is_dir()) {
+            bail!("Unsupported entry in skill package: {}", path.display());
+        }
+        unpacked += entry.size();
+        if unpacked > MAX_PACKAGE_BYTES {
+            bail!("Skill package exceeds {} bytes unpacked", MAX_PACKAGE_BYTES);
+        }
+        entry
+            .unpack_in(dest)
+            .context("Failed to unpack skill package")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_skill(dir: &Path, name: &str, capability: &str, script: &str) {
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {name}\ndescription: test\ncapabilities: [{capability}]\n---\n"),
+        )
+        .unwrap();
+        std::fs::write(dir.join("scripts").join("main.py"), script).unwrap();
+    }
+
+    #[test]
+    fn package_round_trip_excludes_env_dirs_and_keeps_hash() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill = tmp.path().join("calc");
+        write_skill(&skill, "calc", "math", "print(3)\n");
+        std::fs::create_dir_all(skill.join(".venv").join("bin")).unwrap();
+        std::fs::write(skill.join(".venv").join("bin").join("python"), "x").unwrap();
+
+        let package = pack_skill(&skill).unwrap();
+        let out = tmp.path().join("out");
+        unpack_skill(&package, &out).unwrap();
+        assert!(out.join("scripts").join("main.py").is_file());
+        assert!(!out.join(".venv").exists());
+        assert_eq!(
+            manifest::compute_skill_fingerprint(&out).unwrap(),
+            manifest::compute_skill_fingerprint(&skill).unwrap()
+        );
+    }
+
+    #[test]
+    fn finds_skill_by_name_then_capability() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(&tmp.path().join("calc"), "calc", "math", "");
+        write_skill(&tmp.path().join("weather"), "weather", "web", "");
+        let dirs = [tmp.path().to_path_buf()];
+        assert_eq!(find_local_skill(&dirs, "calc", &[]).unwrap().0, "calc");
+        assert_eq!(
+            find_local_skill(&dirs, "web", &["web".into()]).unwrap().0,
+            "weather"
+        );
+        assert!(find_local_skill(&dirs, "ml", &["ml".into()]).is_none());
+    }
+
+    #[test]
+    fn rejects_unsafe_names() {
+        assert!(validate_skill_name("calc-2.v1").is_ok());
+        for bad in ["", "../etc", ".hidden", "a/b"] {
+            assert!(validate_skill_name(bad).is_err(), "{bad}");
+        }
+    }
+}
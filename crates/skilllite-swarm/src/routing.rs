@@ -58,19 +58,23 @@ fn matching_peers(required: &[String], peers: &[PeerInfo]) -> Vec<PeerInfo> {
         }
     }
     let mut matching: Vec<_> = by_port.into_values().collect();
-    // Sort: prefer 127.0.0.1 first, then by addr for stable order
+    // Sort: lowest measured latency first, then prefer 127.0.0.1, then by addr for stable order
     matching.sort_by(|a, b| {
         let a_loopback = a.addr.starts_with("127.");
         let b_loopback = b.addr.starts_with("127.");
-        match (a_loopback, b_loopback) {
-            (true, false) => std::cmp::Ordering::Less, // loopback first
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.addr.cmp(&b.addr),
-        }
+        latency_key(a)
+            .cmp(&latency_key(b))
+            .then(b_loopback.cmp(&a_loopback)) // loopback first
+            .then_with(|| a.addr.cmp(&b.addr))
     });
     matching
 }
 
+/// Sort key for latency preference: peers without a measurement (mDNS) go after measured ones.
+fn latency_key(peer: &PeerInfo) -> u64 {
+    peer.latency_ms.unwrap_or(u64::MAX)
+}
+
 /// Decide routing for a NodeTask given local capabilities and discovered peers.
 ///
 /// **Skill sharing**: When `required_capabilities` is empty, prefer forwarding to a peer
@@ -104,9 +108,14 @@ pub fn route_task(
         .collect();
 
     if !local_has_caps && !peers_with_caps.is_empty() {
-        // Prefer peer with most capabilities; dedupe by addr (same port = same node, avoid stale mDNS)
+        // Prefer peer with most capabilities, then lower latency; dedupe by addr (same port = same node, avoid stale mDNS)
         let mut sorted: Vec<_> = peers_with_caps;
-        sorted.sort_by_key(|peer| std::cmp::Reverse(peer.capabilities.len()));
+        sorted.sort_by_key(|peer| {
+            (
+                std::cmp::Reverse(peer.capabilities.len()),
+                latency_key(peer),
+            )
+        });
         let mut seen_addr = std::collections::HashSet::new();
         let deduped: Vec<_> = sorted
             .into_iter()
@@ -173,6 +182,7 @@ mod tests {
             instance_name: "peer1".into(),
             addr: "127.0.0.1:7701".into(),
             capabilities: vec!["calc".into()],
+            ..Default::default()
         }];
         let target = route_task(&task, &local, &peers);
         assert!(
@@ -200,11 +210,13 @@ mod tests {
                 instance_name: "peer-lan".into(),
                 addr: "10.55.157.245:7701".into(),
                 capabilities: vec!["calc".into()],
+                ..Default::default()
             },
             PeerInfo {
                 instance_name: "peer-loopback".into(),
                 addr: "127.0.0.1:7701".into(),
                 capabilities: vec!["calc".into(), "math".into()],
+                ..Default::default()
             },
         ];
         let target = route_task(&task, &local, &peers);
@@ -218,6 +230,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_matching_peers_prefer_lower_latency() {
+        let task = NodeTask {
+            id: "t1".into(),
+            description: "1+1=?".into(),
+            context: skilllite_core::protocol::NodeContext {
+                workspace: ".".into(),
+                session_key: "test".into(),
+                required_capabilities: vec!["calc".into()],
+            },
+            tool_hint: None,
+        };
+        let peer = |name: &str, addr: &str, latency_ms: Option<u64>| PeerInfo {
+            instance_name: name.into(),
+            addr: addr.into(),
+            capabilities: vec!["calc".into()],
+            latency_ms,
+            ..Default::default()
+        };
+        let peers = vec![
+            peer("mdns", "127.0.0.1:7701", None),
+            peer("vm", "203.0.113.7:7700", Some(80)),
+            peer("office", "10.1.2.3:7702", Some(12)),
+        ];
+        let RouteTarget::Forward(v) = route_task(&task, &[], &peers) else {
+            panic!("expected Forward")
+        };
+        let order: Vec<_> = v.iter().map(|p| p.instance_name.as_str()).collect();
+        assert_eq!(order, ["office", "vm", "mdns"]);
+    }
+
     #[test]
     fn test_route_skill_sharing_empty_required_local_has_caps() {
         let task = NodeTask {
@@ -235,6 +278,7 @@ mod tests {
             instance_name: "peer1".into(),
             addr: "127.0.0.1:7701".into(),
             capabilities: vec!["calc".into(), "web".into()],
+            ..Default::default()
         }];
         let target = route_task(&task, &local, &peers);
         assert!(matches!(target, RouteTarget::Local));
@@ -0,0 +1,398 @@
+//! Static peers — nodes mDNS cannot see (other subnets, cloud VMs), configured with
+//! `--peer host:port` and/or `<data_root>/peers.toml`.
+//!
+//! There is no TXT record for these, so capabilities come from a `GET /capabilities`
+//! handshake that doubles as the health check. The host is re-resolved on every check
+//! (DNS changes are picked up), unreachable peers are retried with exponential backoff and
+//! left out of routing until they answer again.
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use skilllite_core::config::env_keys::swarm;
+
+use crate::discovery::{PeerInfo, PeerSource};
+use crate::error::bail;
+use crate::Result;
+
+/// Peer list file under the data root: `peers = ["10.0.0.5:7700", "vm.example.com:7700"]`.
+pub const PEERS_FILE: &str = "peers.toml";
+
+const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Upper bound between two passes over the table, so due peers are not checked late.
+const MAX_TICK: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize)]
+struct PeersFile {
+    #[serde(default)]
+    peers: Vec<String>,
+}
+
+/// Validate a `host:port` peer address.
+pub fn parse_peer_spec(spec: &str) -> Result<String> {
+    let spec = spec.trim();
+    let port_ok = spec
+        .rsplit_once(':')
+        .filter(|(host, _)| !host.is_empty())
+        .and_then(|(_, port)| port.parse::<u16>().ok())
+        .is_some_and(|port| port > 0);
+    if !port_ok {
+        bail!("Invalid peer address: expected host:port, got {:?}", spec);
+    }
+    Ok(spec.to_string())
+}
+
+/// `--peer` flags followed by the entries of `<data_root>/peers.toml` (if present), deduplicated.
+pub fn load_static_peers(cli_peers: &[String], data_root: &Path) -> Result<Vec<String>> {
+    let mut specs = Vec::new();
+    for spec in cli_peers {
+        specs.push(parse_peer_spec(spec)?);
+    }
+    let path = data_root.join(PEERS_FILE);
+    if path.is_file() {
+        let text = std::fs::read_to_string(&path)?;
+        let file: PeersFile = match toml::from_str(&text) {
+            Ok(f) => f,
+            Err(e) => bail!("Invalid {}: {}", path.display(), e),
+        };
+        for spec in &file.peers {
+            specs.push(parse_peer_spec(spec)?);
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    specs.retain(|s| seen.insert(s.clone()));
+    Ok(specs)
+}
+
+/// Health-check timing.
+#[derive(Debug, Clone)]
+pub struct HealthConfig {
+    /// Delay between checks of a healthy peer; also the first backoff step.
+    pub interval: Duration,
+    /// Cap for the exponential backoff of an unreachable peer.
+    pub max_backoff: Duration,
+    /// Per-address handshake timeout.
+    pub timeout: Duration,
+}
+
+impl HealthConfig {
+    /// Interval from `SKILLLITE_SWARM_PEER_CHECK_SECS` (default 30s).
+    pub fn from_env() -> Self {
+        let interval = std::env::var(swarm::SKILLLITE_SWARM_PEER_CHECK_SECS)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL);
+        Self {
+            interval,
+            max_backoff: MAX_BACKOFF.max(interval),
+            timeout: HANDSHAKE_TIMEOUT,
+        }
+    }
+}
+
+/// Health of a static peer as shown by `GET /peers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerStatus {
+    /// Not checked yet.
+    Pending,
+    Up,
+    Down,
+}
+
+/// A configured peer and its health-check state.
+#[derive(Debug, Clone)]
+pub struct StaticPeer {
+    /// Address as configured (`host:port`); re-resolved on every check.
+    pub spec: String,
+    /// Last handshake result; `addr` is the resolved address that answered.
+    pub peer: PeerInfo,
+    pub status: PeerStatus,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    next_check: Instant,
+}
+
+impl StaticPeer {
+    pub fn new(spec: &str) -> Self {
+        Self {
+            spec: spec.to_string(),
+            peer: PeerInfo {
+                addr: spec.to_string(),
+                source: PeerSource::Static,
+                ..Default::default()
+            },
+            status: PeerStatus::Pending,
+            consecutive_failures: 0,
+            last_error: None,
+            next_check: Instant::now(),
+        }
+    }
+
+    /// Delay before the next check after `consecutive_failures` failures in a row.
+    fn backoff(&self, config: &HealthConfig) -> Duration {
+        let exp = self.consecutive_failures.saturating_sub(1).min(16);
+        config
+            .interval
+            .saturating_mul(1 << exp)
+            .min(config.max_backoff)
+    }
+}
+
+pub(crate) type StaticPeerTable = Arc<Mutex<Vec<StaticPeer>>>;
+
+pub(crate) fn new_table(specs: &[String]) -> StaticPeerTable {
+    Arc::new(Mutex::new(
+        specs.iter().map(|s| StaticPeer::new(s)).collect(),
+    ))
+}
+
+/// Body of `GET /capabilities`.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CapabilitiesResponse {
+    pub instance_name: String,
+    pub capabilities: Vec<String>,
+}
+
+struct Handshake {
+    addr: SocketAddr,
+    response: CapabilitiesResponse,
+    latency: Duration,
+}
+
+/// Resolve `spec` and handshake with each address until one answers.
+async fn handshake(
+    client: &reqwest::Client,
+    spec: &str,
+    token: Option<&str>,
+    timeout: Duration,
+) -> std::result::Result<Handshake, String> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host(spec)
+        .await
+        .map_err(|e| format!("resolve failed: {}", e))?
+        .collect();
+    let mut last_err = format!("{} resolved to no address", spec);
+    for addr in addrs {
+        let url = format!("http://{}/capabilities", addr);
+        let start = Instant::now();
+        let sent = crate::handler::reqwest_swarm_auth(client.get(&url).timeout(timeout), token)
+            .send()
+            .await;
+        match sent {
+            Ok(resp) if resp.status().is_success() => {
+                match resp.json::<CapabilitiesResponse>().await {
+                    Ok(response) => {
+                        return Ok(Handshake {
+                            addr,
+                            response,
+                            latency: start.elapsed(),
+                        })
+                    }
+                    Err(e) => last_err = format!("{}: invalid handshake: {}", addr, e),
+                }
+            }
+            Ok(resp) => last_err = format!("{}: status {}", addr, resp.status()),
+            Err(e) => last_err = format!("{}: {}", addr, e),
+        }
+    }
+    Err(last_err)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Check every peer whose next check is due at `now` and record the results.
+pub(crate) async fn check_due(
+    table: &StaticPeerTable,
+    client: &reqwest::Client,
+    token: Option<&str>,
+    config: &HealthConfig,
+    self_instance: &str,
+    now: Instant,
+) {
+    let due: Vec<String> = match table.lock() {
+        Ok(t) => t
+            .iter()
+            .filter(|p| p.next_check <= now)
+            .map(|p| p.spec.clone())
+            .collect(),
+        Err(_) => return,
+    };
+    if due.is_empty() {
+        return;
+    }
+    let results = futures_util::future::join_all(
+        due.iter()
+            .map(|spec| handshake(client, spec, token, config.timeout)),
+    )
+    .await;
+
+    let Ok(mut t) = table.lock() else { return };
+    for (spec, result) in due.iter().zip(results) {
+        let Some(entry) = t.iter_mut().find(|p| &p.spec == spec) else {
+            continue;
+        };
+        let result = result.and_then(|h| {
+            if h.response.instance_name == self_instance {
+                Err(format!("{} is this node", spec))
+            } else {
+                Ok(h)
+            }
+        });
+        match result {
+            Ok(h) => {
+                let addr = h.addr.to_string();
+                if entry.status == PeerStatus::Up && entry.peer.addr != addr {
+                    tracing::info!(peer = %spec, from = %entry.peer.addr, to = %addr, "Static peer re-resolved");
+                }
+                if entry.status != PeerStatus::Up {
+                    tracing::info!(
+                        peer = %spec,
+                        instance = %h.response.instance_name,
+                        addr = %addr,
+                        capabilities = ?h.response.capabilities,
+                        "Static peer reachable"
+                    );
+                }
+                entry.peer = PeerInfo {
+                    instance_name: h.response.instance_name,
+                    addr,
+                    capabilities: h.response.capabilities,
+                    source: PeerSource::Static,
+                    latency_ms: Some(h.latency.as_millis() as u64),
+                    last_seen: Some(unix_now()),
+                };
+                entry.status = PeerStatus::Up;
+                entry.consecutive_failures = 0;
+                entry.last_error = None;
+                entry.next_check = now + config.interval;
+            }
+            Err(e) => {
+                entry.consecutive_failures += 1;
+                let backoff = entry.backoff(config);
+                if entry.status != PeerStatus::Down {
+                    tracing::warn!(peer = %spec, err = %e, retry_in_secs = backoff.as_secs(), "Static peer unreachable");
+                } else {
+                    tracing::debug!(peer = %spec, err = %e, failures = entry.consecutive_failures, "Static peer still unreachable");
+                }
+                entry.status = PeerStatus::Down;
+                entry.peer.latency_ms = None;
+                entry.last_error = Some(e);
+                entry.next_check = now + backoff;
+            }
+        }
+    }
+}
+
+/// Health-check loop for the lifetime of the daemon.
+pub(crate) async fn run_health_checks(
+    table: StaticPeerTable,
+    token: Option<Arc<str>>,
+    config: HealthConfig,
+    self_instance: String,
+) {
+    let client = reqwest::Client::new();
+    let tick = config.interval.min(MAX_TICK);
+    loop {
+        check_due(
+            &table,
+            &client,
+            token.as_deref(),
+            &config,
+            &self_instance,
+            Instant::now(),
+        )
+        .await;
+        tokio::time::sleep(tick).await;
+    }
+}
+
+/// Routing view: mDNS peers plus static peers whose last check succeeded. A node that is
+/// both announced via mDNS and configured statically appears once, as the static entry
+/// (it carries the measured latency).
+pub(crate) fn merge_peers(mdns: &[PeerInfo], statics: &[StaticPeer]) -> Vec<PeerInfo> {
+    let up: Vec<&PeerInfo> = statics
+        .iter()
+        .filter(|p| p.status == PeerStatus::Up)
+        .map(|p| &p.peer)
+        .collect();
+    let mut merged: Vec<PeerInfo> = mdns
+        .iter()
+        .filter(|m| !up.iter().any(|s| s.instance_name == m.instance_name))
+        .cloned()
+        .collect();
+    merged.extend(up.into_iter().cloned());
+    merged
+}
+
+/// `GET /peers` entries: every mDNS peer and every configured static peer, reachable or not.
+pub(crate) fn peer_listing(mdns: &[PeerInfo], statics: &[StaticPeer]) -> Vec<serde_json::Value> {
+    let mut out: Vec<serde_json::Value> = mdns
+        .iter()
+        .map(|p| {
+            let mut v = serde_json::to_value(p).unwrap_or_default();
+            v["status"] = serde_json::json!(PeerStatus::Up);
+            v
+        })
+        .collect();
+    out.extend(statics.iter().map(|p| {
+        let mut v = serde_json::to_value(&p.peer).unwrap_or_default();
+        v["status"] = serde_json::json!(p.status);
+        v["configured_addr"] = serde_json::json!(p.spec);
+        v["consecutive_failures"] = serde_json::json!(p.consecutive_failures);
+        v["last_error"] = serde_json::json!(p.last_error);
+        v
+    }));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peer_specs_merge_flags_and_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(PEERS_FILE),
+            "peers = [\"vm.example.com:7700\", \"10.0.0.5:7700\"]\n",
+        )
+        .unwrap();
+        let specs = load_static_peers(&["10.0.0.5:7700".to_string()], dir.path()).unwrap();
+        assert_eq!(specs, ["10.0.0.5:7700", "vm.example.com:7700"]);
+
+        assert!(parse_peer_spec("vm.example.com").is_err());
+        assert!(parse_peer_spec(":7700").is_err());
+        assert!(parse_peer_spec("host:0").is_err());
+        assert!(load_static_peers(&[], &dir.path().join("missing"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let config = HealthConfig {
+            interval: Duration::from_secs(30),
+            max_backoff: Duration::from_secs(600),
+            timeout: HANDSHAKE_TIMEOUT,
+        };
+        let mut peer = StaticPeer::new("vm.example.com:7700");
+        let mut delays = Vec::new();
+        for failures in 1..=6 {
+            peer.consecutive_failures = failures;
+            delays.push(peer.backoff(&config).as_secs());
+        }
+        assert_eq!(delays, [30, 60, 120, 240, 480, 600]);
+    }
+}
@@ -204,21 +204,27 @@ class IPCClient:
     def run(
         self,
         skill_dir: str,
-        input_json: str,
+        input_json: str | None = None,
         *,
+        input_path: str | None = None,
         sandbox_level: int = 3,
         allow_network: bool = False,
     ) -> dict[str, Any]:
-        """Run a skill. Returns {output, exit_code}."""
-        return self._request(
-            "run",
-            {
-                "skill_dir": skill_dir,
-                "input_json": input_json,
-                "allow_network": allow_network,
-                "sandbox_level": sandbox_level,
-            },
-        )
+        """Run a skill. Returns {output, exit_code}.
+
+        Pass ``input_path`` instead of ``input_json`` to have the daemon read a large
+        input from disk rather than sending it over the pipe.
+        """
+        params: dict[str, Any] = {
+            "skill_dir": skill_dir,
+            "allow_network": allow_network,
+            "sandbox_level": sandbox_level,
+        }
+        if input_path is not None:
+            params["input_path"] = input_path
+        else:
+            params["input_json"] = input_json if input_json is not None else "{}"
+        return self._request("run", params)
 
     def exec(
         self,
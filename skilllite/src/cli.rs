@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 /// SkillBox - A lightweight Skills secure execution engine
 #[derive(Parser, Debug)]
@@ -21,10 +21,17 @@ pub enum Commands {
         #[arg(value_name = "SKILL_DIR")]
         skill_dir: Option<String>,
 
-        /// Input JSON string (required for skill mode)
+        /// Input JSON string (required for skill mode unless --input-file). Use "-" to read from stdin
         #[arg(value_name = "INPUT_JSON")]
         input_json: Option<String>,
 
+        #[command(flatten)]
+        io: SkillIoArgs,
+
+        /// Named command from the skill's SKILL.md `entry_points` to run
+        #[arg(long, value_name = "NAME")]
+        entry: Option<String>,
+
         /// [Agent run] Path to SOUL.md identity document
         #[arg(long)]
         soul: Option<String>,
@@ -53,6 +60,14 @@ pub enum Commands {
         #[arg(long)]
         sandbox_level: Option<u8>,
 
+        /// Audit mode: run unconfined and report what the sandbox level would have blocked
+        #[arg(long)]
+        audit: bool,
+
+        /// Always execute, ignoring the result cache of skills declaring SKILL.md `cache:`
+        #[arg(long)]
+        no_cache: bool,
+
         /// [Agent run] Workspace directory (default: current directory)
         #[arg(long, short)]
         workspace: Option<String>,
@@ -69,9 +84,36 @@ pub enum Commands {
         #[arg(long)]
         max_failures: Option<usize>,
 
-        /// [Agent run] Resume from last checkpoint (A13: 断点续跑)
+        /// [Agent run] Resume a checkpoint by id (or unique id prefix); without an id, the
+        /// latest checkpoint of the current workspace (A13: 断点续跑)
+        #[arg(long, value_name = "ID", num_args = 0..=1)]
+        resume: Option<Option<String>>,
+
+        /// [Agent run] List checkpoints that can be resumed, with task progress
+        #[arg(long, conflicts_with_all = ["goal", "resume", "discard"])]
+        list_resumable: bool,
+
+        /// [Agent run] Delete a checkpoint by id (or unique id prefix)
+        #[arg(long, value_name = "ID", conflicts_with_all = ["goal", "resume"])]
+        discard: Option<String>,
+
+        /// [Agent run] Stop (with a checkpoint) once estimated spend reaches this many USD.
+        /// Needs the model's price in SKILLLITE_MODEL_PRICES_JSON
+        #[arg(long, value_name = "USD")]
+        max_cost: Option<f64>,
+
+        /// [Agent run] Stop (with a checkpoint) once LLM usage reaches this many tokens (0 = no limit)
         #[arg(long)]
-        resume: bool,
+        max_tokens: Option<u64>,
+
+        /// [Agent run] Result format: text, or json for one result envelope on stdout
+        /// (progress stays on stderr)
+        #[arg(long, value_parser = ["text", "json"])]
+        output: Option<String>,
+
+        /// [Agent run] Treat an empty or whitespace-only final response as a failure
+        #[arg(long)]
+        fail_on_empty: bool,
     },
 
     /// Execute a specific script directly in sandbox (no SKILL.md entry_point required)
@@ -85,8 +127,11 @@ pub enum Commands {
         script_path: String,
 
         /// Input JSON string. Use "-" to read from stdin (for large input > ARG_MAX)
-        #[arg(value_name = "INPUT_JSON")]
-        input_json: String,
+        #[arg(value_name = "INPUT_JSON", required_unless_present = "input_file")]
+        input_json: Option<String>,
+
+        #[command(flatten)]
+        io: SkillIoArgs,
 
         /// Script arguments (passed as command line args)
         #[arg(long, value_name = "ARGS")]
@@ -111,6 +156,10 @@ pub enum Commands {
         /// Sandbox level: 1=no sandbox, 2=sandbox only, 3=sandbox+scan (default: from env or 3)
         #[arg(long)]
         sandbox_level: Option<u8>,
+
+        /// Audit mode: run unconfined and report what the sandbox level would have blocked
+        #[arg(long)]
+        audit: bool,
     },
 
     /// Scan skill directory and list all executable scripts (JSON output for LLM analysis)
@@ -122,6 +171,20 @@ pub enum Commands {
         /// Include file content preview (first N lines)
         #[arg(long, default_value = "10")]
         preview_lines: usize,
+
+        /// Output format: json (default, script listing) or sarif (security findings of all
+        /// scripts in one SARIF 2.1.0 run)
+        #[arg(long, default_value = "json", value_parser = ["json", "sarif"])]
+        format: String,
+
+        /// Infer `capabilities` from the scripts' imports and API hosts and print a
+        /// ready-to-paste SKILL.md front matter line
+        #[arg(long)]
+        suggest_capabilities: bool,
+
+        /// With --suggest-capabilities: merge the suggestions into SKILL.md
+        #[arg(long, requires = "suggest_capabilities")]
+        write: bool,
     },
 
     /// Validate a skill without running it
@@ -159,6 +222,10 @@ pub enum Commands {
         /// Output results as structured JSON (default: false)
         #[arg(long, default_value = "false")]
         json: bool,
+
+        /// Output format: text, json or sarif (SARIF 2.1.0 for CI code scanning)
+        #[arg(long, value_parser = ["text", "json", "sarif"], conflicts_with = "json")]
+        format: Option<String>,
     },
 
     /// Execute a bash command for a bash-tool skill (validates against allowed-tools pattern)
@@ -248,6 +315,11 @@ pub enum Commands {
         #[arg(long)]
         no_plan: bool,
 
+        /// Show the task plan and wait for approve / edit / remove / add / reject before
+        /// executing (interactive chat only; same as SKILLLITE_APPROVE_PLAN)
+        #[arg(long, conflicts_with_all = ["no_plan", "message"])]
+        approve_plan: bool,
+
         /// Disable memory tools (default: memory enabled)
         #[arg(long)]
         no_memory: bool,
@@ -256,6 +328,29 @@ pub enum Commands {
         /// Resolution order: --soul > .skilllite/SOUL.md > ~/.skilllite/SOUL.md
         #[arg(long)]
         soul: Option<String>,
+
+        /// Record every LLM request/response to numbered JSON files in this directory
+        /// (same as SKILLLITE_LLM_RECORD)
+        #[arg(long, conflicts_with = "llm_replay")]
+        llm_record: Option<String>,
+
+        /// Answer LLM calls from a recording directory instead of the API
+        /// (same as SKILLLITE_LLM_REPLAY)
+        #[arg(long)]
+        llm_replay: Option<String>,
+
+        /// [Replay] Match recorded requests on message count only (no request hash check)
+        #[arg(long, requires = "llm_replay")]
+        loose: bool,
+
+        /// [Single-shot] Result format: text, or json for one result envelope on stdout
+        /// (progress stays on stderr)
+        #[arg(long, value_parser = ["text", "json"], requires = "message")]
+        output: Option<String>,
+
+        /// [Single-shot] Treat an empty or whitespace-only final response as a failure
+        #[arg(long, requires = "message")]
+        fail_on_empty: bool,
     },
 
     // ─── Phase 3: CLI Migration Commands (flat, no nesting) ────────────
@@ -266,10 +361,13 @@ pub enum Commands {
     ///   skilllite add owner/repo
     ///   skilllite add https://github.com/owner/repo
     ///   skilllite add ./local/path
+    ///   skilllite add --list pdf --capability ocr
+    ///   skilllite add --show <skill-name>
     Add {
-        /// Skill source: owner/repo, GitHub URL, git URL, or local path
-        #[arg(value_name = "SOURCE")]
-        source: String,
+        /// Skill source: owner/repo, GitHub URL, git URL, or local path.
+        /// With --list, a plain word searches the registry index instead.
+        #[arg(value_name = "SOURCE", required_unless_present_any = ["list", "show"])]
+        source: Option<String>,
 
         /// Skills directory path (default: skills)
         #[arg(long, short = 's', default_value = "skills")]
@@ -279,16 +377,36 @@ pub enum Commands {
         #[arg(long, short)]
         force: bool,
 
-        /// List available skills without installing
+        /// List available skills without installing (no SOURCE or a search query: the registry index)
         #[arg(long, short)]
         list: bool,
 
+        /// [Registry] Only skills tagged with this capability (repeatable)
+        #[arg(long = "capability", value_name = "TAG", requires = "list")]
+        capabilities: Vec<String>,
+
+        /// [Registry] Results per page
+        #[arg(long, default_value_t = 20, requires = "list")]
+        limit: usize,
+
+        /// [Registry] Page number (1-based)
+        #[arg(long, default_value_t = 1, requires = "list")]
+        page: usize,
+
+        /// [Registry] Print a registry skill's SKILL.md for review (untrusted, not installed)
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["list", "source"])]
+        show: Option<String>,
+
+        /// [Registry] Output as JSON
+        #[arg(long)]
+        json: bool,
+
         /// Offline scan only: skip LLM analysis and network dependency audit
         #[arg(long)]
         scan_offline: bool,
     },
 
-    /// Remove an installed skill
+    /// Remove an installed skill with its cached environment, scan-cache and manifest entries
     Remove {
         /// Name of the skill to remove
         #[arg(value_name = "SKILL_NAME")]
@@ -301,6 +419,14 @@ pub enum Commands {
         /// Skip confirmation prompt
         #[arg(long, short)]
         force: bool,
+
+        /// Keep the skill's cached environment (venv / node_modules)
+        #[arg(long)]
+        keep_env: bool,
+
+        /// List everything that would be removed without touching anything
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// List all installed skills
@@ -327,7 +453,8 @@ pub enum Commands {
         #[arg(long, short = 's', default_value = "skills")]
         skills_dir: String,
 
-        /// Output format: openai (default) or claude
+        /// Output format: openai (default), claude, or markdown (a tool catalog for another
+        /// agent's system prompt)
         #[arg(long, default_value = "openai")]
         format: String,
     },
@@ -345,6 +472,10 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Include SKILL.md body, generated tool definitions, entry scripts and provenance
+        #[arg(long)]
+        full: bool,
     },
 
     /// Verify skill integrity (fingerprint/signature) by skill name or path
@@ -361,9 +492,19 @@ pub enum Commands {
         #[arg(long)]
         json: bool,
 
-        /// Strict mode: return non-zero when HASH_CHANGED or SIGNATURE_INVALID
+        /// Strict mode: return non-zero when HASH_CHANGED or SIGNATURE_INVALID, or when a
+        /// `skilllite-allow:` suppression comment has no `reason="..."`
         #[arg(long)]
         strict: bool,
+
+        /// Check the skill's installed environment against its skill.lock (non-zero on
+        /// CONFLICT or DRIFT)
+        #[arg(long)]
+        deps: bool,
+
+        /// Regenerate skill.lock from the declared dependencies before checking
+        #[arg(long, requires = "deps")]
+        relock: bool,
     },
 
     /// Import skills from OpenClaw-style directories (workspace skills/, ~/.openclaw/skills, ~/.agents/skills, …).
@@ -456,19 +597,55 @@ pub enum Commands {
         force: bool,
     },
 
+    /// Write an MCP client config that runs `skilllite mcp --skills-dir <abs path>`
+    ///
+    /// Targets: cursor (.cursor/mcp.json), opencode (opencode.json), claude-code (.mcp.json,
+    /// or ~/.claude.json with --global), vscode (.vscode/settings.json `mcp.servers`, or the
+    /// user settings.json with --global), generic (prints a JSON snippet).
+    /// Existing files are merged: other servers are kept and only the `skilllite` entry is
+    /// added or updated. A conflicting entry is shown as a diff unless --force is given.
+    #[command(name = "init-ide")]
+    InitIde {
+        /// cursor | opencode | claude-code | vscode | generic
+        #[arg(long, short = 't')]
+        target: String,
+
+        /// Project directory (default: current directory)
+        #[arg(long, short = 'p')]
+        project_dir: Option<String>,
+
+        /// Skills directory path (default: ./skills)
+        #[arg(long, short = 's', default_value = "./skills")]
+        skills_dir: String,
+
+        /// Write to the user-level config instead of the project
+        #[arg(long, short = 'g')]
+        global: bool,
+
+        /// Replace a conflicting `skilllite` entry
+        #[arg(long, short)]
+        force: bool,
+    },
+
     /// Audit skill dependencies for known vulnerabilities
     ///
-    /// Parses requirements.txt / package.json and queries vulnerability databases.
+    /// Parses requirements.txt / package.json and lockfiles (package-lock.json, poetry.lock,
+    /// pip-compile requirements.txt) and queries vulnerability databases.
     /// Python packages use PyPI JSON API; npm packages use OSV.dev batch API.
+    /// Responses are cached under the skilllite cache dir.
+    ///
+    /// Exit codes: 0 clean, 1 vulnerabilities found, 3 audit incomplete (unverified packages).
     ///
     /// Environment variables:
     ///   SKILLLITE_AUDIT_API  — Custom security API (overrides all backends)
     ///   PYPI_MIRROR_URL     — PyPI mirror (default: https://pypi.org)
     ///   OSV_API_URL         — OSV API for npm (default: https://api.osv.dev)
+    ///   SKILLLITE_AUDIT_CACHE_TTL_SECS — Cache freshness in seconds (default: 86400)
     ///
     /// Examples:
     ///   skilllite dependency-audit ./my-skill
     ///   skilllite dependency-audit ./my-skill --json
+    ///   skilllite dependency-audit ./my-skill --offline
     ///   PYPI_MIRROR_URL=https://pypi.tuna.tsinghua.edu.cn skilllite dependency-audit ./my-skill
     ///   SKILLLITE_AUDIT_API=https://api.mycompany.com skilllite dependency-audit ./my-skill
     #[cfg(feature = "audit")]
@@ -481,6 +658,14 @@ pub enum Commands {
         /// Output results as structured JSON
         #[arg(long, default_value = "false")]
         json: bool,
+
+        /// Output format: text, json or sarif (SARIF 2.1.0 for CI code scanning)
+        #[arg(long, value_parser = ["text", "json", "sarif"], conflicts_with = "json")]
+        format: Option<String>,
+
+        /// Use only the local audit cache; uncached packages are reported as unverified
+        #[arg(long, default_value = "false")]
+        offline: bool,
     },
 
     /// Summarize audit JSONL: per-skill invocations, failure rates, edit path distribution; optional alerts
@@ -517,6 +702,30 @@ pub enum Commands {
         /// Force removal without confirmation
         #[arg(long, short)]
         force: bool,
+
+        /// Only remove shared environments not referenced by any installed skill
+        #[arg(long)]
+        unused: bool,
+
+        /// Remove cached skill results (SKILL.md `cache:`) instead of environments
+        #[arg(long, conflicts_with = "unused")]
+        results: bool,
+    },
+
+    /// Remove old files from the output directory (write_output artifacts)
+    #[command(name = "clean-output")]
+    CleanOutput {
+        /// Age threshold, e.g. `30d`, `2w`, `12h` (bare number = days)
+        #[arg(long, default_value = "30d")]
+        older_than: String,
+
+        /// Dry run — show what would be removed without deleting
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output directory (default: SKILLLITE_OUTPUT_DIR or {workspace}/output)
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<String>,
     },
 
     /// Reindex skills — rescan skills directory and rebuild metadata cache
@@ -532,6 +741,15 @@ pub enum Commands {
         /// Rebuild .skilllite-manifest.json for existing skills
         #[arg(long)]
         rebuild_manifest: bool,
+
+        /// Resolve duplicate skill names across skills dirs: keep-newest, keep-first or interactive
+        #[arg(long, value_name = "STRATEGY")]
+        resolve: Option<String>,
+
+        /// Re-embed memory files into the vector index with the configured embedding backend
+        /// (run after changing SKILLLITE_EMBEDDING_BACKEND / model)
+        #[arg(long, conflicts_with_all = ["rebuild_manifest", "resolve"])]
+        memory: bool,
     },
 
     /// Manage the project Repo Wiki (`.skilllite/wiki/`, Markdown-only)
@@ -551,6 +769,7 @@ pub enum Commands {
     ///   skilllite init --skip-deps
     ///   skilllite init --strict
     ///   skilllite init --force
+    ///   skilllite init --from ./my-scripts --dry-run
     Init {
         /// Skills directory path (default: skills)
         #[arg(long, short = 's', default_value = "skills")]
@@ -568,11 +787,21 @@ pub enum Commands {
         #[arg(long)]
         strict: bool,
 
-        /// Force re-resolve and update dependencies (ignore .skilllite.lock)
+        /// Force re-resolve and update dependencies (ignore .skilllite.lock);
+        /// with --from, overwrite an existing SKILL.md
         #[arg(long)]
         force: bool,
 
-        /// Use LLM to resolve dependencies from compatibility string (requires agent feature, API key)
+        /// Generate SKILL.md for an existing directory of scripts instead of setting up a project
+        #[arg(long, value_name = "DIR")]
+        from: Option<String>,
+
+        /// With --from: print the generated SKILL.md instead of writing it
+        #[arg(long, requires = "from")]
+        dry_run: bool,
+
+        /// Use LLM to resolve dependencies from compatibility string (requires agent feature, API key);
+        /// with --from, draft the description and parameter schema from the script source
         #[cfg(feature = "agent")]
         #[arg(long)]
         use_llm: bool,
@@ -581,20 +810,42 @@ pub enum Commands {
     /// Quick start — auto-detect LLM, setup skills, and launch chat (requires agent feature)
     ///
     /// Zero-config flow:
-    ///   1. Detect existing .env or probe local Ollama
-    ///   2. Interactive LLM provider selection if needed
+    ///   1. Review ~/.skilllite/config.toml, detect existing .env, or probe local Ollama
+    ///   2. Interactive LLM provider selection if needed (validated, then saved to config.toml)
     ///   3. Ensure skills are available
     ///   4. Launch interactive chat
     ///
     /// Examples:
     ///   skilllite quickstart
     ///   skilllite quickstart --skills-dir ./my-skills
+    ///   skilllite quickstart --non-interactive --provider deepseek --api-key sk-...
     #[cfg(feature = "agent")]
     #[command(name = "quickstart")]
     Quickstart {
         /// Skills directory path (default: skills)
         #[arg(long, short = 's', default_value = "skills")]
         skills_dir: String,
+        /// Apply the flags below to config.toml, validate and exit (no prompts, no chat)
+        #[arg(long)]
+        non_interactive: bool,
+        /// Provider preset: openai, deepseek, ollama, moonshot, qwen, custom
+        #[arg(long)]
+        provider: Option<String>,
+        /// OpenAI-compatible API base URL (overrides the preset)
+        #[arg(long)]
+        api_base: Option<String>,
+        /// API key
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Default model (default: inferred from the API base)
+        #[arg(long)]
+        model: Option<String>,
+        /// Default workspace directory
+        #[arg(long)]
+        workspace: Option<String>,
+        /// Save without the live 1-token completion check
+        #[arg(long)]
+        skip_validation: bool,
     },
 
     /// Clear session (OpenClaw-style): summarize to memory, archive transcript, reset counts.
@@ -611,6 +862,31 @@ pub enum Commands {
         workspace: String,
     },
 
+    /// List, create, rename, or delete chat sessions (desktop UI)
+    ///
+    /// Examples:
+    ///   skilllite sessions list --json
+    ///   skilllite sessions create --title "Trip plans" --json
+    ///   skilllite sessions rename --session-key s-1a2b --title "Trip plans"
+    ///   skilllite sessions delete --session-key s-1a2b --workspace .
+    #[cfg(feature = "agent")]
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsAction,
+    },
+
+    /// Review or revert files edited by the agent during a session (desktop UI)
+    ///
+    /// Examples:
+    ///   skilllite file-history changes --session-key default --since 1760000000 --json
+    ///   skilllite file-history revert --session-key default --path src/main.rs
+    #[cfg(feature = "agent")]
+    #[command(name = "file-history")]
+    FileHistory {
+        #[command(subcommand)]
+        action: FileHistoryAction,
+    },
+
     /// Run agent_chat RPC server over stdio (JSON-Lines event stream)
     ///
     /// Used by Python/TypeScript SDKs to call the Rust agent engine.
@@ -684,6 +960,7 @@ pub enum Commands {
     /// Examples:
     ///   skilllite swarm --listen 127.0.0.1:7700
     ///   skilllite swarm --listen 0.0.0.0:7700 --skills-dir skills
+    ///   skilllite swarm --listen 0.0.0.0:7700 --allow-skill-replication
     ///
     /// Default bind is loopback only. For LAN peers use `0.0.0.0:PORT` and set `SKILLLITE_SWARM_TOKEN`
     /// so HTTP clients must send `Authorization: Bearer <token>`.
@@ -696,6 +973,19 @@ pub enum Commands {
         /// Skills directory for capability aggregation (default: skills, .skills)
         #[arg(long, short = 's')]
         skills_dir: Option<Vec<String>>,
+
+        /// Instead of forwarding a task to the peer that has the skill, fetch the skill from
+        /// it (admission-scanned, installed to <skills>/_replicated/, run under the
+        /// evolved-skill sandbox policy) and execute locally. Also serves local skills to
+        /// peers via GET /skill/{name}.
+        #[arg(long, default_value = "false")]
+        allow_skill_replication: bool,
+
+        /// Static peer (host:port) for nodes mDNS cannot reach, e.g. another subnet or a cloud
+        /// VM. Repeatable; merged with <data_root>/peers.toml (`peers = ["host:port", ...]`).
+        /// Health-checked via GET /capabilities; listed by GET /peers.
+        #[arg(long = "peer", value_name = "HOST:PORT")]
+        peers: Vec<String>,
     },
 
     /// Serve run-scoped artifact storage over HTTP (OpenAPI v1)
@@ -744,6 +1034,43 @@ pub enum Commands {
         skills_dir: String,
     },
 
+    /// Diagnose the local environment (runtimes, sandbox, proxy, config, skills, evolution DB)
+    ///
+    /// Prints pass / warn / fail per check with a remediation hint. Exits 1 if any check
+    /// fails, so it can gate CI images.
+    ///
+    /// Examples:
+    ///   skilllite doctor
+    ///   skilllite doctor --online
+    ///   skilllite doctor --json > doctor.json
+    Doctor {
+        /// Skills directory path (default: skills)
+        #[arg(long, short = 's', default_value = "skills")]
+        skills_dir: String,
+
+        /// Also send a 1-token request to the configured LLM endpoint
+        #[arg(long)]
+        online: bool,
+
+        /// Output JSON (for support bundles)
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show or edit the layered configuration
+    ///
+    /// Values merge from defaults, ~/.skilllite/config.toml, <workspace>/.skilllite/config.toml,
+    /// environment variables (and .env) and CLI flags, highest last.
+    ///
+    /// Examples:
+    ///   skilllite config show
+    ///   skilllite config show --json
+    ///   skilllite config set sandbox.timeout_secs 60
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
     /// Python/Node runtime probe and provision (desktop UI)
     ///
     /// Examples:
@@ -849,6 +1176,68 @@ pub enum Commands {
     },
 }
 
+/// Input/output redirection for `run` (skill mode) and `exec`.
+#[derive(Args, Debug, Clone, Default)]
+pub struct SkillIoArgs {
+    /// Read the input JSON from a file instead of INPUT_JSON ("-" = stdin)
+    #[arg(long, value_name = "PATH", conflicts_with = "input_json")]
+    pub input_file: Option<String>,
+
+    /// Write the result to this file (atomic, parent dirs created) instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output_file: Option<String>,
+
+    /// Emit only this field of the result JSON, e.g. `files.0.path` (strings unquoted)
+    #[arg(long, value_name = "JSONPATH")]
+    pub output_field: Option<String>,
+}
+
+impl SkillIoArgs {
+    fn is_set(&self) -> bool {
+        self.input_file.is_some() || self.output_file.is_some() || self.output_field.is_some()
+    }
+
+    /// Reject the flags outside skill mode, where they would be silently ignored.
+    pub fn ensure_unused(&self, mode: &str) -> crate::Result<()> {
+        if self.is_set() {
+            return Err(crate::Error::msg(format!(
+                "--input-file/--output-file/--output-field only apply to skill execution, not {}",
+                mode
+            )));
+        }
+        Ok(())
+    }
+
+    /// Skill input from INPUT_JSON or --input-file; "-" reads stdin in both.
+    pub fn resolve_input(&self, input_json: Option<&str>) -> crate::Result<Option<String>> {
+        match (input_json, self.input_file.as_deref()) {
+            (Some(_), Some(_)) => Err(crate::Error::msg(
+                "INPUT_JSON and --input-file are mutually exclusive",
+            )),
+            (Some("-"), None) | (None, Some(_)) => {
+                Ok(Some(skilllite_commands::execute::read_input_file(
+                    self.input_file.as_deref().unwrap_or("-"),
+                )?))
+            }
+            (Some(json), None) => Ok(Some(json.to_string())),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Print the result, or the selected --output-field of it, to stdout or --output-file.
+    pub fn emit(&self, result: &str) -> crate::Result<()> {
+        let out = match &self.output_field {
+            Some(field) => skilllite_commands::execute::extract_output_field(result, field)?,
+            None => result.to_string(),
+        };
+        match &self.output_file {
+            Some(path) => skilllite_commands::execute::write_output_file(path, &out)?,
+            None => println!("{}", out),
+        }
+        Ok(())
+    }
+}
+
 /// `skilllite claw` subcommands.
 #[derive(Subcommand, Debug)]
 pub enum ClawAction {
@@ -1057,6 +1446,23 @@ pub enum GatewayAction {
     },
 }
 
+/// `skilllite config` subcommands.
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print every setting's effective value and where it came from (API keys masked)
+    Show {
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Validate and write a value to the user config file (~/.skilllite/config.toml)
+    Set {
+        /// Setting key, e.g. `model` or `sandbox.level`
+        key: String,
+        value: String,
+    },
+}
+
 /// `skilllite runtime` subcommands.
 #[derive(Subcommand, Debug)]
 pub enum RuntimeAction {
@@ -1085,6 +1491,81 @@ pub enum RuntimeAction {
     },
 }
 
+/// `skilllite sessions` subcommands.
+#[cfg(feature = "agent")]
+#[derive(Subcommand, Debug)]
+pub enum SessionsAction {
+    /// Sessions under the chat root, newest activity first
+    List {
+        /// Emit `SessionSummary` JSON array on stdout
+        #[arg(long)]
+        json: bool,
+    },
+    /// Add a new session with a display title
+    Create {
+        #[arg(long)]
+        title: String,
+        /// Emit the new `SessionSummary` as JSON on stdout
+        #[arg(long)]
+        json: bool,
+    },
+    /// Set a session's display title
+    Rename {
+        #[arg(long)]
+        session_key: String,
+        #[arg(long)]
+        title: String,
+    },
+    /// Clear the session (as `clear-session`), then remove its transcripts and plans
+    Delete {
+        #[arg(long)]
+        session_key: String,
+        /// Workspace path for .env (API key used for the memory summary)
+        #[arg(long, short = 'w', default_value = ".")]
+        workspace: String,
+    },
+    /// Delete end-of-session auto-memories (memory/auto/); explicit memories are kept
+    PurgeAutoMemory {
+        /// Only this session's auto-memories (default: all sessions)
+        #[arg(long)]
+        session_key: Option<String>,
+        /// List what would be removed without deleting
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// `skilllite file-history` subcommands.
+#[cfg(feature = "agent")]
+#[derive(Subcommand, Debug)]
+pub enum FileHistoryAction {
+    /// Files changed since a timestamp, with unified diffs
+    Changes {
+        #[arg(long, default_value = "default")]
+        session_key: String,
+        /// Unix seconds; only edits at or after this time are listed
+        #[arg(long, default_value_t = 0)]
+        since: u64,
+        #[arg(long, short = 'w', default_value = ".")]
+        workspace: String,
+        /// Emit `FileChange` JSON array on stdout
+        #[arg(long)]
+        json: bool,
+    },
+    /// Restore a file to its pre-session version
+    Revert {
+        #[arg(long, default_value = "default")]
+        session_key: String,
+        /// File path relative to the workspace
+        #[arg(long)]
+        path: String,
+        #[arg(long, short = 'w', default_value = ".")]
+        workspace: String,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 /// `skilllite skills` subcommands.
 #[derive(Subcommand, Debug)]
 pub enum SkillsAction {
@@ -1112,6 +1593,15 @@ pub enum EvolutionAction {
         /// Life Pulse periodic arm anchor (unix seconds); omit for first-tick semantics
         #[arg(long)]
         periodic_anchor_unix: Option<i64>,
+        /// Show per-tool timing and failure stats instead of the overview
+        #[arg(long)]
+        tools: bool,
+        /// Only list pending evolved skills with their review details
+        #[arg(long, conflicts_with = "tools")]
+        pending_only: bool,
+        /// Show daily success / correction rates per workspace and per skill
+        #[arg(long, conflicts_with_all = ["tools", "pending_only"])]
+        breakdown: bool,
     },
 
     /// Query evolution backlog proposals with optional filters
@@ -1160,6 +1650,34 @@ pub enum EvolutionAction {
         /// Skip confirmation prompt
         #[arg(long, short)]
         force: bool,
+        /// Only migrate the feedback DB and rebuild tables derived from decisions (daily
+        /// metrics, tool sequence keys); evolved rules, skills, decisions and logs are kept
+        #[arg(long)]
+        keep_data: bool,
+    },
+
+    /// Prune rotated log archives, old snapshots, and old evolution_log rows
+    Gc {
+        /// Retention period, e.g. `90d`, `12w`, `48h` (bare number = days)
+        #[arg(long, default_value = "90d")]
+        older_than: String,
+    },
+
+    /// Print recorded decisions (outcome, duration, tools, consuming evolution txn) for
+    /// external analysis
+    Export {
+        /// Output format: md (default) or json
+        #[arg(long, default_value = "md")]
+        format: String,
+        /// Only decisions on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Verify the hash chains of evolution.log and the audit log; fails on the first broken link
+    VerifyAudit {
+        #[arg(long)]
+        json: bool,
     },
 
     /// Disable a specific evolved rule by ID
@@ -1169,10 +1687,16 @@ pub enum EvolutionAction {
         rule_id: String,
     },
 
-    /// Show the origin, trigger history, and effectiveness of a specific rule
+    /// Show the decision trail of a rule or evolved skill: introducing txn, decisions in
+    /// scope, later changes, usage and (for skills) scan result and confirm/reject history
     Explain {
-        /// The rule ID to explain
-        #[arg(value_name = "RULE_ID")]
+        #[arg(long)]
+        json: bool,
+        /// Workspace whose skills directory is checked for evolved skills
+        #[arg(long, short = 'w', default_value = ".")]
+        workspace: String,
+        /// Rule ID or evolved skill name
+        #[arg(value_name = "ID")]
         rule_id: String,
     },
 
@@ -1,19 +1,107 @@
 //! 执行类命令：Run, Exec, Bash, Scan, Validate, Info, SecurityScan
 
-use std::io::Read;
-
 use skilllite_core::path_validation::validate_skill_path;
 use skilllite_core::skill::metadata::parse_skill_metadata;
 
 use crate::cli::Commands;
 use crate::command_registry::CommandRegistry;
 use crate::Error;
+#[cfg(feature = "agent")]
+use skilllite_agent::run_checkpoint::ResumeFrom;
+
+/// On a classified run failure, print `{"error": {kind, details}, "message"}` to stdout so
+/// scripts get a machine-readable reason; the message still goes to stderr via the caller.
+fn print_run_error_json(e: &skilllite_commands::Error) {
+    if let Some(err) = e.sandbox_error() {
+        println!(
+            "{}",
+            serde_json::json!({ "error": err.to_json(), "message": e.to_string() })
+        );
+    }
+}
+
+/// With `--audit`, print the human-readable `sandbox_audit` report to stderr (JSON stays on stdout).
+fn print_audit_report(output: &str) {
+    if let Some(report) = skilllite_sandbox::sandbox_audit::SandboxAuditReport::from_output(output)
+    {
+        eprintln!("{}", report.format_report());
+    }
+}
+
+/// Expose `--sandbox-level` / `--max-memory` / `--timeout` as the CLI layer of
+/// [`skilllite_core::config::Settings`], so settings read deeper in the run see them too.
+fn register_cli_overrides(
+    sandbox_level: Option<u8>,
+    max_memory: Option<u64>,
+    timeout: Option<u64>,
+) {
+    use skilllite_core::config::set_cli_override;
+    if let Some(level) = sandbox_level {
+        set_cli_override("sandbox.level", level);
+    }
+    if let Some(mb) = max_memory {
+        set_cli_override("sandbox.max_memory_mb", mb);
+    }
+    if let Some(secs) = timeout {
+        set_cli_override("sandbox.timeout_secs", secs);
+    }
+}
+
+/// `skilllite run --list-resumable` / `--discard <id>` (A13).
+#[cfg(feature = "agent")]
+fn manage_run_checkpoints(list: bool, discard: Option<&str>) -> crate::Result<()> {
+    use skilllite_agent::run_checkpoint;
+    use skilllite_agent::types::safe_truncate;
+
+    let chat_root = skilllite_executor::chat_root();
+    if let Some(id) = discard {
+        return match run_checkpoint::discard_checkpoint(&chat_root, id)? {
+            Some(id) => {
+                println!("🗑 已删除断点 {}", id);
+                Ok(())
+            }
+            None => Err(Error::msg(format!("未找到断点 {}", id))),
+        };
+    }
+    if !list {
+        return Ok(());
+    }
+    let checkpoints = run_checkpoint::list_checkpoints(&chat_root)?;
+    if checkpoints.is_empty() {
+        println!("暂无可续跑的断点。");
+        return Ok(());
+    }
+    println!("可续跑的断点 ({}):", checkpoints.len());
+    for cp in &checkpoints {
+        let updated = chrono::DateTime::parse_from_rfc3339(&cp.updated_at)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| cp.updated_at.clone());
+        let goal = cp.goal.lines().next().unwrap_or_default();
+        println!(
+            "  {}  {}/{} 任务  {}  {}",
+            safe_truncate(&cp.id, 8),
+            cp.tasks_done,
+            cp.tasks_total,
+            updated,
+            cp.workspace
+        );
+        println!("      🎯 {}", safe_truncate(goal, 120));
+    }
+    println!("\n用 `skilllite run --resume <id>` 续跑，`skilllite run --discard <id>` 删除。");
+    Ok(())
+}
 
 pub fn register(reg: &mut CommandRegistry) {
     reg.register(|cmd| {
         if let Commands::Run {
             skill_dir,
             input_json,
+            io,
+            entry,
             soul,
             goal,
             allow_network,
@@ -21,17 +109,48 @@ pub fn register(reg: &mut CommandRegistry) {
             max_memory,
             timeout,
             sandbox_level,
+            audit,
+            no_cache,
             workspace,
             skill_dirs,
             max_iterations,
             max_failures,
             resume,
+            list_resumable,
+            discard,
+            max_cost,
+            max_tokens,
+            output,
+            fail_on_empty,
         } = cmd
         {
             #[cfg(not(feature = "agent"))]
-            let _ = (soul, workspace, skill_dirs, max_iterations, max_failures);
+            let _ = (
+                soul,
+                workspace,
+                skill_dirs,
+                max_iterations,
+                max_failures,
+                max_cost,
+                max_tokens,
+                output,
+                fail_on_empty,
+            );
             let run = || -> crate::Result<()> {
-                if *resume || goal.is_some() {
+                if *list_resumable || discard.is_some() {
+                    #[cfg(feature = "agent")]
+                    {
+                        return manage_run_checkpoints(*list_resumable, discard.as_deref());
+                    }
+                    #[cfg(not(feature = "agent"))]
+                    {
+                        return Err(Error::msg(
+                            "Run checkpoints require the agent feature. Build with: cargo build --features agent",
+                        ));
+                    }
+                }
+                if resume.is_some() || goal.is_some() {
+                    io.ensure_unused("agent run mode")?;
                     #[cfg(feature = "agent")]
                     {
                         let g = goal.as_deref().unwrap_or("");
@@ -50,12 +169,28 @@ pub fn register(reg: &mut CommandRegistry) {
                             Some(n) => Some(n),
                             None => Some(5),
                         };
-                        skilllite_agent::chat::run_agent_run(
+                        if let Some(usd) = *max_cost {
+                            if usd.is_nan() || usd <= 0.0 {
+                                return Err(Error::msg("--max-cost must be a positive USD amount"));
+                            }
+                            config.max_cost_usd = Some(usd);
+                        }
+                        config.max_run_tokens = max_tokens.filter(|&n| n > 0);
+                        let resume = resume.as_ref().map(|id| match id {
+                            Some(id) => ResumeFrom::Id(id.clone()),
+                            None => ResumeFrom::LatestInWorkspace,
+                        });
+                        let opts = super::single_shot_options(output.as_deref(), *fail_on_empty);
+                        let code = skilllite_agent::chat::run_agent_run(
                             config,
                             g.to_string(),
-                            *resume,
-                        )
-                        .map_err(Into::into)
+                            resume,
+                            opts,
+                        );
+                        if code != skilllite_agent::single_shot::EXIT_OK {
+                            std::process::exit(code);
+                        }
+                        Ok(())
                     }
                     #[cfg(not(feature = "agent"))]
                     {
@@ -63,18 +198,16 @@ pub fn register(reg: &mut CommandRegistry) {
                             "Agent run mode requires the agent feature. Build with: cargo build --features agent",
                         ));
                     }
-                } else if let (Some(sd), Some(ij)) = (skill_dir, input_json) {
-                    let input_json = if ij == "-" {
-                        let mut s = String::new();
-                        std::io::stdin().read_to_string(&mut s)?;
-                        s
-                    } else {
-                        ij.clone()
-                    };
+                } else if let (Some(sd), Some(input_json)) =
+                    (skill_dir, io.resolve_input(input_json.as_deref())?)
+                {
                     let skill_path = validate_skill_path(sd)?;
                     let meta = parse_skill_metadata(&skill_path)?;
                     // 无入口时用大模型从 SKILL.md 推理入口，再执行（仅 agent feature 且已配 API）
-                    let inferred_entry: Option<String> = if meta.entry_point.is_empty() {
+                    let inferred_entry: Option<String> = if meta.entry_point.is_empty()
+                        && entry.is_none()
+                        && meta.entry_points.is_empty()
+                    {
                         #[cfg(feature = "agent")]
                         {
                             let config = skilllite_agent::types::AgentConfig::from_env();
@@ -107,6 +240,7 @@ pub fn register(reg: &mut CommandRegistry) {
                         None
                     };
                     let entry_override = inferred_entry.as_deref();
+                    register_cli_overrides(*sandbox_level, *max_memory, *timeout);
                     let sandbox_level =
                         skilllite_sandbox::runner::SandboxLevel::from_env_or_cli(*sandbox_level);
                     let limits = skilllite_sandbox::runner::ResourceLimits::from_env()
@@ -119,9 +253,13 @@ pub fn register(reg: &mut CommandRegistry) {
                         limits,
                         sandbox_level,
                         entry_override,
-                    )?;
-                    println!("{}", result);
-                    Ok(())
+                        entry.as_deref(),
+                        *audit,
+                        *no_cache,
+                    )
+                    .inspect_err(print_run_error_json)?;
+                    print_audit_report(&result);
+                    io.emit(&result)
                 } else {
                     Err(Error::msg(
                         "Use either: skilllite run <SKILL_DIR> '<INPUT_JSON>'  OR  skilllite run --goal \"...\" [--soul SOUL.md]  OR  skilllite run --resume",
@@ -139,22 +277,21 @@ pub fn register(reg: &mut CommandRegistry) {
             skill_dir,
             script_path,
             input_json,
+            io,
             args,
             allow_network,
             cache_dir,
             max_memory,
             timeout,
             sandbox_level,
+            audit,
         } = cmd
         {
             let run = || -> crate::Result<()> {
-                let input_json = if input_json == "-" {
-                    let mut s = String::new();
-                    std::io::stdin().read_to_string(&mut s)?;
-                    s
-                } else {
-                    input_json.clone()
-                };
+                let input_json = io
+                    .resolve_input(input_json.as_deref())?
+                    .ok_or_else(|| Error::msg("Provide INPUT_JSON or --input-file"))?;
+                register_cli_overrides(*sandbox_level, *max_memory, *timeout);
                 let sandbox_level =
                     skilllite_sandbox::runner::SandboxLevel::from_env_or_cli(*sandbox_level);
                 let limits = skilllite_sandbox::runner::ResourceLimits::from_env()
@@ -168,9 +305,11 @@ pub fn register(reg: &mut CommandRegistry) {
                     cache_dir.as_ref(),
                     limits,
                     sandbox_level,
-                )?;
-                println!("{}", result);
-                Ok(())
+                    *audit,
+                )
+                .inspect_err(print_run_error_json)?;
+                print_audit_report(&result);
+                io.emit(&result)
             };
             Some(run())
         } else {
@@ -208,10 +347,19 @@ pub fn register(reg: &mut CommandRegistry) {
         if let Commands::Scan {
             skill_dir,
             preview_lines,
+            format,
+            suggest_capabilities,
+            write,
         } = cmd
         {
             let r = (|| -> crate::Result<()> {
-                let result = skilllite_commands::scan::scan_skill(skill_dir, *preview_lines)?;
+                let result = if *suggest_capabilities {
+                    skilllite_commands::scan::suggest_capabilities(skill_dir, *write)?
+                } else if format == "sarif" {
+                    skilllite_commands::scan::scan_skill_sarif(skill_dir)?
+                } else {
+                    skilllite_commands::scan::scan_skill(skill_dir, *preview_lines)?
+                };
                 println!("{}", result);
                 Ok(())
             })();
@@ -249,18 +397,22 @@ pub fn register(reg: &mut CommandRegistry) {
             allow_file_ops,
             allow_process_exec,
             json,
+            format,
         } = cmd
         {
-            Some(
-                skilllite_commands::security::security_scan_script(
-                    script_path,
-                    *allow_network,
-                    *allow_file_ops,
-                    *allow_process_exec,
-                    *json,
-                )
-                .map_err(Into::into),
-            )
+            use skilllite_commands::security::{security_scan_script, ReportFormat};
+            let r = ReportFormat::from_args(format.as_deref(), *json, ReportFormat::Text).and_then(
+                |format| {
+                    security_scan_script(
+                        script_path,
+                        *allow_network,
+                        *allow_file_ops,
+                        *allow_process_exec,
+                        format,
+                    )
+                },
+            );
+            Some(r.map_err(Into::into))
         } else {
             None
         }
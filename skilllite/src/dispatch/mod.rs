@@ -28,6 +28,8 @@ pub fn register_all(reg: &mut CommandRegistry) {
     channel_serve::register(reg);
     register_ide(reg);
     register_env(reg);
+    register_doctor(reg);
+    register_config(reg);
     register_runtime(reg);
     register_skills(reg);
     register_reindex(reg);
@@ -172,6 +174,24 @@ fn register_ide(reg: &mut CommandRegistry) {
                 skilllite_commands::ide::cmd_opencode(project_dir.as_deref(), skills_dir, *force)
                     .map_err(Into::into),
             )
+        } else if let Commands::InitIde {
+            target,
+            project_dir,
+            skills_dir,
+            global,
+            force,
+        } = cmd
+        {
+            Some(
+                skilllite_commands::ide::cmd_init_ide(
+                    target,
+                    project_dir.as_deref(),
+                    skills_dir,
+                    *global,
+                    *force,
+                )
+                .map_err(Into::into),
+            )
         } else {
             None
         }
@@ -180,8 +200,69 @@ fn register_ide(reg: &mut CommandRegistry) {
 
 fn register_env(reg: &mut CommandRegistry) {
     reg.register(|cmd| {
-        if let Commands::CleanEnv { dry_run, force } = cmd {
-            Some(skilllite_commands::env::cmd_clean(*dry_run, *force).map_err(Into::into))
+        if let Commands::CleanEnv {
+            dry_run,
+            force,
+            unused,
+            results,
+        } = cmd
+        {
+            Some(
+                skilllite_commands::env::cmd_clean(*dry_run, *force, *unused, *results)
+                    .map_err(Into::into),
+            )
+        } else {
+            None
+        }
+    });
+    reg.register(|cmd| {
+        if let Commands::CleanOutput {
+            older_than,
+            dry_run,
+            output_dir,
+        } = cmd
+        {
+            Some(
+                skilllite_commands::output::cmd_clean_output(
+                    output_dir.as_deref(),
+                    older_than,
+                    *dry_run,
+                )
+                .map_err(Into::into),
+            )
+        } else {
+            None
+        }
+    });
+}
+
+fn register_doctor(reg: &mut CommandRegistry) {
+    reg.register(|cmd| {
+        if let Commands::Doctor {
+            skills_dir,
+            online,
+            json,
+        } = cmd
+        {
+            Some(
+                skilllite_commands::doctor::cmd_doctor(skills_dir, *online, *json)
+                    .map_err(Into::into),
+            )
+        } else {
+            None
+        }
+    });
+}
+
+fn register_config(reg: &mut CommandRegistry) {
+    reg.register(|cmd| {
+        if let Commands::Config { action } = cmd {
+            use crate::cli::ConfigAction;
+            let r = match action {
+                ConfigAction::Show { json } => skilllite_commands::config::cmd_show(*json),
+                ConfigAction::Set { key, value } => skilllite_commands::config::cmd_set(key, value),
+            };
+            Some(r.map_err(Into::into))
         } else {
             None
         }
@@ -239,11 +320,23 @@ fn register_reindex(reg: &mut CommandRegistry) {
             skills_dir,
             verbose,
             rebuild_manifest,
+            resolve,
+            memory,
         } = cmd
         {
+            if *memory {
+                return Some(
+                    skilllite_commands::reindex::cmd_reindex_memory(*verbose).map_err(Into::into),
+                );
+            }
             Some(
-                skilllite_commands::reindex::cmd_reindex(skills_dir, *verbose, *rebuild_manifest)
-                    .map_err(Into::into),
+                skilllite_commands::reindex::cmd_reindex(
+                    skills_dir,
+                    *verbose,
+                    *rebuild_manifest,
+                    resolve.as_deref(),
+                )
+                .map_err(Into::into),
             )
         } else {
             None
@@ -254,11 +347,17 @@ fn register_reindex(reg: &mut CommandRegistry) {
 fn register_security(reg: &mut CommandRegistry) {
     #[cfg(feature = "audit")]
     reg.register(|cmd| {
-        if let Commands::DependencyAudit { skill_dir, json } = cmd {
-            Some(
-                skilllite_commands::security::dependency_audit_skill(skill_dir, *json)
-                    .map_err(Into::into),
-            )
+        if let Commands::DependencyAudit {
+            skill_dir,
+            json,
+            format,
+            offline,
+        } = cmd
+        {
+            use skilllite_commands::security::{dependency_audit_skill, ReportFormat};
+            let r = ReportFormat::from_args(format.as_deref(), *json, ReportFormat::Text)
+                .and_then(|format| dependency_audit_skill(skill_dir, format, *offline));
+            Some(r.map_err(Into::into))
         } else {
             None
         }
@@ -292,7 +391,6 @@ fn register_audit_report(reg: &mut CommandRegistry) {
 }
 
 fn register_init(reg: &mut CommandRegistry) {
-    #[cfg(feature = "agent")]
     reg.register(|cmd| {
         if let Commands::Init {
             skills_dir,
@@ -300,43 +398,62 @@ fn register_init(reg: &mut CommandRegistry) {
             skip_audit,
             strict,
             force,
+            from,
+            dry_run,
+            #[cfg(feature = "agent")]
             use_llm,
         } = cmd
         {
-            Some(
-                skilllite_commands::init::cmd_init(
+            #[cfg(feature = "agent")]
+            let use_llm = *use_llm;
+            #[cfg(not(feature = "agent"))]
+            let use_llm = false;
+            let r = match from {
+                Some(dir) => {
+                    skilllite_commands::init_from::cmd_init_from(dir, use_llm, *dry_run, *force)
+                }
+                None => skilllite_commands::init::cmd_init(
                     skills_dir,
                     *skip_deps,
                     *skip_audit,
                     *strict,
                     *force,
-                    *use_llm,
-                )
-                .map_err(Into::into),
-            )
+                    use_llm,
+                ),
+            };
+            Some(r.map_err(Into::into))
         } else {
             None
         }
     });
-    #[cfg(not(feature = "agent"))]
+}
+
+#[cfg(feature = "agent")]
+fn register_quickstart(reg: &mut CommandRegistry) {
     reg.register(|cmd| {
-        if let Commands::Init {
+        if let Commands::Quickstart {
             skills_dir,
-            skip_deps,
-            skip_audit,
-            strict,
-            force,
-            ..
+            non_interactive,
+            provider,
+            api_base,
+            api_key,
+            model,
+            workspace,
+            skip_validation,
         } = cmd
         {
             Some(
-                skilllite_commands::init::cmd_init(
-                    skills_dir,
-                    *skip_deps,
-                    *skip_audit,
-                    *strict,
-                    *force,
-                    false,
+                skilllite_commands::quickstart::cmd_quickstart(
+                    skilllite_commands::quickstart::QuickstartOptions {
+                        skills_dir,
+                        non_interactive: *non_interactive,
+                        provider: provider.as_deref(),
+                        api_base: api_base.as_deref(),
+                        api_key: api_key.as_deref(),
+                        model: model.as_deref(),
+                        workspace: workspace.as_deref(),
+                        skip_validation: *skip_validation,
+                    },
                 )
                 .map_err(Into::into),
             )
@@ -346,15 +463,18 @@ fn register_init(reg: &mut CommandRegistry) {
     });
 }
 
+/// `--output` / `--fail-on-empty` of `chat -m` and `run --goal` (clap already validated the format).
 #[cfg(feature = "agent")]
-fn register_quickstart(reg: &mut CommandRegistry) {
-    reg.register(|cmd| {
-        if let Commands::Quickstart { skills_dir } = cmd {
-            Some(skilllite_commands::quickstart::cmd_quickstart(skills_dir).map_err(Into::into))
-        } else {
-            None
-        }
-    });
+fn single_shot_options(
+    output: Option<&str>,
+    fail_on_empty: bool,
+) -> skilllite_agent::single_shot::SingleShotOptions {
+    skilllite_agent::single_shot::SingleShotOptions {
+        output: output
+            .and_then(skilllite_agent::single_shot::OutputFormat::parse)
+            .unwrap_or_default(),
+        fail_on_empty,
+    }
 }
 
 #[cfg(feature = "agent")]
@@ -373,8 +493,14 @@ fn register_agent(reg: &mut CommandRegistry) {
             message,
             plan,
             no_plan,
+            approve_plan,
             no_memory,
             soul,
+            llm_record,
+            llm_replay,
+            loose,
+            output,
+            fail_on_empty,
         } = cmd
         {
             let mut config = skilllite_agent::types::AgentConfig::from_env();
@@ -395,16 +521,39 @@ fn register_agent(reg: &mut CommandRegistry) {
             config.system_prompt = system_prompt.clone();
             config.verbose = *verbose;
             config.soul_path = soul.clone();
-            if *plan {
+            if *plan || *approve_plan {
                 config.enable_task_planning = true;
             } else if *no_plan {
                 config.enable_task_planning = false;
             }
+            if *approve_plan {
+                config.approve_plan = true;
+            }
             config.enable_memory = !*no_memory;
-            Some(
-                skilllite_agent::chat::run_chat(config, session.clone(), message.clone())
-                    .map_err(Into::into),
-            )
+            if let Some(dir) = llm_replay {
+                config.llm_tape = Some(std::sync::Arc::new(skilllite_agent::llm::LlmTape::replay(
+                    dir, *loose,
+                )));
+            } else if let Some(dir) = llm_record {
+                match skilllite_agent::llm::LlmTape::record(dir) {
+                    Ok(tape) => config.llm_tape = Some(std::sync::Arc::new(tape)),
+                    Err(e) => return Some(Err(e.into())),
+                }
+            }
+            if let Some(msg) = message {
+                let opts = single_shot_options(output.as_deref(), *fail_on_empty);
+                let code = skilllite_agent::chat::run_chat_single_shot(
+                    config,
+                    session.clone(),
+                    msg.clone(),
+                    opts,
+                );
+                if code != skilllite_agent::single_shot::EXIT_OK {
+                    std::process::exit(code);
+                }
+                return Some(Ok(()));
+            }
+            Some(skilllite_agent::chat::run_chat(config, session.clone(), None).map_err(Into::into))
         } else {
             None
         }
@@ -423,6 +572,53 @@ fn register_agent(reg: &mut CommandRegistry) {
             None
         }
     });
+    reg.register(|cmd| {
+        if let Commands::Sessions { action } = cmd {
+            use crate::cli::SessionsAction;
+            let r = match action {
+                SessionsAction::List { json } => skilllite_agent::chat::run_list_sessions(*json),
+                SessionsAction::Create { title, json } => {
+                    skilllite_agent::chat::run_create_session(title, *json)
+                }
+                SessionsAction::Rename { session_key, title } => {
+                    skilllite_agent::chat::run_rename_session(session_key, title)
+                }
+                SessionsAction::Delete {
+                    session_key,
+                    workspace,
+                } => skilllite_agent::chat::run_delete_session(session_key, workspace),
+                SessionsAction::PurgeAutoMemory {
+                    session_key,
+                    dry_run,
+                } => skilllite_agent::chat::run_purge_auto_memory(session_key.as_deref(), *dry_run),
+            };
+            Some(r.map_err(Into::into))
+        } else {
+            None
+        }
+    });
+    reg.register(|cmd| {
+        if let Commands::FileHistory { action } = cmd {
+            use crate::cli::FileHistoryAction;
+            let r = match action {
+                FileHistoryAction::Changes {
+                    session_key,
+                    since,
+                    workspace,
+                    json,
+                } => skilllite_agent::chat::run_file_changes(session_key, workspace, *since, *json),
+                FileHistoryAction::Revert {
+                    session_key,
+                    path,
+                    workspace,
+                    json,
+                } => skilllite_agent::chat::run_revert_file(session_key, workspace, path, *json),
+            };
+            Some(r.map_err(Into::into))
+        } else {
+            None
+        }
+    });
     reg.register(|cmd| {
         if let Commands::Replay {
             dataset,
@@ -494,10 +690,16 @@ fn register_agent(reg: &mut CommandRegistry) {
                     json,
                     workspace,
                     periodic_anchor_unix,
+                    tools,
+                    pending_only,
+                    breakdown,
                 } => skilllite_commands::evolution::cmd_status(
                     *json,
                     workspace,
                     *periodic_anchor_unix,
+                    *tools,
+                    *pending_only,
+                    *breakdown,
                 ),
                 EvolutionAction::Backlog {
                     json,
@@ -526,15 +728,26 @@ fn register_agent(reg: &mut CommandRegistry) {
                     workspace,
                     proposal_id,
                 ),
-                EvolutionAction::Reset { force } => {
-                    skilllite_commands::evolution::cmd_reset(*force)
+                EvolutionAction::Reset { force, keep_data } => {
+                    skilllite_commands::evolution::cmd_reset(*force, *keep_data)
+                }
+                EvolutionAction::Gc { older_than } => {
+                    skilllite_commands::evolution::cmd_gc(older_than)
+                }
+                EvolutionAction::Export { format, since } => {
+                    skilllite_commands::evolution::cmd_export(format, since.as_deref())
+                }
+                EvolutionAction::VerifyAudit { json } => {
+                    skilllite_commands::evolution::cmd_verify_audit(*json)
                 }
                 EvolutionAction::Disable { rule_id } => {
                     skilllite_commands::evolution::cmd_disable(rule_id)
                 }
-                EvolutionAction::Explain { rule_id } => {
-                    skilllite_commands::evolution::cmd_explain(rule_id)
-                }
+                EvolutionAction::Explain {
+                    json,
+                    workspace,
+                    rule_id,
+                } => skilllite_commands::evolution::cmd_explain(rule_id, workspace, *json),
                 EvolutionAction::AuthorizeCapability {
                     json,
                     workspace,
@@ -30,7 +30,13 @@ pub fn register(reg: &mut CommandRegistry) {
     });
 
     reg.register(|cmd| {
-        if let Commands::Swarm { listen, skills_dir } = cmd {
+        if let Commands::Swarm {
+            listen,
+            skills_dir,
+            allow_skill_replication,
+            peers,
+        } = cmd
+        {
             let capability_tags = crate::aggregate_capability_tags(skills_dir.as_deref());
             #[cfg(feature = "swarm")]
             {
@@ -44,22 +50,28 @@ pub fn register(reg: &mut CommandRegistry) {
                 let executor: Option<
                     std::sync::Arc<dyn skilllite_swarm::TaskExecutor>,
                 > = None;
+                let replication = allow_skill_replication
+                    .then(|| crate::swarm_replication::replication_for(skills_dir.as_deref()));
                 Some(
                     crate::protocol::SwarmHandler.serve(crate::protocol::ProtocolParams::P2p {
                         listen_addr: listen.clone(),
                         capability_tags,
                         skills_dir: skills_dir.clone(),
+                        static_peers: peers.clone(),
                         executor,
+                        replication,
                     }),
                 )
             }
             #[cfg(not(feature = "swarm"))]
             {
+                let _ = allow_skill_replication;
                 Some(
                     crate::protocol::SwarmHandler.serve(crate::protocol::ProtocolParams::P2p {
                         listen_addr: listen.clone(),
                         capability_tags,
                         skills_dir: skills_dir.clone(),
+                        static_peers: peers.clone(),
                     }),
                 )
             }
@@ -12,19 +12,26 @@ pub fn register(reg: &mut CommandRegistry) {
             skills_dir,
             force,
             list,
+            capabilities,
+            limit,
+            page,
+            show,
+            json,
             scan_offline,
         } = cmd
         {
-            Some(
-                skilllite_commands::skill::cmd_add(
-                    source,
-                    skills_dir,
-                    *force,
-                    *list,
-                    *scan_offline,
-                )
-                .map_err(Into::into),
-            )
+            use skilllite_commands::skill;
+            let result = if let Some(name) = show {
+                skill::cmd_registry_show(name, *json)
+            } else {
+                match source.as_deref() {
+                    Some(source) if !(*list && skill::is_registry_query(source)) => {
+                        skill::cmd_add(source, skills_dir, *force, *list, *scan_offline)
+                    }
+                    query => skill::cmd_registry_search(query, capabilities, *limit, *page, *json),
+                }
+            };
+            Some(result.map_err(Into::into))
         } else {
             None
         }
@@ -35,11 +42,15 @@ pub fn register(reg: &mut CommandRegistry) {
             skill_name,
             skills_dir,
             force,
+            keep_env,
+            dry_run,
         } = cmd
         {
             Some(
-                skilllite_commands::skill::cmd_remove(skill_name, skills_dir, *force)
-                    .map_err(Into::into),
+                skilllite_commands::skill::cmd_remove(
+                    skill_name, skills_dir, *force, *keep_env, *dry_run,
+                )
+                .map_err(Into::into),
             )
         } else {
             None
@@ -67,7 +78,15 @@ pub fn register(reg: &mut CommandRegistry) {
                     "skills_dir": skills_dir,
                     "format": format
                 });
-                let result = stdio_rpc::handle_list_tools(&params)?;
+                let mut result = stdio_rpc::handle_list_tools(&params)?;
+                if let Some(markdown) = result.get("markdown").and_then(|m| m.as_str()) {
+                    print!("{}", markdown);
+                    return Ok(());
+                }
+                // One-shot process: the RPC cache status is meaningless here.
+                if let Some(obj) = result.as_object_mut() {
+                    obj.remove("cache");
+                }
                 println!("{}", serde_json::to_string_pretty(&result)?);
                 Ok(())
             })();
@@ -82,10 +101,11 @@ pub fn register(reg: &mut CommandRegistry) {
             skill_name,
             skills_dir,
             json,
+            full,
         } = cmd
         {
             Some(
-                skilllite_commands::skill::cmd_show(skill_name, skills_dir, *json)
+                skilllite_commands::skill::cmd_show(skill_name, skills_dir, *json, *full)
                     .map_err(Into::into),
             )
         } else {
@@ -99,11 +119,15 @@ pub fn register(reg: &mut CommandRegistry) {
             skills_dir,
             json,
             strict,
+            deps,
+            relock,
         } = cmd
         {
             Some(
-                skilllite_commands::skill::cmd_verify(target, skills_dir, *json, *strict)
-                    .map_err(Into::into),
+                skilllite_commands::skill::cmd_verify(
+                    target, skills_dir, *json, *strict, *deps, *relock,
+                )
+                .map_err(Into::into),
             )
         } else {
             None
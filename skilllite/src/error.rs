@@ -77,4 +77,24 @@ impl Error {
             source: Box::new(err.into()),
         }
     }
+
+    /// A session or plan file stayed locked past the executor's wait budget.
+    pub fn is_lock_timeout(&self) -> bool {
+        match self {
+            #[cfg(feature = "executor")]
+            Error::Executor(e) => e.is_lock_timeout(),
+            Error::Context { source, .. } => source.is_lock_timeout(),
+            _ => false,
+        }
+    }
+
+    /// Structured failure kind of a classified skill run, through any wrapping.
+    pub fn sandbox_error(&self) -> Option<&skilllite_sandbox::SandboxError> {
+        match self {
+            Error::Sandbox(e) => e.sandbox_error(),
+            Error::Commands(e) => e.sandbox_error(),
+            Error::Context { source, .. } => source.sandbox_error(),
+            _ => None,
+        }
+    }
 }
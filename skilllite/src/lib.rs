@@ -8,9 +8,15 @@ mod dispatch;
 mod mcp;
 mod protocol;
 mod stdio_rpc;
+#[cfg(feature = "agent")]
+mod stdio_rpc_cache;
 mod stdio_rpc_params;
 #[cfg(all(feature = "agent", feature = "swarm"))]
 mod swarm_executor;
+#[cfg(feature = "swarm")]
+mod swarm_replication;
+#[cfg(feature = "agent")]
+mod tool_catalog;
 
 pub use error::Error;
 
@@ -49,6 +55,9 @@ fn aggregate_capability_tags(_skills_dir: Option<&[String]>) -> Vec<String> {
 /// Run the CLI — parses args and dispatches to command handlers.
 /// Used by both `skilllite` (full) and `skilllite-sandbox` (minimal) binaries.
 pub fn run_cli() -> Result<()> {
+    // Inside bwrap this process may be the seccomp exec shim; then this never returns.
+    #[cfg(target_os = "linux")]
+    skilllite_sandbox::seccomp::run_exec_shim_if_requested();
     let cli = Cli::parse();
     #[cfg(feature = "agent")]
     let is_chat = matches!(cli.command, cli::Commands::Chat { .. });
@@ -62,5 +71,7 @@ pub fn run_cli() -> Result<()> {
 
     let mut reg = command_registry::CommandRegistry::new();
     dispatch::register_all(&mut reg);
-    reg.dispatch(&cli.command)
+    let result = reg.dispatch(&cli.command);
+    skilllite_core::observability::shutdown_tracing();
+    result
 }
@@ -0,0 +1,325 @@
+//! Evolution management tools: evolution_status, evolution_pending, evolution_confirm.
+//!
+//! Mirrors `skilllite evolution {status,pending,confirm,reject}` for MCP clients.
+//! `evolution_confirm` uses the same two-phase pattern as `execute_code`: the first call
+//! returns a review payload and a `confirmation_token`; the second call with the token applies
+//! the decision. Tokens are single-use, expire after the scan cache TTL, and are bound to the
+//! skill name, the decision, and the pending skill's content.
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::Error;
+use crate::Result;
+
+use skilllite_commands::evolution::{
+    build_evolution_status_snapshot, desktop_confirm_pending_skill, desktop_list_pending_skills,
+    desktop_pending_skill_dir, desktop_reject_pending_skill, EvolutionStatusParams,
+};
+use skilllite_core::skill::metadata;
+use skilllite_sandbox::security::run_skill_precheck;
+
+use super::state::{McpServer, PendingEvolutionDecision};
+
+/// Static precheck of a pending skill (SKILL.md + entry script), as shown to the reviewer.
+fn pending_scan_summary(skill_dir: &Path) -> Value {
+    let (entry_point, network) = metadata::parse_skill_metadata(skill_dir)
+        .map(|m| (m.entry_point, m.network.enabled))
+        .unwrap_or_default();
+    let summary = run_skill_precheck(skill_dir, &entry_point, network);
+    json!({
+        "has_critical": summary.has_critical_script_issue,
+        "needs_review": summary.review_text.is_some(),
+        "report": summary.review_text,
+    })
+}
+
+/// SHA256 over the pending skill's files, so a token cannot approve content changed after review.
+fn pending_content_hash(skill_dir: &Path) -> String {
+    fn collect(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+        for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect(&path, files);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    let mut files = Vec::new();
+    collect(skill_dir, &mut files);
+    files.sort();
+    let mut hasher = Sha256::new();
+    for path in files {
+        let rel = path.strip_prefix(skill_dir).unwrap_or(&path);
+        hasher.update(rel.to_string_lossy().as_bytes());
+        if let Ok(content) = std::fs::read(&path) {
+            hasher.update(&content);
+        }
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Handle the `evolution_status` tool call (same JSON as `skilllite evolution status --json`).
+pub(super) fn handle_evolution_status(server: &McpServer) -> Result<String> {
+    let snapshot = build_evolution_status_snapshot(&EvolutionStatusParams {
+        workspace: server.workspace_str(),
+        periodic_anchor_unix: None,
+    });
+    Ok(serde_json::to_string_pretty(&snapshot)?)
+}
+
+/// Handle the `evolution_pending` tool call: pending evolved skills with scan summaries.
+pub(super) fn handle_evolution_pending(server: &McpServer) -> Result<String> {
+    let workspace = server.workspace_str();
+    let rows = desktop_list_pending_skills(&workspace)?;
+    let skills: Vec<Value> = rows
+        .into_iter()
+        .map(|row| {
+            let scan = desktop_pending_skill_dir(&workspace, &row.name)
+                .map(|dir| pending_scan_summary(&dir))
+                .unwrap_or(Value::Null);
+            json!({
+                "name": row.name,
+                "needs_review": row.needs_review,
+                "preview": row.preview,
+                "scan": scan,
+            })
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&json!({ "pending": skills }))?)
+}
+
+/// Handle the `evolution_confirm` tool call (two-phase).
+pub(super) fn handle_evolution_confirm(
+    server: &mut McpServer,
+    arguments: &Value,
+) -> Result<String> {
+    let skill_name = arguments
+        .get("skill_name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::msg("skill_name is required"))?;
+    let confirm = arguments
+        .get("confirm")
+        .and_then(|v| v.as_bool())
+        .ok_or_else(|| Error::msg("confirm (boolean) is required"))?;
+    let token = arguments.get("confirmation_token").and_then(|v| v.as_str());
+
+    let workspace = server.workspace_str();
+    let skill_dir = desktop_pending_skill_dir(&workspace, skill_name)?;
+    let action = if confirm { "confirm" } else { "reject" };
+
+    let Some(token) = token else {
+        let content_hash = pending_content_hash(&skill_dir);
+        let scan = pending_scan_summary(&skill_dir);
+        let blocked = confirm && scan["has_critical"].as_bool().unwrap_or(false);
+        let token =
+            McpServer::generate_scan_id(&format!("evo:{}:{}:{}", action, skill_name, content_hash));
+        skilllite_core::observability::audit_confirmation_requested(
+            skill_name,
+            &content_hash,
+            0,
+            &format!("EVOLUTION_{}", action.to_uppercase()),
+        );
+        if !blocked {
+            server.evolution_tokens.insert(
+                token.clone(),
+                PendingEvolutionDecision {
+                    skill_name: skill_name.to_string(),
+                    confirm,
+                    content_hash,
+                    created_at: Instant::now(),
+                },
+            );
+        }
+        let mut output = format!(
+            "📋 Evolution review: {} pending skill '{}'\n\n",
+            action, skill_name
+        );
+        if let Some(report) = scan["report"].as_str() {
+            output.push_str(report);
+            output.push('\n');
+        }
+        if blocked {
+            output.push_str(
+                "\n🚫 BLOCKED: Critical issues in the pending skill. It can only be rejected.\n",
+            );
+        } else {
+            output.push_str(&format!(
+                "\n⚠️ User confirmation is required. Call evolution_confirm again with the same skill_name, confirm={} and this confirmation_token.\n",
+                confirm
+            ));
+        }
+        let details = json!({
+            "skill_name": skill_name,
+            "action": action,
+            "confirmation_token": if blocked { Value::Null } else { json!(token) },
+            "scan": scan,
+            "skill_md": skill_md_preview(&skill_dir),
+        });
+        output.push_str(&format!(
+            "\n```json\n{}\n```",
+            serde_json::to_string_pretty(&details)?
+        ));
+        return Ok(output);
+    };
+
+    // One-time consumption: remove before any check so a failed attempt cannot be replayed.
+    let pending = server.evolution_tokens.remove(token).ok_or_else(|| {
+        Error::msg(
+            "Invalid or already used confirmation_token. Call evolution_confirm without a token to start a new review.",
+        )
+    })?;
+    if pending.created_at.elapsed() >= server.cache_ttl {
+        return Err(Error::msg(format!(
+            "confirmation_token expired (TTL: {}s). Call evolution_confirm without a token to start a new review.",
+            server.cache_ttl.as_secs()
+        )));
+    }
+    if pending.skill_name != skill_name || pending.confirm != confirm {
+        return Err(Error::msg(
+            "confirmation_token was issued for a different skill or decision.",
+        ));
+    }
+    if pending.content_hash != pending_content_hash(&skill_dir) {
+        return Err(Error::msg(
+            "Stale confirmation_token: pending skill changed since it was reviewed.",
+        ));
+    }
+
+    skilllite_core::observability::audit_confirmation_response(skill_name, true, "user");
+    if confirm {
+        desktop_confirm_pending_skill(&workspace, skill_name)?;
+        Ok(format!("✅ Skill '{}' confirmed", skill_name))
+    } else {
+        desktop_reject_pending_skill(&workspace, skill_name)?;
+        Ok(format!("✅ Skill '{}' rejected", skill_name))
+    }
+}
+
+fn skill_md_preview(skill_dir: &Path) -> String {
+    std::fs::read_to_string(skill_dir.join("SKILL.md"))
+        .map(|s| s.chars().take(4000).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn server_with_pending(name: &str) -> (tempfile::TempDir, McpServer) {
+        let tmp = tempfile::tempdir().unwrap();
+        let pending = tmp.path().join("skills/_evolved/_pending").join(name);
+        std::fs::create_dir_all(&pending).unwrap();
+        std::fs::write(
+            pending.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: test\n---\n# {}\n", name, name),
+        )
+        .unwrap();
+        let server = McpServer::new(tmp.path().to_path_buf(), tmp.path().join("skills"));
+        (tmp, server)
+    }
+
+    fn token_from(review: &str) -> String {
+        let json_start = review.find("```json\n").unwrap() + 8;
+        let json_end = review.rfind("\n```").unwrap();
+        let details: Value = serde_json::from_str(&review[json_start..json_end]).unwrap();
+        details["confirmation_token"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn reject_requires_token_then_removes_pending_skill() {
+        let (tmp, mut server) = server_with_pending("demo");
+        let pending = tmp.path().join("skills/_evolved/_pending/demo");
+
+        let review = handle_evolution_confirm(
+            &mut server,
+            &json!({"skill_name": "demo", "confirm": false}),
+        )
+        .unwrap();
+        assert!(review.contains("reject pending skill 'demo'"));
+        assert!(pending.exists(), "first phase must not apply the decision");
+
+        let token = token_from(&review);
+        let mismatched = handle_evolution_confirm(
+            &mut server,
+            &json!({"skill_name": "demo", "confirm": true, "confirmation_token": token}),
+        );
+        assert!(mismatched.unwrap_err().to_string().contains("different"));
+        assert!(pending.exists());
+
+        let review = handle_evolution_confirm(
+            &mut server,
+            &json!({"skill_name": "demo", "confirm": false}),
+        )
+        .unwrap();
+        let token = token_from(&review);
+        let done = handle_evolution_confirm(
+            &mut server,
+            &json!({"skill_name": "demo", "confirm": false, "confirmation_token": token}),
+        )
+        .unwrap();
+        assert!(done.contains("rejected"));
+        assert!(!pending.exists());
+
+        let replay = handle_evolution_confirm(
+            &mut server,
+            &json!({"skill_name": "demo", "confirm": false, "confirmation_token": token}),
+        );
+        assert!(replay.is_err());
+    }
+
+    #[test]
+    fn expired_token_is_refused_and_consumed() {
+        let (tmp, mut server) = server_with_pending("demo");
+        server.cache_ttl = Duration::ZERO;
+
+        let review =
+            handle_evolution_confirm(&mut server, &json!({"skill_name": "demo", "confirm": true}))
+                .unwrap();
+        let token = token_from(&review);
+        let err = handle_evolution_confirm(
+            &mut server,
+            &json!({"skill_name": "demo", "confirm": true, "confirmation_token": token}),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("expired"));
+        assert!(server.evolution_tokens.is_empty());
+        assert!(tmp.path().join("skills/_evolved/_pending/demo").exists());
+        assert!(!tmp.path().join("skills/_evolved/demo").exists());
+    }
+
+    #[test]
+    fn stale_token_is_refused_after_content_change() {
+        let (tmp, mut server) = server_with_pending("demo");
+        let review =
+            handle_evolution_confirm(&mut server, &json!({"skill_name": "demo", "confirm": true}))
+                .unwrap();
+        let token = token_from(&review);
+        std::fs::write(
+            tmp.path().join("skills/_evolved/_pending/demo/run.py"),
+            "print('changed')",
+        )
+        .unwrap();
+        let err = handle_evolution_confirm(
+            &mut server,
+            &json!({"skill_name": "demo", "confirm": true, "confirmation_token": token}),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Stale"));
+    }
+
+    #[test]
+    fn pending_lists_skills_with_scan_summary() {
+        let (_tmp, server) = server_with_pending("demo");
+        let listed: Value =
+            serde_json::from_str(&handle_evolution_pending(&server).unwrap()).unwrap();
+        let pending = listed["pending"].as_array().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0]["name"], "demo");
+        assert_eq!(pending[0]["scan"]["has_critical"], false);
+    }
+}
@@ -9,6 +9,7 @@ use crate::Result;
 
 use skilllite_core::skill::manifest::{self, SkillIntegrityStatus};
 use skilllite_core::skill::metadata;
+use skilllite_core::skill::origin::SkillSandboxPolicy;
 use skilllite_core::skill::trust::TrustDecision;
 use skilllite_sandbox::runner::SandboxLevel;
 use skilllite_sandbox::security::types::{
@@ -140,6 +141,22 @@ pub(super) fn handle_get_skill_info(server: &McpServer, arguments: &Value) -> Re
     let mut output = format!("# Skill: {}\n\n", skill_name);
     output.push_str(&skill_content);
 
+    if !meta.entry_points.is_empty() {
+        output.push_str("\n\n## Entry Points\n\nPass one of these as `entry` to run_skill.\n");
+        for ep in &meta.entry_points {
+            output.push_str(&format!("\n### `{}` (`{}`)\n\n", ep.name, ep.script));
+            if let Some(ref desc) = ep.description {
+                output.push_str(&format!("{}\n", desc));
+            }
+            if let Some(ref schema) = ep.parameters {
+                output.push_str(&format!(
+                    "\n```json\n{}\n```\n",
+                    serde_json::to_string_pretty(schema)?
+                ));
+            }
+        }
+    }
+
     if !scripts.is_empty() {
         output.push_str("\n\n## Available Scripts\n\n");
         for script in &scripts {
@@ -257,6 +274,7 @@ pub(super) fn handle_run_skill(server: &mut McpServer, arguments: &Value) -> Res
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
     let scan_id = arguments.get("scan_id").and_then(|v| v.as_str());
+    let entry = arguments.get("entry").and_then(|v| v.as_str());
 
     // Find the skill
     let skill_dir = server.skills_dir.join(skill_name);
@@ -267,7 +285,10 @@ pub(super) fn handle_run_skill(server: &mut McpServer, arguments: &Value) -> Res
             server.skills_dir.display()
         )));
     }
-    let meta_early = metadata::parse_skill_metadata(&skill_dir)?;
+    let mut meta_early = metadata::parse_skill_metadata(&skill_dir)?;
+    if let Some(name) = entry {
+        meta_early.select_entry(name)?;
+    }
     if let Some(msg) = skilllite_core::skill::denylist::deny_reason_for_skill_name(&meta_early.name)
     {
         return Err(Error::msg(msg));
@@ -308,12 +329,26 @@ pub(super) fn handle_run_skill(server: &mut McpServer, arguments: &Value) -> Res
         );
     }
 
-    let meta = meta_early;
-    let sandbox_level = SandboxLevel::from_env_or_cli(None);
+    let mut meta = meta_early;
+    // Evolved skills: level floor + restricted network (skilllite_core::skill::origin).
+    let policy = SkillSandboxPolicy::for_skill(
+        &skill_dir,
+        SandboxLevel::from_env_or_cli(None).as_u8(),
+        &meta.network,
+    );
+    policy.apply_network(&meta.name, &mut meta.network);
+    let sandbox_level = SandboxLevel::from_env_or_cli(Some(policy.level));
 
     // Level 3: unified SKILL.md + entry script precheck (same as CLI runner / agent policy).
     // MCP is non-interactive: never rely on runner stdin; gate here with scan_id + skip runner precheck.
+    let mut entry_fingerprint = None;
     if sandbox_level == SandboxLevel::Level3 {
+        // Fingerprint the entry before it is hashed and scanned; the runner verifies the
+        // launch against this instead of re-capturing after the gate.
+        entry_fingerprint = skilllite_sandbox::move_protection::EntryFingerprint::capture(
+            &skill_dir.join(&meta.entry_point),
+        )
+        .ok();
         let code_hash = McpServer::compute_skill_hash(&skill_dir, &meta.entry_point);
 
         let already_confirmed = server
@@ -349,7 +384,11 @@ pub(super) fn handle_run_skill(server: &mut McpServer, arguments: &Value) -> Res
                 }
 
                 if cached.l3_script_critical {
-                    return Err(Error::msg(SKILL_PRECHECK_CRITICAL_BLOCKED.to_string()));
+                    return Err(skilllite_sandbox::Error::run(
+                        skilllite_sandbox::SandboxError::scan_blocked(Some(&cached.code)),
+                        SKILL_PRECHECK_CRITICAL_BLOCKED,
+                    )
+                    .into());
                 }
 
                 skilllite_core::observability::audit_confirmation_response(
@@ -374,10 +413,13 @@ pub(super) fn handle_run_skill(server: &mut McpServer, arguments: &Value) -> Res
                             severity: SecuritySeverity::High,
                             issue_type: SecurityIssueType::SystemAccess,
                             line_number: 0,
+                            column: None,
                             description: "Level-3 skill precheck: see report in this response."
                                 .to_string(),
                             code_snippet: String::new(),
+                            source_line: None,
                         }],
+                        suppressed: Vec::new(),
                     };
                     server.scan_cache.insert(
                         scan_token.clone(),
@@ -416,6 +458,14 @@ pub(super) fn handle_run_skill(server: &mut McpServer, arguments: &Value) -> Res
     let input_json = serde_json::to_string(&input)?;
 
     if meta.entry_point.is_empty() {
+        if !meta.entry_points.is_empty() {
+            let names: Vec<&str> = meta.entry_points.iter().map(|e| e.name.as_str()).collect();
+            return Ok(format!(
+                "Skill '{}' declares named entry points. Pass `entry` to pick one: {}",
+                skill_name,
+                names.join(", ")
+            ));
+        }
         // Prompt-only skill or multi-script skill without entry_point
         return Ok(format!(
             "Skill '{}' has no entry point. It is a prompt-only skill or uses multi-script tools.\n\
@@ -451,7 +501,11 @@ pub(super) fn handle_run_skill(server: &mut McpServer, arguments: &Value) -> Res
         language: metadata::detect_language(&skill_dir, &meta),
         network_enabled: meta.network.enabled,
         network_outbound: meta.network.outbound.clone(),
+        network_deny: meta.network.deny.clone(),
         uses_playwright: meta.uses_playwright(),
+        script_args: Vec::new(),
+        results_dir: None,
+        enforce_isolation: policy.enforced,
     };
     let output = skilllite_sandbox::runner::run_in_sandbox_with_limits_and_level_opt(
         &skill_dir,
@@ -462,8 +516,13 @@ pub(super) fn handle_run_skill(server: &mut McpServer, arguments: &Value) -> Res
         sandbox_level,
         skilllite_sandbox::runner::SandboxRunOptions {
             skip_skill_precheck: matches!(sandbox_level, SandboxLevel::Level3),
+            entry_fingerprint,
+            ..Default::default()
         },
     )?;
 
+    if policy.is_evolved() {
+        return Ok(policy.attach_to_output(&output));
+    }
     Ok(output)
 }
@@ -2,6 +2,7 @@
 //!
 //! Implements the standard MCP JSON-RPC 2.0 over stdio protocol.
 //! Provides 5 tools: list_skills, get_skill_info, run_skill, scan_code, execute_code.
+//! With the `agent` feature, also evolution_status, evolution_pending, evolution_confirm.
 //!
 //! This replaces the Python `mcp/server.py` implementation. Once this is
 //! complete, `skilllite mcp` can be removed from the Python SDK.
@@ -9,14 +10,17 @@
 //! Protocol flow:
 //!   1. Client sends `initialize` → Server returns capabilities
 //!   2. Client sends `notifications/initialized`
-//!   3. Client sends `tools/list` → Server returns 5 tool definitions
+//!   3. Client sends `tools/list` → Server returns the tool definitions
 //!   4. Client sends `tools/call` → Server executes tool, returns result
 //!
 //! Security model (two-phase confirmation):
 //!   - `scan_code` / auto-scan in `run_skill` / `execute_code` → returns scan_id
 //!   - Caller re-calls with `confirmed=true` + `scan_id` → executes
 //!   - Hard-blocked issues (Critical severity) cannot be overridden
+//!   - `evolution_confirm` → returns confirmation_token; re-call with it to apply
 
+#[cfg(feature = "agent")]
+mod evolution;
 mod handlers;
 mod scan;
 mod state;
@@ -133,7 +137,7 @@ pub fn serve_mcp_stdio(skills_dir: &str) -> Result<()> {
     if let Some(warning) = resolution.conflict_warning() {
         eprintln!("{}", warning);
     }
-    let mut server = McpServer::new(resolution.effective_path);
+    let mut server = McpServer::new(workspace, resolution.effective_path);
 
     let stdin = io::stdin();
     let mut stdout = io::stdout();
@@ -208,6 +212,14 @@ pub fn serve_mcp_stdio(skills_dir: &str) -> Result<()> {
                     "run_skill" => handle_run_skill(&mut server, &arguments),
                     "scan_code" => handle_scan_code(&mut server, &arguments),
                     "execute_code" => handle_execute_code(&mut server, &arguments),
+                    #[cfg(feature = "agent")]
+                    "evolution_status" => evolution::handle_evolution_status(&server),
+                    #[cfg(feature = "agent")]
+                    "evolution_pending" => evolution::handle_evolution_pending(&server),
+                    #[cfg(feature = "agent")]
+                    "evolution_confirm" => {
+                        evolution::handle_evolution_confirm(&mut server, &arguments)
+                    }
                     _ => Err(Error::msg(format!("Unknown tool: {}", tool_name))),
                 };
 
@@ -220,10 +232,13 @@ pub fn serve_mcp_stdio(skills_dir: &str) -> Result<()> {
                         send_response(&mut stdout, id, Ok(resp))?;
                     }
                     Err(e) => {
-                        let resp = json!({
+                        let mut resp = json!({
                             "content": [{"type": "text", "text": format!("Error: {}", e)}],
                             "isError": true
                         });
+                        if let Some(err) = e.sandbox_error() {
+                            resp["structuredContent"] = json!({ "error": err.to_json() });
+                        }
                         send_response(&mut stdout, id, Ok(resp))?;
                     }
                 }
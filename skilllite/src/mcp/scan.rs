@@ -4,7 +4,8 @@ use serde_json::{json, Value};
 
 use crate::Error;
 use crate::Result;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
 use std::time::Instant;
 
 use skilllite_sandbox::runner::{ResourceLimits, SandboxLevel};
@@ -17,6 +18,11 @@ use super::state::{CachedScan, McpServer};
 
 const ALLOWED_SCAN_LANGUAGES: &[&str] = &["python", "javascript", "node", "bash", "shell"];
 
+/// Caps on a `files` submission.
+const MAX_FILES: usize = 32;
+const MAX_FILE_BYTES: usize = 256 * 1024;
+const MAX_TOTAL_BYTES: usize = 1024 * 1024;
+
 fn validate_scan_language(language: &str) -> Result<()> {
     if ALLOWED_SCAN_LANGUAGES.contains(&language) {
         Ok(())
@@ -43,19 +49,198 @@ fn parse_sandbox_level_arg(arguments: &Value) -> Result<Option<u8>> {
     }
 }
 
+/// Code submitted to `scan_code` / `execute_code`.
+pub(super) enum CodeInput {
+    /// `code` string, written as `main.<ext>`.
+    Code(String),
+    /// `files` map (relative path → content) and the `entry` file to run.
+    Files {
+        files: BTreeMap<String, String>,
+        entry: String,
+    },
+}
+
+impl CodeInput {
+    /// Read `code`, or `files` + `entry`, from the tool arguments (exactly one form).
+    pub(super) fn from_args(arguments: &Value, language: &str) -> Result<Self> {
+        match (arguments.get("code"), arguments.get("files")) {
+            (Some(_), Some(_)) => Err(Error::msg("Pass either code or files, not both")),
+            (Some(code), None) => code
+                .as_str()
+                .map(|c| Self::Code(c.to_string()))
+                .ok_or_else(|| Error::msg("code must be a string")),
+            (None, Some(files)) => parse_files(files, arguments.get("entry"), language),
+            (None, None) => Err(Error::msg("code or files is required")),
+        }
+    }
+
+    /// Hash tying a scan_id to this exact submission.
+    pub(super) fn code_hash(&self, language: &str) -> String {
+        match self {
+            Self::Code(code) => McpServer::generate_code_hash(language, code),
+            Self::Files { files, entry } => McpServer::generate_files_hash(language, entry, files),
+        }
+    }
+
+    /// Short description kept with the cached scan.
+    fn describe(&self) -> String {
+        match self {
+            Self::Code(code) => code.clone(),
+            Self::Files { files, entry } => format!(
+                "entry={} files={}",
+                entry,
+                files.keys().cloned().collect::<Vec<_>>().join(",")
+            ),
+        }
+    }
+}
+
+fn parse_files(files: &Value, entry: Option<&Value>, language: &str) -> Result<CodeInput> {
+    let map = files
+        .as_object()
+        .ok_or_else(|| Error::msg("files must be an object of {relative_path: content}"))?;
+    if map.is_empty() || map.len() > MAX_FILES {
+        return Err(Error::msg(format!(
+            "files must contain between 1 and {} entries",
+            MAX_FILES
+        )));
+    }
+    let mut parsed = BTreeMap::new();
+    let mut total = 0usize;
+    for (path, content) in map {
+        let path = validate_relative_path(path)?;
+        let content = content
+            .as_str()
+            .ok_or_else(|| Error::msg(format!("Content of '{}' must be a string", path)))?;
+        if content.len() > MAX_FILE_BYTES {
+            return Err(Error::msg(format!(
+                "File '{}' exceeds {} bytes",
+                path, MAX_FILE_BYTES
+            )));
+        }
+        total += content.len();
+        if total > MAX_TOTAL_BYTES {
+            return Err(Error::msg(format!(
+                "files exceed {} bytes in total",
+                MAX_TOTAL_BYTES
+            )));
+        }
+        if parsed.insert(path.clone(), content.to_string()).is_some() {
+            return Err(Error::msg(format!("Duplicate file path '{}'", path)));
+        }
+    }
+    let entry = entry
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::msg("entry is required with files"))?;
+    let entry = validate_relative_path(entry)?;
+    if !parsed.contains_key(&entry) {
+        return Err(Error::msg(format!("entry '{}' is not in files", entry)));
+    }
+    let ext = Path::new(&entry)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    if format!(".{}", ext) != script_extension(language)? {
+        return Err(Error::msg(format!(
+            "entry '{}' does not match language {}",
+            entry, language
+        )));
+    }
+    Ok(CodeInput::Files {
+        files: parsed,
+        entry,
+    })
+}
+
+/// Normalize a submitted path to `a/b/c`; rejects absolute paths, drive prefixes and `..`.
+fn validate_relative_path(path: &str) -> Result<String> {
+    let normalized = path.replace('\\', "/");
+    let mut parts = Vec::new();
+    for component in Path::new(&normalized).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(Error::msg(format!(
+                    "Invalid file path '{}': must be relative without '..'",
+                    path
+                )));
+            }
+        }
+    }
+    if parts.is_empty() || parts.iter().any(|p| p.contains(':')) {
+        return Err(Error::msg(format!("Invalid file path '{}'", path)));
+    }
+    Ok(parts.join("/"))
+}
+
+fn script_extension(language: &str) -> Result<&'static str> {
+    match language {
+        "python" => Ok(".py"),
+        "javascript" | "node" => Ok(".js"),
+        "bash" | "shell" => Ok(".sh"),
+        _ => Err(Error::msg(format!("Unsupported language: {}", language))),
+    }
+}
+
+/// Per-call directory holding the submitted files; removed on drop, so every return path
+/// (scan rejection included) cleans it up.
+pub(super) struct Scratch {
+    dir: tempfile::TempDir,
+    entry: String,
+    /// (relative path, content), in path order.
+    files: Vec<(String, String)>,
+    multi_file: bool,
+}
+
+impl Scratch {
+    pub(super) fn create(root: &Path, language: &str, input: &CodeInput) -> Result<Self> {
+        let (entry, files, multi_file) = match input {
+            CodeInput::Code(code) => {
+                let entry = format!("main{}", script_extension(language)?);
+                (entry.clone(), vec![(entry, code.clone())], false)
+            }
+            CodeInput::Files { files, entry } => (
+                entry.clone(),
+                files.iter().map(|(p, c)| (p.clone(), c.clone())).collect(),
+                true,
+            ),
+        };
+        std::fs::create_dir_all(root)?;
+        let dir = tempfile::Builder::new()
+            .prefix("skilllite-exec-")
+            .tempdir_in(root)?;
+        for (rel, content) in &files {
+            let path = dir.path().join(rel);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, content)?;
+        }
+        Ok(Self {
+            dir,
+            entry,
+            files,
+            multi_file,
+        })
+    }
+
+    pub(super) fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
 /// Handle the `scan_code` tool call.
 pub(super) fn handle_scan_code(server: &mut McpServer, arguments: &Value) -> Result<String> {
     let language = arguments
         .get("language")
         .and_then(|v| v.as_str())
         .ok_or_else(|| Error::msg("language is required"))?;
-    let code = arguments
-        .get("code")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| Error::msg("code is required"))?;
     validate_scan_language(language)?;
+    let input = CodeInput::from_args(arguments, language)?;
+    let scratch = Scratch::create(&server.scratch_root, language, &input)?;
 
-    let (scan_result, scan_id, code_hash) = perform_scan(server, language, code)?;
+    let (scan_result, scan_id, code_hash) = perform_scan(server, language, &input, &scratch)?;
 
     format_scan_response(&scan_result, &scan_id, &code_hash)
 }
@@ -70,9 +255,12 @@ pub(super) fn scan_error_result(err: &str) -> ScanResult {
             severity: SecuritySeverity::High,
             issue_type: SecurityIssueType::ScanError,
             line_number: 0,
+            column: None,
             description: format!("Security scan failed: {}. Manual review required.", err),
             code_snippet: String::new(),
+            source_line: None,
         }],
+        suppressed: Vec::new(),
     }
 }
 
@@ -82,12 +270,14 @@ pub(super) fn scan_error_result(err: &str) -> ScanResult {
 pub(super) fn perform_scan(
     server: &mut McpServer,
     language: &str,
-    code: &str,
+    input: &CodeInput,
+    scratch: &Scratch,
 ) -> Result<(ScanResult, String, String)> {
-    let code_hash = McpServer::generate_code_hash(language, code);
+    let code_hash = input.code_hash(language);
     let scan_id = McpServer::generate_scan_id(&code_hash);
+    let code = input.describe();
 
-    let scan_result = match do_scan(language, code) {
+    let scan_result = match do_scan(scratch) {
         Ok(r) => r,
         Err(e) => {
             // Fail-secure: return ScanResult requiring confirmation, not Err
@@ -98,7 +288,7 @@ pub(super) fn perform_scan(
                     scan_result: err_result.clone(),
                     code_hash: code_hash.clone(),
                     language: language.to_string(),
-                    code: code.to_string(),
+                    code: code.clone(),
                     created_at: Instant::now(),
                     is_l3_skill_precheck: false,
                     l3_script_critical: false,
@@ -115,7 +305,7 @@ pub(super) fn perform_scan(
             scan_result: scan_result.clone(),
             code_hash: code_hash.clone(),
             language: language.to_string(),
-            code: code.to_string(),
+            code,
             created_at: Instant::now(),
             is_l3_skill_precheck: false,
             l3_script_critical: false,
@@ -125,20 +315,29 @@ pub(super) fn perform_scan(
     Ok((scan_result, scan_id, code_hash))
 }
 
-/// Inner scan logic — may return Err on temp file or scanner failure.
-fn do_scan(language: &str, code: &str) -> Result<ScanResult> {
-    let ext = match language {
-        "python" => ".py",
-        "javascript" | "node" => ".js",
-        "bash" | "shell" => ".sh",
-        _ => ".txt",
-    };
-    let temp_dir = tempfile::tempdir()?;
-    let temp_path = temp_dir.path().join(format!("scan{}", ext));
-    std::fs::write(&temp_path, code)?;
-
+/// Inner scan logic — scans every file in the scratch dir and merges the findings; with
+/// several files each description is prefixed by its path. May return Err on scanner failure.
+fn do_scan(scratch: &Scratch) -> Result<ScanResult> {
     let scanner = ScriptScanner::new();
-    scanner.scan_file(&temp_path).map_err(Into::into)
+    let mut merged = ScanResult {
+        is_safe: true,
+        issues: Vec::new(),
+        suppressed: Vec::new(),
+    };
+    for (rel, content) in &scratch.files {
+        let result = scanner.scan_content(content, &scratch.path().join(rel))?;
+        merged.is_safe &= result.is_safe;
+        merged
+            .issues
+            .extend(result.issues.into_iter().map(|mut issue| {
+                if scratch.multi_file {
+                    issue.description = format!("{}: {}", rel, issue.description);
+                }
+                issue
+            }));
+        merged.suppressed.extend(result.suppressed);
+    }
+    Ok(merged)
 }
 
 /// Format a scan result as a human-readable response.
@@ -259,11 +458,8 @@ pub(super) fn handle_execute_code(server: &mut McpServer, arguments: &Value) ->
         .get("language")
         .and_then(|v| v.as_str())
         .ok_or_else(|| Error::msg("language is required"))?;
-    let code = arguments
-        .get("code")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| Error::msg("code is required"))?;
     validate_scan_language(language)?;
+    let input = CodeInput::from_args(arguments, language)?;
     let confirmed = arguments
         .get("confirmed")
         .and_then(|v| v.as_bool())
@@ -273,6 +469,8 @@ pub(super) fn handle_execute_code(server: &mut McpServer, arguments: &Value) ->
 
     let sandbox_level = SandboxLevel::from_env_or_cli(sandbox_level_arg);
 
+    let scratch = Scratch::create(&server.scratch_root, language, &input)?;
+
     // For Level 3: automatic security scan
     if sandbox_level == SandboxLevel::Level3 {
         if confirmed {
@@ -302,7 +500,7 @@ pub(super) fn handle_execute_code(server: &mut McpServer, arguments: &Value) ->
             };
 
             // Verify code_hash matches
-            let current_hash = McpServer::generate_code_hash(language, code);
+            let current_hash = input.code_hash(language);
             if cached_code_hash != current_hash {
                 return Err(Error::msg(
                     "Code has changed since the scan. Please call scan_code again with the new code.",
@@ -337,7 +535,8 @@ pub(super) fn handle_execute_code(server: &mut McpServer, arguments: &Value) ->
             );
         } else {
             // Auto-scan
-            let (scan_result, new_scan_id, code_hash) = perform_scan(server, language, code)?;
+            let (scan_result, new_scan_id, code_hash) =
+                perform_scan(server, language, &input, &scratch)?;
 
             let has_high = scan_result.issues.iter().any(|i| {
                 matches!(
@@ -355,50 +554,40 @@ pub(super) fn handle_execute_code(server: &mut McpServer, arguments: &Value) ->
     }
 
     // Execute the code
-    execute_code_in_sandbox(language, code, sandbox_level)
+    execute_in_sandbox(&scratch, language, sandbox_level)
 }
 
-/// Execute code in the sandbox.
-pub(super) fn execute_code_in_sandbox(
+/// Execute the scratch dir's entry file in the sandbox.
+pub(super) fn execute_in_sandbox(
+    scratch: &Scratch,
     language: &str,
-    code: &str,
     sandbox_level: SandboxLevel,
 ) -> Result<String> {
-    let ext = match language {
-        "python" => ".py",
-        "javascript" | "node" => ".js",
-        "bash" | "shell" => ".sh",
-        _ => return Err(Error::msg(format!("Unsupported language: {}", language))),
-    };
-
-    // Create a temporary skill-like directory
-    let temp_dir = tempfile::tempdir()?;
-    let script_name = format!("main{}", ext);
-    let script_path = temp_dir.path().join(&script_name);
-    std::fs::write(&script_path, code)?;
-
-    // Create minimal metadata
     let lang_str = match language {
         "python" => "python",
         "javascript" | "node" => "node",
         "bash" | "shell" => "shell",
-        _ => "python",
+        _ => return Err(Error::msg(format!("Unsupported language: {}", language))),
     };
 
     let config = skilllite_sandbox::runner::SandboxConfig {
         name: "execute_code".to_string(),
-        entry_point: script_name,
+        entry_point: scratch.entry.clone(),
         language: lang_str.to_string(),
         network_enabled: false,
         network_outbound: Vec::new(),
+        network_deny: Vec::new(),
         uses_playwright: false,
+        script_args: Vec::new(),
+        results_dir: None,
+        enforce_isolation: false,
     };
 
     let limits = ResourceLimits::from_env();
     let runtime = skilllite_sandbox::env::builder::build_runtime_paths(&PathBuf::new());
 
     let output = skilllite_sandbox::runner::run_in_sandbox_with_limits_and_level(
-        temp_dir.path(),
+        scratch.path(),
         &runtime,
         &config,
         "{}",
@@ -434,4 +623,117 @@ mod tests {
         assert!(parse_sandbox_level_arg(&json!({"sandbox_level": "3"})).is_err());
         assert!(parse_sandbox_level_arg(&json!({"sandbox_level": 9})).is_err());
     }
+
+    fn server() -> (tempfile::TempDir, McpServer) {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut server = McpServer::new(tmp.path().to_path_buf(), tmp.path().join("skills"));
+        server.scratch_root = tmp.path().join("scratch");
+        (tmp, server)
+    }
+
+    fn scratch_is_empty(server: &McpServer) -> bool {
+        std::fs::read_dir(&server.scratch_root).map_or(true, |mut d| d.next().is_none())
+    }
+
+    fn package_files(helper: &str) -> Value {
+        json!({
+            "main.py": "import json\nfrom pkg.helper import greet\nprint(json.dumps({'greeting': greet()}))\n",
+            "pkg/__init__.py": "",
+            "pkg/helper.py": helper,
+        })
+    }
+
+    fn scan_details(report: &str) -> Value {
+        let start = report.find("```json\n").unwrap() + "```json\n".len();
+        let end = report[start..].find("\n```").unwrap() + start;
+        serde_json::from_str(&report[start..end]).unwrap()
+    }
+
+    #[test]
+    fn execute_code_runs_two_file_python_package() {
+        let (_tmp, mut server) = server();
+        let args = json!({
+            "language": "python",
+            "files": package_files("def greet():\n    return 'hello from helper'\n"),
+            "entry": "main.py",
+            "sandbox_level": 1,
+        });
+        let out = handle_execute_code(&mut server, &args).unwrap();
+        assert!(out.contains("hello from helper"), "{}", out);
+        assert!(scratch_is_empty(&server));
+    }
+
+    #[test]
+    fn execute_code_rejects_unsafe_file_paths() {
+        let (_tmp, mut server) = server();
+        for bad in [
+            "../escape.py",
+            "/etc/evil.py",
+            "pkg/../../x.py",
+            "C:\\evil.py",
+        ] {
+            let args = json!({
+                "language": "python",
+                "files": {"main.py": "print(1)", bad: "x = 1"},
+                "entry": "main.py",
+                "sandbox_level": 1,
+            });
+            let err = handle_execute_code(&mut server, &args).unwrap_err();
+            assert!(
+                err.to_string().contains("Invalid file path"),
+                "{}: {}",
+                bad,
+                err
+            );
+        }
+        let missing_entry = json!({"language": "python", "files": {"a.py": ""}, "entry": "b.py"});
+        assert!(handle_execute_code(&mut server, &missing_entry).is_err());
+        let both =
+            json!({"language": "python", "code": "", "files": {"a.py": ""}, "entry": "a.py"});
+        assert!(handle_execute_code(&mut server, &both).is_err());
+        assert!(scratch_is_empty(&server));
+    }
+
+    #[test]
+    fn multi_file_confirmation_round_trip() {
+        let (_tmp, mut server) = server();
+        let risky = "import subprocess\n\ndef greet():\n    return subprocess.run(['echo', 'hi'], capture_output=True).stdout\n";
+        let args = json!({
+            "language": "python",
+            "files": package_files(risky),
+            "entry": "main.py",
+            "sandbox_level": 3,
+        });
+        let report = handle_execute_code(&mut server, &args).unwrap();
+        assert!(report.contains("pkg/helper.py: "), "{}", report);
+        let details = scan_details(&report);
+        assert_eq!(details["requires_confirmation"], true);
+        let scan_id = details["scan_id"].as_str().unwrap().to_string();
+        // Scan rejection still removes the scratch dir.
+        assert!(scratch_is_empty(&server));
+
+        let mut edited = args.clone();
+        edited["files"]["pkg/helper.py"] = json!("def greet():\n    return 'edited'\n");
+        edited["confirmed"] = json!(true);
+        edited["scan_id"] = json!(scan_id);
+        let err = handle_execute_code(&mut server, &edited).unwrap_err();
+        assert!(err.to_string().contains("Code has changed"), "{}", err);
+        assert!(server.scan_cache.contains_key(&scan_id));
+
+        let mut confirmed = args.clone();
+        confirmed["confirmed"] = json!(true);
+        confirmed["scan_id"] = json!(scan_id);
+        // Execution itself may fail where no sandbox backend exists; the confirmation passed
+        // once the scan_id has been consumed.
+        if let Err(e) = handle_execute_code(&mut server, &confirmed) {
+            let msg = e.to_string();
+            assert!(
+                !msg.contains("scan_id") && !msg.contains("Code has changed"),
+                "{}",
+                msg
+            );
+        }
+        assert!(!server.scan_cache.contains_key(&scan_id));
+        assert!(scratch_is_empty(&server));
+    }
 }
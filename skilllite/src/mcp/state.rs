@@ -1,7 +1,7 @@
 //! MCP Server state: cache structures and server instance.
 
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
@@ -28,33 +28,65 @@ pub(super) struct ConfirmedSkill {
     pub code_hash: String,
 }
 
+/// `evolution_confirm` decision awaiting its confirmation token.
+#[cfg(feature = "agent")]
+pub(super) struct PendingEvolutionDecision {
+    pub skill_name: String,
+    /// `true` = confirm, `false` = reject.
+    pub confirm: bool,
+    /// Hash of the pending skill's files at review time.
+    pub content_hash: String,
+    pub created_at: Instant,
+}
+
 /// MCP Server state maintained across requests.
 pub(super) struct McpServer {
+    /// Workspace root (current directory of `skilllite mcp`)
+    #[cfg_attr(not(feature = "agent"), allow(dead_code))]
+    pub workspace: PathBuf,
     /// Skills directory path
     pub skills_dir: PathBuf,
     /// Scan result cache: scan_id → CachedScan (TTL: 300s)
     pub scan_cache: HashMap<String, CachedScan>,
     /// Session-level confirmation cache: skill_name → ConfirmedSkill
     pub confirmed_skills: HashMap<String, ConfirmedSkill>,
+    /// Evolution confirmation tokens: token → PendingEvolutionDecision (same TTL as scans)
+    #[cfg(feature = "agent")]
+    pub evolution_tokens: HashMap<String, PendingEvolutionDecision>,
     /// Scan cache TTL
     pub cache_ttl: Duration,
+    /// Parent of the per-call `execute_code` / `scan_code` scratch directories
+    pub scratch_root: PathBuf,
 }
 
 impl McpServer {
-    pub fn new(skills_dir: PathBuf) -> Self {
+    pub fn new(workspace: PathBuf, skills_dir: PathBuf) -> Self {
         Self {
+            workspace,
             skills_dir,
             scan_cache: HashMap::new(),
             confirmed_skills: HashMap::new(),
+            #[cfg(feature = "agent")]
+            evolution_tokens: HashMap::new(),
             cache_ttl: Duration::from_secs(300),
+            scratch_root: std::env::temp_dir(),
         }
     }
 
-    /// Remove expired scan cache entries.
+    /// Workspace root as the string form the evolution commands take.
+    #[cfg(feature = "agent")]
+    pub fn workspace_str(&self) -> String {
+        self.workspace.to_string_lossy().into_owned()
+    }
+
+    /// Remove expired scan cache entries (and expired evolution confirmation tokens).
     pub fn cleanup_expired_scans(&mut self) {
         let now = Instant::now();
         self.scan_cache
             .retain(|_, v| now.duration_since(v.created_at) < self.cache_ttl);
+        #[cfg(feature = "agent")]
+        self.evolution_tokens
+            .retain(|_, v| now.duration_since(v.created_at) < self.cache_ttl);
     }
 
     /// Generate a code hash: SHA256(language:code) full hexdigest.
@@ -65,6 +97,24 @@ impl McpServer {
         hex::encode(hasher.finalize())
     }
 
+    /// Hash of a multi-file submission: language, entry, then each `path NUL content NUL`
+    /// in path order.
+    pub fn generate_files_hash(
+        language: &str,
+        entry: &str,
+        files: &BTreeMap<String, String>,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}:{}\n", language, entry).as_bytes());
+        for (path, content) in files {
+            hasher.update(path.as_bytes());
+            hasher.update([0]);
+            hasher.update(content.as_bytes());
+            hasher.update([0]);
+        }
+        hex::encode(hasher.finalize())
+    }
+
     /// Generate a scan_id: SHA256(code_hash:timestamp)[:16].
     pub fn generate_scan_id(code_hash: &str) -> String {
         let timestamp = std::time::SystemTime::now()
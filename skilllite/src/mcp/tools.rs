@@ -1,10 +1,11 @@
-//! MCP tool definitions — the 5 tools exposed by the MCP server.
+//! MCP tool definitions — the 5 core tools, plus evolution tools with the `agent` feature.
 
 use serde_json::{json, Value};
 
-/// Return the 5 MCP tool definitions.
+/// Return the MCP tool definitions.
 pub(super) fn get_mcp_tools() -> Vec<Value> {
-    vec![
+    #[allow(unused_mut)]
+    let mut tools = vec![
         json!({
             "name": "list_skills",
             "description": "List all available skills with their names, descriptions, and languages. Returns a formatted list of installed skills.",
@@ -42,6 +43,10 @@ pub(super) fn get_mcp_tools() -> Vec<Value> {
                         "type": "object",
                         "description": "Input parameters for the skill"
                     },
+                    "entry": {
+                        "type": "string",
+                        "description": "Named command to run, for skills that declare entry_points in SKILL.md (see get_skill_info)"
+                    },
                     "confirmed": {
                         "type": "boolean",
                         "description": "Set to true ONLY after the user has explicitly approved execution. You must ask the user for confirmation first."
@@ -67,10 +72,19 @@ pub(super) fn get_mcp_tools() -> Vec<Value> {
                     },
                     "code": {
                         "type": "string",
-                        "description": "Code to scan for security issues"
+                        "description": "Code to scan for security issues (or use files + entry)"
+                    },
+                    "files": {
+                        "type": "object",
+                        "description": "Multi-file submission: {relative_path: content}; every file is scanned. Use instead of code, with entry.",
+                        "additionalProperties": { "type": "string" }
+                    },
+                    "entry": {
+                        "type": "string",
+                        "description": "Key of files to execute; required with files"
                     }
                 },
-                "required": ["language", "code"]
+                "required": ["language"]
             }
         }),
         json!({
@@ -86,7 +100,16 @@ pub(super) fn get_mcp_tools() -> Vec<Value> {
                     },
                     "code": {
                         "type": "string",
-                        "description": "Code to execute"
+                        "description": "Code to execute (or use files + entry)"
+                    },
+                    "files": {
+                        "type": "object",
+                        "description": "Multi-file submission: {relative_path: content} (helper modules, data files). Paths must be relative without '..'; max 32 files, 256 KiB each, 1 MiB total. Use instead of code, with entry.",
+                        "additionalProperties": { "type": "string" }
+                    },
+                    "entry": {
+                        "type": "string",
+                        "description": "Key of files to execute; must match language (.py / .js / .sh). Required with files."
                     },
                     "confirmed": {
                         "type": "boolean",
@@ -104,7 +127,57 @@ pub(super) fn get_mcp_tools() -> Vec<Value> {
                         "enum": [1, 2, 3]
                     }
                 },
-                "required": ["language", "code"]
+                "required": ["language"]
+            }
+        }),
+    ];
+    #[cfg(feature = "agent")]
+    tools.extend(evolution_tools());
+    tools
+}
+
+/// Evolution management tools (`skilllite evolution status / pending / confirm / reject`).
+#[cfg(feature = "agent")]
+fn evolution_tools() -> Vec<Value> {
+    vec![
+        json!({
+            "name": "evolution_status",
+            "description": "Get the self-evolution engine status for the workspace: mode, metrics, rules, pending skill count. Same data as `skilllite evolution status --json`.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        }),
+        json!({
+            "name": "evolution_pending",
+            "description": "List evolved skills waiting for confirmation, with a SKILL.md preview and a static security scan summary for each.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        }),
+        json!({
+            "name": "evolution_confirm",
+            "description": "Confirm (add) or reject (delete) a pending evolved skill. The first call returns a review payload and a confirmation_token; nothing changes until you call again with the token. IMPORTANT: show the review to the user and ASK for their explicit approval before sending the token.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "skill_name": {
+                        "type": "string",
+                        "description": "Name of the pending skill (from evolution_pending)"
+                    },
+                    "confirm": {
+                        "type": "boolean",
+                        "description": "true to confirm the skill, false to reject it"
+                    },
+                    "confirmation_token": {
+                        "type": "string",
+                        "description": "Token from the review call. Send ONLY after the user has approved; expires after 300s and works once."
+                    }
+                },
+                "required": ["skill_name", "confirm"]
             }
         }),
     ]
@@ -20,11 +20,36 @@
 //! 2. Implement [`ProtocolHandler`] for your handler struct.
 //! 3. Add a `Commands` variant in `cli.rs`.
 //! 4. Add a match arm in `lib.rs`: `Commands::X { .. } => XHandler.serve(params)?`.
+//! 5. Override [`ProtocolHandler::methods`] and answer `initialize` with
+//!    [`ProtocolHandler::server_info`] so clients can negotiate (see
+//!    [`skilllite_core::protocol::negotiation`]).
 
 use crate::mcp;
 use crate::stdio_rpc;
 use crate::Error;
 use crate::Result;
+use skilllite_core::protocol::negotiation::{MethodSpec, ServerInfo};
+
+/// Cargo features compiled into this binary, advertised in the `initialize` result.
+pub fn compiled_features() -> Vec<String> {
+    [
+        ("sandbox", cfg!(feature = "sandbox")),
+        ("audit", cfg!(feature = "audit")),
+        ("executor", cfg!(feature = "executor")),
+        ("agent", cfg!(feature = "agent")),
+        ("memory_vector", cfg!(feature = "memory_vector")),
+        ("local_embedding", cfg!(feature = "local_embedding")),
+        ("swarm", cfg!(feature = "swarm")),
+        ("artifact_http", cfg!(feature = "artifact_http")),
+        ("channel_serve", cfg!(feature = "channel_serve")),
+        ("gateway", cfg!(feature = "gateway")),
+        ("otel", cfg!(feature = "otel")),
+    ]
+    .into_iter()
+    .filter(|(_, on)| *on)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
 
 // ─── Protocol Parameters ─────────────────────────────────────────────────────
 
@@ -54,9 +79,14 @@ pub enum ProtocolParams {
         capability_tags: Vec<String>,
         /// Skills directory (for loading .env from project root when cwd differs).
         skills_dir: Option<Vec<String>>,
+        /// Static peers (`--peer host:port`), merged with `peers.toml` under the data root.
+        static_peers: Vec<String>,
         /// Executor for local task execution (when swarm+agent enabled).
         #[cfg(feature = "swarm")]
         executor: Option<std::sync::Arc<dyn skilllite_swarm::TaskExecutor>>,
+        /// Skill replication from peers (`--allow-skill-replication`).
+        #[cfg(feature = "swarm")]
+        replication: Option<skilllite_swarm::SkillReplication>,
     },
 }
 
@@ -78,6 +108,23 @@ pub trait ProtocolHandler: Send + Sync {
 
     /// Start the protocol server.  Blocks until shutdown.
     fn serve(&self, params: ProtocolParams) -> Result<()>;
+
+    /// Methods and params this transport understands, advertised by `initialize` and used
+    /// for `strict` request checks. Transports with their own handshake (MCP) or no
+    /// request/response framing (swarm HTTP) keep the empty default.
+    fn methods(&self) -> Vec<MethodSpec> {
+        Vec::new()
+    }
+
+    /// `initialize` result: binary version, protocol version, methods and compiled features.
+    fn server_info(&self) -> ServerInfo {
+        ServerInfo::new(
+            self.name(),
+            env!("CARGO_PKG_VERSION"),
+            self.methods(),
+            compiled_features(),
+        )
+    }
 }
 
 // ─── Handler Implementations ─────────────────────────────────────────────────
@@ -92,10 +139,14 @@ impl ProtocolHandler for StdioRpcHandler {
 
     fn serve(&self, params: ProtocolParams) -> Result<()> {
         match params {
-            ProtocolParams::Stdio => stdio_rpc::serve_stdio(),
+            ProtocolParams::Stdio => stdio_rpc::serve_stdio(self.server_info()),
             _ => Err(Error::msg("StdioRpcHandler requires ProtocolParams::Stdio")),
         }
     }
+
+    fn methods(&self) -> Vec<MethodSpec> {
+        stdio_rpc::methods()
+    }
 }
 
 /// MCP (Model Context Protocol) handler (`skilllite mcp`).
@@ -130,12 +181,18 @@ impl ProtocolHandler for AgentRpcHandler {
 
     fn serve(&self, params: ProtocolParams) -> Result<()> {
         match params {
-            ProtocolParams::AgentRpc => skilllite_agent::rpc::serve_agent_rpc().map_err(Into::into),
+            ProtocolParams::AgentRpc => {
+                skilllite_agent::rpc::serve_agent_rpc(self.server_info()).map_err(Into::into)
+            }
             _ => Err(Error::msg(
                 "AgentRpcHandler requires ProtocolParams::AgentRpc",
             )),
         }
     }
+
+    fn methods(&self) -> Vec<MethodSpec> {
+        skilllite_agent::rpc::methods()
+    }
 }
 
 /// Swarm P2P mesh handler (`skilllite swarm --listen <ADDR>`).
@@ -156,7 +213,9 @@ impl ProtocolHandler for SwarmHandler {
                 listen_addr,
                 capability_tags,
                 skills_dir,
+                static_peers,
                 executor,
+                replication,
             } = params
             else {
                 return Err(Error::msg("SwarmHandler requires ProtocolParams::P2p"));
@@ -165,7 +224,9 @@ impl ProtocolHandler for SwarmHandler {
                 &listen_addr,
                 capability_tags,
                 skills_dir.as_deref(),
+                &static_peers,
                 executor,
+                replication,
             )
             .map_err(Into::into)
         }
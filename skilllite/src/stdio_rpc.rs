@@ -12,6 +12,20 @@
 //!
 //! Request: `{"jsonrpc":"2.0","id":1,"method":"run"|"exec"|...","params":{...}}`
 //! Response: `{"jsonrpc":"2.0","id":1,"result":{...}}` or `{"jsonrpc":"2.0","id":1,"error":{...}}`
+//!
+//! Optional request field `"ordered": true` holds the response until all earlier requests
+//! have been answered. Error codes: `-32700` parse error, `-32600` oversized request,
+//! `-32603` handler failure, `-32001` session/plan file lock contention (retry later). A failed `run` / `exec` with a classified cause also carries
+//! `error.data = {"kind": "timeout", "details": {...}}` (see `skilllite_sandbox::SandboxError`).
+//!
+//! `initialize` returns the binary version, protocol version, supported methods with their
+//! params, and compiled features. Unknown params are ignored unless the request sets
+//! `"strict": true` (or a list of required param names), which answers `-32602` for params
+//! the method does not know (see [`skilllite_core::protocol::negotiation`]).
+//!
+//! `list_tools` / `build_skills_context` responses are cached while the skills dir is
+//! unchanged and carry `"cache": "hit" | "miss"`; pass `"refresh": true` to rebuild
+//! (see [`crate::stdio_rpc_cache`]).
 
 use serde_json::{json, Value};
 
@@ -20,44 +34,183 @@ use crate::Result;
 use skilllite_commands::execute;
 #[cfg(feature = "agent")]
 use skilllite_core::path_validation;
-use skilllite_sandbox::runner::{ResourceLimits, SandboxLevel};
+use skilllite_core::protocol::negotiation::{self, MethodSpec, ServerInfo};
+use skilllite_sandbox::runner::{ResourceLimits, SandboxLevel, RUN_METADATA_KEY};
 
+#[cfg(feature = "agent")]
+use crate::stdio_rpc_cache::{with_cache_status, ResponseCache};
 use crate::stdio_rpc_params::{IpcBashParams, IpcExecParams, IpcRunParams};
 #[cfg(feature = "agent")]
 use crate::stdio_rpc_params::{IpcBuildSkillsContextParams, IpcListToolsParams};
+
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{self, BufRead, BufReader, Write};
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc, Condvar, Mutex};
 use std::thread;
 
+use skilllite_core::config::env_keys::ipc as ipc_keys;
+
 /// Maximum JSON-RPC request size (10 MB) to prevent OOM DoS.
 const MAX_REQUEST_SIZE: usize = 10 * 1024 * 1024;
 
+/// JSON-RPC 2.0 error codes used by this daemon (same as the MCP server).
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+/// Server-defined: a session or plan file was locked by another writer past the wait budget.
+const LOCK_CONTENTION: i64 = -32001;
+
+/// One response on its way to the writer thread.
+struct RpcResponse {
+    /// Arrival order of the request line (every line gets one, including rejected ones).
+    seq: u64,
+    /// When true the response is held until all earlier responses were flushed.
+    ordered: bool,
+    id: Value,
+    result: std::result::Result<Value, (i64, String)>,
+    /// JSON-RPC `error.data`: `{kind, details}` of a classified skill run failure.
+    error_data: Option<Value>,
+}
+
+impl RpcResponse {
+    fn to_json(&self) -> Value {
+        match &self.result {
+            Ok(res) => json!({"jsonrpc": "2.0", "id": self.id, "result": res}),
+            Err((code, msg)) => {
+                let mut error = json!({"code": code, "message": msg});
+                if let Some(data) = &self.error_data {
+                    error["data"] = data.clone();
+                }
+                json!({"jsonrpc": "2.0", "id": self.id, "error": error})
+            }
+        }
+    }
+}
+
+/// Reorder buffer: unordered responses pass straight through, ordered ones wait until
+/// every response with a smaller `seq` has been written.
+#[derive(Default)]
+struct ReorderBuffer {
+    next_seq: u64,
+    written: BTreeSet<u64>,
+    held: BTreeMap<u64, RpcResponse>,
+}
+
+impl ReorderBuffer {
+    /// Accept one response and return the responses that may be written now, in write order.
+    fn push(&mut self, resp: RpcResponse) -> Vec<RpcResponse> {
+        let mut out = Vec::new();
+        if resp.ordered {
+            self.held.insert(resp.seq, resp);
+        } else {
+            self.written.insert(resp.seq);
+            out.push(resp);
+        }
+        loop {
+            if self.written.remove(&self.next_seq) {
+                self.next_seq += 1;
+            } else if let Some(resp) = self.held.remove(&self.next_seq) {
+                self.next_seq += 1;
+                out.push(resp);
+            } else {
+                break;
+            }
+        }
+        out
+    }
+}
+
+/// Counting semaphore bounding in-flight requests. The stdin reader blocks in
+/// [`Semaphore::acquire`] when the limit is reached, which pushes back on the client.
+struct Semaphore {
+    permits: Mutex<usize>,
+    available: Condvar,
+}
+
+/// Returns its permit to the [`Semaphore`] on drop.
+struct Permit(Arc<Semaphore>);
+
+impl Semaphore {
+    fn new(permits: usize) -> Arc<Self> {
+        Arc::new(Self {
+            permits: Mutex::new(permits.max(1)),
+            available: Condvar::new(),
+        })
+    }
+
+    fn acquire(self: &Arc<Self>) -> Permit {
+        // A poisoned lock only means a worker panicked; the counter itself is still valid.
+        let mut permits = self.permits.lock().unwrap_or_else(|e| e.into_inner());
+        while *permits == 0 {
+            permits = self
+                .available
+                .wait(permits)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *permits -= 1;
+        Permit(Arc::clone(self))
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut permits = self.0.permits.lock().unwrap_or_else(|e| e.into_inner());
+        *permits += 1;
+        self.0.available.notify_one();
+    }
+}
+
+/// Default concurrency floor: requests mostly wait on child processes, so even a single-CPU
+/// host should run several at once.
+const MIN_DEFAULT_CONCURRENCY: usize = 4;
+
+/// `SKILLLITE_IPC_MAX_CONCURRENCY`, defaulting to the number of available CPUs (at least
+/// [`MIN_DEFAULT_CONCURRENCY`]).
+fn max_concurrency_from_env() -> usize {
+    std::env::var(ipc_keys::SKILLLITE_IPC_MAX_CONCURRENCY)
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or_else(|| {
+            thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(MIN_DEFAULT_CONCURRENCY)
+                .max(MIN_DEFAULT_CONCURRENCY)
+        })
+}
+
 /// Run the skill execution stdio RPC daemon.
 ///
 /// Reads JSON-RPC requests from stdin (one per line), writes responses to stdout.
-/// Uses rayon thread pool for concurrent request handling.
-pub fn serve_stdio() -> Result<()> {
+/// Uses a rayon thread pool with one thread per allowed in-flight request
+/// (`SKILLLITE_IPC_MAX_CONCURRENCY`) for concurrent request handling. Responses are written as they
+/// complete unless the request sets `"ordered": true` (or `SKILLLITE_IPC_ORDERED=1`),
+/// in which case they are flushed in request order.
+pub fn serve_stdio(info: ServerInfo) -> Result<()> {
     skilllite_core::config::init_daemon_env();
 
-    let (tx, rx) = mpsc::channel::<(Value, std::result::Result<Value, String>)>();
+    let info = Arc::new(info);
+    let ordered_by_default =
+        skilllite_core::config::env_bool(ipc_keys::SKILLLITE_IPC_ORDERED, &[], false);
+    let max_concurrency = max_concurrency_from_env();
+    let limiter = Semaphore::new(max_concurrency);
+    // Not the global pool: that one is sized to the CPU count, which would serialize
+    // requests that just wait on their child process.
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(max_concurrency)
+        .thread_name(|i| format!("stdio-rpc-{}", i))
+        .build()
+        .map_err(|e| Error::msg(format!("Failed to start request pool: {}", e)))?;
+    let (tx, rx) = mpsc::channel::<RpcResponse>();
 
     // Writer thread: receives results and writes to stdout (stdout is not Sync)
     let writer_handle = thread::spawn(move || -> Result<()> {
         let mut stdout = io::stdout();
-        for (id, result) in rx {
-            match result {
-                Ok(res) => {
-                    let resp = json!({"jsonrpc": "2.0", "id": id, "result": res});
-                    writeln!(stdout, "{}", resp)?;
-                }
-                Err(msg) => {
-                    let err_resp = json!({
-                        "jsonrpc": "2.0",
-                        "id": id,
-                        "error": {"code": -32603, "message": msg}
-                    });
-                    writeln!(stdout, "{}", err_resp)?;
-                }
+        let mut reorder = ReorderBuffer::default();
+        for resp in rx {
+            for ready in reorder.push(resp) {
+                writeln!(stdout, "{}", ready.to_json())?;
             }
             stdout.flush()?;
         }
@@ -68,13 +221,21 @@ pub fn serve_stdio() -> Result<()> {
     let mut reader = BufReader::new(stdin.lock());
     let (done_tx, done_rx) = mpsc::channel::<()>();
     let mut pending = 0usize;
+    let mut seq = 0u64;
 
     loop {
         let line = match read_line_limited(&mut reader) {
             Ok(None) => break, // EOF
             Ok(Some(l)) => l,
             Err(e) => {
-                let _ = tx.send((Value::Null, Err(format!("Request size error: {}", e))));
+                let _ = tx.send(RpcResponse {
+                    seq,
+                    ordered: false,
+                    id: Value::Null,
+                    result: Err((INVALID_REQUEST, format!("Request size error: {}", e))),
+                    error_data: None,
+                });
+                seq += 1;
                 continue;
             }
         };
@@ -82,16 +243,28 @@ pub fn serve_stdio() -> Result<()> {
         if line.is_empty() {
             continue;
         }
+        let this_seq = seq;
+        seq += 1;
 
         let request: Value = match serde_json::from_str(line) {
             Ok(v) => v,
             Err(e) => {
-                let _ = tx.send((Value::Null, Err(format!("Parse error: {}", e))));
+                let _ = tx.send(RpcResponse {
+                    seq: this_seq,
+                    ordered: false,
+                    id: Value::Null,
+                    result: Err((PARSE_ERROR, format!("Parse error: {}", e))),
+                    error_data: None,
+                });
                 continue;
             }
         };
 
         let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let ordered = request
+            .get("ordered")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(ordered_by_default);
         let method = request
             .get("method")
             .and_then(|m| m.as_str())
@@ -102,12 +275,37 @@ pub fn serve_stdio() -> Result<()> {
             .cloned()
             .unwrap_or(Value::Object(serde_json::Map::new()));
 
+        if let Err(msg) = negotiation::check_strict(&info, &method, &request) {
+            let _ = tx.send(RpcResponse {
+                seq: this_seq,
+                ordered,
+                id,
+                result: Err((INVALID_PARAMS, msg)),
+                error_data: None,
+            });
+            continue;
+        }
+
+        let permit = limiter.acquire();
         pending += 1;
         let tx = tx.clone();
         let done_tx = done_tx.clone();
-        rayon::spawn(move || {
-            let result = dispatch_request(&method, &params);
-            let _ = tx.send((id, result.map_err(|e| e.to_string())));
+        let info = Arc::clone(&info);
+        pool.spawn(move || {
+            let result = dispatch_request(&info, &method, &params);
+            drop(permit);
+            let error_data = result
+                .as_ref()
+                .err()
+                .and_then(Error::sandbox_error)
+                .map(|e| e.to_json());
+            let _ = tx.send(RpcResponse {
+                seq: this_seq,
+                ordered,
+                id,
+                result: result.map_err(|e| (rpc_error_code(&e), e.to_string())),
+                error_data,
+            });
             let _ = done_tx.send(());
         });
     }
@@ -201,15 +399,131 @@ fn skip_until_newline(reader: &mut impl BufRead) {
     }
 }
 
+/// JSON-RPC error code for a failed handler: lock contention is distinct so clients can retry.
+fn rpc_error_code(e: &Error) -> i64 {
+    if e.is_lock_timeout() {
+        LOCK_CONTENTION
+    } else {
+        INTERNAL_ERROR
+    }
+}
+
 /// Map executor-layer result into this crate's [`Error`].
 #[cfg(feature = "executor")]
 fn map_executor<T>(r: skilllite_executor::Result<T>) -> Result<T> {
     r.map_err(Into::into)
 }
 
+/// Methods served by this daemon with the params each understands (feature-gated like
+/// [`dispatch_request`]). Keep in sync when adding a method or param.
+pub fn methods() -> Vec<MethodSpec> {
+    const SANDBOX_PARAMS: &[&str] = &[
+        "skill_dir",
+        "input_json",
+        "input_path",
+        "allow_network",
+        "cache_dir",
+        "max_memory",
+        "timeout",
+        "sandbox_level",
+        "audit",
+        "entry",
+        "no_cache",
+    ];
+    #[allow(unused_mut)]
+    let mut methods = vec![
+        MethodSpec::new(negotiation::INITIALIZE_METHOD, &[]),
+        MethodSpec::new("run", SANDBOX_PARAMS),
+        MethodSpec::new(
+            "exec",
+            &[
+                "skill_dir",
+                "script_path",
+                "input_json",
+                "input_path",
+                "args",
+                "allow_network",
+                "cache_dir",
+                "max_memory",
+                "timeout",
+                "sandbox_level",
+                "audit",
+            ],
+        ),
+        MethodSpec::new(
+            "bash",
+            &["skill_dir", "command", "cache_dir", "timeout", "cwd"],
+        ),
+    ];
+    #[cfg(feature = "executor")]
+    methods.extend([
+        MethodSpec::new("session_create", &["session_key", "workspace_path"]),
+        MethodSpec::new("session_get", &["session_key", "workspace_path"]),
+        MethodSpec::new(
+            "session_update",
+            &[
+                "session_key",
+                "workspace_path",
+                "input_tokens",
+                "output_tokens",
+                "total_tokens",
+                "context_tokens",
+                "compaction_count",
+            ],
+        ),
+        MethodSpec::new(
+            "transcript_append",
+            &["session_key", "workspace_path", "entry"],
+        ),
+        MethodSpec::new("transcript_read", &["session_key", "workspace_path"]),
+        MethodSpec::new(
+            "transcript_ensure",
+            &["session_key", "session_id", "workspace_path", "cwd"],
+        ),
+        MethodSpec::new(
+            "memory_write",
+            &[
+                "rel_path",
+                "content",
+                "workspace_path",
+                "append",
+                "agent_id",
+            ],
+        ),
+        MethodSpec::new(
+            "memory_search",
+            &["query", "limit", "workspace_path", "agent_id"],
+        ),
+        MethodSpec::new("token_count", &["text", "model"]),
+        MethodSpec::new("plan_textify", &["plan"]),
+        MethodSpec::new(
+            "plan_write",
+            &[
+                "session_key",
+                "workspace_path",
+                "task_id",
+                "task",
+                "steps",
+                "task_list",
+            ],
+        ),
+        MethodSpec::new("plan_read", &["session_key", "workspace_path", "date"]),
+    ]);
+    #[cfg(feature = "agent")]
+    methods.extend([
+        MethodSpec::new(
+            "build_skills_context",
+            &["skills_dir", "mode", "skills", "refresh"],
+        ),
+        MethodSpec::new("list_tools", &["skills_dir", "skills", "format", "refresh"]),
+    ]);
+    methods
+}
+
 /// Dispatch JSON-RPC request to the appropriate handler.
-fn dispatch_request(method: &str, params: &Value) -> Result<Value> {
+fn dispatch_request(info: &ServerInfo, method: &str, params: &Value) -> Result<Value> {
     match method {
+        negotiation::INITIALIZE_METHOD => Ok(info.to_json()),
         "run" => handle_run(params),
         "exec" => handle_exec(params),
         "bash" => handle_bash(params),
@@ -245,6 +559,11 @@ fn dispatch_request(method: &str, params: &Value) -> Result<Value> {
         "build_skills_context" => handle_build_skills_context(params),
         #[cfg(feature = "agent")]
         "list_tools" => handle_list_tools(params),
+        #[cfg(not(feature = "agent"))]
+        "build_skills_context" | "list_tools" => Err(Error::msg(format!(
+            "Method {} requires the 'agent' feature (use the full skilllite binary)",
+            method
+        ))),
         _ => Err(Error::msg(format!("Method not found: {}", method))),
     }
 }
@@ -262,11 +581,11 @@ fn handle_run(params: &Value) -> Result<Value> {
         limits,
         sandbox_level,
         None,
+        p.entry.as_deref(),
+        p.audit,
+        p.no_cache,
     )?;
-    Ok(json!({
-        "output": output,
-        "exit_code": 0
-    }))
+    Ok(run_response(output))
 }
 
 fn handle_exec(params: &Value) -> Result<Value> {
@@ -283,11 +602,23 @@ fn handle_exec(params: &Value) -> Result<Value> {
         p.cache_dir.as_ref(),
         limits,
         sandbox_level,
+        p.audit,
     )?;
-    Ok(json!({
+    Ok(run_response(output))
+}
+
+/// `run` / `exec` result: the skill output plus its run metadata (limit enforcement, seccomp
+/// profile) lifted to the top level so clients need not parse `output`.
+fn run_response(output: String) -> Value {
+    let sandbox = serde_json::from_str::<Value>(&output)
+        .ok()
+        .and_then(|v| v.get(RUN_METADATA_KEY).cloned())
+        .unwrap_or(Value::Null);
+    json!({
         "output": output,
-        "exit_code": 0
-    }))
+        "exit_code": 0,
+        RUN_METADATA_KEY: sandbox,
+    })
 }
 
 fn handle_bash(params: &Value) -> Result<Value> {
@@ -309,13 +640,27 @@ fn handle_bash(params: &Value) -> Result<Value> {
 
 #[cfg(feature = "agent")]
 fn handle_build_skills_context(params: &Value) -> Result<Value> {
+    let p = IpcBuildSkillsContextParams::try_from(params)?;
+    let skills_path = path_validation::validate_path_under_root(&p.skills_dir, "skills_dir")?;
+    let (response, status) = ResponseCache::shared().get_or_build(
+        "build_skills_context",
+        &skills_path,
+        format!("{}|{:?}", p.mode, p.skills),
+        p.refresh,
+        || build_skills_context_response(&p, &skills_path),
+    )?;
+    Ok(with_cache_status(response, status))
+}
+
+#[cfg(feature = "agent")]
+fn build_skills_context_response(
+    p: &IpcBuildSkillsContextParams,
+    skills_path: &std::path::Path,
+) -> Result<Value> {
     use skilllite_agent::prompt::{build_skills_context, PromptMode};
     use skilllite_agent::skills;
 
-    let p = IpcBuildSkillsContextParams::try_from(params)?;
-    let skills_path = path_validation::validate_path_under_root(&p.skills_dir, "skills_dir")?;
     let skills_path_str = skills_path.to_string_lossy().to_string();
-
     let loaded = skills::load_skills(&[skills_path_str]);
     let loaded: Vec<_> = if let Some(ref filter) = p.skills {
         loaded
@@ -327,6 +672,7 @@ fn handle_build_skills_context(params: &Value) -> Result<Value> {
     };
 
     let mode = match p.mode.as_str() {
+        "index" => PromptMode::Index,
         "summary" => PromptMode::Summary,
         "standard" => PromptMode::Standard,
         "full" => PromptMode::Full,
@@ -339,10 +685,22 @@ fn handle_build_skills_context(params: &Value) -> Result<Value> {
 
 #[cfg(feature = "agent")]
 pub fn handle_list_tools(params: &Value) -> Result<Value> {
-    use skilllite_agent::skills;
-
     let p = IpcListToolsParams::try_from(params)?;
     let skills_path = path_validation::validate_path_under_root(&p.skills_dir, "skills_dir")?;
+    let (response, status) = ResponseCache::shared().get_or_build(
+        "list_tools",
+        &skills_path,
+        format!("{}|{:?}", p.format, p.skills),
+        p.refresh,
+        || list_tools_response(&p, &skills_path),
+    )?;
+    Ok(with_cache_status(response, status))
+}
+
+#[cfg(feature = "agent")]
+fn list_tools_response(p: &IpcListToolsParams, skills_path: &std::path::Path) -> Result<Value> {
+    use skilllite_agent::skills;
+
     let skills_path_str = skills_path.to_string_lossy().to_string();
 
     let loaded = skills::load_skills(&[skills_path_str]);
@@ -355,6 +713,7 @@ pub fn handle_list_tools(params: &Value) -> Result<Value> {
         loaded
     };
 
+    let history = crate::tool_catalog::historical_durations(&skilllite_core::paths::chat_root());
     let mut tools: Vec<Value> = Vec::new();
     let mut tool_meta: serde_json::Map<String, Value> = serde_json::Map::new();
     for skill in &loaded {
@@ -373,18 +732,105 @@ pub fn handle_list_tools(params: &Value) -> Result<Value> {
                 script_path.clone()
             };
             let is_bash = skill.metadata.is_bash_tool_skill();
-            tool_meta.insert(
-                tool_name.clone(),
-                json!({
-                    "skill_dir": skill_dir_str,
-                    "script_path": script_path,
-                    "entry_point": entry_point,
-                    "is_bash": is_bash,
-                    "capabilities": skill.metadata.capabilities
-                }),
-            );
+            let mut meta = json!({
+                "skill_dir": skill_dir_str,
+                "script_path": script_path,
+                "entry_point": entry_point,
+                "is_bash": is_bash,
+                "capabilities": skill.metadata.capabilities
+            });
+            if let (Some(meta), Value::Object(hints)) = (
+                meta.as_object_mut(),
+                crate::tool_catalog::tool_hints(skill, tool_name, &history),
+            ) {
+                meta.extend(hints);
+            }
+            tool_meta.insert(tool_name.clone(), meta);
+        }
+    }
+
+    let mut response = json!({ "tools": tools, "tool_meta": tool_meta });
+    if p.format == "markdown" {
+        response["markdown"] = Value::String(crate::tool_catalog::render_markdown(&response));
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resp(seq: u64, ordered: bool) -> RpcResponse {
+        RpcResponse {
+            seq,
+            ordered,
+            id: json!(seq),
+            result: Ok(Value::Null),
+            error_data: None,
         }
     }
 
-    Ok(json!({ "tools": tools, "tool_meta": tool_meta }))
+    fn seqs(out: Vec<RpcResponse>) -> Vec<u64> {
+        out.into_iter().map(|r| r.seq).collect()
+    }
+
+    #[test]
+    fn reorder_buffer_passes_unordered_through() {
+        let mut buf = ReorderBuffer::default();
+        assert_eq!(seqs(buf.push(resp(2, false))), vec![2]);
+        assert_eq!(seqs(buf.push(resp(0, false))), vec![0]);
+        assert_eq!(seqs(buf.push(resp(1, false))), vec![1]);
+    }
+
+    #[test]
+    fn reorder_buffer_holds_ordered_until_predecessors_written() {
+        let mut buf = ReorderBuffer::default();
+        assert!(buf.push(resp(2, true)).is_empty());
+        assert!(buf.push(resp(1, true)).is_empty());
+        assert_eq!(seqs(buf.push(resp(0, true))), vec![0, 1, 2]);
+        assert_eq!(seqs(buf.push(resp(3, true))), vec![3]);
+    }
+
+    #[test]
+    fn reorder_buffer_mixed_unordered_unblocks_ordered() {
+        let mut buf = ReorderBuffer::default();
+        assert!(buf.push(resp(1, true)).is_empty());
+        // An unordered response is written immediately and counts as flushed.
+        assert_eq!(seqs(buf.push(resp(0, false))), vec![0, 1]);
+    }
+
+    #[test]
+    fn semaphore_blocks_until_permit_released() {
+        let sem = Semaphore::new(1);
+        let first = sem.acquire();
+        let sem2 = Arc::clone(&sem);
+        let (tx, rx) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            let _second = sem2.acquire();
+            let _ = tx.send(());
+        });
+        assert!(rx
+            .recv_timeout(std::time::Duration::from_millis(100))
+            .is_err());
+        drop(first);
+        assert!(rx.recv_timeout(std::time::Duration::from_secs(5)).is_ok());
+        handle.join().unwrap();
+    }
+
+    #[cfg(feature = "executor")]
+    #[test]
+    fn lock_contention_has_its_own_error_code() {
+        let timeout = || {
+            Error::from(skilllite_executor::Error::LockTimeout {
+                path: "sessions.json".into(),
+                waited_ms: 5000,
+            })
+        };
+        assert_eq!(rpc_error_code(&timeout()), LOCK_CONTENTION);
+        assert_eq!(
+            rpc_error_code(&Error::with_context("session_update", timeout())),
+            LOCK_CONTENTION
+        );
+        assert_eq!(rpc_error_code(&Error::msg("boom")), INTERNAL_ERROR);
+    }
 }
@@ -0,0 +1,277 @@
+//! Response cache for the `list_tools` / `build_skills_context` stdio RPC methods.
+//!
+//! SDKs call both on every agent construction; without a cache each call re-walks the
+//! skills directory and re-parses every SKILL.md. Entries are keyed on the method, the
+//! canonical skills dir and the request params, and are reused while the directory
+//! fingerprint (max mtime, entry count, manifest hash) is unchanged.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::{Instant, UNIX_EPOCH};
+
+use serde_json::Value;
+
+use crate::Result;
+
+/// At most this many responses are kept (a handful of skills dirs × methods / params).
+const MAX_ENTRIES: usize = 16;
+
+/// Directories never containing skill definitions; skipped when fingerprinting.
+const SKIP_DIRS: &[&str] = &["node_modules", ".venv", "venv", "__pycache__", ".git"];
+
+/// Cheap summary of a skills directory tree; any edit, add or remove changes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirFingerprint {
+    max_mtime_ns: u128,
+    entries: usize,
+    manifest_hash: u64,
+}
+
+impl DirFingerprint {
+    pub fn of(skills_dir: &Path) -> Self {
+        let mut fp = Self {
+            max_mtime_ns: 0,
+            entries: 0,
+            manifest_hash: 0,
+        };
+        fp.walk(skills_dir);
+        let manifest = skilllite_core::skill::manifest::manifest_path(skills_dir);
+        if let Ok(bytes) = std::fs::read(manifest) {
+            let mut h = DefaultHasher::new();
+            bytes.hash(&mut h);
+            fp.manifest_hash = h.finish();
+        }
+        fp
+    }
+
+    fn walk(&mut self, dir: &Path) {
+        self.note_mtime(dir);
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            self.entries += 1;
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(t) if t.is_dir() => {
+                    let skip = path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| SKIP_DIRS.contains(&n));
+                    if !skip {
+                        self.walk(&path);
+                    }
+                }
+                _ => self.note_mtime(&path),
+            }
+        }
+    }
+
+    fn note_mtime(&mut self, path: &Path) {
+        let mtime = std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        self.max_mtime_ns = self.max_mtime_ns.max(mtime);
+    }
+}
+
+/// Whether a response came from the cache; reported as `"cache": "hit" | "miss"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+}
+
+impl CacheStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hit => "hit",
+            Self::Miss => "miss",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    method: &'static str,
+    skills_dir: PathBuf,
+    params: String,
+}
+
+struct CacheEntry {
+    fingerprint: DirFingerprint,
+    response: Value,
+    last_used: Instant,
+}
+
+/// Bounded response cache; safe to share across the concurrent request workers.
+#[derive(Default)]
+pub struct ResponseCache {
+    entries: RwLock<HashMap<CacheKey, CacheEntry>>,
+}
+
+impl ResponseCache {
+    /// Process-wide cache used by the stdio RPC daemon.
+    pub fn shared() -> &'static ResponseCache {
+        static SHARED: OnceLock<ResponseCache> = OnceLock::new();
+        SHARED.get_or_init(ResponseCache::default)
+    }
+
+    /// Return the cached response for (`method`, `skills_dir`, `params`) while the
+    /// directory is unchanged, otherwise run `build` and remember its result.
+    /// `refresh` forces a rebuild.
+    pub fn get_or_build(
+        &self,
+        method: &'static str,
+        skills_dir: &Path,
+        params: String,
+        refresh: bool,
+        build: impl FnOnce() -> Result<Value>,
+    ) -> Result<(Value, CacheStatus)> {
+        let key = CacheKey {
+            method,
+            skills_dir: skills_dir
+                .canonicalize()
+                .unwrap_or_else(|_| skills_dir.to_path_buf()),
+            params,
+        };
+        let fingerprint = DirFingerprint::of(&key.skills_dir);
+        if !refresh {
+            let hit = self
+                .entries
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&key)
+                .filter(|e| e.fingerprint == fingerprint)
+                .map(|e| e.response.clone());
+            if let Some(response) = hit {
+                if let Some(e) = self
+                    .entries
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get_mut(&key)
+                {
+                    e.last_used = Instant::now();
+                }
+                return Ok((response, CacheStatus::Hit));
+            }
+        }
+
+        let response = build()?;
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        if !entries.contains_key(&key) && entries.len() >= MAX_ENTRIES {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(k) = oldest {
+                entries.remove(&k);
+            }
+        }
+        entries.insert(
+            key,
+            CacheEntry {
+                fingerprint,
+                response: response.clone(),
+                last_used: Instant::now(),
+            },
+        );
+        Ok((response, CacheStatus::Miss))
+    }
+}
+
+/// Add the `"cache"` field to a JSON object response.
+pub fn with_cache_status(mut response: Value, status: CacheStatus) -> Value {
+    if let Some(obj) = response.as_object_mut() {
+        obj.insert("cache".to_string(), Value::from(status.as_str()));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn write_skill(root: &Path, name: &str) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {name}\ndescription: test\n---\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn unchanged_dir_is_served_without_rebuilding() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(tmp.path(), "calc");
+        let cache = ResponseCache::default();
+        let parses = AtomicUsize::new(0);
+        let build = || {
+            parses.fetch_add(1, Ordering::SeqCst);
+            Ok(json!({"tools": []}))
+        };
+
+        let (_, first) = cache
+            .get_or_build("list_tools", tmp.path(), "openai".into(), false, build)
+            .unwrap();
+        let (resp, second) = cache
+            .get_or_build("list_tools", tmp.path(), "openai".into(), false, build)
+            .unwrap();
+        assert_eq!((first, second), (CacheStatus::Miss, CacheStatus::Hit));
+        assert_eq!(parses.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            with_cache_status(resp, second),
+            json!({"tools": [], "cache": "hit"})
+        );
+
+        // Different params and `refresh` both rebuild.
+        cache
+            .get_or_build("list_tools", tmp.path(), "claude".into(), false, build)
+            .unwrap();
+        let (_, forced) = cache
+            .get_or_build("list_tools", tmp.path(), "openai".into(), true, build)
+            .unwrap();
+        assert_eq!(forced, CacheStatus::Miss);
+        assert_eq!(parses.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn adding_a_skill_invalidates_the_entry() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(tmp.path(), "calc");
+        let cache = ResponseCache::default();
+        let build = || Ok(json!({}));
+        cache
+            .get_or_build("list_tools", tmp.path(), String::new(), false, build)
+            .unwrap();
+        write_skill(tmp.path(), "weather");
+        let (_, status) = cache
+            .get_or_build("list_tools", tmp.path(), String::new(), false, build)
+            .unwrap();
+        assert_eq!(status, CacheStatus::Miss);
+    }
+
+    #[test]
+    fn cache_is_bounded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::default();
+        for i in 0..MAX_ENTRIES + 4 {
+            cache
+                .get_or_build("list_tools", tmp.path(), i.to_string(), false, || {
+                    Ok(json!({}))
+                })
+                .unwrap();
+        }
+        assert_eq!(cache.entries.read().unwrap().len(), MAX_ENTRIES);
+    }
+}
@@ -31,6 +31,21 @@ fn opt_u64(p: &serde_json::Map<String, Value>, key: &str) -> Option<u64> {
     p.get(key).and_then(|v| v.as_u64())
 }
 
+/// Skill input from `input_json` or, to keep large payloads off the pipe, a file at `input_path`.
+fn req_input(p: &serde_json::Map<String, Value>) -> Result<String> {
+    match (opt_str(p, "input_json"), opt_str(p, "input_path")) {
+        (Some(_), Some(_)) => Err(Error::msg(
+            "input_json and input_path are mutually exclusive",
+        )),
+        (Some(json), None) => Ok(json),
+        (None, Some(path)) if path == "-" => Err(Error::msg(
+            "input_path cannot be '-' over stdio RPC (stdin carries requests)",
+        )),
+        (None, Some(path)) => Ok(skilllite_commands::execute::read_input_file(&path)?),
+        (None, None) => Err(Error::msg("input_json or input_path required")),
+    }
+}
+
 #[cfg(feature = "agent")]
 fn opt_array_strings(p: &serde_json::Map<String, Value>, key: &str) -> Option<Vec<String>> {
     p.get(key).and_then(|v| v.as_array()).map(|arr| {
@@ -44,12 +59,18 @@ fn opt_array_strings(p: &serde_json::Map<String, Value>, key: &str) -> Option<Ve
 #[derive(Debug)]
 pub struct IpcRunParams {
     pub skill_dir: String,
+    /// From `input_json`, or read from `input_path`.
     pub input_json: String,
     pub allow_network: bool,
     pub cache_dir: Option<String>,
     pub max_memory: Option<u64>,
     pub timeout: Option<u64>,
     pub sandbox_level: Option<u8>,
+    pub audit: bool,
+    /// Named command from SKILL.md `entry_points`.
+    pub entry: Option<String>,
+    /// Skip the result cache of skills declaring SKILL.md `cache:`.
+    pub no_cache: bool,
 }
 
 impl TryFrom<&Value> for IpcRunParams {
@@ -59,12 +80,15 @@ impl TryFrom<&Value> for IpcRunParams {
         let p = obj(v)?;
         Ok(IpcRunParams {
             skill_dir: req_str(p, "skill_dir")?,
-            input_json: req_str(p, "input_json")?,
+            input_json: req_input(p)?,
             allow_network: opt_bool(p, "allow_network"),
             cache_dir: opt_str(p, "cache_dir"),
             max_memory: opt_u64(p, "max_memory"),
             timeout: opt_u64(p, "timeout"),
             sandbox_level: opt_u64(p, "sandbox_level").map(|u| u as u8),
+            audit: opt_bool(p, "audit"),
+            entry: opt_str(p, "entry"),
+            no_cache: opt_bool(p, "no_cache"),
         })
     }
 }
@@ -74,6 +98,7 @@ impl TryFrom<&Value> for IpcRunParams {
 pub struct IpcExecParams {
     pub skill_dir: String,
     pub script_path: String,
+    /// From `input_json`, or read from `input_path`.
     pub input_json: String,
     pub args: Option<String>,
     pub allow_network: bool,
@@ -81,6 +106,7 @@ pub struct IpcExecParams {
     pub max_memory: Option<u64>,
     pub timeout: Option<u64>,
     pub sandbox_level: Option<u8>,
+    pub audit: bool,
 }
 
 impl TryFrom<&Value> for IpcExecParams {
@@ -91,13 +117,14 @@ impl TryFrom<&Value> for IpcExecParams {
         Ok(IpcExecParams {
             skill_dir: req_str(p, "skill_dir")?,
             script_path: req_str(p, "script_path")?,
-            input_json: req_str(p, "input_json")?,
+            input_json: req_input(p)?,
             args: opt_str(p, "args"),
             allow_network: opt_bool(p, "allow_network"),
             cache_dir: opt_str(p, "cache_dir"),
             max_memory: opt_u64(p, "max_memory"),
             timeout: opt_u64(p, "timeout"),
             sandbox_level: opt_u64(p, "sandbox_level").map(|u| u as u8),
+            audit: opt_bool(p, "audit"),
         })
     }
 }
@@ -134,6 +161,8 @@ pub struct IpcBuildSkillsContextParams {
     pub skills_dir: String,
     pub mode: String,
     pub skills: Option<Vec<String>>,
+    /// Rebuild instead of returning the cached response.
+    pub refresh: bool,
 }
 
 #[cfg(feature = "agent")]
@@ -146,6 +175,7 @@ impl TryFrom<&Value> for IpcBuildSkillsContextParams {
             skills_dir: req_str(p, "skills_dir")?,
             mode: opt_str(p, "mode").unwrap_or_else(|| "progressive".into()),
             skills: opt_array_strings(p, "skills"),
+            refresh: opt_bool(p, "refresh"),
         })
     }
 }
@@ -157,6 +187,8 @@ pub struct IpcListToolsParams {
     pub skills_dir: String,
     pub skills: Option<Vec<String>>,
     pub format: String,
+    /// Rebuild instead of returning the cached response.
+    pub refresh: bool,
 }
 
 #[cfg(feature = "agent")]
@@ -169,6 +201,7 @@ impl TryFrom<&Value> for IpcListToolsParams {
             skills_dir: req_str(p, "skills_dir")?,
             skills: opt_array_strings(p, "skills"),
             format: opt_str(p, "format").unwrap_or_else(|| "openai".into()),
+            refresh: opt_bool(p, "refresh"),
         })
     }
 }
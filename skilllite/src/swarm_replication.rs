@@ -0,0 +1,40 @@
+//! Swarm SkillVerifier — admission check for skills replicated from peers
+//! (`skilllite swarm --allow-skill-replication`).
+//!
+//! Runs the offline `skilllite add` scan plus the signature check before a fetched skill is
+//! installed into `<skills>/_replicated/`.
+
+use std::path::Path;
+
+use skilllite_swarm::{SkillReplication, SkillVerifier};
+
+/// Verifier backed by [`skilllite_commands::skill::admit_replicated_skill`].
+#[derive(Debug)]
+pub struct AdmissionSkillVerifier;
+
+impl SkillVerifier for AdmissionSkillVerifier {
+    fn verify(
+        &self,
+        name: &str,
+        skill_dir: &Path,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        skilllite_commands::skill::admit_replicated_skill(name, skill_dir)
+            .map(str::to_string)
+            .map_err(Into::into)
+    }
+}
+
+/// Replication settings for the swarm: skills go under the first `--skills-dir`
+/// (default `.skills` when present, else `skills`).
+pub fn replication_for(skills_dir: Option<&[String]>) -> SkillReplication {
+    let root = skills_dir
+        .and_then(|d| d.first().cloned())
+        .unwrap_or_else(|| {
+            if Path::new(".skills").is_dir() {
+                ".skills".to_string()
+            } else {
+                "skills".to_string()
+            }
+        });
+    SkillReplication::new(root, std::sync::Arc::new(AdmissionSkillVerifier))
+}
@@ -0,0 +1,232 @@
+//! Per-tool hints for `list_tools` consumers outside the agent (`skilllite list-tools`, the
+//! stdio RPC): SKILL.md `examples:`, a cost hint, declared network domains and whether a call
+//! asks for confirmation. `--format markdown` renders the same data as a catalog to paste into
+//! another agent's system prompt.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde_json::{json, Value};
+use skilllite_agent::evolution::feedback::{self, ToolStatOrder};
+use skilllite_agent::skills::{self, LoadedSkill};
+use skilllite_core::skill::metadata::CostHint;
+
+/// Days of `tool_metrics` behind a historical cost hint.
+const COST_HISTORY_DAYS: u32 = 30;
+/// Calls a tool needs in that window before its timings replace SKILL.md `cost:`.
+const COST_HISTORY_MIN_CALLS: i64 = 3;
+const COST_HISTORY_MAX_TOOLS: usize = 10_000;
+
+/// Average duration (ms) per tool name from the evolution DB under `chat_root`. Empty when
+/// there is no DB yet; never creates one.
+pub fn historical_durations(chat_root: &Path) -> HashMap<String, f64> {
+    if !chat_root.join("feedback.sqlite").is_file() {
+        return HashMap::new();
+    }
+    let stats = feedback::open_evolution_db(chat_root).and_then(|conn| {
+        feedback::tool_stats(
+            &conn,
+            COST_HISTORY_DAYS,
+            COST_HISTORY_MIN_CALLS,
+            ToolStatOrder::Slowest,
+            COST_HISTORY_MAX_TOOLS,
+        )
+    });
+    match stats {
+        Ok(stats) => stats
+            .into_iter()
+            .map(|s| (s.tool, s.avg_duration_ms))
+            .collect(),
+        Err(e) => {
+            tracing::debug!("list_tools: no timing history: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// The hint fields merged into a tool's `tool_meta` entry. Observed timings win over the
+/// declared `cost:`, which may be stale.
+pub fn tool_hints(skill: &LoadedSkill, tool_name: &str, history: &HashMap<String, f64>) -> Value {
+    let (cost_hint, cost_source) = match history.get(tool_name) {
+        Some(&avg_ms) => (
+            Some(CostHint::from_avg_duration_ms(avg_ms)),
+            Some("history"),
+        ),
+        None => (
+            skill.metadata.cost_hint,
+            skill.metadata.cost_hint.map(|_| "declared"),
+        ),
+    };
+    let entry = skill
+        .multi_script_entries
+        .get(tool_name)
+        .and_then(|script| {
+            skill
+                .metadata
+                .entry_points
+                .iter()
+                .find(|e| &e.script == script)
+                .map(|e| e.name.as_str())
+        });
+    json!({
+        "examples": skill.metadata.examples_for(entry),
+        "cost_hint": cost_hint,
+        "cost_source": cost_source,
+        "network_domains": skill.metadata.network.declared_hosts(),
+        "requires_confirmation": skills::requires_confirmation(skill),
+    })
+}
+
+/// Markdown catalog of a `list_tools` response built with OpenAI-format `tools`.
+pub fn render_markdown(response: &Value) -> String {
+    let empty = Vec::new();
+    let tools = response["tools"].as_array().unwrap_or(&empty);
+    let mut out = String::from("# Tool catalog\n");
+    if tools.is_empty() {
+        out.push_str("\nNo tools available.\n");
+        return out;
+    }
+    for tool in tools {
+        let function = &tool["function"];
+        let name = function["name"].as_str().unwrap_or_default();
+        let meta = &response["tool_meta"][name];
+        let _ = write!(out, "\n## `{}`\n\n", name);
+        if let Some(desc) = function["description"].as_str().filter(|d| !d.is_empty()) {
+            let _ = writeln!(out, "{}\n", desc.trim());
+        }
+
+        if let Some(cost) = meta["cost_hint"].as_str() {
+            let source = match meta["cost_source"].as_str() {
+                Some("history") => "observed",
+                _ => "declared",
+            };
+            let _ = writeln!(out, "- Cost: {} ({})", cost, source);
+        }
+        let domains: Vec<&str> = meta["network_domains"]
+            .as_array()
+            .map(|d| d.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        if !domains.is_empty() {
+            let _ = writeln!(out, "- Network: {}", domains.join(", "));
+        }
+        if meta["requires_confirmation"].as_bool() == Some(true) {
+            out.push_str("- Asks the user for confirmation before its first run\n");
+        }
+
+        if let Some(params) = function.get("parameters").filter(|p| !p.is_null()) {
+            let _ = write!(
+                out,
+                "\nParameters:\n\n```json\n{}\n```\n",
+                serde_json::to_string_pretty(params).unwrap_or_default()
+            );
+        }
+
+        let examples = meta["examples"].as_array().unwrap_or(&empty);
+        if !examples.is_empty() {
+            out.push_str("\nExamples:\n\n");
+            for example in examples {
+                let label = example["description"].as_str().unwrap_or("Example");
+                let _ = writeln!(out, "- {}", label);
+                let _ = writeln!(out, "  - Input: `{}`", example["input"]);
+                if let Some(output) = example.get("output") {
+                    let _ = writeln!(out, "  - Output: `{}`", output);
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skilllite_agent::evolution::feedback::{DecisionInput, FeedbackSignal, ToolExecDetail};
+
+    fn call(tool: &str, duration_ms: u64) -> DecisionInput {
+        DecisionInput {
+            total_tools: 1,
+            task_completed: true,
+            tools_detail: vec![ToolExecDetail {
+                tool: tool.to_string(),
+                success: true,
+                duration_ms,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn history_needs_enough_calls_and_no_db_means_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(historical_durations(dir.path()).is_empty());
+        assert!(!dir.path().join("feedback.sqlite").exists());
+
+        let conn = feedback::open_evolution_db(dir.path()).unwrap();
+        let calls = [
+            call("render_pdf", 30_000),
+            call("render_pdf", 30_000),
+            call("render_pdf", 30_000),
+            call("slugify", 10),
+        ];
+        for input in &calls {
+            feedback::insert_decision(&conn, None, input, FeedbackSignal::Neutral).unwrap();
+        }
+        feedback::update_daily_metrics(&conn).unwrap();
+
+        let history = historical_durations(dir.path());
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            CostHint::from_avg_duration_ms(history["render_pdf"]),
+            CostHint::Slow
+        );
+    }
+
+    #[test]
+    fn markdown_lists_hints_and_examples() {
+        let response = json!({
+            "tools": [{
+                "type": "function",
+                "function": {
+                    "name": "slugify",
+                    "description": "Slugify text",
+                    "parameters": { "type": "object" }
+                }
+            }],
+            "tool_meta": {
+                "slugify": {
+                    "examples": [{
+                        "description": "Basic title",
+                        "input": { "text": "Hello World" },
+                        "output": { "slug": "hello-world" }
+                    }],
+                    "cost_hint": "fast",
+                    "cost_source": "declared",
+                    "network_domains": ["api.example.com"],
+                    "requires_confirmation": true
+                }
+            }
+        });
+
+        let md = render_markdown(&response);
+        assert!(md.contains("## `slugify`"), "{}", md);
+        assert!(md.contains("- Cost: fast (declared)"), "{}", md);
+        assert!(md.contains("- Network: api.example.com"), "{}", md);
+        assert!(md.contains("confirmation"), "{}", md);
+        assert!(
+            md.contains(r#"  - Input: `{"text":"Hello World"}`"#),
+            "{}",
+            md
+        );
+        assert!(
+            md.contains(r#"  - Output: `{"slug":"hello-world"}`"#),
+            "{}",
+            md
+        );
+        assert_eq!(
+            render_markdown(&json!({ "tools": [] })),
+            "# Tool catalog\n\nNo tools available.\n"
+        );
+    }
+}
@@ -92,6 +92,8 @@ fn validate_help_succeeds() {
 fn show_help_succeeds() {
     let out = run(&["show", "--help"]);
     assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("--full"), "show should document --full");
 }
 
 #[test]
@@ -118,6 +120,12 @@ fn clean_env_help_succeeds() {
     assert!(out.status.success());
 }
 
+#[test]
+fn clean_output_help_succeeds() {
+    let out = run(&["clean-output", "--help"]);
+    assert!(out.status.success());
+}
+
 #[test]
 fn reindex_help_succeeds() {
     let out = run(&["reindex", "--help"]);
@@ -0,0 +1,106 @@
+//! Integration tests for `skilllite config show|set` and the layered settings precedence.
+
+mod common;
+
+use common::{skilllite_bin, stderr_str, stdout_str};
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn config_cmd(home: &Path, workspace: &Path, args: &[&str], env: &[(&str, &str)]) -> Output {
+    let mut cmd = Command::new(skilllite_bin());
+    cmd.arg("config")
+        .args(args)
+        .current_dir(workspace)
+        .env("NO_COLOR", "1")
+        .env("SKILLLITE_CONFIG_FILE", home.join("config.toml"))
+        .env_remove("SKILLLITE_WORKSPACE")
+        .env_remove("SKILLLITE_MODEL")
+        .env_remove("OPENAI_MODEL")
+        .env_remove("MODEL")
+        .env_remove("SKILLLITE_SANDBOX_LEVEL")
+        .env_remove("SKILLBOX_SANDBOX_LEVEL")
+        .env_remove("SKILLLITE_TIMEOUT_SECS")
+        .env_remove("SKILLBOX_TIMEOUT_SECS");
+    for (k, v) in env {
+        cmd.env(k, v);
+    }
+    cmd.output().expect("failed to spawn skilllite")
+}
+
+fn setting<'a>(report: &'a serde_json::Value, key: &str) -> &'a serde_json::Value {
+    report["settings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["key"] == key)
+        .unwrap_or_else(|| panic!("{key} missing from {report}"))
+}
+
+#[test]
+fn set_then_show_reports_each_layer() {
+    let home = tempfile::tempdir().unwrap();
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(workspace.path().join(".skilllite")).unwrap();
+    std::fs::write(
+        workspace.path().join(".skilllite").join("config.toml"),
+        "[sandbox]\nmax_memory_mb = 1024\nlevel = 1\n",
+    )
+    .unwrap();
+
+    for (key, value) in [("model", "user-model"), ("sandbox.timeout_secs", "60")] {
+        let out = config_cmd(home.path(), workspace.path(), &["set", key, value], &[]);
+        assert!(out.status.success(), "{}", stderr_str(&out));
+    }
+
+    let out = config_cmd(
+        home.path(),
+        workspace.path(),
+        &["show", "--json"],
+        &[("SKILLLITE_TIMEOUT_SECS", "5")],
+    );
+    assert!(out.status.success(), "{}", stderr_str(&out));
+    let report: serde_json::Value = serde_json::from_str(&stdout_str(&out)).unwrap();
+
+    assert_eq!(setting(&report, "model")["value"], "user-model");
+    assert_eq!(setting(&report, "model")["source"], "user_file");
+    assert_eq!(setting(&report, "sandbox.max_memory_mb")["value"], "1024");
+    assert_eq!(
+        setting(&report, "sandbox.max_memory_mb")["source"],
+        "workspace_file"
+    );
+    let timeout = setting(&report, "sandbox.timeout_secs");
+    assert_eq!(timeout["value"], "5");
+    assert_eq!(timeout["env"], "SKILLLITE_TIMEOUT_SECS");
+    // The workspace file may not lower the sandbox level.
+    assert_eq!(setting(&report, "sandbox.level")["value"], "3");
+    assert!(report["warnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|w| w.as_str().unwrap().contains("sandbox.level")));
+}
+
+#[test]
+fn set_rejects_unknown_keys_and_bad_values() {
+    let home = tempfile::tempdir().unwrap();
+    let workspace = tempfile::tempdir().unwrap();
+
+    let out = config_cmd(
+        home.path(),
+        workspace.path(),
+        &["set", "sandbox.colour", "red"],
+        &[],
+    );
+    assert!(!out.status.success());
+    assert!(stderr_str(&out).contains("unknown config key"));
+
+    let out = config_cmd(
+        home.path(),
+        workspace.path(),
+        &["set", "sandbox.level", "7"],
+        &[],
+    );
+    assert!(!out.status.success());
+    assert!(stderr_str(&out).contains("sandbox.level"));
+    assert!(!home.path().join("config.toml").exists());
+}
@@ -0,0 +1,81 @@
+//! `skilllite run --entry <name>` for skills declaring SKILL.md `entry_points`.
+
+mod common;
+
+use common::{create_toolkit_skill, skilllite_bin, stderr_str, stdout_str};
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn run_skill(root: &Path, args: &[&str]) -> Output {
+    Command::new(skilllite_bin())
+        .args(args)
+        .current_dir(root)
+        .env("NO_COLOR", "1")
+        .env("SKILLLITE_NO_SANDBOX", "1")
+        .env("SKILLLITE_AUTO_APPROVE", "1")
+        .env("SKILLLITE_AUDIT_DISABLED", "1")
+        .output()
+        .expect("failed to spawn skilllite")
+}
+
+#[test]
+fn run_entry_selects_the_named_script() {
+    let tmp = tempfile::tempdir().unwrap();
+    let skill = create_toolkit_skill(tmp.path());
+    let sp = skill.to_str().unwrap();
+
+    let out = run_skill(
+        tmp.path(),
+        &["run", sp, r#"{"text":"two words"}"#, "--entry", "upper"],
+    );
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    assert!(
+        stdout_str(&out).contains("TWO WORDS"),
+        "{}",
+        stdout_str(&out)
+    );
+
+    let out = run_skill(
+        tmp.path(),
+        &["run", sp, r#"{"text":"two words"}"#, "--entry", "count"],
+    );
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    let output: serde_json::Value = serde_json::from_str(stdout_str(&out).trim())
+        .unwrap_or_else(|_| panic!("{}", stdout_str(&out)));
+    assert_eq!(output["words"], 2);
+}
+
+#[test]
+fn run_without_or_with_unknown_entry_lists_the_entries() {
+    let tmp = tempfile::tempdir().unwrap();
+    let skill = create_toolkit_skill(tmp.path());
+    let sp = skill.to_str().unwrap();
+
+    let out = run_skill(tmp.path(), &["run", sp, "{}"]);
+    assert!(!out.status.success());
+    let combined = stdout_str(&out) + &stderr_str(&out);
+    assert!(combined.contains("--entry"), "{}", combined);
+    assert!(combined.contains("upper, count"), "{}", combined);
+
+    let out = run_skill(tmp.path(), &["run", sp, "{}", "--entry", "reverse"]);
+    assert!(!out.status.success());
+    let combined = stdout_str(&out) + &stderr_str(&out);
+    assert!(combined.contains("'reverse'"), "{}", combined);
+    assert!(combined.contains("upper, count"), "{}", combined);
+}
+
+#[test]
+fn validate_names_the_broken_entry() {
+    let tmp = tempfile::tempdir().unwrap();
+    let skill = create_toolkit_skill(tmp.path());
+    std::fs::remove_file(skill.join("scripts").join("count.py")).unwrap();
+
+    let out = run_skill(tmp.path(), &["validate", skill.to_str().unwrap()]);
+    assert!(!out.status.success());
+    let combined = stdout_str(&out) + &stderr_str(&out);
+    assert!(
+        combined.contains("entry_points.count: script 'scripts/count.py' not found"),
+        "{}",
+        combined
+    );
+}
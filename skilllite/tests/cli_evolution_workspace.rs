@@ -88,3 +88,108 @@ fn evolution_backlog_workspace_flag_overrides_env_workspace() {
         "env workspace backlog row should not leak into target query: {notes:?}"
     );
 }
+
+#[test]
+fn evolution_status_pending_only_lists_review_details() {
+    let workspace = tempfile::tempdir().expect("workspace");
+    let skill = workspace
+        .path()
+        .join("skills")
+        .join("_evolved")
+        .join("_pending")
+        .join("csv-merge");
+    std::fs::create_dir_all(skill.join("scripts")).unwrap();
+    std::fs::write(
+        skill.join("SKILL.md"),
+        "---\nname: csv-merge\ndescription: Merge CSV files by key\nentry_point: scripts/main.py\n---\n\nJoins two CSV files.\n",
+    )
+    .unwrap();
+    std::fs::write(skill.join("scripts").join("main.py"), "print('{}')\n").unwrap();
+
+    let ws = workspace.path().to_string_lossy();
+    let out = run_with_workspace_env(
+        &[
+            "evolution",
+            "status",
+            "--pending-only",
+            "--json",
+            "-w",
+            ws.as_ref(),
+        ],
+        workspace.path(),
+    );
+    assert!(
+        out.status.success(),
+        "status failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let skills: Vec<serde_json::Value> =
+        serde_json::from_str(stdout_str(&out).trim()).expect("valid pending JSON");
+    assert_eq!(skills.len(), 1);
+    let s = &skills[0];
+    assert_eq!(s["name"], "csv-merge");
+    assert_eq!(
+        s["excerpt"],
+        serde_json::json!(["Merge CSV files by key", "Joins two CSV files."])
+    );
+    assert_eq!(s["language"], "python");
+    assert_eq!(s["network"], false);
+    assert!(s["scan_status"].is_null());
+    assert_eq!(s["files"][1]["path"], "scripts/main.py");
+    assert_eq!(s["stale"], false);
+    let confirm = s["confirm_command"].as_str().unwrap();
+    assert!(confirm.starts_with("skilllite evolution confirm csv-merge -w "));
+
+    let out = run_with_workspace_env(
+        &["evolution", "status", "--pending-only", "-w", ws.as_ref()],
+        workspace.path(),
+    );
+    let text = stdout_str(&out);
+    assert!(text.contains("csv-merge"), "{text}");
+    assert!(text.contains("scripts/main.py"), "{text}");
+    assert!(
+        text.contains("skilllite evolution reject csv-merge"),
+        "{text}"
+    );
+}
+
+#[test]
+fn evolution_status_breakdown_renders_json_and_table() {
+    let workspace = tempfile::tempdir().expect("workspace");
+    let ws = workspace.path().to_string_lossy();
+
+    let out = run_with_workspace_env(
+        &[
+            "evolution",
+            "status",
+            "--breakdown",
+            "--json",
+            "-w",
+            ws.as_ref(),
+        ],
+        workspace.path(),
+    );
+    assert!(
+        out.status.success(),
+        "status failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let report: serde_json::Value =
+        serde_json::from_str(stdout_str(&out).trim()).expect("valid breakdown JSON");
+    assert_eq!(report["days"], 7);
+    assert_eq!(report["rows"], serde_json::json!([]));
+
+    let out = run_with_workspace_env(
+        &["evolution", "status", "--breakdown", "-w", ws.as_ref()],
+        workspace.path(),
+    );
+    let text = stdout_str(&out);
+    assert!(text.contains("按工作区"), "{text}");
+    assert!(text.contains("按技能"), "{text}");
+
+    let out = run_with_workspace_env(
+        &["evolution", "status", "--breakdown", "--tools"],
+        workspace.path(),
+    );
+    assert!(!out.status.success());
+}
@@ -166,3 +166,162 @@ fn clean_env_force() {
     let out = common::run(&["clean-env", "--force"]);
     assert!(out.status.success());
 }
+
+#[test]
+fn clean_env_unused_keeps_referenced_and_legacy_envs() {
+    let cache = tempfile::tempdir().unwrap();
+    let skill = tempfile::tempdir().unwrap();
+    let envs = cache.path().join("envs");
+    for name in ["used", "orphan"] {
+        std::fs::create_dir_all(envs.join(name)).unwrap();
+        std::fs::write(
+            envs.join(name).join(".skilllite_env.json"),
+            r#"{"language":"python","runtime":"python-3.12","created_at":""}"#,
+        )
+        .unwrap();
+    }
+    std::fs::create_dir_all(envs.join("legacy").join("bin")).unwrap();
+    std::fs::create_dir_all(envs.join(".refs")).unwrap();
+    std::fs::write(
+        envs.join(".refs").join("skill.json"),
+        serde_json::json!({
+            "skill_dir": skill.path(),
+            "language": "python",
+            "env": "used",
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let out = std::process::Command::new(common::skilllite_bin())
+        .args(["clean-env", "--unused", "--force"])
+        .env("SKILLLITE_CACHE_DIR", cache.path())
+        .output()
+        .expect("run clean-env");
+    assert!(out.status.success(), "{}", stderr_str(&out));
+    assert!(envs.join("used").exists());
+    assert!(envs.join("legacy").exists());
+    assert!(!envs.join("orphan").exists());
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// skilllite quickstart --non-interactive
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn quickstart_non_interactive_writes_and_updates_config_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let config = tmp.path().join("config.toml");
+    let quickstart = |args: &[&str]| {
+        std::process::Command::new(common::skilllite_bin())
+            .args(["quickstart", "--non-interactive", "--skip-validation"])
+            .args(args)
+            .env("SKILLLITE_CONFIG_FILE", &config)
+            .current_dir(tmp.path())
+            .output()
+            .expect("run quickstart")
+    };
+
+    let out = quickstart(&["--provider", "deepseek", "--api-key", "sk-test-123"]);
+    assert!(out.status.success(), "{}", stderr_str(&out));
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains("api_base = \"https://api.deepseek.com/v1\""));
+    assert!(content.contains("api_key = \"sk-test-123\""));
+    assert!(content.contains("model = \"deepseek-chat\""));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&config).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // Re-running with one flag edits only that field
+    let out = quickstart(&["--model", "deepseek-reasoner"]);
+    assert!(out.status.success(), "{}", stderr_str(&out));
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains("model = \"deepseek-reasoner\""));
+    assert!(content.contains("api_key = \"sk-test-123\""));
+
+    // A keyed provider without a key is rejected
+    std::fs::remove_file(&config).unwrap();
+    let out = quickstart(&["--provider", "openai"]);
+    assert!(!out.status.success());
+    assert!(stderr_str(&out).contains("--api-key"));
+    assert!(!config.exists());
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// skilllite init-ide — MCP client config generation
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn init_ide_merges_claude_code_config_and_guards_conflicts() {
+    let tmp = tempfile::tempdir().unwrap();
+    create_calculator_skill(tmp.path());
+    let mcp_json = tmp.path().join(".mcp.json");
+    std::fs::write(
+        &mcp_json,
+        r#"{"mcpServers":{"other":{"command":"other-server"}}}"#,
+    )
+    .unwrap();
+
+    let args = ["init-ide", "--target", "claude-code", "-s", ".skills"];
+    let out = run_in_dir(&args, tmp.path());
+    assert!(out.status.success(), "{}", stderr_str(&out));
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&mcp_json).unwrap()).unwrap();
+    assert_eq!(config["mcpServers"]["other"]["command"], "other-server");
+    let entry = &config["mcpServers"]["skilllite"];
+    assert_eq!(entry["type"], "stdio");
+    assert_eq!(entry["args"][0], "mcp");
+    assert_eq!(entry["args"][1], "--skills-dir");
+    assert!(std::path::Path::new(entry["args"][2].as_str().unwrap()).is_absolute());
+
+    // A hand-edited entry is not overwritten without --force; the diff is shown instead.
+    let mut edited = config.clone();
+    edited["mcpServers"]["skilllite"]["command"] = "custom".into();
+    std::fs::write(&mcp_json, serde_json::to_string(&edited).unwrap()).unwrap();
+    let out = run_in_dir(&args, tmp.path());
+    assert!(out.status.success());
+    assert!(stderr_str(&out).contains("--force"));
+    assert!(stderr_str(&out).contains("- ") && stderr_str(&out).contains("+ "));
+    assert!(std::fs::read_to_string(&mcp_json)
+        .unwrap()
+        .contains("custom"));
+
+    let mut forced = args.to_vec();
+    forced.push("--force");
+    let out = run_in_dir(&forced, tmp.path());
+    assert!(out.status.success());
+    assert!(!std::fs::read_to_string(&mcp_json)
+        .unwrap()
+        .contains("custom"));
+}
+
+#[test]
+fn init_ide_generic_prints_snippet_without_writing() {
+    let tmp = tempfile::tempdir().unwrap();
+    create_calculator_skill(tmp.path());
+
+    let out = run_in_dir(
+        &["init-ide", "--target", "generic", "-s", ".skills"],
+        tmp.path(),
+    );
+    assert!(out.status.success(), "{}", stderr_str(&out));
+    let snippet: serde_json::Value = serde_json::from_str(&stdout_str(&out)).unwrap();
+    assert_eq!(snippet["mcpServers"]["skilllite"]["args"][0], "mcp");
+    assert!(!tmp.path().join(".mcp.json").exists());
+    assert!(!tmp.path().join(".cursor").exists());
+}
+
+#[test]
+fn init_ide_rejects_unknown_target() {
+    let tmp = tempfile::tempdir().unwrap();
+    create_calculator_skill(tmp.path());
+    let out = run_in_dir(
+        &["init-ide", "--target", "emacs", "-s", ".skills"],
+        tmp.path(),
+    );
+    assert!(!out.status.success());
+    assert!(stderr_str(&out).contains("claude-code"));
+}
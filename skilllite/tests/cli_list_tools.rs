@@ -0,0 +1,119 @@
+//! `skilllite list-tools` hints for external frameworks: SKILL.md `examples:`, cost hints,
+//! network domains, confirmation, and the `--format markdown` catalog.
+
+mod common;
+
+use common::{skilllite_bin, stderr_str, stdout_str};
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn write_fixture_skills(skills: &Path) {
+    let slugify = skills.join("slugify");
+    std::fs::create_dir_all(slugify.join("scripts")).unwrap();
+    std::fs::write(
+        slugify.join("SKILL.md"),
+        r#"---
+name: slugify
+description: Turn a title into a URL slug.
+cost: fast
+network:
+  outbound: ["api.example.com"]
+examples:
+  - description: Basic title
+    input: { text: "Hello World" }
+    output: { slug: hello-world }
+---
+
+# slugify
+"#,
+    )
+    .unwrap();
+    std::fs::write(slugify.join("scripts").join("main.py"), "print('{}')\n").unwrap();
+
+    let toolkit = skills.join("text-toolkit");
+    std::fs::create_dir_all(toolkit.join("scripts")).unwrap();
+    std::fs::write(
+        toolkit.join("SKILL.md"),
+        r#"---
+name: text-toolkit
+description: Small text utilities.
+entry_points:
+  upper:
+    script: scripts/upper.py
+    description: Upper-case a string
+  count:
+    script: scripts/count.py
+    description: Count the words of a string
+examples:
+  - tool: upper
+    input: { text: "two words" }
+    output: { upper: "TWO WORDS" }
+---
+"#,
+    )
+    .unwrap();
+    for script in ["upper.py", "count.py"] {
+        std::fs::write(toolkit.join("scripts").join(script), "print('{}')\n").unwrap();
+    }
+}
+
+fn list_tools(root: &Path, format: &str) -> Output {
+    Command::new(skilllite_bin())
+        .args(["list-tools", "-s", ".skills", "--format", format])
+        .current_dir(root)
+        .env("NO_COLOR", "1")
+        .env("SKILLLITE_WORKSPACE", root.join("data"))
+        .env("SKILLLITE_SANDBOX_LEVEL", "3")
+        .output()
+        .expect("failed to spawn skilllite")
+}
+
+#[test]
+fn openai_output_carries_examples_and_hints_per_tool() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_fixture_skills(&tmp.path().join(".skills"));
+
+    let out = list_tools(tmp.path(), "openai");
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&out)).unwrap();
+    let meta = &json["tool_meta"];
+
+    let slugify = &meta["slugify"];
+    assert_eq!(slugify["examples"][0]["description"], "Basic title");
+    assert_eq!(slugify["examples"][0]["input"]["text"], "Hello World");
+    assert_eq!(slugify["examples"][0]["output"]["slug"], "hello-world");
+    assert_eq!(slugify["cost_hint"], "fast");
+    assert_eq!(slugify["cost_source"], "declared");
+    assert_eq!(slugify["network_domains"][0], "api.example.com");
+    assert_eq!(slugify["requires_confirmation"], true);
+
+    // Examples naming an entry point only show up on that tool.
+    assert_eq!(
+        meta["text_toolkit__upper"]["examples"][0]["input"]["text"],
+        "two words"
+    );
+    assert_eq!(
+        meta["text_toolkit__count"]["examples"],
+        serde_json::json!([])
+    );
+    assert!(meta["text_toolkit__count"]["cost_hint"].is_null());
+}
+
+#[test]
+fn markdown_format_prints_a_tool_catalog() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_fixture_skills(&tmp.path().join(".skills"));
+
+    let out = list_tools(tmp.path(), "markdown");
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    let md = stdout_str(&out);
+    assert!(md.starts_with("# Tool catalog\n"), "{}", md);
+    assert!(md.contains("## `slugify`"), "{}", md);
+    assert!(md.contains("- Cost: fast (declared)"), "{}", md);
+    assert!(
+        md.contains(r#"  - Input: `{"text":"Hello World"}`"#),
+        "{}",
+        md
+    );
+    assert!(md.contains("## `text_toolkit__count`"), "{}", md);
+}
@@ -0,0 +1,86 @@
+//! `skilllite run` result cache for skills declaring SKILL.md `cache: { enabled: true }`.
+
+mod common;
+
+use common::{skilllite_bin, stderr_str, stdout_str};
+use std::path::Path;
+use std::process::{Command, Output};
+
+const SCRIPT: &str = r#"import json, sys
+data = json.loads(sys.stdin.read())
+with open(data["counter"], "a") as f:
+    f.write("x")
+print(json.dumps({"double": data["n"] * 2}))
+"#;
+
+fn write_counter_skill(dir: &Path, script: &str) {
+    std::fs::create_dir_all(dir.join("scripts")).unwrap();
+    std::fs::write(
+        dir.join("SKILL.md"),
+        "---\nname: double\ndescription: Doubles a number.\nentry_point: scripts/main.py\n\
+         cache:\n  enabled: true\n  ttl: 3600\n---\n\n# double\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("scripts").join("main.py"), script).unwrap();
+}
+
+fn run(root: &Path, skill: &Path, input: &str, extra: &[&str]) -> Output {
+    Command::new(skilllite_bin())
+        .args(["run", skill.to_str().unwrap(), input])
+        .args(extra)
+        .current_dir(root)
+        .env("NO_COLOR", "1")
+        .env("SKILLLITE_NO_SANDBOX", "1")
+        .env("SKILLLITE_AUTO_APPROVE", "1")
+        .env("SKILLLITE_AUDIT_DISABLED", "1")
+        .env("SKILLLITE_CACHE_DIR", root.join("cache"))
+        .output()
+        .expect("failed to spawn skilllite")
+}
+
+#[test]
+fn identical_calls_are_served_from_the_cache_until_input_or_script_change() {
+    let tmp = tempfile::tempdir().unwrap();
+    let skill = tmp.path().join("double");
+    write_counter_skill(&skill, SCRIPT);
+    let counter = tmp.path().join("counter.txt");
+    let executions = || std::fs::read_to_string(&counter).unwrap_or_default().len();
+    let input = |n: u32| format!(r#"{{"counter": {:?}, "n": {}}}"#, counter, n);
+
+    let first = run(tmp.path(), &skill, &input(2), &[]);
+    assert!(first.status.success(), "stderr: {}", stderr_str(&first));
+    assert!(stdout_str(&first).contains("4"), "{}", stdout_str(&first));
+    assert!(!stdout_str(&first).contains("cached"));
+    assert_eq!(executions(), 1);
+
+    let second = run(tmp.path(), &skill, &input(2), &[]);
+    assert!(second.status.success(), "stderr: {}", stderr_str(&second));
+    assert!(
+        stdout_str(&second).contains(r#""cached":true"#),
+        "{}",
+        stdout_str(&second)
+    );
+    assert_eq!(executions(), 1, "identical call must not re-execute");
+
+    run(tmp.path(), &skill, &input(2), &["--no-cache"]);
+    assert_eq!(executions(), 2);
+
+    let changed_input = run(tmp.path(), &skill, &input(5), &[]);
+    assert!(stdout_str(&changed_input).contains("10"));
+    assert_eq!(executions(), 3);
+
+    write_counter_skill(&skill, &format!("{}# v2\n", SCRIPT));
+    let changed_script = run(tmp.path(), &skill, &input(2), &[]);
+    assert!(!stdout_str(&changed_script).contains("cached"));
+    assert_eq!(executions(), 4);
+
+    let clean = Command::new(skilllite_bin())
+        .args(["clean-env", "--results", "--force"])
+        .env("SKILLLITE_CACHE_DIR", tmp.path().join("cache"))
+        .output()
+        .unwrap();
+    assert!(clean.status.success(), "stderr: {}", stderr_str(&clean));
+    assert!(!tmp.path().join("cache").join("results").exists());
+    run(tmp.path(), &skill, &input(2), &[]);
+    assert_eq!(executions(), 5);
+}
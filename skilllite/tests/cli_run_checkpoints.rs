@@ -0,0 +1,63 @@
+//! `skilllite run --list-resumable` / `--discard`: the SQLite checkpoint store, seeded through
+//! the legacy `run_checkpoints/latest.json` import.
+
+mod common;
+
+use common::{skilllite_bin, stderr_str, stdout_str};
+use std::path::Path;
+use std::process::{Command, Output};
+
+const RUN_ID: &str = "3f2a9c1e-0000-4000-8000-000000000001";
+
+fn seed_legacy_checkpoint(data_root: &Path) {
+    let dir = data_root.join("chat").join("run_checkpoints");
+    std::fs::create_dir_all(&dir).unwrap();
+    let checkpoint = serde_json::json!({
+        "run_id": RUN_ID,
+        "goal": "整理发布说明\n第二行不显示",
+        "workspace": "/tmp/legacy-ws",
+        "task_plan": [
+            { "id": 1, "description": "读取变更", "completed": true },
+            { "id": 2, "description": "写入说明", "completed": false }
+        ],
+        "messages": [],
+        "updated_at": chrono::Utc::now().to_rfc3339(),
+    });
+    std::fs::write(dir.join("latest.json"), checkpoint.to_string()).unwrap();
+}
+
+fn run(data_root: &Path, args: &[&str]) -> Output {
+    Command::new(skilllite_bin())
+        .arg("run")
+        .args(args)
+        .env("NO_COLOR", "1")
+        .env("SKILLLITE_WORKSPACE", data_root)
+        .output()
+        .expect("failed to spawn skilllite")
+}
+
+#[test]
+fn list_resumable_shows_progress_and_discard_removes_it() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_root = tmp.path();
+
+    let out = run(data_root, &["--list-resumable"]);
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    assert!(stdout_str(&out).contains("暂无可续跑的断点"));
+
+    seed_legacy_checkpoint(data_root);
+    let out = run(data_root, &["--list-resumable"]);
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    let listing = stdout_str(&out);
+    assert!(listing.contains("3f2a9c1e  1/2 任务"), "{}", listing);
+    assert!(listing.contains("🎯 整理发布说明"), "{}", listing);
+    assert!(!listing.contains("第二行不显示"), "{}", listing);
+
+    let out = run(data_root, &["--discard", "3f2a9c1e"]);
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    assert!(stdout_str(&out).contains(RUN_ID));
+
+    let out = run(data_root, &["--discard", "3f2a9c1e"]);
+    assert!(!out.status.success());
+    assert!(stderr_str(&out).contains("未找到断点"));
+}
@@ -128,3 +128,190 @@ fn security_scan_with_all_allows() {
     let parsed: serde_json::Value = serde_json::from_str(text.trim()).unwrap();
     assert!(parsed.is_object() || parsed.is_array());
 }
+
+#[test]
+fn security_scan_reports_justified_suppressions() {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::write(
+        tmp.path().join("convert.py"),
+        "import subprocess\nsubprocess.run(['ffmpeg', '-version'])  # skilllite-allow: process-exec reason=\"invokes bundled ffmpeg\"\n",
+    )
+    .unwrap();
+
+    let out = run_in_dir(&["security-scan", "convert.py"], tmp.path());
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    assert!(
+        stdout_str(&out).contains("1 finding(s) suppressed with justification"),
+        "{}",
+        stdout_str(&out)
+    );
+
+    let out = run_in_dir(&["security-scan", "convert.py", "--json"], tmp.path());
+    let parsed: serde_json::Value = serde_json::from_str(stdout_str(&out).trim()).unwrap();
+    assert_eq!(parsed["suppressed_count"], 1);
+    assert_eq!(parsed["suppressed"][0]["reason"], "invokes bundled ffmpeg");
+    assert_eq!(parsed["suppressed"][0]["severity"], "Info");
+}
+
+#[test]
+fn security_scan_sarif_output() {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::write(
+        tmp.path().join("convert.py"),
+        "import subprocess\nsubprocess.run(['ffmpeg', '-version'])  # skilllite-allow: process-exec reason=\"invokes bundled ffmpeg\"\n",
+    )
+    .unwrap();
+
+    let out = run_in_dir(
+        &["security-scan", "convert.py", "--format", "sarif"],
+        tmp.path(),
+    );
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    let sarif: serde_json::Value = serde_json::from_str(stdout_str(&out).trim()).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "skilllite");
+    assert!(!run["tool"]["driver"]["rules"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+    let suppressed = run["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["suppressions"].is_array())
+        .expect("suppressed finding should be reported");
+    assert_eq!(
+        suppressed["suppressions"][0]["justification"],
+        "invokes bundled ffmpeg"
+    );
+    assert_eq!(
+        suppressed["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "convert.py"
+    );
+    assert_eq!(
+        suppressed["locations"][0]["physicalLocation"]["region"]["startLine"],
+        2
+    );
+
+    let out = run_in_dir(
+        &["security-scan", "convert.py", "--json", "--format", "sarif"],
+        tmp.path(),
+    );
+    assert!(!out.status.success(), "--json and --format should conflict");
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// skilllite scan --format sarif
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn scan_sarif_aggregates_scripts_into_one_run() {
+    let tmp = tempfile::tempdir().unwrap();
+    let skill = tmp.path().join("sarif-skill");
+    std::fs::create_dir_all(skill.join("scripts")).unwrap();
+    std::fs::write(
+        skill.join("SKILL.md"),
+        "---\nname: sarif-skill\ndescription: test\n---\n",
+    )
+    .unwrap();
+    std::fs::write(
+        skill.join("scripts/a.py"),
+        "import subprocess\nsubprocess.run(['ls'])\n",
+    )
+    .unwrap();
+    std::fs::write(skill.join("scripts/b.py"), "import os\nos.remove('x')\n").unwrap();
+
+    let out = run_in_dir(&["scan", "sarif-skill", "--format", "sarif"], tmp.path());
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    let sarif: serde_json::Value = serde_json::from_str(stdout_str(&out).trim()).unwrap();
+    let runs = sarif["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    let uris: std::collections::BTreeSet<&str> = runs[0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|r| r["locations"][0]["physicalLocation"]["artifactLocation"]["uri"].as_str())
+        .collect();
+    assert!(uris.contains("sarif-skill/scripts/a.py"), "{:?}", uris);
+    assert!(uris.contains("sarif-skill/scripts/b.py"), "{:?}", uris);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// network.outbound / network.deny grammar (validate / info / scan)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn create_network_skill(dir: &std::path::Path, outbound: &[&str]) {
+    let skill = dir.join("net-skill");
+    std::fs::create_dir_all(&skill).unwrap();
+    let entries: String = outbound
+        .iter()
+        .map(|e| format!("    - \"{}\"\n", e))
+        .collect();
+    std::fs::write(
+        skill.join("SKILL.md"),
+        format!(
+            "---\nname: net-skill\ndescription: test\nnetwork:\n  outbound:\n{}  deny:\n    - gist.github.com\n---\n",
+            entries
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn validate_reports_invalid_network_entries_by_line() {
+    let tmp = tempfile::tempdir().unwrap();
+    create_network_skill(
+        tmp.path(),
+        &["*.github.com", "api.*.com", "10.0.0.0/8:99999"],
+    );
+
+    let out = run_in_dir(&["validate", "net-skill"], tmp.path());
+    assert!(!out.status.success());
+    let combined = stdout_str(&out) + &stderr_str(&out);
+    assert!(
+        combined.contains("SKILL.md:7: network.outbound entry 'api.*.com'"),
+        "{}",
+        combined
+    );
+    assert!(
+        combined.contains("SKILL.md:8: network.outbound entry '10.0.0.0/8:99999'"),
+        "{}",
+        combined
+    );
+}
+
+#[test]
+fn info_and_scan_render_the_parsed_network_policy() {
+    let tmp = tempfile::tempdir().unwrap();
+    create_network_skill(tmp.path(), &["*.github.com:443", "[2001:db8::/32]:443-450"]);
+
+    let out = run_in_dir(&["validate", "net-skill"], tmp.path());
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+
+    let out = run_in_dir(&["info", "net-skill"], tmp.path());
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    let text = stdout_str(&out);
+    assert!(
+        text.contains("*.github.com:443  (subdomains of github.com, port 443)"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("addresses in 2001:db8::/32, ports 443-450"),
+        "{}",
+        text
+    );
+    assert!(text.contains("Outbound Deny:"), "{}", text);
+
+    let out = run_in_dir(&["scan", "net-skill"], tmp.path());
+    assert!(out.status.success(), "stderr: {}", stderr_str(&out));
+    let json: serde_json::Value = serde_json::from_str(stdout_str(&out).trim()).unwrap();
+    let policy = &json["skill_metadata"]["network_policy"];
+    assert_eq!(policy["outbound"][1]["host"], "2001:db8::/32");
+    assert_eq!(policy["outbound"][1]["ports"], "443-450");
+    assert_eq!(
+        policy["deny"][0]["description"],
+        "host gist.github.com, any port"
+    );
+}
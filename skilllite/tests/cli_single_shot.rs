@@ -0,0 +1,220 @@
+//! Scripting contract of `skilllite chat --message` and `skilllite run --goal`: exit codes
+//! and the `--output json` envelope, driven by hand-written LLM replay tapes.
+
+mod common;
+
+use common::{skilllite_bin, stderr_str, stdout_str};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::{Command, Output};
+
+const QUESTION: &str = "What release date is written in notes.md?";
+
+/// One replayed completion: a text answer, or a call to `read_file` on `path`.
+fn completion(message_count: usize, text: &str, read_path: Option<&str>) -> Value {
+    let tool_calls = read_path.map(|path| {
+        json!([{
+            "id": "call_1",
+            "type": "function",
+            "function": { "name": "read_file", "arguments": json!({ "path": path }).to_string() }
+        }])
+    });
+    json!({
+        "message_count": message_count,
+        "chunks": if text.is_empty() { json!([]) } else { json!([text]) },
+        "response": {
+            "id": "chatcmpl-test",
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": text, "tool_calls": tool_calls },
+                "finish_reason": if read_path.is_some() { "tool_calls" } else { "stop" }
+            }],
+            "usage": { "prompt_tokens": 100, "completion_tokens": 10, "total_tokens": 110 }
+        }
+    })
+}
+
+fn write_tape(dir: &Path, calls: &[Value]) {
+    std::fs::create_dir_all(dir).unwrap();
+    for (i, call) in calls.iter().enumerate() {
+        std::fs::write(dir.join(format!("{:04}.json", i)), call.to_string()).unwrap();
+    }
+}
+
+/// Temp data root + workspace with `notes.md`.
+fn setup() -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    let ws = tmp.path().join("ws");
+    std::fs::create_dir_all(&ws).unwrap();
+    std::fs::write(ws.join("notes.md"), "release: 2026-10-20\n").unwrap();
+    tmp
+}
+
+/// `skilllite <args> --workspace <tmp>/ws` against the temp data root. `replay_env` sets
+/// `SKILLLITE_LLM_REPLAY` for commands without an `--llm-replay` flag.
+fn skilllite(tmp: &Path, args: &[&str], api_key: &str, replay_env: Option<&Path>) -> Output {
+    let mut cmd = Command::new(skilllite_bin());
+    if let Some(tape) = replay_env {
+        cmd.env("SKILLLITE_LLM_REPLAY", tape);
+    }
+    cmd.args(args)
+        .arg("--workspace")
+        .arg(tmp.join("ws"))
+        .current_dir(tmp.join("ws"))
+        .env("NO_COLOR", "1")
+        .env("SKILLLITE_WORKSPACE", tmp.join("data"))
+        .env("OPENAI_API_KEY", api_key)
+        .env("OPENAI_API_BASE", "http://127.0.0.1:9/v1")
+        .env("SKILLLITE_MODEL", "gpt-4o-mini")
+        .env("SKILLLITE_LLM_REPLAY_LOOSE", "1")
+        .output()
+        .expect("failed to spawn skilllite")
+}
+
+fn chat(tmp: &Path, tape: &Path, extra: &[&str], api_key: &str) -> Output {
+    let mut args = vec![
+        "chat",
+        "--message",
+        QUESTION,
+        "--no-plan",
+        "--llm-replay",
+        tape.to_str().unwrap(),
+        "--loose",
+    ];
+    args.extend_from_slice(extra);
+    skilllite(tmp, &args, api_key, None)
+}
+
+/// stdout must be exactly one JSON document.
+fn envelope(out: &Output) -> Value {
+    serde_json::from_str(&stdout_str(out)).unwrap_or_else(|e| {
+        panic!(
+            "stdout is not one JSON envelope ({}):\n{}\nstderr: {}",
+            e,
+            stdout_str(out),
+            stderr_str(out)
+        )
+    })
+}
+
+#[test]
+fn chat_json_envelope_on_success() {
+    let tmp = setup();
+    let tape = tmp.path().join("tape");
+    write_tape(
+        &tape,
+        &[
+            completion(2, "", Some("notes.md")),
+            completion(4, "The release date is 2026-10-20.", None),
+        ],
+    );
+
+    let out = chat(tmp.path(), &tape, &["--output", "json"], "test");
+    assert_eq!(out.status.code(), Some(0), "stderr: {}", stderr_str(&out));
+    let env = envelope(&out);
+    assert_eq!(
+        env,
+        json!({
+            "ok": true,
+            "response": "The release date is 2026-10-20.",
+            "artifacts": [],
+            "usage": { "prompt_tokens": 200, "completion_tokens": 20, "total_tokens": 220 },
+            "error": null,
+        })
+    );
+    // Progress and the streamed answer stay on stderr.
+    let stderr = stderr_str(&out);
+    assert!(stderr.contains("read_file"), "{}", stderr);
+    assert!(
+        stderr.contains("The release date is 2026-10-20."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn chat_llm_error_exits_3_in_both_output_formats() {
+    let tmp = setup();
+    let tape = tmp.path().join("tape");
+    let failing =
+        [json!({ "message_count": 2, "chunks": [], "error": "HTTP 500: upstream overloaded" })];
+    write_tape(&tape, &failing);
+
+    let out = chat(tmp.path(), &tape, &["--output", "json"], "test");
+    assert_eq!(out.status.code(), Some(3), "stderr: {}", stderr_str(&out));
+    let env = envelope(&out);
+    assert_eq!(env["ok"], false);
+    assert_eq!(env["response"], "");
+    assert_eq!(env["error"]["kind"], "llm");
+    assert!(
+        env["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("upstream overloaded"),
+        "{}",
+        env
+    );
+
+    // Text mode, fresh data root (the session above kept the failed user message).
+    let tmp = setup();
+    write_tape(&tmp.path().join("tape"), &failing);
+    let out = chat(tmp.path(), &tmp.path().join("tape"), &[], "test");
+    assert_eq!(out.status.code(), Some(3), "stderr: {}", stderr_str(&out));
+    assert!(stdout_str(&out).trim().is_empty(), "{}", stdout_str(&out));
+    assert!(stderr_str(&out).contains("Error (llm): HTTP 500"));
+}
+
+#[test]
+fn chat_without_api_key_is_a_configuration_error() {
+    let tmp = setup();
+    let tape = tmp.path().join("tape");
+    write_tape(&tape, &[]);
+
+    let out = chat(tmp.path(), &tape, &["--output", "json"], "");
+    assert_eq!(out.status.code(), Some(2), "stderr: {}", stderr_str(&out));
+    let env = envelope(&out);
+    assert_eq!(env["ok"], false);
+    assert_eq!(env["error"]["kind"], "config");
+    assert!(env["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("API key required"));
+}
+
+#[test]
+fn run_goal_stops_on_tool_failures_with_exit_4() {
+    let tmp = setup();
+    let tape = tmp.path().join("tape");
+    let plan = json!([{ "id": 1, "description": "Read missing.md", "tool_hint": "file_operation", "completed": false }]);
+    write_tape(
+        &tape,
+        &[
+            // Goal-contract extraction, then the planner, then the agent loop.
+            completion(2, "{}", None),
+            completion(2, &plan.to_string(), None),
+            completion(2, "", Some("missing.md")),
+        ],
+    );
+
+    let out = skilllite(
+        tmp.path(),
+        &[
+            "run",
+            "--goal",
+            "Summarize missing.md",
+            "--max-failures",
+            "1",
+            "--output",
+            "json",
+        ],
+        "test",
+        Some(&tape),
+    );
+    assert_eq!(out.status.code(), Some(4), "stderr: {}", stderr_str(&out));
+    let env = envelope(&out);
+    assert_eq!(env["ok"], false);
+    assert_eq!(env["error"]["kind"], "tool_failures");
+    assert_eq!(env["usage"]["total_tokens"], 330);
+    assert!(stderr_str(&out).contains("File not found: missing.md"));
+}
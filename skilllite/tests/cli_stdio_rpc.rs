@@ -0,0 +1,330 @@
+//! Integration tests for the skill execution stdio daemon (`serve --stdio`).
+//!
+//! Requests are fed via stdin as JSON-RPC 2.0 lines; responses are read from stdout.
+//! Every test runs against `skilllite` and, when built, `skilllite-sandbox` — both share
+//! the dispatcher in `skilllite::run_cli`.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use common::{run_bin_with_stdin, skilllite_bin, skilllite_sandbox_bin, stdout_str};
+use serde_json::{json, Value};
+
+/// Binaries exposing `serve --stdio`.
+fn stdio_binaries() -> Vec<PathBuf> {
+    vec![skilllite_bin(), skilllite_sandbox_bin()]
+}
+
+/// Run `<bin> serve --stdio` with the given request lines and parse all responses.
+fn serve(bin: &Path, requests: &[Value]) -> Vec<Value> {
+    serve_in(bin, None, requests)
+}
+
+/// Like [`serve`], with the daemon's working directory (the skill path root) set to `dir`.
+fn serve_in(bin: &Path, dir: Option<&Path>, requests: &[Value]) -> Vec<Value> {
+    let stdin: String = requests.iter().map(|r| format!("{}\n", r)).collect();
+    let out = run_bin_with_stdin(bin, &["serve", "--stdio"], dir, &stdin);
+    assert!(
+        out.status.success(),
+        "{} exited with failure",
+        bin.display()
+    );
+    stdout_str(&out)
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+#[test]
+fn stdio_parse_error_uses_jsonrpc_code() {
+    for bin in stdio_binaries() {
+        let out = run_bin_with_stdin(&bin, &["serve", "--stdio"], None, "{not json\n");
+        assert!(out.status.success());
+        let responses: Vec<Value> = stdout_str(&out)
+            .lines()
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[0]["error"]["code"], json!(-32700));
+    }
+}
+
+#[test]
+fn stdio_unknown_method_returns_error_with_id() {
+    for bin in stdio_binaries() {
+        let responses = serve(
+            &bin,
+            &[json!({"jsonrpc": "2.0", "id": 7, "method": "no_such_method"})],
+        );
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], json!(7));
+        assert!(responses[0]["error"]["message"]
+            .as_str()
+            .unwrap_or("")
+            .contains("no_such_method"));
+    }
+}
+
+#[test]
+fn stdio_initialize_reports_versions_methods_and_features() {
+    for bin in stdio_binaries() {
+        let responses = serve(
+            &bin,
+            &[json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"})],
+        );
+        let info = &responses[0]["result"];
+        assert_eq!(info["server"], json!("stdio-rpc"));
+        assert_eq!(info["version"], json!(env!("CARGO_PKG_VERSION")));
+        assert_eq!(info["protocol_version"], json!(1));
+        assert!(info["features"].as_array().is_some());
+        let run = info["methods"]
+            .as_array()
+            .and_then(|m| m.iter().find(|m| m["name"] == "run"))
+            .expect("run advertised");
+        assert!(run["params"]
+            .as_array()
+            .is_some_and(|p| p.contains(&json!("skill_dir"))));
+    }
+}
+
+#[test]
+fn stdio_strict_rejects_unknown_params_and_ignores_them_otherwise() {
+    for bin in stdio_binaries() {
+        let responses = serve(
+            &bin,
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "run", "strict": true, "ordered": true,
+                       "params": {"skill_dir": "x", "input_json": "{}", "turbo": true}}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "run", "ordered": true,
+                       "params": {"turbo": true}}),
+            ],
+        );
+        assert_eq!(responses[0]["error"]["code"], json!(-32602));
+        assert!(responses[0]["error"]["message"]
+            .as_str()
+            .unwrap_or("")
+            .contains("turbo"));
+        // Without strict the unknown param is ignored and the usual validation runs.
+        assert_eq!(responses[1]["error"]["code"], json!(-32603));
+    }
+}
+
+#[test]
+fn stdio_missing_params_reports_required_field() {
+    for bin in stdio_binaries() {
+        let responses = serve(
+            &bin,
+            &[json!({"jsonrpc": "2.0", "id": 1, "method": "run", "params": {}})],
+        );
+        assert_eq!(responses[0]["error"]["code"], json!(-32603));
+        assert!(responses[0]["error"]["message"]
+            .as_str()
+            .unwrap_or("")
+            .contains("skill_dir required"));
+    }
+}
+
+#[test]
+fn stdio_run_input_json_and_input_path_are_exclusive() {
+    for bin in stdio_binaries() {
+        let responses = serve(
+            &bin,
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "run", "ordered": true,
+                       "params": {"skill_dir": "x", "input_json": "{}", "input_path": "in.json"}}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "run", "ordered": true,
+                       "params": {"skill_dir": "x"}}),
+            ],
+        );
+        let message = |i: usize| {
+            responses[i]["error"]["message"]
+                .as_str()
+                .unwrap_or("")
+                .to_string()
+        };
+        assert!(message(0).contains("mutually exclusive"), "{}", message(0));
+        assert!(
+            message(1).contains("input_json or input_path required"),
+            "{}",
+            message(1)
+        );
+    }
+}
+
+#[test]
+fn stdio_answers_every_concurrent_request() {
+    for bin in stdio_binaries() {
+        let requests: Vec<Value> = (1..=32)
+            .map(|id| json!({"jsonrpc": "2.0", "id": id, "method": "no_such_method"}))
+            .collect();
+        let mut ids: Vec<u64> = serve(&bin, &requests)
+            .iter()
+            .filter_map(|r| r["id"].as_u64())
+            .collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (1..=32).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn stdio_ordered_requests_respond_in_request_order() {
+    for bin in stdio_binaries() {
+        let requests: Vec<Value> = (1..=8)
+            .map(|id| {
+                json!({"jsonrpc": "2.0", "id": id, "method": "no_such_method", "ordered": true})
+            })
+            .collect();
+        let ids: Vec<Value> = serve(&bin, &requests)
+            .into_iter()
+            .map(|r| r["id"].clone())
+            .collect();
+        assert_eq!(ids, (1..=8).map(|id| json!(id)).collect::<Vec<_>>());
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn stdio_concurrent_exec_keeps_args_per_request() {
+    let tmp = tempfile::tempdir().unwrap();
+    let skill_dir = tmp.path().join("echo-args");
+    std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+    std::fs::write(
+        skill_dir.join("scripts").join("echo_args.sh"),
+        "#!/bin/bash\nsleep 0.3\nprintf '{\"args\":\"%s\"}' \"$*\"\n",
+    )
+    .unwrap();
+
+    for bin in stdio_binaries() {
+        let requests: Vec<Value> = ["alpha one", "beta two", "gamma three"]
+            .iter()
+            .enumerate()
+            .map(|(i, args)| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": i + 1,
+                    "method": "exec",
+                    "params": {
+                        "skill_dir": "echo-args",
+                        "script_path": "scripts/echo_args.sh",
+                        "input_json": "{}",
+                        "args": args,
+                        "sandbox_level": 1,
+                        "cache_dir": tmp.path().join("cache").to_string_lossy(),
+                    }
+                })
+            })
+            .collect();
+        let responses = serve_in(&bin, Some(tmp.path()), &requests);
+        assert_eq!(responses.len(), 3);
+        for resp in &responses {
+            let expected = match resp["id"].as_u64() {
+                Some(1) => "alpha one",
+                Some(2) => "beta two",
+                Some(3) => "gamma three",
+                other => panic!("unexpected id {:?}", other),
+            };
+            let raw = resp["result"]["output"]
+                .as_str()
+                .unwrap_or_else(|| panic!("exec failed: {}", resp));
+            let output: Value = serde_json::from_str(raw).unwrap();
+            assert_eq!(output["args"], json!(expected));
+            assert!(resp["result"]["sandbox"]["enforcement"].is_string());
+        }
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn stdio_concurrent_runs_execute_in_parallel() {
+    let tmp = tempfile::tempdir().unwrap();
+    let skill_dir = tmp.path().join("rendezvous");
+    std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+    std::fs::write(
+        skill_dir.join("SKILL.md"),
+        "---\nname: rendezvous\ndescription: Waits until its peer run has started.\n---\n\n# Rendezvous\n",
+    )
+    .unwrap();
+    // Each run marks its arrival, then waits for the peer's mark. Run one after another, the
+    // first would give up waiting.
+    std::fs::write(
+        skill_dir.join("scripts").join("main.py"),
+        "import json, os, sys, time\nargs = json.load(sys.stdin)\n\
+         open(os.path.join(args['dir'], args['me']), 'w').close()\n\
+         peer = os.path.join(args['dir'], args['peer'])\n\
+         deadline = time.time() + 30\n\
+         while not os.path.exists(peer) and time.time() < deadline:\n    time.sleep(0.05)\n\
+         print(json.dumps({'met': os.path.exists(peer)}))\n",
+    )
+    .unwrap();
+
+    for (n, bin) in stdio_binaries().into_iter().enumerate() {
+        let meet = tmp.path().join(format!("meet-{}", n));
+        std::fs::create_dir_all(&meet).unwrap();
+        let requests: Vec<Value> = [("a", "b"), ("b", "a")]
+            .iter()
+            .enumerate()
+            .map(|(i, (me, peer))| {
+                let input = json!({ "dir": meet.to_string_lossy(), "me": me, "peer": peer });
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": i + 1,
+                    "method": "run",
+                    "params": {
+                        "skill_dir": "rendezvous",
+                        "input_json": input.to_string(),
+                        "sandbox_level": 1,
+                        "cache_dir": tmp.path().join("cache").to_string_lossy(),
+                    }
+                })
+            })
+            .collect();
+        let responses = serve_in(&bin, Some(tmp.path()), &requests);
+        assert_eq!(responses.len(), 2);
+        for resp in &responses {
+            let raw = resp["result"]["output"]
+                .as_str()
+                .unwrap_or_else(|| panic!("run failed: {}", resp));
+            let output: Value = serde_json::from_str(raw.trim()).unwrap();
+            assert_eq!(
+                output["met"],
+                true,
+                "{}: runs did not overlap",
+                bin.display()
+            );
+        }
+    }
+}
+
+#[test]
+fn stdio_token_count_accepts_batches_and_reports_the_tokenizer() {
+    let responses = serve(
+        &skilllite_bin(),
+        &[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "token_count",
+                   "params": {"text": ["abcdefgh", "", "abcdefghi"], "model": "claude-sonnet-4"}}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "token_count",
+                   "params": {"text": "hello", "model": "gpt-4o"}}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "token_count",
+                   "params": {"text": ["ok", 3]}}),
+        ],
+    );
+    let by_id = |id: u64| responses.iter().find(|r| r["id"] == json!(id)).unwrap();
+
+    // No public Claude tokenizer: chars/4, flagged as approximate.
+    let batch = &by_id(1)["result"];
+    assert_eq!(batch["tokens"], json!([2, 0, 3]));
+    assert_eq!(batch["model_family"], "heuristic");
+    assert_eq!(batch["approximate"], json!(true));
+
+    let single = &by_id(2)["result"];
+    assert_eq!(single["model_family"], "o200k");
+    assert!(single["tokens"].as_u64().unwrap() >= 1);
+
+    assert!(by_id(3)["error"]["message"]
+        .as_str()
+        .unwrap_or("")
+        .contains("only strings"));
+}
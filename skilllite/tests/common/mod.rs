@@ -30,6 +30,39 @@ pub fn skilllite_bin() -> PathBuf {
     path
 }
 
+/// The `skilllite-sandbox` binary. Its `sandbox_binary` feature is enabled for tests through the
+/// self dev-dependency in Cargo.toml, so cargo builds it alongside the test executables.
+pub fn skilllite_sandbox_bin() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_skilllite-sandbox"))
+}
+
+/// Run `<bin> <args>` (optionally inside `dir`), feed `stdin_data` via stdin, and return output.
+pub fn run_bin_with_stdin(
+    bin: &Path,
+    args: &[&str],
+    dir: Option<&Path>,
+    stdin_data: &str,
+) -> Output {
+    let mut cmd = Command::new(bin);
+    cmd.args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env("NO_COLOR", "1");
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    let mut child = cmd.spawn().expect("failed to spawn binary");
+
+    if let Some(ref mut stdin) = child.stdin {
+        stdin
+            .write_all(stdin_data.as_bytes())
+            .expect("failed to write stdin");
+    }
+    drop(child.stdin.take());
+    child.wait_with_output().expect("failed to wait on child")
+}
+
 /// Run `skilllite <args>` and return the full output.
 pub fn run(args: &[&str]) -> Output {
     Command::new(skilllite_bin())
@@ -158,6 +191,58 @@ if __name__ == "__main__":
     .unwrap();
 }
 
+/// Create a toolkit skill with two named `entry_points` (`upper`, `count`) under
+/// `<dir>/.skills/text-toolkit/`.
+pub fn create_toolkit_skill(dir: &Path) -> PathBuf {
+    let skill_dir = dir.join(".skills").join("text-toolkit");
+    std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+
+    std::fs::write(
+        skill_dir.join("SKILL.md"),
+        r#"---
+name: text-toolkit
+description: Small text utilities.
+license: MIT
+entry_points:
+  upper:
+    script: scripts/upper.py
+    description: Upper-case a string
+    parameters:
+      type: object
+      properties:
+        text: { type: string, description: Text to convert }
+      required: [text]
+  count:
+    script: scripts/count.py
+    description: Count the words of a string
+---
+
+# Text Toolkit
+"#,
+    )
+    .unwrap();
+
+    std::fs::write(
+        skill_dir.join("scripts").join("upper.py"),
+        r#"#!/usr/bin/env python3
+import json, sys
+data = json.loads(sys.stdin.read())
+print(json.dumps({"upper": data.get("text", "").upper()}))
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        skill_dir.join("scripts").join("count.py"),
+        r#"#!/usr/bin/env python3
+import json, sys
+data = json.loads(sys.stdin.read())
+print(json.dumps({"words": len(data.get("text", "").split())}))
+"#,
+    )
+    .unwrap();
+    skill_dir
+}
+
 /// Create a prompt-only skill (no entry_point) under `<dir>/.skills/prompt-helper/`.
 pub fn create_prompt_only_skill(dir: &Path) {
     let skill_dir = dir.join(".skills").join("prompt-helper");
@@ -0,0 +1,89 @@
+//! Origin-aware sandbox policy: the same network-using skill runs with network when
+//! installed under `skills/`, but is isolated without network under `skills/_evolved/`.
+
+mod common;
+
+use common::{skilllite_bin, stderr_str, stdout_str};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+
+fn write_net_probe_skill(dir: &Path) {
+    std::fs::create_dir_all(dir.join("scripts")).unwrap();
+    std::fs::write(
+        dir.join("SKILL.md"),
+        "---\nname: net-probe\ndescription: Connects to a local TCP port.\nlicense: MIT\ncompatibility: Requires Python 3.x, network access\n---\n\n# net-probe\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("scripts").join("main.py"),
+        r#"#!/usr/bin/env python3
+import json, socket, sys
+port = json.loads(sys.stdin.read())["port"]
+try:
+    socket.create_connection(("127.0.0.1", port), timeout=3).close()
+    connected = True
+except OSError:
+    connected = False
+print(json.dumps({"connected": connected}))
+"#,
+    )
+    .unwrap();
+}
+
+fn run_skill(skill: &Path, port: u16, root: &Path) -> std::process::Output {
+    Command::new(skilllite_bin())
+        .args([
+            "run",
+            skill.to_str().unwrap(),
+            &format!(r#"{{"port":{port}}}"#),
+        ])
+        .current_dir(root)
+        .env("NO_COLOR", "1")
+        .env("SKILLLITE_SANDBOX_LEVEL", "1")
+        .env("SKILLLITE_NO_SANDBOX", "1")
+        .env("SKILLLITE_AUTO_APPROVE", "1")
+        .env("SKILLLITE_AUDIT_DISABLED", "1")
+        .env_remove("SKILLLITE_EVOLVED_SKILL_LEVEL")
+        .output()
+        .expect("failed to spawn skilllite")
+}
+
+#[test]
+fn evolved_skill_network_is_blocked_while_installed_copy_connects() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let installed = root.join("skills").join("net-probe");
+    let evolved = root.join("skills").join("_evolved").join("net-probe");
+    write_net_probe_skill(&installed);
+    write_net_probe_skill(&evolved);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || for _ in listener.incoming() {});
+
+    let out = run_skill(&installed, port, root);
+    assert!(
+        out.status.success(),
+        "installed run failed: {}",
+        stderr_str(&out)
+    );
+    let result: serde_json::Value = serde_json::from_str(stdout_str(&out).trim()).unwrap();
+    assert_eq!(result["connected"], true, "{result}");
+    assert!(result.get("sandbox_policy").is_none());
+
+    // Level 1 + SKILLLITE_NO_SANDBOX are overridden: the evolved copy either runs isolated
+    // without network or, when no sandbox backend is available, fails closed.
+    let out = run_skill(&evolved, port, root);
+    if out.status.success() {
+        let result: serde_json::Value = serde_json::from_str(stdout_str(&out).trim()).unwrap();
+        assert_eq!(result["connected"], false, "{result}");
+        let policy = &result["sandbox_policy"];
+        assert_eq!(policy["origin"], "evolved");
+        assert_eq!(policy["level"], 2);
+        assert_eq!(policy["network_enabled"], false);
+    } else {
+        let text = stderr_str(&out) + &stdout_str(&out);
+        assert!(!text.contains("\"connected\": true"), "{text}");
+    }
+}
@@ -0,0 +1,143 @@
+//! `result.json` output contract: Python and Node skills write files plus `result.json` into
+//! `$SKILLLITE_RESULTS_DIR`; `skilllite run` merges them with stdout.
+
+mod common;
+
+use common::{skilllite_bin, stderr_str, stdout_str};
+use std::path::Path;
+use std::process::Command;
+
+fn write_skill(dir: &Path, name: &str, script: &str, body: &str) {
+    std::fs::create_dir_all(dir.join("scripts")).unwrap();
+    std::fs::write(
+        dir.join("SKILL.md"),
+        format!("---\nname: {name}\ndescription: result.json contract fixture.\nlicense: MIT\n---\n\n# {name}\n"),
+    )
+    .unwrap();
+    std::fs::write(dir.join("scripts").join(script), body).unwrap();
+}
+
+fn write_python_charts_skill(dir: &Path) {
+    write_skill(
+        dir,
+        "result-charts",
+        "main.py",
+        r#"#!/usr/bin/env python3
+import json, os, sys
+data = json.loads(sys.stdin.read())
+out = os.environ["SKILLLITE_RESULTS_DIR"]
+outputs = []
+for i in range(data.get("count", 3)):
+    name = "chart-%d.svg" % i
+    with open(os.path.join(out, name), "w") as f:
+        f.write("<svg/>")
+    outputs.append({"path": name, "type": "image/svg+xml", "description": "chart %d" % i})
+if data.get("escape"):
+    outputs.append({"path": "../../../../../../etc/hostname"})
+with open(os.path.join(out, "result.json"), "w") as f:
+    json.dump({"outputs": outputs, "data": {"count": len(outputs)}}, f)
+print(json.dumps({"ok": True}))
+"#,
+    );
+}
+
+fn write_node_summary_skill(dir: &Path) {
+    write_skill(
+        dir,
+        "result-summary",
+        "main.js",
+        r#"const fs = require("fs");
+const path = require("path");
+const input = JSON.parse(fs.readFileSync(0, "utf8"));
+const out = process.env.SKILLLITE_RESULTS_DIR;
+fs.writeFileSync(path.join(out, "summary.txt"), input.text);
+fs.writeFileSync(
+  path.join(out, "result.json"),
+  JSON.stringify({
+    outputs: [{ path: "summary.txt", type: "text/plain" }],
+    data: { length: input.text.length },
+  })
+);
+console.log(JSON.stringify({ ok: true }));
+"#,
+    );
+}
+
+fn run_skill(skill: &Path, input: &str, root: &Path) -> std::process::Output {
+    Command::new(skilllite_bin())
+        .args(["run", skill.to_str().unwrap(), input])
+        .current_dir(root)
+        .env("NO_COLOR", "1")
+        .env("SKILLLITE_NO_SANDBOX", "1")
+        .env("SKILLLITE_AUTO_APPROVE", "1")
+        .env("SKILLLITE_AUDIT_DISABLED", "1")
+        // V8 reserves more address space up front than the default RLIMIT_AS allows.
+        .env("SKILLLITE_MAX_MEMORY_MB", "1024")
+        .env("SKILLLITE_OUTPUT_DIR", root.join("output"))
+        .output()
+        .expect("failed to spawn skilllite")
+}
+
+fn merged_output(out: &std::process::Output) -> serde_json::Value {
+    assert!(
+        out.status.success(),
+        "run failed: stdout={}\nstderr={}",
+        stdout_str(out),
+        stderr_str(out)
+    );
+    serde_json::from_str(stdout_str(out).trim()).expect("run should print JSON")
+}
+
+#[test]
+fn python_skill_outputs_are_collected_from_result_json() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let skill = root.join("result-charts");
+    write_python_charts_skill(&skill);
+
+    let merged = merged_output(&run_skill(&skill, r#"{"count":3}"#, root));
+    assert_eq!(merged["stdout"], serde_json::json!({"ok": true}));
+    assert_eq!(merged["data"]["count"], 3);
+    let outputs = merged["outputs"].as_array().unwrap();
+    assert_eq!(outputs.len(), 3);
+    let output_dir = root.join("output").canonicalize().unwrap();
+    for (i, output) in outputs.iter().enumerate() {
+        let path = Path::new(output["path"].as_str().unwrap());
+        assert!(path.starts_with(&output_dir), "{}", path.display());
+        assert!(path.is_file());
+        assert_eq!(output["type"], "image/svg+xml");
+        assert_eq!(output["description"], format!("chart {i}"));
+    }
+}
+
+#[test]
+fn result_json_paths_outside_results_dir_fail_the_run() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let skill = root.join("result-charts");
+    write_python_charts_skill(&skill);
+
+    let out = run_skill(&skill, r#"{"count":1,"escape":true}"#, root);
+    assert!(!out.status.success(), "stdout={}", stdout_str(&out));
+    let combined = stdout_str(&out) + &stderr_str(&out);
+    assert!(combined.contains("result.json"), "{}", combined);
+}
+
+#[test]
+fn node_skill_outputs_are_collected_from_result_json() {
+    if Command::new("node").arg("--version").output().is_err() {
+        eprintln!("node not found; skipping");
+        return;
+    }
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let skill = root.join("result-summary");
+    write_node_summary_skill(&skill);
+
+    let merged = merged_output(&run_skill(&skill, r#"{"text":"hello"}"#, root));
+    assert_eq!(merged["stdout"]["ok"], true);
+    assert_eq!(merged["data"]["length"], 5);
+    let path = merged["outputs"][0]["path"].as_str().unwrap();
+    assert_eq!(std::fs::read_to_string(path).unwrap(), "hello");
+    assert_eq!(merged["outputs"][0]["type"], "text/plain");
+}